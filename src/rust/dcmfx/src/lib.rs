@@ -23,6 +23,15 @@ pub mod core {
   pub use dcmfx_core::*;
 }
 
+/// Reads and writes DICOMDIR files that index the DICOM P10 files in a
+/// File-set.
+///
+/// This module is a re-export of the `dcmfx_dicomdir` crate.
+///
+pub mod dicomdir {
+  pub use dcmfx_dicomdir::*;
+}
+
 /// Converts between DICOM data sets and DICOM JSON.
 ///
 /// This module is a re-export of the `dcmfx_json` crate.
@@ -49,6 +58,23 @@ pub mod pixel_data {
   pub use dcmfx_pixel_data::*;
 }
 
+/// Generates synthetic DICOM data sets for use as test data.
+///
+/// This module is a re-export of the `dcmfx_testgen` crate.
+///
+pub mod testgen {
+  pub use dcmfx_testgen::*;
+}
+
+/// Assembles the instances of a series into 3D voxel volumes, and exports them
+/// to formats such as NIfTI-1.
+///
+/// This module is a re-export of the `dcmfx_volume` crate.
+///
+pub mod volume {
+  pub use dcmfx_volume::*;
+}
+
 /// Encodes and decodes DICOM Waveform Interchange acquisitions.
 ///
 /// This module is a re-export of the `dcmfx_waveform` crate.
@@ -0,0 +1,170 @@
+mod utils;
+
+use dcmfx_anonymize::*;
+use dcmfx_core::*;
+use dcmfx_dicomdir::*;
+use dcmfx_p10::*;
+
+fn instance_data_set(sop_instance_uid: &str) -> DataSet {
+  let mut data_set = DataSet::new();
+
+  data_set
+    .insert_string_value(
+      &dictionary::TRANSFER_SYNTAX_UID,
+      &[transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN.uid],
+    )
+    .unwrap();
+  data_set
+    .insert_string_value(
+      &dictionary::SOP_CLASS_UID,
+      &["1.2.840.10008.5.1.4.1.1.4"],
+    )
+    .unwrap();
+  data_set
+    .insert_string_value(&dictionary::SOP_INSTANCE_UID, &[sop_instance_uid])
+    .unwrap();
+  data_set
+    .insert_string_value(&dictionary::PATIENT_ID, &["123"])
+    .unwrap();
+  data_set
+    .insert_string_value(&dictionary::STUDY_INSTANCE_UID, &["1.2.3"])
+    .unwrap();
+  data_set
+    .insert_string_value(&dictionary::SERIES_INSTANCE_UID, &["1.2.3.4"])
+    .unwrap();
+
+  data_set
+}
+
+#[test]
+fn study_export_test() {
+  let temp_dir = utils::create_temp_dir();
+  let data_sets = [
+    instance_data_set("1.2.3.4.1"),
+    instance_data_set("1.2.3.4.2"),
+  ];
+
+  // Simulate an interrupted export whose UID map must be continued
+  let target = StudyExportTarget::Directory(temp_dir.path().join("export"));
+  std::fs::create_dir_all(target.staging_directory()).unwrap();
+  std::fs::write(
+    target.staging_directory().join("uid_map.csv"),
+    "1.2.3,2.25.1\n",
+  )
+  .unwrap();
+
+  let mut exporter =
+    StudyExporter::new().uid_remapper(UidRemapper::new("").unwrap());
+
+  assert_eq!(exporter.export(&data_sets, &target), Ok(2));
+  assert!(!target.staging_directory().exists());
+  assert!(!target.path().join("uid_map.csv").exists());
+
+  let dicom_dir =
+    DicomDir::read_p10_file(target.path().join("DICOMDIR")).unwrap();
+  assert_eq!(dicom_dir.record_count(), 5);
+
+  let data_set =
+    DataSet::read_p10_file(target.path().join("DICOM/00000002"), None).unwrap();
+  assert_eq!(
+    data_set.get_string(dictionary::STUDY_INSTANCE_UID.tag),
+    Ok("2.25.1")
+  );
+
+  let sop_instance_uid = data_set
+    .get_string(dictionary::SOP_INSTANCE_UID.tag)
+    .unwrap();
+  assert!(
+    exporter
+      .get_uid_remapper()
+      .unwrap()
+      .export_map()
+      .contains(&format!("1.2.3.4.2,{sop_instance_uid}\n"))
+  );
+
+  assert_eq!(
+    exporter.export(&data_sets, &target),
+    Err(StudyExportError::TargetExists {
+      path: target.path().to_path_buf()
+    })
+  );
+
+  // Export to a ZIP archive
+  let target = StudyExportTarget::Zip(temp_dir.path().join("export.zip"));
+
+  assert_eq!(StudyExporter::new().export(&data_sets, &target), Ok(2));
+  assert!(!target.staging_directory().exists());
+
+  let zip = std::fs::read(target.path()).unwrap();
+  assert_eq!(&zip[0..4], b"PK\x03\x04");
+  assert_eq!(&zip[30..38], b"DICOMDIR");
+  assert_eq!(&zip[zip.len() - 22..zip.len() - 18], b"PK\x05\x06");
+  assert_eq!(&zip[zip.len() - 12..zip.len() - 10], &3u16.to_le_bytes());
+}
+
+#[test]
+fn study_export_resume_test() {
+  let temp_dir = utils::create_temp_dir();
+  let data_sets = [
+    instance_data_set("1.2.3.4.1"),
+    instance_data_set("1.2.3.4.2"),
+  ];
+
+  // Each export uses a new anonymization profile, which has a new UID salt
+  let export = |target: &StudyExportTarget| {
+    let mut exporter = StudyExporter::new()
+      .anonymization_profile(AnonymizationProfile::default())
+      .uid_remapper(UidRemapper::new("").unwrap());
+
+    assert_eq!(exporter.export(&data_sets, target), Ok(2));
+
+    exporter.get_uid_remapper().unwrap().export_map()
+  };
+
+  let first_target =
+    StudyExportTarget::Directory(temp_dir.path().join("first"));
+  let uid_map = export(&first_target);
+
+  // Simulate an interrupted export that wrote its UID map and first file, and
+  // then resume it
+  let second_target =
+    StudyExportTarget::Directory(temp_dir.path().join("second"));
+  let staging_directory = second_target.staging_directory();
+  std::fs::create_dir_all(staging_directory.join("DICOM")).unwrap();
+  std::fs::write(staging_directory.join("uid_map.csv"), uid_map).unwrap();
+  std::fs::copy(
+    first_target.path().join("DICOM/00000001"),
+    staging_directory.join("DICOM/00000001"),
+  )
+  .unwrap();
+
+  export(&second_target);
+
+  for (filename, sop_instance_uid) in
+    [("00000001", "1.2.3.4.1"), ("00000002", "1.2.3.4.2")]
+  {
+    let read_data_set = |target: &StudyExportTarget| {
+      DataSet::read_p10_file(target.path().join("DICOM").join(filename), None)
+        .unwrap()
+    };
+
+    let first_data_set = read_data_set(&first_target);
+    let second_data_set = read_data_set(&second_target);
+
+    assert_ne!(
+      first_data_set.get_string(dictionary::SOP_INSTANCE_UID.tag),
+      Ok(sop_instance_uid)
+    );
+
+    for tag in [
+      dictionary::SOP_INSTANCE_UID.tag,
+      dictionary::STUDY_INSTANCE_UID.tag,
+      dictionary::SERIES_INSTANCE_UID.tag,
+    ] {
+      assert_eq!(
+        first_data_set.get_string(tag),
+        second_data_set.get_string(tag)
+      );
+    }
+  }
+}
@@ -0,0 +1,1069 @@
+//! Anonymization profiles based on the Basic Application Level
+//! Confidentiality Profile defined in DICOM PS3.15 Annex E.
+//!
+//! Ref: PS3.15 E.
+
+use dcmfx_core::{
+  DataElementTag, DataElementValue, DataSet, ValueRepresentation,
+  data_element_value::unique_identifier, dictionary,
+};
+
+use crate::UidRemapper;
+use crate::private_element_risk::{
+  PrivateElementRisk, is_known_private_creator, private_element_risk,
+};
+
+use AnonymizationAction::{
+  Clean as C, Dummy as D, Keep as K, Remove as X, ReplaceUid as U, Zero as Z,
+};
+use ProfileOption::*;
+
+/// The action to take on a data element when it is anonymized. Each variant
+/// corresponds to one of the action codes used in PS3.15 Table E.1-1.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnonymizationAction {
+  /// `D`: Replace with a non-zero length dummy value that is consistent with
+  /// the VR.
+  Dummy,
+
+  /// `Z`: Replace with a zero length value.
+  Zero,
+
+  /// `X`: Remove the data element.
+  Remove,
+
+  /// `K`: Keep the data element. Sequences are still anonymized recursively.
+  Keep,
+
+  /// `C`: Clean the data element, i.e. replace values that contain
+  /// identifying information with values of similar meaning that don't.
+  ///
+  /// Free text can't be cleaned automatically, so this action keeps the value
+  /// and is intended for use where the values have been reviewed as being
+  /// safe. Sequences are anonymized recursively.
+  Clean,
+
+  /// `U`: Replace each UID with a non-zero length UID that is internally
+  /// consistent, i.e. the same original UID is always replaced with the same
+  /// new UID.
+  ReplaceUid,
+}
+
+/// Options from PS3.15 Table E.1-1 that modify the action taken on a data
+/// element by the Basic Application Level Confidentiality Profile.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProfileOption {
+  RetainLongitudinalTemporalInformation,
+  RetainPatientCharacteristics,
+  RetainDeviceIdentity,
+  RetainInstitutionIdentity,
+  RetainUids,
+  CleanDescriptors,
+}
+
+/// An anonymization profile that implements the Basic Application Level
+/// Confidentiality Profile defined in PS3.15 Annex E, with support for a
+/// number of its options.
+///
+/// The default profile has no options enabled, removes all private data
+/// elements, and uses a randomly generated salt for replacement UIDs.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnonymizationProfile {
+  retain_longitudinal_temporal_information: bool,
+  retain_patient_characteristics: bool,
+  retain_device_identity: bool,
+  retain_institution_identity: bool,
+  retain_uids: bool,
+  clean_descriptors: bool,
+  keep_safe_private_data_elements: bool,
+  uid_salt: String,
+}
+
+impl Default for AnonymizationProfile {
+  fn default() -> Self {
+    Self {
+      retain_longitudinal_temporal_information: false,
+      retain_patient_characteristics: false,
+      retain_device_identity: false,
+      retain_institution_identity: false,
+      retain_uids: false,
+      clean_descriptors: false,
+      keep_safe_private_data_elements: false,
+      uid_salt: random_uid_salt(),
+    }
+  }
+}
+
+/// Returns a new random salt for generating replacement UIDs. A random UID is
+/// used as it is made up of 64 randomly generated characters.
+///
+#[cfg(not(target_arch = "wasm32"))]
+fn random_uid_salt() -> String {
+  unique_identifier::new("").unwrap()
+}
+
+/// Returns the salt for generating replacement UIDs. A random number
+/// generator isn't available on WASM, so the salt is empty and should be set
+/// explicitly with [`AnonymizationProfile::uid_salt()`].
+///
+#[cfg(target_arch = "wasm32")]
+fn random_uid_salt() -> String {
+  String::new()
+}
+
+impl AnonymizationProfile {
+  /// Whether to apply the Retain Longitudinal Temporal Information With Full
+  /// Dates Option, which keeps dates and times.
+  ///
+  /// By default this option is not applied.
+  ///
+  pub fn retain_longitudinal_temporal_information(
+    mut self,
+    value: bool,
+  ) -> Self {
+    self.retain_longitudinal_temporal_information = value;
+    self
+  }
+
+  /// Whether to apply the Retain Patient Characteristics Option, which keeps
+  /// the patient's sex, age, size, weight, and similar physical
+  /// characteristics.
+  ///
+  /// By default this option is not applied.
+  ///
+  pub fn retain_patient_characteristics(mut self, value: bool) -> Self {
+    self.retain_patient_characteristics = value;
+    self
+  }
+
+  /// Whether to apply the Retain Device Identity Option, which keeps data
+  /// elements that identify the device that acquired the data.
+  ///
+  /// By default this option is not applied.
+  ///
+  pub fn retain_device_identity(mut self, value: bool) -> Self {
+    self.retain_device_identity = value;
+    self
+  }
+
+  /// Whether to apply the Retain Institution Identity Option, which keeps
+  /// data elements that identify the institution where the data was acquired.
+  ///
+  /// By default this option is not applied.
+  ///
+  pub fn retain_institution_identity(mut self, value: bool) -> Self {
+    self.retain_institution_identity = value;
+    self
+  }
+
+  /// Whether to apply the Retain UIDs Option, which keeps the original UIDs
+  /// rather than replacing them.
+  ///
+  /// By default this option is not applied.
+  ///
+  pub fn retain_uids(mut self, value: bool) -> Self {
+    self.retain_uids = value;
+    self
+  }
+
+  /// Whether to apply the Clean Descriptors Option, which keeps descriptive
+  /// free text such as study and series descriptions rather than removing it.
+  /// See [`AnonymizationAction::Clean`] for details.
+  ///
+  /// By default this option is not applied.
+  ///
+  pub fn clean_descriptors(mut self, value: bool) -> Self {
+    self.clean_descriptors = value;
+    self
+  }
+
+  /// Whether to keep well-known private data elements that have been reviewed
+  /// as not containing identifying information, rather than removing all
+  /// private data elements. Well-known private data elements that are able to
+  /// contain identifying information are removed, as are private data elements
+  /// with an unknown identity risk.
+  ///
+  /// This is similar to the Retain Safe Private Option, but uses a fixed list
+  /// of well-known private data elements rather than requiring a list of safe
+  /// private data elements to be supplied.
+  ///
+  /// By default this option is not applied.
+  ///
+  pub fn keep_safe_private_data_elements(mut self, value: bool) -> Self {
+    self.keep_safe_private_data_elements = value;
+    self
+  }
+
+  /// A salt that is mixed into the hash used to generate replacement UIDs.
+  /// Replacement UIDs are deterministic, so anonymizing related data sets with
+  /// the same profile, or with profiles that use the same salt, preserves the
+  /// references between them.
+  ///
+  /// The salt must be kept secret, because anyone who knows it can recover
+  /// the original UIDs by hashing candidate UIDs and comparing the results.
+  ///
+  /// By default a random salt is generated for each profile, which means
+  /// replacement UIDs are only consistent within a single profile.
+  ///
+  pub fn uid_salt(mut self, value: String) -> Self {
+    self.uid_salt = value;
+    self
+  }
+
+  /// Returns the action this profile takes on the data element with the
+  /// given tag.
+  ///
+  /// Private data elements and data elements in the patient group (0010,xxxx)
+  /// not otherwise listed are removed. Data elements not listed in the
+  /// profile are kept.
+  ///
+  pub fn action(&self, tag: DataElementTag) -> AnonymizationAction {
+    if tag.is_private() {
+      return AnonymizationAction::Remove;
+    }
+
+    // Overlay and curve data may contain burned in identifying information
+    if tag.group & 0xFF00 == 0x5000
+      || (tag.group & 0xFF00 == 0x6000 && tag.element == 0x4000)
+    {
+      return AnonymizationAction::Remove;
+    }
+
+    let Some((_, action, option)) =
+      PROFILE_ENTRIES.iter().find(|entry| entry.0.tag == tag)
+    else {
+      if tag.group == 0x0010 {
+        return AnonymizationAction::Remove;
+      }
+
+      return AnonymizationAction::Keep;
+    };
+
+    match option {
+      Some((option, option_action)) if self.is_option_enabled(*option) => {
+        *option_action
+      }
+      _ => *action,
+    }
+  }
+
+  /// Returns the action this profile takes on the data element with the
+  /// given tag, taking into account the private creator when the tag is
+  /// private. For private creator data elements the private creator is their
+  /// own value.
+  ///
+  /// Private data elements are removed unless
+  /// [`Self::keep_safe_private_data_elements()`] is enabled and the data
+  /// element is a well-known private data element that doesn't contain
+  /// identifying information.
+  ///
+  pub fn private_action(
+    &self,
+    tag: DataElementTag,
+    private_creator: Option<&str>,
+  ) -> AnonymizationAction {
+    if !tag.is_private() {
+      return self.action(tag);
+    }
+
+    if !self.keep_safe_private_data_elements {
+      return AnonymizationAction::Remove;
+    }
+
+    let Some(private_creator) = private_creator else {
+      return AnonymizationAction::Remove;
+    };
+
+    // Keep the private creator data elements of well-known private creators
+    // so that the private data elements that are kept can still be looked up
+    if tag.is_private_creator() {
+      if is_known_private_creator(private_creator) {
+        return AnonymizationAction::Keep;
+      }
+
+      return AnonymizationAction::Remove;
+    }
+
+    match private_element_risk(tag, private_creator) {
+      Some(PrivateElementRisk::Safe) => AnonymizationAction::Keep,
+      Some(PrivateElementRisk::Identifying) | None => {
+        AnonymizationAction::Remove
+      }
+    }
+  }
+
+  fn is_option_enabled(&self, option: ProfileOption) -> bool {
+    match option {
+      ProfileOption::RetainLongitudinalTemporalInformation => {
+        self.retain_longitudinal_temporal_information
+      }
+      ProfileOption::RetainPatientCharacteristics => {
+        self.retain_patient_characteristics
+      }
+      ProfileOption::RetainDeviceIdentity => self.retain_device_identity,
+      ProfileOption::RetainInstitutionIdentity => {
+        self.retain_institution_identity
+      }
+      ProfileOption::RetainUids => self.retain_uids,
+      ProfileOption::CleanDescriptors => self.clean_descriptors,
+    }
+  }
+
+  /// Applies this profile to a data set, including the data sets in any
+  /// sequence items.
+  ///
+  pub(crate) fn apply(&self, data_set: &mut DataSet) {
+    let mut uid_remapper = UidRemapper::new_with_salt(&self.uid_salt);
+    self.apply_to_data_set(data_set, &mut uid_remapper);
+  }
+
+  /// Applies this profile to a data set in the same way as [`Self::apply()`],
+  /// except that UIDs are replaced using the given UID remapper rather than
+  /// one that uses this profile's salt.
+  ///
+  pub(crate) fn apply_with_uid_remapper(
+    &self,
+    data_set: &mut DataSet,
+    uid_remapper: &mut UidRemapper,
+  ) {
+    self.apply_to_data_set(data_set, uid_remapper);
+  }
+
+  fn apply_to_data_set(
+    &self,
+    data_set: &mut DataSet,
+    uid_remapper: &mut UidRemapper,
+  ) {
+    for tag in data_set.tags() {
+      let private_creator = if tag.is_private_creator() {
+        data_set.get_string(tag).ok()
+      } else {
+        data_set.private_creator_for_tag(tag).ok()
+      };
+
+      let action = self.private_action(tag, private_creator);
+
+      if action == AnonymizationAction::Remove {
+        data_set.delete(tag);
+        continue;
+      }
+
+      let Some(value) = data_set.delete(tag) else {
+        continue;
+      };
+
+      let vr = value.value_representation();
+
+      let new_value = match action {
+        AnonymizationAction::Zero => zero_value(vr),
+
+        AnonymizationAction::Dummy => dummy_value(vr),
+
+        AnonymizationAction::ReplaceUid => self
+          .replace_uids(&value, uid_remapper)
+          .unwrap_or_else(|| dummy_value(vr)),
+
+        AnonymizationAction::Keep
+        | AnonymizationAction::Clean
+        | AnonymizationAction::Remove => value,
+      };
+
+      data_set.insert(tag, self.apply_to_sequence(new_value, uid_remapper));
+    }
+  }
+
+  fn apply_to_sequence(
+    &self,
+    mut value: DataElementValue,
+    uid_remapper: &mut UidRemapper,
+  ) -> DataElementValue {
+    if let Ok(items) = value.sequence_items_mut() {
+      for item in items.iter_mut() {
+        self.apply_to_data_set(item, uid_remapper);
+      }
+    }
+
+    value
+  }
+
+  /// Replaces each UID in a value with the UID it is remapped to by the given
+  /// UID remapper.
+  ///
+  fn replace_uids(
+    &self,
+    value: &DataElementValue,
+    uid_remapper: &mut UidRemapper,
+  ) -> Option<DataElementValue> {
+    if value.value_representation() != ValueRepresentation::UniqueIdentifier {
+      return None;
+    }
+
+    uid_remapper.remap_value(value)
+  }
+}
+
+/// Returns a zero length value with the given VR. Sequences are replaced with
+/// an empty sequence.
+///
+fn zero_value(vr: ValueRepresentation) -> DataElementValue {
+  if vr == ValueRepresentation::Sequence {
+    return DataElementValue::new_sequence(vec![]);
+  }
+
+  DataElementValue::new_binary_unchecked(vr, vec![].into())
+}
+
+/// Returns a non-zero length dummy value that is valid for the given VR. VRs
+/// that have no sensible dummy value are given a zero length value.
+///
+fn dummy_value(vr: ValueRepresentation) -> DataElementValue {
+  let mut bytes = match vr {
+    ValueRepresentation::ApplicationEntity
+    | ValueRepresentation::CodeString
+    | ValueRepresentation::LongString
+    | ValueRepresentation::LongText
+    | ValueRepresentation::PersonName
+    | ValueRepresentation::ShortString
+    | ValueRepresentation::ShortText
+    | ValueRepresentation::UnlimitedCharacters
+    | ValueRepresentation::UnlimitedText => b"ANONYMIZED".to_vec(),
+
+    ValueRepresentation::AgeString => b"000Y".to_vec(),
+    ValueRepresentation::Date => b"19000101".to_vec(),
+    ValueRepresentation::DateTime => b"19000101000000".to_vec(),
+    ValueRepresentation::Time => b"000000".to_vec(),
+
+    ValueRepresentation::DecimalString | ValueRepresentation::IntegerString => {
+      b"0".to_vec()
+    }
+
+    ValueRepresentation::UniqueIdentifier => b"2.25.0".to_vec(),
+
+    ValueRepresentation::SignedShort | ValueRepresentation::UnsignedShort => {
+      vec![0; 2]
+    }
+
+    ValueRepresentation::AttributeTag
+    | ValueRepresentation::FloatingPointSingle
+    | ValueRepresentation::SignedLong
+    | ValueRepresentation::UnsignedLong => vec![0; 4],
+
+    ValueRepresentation::FloatingPointDouble
+    | ValueRepresentation::SignedVeryLong
+    | ValueRepresentation::UnsignedVeryLong => vec![0; 8],
+
+    _ => return zero_value(vr),
+  };
+
+  vr.pad_bytes_to_even_length(&mut bytes);
+
+  DataElementValue::new_binary_unchecked(vr, bytes.into())
+}
+
+type ProfileEntry = (
+  &'static dictionary::Item,
+  AnonymizationAction,
+  Option<(ProfileOption, AnonymizationAction)>,
+);
+
+/// The data elements listed in PS3.15 Table E.1-1 along with the action taken
+/// on them by the Basic Profile, and the option that changes that action, if
+/// any. Where the standard allows a choice of actions, e.g. `X/Z`, the action
+/// that is valid for all IODs is used.
+///
+/// This is a subset of the full table that covers the data elements most
+/// commonly found in practice.
+///
+const PROFILE_ENTRIES: &[ProfileEntry] = &[
+  // Patient
+  (&dictionary::PATIENT_NAME, Z, None),
+  (&dictionary::PATIENT_ID, Z, None),
+  (&dictionary::ISSUER_OF_PATIENT_ID, X, None),
+  (&dictionary::PATIENT_BIRTH_DATE, Z, None),
+  (&dictionary::PATIENT_BIRTH_TIME, X, None),
+  (
+    &dictionary::PATIENT_SEX,
+    Z,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (
+    &dictionary::PATIENT_AGE,
+    X,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (
+    &dictionary::PATIENT_SIZE,
+    X,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (
+    &dictionary::PATIENT_WEIGHT,
+    X,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (
+    &dictionary::ETHNIC_GROUP,
+    X,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (
+    &dictionary::SMOKING_STATUS,
+    X,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (
+    &dictionary::PREGNANCY_STATUS,
+    X,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (
+    &dictionary::PATIENT_SEX_NEUTERED,
+    X,
+    Some((RetainPatientCharacteristics, K)),
+  ),
+  (&dictionary::PATIENT_ADDRESS, X, None),
+  (&dictionary::PATIENT_TELEPHONE_NUMBERS, X, None),
+  (&dictionary::PATIENT_BIRTH_NAME, X, None),
+  (&dictionary::PATIENT_MOTHER_BIRTH_NAME, X, None),
+  (&dictionary::PATIENT_RELIGIOUS_PREFERENCE, X, None),
+  (&dictionary::OTHER_PATIENT_IDS, X, None),
+  (&dictionary::OTHER_PATIENT_NAMES, X, None),
+  (&dictionary::OTHER_PATIENT_IDS_SEQUENCE, X, None),
+  (&dictionary::MILITARY_RANK, X, None),
+  (&dictionary::BRANCH_OF_SERVICE, X, None),
+  (&dictionary::MEDICAL_RECORD_LOCATOR, X, None),
+  (&dictionary::COUNTRY_OF_RESIDENCE, X, None),
+  (&dictionary::REGION_OF_RESIDENCE, X, None),
+  (&dictionary::RESPONSIBLE_PERSON, X, None),
+  (&dictionary::RESPONSIBLE_ORGANIZATION, X, None),
+  (&dictionary::OCCUPATION, X, Some((CleanDescriptors, C))),
+  (
+    &dictionary::ADDITIONAL_PATIENT_HISTORY,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::PATIENT_COMMENTS,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (&dictionary::PATIENT_STATE, X, Some((CleanDescriptors, C))),
+  (&dictionary::MEDICAL_ALERTS, X, Some((CleanDescriptors, C))),
+  (&dictionary::ALLERGIES, X, Some((CleanDescriptors, C))),
+  (
+    &dictionary::LAST_MENSTRUAL_DATE,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (&dictionary::REFERENCED_PATIENT_SEQUENCE, X, None),
+  // Study
+  (&dictionary::STUDY_INSTANCE_UID, U, Some((RetainUids, K))),
+  (
+    &dictionary::STUDY_DATE,
+    Z,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::STUDY_TIME,
+    Z,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (&dictionary::STUDY_ID, Z, None),
+  (&dictionary::ACCESSION_NUMBER, Z, None),
+  (
+    &dictionary::STUDY_DESCRIPTION,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (&dictionary::STUDY_COMMENTS, X, Some((CleanDescriptors, C))),
+  (
+    &dictionary::REASON_FOR_STUDY,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::ADMITTING_DIAGNOSES_DESCRIPTION,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::ADMITTING_DIAGNOSES_CODE_SEQUENCE,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::PROCEDURE_CODE_SEQUENCE,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::REQUESTED_PROCEDURE_DESCRIPTION,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (&dictionary::ADMISSION_ID, X, None),
+  (&dictionary::REQUESTED_PROCEDURE_ID, X, None),
+  (&dictionary::REQUEST_ATTRIBUTES_SEQUENCE, X, None),
+  (&dictionary::REQUESTING_PHYSICIAN, X, None),
+  (&dictionary::REQUESTING_SERVICE, X, None),
+  (&dictionary::REFERRING_PHYSICIAN_NAME, Z, None),
+  (&dictionary::REFERRING_PHYSICIAN_ADDRESS, X, None),
+  (&dictionary::REFERRING_PHYSICIAN_TELEPHONE_NUMBERS, X, None),
+  (
+    &dictionary::REFERRING_PHYSICIAN_IDENTIFICATION_SEQUENCE,
+    X,
+    None,
+  ),
+  (&dictionary::PHYSICIANS_OF_RECORD, X, None),
+  (
+    &dictionary::PHYSICIANS_OF_RECORD_IDENTIFICATION_SEQUENCE,
+    X,
+    None,
+  ),
+  (&dictionary::NAME_OF_PHYSICIANS_READING_STUDY, X, None),
+  (
+    &dictionary::PHYSICIANS_READING_STUDY_IDENTIFICATION_SEQUENCE,
+    X,
+    None,
+  ),
+  (&dictionary::REFERENCED_STUDY_SEQUENCE, X, None),
+  (
+    &dictionary::REFERENCED_PERFORMED_PROCEDURE_STEP_SEQUENCE,
+    X,
+    None,
+  ),
+  (&dictionary::PERFORMED_PROCEDURE_STEP_ID, X, None),
+  (
+    &dictionary::PERFORMED_PROCEDURE_STEP_DESCRIPTION,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::PERFORMED_PROCEDURE_STEP_START_DATE,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::PERFORMED_PROCEDURE_STEP_START_TIME,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::PERFORMED_PROCEDURE_STEP_END_DATE,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::PERFORMED_PROCEDURE_STEP_END_TIME,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (&dictionary::SCHEDULED_PROCEDURE_STEP_ID, X, None),
+  // Series
+  (&dictionary::SERIES_INSTANCE_UID, U, Some((RetainUids, K))),
+  (
+    &dictionary::SERIES_DATE,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::SERIES_TIME,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::SERIES_DESCRIPTION,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::SERIES_DESCRIPTION_CODE_SEQUENCE,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (&dictionary::PROTOCOL_NAME, X, Some((CleanDescriptors, C))),
+  (&dictionary::PERFORMING_PHYSICIAN_NAME, X, None),
+  (
+    &dictionary::PERFORMING_PHYSICIAN_IDENTIFICATION_SEQUENCE,
+    X,
+    None,
+  ),
+  (&dictionary::OPERATORS_NAME, X, None),
+  (&dictionary::OPERATOR_IDENTIFICATION_SEQUENCE, X, None),
+  // Frame of reference
+  (
+    &dictionary::FRAME_OF_REFERENCE_UID,
+    U,
+    Some((RetainUids, K)),
+  ),
+  (
+    &dictionary::SYNCHRONIZATION_FRAME_OF_REFERENCE_UID,
+    U,
+    Some((RetainUids, K)),
+  ),
+  (
+    &dictionary::REFERENCED_FRAME_OF_REFERENCE_UID,
+    U,
+    Some((RetainUids, K)),
+  ),
+  // Equipment
+  (
+    &dictionary::STATION_NAME,
+    X,
+    Some((RetainDeviceIdentity, K)),
+  ),
+  (&dictionary::STATION_AE_TITLE, X, None),
+  (
+    &dictionary::DEVICE_SERIAL_NUMBER,
+    X,
+    Some((RetainDeviceIdentity, K)),
+  ),
+  (&dictionary::DEVICE_UID, U, Some((RetainDeviceIdentity, K))),
+  (&dictionary::GANTRY_ID, X, Some((RetainDeviceIdentity, K))),
+  (&dictionary::DETECTOR_ID, X, Some((RetainDeviceIdentity, K))),
+  (&dictionary::CASSETTE_ID, X, Some((RetainDeviceIdentity, K))),
+  (&dictionary::PLATE_ID, X, Some((RetainDeviceIdentity, K))),
+  (
+    &dictionary::INSTITUTION_NAME,
+    X,
+    Some((RetainInstitutionIdentity, K)),
+  ),
+  (
+    &dictionary::INSTITUTION_ADDRESS,
+    X,
+    Some((RetainInstitutionIdentity, K)),
+  ),
+  (
+    &dictionary::INSTITUTION_CODE_SEQUENCE,
+    X,
+    Some((RetainInstitutionIdentity, K)),
+  ),
+  (
+    &dictionary::INSTITUTIONAL_DEPARTMENT_NAME,
+    X,
+    Some((RetainInstitutionIdentity, K)),
+  ),
+  (
+    &dictionary::INSTITUTIONAL_DEPARTMENT_TYPE_CODE_SEQUENCE,
+    X,
+    Some((RetainInstitutionIdentity, K)),
+  ),
+  // Instance
+  (&dictionary::SOP_INSTANCE_UID, U, Some((RetainUids, K))),
+  (
+    &dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID,
+    U,
+    Some((RetainUids, K)),
+  ),
+  (
+    &dictionary::REFERENCED_SOP_INSTANCE_UID,
+    U,
+    Some((RetainUids, K)),
+  ),
+  (&dictionary::INSTANCE_CREATOR_UID, U, Some((RetainUids, K))),
+  (
+    &dictionary::STORAGE_MEDIA_FILE_SET_UID,
+    U,
+    Some((RetainUids, K)),
+  ),
+  (&dictionary::IRRADIATION_EVENT_UID, U, Some((RetainUids, K))),
+  (&dictionary::UID, U, Some((RetainUids, K))),
+  (
+    &dictionary::INSTANCE_CREATION_DATE,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::INSTANCE_CREATION_TIME,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::CONTENT_DATE,
+    Z,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::CONTENT_TIME,
+    Z,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::ACQUISITION_DATE,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::ACQUISITION_TIME,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::ACQUISITION_DATE_TIME,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::TIMEZONE_OFFSET_FROM_UTC,
+    X,
+    Some((RetainLongitudinalTemporalInformation, K)),
+  ),
+  (
+    &dictionary::ACQUISITION_COMMENTS,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::ACQUISITION_DEVICE_PROCESSING_DESCRIPTION,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (
+    &dictionary::DERIVATION_DESCRIPTION,
+    X,
+    Some((CleanDescriptors, C)),
+  ),
+  (&dictionary::IMAGE_COMMENTS, X, Some((CleanDescriptors, C))),
+  (
+    &dictionary::CONTRAST_BOLUS_AGENT,
+    Z,
+    Some((CleanDescriptors, C)),
+  ),
+  (&dictionary::CONTENT_CREATOR_NAME, Z, None),
+  (&dictionary::TEXT_VALUE, X, Some((CleanDescriptors, C))),
+  (&dictionary::PERSON_NAME, D, None),
+  (&dictionary::PERSON_ADDRESS, X, None),
+  (&dictionary::PERSON_TELEPHONE_NUMBERS, X, None),
+  (&dictionary::PERSON_TELECOM_INFORMATION, X, None),
+  (&dictionary::VERIFYING_OBSERVER_NAME, D, None),
+  (&dictionary::VERIFYING_ORGANIZATION, X, None),
+  (&dictionary::NETWORK_ID, X, None),
+  (&dictionary::DIGITAL_SIGNATURES_SEQUENCE, X, None),
+  (&dictionary::ORIGINAL_ATTRIBUTES_SEQUENCE, X, None),
+  (&dictionary::STUDY_ACCESS_END_POINTS_SEQUENCE, X, None),
+  (&dictionary::INVENTORY_ACCESS_END_POINTS_SEQUENCE, X, None),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn action_test() {
+    let profile = AnonymizationProfile::default();
+
+    assert_eq!(profile.action(dictionary::PATIENT_NAME.tag), Z);
+    assert_eq!(profile.action(dictionary::PATIENT_AGE.tag), X);
+    assert_eq!(profile.action(dictionary::STUDY_DATE.tag), Z);
+    assert_eq!(profile.action(dictionary::SOP_INSTANCE_UID.tag), U);
+    assert_eq!(profile.action(dictionary::PERSON_NAME.tag), D);
+    assert_eq!(profile.action(dictionary::MODALITY.tag), K);
+    assert_eq!(profile.action(DataElementTag::new(0x0009, 0x0010)), X);
+    assert_eq!(profile.action(DataElementTag::new(0x0010, 0xABCD)), X);
+    assert_eq!(profile.action(dictionary::OVERLAY_DATA.tag), K);
+    assert_eq!(profile.action(DataElementTag::new(0x6002, 0x4000)), X);
+
+    let profile = AnonymizationProfile::default()
+      .retain_longitudinal_temporal_information(true)
+      .retain_patient_characteristics(true)
+      .retain_uids(true)
+      .clean_descriptors(true);
+
+    assert_eq!(profile.action(dictionary::PATIENT_NAME.tag), Z);
+    assert_eq!(profile.action(dictionary::PATIENT_AGE.tag), K);
+    assert_eq!(profile.action(dictionary::STUDY_DATE.tag), K);
+    assert_eq!(profile.action(dictionary::SOP_INSTANCE_UID.tag), K);
+    assert_eq!(profile.action(dictionary::STUDY_DESCRIPTION.tag), C);
+    assert_eq!(profile.action(dictionary::STATION_NAME.tag), X);
+  }
+
+  #[test]
+  fn apply_test() {
+    let mut item = DataSet::new();
+    item.insert(
+      dictionary::PERSON_NAME.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::PersonName,
+        b"Jane".to_vec().into(),
+      ),
+    );
+    item
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+
+    let mut data_set = DataSet::new();
+    data_set.insert(
+      dictionary::PATIENT_NAME.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::PersonName,
+        b"Doe^John".to_vec().into(),
+      ),
+    );
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ADDRESS, &["1 Main St"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::MODALITY, &["CT"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::STUDY_DESCRIPTION, &["Head"])
+      .unwrap();
+    data_set.insert(
+      dictionary::CONTENT_SEQUENCE.tag,
+      DataElementValue::new_sequence(vec![item]),
+    );
+    data_set.insert(
+      DataElementTag::new(0x0009, 0x1001),
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::Unknown,
+        vec![1, 2].into(),
+      ),
+    );
+
+    AnonymizationProfile::default().apply(&mut data_set);
+
+    assert_eq!(
+      data_set.get_value_bytes(dictionary::PATIENT_NAME.tag),
+      Ok(&vec![].into())
+    );
+    assert!(!data_set.has(dictionary::PATIENT_ADDRESS.tag));
+    assert!(!data_set.has(dictionary::STUDY_DESCRIPTION.tag));
+    assert!(!data_set.has(DataElementTag::new(0x0009, 0x1001)));
+    assert_eq!(data_set.get_string(dictionary::MODALITY.tag), Ok("CT"));
+
+    let sop_instance_uid = data_set
+      .get_string(dictionary::SOP_INSTANCE_UID.tag)
+      .unwrap()
+      .to_string();
+    assert!(sop_instance_uid.starts_with("2.25."));
+    assert_ne!(sop_instance_uid, "1.2.3");
+
+    let item = &data_set
+      .get_sequence_items(dictionary::CONTENT_SEQUENCE.tag)
+      .unwrap()[0];
+
+    assert_eq!(
+      item.get_value_bytes(dictionary::PERSON_NAME.tag),
+      Ok(&b"ANONYMIZED".to_vec().into())
+    );
+    assert_eq!(
+      item.get_string(dictionary::REFERENCED_SOP_INSTANCE_UID.tag),
+      Ok(sop_instance_uid.as_str())
+    );
+  }
+
+  #[test]
+  fn keep_safe_private_data_elements_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_private_value(
+        0x0029,
+        "SIEMENS CSA HEADER",
+        0x10,
+        DataElementValue::new_binary_unchecked(
+          ValueRepresentation::OtherByteString,
+          vec![1, 2].into(),
+        ),
+      )
+      .unwrap();
+    data_set
+      .insert_private_value(
+        0x0029,
+        "SIEMENS CSA HEADER",
+        0x20,
+        DataElementValue::new_binary_unchecked(
+          ValueRepresentation::OtherByteString,
+          vec![3, 4].into(),
+        ),
+      )
+      .unwrap();
+    data_set
+      .insert_private_value(
+        0x0029,
+        "VENDOR A",
+        0x10,
+        DataElementValue::new_binary_unchecked(
+          ValueRepresentation::OtherByteString,
+          vec![5, 6].into(),
+        ),
+      )
+      .unwrap();
+
+    let mut a = data_set.clone();
+    AnonymizationProfile::default().apply(&mut a);
+    assert_eq!(a, DataSet::new());
+
+    let mut b = data_set.clone();
+    AnonymizationProfile::default()
+      .keep_safe_private_data_elements(true)
+      .apply(&mut b);
+
+    assert_eq!(
+      b.tags(),
+      vec![
+        DataElementTag::new(0x0029, 0x0010),
+        DataElementTag::new(0x0029, 0x1010),
+      ]
+    );
+  }
+
+  #[test]
+  fn uid_salt_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+
+    let profile = AnonymizationProfile::default();
+
+    let mut a = data_set.clone();
+    profile.apply(&mut a);
+
+    let mut b = data_set.clone();
+    profile.apply(&mut b);
+
+    let mut c = data_set.clone();
+    AnonymizationProfile::default().apply(&mut c);
+
+    let mut d = data_set.clone();
+    AnonymizationProfile::default()
+      .uid_salt("salt".to_string())
+      .apply(&mut d);
+
+    let mut e = data_set.clone();
+    AnonymizationProfile::default()
+      .uid_salt("salt".to_string())
+      .apply(&mut e);
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_ne!(a, d);
+    assert_eq!(d, e);
+  }
+
+  #[test]
+  fn apply_with_uid_remapper_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+
+    let mut uid_remapper = UidRemapper::new("").unwrap();
+    uid_remapper.import_map("1.2.3,2.25.1\n").unwrap();
+
+    AnonymizationProfile::default()
+      .apply_with_uid_remapper(&mut data_set, &mut uid_remapper);
+
+    assert_eq!(
+      data_set.get_string(dictionary::SOP_INSTANCE_UID.tag),
+      Ok("2.25.1")
+    );
+    assert_eq!(uid_remapper.len(), 1);
+  }
+}
@@ -1,7 +1,23 @@
 //! Anonymization of data sets by removing data elements that identify the
 //! patient, or potentially contribute to identification of the patient.
 
+mod anonymization_profile;
+#[cfg(feature = "pixel-data-redaction")]
+mod pixel_data_redaction;
+mod private_element_risk;
+mod uid_remapper;
+
+pub use anonymization_profile::{AnonymizationAction, AnonymizationProfile};
+#[cfg(feature = "pixel-data-redaction")]
+pub use pixel_data_redaction::PixelDataRedaction;
+pub use private_element_risk::{PrivateElementRisk, private_element_risk};
+pub use uid_remapper::UidRemapper;
+
 use dcmfx_core::{DataElementTag, DataSet, ValueRepresentation, dictionary};
+#[cfg(feature = "pixel-data-redaction")]
+use dcmfx_pixel_data::transforms::{
+  CropRect, P10PixelDataTranscodeTransformError,
+};
 
 const IDENTIFYING_DATA_ELEMENTS: [&dictionary::Item; 42] = [
   &dictionary::ACCESSION_NUMBER,
@@ -77,12 +93,82 @@ pub trait DataSetAnonymizeExtensions {
   /// or potentially contribute to identification of the patient.
   ///
   fn anonymize(&mut self);
+
+  /// Anonymizes a data set by applying the actions specified by the given
+  /// anonymization profile to each of its data elements, including those in
+  /// sequence items.
+  ///
+  fn anonymize_with_profile(&mut self, profile: &AnonymizationProfile);
+
+  /// Anonymizes a data set in the same way as
+  /// [`Self::anonymize_with_profile()`], except that the UIDs the profile
+  /// replaces are remapped using the passed remapper rather than being
+  /// generated using the profile's salt. This means each UID is replaced once,
+  /// and consistently with other data sets that use the same remapper.
+  ///
+  fn anonymize_with_profile_and_uid_remapper(
+    &mut self,
+    profile: &AnonymizationProfile,
+    remapper: &mut UidRemapper,
+  );
+
+  /// Replaces the study, series, instance, and other identifying UIDs in a
+  /// data set with newly generated UIDs. The passed remapper records the UIDs
+  /// it has replaced so that references between data sets remain consistent.
+  ///
+  /// This should be called prior to [`Self::anonymize()`], which removes
+  /// some UID data elements.
+  ///
+  fn remap_uids(&mut self, remapper: &mut UidRemapper);
+
+  /// Redacts burned-in annotations by blacking out the regions of pixel data
+  /// specified by the passed redaction for the data set's SOP Class, as well
+  /// as the passed file-specific regions. The pixel data is decoded and then
+  /// re-encoded, and is left unchanged if there are no regions to black out.
+  ///
+  /// Requires the `pixel-data-redaction` feature.
+  ///
+  #[cfg(feature = "pixel-data-redaction")]
+  fn redact_pixel_data(
+    &mut self,
+    redaction: &PixelDataRedaction,
+    file_regions: &[CropRect],
+  ) -> Result<(), P10PixelDataTranscodeTransformError>;
 }
 
 impl DataSetAnonymizeExtensions for DataSet {
   fn anonymize(&mut self) {
     self.retain(|tag, value| filter_tag(tag, value.value_representation()));
   }
+
+  fn anonymize_with_profile(&mut self, profile: &AnonymizationProfile) {
+    profile.apply(self);
+  }
+
+  fn anonymize_with_profile_and_uid_remapper(
+    &mut self,
+    profile: &AnonymizationProfile,
+    remapper: &mut UidRemapper,
+  ) {
+    profile.apply_with_uid_remapper(self, remapper);
+  }
+
+  fn remap_uids(&mut self, remapper: &mut UidRemapper) {
+    remapper.remap_data_set(self);
+  }
+
+  #[cfg(feature = "pixel-data-redaction")]
+  fn redact_pixel_data(
+    &mut self,
+    redaction: &PixelDataRedaction,
+    file_regions: &[CropRect],
+  ) -> Result<(), P10PixelDataTranscodeTransformError> {
+    if let Some(data_set) = redaction.apply(self, file_regions)? {
+      *self = data_set;
+    }
+
+    Ok(())
+  }
 }
 
 #[cfg(test)]
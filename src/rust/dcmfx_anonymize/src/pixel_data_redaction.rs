@@ -0,0 +1,216 @@
+//! Redaction of burned-in annotations by blacking out rectangular regions of
+//! pixel data.
+
+use std::collections::HashMap;
+
+use dcmfx_core::{DataSet, SopClass, TransferSyntax, dictionary};
+use dcmfx_pixel_data::{
+  DataSetPixelDataExtensions, PixelDataDecodeConfig, PixelDataEncodeConfig,
+  transforms::{
+    CropRect, P10PixelDataTranscodeTransformError, TranscodeImageDataFunctions,
+  },
+};
+
+/// Specifies rectangular regions of pixel data to black out in order to
+/// remove burned-in annotations, such as the patient demographics commonly
+/// burned into ultrasound images.
+///
+/// Regions can be specified per SOP Class, and additional regions for a
+/// specific file can be passed when redacting it. Redaction decodes every
+/// frame of pixel data, blacks out the regions, and then re-encodes the frames
+/// in either the original transfer syntax or the one specified by
+/// [`Self::output_transfer_syntax()`].
+///
+#[derive(Clone, Debug, Default)]
+pub struct PixelDataRedaction {
+  sop_class_regions: HashMap<&'static str, Vec<CropRect>>,
+  output_transfer_syntax: Option<&'static TransferSyntax>,
+}
+
+impl PixelDataRedaction {
+  /// Adds regions to black out in all data sets that have the given SOP Class.
+  ///
+  pub fn sop_class_regions(
+    mut self,
+    sop_class: &'static SopClass,
+    regions: Vec<CropRect>,
+  ) -> Self {
+    self
+      .sop_class_regions
+      .entry(sop_class.uid)
+      .or_default()
+      .extend(regions);
+    self
+  }
+
+  /// The transfer syntax to re-encode redacted pixel data in. If this is not
+  /// set then the data set's original transfer syntax is used.
+  ///
+  /// By default this is `None`.
+  ///
+  pub fn output_transfer_syntax(
+    mut self,
+    value: Option<&'static TransferSyntax>,
+  ) -> Self {
+    self.output_transfer_syntax = value;
+    self
+  }
+
+  /// Returns the regions to black out in the given data set, which are the
+  /// regions for its SOP Class followed by the passed file-specific regions.
+  ///
+  pub fn regions(
+    &self,
+    data_set: &DataSet,
+    file_regions: &[CropRect],
+  ) -> Vec<CropRect> {
+    let mut regions = data_set
+      .get_string(dictionary::SOP_CLASS_UID.tag)
+      .ok()
+      .and_then(|uid| self.sop_class_regions.get(uid))
+      .cloned()
+      .unwrap_or_default();
+
+    regions.extend_from_slice(file_regions);
+
+    regions
+  }
+
+  /// Blacks out the redaction regions in the pixel data of a data set and
+  /// returns the resulting data set. If there are no regions to black out, or
+  /// the data set doesn't contain a valid Image Pixel Module, then `Ok(None)`
+  /// is returned.
+  ///
+  pub(crate) fn apply(
+    &self,
+    data_set: &DataSet,
+    file_regions: &[CropRect],
+  ) -> Result<Option<DataSet>, P10PixelDataTranscodeTransformError> {
+    let regions = self.regions(data_set, file_regions);
+    if regions.is_empty() {
+      return Ok(None);
+    }
+
+    let Some(output_transfer_syntax) = self
+      .output_transfer_syntax
+      .or_else(|| data_set.get_transfer_syntax().ok())
+    else {
+      return Ok(None);
+    };
+
+    let mut functions = TranscodeImageDataFunctions::standard_behavior(
+      output_transfer_syntax,
+      dcmfx_core::Rc::new(|_| None),
+      dcmfx_core::Rc::new(|_| None),
+      None,
+      None,
+      false,
+    );
+
+    // Decoding and re-encoding is always required in order to alter the
+    // pixel data
+    functions.is_encode_decode_cycle_required = Box::new(|_| true);
+
+    let process_monochrome_image = functions.process_monochrome_image;
+    let monochrome_regions = regions.clone();
+    functions.process_monochrome_image =
+      Box::new(move |image, image_pixel_module| {
+        process_monochrome_image(image, image_pixel_module)?;
+
+        for region in monochrome_regions.iter() {
+          image.black_out(region);
+        }
+
+        Ok(())
+      });
+
+    let process_color_image = functions.process_color_image;
+    functions.process_color_image =
+      Box::new(move |image, image_pixel_module| {
+        process_color_image(image, image_pixel_module)?;
+
+        for region in regions.iter() {
+          image.black_out(region);
+        }
+
+        Ok(())
+      });
+
+    data_set.transcode_pixel_data(
+      output_transfer_syntax,
+      PixelDataDecodeConfig::default(),
+      PixelDataEncodeConfig::default(),
+      Some(functions),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx_core::{sop_class, transfer_syntax};
+  use dcmfx_testgen::TestDataSetConfig;
+
+  fn region(left: u16, top: u16, width: i32, height: i32) -> CropRect {
+    CropRect {
+      left,
+      top,
+      width_or_right: Some(width),
+      height_or_bottom: Some(height),
+    }
+  }
+
+  #[test]
+  fn apply_test() {
+    let data_set = dcmfx_testgen::generate_data_set(
+      &TestDataSetConfig::default()
+        .sop_class(&sop_class::ULTRASOUND_IMAGE_STORAGE)
+        .matrix_size(8, 8)
+        .bit_depth(8, 8)
+        .color(true)
+        .seed(1),
+    )
+    .unwrap();
+
+    let redaction = PixelDataRedaction::default()
+      .sop_class_regions(
+        &sop_class::ULTRASOUND_IMAGE_STORAGE,
+        vec![region(0, 0, 8, 2)],
+      )
+      .output_transfer_syntax(Some(&transfer_syntax::RLE_LOSSLESS));
+
+    let redacted_data_set = redaction
+      .apply(&data_set, &[region(6, 6, 2, 2)])
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(
+      redacted_data_set.get_transfer_syntax(),
+      Ok(&transfer_syntax::RLE_LOSSLESS)
+    );
+
+    let images = redacted_data_set.get_pixel_data_color_images().unwrap();
+    let pixels = images[0].clone().into_rgb_u8_image().into_raw();
+
+    for y in 0..8 {
+      for x in 0..8 {
+        let is_redacted = y < 2 || (x >= 6 && y >= 6);
+        let pixel = &pixels[(y * 8 + x) * 3..(y * 8 + x) * 3 + 3];
+
+        assert_eq!(pixel == [0, 0, 0], is_redacted, "Pixel {x},{y}");
+      }
+    }
+  }
+
+  #[test]
+  fn apply_without_regions_test() {
+    let data_set =
+      dcmfx_testgen::generate_data_set(&TestDataSetConfig::default()).unwrap();
+
+    assert_eq!(
+      PixelDataRedaction::default().apply(&data_set, &[]),
+      Ok(None)
+    );
+  }
+}
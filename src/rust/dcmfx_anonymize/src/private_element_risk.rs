@@ -0,0 +1,149 @@
+//! Identity risk of well-known privately defined data elements, used by
+//! anonymization profiles that keep known-safe private data elements.
+
+use dcmfx_core::DataElementTag;
+
+use PrivateElementRisk::{Identifying as I, Safe as S};
+
+/// Whether a well-known privately defined data element is able to contain
+/// information that identifies the patient.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrivateElementRisk {
+  /// The data element holds acquisition or device parameters, and doesn't
+  /// contain identifying information.
+  Safe,
+
+  /// The data element contains, or is able to contain, identifying
+  /// information such as names, dates, descriptions, or free text.
+  Identifying,
+}
+
+/// Returns the identity risk of a privately defined data element given its
+/// private creator. Returns `None` if the data element isn't a well-known
+/// private data element, in which case its risk is unknown.
+///
+pub fn private_element_risk(
+  tag: DataElementTag,
+  private_creator: &str,
+) -> Option<PrivateElementRisk> {
+  if !tag.is_private() || tag.is_private_creator() {
+    return None;
+  }
+
+  let element = tag.element & 0xFF;
+
+  PRIVATE_ELEMENT_RISKS
+    .iter()
+    .find(|entry| {
+      entry.0 == private_creator && entry.1 == tag.group && entry.2 == element
+    })
+    .map(|entry| entry.3)
+}
+
+/// Returns whether the given private creator has any data elements with a
+/// known identity risk.
+///
+pub fn is_known_private_creator(private_creator: &str) -> bool {
+  PRIVATE_ELEMENT_RISKS
+    .iter()
+    .any(|entry| entry.0 == private_creator)
+}
+
+/// The well-known private data elements that have been reviewed for identity
+/// risk, along with their private creator, group, and the low byte of their
+/// element. Each of these data elements is also present in the private data
+/// element dictionary.
+///
+const PRIVATE_ELEMENT_RISKS: &[(&str, u16, u16, PrivateElementRisk)] = &[
+  // Siemens CSA headers. The CSA Series Header Info can contain free text
+  // from the protocol, and so is treated as identifying.
+  ("SIEMENS CSA HEADER", 0x0029, 0x08, S),
+  ("SIEMENS CSA HEADER", 0x0029, 0x09, S),
+  ("SIEMENS CSA HEADER", 0x0029, 0x10, S),
+  ("SIEMENS CSA HEADER", 0x0029, 0x18, S),
+  ("SIEMENS CSA HEADER", 0x0029, 0x19, S),
+  ("SIEMENS CSA HEADER", 0x0029, 0x20, I),
+  // GE identification
+  ("GEMS_IDEN_01", 0x0009, 0x01, S),
+  ("GEMS_IDEN_01", 0x0009, 0x02, I),
+  ("GEMS_IDEN_01", 0x0009, 0x04, S),
+  ("GEMS_IDEN_01", 0x0009, 0x17, I),
+  ("GEMS_IDEN_01", 0x0009, 0x27, I),
+  ("GEMS_IDEN_01", 0x0009, 0x30, I),
+  ("GEMS_IDEN_01", 0x0009, 0x31, I),
+  ("GEMS_IDEN_01", 0x0009, 0xe2, S),
+  ("GEMS_IDEN_01", 0x0009, 0xe3, I),
+  ("GEMS_IDEN_01", 0x0009, 0xe6, S),
+  ("GEMS_IDEN_01", 0x0009, 0xe7, S),
+  ("GEMS_IDEN_01", 0x0009, 0xe8, I),
+  ("GEMS_IDEN_01", 0x0009, 0xe9, I),
+  // GE patient
+  ("GEMS_PATI_01", 0x0011, 0x10, I),
+  // GE study
+  ("GEMS_STDY_01", 0x0023, 0x01, S),
+  ("GEMS_STDY_01", 0x0023, 0x02, S),
+  ("GEMS_STDY_01", 0x0023, 0x10, S),
+  ("GEMS_STDY_01", 0x0023, 0x50, S),
+  ("GEMS_STDY_01", 0x0023, 0x70, I),
+  ("GEMS_STDY_01", 0x0023, 0x74, S),
+  ("GEMS_STDY_01", 0x0023, 0x7d, S),
+  // GE series
+  ("GEMS_SERS_01", 0x0025, 0x06, S),
+  ("GEMS_SERS_01", 0x0025, 0x07, S),
+  ("GEMS_SERS_01", 0x0025, 0x10, S),
+  ("GEMS_SERS_01", 0x0025, 0x11, S),
+  ("GEMS_SERS_01", 0x0025, 0x14, S),
+  ("GEMS_SERS_01", 0x0025, 0x17, S),
+  ("GEMS_SERS_01", 0x0025, 0x18, S),
+  ("GEMS_SERS_01", 0x0025, 0x19, S),
+  ("GEMS_SERS_01", 0x0025, 0x1a, I),
+  ("GEMS_SERS_01", 0x0025, 0x1b, I),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use dcmfx_core::dictionary;
+
+  #[test]
+  fn entries_are_in_dictionary_test() {
+    for (private_creator, group, element, _) in PRIVATE_ELEMENT_RISKS {
+      let tag = DataElementTag::new(*group, 0x1000 | element);
+
+      assert!(
+        dictionary::find(tag, Some(*private_creator)).is_ok(),
+        "{tag} is not in the dictionary for '{private_creator}'"
+      );
+    }
+  }
+
+  #[test]
+  fn private_element_risk_test() {
+    assert_eq!(
+      private_element_risk(
+        DataElementTag::new(0x0029, 0x1010),
+        "SIEMENS CSA HEADER"
+      ),
+      Some(S)
+    );
+
+    assert_eq!(
+      private_element_risk(DataElementTag::new(0x0011, 0x1210), "GEMS_PATI_01"),
+      Some(I)
+    );
+
+    assert_eq!(
+      private_element_risk(DataElementTag::new(0x0029, 0x1010), "VENDOR A"),
+      None
+    );
+
+    assert_eq!(
+      private_element_risk(
+        DataElementTag::new(0x0029, 0x0010),
+        "SIEMENS CSA HEADER"
+      ),
+      None
+    );
+  }
+}
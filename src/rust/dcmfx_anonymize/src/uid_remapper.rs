@@ -0,0 +1,362 @@
+//! Remapping of instance UIDs to newly generated UIDs in a way that preserves
+//! the references between data sets.
+
+use std::collections::HashMap;
+
+use dcmfx_core::{
+  DataElementTag, DataElementValue, DataError, DataSet, ValueRepresentation,
+  data_element_value::unique_identifier, dictionary, error_details,
+};
+use sha2::{Digest, Sha256};
+
+/// The data elements whose UIDs are remapped by a [`UidRemapper`]. These
+/// identify specific studies, series, instances, and frames of reference, and
+/// so are replaced consistently in order to keep the references between them
+/// intact.
+///
+const REMAPPED_DATA_ELEMENTS: [&dictionary::Item; 15] = [
+  &dictionary::CONCATENATION_UID,
+  &dictionary::DIMENSION_ORGANIZATION_UID,
+  &dictionary::FRAME_OF_REFERENCE_UID,
+  &dictionary::INSTANCE_CREATOR_UID,
+  &dictionary::IRRADIATION_EVENT_UID,
+  &dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID,
+  &dictionary::REFERENCED_FRAME_OF_REFERENCE_UID,
+  &dictionary::REFERENCED_SOP_INSTANCE_UID,
+  &dictionary::REFERENCED_SOP_INSTANCE_UID_IN_FILE,
+  &dictionary::SERIES_INSTANCE_UID,
+  &dictionary::SOP_INSTANCE_UID,
+  &dictionary::STORAGE_MEDIA_FILE_SET_UID,
+  &dictionary::STUDY_INSTANCE_UID,
+  &dictionary::SYNCHRONIZATION_FRAME_OF_REFERENCE_UID,
+  &dictionary::UID,
+];
+
+/// Replaces study, series, instance, and other identifying UIDs with newly
+/// generated UIDs, including in sequence items.
+///
+/// Every UID that is replaced is recorded in a mapping table so that the same
+/// input UID is always replaced with the same output UID. This means a single
+/// remapper can be used to process all the files in a study, or multiple
+/// studies, and the references between them will remain valid. The mapping
+/// table can be exported with [`Self::export_map()`] and imported into another
+/// remapper with [`Self::import_map()`] so that a mapping can be continued
+/// across sessions.
+///
+/// New UIDs are either randomly generated, or are generated by hashing the
+/// original UID together with a salt. See [`Self::new()`] and
+/// [`Self::new_with_salt()`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct UidRemapper {
+  generator: UidGenerator,
+  uid_map: HashMap<String, String>,
+}
+
+/// How a [`UidRemapper`] generates new UIDs.
+///
+#[derive(Clone, Debug, PartialEq)]
+enum UidGenerator {
+  #[cfg(not(target_arch = "wasm32"))]
+  Random {
+    prefix: String,
+  },
+  Hashed {
+    salt: String,
+  },
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for UidRemapper {
+  fn default() -> Self {
+    Self {
+      generator: UidGenerator::Random {
+        prefix: String::new(),
+      },
+      uid_map: HashMap::new(),
+    }
+  }
+}
+
+impl UidRemapper {
+  /// Creates a new UID remapper whose generated UIDs are random and start with
+  /// the given prefix. The prefix must be empty, or be a valid UID that is no
+  /// longer than 60 characters.
+  ///
+  #[cfg(not(target_arch = "wasm32"))]
+  pub fn new(prefix: &str) -> Result<Self, DataError> {
+    if prefix.len() > 60
+      || !prefix.is_empty() && !unique_identifier::is_valid(prefix)
+    {
+      return Err(DataError::new_value_invalid(error_details!(
+        "UID prefix '{prefix}' is invalid"
+      )));
+    }
+
+    Ok(Self {
+      generator: UidGenerator::Random {
+        prefix: prefix.to_string(),
+      },
+      uid_map: HashMap::new(),
+    })
+  }
+
+  /// Creates a new UID remapper whose generated UIDs are created by hashing
+  /// the original UID together with the given salt, and use the `2.25` root
+  /// defined in PS3.5 B.2. This means that separate remappers with the same
+  /// salt generate the same UIDs.
+  ///
+  /// The salt must be kept secret, because anyone who knows it can recover
+  /// the original UIDs by hashing candidate UIDs and comparing the results.
+  ///
+  pub fn new_with_salt(salt: &str) -> Self {
+    Self {
+      generator: UidGenerator::Hashed {
+        salt: salt.to_string(),
+      },
+      uid_map: HashMap::new(),
+    }
+  }
+
+  /// Returns whether the UIDs in the given data element are remapped.
+  ///
+  pub fn is_remapped_tag(tag: DataElementTag) -> bool {
+    REMAPPED_DATA_ELEMENTS.iter().any(|item| item.tag == tag)
+  }
+
+  /// Returns the UID that the given UID is remapped to, generating and
+  /// recording a new UID if the given UID hasn't been seen before.
+  ///
+  pub fn remap_uid(&mut self, uid: &str) -> &str {
+    self.uid_map.entry(uid.to_string()).or_insert_with(|| {
+      match &self.generator {
+        // The prefix is validated on construction so generation can't fail
+        #[cfg(not(target_arch = "wasm32"))]
+        UidGenerator::Random { prefix } => {
+          unique_identifier::new(prefix).unwrap()
+        }
+
+        UidGenerator::Hashed { salt } => hashed_uid(salt, uid),
+      }
+    })
+  }
+
+  /// Remaps the UIDs in a data set, including the data sets in any sequence
+  /// items. Data elements that don't hold valid UIDs are left unchanged.
+  ///
+  pub fn remap_data_set(&mut self, data_set: &mut DataSet) {
+    for (tag, value) in data_set.iter_mut() {
+      if let Ok(items) = value.sequence_items_mut() {
+        for item in items.iter_mut() {
+          self.remap_data_set(item);
+        }
+      } else if Self::is_remapped_tag(*tag)
+        && value.value_representation() == ValueRepresentation::UniqueIdentifier
+        && let Some(new_value) = self.remap_value(value)
+      {
+        *value = new_value;
+      }
+    }
+  }
+
+  /// Remaps all the UIDs in a value. Returns `None` if the value doesn't hold
+  /// valid UIDs.
+  ///
+  pub(crate) fn remap_value(
+    &mut self,
+    value: &DataElementValue,
+  ) -> Option<DataElementValue> {
+    let uids = value.get_strings().ok()?;
+
+    let new_uids: Vec<String> = uids
+      .iter()
+      .map(|uid| self.remap_uid(uid).to_string())
+      .collect();
+
+    let new_uids: Vec<&str> = new_uids.iter().map(|s| s.as_str()).collect();
+
+    DataElementValue::new_unique_identifier(&new_uids).ok()
+  }
+
+  /// Returns the number of UIDs in the mapping table.
+  ///
+  pub fn len(&self) -> usize {
+    self.uid_map.len()
+  }
+
+  /// Returns whether the mapping table is empty.
+  ///
+  pub fn is_empty(&self) -> bool {
+    self.uid_map.is_empty()
+  }
+
+  /// Exports the mapping table as text. Each line holds an original UID and
+  /// the UID it is remapped to, separated by a comma. Lines are sorted by
+  /// original UID.
+  ///
+  pub fn export_map(&self) -> String {
+    let mut entries: Vec<_> = self.uid_map.iter().collect();
+    entries.sort();
+
+    entries
+      .into_iter()
+      .map(|(original, remapped)| format!("{original},{remapped}\n"))
+      .collect()
+  }
+
+  /// Imports a mapping table in the format created by [`Self::export_map()`].
+  /// Imported entries replace any existing entries for the same original UIDs.
+  /// Blank lines are ignored.
+  ///
+  pub fn import_map(&mut self, map: &str) -> Result<(), DataError> {
+    let mut entries = vec![];
+
+    for (i, line) in map.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() {
+        continue;
+      }
+
+      match line.split_once(',') {
+        Some((original, remapped))
+          if unique_identifier::is_valid(original)
+            && unique_identifier::is_valid(remapped) =>
+        {
+          entries.push((original.to_string(), remapped.to_string()));
+        }
+
+        _ => {
+          return Err(DataError::new_value_invalid(error_details!(
+            "UID map line {} is invalid",
+            i + 1
+          )));
+        }
+      }
+    }
+
+    self.uid_map.extend(entries);
+
+    Ok(())
+  }
+}
+
+/// Generates a UID by hashing the given UID together with a salt. The
+/// generated UID uses the `2.25` root defined in PS3.5 B.2.
+///
+fn hashed_uid(salt: &str, uid: &str) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(salt.as_bytes());
+  hasher.update(uid.as_bytes());
+  let digest = hasher.finalize();
+
+  let mut bytes = [0u8; 16];
+  bytes.copy_from_slice(&digest[0..16]);
+
+  format!("2.25.{}", u128::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn remap_data_set_test() {
+    let mut remapper = UidRemapper::new("1.2.3").unwrap();
+
+    let mut referenced_item = DataSet::new();
+    referenced_item
+      .insert_string_value(&dictionary::REFERENCED_SOP_CLASS_UID, &["1.2.840"])
+      .unwrap();
+    referenced_item
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.1"])
+      .unwrap();
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.1"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::STUDY_INSTANCE_UID, &["1.2"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::SOP_CLASS_UID, &["1.2.840"])
+      .unwrap();
+    data_set.insert(
+      dictionary::REFERENCED_IMAGE_SEQUENCE.tag,
+      DataElementValue::new_sequence(vec![referenced_item]),
+    );
+
+    remapper.remap_data_set(&mut data_set);
+
+    let sop_instance_uid = data_set
+      .get_string(dictionary::SOP_INSTANCE_UID.tag)
+      .unwrap()
+      .to_string();
+    assert!(sop_instance_uid.starts_with("1.2.3."));
+    assert_eq!(sop_instance_uid.len(), 64);
+
+    assert_ne!(
+      data_set.get_string(dictionary::STUDY_INSTANCE_UID.tag),
+      Ok("1.2")
+    );
+    assert_eq!(
+      data_set.get_string(dictionary::SOP_CLASS_UID.tag),
+      Ok("1.2.840")
+    );
+
+    let items = data_set
+      .get_sequence_items(dictionary::REFERENCED_IMAGE_SEQUENCE.tag)
+      .unwrap();
+    assert_eq!(
+      items[0].get_string(dictionary::REFERENCED_SOP_INSTANCE_UID.tag),
+      Ok(sop_instance_uid.as_str())
+    );
+    assert_eq!(
+      items[0].get_string(dictionary::REFERENCED_SOP_CLASS_UID.tag),
+      Ok("1.2.840")
+    );
+
+    assert_eq!(remapper.len(), 2);
+  }
+
+  #[test]
+  fn export_and_import_map_test() {
+    let mut remapper = UidRemapper::default();
+    let remapped_uid = remapper.remap_uid("1.2.3").to_string();
+
+    let map = remapper.export_map();
+    assert_eq!(map, format!("1.2.3,{remapped_uid}\n"));
+
+    let mut imported_remapper = UidRemapper::default();
+    assert_eq!(imported_remapper.import_map(&map), Ok(()));
+    assert_eq!(imported_remapper.remap_uid("1.2.3"), remapped_uid);
+
+    assert_eq!(
+      imported_remapper.import_map("\n1.2,1.3\n1.4;1.5\n"),
+      Err(DataError::new_value_invalid("UID map line 3 is invalid"))
+    );
+    assert_eq!(imported_remapper.len(), 1);
+  }
+
+  #[test]
+  fn new_with_salt_test() {
+    let mut a = UidRemapper::new_with_salt("salt");
+    let mut b = UidRemapper::new_with_salt("salt");
+    let mut c = UidRemapper::new_with_salt("other");
+
+    let uid = a.remap_uid("1.2.3").to_string();
+    assert!(uid.starts_with("2.25."));
+    assert!(unique_identifier::is_valid(&uid));
+
+    assert_eq!(b.remap_uid("1.2.3"), uid);
+    assert_ne!(c.remap_uid("1.2.3"), uid);
+  }
+
+  #[test]
+  fn new_test() {
+    assert!(UidRemapper::new("").is_ok());
+    assert!(UidRemapper::new("1.2.3").is_ok());
+    assert!(UidRemapper::new("1.2.").is_err());
+    assert!(UidRemapper::new(&"1.".repeat(31)).is_err());
+  }
+}
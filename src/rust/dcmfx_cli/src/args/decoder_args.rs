@@ -2,7 +2,7 @@ use clap::{Args, ValueEnum, builder::PossibleValue};
 
 use dcmfx::pixel_data::{
   PixelDataDecodeConfig,
-  decode::{HighThroughputJpeg2000Decoder, JpegXlDecoder},
+  decode::{HighThroughputJpeg2000Decoder, JpegLsDecoder, JpegXlDecoder},
 };
 
 #[derive(Args, Debug)]
@@ -10,39 +10,83 @@ pub struct DecoderArgs {
   #[arg(
     long,
     help_heading = "Pixel Data Decoding",
-    help = "The library to use for decoding High-Throughput JPEG 2000 pixel \
+    help = "The libraries to use for decoding High-Throughput JPEG 2000 pixel \
       data. The OpenJPH library is preferred because it is the fastest \
       available decoder. However, WASM builds of DCMfx always use OpenJPEG and \
       so testing that library via the CLI tool is sometimes useful.\n\
       \n\
+      Multiple libraries can be specified separated by commas, in which case \
+      each library is tried in order when the previous one fails due to \
+      invalid data.\n\
+      \n\
       There can be very slight differences in output between decoders when \
       decoding lossy High-Throughput JPEG 2000.",
-    default_value_t = HighThroughputJpeg2000DecoderArg::OpenJph
+    value_delimiter = ',',
+    default_values_t = [HighThroughputJpeg2000DecoderArg::OpenJph]
+  )]
+  high_throughput_jpeg_2000_decoder: Vec<HighThroughputJpeg2000DecoderArg>,
+
+  #[arg(
+    long,
+    help_heading = "Pixel Data Decoding",
+    help = "The libraries to use for decoding JPEG-LS pixel data. The CharLS \
+      library is preferred because it is the fastest available decoder. \
+      However, WASM builds of DCMfx always use the JPEG-LS decoder built into \
+      DCMfx and so testing that decoder via the CLI tool is sometimes useful.\n\
+      \n\
+      Multiple libraries can be specified separated by commas, in which case \
+      each library is tried in order when the previous one fails due to \
+      invalid data.\n\
+      \n\
+      There should be no difference in output between decoders.",
+    value_delimiter = ',',
+    default_values_t = [JpegLsDecoderArg::CharLs]
   )]
-  high_throughput_jpeg_2000_decoder: HighThroughputJpeg2000DecoderArg,
+  jpeg_ls_decoder: Vec<JpegLsDecoderArg>,
 
   #[arg(
     long,
     help_heading = "Pixel Data Decoding",
-    help = "The library to use for decoding JPEG XL pixel data. The libjxl \
+    help = "The libraries to use for decoding JPEG XL pixel data. The libjxl \
       library is preferred because it is the reference implementation and is \
       the fastest available decoder. However, WASM builds of DCMfx always use \
       jxl-oxide and so testing that library via the CLI tool is sometimes \
       useful.\n\
       \n\
+      Multiple libraries can be specified separated by commas, in which case \
+      each library is tried in order when the previous one fails due to \
+      invalid data.\n\
+      \n\
       There should be no difference in output between decoders.",
-    default_value_t = JpegXlDecoderArg::LibJxl
+    value_delimiter = ',',
+    default_values_t = [JpegXlDecoderArg::LibJxl]
   )]
-  jpeg_xl_decoder: JpegXlDecoderArg,
+  jpeg_xl_decoder: Vec<JpegXlDecoderArg>,
 }
 
 impl DecoderArgs {
-  pub fn pixel_data_decode_config(&self) -> PixelDataDecodeConfig {
+  pub fn pixel_data_decode_config(
+    &self,
+    thread_count: Option<usize>,
+  ) -> PixelDataDecodeConfig {
     PixelDataDecodeConfig {
-      high_throughput_jpeg_2000_decoder: self
+      high_throughput_jpeg_2000_decoders: self
         .high_throughput_jpeg_2000_decoder
-        .into(),
-      jpeg_xl_decoder: self.jpeg_xl_decoder.into(),
+        .iter()
+        .map(|decoder| (*decoder).into())
+        .collect(),
+      jpeg_ls_decoders: self
+        .jpeg_ls_decoder
+        .iter()
+        .map(|decoder| (*decoder).into())
+        .collect(),
+      jpeg_xl_decoders: self
+        .jpeg_xl_decoder
+        .iter()
+        .map(|decoder| (*decoder).into())
+        .collect(),
+      thread_count,
+      min_decoded_size: None,
     }
   }
 }
@@ -94,6 +138,47 @@ impl core::fmt::Display for HighThroughputJpeg2000DecoderArg {
   }
 }
 
+/// Enum for specifying the decoder to use for JPEG-LS pixel data.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JpegLsDecoderArg {
+  CharLs,
+  Dcmfx,
+}
+
+impl From<JpegLsDecoderArg> for JpegLsDecoder {
+  fn from(value: JpegLsDecoderArg) -> Self {
+    match value {
+      JpegLsDecoderArg::CharLs => JpegLsDecoder::CharLs,
+      JpegLsDecoderArg::Dcmfx => JpegLsDecoder::Dcmfx,
+    }
+  }
+}
+
+impl ValueEnum for JpegLsDecoderArg {
+  fn value_variants<'a>() -> &'a [Self] {
+    &[Self::CharLs, Self::Dcmfx]
+  }
+
+  fn to_possible_value(&self) -> Option<PossibleValue> {
+    Some(match self {
+      Self::CharLs => PossibleValue::new("charls")
+        .help("Use CharLS for decoding JPEG-LS pixel data."),
+      Self::Dcmfx => PossibleValue::new("dcmfx")
+        .help("Use the DCMfx decoder for decoding JPEG-LS pixel data."),
+    })
+  }
+}
+
+impl core::fmt::Display for JpegLsDecoderArg {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      JpegLsDecoderArg::CharLs => write!(f, "charls"),
+      JpegLsDecoderArg::Dcmfx => write!(f, "dcmfx"),
+    }
+  }
+}
+
 /// Enum for specifying the decoder to use for JPEG XL pixel data.
 ///
 #[derive(Debug, Clone, Copy, PartialEq)]
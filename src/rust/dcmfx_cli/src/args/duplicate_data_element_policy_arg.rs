@@ -0,0 +1,35 @@
+use clap::ValueEnum;
+use dcmfx::p10::DuplicateDataElementPolicy;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DuplicateDataElementPolicyArg {
+  /// Keep the first occurrence of a duplicate data element.
+  FirstWins,
+
+  /// Keep the last occurrence of a duplicate data element.
+  LastWins,
+
+  /// Treat duplicate data elements as an error.
+  Error,
+
+  /// Keep the first occurrence of a duplicate data element, and record later
+  /// occurrences separately.
+  KeepBoth,
+}
+
+impl DuplicateDataElementPolicyArg {
+  pub fn duplicate_data_element_policy(&self) -> DuplicateDataElementPolicy {
+    match self {
+      DuplicateDataElementPolicyArg::FirstWins => {
+        DuplicateDataElementPolicy::FirstWins
+      }
+      DuplicateDataElementPolicyArg::LastWins => {
+        DuplicateDataElementPolicy::LastWins
+      }
+      DuplicateDataElementPolicyArg::Error => DuplicateDataElementPolicy::Error,
+      DuplicateDataElementPolicyArg::KeepBoth => {
+        DuplicateDataElementPolicy::KeepBoth
+      }
+    }
+  }
+}
@@ -0,0 +1,34 @@
+use clap::ValueEnum;
+use dcmfx::pixel_data::GrayscaleOutputDepth;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum GrayscaleOutputDepthArg {
+  /// Apply the full grayscale pipeline, including VOI windowing, and output
+  /// 8-bit values.
+  Windowed8,
+
+  /// Apply the full grayscale pipeline, including VOI windowing, and output
+  /// 16-bit values.
+  Windowed16,
+
+  /// Apply only the Modality LUT, and linearly map the full range of its output
+  /// to 16-bit values.
+  Linear16,
+
+  /// Output stored values unaltered as 16-bit values. Signed values are offset
+  /// so that the smallest storable value maps to zero.
+  StoredValues,
+}
+
+impl GrayscaleOutputDepthArg {
+  pub fn grayscale_output_depth(&self) -> GrayscaleOutputDepth {
+    match self {
+      GrayscaleOutputDepthArg::Windowed8 => GrayscaleOutputDepth::Windowed8,
+      GrayscaleOutputDepthArg::Windowed16 => GrayscaleOutputDepth::Windowed16,
+      GrayscaleOutputDepthArg::Linear16 => GrayscaleOutputDepth::Linear16,
+      GrayscaleOutputDepthArg::StoredValues => {
+        GrayscaleOutputDepth::StoredValues
+      }
+    }
+  }
+}
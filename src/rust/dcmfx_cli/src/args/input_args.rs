@@ -9,9 +9,14 @@ use dcmfx::{
 };
 use tokio::io::AsyncBufReadExt;
 
-use crate::utils::{
-  input_source::InputSource,
-  object_store::{local_path_to_store_and_path, object_url_to_store_and_path},
+use crate::{
+  args::duplicate_data_element_policy_arg::DuplicateDataElementPolicyArg,
+  utils::{
+    input_source::InputSource,
+    object_store::{
+      local_path_to_store_and_path, object_url_to_store_and_path,
+    },
+  },
 };
 
 #[derive(Args, Debug)]
@@ -55,15 +60,33 @@ pub struct P10InputArgs {
     value_parser = default_transfer_syntax_arg_validate,
   )]
   pub default_transfer_syntax: Option<&'static TransferSyntax>,
+
+  #[arg(
+    long,
+    help_heading = "Input",
+    help = "How to handle data elements that occur more than once in the same \
+      data set or sequence item.\n\
+      \n\
+      Defaults to 'last-wins'."
+  )]
+  pub duplicate_data_elements: Option<DuplicateDataElementPolicyArg>,
 }
 
 impl P10InputArgs {
   pub fn p10_read_config(&self) -> P10ReadConfig {
-    P10ReadConfig::default().default_transfer_syntax(
+    let mut config = P10ReadConfig::default().default_transfer_syntax(
       self
         .default_transfer_syntax
         .unwrap_or(&transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN),
-    )
+    );
+
+    if let Some(duplicate_data_elements) = self.duplicate_data_elements {
+      config = config.duplicate_data_element_policy(
+        duplicate_data_elements.duplicate_data_element_policy(),
+      );
+    }
+
+    config
   }
 }
 
@@ -4,10 +4,13 @@ use dcmfx::{
 };
 
 pub mod decoder_args;
-pub mod frame_selection_arg;
+pub mod duplicate_data_element_policy_arg;
+pub mod grayscale_output_depth_arg;
 pub mod input_args;
 pub mod photometric_interpretation_arg;
 pub mod planar_configuration_arg;
+pub mod progression_order_arg;
+pub mod sop_class_arg;
 pub mod standard_color_palette_arg;
 pub mod transfer_syntax_arg;
 pub mod transform_arg;
@@ -0,0 +1,32 @@
+use clap::ValueEnum;
+use dcmfx::pixel_data::encode::Jpeg2000ProgressionOrder;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ProgressionOrderArg {
+  /// Layer-resolution-component-position.
+  Lrcp,
+
+  /// Resolution-layer-component-position.
+  Rlcp,
+
+  /// Resolution-position-component-layer.
+  Rpcl,
+
+  /// Position-component-resolution-layer.
+  Pcrl,
+
+  /// Component-position-resolution-layer.
+  Cprl,
+}
+
+impl ProgressionOrderArg {
+  pub fn progression_order(&self) -> Jpeg2000ProgressionOrder {
+    match self {
+      ProgressionOrderArg::Lrcp => Jpeg2000ProgressionOrder::Lrcp,
+      ProgressionOrderArg::Rlcp => Jpeg2000ProgressionOrder::Rlcp,
+      ProgressionOrderArg::Rpcl => Jpeg2000ProgressionOrder::Rpcl,
+      ProgressionOrderArg::Pcrl => Jpeg2000ProgressionOrder::Pcrl,
+      ProgressionOrderArg::Cprl => Jpeg2000ProgressionOrder::Cprl,
+    }
+  }
+}
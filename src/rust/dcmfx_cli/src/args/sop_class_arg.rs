@@ -0,0 +1,70 @@
+use clap::ValueEnum;
+
+use dcmfx::core::{SopClass, sop_class};
+
+/// Enum for specifying one of the SOP Classes supported by the `generate`
+/// command as a CLI argument.
+///
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SopClassArg {
+  /// Computed Radiography Image Storage.
+  Cr,
+
+  /// CT Image Storage.
+  Ct,
+
+  /// Digital X-Ray Image Storage - For Presentation.
+  Dx,
+
+  /// MR Image Storage.
+  Mr,
+
+  /// Multi-frame Grayscale Byte Secondary Capture Image Storage.
+  MultiFrameGrayscaleByteSc,
+
+  /// Multi-frame Grayscale Word Secondary Capture Image Storage.
+  MultiFrameGrayscaleWordSc,
+
+  /// Multi-frame True Color Secondary Capture Image Storage.
+  MultiFrameTrueColorSc,
+
+  /// Secondary Capture Image Storage.
+  Sc,
+
+  /// Ultrasound Image Storage.
+  Us,
+
+  /// Ultrasound Multi-frame Image Storage.
+  UsMultiFrame,
+
+  /// X-Ray Angiographic Image Storage.
+  Xa,
+}
+
+impl From<SopClassArg> for &'static SopClass {
+  fn from(value: SopClassArg) -> Self {
+    match value {
+      SopClassArg::Cr => &sop_class::COMPUTED_RADIOGRAPHY_IMAGE_STORAGE,
+      SopClassArg::Ct => &sop_class::CT_IMAGE_STORAGE,
+      SopClassArg::Dx => {
+        &sop_class::DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION
+      }
+      SopClassArg::Mr => &sop_class::MR_IMAGE_STORAGE,
+      SopClassArg::MultiFrameGrayscaleByteSc => {
+        &sop_class::MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE
+      }
+      SopClassArg::MultiFrameGrayscaleWordSc => {
+        &sop_class::MULTI_FRAME_GRAYSCALE_WORD_SECONDARY_CAPTURE_IMAGE_STORAGE
+      }
+      SopClassArg::MultiFrameTrueColorSc => {
+        &sop_class::MULTI_FRAME_TRUE_COLOR_SECONDARY_CAPTURE_IMAGE_STORAGE
+      }
+      SopClassArg::Sc => &sop_class::SECONDARY_CAPTURE_IMAGE_STORAGE,
+      SopClassArg::Us => &sop_class::ULTRASOUND_IMAGE_STORAGE,
+      SopClassArg::UsMultiFrame => {
+        &sop_class::ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE
+      }
+      SopClassArg::Xa => &sop_class::X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE,
+    }
+  }
+}
@@ -1,33 +1,134 @@
-use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
 use dcmfx::pixel_data::{StandardColorPalette, standard_color_palettes};
 
-#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
-pub enum StandardColorPaletteArg {
-  HotIron,
-  Pet,
-  HotMetalBlue,
-  Pet20Step,
-  Spring,
-  Summer,
-  Fall,
-  Winter,
-}
+/// A color palette specified by name. This is either the name of a standard
+/// color palette, e.g. 'hot-iron', the name of a user color palette stored in
+/// the `palettes` subdirectory of the user configuration directory, or the path
+/// to a color palette file.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StandardColorPaletteArg(&'static StandardColorPalette);
 
 impl StandardColorPaletteArg {
   pub fn color_palette(&self) -> &'static StandardColorPalette {
-    match self {
-      StandardColorPaletteArg::HotIron => &standard_color_palettes::HOT_IRON,
-      StandardColorPaletteArg::Pet => &standard_color_palettes::PET,
-      StandardColorPaletteArg::HotMetalBlue => {
-        &standard_color_palettes::HOT_METAL_BLUE
-      }
-      StandardColorPaletteArg::Pet20Step => {
-        &standard_color_palettes::PET_20_STEP
-      }
-      StandardColorPaletteArg::Spring => &standard_color_palettes::SPRING,
-      StandardColorPaletteArg::Summer => &standard_color_palettes::SUMMER,
-      StandardColorPaletteArg::Fall => &standard_color_palettes::FALL,
-      StandardColorPaletteArg::Winter => &standard_color_palettes::WINTER,
-    }
+    self.0
+  }
+}
+
+/// Parses a color palette argument. See [`StandardColorPaletteArg`] for
+/// details.
+///
+pub fn parse_color_palette_arg(
+  s: &str,
+) -> Result<StandardColorPaletteArg, String> {
+  parse_color_palette(s).map(StandardColorPaletteArg)
+}
+
+/// Returns the standard color palette, user color palette, or color palette
+/// file with the given name.
+///
+/// Color palettes loaded from files are leaked so that they have a static
+/// lifetime. They are small, and only loaded once when parsing arguments.
+///
+pub fn parse_color_palette(
+  name: &str,
+) -> Result<&'static StandardColorPalette, String> {
+  if let Some(color_palette) = standard_color_palettes::from_name(name) {
+    return Ok(color_palette);
   }
+
+  let path = Path::new(name);
+  let path = if path.is_file() {
+    Some(path.to_path_buf())
+  } else {
+    user_color_palette_path(name)
+  };
+
+  let Some(path) = path else {
+    return Err(format!(
+      "Unknown color palette '{name}', valid standard color palettes are: {}",
+      standard_color_palettes::ALL
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+    ));
+  };
+
+  let color_palette = read_color_palette_file(&path).map_err(|e| {
+    format!("Invalid color palette file '{}': {}", path.display(), e)
+  })?;
+
+  Ok(Box::leak(Box::new(color_palette)))
+}
+
+/// Returns the path of the user color palette with the given name, if it
+/// exists. User color palettes are stored in the `palettes` subdirectory of the
+/// user configuration directory as `<name>.csv` or `<name>.json` files.
+///
+fn user_color_palette_path(name: &str) -> Option<PathBuf> {
+  let dir = crate::utils::user_config_directory()?.join("palettes");
+
+  ["csv", "json"]
+    .iter()
+    .map(|extension| dir.join(format!("{name}.{extension}")))
+    .find(|path| path.is_file())
+}
+
+/// Reads a color palette from a CSV or JSON file. The format is determined by
+/// the file's extension.
+///
+fn read_color_palette_file(
+  path: &Path,
+) -> Result<StandardColorPalette, String> {
+  let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+  let is_json = path
+    .extension()
+    .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+
+  if is_json {
+    color_palette_from_json(&content)
+  } else {
+    StandardColorPalette::from_csv(&content)
+  }
+}
+
+/// Parses a color palette from JSON. The JSON is an array of either 256
+/// `[red, green, blue]` colors, or `[grayscale value, red, green, blue]`
+/// segments.
+///
+pub fn color_palette_from_json(
+  json: &str,
+) -> Result<StandardColorPalette, String> {
+  let rows: Vec<Vec<u8>> =
+    serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+  if rows.iter().all(|row| row.len() == 3) {
+    let colors: Vec<[u8; 3]> =
+      rows.iter().map(|row| [row[0], row[1], row[2]]).collect();
+
+    StandardColorPalette::from_colors(&colors)
+  } else if rows.iter().all(|row| row.len() == 4) {
+    let segments: Vec<(u8, [u8; 3])> = rows
+      .iter()
+      .map(|row| (row[0], [row[1], row[2], row[3]]))
+      .collect();
+
+    StandardColorPalette::from_segments(&segments)
+  } else {
+    Err(
+      "Color palette entries must all be [red, green, blue] or [grayscale \
+       value, red, green, blue]"
+        .to_string(),
+    )
+  }
+}
+
+/// Converts a color palette to JSON as an array of its 256 `[red, green,
+/// blue]` colors.
+///
+pub fn color_palette_to_json(color_palette: &StandardColorPalette) -> String {
+  serde_json::to_string(&color_palette.colors()).unwrap()
 }
@@ -26,6 +26,18 @@ pub enum TransformArg {
 }
 
 impl TransformArg {
+  /// Returns whether this transform swaps the width and height of images.
+  ///
+  pub fn swaps_dimensions(&self) -> bool {
+    matches!(
+      self,
+      Self::Rotate90
+        | Self::Rotate270
+        | Self::Rotate90FlipH
+        | Self::Rotate270FlipH
+    )
+  }
+
   pub fn orientation(&self) -> image::metadata::Orientation {
     match self {
       Self::Rotate90 => image::metadata::Orientation::Rotate90,
@@ -0,0 +1,543 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicBool, Ordering},
+};
+
+use clap::{Args, ValueEnum};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use dcmfx::{
+  core::*,
+  p10::*,
+  pixel_data::{
+    DataSetPixelDataExtensions, PixelDataDecodeError, PixelDataRenderer,
+    iods::voi_lut_module::VoiWindow,
+    transforms::P10PixelDataFrameTransformError,
+  },
+  volume::{Volume, VolumeError},
+};
+
+use crate::utils::{self, OutputTarget, tiff_encoder::TiffEncoder};
+
+pub const ABOUT: &str = "Scans directories for DICOM P10 files, groups them \
+  by series, and converts each series to a PNG stack, NIfTI, or multi-page \
+  TIFF file";
+
+#[derive(Args)]
+pub struct ConvertSeriesArgs {
+  #[arg(
+    long,
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
+  )]
+  concurrency: Option<usize>,
+
+  #[arg(
+    required = true,
+    help_heading = "Input",
+    help = "Directories to recursively search for DICOM P10 files."
+  )]
+  directories: Vec<PathBuf>,
+
+  #[arg(
+    long,
+    short,
+    help_heading = "Input",
+    help = "Extension that a file must have in order to be checked for whether \
+      it's a DICOM file. The extension check is not case sensitive."
+  )]
+  extension: Option<String>,
+
+  #[arg(
+    long,
+    short,
+    value_enum,
+    help_heading = "Output",
+    help = "The output format for each series.",
+    default_value_t = SeriesFormat::Nifti
+  )]
+  format: SeriesFormat,
+
+  #[arg(
+    long,
+    short = 'd',
+    help_heading = "Output",
+    help = "The directory to write the converted series to. Each series is \
+      named using its Series Number and Series Description, falling back to \
+      its Series Instance UID when these aren't present."
+  )]
+  output_directory: PathBuf,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Overwrite output files if they already exist",
+    default_value_t = false
+  )]
+  overwrite: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum SeriesFormat {
+  /// A PNG file for each frame in the series, in instance number order, with
+  /// monochrome frames rendered to 8-bit grayscale.
+  Png,
+
+  /// A single-file NIfTI-1 image of the series assembled into a 3D volume,
+  /// with 32-bit float voxels. This requires the series to be a consistent
+  /// stack of single-frame slices.
+  Nifti,
+
+  /// A multi-page TIFF file containing every frame in the series, in instance
+  /// number order, with monochrome frames rendered to 8-bit grayscale.
+  Tiff,
+}
+
+/// The files in a series found when scanning the input directories.
+///
+#[derive(Default)]
+struct SeriesFiles {
+  series_number: Option<i64>,
+  series_description: Option<String>,
+  instances: Vec<(Option<i64>, PathBuf)>,
+}
+
+#[allow(clippy::enum_variant_names)]
+enum ConvertSeriesError {
+  P10Error(P10Error),
+  DataError(DataError),
+  PixelDataDecodeError(PixelDataDecodeError),
+  ImageError(image::ImageError),
+  TiffError(String),
+  VolumeError(VolumeError),
+}
+
+impl ConvertSeriesError {
+  /// Returns lines of text that describe this error in a human-readable
+  /// format.
+  ///
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::P10Error(e) => e.to_lines(task_description),
+      Self::DataError(e) => e.to_lines(task_description),
+      Self::PixelDataDecodeError(e) => e.to_lines(task_description),
+      Self::ImageError(e) => vec![
+        format!("Image error {task_description}"),
+        "".to_string(),
+        format!("  Error: {e}"),
+      ],
+      Self::TiffError(e) => vec![
+        format!("TIFF encoding error {task_description}"),
+        "".to_string(),
+        format!("  Error: {e}"),
+      ],
+      Self::VolumeError(e) => e.to_lines(task_description),
+    }
+  }
+}
+
+impl From<P10PixelDataFrameTransformError> for ConvertSeriesError {
+  fn from(e: P10PixelDataFrameTransformError) -> Self {
+    match e {
+      P10PixelDataFrameTransformError::DataError(e) => Self::DataError(e),
+      P10PixelDataFrameTransformError::P10Error(e) => Self::P10Error(e),
+    }
+  }
+}
+
+/// The data elements read from each file when grouping files into series.
+///
+const SERIES_TAGS: [DataElementTag; 4] = [
+  dictionary::SERIES_INSTANCE_UID.tag,
+  dictionary::SERIES_NUMBER.tag,
+  dictionary::SERIES_DESCRIPTION.tag,
+  dictionary::INSTANCE_NUMBER.tag,
+];
+
+pub async fn run(args: ConvertSeriesArgs) -> Result<(), ()> {
+  OutputTarget::set_overwrite(args.overwrite);
+
+  crate::validate_output_args(None, Some(&args.output_directory)).await;
+
+  // Check that all input directories are valid
+  for dir in args.directories.iter() {
+    if !dir.is_dir() {
+      crate::utils::exit_with_error(
+        &format!("{:?} is not a directory", dir),
+        "",
+      );
+    }
+  }
+
+  let series = match find_series(&args).await {
+    Ok(series) => series,
+
+    Err((path, e)) => {
+      error::print_error_lines(
+        &e.to_lines(&format!("reading \"{}\"", path.display())),
+      );
+
+      return Err(());
+    }
+  };
+
+  let series = name_series(series);
+
+  // Convert each series, reporting errors as they occur so that one invalid
+  // series doesn't prevent the others from being converted
+  let is_error = AtomicBool::new(false);
+
+  let _ = utils::run_tasks(
+    utils::task_count(args.concurrency),
+    futures::stream::iter(series),
+    async |(name, series_files): (String, SeriesFiles)| {
+      if let Err(e) = convert_series(&name, series_files, &args).await {
+        error::print_error_lines(
+          &e.to_lines(&format!("converting series \"{name}\"")),
+        );
+
+        is_error.store(true, Ordering::Relaxed);
+      }
+
+      Ok::<(), ()>(())
+    },
+  )
+  .await;
+
+  if is_error.load(Ordering::Relaxed) {
+    Err(())
+  } else {
+    Ok(())
+  }
+}
+
+/// Scans the input directories for DICOM P10 files and groups them by their
+/// Series Instance UID. Files that aren't DICOM P10, or that don't have a
+/// Series Instance UID, are ignored.
+///
+async fn find_series(
+  args: &ConvertSeriesArgs,
+) -> Result<HashMap<String, SeriesFiles>, (PathBuf, ConvertSeriesError)> {
+  // Convert extension to lowercase for comparison
+  let extension = args.extension.as_ref().map(|e| e.to_lowercase());
+
+  let file_iterator = args.directories.clone().into_iter().flat_map(|dir| {
+    walkdir::WalkDir::new(&dir)
+      .into_iter()
+      .filter_map(move |entry| match entry {
+        Ok(entry) => {
+          if entry.file_type().is_file() {
+            Some(entry.path().to_path_buf())
+          } else {
+            None
+          }
+        }
+
+        Err(e) => {
+          crate::utils::exit_with_error(
+            &format!("Failed listing directory '{}'", dir.display()),
+            e,
+          );
+        }
+      })
+  });
+
+  let series = Mutex::new(HashMap::<String, SeriesFiles>::new());
+
+  utils::run_tasks(
+    utils::task_count(args.concurrency),
+    futures::stream::iter(file_iterator),
+    async |path: PathBuf| {
+      // Check file's extension is allowed, if this check was requested
+      if let Some(extension) = &extension {
+        let Some(path_extension) = path.extension() else {
+          return Ok(());
+        };
+
+        if path_extension.to_string_lossy().to_lowercase() != *extension {
+          return Ok(());
+        }
+      }
+
+      let data_set = match dcmfx::p10::read_file_partial_async(
+        &path,
+        &SERIES_TAGS,
+        Some(P10ReadConfig::default().require_dicm_prefix(true)),
+      )
+      .await
+      {
+        Ok(data_set) => data_set,
+        Err(P10Error::DicmPrefixNotPresent) => return Ok(()),
+        Err(e) => return Err((path, ConvertSeriesError::P10Error(e))),
+      };
+
+      let Ok(series_instance_uid) =
+        data_set.get_string(dictionary::SERIES_INSTANCE_UID.tag)
+      else {
+        return Ok(());
+      };
+
+      let mut series = series.lock().await;
+      let series_files =
+        series.entry(series_instance_uid.to_string()).or_default();
+
+      if series_files.series_number.is_none() {
+        series_files.series_number =
+          data_set.get_int::<i64>(dictionary::SERIES_NUMBER.tag).ok();
+      }
+
+      if series_files.series_description.is_none() {
+        series_files.series_description = data_set
+          .get_string(dictionary::SERIES_DESCRIPTION.tag)
+          .ok()
+          .map(String::from);
+      }
+
+      let instance_number = data_set
+        .get_int::<i64>(dictionary::INSTANCE_NUMBER.tag)
+        .ok();
+
+      series_files.instances.push((instance_number, path));
+
+      Ok(())
+    },
+  )
+  .await?;
+
+  Ok(series.into_inner())
+}
+
+/// Assigns an output name to each series based on its Series Number and Series
+/// Description, falling back to its Series Instance UID. Names that would
+/// otherwise be the same have the Series Instance UID appended. The returned
+/// series are sorted by Series Number, and their instances by Instance Number.
+///
+fn name_series(
+  series: HashMap<String, SeriesFiles>,
+) -> Vec<(String, SeriesFiles)> {
+  let mut series: Vec<(String, SeriesFiles)> = series.into_iter().collect();
+  series.sort_by(|(uid_a, a), (uid_b, b)| {
+    (a.series_number, uid_a).cmp(&(b.series_number, uid_b))
+  });
+
+  let base_name = |series_files: &SeriesFiles| {
+    let mut parts = vec![];
+
+    if let Some(series_number) = series_files.series_number {
+      parts.push(series_number.to_string());
+    }
+
+    if let Some(series_description) = &series_files.series_description {
+      let series_description = sanitize_filename(series_description);
+      if !series_description.is_empty() {
+        parts.push(series_description);
+      }
+    }
+
+    parts.join("_")
+  };
+
+  let mut base_name_counts = HashMap::<String, usize>::new();
+  for (_, series_files) in series.iter() {
+    *base_name_counts.entry(base_name(series_files)).or_default() += 1;
+  }
+
+  series
+    .into_iter()
+    .map(|(series_instance_uid, mut series_files)| {
+      series_files.instances.sort();
+
+      let base_name = base_name(&series_files);
+
+      let name = if base_name.is_empty() {
+        series_instance_uid
+      } else if base_name_counts[&base_name] > 1 {
+        format!("{base_name}_{series_instance_uid}")
+      } else {
+        base_name
+      };
+
+      (name, series_files)
+    })
+    .collect()
+}
+
+/// Replaces characters that aren't safe to use in a filename with underscores.
+///
+fn sanitize_filename(s: &str) -> String {
+  s.trim()
+    .chars()
+    .map(|c| {
+      if c.is_alphanumeric() || c == '-' || c == '.' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect()
+}
+
+/// Reads all the files in a series and converts them to the output format.
+///
+async fn convert_series(
+  name: &str,
+  series_files: SeriesFiles,
+  args: &ConvertSeriesArgs,
+) -> Result<(), ConvertSeriesError> {
+  let mut data_sets = vec![];
+  for (_, path) in series_files.instances.iter() {
+    data_sets.push(read_file(path).await?);
+  }
+
+  let output_target_base =
+    OutputTarget::new(args.output_directory.join(name)).await;
+
+  match args.format {
+    SeriesFormat::Nifti => {
+      let volume =
+        Volume::from_data_sets(&data_sets.iter().collect::<Vec<_>>())
+          .map_err(ConvertSeriesError::VolumeError)?;
+
+      write_bytes_to_output_target(
+        output_target_base.append(".nii"),
+        &volume.to_nifti1(),
+      )
+      .await
+    }
+
+    SeriesFormat::Png => {
+      let mut index = 0;
+
+      for image in render_series_frames(&data_sets)? {
+        let mut image_buffer = std::io::Cursor::new(vec![]);
+        image
+          .write_to(&mut image_buffer, image::ImageFormat::Png)
+          .map_err(ConvertSeriesError::ImageError)?;
+
+        write_bytes_to_output_target(
+          output_target_base.append(&format!(".{index:04}.png")),
+          &image_buffer.into_inner(),
+        )
+        .await?;
+
+        index += 1;
+      }
+
+      Ok(())
+    }
+
+    SeriesFormat::Tiff => {
+      let pixel_spacing = data_sets.first().and_then(|data_set| match data_set
+        .get_floats(dictionary::PIXEL_SPACING.tag)
+        .ok()?
+        .as_slice()
+      {
+        [row_spacing, column_spacing] => Some([*row_spacing, *column_spacing]),
+        _ => None,
+      });
+
+      let mut tiff_encoder = TiffEncoder::new(pixel_spacing)
+        .map_err(ConvertSeriesError::TiffError)?;
+
+      for image in render_series_frames(&data_sets)? {
+        tiff_encoder
+          .add_frame(&image)
+          .map_err(ConvertSeriesError::TiffError)?;
+      }
+
+      write_bytes_to_output_target(
+        output_target_base.append(".tiff"),
+        &tiff_encoder.finish(),
+      )
+      .await
+    }
+  }
+}
+
+async fn read_file(path: &Path) -> Result<DataSet, ConvertSeriesError> {
+  dcmfx::p10::read_file_async(path, None)
+    .await
+    .map_err(ConvertSeriesError::P10Error)
+}
+
+/// Renders every frame of pixel data in the data sets of a series to 8-bit
+/// images. Monochrome frames that don't have a VOI LUT use the default VOI
+/// window of the first monochrome frame in the series, so that all frames are
+/// rendered consistently. Data sets without pixel data are skipped.
+///
+fn render_series_frames(
+  data_sets: &[DataSet],
+) -> Result<Vec<image::DynamicImage>, ConvertSeriesError> {
+  let mut images = vec![];
+  let mut series_voi_window: Option<VoiWindow> = None;
+
+  for data_set in data_sets {
+    if !data_set.has(dictionary::PIXEL_DATA.tag) {
+      continue;
+    }
+
+    let pixel_data_renderer = PixelDataRenderer::from_data_set(data_set)
+      .map_err(ConvertSeriesError::DataError)?;
+
+    for mut frame in data_set.get_pixel_data_frames()? {
+      let image = if pixel_data_renderer.image_pixel_module.is_monochrome() {
+        let monochrome_image = pixel_data_renderer
+          .decode_monochrome_frame(&mut frame)
+          .map_err(ConvertSeriesError::PixelDataDecodeError)?;
+
+        let mut grayscale_pipeline = pixel_data_renderer
+          .frame_grayscale_pipeline(frame.index())
+          .into_owned();
+
+        if grayscale_pipeline.voi_lut().is_empty() {
+          if series_voi_window.is_none() {
+            series_voi_window = monochrome_image.default_voi_window();
+          }
+
+          if let Some(voi_window) = series_voi_window.clone() {
+            grayscale_pipeline.set_voi_window(voi_window);
+          }
+        }
+
+        monochrome_image
+          .to_gray_u8_image(&grayscale_pipeline)
+          .into()
+      } else {
+        pixel_data_renderer
+          .decode_color_frame(&mut frame)
+          .map_err(ConvertSeriesError::PixelDataDecodeError)?
+          .into_rgb_u8_image()
+          .into()
+      };
+
+      images.push(image);
+    }
+  }
+
+  Ok(images)
+}
+
+async fn write_bytes_to_output_target(
+  output_target: OutputTarget,
+  bytes: &[u8],
+) -> Result<(), ConvertSeriesError> {
+  let output_stream_handle = output_target
+    .open_write_stream(true)
+    .await
+    .map_err(ConvertSeriesError::P10Error)?;
+
+  let mut output_stream = output_stream_handle.lock().await;
+
+  output_stream.write_all(bytes).await.map_err(|e| {
+    ConvertSeriesError::P10Error(P10Error::FileError {
+      when: "Writing series".into(),
+      details: e.to_string().into(),
+    })
+  })?;
+
+  output_target
+    .commit(&mut output_stream)
+    .await
+    .map_err(ConvertSeriesError::P10Error)
+}
@@ -13,11 +13,10 @@ pub const ABOUT: &str = "Converts DICOM P10 files to DICOM JSON files";
 pub struct ToJsonArgs {
   #[arg(
     long,
-    help = "The number of concurrent tasks to use. Defaults to the number of \
-      CPU cores.",
-    default_value_t = {num_cpus::get()}
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
   )]
-  concurrency: usize,
+  concurrency: Option<usize>,
 
   #[command(flatten)]
   input: crate::args::input_args::P10InputArgs,
@@ -151,7 +150,7 @@ pub async fn run(args: ToJsonArgs) -> Result<(), ()> {
   };
 
   let result = utils::run_tasks(
-    args.concurrency,
+    utils::task_count(args.concurrency),
     input_sources,
     async |input_source: InputSource| {
       let output_target = if let Some(output_filename) = &args.output_filename {
@@ -258,8 +257,8 @@ async fn input_source_to_json(
             .await
             .map_err(|e| {
               ToJsonError::P10Error(P10Error::FileError {
-                when: "Writing output file".to_string(),
-                details: e.to_string(),
+                when: "Writing output file".into(),
+                details: e.to_string().into(),
               })
             })?,
 
@@ -272,8 +271,8 @@ async fn input_source_to_json(
             Ok(()) => (),
             Err(e) => {
               return Err(ToJsonError::P10Error(P10Error::FileError {
-                when: "Writing output file".to_string(),
-                details: e.to_string(),
+                when: "Writing output file".into(),
+                details: e.to_string().into(),
               }));
             }
           };
@@ -282,8 +281,8 @@ async fn input_source_to_json(
             Ok(()) => (),
             Err(e) => {
               return Err(ToJsonError::P10Error(P10Error::FileError {
-                when: "Writing output file".to_string(),
-                details: e.to_string(),
+                when: "Writing output file".into(),
+                details: e.to_string().into(),
               }));
             }
           };
@@ -325,8 +324,8 @@ async fn input_source_to_json(
       .await
       .map_err(|e| {
         ToJsonError::P10Error(P10Error::FileError {
-          when: "Writing DICOM JSON to output stream".to_string(),
-          details: e.to_string(),
+          when: "Writing DICOM JSON to output stream".into(),
+          details: e.to_string().into(),
         })
       })?;
 
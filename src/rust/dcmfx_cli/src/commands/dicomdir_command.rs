@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+
+use dcmfx::{core::*, dicomdir::*, p10::*};
+
+pub const ABOUT: &str = "Creates DICOMDIR files that index the DICOM P10 files \
+  in a directory";
+
+#[derive(Args)]
+pub struct DicomdirArgs {
+  #[command(subcommand)]
+  command: DicomdirCommand,
+}
+
+#[derive(Subcommand)]
+enum DicomdirCommand {
+  #[command(about = CREATE_ABOUT)]
+  Create(CreateArgs),
+}
+
+const CREATE_ABOUT: &str = "Creates a DICOMDIR file in a directory that \
+  references all the DICOM P10 files in the directory and its subdirectories";
+
+#[derive(Args)]
+struct CreateArgs {
+  #[arg(
+    help = "The directory to create the DICOMDIR in. This directory is the \
+      root of the File-set."
+  )]
+  directory: PathBuf,
+
+  #[arg(
+    long,
+    help = "The File-set ID to store in the DICOMDIR. This is at most 16 \
+      characters long."
+  )]
+  file_set_id: Option<String>,
+
+  #[arg(
+    long,
+    help = "Overwrite the DICOMDIR file if it already exists",
+    default_value_t = false
+  )]
+  overwrite: bool,
+}
+
+/// The data elements read from each DICOM P10 file in order to create its
+/// directory records.
+///
+const DIRECTORY_RECORD_TAGS: [DataElementTag; 18] = [
+  dictionary::MEDIA_STORAGE_SOP_CLASS_UID.tag,
+  dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID.tag,
+  dictionary::TRANSFER_SYNTAX_UID.tag,
+  dictionary::SOP_CLASS_UID.tag,
+  dictionary::SOP_INSTANCE_UID.tag,
+  dictionary::STUDY_DATE.tag,
+  dictionary::STUDY_TIME.tag,
+  dictionary::ACCESSION_NUMBER.tag,
+  dictionary::MODALITY.tag,
+  dictionary::STUDY_DESCRIPTION.tag,
+  dictionary::SERIES_DESCRIPTION.tag,
+  dictionary::PATIENT_NAME.tag,
+  dictionary::PATIENT_ID.tag,
+  dictionary::STUDY_INSTANCE_UID.tag,
+  dictionary::SERIES_INSTANCE_UID.tag,
+  dictionary::STUDY_ID.tag,
+  dictionary::SERIES_NUMBER.tag,
+  dictionary::INSTANCE_NUMBER.tag,
+];
+
+#[allow(clippy::enum_variant_names)]
+enum CreateError {
+  IoError(std::io::Error),
+  P10Error(P10Error),
+  DicomDirError(DicomDirError),
+}
+
+pub async fn run(args: DicomdirArgs) -> Result<(), ()> {
+  match args.command {
+    DicomdirCommand::Create(args) => run_create(args).await,
+  }
+}
+
+async fn run_create(args: CreateArgs) -> Result<(), ()> {
+  if !args.directory.is_dir() {
+    crate::utils::exit_with_error(
+      &format!("{:?} is not a directory", args.directory),
+      "",
+    );
+  }
+
+  let output_filename = args.directory.join("DICOMDIR");
+
+  if !args.overwrite && output_filename.exists() {
+    crate::utils::exit_with_error(
+      &format!(
+        "Output file \"{}\" already exists",
+        output_filename.display()
+      ),
+      "Specify --overwrite to overwrite it",
+    );
+  }
+
+  match create(&args, &output_filename).await {
+    Ok(record_count) => {
+      println!(
+        "Wrote \"{}\" with {record_count} directory records",
+        output_filename.display()
+      );
+
+      Ok(())
+    }
+
+    Err((path, e)) => {
+      let task_description = format!("adding \"{}\"", path.display());
+
+      match e {
+        CreateError::IoError(e) => error::print_error_lines(&[e.to_string()]),
+        CreateError::P10Error(e) => e.print(&task_description),
+        CreateError::DicomDirError(e) => e.print(&task_description),
+      }
+
+      Err(())
+    }
+  }
+}
+
+async fn create(
+  args: &CreateArgs,
+  output_filename: &Path,
+) -> Result<usize, (PathBuf, CreateError)> {
+  let mut dicom_dir = DicomDir::new();
+  dicom_dir.file_set_id = args.file_set_id.clone();
+
+  // Visit files in name order so the generated DICOMDIR is deterministic
+  for entry in walkdir::WalkDir::new(&args.directory)
+    .sort_by_file_name()
+    .into_iter()
+  {
+    let entry = entry
+      .map_err(|e| (args.directory.clone(), CreateError::IoError(e.into())))?;
+
+    let path = entry.path();
+    if !entry.file_type().is_file() || path == output_filename {
+      continue;
+    }
+
+    add_file(&mut dicom_dir, &args.directory, path)
+      .await
+      .map_err(|e| (path.to_path_buf(), e))?;
+  }
+
+  dicom_dir
+    .write_p10_file(output_filename, None)
+    .map_err(|e| {
+      (output_filename.to_path_buf(), CreateError::DicomDirError(e))
+    })?;
+
+  Ok(dicom_dir.record_count())
+}
+
+async fn add_file(
+  dicom_dir: &mut DicomDir,
+  directory: &Path,
+  path: &Path,
+) -> Result<(), CreateError> {
+  // Files that aren't DICOM P10 files aren't part of the File-set
+  if !dcmfx::p10::is_valid_file_async(path).await {
+    return Ok(());
+  }
+
+  let data_set =
+    dcmfx::p10::read_file_partial_async(path, &DIRECTORY_RECORD_TAGS, None)
+      .await
+      .map_err(CreateError::P10Error)?;
+
+  // The referenced file ID is the file's path relative to the root of the
+  // File-set
+  let relative_path = path.strip_prefix(directory).unwrap_or(path);
+  let components = relative_path
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy().to_string())
+    .collect::<Vec<_>>();
+  let referenced_file_id =
+    components.iter().map(|c| c.as_str()).collect::<Vec<_>>();
+
+  dicom_dir
+    .add_file(&referenced_file_id, &data_set)
+    .map_err(CreateError::DicomDirError)
+}
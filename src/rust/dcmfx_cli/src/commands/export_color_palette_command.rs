@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use tokio::io::AsyncWriteExt;
+
+use dcmfx::{core::*, p10::*};
+
+use crate::{
+  args::standard_color_palette_arg::{
+    StandardColorPaletteArg, color_palette_to_json, parse_color_palette_arg,
+  },
+  utils::OutputTarget,
+};
+
+pub const ABOUT: &str = "Exports a standard or user color palette to a CSV or \
+  JSON file that can be edited and used as a custom color palette";
+
+#[derive(Args)]
+pub struct ExportColorPaletteArgs {
+  #[arg(
+    value_parser = parse_color_palette_arg,
+    help = "The color palette to export. This is the name of a standard color \
+      palette, i.e. 'hot-iron', 'pet', 'hot-metal-blue', 'pet-20-step', \
+      'spring', 'summer', 'fall', or 'winter', the name of a user color \
+      palette, or the path to a color palette file."
+  )]
+  color_palette: StandardColorPaletteArg,
+
+  #[arg(
+    long,
+    short,
+    value_enum,
+    help_heading = "Output",
+    help = "The output format for the color palette.",
+    default_value_t = ColorPaletteFormat::Csv
+  )]
+  format: ColorPaletteFormat,
+
+  #[arg(
+    long,
+    short,
+    help_heading = "Output",
+    help = "The name of the output file. Specify '-' to write to stdout.",
+    default_value = "-"
+  )]
+  output_filename: PathBuf,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Overwrite the output file if it already exists",
+    default_value_t = false
+  )]
+  overwrite: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum ColorPaletteFormat {
+  /// A CSV file with a header line followed by the red, green, and blue values
+  /// of each of the 256 colors.
+  Csv,
+
+  /// A JSON array of the 256 colors, each of which is an array of its red,
+  /// green, and blue values.
+  Json,
+}
+
+pub async fn run(args: ExportColorPaletteArgs) -> Result<(), ()> {
+  OutputTarget::set_overwrite(args.overwrite);
+
+  let color_palette = args.color_palette.color_palette();
+
+  let content = match args.format {
+    ColorPaletteFormat::Csv => color_palette.to_csv(),
+    ColorPaletteFormat::Json => color_palette_to_json(color_palette),
+  };
+
+  match write_output(&args, content.as_bytes()).await {
+    Ok(()) => Ok(()),
+
+    Err(e) => {
+      error::print_error_lines(&e.to_lines("exporting color palette"));
+      Err(())
+    }
+  }
+}
+
+async fn write_output(
+  args: &ExportColorPaletteArgs,
+  bytes: &[u8],
+) -> Result<(), P10Error> {
+  let output_target = OutputTarget::new(&args.output_filename).await;
+
+  let output_stream_handle = output_target.open_write_stream(true).await?;
+  let mut output_stream = output_stream_handle.lock().await;
+
+  output_stream
+    .write_all(bytes)
+    .await
+    .map_err(|e| P10Error::FileError {
+      when: "Writing color palette".into(),
+      details: e.to_string().into(),
+    })?;
+
+  output_target.commit(&mut output_stream).await
+}
@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+use dcmfx::{core::*, p10::*, volume::*};
+
+use crate::utils::{InputSource, OutputTarget};
+
+pub const ABOUT: &str = "Assembles the single-frame DICOM P10 files of a \
+  series into a 3D volume, and exports it to a NIfTI file";
+
+#[derive(Args)]
+pub struct ExportVolumeArgs {
+  #[command(flatten)]
+  input: crate::args::input_args::P10InputArgs,
+
+  #[arg(
+    long,
+    short,
+    value_enum,
+    help_heading = "Output",
+    help = "The output format for the volume.",
+    default_value_t = VolumeFormat::Nifti
+  )]
+  format: VolumeFormat,
+
+  #[arg(
+    long,
+    short,
+    help_heading = "Output",
+    help = "The name of the output file. Specify '-' to write to stdout."
+  )]
+  output_filename: PathBuf,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Overwrite the output file if it already exists",
+    default_value_t = false
+  )]
+  overwrite: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum VolumeFormat {
+  /// A single-file NIfTI-1 image with 32-bit float voxels, i.e. a '.nii'
+  /// file.
+  Nifti,
+}
+
+enum ExportVolumeError {
+  P10Error(P10Error),
+  VolumeError(VolumeError),
+}
+
+impl ExportVolumeError {
+  /// Returns lines of text that describe this error in a human-readable
+  /// format.
+  ///
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::P10Error(e) => e.to_lines(task_description),
+      Self::VolumeError(e) => e.to_lines(task_description),
+    }
+  }
+}
+
+pub async fn run(args: ExportVolumeArgs) -> Result<(), ()> {
+  OutputTarget::set_overwrite(args.overwrite);
+
+  // Read all the input files into memory
+  let mut data_sets = vec![];
+  let mut input_sources = args.input.base.input_sources().await;
+
+  while let Some(input_source) = input_sources.next().await {
+    match read_input_source(&input_source, &args).await {
+      Ok(data_set) => data_sets.push(data_set),
+
+      Err(ExportVolumeError::P10Error(P10Error::DicmPrefixNotPresent))
+        if args.input.ignore_invalid => {}
+
+      Err(e) => {
+        error::print_error_lines(
+          &e.to_lines(&format!("reading \"{input_source}\"")),
+        );
+
+        return Err(());
+      }
+    }
+  }
+
+  match export_volume(&data_sets, &args).await {
+    Ok(()) => Ok(()),
+
+    Err(e) => {
+      error::print_error_lines(&e.to_lines("exporting volume"));
+      Err(())
+    }
+  }
+}
+
+async fn read_input_source(
+  input_source: &InputSource,
+  args: &ExportVolumeArgs,
+) -> Result<DataSet, ExportVolumeError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(ExportVolumeError::P10Error)?;
+
+  dcmfx::p10::read_stream_async(&mut stream, Some(args.input.p10_read_config()))
+    .await
+    .map_err(|(e, _)| ExportVolumeError::P10Error(e))
+}
+
+/// Assembles the data sets into a volume and writes it to the output file in
+/// the requested format.
+///
+async fn export_volume(
+  data_sets: &[DataSet],
+  args: &ExportVolumeArgs,
+) -> Result<(), ExportVolumeError> {
+  let volume = Volume::from_data_sets(&data_sets.iter().collect::<Vec<_>>())
+    .map_err(ExportVolumeError::VolumeError)?;
+
+  let bytes = match args.format {
+    VolumeFormat::Nifti => volume.to_nifti1(),
+  };
+
+  let output_target = OutputTarget::new(&args.output_filename).await;
+
+  let output_stream_handle = output_target
+    .open_write_stream(true)
+    .await
+    .map_err(ExportVolumeError::P10Error)?;
+
+  let mut output_stream = output_stream_handle.lock().await;
+
+  output_stream.write_all(&bytes).await.map_err(|e| {
+    ExportVolumeError::P10Error(P10Error::FileError {
+      when: "Writing volume".into(),
+      details: e.to_string().into(),
+    })
+  })?;
+
+  output_target
+    .commit(&mut output_stream)
+    .await
+    .map_err(ExportVolumeError::P10Error)
+}
@@ -0,0 +1,268 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use tokio::io::AsyncReadExt;
+
+use dcmfx::{
+  core::*, json::*, p10::*, pixel_data::image_import::*, pixel_data::*,
+};
+
+use crate::{
+  args::transfer_syntax_arg::TransferSyntaxArg,
+  utils::{self, InputSource, OutputTarget},
+};
+
+pub const ABOUT: &str = "Converts PNG, JPEG, and TIFF images to DICOM P10 \
+  files that use the Secondary Capture or VL Photographic SOP Class";
+
+#[derive(Args)]
+pub struct FromImageArgs {
+  #[arg(
+    long,
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
+  )]
+  concurrency: Option<usize>,
+
+  #[command(flatten)]
+  input: crate::args::input_args::BaseInputArgs,
+
+  #[arg(
+    long,
+    help = "A DICOM JSON file containing data elements to include in every \
+      output DICOM P10 file, e.g. patient and study details. Its File Meta \
+      Information, Image Pixel Module, Pixel Data, SOP Class UID, and SOP \
+      Instance UID are ignored."
+  )]
+  template: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help = "The SOP Class of the output DICOM P10 files. VL Photographic \
+      Image Storage requires images with 8-bit samples.",
+    default_value = "sc"
+  )]
+  sop_class: ImageSopClassArg,
+
+  #[arg(
+    long,
+    help = "The transfer syntax of the output DICOM P10 files. Pixel data is \
+      encoded into this transfer syntax.",
+    default_value = "explicit-vr-little-endian"
+  )]
+  transfer_syntax: TransferSyntaxArg,
+
+  #[arg(
+    long,
+    help = "When encoding into a lossy transfer syntax, specifies the quality \
+      level in the range 1-100.\n\
+      \n\
+      Default value: 90",
+    value_parser = clap::value_parser!(u8).range(1..=100),
+  )]
+  quality: Option<u8>,
+
+  #[arg(
+    long,
+    short,
+    help_heading = "Output",
+    help = "The name of the DICOM P10 output file. By default the output \
+      DICOM P10 file is the name of the input file with '.dcm' appended. \
+      Specify '-' to write to stdout."
+  )]
+  output_filename: Option<PathBuf>,
+
+  #[arg(
+    long,
+    short = 'd',
+    help_heading = "Output",
+    help = "The directory to write output files into. The names of the output \
+      DICOM P10 files will be the name of the input file with '.dcm' \
+      appended."
+  )]
+  output_directory: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Overwrite any output files that already exist",
+    default_value_t = false
+  )]
+  overwrite: bool,
+}
+
+/// Enum for specifying the SOP Class of DICOM P10 files created from images.
+///
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ImageSopClassArg {
+  /// Secondary Capture Image Storage.
+  Sc,
+
+  /// VL Photographic Image Storage.
+  VlPhotographic,
+}
+
+impl From<ImageSopClassArg> for &'static SopClass {
+  fn from(value: ImageSopClassArg) -> Self {
+    match value {
+      ImageSopClassArg::Sc => &sop_class::SECONDARY_CAPTURE_IMAGE_STORAGE,
+      ImageSopClassArg::VlPhotographic => {
+        &sop_class::VL_PHOTOGRAPHIC_IMAGE_STORAGE
+      }
+    }
+  }
+}
+
+enum FromImageError {
+  P10Error(P10Error),
+  ImageImportError(ImageImportError),
+}
+
+pub async fn run(args: FromImageArgs) -> Result<(), ()> {
+  crate::validate_output_args(
+    args.output_filename.as_ref(),
+    args.output_directory.as_ref(),
+  )
+  .await;
+
+  let Some(transfer_syntax) = args.transfer_syntax.as_transfer_syntax() else {
+    utils::exit_with_error(
+      "--transfer-syntax can't be 'pass-through' when converting images",
+      "",
+    );
+  };
+
+  let template = args.template.as_ref().map(|path| read_template(path));
+
+  let mut encode_config = PixelDataEncodeConfig::default();
+  encode_config.set_quality(args.quality.unwrap_or(90));
+
+  let config = ImageImportConfig::default()
+    .sop_class(args.sop_class.into())
+    .transfer_syntax(transfer_syntax)
+    .encode_config(encode_config);
+
+  OutputTarget::set_overwrite(args.overwrite);
+
+  let input_sources = args.input.input_sources().await;
+
+  let result = utils::run_tasks(
+    utils::task_count(args.concurrency),
+    input_sources,
+    async |input_source: InputSource| {
+      let output_target = if let Some(output_filename) = &args.output_filename {
+        OutputTarget::new(output_filename).await
+      } else {
+        OutputTarget::from_input_source(
+          &input_source,
+          ".dcm",
+          &args.output_directory,
+        )
+        .await
+      };
+
+      match input_source_to_dcm(
+        &input_source,
+        output_target,
+        template.as_ref(),
+        &config,
+      )
+      .await
+      {
+        Ok(()) => Ok(()),
+
+        Err(e) => {
+          let task_description = format!("converting \"{input_source}\"");
+
+          Err(match e {
+            FromImageError::P10Error(e) => e.to_lines(&task_description),
+            FromImageError::ImageImportError(e) => {
+              e.to_lines(&task_description)
+            }
+          })
+        }
+      }
+    },
+  )
+  .await;
+
+  match result {
+    Ok(()) => Ok(()),
+
+    Err(lines) => {
+      error::print_error_lines(&lines);
+      Err(())
+    }
+  }
+}
+
+/// Reads the DICOM JSON template file, exiting with an error if it can't be
+/// read.
+///
+fn read_template(path: &PathBuf) -> DataSet {
+  let json = match std::fs::read_to_string(path) {
+    Ok(json) => json,
+    Err(e) => utils::exit_with_error(
+      &format!("Failed reading template \"{}\"", path.display()),
+      e,
+    ),
+  };
+
+  match DataSet::from_json(&json) {
+    Ok(data_set) => data_set,
+    Err(e) => utils::exit_with_error(
+      &format!("Invalid template \"{}\"", path.display()),
+      e,
+    ),
+  }
+}
+
+async fn input_source_to_dcm(
+  input_source: &InputSource,
+  output_target: OutputTarget,
+  template: Option<&DataSet>,
+  config: &ImageImportConfig,
+) -> Result<(), FromImageError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(FromImageError::P10Error)?;
+
+  let mut buffer = vec![];
+  if let Err(e) = stream.read_to_end(&mut buffer).await {
+    return Err(FromImageError::P10Error(P10Error::FileError {
+      when: "Reading file".into(),
+      details: e.to_string().into(),
+    }));
+  }
+
+  let image = image::load_from_memory(&buffer).map_err(|e| {
+    FromImageError::P10Error(P10Error::FileError {
+      when: "Reading image".into(),
+      details: e.to_string().into(),
+    })
+  })?;
+
+  let data_set = import_image(&image, template, config)
+    .map_err(FromImageError::ImageImportError)?;
+
+  // Open output stream
+  let output_stream = output_target
+    .open_write_stream(true)
+    .await
+    .map_err(FromImageError::P10Error)?;
+
+  // Get exclusive access to the output stream
+  let mut output_stream = output_stream.lock().await;
+
+  // Write P10 data to output stream
+  data_set
+    .write_p10_stream_async(&mut *output_stream, None)
+    .await
+    .map_err(FromImageError::P10Error)?;
+
+  output_target
+    .commit(&mut output_stream)
+    .await
+    .map_err(FromImageError::P10Error)
+}
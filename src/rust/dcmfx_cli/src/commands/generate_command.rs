@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use dcmfx::{core::*, p10::*, testgen::*};
+
+use crate::{
+  args::{sop_class_arg::SopClassArg, transfer_syntax_arg::TransferSyntaxArg},
+  utils::{self, OutputTarget},
+};
+
+pub const ABOUT: &str = "Generates a synthetic DICOM P10 file for use as test \
+  data";
+
+#[derive(Args)]
+pub struct GenerateArgs {
+  #[arg(
+    long,
+    help = "The SOP Class of the generated DICOM P10 file",
+    default_value = "sc"
+  )]
+  sop_class: SopClassArg,
+
+  #[arg(
+    long,
+    help = "The number of rows in each frame of pixel data",
+    default_value_t = 256
+  )]
+  rows: u16,
+
+  #[arg(
+    long,
+    help = "The number of columns in each frame of pixel data",
+    default_value_t = 256
+  )]
+  columns: u16,
+
+  #[arg(
+    long,
+    help = "The number of bits allocated for each pixel sample. Must be 8 or \
+      16.",
+    default_value_t = 16
+  )]
+  bits_allocated: u16,
+
+  #[arg(
+    long,
+    help = "The number of bits stored for each pixel sample. Defaults to the \
+      value of --bits-allocated."
+  )]
+  bits_stored: Option<u16>,
+
+  #[arg(
+    long,
+    help = "Generate RGB color pixel data rather than MONOCHROME2 grayscale \
+      pixel data. Requires --bits-allocated to be 8.",
+    default_value_t = false
+  )]
+  color: bool,
+
+  #[arg(
+    long,
+    help = "The number of frames of pixel data. Values greater than one \
+      require a multi-frame SOP Class.",
+    default_value_t = 1
+  )]
+  number_of_frames: u32,
+
+  #[arg(
+    long,
+    help = "The transfer syntax of the generated DICOM P10 file. Pixel data \
+      is encoded into this transfer syntax.",
+    default_value = "explicit-vr-little-endian"
+  )]
+  transfer_syntax: TransferSyntaxArg,
+
+  #[arg(
+    long,
+    help = "The seed used to generate UIDs, identifiers, and pixel data. The \
+      same arguments and seed always generate the same DICOM P10 file.",
+    default_value_t = 0
+  )]
+  seed: u64,
+
+  #[arg(
+    long,
+    short,
+    help_heading = "Output",
+    help = "The name of the DICOM P10 output file. Specify '-' to write to \
+      stdout."
+  )]
+  output_filename: PathBuf,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Overwrite the output file if it already exists",
+    default_value_t = false
+  )]
+  overwrite: bool,
+}
+
+enum GenerateError {
+  TestDataSetError(TestDataSetError),
+  P10Error(P10Error),
+}
+
+pub async fn run(args: GenerateArgs) -> Result<(), ()> {
+  let Some(transfer_syntax) = args.transfer_syntax.as_transfer_syntax() else {
+    utils::exit_with_error(
+      "--transfer-syntax can't be 'pass-through' when generating a DICOM P10 \
+        file",
+      "",
+    );
+  };
+
+  OutputTarget::set_overwrite(args.overwrite);
+
+  let config = TestDataSetConfig::default()
+    .sop_class(args.sop_class.into())
+    .matrix_size(args.rows, args.columns)
+    .bit_depth(
+      args.bits_allocated,
+      args.bits_stored.unwrap_or(args.bits_allocated),
+    )
+    .color(args.color)
+    .number_of_frames(args.number_of_frames)
+    .transfer_syntax(transfer_syntax)
+    .seed(args.seed);
+
+  match generate(&config, &args.output_filename).await {
+    Ok(()) => Ok(()),
+
+    Err(e) => {
+      let task_description =
+        format!("generating \"{}\"", args.output_filename.display());
+
+      error::print_error_lines(&match e {
+        GenerateError::TestDataSetError(e) => e.to_lines(&task_description),
+        GenerateError::P10Error(e) => e.to_lines(&task_description),
+      });
+
+      Err(())
+    }
+  }
+}
+
+async fn generate(
+  config: &TestDataSetConfig,
+  output_filename: &PathBuf,
+) -> Result<(), GenerateError> {
+  let data_set =
+    generate_data_set(config).map_err(GenerateError::TestDataSetError)?;
+
+  let output_target = OutputTarget::new(output_filename).await;
+
+  // Open output stream
+  let output_stream = output_target
+    .open_write_stream(true)
+    .await
+    .map_err(GenerateError::P10Error)?;
+
+  // Get exclusive access to the output stream
+  let mut output_stream = output_stream.lock().await;
+
+  // Write P10 data to output stream
+  data_set
+    .write_p10_stream_async(&mut *output_stream, None)
+    .await
+    .map_err(GenerateError::P10Error)?;
+
+  output_target
+    .commit(&mut output_stream)
+    .await
+    .map_err(GenerateError::P10Error)
+}
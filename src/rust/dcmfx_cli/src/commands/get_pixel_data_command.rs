@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use clap::{Args, ValueEnum};
 use tokio::io::AsyncWriteExt;
@@ -7,29 +7,41 @@ use dcmfx::{
   core::*,
   p10::*,
   pixel_data::{
-    PixelDataDecodeError, PixelDataFrame, PixelDataRenderer,
+    FrameSelection, FrameTiming, GrayscaleOutputDepth, GrayscalePipeline,
+    IndexedColorImage, MonochromeImage, Mp4RemuxError, PixelDataDecodeError,
+    PixelDataFrame, PixelDataRenderer, RenderProfile, RenderProfiles,
+    VideoCodec,
     iods::{
-      CineModule, MultiFrameModule, OverlayPlaneModule,
+      CineModule, ImagePixelModule, ImagePlaneModule, ModalityLutModule,
+      MultiFrameModule, OverlayPlaneModule, VoiLutModule,
       voi_lut_module::{VoiLutFunction, VoiWindow},
     },
     transforms::{
       CropRect, P10PixelDataFrameTransform, P10PixelDataFrameTransformError,
     },
   },
+  volume::{
+    Interpolation, Plane, ProjectionMode, ProjectionSlab, Volume, VolumeError,
+  },
 };
 
 use crate::{
   args::{
-    frame_selection_arg::FrameSelection,
-    standard_color_palette_arg::StandardColorPaletteArg,
+    grayscale_output_depth_arg::GrayscaleOutputDepthArg,
+    standard_color_palette_arg::{
+      StandardColorPaletteArg, parse_color_palette_arg,
+    },
     transform_arg::TransformArg,
   },
   utils::{
     self, InputSource, OutputTarget,
+    animation_encoder::{AnimationEncoder, AnimationFormat},
     mp4_encoder::{
       LogLevel, Mp4Codec, Mp4CompressionPreset, Mp4Encoder, Mp4EncoderConfig,
       Mp4PixelFormat, ResizeFilter,
     },
+    render_config,
+    tiff_encoder::TiffEncoder,
   },
 };
 
@@ -40,11 +52,10 @@ pub const ABOUT: &str = "Extracts pixel data from DICOM P10 files, writing it \
 pub struct GetPixelDataArgs {
   #[arg(
     long,
-    help = "The number of concurrent tasks to use. Defaults to the number of \
-      CPU cores.",
-    default_value_t = {num_cpus::get()}
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
   )]
-  concurrency: usize,
+  concurrency: Option<usize>,
 
   #[command(flatten)]
   input: crate::args::input_args::P10InputArgs,
@@ -92,6 +103,20 @@ pub struct GetPixelDataArgs {
   )]
   select_frames: Option<FrameSelection>,
 
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Selects the frames to extract that are displayed during a range \
+      of time, measured in seconds from the start of the first frame. The \
+      range is specified as '2.5s-7.0s', or '2.5s-' for an open range. Frame \
+      times are taken from per-frame Frame Acquisition DateTime values when \
+      present, otherwise from the Cine Module, including variable frame \
+      times specified by a Frame Time Vector.",
+    value_parser = FrameSelection::parse_time_range,
+    conflicts_with = "select_frames"
+  )]
+  time_range: Option<FrameSelection>,
+
   #[arg(
     long,
     help_heading = "Output",
@@ -126,6 +151,11 @@ pub struct GetPixelDataArgs {
       output images and videos. If either width or height is zero then it is \
       calculated automatically such that the input aspect ratio is preserved.\n\
       \n\
+      When the output format is 'mp4', and no crop or overlay rendering is \
+      active, JPEG Baseline 8-bit and JPEG 2000 pixel data is decoded at the \
+      lowest resolution that is still at least the output resolution, which \
+      is faster than decoding at full resolution.\n\
+      \n\
       The order of image data operations is: crop, transform, resize."
   )]
   resize: Option<Vec<u32>>,
@@ -148,6 +178,49 @@ pub struct GetPixelDataArgs {
   )]
   jpg_quality: u8,
 
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "When the output format is 'jpg' and the transfer syntax is JPEG \
+      Baseline (Process 1), writes each frame's JPEG data directly to the \
+      output file without decoding and re-encoding it. This is lossless and \
+      fast, however all arguments that alter the rendered image are ignored.",
+    default_value_t = false
+  )]
+  passthrough: bool,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "When the output format is 'tiff', writes all frames into a single \
+      multi-page TIFF file instead of writing one TIFF file per frame.",
+    default_value_t = false
+  )]
+  tiff_multi_page: bool,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "When the output format is 'gif' or 'apng', specifies the frame \
+      rate of the animation. This overrides any frame timing information \
+      contained in the input DICOM P10 file, e.g. in its Cine Module. The \
+      fallback frame rate is 1 frame per second."
+  )]
+  fps: Option<f64>,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "When the output format is 'png', writes indexed color PNGs that \
+      store a palette rather than expanding every pixel to RGB. This applies \
+      to palette color data that uses no more than 256 palette entries, and to \
+      grayscale data visualized with --color-palette. This can greatly reduce \
+      output size. Other images, and images that are having overlays \
+      rendered or are being resized, are written as regular PNGs.",
+    default_value_t = false
+  )]
+  indexed_png: bool,
+
   #[arg(
     long,
     help_heading = "MP4 Encoding",
@@ -218,6 +291,64 @@ pub struct GetPixelDataArgs {
   )]
   mp4_log_level: LogLevel,
 
+  #[arg(
+    long,
+    help_heading = "MP4 Encoding",
+    help = "When the output format is 'mp4', writes the frames of all input \
+      files in the same series to a single MP4 file rather than writing one \
+      MP4 file per input file. Input files are grouped by their Series \
+      Instance UID and ordered by their Instance Number, with their position \
+      along the slice normal used to order instances with the same Instance \
+      Number. The VOI window of the first instance in each series is used for \
+      every instance in it. Each MP4 file is named after the first instance \
+      in its series.",
+    default_value_t = false
+  )]
+  series_to_video: bool,
+
+  #[arg(
+    long,
+    value_enum,
+    help_heading = "Multi-Planar Reconstruction",
+    help = "Assembles the input files into a volume and writes each of its \
+      slices along the given plane to an image file, which is useful for \
+      quick visual QA of a series. The input files must be the single-frame \
+      instances of one series, and the output format must be 'png' or 'jpg'. \
+      Each image file is named after the first input file, with the plane and \
+      slice index appended. Slices use the VOI window of the first input file \
+      when --voi-window isn't specified."
+  )]
+  mpr: Option<MprPlaneArg>,
+
+  #[arg(
+    long,
+    value_enum,
+    help_heading = "Multi-Planar Reconstruction",
+    help = "The interpolation used to resample slices when using --mpr.",
+    default_value_t = MprInterpolationArg::Linear
+  )]
+  mpr_interpolation: MprInterpolationArg,
+
+  #[arg(
+    long,
+    value_enum,
+    help_heading = "Multi-Planar Reconstruction",
+    help = "When using --mpr, writes an intensity projection along the plane's \
+      normal instead of its individual slices. The projection covers the whole \
+      volume unless --mpr-slab-thickness is specified."
+  )]
+  mpr_projection: Option<MprProjectionArg>,
+
+  #[arg(
+    long,
+    value_parser = clap::value_parser!(f64),
+    help_heading = "Multi-Planar Reconstruction",
+    help = "When using --mpr-projection, specifies the thickness in \
+      millimeters of the slab of slices to project. One projection is \
+      written for each slice, using the slab centered on that slice."
+  )]
+  mpr_slab_thickness: Option<f64>,
+
   #[arg(
     long,
     short = 'w',
@@ -233,14 +364,56 @@ pub struct GetPixelDataArgs {
 
   #[arg(
     long,
-    value_enum,
+    help_heading = "Output",
+    help = "For grayscale Enhanced multi-frame DICOM P10 files, such as \
+      Enhanced CT and Enhanced MR, renders all frames using the VOI LUT of \
+      the first frame instead of each frame's own VOI LUT. This gives frames \
+      a consistent appearance. Each frame's rescale is still applied.",
+    default_value_t = false
+  )]
+  uniform_voi_window: bool,
+
+  #[arg(
+    long,
+    value_parser = parse_color_palette_arg,
     help_heading = "Output",
     help = "For grayscale DICOM P10 files, when the output format is 'jpg' or \
-      'png', specifies the well-known color palette to apply to visualize the \
-      grayscale image in color."
+      'png', specifies the color palette to apply to visualize the grayscale \
+      image in color. This is the name of a standard color palette, i.e. \
+      'hot-iron', 'pet', 'hot-metal-blue', 'pet-20-step', 'spring', 'summer', \
+      'fall', or 'winter', the name of a user color palette in the 'palettes' \
+      directory of the DCMfx configuration directory, e.g. \
+      '~/.config/dcmfx/palettes/<NAME>.csv', or the path to a color palette \
+      file. Color palette files are CSV or JSON, and specify either 256 \
+      colors or segments that are linearly interpolated between. Use the \
+      'export-color-palette' command to see an example."
   )]
   color_palette: Option<StandardColorPaletteArg>,
 
+  #[arg(
+    long,
+    value_enum,
+    help_heading = "Output",
+    help = "For grayscale DICOM P10 files, specifies the bit depth of output \
+      values and which parts of the grayscale pipeline are applied. The \
+      16-bit options are reduced to 8-bit when the output format doesn't \
+      support 16-bit. Ignored when --color-palette is specified. Defaults to \
+      'windowed16' for output formats that support 16-bit, and 'windowed8' \
+      otherwise."
+  )]
+  grayscale_output_depth: Option<GrayscaleOutputDepthArg>,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "For grayscale DICOM P10 files, specifies a TOML file that defines \
+      default rendering settings for each modality, i.e. the VOI window, color \
+      palette, whether to invert, and grayscale output depth. Settings \
+      specified on the command line take precedence. Defaults to \
+      'dcmfx/render.toml' in the user's configuration directory, if it exists."
+  )]
+  render_config: Option<PathBuf>,
+
   #[arg(
     long = "overlays",
     help_heading = "Output",
@@ -251,6 +424,18 @@ pub struct GetPixelDataArgs {
   )]
   render_overlays: bool,
 
+  #[arg(
+    long,
+    value_enum,
+    help_heading = "Output",
+    help = "Writes a sidecar file for each extracted frame that records the \
+      frame's geometry from the Image Plane Module, the VOI window and rescale \
+      that were applied to it, its photometric interpretation, and its output \
+      dimensions. The name of the sidecar file is the name of the input file \
+      suffixed with a 4-digit frame number and the sidecar's file extension."
+  )]
+  sidecar: Option<SidecarFormat>,
+
   #[command(flatten)]
   decoder: crate::args::decoder_args::DecoderArgs,
 }
@@ -278,15 +463,168 @@ impl GetPixelDataArgs {
     Some((new_width, new_height))
   }
 
+  /// Given an input image's columns and rows, returns the dimensions of the
+  /// output image prior to any resize, taking into account any active crop and
+  /// transform.
+  ///
+  fn output_dimensions(&self, columns: u16, rows: u16) -> (u32, u32) {
+    let (rows, columns) = match self.crop {
+      Some(crop) => crop.apply(rows, columns),
+      None => (rows, columns),
+    };
+
+    if self.transform.is_some_and(|t| t.swaps_dimensions()) {
+      (rows.into(), columns.into())
+    } else {
+      (columns.into(), rows.into())
+    }
+  }
+
+  /// Returns the minimum size that frames need to be decoded at, given the
+  /// input's columns and rows. This is set when writing an MP4 with a resize
+  /// active, and allows decoders that support it to decode at a reduced
+  /// resolution that is still at least as large as the output.
+  ///
+  /// Returns `None` when overlays are being rendered or a crop is active,
+  /// because both of these operate on full resolution frames.
+  ///
+  fn min_decoded_size(&self, columns: u16, rows: u16) -> Option<(u16, u16)> {
+    if self.format != OutputFormat::Mp4
+      || self.render_overlays
+      || self.crop.is_some()
+    {
+      return None;
+    }
+
+    let (width, height) = self.output_dimensions(columns, rows);
+    let (new_width, new_height) = self.new_dimensions(width, height)?;
+
+    let new_width = u16::try_from(new_width).unwrap_or(u16::MAX);
+    let new_height = u16::try_from(new_height).unwrap_or(u16::MAX);
+
+    // The minimum decoded size is prior to any transform being applied
+    if self.transform.is_some_and(|t| t.swaps_dimensions()) {
+      Some((new_height, new_width))
+    } else {
+      Some((new_width, new_height))
+    }
+  }
+
   /// Returns whether the output format is HDR, i.e. supports more than 8 bits
   /// per color/grayscale component.
   ///
   fn is_output_hdr(&self) -> bool {
     self.format == OutputFormat::Png16
+      || self.format == OutputFormat::Tiff
       || self.format == OutputFormat::Mp4
         && self.mp4_pixel_format_to_use().is_hdr()
   }
 
+  /// Returns the render profile to apply to monochrome pixel data of the given
+  /// modality. Settings specified in the arguments override those in the
+  /// matching render profile from the render config.
+  ///
+  /// If no grayscale output depth is specified then 16-bit windowed output is
+  /// used for output formats that support it, and 8-bit windowed output is
+  /// used otherwise.
+  ///
+  fn render_profile(
+    &self,
+    render_profiles: &RenderProfiles,
+    modality: Option<&str>,
+  ) -> RenderProfile {
+    let overrides = RenderProfile {
+      color_palette: self.color_palette.map(|p| p.color_palette()),
+      voi_window: self.voi_window.as_ref().map(|voi_window| {
+        VoiWindow::new(
+          voi_window[0],
+          voi_window[1],
+          "".to_string(),
+          VoiLutFunction::LinearExact,
+        )
+      }),
+      invert: None,
+      grayscale_output_depth: self
+        .grayscale_output_depth
+        .map(|depth| depth.grayscale_output_depth()),
+    };
+
+    let mut profile = match render_profiles.profile_for_modality(modality) {
+      Some(profile) => profile.merge(&overrides),
+      None => overrides,
+    };
+
+    if profile.grayscale_output_depth.is_none() {
+      profile.grayscale_output_depth = if self.is_output_hdr() {
+        Some(GrayscaleOutputDepth::Windowed16)
+      } else {
+        Some(GrayscaleOutputDepth::Windowed8)
+      };
+    }
+
+    profile
+  }
+
+  /// Returns whether indexed color PNGs should be written when possible. This
+  /// isn't done when overlays are being rendered or a resize is active because
+  /// both of these require RGB pixels.
+  ///
+  fn is_indexed_png_output(&self) -> bool {
+    self.indexed_png
+      && self.format == OutputFormat::Png
+      && !self.render_overlays
+      && self.resize.is_none()
+  }
+
+  /// Returns the duration to display a frame for in an animated GIF or APNG.
+  /// This is determined by the --fps argument if specified, then the frame's
+  /// timing in the input DICOM P10 file, and otherwise defaults to one second.
+  ///
+  fn animation_frame_duration(&self, frame: &PixelDataFrame) -> Duration {
+    if let Some(fps) = self.fps {
+      return Duration::from_secs_f64(1.0 / fps);
+    }
+
+    frame
+      .time_interval()
+      .map(|interval| interval.end - interval.start)
+      .unwrap_or(Duration::from_secs(1))
+  }
+
+  /// Returns whether frames stored in the given transfer syntax should be
+  /// written directly to JPG files without being decoded and re-encoded.
+  ///
+  fn is_jpg_passthrough(&self, ts: &TransferSyntax) -> bool {
+    self.passthrough
+      && self.format == OutputFormat::Jpg
+      && ts == &transfer_syntax::JPEG_BASELINE_8BIT
+  }
+
+  /// Returns the pixel spacing to store in the resolution tags of TIFF output,
+  /// taking into account any active transform. Returns `None` if a resize is
+  /// active because the pixel spacing of the output is then not known.
+  ///
+  fn tiff_pixel_spacing(
+    &self,
+    pixel_spacing: Option<[f64; 2]>,
+  ) -> Option<[f64; 2]> {
+    let [row_spacing, column_spacing] = pixel_spacing?;
+
+    if self.resize.is_some() {
+      return None;
+    }
+
+    match self.transform {
+      Some(
+        TransformArg::Rotate90
+        | TransformArg::Rotate270
+        | TransformArg::Rotate90FlipH
+        | TransformArg::Rotate270FlipH,
+      ) => Some([column_spacing, row_spacing]),
+      _ => Some([row_spacing, column_spacing]),
+    }
+  }
+
   /// Returns the MP4 pixel format to use, which has a different default
   /// depending on the codec.
   ///
@@ -307,8 +645,19 @@ enum OutputFormat {
   /// Reads the pixel data for each frame and writes it out exactly as it is
   /// stored in the DICOM P10 file without any alteration. A sensible file
   /// extension is selected based on the file's DICOM transfer syntax.
+  ///
+  /// Pixel data stored using an MPEG-2, H.264, or HEVC transfer syntax is
+  /// instead remuxed by FFmpeg without re-encoding into a single MP4 file,
+  /// with the frame rate taken from the input file.
   Raw,
 
+  /// Decodes monochrome pixel data and writes each frame's modality values,
+  /// e.g. Hounsfield Units for CT, as little endian 32-bit floats in row-major
+  /// order. The Modality LUT, i.e. Rescale Slope and Rescale Intercept, is
+  /// applied, but no VOI LUT windowing is performed. Crop, transform, resize,
+  /// and overlay arguments are ignored.
+  RawF32,
+
   /// Decodes the pixel data and writes each frame to an 8-bit PNG image.
   Png,
 
@@ -321,20 +670,234 @@ enum OutputFormat {
   /// quality can be controlled with the --jpg-quality argument.
   Jpg,
 
+  /// Decodes the pixel data and writes each frame to a TIFF image. If the
+  /// pixel data bit depth is greater than 8-bit then the TIFF will be 16-bit,
+  /// otherwise it will be 8-bit. Resolution tags are set from the Pixel
+  /// Spacing, if present. All frames can be written to a single multi-page
+  /// TIFF using the --tiff-multi-page argument.
+  Tiff,
+
+  /// Decodes the pixel data and writes the frames to an animated GIF. Frames
+  /// are displayed using the frame timing in the DICOM P10 file, which can be
+  /// overridden with the --fps argument. Colors are reduced to a palette of at
+  /// most 256 colors.
+  Gif,
+
+  /// Decodes the pixel data and writes the frames to an 8-bit animated PNG
+  /// (APNG). Frames are displayed using the frame timing in the DICOM P10
+  /// file, which can be overridden with the --fps argument.
+  Apng,
+
   /// Decodes the pixel data and writes the frames to an MP4 file. The MP4
   /// codec, quality, preset, and other settings can be controlled with the
   /// --mp4-* arguments.
   Mp4,
 }
 
+impl OutputFormat {
+  /// Returns the animation format for output formats that write all frames to
+  /// a single animated image file.
+  ///
+  fn animation_format(&self) -> Option<AnimationFormat> {
+    match self {
+      Self::Gif => Some(AnimationFormat::Gif),
+      Self::Apng => Some(AnimationFormat::Apng),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum SidecarFormat {
+  /// Writes a JSON file for each frame.
+  Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum MprPlaneArg {
+  /// Slices perpendicular to the head-foot axis, ordered from inferior to
+  /// superior.
+  Axial,
+
+  /// Slices perpendicular to the anterior-posterior axis, ordered from
+  /// anterior to posterior.
+  Coronal,
+
+  /// Slices perpendicular to the left-right axis, ordered from the patient's
+  /// right to their left.
+  Sagittal,
+}
+
+impl MprPlaneArg {
+  fn plane(&self) -> Plane {
+    match self {
+      Self::Axial => Plane::Axial,
+      Self::Coronal => Plane::Coronal,
+      Self::Sagittal => Plane::Sagittal,
+    }
+  }
+
+  fn name(&self) -> &'static str {
+    match self {
+      Self::Axial => "axial",
+      Self::Coronal => "coronal",
+      Self::Sagittal => "sagittal",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum MprInterpolationArg {
+  /// Takes the value of the nearest voxel.
+  Nearest,
+
+  /// Bilinearly interpolates between the nearest voxels.
+  Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum MprProjectionArg {
+  /// Maximum Intensity Projection (MIP).
+  Max,
+
+  /// Minimum Intensity Projection (MinIP).
+  Min,
+
+  /// Average Intensity Projection (AvgIP).
+  Average,
+}
+
+impl MprProjectionArg {
+  fn projection_mode(&self) -> ProjectionMode {
+    match self {
+      Self::Max => ProjectionMode::Maximum,
+      Self::Min => ProjectionMode::Minimum,
+      Self::Average => ProjectionMode::Average,
+    }
+  }
+
+  fn name(&self) -> &'static str {
+    match self {
+      Self::Max => "mip",
+      Self::Min => "minip",
+      Self::Average => "avgip",
+    }
+  }
+}
+
+impl MprInterpolationArg {
+  fn interpolation(&self) -> Interpolation {
+    match self {
+      Self::Nearest => Interpolation::Nearest,
+      Self::Linear => Interpolation::Linear,
+    }
+  }
+}
+
 #[allow(clippy::enum_variant_names)]
 enum GetPixelDataError {
   P10Error(P10Error),
   DataError(DataError),
   PixelDataDecodeError(PixelDataDecodeError),
+  Mp4RemuxError(Mp4RemuxError),
   ImageError(image::ImageError),
+  PngError(String),
   FFmpegError(String),
+  TiffError(String),
+  AnimationError(String),
+  VolumeError(VolumeError),
   OtherError(String),
+  WithContext {
+    error: Box<GetPixelDataError>,
+    context: ErrorContext,
+  },
+}
+
+impl GetPixelDataError {
+  /// Returns lines of text that describe this error in a human-readable
+  /// format.
+  ///
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      GetPixelDataError::DataError(e) => e.to_lines(task_description),
+      GetPixelDataError::P10Error(e) => e.to_lines(task_description),
+      GetPixelDataError::PixelDataDecodeError(e) => {
+        e.to_lines(task_description)
+      }
+      GetPixelDataError::Mp4RemuxError(e) => e.to_lines(task_description),
+      GetPixelDataError::ImageError(e) => vec![
+        format!("Image error {}", task_description),
+        "".to_string(),
+        format!("  Error: {}", e),
+      ],
+      GetPixelDataError::PngError(e) => vec![
+        format!("PNG encoding error {}", task_description),
+        "".to_string(),
+        format!("  Error: {}", e),
+      ],
+      GetPixelDataError::FFmpegError(e) => vec![
+        format!("FFmpeg encoding error {}", task_description),
+        "".to_string(),
+        format!("  Error: {}", e),
+      ],
+      GetPixelDataError::TiffError(e) => vec![
+        format!("TIFF encoding error {}", task_description),
+        "".to_string(),
+        format!("  Error: {}", e),
+      ],
+      GetPixelDataError::AnimationError(e) => vec![
+        format!("Animation encoding error {}", task_description),
+        "".to_string(),
+        format!("  Error: {}", e),
+      ],
+      GetPixelDataError::VolumeError(e) => e.to_lines(task_description),
+      GetPixelDataError::OtherError(s) => vec![
+        format!("Error {}", task_description),
+        "".to_string(),
+        format!("  Error: {}", s),
+      ],
+      GetPixelDataError::WithContext { error, context } => {
+        let mut lines = error.to_lines(task_description);
+        context.append_to_lines(&mut lines);
+        lines
+      }
+    }
+  }
+}
+
+impl ErrorContextChain for GetPixelDataError {
+  fn with_context(self, context: ErrorContext) -> Self {
+    match self {
+      Self::WithContext {
+        error,
+        context: existing_context,
+      } => Self::WithContext {
+        error,
+        context: existing_context.merge(context),
+      },
+
+      error if context.is_empty() => error,
+
+      error => Self::WithContext {
+        error: Box::new(error),
+        context,
+      },
+    }
+  }
+
+  fn context(&self) -> Option<&ErrorContext> {
+    match self {
+      Self::WithContext { context, .. } => Some(context),
+      _ => None,
+    }
+  }
+
+  fn without_context(&self) -> &Self {
+    match self {
+      Self::WithContext { error, .. } => error,
+      _ => self,
+    }
+  }
 }
 
 pub async fn run(args: GetPixelDataArgs) -> Result<(), ()> {
@@ -342,10 +905,42 @@ pub async fn run(args: GetPixelDataArgs) -> Result<(), ()> {
 
   OutputTarget::set_overwrite(args.overwrite);
 
+  let render_profiles =
+    match render_config::load_render_profiles(args.render_config.as_ref()) {
+      Ok(render_profiles) => render_profiles,
+      Err(e) => utils::exit_with_error("Failed loading render config", e),
+    };
+
   let input_sources = args.input.base.input_sources().await;
 
+  if args.fps.is_some_and(|fps| fps.is_nan() || fps <= 0.0) {
+    utils::exit_with_error("--fps must be greater than zero", "");
+  }
+
+  if args.series_to_video {
+    if args.format != OutputFormat::Mp4 {
+      utils::exit_with_error(
+        "--series-to-video requires the output format to be 'mp4'",
+        "",
+      );
+    }
+
+    return run_series_to_video(input_sources, &render_profiles, &args).await;
+  }
+
+  if let Some(mpr) = args.mpr {
+    if args.format != OutputFormat::Png && args.format != OutputFormat::Jpg {
+      utils::exit_with_error(
+        "--mpr requires the output format to be 'png' or 'jpg'",
+        "",
+      );
+    }
+
+    return run_mpr(input_sources, mpr, &render_profiles, &args).await;
+  }
+
   let result = utils::run_tasks(
-    args.concurrency,
+    utils::task_count(args.concurrency),
     input_sources,
     async |input_source: InputSource| {
       let output_target_base = OutputTarget::from_input_source(
@@ -358,7 +953,9 @@ pub async fn run(args: GetPixelDataArgs) -> Result<(), ()> {
       match get_pixel_data_from_input_source(
         &input_source,
         output_target_base,
+        &render_profiles,
         &args,
+        None,
       )
       .await
       {
@@ -374,29 +971,7 @@ pub async fn run(args: GetPixelDataArgs) -> Result<(), ()> {
           let task_description =
             format!("extracting pixel data from \"{input_source}\"");
 
-          Err(match e {
-            GetPixelDataError::DataError(e) => e.to_lines(&task_description),
-            GetPixelDataError::P10Error(e) => e.to_lines(&task_description),
-            GetPixelDataError::PixelDataDecodeError(e) => {
-              e.to_lines(&task_description)
-            }
-            GetPixelDataError::ImageError(e) => vec![
-              format!("Image error {}", task_description),
-              "".to_string(),
-              format!("  Error: {}", e),
-            ],
-
-            GetPixelDataError::FFmpegError(e) => vec![
-              format!("FFmpeg encoding error {}", task_description),
-              "".to_string(),
-              format!("  Error: {}", e),
-            ],
-            GetPixelDataError::OtherError(s) => vec![
-              format!("Error {}", task_description),
-              "".to_string(),
-              format!("  Error: {}", s),
-            ],
-          })
+          Err(e.to_lines(&task_description))
         }
       }
     },
@@ -413,52 +988,506 @@ pub async fn run(args: GetPixelDataArgs) -> Result<(), ()> {
   }
 }
 
-async fn get_pixel_data_from_input_source(
-  input_source: &InputSource,
-  output_target_base: OutputTarget,
+/// The details of an input file that are used to group and order the
+/// instances in each series when using `--series-to-video`.
+///
+struct SeriesInstance {
+  input_source: InputSource,
+  series_instance_uid: String,
+  instance_number: Option<i64>,
+  slice_position: Option<f32>,
+}
+
+/// The state shared by the instances in a series as their frames are written
+/// to a single MP4 file.
+///
+struct SeriesVideo {
+  output_target: OutputTarget,
+  mp4_encoder: Option<Mp4Encoder>,
+  voi_window: Option<VoiWindow>,
+}
+
+/// Writes the frames of each series in the input files to a single MP4 file.
+///
+async fn run_series_to_video(
+  mut input_sources: impl futures::Stream<Item = InputSource> + Unpin,
+  render_profiles: &RenderProfiles,
   args: &GetPixelDataArgs,
-) -> Result<(), GetPixelDataError> {
-  let mut stream = input_source
-    .open_read_stream()
-    .await
-    .map_err(GetPixelDataError::P10Error)?;
+) -> Result<(), ()> {
+  use futures::StreamExt;
+
+  // Read the series details of every input file
+  let mut series =
+    std::collections::BTreeMap::<String, Vec<SeriesInstance>>::new();
+  while let Some(input_source) = input_sources.next().await {
+    if matches!(input_source, InputSource::Stdin) {
+      utils::exit_with_error(
+        "--series-to-video can't be used when reading from stdin",
+        "",
+      );
+    }
 
-  // Create read context with a small max token size to keep memory usage low
-  let read_config = args.input.p10_read_config().max_token_size(1024 * 1024);
-  let mut read_context = P10ReadContext::new(Some(read_config));
+    match read_series_instance(input_source.clone(), args).await {
+      Ok(Some(instance)) => series
+        .entry(instance.series_instance_uid.clone())
+        .or_default()
+        .push(instance),
 
-  let mut p10_pixel_data_frame_transform = P10PixelDataFrameTransform::new();
+      Ok(None) => (),
 
-  let mut pixel_data_renderer_transform = if args.format == OutputFormat::Raw {
-    None
-  } else {
-    Some(P10CustomTypeTransform::<PixelDataRenderer>::new_for_iod_module())
-  };
+      Err(e) => {
+        let task_description =
+          format!("reading series details from \"{input_source}\"");
+        error::print_error_lines(&e.to_lines(&task_description));
+        return Err(());
+      }
+    }
+  }
 
-  let mut overlay_plane_module_transform = if args.render_overlays {
-    Some(P10CustomTypeTransform::<OverlayPlaneModule>::new_for_iod_module())
-  } else {
-    None
-  };
+  let result = utils::run_tasks(
+    utils::task_count(args.concurrency),
+    futures::stream::iter(series.into_values()),
+    async |mut instances: Vec<SeriesInstance>| -> Result<(), Vec<String>> {
+      // Order instances by Instance Number, then by position along the slice
+      // normal
+      instances.sort_by(|a, b| {
+        let instance_number =
+          |i: &SeriesInstance| i.instance_number.unwrap_or(i64::MAX);
+
+        instance_number(a).cmp(&instance_number(b)).then_with(|| {
+          a.slice_position
+            .unwrap_or(0.0)
+            .total_cmp(&b.slice_position.unwrap_or(0.0))
+        })
+      });
+
+      let mut series_video = SeriesVideo {
+        output_target: OutputTarget::from_input_source(
+          &instances[0].input_source,
+          "",
+          &args.output_directory,
+        )
+        .await
+        .append(".mp4"),
+        mp4_encoder: None,
+        voi_window: None,
+      };
 
-  let (mut cine_module_transform, mut multiframe_module_transform) =
-    if args.format == OutputFormat::Mp4 {
-      (
-        Some(P10CustomTypeTransform::<CineModule>::new_for_iod_module()),
-        Some(P10CustomTypeTransform::<MultiFrameModule>::new_for_iod_module()),
-      )
-    } else {
-      (None, None)
-    };
+      for instance in instances.iter() {
+        let output_target_base = OutputTarget::from_input_source(
+          &instance.input_source,
+          "",
+          &args.output_directory,
+        )
+        .await;
+
+        get_pixel_data_from_input_source(
+          &instance.input_source,
+          output_target_base,
+          render_profiles,
+          args,
+          Some(&mut series_video),
+        )
+        .await
+        .map_err(|e| {
+          e.to_lines(&format!(
+            "extracting pixel data from \"{}\"",
+            instance.input_source
+          ))
+        })?;
+      }
 
-  let mut output_extension = match args.format {
+      if let Some(mp4_encoder) = series_video.mp4_encoder.as_mut() {
+        mp4_encoder.finish().await.map_err(|e| {
+          GetPixelDataError::FFmpegError(e)
+            .to_lines(&format!("writing \"{}\"", instances[0].input_source))
+        })?;
+      }
+
+      Ok(())
+    },
+  )
+  .await;
+
+  match result {
+    Ok(()) => Ok(()),
+
+    Err(lines) => {
+      error::print_error_lines(&lines);
+      Err(())
+    }
+  }
+}
+
+/// Reads the data elements of an input file that are used to group and order
+/// it within its series. Returns `None` if the input file isn't a DICOM P10
+/// file and invalid input files are being ignored.
+///
+async fn read_series_instance(
+  input_source: InputSource,
+  args: &GetPixelDataArgs,
+) -> Result<Option<SeriesInstance>, GetPixelDataError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(GetPixelDataError::P10Error)?;
+
+  let mut tags = vec![
+    dictionary::SERIES_INSTANCE_UID.tag,
+    dictionary::INSTANCE_NUMBER.tag,
+  ];
+  tags.extend_from_slice(&ImagePlaneModule::TAGS);
+
+  let data_set = match dcmfx::p10::read_stream_partial_async(
+    &mut stream,
+    &tags,
+    Some(args.input.p10_read_config()),
+  )
+  .await
+  {
+    Ok(data_set) => data_set,
+
+    Err(P10Error::DicmPrefixNotPresent) if args.input.ignore_invalid => {
+      return Ok(None);
+    }
+
+    Err(e) => return Err(GetPixelDataError::P10Error(e)),
+  };
+
+  let series_instance_uid = data_set
+    .get_string(dictionary::SERIES_INSTANCE_UID.tag)
+    .map_err(GetPixelDataError::DataError)?
+    .to_string();
+
+  let instance_number = data_set.get_int(dictionary::INSTANCE_NUMBER.tag).ok();
+
+  // The slice position is the projection of the image position onto the
+  // normal of the image orientation
+  let slice_position =
+    ImagePlaneModule::from_data_set(&data_set)
+      .ok()
+      .map(|image_plane_module| {
+        let [rx, ry, rz, cx, cy, cz] =
+          image_plane_module.image_orientation_patient;
+        let normal = [ry * cz - rz * cy, rz * cx - rx * cz, rx * cy - ry * cx];
+        let [px, py, pz] = image_plane_module.image_position_patient;
+
+        px * normal[0] + py * normal[1] + pz * normal[2]
+      });
+
+  Ok(Some(SeriesInstance {
+    input_source,
+    series_instance_uid,
+    instance_number,
+    slice_position,
+  }))
+}
+
+/// Assembles the input files into a volume and writes each of its slices along
+/// the plane specified by `--mpr` to an image file.
+///
+async fn run_mpr(
+  mut input_sources: impl futures::Stream<Item = InputSource> + Unpin,
+  mpr: MprPlaneArg,
+  render_profiles: &RenderProfiles,
+  args: &GetPixelDataArgs,
+) -> Result<(), ()> {
+  use futures::StreamExt;
+
+  // Read all the input files into memory
+  let mut inputs = vec![];
+  while let Some(input_source) = input_sources.next().await {
+    let read_data_set = async || {
+      let mut stream = input_source.open_read_stream().await?;
+
+      dcmfx::p10::read_stream_async(
+        &mut stream,
+        Some(args.input.p10_read_config()),
+      )
+      .await
+      .map_err(|(e, _)| e)
+    };
+
+    match read_data_set().await {
+      Ok(data_set) => inputs.push((input_source, data_set)),
+
+      Err(P10Error::DicmPrefixNotPresent) if args.input.ignore_invalid => (),
+
+      Err(e) => {
+        error::print_error_lines(
+          &e.to_lines(&format!("reading \"{input_source}\"")),
+        );
+        return Err(());
+      }
+    }
+  }
+
+  let Some((first_input_source, _)) = inputs.first() else {
+    return Ok(());
+  };
+
+  let output_target_base = OutputTarget::from_input_source(
+    first_input_source,
+    "",
+    &args.output_directory,
+  )
+  .await;
+
+  let data_sets: Vec<&DataSet> =
+    inputs.iter().map(|(_, data_set)| data_set).collect();
+
+  match write_mpr_slices(
+    &data_sets,
+    mpr,
+    output_target_base,
+    render_profiles,
+    args,
+  )
+  .await
+  {
+    Ok(()) => Ok(()),
+
+    Err(e) => {
+      error::print_error_lines(&e.to_lines(&format!(
+        "writing {} slices of \"{first_input_source}\"",
+        mpr.name()
+      )));
+      Err(())
+    }
+  }
+}
+
+/// Assembles data sets into a volume and writes each of its slices along the
+/// given plane to an image file.
+///
+async fn write_mpr_slices(
+  data_sets: &[&DataSet],
+  mpr: MprPlaneArg,
+  output_target_base: OutputTarget,
+  render_profiles: &RenderProfiles,
+  args: &GetPixelDataArgs,
+) -> Result<(), GetPixelDataError> {
+  let volume = Volume::from_data_sets(data_sets)
+    .map_err(GetPixelDataError::VolumeError)?;
+
+  // Slices hold modality values, so a VOI window from the render profile or
+  // the first data set can be applied to them directly
+  let modality = data_sets[0].get_string(dictionary::MODALITY.tag).ok();
+  let voi_window = args
+    .render_profile(render_profiles, modality)
+    .voi_window
+    .or_else(|| {
+      VoiLutModule::from_data_set(data_sets[0])
+        .ok()
+        .and_then(|module| module.windows().first().cloned())
+    });
+
+  let plane = mpr.plane();
+
+  // Determine the images to write, which are either the slices, one projection
+  // per slice when using a slab, or a single projection of the whole volume
+  let indices = match (args.mpr_projection, args.mpr_slab_thickness) {
+    (Some(_), None) => vec![None],
+    _ => (0..volume.slice_count(plane)).map(Some).collect(),
+  };
+
+  for index in indices {
+    let image = match args.mpr_projection {
+      Some(projection) => volume.project(
+        plane,
+        projection.projection_mode(),
+        index
+          .zip(args.mpr_slab_thickness)
+          .map(|(center, thickness)| ProjectionSlab { center, thickness }),
+      ),
+
+      None => volume.slice_with_interpolation(
+        plane,
+        index.unwrap_or(0),
+        args.mpr_interpolation.interpolation(),
+      ),
+    }
+    .ok_or_else(|| {
+      GetPixelDataError::OtherError(
+        "Volume is too large to be resliced".to_string(),
+      )
+    })?;
+
+    let Some((min, max)) = image.min_max_values() else {
+      continue;
+    };
+
+    let mut grayscale_pipeline =
+      GrayscalePipeline::from_data_set(&DataSet::new(), min..=max)
+        .map_err(GetPixelDataError::DataError)?;
+
+    if let Some(window) =
+      voi_window.clone().or_else(|| image.default_voi_window())
+    {
+      grayscale_pipeline.set_voi_window(window);
+    }
+
+    let image: image::DynamicImage =
+      image.to_gray_u8_image(&grayscale_pipeline).into();
+
+    let mut image_buffer = std::io::Cursor::new(vec![]);
+
+    let extension = match args.format {
+      OutputFormat::Jpg => {
+        image::codecs::jpeg::JpegEncoder::new_with_quality(
+          &mut image_buffer,
+          args.jpg_quality,
+        )
+        .encode_image(&image)
+        .map_err(GetPixelDataError::ImageError)?;
+
+        "jpg"
+      }
+
+      _ => {
+        image
+          .write_to(&mut image_buffer, image::ImageFormat::Png)
+          .map_err(GetPixelDataError::ImageError)?;
+
+        "png"
+      }
+    };
+
+    let mut suffix = format!(".{}", mpr.name());
+    if let Some(projection) = args.mpr_projection {
+      suffix.push_str(&format!(".{}", projection.name()));
+    }
+    if let Some(index) = index {
+      suffix.push_str(&format!(".{index:04}"));
+    }
+
+    let output_target =
+      output_target_base.append(&format!("{suffix}.{extension}"));
+
+    write_bytes_to_output_target(output_target, &image_buffer.into_inner())
+      .await?;
+  }
+
+  Ok(())
+}
+
+async fn get_pixel_data_from_input_source(
+  input_source: &InputSource,
+  output_target_base: OutputTarget,
+  render_profiles: &RenderProfiles,
+  args: &GetPixelDataArgs,
+  mut series_video: Option<&mut SeriesVideo>,
+) -> Result<(), GetPixelDataError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(GetPixelDataError::P10Error)?;
+
+  // Create read context with a small max token size to keep memory usage low
+  let read_config = args.input.p10_read_config().max_token_size(1024 * 1024);
+  let mut read_context = P10ReadContext::new(Some(read_config));
+
+  let mut p10_pixel_data_frame_transform = P10PixelDataFrameTransform::new();
+
+  // The pixel data renderer is also needed for raw output when writing
+  // sidecars, as they record details of the image pixel and grayscale pipeline
+  let mut pixel_data_renderer_transform =
+    if args.format == OutputFormat::Raw && args.sidecar.is_none() {
+      None
+    } else {
+      Some(P10CustomTypeTransform::<PixelDataRenderer>::new_for_iod_module())
+    };
+
+  // The modality is used to select the render profile to apply to the pixel
+  // data renderer
+  let mut modality_transform = if pixel_data_renderer_transform.is_some() {
+    Some(P10CustomTypeTransform::<Option<String>>::new(
+      &[dictionary::MODALITY.tag],
+      |data_set| {
+        Ok(
+          data_set
+            .get_string(dictionary::MODALITY.tag)
+            .ok()
+            .map(String::from),
+        )
+      },
+    ))
+  } else {
+    None
+  };
+  let mut is_render_profile_applied = false;
+
+  let mut overlay_plane_module_transform = if args.render_overlays {
+    Some(P10CustomTypeTransform::<OverlayPlaneModule>::new_for_iod_module())
+  } else {
+    None
+  };
+
+  let mut pixel_spacing_transform = if args.format == OutputFormat::Tiff {
+    Some(P10CustomTypeTransform::<Option<[f64; 2]>>::new(
+      &[dictionary::PIXEL_SPACING.tag],
+      |data_set| match data_set.get_floats(dictionary::PIXEL_SPACING.tag) {
+        Ok(pixel_spacing) => match pixel_spacing.as_slice() {
+          [row_spacing, column_spacing] => {
+            Ok(Some([*row_spacing, *column_spacing]))
+          }
+          _ => Ok(None),
+        },
+        Err(_) => Ok(None),
+      },
+    ))
+  } else {
+    None
+  };
+
+  let mut image_plane_module_transform = if args.sidecar.is_some() {
+    Some(P10CustomTypeTransform::<Option<ImagePlaneModule>>::new(
+      &ImagePlaneModule::TAGS,
+      |data_set| Ok(ImagePlaneModule::from_data_set(data_set).ok()),
+    ))
+  } else {
+    None
+  };
+
+  let frame_selection =
+    args.select_frames.as_ref().or(args.time_range.as_ref());
+
+  let is_time_based_frame_selection =
+    frame_selection.is_some_and(|selection| selection.is_time_based());
+
+  let (mut cine_module_transform, mut multiframe_module_transform) =
+    if args.format == OutputFormat::Mp4
+      || args.format.animation_format().is_some()
+    {
+      (
+        Some(P10CustomTypeTransform::<CineModule>::new_for_iod_module()),
+        Some(P10CustomTypeTransform::<MultiFrameModule>::new_for_iod_module()),
+      )
+    } else {
+      (None, None)
+    };
+
+  let mut output_extension = match args.format {
     OutputFormat::Raw => "",
+    OutputFormat::RawF32 => ".f32",
     OutputFormat::Png | OutputFormat::Png16 => ".png",
     OutputFormat::Jpg => ".jpg",
+    OutputFormat::Tiff => ".tiff",
+    OutputFormat::Gif => ".gif",
+    OutputFormat::Apng => ".png",
     OutputFormat::Mp4 => ".mp4",
   };
 
+  // Raw output of pixel data that holds a video stream is remuxed into an MP4
+  // file, which requires the whole video stream and the frame timing
+  let mut video_codec: Option<VideoCodec> = None;
+  let mut video_stream: Vec<u8> = vec![];
+  let mut frame_timing_transform: Option<P10CustomTypeTransform<FrameTiming>> =
+    None;
+
   let mut mp4_encoder: Option<Mp4Encoder> = None;
+  let mut multi_page_tiff_encoder: Option<TiffEncoder> = None;
+  let mut animation_encoder: Option<AnimationEncoder> = None;
 
   loop {
     // Read the next tokens from the input stream
@@ -481,13 +1510,45 @@ async fn get_pixel_data_from_input_source(
 
         output_extension =
           dcmfx::pixel_data::file_extension_for_transfer_syntax(ts);
+
+        video_codec = VideoCodec::from_transfer_syntax(ts);
+        if video_codec.is_some() {
+          if frame_selection.is_some() || args.sidecar.is_some() {
+            return Err(GetPixelDataError::OtherError(
+              "Frame selection and sidecars are not supported for raw output \
+               of video pixel data"
+                .to_string(),
+            ));
+          }
+
+          frame_timing_transform =
+            Some(P10CustomTypeTransform::<FrameTiming>::new_for_iod_module());
+        }
+      }
+
+      // The pictures of a video stream that's output as images are given
+      // their time interval using the frame timing
+      if args.format != OutputFormat::Raw
+        && let P10Token::FileMetaInformation { data_set } = token
+        && data_set
+          .get_transfer_syntax()
+          .ok()
+          .and_then(VideoCodec::from_transfer_syntax)
+          .is_some()
+      {
+        frame_timing_transform =
+          Some(P10CustomTypeTransform::<FrameTiming>::new_for_iod_module());
       }
 
       // Pass token through the transforms to extract relevant data
+      add_token_to_p10_transform(&mut modality_transform, token)?;
       add_token_to_p10_transform(&mut pixel_data_renderer_transform, token)?;
       add_token_to_p10_transform(&mut overlay_plane_module_transform, token)?;
       add_token_to_p10_transform(&mut cine_module_transform, token)?;
       add_token_to_p10_transform(&mut multiframe_module_transform, token)?;
+      add_token_to_p10_transform(&mut pixel_spacing_transform, token)?;
+      add_token_to_p10_transform(&mut image_plane_module_transform, token)?;
+      add_token_to_p10_transform(&mut frame_timing_transform, token)?;
 
       let pixel_data_renderer: &mut Option<PixelDataRenderer> =
         if let Some(pixel_data_renderer_transform) =
@@ -497,8 +1558,46 @@ async fn get_pixel_data_from_input_source(
             pixel_data_renderer_transform.get_output_mut();
 
           if let Some(pixel_data_renderer) = pixel_data_renderer {
-            pixel_data_renderer.decode_config =
-              args.decoder.pixel_data_decode_config();
+            pixel_data_renderer.decode_config = args
+              .decoder
+              .pixel_data_decode_config(utils::codec_thread_count(
+                args.concurrency,
+              ));
+            pixel_data_renderer.decode_config.min_decoded_size = args
+              .min_decoded_size(
+                pixel_data_renderer.image_pixel_module.columns(),
+                pixel_data_renderer.image_pixel_module.rows(),
+              );
+
+            // Apply the render profile once the pixel data renderer is
+            // available
+            if !is_render_profile_applied {
+              let modality =
+                modality_transform.as_ref().and_then(|transform| {
+                  transform.get_output().cloned().flatten()
+                });
+
+              pixel_data_renderer.apply_render_profile(
+                &args.render_profile(render_profiles, modality.as_deref()),
+              );
+
+              if args.uniform_voi_window {
+                pixel_data_renderer.set_uniform_voi_lut(0);
+              }
+
+              // Instances in a series video use the VOI window of the first
+              // instance so that their frames are rendered consistently
+              if let Some(voi_window) = series_video
+                .as_ref()
+                .and_then(|series_video| series_video.voi_window.clone())
+              {
+                pixel_data_renderer
+                  .grayscale_pipeline
+                  .set_voi_window(voi_window);
+              }
+
+              is_render_profile_applied = true;
+            }
           }
 
           pixel_data_renderer
@@ -527,77 +1626,270 @@ async fn get_pixel_data_from_input_source(
           }
         })?;
 
+      let pixel_spacing = pixel_spacing_transform
+        .as_ref()
+        .and_then(|transform| transform.get_output().copied().flatten());
+
+      let image_plane_module = image_plane_module_transform
+        .as_ref()
+        .and_then(|transform| transform.get_output().cloned().flatten());
+
       let number_of_frames =
         p10_pixel_data_frame_transform.get_number_of_frames();
 
       // Process available frames
-      for frame in frames.iter_mut() {
-        let frame_index = frame.index().unwrap();
-
-        // If selecting a subset of frames, only export this frame if is
-        // selected
-        let mut is_frame_selected = true;
-        if let Some(frame_selection) = args.select_frames.as_ref() {
-          is_frame_selected =
-            frame_selection.contains(frame_index, number_of_frames);
+      'frames: for frame in frames.iter_mut() {
+        // The video stream is remuxed once it has been read
+        if video_codec.is_some() {
+          video_stream.extend_from_slice(&frame.to_bytes());
+          continue;
         }
 
-        if is_frame_selected {
-          if args.format == OutputFormat::Mp4 {
-            let pixel_data_renderer = pixel_data_renderer.as_mut().unwrap();
-
-            let cine_module = cine_module_transform
-              .as_ref()
-              .unwrap()
-              .get_output()
-              .unwrap();
-
-            let multiframe_module = multiframe_module_transform
-              .as_ref()
-              .unwrap()
-              .get_output()
-              .unwrap();
-
-            let output_target = output_target_base.append(".mp4");
-
-            write_frame_to_mp4_file(
-              frame,
-              &mut mp4_encoder,
-              pixel_data_renderer,
-              cine_module,
-              multiframe_module,
-              overlay_plane_module,
-              args,
-              output_target,
+        // Pixel data that holds a video stream is read as a single frame. All
+        // of its pictures are decoded at once by the pixel data renderer, and
+        // are then output in turn by setting the index of the frame.
+        let video_picture_count = match pixel_data_renderer.as_mut() {
+          Some(pixel_data_renderer)
+            if VideoCodec::from_transfer_syntax(
+              pixel_data_renderer.transfer_syntax,
+            )
+            .is_some() =>
+          {
+            Some(
+              pixel_data_renderer
+                .decode_video_frame(frame)
+                .map_err(GetPixelDataError::PixelDataDecodeError)?,
             )
-            .await?;
+          }
+
+          _ => None,
+        };
+
+        for picture_index in 0..video_picture_count.unwrap_or(1) {
+          if video_picture_count.is_some() {
+            frame.set_index(picture_index);
+            frame.set_time_interval(
+              frame_timing_transform
+                .as_ref()
+                .and_then(|transform| transform.get_output())
+                .and_then(|frame_timing| {
+                  frame_timing.frame_interval(picture_index)
+                }),
+            );
+          }
+
+          let frame_index = frame.index().unwrap();
+
+          // Time-based frame selection requires the time interval of the frame,
+          // which is set by the pixel data frame transform
+          let frame_interval = if is_time_based_frame_selection {
+            Some(frame.time_interval().cloned().ok_or_else(|| {
+              GetPixelDataError::OtherError(
+                "Frame timing is not available for time range selection"
+                  .to_string(),
+              )
+            })?)
           } else {
-            let output_target = output_target_base.append(&format!(
-              ".{:04}{}",
-              frame.index().unwrap(),
-              output_extension
-            ));
+            None
+          };
+
+          // If selecting a subset of frames, only export this frame if is
+          // selected
+          let mut is_frame_selected = true;
+          if let Some(frame_selection) = frame_selection {
+            is_frame_selected = frame_selection.contains(
+              frame_index,
+              number_of_frames,
+              frame_interval.as_ref(),
+            );
+          }
 
-            write_frame_to_image_file(
-              frame,
-              pixel_data_renderer,
-              overlay_plane_module,
-              args,
-              output_target,
-            )
-            .await?;
+          // Errors that occur on this frame have the path to the pixel data,
+          // the frame index, and the transfer syntax added to their context
+          let mut error_context = ErrorContext::new()
+            .with_path(&DataSetPath::new_with_data_element(
+              dictionary::PIXEL_DATA.tag,
+            ))
+            .with_frame_index(frame_index);
+          if let Some(pixel_data_renderer) = pixel_data_renderer.as_ref() {
+            error_context = error_context
+              .with_transfer_syntax(pixel_data_renderer.transfer_syntax);
           }
-        }
+          let frame_error_context =
+            |e: GetPixelDataError| e.with_context(error_context.clone());
+
+          if is_frame_selected {
+            if args.format == OutputFormat::Mp4 {
+              let pixel_data_renderer = pixel_data_renderer.as_mut().unwrap();
+
+              let cine_module = cine_module_transform
+                .as_ref()
+                .unwrap()
+                .get_output()
+                .unwrap();
+
+              let multiframe_module = multiframe_module_transform
+                .as_ref()
+                .unwrap()
+                .get_output()
+                .unwrap();
+
+              let (mp4_encoder, output_target) =
+                match series_video.as_deref_mut() {
+                  Some(series_video) => (
+                    &mut series_video.mp4_encoder,
+                    series_video.output_target.clone(),
+                  ),
+                  None => (&mut mp4_encoder, output_target_base.append(".mp4")),
+                };
+
+              write_frame_to_mp4_file(
+                frame,
+                mp4_encoder,
+                pixel_data_renderer,
+                cine_module,
+                multiframe_module,
+                overlay_plane_module,
+                args,
+                output_target,
+              )
+              .await
+              .map_err(frame_error_context)?;
+
+              // Record the VOI window used by the first instance in a series
+              // video so it can be applied to the remaining instances
+              if let Some(series_video) = series_video.as_deref_mut()
+                && series_video.voi_window.is_none()
+              {
+                series_video.voi_window = pixel_data_renderer
+                  .grayscale_pipeline
+                  .voi_lut()
+                  .windows()
+                  .first()
+                  .cloned();
+              }
+            } else if let Some(animation_format) =
+              args.format.animation_format()
+            {
+              let pixel_data_renderer = pixel_data_renderer.as_mut().unwrap();
+
+              let cine_module = cine_module_transform
+                .as_ref()
+                .unwrap()
+                .get_output()
+                .unwrap();
+
+              // Respect frame trimming
+              if !cine_module.is_frame_trimmed(frame_index) {
+                let image = frame_to_final_image(
+                  frame,
+                  pixel_data_renderer,
+                  overlay_plane_module,
+                  args,
+                )
+                .map_err(frame_error_context)?;
+
+                animation_encoder
+                  .get_or_insert_with(|| {
+                    AnimationEncoder::new(animation_format)
+                  })
+                  .add_frame(image, args.animation_frame_duration(frame))
+                  .map_err(GetPixelDataError::AnimationError)
+                  .map_err(frame_error_context)?;
+              }
+            } else if args.format == OutputFormat::Tiff && args.tiff_multi_page
+            {
+              let pixel_data_renderer = pixel_data_renderer.as_mut().unwrap();
+
+              let image = frame_to_final_image(
+                frame,
+                pixel_data_renderer,
+                overlay_plane_module,
+                args,
+              )
+              .map_err(frame_error_context)?;
+
+              if multi_page_tiff_encoder.is_none() {
+                multi_page_tiff_encoder = Some(
+                  TiffEncoder::new(args.tiff_pixel_spacing(pixel_spacing))
+                    .map_err(GetPixelDataError::TiffError)?,
+                );
+              }
+
+              multi_page_tiff_encoder
+                .as_mut()
+                .unwrap()
+                .add_frame(&image)
+                .map_err(GetPixelDataError::TiffError)
+                .map_err(frame_error_context)?;
+            } else {
+              let output_target = output_target_base.append(&format!(
+                ".{:04}{}",
+                frame.index().unwrap(),
+                output_extension
+              ));
+
+              write_frame_to_image_file(
+                frame,
+                pixel_data_renderer,
+                overlay_plane_module,
+                pixel_spacing,
+                args,
+                output_target,
+              )
+              .await
+              .map_err(frame_error_context)?;
+            }
 
-        // If selecting a subset of frames, stop once they're all done
-        if let Some(frame_selection) = args.select_frames.as_ref()
-          && frame_selection.is_complete(frame_index, number_of_frames)
-        {
-          break;
+            if args.sidecar == Some(SidecarFormat::Json) {
+              let output_target =
+                output_target_base.append(&format!(".{frame_index:04}.json"));
+
+              write_frame_json_sidecar(
+                frame_index,
+                pixel_data_renderer.as_ref().unwrap(),
+                image_plane_module.as_ref(),
+                args,
+                output_target,
+              )
+              .await
+              .map_err(frame_error_context)?;
+            }
+          }
+
+          // If selecting a subset of frames, stop once they're all done
+          if let Some(frame_selection) = frame_selection
+            && frame_selection.is_complete(
+              frame_index,
+              number_of_frames,
+              frame_interval.as_ref(),
+            )
+          {
+            break 'frames;
+          }
         }
       }
 
       if *token == P10Token::End {
+        if let Some(video_codec) = video_codec {
+          let frame_timing = frame_timing_transform
+            .as_ref()
+            .and_then(|transform| transform.get_output());
+
+          let bytes = dcmfx::pixel_data::remux_to_mp4(
+            &video_stream,
+            video_codec,
+            frame_timing,
+          )
+          .map_err(GetPixelDataError::Mp4RemuxError)?;
+
+          write_bytes_to_output_target(
+            output_target_base.append(".mp4"),
+            &bytes,
+          )
+          .await?;
+        }
+
         if let Some(mp4_encoder) = mp4_encoder.as_mut() {
           mp4_encoder
             .finish()
@@ -605,6 +1897,26 @@ async fn get_pixel_data_from_input_source(
             .map_err(GetPixelDataError::FFmpegError)?;
         }
 
+        if let Some(animation_encoder) = animation_encoder.take() {
+          let bytes = animation_encoder
+            .finish()
+            .map_err(GetPixelDataError::AnimationError)?;
+
+          write_bytes_to_output_target(
+            output_target_base.append(output_extension),
+            &bytes,
+          )
+          .await?;
+        }
+
+        if let Some(tiff_encoder) = multi_page_tiff_encoder.take() {
+          write_bytes_to_output_target(
+            output_target_base.append(".tiff"),
+            &tiff_encoder.finish(),
+          )
+          .await?;
+        }
+
         return Ok(());
       }
     }
@@ -617,19 +1929,54 @@ async fn write_frame_to_image_file(
   frame: &mut PixelDataFrame,
   pixel_data_renderer: &mut Option<PixelDataRenderer>,
   overlay_plane_module: Option<&OverlayPlaneModule>,
+  pixel_spacing: Option<[f64; 2]>,
   args: &GetPixelDataArgs,
   output_target: OutputTarget,
 ) -> Result<(), GetPixelDataError> {
   if args.format == OutputFormat::Raw {
     write_fragments(output_target, frame).await.map_err(|e| {
       GetPixelDataError::P10Error(P10Error::FileError {
-        when: "Writing pixel data frame".to_string(),
-        details: e.to_string(),
+        when: "Writing pixel data frame".into(),
+        details: e.to_string().into(),
       })
     })?;
+  } else if args.format == OutputFormat::RawF32 {
+    let pixel_data_renderer = pixel_data_renderer.as_ref().unwrap();
+
+    let values = pixel_data_renderer
+      .decode_monochrome_frame_f32(frame)
+      .map_err(GetPixelDataError::PixelDataDecodeError)?;
+
+    let bytes: Vec<u8> = values
+      .iter()
+      .flat_map(|value| value.to_le_bytes())
+      .collect();
+
+    write_bytes_to_output_target(output_target, &bytes).await?;
   } else {
     let pixel_data_renderer = pixel_data_renderer.as_mut().unwrap();
 
+    // Write the JPEG data as-is if passthrough is requested and possible
+    if args.is_jpg_passthrough(pixel_data_renderer.transfer_syntax) {
+      let mut jpg_bytes = vec![];
+      frame
+        .write_raw_to(&mut jpg_bytes)
+        .map_err(|e| GetPixelDataError::OtherError(e.to_string()))?;
+
+      return write_bytes_to_output_target(output_target, &jpg_bytes).await;
+    }
+
+    // Write an indexed color PNG if requested and possible for this frame
+    if args.is_indexed_png_output()
+      && let Some(indexed_image) =
+        frame_to_final_indexed_image(frame, pixel_data_renderer, args)?
+    {
+      let png_bytes = encode_indexed_png(&indexed_image)
+        .map_err(GetPixelDataError::PngError)?;
+
+      return write_bytes_to_output_target(output_target, &png_bytes).await;
+    }
+
     let image = frame_to_final_image(
       frame,
       pixel_data_renderer,
@@ -651,35 +1998,188 @@ async fn write_frame_to_image_file(
       .encode_image(&image)
       .map_err(GetPixelDataError::ImageError)?,
 
-      OutputFormat::Raw | OutputFormat::Mp4 => unreachable!(),
-    }
+      OutputFormat::Tiff => {
+        let mut tiff_encoder =
+          TiffEncoder::new(args.tiff_pixel_spacing(pixel_spacing))
+            .map_err(GetPixelDataError::TiffError)?;
 
-    let output_stream_handle = output_target
-      .open_write_stream(true)
-      .await
-      .map_err(GetPixelDataError::P10Error)?;
+        tiff_encoder
+          .add_frame(&image)
+          .map_err(GetPixelDataError::TiffError)?;
 
-    let mut output_stream = output_stream_handle.lock().await;
+        image_buffer.get_mut().extend(tiff_encoder.finish());
+      }
 
-    output_stream
-      .write_all(&image_buffer.into_inner())
-      .await
-      .map_err(|e| {
-        GetPixelDataError::P10Error(P10Error::FileError {
-          when: "Writing image data".to_string(),
-          details: e.to_string(),
-        })
-      })?;
+      OutputFormat::Raw
+      | OutputFormat::RawF32
+      | OutputFormat::Gif
+      | OutputFormat::Apng
+      | OutputFormat::Mp4 => unreachable!(),
+    }
 
-    output_target
-      .commit(&mut output_stream)
-      .await
-      .map_err(GetPixelDataError::P10Error)?;
+    write_bytes_to_output_target(output_target, &image_buffer.into_inner())
+      .await?;
   }
 
   Ok(())
 }
 
+/// Writes a JSON sidecar for a frame that records its geometry, the VOI window
+/// and rescale applied to it, its photometric interpretation, and its output
+/// dimensions. This is context that's lost when a frame is written to a
+/// regular image file.
+///
+async fn write_frame_json_sidecar(
+  frame_index: usize,
+  pixel_data_renderer: &PixelDataRenderer,
+  image_plane_module: Option<&ImagePlaneModule>,
+  args: &GetPixelDataArgs,
+  output_target: OutputTarget,
+) -> Result<(), GetPixelDataError> {
+  let image_pixel_module = &pixel_data_renderer.image_pixel_module;
+  let grayscale_pipeline =
+    pixel_data_renderer.frame_grayscale_pipeline(Some(frame_index));
+
+  let geometry = image_plane_module.map(|module| {
+    serde_json::json!({
+      "image_position_patient": module.image_position_patient.map(json_f32),
+      "image_orientation_patient":
+        module.image_orientation_patient.map(json_f32),
+      "pixel_spacing": module.pixel_spacing.map(json_f32),
+      "slice_thickness": module.slice_thickness.map(json_f32),
+      "spacing_between_slices": module.spacing_between_slices.map(json_f32),
+      "slice_location": module.slice_location.map(json_f32),
+    })
+  });
+
+  let photometric_interpretation = image_pixel_module
+    .photometric_interpretation()
+    .to_data_element_value()
+    .get_string()
+    .map(|s| s.to_string())
+    .map_err(GetPixelDataError::DataError)?;
+
+  let photometric = serde_json::json!({
+    "photometric_interpretation": photometric_interpretation,
+    "samples_per_pixel": u8::from(image_pixel_module.samples_per_pixel()),
+    "bits_allocated": u8::from(image_pixel_module.bits_allocated()),
+    "bits_stored": image_pixel_module.bits_stored(),
+    "pixel_representation":
+      if image_pixel_module.pixel_representation().is_signed() {
+        "signed"
+      } else {
+        "unsigned"
+      },
+  });
+
+  // Rescale and VOI windows only apply to monochrome images
+  let is_monochrome = image_pixel_module.is_monochrome();
+
+  let rescale = match grayscale_pipeline.modality_lut() {
+    ModalityLutModule::Rescale {
+      rescale_intercept,
+      rescale_slope,
+      rescale_type,
+    } if is_monochrome => Some(serde_json::json!({
+      "intercept": json_f32(*rescale_intercept),
+      "slope": json_f32(*rescale_slope),
+      "type": rescale_type.as_str(),
+    })),
+
+    _ => None,
+  };
+
+  // A VOI window is only applied to monochrome images that have no VOI LUT
+  let voi_lut = grayscale_pipeline.voi_lut();
+  let voi_window = voi_lut
+    .windows()
+    .first()
+    .filter(|_| is_monochrome && voi_lut.luts().is_empty())
+    .map(|window| {
+      serde_json::json!({
+        "center": json_f32(window.center()),
+        "width": json_f32(window.width()),
+        "function": window.function().as_str(),
+        "explanation": window.explanation(),
+      })
+    });
+
+  // Raw output isn't decoded so it has no output dimensions, and raw float
+  // output ignores any crop, transform, or resize
+  let output_dimensions = if args.format == OutputFormat::Raw {
+    None
+  } else if args.format == OutputFormat::RawF32 {
+    Some(serde_json::json!({
+      "width": image_pixel_module.columns(),
+      "height": image_pixel_module.rows(),
+    }))
+  } else {
+    let (width, height) = args.output_dimensions(
+      image_pixel_module.columns(),
+      image_pixel_module.rows(),
+    );
+    let (width, height) = args
+      .new_dimensions(width, height)
+      .unwrap_or((width, height));
+
+    Some(serde_json::json!({ "width": width, "height": height }))
+  };
+
+  let sidecar = serde_json::json!({
+    "frame_index": frame_index,
+    "columns": image_pixel_module.columns(),
+    "rows": image_pixel_module.rows(),
+    "geometry": geometry,
+    "photometric": photometric,
+    "rescale": rescale,
+    "voi_window": voi_window,
+    "output": output_dimensions,
+  });
+
+  let mut bytes = serde_json::to_vec_pretty(&sidecar).unwrap();
+  bytes.push(b'\n');
+
+  write_bytes_to_output_target(output_target, &bytes).await
+}
+
+/// Converts an `f32` to a JSON number that has the shortest decimal
+/// representation of the `f32` value, rather than that of the equivalent `f64`
+/// value, e.g. 0.1 rather than 0.10000000149011612.
+///
+fn json_f32(value: f32) -> serde_json::Value {
+  value
+    .to_string()
+    .parse::<f64>()
+    .map(serde_json::Value::from)
+    .unwrap_or(serde_json::Value::Null)
+}
+
+/// Writes the complete data for an output image file to an output target.
+///
+async fn write_bytes_to_output_target(
+  output_target: OutputTarget,
+  bytes: &[u8],
+) -> Result<(), GetPixelDataError> {
+  let output_stream_handle = output_target
+    .open_write_stream(true)
+    .await
+    .map_err(GetPixelDataError::P10Error)?;
+
+  let mut output_stream = output_stream_handle.lock().await;
+
+  output_stream.write_all(bytes).await.map_err(|e| {
+    GetPixelDataError::P10Error(P10Error::FileError {
+      when: "Writing image data".into(),
+      details: e.to_string().into(),
+    })
+  })?;
+
+  output_target
+    .commit(&mut output_stream)
+    .await
+    .map_err(GetPixelDataError::P10Error)
+}
+
 /// Writes the data for a single frame of pixel data to an output target.
 ///
 async fn write_fragments(
@@ -694,8 +2194,8 @@ async fn write_fragments(
     for fragment in frame.chunks() {
       output_stream.write_all(fragment).await.map_err(|e| {
         P10Error::FileError {
-          when: "Writing pixel data fragment".to_string(),
-          details: e.to_string(),
+          when: "Writing pixel data fragment".into(),
+          details: e.to_string().into(),
         }
       })?;
     }
@@ -704,15 +2204,15 @@ async fn write_fragments(
       .write_all(&frame.to_bytes())
       .await
       .map_err(|e| P10Error::FileError {
-        when: "Writing pixel data frame".to_string(),
-        details: e.to_string(),
+        when: "Writing pixel data frame".into(),
+        details: e.to_string().into(),
       })?;
   }
 
   output_target.commit(&mut output_stream).await.map_err(|e| {
     P10Error::FileError {
-      when: "Writing pixel data frame".to_string(),
-      details: e.to_string(),
+      when: "Writing pixel data frame".into(),
+      details: e.to_string().into(),
     }
   })
 }
@@ -743,6 +2243,92 @@ fn frame_to_final_image(
       .unwrap();
   }
 
+  image = apply_crop_and_transform(image, args);
+
+  // Apply the image resize, if specified. Note that no resize is performed here
+  // when outputting to an MP4 because in that case FFmpeg is used to do the
+  // resize, which is faster.
+  if args.format != OutputFormat::Mp4
+    && let Some((new_width, new_height)) =
+      args.new_dimensions(image.width(), image.height())
+  {
+    image = image.resize_exact(
+      new_width,
+      new_height,
+      args.resize_filter.filter_type(),
+    );
+  }
+
+  Ok(image)
+}
+
+/// Turns a raw frame of pixel data into an [`IndexedColorImage`], with any
+/// active crop or transform applied. Returns `None` if the frame can't be
+/// represented as an indexed color image.
+///
+fn frame_to_final_indexed_image(
+  frame: &mut PixelDataFrame,
+  pixel_data_renderer: &mut PixelDataRenderer,
+  args: &GetPixelDataArgs,
+) -> Result<Option<IndexedColorImage>, GetPixelDataError> {
+  let indexed_image = if pixel_data_renderer.image_pixel_module.is_monochrome()
+  {
+    let Some(color_palette) = pixel_data_renderer.color_palette else {
+      return Ok(None);
+    };
+
+    let monochrome_image = pixel_data_renderer
+      .decode_monochrome_frame(frame)
+      .map_err(GetPixelDataError::PixelDataDecodeError)?;
+
+    update_voi_window(&monochrome_image, pixel_data_renderer);
+
+    let grayscale_pipeline =
+      pixel_data_renderer.frame_grayscale_pipeline(frame.index());
+
+    pixel_data_renderer.render_monochrome_image_indexed_with_grayscale_pipeline(
+      &monochrome_image,
+      &grayscale_pipeline,
+      color_palette,
+    )
+  } else if pixel_data_renderer
+    .image_pixel_module
+    .photometric_interpretation()
+    .is_palette_color()
+  {
+    let color_image = pixel_data_renderer
+      .decode_color_frame(frame)
+      .map_err(GetPixelDataError::PixelDataDecodeError)?;
+
+    match color_image.to_indexed_color_image() {
+      Some(indexed_image) => indexed_image,
+      None => return Ok(None),
+    }
+  } else {
+    return Ok(None);
+  };
+
+  let (indices, palette) = indexed_image.into_indices_image();
+
+  let indices = apply_crop_and_transform(indices.into(), args).into_luma8();
+
+  Ok(
+    IndexedColorImage::new(
+      indices.width() as u16,
+      indices.height() as u16,
+      indices.into_raw(),
+      palette,
+    )
+    .ok(),
+  )
+}
+
+/// Applies the image crop and transform specified in the arguments, if any.
+///
+fn apply_crop_and_transform(
+  mut image: image::DynamicImage,
+  args: &GetPixelDataArgs,
+) -> image::DynamicImage {
   // Apply the image crop, if specified
   if let Some(crop) = args.crop {
     let (cropped_height, cropped_width) =
@@ -761,21 +2347,30 @@ fn frame_to_final_image(
     image.apply_orientation(transform.orientation());
   }
 
-  // Apply the image resize, if specified. Note that no resize is performed here
-  // when outputting to an MP4 because in that case FFmpeg is used to do the
-  // resize, which is faster.
-  if args.format != OutputFormat::Mp4
-    && let Some((new_width, new_height)) =
-      args.new_dimensions(image.width(), image.height())
-  {
-    image = image.resize_exact(
-      new_width,
-      new_height,
-      args.resize_filter.filter_type(),
-    );
-  }
+  image
+}
 
-  Ok(image)
+/// Encodes an [`IndexedColorImage`] as an 8-bit indexed color PNG.
+///
+fn encode_indexed_png(image: &IndexedColorImage) -> Result<Vec<u8>, String> {
+  let mut png_bytes = vec![];
+
+  let mut encoder = png::Encoder::new(
+    &mut png_bytes,
+    image.width().into(),
+    image.height().into(),
+  );
+  encoder.set_color(png::ColorType::Indexed);
+  encoder.set_depth(png::BitDepth::Eight);
+  encoder.set_palette(image.palette().as_flattened());
+
+  let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+  writer
+    .write_image_data(image.indices())
+    .map_err(|e| e.to_string())?;
+  writer.finish().map_err(|e| e.to_string())?;
+
+  Ok(png_bytes)
 }
 
 /// Turns a raw frame of pixel data into an [`image::DynamicImage`], applying
@@ -793,56 +2388,40 @@ fn frame_to_dynamic_image(
       .decode_monochrome_frame(frame)
       .map_err(GetPixelDataError::PixelDataDecodeError)?;
 
-    // Apply the VOI override if it's set
-    if let Some(voi_window_override) = &args.voi_window {
-      pixel_data_renderer
-        .grayscale_pipeline
-        .set_voi_window(VoiWindow::new(
-          voi_window_override[0],
-          voi_window_override[1],
-          "".to_string(),
-          VoiLutFunction::LinearExact,
-        ));
-    }
-    // If there's no VOI LUT in the DICOM or specified on the command line
-    // then calculate a VOI Window from the content of the first frame and use
-    // it for all subsequent frames.
-    else if pixel_data_renderer.grayscale_pipeline.voi_lut().is_empty() {
-      let image = pixel_data_renderer
-        .decode_monochrome_frame(frame)
-        .map_err(GetPixelDataError::PixelDataDecodeError)?;
-
-      if let Some(window) = image.default_voi_window() {
-        pixel_data_renderer
-          .grayscale_pipeline
-          .set_voi_window(window);
-      }
-    }
+    update_voi_window(&monochrome_image, pixel_data_renderer);
 
-    // For HDR outputs, emit a Luma16 buffer. A color palette implies 8-bit
-    // output because looking up a color palette always returns 8-bit values.
-    if args.is_output_hdr() && args.color_palette.is_none() {
-      let image = monochrome_image
-        .to_gray_u16_image(&pixel_data_renderer.grayscale_pipeline);
+    // Enhanced multi-frame data sets can specify a different Modality LUT and
+    // VOI LUT for each frame
+    let grayscale_pipeline =
+      pixel_data_renderer.frame_grayscale_pipeline(frame.index());
 
-      Ok(image.into())
-    }
     // If there is an active color palette then use it and output the
-    // resulting RGB8
-    else if let Some(color_palette) = args.color_palette {
-      let image = pixel_data_renderer.render_monochrome_image(
-        &monochrome_image,
-        Some(color_palette.color_palette()),
-      );
+    // resulting RGB8. A color palette implies 8-bit windowed output because
+    // looking up a color palette always returns 8-bit values.
+    if pixel_data_renderer.color_palette.is_some() {
+      let image = pixel_data_renderer
+        .render_monochrome_image_with_grayscale_pipeline(
+          &monochrome_image,
+          &grayscale_pipeline,
+          None,
+        );
 
       Ok(image.into())
     }
-    // Otherwise, emit a Luma8 image
+    // Otherwise, emit a Luma8 or Luma16 image based on the grayscale output
+    // depth. If the output format doesn't support 16-bit then reduce to 8-bit.
     else {
-      let image = monochrome_image
-        .to_gray_u8_image(&pixel_data_renderer.grayscale_pipeline);
+      let image = pixel_data_renderer
+        .render_grayscale_image_with_grayscale_pipeline(
+          &monochrome_image,
+          &grayscale_pipeline,
+        );
 
-      Ok(image.into())
+      if args.is_output_hdr() || image.color() == image::ColorType::L8 {
+        Ok(image)
+      } else {
+        Ok(image.into_luma8().into())
+      }
     }
   } else {
     let image = pixel_data_renderer
@@ -861,10 +2440,28 @@ fn frame_to_dynamic_image(
   }
 }
 
+/// If there's no VOI LUT in the DICOM, in the render profile, or specified on
+/// the command line then a VOI window is calculated from the content of the
+/// first frame and used for all subsequent frames.
+///
+fn update_voi_window(
+  monochrome_image: &MonochromeImage,
+  pixel_data_renderer: &mut PixelDataRenderer,
+) {
+  if pixel_data_renderer.grayscale_pipeline.voi_lut().is_empty()
+    && let Some(window) = monochrome_image.default_voi_window()
+  {
+    pixel_data_renderer
+      .grayscale_pipeline
+      .set_voi_window(window);
+  }
+}
+
 /// Creates an [`Mp4Encoder`] based on the first frame to be encoded.
 ///
 async fn create_mp4_encoder(
   first_frame: &image::DynamicImage,
+  image_pixel_module: &ImagePixelModule,
   cine_module: &CineModule,
   multiframe_module: &MultiFrameModule,
   args: &GetPixelDataArgs,
@@ -887,9 +2484,13 @@ async fn create_mp4_encoder(
   }
 
   // Determine output dimensions. If there is a resize active then it will be
-  // performed by the MP4 encoder.
+  // performed by the MP4 encoder. The resize is calculated from the full
+  // resolution dimensions because frames may have been decoded at a reduced
+  // resolution.
+  let (width, height) = args
+    .output_dimensions(image_pixel_module.columns(), image_pixel_module.rows());
   let (output_width, output_height) = args
-    .new_dimensions(first_frame.width(), first_frame.height())
+    .new_dimensions(width, height)
     .unwrap_or((first_frame.width(), first_frame.height()));
 
   // Use the Cine Module to determine the frame rate. This can be overridden by
@@ -944,6 +2545,7 @@ async fn write_frame_to_mp4_file(
     *mp4_encoder = Some(
       create_mp4_encoder(
         &image,
+        &pixel_data_renderer.image_pixel_module,
         cine_module,
         multiframe_module,
         args,
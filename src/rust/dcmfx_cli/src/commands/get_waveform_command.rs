@@ -0,0 +1,437 @@
+use std::{path::PathBuf, sync::Arc};
+
+use clap::{Args, ValueEnum};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use dcmfx::{
+  core::*,
+  p10::*,
+  waveform::{
+    P10WaveformChunkTransform, P10WaveformChunkTransformError, WaveformChunk,
+    WaveformDecodeError, WaveformMultiplexGroup,
+  },
+};
+
+use crate::utils::{self, InputSource, OutputTarget, wav_encoder::WavEncoder};
+
+pub const ABOUT: &str = "Extracts waveform data, such as ECGs and audio, from \
+  DICOM P10 files, writing it to CSV and WAV files";
+
+#[derive(Args)]
+pub struct GetWaveformArgs {
+  #[arg(
+    long,
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
+  )]
+  concurrency: Option<usize>,
+
+  #[command(flatten)]
+  input: crate::args::input_args::P10InputArgs,
+
+  #[arg(
+    long,
+    short = 'd',
+    help_heading = "Output",
+    help = "The directory to write output files into. The names of the output \
+      files will be the name of the input file suffixed with the index of the \
+      waveform multiplex group, and an appropriate file extension."
+  )]
+  output_directory: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help = "Overwrite any output files that already exist",
+    default_value_t = false
+  )]
+  overwrite: bool,
+
+  #[arg(
+    long,
+    short,
+    value_enum,
+    help_heading = "Output",
+    help = "The output format for the waveform data. One file is written for \
+      each waveform multiplex group.",
+    default_value_t = OutputFormat::Csv
+  )]
+  format: OutputFormat,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Whether to write raw stored sample values into CSV files instead \
+      of physical values. Physical values have the channel sensitivity, \
+      sensitivity correction factor, and baseline applied.",
+    default_value_t = false
+  )]
+  stored_values: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+  /// Writes a CSV file with a time column followed by a column for each
+  /// channel. Times are in seconds, and padding samples are left empty.
+  Csv,
+
+  /// Writes a WAV file with the raw stored sample values of each channel.
+  /// Sampling frequencies are rounded to the nearest whole number. 64-bit
+  /// sample interpretations aren't supported.
+  Wav,
+}
+
+impl OutputFormat {
+  fn extension(&self) -> &'static str {
+    match self {
+      OutputFormat::Csv => "csv",
+      OutputFormat::Wav => "wav",
+    }
+  }
+}
+
+#[allow(clippy::enum_variant_names)]
+enum GetWaveformError {
+  P10Error(P10Error),
+  DataError(DataError),
+  WaveformDecodeError(WaveformDecodeError),
+  WavError(String),
+}
+
+impl GetWaveformError {
+  /// Returns lines of text that describe this error in a human-readable
+  /// format.
+  ///
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      GetWaveformError::P10Error(e) => e.to_lines(task_description),
+      GetWaveformError::DataError(e) => e.to_lines(task_description),
+      GetWaveformError::WaveformDecodeError(e) => e.to_lines(task_description),
+      GetWaveformError::WavError(e) => vec![
+        format!("WAV encoding error {}", task_description),
+        "".to_string(),
+        format!("  Error: {}", e),
+      ],
+    }
+  }
+}
+
+impl From<P10WaveformChunkTransformError> for GetWaveformError {
+  fn from(e: P10WaveformChunkTransformError) -> Self {
+    match e {
+      P10WaveformChunkTransformError::P10Error(e) => Self::P10Error(e),
+      P10WaveformChunkTransformError::DataError(e) => Self::DataError(e),
+    }
+  }
+}
+
+pub async fn run(args: GetWaveformArgs) -> Result<(), ()> {
+  crate::validate_output_args(None, args.output_directory.as_ref()).await;
+
+  OutputTarget::set_overwrite(args.overwrite);
+
+  let input_sources = args.input.base.input_sources().await;
+
+  let result = utils::run_tasks(
+    utils::task_count(args.concurrency),
+    input_sources,
+    async |input_source: InputSource| {
+      let output_target_base = OutputTarget::from_input_source(
+        &input_source,
+        "",
+        &args.output_directory,
+      )
+      .await;
+
+      match get_waveform_from_input_source(
+        &input_source,
+        output_target_base,
+        &args,
+      )
+      .await
+      {
+        Ok(()) => Ok(()),
+
+        Err(GetWaveformError::P10Error(P10Error::DicmPrefixNotPresent))
+          if args.input.ignore_invalid =>
+        {
+          Ok(())
+        }
+
+        Err(e) => {
+          let task_description =
+            format!("extracting waveform data from \"{input_source}\"");
+
+          Err(e.to_lines(&task_description))
+        }
+      }
+    },
+  )
+  .await;
+
+  match result {
+    Ok(()) => Ok(()),
+
+    Err(lines) => {
+      error::print_error_lines(&lines);
+      Err(())
+    }
+  }
+}
+
+/// An output file that the chunks of a single waveform multiplex group are
+/// being written to.
+///
+struct WaveformOutput {
+  multiplex_group_index: usize,
+  output_target: OutputTarget,
+  output_stream: Arc<Mutex<Box<dyn IoAsyncWrite>>>,
+  wav_encoder: Option<WavEncoder>,
+}
+
+async fn get_waveform_from_input_source(
+  input_source: &InputSource,
+  output_target_base: OutputTarget,
+  args: &GetWaveformArgs,
+) -> Result<(), GetWaveformError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(GetWaveformError::P10Error)?;
+
+  // Create read context with a small max token size to keep memory usage low
+  let read_config = args.input.p10_read_config().max_token_size(1024 * 1024);
+  let mut read_context = P10ReadContext::new(Some(read_config));
+
+  let mut p10_waveform_chunk_transform = P10WaveformChunkTransform::new();
+
+  // The output for the waveform multiplex group currently being written. The
+  // chunks of each multiplex group are emitted in order, so only one output
+  // is open at a time.
+  let mut waveform_output: Option<WaveformOutput> = None;
+
+  loop {
+    let tokens = dcmfx::p10::read_tokens_from_stream_async(
+      &mut stream,
+      &mut read_context,
+      None,
+    )
+    .await
+    .map_err(GetWaveformError::P10Error)?;
+
+    for token in tokens.iter() {
+      if *token == P10Token::End {
+        if let Some(waveform_output) = waveform_output.take() {
+          finish_waveform_output(waveform_output).await?;
+        }
+
+        return Ok(());
+      }
+
+      let chunks = p10_waveform_chunk_transform.add_token(token)?;
+
+      for chunk in chunks {
+        // Move to a new output file when a new multiplex group starts
+        if waveform_output.as_ref().map(|o| o.multiplex_group_index)
+          != Some(chunk.multiplex_group_index())
+        {
+          if let Some(waveform_output) = waveform_output.take() {
+            finish_waveform_output(waveform_output).await?;
+          }
+
+          waveform_output = Some(
+            start_waveform_output(&output_target_base, &chunk, args).await?,
+          );
+        }
+
+        let waveform_output = waveform_output.as_mut().unwrap();
+
+        let channel_samples = chunk
+          .channel_samples()
+          .map_err(GetWaveformError::WaveformDecodeError)?;
+
+        let bytes = match &waveform_output.wav_encoder {
+          Some(wav_encoder) => wav_encoder.encode_samples(&channel_samples),
+          None => {
+            csv_rows(&chunk, &channel_samples, args.stored_values).into_bytes()
+          }
+        };
+
+        write_to_output_stream(&waveform_output.output_stream, &bytes).await?;
+      }
+    }
+  }
+}
+
+/// Opens the output file for the waveform multiplex group of the given chunk,
+/// and writes its CSV or WAV header.
+///
+async fn start_waveform_output(
+  output_target_base: &OutputTarget,
+  chunk: &WaveformChunk,
+  args: &GetWaveformArgs,
+) -> Result<WaveformOutput, GetWaveformError> {
+  let multiplex_group = chunk.multiplex_group();
+
+  let (wav_encoder, header) = match args.format {
+    OutputFormat::Csv => (
+      None,
+      csv_header(multiplex_group, args.stored_values).into_bytes(),
+    ),
+
+    OutputFormat::Wav => {
+      let (wav_encoder, header) =
+        WavEncoder::new(multiplex_group).map_err(GetWaveformError::WavError)?;
+
+      (Some(wav_encoder), header)
+    }
+  };
+
+  let output_target = output_target_base.append(&format!(
+    ".{}.{}",
+    chunk.multiplex_group_index(),
+    args.format.extension()
+  ));
+
+  let output_stream = output_target
+    .open_write_stream(true)
+    .await
+    .map_err(GetWaveformError::P10Error)?;
+
+  write_to_output_stream(&output_stream, &header).await?;
+
+  Ok(WaveformOutput {
+    multiplex_group_index: chunk.multiplex_group_index(),
+    output_target,
+    output_stream,
+    wav_encoder,
+  })
+}
+
+/// Writes any trailing bytes to the output file for a waveform multiplex
+/// group, then commits it.
+///
+async fn finish_waveform_output(
+  waveform_output: WaveformOutput,
+) -> Result<(), GetWaveformError> {
+  if let Some(wav_encoder) = &waveform_output.wav_encoder {
+    write_to_output_stream(
+      &waveform_output.output_stream,
+      &wav_encoder.finish(),
+    )
+    .await?;
+  }
+
+  let mut output_stream = waveform_output.output_stream.lock().await;
+
+  waveform_output
+    .output_target
+    .commit(&mut output_stream)
+    .await
+    .map_err(GetWaveformError::P10Error)
+}
+
+async fn write_to_output_stream(
+  output_stream: &Arc<Mutex<Box<dyn IoAsyncWrite>>>,
+  bytes: &[u8],
+) -> Result<(), GetWaveformError> {
+  output_stream
+    .lock()
+    .await
+    .write_all(bytes)
+    .await
+    .map_err(|e| {
+      GetWaveformError::P10Error(P10Error::FileError {
+        when: "Writing waveform data".into(),
+        details: e.to_string().into(),
+      })
+    })
+}
+
+/// Returns the header row of the CSV file for a waveform multiplex group. Each
+/// channel's column is named using its label, falling back to the meaning of
+/// its source code, and when physical values are being written the code value
+/// of its sensitivity units is appended.
+///
+fn csv_header(
+  multiplex_group: &WaveformMultiplexGroup,
+  stored_values: bool,
+) -> String {
+  let mut columns = vec!["Time (s)".to_string()];
+
+  for (i, channel) in multiplex_group.channels().iter().enumerate() {
+    let mut column = channel
+      .label
+      .clone()
+      .or_else(|| channel.source.code_meaning.clone())
+      .unwrap_or_else(|| format!("Channel {}", i + 1));
+
+    if !stored_values
+      && let Some(units) = channel
+        .sensitivity_units
+        .as_ref()
+        .and_then(|units| units.code_value.as_ref())
+    {
+      column.push_str(&format!(" ({units})"));
+    }
+
+    columns.push(csv_field(&column));
+  }
+
+  columns.join(",") + "\n"
+}
+
+/// Returns the CSV rows for a chunk of waveform data, one row per sample set.
+///
+fn csv_rows(
+  chunk: &WaveformChunk,
+  channel_samples: &[Vec<i64>],
+  stored_values: bool,
+) -> String {
+  let multiplex_group = chunk.multiplex_group();
+
+  // The multiplex group time offset is in milliseconds
+  let time_offset = multiplex_group.time_offset().unwrap_or(0.0) / 1000.0;
+  let sampling_frequency = multiplex_group.sampling_frequency();
+
+  let mut rows = String::new();
+
+  for i in 0..chunk.number_of_samples() as usize {
+    let sample_index = chunk.sample_offset() + i as u64;
+    let time = time_offset + sample_index as f64 / sampling_frequency;
+
+    rows.push_str(&format!("{time:.6}"));
+
+    for (samples, channel) in
+      channel_samples.iter().zip(multiplex_group.channels())
+    {
+      rows.push(',');
+
+      let value = samples[i];
+
+      // Padding samples aren't real measurements, so are left empty
+      if Some(value) == multiplex_group.padding_value() {
+        continue;
+      }
+
+      if stored_values {
+        rows.push_str(&value.to_string());
+      } else {
+        rows.push_str(&channel.to_physical_value(value).to_string());
+      }
+    }
+
+    rows.push('\n');
+  }
+
+  rows
+}
+
+/// Quotes a CSV field if it contains characters that require quoting.
+///
+fn csv_field(s: &str) -> String {
+  if s.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", s.replace('"', "\"\""))
+  } else {
+    s.to_string()
+  }
+}
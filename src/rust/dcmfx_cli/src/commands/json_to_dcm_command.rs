@@ -1,11 +1,14 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Args;
 use tokio::io::AsyncReadExt;
 
 use dcmfx::{core::*, json::*, p10::*};
 
-use crate::utils::{self, InputSource, OutputTarget};
+use crate::{
+  args::transfer_syntax_arg::TransferSyntaxArg,
+  utils::{self, InputSource, OutputTarget},
+};
 
 pub const ABOUT: &str = "Converts DICOM JSON files to DICOM P10 files";
 
@@ -13,11 +16,10 @@ pub const ABOUT: &str = "Converts DICOM JSON files to DICOM P10 files";
 pub struct ToDcmArgs {
   #[arg(
     long,
-    help = "The number of concurrent tasks to use. Defaults to the number of \
-      CPU cores.",
-    default_value_t = {num_cpus::get()}
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
   )]
-  concurrency: usize,
+  concurrency: Option<usize>,
 
   #[command(flatten)]
   input: crate::args::input_args::BaseInputArgs,
@@ -59,9 +61,55 @@ pub struct ToDcmArgs {
     default_value_t = uids::DCMFX_IMPLEMENTATION_VERSION_NAME.to_string(),
   )]
   implementation_version_name: String,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "The value of the Implementation Class UID data element in output \
+      DICOM P10 files. The value must conform to the specification of the UI \
+      (Unique Identifier) value representation.",
+    default_value_t = uids::DCMFX_IMPLEMENTATION_CLASS_UID.to_string(),
+  )]
+  implementation_class_uid: String,
+
+  #[arg(
+    long,
+    help_heading = "File Meta Information",
+    help = "The transfer syntax to specify in the File Meta Information when \
+      the DICOM JSON doesn't specify one. Pixel data isn't transcoded, so \
+      this must match the encoding of any pixel data in the DICOM JSON. \
+      Defaults to 'implicit-vr-little-endian'."
+  )]
+  transfer_syntax: Option<TransferSyntaxArg>,
+
+  #[arg(
+    long,
+    help_heading = "File Meta Information",
+    help = "Don't add File Meta Information data elements that are missing \
+      from the DICOM JSON. By default, the Media Storage SOP Class UID and \
+      Media Storage SOP Instance UID are taken from the SOP Class UID and SOP \
+      Instance UID, and the Transfer Syntax UID is set to the value of \
+      --transfer-syntax.",
+    default_value_t = false
+  )]
+  no_synthesize_file_meta_information: bool,
+
+  #[arg(
+    long,
+    help_heading = "Pixel Data",
+    help = "A directory of files that each hold one frame of encapsulated pixel \
+      data, e.g. one JPEG file per frame. The frames are encapsulated into the \
+      output's Pixel Data as they're read, in order of their file names, and \
+      replace any Pixel Data in the DICOM JSON, including one that references \
+      its value with a BulkDataURI. The transfer syntax must be encapsulated \
+      and match the encoding of the frames."
+  )]
+  frames_dir: Option<PathBuf>,
 }
 
+#[allow(clippy::enum_variant_names)]
 enum ToDcmError {
+  DataError(DataError),
   P10Error(P10Error),
   JsonDeserializeError(JsonDeserializeError),
 }
@@ -73,12 +121,19 @@ pub async fn run(args: ToDcmArgs) -> Result<(), ()> {
   )
   .await;
 
+  if args.transfer_syntax == Some(TransferSyntaxArg::PassThrough) {
+    utils::exit_with_error(
+      "--transfer-syntax can't be 'pass-through' when converting DICOM JSON",
+      "",
+    );
+  }
+
   OutputTarget::set_overwrite(args.overwrite);
 
   let input_sources = args.input.input_sources().await;
 
   let result = utils::run_tasks(
-    args.concurrency,
+    utils::task_count(args.concurrency),
     input_sources,
     async |input_source: InputSource| {
       let output_target = if let Some(output_filename) = &args.output_filename {
@@ -99,6 +154,7 @@ pub async fn run(args: ToDcmArgs) -> Result<(), ()> {
           let task_description = format!("converting \"{input_source}\"");
 
           Err(match e {
+            ToDcmError::DataError(e) => e.to_lines(&task_description),
             ToDcmError::P10Error(e) => e.to_lines(&task_description),
             ToDcmError::JsonDeserializeError(e) => {
               e.to_lines(&task_description)
@@ -141,8 +197,8 @@ async fn input_source_to_dcm(
   // Read the DICOM JSON from the input stream
   if let Err(e) = stream.read_to_end(&mut buffer).await {
     return Err(ToDcmError::P10Error(P10Error::FileError {
-      when: "Reading file".to_string(),
-      details: e.to_string(),
+      when: "Reading file".into(),
+      details: e.to_string().into(),
     }));
   }
 
@@ -151,30 +207,290 @@ async fn input_source_to_dcm(
     Ok(s) => s,
     Err(e) => {
       return Err(ToDcmError::P10Error(P10Error::FileError {
-        when: "Reading file".to_string(),
-        details: format!("Invalid UTF-8 at byte {}", e.valid_up_to()),
+        when: "Reading file".into(),
+        details: error_details!("Invalid UTF-8 at byte {}", e.valid_up_to()),
       }));
     }
   };
 
-  // Read DICOM JSON into a data set
-  let data_set =
-    DataSet::from_json(json).map_err(ToDcmError::JsonDeserializeError)?;
+  // Read DICOM JSON into a data set. When frames are being added then any
+  // Pixel Data in the DICOM JSON is removed first, as it's commonly a
+  // BulkDataURI that can't be read.
+  let mut data_set = match &args.frames_dir {
+    Some(_) => DataSet::from_json(&remove_json_pixel_data(json)),
+    None => DataSet::from_json(json),
+  }
+  .map_err(ToDcmError::JsonDeserializeError)?;
 
   let write_config = P10WriteConfig::default()
+    .implementation_class_uid(args.implementation_class_uid.clone())
     .implementation_version_name(args.implementation_version_name.clone());
 
+  // Add any File Meta Information data elements that are missing, as DICOM
+  // JSON commonly omits group 0x0002
+  if !args.no_synthesize_file_meta_information {
+    let transfer_syntax = args
+      .transfer_syntax
+      .and_then(|ts| ts.as_transfer_syntax())
+      .unwrap_or(&transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN);
+
+    data_set
+      .synthesize_file_meta_information(
+        transfer_syntax,
+        Some(write_config.clone()),
+      )
+      .map_err(ToDcmError::DataError)?;
+  }
+
   // Get exclusive access to the output stream
   let mut output_stream = output_stream.lock().await;
 
   // Write P10 data to output stream
-  data_set
-    .write_p10_stream_async(&mut *output_stream, Some(write_config))
-    .await
-    .map_err(ToDcmError::P10Error)?;
+  match &args.frames_dir {
+    Some(frames_dir) => {
+      write_data_set_with_frames(
+        &data_set,
+        frames_dir,
+        &mut *output_stream,
+        write_config,
+      )
+      .await?
+    }
+
+    None => data_set
+      .write_p10_stream_async(&mut *output_stream, Some(write_config))
+      .await
+      .map_err(ToDcmError::P10Error)?,
+  }
 
   output_target
     .commit(&mut output_stream)
     .await
     .map_err(ToDcmError::P10Error)
 }
+
+/// Removes the *'(7FE0,0010) Pixel Data'* data element from the root of DICOM
+/// JSON. If the DICOM JSON isn't valid then it's returned unchanged so that the
+/// error is reported when it's read into a data set.
+///
+fn remove_json_pixel_data(json: &str) -> String {
+  match serde_json::from_str::<serde_json::Value>(json) {
+    Ok(serde_json::Value::Object(mut map)) => {
+      map.remove(&dictionary::PIXEL_DATA.tag.to_hex_string());
+      serde_json::Value::Object(map).to_string()
+    }
+
+    _ => json.to_string(),
+  }
+}
+
+/// Writes a data set as DICOM P10 to a stream, with its *'(7FE0,0010) Pixel
+/// Data'* data element holding the frames of encapsulated pixel data read from
+/// the files in the given directory. Each frame is streamed from its file into
+/// the output, so frames are never held in memory in their entirety.
+///
+async fn write_data_set_with_frames(
+  data_set: &DataSet,
+  frames_dir: &Path,
+  output_stream: &mut impl IoAsyncWrite,
+  write_config: P10WriteConfig,
+) -> Result<(), ToDcmError> {
+  let transfer_syntax = data_set
+    .get_transfer_syntax()
+    .map_err(ToDcmError::DataError)?;
+
+  if !transfer_syntax.is_encapsulated {
+    return Err(ToDcmError::DataError(DataError::new_value_invalid(
+      format!(
+        "Transfer syntax '{}' does not use encapsulated pixel data, which is \
+       required when adding frames",
+        transfer_syntax.name
+      ),
+    )));
+  }
+
+  let frames = list_frame_files(frames_dir)
+    .await
+    .map_err(ToDcmError::P10Error)?;
+
+  let mut context = P10WriteContext::new(Some(write_config));
+  let mut is_pixel_data_written = false;
+
+  let mut process_token = async |token: P10Token| -> Result<(), P10Error> {
+    // Write the pixel data prior to the first root data element that follows
+    // it, or prior to the end if there is no such data element
+    let is_after_pixel_data = match &token {
+      P10Token::DataElementHeader { tag, path, .. }
+      | P10Token::SequenceStart { tag, path, .. } => {
+        path.is_root() && *tag > dictionary::PIXEL_DATA.tag
+      }
+      P10Token::End => true,
+      _ => false,
+    };
+
+    if is_after_pixel_data && !is_pixel_data_written {
+      write_frames(&frames, output_stream, &mut context).await?;
+      is_pixel_data_written = true;
+    }
+
+    dcmfx::p10::write_tokens_to_stream_async(
+      &[token],
+      output_stream,
+      &mut context,
+    )
+    .await?;
+
+    Ok(())
+  };
+
+  data_set
+    .to_p10_token_stream_async(&mut process_token)
+    .await
+    .map_err(ToDcmError::P10Error)
+}
+
+/// Returns the paths and sizes of the files in a directory of frames, sorted by
+/// file name.
+///
+async fn list_frame_files(
+  frames_dir: &Path,
+) -> Result<Vec<(PathBuf, u64)>, P10Error> {
+  let file_error = |e: std::io::Error| P10Error::FileError {
+    when: format!("Listing frames in \"{}\"", frames_dir.display()).into(),
+    details: e.to_string().into(),
+  };
+
+  let mut frames = vec![];
+
+  let mut read_dir =
+    tokio::fs::read_dir(frames_dir).await.map_err(file_error)?;
+  while let Some(entry) = read_dir.next_entry().await.map_err(file_error)? {
+    let metadata = entry.metadata().await.map_err(file_error)?;
+    if metadata.is_file() {
+      frames.push((entry.path(), metadata.len()));
+    }
+  }
+
+  frames.sort();
+
+  Ok(frames)
+}
+
+/// Writes the tokens for encapsulated pixel data containing the given frames.
+/// The Basic Offset Table is populated unless the frames are too large for it
+/// to be able to hold their offsets.
+///
+async fn write_frames(
+  frames: &[(PathBuf, u64)],
+  output_stream: &mut impl IoAsyncWrite,
+  context: &mut P10WriteContext,
+) -> Result<(), P10Error> {
+  // Item lengths must be even, so frames of odd length are padded
+  let item_lengths = frames
+    .iter()
+    .map(|(path, length)| {
+      u32::try_from(length + length % 2).map_err(|_| P10Error::OtherError {
+        error_type: "Frame too large".to_string(),
+        details: format!("Frame file \"{}\" exceeds 4 GiB", path.display())
+          .into(),
+      })
+    })
+    .collect::<Result<Vec<u32>, P10Error>>()?;
+
+  let mut basic_offset_table = vec![];
+  let mut offset = 0u64;
+  for item_length in item_lengths.iter() {
+    basic_offset_table.extend_from_slice(&(offset as u32).to_le_bytes());
+    offset += 8 + u64::from(*item_length);
+
+    if offset > u64::from(u32::MAX) {
+      basic_offset_table.clear();
+      break;
+    }
+  }
+
+  let tokens = [
+    P10Token::SequenceStart {
+      tag: dictionary::PIXEL_DATA.tag,
+      vr: ValueRepresentation::OtherByteString,
+      path: DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+    },
+    P10Token::PixelDataItem {
+      index: 0,
+      length: basic_offset_table.len() as u32,
+    },
+    P10Token::DataElementValueBytes {
+      tag: dictionary::ITEM.tag,
+      vr: ValueRepresentation::OtherByteString,
+      data: basic_offset_table.into(),
+      bytes_remaining: 0,
+    },
+  ];
+
+  dcmfx::p10::write_tokens_to_stream_async(&tokens, output_stream, context)
+    .await?;
+
+  for (index, ((path, length), item_length)) in
+    frames.iter().zip(item_lengths).enumerate()
+  {
+    let file_error = |e: std::io::Error| P10Error::FileError {
+      when: format!("Reading frame \"{}\"", path.display()).into(),
+      details: e.to_string().into(),
+    };
+
+    let mut file = tokio::fs::File::open(path).await.map_err(file_error)?;
+
+    dcmfx::p10::write_tokens_to_stream_async(
+      &[P10Token::PixelDataItem {
+        index: index + 1,
+        length: item_length,
+      }],
+      output_stream,
+      context,
+    )
+    .await?;
+
+    let mut bytes_remaining = item_length;
+    let mut buffer = vec![0u8; 256 * 1024];
+
+    while bytes_remaining > 0 {
+      let chunk_size = (bytes_remaining as usize).min(buffer.len());
+      let chunk = &mut buffer[..chunk_size];
+
+      // Read the chunk from the file, leaving the final padding byte as zero
+      let file_bytes_remaining =
+        bytes_remaining as u64 - (u64::from(item_length) - length);
+      let file_chunk_size = (file_bytes_remaining as usize).min(chunk_size);
+      chunk[file_chunk_size..].fill(0);
+      file
+        .read_exact(&mut chunk[..file_chunk_size])
+        .await
+        .map_err(file_error)?;
+
+      bytes_remaining -= chunk_size as u32;
+
+      dcmfx::p10::write_tokens_to_stream_async(
+        &[P10Token::DataElementValueBytes {
+          tag: dictionary::ITEM.tag,
+          vr: ValueRepresentation::OtherByteString,
+          data: chunk.to_vec().into(),
+          bytes_remaining,
+        }],
+        output_stream,
+        context,
+      )
+      .await?;
+    }
+  }
+
+  dcmfx::p10::write_tokens_to_stream_async(
+    &[P10Token::SequenceDelimiter {
+      tag: dictionary::PIXEL_DATA.tag,
+    }],
+    output_stream,
+    context,
+  )
+  .await?;
+
+  Ok(())
+}
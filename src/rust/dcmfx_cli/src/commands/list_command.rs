@@ -10,7 +10,12 @@ use tokio::{
   sync::{Mutex, mpsc::Sender},
 };
 
-use dcmfx::{core::*, json::*, p10::*};
+use dcmfx::{
+  core::*,
+  json::*,
+  p10::*,
+  pixel_data::{SeriesGeometry, volume_consistency::VolumeConsistencyConfig},
+};
 
 use crate::utils;
 
@@ -20,11 +25,10 @@ pub const ABOUT: &str = "Lists DICOM P10 files in one or more directories";
 pub struct ListArgs {
   #[arg(
     long,
-    help = "The number of concurrent tasks to use. Defaults to the number of \
-      CPU cores.",
-    default_value_t = {num_cpus::get()}
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
   )]
-  concurrency: usize,
+  concurrency: Option<usize>,
 
   #[arg(
     required = true,
@@ -108,6 +112,20 @@ pub struct ListArgs {
     default_value_t = false
   )]
   summarize: bool,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Whether to validate the geometry of each series in the listed \
+      DICOM files. Instances are grouped by their Series Instance UID, and \
+      each series with more than one instance that has an Image Plane Module \
+      is checked for consistent orientation, dimensions, and uniform slice \
+      spacing, and for duplicate and missing slices. The results are printed \
+      to stderr, and the exit code is non-zero if any series is \
+      inconsistent.",
+    default_value_t = false
+  )]
+  validate_geometry: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
@@ -178,6 +196,10 @@ pub async fn run(args: ListArgs) -> Result<(), ()> {
   // output
   let summary = Arc::new(Mutex::new(Summary::new()));
 
+  // Collect the data elements needed to validate the geometry of each series
+  let series_geometry_data_sets =
+    Arc::new(Mutex::new(HashMap::<String, Vec<DataSet>>::new()));
+
   // Start a task to write output lines to stdout
   let (stdout_tx, mut stdout_rx) = tokio::sync::mpsc::channel::<String>(256);
   let stdout_write_task = tokio::spawn(async move {
@@ -205,7 +227,7 @@ pub async fn run(args: ListArgs) -> Result<(), ()> {
   let ignore_patterns_glob_set = glob_set_builder.build().unwrap();
 
   let result = utils::run_tasks(
-    args.concurrency,
+    utils::task_count(args.concurrency),
     futures::stream::iter(file_iterator),
     async |path: PathBuf| {
       // Check file's extension is allowed, if this check was requested
@@ -225,9 +247,15 @@ pub async fn run(args: ListArgs) -> Result<(), ()> {
         return Ok(());
       }
 
-      process_file(&path, &args, summary.clone(), stdout_tx.clone())
-        .await
-        .map_err(|e| (path, e))
+      process_file(
+        &path,
+        &args,
+        summary.clone(),
+        series_geometry_data_sets.clone(),
+        stdout_tx.clone(),
+      )
+      .await
+      .map_err(|e| (path, e))
     },
   )
   .await;
@@ -259,9 +287,58 @@ pub async fn run(args: ListArgs) -> Result<(), ()> {
     summary.lock().await.print_tables();
   }
 
+  if args.validate_geometry {
+    tokio::io::stdout().flush().await.unwrap();
+    return validate_series_geometry(&*series_geometry_data_sets.lock().await);
+  }
+
   Ok(())
 }
 
+/// Validates the geometry of each series and prints the results to stderr.
+/// Returns an error if any series is inconsistent.
+///
+fn validate_series_geometry(
+  series_data_sets: &HashMap<String, Vec<DataSet>>,
+) -> Result<(), ()> {
+  let mut series_instance_uids: Vec<&String> = series_data_sets
+    .iter()
+    .filter(|(_, data_sets)| data_sets.len() > 1)
+    .map(|(uid, _)| uid)
+    .collect();
+  series_instance_uids.sort();
+
+  let mut is_consistent = true;
+
+  for series_instance_uid in series_instance_uids {
+    let data_sets: Vec<&DataSet> =
+      series_data_sets[series_instance_uid].iter().collect();
+
+    eprintln!();
+    eprintln!("Series {series_instance_uid}");
+
+    match SeriesGeometry::from_data_sets(
+      &data_sets,
+      &VolumeConsistencyConfig::default(),
+    ) {
+      Ok(geometry) => {
+        for line in geometry.to_lines() {
+          eprintln!("  {line}");
+        }
+
+        is_consistent &= geometry.is_consistent();
+      }
+
+      Err(e) => {
+        eprintln!("  Issue: {e}");
+        is_consistent = false;
+      }
+    }
+  }
+
+  if is_consistent { Ok(()) } else { Err(()) }
+}
+
 #[allow(clippy::enum_variant_names)]
 enum ProcessFileError {
   IoError(std::io::Error),
@@ -274,6 +351,7 @@ async fn process_file(
   path: &Path,
   args: &ListArgs,
   summary: Arc<Mutex<Summary>>,
+  series_geometry_data_sets: Arc<Mutex<HashMap<String, Vec<DataSet>>>>,
   stdout_tx: Sender<String>,
 ) -> Result<(), ProcessFileError> {
   // Memoized closure that returns the size of the file in bytes. This allows
@@ -291,8 +369,14 @@ async fn process_file(
   };
 
   // Get the line of output for this file
-  let output_line =
-    output_line_for_file(path, args, &summary, &mut file_size).await?;
+  let output_line = output_line_for_file(
+    path,
+    args,
+    &summary,
+    &series_geometry_data_sets,
+    &mut file_size,
+  )
+  .await?;
 
   // If None was returned then it's not a DICOM P10 file
   let Some(output_line) = output_line else {
@@ -314,16 +398,23 @@ async fn output_line_for_file(
   path: &Path,
   args: &ListArgs,
   summary: &Arc<Mutex<Summary>>,
+  series_geometry_data_sets: &Arc<Mutex<HashMap<String, Vec<DataSet>>>>,
   mut file_size: impl FnMut() -> Result<u64, ProcessFileError>,
 ) -> Result<Option<String>, ProcessFileError> {
   let mut tags_to_read = args.selected_data_elements.to_vec();
   tags_to_read.extend(&args.optional_selected_data_elements);
+  let selected_tag_count = tags_to_read.len();
 
   // If summarizing, read extra tags from the DICOM file
   if args.summarize {
     tags_to_read.extend_from_slice(&Summary::SUMMARY_DATA_ELEMENT_TAGS);
   }
 
+  // If validating geometry, read the tags needed to do so
+  if args.validate_geometry {
+    tags_to_read.extend_from_slice(&SeriesGeometry::TAGS);
+  }
+
   if tags_to_read.is_empty() {
     // If this isn't a DICOM P10 file then there's nothing to do
     if !dcmfx::p10::is_valid_file_async(path).await {
@@ -361,14 +452,37 @@ async fn output_line_for_file(
     // If summarizing, add details of this DICOM to the summary
     if args.summarize {
       summary.lock().await.update(path, &data_set, file_size()?);
+    }
 
-      // Remove data elements that were only added for use in the summary
-      for tag in Summary::SUMMARY_DATA_ELEMENT_TAGS {
-        tags_to_read.pop();
-        if !tags_to_read.contains(&tag) {
-          data_set.delete(tag);
+    // If validating geometry, add this DICOM to its series if it has an image
+    // position
+    if args.validate_geometry
+      && data_set.has(dictionary::IMAGE_POSITION_PATIENT.tag)
+      && let Ok(series_instance_uid) =
+        data_set.get_string(dictionary::SERIES_INSTANCE_UID.tag)
+    {
+      let mut geometry_data_set = DataSet::new();
+      for tag in SeriesGeometry::TAGS {
+        if let Ok(value) = data_set.get_value(tag) {
+          geometry_data_set.insert(tag, value.clone());
         }
       }
+
+      series_geometry_data_sets
+        .lock()
+        .await
+        .entry(series_instance_uid.to_string())
+        .or_default()
+        .push(geometry_data_set);
+    }
+
+    // Remove data elements that were only added for use in the summary or
+    // geometry validation
+    let extra_tags = tags_to_read.split_off(selected_tag_count);
+    for tag in extra_tags {
+      if !tags_to_read.contains(&tag) {
+        data_set.delete(tag);
+      }
     }
 
     match args.format {
@@ -1,7 +1,16 @@
+pub mod convert_series_command;
 pub mod dcm_to_json_command;
+pub mod dicomdir_command;
+pub mod export_color_palette_command;
+pub mod export_volume_command;
+pub mod from_image_command;
+pub mod generate_command;
 pub mod get_pixel_data_command;
+pub mod get_waveform_command;
 pub mod json_to_dcm_command;
 pub mod list_command;
 pub mod modify_command;
 pub mod print_command;
 pub mod rewrite_command;
+pub mod stats_command;
+pub mod transcode_sweep_command;
@@ -5,7 +5,10 @@ use clap::Args;
 use dcmfx::{
   core::*,
   p10::*,
-  pixel_data::{transforms::*, *},
+  pixel_data::{
+    encode::HighThroughputJpeg2000EncodeParams,
+    iods::image_pixel_module::ImagePixelModule, transforms::*, *,
+  },
 };
 
 use crate::{
@@ -14,6 +17,7 @@ use crate::{
       PhotometricInterpretationColorArg, PhotometricInterpretationMonochromeArg,
     },
     planar_configuration_arg::PlanarConfigurationArg,
+    progression_order_arg::ProgressionOrderArg,
     transfer_syntax_arg::TransferSyntaxArg,
   },
   utils::{self, InputSource, OutputTarget},
@@ -25,11 +29,10 @@ pub const ABOUT: &str = "Modifies the content of DICOM P10 files";
 pub struct ModifyArgs {
   #[arg(
     long,
-    help = "The number of concurrent tasks to use. Defaults to the number of \
-      CPU cores.",
-    default_value_t = {num_cpus::get()}
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
   )]
-  concurrency: usize,
+  concurrency: Option<usize>,
 
   #[command(flatten)]
   input: crate::args::input_args::P10InputArgs,
@@ -86,6 +89,29 @@ pub struct ModifyArgs {
   )]
   zlib_compression_level: u32,
 
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "The number of threads to use for zlib compression when outputting \
+      to the 'Deflated Explicit VR Little Endian' and 'Deflated Image Frame \
+      Compression' transfer syntaxes. When greater than one, data is split \
+      into chunks that are compressed in parallel, which is faster for large \
+      files but gives slightly larger output.",
+    default_value_t = 1
+  )]
+  deflate_threads: usize,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Check that every data element written conforms to the rules for \
+      its VR, VM, and length, and fail with an error that gives the path of \
+      the first non-conformant data element. Use this when the output must be \
+      standards-conformant.",
+    default_value_t = false
+  )]
+  strict_conformance: bool,
+
   #[arg(
     long,
     help_heading = "Data Set Content",
@@ -117,6 +143,46 @@ pub struct ModifyArgs {
   )]
   delete_private: bool,
 
+  #[arg(
+    long,
+    help_heading = "Data Set Content",
+    help = "Delete all overlay planes, which are the data elements in the \
+      repeating groups 6000-601E.",
+    default_value_t = false
+  )]
+  delete_overlays: bool,
+
+  #[arg(
+    long,
+    help_heading = "Data Set Content",
+    help = "Delete all curves, which are the data elements in the retired \
+      repeating groups 5000-501E.",
+    default_value_t = false
+  )]
+  delete_curves: bool,
+
+  #[arg(
+    long,
+    help_heading = "Data Set Content",
+    help = "Delete the '(0088,0200) Icon Image Sequence' data element.",
+    default_value_t = false
+  )]
+  delete_icon_image: bool,
+
+  #[arg(
+    long,
+    value_name = "IMAGE_FILE",
+    help_heading = "Data Set Content",
+    help = "A PNG or JPEG image to insert as a graphics overlay in group 6000. \
+      Pixels in the image that aren't black become set bits in the overlay, \
+      and the overlay's rows and columns are taken from the image's \
+      dimensions. Any existing data elements in group 6000 are removed.\n\
+      \n\
+      This option can be combined with --delete-overlays to replace all \
+      existing overlay planes with the one being inserted."
+  )]
+  insert_overlay: Option<PathBuf>,
+
   #[arg(
     long,
     help_heading = "Data Set Content",
@@ -129,6 +195,24 @@ pub struct ModifyArgs {
   )]
   anonymize: bool,
 
+  #[arg(
+    long,
+    help_heading = "Data Set Content",
+    help = "Copy the input DICOM P10 data through to the output byte-for-byte, \
+      except for the root-level data elements removed by --delete, \
+      --delete-private, and --anonymize. Data element values, including pixel \
+      data fragments, are never re-encoded, and the File Meta Information is \
+      left unaltered. This is the fastest way to remove data elements from \
+      large numbers of files.\n\
+      \n\
+      Nested data elements inside sequences are not removed. This option \
+      can't be used with --merge-dicom-json, --transfer-syntax, \
+      --delete-overlays, --delete-curves, or --insert-overlay, or on input \
+      that uses the 'Deflated Explicit VR Little Endian' transfer syntax.",
+    default_value_t = false
+  )]
+  copy_through: bool,
+
   #[arg(
     long,
     help_heading = "Data Set Content",
@@ -194,6 +278,99 @@ pub struct ModifyArgs {
   )]
   effort: Option<u8>,
 
+  #[arg(
+    long,
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data to 'JPEG-LS Lossy (Near-Lossless)', \
+      specifies the NEAR parameter, which is the maximum error allowed between \
+      an original and a reconstructed sample value. This overrides the NEAR \
+      parameter derived from --quality. It is limited to the maximum allowed \
+      for the bits stored of the pixel data. A value of zero results in \
+      lossless compression.",
+    value_parser = clap::value_parser!(u8),
+  )]
+  jpeg_ls_near: Option<u8>,
+
+  #[arg(
+    long,
+    num_args = 2..=2,
+    value_parser = clap::value_parser!(u32).range(1..),
+    value_names = ["WIDTH", "HEIGHT"],
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data to High-Throughput JPEG 2000, specifies \
+      the size of the tiles that frames are divided into. Tiling large frames, \
+      such as those in mammography and whole slide imaging, allows regions of \
+      interest to be decoded without decoding the whole frame. By default each \
+      frame is encoded as a single tile."
+  )]
+  htj2k_tile_size: Option<Vec<u32>>,
+
+  #[arg(
+    long,
+    num_args = 2..=2,
+    value_parser = clap::value_parser!(u32),
+    value_names = ["WIDTH", "HEIGHT"],
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data to High-Throughput JPEG 2000, specifies \
+      the codeblock size. The width and height must be powers of two in the \
+      range 4-1024 with a product no greater than 4096.\n\
+      \n\
+      Default value: 64 64"
+  )]
+  htj2k_codeblock_size: Option<Vec<u32>>,
+
+  #[arg(
+    long,
+    value_enum,
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data to High-Throughput JPEG 2000, specifies \
+      the progression order of the codestream.\n\
+      \n\
+      Default value: rpcl"
+  )]
+  htj2k_progression_order: Option<ProgressionOrderArg>,
+
+  #[arg(
+    long,
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data to High-Throughput JPEG 2000, specifies \
+      the number of wavelet decompositions in the range 0-32.\n\
+      \n\
+      Default value: 5",
+    value_parser = clap::value_parser!(u8).range(0..=32),
+  )]
+  htj2k_decompositions: Option<u8>,
+
+  #[arg(
+    long,
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data to an encapsulated transfer syntax, \
+      specifies the maximum size in bytes of the fragments that each encoded \
+      frame is split into. A Basic Offset Table is generated so that the \
+      fragments belonging to each frame can be located. This is useful when \
+      the receiver of the DICOM P10 files requires fragments be no larger \
+      than a fixed size. The size is rounded down to a multiple of two.\n\
+      \n\
+      By default each encoded frame is written as a single fragment.",
+    value_parser = clap::value_parser!(u32).range(2..),
+  )]
+  max_fragment_size: Option<u32>,
+
+  #[arg(
+    long,
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data, specifies the maximum number of MiB \
+      of encoded frames to buffer in memory. Encoded frames are buffered when \
+      --max-fragment-size is used, because the Basic Offset Table can't be \
+      written until every frame has been encoded. Frames buffered beyond this \
+      limit are spilled to a temporary file and re-read when the output pixel \
+      data is written. This allows huge instances to be transcoded on \
+      machines with limited memory.\n\
+      \n\
+      By default all buffering is done in memory."
+  )]
+  frame_buffer_memory_limit: Option<usize>,
+
   #[arg(
     long,
     help_heading = "Transcoding",
@@ -277,6 +454,21 @@ pub struct ModifyArgs {
   )]
   crop: Option<CropRect>,
 
+  #[arg(
+    long,
+    help_heading = "Transcoding",
+    help = "When transcoding pixel data using --transfer-syntax, prints an \
+      estimate of the size of the transcoded pixel data instead of writing \
+      output files. Only the Image Pixel Module and Number of Frames are read \
+      from input files, so this is fast even for large files.\n\
+      \n\
+      Estimates are exact for uncompressed transfer syntaxes, bounded for \
+      'RLE Lossless' and 'Deflated Image Frame Compression', and approximate \
+      for all other transfer syntaxes.",
+    default_value_t = false
+  )]
+  estimate_only: bool,
+
   #[command(flatten)]
   decoder: crate::args::decoder_args::DecoderArgs,
 }
@@ -287,10 +479,62 @@ impl ModifyArgs {
 
     config.set_quality(self.quality.unwrap_or(90));
     config.set_effort(self.effort.unwrap_or(7));
+    config.set_jpeg_ls_near(self.jpeg_ls_near);
+
+    let mut htj2k_params = HighThroughputJpeg2000EncodeParams::default();
+    if let Some(tile_size) = &self.htj2k_tile_size {
+      htj2k_params.tile_size = Some((tile_size[0], tile_size[1]));
+    }
+    if let Some(codeblock_size) = &self.htj2k_codeblock_size {
+      htj2k_params.codeblock_size = (codeblock_size[0], codeblock_size[1]);
+    }
+    if let Some(progression_order) = self.htj2k_progression_order {
+      htj2k_params.progression_order = progression_order.progression_order();
+    }
+    if let Some(decompositions) = self.htj2k_decompositions {
+      htj2k_params.decompositions = decompositions;
+    }
+    config.set_high_throughput_jpeg_2000_params(htj2k_params);
+    config.set_max_fragment_size(self.max_fragment_size);
+    config.set_frame_buffer_memory_limit(
+      self
+        .frame_buffer_memory_limit
+        .map(|limit| limit.saturating_mul(1024 * 1024)),
+    );
     config.set_zlib_compression_level(self.zlib_compression_level);
+    config.set_deflate_thread_count(self.deflate_threads);
+    config.set_thread_count(utils::codec_thread_count(self.concurrency));
 
     config
   }
+
+  fn image_data_functions(
+    &self,
+    output_transfer_syntax: &'static TransferSyntax,
+  ) -> TranscodeImageDataFunctions {
+    let photometric_interpretation_monochrome_arg =
+      self.photometric_interpretation_monochrome;
+    let photometric_interpretation_color_arg =
+      self.photometric_interpretation_color;
+
+    TranscodeImageDataFunctions::standard_behavior(
+      output_transfer_syntax,
+      Rc::new(move |image_pixel_module| {
+        photometric_interpretation_monochrome_arg.and_then(|arg| {
+          arg.as_photometric_interpretation(
+            image_pixel_module.pixel_representation(),
+          )
+        })
+      }),
+      Rc::new(move |_image_pixel_module| {
+        photometric_interpretation_color_arg
+          .and_then(|arg| arg.as_photometric_interpretation())
+      }),
+      self.planar_configuration.map(|a| a.into()),
+      self.crop,
+      self.quality.is_some(),
+    )
+  }
 }
 
 enum ModifyCommandError {
@@ -299,10 +543,11 @@ enum ModifyCommandError {
 }
 
 pub async fn run(args: ModifyArgs) -> Result<(), ()> {
-  if (args.output_filename.is_some() as u8
-    + args.output_directory.is_some() as u8
-    + args.in_place as u8)
-    != 1
+  if !args.estimate_only
+    && (args.output_filename.is_some() as u8
+      + args.output_directory.is_some() as u8
+      + args.in_place as u8)
+      != 1
   {
     eprintln!(
       "Error: Exactly one of --output-filename, --output-directory, or \
@@ -351,6 +596,63 @@ pub async fn run(args: ModifyArgs) -> Result<(), ()> {
       );
       return Err(());
     }
+
+    if args.estimate_only {
+      eprintln!(
+        "Error: The --estimate-only option is only valid when \
+         --transfer-syntax is specified"
+      );
+      return Err(());
+    }
+  }
+
+  if args.estimate_only {
+    return estimate(args).await;
+  }
+
+  if args.copy_through {
+    if args.transfer_syntax.is_some() {
+      eprintln!(
+        "Error: The --copy-through option can't be used with --transfer-syntax"
+      );
+      return Err(());
+    }
+
+    if args.merge_dicom_json.is_some() {
+      eprintln!(
+        "Error: The --copy-through option can't be used with \
+         --merge-dicom-json"
+      );
+      return Err(());
+    }
+
+    // Removing whole groups isn't possible when copying through because any
+    // group length data elements for those groups would be left behind
+    if args.delete_overlays
+      || args.delete_curves
+      || args.insert_overlay.is_some()
+    {
+      eprintln!(
+        "Error: The --copy-through option can't be used with \
+         --delete-overlays, --delete-curves, or --insert-overlay"
+      );
+      return Err(());
+    }
+  }
+
+  // Gather the data elements to insert into each output data set
+  let mut insert_data_set = args.merge_dicom_json.clone();
+  if let Some(path) = &args.insert_overlay {
+    match load_overlay_data_set(path) {
+      Ok(overlay_data_set) => insert_data_set
+        .get_or_insert_with(DataSet::new)
+        .merge(overlay_data_set),
+
+      Err(e) => {
+        eprintln!("Error: Invalid overlay image '{}': {}", path.display(), e);
+        return Err(());
+      }
+    }
   }
 
   crate::validate_output_args(
@@ -364,7 +666,7 @@ pub async fn run(args: ModifyArgs) -> Result<(), ()> {
   let input_sources = args.input.base.input_sources().await;
 
   let result = utils::run_tasks(
-    args.concurrency,
+    utils::task_count(args.concurrency),
     input_sources,
     async |input_source: InputSource| {
       if args.in_place
@@ -389,7 +691,14 @@ pub async fn run(args: ModifyArgs) -> Result<(), ()> {
         .await
       };
 
-      match modify_input_source(&input_source, output_target, &args).await {
+      match modify_input_source(
+        &input_source,
+        output_target,
+        insert_data_set.as_ref(),
+        &args,
+      )
+      .await
+      {
         Ok(()) => Ok(()),
 
         Err(ModifyCommandError::P10Error(P10Error::DicmPrefixNotPresent))
@@ -423,9 +732,164 @@ pub async fn run(args: ModifyArgs) -> Result<(), ()> {
   }
 }
 
+/// Prints an estimate of the size of the transcoded pixel data for each input,
+/// without writing any output.
+///
+async fn estimate(args: ModifyArgs) -> Result<(), ()> {
+  let input_sources = args.input.base.input_sources().await;
+
+  let result = utils::run_tasks(
+    utils::task_count(args.concurrency),
+    input_sources,
+    async |input_source: InputSource| match estimate_input_source(
+      &input_source,
+      &args,
+    )
+    .await
+    {
+      Ok(()) => Ok(()),
+
+      Err(ModifyCommandError::P10Error(P10Error::DicmPrefixNotPresent))
+        if args.input.ignore_invalid =>
+      {
+        Ok(())
+      }
+
+      Err(e) => {
+        let task_description = format!("estimating \"{input_source}\"");
+
+        Err(match e {
+          ModifyCommandError::P10Error(e) => e.to_lines(&task_description),
+          ModifyCommandError::P10PixelDataTranscodeTransformError(e) => {
+            e.to_lines(&task_description)
+          }
+        })
+      }
+    },
+  )
+  .await;
+
+  match result {
+    Ok(()) => Ok(()),
+
+    Err(lines) => {
+      error::print_error_lines(&lines);
+      Err(())
+    }
+  }
+}
+
+async fn estimate_input_source(
+  input_source: &InputSource,
+  args: &ModifyArgs,
+) -> Result<(), ModifyCommandError> {
+  let mut input_stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(ModifyCommandError::P10Error)?;
+
+  // Read only the data elements needed to determine the output Image Pixel
+  // Module and the number of frames
+  let tags: Vec<DataElementTag> = [
+    dictionary::TRANSFER_SYNTAX_UID.tag,
+    dictionary::NUMBER_OF_FRAMES.tag,
+    dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+    dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+    dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+    dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::SEGMENTED_RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::SEGMENTED_GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::SEGMENTED_BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+  ]
+  .into_iter()
+  .chain(ImagePixelModule::TAGS)
+  .collect();
+
+  let read_config = args
+    .input
+    .p10_read_config()
+    .require_dicm_prefix(args.input.ignore_invalid);
+
+  let data_set = dcmfx::p10::read_stream_partial_async(
+    &mut input_stream,
+    &tags,
+    Some(read_config),
+  )
+  .await
+  .map_err(ModifyCommandError::P10Error)?;
+
+  if !data_set.has(dictionary::ROWS.tag) {
+    println!("\"{input_source}\" has no pixel data to estimate");
+    return Ok(());
+  }
+
+  let map_data_error = |e| {
+    ModifyCommandError::P10PixelDataTranscodeTransformError(
+      P10PixelDataTranscodeTransformError::DataError(e),
+    )
+  };
+
+  let input_transfer_syntax = data_set
+    .get_transfer_syntax()
+    .unwrap_or(&transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN);
+  let output_transfer_syntax = args
+    .transfer_syntax
+    .and_then(|arg| arg.as_transfer_syntax())
+    .unwrap_or(input_transfer_syntax);
+
+  let number_of_frames = data_set
+    .get_int_with_default::<usize>(dictionary::NUMBER_OF_FRAMES.tag, 1)
+    .map_err(map_data_error)?;
+
+  // Determine the Image Pixel Module of the images that will be encoded, in
+  // the same way as happens when transcoding
+  let mut image_pixel_module =
+    ImagePixelModule::from_data_set(&data_set).map_err(map_data_error)?;
+
+  let decoded_photometric_interpretation =
+    decode::decode_photometric_interpretation(
+      image_pixel_module.photometric_interpretation(),
+      input_transfer_syntax,
+    )
+    .map_err(|e| {
+      ModifyCommandError::P10PixelDataTranscodeTransformError(
+        P10PixelDataTranscodeTransformError::PixelDataDecodeError(e),
+      )
+    })?
+    .clone();
+  image_pixel_module
+    .set_photometric_interpretation(decoded_photometric_interpretation);
+
+  let image_data_functions = args.image_data_functions(output_transfer_syntax);
+  (image_data_functions.process_image_pixel_module)(&mut image_pixel_module)
+    .map_err(ModifyCommandError::P10PixelDataTranscodeTransformError)?;
+
+  let estimate = encode::estimate_encoded_size(
+    &image_pixel_module,
+    number_of_frames,
+    output_transfer_syntax,
+    &args.pixel_data_encode_config(),
+  )
+  .map_err(|e| {
+    ModifyCommandError::P10PixelDataTranscodeTransformError(
+      P10PixelDataTranscodeTransformError::PixelDataEncodeError(e),
+    )
+  })?;
+
+  println!(
+    "Estimated size of pixel data for \"{input_source}\" as '{}': {estimate}",
+    output_transfer_syntax.name
+  );
+
+  Ok(())
+}
+
 async fn modify_input_source(
   input_source: &InputSource,
   output_target: OutputTarget,
+  insert_data_set: Option<&DataSet>,
   args: &ModifyArgs,
 ) -> Result<(), ModifyCommandError> {
   if args.in_place {
@@ -444,20 +908,48 @@ async fn modify_input_source(
     );
   }
 
-  // Create an insert transform for merging in another data set, if needed
-  let insert_transform = args
-    .merge_dicom_json
-    .as_ref()
-    .map(|merge_dicom_json| P10InsertTransform::new(merge_dicom_json.clone()));
+  // Create an insert transform for merging in another data set and inserting
+  // an overlay, if needed
+  let insert_transform = insert_data_set
+    .map(|insert_data_set| P10InsertTransform::new(insert_data_set.clone()));
 
   // Create a filter transform for anonymization and tag deletion, if needed
   let deletions = args.deletions.clone();
   let delete_private = args.delete_private;
+  let delete_overlays = args.delete_overlays;
+  let delete_curves = args.delete_curves;
+  let delete_icon_image = args.delete_icon_image;
   let anonymize = args.anonymize;
-  let filter_transform = if anonymize || !deletions.is_empty() || delete_private
+  let copy_through = args.copy_through;
+
+  // When inserting an overlay, the inserted data elements are the only ones
+  // kept in its group so that the overlay is replaced as a whole
+  let inserted_overlay_tags = match (&args.insert_overlay, insert_data_set) {
+    (Some(_), Some(insert_data_set)) => insert_data_set
+      .tags()
+      .into_iter()
+      .filter(|tag| tag.group == INSERTED_OVERLAY_GROUP)
+      .collect(),
+    _ => vec![],
+  };
+
+  let filter_transform = if anonymize
+    || !deletions.is_empty()
+    || delete_private
+    || delete_overlays
+    || delete_curves
+    || delete_icon_image
+    || !inserted_overlay_tags.is_empty()
   {
     Some(P10FilterTransform::new(Box::new(
-      move |tag, vr, _length, _path| {
+      move |tag, vr, _length, path| {
+        // Copy-through only removes root-level data elements because removing
+        // nested ones would invalidate the lengths of defined-length sequences
+        // and items
+        if copy_through && !path.is_root() {
+          return true;
+        }
+
         if deletions.contains(&tag) {
           return false;
         }
@@ -466,6 +958,27 @@ async fn modify_input_source(
           return false;
         }
 
+        // Overlays and curves are only removed from the root data set, which
+        // is the only place they're defined
+        if path.is_root() {
+          let is_overlay = is_repeating_group(tag.group, 0x6000);
+          if is_overlay && inserted_overlay_tags.contains(&tag) {
+            return true;
+          }
+
+          if (delete_overlays && is_overlay)
+            || (delete_curves && is_repeating_group(tag.group, 0x5000))
+            || (!inserted_overlay_tags.is_empty()
+              && tag.group == INSERTED_OVERLAY_GROUP)
+          {
+            return false;
+          }
+        }
+
+        if delete_icon_image && tag == dictionary::ICON_IMAGE_SEQUENCE.tag {
+          return false;
+        }
+
         if anonymize && !dcmfx::anonymize::filter_tag(tag, vr) {
           return false;
         }
@@ -480,7 +993,9 @@ async fn modify_input_source(
   // Setup write config
   let write_config = P10WriteConfig::default()
     .implementation_version_name(args.implementation_version_name.clone())
-    .zlib_compression_level(args.zlib_compression_level);
+    .zlib_compression_level(args.zlib_compression_level)
+    .deflate_thread_count(args.deflate_threads)
+    .strict_conformance(args.strict_conformance);
 
   let mut input_stream = input_source
     .open_read_stream()
@@ -496,15 +1011,25 @@ async fn modify_input_source(
   // Get exclusive access to the output stream
   let mut output_stream = output_stream_handle.lock().await;
 
-  streaming_rewrite(
-    &mut input_stream,
-    &mut *output_stream,
-    write_config,
-    insert_transform,
-    filter_transform,
-    args,
-  )
-  .await?;
+  if args.copy_through {
+    copy_through_rewrite(
+      &mut input_stream,
+      &mut *output_stream,
+      filter_transform,
+      args,
+    )
+    .await?;
+  } else {
+    streaming_rewrite(
+      &mut input_stream,
+      &mut *output_stream,
+      write_config,
+      insert_transform,
+      filter_transform,
+      args,
+    )
+    .await?;
+  }
 
   output_target
     .commit(&mut output_stream)
@@ -561,34 +1086,17 @@ async fn streaming_rewrite<I: IoAsyncRead, O: IoAsyncWrite>(
           .as_transfer_syntax()
           .unwrap_or(input_transfer_syntax);
 
-        let photometric_interpretation_monochrome_arg =
-          args.photometric_interpretation_monochrome;
-        let photometric_interpretation_color_arg =
-          args.photometric_interpretation_color;
-
         let image_data_functions =
-          TranscodeImageDataFunctions::standard_behavior(
-            output_transfer_syntax,
-            Rc::new(move |image_pixel_module| {
-              photometric_interpretation_monochrome_arg.and_then(|arg| {
-                arg.as_photometric_interpretation(
-                  image_pixel_module.pixel_representation(),
-                )
-              })
-            }),
-            Rc::new(move |_image_pixel_module| {
-              photometric_interpretation_color_arg
-                .and_then(|arg| arg.as_photometric_interpretation())
-            }),
-            args.planar_configuration.map(|a| a.into()),
-            args.crop,
-            args.quality.is_some(),
-          );
+          args.image_data_functions(output_transfer_syntax);
 
         pixel_data_transcode_transform =
           Some(P10PixelDataTranscodeTransform::new(
             output_transfer_syntax,
-            args.decoder.pixel_data_decode_config(),
+            args
+              .decoder
+              .pixel_data_decode_config(utils::codec_thread_count(
+                args.concurrency,
+              )),
             args.pixel_data_encode_config(),
             Some(image_data_functions),
           ));
@@ -659,53 +1167,325 @@ async fn streaming_rewrite<I: IoAsyncRead, O: IoAsyncWrite>(
       tokens = new_tokens
     }
 
-    // Pass tokens through the insert transform if one is specified
-    let tokens = if let Some(insert_transform) = insert_transform.as_mut() {
-      let mut new_tokens = vec![];
+    // Write the tokens to the output stream, followed by any tokens for
+    // transcoded frames that were held back by the pixel data transcode
+    // transform in order to keep its memory usage bounded
+    let mut next_tokens = tokens;
+    loop {
+      let tokens = core::mem::take(&mut next_tokens);
+
+      // Pass tokens through the insert transform if one is specified
+      let tokens = if let Some(insert_transform) = insert_transform.as_mut() {
+        let mut new_tokens = vec![];
+
+        for token in tokens.iter() {
+          new_tokens.extend(
+            insert_transform
+              .add_token(token)
+              .map_err(ModifyCommandError::P10Error)?,
+          );
+        }
 
-      for token in tokens.iter() {
-        new_tokens.extend(
-          insert_transform
-            .add_token(token)
-            .map_err(ModifyCommandError::P10Error)?,
-        );
+        Ok(new_tokens)
+      } else {
+        Ok(tokens)
+      }?;
+
+      // Pass tokens through the filter transform if one is specified
+      let tokens = if let Some(filter_transform) = filter_transform.as_mut() {
+        tokens.into_iter().try_fold(vec![], |mut acc, token| {
+          if filter_transform
+            .add_token(&token)
+            .map_err(ModifyCommandError::P10Error)?
+          {
+            acc.push(token);
+          }
+
+          Ok(acc)
+        })
+      } else {
+        Ok(tokens)
+      }?;
+
+      // Write tokens to the output stream
+      let ended = dcmfx::p10::write_tokens_to_stream_async(
+        &tokens,
+        &mut *output_stream,
+        &mut p10_write_context,
+      )
+      .await
+      .map_err(ModifyCommandError::P10Error)?;
+
+      // Stop when the end token is received
+      if ended {
+        return Ok(());
       }
 
-      Ok(new_tokens)
-    } else {
-      Ok(tokens)
-    }?;
-
-    // Pass tokens through the filter transform if one is specified
-    let tokens = if let Some(filter_transform) = filter_transform.as_mut() {
-      tokens.into_iter().try_fold(vec![], |mut acc, token| {
-        if filter_transform
-          .add_token(&token)
-          .map_err(ModifyCommandError::P10Error)?
-        {
-          acc.push(token);
+      next_tokens = match pixel_data_transcode_transform.as_mut() {
+        Some(transcode_transform) => transcode_transform
+          .read_pending_tokens()
+          .map_err(ModifyCommandError::P10PixelDataTranscodeTransformError)?,
+        None => vec![],
+      };
+
+      if next_tokens.is_empty() {
+        break;
+      }
+    }
+  }
+}
+
+/// Rewrites by copying the raw bytes of the DICOM P10 straight to the output
+/// file, omitting only the bytes of root-level data elements removed by the
+/// filter transform. The P10 tokens are read only to locate the data elements
+/// in the input bytes, and are never themselves written out.
+///
+async fn copy_through_rewrite<I: IoAsyncRead, O: IoAsyncWrite>(
+  input_stream: &mut I,
+  output_stream: &mut O,
+  mut filter_transform: Option<P10FilterTransform>,
+  args: &ModifyArgs,
+) -> Result<(), ModifyCommandError> {
+  use tokio::io::AsyncReadExt;
+
+  let read_config = args
+    .input
+    .p10_read_config()
+    .max_token_size(256 * 1024)
+    .require_dicm_prefix(args.input.ignore_invalid);
+
+  let mut p10_read_context = P10ReadContext::new(Some(read_config));
+
+  // Input bytes that have been passed to the read context but not yet either
+  // written to the output or discarded, and the input offset they start at
+  let mut pending_bytes = std::collections::VecDeque::<u8>::new();
+  let mut pending_bytes_offset = 0u64;
+
+  // The input offset of the root-level data element currently being copied or
+  // discarded, and whether it is being discarded
+  let mut data_element_offset = 0u64;
+  let mut is_discarding = false;
+
+  let mut buffer = vec![0u8; 256 * 1024];
+
+  loop {
+    let tokens = match p10_read_context.read_tokens() {
+      Ok(tokens) => tokens,
+
+      // If the read context needs more data then read bytes from the stream,
+      // write them to the read context, and try again
+      Err(P10Error::DataRequired { .. }) => {
+        let read_bytes_count =
+          input_stream.read(&mut buffer).await.map_err(|e| {
+            ModifyCommandError::P10Error(P10Error::FileError {
+              when: "Reading from stream".into(),
+              details: e.to_string().into(),
+            })
+          })?;
+
+        let chunk = &buffer[..read_bytes_count];
+        pending_bytes.extend(chunk);
+
+        p10_read_context
+          .write_bytes(chunk.to_vec().into(), read_bytes_count == 0)
+          .map_err(ModifyCommandError::P10Error)?;
+
+        continue;
+      }
+
+      Err(e) => return Err(ModifyCommandError::P10Error(e)),
+    };
+
+    let mut is_ended = false;
+
+    for token in tokens.iter() {
+      if let P10Token::FileMetaInformation { .. } = token
+        && p10_read_context.transfer_syntax().is_deflated
+      {
+        return Err(ModifyCommandError::P10Error(
+          P10Error::TransferSyntaxNotSupported {
+            transfer_syntax_uid: p10_read_context
+              .transfer_syntax()
+              .uid
+              .to_string(),
+          },
+        ));
+      }
+
+      // Determine if this token starts a root-level data element that is
+      // present in the input bytes, i.e. that wasn't inserted by the read
+      // context
+      let (header_tag, header_offset) =
+        p10_read_context.last_data_element_header();
+      let is_root_data_element_start = match token {
+        P10Token::DataElementHeader { tag, path, .. }
+        | P10Token::SequenceStart { tag, path, .. } => {
+          path.is_root() && *tag == header_tag
         }
+        _ => false,
+      };
 
-        Ok(acc)
-      })
-    } else {
-      Ok(tokens)
-    }?;
+      let is_included = match filter_transform.as_mut() {
+        Some(filter_transform) => filter_transform
+          .add_token(token)
+          .map_err(ModifyCommandError::P10Error)?,
+        None => true,
+      };
 
-    // Write tokens to the output stream
-    let ended = dcmfx::p10::write_tokens_to_stream_async(
-      &tokens,
-      &mut *output_stream,
-      &mut p10_write_context,
+      // When a new root-level data element starts, all preceding bytes belong
+      // to the previous data element, and so are finished with
+      if is_root_data_element_start {
+        data_element_offset = header_offset;
+
+        flush_copy_through_bytes(
+          &mut pending_bytes,
+          &mut pending_bytes_offset,
+          data_element_offset,
+          is_discarding,
+          output_stream,
+        )
+        .await?;
+
+        is_discarding = !is_included;
+      }
+
+      if *token == P10Token::End {
+        is_ended = true;
+      }
+    }
+
+    // Finish with as many bytes as possible. If the read context has started
+    // reading a data element that hasn't yet emitted its header token then
+    // its bytes can't be finished with until it's known whether it is to be
+    // discarded.
+    let (_, header_offset) = p10_read_context.last_data_element_header();
+    let flush_offset = if is_ended || header_offset == data_element_offset {
+      p10_read_context.bytes_read()
+    } else {
+      header_offset
+    };
+
+    flush_copy_through_bytes(
+      &mut pending_bytes,
+      &mut pending_bytes_offset,
+      flush_offset,
+      is_discarding,
+      output_stream,
     )
-    .await
-    .map_err(ModifyCommandError::P10Error)?;
+    .await?;
 
-    // Stop when the end token is received
-    if ended {
+    if is_ended {
       break;
     }
   }
 
   Ok(())
 }
+
+/// Writes or discards the pending bytes for a copy-through rewrite that come
+/// before the given input offset.
+///
+async fn flush_copy_through_bytes<O: IoAsyncWrite>(
+  pending_bytes: &mut std::collections::VecDeque<u8>,
+  pending_bytes_offset: &mut u64,
+  offset: u64,
+  is_discarding: bool,
+  output_stream: &mut O,
+) -> Result<(), ModifyCommandError> {
+  use tokio::io::AsyncWriteExt;
+
+  let byte_count = offset.saturating_sub(*pending_bytes_offset) as usize;
+  if byte_count == 0 {
+    return Ok(());
+  }
+
+  if !is_discarding {
+    let (front, back) = pending_bytes.as_slices();
+    let front_count = front.len().min(byte_count);
+
+    for bytes in [&front[..front_count], &back[..byte_count - front_count]] {
+      output_stream.write_all(bytes).await.map_err(|e| {
+        ModifyCommandError::P10Error(P10Error::FileError {
+          when: "Writing to output stream".into(),
+          details: e.to_string().into(),
+        })
+      })?;
+    }
+  }
+
+  pending_bytes.drain(..byte_count);
+  *pending_bytes_offset = offset;
+
+  Ok(())
+}
+
+/// The overlay group that `--insert-overlay` inserts into.
+///
+const INSERTED_OVERLAY_GROUP: u16 = 0x6000;
+
+/// Returns whether a tag group is one of the 16 repeating groups that start at
+/// the given base group, i.e. the even groups in the range `base..=base+0x1E`.
+/// Ref: PS3.5 7.6.
+///
+fn is_repeating_group(group: u16, base_group: u16) -> bool {
+  (base_group..=base_group + 0x1E).contains(&group) && group & 1 == 0
+}
+
+/// Loads an image file and converts it into the data elements for a graphics
+/// overlay in group 6000. Pixels that aren't black become set bits in the
+/// overlay's data.
+///
+fn load_overlay_data_set(path: &PathBuf) -> Result<DataSet, String> {
+  let image = image::open(path).map_err(|e| e.to_string())?.to_luma8();
+
+  let (Ok(columns), Ok(rows)) =
+    (u16::try_from(image.width()), u16::try_from(image.height()))
+  else {
+    return Err("Image dimensions exceed 65535 pixels".to_string());
+  };
+
+  // Pack the overlay bits, with the first pixel in the least significant bit
+  // of the first byte. Ref: PS3.5 8.1.2.
+  let mut overlay_data = vec![0u8; image.as_raw().len().div_ceil(8)];
+  for (i, value) in image.as_raw().iter().enumerate() {
+    if *value != 0 {
+      overlay_data[i / 8] |= 1 << (i % 8);
+    }
+  }
+
+  let tag =
+    |item: &dictionary::Item| item.tag.with_group(INSERTED_OVERLAY_GROUP);
+
+  let mut data_set = DataSet::new();
+
+  data_set.insert(
+    tag(&dictionary::OVERLAY_ROWS),
+    DataElementValue::new_unsigned_short(&[rows]).unwrap(),
+  );
+  data_set.insert(
+    tag(&dictionary::OVERLAY_COLUMNS),
+    DataElementValue::new_unsigned_short(&[columns]).unwrap(),
+  );
+  data_set.insert(
+    tag(&dictionary::OVERLAY_TYPE),
+    DataElementValue::new_code_string(&["G"]).unwrap(),
+  );
+  data_set.insert(
+    tag(&dictionary::OVERLAY_ORIGIN),
+    DataElementValue::new_signed_short(&[1, 1]).unwrap(),
+  );
+  data_set.insert(
+    tag(&dictionary::OVERLAY_BITS_ALLOCATED),
+    DataElementValue::new_unsigned_short(&[1]).unwrap(),
+  );
+  data_set.insert(
+    tag(&dictionary::OVERLAY_BIT_POSITION),
+    DataElementValue::new_unsigned_short(&[0]).unwrap(),
+  );
+  data_set.insert(
+    tag(&dictionary::OVERLAY_DATA),
+    DataElementValue::new_other_byte_string(overlay_data).unwrap(),
+  );
+
+  Ok(data_set)
+}
@@ -3,7 +3,7 @@ use std::io::Write;
 use clap::Args;
 use futures::StreamExt;
 
-use dcmfx::{core::*, p10::*};
+use dcmfx::{core::iods::sr::SrDocumentContentModule, core::*, p10::*};
 
 use crate::utils::InputSource;
 
@@ -32,6 +32,16 @@ pub struct PrintArgs {
       colored output."
   )]
   styled: Option<bool>,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Print the content tree of DICOM Structured Report (SR) documents \
+      rather than their data elements. Each content item is printed on its own \
+      line, indented beneath its parent.",
+    default_value_t = false
+  )]
+  sr_tree: bool,
 }
 
 pub async fn run(args: PrintArgs) -> Result<(), ()> {
@@ -55,11 +65,20 @@ pub async fn run(args: PrintArgs) -> Result<(), ()> {
     .require_dicm_prefix(args.input.ignore_invalid);
 
   while let Some(input_source) = input_sources.next().await {
-    match print_input_source(&input_source, &read_config, &print_options).await
-    {
+    let result = if args.sr_tree {
+      print_sr_tree(&input_source, &read_config).await
+    } else {
+      print_input_source(&input_source, &read_config, &print_options)
+        .await
+        .map_err(P10CustomTypeTransformError::P10Error)
+    };
+
+    match result {
       Ok(()) => (),
 
-      Err(P10Error::DicmPrefixNotPresent) if args.input.ignore_invalid => (),
+      Err(P10CustomTypeTransformError::P10Error(
+        P10Error::DicmPrefixNotPresent,
+      )) if args.input.ignore_invalid => (),
 
       Err(e) => {
         e.print(&format!("printing \"{input_source}\""));
@@ -100,8 +119,8 @@ async fn print_input_source(
 
           std::io::stdout().write(s.as_bytes()).map_err(|e| {
             P10Error::FileError {
-              when: "Writing to stdout".to_string(),
-              details: e.to_string(),
+              when: "Writing to stdout".into(),
+              details: e.to_string().into(),
             }
           })?;
         }
@@ -109,3 +128,45 @@ async fn print_input_source(
     }
   }
 }
+
+async fn print_sr_tree(
+  input_source: &InputSource,
+  read_config: &P10ReadConfig,
+) -> Result<(), P10CustomTypeTransformError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(P10CustomTypeTransformError::P10Error)?;
+  let mut context = P10ReadContext::new(Some(*read_config));
+  let mut sr_transform =
+    P10CustomTypeTransform::<SrDocumentContentModule>::new_for_iod_module();
+
+  while sr_transform.get_output().is_none() {
+    let tokens = dcmfx::p10::read_tokens_from_stream_async(
+      &mut stream,
+      &mut context,
+      None,
+    )
+    .await
+    .map_err(P10CustomTypeTransformError::P10Error)?;
+
+    for token in tokens.iter() {
+      sr_transform.add_token(token)?;
+    }
+  }
+
+  if let Some(sr_document_content_module) = sr_transform.get_output() {
+    let mut stdout = std::io::stdout();
+
+    for line in sr_document_content_module.root.to_lines() {
+      writeln!(stdout, "{line}").map_err(|e| {
+        P10CustomTypeTransformError::P10Error(P10Error::FileError {
+          when: "Writing to stdout".into(),
+          details: e.to_string().into(),
+        })
+      })?;
+    }
+  }
+
+  Ok(())
+}
@@ -27,11 +27,10 @@ pub const LONG_ABOUT: &str = "Rewrites DICOM P10 files in order to standardize \
 pub struct RewriteArgs {
   #[arg(
     long,
-    help = "The number of concurrent tasks to use. Defaults to the number of \
-      CPU cores.",
-    default_value_t = {num_cpus::get()}
+    help = "The number of concurrent tasks to use. Defaults to the value of \
+      --threads if specified, otherwise the number of CPU cores."
   )]
-  concurrency: usize,
+  concurrency: Option<usize>,
 
   #[command(flatten)]
   input: crate::args::input_args::P10InputArgs,
@@ -121,7 +120,7 @@ pub async fn run(args: RewriteArgs) -> Result<(), ()> {
   let input_sources = args.input.base.input_sources().await;
 
   let result = utils::run_tasks(
-    args.concurrency,
+    utils::task_count(args.concurrency),
     input_sources,
     async |input_source: InputSource| {
       if args.in_place
@@ -210,10 +209,14 @@ async fn rewrite_input_source(
     {
       Ok(ds) => ds,
 
-      Err((
-        P10Error::DataInvalid { .. } | P10Error::DataEndedUnexpectedly { .. },
-        mut data_set_builder,
-      )) if args.partial_rewrite_on_invalid_data => {
+      Err((e, mut data_set_builder))
+        if args.partial_rewrite_on_invalid_data
+          && matches!(
+            e.without_context(),
+            P10Error::DataInvalid { .. }
+              | P10Error::DataEndedUnexpectedly { .. }
+          ) =>
+      {
         data_set_builder.force_end();
         data_set_builder.final_data_set().unwrap()
       }
@@ -0,0 +1,466 @@
+use std::sync::Arc;
+
+use clap::Args;
+use futures::StreamExt;
+use tokio::sync::Mutex;
+
+use dcmfx::{
+  core::*,
+  p10::*,
+  pixel_data::{
+    PixelDataDecodeError, PixelDataFrame, PixelDataRenderer,
+    duplicate_frames::{self, DuplicateFrameFinder},
+    transforms::{P10PixelDataFrameTransform, P10PixelDataFrameTransformError},
+  },
+};
+
+use crate::utils::{self, InputSource};
+
+pub const ABOUT: &str = "Prints statistics on the pixel data in DICOM P10 \
+  files";
+
+#[derive(Args)]
+pub struct StatsArgs {
+  #[command(flatten)]
+  input: crate::args::input_args::P10InputArgs,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Whether to print hashes of each frame of pixel data. These are a \
+      SHA-256 hash of the frame's stored values, which matches only when the \
+      stored values are identical, and dHash and pHash perceptual hashes, \
+      which match frames that look the same. These hashes don't depend on the \
+      transfer syntax, so can be used to detect duplicate frames that have \
+      been encoded differently.",
+    default_value_t = false
+  )]
+  image_hash: bool,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Instead of printing statistics for each input file, finds frames \
+      of pixel data that are duplicated across the input files, or within a \
+      single input file, and prints each group of duplicate frames. Frames \
+      are compared using a SHA-256 hash of their decoded stored values, so \
+      duplicates are found even if they have been encoded using different \
+      transfer syntaxes.",
+    default_value_t = false
+  )]
+  find_duplicate_frames: bool,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "When using --find-duplicate-frames, compares frames using a \
+      SHA-256 hash of their data exactly as it is stored rather than their \
+      decoded stored values. This avoids decoding, but only finds duplicates \
+      that have been encoded identically.",
+    default_value_t = false
+  )]
+  compare_stored_data: bool,
+
+  #[arg(
+    long,
+    help = "The number of concurrent tasks to use when using \
+      --find-duplicate-frames. Defaults to the value of --threads if \
+      specified, otherwise the number of CPU cores."
+  )]
+  concurrency: Option<usize>,
+
+  #[command(flatten)]
+  decoder: crate::args::decoder_args::DecoderArgs,
+}
+
+#[allow(clippy::enum_variant_names)]
+enum StatsError {
+  P10Error(P10Error),
+  DataError(DataError),
+  PixelDataDecodeError(PixelDataDecodeError),
+}
+
+impl StatsError {
+  /// Returns lines of text that describe this error in a human-readable
+  /// format.
+  ///
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      StatsError::P10Error(e) => e.to_lines(task_description),
+      StatsError::DataError(e) => e.to_lines(task_description),
+      StatsError::PixelDataDecodeError(e) => e.to_lines(task_description),
+    }
+  }
+}
+
+pub async fn run(args: StatsArgs) -> Result<(), ()> {
+  let mut input_sources = args.input.base.input_sources().await;
+
+  if args.find_duplicate_frames {
+    return find_duplicate_frames(input_sources, &args).await;
+  }
+
+  while let Some(input_source) = input_sources.next().await {
+    match stats_for_input_source(&input_source, &args).await {
+      Ok(lines) => {
+        for line in lines {
+          println!("{line}");
+        }
+      }
+
+      Err(StatsError::P10Error(P10Error::DicmPrefixNotPresent))
+        if args.input.ignore_invalid => {}
+
+      Err(e) => {
+        error::print_error_lines(
+          &e.to_lines(&format!("getting stats for \"{input_source}\"")),
+        );
+
+        return Err(());
+      }
+    }
+  }
+
+  Ok(())
+}
+
+async fn stats_for_input_source(
+  input_source: &InputSource,
+  args: &StatsArgs,
+) -> Result<Vec<String>, StatsError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(StatsError::P10Error)?;
+
+  // Create read context with a small max token size to keep memory usage low
+  let read_config = args.input.p10_read_config().max_token_size(1024 * 1024);
+  let mut read_context = P10ReadContext::new(Some(read_config));
+
+  let mut p10_pixel_data_frame_transform = P10PixelDataFrameTransform::new();
+  let mut pixel_data_renderer_transform =
+    P10CustomTypeTransform::<PixelDataRenderer>::new_for_iod_module();
+
+  let mut pixel_data_renderer_error = None;
+
+  let mut frame_lines = vec![];
+  let mut number_of_frames = 0;
+
+  loop {
+    let tokens = dcmfx::p10::read_tokens_from_stream_async(
+      &mut stream,
+      &mut read_context,
+      None,
+    )
+    .await
+    .map_err(StatsError::P10Error)?;
+
+    for token in tokens.iter() {
+      if *token == P10Token::End {
+        let mut lines = vec![format!("\"{input_source}\"")];
+
+        match pixel_data_renderer_transform.get_output() {
+          Some(pixel_data_renderer) if number_of_frames > 0 => {
+            let image_pixel_module = &pixel_data_renderer.image_pixel_module;
+
+            lines.push(format!(
+              "  Transfer syntax: {}",
+              pixel_data_renderer.transfer_syntax.name
+            ));
+            lines.push(format!(
+              "  Dimensions: {}x{}",
+              image_pixel_module.columns(),
+              image_pixel_module.rows()
+            ));
+            lines.push(format!(
+              "  Photometric interpretation: {}",
+              image_pixel_module.photometric_interpretation()
+            ));
+            lines.push(format!("  Number of frames: {number_of_frames}"));
+            lines.append(&mut frame_lines);
+          }
+
+          _ => lines.push("  No pixel data".to_string()),
+        }
+
+        return Ok(lines);
+      }
+
+      // Errors creating the pixel data renderer are only reported if pixel
+      // data is present, as files without pixel data commonly don't have an
+      // Image Pixel Module
+      if pixel_data_renderer_error.is_none() {
+        match pixel_data_renderer_transform.add_token(token) {
+          Ok(()) => (),
+          Err(P10CustomTypeTransformError::DataError(e)) => {
+            pixel_data_renderer_error = Some(e);
+          }
+          Err(P10CustomTypeTransformError::P10Error(e)) => {
+            return Err(StatsError::P10Error(e));
+          }
+        }
+      }
+
+      let mut frames = p10_pixel_data_frame_transform
+        .add_token(token)
+        .map_err(|e| match e {
+          P10PixelDataFrameTransformError::DataError(e) => {
+            StatsError::DataError(e)
+          }
+          P10PixelDataFrameTransformError::P10Error(e) => {
+            StatsError::P10Error(e)
+          }
+        })?;
+
+      for frame in frames.iter_mut() {
+        number_of_frames += 1;
+
+        if let Some(e) = pixel_data_renderer_error {
+          return Err(StatsError::DataError(e));
+        }
+
+        let Some(pixel_data_renderer) =
+          pixel_data_renderer_transform.get_output_mut()
+        else {
+          continue;
+        };
+
+        let frame_index = frame.index().unwrap();
+
+        pixel_data_renderer.decode_config = args
+          .decoder
+          .pixel_data_decode_config(utils::codec_thread_count(None));
+
+        // Stored value ranges are printed for monochrome frames, so they are
+        // always decoded. Color frames are only decoded when hashing.
+        if pixel_data_renderer.image_pixel_module.is_monochrome() {
+          let image = pixel_data_renderer
+            .decode_monochrome_frame(frame)
+            .map_err(StatsError::PixelDataDecodeError)?;
+
+          frame_lines.push(format!("  Frame {frame_index}:"));
+
+          if let Some((min, max)) = image.min_max_values() {
+            frame_lines.push(format!("    Stored value range: {min} to {max}"));
+          }
+
+          if args.image_hash {
+            add_hash_lines(
+              &mut frame_lines,
+              image.stored_value_hash(),
+              image.dhash(),
+              image.phash(),
+            );
+          }
+        } else if args.image_hash {
+          let image = pixel_data_renderer
+            .decode_color_frame(frame)
+            .map_err(StatsError::PixelDataDecodeError)?;
+
+          frame_lines.push(format!("  Frame {frame_index}:"));
+
+          add_hash_lines(
+            &mut frame_lines,
+            image.stored_value_hash(),
+            image.dhash(),
+            image.phash(),
+          );
+        }
+      }
+    }
+  }
+}
+
+/// Hashes the frames of pixel data in all the input files and prints each
+/// group of duplicate frames.
+///
+async fn find_duplicate_frames(
+  input_sources: impl futures::Stream<Item = InputSource>,
+  args: &StatsArgs,
+) -> Result<(), ()> {
+  let frame_hashes = Arc::new(Mutex::new(vec![]));
+
+  let result = utils::run_tasks(
+    utils::task_count(args.concurrency),
+    input_sources,
+    async |input_source: InputSource| match frame_hashes_for_input_source(
+      &input_source,
+      args,
+    )
+    .await
+    {
+      Ok(hashes) => {
+        frame_hashes
+          .lock()
+          .await
+          .push((input_source.to_string(), hashes));
+
+        Ok(())
+      }
+
+      Err(StatsError::P10Error(P10Error::DicmPrefixNotPresent))
+        if args.input.ignore_invalid =>
+      {
+        Ok(())
+      }
+
+      Err(e) => {
+        Err(e.to_lines(&format!("hashing frames of \"{input_source}\"")))
+      }
+    },
+  )
+  .await;
+
+  if let Err(lines) = result {
+    error::print_error_lines(&lines);
+    return Err(());
+  }
+
+  // Add the hashes in input file order so the output doesn't depend on the
+  // order that the concurrent tasks completed in
+  let mut frame_hashes = std::mem::take(&mut *frame_hashes.lock().await);
+  frame_hashes.sort_by(|a, b| a.0.cmp(&b.0));
+
+  let mut finder = DuplicateFrameFinder::new();
+  for (input_source, hashes) in frame_hashes {
+    for (frame_index, hash) in hashes {
+      finder.add_frame(hash, input_source.clone(), frame_index);
+    }
+  }
+
+  let duplicates = finder.duplicates();
+
+  for (i, frames) in duplicates.iter().enumerate() {
+    println!("Duplicate frames {}:", i + 1);
+
+    for (input_source, frame_index) in frames.iter() {
+      println!("  \"{input_source}\" frame {frame_index}");
+    }
+  }
+
+  println!(
+    "Found {} groups of duplicate frames among {} frames",
+    duplicates.len(),
+    finder.frame_count()
+  );
+
+  Ok(())
+}
+
+/// Returns the index and hash of each frame of pixel data in an input file.
+///
+async fn frame_hashes_for_input_source(
+  input_source: &InputSource,
+  args: &StatsArgs,
+) -> Result<Vec<(usize, [u8; 32])>, StatsError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(StatsError::P10Error)?;
+
+  // Create read context with a small max token size to keep memory usage low
+  let read_config = args.input.p10_read_config().max_token_size(1024 * 1024);
+  let mut read_context = P10ReadContext::new(Some(read_config));
+
+  let mut p10_pixel_data_frame_transform = P10PixelDataFrameTransform::new();
+  let mut pixel_data_renderer_transform =
+    P10CustomTypeTransform::<PixelDataRenderer>::new_for_iod_module();
+
+  let mut hashes = vec![];
+
+  loop {
+    let tokens = dcmfx::p10::read_tokens_from_stream_async(
+      &mut stream,
+      &mut read_context,
+      None,
+    )
+    .await
+    .map_err(StatsError::P10Error)?;
+
+    for token in tokens.iter() {
+      if *token == P10Token::End {
+        return Ok(hashes);
+      }
+
+      if !args.compare_stored_data {
+        pixel_data_renderer_transform.add_token(token).map_err(
+          |e| match e {
+            P10CustomTypeTransformError::DataError(e) => {
+              StatsError::DataError(e)
+            }
+            P10CustomTypeTransformError::P10Error(e) => StatsError::P10Error(e),
+          },
+        )?;
+      }
+
+      let mut frames = p10_pixel_data_frame_transform
+        .add_token(token)
+        .map_err(|e| match e {
+          P10PixelDataFrameTransformError::DataError(e) => {
+            StatsError::DataError(e)
+          }
+          P10PixelDataFrameTransformError::P10Error(e) => {
+            StatsError::P10Error(e)
+          }
+        })?;
+
+      for frame in frames.iter_mut() {
+        let hash = if args.compare_stored_data {
+          duplicate_frames::stored_data_hash(frame)
+        } else {
+          stored_value_hash(frame, &mut pixel_data_renderer_transform, args)?
+        };
+
+        hashes.push((frame.index().unwrap(), hash));
+      }
+    }
+  }
+}
+
+/// Decodes a frame of pixel data and returns a hash of its stored values.
+///
+fn stored_value_hash(
+  frame: &mut PixelDataFrame,
+  pixel_data_renderer_transform: &mut P10CustomTypeTransform<PixelDataRenderer>,
+  args: &StatsArgs,
+) -> Result<[u8; 32], StatsError> {
+  let pixel_data_renderer = pixel_data_renderer_transform
+    .get_output_mut()
+    .ok_or_else(|| {
+      StatsError::DataError(DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+      ))
+    })?;
+
+  pixel_data_renderer.decode_config = args
+    .decoder
+    .pixel_data_decode_config(utils::codec_thread_count(args.concurrency));
+
+  if pixel_data_renderer.image_pixel_module.is_monochrome() {
+    pixel_data_renderer
+      .decode_monochrome_frame(frame)
+      .map(|image| image.stored_value_hash())
+      .map_err(StatsError::PixelDataDecodeError)
+  } else {
+    pixel_data_renderer
+      .decode_color_frame(frame)
+      .map(|image| image.stored_value_hash())
+      .map_err(StatsError::PixelDataDecodeError)
+  }
+}
+
+fn add_hash_lines(
+  lines: &mut Vec<String>,
+  stored_value_hash: [u8; 32],
+  dhash: u64,
+  phash: u64,
+) {
+  let stored_value_hash: String = stored_value_hash
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect();
+
+  lines.push(format!("    Stored value SHA-256: {stored_value_hash}"));
+  lines.push(format!("    dHash: {dhash:016x}"));
+  lines.push(format!("    pHash: {phash:016x}"));
+}
@@ -0,0 +1,457 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+use dcmfx::{
+  core::*,
+  p10::*,
+  pixel_data::{
+    ColorImage, MonochromeImage, PixelDataDecodeError, PixelDataEncodeConfig,
+    PixelDataEncodeError, PixelDataFrame, PixelDataRenderer, decode, encode,
+    transforms::{P10PixelDataFrameTransform, P10PixelDataFrameTransformError},
+  },
+};
+
+use crate::{
+  args::transfer_syntax_arg::TransferSyntaxArg,
+  utils::{self, InputSource, OutputTarget},
+};
+
+pub const ABOUT: &str = "Encodes a frame of pixel data in DICOM P10 files at \
+  several quality levels, and reports the resulting sizes and image quality";
+
+#[derive(Args)]
+pub struct TranscodeSweepArgs {
+  #[command(flatten)]
+  input: crate::args::input_args::P10InputArgs,
+
+  #[arg(
+    long,
+    help = "The transfer syntax to encode the frame into. This should be a \
+      lossy transfer syntax, i.e. one that the --quality argument of the \
+      modify command applies to."
+  )]
+  transfer_syntax: TransferSyntaxArg,
+
+  #[arg(
+    long,
+    help = "The index of the frame to encode. Frame indexes start at zero.",
+    default_value_t = 0
+  )]
+  frame: usize,
+
+  #[arg(
+    long,
+    help = "The quality levels to encode the frame at, in the range 1-100, \
+      separated by commas.",
+    value_delimiter = ',',
+    value_parser = clap::value_parser!(u8).range(1..=100),
+    default_value = "50,60,70,80,90,95"
+  )]
+  qualities: Vec<u8>,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Whether to write a contact sheet PNG image that shows the original \
+      frame followed by the frame at each quality level, from left to right. \
+      The name of the PNG file is the name of the input file with \
+      '.transcode-sweep.png' appended.",
+    default_value_t = false
+  )]
+  contact_sheet: bool,
+
+  #[arg(
+    long,
+    short = 'd',
+    help_heading = "Output",
+    help = "The directory to write contact sheet images into"
+  )]
+  output_directory: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help_heading = "Output",
+    help = "Overwrite any contact sheet images that already exist",
+    default_value_t = false
+  )]
+  overwrite: bool,
+
+  #[command(flatten)]
+  decoder: crate::args::decoder_args::DecoderArgs,
+}
+
+#[allow(clippy::enum_variant_names)]
+enum TranscodeSweepError {
+  P10Error(P10Error),
+  DataError(DataError),
+  PixelDataDecodeError(PixelDataDecodeError),
+  PixelDataEncodeError(PixelDataEncodeError),
+  ImageError(image::ImageError),
+}
+
+impl TranscodeSweepError {
+  /// Returns lines of text that describe this error in a human-readable
+  /// format.
+  ///
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::P10Error(e) => e.to_lines(task_description),
+      Self::DataError(e) => e.to_lines(task_description),
+      Self::PixelDataDecodeError(e) => e.to_lines(task_description),
+      Self::PixelDataEncodeError(e) => e.to_lines(task_description),
+      Self::ImageError(e) => vec![
+        format!("Image error {task_description}"),
+        "".to_string(),
+        format!("  Error: {e}"),
+      ],
+    }
+  }
+}
+
+/// The original frame decoded from the input, or the frame after it has been
+/// encoded and then decoded again.
+///
+enum SweepImage {
+  Monochrome(MonochromeImage),
+  Color(ColorImage),
+}
+
+pub async fn run(args: TranscodeSweepArgs) -> Result<(), ()> {
+  let Some(transfer_syntax) = args.transfer_syntax.as_transfer_syntax() else {
+    utils::exit_with_error(
+      "--transfer-syntax can't be 'pass-through' for a transcode sweep",
+      "",
+    );
+  };
+
+  OutputTarget::set_overwrite(args.overwrite);
+
+  let mut input_sources = args.input.base.input_sources().await;
+
+  while let Some(input_source) = input_sources.next().await {
+    match sweep_input_source(&input_source, transfer_syntax, &args).await {
+      Ok(lines) => {
+        for line in lines {
+          println!("{line}");
+        }
+      }
+
+      Err(TranscodeSweepError::P10Error(P10Error::DicmPrefixNotPresent))
+        if args.input.ignore_invalid => {}
+
+      Err(e) => {
+        error::print_error_lines(
+          &e.to_lines(&format!(
+            "running transcode sweep on \"{input_source}\""
+          )),
+        );
+
+        return Err(());
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Reads the requested frame from an input source, then runs the transcode
+/// sweep on it and returns the lines of output to print.
+///
+async fn sweep_input_source(
+  input_source: &InputSource,
+  transfer_syntax: &'static TransferSyntax,
+  args: &TranscodeSweepArgs,
+) -> Result<Vec<String>, TranscodeSweepError> {
+  let mut stream = input_source
+    .open_read_stream()
+    .await
+    .map_err(TranscodeSweepError::P10Error)?;
+
+  // Create read context with a small max token size to keep memory usage low
+  let read_config = args.input.p10_read_config().max_token_size(1024 * 1024);
+  let mut read_context = P10ReadContext::new(Some(read_config));
+
+  let mut p10_pixel_data_frame_transform = P10PixelDataFrameTransform::new();
+  let mut pixel_data_renderer_transform =
+    P10CustomTypeTransform::<PixelDataRenderer>::new_for_iod_module();
+
+  loop {
+    let tokens = dcmfx::p10::read_tokens_from_stream_async(
+      &mut stream,
+      &mut read_context,
+      None,
+    )
+    .await
+    .map_err(TranscodeSweepError::P10Error)?;
+
+    for token in tokens.iter() {
+      if *token == P10Token::End {
+        return Err(TranscodeSweepError::P10Error(P10Error::OtherError {
+          error_type: "Frame not present".to_string(),
+          details: format!("Frame {} is not present", args.frame).into(),
+        }));
+      }
+
+      pixel_data_renderer_transform
+        .add_token(token)
+        .map_err(|e| match e {
+          P10CustomTypeTransformError::DataError(e) => {
+            TranscodeSweepError::DataError(e)
+          }
+          P10CustomTypeTransformError::P10Error(e) => {
+            TranscodeSweepError::P10Error(e)
+          }
+        })?;
+
+      let mut frames = p10_pixel_data_frame_transform
+        .add_token(token)
+        .map_err(|e| match e {
+          P10PixelDataFrameTransformError::DataError(e) => {
+            TranscodeSweepError::DataError(e)
+          }
+          P10PixelDataFrameTransformError::P10Error(e) => {
+            TranscodeSweepError::P10Error(e)
+          }
+        })?;
+
+      for frame in frames.iter_mut() {
+        if frame.index() != Some(args.frame) {
+          continue;
+        }
+
+        let Some(pixel_data_renderer) =
+          pixel_data_renderer_transform.get_output_mut()
+        else {
+          continue;
+        };
+
+        pixel_data_renderer.decode_config = args
+          .decoder
+          .pixel_data_decode_config(utils::codec_thread_count(None));
+
+        return sweep_frame(
+          input_source,
+          frame,
+          pixel_data_renderer,
+          transfer_syntax,
+          args,
+        )
+        .await;
+      }
+    }
+  }
+}
+
+/// Encodes a frame at each of the requested quality levels, decodes it again,
+/// and compares the result against the original frame.
+///
+async fn sweep_frame(
+  input_source: &InputSource,
+  frame: &mut PixelDataFrame,
+  pixel_data_renderer: &PixelDataRenderer,
+  transfer_syntax: &'static TransferSyntax,
+  args: &TranscodeSweepArgs,
+) -> Result<Vec<String>, TranscodeSweepError> {
+  let original_size = frame.len();
+
+  let original = if pixel_data_renderer.image_pixel_module.is_monochrome() {
+    SweepImage::Monochrome(
+      pixel_data_renderer
+        .decode_monochrome_frame(frame)
+        .map_err(TranscodeSweepError::PixelDataDecodeError)?,
+    )
+  } else {
+    SweepImage::Color(
+      pixel_data_renderer
+        .decode_color_frame(frame)
+        .map_err(TranscodeSweepError::PixelDataDecodeError)?,
+    )
+  };
+
+  let mut lines = vec![
+    format!("\"{input_source}\""),
+    format!(
+      "  Source: frame {}, {} bytes, {}",
+      args.frame, original_size, pixel_data_renderer.transfer_syntax.name
+    ),
+    format!("  Target: {}", transfer_syntax.name),
+    format!(
+      "  {:>7}  {:>12}  {:>8}  {:>10}  {:>8}",
+      "Quality", "Size", "Ratio", "PSNR", "SSIM"
+    ),
+  ];
+
+  let mut images = vec![];
+
+  for quality in args.qualities.iter() {
+    let mut encode_config = PixelDataEncodeConfig::default();
+    encode_config.set_quality(*quality);
+
+    let image_pixel_module = encode::encode_image_pixel_module(
+      pixel_data_renderer.image_pixel_module.clone(),
+      transfer_syntax,
+      &encode_config,
+    )
+    .map_err(TranscodeSweepError::PixelDataEncodeError)?;
+
+    let decode_config = &pixel_data_renderer.decode_config;
+
+    let (size, image) = match &original {
+      SweepImage::Monochrome(original) => {
+        let mut encoded_frame = encode::encode_monochrome(
+          original,
+          &image_pixel_module,
+          transfer_syntax,
+          &encode_config,
+        )
+        .map_err(TranscodeSweepError::PixelDataEncodeError)?;
+
+        let size = encoded_frame.len();
+
+        let image = decode::decode_monochrome(
+          &mut encoded_frame,
+          transfer_syntax,
+          &image_pixel_module,
+          decode_config,
+        )
+        .map_err(TranscodeSweepError::PixelDataDecodeError)?;
+
+        (size, SweepImage::Monochrome(image))
+      }
+
+      SweepImage::Color(original) => {
+        let mut encoded_frame = encode::encode_color(
+          original,
+          &image_pixel_module,
+          transfer_syntax,
+          &encode_config,
+        )
+        .map_err(TranscodeSweepError::PixelDataEncodeError)?;
+
+        let size = encoded_frame.len();
+
+        let image = decode::decode_color(
+          &mut encoded_frame,
+          transfer_syntax,
+          &image_pixel_module,
+          decode_config,
+        )
+        .map_err(TranscodeSweepError::PixelDataDecodeError)?;
+
+        (size, SweepImage::Color(image))
+      }
+    };
+
+    let metrics = match (&image, &original) {
+      (SweepImage::Monochrome(image), SweepImage::Monochrome(original)) => {
+        image.quality_metrics(original)
+      }
+      (SweepImage::Color(image), SweepImage::Color(original)) => {
+        image.quality_metrics(original)
+      }
+      _ => None,
+    };
+
+    let ratio = if size == 0 {
+      0.0
+    } else {
+      original_size as f64 / size as f64
+    };
+
+    lines.push(format!(
+      "  {:>7}  {:>12}  {:>7.2}x  {:>10}  {:>8}",
+      quality,
+      size,
+      ratio,
+      metrics
+        .map(|metrics| format!("{:.2} dB", metrics.psnr))
+        .unwrap_or_default(),
+      metrics
+        .map(|metrics| format!("{:.4}", metrics.ssim))
+        .unwrap_or_default(),
+    ));
+
+    if args.contact_sheet {
+      images.push(image);
+    }
+  }
+
+  if args.contact_sheet {
+    images.insert(0, original);
+
+    let output_target = OutputTarget::from_input_source(
+      input_source,
+      ".transcode-sweep.png",
+      &args.output_directory,
+    )
+    .await;
+
+    write_contact_sheet(output_target, &images, pixel_data_renderer).await?;
+  }
+
+  Ok(lines)
+}
+
+/// Renders the images side by side into a single PNG image and writes it to
+/// the output target.
+///
+async fn write_contact_sheet(
+  output_target: OutputTarget,
+  images: &[SweepImage],
+  pixel_data_renderer: &PixelDataRenderer,
+) -> Result<(), TranscodeSweepError> {
+  let rendered_images: Vec<image::RgbImage> = images
+    .iter()
+    .map(|image| match image {
+      SweepImage::Monochrome(image) => {
+        pixel_data_renderer.render_monochrome_image(image, None)
+      }
+      SweepImage::Color(image) => image.clone().into_rgb_u8_image(),
+    })
+    .collect();
+
+  let width: u32 = rendered_images.iter().map(|image| image.width()).sum();
+  let height = rendered_images
+    .iter()
+    .map(|image| image.height())
+    .max()
+    .unwrap_or(0);
+
+  let mut contact_sheet = image::RgbImage::new(width, height);
+
+  let mut x = 0;
+  for image in rendered_images.iter() {
+    image::imageops::replace(&mut contact_sheet, image, x, 0);
+    x += i64::from(image.width());
+  }
+
+  let mut png_bytes = std::io::Cursor::new(vec![]);
+  contact_sheet
+    .write_to(&mut png_bytes, image::ImageFormat::Png)
+    .map_err(TranscodeSweepError::ImageError)?;
+
+  let output_stream_handle = output_target
+    .open_write_stream(true)
+    .await
+    .map_err(TranscodeSweepError::P10Error)?;
+
+  let mut output_stream = output_stream_handle.lock().await;
+
+  output_stream
+    .write_all(png_bytes.get_ref())
+    .await
+    .map_err(|e| {
+      TranscodeSweepError::P10Error(P10Error::FileError {
+        when: "Writing contact sheet".into(),
+        details: e.to_string().into(),
+      })
+    })?;
+
+  output_target
+    .commit(&mut output_stream)
+    .await
+    .map_err(TranscodeSweepError::P10Error)
+}
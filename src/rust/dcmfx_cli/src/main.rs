@@ -9,8 +9,11 @@ use std::path::PathBuf;
 use clap::{Parser, Subcommand};
 
 use commands::{
-  dcm_to_json_command, get_pixel_data_command, json_to_dcm_command,
-  list_command, modify_command, print_command, rewrite_command,
+  convert_series_command, dcm_to_json_command, dicomdir_command,
+  export_color_palette_command, export_volume_command, from_image_command,
+  generate_command, get_pixel_data_command, get_waveform_command,
+  json_to_dcm_command, list_command, modify_command, print_command,
+  rewrite_command, stats_command, transcode_sweep_command,
 };
 
 #[derive(Parser)]
@@ -31,6 +34,38 @@ struct Cli {
     help = "Write timing and memory stats to stderr on exit"
   )]
   print_stats: bool,
+
+  #[arg(
+    long,
+    global = true,
+    help = "The total number of threads to use. This sets the default number \
+      of concurrent tasks, and the remaining threads are divided among pixel \
+      data codecs that support multithreading. Defaults to the number of CPU \
+      cores."
+  )]
+  threads: Option<usize>,
+
+  #[arg(
+    long,
+    global = true,
+    help_heading = "Object Store",
+    default_value_t = utils::output_target::DEFAULT_UPLOAD_CONCURRENCY,
+    help = "The maximum number of parts of each multipart upload that are \
+      uploaded concurrently when writing output to an object store."
+  )]
+  object_store_upload_concurrency: usize,
+
+  #[arg(
+    long,
+    global = true,
+    help_heading = "Object Store",
+    default_value_t = 5,
+    value_parser = clap::value_parser!(u64).range(5..=5120),
+    help = "The size in MiB of each part of a multipart upload when writing \
+      output to an object store. Output is streamed to the object store in \
+      parts of this size without being written to a local file first."
+  )]
+  object_store_part_size: u64,
 }
 
 #[derive(Subcommand)]
@@ -38,6 +73,9 @@ enum Commands {
   #[command(about = get_pixel_data_command::ABOUT)]
   GetPixelData(get_pixel_data_command::GetPixelDataArgs),
 
+  #[command(about = get_waveform_command::ABOUT)]
+  GetWaveform(get_waveform_command::GetWaveformArgs),
+
   #[command(about = modify_command::ABOUT)]
   Modify(modify_command::ModifyArgs),
 
@@ -58,22 +96,68 @@ enum Commands {
     long_about = rewrite_command::LONG_ABOUT
   )]
   Rewrite(rewrite_command::RewriteArgs),
+
+  #[command(about = stats_command::ABOUT)]
+  Stats(stats_command::StatsArgs),
+
+  #[command(about = generate_command::ABOUT)]
+  Generate(generate_command::GenerateArgs),
+
+  #[command(about = dicomdir_command::ABOUT)]
+  Dicomdir(dicomdir_command::DicomdirArgs),
+
+  #[command(about = from_image_command::ABOUT)]
+  FromImage(from_image_command::FromImageArgs),
+
+  #[command(about = transcode_sweep_command::ABOUT)]
+  TranscodeSweep(transcode_sweep_command::TranscodeSweepArgs),
+
+  #[command(about = export_volume_command::ABOUT)]
+  ExportVolume(export_volume_command::ExportVolumeArgs),
+
+  #[command(about = export_color_palette_command::ABOUT)]
+  ExportColorPalette(export_color_palette_command::ExportColorPaletteArgs),
+
+  #[command(about = convert_series_command::ABOUT)]
+  ConvertSeries(convert_series_command::ConvertSeriesArgs),
 }
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
   let cli = Cli::parse();
 
+  if let Some(threads) = cli.threads {
+    utils::set_thread_count(threads);
+  }
+
+  utils::output_target::OutputTarget::set_upload_concurrency(
+    cli.object_store_upload_concurrency,
+  );
+  utils::output_target::OutputTarget::set_upload_part_size(
+    cli.object_store_part_size as usize * 1024 * 1024,
+  );
+
   let started_at = std::time::Instant::now();
 
   let r = match cli.command {
     Commands::GetPixelData(args) => get_pixel_data_command::run(args).await,
+    Commands::GetWaveform(args) => get_waveform_command::run(args).await,
     Commands::Modify(args) => modify_command::run(args).await,
     Commands::Print(args) => print_command::run(args).await,
     Commands::JsonToDcm(args) => json_to_dcm_command::run(args).await,
     Commands::DcmToJson(args) => dcm_to_json_command::run(args).await,
     Commands::List(args) => list_command::run(args).await,
     Commands::Rewrite(args) => rewrite_command::run(args).await,
+    Commands::Stats(args) => stats_command::run(args).await,
+    Commands::Generate(args) => generate_command::run(args).await,
+    Commands::Dicomdir(args) => dicomdir_command::run(args).await,
+    Commands::FromImage(args) => from_image_command::run(args).await,
+    Commands::TranscodeSweep(args) => transcode_sweep_command::run(args).await,
+    Commands::ExportVolume(args) => export_volume_command::run(args).await,
+    Commands::ExportColorPalette(args) => {
+      export_color_palette_command::run(args).await
+    }
+    Commands::ConvertSeries(args) => convert_series_command::run(args).await,
   };
 
   if cli.print_stats {
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+
+/// The file formats that an [`AnimationEncoder`] can write.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationFormat {
+  Gif,
+  Apng,
+}
+
+/// Encodes frames into an animated GIF or APNG file held in memory. Animations
+/// loop forever, and each frame has its own display duration.
+///
+/// Frames are held until [`AnimationEncoder::finish()`] is called because the
+/// number of frames has to be written at the start of an APNG file.
+///
+pub struct AnimationEncoder {
+  format: AnimationFormat,
+  frames: Vec<(image::DynamicImage, Duration)>,
+}
+
+impl AnimationEncoder {
+  /// Creates a new animation encoder for the given format.
+  ///
+  pub fn new(format: AnimationFormat) -> Self {
+    Self {
+      format,
+      frames: vec![],
+    }
+  }
+
+  /// Adds a frame to the animation that is displayed for the given duration.
+  /// All frames must have the same dimensions.
+  ///
+  pub fn add_frame(
+    &mut self,
+    image: image::DynamicImage,
+    duration: Duration,
+  ) -> Result<(), String> {
+    if let Some((first_frame, _)) = self.frames.first()
+      && (first_frame.width(), first_frame.height())
+        != (image.width(), image.height())
+    {
+      return Err(format!(
+        "Frame dimensions {}x{} differ from the first frame's dimensions {}x{}",
+        image.width(),
+        image.height(),
+        first_frame.width(),
+        first_frame.height()
+      ));
+    }
+
+    self.frames.push((image, duration));
+
+    Ok(())
+  }
+
+  /// Completes the animation and returns its bytes.
+  ///
+  pub fn finish(self) -> Result<Vec<u8>, String> {
+    match self.format {
+      AnimationFormat::Gif => encode_gif(self.frames),
+      AnimationFormat::Apng => encode_apng(self.frames),
+    }
+  }
+}
+
+fn encode_gif(
+  frames: Vec<(image::DynamicImage, Duration)>,
+) -> Result<Vec<u8>, String> {
+  let mut bytes = vec![];
+
+  {
+    let mut encoder = GifEncoder::new(&mut bytes);
+    encoder
+      .set_repeat(Repeat::Infinite)
+      .map_err(|e| e.to_string())?;
+
+    for (image, duration) in frames {
+      let frame = image::Frame::from_parts(
+        image.into_rgba8(),
+        0,
+        0,
+        image::Delay::from_saturating_duration(duration),
+      );
+
+      encoder.encode_frame(frame).map_err(|e| e.to_string())?;
+    }
+  }
+
+  Ok(bytes)
+}
+
+fn encode_apng(
+  frames: Vec<(image::DynamicImage, Duration)>,
+) -> Result<Vec<u8>, String> {
+  let Some((first_frame, _)) = frames.first() else {
+    return Err("Animation has no frames".to_string());
+  };
+
+  // Write grayscale when all frames are grayscale, and RGB otherwise
+  let is_grayscale = frames
+    .iter()
+    .all(|(image, _)| image.color() == image::ColorType::L8);
+
+  let mut bytes = vec![];
+
+  let mut encoder =
+    png::Encoder::new(&mut bytes, first_frame.width(), first_frame.height());
+  encoder.set_color(if is_grayscale {
+    png::ColorType::Grayscale
+  } else {
+    png::ColorType::Rgb
+  });
+  encoder.set_depth(png::BitDepth::Eight);
+  encoder
+    .set_animated(frames.len() as u32, 0)
+    .map_err(|e| e.to_string())?;
+
+  let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+
+  for (image, duration) in frames {
+    // Frame delays are stored in milliseconds
+    let delay = duration.as_millis().min(u16::MAX.into()) as u16;
+    writer
+      .set_frame_delay(delay, 1000)
+      .map_err(|e| e.to_string())?;
+
+    let data = if is_grayscale {
+      image.into_luma8().into_raw()
+    } else {
+      image.into_rgb8().into_raw()
+    };
+
+    writer.write_image_data(&data).map_err(|e| e.to_string())?;
+  }
+
+  writer.finish().map_err(|e| e.to_string())?;
+
+  Ok(bytes)
+}
@@ -60,8 +60,8 @@ impl InputSource {
         let get_result =
           object_store.get(&object_path.clone()).await.map_err(|e| {
             P10Error::FileError {
-              when: "Opening read stream".to_string(),
-              details: e.to_string(),
+              when: "Opening read stream".into(),
+              details: e.to_string().into(),
             }
           })?;
 
@@ -1,12 +1,19 @@
+pub mod animation_encoder;
 pub mod input_source;
 pub mod mp4_encoder;
 pub mod object_store;
 pub mod output_target;
+pub mod render_config;
+pub mod tiff_encoder;
+pub mod wav_encoder;
 
 pub use input_source::InputSource;
 pub use output_target::OutputTarget;
 
-use std::path::{Component, Path, PathBuf};
+use std::{
+  path::{Component, Path, PathBuf},
+  sync::OnceLock,
+};
 
 use futures::{TryStreamExt, stream::StreamExt};
 
@@ -30,6 +37,59 @@ where
     .await
 }
 
+/// The total thread count specified by the global `--threads` argument.
+///
+static THREAD_COUNT: OnceLock<usize> = OnceLock::new();
+
+/// Sets the total number of threads that commands should use. This is called
+/// once at startup with the value of the global `--threads` argument.
+///
+pub fn set_thread_count(thread_count: usize) {
+  let _ = THREAD_COUNT.set(thread_count.max(1));
+}
+
+/// Returns the number of concurrent tasks to run for a command. This is the
+/// command's `--concurrency` argument if specified, otherwise the global
+/// `--threads` argument if specified, otherwise the number of CPU cores.
+///
+pub fn task_count(concurrency: Option<usize>) -> usize {
+  concurrency
+    .or_else(|| THREAD_COUNT.get().copied())
+    .unwrap_or_else(num_cpus::get)
+    .max(1)
+}
+
+/// Returns the number of threads that each pixel data codec may use when
+/// processing a single frame. When the global `--threads` argument is
+/// specified its threads are divided evenly among the concurrent tasks so the
+/// total thread count is respected. Returns `None` if `--threads` wasn't
+/// specified, in which case codecs use their default thread count.
+///
+pub fn codec_thread_count(concurrency: Option<usize>) -> Option<usize> {
+  THREAD_COUNT
+    .get()
+    .map(|thread_count| (thread_count / task_count(concurrency)).max(1))
+}
+
+/// Returns the directory that holds DCMfx's user configuration, which is
+/// `dcmfx` in the user's configuration directory. Returns `None` if the user's
+/// configuration directory can't be determined.
+///
+pub fn user_config_directory() -> Option<PathBuf> {
+  let config_dir = if cfg!(windows) {
+    std::env::var_os("APPDATA").map(PathBuf::from)
+  } else {
+    std::env::var_os("XDG_CONFIG_HOME")
+      .filter(|dir| !dir.is_empty())
+      .map(PathBuf::from)
+      .or_else(|| {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+      })
+  };
+
+  config_dir.map(|dir| dir.join("dcmfx"))
+}
+
 /// Normalizes a path by making it absolute if it is a relative path, and
 /// removing '.' and '..' components when present.
 ///
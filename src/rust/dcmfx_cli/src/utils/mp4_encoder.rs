@@ -9,6 +9,9 @@ use tokio::{io::AsyncWriteExt, task::JoinHandle};
 pub struct Mp4Encoder {
   ffmpeg_stdin: Option<tokio::process::ChildStdin>,
   join_handle: Option<JoinHandle<Result<ExitStatus, String>>>,
+  frame_width: u32,
+  frame_height: u32,
+  frame_color: image::ColorType,
 }
 
 impl Mp4Encoder {
@@ -143,6 +146,9 @@ impl Mp4Encoder {
     Ok(Self {
       ffmpeg_stdin: Some(ffmpeg_stdin),
       join_handle: Some(join_handle),
+      frame_width: first_frame.width(),
+      frame_height: first_frame.height(),
+      frame_color: first_frame.color(),
     })
   }
 
@@ -174,12 +180,29 @@ impl Mp4Encoder {
       })
   }
 
-  /// Writes the next frame of video.
+  /// Writes the next frame of video. The frame must have the same dimensions
+  /// and color type as the first frame.
   ///
   pub async fn add_frame(
     &mut self,
     frame_image: &image::DynamicImage,
   ) -> Result<(), String> {
+    if frame_image.width() != self.frame_width
+      || frame_image.height() != self.frame_height
+      || frame_image.color() != self.frame_color
+    {
+      return Err(format!(
+        "Frame of size {}x{} with color type {:?} does not match the first \
+         frame of size {}x{} with color type {:?}",
+        frame_image.width(),
+        frame_image.height(),
+        frame_image.color(),
+        self.frame_width,
+        self.frame_height,
+        self.frame_color
+      ));
+    }
+
     let Some(ffmpeg_stdin) = &mut self.ffmpeg_stdin else {
       panic!("FFmpeg stdin stream has been closed for writing");
     };
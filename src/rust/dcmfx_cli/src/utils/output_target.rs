@@ -1,7 +1,10 @@
 use std::{
   path::{Path, PathBuf},
   pin::Pin,
-  sync::{Arc, LazyLock, atomic::AtomicBool},
+  sync::{
+    Arc, LazyLock,
+    atomic::{AtomicBool, AtomicUsize},
+  },
   task::{Context, Poll},
 };
 
@@ -12,7 +15,7 @@ use object_store::{
 use tokio::{
   io::{AsyncWriteExt, BufWriter, stdout},
   sync::{Mutex, mpsc},
-  task::JoinHandle,
+  task::{JoinHandle, JoinSet},
 };
 
 use dcmfx::p10::{IoAsyncWrite, P10Error};
@@ -21,9 +24,20 @@ use crate::utils::{InputSource, object_store::object_url_to_store_and_path};
 
 static OVERWRITE: AtomicBool = AtomicBool::new(false);
 
+static UPLOAD_CONCURRENCY: AtomicUsize =
+  AtomicUsize::new(DEFAULT_UPLOAD_CONCURRENCY);
+
+static UPLOAD_PART_SIZE: AtomicUsize = AtomicUsize::new(MINIMUM_PART_SIZE);
+
+/// The default number of parts of a multipart upload that are uploaded
+/// concurrently.
+///
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
 /// An output target that abstracts over the different locations that output can
 /// be sent to.
 ///
+#[derive(Clone)]
 pub enum OutputTarget {
   /// An output target that writes to stdout. There is a shared stdout stream,
   /// so only one task can write to it at a time. This avoids output from
@@ -55,6 +69,31 @@ impl OutputTarget {
     OVERWRITE.store(overwrite, std::sync::atomic::Ordering::Relaxed);
   }
 
+  /// Sets the maximum number of parts of each multipart upload to an object
+  /// store that are uploaded concurrently.
+  ///
+  /// This is a global setting.
+  ///
+  pub fn set_upload_concurrency(upload_concurrency: usize) {
+    UPLOAD_CONCURRENCY.store(
+      upload_concurrency.max(1),
+      std::sync::atomic::Ordering::Relaxed,
+    );
+  }
+
+  /// Sets the size in bytes of each part of a multipart upload to an object
+  /// store, other than the final part. Sizes below the 5 MiB minimum allowed by
+  /// object stores are increased to the minimum.
+  ///
+  /// This is a global setting.
+  ///
+  pub fn set_upload_part_size(upload_part_size: usize) {
+    UPLOAD_PART_SIZE.store(
+      upload_part_size.max(MINIMUM_PART_SIZE),
+      std::sync::atomic::Ordering::Relaxed,
+    );
+  }
+
   /// Creates a new output target for the specified path.
   ///
   pub async fn new<P: AsRef<Path>>(path: P) -> Self {
@@ -193,8 +232,8 @@ impl OutputTarget {
           .put_multipart(object_path)
           .await
           .map_err(|e| P10Error::FileError {
-            when: "Initiating put to object store".to_string(),
-            details: e.to_string(),
+            when: "Initiating put to object store".into(),
+            details: e.to_string().into(),
           })?;
 
         // Create an async write stream that uploads multipart data
@@ -214,14 +253,14 @@ impl OutputTarget {
   ) -> Result<(), P10Error> {
     match self {
       Self::StdOut => stream.flush().await.map_err(|e| P10Error::FileError {
-        when: "Flushing stdout".to_string(),
-        details: e.to_string(),
+        when: "Flushing stdout".into(),
+        details: e.to_string().into(),
       }),
 
       Self::Object { .. } => {
         stream.shutdown().await.map_err(|e| P10Error::FileError {
-          when: "Shutting down output stream".to_string(),
-          details: e.to_string(),
+          when: "Shutting down output stream".into(),
+          details: e.to_string().into(),
         })
       }
     }
@@ -236,11 +275,12 @@ static GLOBAL_STDOUT: LazyLock<Arc<Mutex<Box<dyn dcmfx::p10::IoAsyncWrite>>>> =
 
 /// Makes an [`object_store::MultipartUpload`] usable as a
 /// [`tokio::io::AsyncWrite`] stream by buffering data into parts of at least
-/// 5 MiB before sending them.
+/// 5 MiB before sending them. Parts are uploaded concurrently, up to the limit
+/// set by [`OutputTarget::set_upload_concurrency()`].
 ///
 struct MultipartUploadAsyncWrite {
   // Sender for complete parts ready to be uploaded. Parts are always at least
-  // 5 MiB in size, except for the final part. Sending `None` indicates that all
+  // the part size, except for the final part. Sending `None` indicates that all
   // parts have been written.
   tx: Option<mpsc::UnboundedSender<Option<Vec<u8>>>>,
 
@@ -248,8 +288,11 @@ struct MultipartUploadAsyncWrite {
   join_handle: JoinHandle<object_store::Result<bool>>,
 
   // The current part being buffered and that will be sent via the channel once
-  // it reaches 5 MiB in size.
+  // it reaches the part size.
   current_part: Vec<u8>,
+
+  // The size of each part other than the final part.
+  part_size: usize,
 }
 
 const MINIMUM_PART_SIZE: usize = 5 * 1024 * 1024;
@@ -258,39 +301,83 @@ impl MultipartUploadAsyncWrite {
   fn new(mut multipart_upload: Box<dyn MultipartUpload>) -> Self {
     let (tx, mut rx) = mpsc::unbounded_channel::<Option<Vec<u8>>>();
 
+    let upload_concurrency =
+      UPLOAD_CONCURRENCY.load(std::sync::atomic::Ordering::Relaxed);
+
     // Spawn a task that puts received parts for the multipart upload as they
-    // are received.
+    // are received. Each part is uploaded in its own task so that parts upload
+    // concurrently. The order of the parts is set when they are put, so they
+    // are able to complete in any order.
     let join_handle = tokio::spawn(async move {
-      while let Some(data) = rx.recv().await {
-        match data {
-          Some(data) => {
-            if !data.is_empty() {
-              multipart_upload.put_part(PutPayload::from(data)).await?;
+      let mut part_uploads = JoinSet::new();
+
+      let result = async {
+        while let Some(data) = rx.recv().await {
+          match data {
+            Some(data) => {
+              if !data.is_empty() {
+                // Wait for a part to finish uploading if the concurrency limit
+                // has been reached
+                while part_uploads.len() >= upload_concurrency {
+                  join_next_part_upload(&mut part_uploads).await?;
+                }
+
+                part_uploads
+                  .spawn(multipart_upload.put_part(PutPayload::from(data)));
+              }
             }
-          }
 
-          None => {
-            multipart_upload.complete().await?;
-            return Ok(true);
+            None => {
+              while !part_uploads.is_empty() {
+                join_next_part_upload(&mut part_uploads).await?;
+              }
+
+              multipart_upload.complete().await?;
+              return Ok(true);
+            }
           }
         }
+
+        Ok(false)
       }
+      .await;
 
-      // The channel was closed before a `None` was received to indicate the
-      // end of the parts, so abort the upload
-      multipart_upload.abort().await?;
+      match result {
+        Ok(true) => Ok(true),
 
-      Ok(false)
+        // A part failed to upload, or the channel was closed before a `None`
+        // was received to indicate the end of the parts, so abort the upload.
+        // The original error takes precedence over any error aborting.
+        result => {
+          part_uploads.abort_all();
+          let abort_result = multipart_upload.abort().await;
+
+          result.and(abort_result.map(|_| false))
+        }
+      }
     });
 
     Self {
       tx: Some(tx),
       join_handle,
       current_part: vec![],
+      part_size: UPLOAD_PART_SIZE.load(std::sync::atomic::Ordering::Relaxed),
     }
   }
 }
 
+/// Waits for the next part upload in the set to complete, returning its result.
+///
+async fn join_next_part_upload(
+  part_uploads: &mut JoinSet<object_store::Result<()>>,
+) -> object_store::Result<()> {
+  match part_uploads.join_next().await {
+    Some(Ok(result)) => result,
+    Some(Err(e)) => Err(e.into()),
+    None => Ok(()),
+  }
+}
+
 impl tokio::io::AsyncWrite for MultipartUploadAsyncWrite {
   fn poll_write(
     self: Pin<&mut Self>,
@@ -309,8 +396,8 @@ impl tokio::io::AsyncWrite for MultipartUploadAsyncWrite {
     // Add data to the current part buffer
     this.current_part.extend_from_slice(buf);
 
-    // Buffer until the minimum part size is reached
-    if this.current_part.len() < MINIMUM_PART_SIZE {
+    // Buffer until the part size is reached
+    if this.current_part.len() < this.part_size {
       return Poll::Ready(Ok(buf.len()));
     }
 
@@ -319,7 +406,7 @@ impl tokio::io::AsyncWrite for MultipartUploadAsyncWrite {
       Ok(()) => Poll::Ready(Ok(buf.len())),
       Err(_) => Poll::Ready(Err(std::io::Error::new(
         std::io::ErrorKind::BrokenPipe,
-        "Multipart uploadtask ended unexpectedly",
+        "Multipart upload task ended unexpectedly",
       ))),
     }
   }
@@ -363,3 +450,125 @@ impl tokio::io::AsyncWrite for MultipartUploadAsyncWrite {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use object_store::{PutResult, UploadPart, memory::InMemory};
+
+  /// Wraps a multipart upload so that its parts complete in reverse order, a
+  /// specific part can be made to fail, and aborts are recorded.
+  ///
+  #[derive(Debug)]
+  struct TestMultipartUpload {
+    inner: Box<dyn MultipartUpload>,
+    part_count: usize,
+    failed_part: Option<usize>,
+    aborted: Arc<AtomicBool>,
+  }
+
+  #[async_trait::async_trait]
+  impl MultipartUpload for TestMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+      let part_index = self.part_count;
+      self.part_count += 1;
+
+      if self.failed_part == Some(part_index) {
+        return Box::pin(async {
+          Err(object_store::Error::Generic {
+            store: "test",
+            source: "part upload failed".into(),
+          })
+        });
+      }
+
+      // Delay earlier parts for longer so they complete after later parts
+      let upload = self.inner.put_part(data);
+      Box::pin(async move {
+        for _ in 0..(100usize.saturating_sub(part_index * 10)) {
+          tokio::task::yield_now().await;
+        }
+
+        upload.await
+      })
+    }
+
+    async fn complete(&mut self) -> object_store::Result<PutResult> {
+      self.inner.complete().await
+    }
+
+    async fn abort(&mut self) -> object_store::Result<()> {
+      self
+        .aborted
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+      self.inner.abort().await
+    }
+  }
+
+  async fn test_writer(
+    object_store: &InMemory,
+    object_path: &ObjectStorePath,
+    failed_part: Option<usize>,
+    aborted: Arc<AtomicBool>,
+  ) -> MultipartUploadAsyncWrite {
+    let multipart_upload = Box::new(TestMultipartUpload {
+      inner: object_store.put_multipart(object_path).await.unwrap(),
+      part_count: 0,
+      failed_part,
+      aborted,
+    });
+
+    let mut writer = MultipartUploadAsyncWrite::new(multipart_upload);
+    writer.part_size = 10;
+    writer
+  }
+
+  #[tokio::test]
+  async fn multipart_upload_part_order_test() {
+    let object_store = InMemory::new();
+    let object_path = ObjectStorePath::from("output.bin");
+    let aborted = Arc::new(AtomicBool::new(false));
+
+    let mut writer =
+      test_writer(&object_store, &object_path, None, aborted.clone()).await;
+
+    let data: Vec<u8> = (0..=255).collect();
+    for chunk in data.chunks(7) {
+      writer.write_all(chunk).await.unwrap();
+    }
+    writer.shutdown().await.unwrap();
+
+    let uploaded_data = object_store
+      .get(&object_path)
+      .await
+      .unwrap()
+      .bytes()
+      .await
+      .unwrap();
+
+    assert_eq!(uploaded_data.as_ref(), data.as_slice());
+    assert!(!aborted.load(std::sync::atomic::Ordering::Relaxed));
+  }
+
+  #[tokio::test]
+  async fn multipart_upload_failed_part_test() {
+    let object_store = InMemory::new();
+    let object_path = ObjectStorePath::from("output.bin");
+    let aborted = Arc::new(AtomicBool::new(false));
+
+    let mut writer =
+      test_writer(&object_store, &object_path, Some(2), aborted.clone()).await;
+
+    // Writes may fail once the upload task has stopped due to the failed part,
+    // but shutting down must always report the failure
+    let _ = writer.write_all(&[0; 100]).await;
+    assert!(writer.shutdown().await.is_err());
+
+    assert!(aborted.load(std::sync::atomic::Ordering::Relaxed));
+    assert!(matches!(
+      object_store.head(&object_path).await,
+      Err(object_store::Error::NotFound { .. })
+    ));
+  }
+}
@@ -0,0 +1,168 @@
+//! Loads default rendering profiles from a user configuration file.
+//!
+//! The configuration file is in TOML format. A `[default]` table specifies the
+//! profile used for all modalities, and `[modality.<MODALITY>]` tables specify
+//! profiles for individual modalities. Values use the same names as the
+//! corresponding command line arguments, e.g.:
+//!
+//! ```toml
+//! [default]
+//! grayscale-output-depth = "windowed8"
+//!
+//! [modality.CT]
+//! voi-window = [40, 400]
+//!
+//! [modality.PT]
+//! color-palette = "hot-iron"
+//! invert = false
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use dcmfx::pixel_data::{
+  RenderProfile, RenderProfiles,
+  iods::voi_lut_module::{VoiLutFunction, VoiWindow},
+};
+
+use crate::args::{
+  grayscale_output_depth_arg::GrayscaleOutputDepthArg,
+  standard_color_palette_arg::parse_color_palette,
+};
+
+/// Returns the path of the default render configuration file, which is
+/// `dcmfx/render.toml` in the user's configuration directory. Returns `None`
+/// if the user's configuration directory can't be determined.
+///
+pub fn default_render_config_path() -> Option<PathBuf> {
+  super::user_config_directory().map(|dir| dir.join("render.toml"))
+}
+
+/// Loads render profiles from the specified configuration file. If no path is
+/// specified then the default render configuration file is loaded if it
+/// exists, and no render profiles are returned if it doesn't.
+///
+pub fn load_render_profiles(
+  path: Option<&PathBuf>,
+) -> Result<RenderProfiles, String> {
+  let path = match path {
+    Some(path) => path.clone(),
+    None => match default_render_config_path() {
+      Some(path) if path.is_file() => path,
+      _ => return Ok(RenderProfiles::new()),
+    },
+  };
+
+  read_render_config_file(&path)
+    .map_err(|e| format!("Invalid render config '{}': {}", path.display(), e))
+}
+
+fn read_render_config_file(path: &Path) -> Result<RenderProfiles, String> {
+  let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+  parse_render_config(&content)
+}
+
+/// Parses render profiles from the content of a render configuration file.
+///
+fn parse_render_config(content: &str) -> Result<RenderProfiles, String> {
+  let table = content
+    .parse::<toml::Table>()
+    .map_err(|e| e.message().to_string())?;
+
+  let mut profiles = RenderProfiles::new();
+
+  for (key, value) in table.iter() {
+    match key.as_str() {
+      "default" => {
+        profiles.set_default_profile(parse_render_profile(key, value)?);
+      }
+
+      "modality" => {
+        let modalities = value
+          .as_table()
+          .ok_or_else(|| "'modality' must be a table".to_string())?;
+
+        for (modality, value) in modalities.iter() {
+          let profile =
+            parse_render_profile(&format!("modality.{modality}"), value)?;
+
+          profiles.insert(modality, profile);
+        }
+      }
+
+      _ => return Err(format!("Unknown key '{key}'")),
+    }
+  }
+
+  Ok(profiles)
+}
+
+fn parse_render_profile(
+  name: &str,
+  value: &toml::Value,
+) -> Result<RenderProfile, String> {
+  let table = value
+    .as_table()
+    .ok_or_else(|| format!("'{name}' must be a table"))?;
+
+  let mut profile = RenderProfile::default();
+
+  for (key, value) in table.iter() {
+    let invalid_value = || format!("Invalid value for '{name}.{key}'");
+
+    match key.as_str() {
+      "color-palette" => {
+        let color_palette = value
+          .as_str()
+          .ok_or_else(invalid_value)
+          .and_then(parse_color_palette)?;
+
+        profile.color_palette = Some(color_palette.color_palette());
+      }
+
+      "voi-window" => {
+        let window = value
+          .as_array()
+          .map(|a| a.iter().map(toml_value_to_f32).collect::<Option<Vec<_>>>())
+          .and_then(|window| match window.as_deref() {
+            Some([center, width]) if *width > 0.0 => Some((*center, *width)),
+            _ => None,
+          })
+          .ok_or_else(invalid_value)?;
+
+        profile.voi_window = Some(VoiWindow::new(
+          window.0,
+          window.1,
+          "".to_string(),
+          VoiLutFunction::LinearExact,
+        ));
+      }
+
+      "invert" => {
+        profile.invert = Some(value.as_bool().ok_or_else(invalid_value)?);
+      }
+
+      "grayscale-output-depth" => {
+        let depth = value
+          .as_str()
+          .and_then(|s| GrayscaleOutputDepthArg::from_str(s, true).ok())
+          .ok_or_else(invalid_value)?;
+
+        profile.grayscale_output_depth = Some(depth.grayscale_output_depth());
+      }
+
+      _ => return Err(format!("Unknown key '{name}.{key}'")),
+    }
+  }
+
+  Ok(profile)
+}
+
+fn toml_value_to_f32(value: &toml::Value) -> Option<f32> {
+  match value {
+    toml::Value::Integer(i) => Some(*i as f32),
+    toml::Value::Float(f) => Some(*f as f32),
+    _ => None,
+  }
+}
@@ -0,0 +1,130 @@
+use std::{
+  io::{Cursor, Seek, SeekFrom, Write},
+  sync::{Arc, Mutex},
+};
+
+use tiff::{
+  encoder::{Rational, TiffEncoder as TiffFileEncoder, colortype},
+  tags::ResolutionUnit,
+};
+
+/// Encodes one or more RGB or Luma frames into a TIFF file held in memory.
+/// Each frame is written as a separate page of the TIFF file.
+///
+pub struct TiffEncoder {
+  encoder: TiffFileEncoder<SharedBuffer>,
+  buffer: SharedBuffer,
+  pixel_spacing: Option<[f64; 2]>,
+}
+
+/// An in-memory buffer that the TIFF encoder writes into, which remains
+/// accessible so the completed TIFF file can be retrieved.
+///
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Cursor<Vec<u8>>>>);
+
+impl Write for SharedBuffer {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.0.lock().unwrap().write(buf)
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+impl Seek for SharedBuffer {
+  fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+    self.0.lock().unwrap().seek(pos)
+  }
+}
+
+impl TiffEncoder {
+  /// Creates a new TIFF encoder. If a pixel spacing is specified, in
+  /// millimeters per pixel in row then column order, then resolution tags are
+  /// written into every page of the TIFF file.
+  ///
+  pub fn new(pixel_spacing: Option<[f64; 2]>) -> Result<Self, String> {
+    let buffer = SharedBuffer::default();
+
+    let encoder =
+      TiffFileEncoder::new(buffer.clone()).map_err(|e| e.to_string())?;
+
+    Ok(Self {
+      encoder,
+      buffer,
+      pixel_spacing,
+    })
+  }
+
+  /// Adds a frame to the TIFF file as a new page.
+  ///
+  pub fn add_frame(
+    &mut self,
+    image: &image::DynamicImage,
+  ) -> Result<(), String> {
+    let (width, height) = (image.width(), image.height());
+
+    match image {
+      image::DynamicImage::ImageLuma8(image) => {
+        self.write_page::<colortype::Gray8>(width, height, image.as_raw())
+      }
+      image::DynamicImage::ImageLuma16(image) => {
+        self.write_page::<colortype::Gray16>(width, height, image.as_raw())
+      }
+      image::DynamicImage::ImageRgb16(image) => {
+        self.write_page::<colortype::RGB16>(width, height, image.as_raw())
+      }
+      image::DynamicImage::ImageRgb8(image) => {
+        self.write_page::<colortype::RGB8>(width, height, image.as_raw())
+      }
+      image => {
+        let image = image.to_rgb8();
+        self.write_page::<colortype::RGB8>(width, height, image.as_raw())
+      }
+    }
+  }
+
+  /// Completes the TIFF file and returns its bytes.
+  ///
+  pub fn finish(self) -> Vec<u8> {
+    drop(self.encoder);
+
+    core::mem::take(self.buffer.0.lock().unwrap().get_mut())
+  }
+
+  fn write_page<C: colortype::ColorType>(
+    &mut self,
+    width: u32,
+    height: u32,
+    data: &[C::Inner],
+  ) -> Result<(), String>
+  where
+    [C::Inner]: tiff::encoder::TiffValue,
+  {
+    let mut page = self
+      .encoder
+      .new_image::<C>(width, height)
+      .map_err(|e| e.to_string())?;
+
+    if let Some([row_spacing, column_spacing]) = self.pixel_spacing {
+      page.resolution_unit(ResolutionUnit::Centimeter);
+      page.x_resolution(pixels_per_centimeter(column_spacing));
+      page.y_resolution(pixels_per_centimeter(row_spacing));
+    }
+
+    page.write_data(data).map_err(|e| e.to_string())
+  }
+}
+
+/// Converts a pixel spacing in millimeters into a TIFF resolution in pixels
+/// per centimeter.
+///
+fn pixels_per_centimeter(spacing: f64) -> Rational {
+  let d = 10_000;
+  let n = (10.0 / spacing * f64::from(d))
+    .round()
+    .clamp(1.0, u32::MAX.into());
+
+  Rational { n: n as u32, d }
+}
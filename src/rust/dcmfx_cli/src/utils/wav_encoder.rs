@@ -0,0 +1,175 @@
+use dcmfx::waveform::{WaveformMultiplexGroup, WaveformSampleInterpretation};
+
+/// Encodes the samples of a waveform multiplex group into a WAV file. The WAV
+/// header is created up front from the multiplex group's details, after which
+/// the samples can be encoded in chunks and streamed out.
+///
+/// The linear sample interpretations are stored as PCM, with 8-bit samples
+/// converted to unsigned and 16-bit and 32-bit samples converted to signed as
+/// required by the WAV format. The µ-law and A-law sample interpretations are
+/// stored without expansion using the matching WAV format codes. 64-bit
+/// samples aren't supported.
+///
+pub struct WavEncoder {
+  sample_interpretation: WaveformSampleInterpretation,
+  data_size: u64,
+}
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_ALAW: u16 = 0x0006;
+const WAVE_FORMAT_MULAW: u16 = 0x0007;
+
+impl WavEncoder {
+  /// Creates a new WAV encoder for the given waveform multiplex group, and
+  /// returns it along with the bytes of the WAV header. The header declares the
+  /// length of all of the multiplex group's sample data.
+  ///
+  pub fn new(
+    multiplex_group: &WaveformMultiplexGroup,
+  ) -> Result<(Self, Vec<u8>), String> {
+    let sample_interpretation = multiplex_group.sample_interpretation();
+
+    let format_tag = match sample_interpretation {
+      WaveformSampleInterpretation::MuLawByte => WAVE_FORMAT_MULAW,
+      WaveformSampleInterpretation::ALawByte => WAVE_FORMAT_ALAW,
+      WaveformSampleInterpretation::SignedVeryLong
+      | WaveformSampleInterpretation::UnsignedVeryLong => {
+        return Err(format!(
+          "Waveform sample interpretation '{sample_interpretation}' can't be \
+           stored in a WAV file"
+        ));
+      }
+      _ => WAVE_FORMAT_PCM,
+    };
+
+    let sampling_frequency = multiplex_group.sampling_frequency().round();
+    if !(1.0..=f64::from(u32::MAX)).contains(&sampling_frequency) {
+      return Err(format!(
+        "Sampling frequency '{}' can't be stored in a WAV file",
+        multiplex_group.sampling_frequency()
+      ));
+    }
+    let sample_rate = sampling_frequency as u32;
+
+    let channels = multiplex_group.number_of_channels();
+    let bytes_per_sample = sample_interpretation.bytes_per_sample() as u16;
+    let block_align = channels * bytes_per_sample;
+    let byte_rate = sample_rate.saturating_mul(u32::from(block_align));
+
+    let data_size =
+      u64::from(multiplex_group.number_of_samples()) * u64::from(block_align);
+
+    // Non-PCM formats have a larger 'fmt ' chunk and require a 'fact' chunk
+    let is_pcm = format_tag == WAVE_FORMAT_PCM;
+    let fmt_chunk_size: u32 = if is_pcm { 16 } else { 18 };
+    let fact_chunk_size: u32 = if is_pcm { 0 } else { 12 };
+
+    // The RIFF size includes the pad byte that follows odd-length data
+    let riff_size = 4
+      + 8
+      + u64::from(fmt_chunk_size)
+      + u64::from(fact_chunk_size)
+      + 8
+      + data_size
+      + data_size % 2;
+    let riff_size = u32::try_from(riff_size)
+      .map_err(|_| "Waveform data is too large for a WAV file".to_string())?;
+
+    let mut header = Vec::with_capacity(58);
+
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&riff_size.to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&fmt_chunk_size.to_le_bytes());
+    header.extend_from_slice(&format_tag.to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&(bytes_per_sample * 8).to_le_bytes());
+
+    if !is_pcm {
+      header.extend_from_slice(&0u16.to_le_bytes());
+
+      header.extend_from_slice(b"fact");
+      header.extend_from_slice(&4u32.to_le_bytes());
+      header
+        .extend_from_slice(&multiplex_group.number_of_samples().to_le_bytes());
+    }
+
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&(data_size as u32).to_le_bytes());
+
+    Ok((
+      Self {
+        sample_interpretation,
+        data_size,
+      },
+      header,
+    ))
+  }
+
+  /// Encodes the raw stored values for every channel of a chunk of waveform
+  /// data into channel-interleaved WAV sample data.
+  ///
+  pub fn encode_samples(&self, channel_samples: &[Vec<i64>]) -> Vec<u8> {
+    let number_of_samples = channel_samples.first().map_or(0, Vec::len);
+
+    let mut data = Vec::with_capacity(
+      number_of_samples
+        * channel_samples.len()
+        * self.sample_interpretation.bytes_per_sample(),
+    );
+
+    for i in 0..number_of_samples {
+      for samples in channel_samples {
+        let value = samples[i];
+
+        match self.sample_interpretation {
+          WaveformSampleInterpretation::SignedByte => {
+            data.push((value + 128) as u8)
+          }
+
+          WaveformSampleInterpretation::UnsignedByte
+          | WaveformSampleInterpretation::MuLawByte
+          | WaveformSampleInterpretation::ALawByte => data.push(value as u8),
+
+          WaveformSampleInterpretation::SignedShort => {
+            data.extend_from_slice(&(value as i16).to_le_bytes())
+          }
+
+          WaveformSampleInterpretation::UnsignedShort => {
+            data.extend_from_slice(&((value - 0x8000) as i16).to_le_bytes())
+          }
+
+          WaveformSampleInterpretation::SignedLong => {
+            data.extend_from_slice(&(value as i32).to_le_bytes())
+          }
+
+          WaveformSampleInterpretation::UnsignedLong => data
+            .extend_from_slice(&((value - 0x8000_0000) as i32).to_le_bytes()),
+
+          WaveformSampleInterpretation::SignedVeryLong
+          | WaveformSampleInterpretation::UnsignedVeryLong => {
+            unreachable!("64-bit samples are rejected by WavEncoder::new()")
+          }
+        }
+      }
+    }
+
+    data
+  }
+
+  /// Returns the bytes that finish the WAV file once all sample data has been
+  /// written. This is a single pad byte when the sample data has odd length.
+  ///
+  pub fn finish(&self) -> Vec<u8> {
+    if self.data_size % 2 == 1 {
+      vec![0]
+    } else {
+      vec![]
+    }
+  }
+}
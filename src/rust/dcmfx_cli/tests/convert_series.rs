@@ -0,0 +1,135 @@
+mod utils;
+
+#[macro_use]
+mod assert_image_snapshot;
+
+use std::path::Path;
+
+use insta::assert_snapshot;
+
+use utils::{create_temp_dir, dcmfx_cli, get_stderr};
+
+const TEST_FILES: &str = "../../../test/assets/pydicom/test_files";
+
+#[test]
+fn png_series() {
+  let input_directory = create_temp_dir();
+  let output_directory = create_temp_dir();
+
+  copy_test_file("SC_rgb_small_odd.dcm", input_directory.path());
+  std::fs::create_dir(input_directory.path().join("MR")).unwrap();
+  copy_test_file("MR_small.dcm", &input_directory.path().join("MR"));
+  std::fs::write(input_directory.path().join("README"), "Not DICOM").unwrap();
+
+  dcmfx_cli()
+    .arg("convert-series")
+    .arg(input_directory.path())
+    .arg("--format")
+    .arg("png")
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .assert()
+    .success();
+
+  assert_snapshot!("png_series", output_filenames(output_directory.path()));
+
+  // Both series have the same Series Number, so their names include their
+  // Series Instance UIDs
+  assert_image_snapshot!(
+    output_directory.path().join(
+      "1_1.2.826.0.1.3680043.8.498.16157229083793556332623330502397121062\
+       .0000.png"
+    ),
+    "png_series.0000.png"
+  );
+}
+
+#[test]
+fn tiff_series() {
+  let input_directory = create_temp_dir();
+  let output_directory = create_temp_dir();
+
+  copy_test_file("CT_small.dcm", input_directory.path());
+
+  dcmfx_cli()
+    .arg("convert-series")
+    .arg(input_directory.path())
+    .arg("--format")
+    .arg("tiff")
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .assert()
+    .success();
+
+  assert_snapshot!("tiff_series", output_filenames(output_directory.path()));
+
+  let image = image::open(output_directory.path().join("1.tiff")).unwrap();
+  assert_eq!((image.width(), image.height()), (128, 128));
+}
+
+#[test]
+fn nifti_series() {
+  let input_directory = create_temp_dir();
+  let output_directory = create_temp_dir();
+
+  copy_test_file("CT_small.dcm", input_directory.path());
+
+  dcmfx_cli()
+    .arg("convert-series")
+    .arg(input_directory.path())
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .assert()
+    .success();
+
+  assert_snapshot!("nifti_series", output_filenames(output_directory.path()));
+
+  // The NIfTI-1 header and extension bytes are followed by 32-bit float voxels
+  let nifti = std::fs::read(output_directory.path().join("1.nii")).unwrap();
+  assert_eq!(nifti.len(), 352 + 128 * 128 * 4);
+  assert_eq!(&nifti[0..4], &348i32.to_le_bytes());
+  assert_eq!(&nifti[344..348], b"n+1\0");
+}
+
+#[test]
+fn nifti_series_without_image_plane() {
+  let input_directory = create_temp_dir();
+  let output_directory = create_temp_dir();
+
+  copy_test_file("SC_rgb_small_odd.dcm", input_directory.path());
+
+  let assert = dcmfx_cli()
+    .arg("convert-series")
+    .arg(input_directory.path())
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .assert()
+    .failure();
+
+  assert!(get_stderr(assert).contains("converting series \"1\""));
+  assert_eq!(output_filenames(output_directory.path()), "");
+}
+
+/// Copies a file from the pydicom test files into the given directory.
+///
+fn copy_test_file(filename: &str, directory: &Path) {
+  std::fs::copy(
+    Path::new(TEST_FILES).join(filename),
+    directory.join(filename),
+  )
+  .unwrap();
+}
+
+/// Returns the names of the files in the given directory, sorted and with one
+/// name per line.
+///
+fn output_filenames(directory: &Path) -> String {
+  let mut filenames: Vec<String> = std::fs::read_dir(directory)
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+    .collect();
+
+  filenames.sort();
+
+  filenames.join("\n")
+}
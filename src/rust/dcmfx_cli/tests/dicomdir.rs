@@ -0,0 +1,78 @@
+mod utils;
+
+use insta::assert_snapshot;
+
+use utils::{create_temp_dir, dcmfx_cli, get_stderr, get_stdout};
+
+#[test]
+fn create() {
+  let temp_dir = create_temp_dir();
+  std::fs::create_dir(temp_dir.path().join("A")).unwrap();
+
+  for (seed, filename) in [("1", "IMG1"), ("2", "IMG2")] {
+    dcmfx_cli()
+      .arg("generate")
+      .arg("--rows")
+      .arg("4")
+      .arg("--columns")
+      .arg("4")
+      .arg("--seed")
+      .arg(seed)
+      .arg("--output-filename")
+      .arg(temp_dir.path().join("A").join(filename))
+      .assert()
+      .success();
+  }
+
+  std::fs::write(temp_dir.path().join("README"), "Not DICOM").unwrap();
+
+  let dicomdir_path = temp_dir.path().join("DICOMDIR");
+
+  dcmfx_cli()
+    .arg("dicomdir")
+    .arg("create")
+    .arg("--file-set-id")
+    .arg("TEST")
+    .arg(temp_dir.path())
+    .assert()
+    .success()
+    .stdout(format!(
+      "Wrote \"{}\" with 8 directory records\n",
+      dicomdir_path.display()
+    ));
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&dicomdir_path)
+    .assert()
+    .success();
+
+  assert_snapshot!("create", get_stdout(assert));
+}
+
+#[test]
+fn create_with_invalid_file_id() {
+  let temp_dir = create_temp_dir();
+
+  dcmfx_cli()
+    .arg("generate")
+    .arg("--rows")
+    .arg("4")
+    .arg("--columns")
+    .arg("4")
+    .arg("--output-filename")
+    .arg(temp_dir.path().join("image.dcm"))
+    .assert()
+    .success();
+
+  let assert = dcmfx_cli()
+    .arg("dicomdir")
+    .arg("create")
+    .arg(temp_dir.path())
+    .assert()
+    .failure();
+
+  assert!(
+    get_stderr(assert).contains("File ID component 'image.dcm' is invalid")
+  );
+}
@@ -0,0 +1,113 @@
+mod utils;
+
+use insta::assert_snapshot;
+
+use utils::{create_temp_dir, dcmfx_cli, get_stderr, get_stdout};
+
+#[test]
+fn with_default_arguments() {
+  let temp_dir = create_temp_dir();
+  let output_path = temp_dir.path().join("output.dcm");
+
+  dcmfx_cli()
+    .arg("generate")
+    .arg("--output-filename")
+    .arg(&output_path)
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", output_path.display()));
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&output_path)
+    .assert()
+    .success();
+
+  assert_snapshot!("with_default_arguments", get_stdout(assert));
+}
+
+#[test]
+fn with_multi_frame_color_rle_lossless() {
+  let temp_dir = create_temp_dir();
+  let output_path = temp_dir.path().join("output.dcm");
+
+  dcmfx_cli()
+    .arg("generate")
+    .arg("--sop-class")
+    .arg("us-multi-frame")
+    .arg("--rows")
+    .arg("32")
+    .arg("--columns")
+    .arg("48")
+    .arg("--bits-allocated")
+    .arg("8")
+    .arg("--color")
+    .arg("--number-of-frames")
+    .arg("3")
+    .arg("--transfer-syntax")
+    .arg("rle-lossless")
+    .arg("--seed")
+    .arg("42")
+    .arg("--output-filename")
+    .arg(&output_path)
+    .assert()
+    .success();
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&output_path)
+    .assert()
+    .success();
+
+  assert_snapshot!("with_multi_frame_color_rle_lossless", get_stdout(assert));
+}
+
+#[test]
+fn is_reproducible() {
+  let temp_dir = create_temp_dir();
+  let output_paths = [
+    temp_dir.path().join("output_0.dcm"),
+    temp_dir.path().join("output_1.dcm"),
+  ];
+
+  for output_path in &output_paths {
+    dcmfx_cli()
+      .arg("generate")
+      .arg("--seed")
+      .arg("7")
+      .arg("--output-filename")
+      .arg(output_path)
+      .assert()
+      .success();
+  }
+
+  assert_eq!(
+    std::fs::read(&output_paths[0]).unwrap(),
+    std::fs::read(&output_paths[1]).unwrap()
+  );
+}
+
+#[test]
+fn with_invalid_config() {
+  let temp_dir = create_temp_dir();
+  let output_path = temp_dir.path().join("output.dcm");
+
+  let assert = dcmfx_cli()
+    .arg("generate")
+    .arg("--sop-class")
+    .arg("ct")
+    .arg("--number-of-frames")
+    .arg("2")
+    .arg("--output-filename")
+    .arg(&output_path)
+    .assert()
+    .failure();
+
+  assert!(!output_path.exists());
+
+  assert_snapshot!(
+    "with_invalid_config",
+    get_stderr(assert)
+      .replace(&output_path.display().to_string(), "output.dcm")
+  );
+}
@@ -1,12 +1,18 @@
 mod utils;
 
 use assert_cmd::Command;
+use insta::assert_snapshot;
 
 #[macro_use]
 mod assert_image_snapshot;
 use tempfile::TempDir;
 use utils::{create_temp_dir, dcmfx_cli, s3_get_object, to_native_path};
 
+use dcmfx::{
+  core::{DataSet, dictionary},
+  p10::DataSetP10Extensions,
+};
+
 #[test]
 fn single_bit_unaligned() {
   let input_file =
@@ -156,6 +162,26 @@ fn modality_lut_sequence() {
   assert_image_snapshot!(output_file, "modality_lut_sequence.png");
 }
 
+#[test]
+fn monochrome_32bit_to_png() {
+  let input_file = "../../../test/assets/pydicom/test_files/rtdose_1frame.dcm";
+  let (output_file, output_directory) =
+    prepare_outputs(input_file, ".0000.png");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("png")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  assert_image_snapshot!(output_file, "monochrome_32bit_to_png.png");
+}
+
 #[test]
 fn rle_lossless_to_jpg() {
   let input_file = "../../../test/assets/pydicom/test_files/MR_small_RLE.dcm";
@@ -241,6 +267,30 @@ fn rle_lossless_color_palette_to_jpg() {
   assert_image_snapshot!(output_file, "rle_lossless_color_palette_to_jpg.jpg");
 }
 
+#[test]
+fn jpeg_baseline_to_jpg_passthrough() {
+  let input_file =
+    "../../../test/assets/pydicom/test_files/SC_rgb_jpeg_dcmtk.dcm";
+  let (output_file, output_directory) =
+    prepare_outputs(input_file, ".0000.jpg");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("jpg")
+    .arg("--passthrough")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  let jpg_bytes = std::fs::read(&output_file).unwrap();
+  assert!(jpg_bytes.starts_with(&[0xFF, 0xD8]));
+  assert!(jpg_bytes.ends_with(&[0xFF, 0xD9]));
+}
+
 #[test]
 fn to_jpg_with_custom_window() {
   let input_file = "../../../test/assets/fo-dicom/GH177_D_CLUNIE_CT1_IVRLE_BigEndian_ELE_undefinded_length.dcm";
@@ -264,6 +314,61 @@ fn to_jpg_with_custom_window() {
   assert_image_snapshot!(output_file, "to_jpg_with_custom_window.jpg");
 }
 
+#[test]
+fn to_jpg_with_render_config() {
+  let input_file = "../../../test/assets/fo-dicom/GH177_D_CLUNIE_CT1_IVRLE_BigEndian_ELE_undefinded_length.dcm";
+  let (output_file, output_directory) =
+    prepare_outputs(input_file, ".0000.jpg");
+
+  let render_config = output_directory.path().join("render.toml");
+  std::fs::write(
+    &render_config,
+    "[default]\ninvert = true\n\n[modality.CT]\nvoi-window = [500, 2000]\n\
+     invert = false\n",
+  )
+  .unwrap();
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("jpg")
+    .arg("--render-config")
+    .arg(render_config)
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  assert_image_snapshot!(output_file, "to_jpg_with_custom_window.jpg");
+}
+
+#[test]
+fn invalid_render_config() {
+  let input_file = "../../../test/assets/fo-dicom/GH177_D_CLUNIE_CT1_IVRLE_BigEndian_ELE_undefinded_length.dcm";
+  let (_, output_directory) = prepare_outputs(input_file, ".0000.jpg");
+
+  let render_config = output_directory.path().join("render.toml");
+  std::fs::write(&render_config, "[modality.CT]\ncolor-palette = \"red\"\n")
+    .unwrap();
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("--render-config")
+    .arg(&render_config)
+    .assert()
+    .failure()
+    .stderr(format!(
+      "Error: Failed loading render config\n\nDetails: Invalid render config \
+       '{}': Invalid value for 'modality.CT.color-palette'\n",
+      render_config.display()
+    ));
+}
+
 #[test]
 fn missing_voi_lut_to_png() {
   let input_file =
@@ -305,6 +410,30 @@ fn palette_color_to_png() {
   assert_image_snapshot!(output_file, "palette_color_to_png.png");
 }
 
+#[test]
+fn palette_color_to_indexed_png() {
+  let input_file = "../../../test/assets/fo-dicom/TestPattern_Palette_16.dcm";
+  let (output_file, output_directory) =
+    prepare_outputs(input_file, ".0000.png");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("png")
+    .arg("--indexed-png")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  // Check the PNG's IHDR chunk specifies indexed color
+  assert_eq!(std::fs::read(&output_file).unwrap()[25], 3);
+
+  assert_image_snapshot!(output_file, "palette_color_to_png.png");
+}
+
 #[test]
 fn resize_using_lanczos3() {
   let input_file = "../../../test/assets/fo-dicom/TestPattern_Palette_16.dcm";
@@ -450,6 +579,57 @@ fn jpeg_2000_monochrome_to_png_16bit() {
   assert_image_snapshot!(output_file, "jpeg_2000_monochrome_to_png_16bit.png");
 }
 
+#[test]
+fn jpeg_2000_monochrome_to_png_16bit_linear() {
+  let input_file =
+    "../../../test/assets/pydicom/test_files/MR_small_jp2klossless.dcm";
+  let (output_file, output_directory) =
+    prepare_outputs(input_file, ".0000.png");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("png16")
+    .arg("--grayscale-output-depth")
+    .arg("linear16")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  assert_image_snapshot!(
+    output_file,
+    "jpeg_2000_monochrome_to_png_16bit_linear.png"
+  );
+}
+
+#[test]
+fn jpeg_extended_12bit_monochrome_to_png_16bit_stored_values() {
+  let input_file = "../../../test/assets/pydicom/test_files/JPEG-lossy.dcm";
+  let (output_file, output_directory) =
+    prepare_outputs(input_file, ".0000.png");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("png16")
+    .arg("--grayscale-output-depth")
+    .arg("stored-values")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  assert_image_snapshot!(
+    output_file,
+    "jpeg_extended_12bit_monochrome_to_png_16bit_stored_values.png"
+  );
+}
+
 #[test]
 fn jpeg_2000_color_to_png() {
   let input_file =
@@ -932,6 +1112,86 @@ fn single_bit_unaligned_to_mp4_h264() {
   assert_eq!(get_video_frame_count(&output_file), Ok(3));
 }
 
+#[test]
+fn series_to_mp4() {
+  let input_directory = create_temp_dir();
+  let output_directory = create_temp_dir();
+
+  // Generate three single-frame instances in the same series, with instance
+  // numbers that are the reverse of their file name order
+  let mut input_files = vec![];
+  for (seed, instance_number) in [(1, 3), (2, 2), (3, 1)] {
+    let generated_file = input_directory.path().join(format!("{seed}.tmp"));
+    let input_file = input_directory.path().join(format!("{seed}.dcm"));
+
+    dcmfx_cli()
+      .arg("generate")
+      .arg("--rows")
+      .arg("16")
+      .arg("--columns")
+      .arg("16")
+      .arg("--seed")
+      .arg(seed.to_string())
+      .arg("--output-filename")
+      .arg(&generated_file)
+      .assert()
+      .success();
+
+    dcmfx_cli()
+      .arg("modify")
+      .arg(&generated_file)
+      .arg("--output-filename")
+      .arg(&input_file)
+      .arg("--merge-dicom-json")
+      .arg(format!(
+        r#"{{"0020000E":{{"vr":"UI","Value":["1.2.3"]}},"00200013":{{"vr":"IS","Value":[{instance_number}]}}}}"#
+      ))
+      .assert()
+      .success();
+
+    input_files.push(input_file);
+  }
+
+  let output_file = format!(
+    "{}{}3.dcm.mp4",
+    output_directory.path().display(),
+    std::path::MAIN_SEPARATOR
+  );
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .args(&input_files)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("mp4")
+    .arg("--series-to-video")
+    .arg("--mp4-preset")
+    .arg("fast")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{0}\" …\n", to_native_path(&output_file)));
+
+  assert_eq!(get_video_frame_count(&output_file), Ok(3));
+}
+
+#[test]
+fn series_to_video_requires_mp4_format() {
+  let input_file = "../../../test/assets/pydicom/test_files/CT_small.dcm";
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("-f")
+    .arg("png")
+    .arg("--series-to-video")
+    .assert()
+    .failure()
+    .stderr(
+      "Error: --series-to-video requires the output format to be 'mp4'\n",
+    );
+}
+
 #[test]
 fn single_bit_unaligned_to_mp4_h265() {
   let input_file =
@@ -974,6 +1234,140 @@ fn single_bit_unaligned_to_mp4_h265() {
   assert_eq!(get_video_frame_count(&output_file), Ok(3));
 }
 
+#[test]
+fn jpeg_2000_monochrome_to_tiff() {
+  let input_file =
+    "../../../test/assets/pydicom/test_files/MR_small_jp2klossless.dcm";
+  let (output_file, output_directory) =
+    prepare_outputs(input_file, ".0000.tiff");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("tiff")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  let mut decoder =
+    tiff::decoder::Decoder::new(std::fs::File::open(&output_file).unwrap())
+      .unwrap();
+
+  assert_eq!(decoder.dimensions().unwrap(), (64, 64));
+  assert_eq!(decoder.colortype().unwrap(), tiff::ColorType::Gray(16));
+  assert_eq!(
+    decoder.get_tag(tiff::tags::Tag::XResolution).unwrap(),
+    tiff::decoder::ifd::Value::Rational(320_000, 10_000)
+  );
+  assert_eq!(
+    decoder
+      .get_tag_u32(tiff::tags::Tag::ResolutionUnit)
+      .unwrap(),
+    u32::from(tiff::tags::ResolutionUnit::Centimeter.to_u16())
+  );
+  assert!(!decoder.more_images());
+}
+
+#[test]
+fn single_bit_unaligned_to_multi_page_tiff() {
+  let input_file =
+    "../../../test/assets/pydicom/test_files/liver_nonbyte_aligned.dcm";
+  let (output_file, output_directory) = prepare_outputs(input_file, ".tiff");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("tiff")
+    .arg("--tiff-multi-page")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  let mut decoder =
+    tiff::decoder::Decoder::new(std::fs::File::open(&output_file).unwrap())
+      .unwrap();
+
+  let mut page_count = 1;
+  while decoder.more_images() {
+    decoder.next_image().unwrap();
+    page_count += 1;
+  }
+
+  assert_eq!(page_count, 3);
+  assert_eq!(decoder.dimensions().unwrap(), (510, 510));
+}
+
+#[test]
+fn single_bit_unaligned_to_gif() {
+  let input_file =
+    "../../../test/assets/pydicom/test_files/liver_nonbyte_aligned.dcm";
+  let (output_file, output_directory) = prepare_outputs(input_file, ".gif");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("gif")
+    .arg("--fps")
+    .arg("4")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(
+    std::fs::File::open(&output_file).unwrap(),
+  ))
+  .unwrap();
+
+  let frames = image::AnimationDecoder::into_frames(decoder)
+    .collect_frames()
+    .unwrap();
+
+  assert_eq!(frames.len(), 3);
+  assert_eq!(frames[0].buffer().dimensions(), (510, 510));
+  assert_eq!(frames[0].delay().numer_denom_ms(), (250, 1));
+}
+
+#[test]
+fn single_bit_unaligned_to_apng() {
+  let input_file =
+    "../../../test/assets/pydicom/test_files/liver_nonbyte_aligned.dcm";
+  let (output_file, output_directory) = prepare_outputs(input_file, ".png");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("apng")
+    .assert()
+    .success()
+    .stdout(format!("Writing \"{}\" …\n", to_native_path(&output_file)));
+
+  let decoder = png::Decoder::new(std::io::BufReader::new(
+    std::fs::File::open(&output_file).unwrap(),
+  ));
+  let reader = decoder.read_info().unwrap();
+
+  assert_eq!(reader.info().size(), (510, 510));
+  assert_eq!(
+    reader
+      .info()
+      .animation_control
+      .map(|animation_control| animation_control.num_frames),
+    Some(3)
+  );
+}
+
 #[test]
 fn render_overlays_and_rotate90() {
   let input_file =
@@ -1096,6 +1490,61 @@ fn with_selected_frames() {
   }
 }
 
+#[test]
+fn with_time_range() {
+  let temp_dir = create_temp_dir();
+  let input_file = temp_dir.path().join("cine.dcm");
+
+  // Generated multi-frame files have a Frame Time of 40ms
+  dcmfx_cli()
+    .arg("generate")
+    .arg("--sop-class")
+    .arg("us-multi-frame")
+    .arg("--rows")
+    .arg("4")
+    .arg("--columns")
+    .arg("4")
+    .arg("--bits-allocated")
+    .arg("8")
+    .arg("--number-of-frames")
+    .arg("10")
+    .arg("--output-filename")
+    .arg(&input_file)
+    .assert()
+    .success();
+
+  let (output_file, output_directory) = prepare_outputs(&input_file, "");
+
+  let test_cases = [
+    ("0.1s-0.2s", vec![2, 3, 4]),
+    ("0.3s-", vec![7, 8, 9]),
+    ("0s-0s", vec![0]),
+  ];
+
+  for (time_range, expected_frames) in test_cases {
+    let expected_output = expected_frames
+      .iter()
+      .map(|f| {
+        format!(
+          "Writing \"{}.{:04}.bin\" …\n",
+          to_native_path(&output_file),
+          f
+        )
+      })
+      .collect::<String>();
+
+    dcmfx_cli()
+      .arg("get-pixel-data")
+      .arg(&input_file)
+      .arg("--output-directory")
+      .arg(output_directory.path())
+      .arg(format!("--time-range={}", time_range))
+      .assert()
+      .success()
+      .stdout(expected_output);
+  }
+}
+
 #[tokio::test]
 #[ignore]
 async fn with_s3_input_and_output() {
@@ -1141,6 +1590,68 @@ fn read_xa_modality() {
   assert_image_snapshot!(output_file, "xa_modality.jpg");
 }
 
+#[test]
+fn per_frame_voi_lut() {
+  let temp_dir = create_temp_dir();
+  let input_file = temp_dir.path().join("per_frame_voi_lut.dcm");
+  write_per_frame_voi_lut_input(&input_file);
+
+  // Each frame is rendered with its own VOI window
+  let (output_file, output_directory) = prepare_outputs(&input_file, "");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(&input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("png")
+    .arg("--sidecar")
+    .arg("json")
+    .assert()
+    .success();
+
+  assert_image_snapshot!(
+    format!("{output_file}.0000.png"),
+    "per_frame_voi_lut.0000.png"
+  );
+  assert_image_snapshot!(
+    format!("{output_file}.0001.png"),
+    "per_frame_voi_lut.0001.png"
+  );
+
+  // All frames are rendered with the VOI window of the first frame
+  let (uniform_output_file, uniform_output_directory) =
+    prepare_outputs(&input_file, "");
+
+  dcmfx_cli()
+    .arg("get-pixel-data")
+    .arg(&input_file)
+    .arg("--output-directory")
+    .arg(uniform_output_directory.path())
+    .arg("-f")
+    .arg("png")
+    .arg("--sidecar")
+    .arg("json")
+    .arg("--uniform-voi-window")
+    .assert()
+    .success();
+
+  assert_image_snapshot!(
+    format!("{uniform_output_file}.0001.png"),
+    "per_frame_voi_lut_uniform.0001.png"
+  );
+
+  assert_snapshot!(
+    "per_frame_voi_lut_windows",
+    format!(
+      "{}\n{}",
+      sidecar_voi_windows(&output_file, 4),
+      sidecar_voi_windows(&uniform_output_file, 4)
+    )
+  );
+}
+
 /// For a given input file, returns a newly created temporary output directory
 /// and the path to the output file in that directory for the input file.
 ///
@@ -1163,6 +1674,88 @@ fn prepare_outputs<P: AsRef<std::path::Path>>(
   (output_file, output_directory)
 }
 
+/// Writes a copy of the four-frame Enhanced MR test file that has a Frame VOI
+/// LUT Sequence in each frame's functional groups. Even frames have a narrow
+/// VOI window, and odd frames have a wide VOI window.
+///
+fn write_per_frame_voi_lut_input(path: &std::path::Path) {
+  let mut data_set = DataSet::read_p10_file(
+    "../../../test/assets/fo-dicom/mr_brucker.dcm",
+    None,
+  )
+  .unwrap();
+
+  let mut per_frame_items = data_set
+    .get_sequence_items(dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag)
+    .unwrap()
+    .to_vec();
+
+  for (i, item) in per_frame_items.iter_mut().enumerate() {
+    let (center, width, explanation) = if i % 2 == 0 {
+      (600.0, 1000.0, "NARROW")
+    } else {
+      (6000.0, 12000.0, "WIDE")
+    };
+
+    let mut frame_voi_lut = DataSet::new();
+    frame_voi_lut
+      .insert_float_value(&dictionary::WINDOW_CENTER, &[center])
+      .unwrap();
+    frame_voi_lut
+      .insert_float_value(&dictionary::WINDOW_WIDTH, &[width])
+      .unwrap();
+    frame_voi_lut
+      .insert_string_value(
+        &dictionary::WINDOW_CENTER_WIDTH_EXPLANATION,
+        &[explanation],
+      )
+      .unwrap();
+
+    item
+      .insert_sequence_value(
+        &dictionary::FRAME_VOILUT_SEQUENCE,
+        vec![frame_voi_lut],
+      )
+      .unwrap();
+  }
+
+  data_set
+    .insert_sequence_value(
+      &dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+      per_frame_items,
+    )
+    .unwrap();
+
+  data_set.write_p10_file(path, None).unwrap();
+}
+
+/// Returns the VOI window recorded in the JSON sidecar of each frame, one per
+/// line.
+///
+fn sidecar_voi_windows(output_file: &str, frame_count: usize) -> String {
+  (0..frame_count)
+    .map(|frame_index| {
+      let sidecar: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(format!(
+          "{output_file}.{frame_index:04}.json"
+        ))
+        .unwrap(),
+      )
+      .unwrap();
+
+      let voi_window = &sidecar["voi_window"];
+
+      format!(
+        "{frame_index}: center={}, width={}, function={}, explanation={}\n",
+        voi_window["center"].as_f64().unwrap(),
+        voi_window["width"].as_f64().unwrap(),
+        voi_window["function"].as_str().unwrap(),
+        voi_window["explanation"].as_str().unwrap(),
+      )
+    })
+    .collect()
+}
+
 /// Returns the number of frames in the specified video file.
 ///
 fn get_video_frame_count(path: &str) -> Result<u32, String> {
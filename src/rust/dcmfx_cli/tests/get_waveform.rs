@@ -0,0 +1,148 @@
+mod utils;
+
+use insta::assert_snapshot;
+use tempfile::TempDir;
+use utils::{dcmfx_cli, get_stderr, to_native_path};
+
+#[test]
+fn multi_group_to_csv() {
+  let input_file =
+    "../../../test/assets/other/waveform_ecg_synthetic_multi_group.dcm";
+  let (output_file, output_directory) = prepare_outputs(input_file);
+
+  dcmfx_cli()
+    .arg("get-waveform")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .assert()
+    .success()
+    .stdout(
+      (0..6)
+        .map(|i| {
+          format!(
+            "Writing \"{}\" …\n",
+            to_native_path(&format!("{output_file}.{i}.csv"))
+          )
+        })
+        .collect::<String>(),
+    );
+
+  assert_snapshot!(
+    "multi_group_to_csv",
+    std::fs::read_to_string(format!("{output_file}.1.csv")).unwrap()
+  );
+}
+
+#[test]
+fn ecg_to_csv_with_physical_and_stored_values() {
+  let input_file =
+    "../../../test/assets/other/waveform_ecg_synthetic_12_lead.dcm";
+  let (output_file, output_directory) = prepare_outputs(input_file);
+
+  dcmfx_cli()
+    .arg("get-waveform")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .assert()
+    .success();
+
+  let physical_values =
+    std::fs::read_to_string(format!("{output_file}.0.csv")).unwrap();
+
+  dcmfx_cli()
+    .arg("get-waveform")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("--stored-values")
+    .arg("--overwrite")
+    .assert()
+    .success();
+
+  let stored_values =
+    std::fs::read_to_string(format!("{output_file}.0.csv")).unwrap();
+
+  assert_snapshot!(
+    "ecg_to_csv_with_physical_and_stored_values",
+    physical_values
+      .lines()
+      .take(4)
+      .chain(stored_values.lines().take(4))
+      .collect::<Vec<_>>()
+      .join("\n")
+  );
+}
+
+#[test]
+fn ecg_to_wav() {
+  let input_file =
+    "../../../test/assets/other/waveform_ecg_synthetic_12_lead.dcm";
+  let (output_file, output_directory) = prepare_outputs(input_file);
+
+  dcmfx_cli()
+    .arg("get-waveform")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("wav")
+    .assert()
+    .success();
+
+  let wav = std::fs::read(format!("{output_file}.0.wav")).unwrap();
+
+  // 2000 samples of 12 channels of 16-bit PCM at 500 Hz
+  assert_eq!(&wav[0..4], b"RIFF");
+  assert_eq!(&wav[8..16], b"WAVEfmt ");
+  assert_eq!(u16::from_le_bytes([wav[20], wav[21]]), 1);
+  assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 12);
+  assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 500);
+  assert_eq!(u16::from_le_bytes([wav[34], wav[35]]), 16);
+  assert_eq!(&wav[36..40], b"data");
+  assert_eq!(
+    u32::from_le_bytes(wav[40..44].try_into().unwrap()),
+    2000 * 12 * 2
+  );
+  assert_eq!(wav.len(), 44 + 2000 * 12 * 2);
+}
+
+#[test]
+fn errors_on_64_bit_samples_to_wav() {
+  let input_file =
+    "../../../test/assets/other/waveform_ecg_synthetic_multi_group.dcm";
+  let (_, output_directory) = prepare_outputs(input_file);
+
+  let assert = dcmfx_cli()
+    .arg("get-waveform")
+    .arg(input_file)
+    .arg("--output-directory")
+    .arg(output_directory.path())
+    .arg("-f")
+    .arg("wav")
+    .assert()
+    .failure();
+
+  assert!(get_stderr(assert).contains(
+    "Error: Waveform sample interpretation 'SV' can't be stored in a WAV file"
+  ));
+}
+
+/// For a given input file, returns a newly created temporary output directory
+/// and the path to the output file in that directory for the input file.
+///
+fn prepare_outputs<P: AsRef<std::path::Path>>(
+  input_file: P,
+) -> (String, TempDir) {
+  let output_directory = utils::create_temp_dir();
+
+  let output_file = format!(
+    "{}{}{}",
+    output_directory.path().display(),
+    std::path::MAIN_SEPARATOR,
+    input_file.as_ref().file_name().unwrap().display(),
+  );
+
+  (output_file, output_directory)
+}
@@ -173,6 +173,42 @@ fn with_multiple_inputs() {
   assert_snapshot!("with_multiple_inputs_1", get_stdout(assert));
 }
 
+#[test]
+fn with_missing_file_meta_information() {
+  let temp_dir = create_temp_dir();
+  let input_path = temp_dir.path().join("input.json");
+  let output_path = temp_dir.path().join("output.dcm");
+
+  std::fs::write(
+    &input_path,
+    r#"{
+  "00080016": { "vr": "UI", "Value": ["1.2.840.10008.5.1.4.1.1.7"] },
+  "00080018": { "vr": "UI", "Value": ["1.2.3.4"] }
+}"#,
+  )
+  .unwrap();
+
+  dcmfx_cli()
+    .arg("json-to-dcm")
+    .arg(&input_path)
+    .arg("--output-filename")
+    .arg(&output_path)
+    .arg("--implementation-version-name")
+    .arg("DCMfx Test")
+    .arg("--transfer-syntax")
+    .arg("explicit-vr-little-endian")
+    .assert()
+    .success();
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&output_path)
+    .assert()
+    .success();
+
+  assert_snapshot!("with_missing_file_meta_information", get_stdout(assert));
+}
+
 #[tokio::test]
 #[ignore]
 async fn with_s3_input_and_output() {
@@ -202,3 +238,48 @@ async fn with_s3_input_and_output() {
 
   assert_snapshot!("with_s3_input_and_output", get_stdout(assert));
 }
+
+#[test]
+fn with_frames_dir() {
+  let temp_dir = create_temp_dir();
+  let input_path = temp_dir.path().join("input.json");
+  let output_path = temp_dir.path().join("output.dcm");
+  let frames_dir = temp_dir.path().join("frames");
+
+  std::fs::write(
+    &input_path,
+    r#"{
+  "00080016": { "vr": "UI", "Value": ["1.2.840.10008.5.1.4.1.1.7"] },
+  "00080018": { "vr": "UI", "Value": ["1.2.3.4"] },
+  "00280008": { "vr": "IS", "Value": [2] },
+  "7FE00010": { "vr": "OB", "BulkDataURI": "http://example.com/frames" }
+}"#,
+  )
+  .unwrap();
+
+  std::fs::create_dir(&frames_dir).unwrap();
+  std::fs::write(frames_dir.join("0001.jpg"), [1, 2, 3]).unwrap();
+  std::fs::write(frames_dir.join("0002.jpg"), [4, 5, 6, 7]).unwrap();
+
+  dcmfx_cli()
+    .arg("json-to-dcm")
+    .arg(&input_path)
+    .arg("--output-filename")
+    .arg(&output_path)
+    .arg("--implementation-version-name")
+    .arg("DCMfx Test")
+    .arg("--transfer-syntax")
+    .arg("jpeg-baseline-8bit")
+    .arg("--frames-dir")
+    .arg(&frames_dir)
+    .assert()
+    .success();
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&output_path)
+    .assert()
+    .success();
+
+  assert_snapshot!("with_frames_dir", get_stdout(assert));
+}
@@ -294,6 +294,207 @@ fn delete_private_tags() {
   assert_snapshot!("delete_private_tags_after", get_stdout(assert));
 }
 
+#[test]
+fn copy_through_without_deletions_is_byte_identical() {
+  let temp_dir = create_temp_dir();
+
+  let input_file = "../../../test/assets/fo-dicom/CT-MONO2-16-ankle.dcm";
+  let output_file = temp_dir.path().join("output.dcm");
+
+  dcmfx_cli()
+    .arg("modify")
+    .arg(input_file)
+    .arg("--output-filename")
+    .arg(&output_file)
+    .arg("--copy-through")
+    .assert()
+    .success();
+
+  assert_eq!(
+    std::fs::read(input_file).unwrap(),
+    std::fs::read(&output_file).unwrap()
+  );
+}
+
+#[test]
+fn frame_buffer_memory_limit_spills_frames_to_disk() {
+  let temp_dir = create_temp_dir();
+
+  let input_file = "../../../test/assets/pydicom/test_files/rtdose.dcm";
+
+  let transcode = |output_file: &std::path::Path, extra_args: &[&str]| {
+    dcmfx_cli()
+      .arg("modify")
+      .arg(input_file)
+      .arg("--output-filename")
+      .arg(output_file)
+      .arg("--transfer-syntax")
+      .arg("rle-lossless")
+      .arg("--max-fragment-size")
+      .arg("64")
+      .args(extra_args)
+      .assert()
+      .success();
+
+    std::fs::read(output_file).unwrap()
+  };
+
+  // With a memory limit of zero every encoded frame is spilled to disk, which
+  // must not alter the output
+  assert_eq!(
+    transcode(&temp_dir.path().join("output0.dcm"), &[]),
+    transcode(
+      &temp_dir.path().join("output1.dcm"),
+      &["--frame-buffer-memory-limit", "0"]
+    )
+  );
+}
+
+#[test]
+fn copy_through_with_deletions() {
+  let temp_dir = create_temp_dir();
+
+  let input_file = "../../../test/assets/fo-dicom/CT-MONO2-16-ankle.dcm";
+  let output_file = temp_dir.path().join("output.dcm");
+
+  dcmfx_cli()
+    .arg("modify")
+    .arg(input_file)
+    .arg("--output-filename")
+    .arg(&output_file)
+    .arg("--copy-through")
+    .arg("--delete")
+    .arg("00181020")
+    .arg("--delete-private")
+    .assert()
+    .success();
+
+  let input_bytes = std::fs::read(input_file).unwrap();
+  let output_bytes = std::fs::read(&output_file).unwrap();
+
+  // The pixel data is the final data element and must be copied verbatim
+  let pixel_data_length = 512 * 512 * 2;
+  assert!(output_bytes.len() < input_bytes.len());
+  assert_eq!(
+    input_bytes[input_bytes.len() - pixel_data_length..],
+    output_bytes[output_bytes.len() - pixel_data_length..]
+  );
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&output_file)
+    .assert()
+    .success();
+
+  let output = get_stdout(assert);
+  assert!(!output.contains("(0018,1020)"));
+  assert!(output.contains("(7FE0,0010)"));
+}
+
+#[test]
+fn errors_on_copy_through_with_transfer_syntax() {
+  let assert = dcmfx_cli()
+    .arg("modify")
+    .arg("--copy-through")
+    .arg("--transfer-syntax")
+    .arg("explicit-vr-little-endian")
+    .arg("input.dcm")
+    .arg("--in-place")
+    .assert()
+    .failure();
+
+  assert_snapshot!(
+    "errors_on_copy_through_with_transfer_syntax",
+    get_stderr(assert)
+  );
+}
+
+#[test]
+fn delete_overlays_and_icon_image() {
+  let temp_dir = create_temp_dir();
+
+  let input_file =
+    "../../../test/assets/pydicom/test_files/examples_overlay.dcm";
+  let output_file = temp_dir.path().join("output.dcm");
+
+  dcmfx_cli()
+    .arg("modify")
+    .arg(input_file)
+    .arg("--output-filename")
+    .arg(&output_file)
+    .arg("--delete-overlays")
+    .arg("--delete-curves")
+    .arg("--delete-icon-image")
+    .assert()
+    .success();
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&output_file)
+    .assert()
+    .success();
+
+  let output = get_stdout(assert);
+  assert!(!output.contains("(6000,"));
+  assert!(!output.contains("(0088,0200)"));
+  assert!(output.contains("(7FE0,0010)"));
+}
+
+#[test]
+fn insert_overlay() {
+  let temp_dir = create_temp_dir();
+
+  let input_file =
+    "../../../test/assets/pydicom/test_files/examples_overlay.dcm";
+  let overlay_file = temp_dir.path().join("overlay.png");
+  let output_file = temp_dir.path().join("output.dcm");
+
+  image::GrayImage::from_raw(5, 2, vec![255, 0, 0, 0, 0, 0, 0, 0, 255, 255])
+    .unwrap()
+    .save(&overlay_file)
+    .unwrap();
+
+  dcmfx_cli()
+    .arg("modify")
+    .arg(input_file)
+    .arg("--output-filename")
+    .arg(&output_file)
+    .arg("--insert-overlay")
+    .arg(&overlay_file)
+    .assert()
+    .success();
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg(&output_file)
+    .assert()
+    .success();
+
+  // The existing overlay in group 6000 is replaced as a whole
+  let output = get_stdout(assert);
+  assert!(output.contains("(6000,0010) US Overlay Rows"));
+  assert!(output.contains("(6000,0011) US Overlay Columns"));
+  assert!(!output.contains("(6000,0022)"));
+  assert!(output.contains("[01 03]"));
+}
+
+#[test]
+fn errors_on_copy_through_with_delete_overlays() {
+  let assert = dcmfx_cli()
+    .arg("modify")
+    .arg("--copy-through")
+    .arg("--delete-overlays")
+    .arg("input.dcm")
+    .arg("--in-place")
+    .assert()
+    .failure();
+
+  assert_snapshot!(
+    "errors_on_copy_through_with_delete_overlays",
+    get_stderr(assert)
+  );
+}
+
 #[test]
 fn dicom_sr_explicit_vr_little_endian_to_implicit_vr_little_endian() {
   modify_transfer_syntax(
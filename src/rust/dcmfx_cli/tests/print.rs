@@ -179,3 +179,17 @@ fn print_command_stdout_sorted(input_file: &str) -> String {
     .collect::<Vec<_>>()
     .join("\n")
 }
+
+#[test]
+fn with_sr_tree() {
+  let input_file = "../../../test/assets/pydicom/test_files/test-SR.dcm";
+
+  let assert = dcmfx_cli()
+    .arg("print")
+    .arg("--sr-tree")
+    .arg(input_file)
+    .assert()
+    .success();
+
+  assert_snapshot!("with_sr_tree", get_stdout(assert));
+}
@@ -0,0 +1,28 @@
+mod utils;
+
+use insta::assert_snapshot;
+use utils::{dcmfx_cli, get_stdout};
+
+#[test]
+fn with_image_hash() {
+  let assert = dcmfx_cli()
+    .arg("stats")
+    .arg("../../../test/assets/pydicom/test_files/CT_small.dcm")
+    .arg("../../../test/assets/pydicom/test_files/SC_rgb_small_odd.dcm")
+    .arg("--image-hash")
+    .assert()
+    .success();
+
+  assert_snapshot!("with_image_hash", get_stdout(assert));
+}
+
+#[test]
+fn with_no_pixel_data() {
+  let assert = dcmfx_cli()
+    .arg("stats")
+    .arg("../../../test/assets/pydicom/test_files/rtplan.dcm")
+    .assert()
+    .success();
+
+  assert_snapshot!("with_no_pixel_data", get_stdout(assert));
+}
@@ -0,0 +1,25 @@
+use dcmfx_cli::utils::{codec_thread_count, set_thread_count, task_count};
+
+// The thread count is global state that can only be set once, so everything is
+// checked in a single test
+#[test]
+fn task_and_codec_thread_counts() {
+  assert_eq!(task_count(None), num_cpus::get());
+  assert_eq!(task_count(Some(3)), 3);
+  assert_eq!(task_count(Some(0)), 1);
+  assert_eq!(codec_thread_count(None), None);
+  assert_eq!(codec_thread_count(Some(3)), None);
+
+  set_thread_count(8);
+
+  assert_eq!(task_count(None), 8);
+  assert_eq!(task_count(Some(3)), 3);
+  assert_eq!(codec_thread_count(None), Some(1));
+  assert_eq!(codec_thread_count(Some(2)), Some(4));
+  assert_eq!(codec_thread_count(Some(3)), Some(2));
+  assert_eq!(codec_thread_count(Some(16)), Some(1));
+
+  // The thread count can't be changed once set
+  set_thread_count(2);
+  assert_eq!(task_count(None), 8);
+}
@@ -17,6 +17,12 @@ pub fn dcmfx_cli() -> Command {
     .env("AWS_REGION", "us-east-1")
     .env("AWS_ENDPOINT_URL", "http://localhost:4566");
 
+  // Prevent any render config in the user's configuration directory from
+  // affecting test outputs
+  cmd
+    .env("XDG_CONFIG_HOME", temp_dir().join("dcmfx-test-config"))
+    .env("APPDATA", temp_dir().join("dcmfx-test-config"));
+
   cmd
 }
 
@@ -78,6 +78,27 @@ impl DataElementTag {
     self.is_private() && (0x10..=0xFF).contains(&self.element)
   }
 
+  /// Creates a new private data element tag in the specified private group for
+  /// the given private creator block and element offset within that block,
+  /// i.e. `(gggg,BBOO)` where `BB` is the block and `OO` is the offset.
+  ///
+  /// The private creator block is the element number of the *'(gggg,00BB)
+  /// Private Creator'* data element that reserves the block, and must be in
+  /// the range 0x10-0xFF.
+  ///
+  /// Ref: PS3.5 7.8.1.
+  ///
+  pub fn new_private(
+    group: u16,
+    private_creator_block: u8,
+    offset: u8,
+  ) -> Self {
+    Self {
+      group,
+      element: (u16::from(private_creator_block) << 8) | u16::from(offset),
+    }
+  }
+
   /// Returns a copy of this tag with an updated group value.
   ///
   pub fn with_group(&self, group: u16) -> Self {
@@ -176,6 +197,19 @@ mod tests {
     assert!(!DataElementTag::new(0x0001, 0x000F).is_private_creator());
   }
 
+  #[test]
+  fn new_private_test() {
+    assert_eq!(
+      DataElementTag::new_private(0x0009, 0x10, 0x02),
+      DataElementTag::new(0x0009, 0x1002)
+    );
+
+    assert_eq!(
+      DataElementTag::new_private(0x0029, 0xFF, 0xFF),
+      DataElementTag::new(0x0029, 0xFFFF)
+    );
+  }
+
   #[test]
   fn to_int_test() {
     assert_eq!(DataElementTag::new(0x1122, 0x3344).to_int(), 0x11223344);
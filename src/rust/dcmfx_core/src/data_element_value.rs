@@ -786,6 +786,146 @@ impl DataElementValue {
     Self::new_binary(ValueRepresentation::UnsignedVeryLong, bytes.into())
   }
 
+  /// Creates a new data element value with a string VR from any iterator of
+  /// strings, e.g. a `Vec<String>` or a `&[&str]`. VRs that don't allow
+  /// multiple values require exactly one string.
+  ///
+  pub fn new_strings<I, S>(
+    vr: ValueRepresentation,
+    value: I,
+  ) -> Result<Self, DataError>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+  {
+    let value = value.into_iter().collect::<Vec<S>>();
+    let value = value.iter().map(|s| s.as_ref()).collect::<Vec<&str>>();
+
+    match (vr, value.as_slice()) {
+      (ValueRepresentation::ApplicationEntity, [value]) => {
+        Self::new_application_entity(value)
+      }
+      (ValueRepresentation::CodeString, _) => Self::new_code_string(&value),
+      (ValueRepresentation::LongString, _) => Self::new_long_string(&value),
+      (ValueRepresentation::LongText, [value]) => Self::new_long_text(value),
+      (ValueRepresentation::ShortString, _) => Self::new_short_string(&value),
+      (ValueRepresentation::ShortText, [value]) => Self::new_short_text(value),
+      (ValueRepresentation::UniqueIdentifier, _) => {
+        Self::new_unique_identifier(&value)
+      }
+      (ValueRepresentation::UniversalResourceIdentifier, [value]) => {
+        Self::new_universal_resource_identifier(value)
+      }
+      (ValueRepresentation::UnlimitedCharacters, _) => {
+        Self::new_unlimited_characters(&value)
+      }
+      (ValueRepresentation::UnlimitedText, [value]) => {
+        Self::new_unlimited_text(value)
+      }
+
+      _ => Err(DataError::new_value_invalid(format!(
+        "{} string values can't be stored in the {} VR",
+        value.len(),
+        vr
+      ))),
+    }
+  }
+
+  /// Creates a new data element value with an integer VR from any iterator of
+  /// integers, e.g. a `Vec<u16>` or a range. An error is returned if a value
+  /// is out of range for the VR.
+  ///
+  pub fn new_ints<I, T>(
+    vr: ValueRepresentation,
+    value: I,
+  ) -> Result<Self, DataError>
+  where
+    I: IntoIterator<Item = T>,
+    T: Into<i128>,
+  {
+    fn convert<U: TryFrom<i128>>(
+      value: &[i128],
+      vr: ValueRepresentation,
+    ) -> Result<Vec<U>, DataError> {
+      value
+        .iter()
+        .map(|i| {
+          U::try_from(*i).map_err(|_| {
+            DataError::new_value_invalid(format!(
+              "Value {i} is out of range for the {vr} VR"
+            ))
+          })
+        })
+        .collect()
+    }
+
+    let value = value.into_iter().map(Into::into).collect::<Vec<i128>>();
+
+    match vr {
+      ValueRepresentation::IntegerString => {
+        Self::new_integer_string(&convert(&value, vr)?)
+      }
+      ValueRepresentation::SignedLong => {
+        Self::new_signed_long(&convert(&value, vr)?)
+      }
+      ValueRepresentation::SignedShort => {
+        Self::new_signed_short(&convert(&value, vr)?)
+      }
+      ValueRepresentation::SignedVeryLong => {
+        Self::new_signed_very_long(&convert(&value, vr)?)
+      }
+      ValueRepresentation::UnsignedLong => {
+        Self::new_unsigned_long(&convert(&value, vr)?)
+      }
+      ValueRepresentation::UnsignedShort => {
+        Self::new_unsigned_short(&convert(&value, vr)?)
+      }
+      ValueRepresentation::UnsignedVeryLong => {
+        Self::new_unsigned_very_long(&convert(&value, vr)?)
+      }
+
+      _ => Err(DataError::new_value_invalid(format!(
+        "Integer values can't be stored in the {vr} VR"
+      ))),
+    }
+  }
+
+  /// Creates a new data element value with a floating point VR from any
+  /// iterator of floats, e.g. a `Vec<f32>` or a `&[f64]`.
+  ///
+  pub fn new_floats<I, T>(
+    vr: ValueRepresentation,
+    value: I,
+  ) -> Result<Self, DataError>
+  where
+    I: IntoIterator<Item = T>,
+    T: Into<f64>,
+  {
+    let value = value.into_iter().map(Into::into).collect::<Vec<f64>>();
+
+    match vr {
+      ValueRepresentation::DecimalString => Self::new_decimal_string(&value),
+      ValueRepresentation::FloatingPointDouble => {
+        Self::new_floating_point_double(&value)
+      }
+      ValueRepresentation::FloatingPointSingle => {
+        Self::new_floating_point_single(
+          &value.iter().map(|f| *f as f32).collect::<Vec<f32>>(),
+        )
+      }
+      ValueRepresentation::OtherDoubleString => {
+        Self::new_other_double_string(&value)
+      }
+      ValueRepresentation::OtherFloatString => Self::new_other_float_string(
+        &value.iter().map(|f| *f as f32).collect::<Vec<f32>>(),
+      ),
+
+      _ => Err(DataError::new_value_invalid(format!(
+        "Float values can't be stored in the {vr} VR"
+      ))),
+    }
+  }
+
   /// Returns the value representation for a data element value.
   ///
   pub fn value_representation(&self) -> ValueRepresentation {
@@ -905,9 +1045,7 @@ impl DataElementValue {
           || *vr == ValueRepresentation::UnlimitedText =>
       {
         let string = core::str::from_utf8(bytes).map_err(|_| {
-          DataError::new_value_invalid(
-            "String bytes are not valid UTF-8".to_string(),
-          )
+          DataError::new_value_invalid("String bytes are not valid UTF-8")
         })?;
 
         let string = match *vr {
@@ -949,9 +1087,7 @@ impl DataElementValue {
           || *vr == ValueRepresentation::UnlimitedCharacters =>
       {
         let string = core::str::from_utf8(bytes).map_err(|_| {
-          DataError::new_value_invalid(
-            "String bytes are not valid UTF-8".to_string(),
-          )
+          DataError::new_value_invalid("String bytes are not valid UTF-8")
         })?;
 
         let strings = string
@@ -1032,9 +1168,7 @@ impl DataElementValue {
         bytes,
       } => {
         if bytes.len() % 4 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Int32 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Int32 data"));
         }
 
         let mut values = Vec::<T>::with_capacity(bytes.len() / 4);
@@ -1051,9 +1185,7 @@ impl DataElementValue {
         bytes,
       } => {
         if bytes.len() % 2 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Int16 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Int16 data"));
         }
 
         let mut values = Vec::<T>::with_capacity(bytes.len() / 2);
@@ -1070,9 +1202,7 @@ impl DataElementValue {
         bytes,
       } => {
         if bytes.len() % 4 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Uint32 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Uint32 data"));
         }
 
         let mut values = Vec::with_capacity(bytes.len() / 4);
@@ -1089,9 +1219,7 @@ impl DataElementValue {
         bytes,
       } => {
         if bytes.len() % 2 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Uint16 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Uint16 data"));
         }
 
         let mut values = Vec::<T>::with_capacity(bytes.len() / 2);
@@ -1146,7 +1274,7 @@ impl DataElementValue {
           Ok((entry_count, first_input_value, bits_per_entry))
         } else {
           Err(DataError::new_value_invalid(
-            "Invalid lookup table descriptor".to_string(),
+            "Invalid lookup table descriptor",
           ))
         }
       }
@@ -1200,9 +1328,7 @@ impl DataElementValue {
         bytes,
       } => {
         if bytes.len() % 8 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Int64 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Int64 data"));
         }
 
         let mut values = Vec::with_capacity(bytes.len() / 8);
@@ -1219,9 +1345,7 @@ impl DataElementValue {
         bytes,
       } => {
         if bytes.len() % 8 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Uint64 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Uint64 data"));
         }
 
         let mut values = Vec::with_capacity(bytes.len() / 8);
@@ -1266,9 +1390,7 @@ impl DataElementValue {
           || *vr == ValueRepresentation::OtherDoubleString =>
       {
         if bytes.len() % 8 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Float64 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Float64 data"));
         }
 
         let mut values = Vec::with_capacity(bytes.len() / 8);
@@ -1284,9 +1406,7 @@ impl DataElementValue {
           || *vr == ValueRepresentation::OtherFloatString =>
       {
         if bytes.len() % 4 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Invalid Float32 data".to_string(),
-          ));
+          return Err(DataError::new_value_invalid("Invalid Float32 data"));
         }
 
         let mut values = Vec::with_capacity(bytes.len() / 4);
@@ -1413,8 +1533,7 @@ impl DataElementValue {
           return Err(DataError::new_value_length_invalid(
             *vr,
             value_length as u64,
-            "Lookup table descriptor length must be exactly 6 bytes"
-              .to_string(),
+            "Lookup table descriptor length must be exactly 6 bytes",
           ));
         }
       }
@@ -1462,7 +1581,7 @@ impl DataElementValue {
             return Err(DataError::new_value_length_invalid(
               *vr,
               item_length as u64,
-              "Must be a multiple of 2 bytes".to_string(),
+              "Must be a multiple of 2 bytes",
             ));
           }
         }
@@ -1475,6 +1594,128 @@ impl DataElementValue {
   }
 }
 
+// Conversions from native types to the VR that naturally holds them
+
+impl TryFrom<Vec<u16>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<u16>) -> Result<Self, Self::Error> {
+    Self::new_unsigned_short(&value)
+  }
+}
+
+impl TryFrom<Vec<i16>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<i16>) -> Result<Self, Self::Error> {
+    Self::new_signed_short(&value)
+  }
+}
+
+impl TryFrom<Vec<u32>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<u32>) -> Result<Self, Self::Error> {
+    Self::new_unsigned_long(&value)
+  }
+}
+
+impl TryFrom<Vec<i32>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<i32>) -> Result<Self, Self::Error> {
+    Self::new_signed_long(&value)
+  }
+}
+
+impl TryFrom<Vec<u64>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<u64>) -> Result<Self, Self::Error> {
+    Self::new_unsigned_very_long(&value)
+  }
+}
+
+impl TryFrom<Vec<i64>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<i64>) -> Result<Self, Self::Error> {
+    Self::new_signed_very_long(&value)
+  }
+}
+
+impl TryFrom<Vec<f32>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<f32>) -> Result<Self, Self::Error> {
+    Self::new_floating_point_single(&value)
+  }
+}
+
+impl TryFrom<Vec<f64>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<f64>) -> Result<Self, Self::Error> {
+    Self::new_floating_point_double(&value)
+  }
+}
+
+impl TryFrom<Vec<DataElementTag>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: Vec<DataElementTag>) -> Result<Self, Self::Error> {
+    Self::new_attribute_tag(&value)
+  }
+}
+
+impl TryFrom<StructuredAge> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: StructuredAge) -> Result<Self, Self::Error> {
+    Self::new_age_string(&value)
+  }
+}
+
+impl TryFrom<StructuredDate> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: StructuredDate) -> Result<Self, Self::Error> {
+    Self::new_date(&value)
+  }
+}
+
+impl TryFrom<StructuredDateTime> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: StructuredDateTime) -> Result<Self, Self::Error> {
+    Self::new_date_time(&value)
+  }
+}
+
+impl TryFrom<StructuredTime> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(value: StructuredTime) -> Result<Self, Self::Error> {
+    Self::new_time(&value)
+  }
+}
+
+impl TryFrom<Vec<person_name::StructuredPersonName>> for DataElementValue {
+  type Error = DataError;
+
+  fn try_from(
+    value: Vec<person_name::StructuredPersonName>,
+  ) -> Result<Self, Self::Error> {
+    Self::new_person_name(&value)
+  }
+}
+
+impl From<Vec<DataSet>> for DataElementValue {
+  fn from(items: Vec<DataSet>) -> Self {
+    Self::new_sequence(items)
+  }
+}
+
 /// Creates a data element containing a multi-valued string. This checks that
 /// the individual values are valid and then combines them into final bytes.
 ///
@@ -1499,7 +1740,7 @@ fn new_string_list(
 
     if s.contains('\\') {
       return Err(DataError::new_value_invalid(
-        "String list item contains backslashes".to_string(),
+        "String list item contains backslashes",
       ));
     }
   }
@@ -1632,6 +1873,7 @@ mod tests {
       Ok("A")
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::ShortText,
@@ -1702,6 +1944,7 @@ mod tests {
       Ok(vec!["A", "B"])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::ShortString,
@@ -1777,6 +2020,7 @@ mod tests {
       Ok(vec![i32::MIN, i32::MAX])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::SignedLong,
@@ -1795,6 +2039,7 @@ mod tests {
       Ok(vec![i16::MIN, i16::MAX])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::SignedShort,
@@ -1813,6 +2058,7 @@ mod tests {
       Ok(vec![u32::MIN, u32::MAX])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::UnsignedLong,
@@ -1831,6 +2077,7 @@ mod tests {
       Ok(vec![u16::MIN, u16::MAX])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::UnsignedShort,
@@ -1860,6 +2107,7 @@ mod tests {
       Ok(vec![0x1234, 0x8000, 0x5678])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_lookup_table_descriptor_unchecked(
         ValueRepresentation::OtherWordString,
@@ -1871,6 +2119,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_lookup_table_descriptor_unchecked(
         ValueRepresentation::UnsignedShort,
@@ -1937,6 +2186,7 @@ mod tests {
       Ok(vec![i64::MIN.into(), i64::MAX.into()])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::SignedVeryLong,
@@ -1955,6 +2205,7 @@ mod tests {
       Ok(vec![u64::MIN, u64::MAX])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::UnsignedVeryLong,
@@ -2044,6 +2295,7 @@ mod tests {
       Ok(vec![1.0, 2.0])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::FloatingPointDouble,
@@ -2069,6 +2321,7 @@ mod tests {
       Ok(vec![1.0, 2.0])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_binary_unchecked(
         ValueRepresentation::FloatingPointSingle,
@@ -2401,6 +2654,7 @@ mod tests {
       Ok(())
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_lookup_table_descriptor_unchecked(
         ValueRepresentation::SignedShort,
@@ -2449,6 +2703,7 @@ mod tests {
       Ok(())
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_encapsulated_pixel_data_unchecked(
         ValueRepresentation::OtherWordString,
@@ -2539,6 +2794,7 @@ mod tests {
       )
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataElementValue::new_code_string(&["\\"]),
       Err(DataError::new_value_invalid(
@@ -2962,4 +3218,101 @@ mod tests {
       )
     );
   }
+
+  #[test]
+  fn new_strings_test() {
+    assert_eq!(
+      DataElementValue::new_strings(
+        ValueRepresentation::CodeString,
+        vec!["A".to_string(), "B".to_string()]
+      ),
+      DataElementValue::new_code_string(&["A", "B"])
+    );
+
+    assert_eq!(
+      DataElementValue::new_strings(ValueRepresentation::ShortText, ["Text"]),
+      DataElementValue::new_short_text("Text")
+    );
+
+    assert!(
+      DataElementValue::new_strings(ValueRepresentation::ShortText, ["A", "B"])
+        .is_err()
+    );
+
+    assert!(
+      DataElementValue::new_strings(ValueRepresentation::UnsignedShort, ["1"])
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn new_ints_test() {
+    assert_eq!(
+      DataElementValue::new_ints(ValueRepresentation::UnsignedShort, 1u16..=3),
+      DataElementValue::new_unsigned_short(&[1, 2, 3])
+    );
+
+    assert_eq!(
+      DataElementValue::new_ints(
+        ValueRepresentation::UnsignedVeryLong,
+        [u64::MAX]
+      ),
+      DataElementValue::new_unsigned_very_long(&[u64::MAX])
+    );
+
+    assert_eq!(
+      DataElementValue::new_ints(ValueRepresentation::SignedShort, [40_000]),
+      Err(DataError::new_value_invalid(
+        "Value 40000 is out of range for the SS VR".to_string()
+      ))
+    );
+
+    assert!(
+      DataElementValue::new_ints(ValueRepresentation::CodeString, [1]).is_err()
+    );
+  }
+
+  #[test]
+  fn new_floats_test() {
+    assert_eq!(
+      DataElementValue::new_floats(
+        ValueRepresentation::FloatingPointSingle,
+        vec![1.5f32, 2.0]
+      ),
+      DataElementValue::new_floating_point_single(&[1.5, 2.0])
+    );
+
+    assert_eq!(
+      DataElementValue::new_floats(ValueRepresentation::DecimalString, [1.5]),
+      DataElementValue::new_decimal_string(&[1.5])
+    );
+  }
+
+  #[test]
+  fn try_from_test() {
+    assert_eq!(
+      DataElementValue::try_from(vec![1u16, 2]),
+      DataElementValue::new_unsigned_short(&[1, 2])
+    );
+
+    assert_eq!(
+      DataElementValue::try_from(vec![-1i32]),
+      DataElementValue::new_signed_long(&[-1])
+    );
+
+    let date = StructuredDate {
+      year: 2024,
+      month: 2,
+      day: 29,
+    };
+    assert_eq!(
+      DataElementValue::try_from(date.clone()),
+      DataElementValue::new_date(&date)
+    );
+
+    assert_eq!(
+      DataElementValue::from(vec![DataSet::new()]),
+      DataElementValue::new_sequence(vec![DataSet::new()])
+    );
+  }
 }
@@ -1,11 +1,11 @@
 //! Work with the DICOM `AgeString` value representation.
 
 #[cfg(not(feature = "std"))]
-use alloc::{format, string::ToString, vec::Vec};
+use alloc::{format, vec::Vec};
 
 use regex::Regex;
 
-use crate::DataError;
+use crate::{DataError, StructuredDate, error_details};
 
 /// The time units that can be specified by a structured age.
 ///
@@ -49,7 +49,7 @@ impl StructuredAge {
   ///
   pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataError> {
     let age_string = core::str::from_utf8(bytes).map_err(|_| {
-      DataError::new_value_invalid("AgeString is invalid UTF-8".to_string())
+      DataError::new_value_invalid("AgeString is invalid UTF-8")
     })?;
 
     let age_string = age_string.trim_matches('\0').trim();
@@ -72,7 +72,7 @@ impl StructuredAge {
         Ok(Self { number, unit })
       }
 
-      _ => Err(DataError::new_value_invalid(format!(
+      _ => Err(DataError::new_value_invalid(error_details!(
         "AgeString is invalid: '{age_string}'"
       ))),
     }
@@ -82,7 +82,7 @@ impl StructuredAge {
   ///
   pub fn to_bytes(&self) -> Result<Vec<u8>, DataError> {
     if self.number > 999 {
-      return Err(DataError::new_value_invalid(format!(
+      return Err(DataError::new_value_invalid(error_details!(
         "AgeString value {} is outside the valid range of 0-999",
         self.number
       )));
@@ -97,6 +97,91 @@ impl StructuredAge {
 
     Ok(format!("{:03}{}", self.number, unit).into_bytes())
   }
+
+  /// Computes the age on a given date of someone born on `birth_date`, e.g. a
+  /// patient's age at the time of a study. Only whole units are counted, and
+  /// the unit is chosen as follows:
+  ///
+  /// - Ages less than one month are in days.
+  /// - Ages less than two years are in months.
+  /// - All other ages are in years.
+  ///
+  /// Returns an error if either date is invalid, if `date` is before
+  /// `birth_date`, or if the age exceeds 999 years.
+  ///
+  pub fn from_dates(
+    birth_date: &StructuredDate,
+    date: &StructuredDate,
+  ) -> Result<Self, DataError> {
+    let (days, months) = elapsed_days_and_months(birth_date, date)?;
+
+    let (number, unit) = if months < 1 {
+      (days, AgeUnit::Days)
+    } else if months < 24 {
+      (months, AgeUnit::Months)
+    } else {
+      (months / 12, AgeUnit::Years)
+    };
+
+    if number > 999 {
+      return Err(DataError::new_value_invalid(error_details!(
+        "Age of {number} years is outside the valid range of 0-999"
+      )));
+    }
+
+    Ok(Self {
+      number: number as u16,
+      unit,
+    })
+  }
+
+  /// Returns whether this age is correct for someone born on `birth_date` at
+  /// the time of `date`, counting only whole units of this age's unit. This
+  /// checks ages in any unit, not just the unit that would be chosen by
+  /// [`Self::from_dates()`].
+  ///
+  pub fn matches_dates(
+    &self,
+    birth_date: &StructuredDate,
+    date: &StructuredDate,
+  ) -> Result<bool, DataError> {
+    let (days, months) = elapsed_days_and_months(birth_date, date)?;
+
+    let number = match self.unit {
+      AgeUnit::Days => days,
+      AgeUnit::Weeks => days / 7,
+      AgeUnit::Months => months,
+      AgeUnit::Years => months / 12,
+    };
+
+    Ok(number == i64::from(self.number))
+  }
+}
+
+/// Returns the number of whole days and whole months elapsed between two
+/// dates. A month is complete once the day of the month reaches that of the
+/// start date.
+///
+fn elapsed_days_and_months(
+  start: &StructuredDate,
+  end: &StructuredDate,
+) -> Result<(i64, i64), DataError> {
+  let days = end.days_since_epoch()? - start.days_since_epoch()?;
+  if days < 0 {
+    return Err(DataError::new_value_invalid(error_details!(
+      "Date '{}' is before the birth date '{}'",
+      end.to_iso8601(),
+      start.to_iso8601()
+    )));
+  }
+
+  let mut months = (i64::from(end.year) - i64::from(start.year)) * 12
+    + (i64::from(end.month) - i64::from(start.month));
+  if end.day < start.day {
+    months -= 1;
+  }
+
+  Ok((days, months))
 }
 
 #[cfg(test)]
@@ -176,6 +261,7 @@ mod tests {
       })
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredAge::from_bytes(&[]),
       Err(DataError::new_value_invalid(
@@ -183,6 +269,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredAge::from_bytes(&[0xD0]),
       Err(DataError::new_value_invalid(
@@ -190,6 +277,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredAge::from_bytes(b"3 days"),
       Err(DataError::new_value_invalid(
@@ -236,6 +324,7 @@ mod tests {
       Ok(b"101Y".to_vec())
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredAge {
         number: 1000,
@@ -247,4 +336,76 @@ mod tests {
       )),
     );
   }
+
+  #[test]
+  fn from_dates_test() {
+    let date = |year, month, day| StructuredDate { year, month, day };
+    let birth_date = date(2020, 1, 31);
+
+    assert_eq!(
+      StructuredAge::from_dates(&birth_date, &date(2020, 2, 20)),
+      Ok(StructuredAge {
+        number: 20,
+        unit: AgeUnit::Days
+      })
+    );
+
+    assert_eq!(
+      StructuredAge::from_dates(&birth_date, &date(2021, 12, 31)),
+      Ok(StructuredAge {
+        number: 23,
+        unit: AgeUnit::Months
+      })
+    );
+
+    assert_eq!(
+      StructuredAge::from_dates(&birth_date, &date(2022, 1, 30)),
+      Ok(StructuredAge {
+        number: 23,
+        unit: AgeUnit::Months
+      })
+    );
+
+    assert_eq!(
+      StructuredAge::from_dates(&birth_date, &date(2065, 1, 31)),
+      Ok(StructuredAge {
+        number: 45,
+        unit: AgeUnit::Years
+      })
+    );
+
+    #[cfg(not(feature = "compact_errors"))]
+    assert_eq!(
+      StructuredAge::from_dates(&birth_date, &date(2019, 12, 31)),
+      Err(DataError::new_value_invalid(
+        "Date '2019-12-31' is before the birth date '2020-01-31'"
+      ))
+    );
+  }
+
+  #[test]
+  fn matches_dates_test() {
+    let date = |year, month, day| StructuredDate { year, month, day };
+    let birth_date = date(1980, 6, 15);
+    let study_date = date(2024, 6, 14);
+
+    let age = |number, unit| StructuredAge { number, unit };
+
+    assert_eq!(
+      age(43, AgeUnit::Years).matches_dates(&birth_date, &study_date),
+      Ok(true)
+    );
+    assert_eq!(
+      age(44, AgeUnit::Years).matches_dates(&birth_date, &study_date),
+      Ok(false)
+    );
+    assert_eq!(
+      age(527, AgeUnit::Months).matches_dates(&birth_date, &study_date),
+      Ok(true)
+    );
+    assert_eq!(
+      age(2295, AgeUnit::Weeks).matches_dates(&birth_date, &study_date),
+      Ok(true)
+    );
+  }
 }
@@ -1,7 +1,7 @@
 //! Work with the DICOM `AttributeTag` value representation.
 
 #[cfg(not(feature = "std"))]
-use alloc::{string::ToString, vec::Vec};
+use alloc::vec::Vec;
 
 use crate::{DataElementTag, DataError};
 
@@ -10,7 +10,7 @@ use crate::{DataElementTag, DataError};
 pub fn from_bytes(bytes: &[u8]) -> Result<Vec<DataElementTag>, DataError> {
   if !bytes.len().is_multiple_of(4) {
     return Err(DataError::new_value_invalid(
-      "AttributeTag data length is not a multiple of 4".to_string(),
+      "AttributeTag data length is not a multiple of 4",
     ));
   }
 
@@ -58,6 +58,7 @@ mod tests {
       ])
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       from_bytes(&[0x00, 0x01]),
       Err(DataError::new_value_invalid(
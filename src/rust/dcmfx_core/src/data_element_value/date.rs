@@ -9,7 +9,7 @@ use alloc::{
 
 use regex::Regex;
 
-use crate::DataError;
+use crate::{DataError, error_details};
 
 /// A structured date that can be converted to/from a `Date` value.
 ///
@@ -26,9 +26,8 @@ impl StructuredDate {
   /// Converts a `Date` value into a structured date.
   ///
   pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataError> {
-    let date_string = core::str::from_utf8(bytes).map_err(|_| {
-      DataError::new_value_invalid("Date is invalid UTF-8".to_string())
-    })?;
+    let date_string = core::str::from_utf8(bytes)
+      .map_err(|_| DataError::new_value_invalid("Date is invalid UTF-8"))?;
 
     let date_string = date_string.trim_matches('\0').trim();
 
@@ -41,7 +40,7 @@ impl StructuredDate {
         Ok(Self { year, month, day })
       }
 
-      _ => Err(DataError::new_value_invalid(format!(
+      _ => Err(DataError::new_value_invalid(error_details!(
         "Date is invalid: '{date_string}'"
       ))),
     }
@@ -67,13 +66,13 @@ impl StructuredDate {
     let has_day_without_month = day.is_some() && month.is_none();
     if has_day_without_month {
       return Err(DataError::new_value_invalid(
-        "Date's month must be present when there is a day value".to_string(),
+        "Date's month must be present when there is a day value",
       ));
     }
 
     // Validate and format the year value
     if year > 9999 {
-      return Err(DataError::new_value_invalid(format!(
+      return Err(DataError::new_value_invalid(error_details!(
         "Date's year is invalid: {year}"
       )));
     }
@@ -83,7 +82,7 @@ impl StructuredDate {
     let month = match month {
       Some(month) => {
         if !(1..=12).contains(&month) {
-          return Err(DataError::new_value_invalid(format!(
+          return Err(DataError::new_value_invalid(error_details!(
             "Date's month is invalid: {month}"
           )));
         }
@@ -98,7 +97,7 @@ impl StructuredDate {
     let day = match day {
       Some(day) => {
         if !(1..=31).contains(&day) {
-          return Err(DataError::new_value_invalid(format!(
+          return Err(DataError::new_value_invalid(error_details!(
             "Date's day is invalid: {day}"
           )));
         }
@@ -117,6 +116,57 @@ impl StructuredDate {
   pub fn to_iso8601(&self) -> String {
     format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
   }
+
+  /// Returns the number of days between 1970-01-01 and this date, which is
+  /// negative for earlier dates. Returns an error if the month or day isn't
+  /// valid, taking into account the number of days in the month.
+  ///
+  pub fn days_since_epoch(&self) -> Result<i64, DataError> {
+    if !(1..=12).contains(&self.month) {
+      return Err(DataError::new_value_invalid(error_details!(
+        "Date's month is invalid: {}",
+        self.month
+      )));
+    }
+
+    if self.day < 1 || self.day > days_in_month(self.year, self.month) {
+      return Err(DataError::new_value_invalid(error_details!(
+        "Date's day is invalid: {}",
+        self.day
+      )));
+    }
+
+    // Ref: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let month = i64::from(self.month);
+    let day = i64::from(self.day);
+    let year = i64::from(self.year) - if month <= 2 { 1 } else { 0 };
+
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year =
+      (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era =
+      year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    Ok(era * 146097 + day_of_era - 719468)
+  }
+}
+
+/// Returns the number of days in a month of the Gregorian calendar.
+///
+fn days_in_month(year: u16, month: u8) -> u8 {
+  match month {
+    2 => {
+      let is_leap_year = (year.is_multiple_of(4) && !year.is_multiple_of(100))
+        || year.is_multiple_of(400);
+
+      if is_leap_year { 29 } else { 28 }
+    }
+
+    4 | 6 | 9 | 11 => 30,
+
+    _ => 31,
+  }
 }
 
 #[cfg(test)]
@@ -147,6 +197,7 @@ mod tests {
       })
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDate::from_bytes(&[0xD0]),
       Err(DataError::new_value_invalid(
@@ -154,6 +205,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDate::from_bytes(&[]),
       Err(DataError::new_value_invalid(
@@ -161,6 +213,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDate::from_bytes(b"2024"),
       Err(DataError::new_value_invalid(
@@ -181,6 +234,7 @@ mod tests {
       Ok(b"20000102".to_vec())
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDate {
         year: 10000,
@@ -193,6 +247,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDate {
         year: 0,
@@ -205,6 +260,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDate {
         year: 100,
@@ -217,4 +273,25 @@ mod tests {
       ))
     );
   }
+
+  #[test]
+  fn days_since_epoch_test() {
+    let date = |year, month, day| StructuredDate { year, month, day };
+
+    assert_eq!(date(1970, 1, 1).days_since_epoch(), Ok(0));
+    assert_eq!(date(1969, 12, 31).days_since_epoch(), Ok(-1));
+    assert_eq!(date(2000, 3, 1).days_since_epoch(), Ok(11017));
+    assert_eq!(date(2024, 2, 29).days_since_epoch(), Ok(19782));
+
+    #[cfg(not(feature = "compact_errors"))]
+    assert_eq!(
+      date(2023, 2, 29).days_since_epoch(),
+      Err(DataError::new_value_invalid("Date's day is invalid: 29"))
+    );
+    #[cfg(not(feature = "compact_errors"))]
+    assert_eq!(
+      date(2023, 0, 1).days_since_epoch(),
+      Err(DataError::new_value_invalid("Date's month is invalid: 0"))
+    );
+  }
 }
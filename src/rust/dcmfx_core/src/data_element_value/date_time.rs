@@ -10,7 +10,7 @@ use alloc::{
 use regex::Regex;
 
 use crate::data_element_value::date::StructuredDate;
-use crate::{DataError, StructuredTime};
+use crate::{DataError, StructuredTime, error_details};
 
 /// A structured date/time that can be converted to/from a `DateTime` value.
 ///
@@ -31,9 +31,8 @@ impl StructuredDateTime {
   /// Converts a `DateTime` value into a structured date/time.
   ///
   pub fn from_bytes(bytes: &[u8]) -> Result<StructuredDateTime, DataError> {
-    let date_time_string = core::str::from_utf8(bytes).map_err(|_| {
-      DataError::new_value_invalid("DateTime is invalid UTF-8".to_string())
-    })?;
+    let date_time_string = core::str::from_utf8(bytes)
+      .map_err(|_| DataError::new_value_invalid("DateTime is invalid UTF-8"))?;
 
     let date_time_string = date_time_string.trim_matches('\0').trim();
 
@@ -62,7 +61,7 @@ impl StructuredDateTime {
         })
       }
 
-      _ => Err(DataError::new_value_invalid(format!(
+      _ => Err(DataError::new_value_invalid(error_details!(
         "DateTime is invalid: '{date_time_string}'"
       ))),
     }
@@ -74,8 +73,7 @@ impl StructuredDateTime {
     let has_hour_without_day = self.hour.is_some() && self.day.is_none();
     if has_hour_without_day {
       return Err(DataError::new_value_invalid(
-        "DateTime day value must be present when there is an hour value"
-          .to_string(),
+        "DateTime day value must be present when there is an hour value",
       ));
     }
 
@@ -101,7 +99,7 @@ impl StructuredDateTime {
           (-1200..=1400).contains(&offset) && (offset % 100 < 60);
 
         if !is_offset_valid {
-          return Err(DataError::new_value_invalid(format!(
+          return Err(DataError::new_value_invalid(error_details!(
             "DateTime time zone offset is invalid: {offset}"
           )));
         }
@@ -233,6 +231,7 @@ mod tests {
       })
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDateTime::from_bytes(b"10pm"),
       Err(DataError::new_value_invalid(
@@ -240,6 +239,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDateTime::from_bytes(&[0xD0]),
       Err(DataError::new_value_invalid(
@@ -292,6 +292,7 @@ mod tests {
       Ok(b"1997+0100 ".to_vec())
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDateTime {
         year: 1997,
@@ -309,6 +310,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDateTime {
         year: 1997,
@@ -341,6 +343,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredDateTime {
         year: 1997,
@@ -7,13 +7,13 @@ use alloc::{
   vec::Vec,
 };
 
-use crate::DataError;
+use crate::{DataError, error_details};
 
 /// Converts a `DecimalString` value to a list of floats.
 ///
 pub fn from_bytes(bytes: &[u8]) -> Result<Vec<f64>, DataError> {
   let decimal_string = core::str::from_utf8(bytes).map_err(|_| {
-    DataError::new_value_invalid("DecimalString is invalid UTF-8".to_string())
+    DataError::new_value_invalid("DecimalString is invalid UTF-8")
   })?;
 
   let decimal_string = decimal_string.trim_matches('\0');
@@ -25,7 +25,7 @@ pub fn from_bytes(bytes: &[u8]) -> Result<Vec<f64>, DataError> {
     .map(|s| s.parse::<f64>())
     .collect::<Result<Vec<f64>, _>>()
     .map_err(|_| {
-      DataError::new_value_invalid(format!(
+      DataError::new_value_invalid(error_details!(
         "DecimalString is invalid: '{decimal_string}'"
       ))
     })
@@ -90,6 +90,7 @@ mod tests {
 
     assert_eq!(from_bytes(b"-0"), Ok(vec![-0.0]));
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       from_bytes(&[0xD0]),
       Err(DataError::new_value_invalid(
@@ -97,6 +98,7 @@ mod tests {
       ))
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       from_bytes(b"1.A"),
       Err(DataError::new_value_invalid(
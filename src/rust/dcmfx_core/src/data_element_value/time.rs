@@ -26,9 +26,8 @@ impl StructuredTime {
   /// Converts a `Time` value into a structured time.
   ///
   pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataError> {
-    let time_string = core::str::from_utf8(bytes).map_err(|_| {
-      DataError::new_value_invalid("Time is invalid UTF-8".to_string())
-    })?;
+    let time_string = core::str::from_utf8(bytes)
+      .map_err(|_| DataError::new_value_invalid("Time is invalid UTF-8"))?;
 
     let time_string = time_string.trim_matches('\0').trim();
 
@@ -64,8 +63,7 @@ impl StructuredTime {
       self.second.is_some() && self.minute.is_none();
     if has_second_without_minute {
       return Err(DataError::new_value_invalid(
-        "Time minute value must be present when there is a second value"
-          .to_string(),
+        "Time minute value must be present when there is a second value",
       ));
     }
 
@@ -231,6 +229,7 @@ mod tests {
       })
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredTime::from_bytes(&[0xD0]),
       Err(DataError::new_value_invalid(
@@ -288,6 +287,7 @@ mod tests {
       Ok(b"2314".to_vec())
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       StructuredTime {
         hour: 23,
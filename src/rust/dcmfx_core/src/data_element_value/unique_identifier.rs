@@ -6,17 +6,58 @@ use alloc::{
   vec::Vec,
 };
 
-use regex::Regex;
-
 use crate::DataError;
 
+/// The ways in which a `UniqueIdentifier` can fail to conform to the rules in
+/// PS3.5 9.1.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UidConformanceError {
+  /// The UID is empty.
+  Empty,
+
+  /// The UID is longer than the maximum of 64 characters.
+  TooLong { length: usize },
+
+  /// The UID contains a character that isn't a digit or a period.
+  InvalidCharacter { character: char },
+
+  /// The UID has an empty component, e.g. it starts or ends with a period, or
+  /// has two consecutive periods.
+  EmptyComponent,
+
+  /// A component of the UID has a leading zero, which isn't permitted unless
+  /// the component is a single zero.
+  LeadingZero,
+
+  /// The UID has leading or trailing spaces or null characters. The only
+  /// padding permitted is a single trailing null that makes the length of the
+  /// whole value even.
+  InvalidPadding,
+}
+
+impl core::fmt::Display for UidConformanceError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Empty => write!(f, "UID is empty"),
+      Self::TooLong { length } => {
+        write!(f, "UID length of {length} exceeds the maximum of 64")
+      }
+      Self::InvalidCharacter { character } => {
+        write!(f, "UID contains invalid character {character:?}")
+      }
+      Self::EmptyComponent => write!(f, "UID has an empty component"),
+      Self::LeadingZero => write!(f, "UID has a component with a leading zero"),
+      Self::InvalidPadding => write!(f, "UID has invalid padding"),
+    }
+  }
+}
+
 /// Converts a list of UIDs into a `UniqueIdentifier` value.
 ///
 pub fn to_bytes(uids: &[&str]) -> Result<Vec<u8>, DataError> {
   if uids.iter().any(|uid| !is_valid(uid)) {
-    return Err(DataError::new_value_invalid(
-      "UniqueIdentifier is invalid".to_string(),
-    ));
+    return Err(DataError::new_value_invalid("UniqueIdentifier is invalid"));
   }
 
   let mut bytes = uids.join("\\").into_bytes();
@@ -28,20 +69,102 @@ pub fn to_bytes(uids: &[&str]) -> Result<Vec<u8>, DataError> {
   Ok(bytes)
 }
 
-const PARSE_UID_REGEX: &str = "^(0|[1-9][0-9]*)(\\.(0|[1-9][0-9]*))*$";
-
 /// Returns whether the given string is a valid `UniqueIdentifier`. Valid UIDs
 /// are 1-64 characters in length, and are made up of sequences of digits
 /// separated by the period character. Leading zeros are not permitted in a
 /// digit sequence unless the zero is the only digit in the sequence.
 ///
 pub fn is_valid(uid: &str) -> bool {
-  // Check the length is valid
-  if uid.is_empty() || uid.len() > 64 {
-    return false;
+  validate(uid).is_ok()
+}
+
+/// Checks that the given string is a valid `UniqueIdentifier`, returning the
+/// first way in which it fails to conform if it isn't. See [`is_valid()`] for
+/// the rules that are checked.
+///
+pub fn validate(uid: &str) -> Result<(), UidConformanceError> {
+  if uid.is_empty() {
+    return Err(UidConformanceError::Empty);
   }
 
-  Regex::new(PARSE_UID_REGEX).unwrap().is_match(uid)
+  if uid.len() > 64 {
+    return Err(UidConformanceError::TooLong { length: uid.len() });
+  }
+
+  if uid.trim_matches([' ', '\0']).len() != uid.len() {
+    return Err(UidConformanceError::InvalidPadding);
+  }
+
+  for component in uid.split('.') {
+    if let Some(character) = component.chars().find(|c| !c.is_ascii_digit()) {
+      return Err(UidConformanceError::InvalidCharacter { character });
+    }
+
+    if component.is_empty() {
+      return Err(UidConformanceError::EmptyComponent);
+    }
+
+    if component.len() > 1 && component.starts_with('0') {
+      return Err(UidConformanceError::LeadingZero);
+    }
+  }
+
+  Ok(())
+}
+
+/// Checks that the raw bytes of a `UniqueIdentifier` value are conformant.
+/// Each of the backslash-separated UIDs in the value is checked with
+/// [`validate()`], and the value may only be padded to an even length using a
+/// single trailing null. An empty value is conformant.
+///
+pub fn validate_bytes(bytes: &[u8]) -> Result<(), UidConformanceError> {
+  let bytes = match bytes {
+    [rest @ .., 0] if bytes.len().is_multiple_of(2) => rest,
+    _ => bytes,
+  };
+
+  if bytes.is_empty() {
+    return Ok(());
+  }
+
+  let Ok(value) = core::str::from_utf8(bytes) else {
+    return Err(UidConformanceError::InvalidCharacter {
+      character: char::REPLACEMENT_CHARACTER,
+    });
+  };
+
+  value.split('\\').try_for_each(validate)
+}
+
+/// Corrects the padding of the raw bytes of a `UniqueIdentifier` value by
+/// removing leading and trailing spaces and nulls from each of its UIDs, then
+/// padding the value to an even length with a single trailing null. Spaces are
+/// commonly, and incorrectly, used as padding for UIDs.
+///
+pub fn fix_padding(bytes: &[u8]) -> Vec<u8> {
+  let is_padding = |b: &u8| *b == b' ' || *b == 0;
+
+  let mut fixed_bytes = Vec::with_capacity(bytes.len() + 1);
+
+  for (i, uid) in bytes.split(|b| *b == b'\\').enumerate() {
+    if i > 0 {
+      fixed_bytes.push(b'\\');
+    }
+
+    let start = uid.iter().position(|b| !is_padding(b)).unwrap_or(uid.len());
+    let end = uid
+      .iter()
+      .rposition(|b| !is_padding(b))
+      .map_or(start, |i| i + 1);
+
+    fixed_bytes.extend_from_slice(&uid[start..end]);
+  }
+
+  if fixed_bytes.len() % 2 == 1 {
+    fixed_bytes.push(0);
+  }
+
+  fixed_bytes
 }
 
 /// Generates a new random UID with the given prefix. The new UID will have a
@@ -102,9 +225,8 @@ mod tests {
 
   #[test]
   fn to_bytes_test() {
-    let invalid_uid_error = Err(DataError::new_value_invalid(
-      "UniqueIdentifier is invalid".to_string(),
-    ));
+    let invalid_uid_error =
+      Err(DataError::new_value_invalid("UniqueIdentifier is invalid"));
 
     assert_eq!(to_bytes(&[]), Ok(vec![]));
 
@@ -139,4 +261,63 @@ mod tests {
 
     assert_eq!(new("1."), Err(()));
   }
+
+  #[test]
+  fn validate_test() {
+    assert_eq!(validate("1.2.840.10008.1.2"), Ok(()));
+    assert_eq!(validate("0"), Ok(()));
+    assert_eq!(validate("1.0.2"), Ok(()));
+
+    assert_eq!(validate(""), Err(UidConformanceError::Empty));
+
+    assert_eq!(
+      validate(&"1".repeat(65)),
+      Err(UidConformanceError::TooLong { length: 65 })
+    );
+
+    assert_eq!(
+      validate("1.2a"),
+      Err(UidConformanceError::InvalidCharacter { character: 'a' })
+    );
+
+    assert_eq!(validate("1..2"), Err(UidConformanceError::EmptyComponent));
+    assert_eq!(validate(".1"), Err(UidConformanceError::EmptyComponent));
+    assert_eq!(validate("1."), Err(UidConformanceError::EmptyComponent));
+
+    assert_eq!(validate("1.02"), Err(UidConformanceError::LeadingZero));
+
+    assert_eq!(validate("1.2 "), Err(UidConformanceError::InvalidPadding));
+    assert_eq!(validate("1.2\0"), Err(UidConformanceError::InvalidPadding));
+  }
+
+  #[test]
+  fn validate_bytes_test() {
+    assert_eq!(validate_bytes(b""), Ok(()));
+    assert_eq!(validate_bytes(b"1.2"), Ok(()));
+    assert_eq!(validate_bytes(b"1.2\0"), Ok(()));
+    assert_eq!(validate_bytes(b"1.2\\3.4\0"), Ok(()));
+
+    assert_eq!(
+      validate_bytes(b"1.23 "),
+      Err(UidConformanceError::InvalidPadding)
+    );
+
+    assert_eq!(
+      validate_bytes(b"1.2\0\0"),
+      Err(UidConformanceError::InvalidPadding)
+    );
+
+    assert_eq!(
+      validate_bytes(b"1.2\\\0"),
+      Err(UidConformanceError::InvalidPadding)
+    );
+  }
+
+  #[test]
+  fn fix_padding_test() {
+    assert_eq!(fix_padding(b""), b"");
+    assert_eq!(fix_padding(b"1.2"), b"1.2\0");
+    assert_eq!(fix_padding(b"1.23 "), b"1.23");
+    assert_eq!(fix_padding(b" 1.2\0\0 \\3.4 "), b"1.2\\3.4\0");
+  }
 }
@@ -1,7 +1,9 @@
 //! Provides the [`DataError`] type that describes the errors that can occur
 //! when working with data sets and elements.
 
-use crate::{DataSetPath, DcmfxError, ValueRepresentation, dictionary};
+use crate::{
+  DataSetPath, DcmfxError, ErrorDetails, ValueRepresentation, dictionary,
+};
 
 #[cfg(not(feature = "std"))]
 use alloc::{
@@ -62,13 +64,13 @@ pub enum DataError {
     path: Option<DataSetPath>,
   },
   ValueInvalid {
-    details: String,
+    details: ErrorDetails,
     path: Option<DataSetPath>,
   },
   ValueLengthInvalid {
     vr: ValueRepresentation,
     length: u64,
-    details: String,
+    details: ErrorDetails,
     path: Option<DataSetPath>,
   },
 }
@@ -135,9 +137,9 @@ impl DataError {
 
   /// Constructs a new 'Value invalid' data error.
   ///
-  pub fn new_value_invalid(details: String) -> Self {
+  pub fn new_value_invalid(details: impl Into<ErrorDetails>) -> Self {
     Self::ValueInvalid {
-      details,
+      details: details.into(),
       path: None,
     }
   }
@@ -147,12 +149,12 @@ impl DataError {
   pub fn new_value_length_invalid(
     vr: ValueRepresentation,
     length: u64,
-    details: String,
+    details: impl Into<ErrorDetails>,
   ) -> Self {
     Self::ValueLengthInvalid {
       vr,
       length,
-      details,
+      details: details.into(),
       path: None,
     }
   }
@@ -219,8 +221,8 @@ impl DataError {
       Self::TagNotPresent { .. } => "",
       Self::ValueNotPresent { .. } => "",
       Self::MultiplicityMismatch { .. } => "",
-      Self::ValueInvalid { details, .. } => details,
-      Self::ValueLengthInvalid { details, .. } => details,
+      Self::ValueInvalid { details, .. } => details.as_str(),
+      Self::ValueLengthInvalid { details, .. } => details.as_str(),
     }
   }
 }
@@ -319,6 +321,7 @@ mod tests {
   Error: Multiplicity mismatch"#
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataError::new_value_invalid("123".to_string())
         .to_lines("testing")
@@ -329,6 +332,7 @@ mod tests {
   Details: 123"#
     );
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       DataError::new_value_length_invalid(
         ValueRepresentation::AgeString,
@@ -1,6 +1,9 @@
 //! A DICOM data set, defined as a map of data element tags to data element
 //! values.
 
+pub mod content_digest;
+pub mod flatten;
+pub mod patient_age;
 pub mod print;
 
 #[cfg(feature = "std")]
@@ -14,6 +17,8 @@ use alloc::{
   vec::Vec,
 };
 
+use sha2::{Digest, Sha256};
+
 use crate::data_element_value::{
   age_string, date, date_time, person_name, time,
 };
@@ -219,7 +224,7 @@ impl DataSet {
     }
 
     let value = match item.vrs {
-      [ValueRepresentation::Date] => DataElementValue::new_date_time(value),
+      [ValueRepresentation::DateTime] => DataElementValue::new_date_time(value),
       _ => invalid_insert_error(item),
     }
     .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(item.tag)))?;
@@ -548,6 +553,91 @@ impl DataSet {
     Ok(())
   }
 
+  /// Inserts a data element with string values taken from any iterator of
+  /// strings, e.g. a `Vec<String>`. The data element being inserted must be
+  /// referenced through its dictionary entry, which determines the VR to use.
+  ///
+  pub fn insert_strings<I, S>(
+    &mut self,
+    item: &dictionary::Item,
+    value: I,
+  ) -> Result<(), DataError>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+  {
+    let value = value.into_iter().collect::<Vec<S>>();
+
+    self.insert_with_item_vr(item, value.len(), |vr| {
+      DataElementValue::new_strings(vr, value)
+    })
+  }
+
+  /// Inserts a data element with integer values taken from any iterator of
+  /// integers, e.g. a `Vec<u16>`. The data element being inserted must be
+  /// referenced through its dictionary entry, which determines the VR to use.
+  ///
+  pub fn insert_ints<I, T>(
+    &mut self,
+    item: &dictionary::Item,
+    value: I,
+  ) -> Result<(), DataError>
+  where
+    I: IntoIterator<Item = T>,
+    T: Into<i128>,
+  {
+    let value = value.into_iter().map(Into::into).collect::<Vec<i128>>();
+
+    self.insert_with_item_vr(item, value.len(), |vr| {
+      DataElementValue::new_ints(vr, value)
+    })
+  }
+
+  /// Inserts a data element with float values taken from any iterator of
+  /// floats, e.g. a `Vec<f32>`. The data element being inserted must be
+  /// referenced through its dictionary entry, which determines the VR to use.
+  ///
+  pub fn insert_floats<I, T>(
+    &mut self,
+    item: &dictionary::Item,
+    value: I,
+  ) -> Result<(), DataError>
+  where
+    I: IntoIterator<Item = T>,
+    T: Into<f64>,
+  {
+    let value = value.into_iter().map(Into::into).collect::<Vec<f64>>();
+
+    self.insert_with_item_vr(item, value.len(), |vr| {
+      DataElementValue::new_floats(vr, value)
+    })
+  }
+
+  /// Inserts a data element built for the single VR of a dictionary item,
+  /// after checking that the number of values matches the item's
+  /// multiplicity.
+  ///
+  fn insert_with_item_vr(
+    &mut self,
+    item: &dictionary::Item,
+    value_count: usize,
+    build: impl FnOnce(ValueRepresentation) -> Result<DataElementValue, DataError>,
+  ) -> Result<(), DataError> {
+    if !item.multiplicity.contains(value_count) {
+      return invalid_insert_error(item);
+    }
+
+    let value = match item.vrs {
+      [vr] => build(*vr),
+      _ => invalid_insert_error(item),
+    }
+    .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(item.tag)))?;
+
+    self.insert(item.tag, value);
+
+    Ok(())
+  }
+
   /// Merges two data sets together. Data elements from the second data set take
   /// precedence.
   ///
@@ -611,6 +701,89 @@ impl DataSet {
     self.0.retain(|tag, value| predicate(*tag, value));
   }
 
+  /// Returns every data element in a data set, including those nested inside
+  /// sequence items, along with the path to each one. Data elements are
+  /// returned in depth-first order, with each sequence preceding the data
+  /// elements in its items.
+  ///
+  pub fn flatten(&self) -> Vec<flatten::FlattenedDataElement<'_>> {
+    let mut output = Vec::new();
+    flatten::flatten(self, &mut DataSetPath::new(), &mut output);
+
+    output
+  }
+
+  /// Extracts the values of the specified data elements into rows, with one
+  /// row for each data set, either the root data set or a nested sequence
+  /// item, that contains at least one of the specified data elements.
+  ///
+  /// This pivots nested data elements such as every *'(0008,1155) Referenced
+  /// SOP Instance UID'* in a sequence into individual rows, which is useful
+  /// when exporting to tabular formats.
+  ///
+  pub fn to_rows(
+    &self,
+    tags: &[DataElementTag],
+  ) -> Vec<flatten::DataSetRow<'_>> {
+    let mut output = Vec::new();
+    flatten::to_rows(self, tags, &mut DataSetPath::new(), &mut output);
+
+    output
+  }
+
+  /// Computes a SHA-256 digest of the semantic content of a data set, which
+  /// can be compared between copies of a data set to detect changes to its
+  /// content.
+  ///
+  /// File Meta Information, group length, and data set trailing padding data
+  /// elements are excluded from the digest, as are trailing padding on string
+  /// values and the basic offset table of encapsulated pixel data. This means
+  /// that copies serialized with a different byte order or length encoding,
+  /// e.g. with defined rather than undefined length sequences, have the same
+  /// digest.
+  ///
+  /// The digest is not independent of transfer syntax in general. Each data
+  /// element's VR is part of the digest, so a VR that differs between implicit
+  /// and explicit VR serializations changes the digest, as does converting
+  /// between native and encapsulated pixel data.
+  ///
+  /// Data elements with a tag in `excluded_tags` are also excluded, at all
+  /// levels of nesting. [`content_digest::VOLATILE_TAGS`] lists data elements
+  /// that are commonly excluded.
+  ///
+  pub fn content_digest(&self, excluded_tags: &[DataElementTag]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    content_digest::update(&mut hasher, self, excluded_tags);
+
+    hasher.finalize().into()
+  }
+
+  /// Computes the patient's age from *'(0010,0030) Patient's Birth Date'* and
+  /// *'(0008,0020) Study Date'*, or the date part of *'(0008,002A) Acquisition
+  /// DateTime'* if there is no study date, and inserts it as *'(0010,1010)
+  /// Patient's Age'*, replacing any existing value. The inserted age is
+  /// returned.
+  ///
+  /// See [`age_string::StructuredAge::from_dates()`] for details on how the
+  /// unit of the age is chosen.
+  ///
+  pub fn insert_derived_patient_age(
+    &mut self,
+  ) -> Result<age_string::StructuredAge, DataError> {
+    let age = patient_age::derive(self)?;
+    self.insert_age_string_value(&dictionary::PATIENT_AGE, &age)?;
+
+    Ok(age)
+  }
+
+  /// Returns whether *'(0010,1010) Patient's Age'* is consistent with
+  /// *'(0010,0030) Patient's Birth Date'* and the study or acquisition date.
+  /// Returns an error if any of these data elements are absent or invalid.
+  ///
+  pub fn is_patient_age_consistent(&self) -> Result<bool, DataError> {
+    patient_age::verify(self)
+  }
+
   /// Prints a data set to stdout formatted for readability.
   ///
   #[cfg(feature = "std")]
@@ -1099,6 +1272,125 @@ impl DataSet {
     })
   }
 
+  /// Returns the private creator block reserved in the specified private group
+  /// for the given private creator name. This is the `XX` in the tag of the
+  /// *'(gggg,00XX) Private Creator'* data element whose value matches the name
+  /// exactly.
+  ///
+  /// Returns `None` if the group isn't private or no block in it is reserved
+  /// for the private creator.
+  ///
+  /// Ref: PS3.5 7.8.1.
+  ///
+  pub fn find_private_creator_block(
+    &self,
+    group: u16,
+    private_creator: &str,
+  ) -> Option<u8> {
+    if group & 1 == 0 {
+      return None;
+    }
+
+    (0x10..=0xFF).find(|block| {
+      self.get_string(DataElementTag::new(group, u16::from(*block)))
+        == Ok(private_creator)
+    })
+  }
+
+  /// Returns the private creator block reserved in the specified private group
+  /// for the given private creator name, reserving a new block if there isn't
+  /// one already. A new block is reserved by inserting a *'(gggg,00XX) Private
+  /// Creator'* data element into the lowest available slot, where a slot is
+  /// available if neither it nor any data elements in its block are present.
+  ///
+  /// An error is returned if the group can't hold private data elements, the
+  /// private creator name is invalid, or all 240 blocks in the group are in
+  /// use.
+  ///
+  /// Ref: PS3.5 7.8.1.
+  ///
+  pub fn allocate_private_creator_block(
+    &mut self,
+    group: u16,
+    private_creator: &str,
+  ) -> Result<u8, DataError> {
+    let path =
+      DataSetPath::new_with_data_element(DataElementTag::new(group, 0x0010));
+
+    // Groups 0001, 0003, 0005, 0007, and FFFF aren't allowed to be used for
+    // private data elements
+    if group & 1 == 0 || group <= 0x0007 || group == 0xFFFF {
+      return Err(
+        DataError::new_value_invalid(format!(
+          "Group {group:04X} can't hold private data elements"
+        ))
+        .with_path(&path),
+      );
+    }
+
+    if private_creator.trim_matches(' ').is_empty() {
+      return Err(
+        DataError::new_value_invalid("Private creator name is empty")
+          .with_path(&path),
+      );
+    }
+
+    if let Some(block) = self.find_private_creator_block(group, private_creator)
+    {
+      return Ok(block);
+    }
+
+    let block = (0x10..=0xFF)
+      .find(|block| {
+        self
+          .0
+          .range(
+            DataElementTag::new_private(group, *block, 0x00)
+              ..=DataElementTag::new_private(group, *block, 0xFF),
+          )
+          .next()
+          .is_none()
+          && !self.has(DataElementTag::new(group, u16::from(*block)))
+      })
+      .ok_or_else(|| {
+        DataError::new_value_invalid(format!(
+          "No private creator blocks are available in group {group:04X}"
+        ))
+        .with_path(&path)
+      })?;
+
+    let tag = DataElementTag::new(group, u16::from(block));
+
+    let value = DataElementValue::new_long_string(&[private_creator])
+      .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(tag)))?;
+
+    self.insert(tag, value);
+
+    Ok(block)
+  }
+
+  /// Inserts a private data element into a data set at the specified offset in
+  /// the block reserved for the given private creator, reserving the block if
+  /// needed. See [`Self::allocate_private_creator_block()`].
+  ///
+  /// Returns the tag the data element was inserted at. If there is already a
+  /// value for that tag then it is replaced with the new value.
+  ///
+  pub fn insert_private_value(
+    &mut self,
+    group: u16,
+    private_creator: &str,
+    offset: u8,
+    value: DataElementValue,
+  ) -> Result<DataElementTag, DataError> {
+    let block = self.allocate_private_creator_block(group, private_creator)?;
+
+    let tag = DataElementTag::new_private(group, block, offset);
+    self.insert(tag, value);
+
+    Ok(tag)
+  }
+
   /// Returns a new data set containing just the private tags for the given
   /// group and private creator name in a data set. The group number must always
   /// be odd for private data elements, and the private creator name must match
@@ -1112,31 +1404,16 @@ impl DataSet {
     group: u16,
     private_creator: &str,
   ) -> Result<Self, String> {
-    if group & 2 == 0 {
+    if group & 1 == 0 {
       return Err("Private group number is even".to_string());
     }
 
-    let private_creator_value =
-      DataElementValue::new_long_string(&[private_creator])
-        .map_err(|_| "Private creator name is invalid")?;
-
-    // Search for a matching `(gggg,00XX) Private Creator' data element.
-    // Ref: PS3.5 7.8.1.
-    let mut private_creator_element = None;
-    for element in 0x10..=0xFF {
-      if self.0.get(&DataElementTag::new(group, element))
-        == Some(&private_creator_value)
-      {
-        private_creator_element = Some(element);
-        break;
-      }
-    }
-
-    let private_creator_element = private_creator_element
+    let private_creator_element = self
+      .find_private_creator_block(group, private_creator)
       .ok_or(format!("Private creator '{private_creator}' not found"))?;
 
     // Calculate the range of element values to include in the returned data set
-    let element_start = private_creator_element << 8;
+    let element_start = u16::from(private_creator_element) << 8;
     let element_end = element_start | 0xFF;
 
     // Filter this data set to only include the relevant private data elements
@@ -1209,3 +1486,162 @@ fn invalid_insert_error<T>(item: &dictionary::Item) -> Result<T, DataError> {
     ))),
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn allocate_private_creator_block_test() {
+    let mut data_set = DataSet::new();
+
+    assert_eq!(
+      data_set.find_private_creator_block(0x0029, "VENDOR A"),
+      None
+    );
+
+    assert_eq!(
+      data_set.allocate_private_creator_block(0x0029, "VENDOR A"),
+      Ok(0x10)
+    );
+    assert_eq!(
+      data_set.get_string(DataElementTag::new(0x0029, 0x0010)),
+      Ok("VENDOR A")
+    );
+
+    // An existing block is reused
+    assert_eq!(
+      data_set.allocate_private_creator_block(0x0029, "VENDOR A"),
+      Ok(0x10)
+    );
+    assert_eq!(
+      data_set.find_private_creator_block(0x0029, "VENDOR A"),
+      Some(0x10)
+    );
+
+    // Slots with orphaned private data elements in their block are skipped
+    data_set.insert(
+      DataElementTag::new(0x0029, 0x1105),
+      DataElementValue::new_long_string(&["ORPHAN"]).unwrap(),
+    );
+    assert_eq!(
+      data_set.allocate_private_creator_block(0x0029, "VENDOR B"),
+      Ok(0x12)
+    );
+
+    assert!(
+      data_set
+        .allocate_private_creator_block(0x0028, "VENDOR A")
+        .is_err()
+    );
+    assert!(
+      data_set
+        .allocate_private_creator_block(0x0007, "VENDOR A")
+        .is_err()
+    );
+    assert!(data_set.allocate_private_creator_block(0x0029, "").is_err());
+  }
+
+  #[test]
+  fn allocate_private_creator_block_full_test() {
+    let mut data_set = DataSet::new();
+
+    for i in 0x10..=0xFF {
+      data_set
+        .allocate_private_creator_block(0x0009, &format!("VENDOR {i}"))
+        .unwrap();
+    }
+
+    assert!(
+      data_set
+        .allocate_private_creator_block(0x0009, "VENDOR")
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn insert_private_value_test() {
+    let mut data_set = DataSet::new();
+
+    data_set.insert(
+      DataElementTag::new(0x0009, 0x0010),
+      DataElementValue::new_long_string(&["VENDOR A"]).unwrap(),
+    );
+
+    let value = DataElementValue::new_short_string(&["VALUE"]).unwrap();
+
+    assert_eq!(
+      data_set.insert_private_value(0x0009, "VENDOR B", 0x02, value.clone()),
+      Ok(DataElementTag::new(0x0009, 0x1102))
+    );
+    assert_eq!(
+      data_set.get_string(DataElementTag::new(0x0009, 0x0011)),
+      Ok("VENDOR B")
+    );
+    assert_eq!(
+      data_set.private_block(0x0009, "VENDOR B"),
+      Ok(DataSet::from_iter([(
+        DataElementTag::new(0x0009, 0x1102),
+        value
+      )]))
+    );
+  }
+
+  #[test]
+  fn insert_strings_test() {
+    let mut data_set = DataSet::new();
+
+    let image_type = vec!["ORIGINAL".to_string(), "PRIMARY".to_string()];
+    data_set
+      .insert_strings(&dictionary::IMAGE_TYPE, &image_type)
+      .unwrap();
+
+    assert_eq!(
+      data_set.get_strings(dictionary::IMAGE_TYPE.tag),
+      Ok(vec!["ORIGINAL", "PRIMARY"])
+    );
+
+    assert!(
+      data_set
+        .insert_strings(&dictionary::PATIENT_ID, ["A", "B"])
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn insert_ints_test() {
+    let mut data_set = DataSet::new();
+
+    data_set
+      .insert_ints(&dictionary::ROWS, core::iter::once(512u16))
+      .unwrap();
+
+    assert_eq!(data_set.get_int::<u16>(dictionary::ROWS.tag), Ok(512));
+
+    assert_eq!(
+      data_set.insert_ints(&dictionary::COLUMNS, [-1]),
+      Err(
+        DataError::new_value_invalid(
+          "Value -1 is out of range for the US VR".to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::COLUMNS.tag
+        ))
+      )
+    );
+  }
+
+  #[test]
+  fn insert_floats_test() {
+    let mut data_set = DataSet::new();
+
+    data_set
+      .insert_floats(&dictionary::PIXEL_SPACING, vec![0.5f32, 0.5])
+      .unwrap();
+
+    assert_eq!(
+      data_set.get_floats(dictionary::PIXEL_SPACING.tag),
+      Ok(vec![0.5, 0.5])
+    );
+  }
+}
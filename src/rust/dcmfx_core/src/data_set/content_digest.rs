@@ -0,0 +1,160 @@
+//! Computation of a digest over the semantic content of a data set, which is
+//! used to detect changes between copies of a data set that may have been
+//! serialized differently.
+
+use sha2::{Digest, Sha256};
+
+use crate::{DataElementTag, DataElementValue, DataSet, dictionary};
+
+/// Data elements whose values commonly change when a data set is copied or
+/// re-serialized, without its semantic content changing. These can be passed
+/// to [`DataSet::content_digest()`] as the tags to exclude.
+///
+pub const VOLATILE_TAGS: [DataElementTag; 4] = [
+  dictionary::INSTANCE_CREATION_DATE.tag,
+  dictionary::INSTANCE_CREATION_TIME.tag,
+  dictionary::INSTANCE_CREATOR_UID.tag,
+  dictionary::INSTANCE_COERCION_DATE_TIME.tag,
+];
+
+/// Adds the semantic content of a data set to a SHA-256 hasher.
+///
+/// File Meta Information, group length, and data set trailing padding data
+/// elements are always excluded, as are any data elements with a tag in
+/// `excluded_tags`. Trailing padding on string values and the basic offset
+/// table of encapsulated pixel data are also excluded because they are
+/// details of serialization.
+///
+pub fn update(
+  hasher: &mut Sha256,
+  data_set: &DataSet,
+  excluded_tags: &[DataElementTag],
+) {
+  for (tag, value) in data_set.iter() {
+    if tag.is_file_meta_information()
+      || tag.element == 0x0000
+      || *tag == dictionary::DATA_SET_TRAILING_PADDING.tag
+      || excluded_tags.contains(tag)
+    {
+      continue;
+    }
+
+    update_tag(hasher, *tag);
+    hasher.update(value.value_representation().to_bytes());
+
+    update_value(hasher, value, excluded_tags);
+  }
+}
+
+fn update_value(
+  hasher: &mut Sha256,
+  value: &DataElementValue,
+  excluded_tags: &[DataElementTag],
+) {
+  if let Ok(bytes) = value.bytes() {
+    let mut bytes: &[u8] = bytes;
+
+    if value.value_representation().is_string() {
+      while let [rest @ .., 0x00 | 0x20] = bytes {
+        bytes = rest;
+      }
+    }
+
+    update_bytes(hasher, bytes);
+  } else if let Ok(items) = value.encapsulated_pixel_data() {
+    let fragments = items.get(1..).unwrap_or_default();
+
+    hasher.update((fragments.len() as u64).to_le_bytes());
+    for fragment in fragments {
+      update_bytes(hasher, fragment);
+    }
+  } else if let Ok(items) = value.sequence_items() {
+    hasher.update((items.len() as u64).to_le_bytes());
+    for item in items {
+      update(hasher, item, excluded_tags);
+      update_tag(hasher, dictionary::ITEM_DELIMITATION_ITEM.tag);
+    }
+  }
+}
+
+fn update_tag(hasher: &mut Sha256, tag: DataElementTag) {
+  hasher.update(tag.group.to_le_bytes());
+  hasher.update(tag.element.to_le_bytes());
+}
+
+fn update_bytes(hasher: &mut Sha256, bytes: &[u8]) {
+  hasher.update((bytes.len() as u64).to_le_bytes());
+  hasher.update(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn test_data_set() -> DataSet {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2"])
+      .unwrap();
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(&dictionary::REFERENCED_IMAGE_SEQUENCE, vec![item])
+      .unwrap();
+
+    data_set
+  }
+
+  #[test]
+  fn content_digest_ignores_serialization_details_test() {
+    let data_set = test_data_set();
+    let digest = data_set.content_digest(&VOLATILE_TAGS);
+
+    let mut other = data_set.clone();
+    other
+      .insert_string_value(&dictionary::TRANSFER_SYNTAX_UID, &["1.2.840.10008"])
+      .unwrap();
+    other
+      .insert_string_value(&dictionary::INSTANCE_CREATOR_UID, &["1.2.3"])
+      .unwrap();
+    other
+      .insert_binary_value(
+        dictionary::PATIENT_ID.tag,
+        crate::ValueRepresentation::LongString,
+        b"123 ".to_vec().into(),
+      )
+      .unwrap();
+
+    assert_eq!(other.content_digest(&VOLATILE_TAGS), digest);
+  }
+
+  #[test]
+  fn content_digest_detects_changes_test() {
+    let data_set = test_data_set();
+    let digest = data_set.content_digest(&[]);
+
+    let mut other = data_set.clone();
+    other
+      .insert_string_value(&dictionary::PATIENT_ID, &["124"])
+      .unwrap();
+    assert_ne!(other.content_digest(&[]), digest);
+
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.3"])
+      .unwrap();
+    let mut other = data_set.clone();
+    other
+      .insert_sequence_value(&dictionary::REFERENCED_IMAGE_SEQUENCE, vec![item])
+      .unwrap();
+    assert_ne!(other.content_digest(&[]), digest);
+
+    assert_eq!(
+      other.content_digest(&[dictionary::REFERENCED_SOP_INSTANCE_UID.tag]),
+      data_set.content_digest(&[dictionary::REFERENCED_SOP_INSTANCE_UID.tag])
+    );
+  }
+}
@@ -0,0 +1,185 @@
+//! Flattening of data sets into lists of data elements, and extraction of
+//! selected data elements into rows for tabular export.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::{DataElementTag, DataElementValue, DataSet, DataSetPath};
+
+/// A single data element in a flattened data set, along with the path to it
+/// from the root data set.
+///
+pub type FlattenedDataElement<'a> =
+  (DataSetPath, DataElementTag, &'a DataElementValue);
+
+/// A row of values extracted from a data set by [`DataSet::to_rows()`]. Each
+/// row corresponds to one data set, either the root data set or a sequence
+/// item, that contains at least one of the requested data elements.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataSetRow<'a> {
+  /// The path to the data set that this row's values were taken from. This is
+  /// empty for the root data set, and otherwise ends with a sequence item
+  /// index.
+  pub path: DataSetPath,
+
+  /// The values of the requested data elements, in the same order as the tags
+  /// that were requested. Data elements not present in the data set are
+  /// `None`.
+  pub values: Vec<Option<&'a DataElementValue>>,
+}
+
+/// Appends every data element in a data set to the given list, recursing into
+/// sequence items. Sequence data elements are included in the output prior to
+/// the data elements in their items.
+///
+pub fn flatten<'a>(
+  data_set: &'a DataSet,
+  path: &mut DataSetPath,
+  output: &mut Vec<FlattenedDataElement<'a>>,
+) {
+  for (tag, value) in data_set.iter() {
+    path.add_data_element(*tag).unwrap();
+
+    output.push((path.clone(), *tag, value));
+
+    if let Ok(items) = value.sequence_items() {
+      for (index, item) in items.iter().enumerate() {
+        path.add_sequence_item(index).unwrap();
+        flatten(item, path, output);
+        path.pop().unwrap();
+      }
+    }
+
+    path.pop().unwrap();
+  }
+}
+
+/// Appends a row to the given list for every data set, including nested
+/// sequence items, that contains at least one of the specified tags.
+///
+pub fn to_rows<'a>(
+  data_set: &'a DataSet,
+  tags: &[DataElementTag],
+  path: &mut DataSetPath,
+  output: &mut Vec<DataSetRow<'a>>,
+) {
+  let values: Vec<_> = tags
+    .iter()
+    .map(|tag| data_set.get_value(*tag).ok())
+    .collect();
+
+  if values.iter().any(Option::is_some) {
+    output.push(DataSetRow {
+      path: path.clone(),
+      values,
+    });
+  }
+
+  for (tag, value) in data_set.iter() {
+    if let Ok(items) = value.sequence_items() {
+      path.add_data_element(*tag).unwrap();
+
+      for (index, item) in items.iter().enumerate() {
+        path.add_sequence_item(index).unwrap();
+        to_rows(item, tags, path, output);
+        path.pop().unwrap();
+      }
+
+      path.pop().unwrap();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dictionary;
+
+  fn test_data_set() -> DataSet {
+    let mut item_0 = DataSet::new();
+    item_0
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2"])
+      .unwrap();
+
+    let mut item_1 = DataSet::new();
+    item_1
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.3"])
+      .unwrap();
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(
+        &dictionary::REFERENCED_IMAGE_SEQUENCE,
+        vec![item_0, item_1],
+      )
+      .unwrap();
+
+    data_set
+  }
+
+  #[test]
+  fn flatten_test() {
+    let data_set = test_data_set();
+
+    let paths: Vec<_> = data_set
+      .flatten()
+      .into_iter()
+      .map(|(path, tag, _)| (path.to_string(), tag))
+      .collect();
+
+    assert_eq!(
+      paths,
+      vec![
+        (
+          "00081140".to_string(),
+          dictionary::REFERENCED_IMAGE_SEQUENCE.tag
+        ),
+        (
+          "00081140/[0]/00081155".to_string(),
+          dictionary::REFERENCED_SOP_INSTANCE_UID.tag
+        ),
+        (
+          "00081140/[1]/00081155".to_string(),
+          dictionary::REFERENCED_SOP_INSTANCE_UID.tag
+        ),
+        ("00100020".to_string(), dictionary::PATIENT_ID.tag),
+      ]
+    );
+  }
+
+  #[test]
+  fn to_rows_test() {
+    let data_set = test_data_set();
+
+    let rows: Vec<_> = data_set
+      .to_rows(&[
+        dictionary::PATIENT_ID.tag,
+        dictionary::REFERENCED_SOP_INSTANCE_UID.tag,
+      ])
+      .into_iter()
+      .map(|row| {
+        (
+          row.path.to_string(),
+          row
+            .values
+            .iter()
+            .map(|value| value.and_then(|v| v.get_string().ok()))
+            .collect::<Vec<_>>(),
+        )
+      })
+      .collect();
+
+    assert_eq!(
+      rows,
+      vec![
+        ("".to_string(), vec![Some("123"), None]),
+        ("00081140/[0]".to_string(), vec![None, Some("1.2")]),
+        ("00081140/[1]".to_string(), vec![None, Some("1.3")]),
+      ]
+    );
+  }
+}
@@ -0,0 +1,171 @@
+//! Derivation of the *'(0010,1010) Patient's Age'* data element from the
+//! patient's birth date and the date of the study or acquisition, and
+//! verification of existing values.
+
+use crate::{
+  DataError, DataSet, DataSetPath, StructuredAge, StructuredDate, dictionary,
+};
+
+/// Returns the date that a patient's age is relative to. This is the
+/// *'(0008,0020) Study Date'* if present, otherwise the date part of the
+/// *'(0008,002A) Acquisition DateTime'*.
+///
+pub fn reference_date(data_set: &DataSet) -> Result<StructuredDate, DataError> {
+  if data_set.has(dictionary::STUDY_DATE.tag) {
+    return data_set.get_date(dictionary::STUDY_DATE.tag);
+  }
+
+  let tag = dictionary::ACQUISITION_DATE_TIME.tag;
+  let date_time = data_set.get_date_time(tag)?;
+
+  match (date_time.month, date_time.day) {
+    (Some(month), Some(day)) => Ok(StructuredDate {
+      year: date_time.year,
+      month,
+      day,
+    }),
+
+    _ => Err(
+      DataError::new_value_invalid(
+        "Acquisition DateTime doesn't specify a full date",
+      )
+      .with_path(&DataSetPath::new_with_data_element(tag)),
+    ),
+  }
+}
+
+/// Computes the patient's age from *'(0010,0030) Patient's Birth Date'* and
+/// the date returned by [`reference_date()`]. See
+/// [`StructuredAge::from_dates()`] for details on how the unit is chosen.
+///
+pub fn derive(data_set: &DataSet) -> Result<StructuredAge, DataError> {
+  let birth_date = data_set.get_date(dictionary::PATIENT_BIRTH_DATE.tag)?;
+  let date = reference_date(data_set)?;
+
+  StructuredAge::from_dates(&birth_date, &date).map_err(|e| {
+    e.with_path(&DataSetPath::new_with_data_element(
+      dictionary::PATIENT_AGE.tag,
+    ))
+  })
+}
+
+/// Returns whether the data set's *'(0010,1010) Patient's Age'* is consistent
+/// with its *'(0010,0030) Patient's Birth Date'* and the date returned by
+/// [`reference_date()`].
+///
+pub fn verify(data_set: &DataSet) -> Result<bool, DataError> {
+  let age = data_set.get_age(dictionary::PATIENT_AGE.tag)?;
+  let birth_date = data_set.get_date(dictionary::PATIENT_BIRTH_DATE.tag)?;
+  let date = reference_date(data_set)?;
+
+  age.matches_dates(&birth_date, &date).map_err(|e| {
+    e.with_path(&DataSetPath::new_with_data_element(
+      dictionary::PATIENT_AGE.tag,
+    ))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::StructuredDateTime;
+  use crate::data_element_value::age_string::AgeUnit;
+
+  #[test]
+  fn derive_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_date_value(
+        &dictionary::PATIENT_BIRTH_DATE,
+        &StructuredDate {
+          year: 2024,
+          month: 3,
+          day: 10,
+        },
+      )
+      .unwrap();
+    data_set
+      .insert_date_time_value(
+        &dictionary::ACQUISITION_DATE_TIME,
+        &StructuredDateTime {
+          year: 2024,
+          month: Some(3),
+          day: Some(24),
+          hour: Some(9),
+          minute: None,
+          second: None,
+          time_zone_offset: None,
+        },
+      )
+      .unwrap();
+
+    assert_eq!(
+      derive(&data_set),
+      Ok(StructuredAge {
+        number: 14,
+        unit: AgeUnit::Days
+      })
+    );
+
+    data_set
+      .insert_date_value(
+        &dictionary::STUDY_DATE,
+        &StructuredDate {
+          year: 2060,
+          month: 3,
+          day: 9,
+        },
+      )
+      .unwrap();
+
+    assert_eq!(
+      derive(&data_set),
+      Ok(StructuredAge {
+        number: 35,
+        unit: AgeUnit::Years
+      })
+    );
+  }
+
+  #[test]
+  fn verify_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_date_value(
+        &dictionary::PATIENT_BIRTH_DATE,
+        &StructuredDate {
+          year: 1990,
+          month: 5,
+          day: 1,
+        },
+      )
+      .unwrap();
+    data_set
+      .insert_date_value(
+        &dictionary::STUDY_DATE,
+        &StructuredDate {
+          year: 2025,
+          month: 4,
+          day: 30,
+        },
+      )
+      .unwrap();
+
+    let mut verify_age = |number| {
+      data_set
+        .insert_age_string_value(
+          &dictionary::PATIENT_AGE,
+          &StructuredAge {
+            number,
+            unit: AgeUnit::Years,
+          },
+        )
+        .unwrap();
+
+      verify(&data_set)
+    };
+
+    assert_eq!(verify_age(34), Ok(true));
+    assert_eq!(verify_age(35), Ok(false));
+  }
+}
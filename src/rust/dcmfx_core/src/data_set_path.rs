@@ -1,15 +1,10 @@
 //! A path in a data set that specifies the precise location of a specific data
 //! element or sequence item.
 
-use crate::{DataElementTag, dictionary};
+use crate::{DataElementTag, ErrorDetails, dictionary, error_details};
 
 #[cfg(not(feature = "std"))]
-use alloc::{
-  format,
-  string::{String, ToString},
-  vec,
-  vec::Vec,
-};
+use alloc::{format, string::String, vec, vec::Vec};
 
 /// A path in a data set that specifies the precise location of a specific data
 /// element or sequence item. Entries in a data set path are separated by a
@@ -25,12 +20,15 @@ use alloc::{
 ///   data element in the second item of the *'(0018,6011) Sequence of
 ///   Ultrasound Regions'* sequence.
 ///
-#[derive(Clone, Debug, Default, PartialEq)]
+/// Data set paths are ordered by comparing their entries in turn, which sorts
+/// a path directly before the paths nested inside it.
+///
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct DataSetPath(Vec<DataSetPathEntry>);
 
 /// An individual entry in a [`DataSetPath`].
 ///
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum DataSetPathEntry {
   DataElement { tag: DataElementTag },
   SequenceItem { index: usize },
@@ -74,6 +72,31 @@ impl DataSetPath {
     )
   }
 
+  /// Returns the nesting depth of the data set that a data set path points
+  /// into, which is the number of sequence items in the path. Root-level data
+  /// elements have a depth of zero.
+  ///
+  pub fn depth(&self) -> usize {
+    self.sequence_item_count()
+  }
+
+  /// Returns the parent of a data set path, i.e. the path with its final entry
+  /// removed. Returns `None` for an empty path.
+  ///
+  pub fn parent(&self) -> Option<Self> {
+    self
+      .0
+      .split_last()
+      .map(|(_, entries)| Self(entries.to_vec()))
+  }
+
+  /// Returns whether a data set path starts with the entries of another data
+  /// set path, i.e. whether it is equal to or nested inside the other path.
+  ///
+  pub fn starts_with(&self, other: &Self) -> bool {
+    self.0.starts_with(&other.0)
+  }
+
   /// Returns the number of sequence items present in a data set path.
   ///
   pub fn sequence_item_count(&self) -> usize {
@@ -121,13 +144,13 @@ impl DataSetPath {
   pub fn add_data_element(
     &mut self,
     tag: DataElementTag,
-  ) -> Result<(), String> {
+  ) -> Result<(), ErrorDetails> {
     match self.0.last() {
       None | Some(DataSetPathEntry::SequenceItem { .. }) => {
         self.0.push(DataSetPathEntry::DataElement { tag });
         Ok(())
       }
-      _ => Err(format!(
+      _ => Err(error_details!(
         "Invalid data set path entry: {}",
         tag.to_hex_string()
       )),
@@ -137,22 +160,25 @@ impl DataSetPath {
   /// Adds a new entry onto a data set path that specifies a sequence item
   /// index. This is only valid when the current path is a data element tag.
   ///
-  pub fn add_sequence_item(&mut self, index: usize) -> Result<(), String> {
+  pub fn add_sequence_item(
+    &mut self,
+    index: usize,
+  ) -> Result<(), ErrorDetails> {
     match self.0.last() {
       Some(DataSetPathEntry::DataElement { .. }) => {
         self.0.push(DataSetPathEntry::SequenceItem { index });
         Ok(())
       }
-      _ => Err(format!("Invalid data set path entry: [{index}]")),
+      _ => Err(error_details!("Invalid data set path entry: [{index}]")),
     }
   }
 
   /// Removes the last entry in a data set path.
   ///
   #[allow(clippy::result_unit_err)]
-  pub fn pop(&mut self) -> Result<&mut Self, String> {
+  pub fn pop(&mut self) -> Result<&mut Self, ErrorDetails> {
     if self.0.is_empty() {
-      Err("Data set path is empty".to_string())
+      Err("Data set path is empty".into())
     } else {
       self.0.pop();
       Ok(self)
@@ -205,6 +231,32 @@ impl DataSetPath {
       .collect::<Vec<_>>()
       .join(" / ")
   }
+
+  /// Formats a data set path in a compact form where sequence item indices
+  /// follow their sequence's tag and nested data elements are separated by a
+  /// period, e.g. `"00186011[1].00186014"`.
+  ///
+  pub fn to_compact_string(&self) -> String {
+    let mut s = String::new();
+
+    for entry in self.0.iter() {
+      match entry {
+        DataSetPathEntry::DataElement { tag } => {
+          if !s.is_empty() {
+            s.push('.');
+          }
+
+          s.push_str(&tag.to_hex_string());
+        }
+
+        DataSetPathEntry::SequenceItem { index } => {
+          s.push_str(&format!("[{index}]"));
+        }
+      }
+    }
+
+    s
+  }
 }
 
 impl core::fmt::Display for DataSetPath {
@@ -240,18 +292,20 @@ mod tests {
 
     assert_eq!(&path.to_string(), "12345678");
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       path.add_data_element(DataElementTag::new(0x1234, 0x5678)),
-      Err("Invalid data set path entry: 12345678".to_string())
+      Err("Invalid data set path entry: 12345678".into())
     );
 
     assert_eq!(path.add_sequence_item(2), Ok(()));
 
     assert_eq!(&path.to_string(), "12345678/[2]");
 
+    #[cfg(not(feature = "compact_errors"))]
     assert_eq!(
       path.add_sequence_item(2),
-      Err("Invalid data set path entry: [2]".to_string())
+      Err("Invalid data set path entry: [2]".into())
     );
 
     assert_eq!(
@@ -284,4 +338,58 @@ mod tests {
       Ok(path.clone())
     );
   }
+
+  #[test]
+  fn navigation_test() {
+    let path = DataSetPath::from_string("00186011/[1]/00186014").unwrap();
+
+    assert_eq!(path.depth(), 1);
+    assert_eq!(
+      path.parent(),
+      Some(DataSetPath::from_string("00186011/[1]").unwrap())
+    );
+    assert_eq!(DataSetPath::new().parent(), None);
+
+    assert!(path.starts_with(&DataSetPath::from_string("00186011").unwrap()));
+    assert!(path.starts_with(&path));
+    assert!(path.starts_with(&DataSetPath::new()));
+    assert!(!path.starts_with(&DataSetPath::from_string("00186012").unwrap()));
+  }
+
+  #[test]
+  fn to_compact_string_test() {
+    assert_eq!(DataSetPath::new().to_compact_string(), "");
+
+    assert_eq!(
+      DataSetPath::from_string("00186011/[1]/00186014")
+        .unwrap()
+        .to_compact_string(),
+      "00186011[1].00186014"
+    );
+  }
+
+  #[test]
+  fn ord_test() {
+    let mut paths = [
+      "00186011/[1]/00186014",
+      "00100010",
+      "00186011/[0]/00186014",
+      "00186011",
+      "00186011/[0]",
+    ]
+    .map(|s| DataSetPath::from_string(s).unwrap());
+
+    paths.sort();
+
+    assert_eq!(
+      paths.map(|path| path.to_string()),
+      [
+        "00100010",
+        "00186011",
+        "00186011/[0]",
+        "00186011/[0]/00186014",
+        "00186011/[1]/00186014",
+      ]
+    );
+  }
 }
@@ -1,7 +1,12 @@
 //! Defines a trait implemented by all error types in DCMfx.
 
 #[cfg(not(feature = "std"))]
-use alloc::{string::String, vec::Vec};
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
 
 #[cfg(feature = "std")]
 use std::io::Write;
@@ -9,6 +14,8 @@ use std::io::Write;
 #[cfg(feature = "std")]
 use owo_colors::{OwoColorize, Stream::Stderr};
 
+use crate::{DataSetPath, TransferSyntax};
+
 /// Error trait implemented by all error types in DCMfx.
 ///
 pub trait DcmfxError {
@@ -25,6 +32,145 @@ pub trait DcmfxError {
   }
 }
 
+/// Describes where an error occurred, i.e. the data set path, the index of the
+/// frame of pixel data, and the transfer syntax, for those that are known.
+///
+/// Errors that implement [`ErrorContextChain`] can have an error context
+/// attached as they propagate upwards, with each level adding the context it
+/// knows about. Context added closer to where the error occurred takes
+/// precedence.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ErrorContext {
+  pub path: Option<DataSetPath>,
+  pub frame_index: Option<usize>,
+  pub transfer_syntax: Option<&'static TransferSyntax>,
+}
+
+impl ErrorContext {
+  /// Creates a new empty error context.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the data set path where the error occurred.
+  ///
+  pub fn with_path(mut self, path: &DataSetPath) -> Self {
+    self.path = Some(path.clone());
+    self
+  }
+
+  /// Sets the index of the frame of pixel data being processed when the error
+  /// occurred.
+  ///
+  pub fn with_frame_index(mut self, frame_index: usize) -> Self {
+    self.frame_index = Some(frame_index);
+    self
+  }
+
+  /// Sets the transfer syntax in use when the error occurred.
+  ///
+  pub fn with_transfer_syntax(
+    mut self,
+    transfer_syntax: &'static TransferSyntax,
+  ) -> Self {
+    self.transfer_syntax = Some(transfer_syntax);
+    self
+  }
+
+  /// Returns whether this error context has no details set.
+  ///
+  pub fn is_empty(&self) -> bool {
+    self.path.is_none()
+      && self.frame_index.is_none()
+      && self.transfer_syntax.is_none()
+  }
+
+  /// Fills in any details not set in this error context with those from an
+  /// outer error context. Details already set in this error context are kept.
+  ///
+  pub fn merge(mut self, outer: ErrorContext) -> Self {
+    self.path = self.path.or(outer.path);
+    self.frame_index = self.frame_index.or(outer.frame_index);
+    self.transfer_syntax = self.transfer_syntax.or(outer.transfer_syntax);
+    self
+  }
+
+  /// Returns lines of text that describe this error context, in the same
+  /// format as used by [`DcmfxError::to_lines()`].
+  ///
+  pub fn to_lines(&self) -> Vec<String> {
+    let mut lines = vec![];
+
+    if let Some(path) = &self.path {
+      lines.push(format!("  Path: {}", path.to_detailed_string()));
+    }
+
+    if let Some(frame_index) = self.frame_index {
+      lines.push(format!("  Frame: {frame_index}"));
+    }
+
+    if let Some(transfer_syntax) = self.transfer_syntax {
+      lines.push(format!("  Transfer syntax: {}", transfer_syntax.name));
+    }
+
+    lines
+  }
+
+  /// Appends the lines for this error context to the lines describing an error.
+  /// Lines for details the error has already described are omitted.
+  ///
+  pub fn append_to_lines(&self, lines: &mut Vec<String>) {
+    for line in self.to_lines() {
+      let label = line.split(':').next().unwrap_or("").to_string() + ":";
+
+      if !lines.iter().any(|l| l.starts_with(&label)) {
+        lines.push(line);
+      }
+    }
+  }
+}
+
+impl core::fmt::Display for ErrorContext {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    let mut parts = vec![];
+
+    if let Some(path) = &self.path {
+      parts.push(format!("path: {path}"));
+    }
+
+    if let Some(frame_index) = self.frame_index {
+      parts.push(format!("frame: {frame_index}"));
+    }
+
+    if let Some(transfer_syntax) = self.transfer_syntax {
+      parts.push(format!("transfer syntax: {}", transfer_syntax.name));
+    }
+
+    write!(f, "{}", parts.join(", "))
+  }
+}
+
+/// Implemented by error types that are able to have an [`ErrorContext`]
+/// attached to them.
+///
+pub trait ErrorContextChain: Sized {
+  /// Wraps an error with a context describing where it occurred. If the error
+  /// already has a context then the two are merged, with existing details
+  /// taking precedence.
+  ///
+  fn with_context(self, context: ErrorContext) -> Self;
+
+  /// Returns the context attached to an error, if any.
+  ///
+  fn context(&self) -> Option<&ErrorContext>;
+
+  /// Returns the underlying error with any attached context removed.
+  ///
+  fn without_context(&self) -> &Self;
+}
+
 /// Prints lines of error information to stderr.
 ///
 #[cfg(feature = "std")]
@@ -41,3 +187,55 @@ pub fn print_error_lines(lines: &[String]) {
 
   eprintln!();
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dictionary, transfer_syntax};
+
+  #[test]
+  fn merge_test() {
+    let inner = ErrorContext::new().with_frame_index(2);
+    let outer = ErrorContext::new()
+      .with_frame_index(5)
+      .with_transfer_syntax(&transfer_syntax::JPEG_BASELINE_8BIT);
+
+    assert_eq!(
+      inner.merge(outer),
+      ErrorContext::new()
+        .with_frame_index(2)
+        .with_transfer_syntax(&transfer_syntax::JPEG_BASELINE_8BIT)
+    );
+  }
+
+  #[test]
+  fn to_lines_test() {
+    let context = ErrorContext::new()
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PIXEL_DATA.tag,
+      ))
+      .with_frame_index(3)
+      .with_transfer_syntax(&transfer_syntax::JPEG_BASELINE_8BIT);
+
+    let mut lines = vec![
+      "Error testing".to_string(),
+      "".to_string(),
+      "  Path: (7FE0,0010) PixelData".to_string(),
+    ];
+    context.append_to_lines(&mut lines);
+
+    assert_eq!(
+      lines.join("\n"),
+      r#"Error testing
+
+  Path: (7FE0,0010) PixelData
+  Frame: 3
+  Transfer syntax: JPEG Baseline (Process 1)"#
+    );
+
+    assert_eq!(
+      context.to_string(),
+      "path: 7FE00010, frame: 3, transfer syntax: JPEG Baseline (Process 1)"
+    );
+  }
+}
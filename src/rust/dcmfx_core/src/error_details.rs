@@ -0,0 +1,200 @@
+//! Provides the [`ErrorDetails`] type that holds the human-readable details of
+//! an error.
+//!
+//! By default, details are stored as a [`String`] so they can include values
+//! from the data that caused the error. When the `compact_errors` feature is
+//! enabled, details are instead stored as a `&'static str` that holds the
+//! message template, which means creating an error never allocates. Numeric
+//! context such as offsets and lengths is carried separately by the fields of
+//! the error types themselves and so is available in both modes.
+
+#[cfg(all(not(feature = "std"), not(feature = "compact_errors")))]
+use alloc::string::{String, ToString};
+
+#[cfg(all(not(feature = "std"), feature = "compact_errors"))]
+use alloc::string::String;
+
+#[doc(hidden)]
+#[cfg(not(feature = "std"))]
+pub use alloc::format as __format;
+
+#[doc(hidden)]
+#[cfg(feature = "std")]
+pub use std::format as __format;
+
+/// The details of an error, which are either a formatted [`String`], or a
+/// static message template when the `compact_errors` feature is enabled.
+///
+/// Use the [`crate::error_details!`] macro to create details from a format
+/// string.
+///
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ErrorDetails(Inner);
+
+#[cfg(not(feature = "compact_errors"))]
+type Inner = String;
+
+#[cfg(feature = "compact_errors")]
+type Inner = &'static str;
+
+/// The details used in place of a dynamically created string when the
+/// `compact_errors` feature is enabled.
+///
+#[cfg(feature = "compact_errors")]
+const DETAILS_OMITTED: &str = "<details omitted>";
+
+impl ErrorDetails {
+  /// Creates error details from a static message.
+  ///
+  #[cfg(not(feature = "compact_errors"))]
+  pub fn from_static(message: &'static str) -> Self {
+    Self(message.to_string())
+  }
+
+  /// Creates error details from a static message.
+  ///
+  #[cfg(feature = "compact_errors")]
+  pub const fn from_static(message: &'static str) -> Self {
+    Self(message)
+  }
+
+  /// Returns the error details as a string slice.
+  ///
+  pub fn as_str(&self) -> &str {
+    #[cfg(not(feature = "compact_errors"))]
+    return self.0.as_str();
+
+    #[cfg(feature = "compact_errors")]
+    return self.0;
+  }
+}
+
+impl core::ops::Deref for ErrorDetails {
+  type Target = str;
+
+  fn deref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl core::fmt::Debug for ErrorDetails {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    core::fmt::Debug::fmt(self.as_str(), f)
+  }
+}
+
+impl core::fmt::Display for ErrorDetails {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl From<&'static str> for ErrorDetails {
+  fn from(message: &'static str) -> Self {
+    Self::from_static(message)
+  }
+}
+
+impl From<String> for ErrorDetails {
+  #[cfg(not(feature = "compact_errors"))]
+  fn from(details: String) -> Self {
+    Self(details)
+  }
+
+  #[cfg(feature = "compact_errors")]
+  fn from(_details: String) -> Self {
+    Self(DETAILS_OMITTED)
+  }
+}
+
+impl From<ErrorDetails> for String {
+  #[cfg(not(feature = "compact_errors"))]
+  fn from(details: ErrorDetails) -> Self {
+    details.0
+  }
+
+  #[cfg(feature = "compact_errors")]
+  fn from(details: ErrorDetails) -> Self {
+    details.0.into()
+  }
+}
+
+impl PartialEq<str> for ErrorDetails {
+  fn eq(&self, other: &str) -> bool {
+    self.as_str() == other
+  }
+}
+
+impl PartialEq<&str> for ErrorDetails {
+  fn eq(&self, other: &&str) -> bool {
+    self.as_str() == *other
+  }
+}
+
+/// Creates [`ErrorDetails`](crate::error_details::ErrorDetails) from a format
+/// string and arguments in the same way as `format!`.
+///
+/// When the `compact_errors` feature is enabled the arguments are not
+/// formatted, and the format string itself is used as the details so that no
+/// allocation takes place.
+///
+#[cfg(not(feature = "compact_errors"))]
+#[macro_export]
+macro_rules! error_details {
+  ($fmt:literal $(, $arg:expr)* $(,)?) => {
+    $crate::error_details::ErrorDetails::from(
+      $crate::error_details::__format!($fmt $(, $arg)*)
+    )
+  };
+}
+
+/// Creates [`ErrorDetails`](crate::error_details::ErrorDetails) from a format
+/// string and arguments in the same way as `format!`.
+///
+/// When the `compact_errors` feature is enabled the arguments are not
+/// formatted, and the format string itself is used as the details so that no
+/// allocation takes place.
+///
+#[cfg(feature = "compact_errors")]
+#[macro_export]
+macro_rules! error_details {
+  ($fmt:literal $(, $arg:expr)* $(,)?) => {{
+    let _ = ::core::format_args!($fmt $(, $arg)*);
+    $crate::error_details::ErrorDetails::from_static($fmt)
+  }};
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(not(feature = "compact_errors"))]
+  #[test]
+  fn error_details_test() {
+    let value = 3;
+
+    assert_eq!(
+      error_details!("Value {value} is invalid"),
+      "Value 3 is invalid"
+    );
+    assert_eq!(
+      ErrorDetails::from("Static".to_string()).to_string(),
+      "Static"
+    );
+  }
+
+  #[cfg(feature = "compact_errors")]
+  #[test]
+  fn error_details_test() {
+    let value = 3;
+
+    assert_eq!(
+      error_details!("Value {value} is invalid"),
+      "Value {value} is invalid"
+    );
+    assert_eq!(
+      ErrorDetails::from("Static".to_string()).as_str(),
+      DETAILS_OMITTED
+    );
+  }
+}
@@ -1 +1,2 @@
 pub mod sop_common_module;
+pub mod sr;
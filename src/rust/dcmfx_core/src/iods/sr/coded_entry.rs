@@ -0,0 +1,131 @@
+//! A coded entry stored in an item of a code sequence.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::{DataElementTag, DataError, DataSet, DataSetPath, dictionary};
+
+use super::{get_optional_single_sequence_item, get_optional_string};
+
+/// A coded entry read from an item of a code sequence, e.g. the *'(0040,A043)
+/// Concept Name Code Sequence'* of an SR content item.
+///
+/// The code value is read from whichever of *'(0008,0100) Code Value'*,
+/// *'(0008,0119) Long Code Value'*, or *'(0008,0120) URN Code Value'* is
+/// present.
+///
+/// Ref: PS3.3 Section 8.8.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodedEntry {
+  pub code_value: String,
+  pub coding_scheme_designator: Option<String>,
+  pub coding_scheme_version: Option<String>,
+  pub code_meaning: String,
+}
+
+impl CodedEntry {
+  /// Creates a new [`CodedEntry`] from an item of a code sequence.
+  ///
+  pub fn from_data_set(item: &DataSet) -> Result<Self, DataError> {
+    let code_value = [
+      dictionary::CODE_VALUE.tag,
+      dictionary::LONG_CODE_VALUE.tag,
+      dictionary::URN_CODE_VALUE.tag,
+    ]
+    .into_iter()
+    .map(|tag| get_optional_string(item, tag))
+    .collect::<Result<Vec<_>, _>>()?
+    .into_iter()
+    .flatten()
+    .next()
+    .ok_or_else(|| {
+      DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(dictionary::CODE_VALUE.tag),
+      )
+    })?;
+
+    let code_meaning = get_optional_string(item, dictionary::CODE_MEANING.tag)?
+      .unwrap_or_default();
+
+    Ok(Self {
+      code_value,
+      coding_scheme_designator: get_optional_string(
+        item,
+        dictionary::CODING_SCHEME_DESIGNATOR.tag,
+      )?,
+      coding_scheme_version: get_optional_string(
+        item,
+        dictionary::CODING_SCHEME_VERSION.tag,
+      )?,
+      code_meaning,
+    })
+  }
+
+  /// Creates a new [`CodedEntry`] from a code sequence that has at most one
+  /// item. Returns `None` if the code sequence isn't present or is empty.
+  ///
+  pub fn from_optional_sequence(
+    data_set: &DataSet,
+    tag: DataElementTag,
+  ) -> Result<Option<Self>, DataError> {
+    match get_optional_single_sequence_item(data_set, tag)? {
+      Some(item) => Self::from_data_set(item)
+        .map(Some)
+        .map_err(|e| super::prefix_error_path(e, tag, 0)),
+      None => Ok(None),
+    }
+  }
+
+  /// Returns whether this coded entry has the given code value and coding
+  /// scheme designator.
+  ///
+  pub fn is(&self, code_value: &str, coding_scheme_designator: &str) -> bool {
+    self.code_value == code_value
+      && self.coding_scheme_designator.as_deref()
+        == Some(coding_scheme_designator)
+  }
+}
+
+impl core::fmt::Display for CodedEntry {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(
+      f,
+      "({}, {}, \"{}\")",
+      self.code_value,
+      self.coding_scheme_designator.as_deref().unwrap_or(""),
+      self.code_meaning
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_data_set_test() {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::CODE_VALUE, &["mm"])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::CODING_SCHEME_DESIGNATOR, &["UCUM"])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::CODE_MEANING, &["millimeter"])
+      .unwrap();
+
+    let coded_entry = CodedEntry::from_data_set(&item).unwrap();
+
+    assert!(coded_entry.is("mm", "UCUM"));
+    assert_eq!(coded_entry.to_string(), "(mm, UCUM, \"millimeter\")");
+
+    assert_eq!(
+      CodedEntry::from_data_set(&DataSet::new()),
+      Err(DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(dictionary::CODE_VALUE.tag)
+      ))
+    );
+  }
+}
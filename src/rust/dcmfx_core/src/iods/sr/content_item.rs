@@ -0,0 +1,819 @@
+//! A content item in the content tree of an SR document.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use crate::{DataError, DataSet, DataSetPath, dictionary};
+
+use super::{
+  CodedEntry, get_optional_single_sequence_item, get_optional_string,
+  prefix_error_path,
+};
+
+/// The relationship between a content item and its parent content item, as
+/// specified by its *'(0040,A010) Relationship Type'* data element.
+///
+/// Ref: PS3.3 C.17.3.2.4.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RelationshipType {
+  Contains,
+  HasProperties,
+  HasObsContext,
+  HasAcqContext,
+  InferredFrom,
+  SelectedFrom,
+  HasConceptMod,
+
+  /// Any other relationship type, holding its defined term.
+  Other(String),
+}
+
+impl RelationshipType {
+  /// Returns the relationship type for a *'(0040,A010) Relationship Type'*
+  /// defined term.
+  ///
+  pub fn from_defined_term(value: &str) -> Self {
+    match value {
+      "CONTAINS" => Self::Contains,
+      "HAS PROPERTIES" => Self::HasProperties,
+      "HAS OBS CONTEXT" => Self::HasObsContext,
+      "HAS ACQ CONTEXT" => Self::HasAcqContext,
+      "INFERRED FROM" => Self::InferredFrom,
+      "SELECTED FROM" => Self::SelectedFrom,
+      "HAS CONCEPT MOD" => Self::HasConceptMod,
+      _ => Self::Other(value.to_string()),
+    }
+  }
+
+  /// Returns the *'(0040,A010) Relationship Type'* defined term for this
+  /// relationship type.
+  ///
+  pub fn defined_term(&self) -> &str {
+    match self {
+      Self::Contains => "CONTAINS",
+      Self::HasProperties => "HAS PROPERTIES",
+      Self::HasObsContext => "HAS OBS CONTEXT",
+      Self::HasAcqContext => "HAS ACQ CONTEXT",
+      Self::InferredFrom => "INFERRED FROM",
+      Self::SelectedFrom => "SELECTED FROM",
+      Self::HasConceptMod => "HAS CONCEPT MOD",
+      Self::Other(value) => value,
+    }
+  }
+}
+
+impl core::fmt::Display for RelationshipType {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(f, "{}", self.defined_term())
+  }
+}
+
+/// A reference to a SOP instance from a COMPOSITE, IMAGE, or WAVEFORM content
+/// item, read from its *'(0008,1199) Referenced SOP Sequence'*.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SopReference {
+  pub sop_class_uid: String,
+  pub sop_instance_uid: String,
+
+  /// The referenced frame numbers, which are only present for IMAGE content
+  /// items that reference specific frames of a multi-frame image.
+  pub frame_numbers: Vec<i32>,
+}
+
+/// The value of a content item, as determined by its *'(0040,A040) Value
+/// Type'* data element.
+///
+/// Ref: PS3.3 C.17.3.2.1.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContentItemValue {
+  Container {
+    /// Whether the content items in the container are continuous, i.e. are
+    /// intended to be read as a single block of text.
+    is_continuous: bool,
+  },
+  Text(String),
+  Code(CodedEntry),
+
+  /// A numeric measurement and its units. The value is `None` when the
+  /// measured value is empty, in which case the qualifier typically gives the
+  /// reason.
+  Num {
+    value: Option<f64>,
+    units: Option<CodedEntry>,
+    qualifier: Option<CodedEntry>,
+  },
+
+  DateTime(String),
+  Date(String),
+  Time(String),
+  PersonName(String),
+  UidRef(String),
+  Composite(Option<SopReference>),
+  Image(Option<SopReference>),
+  Waveform(Option<SopReference>),
+  Scoord {
+    graphic_type: String,
+    graphic_data: Vec<f64>,
+  },
+  Scoord3d {
+    graphic_type: String,
+    graphic_data: Vec<f64>,
+    frame_of_reference_uid: Option<String>,
+  },
+  Tcoord {
+    temporal_range_type: String,
+  },
+
+  /// A reference to another content item in the content tree, given as the
+  /// 1-based indices of the content items to follow from the root content
+  /// item.
+  ByReference {
+    referenced_content_item_identifier: Vec<u32>,
+  },
+
+  /// A value type that isn't supported, holding its defined term.
+  Other(String),
+}
+
+impl ContentItemValue {
+  /// Returns the *'(0040,A040) Value Type'* defined term for this content item
+  /// value. By-reference content items have no value type, and return an empty
+  /// string.
+  ///
+  pub fn value_type(&self) -> &str {
+    match self {
+      Self::Container { .. } => "CONTAINER",
+      Self::Text(_) => "TEXT",
+      Self::Code(_) => "CODE",
+      Self::Num { .. } => "NUM",
+      Self::DateTime(_) => "DATETIME",
+      Self::Date(_) => "DATE",
+      Self::Time(_) => "TIME",
+      Self::PersonName(_) => "PNAME",
+      Self::UidRef(_) => "UIDREF",
+      Self::Composite(_) => "COMPOSITE",
+      Self::Image(_) => "IMAGE",
+      Self::Waveform(_) => "WAVEFORM",
+      Self::Scoord { .. } => "SCOORD",
+      Self::Scoord3d { .. } => "SCOORD3D",
+      Self::Tcoord { .. } => "TCOORD",
+      Self::ByReference { .. } => "",
+      Self::Other(value_type) => value_type,
+    }
+  }
+
+  /// Returns the referenced SOP instance of a COMPOSITE, IMAGE, or WAVEFORM
+  /// content item value.
+  ///
+  pub fn sop_reference(&self) -> Option<&SopReference> {
+    match self {
+      Self::Composite(reference)
+      | Self::Image(reference)
+      | Self::Waveform(reference) => reference.as_ref(),
+      _ => None,
+    }
+  }
+
+  /// Reads the value of a content item based on its *'(0040,A040) Value
+  /// Type'*.
+  ///
+  fn from_data_set(item: &DataSet) -> Result<Self, DataError> {
+    let value_type =
+      match get_optional_string(item, dictionary::VALUE_TYPE.tag)? {
+        Some(value_type) => value_type,
+
+        // Content items without a value type are references to other content
+        // items
+        None => {
+          let referenced_content_item_identifier = item.get_ints::<u32>(
+            dictionary::REFERENCED_CONTENT_ITEM_IDENTIFIER.tag,
+          )?;
+
+          return Ok(Self::ByReference {
+            referenced_content_item_identifier,
+          });
+        }
+      };
+
+    let get_string = |tag| {
+      Ok::<_, DataError>(get_optional_string(item, tag)?.unwrap_or_default())
+    };
+
+    let value = match value_type.as_str() {
+      "CONTAINER" => Self::Container {
+        is_continuous: get_optional_string(
+          item,
+          dictionary::CONTINUITY_OF_CONTENT.tag,
+        )?
+        .as_deref()
+          == Some("CONTINUOUS"),
+      },
+
+      "TEXT" => Self::Text(get_string(dictionary::TEXT_VALUE.tag)?),
+
+      "CODE" => Self::Code(
+        CodedEntry::from_optional_sequence(
+          item,
+          dictionary::CONCEPT_CODE_SEQUENCE.tag,
+        )?
+        .ok_or_else(|| {
+          DataError::new_value_not_present().with_path(
+            &DataSetPath::new_with_data_element(
+              dictionary::CONCEPT_CODE_SEQUENCE.tag,
+            ),
+          )
+        })?,
+      ),
+
+      "NUM" => Self::num_from_data_set(item)?,
+
+      "DATETIME" => Self::DateTime(get_string(dictionary::DATE_TIME.tag)?),
+      "DATE" => Self::Date(get_string(dictionary::DATE.tag)?),
+      "TIME" => Self::Time(get_string(dictionary::TIME.tag)?),
+      "PNAME" => Self::PersonName(get_string(dictionary::PERSON_NAME.tag)?),
+      "UIDREF" => Self::UidRef(get_string(dictionary::UID.tag)?),
+
+      "COMPOSITE" => Self::Composite(Self::sop_reference_from_data_set(item)?),
+      "IMAGE" => Self::Image(Self::sop_reference_from_data_set(item)?),
+      "WAVEFORM" => Self::Waveform(Self::sop_reference_from_data_set(item)?),
+
+      "SCOORD" => Self::Scoord {
+        graphic_type: get_string(dictionary::GRAPHIC_TYPE.tag)?,
+        graphic_data: item.get_floats(dictionary::GRAPHIC_DATA.tag)?,
+      },
+
+      "SCOORD3D" => Self::Scoord3d {
+        graphic_type: get_string(dictionary::GRAPHIC_TYPE.tag)?,
+        graphic_data: item.get_floats(dictionary::GRAPHIC_DATA.tag)?,
+        frame_of_reference_uid: get_optional_string(
+          item,
+          dictionary::REFERENCED_FRAME_OF_REFERENCE_UID.tag,
+        )?,
+      },
+
+      "TCOORD" => Self::Tcoord {
+        temporal_range_type: get_string(dictionary::TEMPORAL_RANGE_TYPE.tag)?,
+      },
+
+      _ => Self::Other(value_type),
+    };
+
+    Ok(value)
+  }
+
+  /// Reads the value of a NUM content item from its *'(0040,A300) Measured
+  /// Value Sequence'*.
+  ///
+  fn num_from_data_set(item: &DataSet) -> Result<Self, DataError> {
+    let qualifier = CodedEntry::from_optional_sequence(
+      item,
+      dictionary::NUMERIC_VALUE_QUALIFIER_CODE_SEQUENCE.tag,
+    )?;
+
+    let Some(measured_value) = get_optional_single_sequence_item(
+      item,
+      dictionary::MEASURED_VALUE_SEQUENCE.tag,
+    )?
+    else {
+      return Ok(Self::Num {
+        value: None,
+        units: None,
+        qualifier,
+      });
+    };
+
+    let read_measured_value = || {
+      // The floating point value takes precedence over the decimal string
+      // value when both are present
+      let value = if measured_value.has(dictionary::FLOATING_POINT_VALUE.tag) {
+        Some(measured_value.get_float(dictionary::FLOATING_POINT_VALUE.tag)?)
+      } else if measured_value.has(dictionary::NUMERIC_VALUE.tag) {
+        measured_value
+          .get_floats(dictionary::NUMERIC_VALUE.tag)?
+          .first()
+          .copied()
+      } else {
+        None
+      };
+
+      let units = CodedEntry::from_optional_sequence(
+        measured_value,
+        dictionary::MEASUREMENT_UNITS_CODE_SEQUENCE.tag,
+      )?;
+
+      Ok::<_, DataError>((value, units))
+    };
+
+    let (value, units) = read_measured_value().map_err(|e| {
+      prefix_error_path(e, dictionary::MEASURED_VALUE_SEQUENCE.tag, 0)
+    })?;
+
+    Ok(Self::Num {
+      value,
+      units,
+      qualifier,
+    })
+  }
+
+  /// Reads the referenced SOP instance of a COMPOSITE, IMAGE, or WAVEFORM
+  /// content item from its *'(0008,1199) Referenced SOP Sequence'*.
+  ///
+  fn sop_reference_from_data_set(
+    item: &DataSet,
+  ) -> Result<Option<SopReference>, DataError> {
+    let Some(reference) = get_optional_single_sequence_item(
+      item,
+      dictionary::REFERENCED_SOP_SEQUENCE.tag,
+    )?
+    else {
+      return Ok(None);
+    };
+
+    let read_reference = || {
+      let frame_numbers =
+        if reference.has(dictionary::REFERENCED_FRAME_NUMBER.tag) {
+          reference.get_ints::<i32>(dictionary::REFERENCED_FRAME_NUMBER.tag)?
+        } else {
+          vec![]
+        };
+
+      Ok::<_, DataError>(SopReference {
+        sop_class_uid: reference
+          .get_string(dictionary::REFERENCED_SOP_CLASS_UID.tag)?
+          .to_string(),
+        sop_instance_uid: reference
+          .get_string(dictionary::REFERENCED_SOP_INSTANCE_UID.tag)?
+          .to_string(),
+        frame_numbers,
+      })
+    };
+
+    read_reference().map(Some).map_err(|e| {
+      prefix_error_path(e, dictionary::REFERENCED_SOP_SEQUENCE.tag, 0)
+    })
+  }
+}
+
+impl core::fmt::Display for ContentItemValue {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::Container { .. } | Self::Other(_) => Ok(()),
+
+      Self::Text(value)
+      | Self::DateTime(value)
+      | Self::Date(value)
+      | Self::Time(value)
+      | Self::PersonName(value)
+      | Self::UidRef(value) => write!(f, "{value:?}"),
+
+      Self::Code(code) => code.fmt(f),
+
+      Self::Num {
+        value,
+        units,
+        qualifier,
+      } => {
+        match value {
+          Some(value) => write!(f, "{value}")?,
+          None => write!(f, "<no value>")?,
+        }
+
+        if let Some(units) = units {
+          write!(f, " {units}")?;
+        }
+
+        if let Some(qualifier) = qualifier {
+          write!(f, " {qualifier}")?;
+        }
+
+        Ok(())
+      }
+
+      Self::Composite(reference)
+      | Self::Image(reference)
+      | Self::Waveform(reference) => match reference {
+        Some(reference) => {
+          write!(
+            f,
+            "{} {}",
+            reference.sop_class_uid, reference.sop_instance_uid
+          )?;
+
+          if !reference.frame_numbers.is_empty() {
+            write!(f, " frames {:?}", reference.frame_numbers)?;
+          }
+
+          Ok(())
+        }
+
+        None => write!(f, "<no reference>"),
+      },
+
+      Self::Scoord {
+        graphic_type,
+        graphic_data,
+      }
+      | Self::Scoord3d {
+        graphic_type,
+        graphic_data,
+        ..
+      } => write!(f, "{graphic_type} {graphic_data:?}"),
+
+      Self::Tcoord {
+        temporal_range_type,
+      } => write!(f, "{temporal_range_type}"),
+
+      Self::ByReference {
+        referenced_content_item_identifier,
+      } => write!(
+        f,
+        "{}",
+        referenced_content_item_identifier
+          .iter()
+          .map(|index| index.to_string())
+          .collect::<Vec<_>>()
+          .join(".")
+      ),
+    }
+  }
+}
+
+/// A content item in the content tree of an SR document, along with the
+/// content items in its *'(0040,A730) Content Sequence'*.
+///
+/// The root of the content tree is the SR document's data set itself, which
+/// has no relationship type.
+///
+/// Ref: PS3.3 C.17.3.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContentItem {
+  pub relationship_type: Option<RelationshipType>,
+  pub concept_name: Option<CodedEntry>,
+  pub value: ContentItemValue,
+  pub children: Vec<ContentItem>,
+}
+
+impl ContentItem {
+  /// Creates a new [`ContentItem`] from a data set or content sequence item,
+  /// including all the content items nested beneath it.
+  ///
+  pub fn from_data_set(item: &DataSet) -> Result<Self, DataError> {
+    let relationship_type =
+      get_optional_string(item, dictionary::RELATIONSHIP_TYPE.tag)?
+        .map(|value| RelationshipType::from_defined_term(&value));
+
+    let concept_name = CodedEntry::from_optional_sequence(
+      item,
+      dictionary::CONCEPT_NAME_CODE_SEQUENCE.tag,
+    )?;
+
+    let value = ContentItemValue::from_data_set(item)?;
+
+    let mut children = vec![];
+    if item.has(dictionary::CONTENT_SEQUENCE.tag) {
+      let items = item.get_sequence_items(dictionary::CONTENT_SEQUENCE.tag)?;
+
+      for (index, child) in items.iter().enumerate() {
+        children.push(Self::from_data_set(child).map_err(|e| {
+          prefix_error_path(e, dictionary::CONTENT_SEQUENCE.tag, index)
+        })?);
+      }
+    }
+
+    Ok(Self {
+      relationship_type,
+      concept_name,
+      value,
+      children,
+    })
+  }
+
+  /// Returns an iterator over this content item and all the content items
+  /// nested beneath it, in depth-first order. Each content item is returned
+  /// along with its depth, which is zero for this content item.
+  ///
+  pub fn iter(&self) -> ContentItemIter<'_> {
+    ContentItemIter {
+      stack: vec![(self, 0)],
+    }
+  }
+
+  /// Returns this content item and all the content items nested beneath it
+  /// that have the given concept name.
+  ///
+  pub fn find_by_concept_name(
+    &self,
+    code_value: &str,
+    coding_scheme_designator: &str,
+  ) -> Vec<&ContentItem> {
+    self
+      .iter()
+      .map(|(item, _)| item)
+      .filter(|item| {
+        item
+          .concept_name
+          .as_ref()
+          .is_some_and(|c| c.is(code_value, coding_scheme_designator))
+      })
+      .collect()
+  }
+
+  /// Returns the content item at the given position in the content tree, as
+  /// specified by a by-reference content item's *'(0040,DB73) Referenced
+  /// Content Item Identifier'*. The first index refers to this content item.
+  ///
+  pub fn get_by_identifier(&self, identifier: &[u32]) -> Option<&ContentItem> {
+    let (first, rest) = identifier.split_first()?;
+    if *first != 1 {
+      return None;
+    }
+
+    let mut item = self;
+    for index in rest {
+      item = item.children.get((*index as usize).checked_sub(1)?)?;
+    }
+
+    Some(item)
+  }
+
+  /// Returns this content item and all the content items nested beneath it
+  /// that are NUM content items, i.e. measurements.
+  ///
+  pub fn measurements(&self) -> Vec<&ContentItem> {
+    self
+      .iter()
+      .map(|(item, _)| item)
+      .filter(|item| matches!(item.value, ContentItemValue::Num { .. }))
+      .collect()
+  }
+
+  /// Returns the SOP instances referenced by this content item and all the
+  /// content items nested beneath it.
+  ///
+  pub fn sop_references(&self) -> Vec<&SopReference> {
+    self
+      .iter()
+      .filter_map(|(item, _)| item.value.sop_reference())
+      .collect()
+  }
+
+  /// Renders this content item and all the content items nested beneath it as
+  /// lines of text, with nested content items indented beneath their parent.
+  ///
+  pub fn to_lines(&self) -> Vec<String> {
+    self
+      .iter()
+      .map(|(item, depth)| {
+        let mut line = "  ".repeat(depth);
+
+        if let Some(relationship_type) = &item.relationship_type {
+          line.push_str(&format!("{relationship_type} "));
+        }
+
+        match &item.value {
+          ContentItemValue::ByReference { .. } => {
+            line.push_str("(by reference)")
+          }
+          value => line.push_str(value.value_type()),
+        }
+
+        if let Some(concept_name) = &item.concept_name {
+          line.push_str(&format!(" {concept_name}"));
+        }
+
+        let value = item.value.to_string();
+        if !value.is_empty() {
+          line.push_str(&format!(" = {value}"));
+        }
+
+        line
+      })
+      .collect()
+  }
+}
+
+/// A depth-first iterator over a content item and the content items nested
+/// beneath it. See [`ContentItem::iter()`].
+///
+pub struct ContentItemIter<'a> {
+  stack: Vec<(&'a ContentItem, usize)>,
+}
+
+impl<'a> Iterator for ContentItemIter<'a> {
+  type Item = (&'a ContentItem, usize);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (item, depth) = self.stack.pop()?;
+
+    self
+      .stack
+      .extend(item.children.iter().rev().map(|child| (child, depth + 1)));
+
+    Some((item, depth))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn code_item(code_value: &str, code_meaning: &str) -> DataSet {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::CODE_VALUE, &[code_value])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::CODING_SCHEME_DESIGNATOR, &["DCM"])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::CODE_MEANING, &[code_meaning])
+      .unwrap();
+    item
+  }
+
+  fn content_item(
+    relationship_type: &str,
+    value_type: &str,
+    concept_name: DataSet,
+  ) -> DataSet {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::RELATIONSHIP_TYPE, &[relationship_type])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::VALUE_TYPE, &[value_type])
+      .unwrap();
+    item
+      .insert_sequence_value(
+        &dictionary::CONCEPT_NAME_CODE_SEQUENCE,
+        vec![concept_name],
+      )
+      .unwrap();
+    item
+  }
+
+  fn sr_data_set() -> DataSet {
+    let mut num =
+      content_item("CONTAINS", "NUM", code_item("410668003", "Length"));
+    let mut measured_value = DataSet::new();
+    measured_value
+      .insert_float_value(&dictionary::NUMERIC_VALUE, &[12.5])
+      .unwrap();
+    measured_value
+      .insert_sequence_value(
+        &dictionary::MEASUREMENT_UNITS_CODE_SEQUENCE,
+        vec![code_item("mm", "millimeter")],
+      )
+      .unwrap();
+    num
+      .insert_sequence_value(
+        &dictionary::MEASURED_VALUE_SEQUENCE,
+        vec![measured_value],
+      )
+      .unwrap();
+
+    let mut image =
+      content_item("INFERRED FROM", "IMAGE", code_item("121112", "Source"));
+    let mut reference = DataSet::new();
+    reference
+      .insert_string_value(&dictionary::REFERENCED_SOP_CLASS_UID, &["1.2.3"])
+      .unwrap();
+    reference
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2.4"])
+      .unwrap();
+    image
+      .insert_sequence_value(
+        &dictionary::REFERENCED_SOP_SEQUENCE,
+        vec![reference],
+      )
+      .unwrap();
+    num
+      .insert_sequence_value(&dictionary::CONTENT_SEQUENCE, vec![image])
+      .unwrap();
+
+    let mut text =
+      content_item("CONTAINS", "TEXT", code_item("121071", "Finding"));
+    text
+      .insert_string_value(&dictionary::TEXT_VALUE, &["Normal"])
+      .unwrap();
+
+    let mut by_reference = DataSet::new();
+    by_reference
+      .insert_string_value(&dictionary::RELATIONSHIP_TYPE, &["INFERRED FROM"])
+      .unwrap();
+    by_reference
+      .insert_int_value(
+        &dictionary::REFERENCED_CONTENT_ITEM_IDENTIFIER,
+        &[1, 1],
+      )
+      .unwrap();
+    text
+      .insert_sequence_value(&dictionary::CONTENT_SEQUENCE, vec![by_reference])
+      .unwrap();
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::VALUE_TYPE, &["CONTAINER"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(
+        &dictionary::CONCEPT_NAME_CODE_SEQUENCE,
+        vec![code_item("126000", "Imaging Measurement Report")],
+      )
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::CONTINUITY_OF_CONTENT, &["SEPARATE"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(&dictionary::CONTENT_SEQUENCE, vec![num, text])
+      .unwrap();
+
+    data_set
+  }
+
+  #[test]
+  fn from_data_set_test() {
+    let root = ContentItem::from_data_set(&sr_data_set()).unwrap();
+
+    assert_eq!(root.relationship_type, None);
+    assert_eq!(
+      root.value,
+      ContentItemValue::Container {
+        is_continuous: false
+      }
+    );
+    assert_eq!(root.iter().count(), 5);
+
+    assert_eq!(
+      root.to_lines(),
+      vec![
+        "CONTAINER (126000, DCM, \"Imaging Measurement Report\")",
+        "  CONTAINS NUM (410668003, DCM, \"Length\") = 12.5 (mm, DCM, \
+         \"millimeter\")",
+        "    INFERRED FROM IMAGE (121112, DCM, \"Source\") = 1.2.3 1.2.4",
+        "  CONTAINS TEXT (121071, DCM, \"Finding\") = \"Normal\"",
+        "    INFERRED FROM (by reference) = 1.1",
+      ]
+    );
+  }
+
+  #[test]
+  fn query_test() {
+    let root = ContentItem::from_data_set(&sr_data_set()).unwrap();
+
+    let measurements = root.measurements();
+    assert_eq!(measurements.len(), 1);
+    assert!(matches!(
+      measurements[0].value,
+      ContentItemValue::Num {
+        value: Some(12.5),
+        ..
+      }
+    ));
+
+    assert_eq!(root.find_by_concept_name("121071", "DCM").len(), 1);
+    assert_eq!(root.find_by_concept_name("121071", "SRT").len(), 0);
+
+    assert_eq!(
+      root
+        .sop_references()
+        .iter()
+        .map(|r| r.sop_instance_uid.as_str())
+        .collect::<Vec<_>>(),
+      vec!["1.2.4"]
+    );
+
+    assert_eq!(root.get_by_identifier(&[1, 1]), Some(measurements[0]));
+    assert_eq!(root.get_by_identifier(&[1, 3]), None);
+    assert_eq!(root.get_by_identifier(&[2]), None);
+  }
+
+  #[test]
+  fn from_data_set_error_path_test() {
+    let mut data_set = sr_data_set();
+
+    let mut invalid_item = DataSet::new();
+    invalid_item
+      .insert_string_value(&dictionary::VALUE_TYPE, &["CODE"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(&dictionary::CONTENT_SEQUENCE, vec![invalid_item])
+      .unwrap();
+
+    let error = ContentItem::from_data_set(&data_set).unwrap_err();
+
+    assert_eq!(
+      error.path().map(|path| path.to_string()),
+      Some("0040A730/[0]/0040A168".to_string())
+    );
+  }
+}
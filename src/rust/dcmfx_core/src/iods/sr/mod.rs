@@ -0,0 +1,102 @@
+//! Reads the content tree of DICOM Structured Report (SR) documents into typed
+//! content items, with helpers for traversing and querying the tree.
+//!
+//! Ref: PS3.3 C.17.3.
+
+pub mod coded_entry;
+pub mod content_item;
+pub mod sr_document_content_module;
+
+pub use coded_entry::CodedEntry;
+pub use content_item::{
+  ContentItem, ContentItemIter, ContentItemValue, RelationshipType,
+  SopReference,
+};
+pub use sr_document_content_module::SrDocumentContentModule;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+};
+
+use crate::{
+  DataElementTag, DataError, DataSet, DataSetPath,
+  data_set_path::DataSetPathEntry,
+};
+
+/// Returns the value of an optional string data element, or `None` when it
+/// isn't present in the data set or is empty.
+///
+pub(crate) fn get_optional_string(
+  data_set: &DataSet,
+  tag: DataElementTag,
+) -> Result<Option<String>, DataError> {
+  if !data_set.has(tag) {
+    return Ok(None);
+  }
+
+  // Text VRs such as UT only hold a single value and aren't supported by
+  // `DataSet::get_strings()`
+  let value = match data_set.get_strings(tag) {
+    Ok(values) => values.first().copied().unwrap_or_default(),
+    Err(_) => data_set.get_string(tag)?,
+  };
+
+  if value.is_empty() {
+    Ok(None)
+  } else {
+    Ok(Some(value.to_string()))
+  }
+}
+
+/// Returns the item of an optional sequence that is allowed to have at most one
+/// item, or `None` when the sequence isn't present or is empty.
+///
+pub(crate) fn get_optional_single_sequence_item(
+  data_set: &DataSet,
+  tag: DataElementTag,
+) -> Result<Option<&DataSet>, DataError> {
+  if !data_set.has(tag) {
+    return Ok(None);
+  }
+
+  match data_set.get_sequence_items(tag)? {
+    [] => Ok(None),
+    [item] => Ok(Some(item)),
+
+    items => Err(
+      DataError::new_value_invalid(format!(
+        "Sequence has more than one item, found {} items",
+        items.len()
+      ))
+      .with_path(&DataSetPath::new_with_data_element(tag)),
+    ),
+  }
+}
+
+/// Prefixes the path of a data error that occurred when reading a sequence
+/// item with the path to that sequence item, so that the error's path is
+/// relative to the data set containing the sequence.
+///
+pub(crate) fn prefix_error_path(
+  error: DataError,
+  tag: DataElementTag,
+  index: usize,
+) -> DataError {
+  let mut path = DataSetPath::new_with_data_element(tag);
+  path.add_sequence_item(index).unwrap();
+
+  if let Some(error_path) = error.path() {
+    for entry in error_path.entries() {
+      let _ = match entry {
+        DataSetPathEntry::DataElement { tag } => path.add_data_element(*tag),
+        DataSetPathEntry::SequenceItem { index } => {
+          path.add_sequence_item(*index)
+        }
+      };
+    }
+  }
+
+  error.with_path(&path)
+}
@@ -0,0 +1,98 @@
+use crate::{
+  DataElementTag, DataError, DataSet, DataSetPath, IodModule,
+  ValueRepresentation, data_set_path::DataSetPathEntry, dictionary,
+};
+
+use super::ContentItem;
+
+/// The content tree of an SR document, read from the attributes of the SR
+/// Document Content Module.
+///
+/// Ref: PS3.3 C.17.3.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SrDocumentContentModule {
+  /// The root content item of the SR document, which is normally a CONTAINER
+  /// content item whose concept name is the document title.
+  pub root: ContentItem,
+}
+
+impl SrDocumentContentModule {
+  /// The tags of the data elements in the root data set that are used when
+  /// reading the SR Document Content Module. All data elements nested in the
+  /// sequences are also used.
+  ///
+  pub const TAGS: [DataElementTag; 4] = [
+    dictionary::VALUE_TYPE.tag,
+    dictionary::CONCEPT_NAME_CODE_SEQUENCE.tag,
+    dictionary::CONTINUITY_OF_CONTENT.tag,
+    dictionary::CONTENT_SEQUENCE.tag,
+  ];
+}
+
+impl IodModule for SrDocumentContentModule {
+  fn is_iod_module_data_element(
+    tag: DataElementTag,
+    _vr: ValueRepresentation,
+    _length: Option<u32>,
+    path: &DataSetPath,
+  ) -> bool {
+    if path.is_root() {
+      return Self::TAGS.contains(&tag);
+    }
+
+    // Include everything nested in the root's sequences
+    match path.entries().first() {
+      Some(DataSetPathEntry::DataElement { tag }) => {
+        *tag == dictionary::CONCEPT_NAME_CODE_SEQUENCE.tag
+          || *tag == dictionary::CONTENT_SEQUENCE.tag
+      }
+
+      _ => false,
+    }
+  }
+
+  fn iod_module_highest_tag() -> DataElementTag {
+    dictionary::CONTENT_SEQUENCE.tag
+  }
+
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    Ok(Self {
+      root: ContentItem::from_data_set(data_set)?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn is_iod_module_data_element_test() {
+    let is_iod_module_data_element = |tag, path: &str| {
+      SrDocumentContentModule::is_iod_module_data_element(
+        tag,
+        ValueRepresentation::CodeString,
+        None,
+        &DataSetPath::from_string(path).unwrap(),
+      )
+    };
+
+    assert!(is_iod_module_data_element(
+      dictionary::VALUE_TYPE.tag,
+      "0040A040"
+    ));
+    assert!(!is_iod_module_data_element(
+      dictionary::PATIENT_ID.tag,
+      "00100020"
+    ));
+    assert!(is_iod_module_data_element(
+      dictionary::TEXT_VALUE.tag,
+      "0040A730/[0]/0040A730/[2]/0040A160"
+    ));
+    assert!(!is_iod_module_data_element(
+      dictionary::CODE_VALUE.tag,
+      "00081032/[0]/00080100"
+    ));
+  }
+}
@@ -15,12 +15,15 @@ pub mod data_set;
 pub mod data_set_path;
 pub mod dictionary;
 pub mod error;
+pub mod error_details;
 pub mod iod_module;
 pub mod iods;
+pub mod sop_class;
 pub mod transfer_syntax;
 pub mod utils;
 pub mod value_multiplicity;
 pub mod value_representation;
+pub mod vendors;
 
 pub use data_element_tag::DataElementTag;
 pub use data_element_value::DataElementValue;
@@ -35,8 +38,10 @@ pub use data_error::DataError;
 pub use data_set::DataSet;
 pub use data_set::print::DataSetPrintOptions;
 pub use data_set_path::DataSetPath;
-pub use error::DcmfxError;
+pub use error::{DcmfxError, ErrorContext, ErrorContextChain};
+pub use error_details::ErrorDetails;
 pub use iod_module::IodModule;
+pub use sop_class::SopClass;
 pub use transfer_syntax::TransferSyntax;
 pub use utils::{Rc, RcByteSlice};
 pub use value_multiplicity::ValueMultiplicity;
@@ -0,0 +1,2314 @@
+//! Defines the storage SOP Classes specified in DICOM PS3.4, along with their
+//! names and categories.
+
+/// The category of a storage SOP Class, which describes the broad kind of
+/// content stored by instances of that SOP Class.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SopClassCategory {
+  /// Images that store pixel data. This includes radiotherapy images.
+  Image,
+
+  /// Structured reports, including key object selection documents.
+  StructuredReport,
+
+  /// Softcopy and volumetric presentation states.
+  PresentationState,
+
+  /// Waveforms such as ECGs and audio.
+  Waveform,
+
+  /// Radiotherapy plans, doses, structure sets, records, and related objects.
+  Radiotherapy,
+
+  /// Encapsulated documents such as PDFs and CDAs.
+  EncapsulatedDocument,
+
+  /// All other storage SOP Classes, e.g. registrations, measurements, and
+  /// protocols.
+  Other,
+}
+
+/// Describes a single DICOM storage SOP Class, with its name, UID, and
+/// category.
+///
+#[derive(Debug, Eq, PartialEq)]
+pub struct SopClass {
+  pub name: &'static str,
+  pub uid: &'static str,
+  pub category: SopClassCategory,
+}
+
+impl core::hash::Hash for SopClass {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.uid.hash(state);
+  }
+}
+
+/// The 'Computed Radiography Image Storage' SOP Class.
+///
+pub const COMPUTED_RADIOGRAPHY_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Computed Radiography Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Digital X-Ray Image Storage - For Presentation' SOP Class.
+///
+pub const DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: SopClass = SopClass {
+  name: "Digital X-Ray Image Storage - For Presentation",
+  uid: "1.2.840.10008.5.1.4.1.1.1.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Digital X-Ray Image Storage - For Processing' SOP Class.
+///
+pub const DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: SopClass = SopClass {
+  name: "Digital X-Ray Image Storage - For Processing",
+  uid: "1.2.840.10008.5.1.4.1.1.1.1.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Digital Mammography X-Ray Image Storage - For Presentation' SOP Class.
+///
+pub const DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: SopClass =
+  SopClass {
+    name: "Digital Mammography X-Ray Image Storage - For Presentation",
+    uid: "1.2.840.10008.5.1.4.1.1.1.2",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Digital Mammography X-Ray Image Storage - For Processing' SOP Class.
+///
+pub const DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: SopClass =
+  SopClass {
+    name: "Digital Mammography X-Ray Image Storage - For Processing",
+    uid: "1.2.840.10008.5.1.4.1.1.1.2.1",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Digital Intra-Oral X-Ray Image Storage - For Presentation' SOP Class.
+///
+pub const DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: SopClass =
+  SopClass {
+    name: "Digital Intra-Oral X-Ray Image Storage - For Presentation",
+    uid: "1.2.840.10008.5.1.4.1.1.1.3",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Digital Intra-Oral X-Ray Image Storage - For Processing' SOP Class.
+///
+pub const DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: SopClass =
+  SopClass {
+    name: "Digital Intra-Oral X-Ray Image Storage - For Processing",
+    uid: "1.2.840.10008.5.1.4.1.1.1.3.1",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'CT Image Storage' SOP Class.
+///
+pub const CT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "CT Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Enhanced CT Image Storage' SOP Class.
+///
+pub const ENHANCED_CT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced CT Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.2.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Legacy Converted Enhanced CT Image Storage' SOP Class.
+///
+pub const LEGACY_CONVERTED_ENHANCED_CT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Legacy Converted Enhanced CT Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.2.2",
+  category: SopClassCategory::Image,
+};
+
+/// The retired 'Ultrasound Multi-frame Image Storage' SOP Class.
+///
+pub const ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE_RETIRED: SopClass = SopClass {
+  name: "Ultrasound Multi-frame Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.3",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Ultrasound Multi-frame Image Storage' SOP Class.
+///
+pub const ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Ultrasound Multi-frame Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.3.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'MR Image Storage' SOP Class.
+///
+pub const MR_IMAGE_STORAGE: SopClass = SopClass {
+  name: "MR Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.4",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Enhanced MR Image Storage' SOP Class.
+///
+pub const ENHANCED_MR_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced MR Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.4.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'MR Spectroscopy Storage' SOP Class.
+///
+pub const MR_SPECTROSCOPY_STORAGE: SopClass = SopClass {
+  name: "MR Spectroscopy Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.4.2",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Enhanced MR Color Image Storage' SOP Class.
+///
+pub const ENHANCED_MR_COLOR_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced MR Color Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.4.3",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Legacy Converted Enhanced MR Image Storage' SOP Class.
+///
+pub const LEGACY_CONVERTED_ENHANCED_MR_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Legacy Converted Enhanced MR Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.4.4",
+  category: SopClassCategory::Image,
+};
+
+/// The retired 'Nuclear Medicine Image Storage' SOP Class.
+///
+pub const NUCLEAR_MEDICINE_IMAGE_STORAGE_RETIRED: SopClass = SopClass {
+  name: "Nuclear Medicine Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.5",
+  category: SopClassCategory::Image,
+};
+
+/// The retired 'Ultrasound Image Storage' SOP Class.
+///
+pub const ULTRASOUND_IMAGE_STORAGE_RETIRED: SopClass = SopClass {
+  name: "Ultrasound Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.6",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Ultrasound Image Storage' SOP Class.
+///
+pub const ULTRASOUND_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Ultrasound Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.6.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Enhanced US Volume Storage' SOP Class.
+///
+pub const ENHANCED_US_VOLUME_STORAGE: SopClass = SopClass {
+  name: "Enhanced US Volume Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.6.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Photoacoustic Image Storage' SOP Class.
+///
+pub const PHOTOACOUSTIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Photoacoustic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.6.3",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Secondary Capture Image Storage' SOP Class.
+///
+pub const SECONDARY_CAPTURE_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Secondary Capture Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.7",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Multi-frame Single Bit Secondary Capture Image Storage' SOP Class.
+///
+pub const MULTI_FRAME_SINGLE_BIT_SECONDARY_CAPTURE_IMAGE_STORAGE: SopClass =
+  SopClass {
+    name: "Multi-frame Single Bit Secondary Capture Image Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.7.1",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Multi-frame Grayscale Byte Secondary Capture Image Storage' SOP Class.
+///
+pub const MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE: SopClass =
+  SopClass {
+    name: "Multi-frame Grayscale Byte Secondary Capture Image Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.7.2",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Multi-frame Grayscale Word Secondary Capture Image Storage' SOP Class.
+///
+pub const MULTI_FRAME_GRAYSCALE_WORD_SECONDARY_CAPTURE_IMAGE_STORAGE: SopClass =
+  SopClass {
+    name: "Multi-frame Grayscale Word Secondary Capture Image Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.7.3",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Multi-frame True Color Secondary Capture Image Storage' SOP Class.
+///
+pub const MULTI_FRAME_TRUE_COLOR_SECONDARY_CAPTURE_IMAGE_STORAGE: SopClass =
+  SopClass {
+    name: "Multi-frame True Color Secondary Capture Image Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.7.4",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Standalone Overlay Storage' SOP Class.
+///
+pub const STANDALONE_OVERLAY_STORAGE: SopClass = SopClass {
+  name: "Standalone Overlay Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.8",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Standalone Curve Storage' SOP Class.
+///
+pub const STANDALONE_CURVE_STORAGE: SopClass = SopClass {
+  name: "Standalone Curve Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Waveform Storage - Trial' SOP Class.
+///
+pub const WAVEFORM_STORAGE_TRIAL: SopClass = SopClass {
+  name: "Waveform Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.1.1.9.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The '12-lead ECG Waveform Storage' SOP Class.
+///
+pub const TWELVE_LEAD_ECG_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "12-lead ECG Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.1.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'General ECG Waveform Storage' SOP Class.
+///
+pub const GENERAL_ECG_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "General ECG Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.1.2",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Ambulatory ECG Waveform Storage' SOP Class.
+///
+pub const AMBULATORY_ECG_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Ambulatory ECG Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.1.3",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'General 32-bit ECG Waveform Storage' SOP Class.
+///
+pub const GENERAL_32_BIT_ECG_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "General 32-bit ECG Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.1.4",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Hemodynamic Waveform Storage' SOP Class.
+///
+pub const HEMODYNAMIC_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Hemodynamic Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.2.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Cardiac Electrophysiology Waveform Storage' SOP Class.
+///
+pub const CARDIAC_ELECTROPHYSIOLOGY_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Cardiac Electrophysiology Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.3.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Basic Voice Audio Waveform Storage' SOP Class.
+///
+pub const BASIC_VOICE_AUDIO_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Basic Voice Audio Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.4.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'General Audio Waveform Storage' SOP Class.
+///
+pub const GENERAL_AUDIO_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "General Audio Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.4.2",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Arterial Pulse Waveform Storage' SOP Class.
+///
+pub const ARTERIAL_PULSE_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Arterial Pulse Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.5.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Respiratory Waveform Storage' SOP Class.
+///
+pub const RESPIRATORY_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Respiratory Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.6.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Multi-channel Respiratory Waveform Storage' SOP Class.
+///
+pub const MULTI_CHANNEL_RESPIRATORY_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Multi-channel Respiratory Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.6.2",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Routine Scalp Electroencephalogram Waveform Storage' SOP Class.
+///
+pub const ROUTINE_SCALP_ELECTROENCEPHALOGRAM_WAVEFORM_STORAGE: SopClass =
+  SopClass {
+    name: "Routine Scalp Electroencephalogram Waveform Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.9.7.1",
+    category: SopClassCategory::Waveform,
+  };
+
+/// The 'Electromyogram Waveform Storage' SOP Class.
+///
+pub const ELECTROMYOGRAM_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Electromyogram Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.7.2",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Electrooculogram Waveform Storage' SOP Class.
+///
+pub const ELECTROOCULOGRAM_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Electrooculogram Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.7.3",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Sleep Electroencephalogram Waveform Storage' SOP Class.
+///
+pub const SLEEP_ELECTROENCEPHALOGRAM_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Sleep Electroencephalogram Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.7.4",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Body Position Waveform Storage' SOP Class.
+///
+pub const BODY_POSITION_WAVEFORM_STORAGE: SopClass = SopClass {
+  name: "Body Position Waveform Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.9.8.1",
+  category: SopClassCategory::Waveform,
+};
+
+/// The 'Standalone Modality LUT Storage' SOP Class.
+///
+pub const STANDALONE_MODALITY_LUT_STORAGE: SopClass = SopClass {
+  name: "Standalone Modality LUT Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.10",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Standalone VOI LUT Storage' SOP Class.
+///
+pub const STANDALONE_VOI_LUT_STORAGE: SopClass = SopClass {
+  name: "Standalone VOI LUT Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Grayscale Softcopy Presentation State Storage' SOP Class.
+///
+pub const GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE: SopClass = SopClass {
+  name: "Grayscale Softcopy Presentation State Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11.1",
+  category: SopClassCategory::PresentationState,
+};
+
+/// The 'Color Softcopy Presentation State Storage' SOP Class.
+///
+pub const COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE: SopClass = SopClass {
+  name: "Color Softcopy Presentation State Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11.2",
+  category: SopClassCategory::PresentationState,
+};
+
+/// The 'Pseudo-Color Softcopy Presentation State Storage' SOP Class.
+///
+pub const PSEUDO_COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE: SopClass =
+  SopClass {
+    name: "Pseudo-Color Softcopy Presentation State Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.11.3",
+    category: SopClassCategory::PresentationState,
+  };
+
+/// The 'Blending Softcopy Presentation State Storage' SOP Class.
+///
+pub const BLENDING_SOFTCOPY_PRESENTATION_STATE_STORAGE: SopClass = SopClass {
+  name: "Blending Softcopy Presentation State Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11.4",
+  category: SopClassCategory::PresentationState,
+};
+
+/// The 'XA/XRF Grayscale Softcopy Presentation State Storage' SOP Class.
+///
+pub const XA_XRF_GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE: SopClass =
+  SopClass {
+    name: "XA/XRF Grayscale Softcopy Presentation State Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.11.5",
+    category: SopClassCategory::PresentationState,
+  };
+
+/// The 'Grayscale Planar MPR Volumetric Presentation State Storage' SOP Class.
+///
+pub const GRAYSCALE_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE: SopClass =
+  SopClass {
+    name: "Grayscale Planar MPR Volumetric Presentation State Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.11.6",
+    category: SopClassCategory::PresentationState,
+  };
+
+/// The 'Compositing Planar MPR Volumetric Presentation State Storage' SOP Class.
+///
+pub const COMPOSITING_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE:
+  SopClass = SopClass {
+  name: "Compositing Planar MPR Volumetric Presentation State Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11.7",
+  category: SopClassCategory::PresentationState,
+};
+
+/// The 'Advanced Blending Presentation State Storage' SOP Class.
+///
+pub const ADVANCED_BLENDING_PRESENTATION_STATE_STORAGE: SopClass = SopClass {
+  name: "Advanced Blending Presentation State Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11.8",
+  category: SopClassCategory::PresentationState,
+};
+
+/// The 'Volume Rendering Volumetric Presentation State Storage' SOP Class.
+///
+pub const VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE: SopClass =
+  SopClass {
+    name: "Volume Rendering Volumetric Presentation State Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.11.9",
+    category: SopClassCategory::PresentationState,
+  };
+
+/// The 'Segmented Volume Rendering Volumetric Presentation State Storage' SOP Class.
+///
+pub const SEGMENTED_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE:
+  SopClass = SopClass {
+  name: "Segmented Volume Rendering Volumetric Presentation State Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11.10",
+  category: SopClassCategory::PresentationState,
+};
+
+/// The 'Multiple Volume Rendering Volumetric Presentation State Storage' SOP Class.
+///
+pub const MULTIPLE_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE:
+  SopClass = SopClass {
+  name: "Multiple Volume Rendering Volumetric Presentation State Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.11.11",
+  category: SopClassCategory::PresentationState,
+};
+
+/// The 'Variable Modality LUT Softcopy Presentation State Storage' SOP Class.
+///
+pub const VARIABLE_MODALITY_LUT_SOFTCOPY_PRESENTATION_STATE_STORAGE: SopClass =
+  SopClass {
+    name: "Variable Modality LUT Softcopy Presentation State Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.11.12",
+    category: SopClassCategory::PresentationState,
+  };
+
+/// The 'X-Ray Angiographic Image Storage' SOP Class.
+///
+pub const X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "X-Ray Angiographic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.12.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Enhanced XA Image Storage' SOP Class.
+///
+pub const ENHANCED_XA_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced XA Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.12.1.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'X-Ray Radiofluoroscopic Image Storage' SOP Class.
+///
+pub const X_RAY_RADIOFLUOROSCOPIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "X-Ray Radiofluoroscopic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.12.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Enhanced XRF Image Storage' SOP Class.
+///
+pub const ENHANCED_XRF_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced XRF Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.12.2.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'X-Ray Angiographic Bi-Plane Image Storage' SOP Class.
+///
+pub const X_RAY_ANGIOGRAPHIC_BI_PLANE_IMAGE_STORAGE: SopClass = SopClass {
+  name: "X-Ray Angiographic Bi-Plane Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.12.3",
+  category: SopClassCategory::Image,
+};
+
+/// The 'X-Ray 3D Angiographic Image Storage' SOP Class.
+///
+pub const X_RAY_3D_ANGIOGRAPHIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "X-Ray 3D Angiographic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.13.1.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'X-Ray 3D Craniofacial Image Storage' SOP Class.
+///
+pub const X_RAY_3D_CRANIOFACIAL_IMAGE_STORAGE: SopClass = SopClass {
+  name: "X-Ray 3D Craniofacial Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.13.1.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Breast Tomosynthesis Image Storage' SOP Class.
+///
+pub const BREAST_TOMOSYNTHESIS_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Breast Tomosynthesis Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.13.1.3",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Breast Projection X-Ray Image Storage - For Presentation' SOP Class.
+///
+pub const BREAST_PROJECTION_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: SopClass =
+  SopClass {
+    name: "Breast Projection X-Ray Image Storage - For Presentation",
+    uid: "1.2.840.10008.5.1.4.1.1.13.1.4",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Breast Projection X-Ray Image Storage - For Processing' SOP Class.
+///
+pub const BREAST_PROJECTION_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: SopClass =
+  SopClass {
+    name: "Breast Projection X-Ray Image Storage - For Processing",
+    uid: "1.2.840.10008.5.1.4.1.1.13.1.5",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'Nuclear Medicine Image Storage' SOP Class.
+///
+pub const NUCLEAR_MEDICINE_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Nuclear Medicine Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.20",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Parametric Map Storage' SOP Class.
+///
+pub const PARAMETRIC_MAP_STORAGE: SopClass = SopClass {
+  name: "Parametric Map Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.30",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Raw Data Storage' SOP Class.
+///
+pub const RAW_DATA_STORAGE: SopClass = SopClass {
+  name: "Raw Data Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.66",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Spatial Registration Storage' SOP Class.
+///
+pub const SPATIAL_REGISTRATION_STORAGE: SopClass = SopClass {
+  name: "Spatial Registration Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.66.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Spatial Fiducials Storage' SOP Class.
+///
+pub const SPATIAL_FIDUCIALS_STORAGE: SopClass = SopClass {
+  name: "Spatial Fiducials Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.66.2",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Deformable Spatial Registration Storage' SOP Class.
+///
+pub const DEFORMABLE_SPATIAL_REGISTRATION_STORAGE: SopClass = SopClass {
+  name: "Deformable Spatial Registration Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.66.3",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Segmentation Storage' SOP Class.
+///
+pub const SEGMENTATION_STORAGE: SopClass = SopClass {
+  name: "Segmentation Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.66.4",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Surface Segmentation Storage' SOP Class.
+///
+pub const SURFACE_SEGMENTATION_STORAGE: SopClass = SopClass {
+  name: "Surface Segmentation Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.66.5",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Tractography Results Storage' SOP Class.
+///
+pub const TRACTOGRAPHY_RESULTS_STORAGE: SopClass = SopClass {
+  name: "Tractography Results Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.66.6",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Real World Value Mapping Storage' SOP Class.
+///
+pub const REAL_WORLD_VALUE_MAPPING_STORAGE: SopClass = SopClass {
+  name: "Real World Value Mapping Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.67",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Surface Scan Mesh Storage' SOP Class.
+///
+pub const SURFACE_SCAN_MESH_STORAGE: SopClass = SopClass {
+  name: "Surface Scan Mesh Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.68.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Surface Scan Point Cloud Storage' SOP Class.
+///
+pub const SURFACE_SCAN_POINT_CLOUD_STORAGE: SopClass = SopClass {
+  name: "Surface Scan Point Cloud Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.68.2",
+  category: SopClassCategory::Other,
+};
+
+/// The 'VL Image Storage - Trial' SOP Class.
+///
+pub const VL_IMAGE_STORAGE_TRIAL: SopClass = SopClass {
+  name: "VL Image Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'VL Endoscopic Image Storage' SOP Class.
+///
+pub const VL_ENDOSCOPIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "VL Endoscopic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Video Endoscopic Image Storage' SOP Class.
+///
+pub const VIDEO_ENDOSCOPIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Video Endoscopic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.1.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'VL Microscopic Image Storage' SOP Class.
+///
+pub const VL_MICROSCOPIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "VL Microscopic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Video Microscopic Image Storage' SOP Class.
+///
+pub const VIDEO_MICROSCOPIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Video Microscopic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.2.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'VL Slide-Coordinates Microscopic Image Storage' SOP Class.
+///
+pub const VL_SLIDE_COORDINATES_MICROSCOPIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "VL Slide-Coordinates Microscopic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.3",
+  category: SopClassCategory::Image,
+};
+
+/// The 'VL Photographic Image Storage' SOP Class.
+///
+pub const VL_PHOTOGRAPHIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "VL Photographic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.4",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Video Photographic Image Storage' SOP Class.
+///
+pub const VIDEO_PHOTOGRAPHIC_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Video Photographic Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.4.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Ophthalmic Photography 8 Bit Image Storage' SOP Class.
+///
+pub const OPHTHALMIC_PHOTOGRAPHY_8_BIT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Ophthalmic Photography 8 Bit Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.5.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Ophthalmic Photography 16 Bit Image Storage' SOP Class.
+///
+pub const OPHTHALMIC_PHOTOGRAPHY_16_BIT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Ophthalmic Photography 16 Bit Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.5.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Stereometric Relationship Storage' SOP Class.
+///
+pub const STEREOMETRIC_RELATIONSHIP_STORAGE: SopClass = SopClass {
+  name: "Stereometric Relationship Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.5.3",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Ophthalmic Tomography Image Storage' SOP Class.
+///
+pub const OPHTHALMIC_TOMOGRAPHY_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Ophthalmic Tomography Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.5.4",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Wide Field Ophthalmic Photography 3D Coordinates Image Storage' SOP Class.
+///
+pub const WIDE_FIELD_OPHTHALMIC_PHOTOGRAPHY_3D_COORDINATES_IMAGE_STORAGE:
+  SopClass = SopClass {
+  name: "Wide Field Ophthalmic Photography 3D Coordinates Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.5.6",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Ophthalmic Optical Coherence Tomography En Face Image Storage' SOP Class.
+///
+pub const OPHTHALMIC_OPTICAL_COHERENCE_TOMOGRAPHY_EN_FACE_IMAGE_STORAGE:
+  SopClass = SopClass {
+  name: "Ophthalmic Optical Coherence Tomography En Face Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.5.7",
+  category: SopClassCategory::Image,
+};
+
+/// The 'VL Whole Slide Microscopy Image Storage' SOP Class.
+///
+pub const VL_WHOLE_SLIDE_MICROSCOPY_IMAGE_STORAGE: SopClass = SopClass {
+  name: "VL Whole Slide Microscopy Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.6",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Dermoscopic Photography Image Storage' SOP Class.
+///
+pub const DERMOSCOPIC_PHOTOGRAPHY_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Dermoscopic Photography Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.7",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Confocal Microscopy Image Storage' SOP Class.
+///
+pub const CONFOCAL_MICROSCOPY_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Confocal Microscopy Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.77.1.8",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Confocal Microscopy Tiled Pyramidal Image Storage' SOP Class.
+///
+pub const CONFOCAL_MICROSCOPY_TILED_PYRAMIDAL_IMAGE_STORAGE: SopClass =
+  SopClass {
+    name: "Confocal Microscopy Tiled Pyramidal Image Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.77.1.9",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'VL Multi-frame Image Storage - Trial' SOP Class.
+///
+pub const VL_MULTI_FRAME_IMAGE_STORAGE_TRIAL: SopClass = SopClass {
+  name: "VL Multi-frame Image Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.1.1.77.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Lensometry Measurements Storage' SOP Class.
+///
+pub const LENSOMETRY_MEASUREMENTS_STORAGE: SopClass = SopClass {
+  name: "Lensometry Measurements Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Autorefraction Measurements Storage' SOP Class.
+///
+pub const AUTOREFRACTION_MEASUREMENTS_STORAGE: SopClass = SopClass {
+  name: "Autorefraction Measurements Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.2",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Keratometry Measurements Storage' SOP Class.
+///
+pub const KERATOMETRY_MEASUREMENTS_STORAGE: SopClass = SopClass {
+  name: "Keratometry Measurements Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.3",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Subjective Refraction Measurements Storage' SOP Class.
+///
+pub const SUBJECTIVE_REFRACTION_MEASUREMENTS_STORAGE: SopClass = SopClass {
+  name: "Subjective Refraction Measurements Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.4",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Visual Acuity Measurements Storage' SOP Class.
+///
+pub const VISUAL_ACUITY_MEASUREMENTS_STORAGE: SopClass = SopClass {
+  name: "Visual Acuity Measurements Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.5",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Spectacle Prescription Report Storage' SOP Class.
+///
+pub const SPECTACLE_PRESCRIPTION_REPORT_STORAGE: SopClass = SopClass {
+  name: "Spectacle Prescription Report Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.6",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Ophthalmic Axial Measurements Storage' SOP Class.
+///
+pub const OPHTHALMIC_AXIAL_MEASUREMENTS_STORAGE: SopClass = SopClass {
+  name: "Ophthalmic Axial Measurements Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.7",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Intraocular Lens Calculations Storage' SOP Class.
+///
+pub const INTRAOCULAR_LENS_CALCULATIONS_STORAGE: SopClass = SopClass {
+  name: "Intraocular Lens Calculations Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.78.8",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Macular Grid Thickness and Volume Report Storage' SOP Class.
+///
+pub const MACULAR_GRID_THICKNESS_AND_VOLUME_REPORT_STORAGE: SopClass =
+  SopClass {
+    name: "Macular Grid Thickness and Volume Report Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.79.1",
+    category: SopClassCategory::StructuredReport,
+  };
+
+/// The 'Ophthalmic Visual Field Static Perimetry Measurements Storage' SOP Class.
+///
+pub const OPHTHALMIC_VISUAL_FIELD_STATIC_PERIMETRY_MEASUREMENTS_STORAGE:
+  SopClass = SopClass {
+  name: "Ophthalmic Visual Field Static Perimetry Measurements Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.80.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Ophthalmic Thickness Map Storage' SOP Class.
+///
+pub const OPHTHALMIC_THICKNESS_MAP_STORAGE: SopClass = SopClass {
+  name: "Ophthalmic Thickness Map Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.81.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Corneal Topography Map Storage' SOP Class.
+///
+pub const CORNEAL_TOPOGRAPHY_MAP_STORAGE: SopClass = SopClass {
+  name: "Corneal Topography Map Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.82.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Text SR Storage - Trial' SOP Class.
+///
+pub const TEXT_SR_STORAGE_TRIAL: SopClass = SopClass {
+  name: "Text SR Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.1.1.88.1",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Audio SR Storage - Trial' SOP Class.
+///
+pub const AUDIO_SR_STORAGE_TRIAL: SopClass = SopClass {
+  name: "Audio SR Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.1.1.88.2",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Detail SR Storage - Trial' SOP Class.
+///
+pub const DETAIL_SR_STORAGE_TRIAL: SopClass = SopClass {
+  name: "Detail SR Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.1.1.88.3",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Comprehensive SR Storage - Trial' SOP Class.
+///
+pub const COMPREHENSIVE_SR_STORAGE_TRIAL: SopClass = SopClass {
+  name: "Comprehensive SR Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.1.1.88.4",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Basic Text SR Storage' SOP Class.
+///
+pub const BASIC_TEXT_SR_STORAGE: SopClass = SopClass {
+  name: "Basic Text SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.11",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Enhanced SR Storage' SOP Class.
+///
+pub const ENHANCED_SR_STORAGE: SopClass = SopClass {
+  name: "Enhanced SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.22",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Comprehensive SR Storage' SOP Class.
+///
+pub const COMPREHENSIVE_SR_STORAGE: SopClass = SopClass {
+  name: "Comprehensive SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.33",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Comprehensive 3D SR Storage' SOP Class.
+///
+pub const COMPREHENSIVE_3D_SR_STORAGE: SopClass = SopClass {
+  name: "Comprehensive 3D SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.34",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Extensible SR Storage' SOP Class.
+///
+pub const EXTENSIBLE_SR_STORAGE: SopClass = SopClass {
+  name: "Extensible SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.35",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Procedure Log Storage' SOP Class.
+///
+pub const PROCEDURE_LOG_STORAGE: SopClass = SopClass {
+  name: "Procedure Log Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.40",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Mammography CAD SR Storage' SOP Class.
+///
+pub const MAMMOGRAPHY_CAD_SR_STORAGE: SopClass = SopClass {
+  name: "Mammography CAD SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.50",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Key Object Selection Document Storage' SOP Class.
+///
+pub const KEY_OBJECT_SELECTION_DOCUMENT_STORAGE: SopClass = SopClass {
+  name: "Key Object Selection Document Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.59",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Chest CAD SR Storage' SOP Class.
+///
+pub const CHEST_CAD_SR_STORAGE: SopClass = SopClass {
+  name: "Chest CAD SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.65",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'X-Ray Radiation Dose SR Storage' SOP Class.
+///
+pub const X_RAY_RADIATION_DOSE_SR_STORAGE: SopClass = SopClass {
+  name: "X-Ray Radiation Dose SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.67",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Radiopharmaceutical Radiation Dose SR Storage' SOP Class.
+///
+pub const RADIOPHARMACEUTICAL_RADIATION_DOSE_SR_STORAGE: SopClass = SopClass {
+  name: "Radiopharmaceutical Radiation Dose SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.68",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Colon CAD SR Storage' SOP Class.
+///
+pub const COLON_CAD_SR_STORAGE: SopClass = SopClass {
+  name: "Colon CAD SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.69",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Implantation Plan SR Storage' SOP Class.
+///
+pub const IMPLANTATION_PLAN_SR_STORAGE: SopClass = SopClass {
+  name: "Implantation Plan SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.70",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Acquisition Context SR Storage' SOP Class.
+///
+pub const ACQUISITION_CONTEXT_SR_STORAGE: SopClass = SopClass {
+  name: "Acquisition Context SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.71",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Simplified Adult Echo SR Storage' SOP Class.
+///
+pub const SIMPLIFIED_ADULT_ECHO_SR_STORAGE: SopClass = SopClass {
+  name: "Simplified Adult Echo SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.72",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Patient Radiation Dose SR Storage' SOP Class.
+///
+pub const PATIENT_RADIATION_DOSE_SR_STORAGE: SopClass = SopClass {
+  name: "Patient Radiation Dose SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.73",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Planned Imaging Agent Administration SR Storage' SOP Class.
+///
+pub const PLANNED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE: SopClass =
+  SopClass {
+    name: "Planned Imaging Agent Administration SR Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.88.74",
+    category: SopClassCategory::StructuredReport,
+  };
+
+/// The 'Performed Imaging Agent Administration SR Storage' SOP Class.
+///
+pub const PERFORMED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE: SopClass =
+  SopClass {
+    name: "Performed Imaging Agent Administration SR Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.88.75",
+    category: SopClassCategory::StructuredReport,
+  };
+
+/// The 'Enhanced X-Ray Radiation Dose SR Storage' SOP Class.
+///
+pub const ENHANCED_X_RAY_RADIATION_DOSE_SR_STORAGE: SopClass = SopClass {
+  name: "Enhanced X-Ray Radiation Dose SR Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.88.76",
+  category: SopClassCategory::StructuredReport,
+};
+
+/// The 'Content Assessment Results Storage' SOP Class.
+///
+pub const CONTENT_ASSESSMENT_RESULTS_STORAGE: SopClass = SopClass {
+  name: "Content Assessment Results Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.90.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Microscopy Bulk Simple Annotations Storage' SOP Class.
+///
+pub const MICROSCOPY_BULK_SIMPLE_ANNOTATIONS_STORAGE: SopClass = SopClass {
+  name: "Microscopy Bulk Simple Annotations Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.91.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Encapsulated PDF Storage' SOP Class.
+///
+pub const ENCAPSULATED_PDF_STORAGE: SopClass = SopClass {
+  name: "Encapsulated PDF Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.104.1",
+  category: SopClassCategory::EncapsulatedDocument,
+};
+
+/// The 'Encapsulated CDA Storage' SOP Class.
+///
+pub const ENCAPSULATED_CDA_STORAGE: SopClass = SopClass {
+  name: "Encapsulated CDA Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.104.2",
+  category: SopClassCategory::EncapsulatedDocument,
+};
+
+/// The 'Encapsulated STL Storage' SOP Class.
+///
+pub const ENCAPSULATED_STL_STORAGE: SopClass = SopClass {
+  name: "Encapsulated STL Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.104.3",
+  category: SopClassCategory::EncapsulatedDocument,
+};
+
+/// The 'Encapsulated OBJ Storage' SOP Class.
+///
+pub const ENCAPSULATED_OBJ_STORAGE: SopClass = SopClass {
+  name: "Encapsulated OBJ Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.104.4",
+  category: SopClassCategory::EncapsulatedDocument,
+};
+
+/// The 'Encapsulated MTL Storage' SOP Class.
+///
+pub const ENCAPSULATED_MTL_STORAGE: SopClass = SopClass {
+  name: "Encapsulated MTL Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.104.5",
+  category: SopClassCategory::EncapsulatedDocument,
+};
+
+/// The 'Positron Emission Tomography Image Storage' SOP Class.
+///
+pub const POSITRON_EMISSION_TOMOGRAPHY_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Positron Emission Tomography Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.128",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Legacy Converted Enhanced PET Image Storage' SOP Class.
+///
+pub const LEGACY_CONVERTED_ENHANCED_PET_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Legacy Converted Enhanced PET Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.128.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Standalone PET Curve Storage' SOP Class.
+///
+pub const STANDALONE_PET_CURVE_STORAGE: SopClass = SopClass {
+  name: "Standalone PET Curve Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.129",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Enhanced PET Image Storage' SOP Class.
+///
+pub const ENHANCED_PET_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced PET Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.130",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Basic Structured Display Storage' SOP Class.
+///
+pub const BASIC_STRUCTURED_DISPLAY_STORAGE: SopClass = SopClass {
+  name: "Basic Structured Display Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.131",
+  category: SopClassCategory::Other,
+};
+
+/// The 'CT Defined Procedure Protocol Storage' SOP Class.
+///
+pub const CT_DEFINED_PROCEDURE_PROTOCOL_STORAGE: SopClass = SopClass {
+  name: "CT Defined Procedure Protocol Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.200.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'CT Performed Procedure Protocol Storage' SOP Class.
+///
+pub const CT_PERFORMED_PROCEDURE_PROTOCOL_STORAGE: SopClass = SopClass {
+  name: "CT Performed Procedure Protocol Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.200.2",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Protocol Approval Storage' SOP Class.
+///
+pub const PROTOCOL_APPROVAL_STORAGE: SopClass = SopClass {
+  name: "Protocol Approval Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.200.3",
+  category: SopClassCategory::Other,
+};
+
+/// The 'XA Defined Procedure Protocol Storage' SOP Class.
+///
+pub const XA_DEFINED_PROCEDURE_PROTOCOL_STORAGE: SopClass = SopClass {
+  name: "XA Defined Procedure Protocol Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.200.7",
+  category: SopClassCategory::Other,
+};
+
+/// The 'XA Performed Procedure Protocol Storage' SOP Class.
+///
+pub const XA_PERFORMED_PROCEDURE_PROTOCOL_STORAGE: SopClass = SopClass {
+  name: "XA Performed Procedure Protocol Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.200.8",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Inventory Storage' SOP Class.
+///
+pub const INVENTORY_STORAGE: SopClass = SopClass {
+  name: "Inventory Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.201.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'RT Image Storage' SOP Class.
+///
+pub const RT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "RT Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'RT Dose Storage' SOP Class.
+///
+pub const RT_DOSE_STORAGE: SopClass = SopClass {
+  name: "RT Dose Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.2",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Structure Set Storage' SOP Class.
+///
+pub const RT_STRUCTURE_SET_STORAGE: SopClass = SopClass {
+  name: "RT Structure Set Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.3",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Beams Treatment Record Storage' SOP Class.
+///
+pub const RT_BEAMS_TREATMENT_RECORD_STORAGE: SopClass = SopClass {
+  name: "RT Beams Treatment Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.4",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Plan Storage' SOP Class.
+///
+pub const RT_PLAN_STORAGE: SopClass = SopClass {
+  name: "RT Plan Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.5",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Brachy Treatment Record Storage' SOP Class.
+///
+pub const RT_BRACHY_TREATMENT_RECORD_STORAGE: SopClass = SopClass {
+  name: "RT Brachy Treatment Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.6",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Treatment Summary Record Storage' SOP Class.
+///
+pub const RT_TREATMENT_SUMMARY_RECORD_STORAGE: SopClass = SopClass {
+  name: "RT Treatment Summary Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.7",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Ion Plan Storage' SOP Class.
+///
+pub const RT_ION_PLAN_STORAGE: SopClass = SopClass {
+  name: "RT Ion Plan Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.8",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Ion Beams Treatment Record Storage' SOP Class.
+///
+pub const RT_ION_BEAMS_TREATMENT_RECORD_STORAGE: SopClass = SopClass {
+  name: "RT Ion Beams Treatment Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.9",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Physician Intent Storage' SOP Class.
+///
+pub const RT_PHYSICIAN_INTENT_STORAGE: SopClass = SopClass {
+  name: "RT Physician Intent Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.10",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Segment Annotation Storage' SOP Class.
+///
+pub const RT_SEGMENT_ANNOTATION_STORAGE: SopClass = SopClass {
+  name: "RT Segment Annotation Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.11",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Radiation Set Storage' SOP Class.
+///
+pub const RT_RADIATION_SET_STORAGE: SopClass = SopClass {
+  name: "RT Radiation Set Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.12",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'C-Arm Photon-Electron Radiation Storage' SOP Class.
+///
+pub const C_ARM_PHOTON_ELECTRON_RADIATION_STORAGE: SopClass = SopClass {
+  name: "C-Arm Photon-Electron Radiation Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.13",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'Tomotherapeutic Radiation Storage' SOP Class.
+///
+pub const TOMOTHERAPEUTIC_RADIATION_STORAGE: SopClass = SopClass {
+  name: "Tomotherapeutic Radiation Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.14",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'Robotic-Arm Radiation Storage' SOP Class.
+///
+pub const ROBOTIC_ARM_RADIATION_STORAGE: SopClass = SopClass {
+  name: "Robotic-Arm Radiation Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.15",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Radiation Record Set Storage' SOP Class.
+///
+pub const RT_RADIATION_RECORD_SET_STORAGE: SopClass = SopClass {
+  name: "RT Radiation Record Set Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.16",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Radiation Salvage Record Storage' SOP Class.
+///
+pub const RT_RADIATION_SALVAGE_RECORD_STORAGE: SopClass = SopClass {
+  name: "RT Radiation Salvage Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.17",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'Tomotherapeutic Radiation Record Storage' SOP Class.
+///
+pub const TOMOTHERAPEUTIC_RADIATION_RECORD_STORAGE: SopClass = SopClass {
+  name: "Tomotherapeutic Radiation Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.18",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'C-Arm Photon-Electron Radiation Record Storage' SOP Class.
+///
+pub const C_ARM_PHOTON_ELECTRON_RADIATION_RECORD_STORAGE: SopClass = SopClass {
+  name: "C-Arm Photon-Electron Radiation Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.19",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'Robotic Radiation Record Storage' SOP Class.
+///
+pub const ROBOTIC_RADIATION_RECORD_STORAGE: SopClass = SopClass {
+  name: "Robotic Radiation Record Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.20",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Radiation Set Delivery Instruction Storage' SOP Class.
+///
+pub const RT_RADIATION_SET_DELIVERY_INSTRUCTION_STORAGE: SopClass = SopClass {
+  name: "RT Radiation Set Delivery Instruction Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.21",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Treatment Preparation Storage' SOP Class.
+///
+pub const RT_TREATMENT_PREPARATION_STORAGE: SopClass = SopClass {
+  name: "RT Treatment Preparation Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.22",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'Enhanced RT Image Storage' SOP Class.
+///
+pub const ENHANCED_RT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced RT Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.23",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Enhanced Continuous RT Image Storage' SOP Class.
+///
+pub const ENHANCED_CONTINUOUS_RT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Enhanced Continuous RT Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.481.24",
+  category: SopClassCategory::Image,
+};
+
+/// The 'RT Patient Position Acquisition Instruction Storage' SOP Class.
+///
+pub const RT_PATIENT_POSITION_ACQUISITION_INSTRUCTION_STORAGE: SopClass =
+  SopClass {
+    name: "RT Patient Position Acquisition Instruction Storage",
+    uid: "1.2.840.10008.5.1.4.1.1.481.25",
+    category: SopClassCategory::Radiotherapy,
+  };
+
+/// The 'DICOS CT Image Storage' SOP Class.
+///
+pub const DICOS_CT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "DICOS CT Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.501.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'DICOS Digital X-Ray Image Storage - For Presentation' SOP Class.
+///
+pub const DICOS_DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: SopClass =
+  SopClass {
+    name: "DICOS Digital X-Ray Image Storage - For Presentation",
+    uid: "1.2.840.10008.5.1.4.1.1.501.2.1",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'DICOS Digital X-Ray Image Storage - For Processing' SOP Class.
+///
+pub const DICOS_DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: SopClass =
+  SopClass {
+    name: "DICOS Digital X-Ray Image Storage - For Processing",
+    uid: "1.2.840.10008.5.1.4.1.1.501.2.2",
+    category: SopClassCategory::Image,
+  };
+
+/// The 'DICOS Threat Detection Report Storage' SOP Class.
+///
+pub const DICOS_THREAT_DETECTION_REPORT_STORAGE: SopClass = SopClass {
+  name: "DICOS Threat Detection Report Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.501.3",
+  category: SopClassCategory::Other,
+};
+
+/// The 'DICOS 2D AIT Storage' SOP Class.
+///
+pub const DICOS_2D_AIT_STORAGE: SopClass = SopClass {
+  name: "DICOS 2D AIT Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.501.4",
+  category: SopClassCategory::Other,
+};
+
+/// The 'DICOS 3D AIT Storage' SOP Class.
+///
+pub const DICOS_3D_AIT_STORAGE: SopClass = SopClass {
+  name: "DICOS 3D AIT Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.501.5",
+  category: SopClassCategory::Other,
+};
+
+/// The 'DICOS Quadrupole Resonance (QR) Storage' SOP Class.
+///
+pub const DICOS_QUADRUPOLE_RESONANCE_QR_STORAGE: SopClass = SopClass {
+  name: "DICOS Quadrupole Resonance (QR) Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.501.6",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Eddy Current Image Storage' SOP Class.
+///
+pub const EDDY_CURRENT_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Eddy Current Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.601.1",
+  category: SopClassCategory::Image,
+};
+
+/// The 'Eddy Current Multi-frame Image Storage' SOP Class.
+///
+pub const EDDY_CURRENT_MULTI_FRAME_IMAGE_STORAGE: SopClass = SopClass {
+  name: "Eddy Current Multi-frame Image Storage",
+  uid: "1.2.840.10008.5.1.4.1.1.601.2",
+  category: SopClassCategory::Image,
+};
+
+/// The 'RT Beams Delivery Instruction Storage - Trial' SOP Class.
+///
+pub const RT_BEAMS_DELIVERY_INSTRUCTION_STORAGE_TRIAL: SopClass = SopClass {
+  name: "RT Beams Delivery Instruction Storage - Trial",
+  uid: "1.2.840.10008.5.1.4.34.1",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Beams Delivery Instruction Storage' SOP Class.
+///
+pub const RT_BEAMS_DELIVERY_INSTRUCTION_STORAGE: SopClass = SopClass {
+  name: "RT Beams Delivery Instruction Storage",
+  uid: "1.2.840.10008.5.1.4.34.7",
+  category: SopClassCategory::Radiotherapy,
+};
+
+/// The 'RT Brachy Application Setup Delivery Instruction Storage' SOP Class.
+///
+pub const RT_BRACHY_APPLICATION_SETUP_DELIVERY_INSTRUCTION_STORAGE: SopClass =
+  SopClass {
+    name: "RT Brachy Application Setup Delivery Instruction Storage",
+    uid: "1.2.840.10008.5.1.4.34.10",
+    category: SopClassCategory::Radiotherapy,
+  };
+
+/// The 'Hanging Protocol Storage' SOP Class.
+///
+pub const HANGING_PROTOCOL_STORAGE: SopClass = SopClass {
+  name: "Hanging Protocol Storage",
+  uid: "1.2.840.10008.5.1.4.38.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Color Palette Storage' SOP Class.
+///
+pub const COLOR_PALETTE_STORAGE: SopClass = SopClass {
+  name: "Color Palette Storage",
+  uid: "1.2.840.10008.5.1.4.39.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Generic Implant Template Storage' SOP Class.
+///
+pub const GENERIC_IMPLANT_TEMPLATE_STORAGE: SopClass = SopClass {
+  name: "Generic Implant Template Storage",
+  uid: "1.2.840.10008.5.1.4.43.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Implant Assembly Template Storage' SOP Class.
+///
+pub const IMPLANT_ASSEMBLY_TEMPLATE_STORAGE: SopClass = SopClass {
+  name: "Implant Assembly Template Storage",
+  uid: "1.2.840.10008.5.1.4.44.1",
+  category: SopClassCategory::Other,
+};
+
+/// The 'Implant Template Group Storage' SOP Class.
+///
+pub const IMPLANT_TEMPLATE_GROUP_STORAGE: SopClass = SopClass {
+  name: "Implant Template Group Storage",
+  uid: "1.2.840.10008.5.1.4.45.1",
+  category: SopClassCategory::Other,
+};
+
+/// A list of all storage SOP Classes.
+///
+pub const ALL: [SopClass; 196] = [
+  COMPUTED_RADIOGRAPHY_IMAGE_STORAGE,
+  DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION,
+  DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING,
+  DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION,
+  DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PROCESSING,
+  DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION,
+  DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING,
+  CT_IMAGE_STORAGE,
+  ENHANCED_CT_IMAGE_STORAGE,
+  LEGACY_CONVERTED_ENHANCED_CT_IMAGE_STORAGE,
+  ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE_RETIRED,
+  ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE,
+  MR_IMAGE_STORAGE,
+  ENHANCED_MR_IMAGE_STORAGE,
+  MR_SPECTROSCOPY_STORAGE,
+  ENHANCED_MR_COLOR_IMAGE_STORAGE,
+  LEGACY_CONVERTED_ENHANCED_MR_IMAGE_STORAGE,
+  NUCLEAR_MEDICINE_IMAGE_STORAGE_RETIRED,
+  ULTRASOUND_IMAGE_STORAGE_RETIRED,
+  ULTRASOUND_IMAGE_STORAGE,
+  ENHANCED_US_VOLUME_STORAGE,
+  PHOTOACOUSTIC_IMAGE_STORAGE,
+  SECONDARY_CAPTURE_IMAGE_STORAGE,
+  MULTI_FRAME_SINGLE_BIT_SECONDARY_CAPTURE_IMAGE_STORAGE,
+  MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE,
+  MULTI_FRAME_GRAYSCALE_WORD_SECONDARY_CAPTURE_IMAGE_STORAGE,
+  MULTI_FRAME_TRUE_COLOR_SECONDARY_CAPTURE_IMAGE_STORAGE,
+  STANDALONE_OVERLAY_STORAGE,
+  STANDALONE_CURVE_STORAGE,
+  WAVEFORM_STORAGE_TRIAL,
+  TWELVE_LEAD_ECG_WAVEFORM_STORAGE,
+  GENERAL_ECG_WAVEFORM_STORAGE,
+  AMBULATORY_ECG_WAVEFORM_STORAGE,
+  GENERAL_32_BIT_ECG_WAVEFORM_STORAGE,
+  HEMODYNAMIC_WAVEFORM_STORAGE,
+  CARDIAC_ELECTROPHYSIOLOGY_WAVEFORM_STORAGE,
+  BASIC_VOICE_AUDIO_WAVEFORM_STORAGE,
+  GENERAL_AUDIO_WAVEFORM_STORAGE,
+  ARTERIAL_PULSE_WAVEFORM_STORAGE,
+  RESPIRATORY_WAVEFORM_STORAGE,
+  MULTI_CHANNEL_RESPIRATORY_WAVEFORM_STORAGE,
+  ROUTINE_SCALP_ELECTROENCEPHALOGRAM_WAVEFORM_STORAGE,
+  ELECTROMYOGRAM_WAVEFORM_STORAGE,
+  ELECTROOCULOGRAM_WAVEFORM_STORAGE,
+  SLEEP_ELECTROENCEPHALOGRAM_WAVEFORM_STORAGE,
+  BODY_POSITION_WAVEFORM_STORAGE,
+  STANDALONE_MODALITY_LUT_STORAGE,
+  STANDALONE_VOI_LUT_STORAGE,
+  GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+  COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+  PSEUDO_COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+  BLENDING_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+  XA_XRF_GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+  GRAYSCALE_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE,
+  COMPOSITING_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE,
+  ADVANCED_BLENDING_PRESENTATION_STATE_STORAGE,
+  VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE,
+  SEGMENTED_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE,
+  MULTIPLE_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE,
+  VARIABLE_MODALITY_LUT_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+  X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE,
+  ENHANCED_XA_IMAGE_STORAGE,
+  X_RAY_RADIOFLUOROSCOPIC_IMAGE_STORAGE,
+  ENHANCED_XRF_IMAGE_STORAGE,
+  X_RAY_ANGIOGRAPHIC_BI_PLANE_IMAGE_STORAGE,
+  X_RAY_3D_ANGIOGRAPHIC_IMAGE_STORAGE,
+  X_RAY_3D_CRANIOFACIAL_IMAGE_STORAGE,
+  BREAST_TOMOSYNTHESIS_IMAGE_STORAGE,
+  BREAST_PROJECTION_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION,
+  BREAST_PROJECTION_X_RAY_IMAGE_STORAGE_FOR_PROCESSING,
+  NUCLEAR_MEDICINE_IMAGE_STORAGE,
+  PARAMETRIC_MAP_STORAGE,
+  RAW_DATA_STORAGE,
+  SPATIAL_REGISTRATION_STORAGE,
+  SPATIAL_FIDUCIALS_STORAGE,
+  DEFORMABLE_SPATIAL_REGISTRATION_STORAGE,
+  SEGMENTATION_STORAGE,
+  SURFACE_SEGMENTATION_STORAGE,
+  TRACTOGRAPHY_RESULTS_STORAGE,
+  REAL_WORLD_VALUE_MAPPING_STORAGE,
+  SURFACE_SCAN_MESH_STORAGE,
+  SURFACE_SCAN_POINT_CLOUD_STORAGE,
+  VL_IMAGE_STORAGE_TRIAL,
+  VL_ENDOSCOPIC_IMAGE_STORAGE,
+  VIDEO_ENDOSCOPIC_IMAGE_STORAGE,
+  VL_MICROSCOPIC_IMAGE_STORAGE,
+  VIDEO_MICROSCOPIC_IMAGE_STORAGE,
+  VL_SLIDE_COORDINATES_MICROSCOPIC_IMAGE_STORAGE,
+  VL_PHOTOGRAPHIC_IMAGE_STORAGE,
+  VIDEO_PHOTOGRAPHIC_IMAGE_STORAGE,
+  OPHTHALMIC_PHOTOGRAPHY_8_BIT_IMAGE_STORAGE,
+  OPHTHALMIC_PHOTOGRAPHY_16_BIT_IMAGE_STORAGE,
+  STEREOMETRIC_RELATIONSHIP_STORAGE,
+  OPHTHALMIC_TOMOGRAPHY_IMAGE_STORAGE,
+  WIDE_FIELD_OPHTHALMIC_PHOTOGRAPHY_3D_COORDINATES_IMAGE_STORAGE,
+  OPHTHALMIC_OPTICAL_COHERENCE_TOMOGRAPHY_EN_FACE_IMAGE_STORAGE,
+  VL_WHOLE_SLIDE_MICROSCOPY_IMAGE_STORAGE,
+  DERMOSCOPIC_PHOTOGRAPHY_IMAGE_STORAGE,
+  CONFOCAL_MICROSCOPY_IMAGE_STORAGE,
+  CONFOCAL_MICROSCOPY_TILED_PYRAMIDAL_IMAGE_STORAGE,
+  VL_MULTI_FRAME_IMAGE_STORAGE_TRIAL,
+  LENSOMETRY_MEASUREMENTS_STORAGE,
+  AUTOREFRACTION_MEASUREMENTS_STORAGE,
+  KERATOMETRY_MEASUREMENTS_STORAGE,
+  SUBJECTIVE_REFRACTION_MEASUREMENTS_STORAGE,
+  VISUAL_ACUITY_MEASUREMENTS_STORAGE,
+  SPECTACLE_PRESCRIPTION_REPORT_STORAGE,
+  OPHTHALMIC_AXIAL_MEASUREMENTS_STORAGE,
+  INTRAOCULAR_LENS_CALCULATIONS_STORAGE,
+  MACULAR_GRID_THICKNESS_AND_VOLUME_REPORT_STORAGE,
+  OPHTHALMIC_VISUAL_FIELD_STATIC_PERIMETRY_MEASUREMENTS_STORAGE,
+  OPHTHALMIC_THICKNESS_MAP_STORAGE,
+  CORNEAL_TOPOGRAPHY_MAP_STORAGE,
+  TEXT_SR_STORAGE_TRIAL,
+  AUDIO_SR_STORAGE_TRIAL,
+  DETAIL_SR_STORAGE_TRIAL,
+  COMPREHENSIVE_SR_STORAGE_TRIAL,
+  BASIC_TEXT_SR_STORAGE,
+  ENHANCED_SR_STORAGE,
+  COMPREHENSIVE_SR_STORAGE,
+  COMPREHENSIVE_3D_SR_STORAGE,
+  EXTENSIBLE_SR_STORAGE,
+  PROCEDURE_LOG_STORAGE,
+  MAMMOGRAPHY_CAD_SR_STORAGE,
+  KEY_OBJECT_SELECTION_DOCUMENT_STORAGE,
+  CHEST_CAD_SR_STORAGE,
+  X_RAY_RADIATION_DOSE_SR_STORAGE,
+  RADIOPHARMACEUTICAL_RADIATION_DOSE_SR_STORAGE,
+  COLON_CAD_SR_STORAGE,
+  IMPLANTATION_PLAN_SR_STORAGE,
+  ACQUISITION_CONTEXT_SR_STORAGE,
+  SIMPLIFIED_ADULT_ECHO_SR_STORAGE,
+  PATIENT_RADIATION_DOSE_SR_STORAGE,
+  PLANNED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE,
+  PERFORMED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE,
+  ENHANCED_X_RAY_RADIATION_DOSE_SR_STORAGE,
+  CONTENT_ASSESSMENT_RESULTS_STORAGE,
+  MICROSCOPY_BULK_SIMPLE_ANNOTATIONS_STORAGE,
+  ENCAPSULATED_PDF_STORAGE,
+  ENCAPSULATED_CDA_STORAGE,
+  ENCAPSULATED_STL_STORAGE,
+  ENCAPSULATED_OBJ_STORAGE,
+  ENCAPSULATED_MTL_STORAGE,
+  POSITRON_EMISSION_TOMOGRAPHY_IMAGE_STORAGE,
+  LEGACY_CONVERTED_ENHANCED_PET_IMAGE_STORAGE,
+  STANDALONE_PET_CURVE_STORAGE,
+  ENHANCED_PET_IMAGE_STORAGE,
+  BASIC_STRUCTURED_DISPLAY_STORAGE,
+  CT_DEFINED_PROCEDURE_PROTOCOL_STORAGE,
+  CT_PERFORMED_PROCEDURE_PROTOCOL_STORAGE,
+  PROTOCOL_APPROVAL_STORAGE,
+  XA_DEFINED_PROCEDURE_PROTOCOL_STORAGE,
+  XA_PERFORMED_PROCEDURE_PROTOCOL_STORAGE,
+  INVENTORY_STORAGE,
+  RT_IMAGE_STORAGE,
+  RT_DOSE_STORAGE,
+  RT_STRUCTURE_SET_STORAGE,
+  RT_BEAMS_TREATMENT_RECORD_STORAGE,
+  RT_PLAN_STORAGE,
+  RT_BRACHY_TREATMENT_RECORD_STORAGE,
+  RT_TREATMENT_SUMMARY_RECORD_STORAGE,
+  RT_ION_PLAN_STORAGE,
+  RT_ION_BEAMS_TREATMENT_RECORD_STORAGE,
+  RT_PHYSICIAN_INTENT_STORAGE,
+  RT_SEGMENT_ANNOTATION_STORAGE,
+  RT_RADIATION_SET_STORAGE,
+  C_ARM_PHOTON_ELECTRON_RADIATION_STORAGE,
+  TOMOTHERAPEUTIC_RADIATION_STORAGE,
+  ROBOTIC_ARM_RADIATION_STORAGE,
+  RT_RADIATION_RECORD_SET_STORAGE,
+  RT_RADIATION_SALVAGE_RECORD_STORAGE,
+  TOMOTHERAPEUTIC_RADIATION_RECORD_STORAGE,
+  C_ARM_PHOTON_ELECTRON_RADIATION_RECORD_STORAGE,
+  ROBOTIC_RADIATION_RECORD_STORAGE,
+  RT_RADIATION_SET_DELIVERY_INSTRUCTION_STORAGE,
+  RT_TREATMENT_PREPARATION_STORAGE,
+  ENHANCED_RT_IMAGE_STORAGE,
+  ENHANCED_CONTINUOUS_RT_IMAGE_STORAGE,
+  RT_PATIENT_POSITION_ACQUISITION_INSTRUCTION_STORAGE,
+  DICOS_CT_IMAGE_STORAGE,
+  DICOS_DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION,
+  DICOS_DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING,
+  DICOS_THREAT_DETECTION_REPORT_STORAGE,
+  DICOS_2D_AIT_STORAGE,
+  DICOS_3D_AIT_STORAGE,
+  DICOS_QUADRUPOLE_RESONANCE_QR_STORAGE,
+  EDDY_CURRENT_IMAGE_STORAGE,
+  EDDY_CURRENT_MULTI_FRAME_IMAGE_STORAGE,
+  RT_BEAMS_DELIVERY_INSTRUCTION_STORAGE_TRIAL,
+  RT_BEAMS_DELIVERY_INSTRUCTION_STORAGE,
+  RT_BRACHY_APPLICATION_SETUP_DELIVERY_INSTRUCTION_STORAGE,
+  HANGING_PROTOCOL_STORAGE,
+  COLOR_PALETTE_STORAGE,
+  GENERIC_IMPLANT_TEMPLATE_STORAGE,
+  IMPLANT_ASSEMBLY_TEMPLATE_STORAGE,
+  IMPLANT_TEMPLATE_GROUP_STORAGE,
+];
+
+impl SopClass {
+  /// Returns the storage SOP Class with the given UID. If the UID isn't
+  /// recognized then an error is returned.
+  ///
+  #[allow(clippy::result_unit_err)]
+  pub fn from_uid(uid: &str) -> Result<&'static Self, ()> {
+    match uid {
+      "1.2.840.10008.5.1.4.1.1.1" => Ok(&COMPUTED_RADIOGRAPHY_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.1.1" => {
+        Ok(&DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION)
+      }
+      "1.2.840.10008.5.1.4.1.1.1.1.1" => {
+        Ok(&DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING)
+      }
+      "1.2.840.10008.5.1.4.1.1.1.2" => {
+        Ok(&DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION)
+      }
+      "1.2.840.10008.5.1.4.1.1.1.2.1" => {
+        Ok(&DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PROCESSING)
+      }
+      "1.2.840.10008.5.1.4.1.1.1.3" => {
+        Ok(&DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION)
+      }
+      "1.2.840.10008.5.1.4.1.1.1.3.1" => {
+        Ok(&DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING)
+      }
+      "1.2.840.10008.5.1.4.1.1.2" => Ok(&CT_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.2.1" => Ok(&ENHANCED_CT_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.2.2" => {
+        Ok(&LEGACY_CONVERTED_ENHANCED_CT_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.3" => {
+        Ok(&ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE_RETIRED)
+      }
+      "1.2.840.10008.5.1.4.1.1.3.1" => {
+        Ok(&ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.4" => Ok(&MR_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.4.1" => Ok(&ENHANCED_MR_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.4.2" => Ok(&MR_SPECTROSCOPY_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.4.3" => Ok(&ENHANCED_MR_COLOR_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.4.4" => {
+        Ok(&LEGACY_CONVERTED_ENHANCED_MR_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.5" => {
+        Ok(&NUCLEAR_MEDICINE_IMAGE_STORAGE_RETIRED)
+      }
+      "1.2.840.10008.5.1.4.1.1.6" => Ok(&ULTRASOUND_IMAGE_STORAGE_RETIRED),
+      "1.2.840.10008.5.1.4.1.1.6.1" => Ok(&ULTRASOUND_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.6.2" => Ok(&ENHANCED_US_VOLUME_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.6.3" => Ok(&PHOTOACOUSTIC_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.7" => Ok(&SECONDARY_CAPTURE_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.7.1" => {
+        Ok(&MULTI_FRAME_SINGLE_BIT_SECONDARY_CAPTURE_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.7.2" => {
+        Ok(&MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.7.3" => {
+        Ok(&MULTI_FRAME_GRAYSCALE_WORD_SECONDARY_CAPTURE_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.7.4" => {
+        Ok(&MULTI_FRAME_TRUE_COLOR_SECONDARY_CAPTURE_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.8" => Ok(&STANDALONE_OVERLAY_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9" => Ok(&STANDALONE_CURVE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.1" => Ok(&WAVEFORM_STORAGE_TRIAL),
+      "1.2.840.10008.5.1.4.1.1.9.1.1" => Ok(&TWELVE_LEAD_ECG_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.1.2" => Ok(&GENERAL_ECG_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.1.3" => Ok(&AMBULATORY_ECG_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.1.4" => {
+        Ok(&GENERAL_32_BIT_ECG_WAVEFORM_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.9.2.1" => Ok(&HEMODYNAMIC_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.3.1" => {
+        Ok(&CARDIAC_ELECTROPHYSIOLOGY_WAVEFORM_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.9.4.1" => {
+        Ok(&BASIC_VOICE_AUDIO_WAVEFORM_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.9.4.2" => Ok(&GENERAL_AUDIO_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.5.1" => Ok(&ARTERIAL_PULSE_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.6.1" => Ok(&RESPIRATORY_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.6.2" => {
+        Ok(&MULTI_CHANNEL_RESPIRATORY_WAVEFORM_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.9.7.1" => {
+        Ok(&ROUTINE_SCALP_ELECTROENCEPHALOGRAM_WAVEFORM_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.9.7.2" => Ok(&ELECTROMYOGRAM_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.7.3" => Ok(&ELECTROOCULOGRAM_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.9.7.4" => {
+        Ok(&SLEEP_ELECTROENCEPHALOGRAM_WAVEFORM_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.9.8.1" => Ok(&BODY_POSITION_WAVEFORM_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.10" => Ok(&STANDALONE_MODALITY_LUT_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.11" => Ok(&STANDALONE_VOI_LUT_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.11.1" => {
+        Ok(&GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.2" => {
+        Ok(&COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.3" => {
+        Ok(&PSEUDO_COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.4" => {
+        Ok(&BLENDING_SOFTCOPY_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.5" => {
+        Ok(&XA_XRF_GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.6" => {
+        Ok(&GRAYSCALE_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.7" => {
+        Ok(&COMPOSITING_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.8" => {
+        Ok(&ADVANCED_BLENDING_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.9" => {
+        Ok(&VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.10" => {
+        Ok(&SEGMENTED_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.11" => {
+        Ok(&MULTIPLE_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.11.12" => {
+        Ok(&VARIABLE_MODALITY_LUT_SOFTCOPY_PRESENTATION_STATE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.12.1" => Ok(&X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.12.1.1" => Ok(&ENHANCED_XA_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.12.2" => {
+        Ok(&X_RAY_RADIOFLUOROSCOPIC_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.12.2.1" => Ok(&ENHANCED_XRF_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.12.3" => {
+        Ok(&X_RAY_ANGIOGRAPHIC_BI_PLANE_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.13.1.1" => {
+        Ok(&X_RAY_3D_ANGIOGRAPHIC_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.13.1.2" => {
+        Ok(&X_RAY_3D_CRANIOFACIAL_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.13.1.3" => {
+        Ok(&BREAST_TOMOSYNTHESIS_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.13.1.4" => {
+        Ok(&BREAST_PROJECTION_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION)
+      }
+      "1.2.840.10008.5.1.4.1.1.13.1.5" => {
+        Ok(&BREAST_PROJECTION_X_RAY_IMAGE_STORAGE_FOR_PROCESSING)
+      }
+      "1.2.840.10008.5.1.4.1.1.20" => Ok(&NUCLEAR_MEDICINE_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.30" => Ok(&PARAMETRIC_MAP_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.66" => Ok(&RAW_DATA_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.66.1" => Ok(&SPATIAL_REGISTRATION_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.66.2" => Ok(&SPATIAL_FIDUCIALS_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.66.3" => {
+        Ok(&DEFORMABLE_SPATIAL_REGISTRATION_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.66.4" => Ok(&SEGMENTATION_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.66.5" => Ok(&SURFACE_SEGMENTATION_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.66.6" => Ok(&TRACTOGRAPHY_RESULTS_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.67" => Ok(&REAL_WORLD_VALUE_MAPPING_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.68.1" => Ok(&SURFACE_SCAN_MESH_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.68.2" => Ok(&SURFACE_SCAN_POINT_CLOUD_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.77.1" => Ok(&VL_IMAGE_STORAGE_TRIAL),
+      "1.2.840.10008.5.1.4.1.1.77.1.1" => Ok(&VL_ENDOSCOPIC_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.77.1.1.1" => Ok(&VIDEO_ENDOSCOPIC_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.77.1.2" => Ok(&VL_MICROSCOPIC_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.77.1.2.1" => {
+        Ok(&VIDEO_MICROSCOPIC_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.3" => {
+        Ok(&VL_SLIDE_COORDINATES_MICROSCOPIC_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.4" => Ok(&VL_PHOTOGRAPHIC_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.77.1.4.1" => {
+        Ok(&VIDEO_PHOTOGRAPHIC_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.5.1" => {
+        Ok(&OPHTHALMIC_PHOTOGRAPHY_8_BIT_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.5.2" => {
+        Ok(&OPHTHALMIC_PHOTOGRAPHY_16_BIT_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.5.3" => {
+        Ok(&STEREOMETRIC_RELATIONSHIP_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.5.4" => {
+        Ok(&OPHTHALMIC_TOMOGRAPHY_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.5.6" => {
+        Ok(&WIDE_FIELD_OPHTHALMIC_PHOTOGRAPHY_3D_COORDINATES_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.5.7" => {
+        Ok(&OPHTHALMIC_OPTICAL_COHERENCE_TOMOGRAPHY_EN_FACE_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.6" => {
+        Ok(&VL_WHOLE_SLIDE_MICROSCOPY_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.7" => {
+        Ok(&DERMOSCOPIC_PHOTOGRAPHY_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.8" => {
+        Ok(&CONFOCAL_MICROSCOPY_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.1.9" => {
+        Ok(&CONFOCAL_MICROSCOPY_TILED_PYRAMIDAL_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.77.2" => Ok(&VL_MULTI_FRAME_IMAGE_STORAGE_TRIAL),
+      "1.2.840.10008.5.1.4.1.1.78.1" => Ok(&LENSOMETRY_MEASUREMENTS_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.78.2" => {
+        Ok(&AUTOREFRACTION_MEASUREMENTS_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.78.3" => Ok(&KERATOMETRY_MEASUREMENTS_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.78.4" => {
+        Ok(&SUBJECTIVE_REFRACTION_MEASUREMENTS_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.78.5" => Ok(&VISUAL_ACUITY_MEASUREMENTS_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.78.6" => {
+        Ok(&SPECTACLE_PRESCRIPTION_REPORT_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.78.7" => {
+        Ok(&OPHTHALMIC_AXIAL_MEASUREMENTS_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.78.8" => {
+        Ok(&INTRAOCULAR_LENS_CALCULATIONS_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.79.1" => {
+        Ok(&MACULAR_GRID_THICKNESS_AND_VOLUME_REPORT_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.80.1" => {
+        Ok(&OPHTHALMIC_VISUAL_FIELD_STATIC_PERIMETRY_MEASUREMENTS_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.81.1" => Ok(&OPHTHALMIC_THICKNESS_MAP_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.82.1" => Ok(&CORNEAL_TOPOGRAPHY_MAP_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.1" => Ok(&TEXT_SR_STORAGE_TRIAL),
+      "1.2.840.10008.5.1.4.1.1.88.2" => Ok(&AUDIO_SR_STORAGE_TRIAL),
+      "1.2.840.10008.5.1.4.1.1.88.3" => Ok(&DETAIL_SR_STORAGE_TRIAL),
+      "1.2.840.10008.5.1.4.1.1.88.4" => Ok(&COMPREHENSIVE_SR_STORAGE_TRIAL),
+      "1.2.840.10008.5.1.4.1.1.88.11" => Ok(&BASIC_TEXT_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.22" => Ok(&ENHANCED_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.33" => Ok(&COMPREHENSIVE_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.34" => Ok(&COMPREHENSIVE_3D_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.35" => Ok(&EXTENSIBLE_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.40" => Ok(&PROCEDURE_LOG_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.50" => Ok(&MAMMOGRAPHY_CAD_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.59" => {
+        Ok(&KEY_OBJECT_SELECTION_DOCUMENT_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.88.65" => Ok(&CHEST_CAD_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.67" => Ok(&X_RAY_RADIATION_DOSE_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.68" => {
+        Ok(&RADIOPHARMACEUTICAL_RADIATION_DOSE_SR_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.88.69" => Ok(&COLON_CAD_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.70" => Ok(&IMPLANTATION_PLAN_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.71" => Ok(&ACQUISITION_CONTEXT_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.72" => Ok(&SIMPLIFIED_ADULT_ECHO_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.73" => Ok(&PATIENT_RADIATION_DOSE_SR_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.88.74" => {
+        Ok(&PLANNED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.88.75" => {
+        Ok(&PERFORMED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.88.76" => {
+        Ok(&ENHANCED_X_RAY_RADIATION_DOSE_SR_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.90.1" => Ok(&CONTENT_ASSESSMENT_RESULTS_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.91.1" => {
+        Ok(&MICROSCOPY_BULK_SIMPLE_ANNOTATIONS_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.104.1" => Ok(&ENCAPSULATED_PDF_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.104.2" => Ok(&ENCAPSULATED_CDA_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.104.3" => Ok(&ENCAPSULATED_STL_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.104.4" => Ok(&ENCAPSULATED_OBJ_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.104.5" => Ok(&ENCAPSULATED_MTL_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.128" => {
+        Ok(&POSITRON_EMISSION_TOMOGRAPHY_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.128.1" => {
+        Ok(&LEGACY_CONVERTED_ENHANCED_PET_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.129" => Ok(&STANDALONE_PET_CURVE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.130" => Ok(&ENHANCED_PET_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.131" => Ok(&BASIC_STRUCTURED_DISPLAY_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.200.1" => {
+        Ok(&CT_DEFINED_PROCEDURE_PROTOCOL_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.200.2" => {
+        Ok(&CT_PERFORMED_PROCEDURE_PROTOCOL_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.200.3" => Ok(&PROTOCOL_APPROVAL_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.200.7" => {
+        Ok(&XA_DEFINED_PROCEDURE_PROTOCOL_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.200.8" => {
+        Ok(&XA_PERFORMED_PROCEDURE_PROTOCOL_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.201.1" => Ok(&INVENTORY_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.1" => Ok(&RT_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.2" => Ok(&RT_DOSE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.3" => Ok(&RT_STRUCTURE_SET_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.4" => Ok(&RT_BEAMS_TREATMENT_RECORD_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.5" => Ok(&RT_PLAN_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.6" => {
+        Ok(&RT_BRACHY_TREATMENT_RECORD_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.7" => {
+        Ok(&RT_TREATMENT_SUMMARY_RECORD_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.8" => Ok(&RT_ION_PLAN_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.9" => {
+        Ok(&RT_ION_BEAMS_TREATMENT_RECORD_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.10" => Ok(&RT_PHYSICIAN_INTENT_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.11" => Ok(&RT_SEGMENT_ANNOTATION_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.12" => Ok(&RT_RADIATION_SET_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.13" => {
+        Ok(&C_ARM_PHOTON_ELECTRON_RADIATION_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.14" => {
+        Ok(&TOMOTHERAPEUTIC_RADIATION_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.15" => Ok(&ROBOTIC_ARM_RADIATION_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.16" => Ok(&RT_RADIATION_RECORD_SET_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.17" => {
+        Ok(&RT_RADIATION_SALVAGE_RECORD_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.18" => {
+        Ok(&TOMOTHERAPEUTIC_RADIATION_RECORD_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.19" => {
+        Ok(&C_ARM_PHOTON_ELECTRON_RADIATION_RECORD_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.20" => Ok(&ROBOTIC_RADIATION_RECORD_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.21" => {
+        Ok(&RT_RADIATION_SET_DELIVERY_INSTRUCTION_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.22" => Ok(&RT_TREATMENT_PREPARATION_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.23" => Ok(&ENHANCED_RT_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.481.24" => {
+        Ok(&ENHANCED_CONTINUOUS_RT_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.481.25" => {
+        Ok(&RT_PATIENT_POSITION_ACQUISITION_INSTRUCTION_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.501.1" => Ok(&DICOS_CT_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.501.2.1" => {
+        Ok(&DICOS_DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION)
+      }
+      "1.2.840.10008.5.1.4.1.1.501.2.2" => {
+        Ok(&DICOS_DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING)
+      }
+      "1.2.840.10008.5.1.4.1.1.501.3" => {
+        Ok(&DICOS_THREAT_DETECTION_REPORT_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.501.4" => Ok(&DICOS_2D_AIT_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.501.5" => Ok(&DICOS_3D_AIT_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.501.6" => {
+        Ok(&DICOS_QUADRUPOLE_RESONANCE_QR_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.1.1.601.1" => Ok(&EDDY_CURRENT_IMAGE_STORAGE),
+      "1.2.840.10008.5.1.4.1.1.601.2" => {
+        Ok(&EDDY_CURRENT_MULTI_FRAME_IMAGE_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.34.1" => {
+        Ok(&RT_BEAMS_DELIVERY_INSTRUCTION_STORAGE_TRIAL)
+      }
+      "1.2.840.10008.5.1.4.34.7" => Ok(&RT_BEAMS_DELIVERY_INSTRUCTION_STORAGE),
+      "1.2.840.10008.5.1.4.34.10" => {
+        Ok(&RT_BRACHY_APPLICATION_SETUP_DELIVERY_INSTRUCTION_STORAGE)
+      }
+      "1.2.840.10008.5.1.4.38.1" => Ok(&HANGING_PROTOCOL_STORAGE),
+      "1.2.840.10008.5.1.4.39.1" => Ok(&COLOR_PALETTE_STORAGE),
+      "1.2.840.10008.5.1.4.43.1" => Ok(&GENERIC_IMPLANT_TEMPLATE_STORAGE),
+      "1.2.840.10008.5.1.4.44.1" => Ok(&IMPLANT_ASSEMBLY_TEMPLATE_STORAGE),
+      "1.2.840.10008.5.1.4.45.1" => Ok(&IMPLANT_TEMPLATE_GROUP_STORAGE),
+
+      _ => Err(()),
+    }
+  }
+
+  /// Returns whether this SOP Class stores images.
+  ///
+  pub fn is_image_storage(&self) -> bool {
+    self.category == SopClassCategory::Image
+  }
+
+  /// Returns whether this SOP Class stores structured reports.
+  ///
+  pub fn is_structured_report(&self) -> bool {
+    self.category == SopClassCategory::StructuredReport
+  }
+
+  /// Returns whether this SOP Class stores presentation states.
+  ///
+  pub fn is_presentation_state(&self) -> bool {
+    self.category == SopClassCategory::PresentationState
+  }
+
+  /// Returns whether this SOP Class stores waveforms.
+  ///
+  pub fn is_waveform(&self) -> bool {
+    self.category == SopClassCategory::Waveform
+  }
+
+  /// Returns whether this SOP Class stores non-image radiotherapy objects.
+  ///
+  pub fn is_radiotherapy(&self) -> bool {
+    self.category == SopClassCategory::Radiotherapy
+  }
+
+  /// Returns whether this SOP Class stores encapsulated documents.
+  ///
+  pub fn is_encapsulated_document(&self) -> bool {
+    self.category == SopClassCategory::EncapsulatedDocument
+  }
+
+  /// Returns whether this SOP Class is one of the X-Ray Angiographic or X-Ray
+  /// Radiofluoroscopic image storage SOP Classes. Pixel data in these SOP
+  /// Classes has special handling of its Modality LUT.
+  ///
+  pub fn is_xa_xrf_image_storage(&self) -> bool {
+    self == &X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE
+      || self == &X_RAY_RADIOFLUOROSCOPIC_IMAGE_STORAGE
+      || self == &X_RAY_ANGIOGRAPHIC_BI_PLANE_IMAGE_STORAGE
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  pub fn from_uid_test() {
+    for sop_class in ALL.iter() {
+      assert_eq!(SopClass::from_uid(sop_class.uid), Ok(sop_class));
+    }
+
+    assert!(SopClass::from_uid("1.2.3.4").is_err());
+  }
+
+  #[test]
+  pub fn category_test() {
+    assert!(CT_IMAGE_STORAGE.is_image_storage());
+    assert!(RT_IMAGE_STORAGE.is_image_storage());
+    assert!(COMPREHENSIVE_SR_STORAGE.is_structured_report());
+    assert!(KEY_OBJECT_SELECTION_DOCUMENT_STORAGE.is_structured_report());
+    assert!(
+      GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE.is_presentation_state()
+    );
+    assert!(TWELVE_LEAD_ECG_WAVEFORM_STORAGE.is_waveform());
+    assert!(RT_DOSE_STORAGE.is_radiotherapy());
+    assert!(ENCAPSULATED_PDF_STORAGE.is_encapsulated_document());
+    assert_eq!(RAW_DATA_STORAGE.category, SopClassCategory::Other);
+
+    assert!(X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE.is_xa_xrf_image_storage());
+    assert!(!ENHANCED_XA_IMAGE_STORAGE.is_xa_xrf_image_storage());
+  }
+}
@@ -0,0 +1,4 @@
+//! Parsers for vendor-specific private data, each behind its own feature.
+
+#[cfg(feature = "vendor-siemens")]
+pub mod siemens_csa;
@@ -0,0 +1,479 @@
+//! Parses the Siemens CSA private header format, which is stored in the
+//! *'(0029,xx10) CSA Image Header Info'* and *'(0029,xx20) CSA Series Header
+//! Info'* private data elements of Siemens MR data. These headers hold values
+//! that aren't available elsewhere in the data set, such as diffusion b-values
+//! and gradient directions, and the slice timing of mosaic images.
+//!
+//! Both the original CSA1 format and the more common CSA2 format that starts
+//! with `SV10` are supported.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+use byteorder::ByteOrder;
+
+use crate::{DataElementTag, DataError, DataSet, DataSetPath};
+
+/// The private creator of the data elements that hold Siemens CSA headers.
+///
+pub const SIEMENS_CSA_HEADER_PRIVATE_CREATOR: &str = "SIEMENS CSA HEADER";
+
+/// The maximum number of elements, and items in an element, that are allowed
+/// in a CSA header. This guards against reading malformed data.
+///
+const MAX_CSA_ITEMS: u32 = 1000;
+
+/// The type of a Siemens CSA header, which determines the private data element
+/// that it is read from.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsaHeaderType {
+  /// The *'(0029,xx10) CSA Image Header Info'* data element.
+  Image,
+
+  /// The *'(0029,xx20) CSA Series Header Info'* data element.
+  Series,
+}
+
+impl CsaHeaderType {
+  /// Returns the offset of the private data element in the private block
+  /// reserved for [`SIEMENS_CSA_HEADER_PRIVATE_CREATOR`].
+  ///
+  fn private_offset(&self) -> u8 {
+    match self {
+      Self::Image => 0x10,
+      Self::Series => 0x20,
+    }
+  }
+}
+
+/// The values of an element in a Siemens CSA header. The type of the values is
+/// determined by the element's VR.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum CsaValues {
+  /// Values for the IS, SL, SS, UL, and US VRs.
+  Ints(Vec<i64>),
+
+  /// Values for the DS, FD, and FL VRs.
+  Floats(Vec<f64>),
+
+  /// Values for all other VRs.
+  Strings(Vec<String>),
+}
+
+/// A named element in a Siemens CSA header.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsaElement {
+  pub name: String,
+  pub vr: String,
+  pub vm: i32,
+  pub syngo_dt: i32,
+  pub values: CsaValues,
+}
+
+/// A Siemens CSA header, holding its elements in the order they're stored.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsaHeader {
+  pub elements: Vec<CsaElement>,
+}
+
+impl CsaHeader {
+  /// Reads a Siemens CSA header from a data set. Returns `None` if the data
+  /// set has no CSA header of the given type.
+  ///
+  pub fn from_data_set(
+    data_set: &DataSet,
+    header_type: CsaHeaderType,
+  ) -> Result<Option<Self>, DataError> {
+    let Some(block) = data_set
+      .find_private_creator_block(0x0029, SIEMENS_CSA_HEADER_PRIVATE_CREATOR)
+    else {
+      return Ok(None);
+    };
+
+    let tag =
+      DataElementTag::new_private(0x0029, block, header_type.private_offset());
+    if !data_set.has(tag) {
+      return Ok(None);
+    }
+
+    let bytes = data_set.get_value_bytes(tag)?;
+
+    Self::from_bytes(bytes)
+      .map(Some)
+      .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(tag)))
+  }
+
+  /// Reads a Siemens CSA header from its raw bytes.
+  ///
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataError> {
+    let mut reader = CsaReader { bytes, offset: 0 };
+
+    // CSA2 headers start with 'SV10' followed by four unused bytes
+    let is_csa2 = bytes.starts_with(b"SV10");
+    if is_csa2 {
+      reader.read(8)?;
+    }
+
+    let element_count = reader.read_u32()?;
+    if element_count == 0 || element_count > MAX_CSA_ITEMS {
+      return Err(DataError::new_value_invalid(format!(
+        "CSA header element count {element_count} is invalid"
+      )));
+    }
+
+    // Skip unused value
+    reader.read_u32()?;
+
+    let mut elements = Vec::with_capacity(element_count as usize);
+    let mut csa1_item_length_adjustment = 0;
+
+    for element_index in 0..element_count {
+      let name = null_terminated_string(reader.read(64)?);
+      let vm = reader.read_i32()?;
+      let vr = null_terminated_string(reader.read(4)?);
+      let syngo_dt = reader.read_i32()?;
+      let item_count = reader.read_i32()?;
+      reader.read_i32()?;
+
+      if !(0..=MAX_CSA_ITEMS as i32).contains(&item_count) {
+        return Err(DataError::new_value_invalid(format!(
+          "CSA header element '{name}' has invalid item count {item_count}"
+        )));
+      }
+
+      // CSA1 item lengths are offset by the item count of the second element.
+      // This matches the behavior of other CSA readers.
+      if element_index == 1 {
+        csa1_item_length_adjustment = item_count;
+      }
+
+      // The number of items holding values, which may be fewer than the
+      // number of items present
+      let mut value_count = if vm == 0 { item_count } else { vm };
+
+      let mut items = Vec::new();
+
+      for item_index in 0..item_count {
+        let item_header = reader.read(16)?;
+        let x0 = byteorder::LittleEndian::read_i32(&item_header[0..4]);
+        let x1 = byteorder::LittleEndian::read_i32(&item_header[4..8]);
+
+        let item_length = if is_csa2 {
+          x1
+        } else {
+          x0 - csa1_item_length_adjustment
+        };
+
+        if item_length < 0 || item_length as usize > reader.remaining() {
+          if is_csa2 {
+            return Err(DataError::new_value_invalid(format!(
+              "CSA header element '{name}' has an item that is too long"
+            )));
+          }
+
+          break;
+        }
+
+        let item_length = item_length as usize;
+        let padded_length = item_length.div_ceil(4) * 4;
+        let item = null_terminated_string(reader.read(item_length)?);
+
+        // Items are padded to a multiple of four bytes, but the padding may be
+        // absent on the final item
+        reader.read((padded_length - item_length).min(reader.remaining()))?;
+
+        if item_index >= value_count {
+          continue;
+        }
+
+        // An empty item marks the end of the values
+        if item.is_empty() {
+          value_count = item_index;
+          continue;
+        }
+
+        items.push(item);
+      }
+
+      let values = CsaValues::from_items(&vr, items).map_err(|_| {
+        DataError::new_value_invalid(format!(
+          "CSA header element '{name}' has an invalid {vr} value"
+        ))
+      })?;
+
+      elements.push(CsaElement {
+        name,
+        vr,
+        vm,
+        syngo_dt,
+        values,
+      });
+    }
+
+    Ok(Self { elements })
+  }
+
+  /// Returns the element with the given name.
+  ///
+  pub fn get(&self, name: &str) -> Option<&CsaElement> {
+    self.elements.iter().find(|element| element.name == name)
+  }
+
+  /// Returns the integer values of the element with the given name. Returns
+  /// `None` if the element isn't present, holds no values, or doesn't hold
+  /// integer values.
+  ///
+  pub fn get_ints(&self, name: &str) -> Option<&[i64]> {
+    match &self.get(name)?.values {
+      CsaValues::Ints(values) if !values.is_empty() => Some(values),
+      _ => None,
+    }
+  }
+
+  /// Returns the floating point values of the element with the given name.
+  /// Returns `None` if the element isn't present, holds no values, or doesn't
+  /// hold floating point values.
+  ///
+  pub fn get_floats(&self, name: &str) -> Option<&[f64]> {
+    match &self.get(name)?.values {
+      CsaValues::Floats(values) if !values.is_empty() => Some(values),
+      _ => None,
+    }
+  }
+
+  /// Returns the string values of the element with the given name. Returns
+  /// `None` if the element isn't present, holds no values, or doesn't hold
+  /// string values.
+  ///
+  pub fn get_strings(&self, name: &str) -> Option<&[String]> {
+    match &self.get(name)?.values {
+      CsaValues::Strings(values) if !values.is_empty() => Some(values),
+      _ => None,
+    }
+  }
+
+  /// Returns the diffusion b-value in s/mm², from the `B_value` element of an
+  /// image header.
+  ///
+  pub fn b_value(&self) -> Option<f64> {
+    match &self.get("B_value")?.values {
+      CsaValues::Ints(values) => values.first().map(|value| *value as f64),
+      CsaValues::Floats(values) => values.first().copied(),
+      CsaValues::Strings(_) => None,
+    }
+  }
+
+  /// Returns the diffusion gradient direction as a unit vector in the patient
+  /// coordinate system, from the `DiffusionGradientDirection` element of an
+  /// image header.
+  ///
+  pub fn diffusion_gradient_direction(&self) -> Option<[f64; 3]> {
+    self
+      .get_floats("DiffusionGradientDirection")?
+      .try_into()
+      .ok()
+  }
+
+  /// Returns the six unique values of the symmetric diffusion b-matrix, from
+  /// the `B_matrix` element of an image header. The values are in the order
+  /// `bxx, bxy, bxz, byy, byz, bzz`.
+  ///
+  pub fn b_matrix(&self) -> Option<[f64; 6]> {
+    self.get_floats("B_matrix")?.try_into().ok()
+  }
+
+  /// Returns the acquisition time of each slice in milliseconds relative to
+  /// the start of the acquisition, from the `MosaicRefAcqTimes` element of an
+  /// image header.
+  ///
+  pub fn slice_timing(&self) -> Option<&[f64]> {
+    self.get_floats("MosaicRefAcqTimes")
+  }
+
+  /// Returns the number of slices in a mosaic image, from the
+  /// `NumberOfImagesInMosaic` element of an image header.
+  ///
+  pub fn number_of_images_in_mosaic(&self) -> Option<i64> {
+    self
+      .get_ints("NumberOfImagesInMosaic")
+      .and_then(|values| values.first().copied())
+  }
+}
+
+impl CsaValues {
+  /// Converts the raw string items of a CSA element to typed values based on
+  /// its VR.
+  ///
+  fn from_items(vr: &str, items: Vec<String>) -> Result<Self, ()> {
+    match vr {
+      "IS" | "SL" | "SS" | "UL" | "US" => items
+        .iter()
+        .map(|item| item.trim().parse::<i64>().map_err(|_| ()))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Self::Ints),
+
+      "DS" | "FD" | "FL" => items
+        .iter()
+        .map(|item| item.trim().parse::<f64>().map_err(|_| ()))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Self::Floats),
+
+      _ => Ok(Self::Strings(items)),
+    }
+  }
+}
+
+/// Reads little endian values from the bytes of a CSA header.
+///
+struct CsaReader<'a> {
+  bytes: &'a [u8],
+  offset: usize,
+}
+
+impl<'a> CsaReader<'a> {
+  fn remaining(&self) -> usize {
+    self.bytes.len() - self.offset
+  }
+
+  fn read(&mut self, length: usize) -> Result<&'a [u8], DataError> {
+    if length > self.remaining() {
+      return Err(DataError::new_value_invalid(format!(
+        "CSA header is truncated at offset {}",
+        self.offset
+      )));
+    }
+
+    let bytes = &self.bytes[self.offset..self.offset + length];
+    self.offset += length;
+
+    Ok(bytes)
+  }
+
+  fn read_u32(&mut self) -> Result<u32, DataError> {
+    Ok(byteorder::LittleEndian::read_u32(self.read(4)?))
+  }
+
+  fn read_i32(&mut self) -> Result<i32, DataError> {
+    Ok(byteorder::LittleEndian::read_i32(self.read(4)?))
+  }
+}
+
+/// Converts bytes up to the first null byte to a string. CSA strings aren't
+/// guaranteed to be UTF-8, so they are decoded as Latin-1.
+///
+fn null_terminated_string(bytes: &[u8]) -> String {
+  bytes
+    .iter()
+    .take_while(|b| **b != 0)
+    .map(|b| char::from(*b))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::DataElementValue;
+
+  /// Encodes a CSA2 header containing the given elements, each with a name,
+  /// VR, and string items.
+  ///
+  fn csa2_bytes(elements: &[(&str, &str, &[&str])]) -> Vec<u8> {
+    let mut bytes = b"SV10\x04\x03\x02\x01".to_vec();
+    bytes.extend_from_slice(&(elements.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&77u32.to_le_bytes());
+
+    for (name, vr, items) in elements {
+      let mut name_bytes = name.as_bytes().to_vec();
+      name_bytes.resize(64, 0);
+      bytes.extend_from_slice(&name_bytes);
+
+      bytes.extend_from_slice(&(items.len() as i32).to_le_bytes());
+
+      let mut vr_bytes = vr.as_bytes().to_vec();
+      vr_bytes.resize(4, 0);
+      bytes.extend_from_slice(&vr_bytes);
+
+      bytes.extend_from_slice(&0i32.to_le_bytes());
+      bytes.extend_from_slice(&(items.len() as i32).to_le_bytes());
+      bytes.extend_from_slice(&77i32.to_le_bytes());
+
+      for item in items.iter() {
+        let item_length = item.len() as i32 + 1;
+        for x in [item_length, item_length, 77, item_length] {
+          bytes.extend_from_slice(&x.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(item.as_bytes());
+        bytes.push(0);
+        bytes.resize(bytes.len().div_ceil(4) * 4, 0);
+      }
+    }
+
+    bytes
+  }
+
+  #[test]
+  fn from_bytes_test() {
+    let bytes = csa2_bytes(&[
+      ("B_value", "IS", &["1000"]),
+      ("DiffusionGradientDirection", "FD", &["0.5", "-0.5", "0.6"]),
+      ("MosaicRefAcqTimes", "FD", &["0.0", "52.5", "105.0"]),
+      ("NumberOfImagesInMosaic", "US", &["3"]),
+      ("ImaCoilString", "LO", &["HE1-4"]),
+    ]);
+
+    let header = CsaHeader::from_bytes(&bytes).unwrap();
+
+    assert_eq!(header.elements.len(), 5);
+    assert_eq!(header.b_value(), Some(1000.0));
+    assert_eq!(
+      header.diffusion_gradient_direction(),
+      Some([0.5, -0.5, 0.6])
+    );
+    assert_eq!(header.b_matrix(), None);
+    assert_eq!(header.slice_timing(), Some(&[0.0, 52.5, 105.0][..]));
+    assert_eq!(header.number_of_images_in_mosaic(), Some(3));
+    assert_eq!(
+      header.get_strings("ImaCoilString"),
+      Some(&["HE1-4".to_string()][..])
+    );
+    assert_eq!(header.get("Missing"), None);
+
+    assert!(CsaHeader::from_bytes(&bytes[0..100]).is_err());
+  }
+
+  #[test]
+  fn from_data_set_test() {
+    let bytes = csa2_bytes(&[("B_value", "IS", &["500"])]);
+
+    let mut data_set = DataSet::new();
+    assert_eq!(
+      CsaHeader::from_data_set(&data_set, CsaHeaderType::Image),
+      Ok(None)
+    );
+
+    data_set
+      .insert_private_value(
+        0x0029,
+        SIEMENS_CSA_HEADER_PRIVATE_CREATOR,
+        0x10,
+        DataElementValue::new_other_byte_string(bytes).unwrap(),
+      )
+      .unwrap();
+
+    let header = CsaHeader::from_data_set(&data_set, CsaHeaderType::Image)
+      .unwrap()
+      .unwrap();
+    assert_eq!(header.b_value(), Some(500.0));
+
+    assert_eq!(
+      CsaHeader::from_data_set(&data_set, CsaHeaderType::Series),
+      Ok(None)
+    );
+  }
+}
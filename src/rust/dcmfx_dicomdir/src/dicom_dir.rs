@@ -0,0 +1,919 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+  collections::BTreeMap,
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use std::{collections::BTreeMap, path::Path};
+
+use sha2::{Digest, Sha256};
+
+use dcmfx_core::{
+  DataElementTag, DataElementValue, DataError, DataSet, DataSetPath,
+  RcByteSlice, SopClass, dictionary,
+  sop_class::{KEY_OBJECT_SELECTION_DOCUMENT_STORAGE, SopClassCategory},
+  transfer_syntax,
+};
+use dcmfx_p10::{
+  DataSetBuilder, DataSetP10Extensions, IoRead, IoWrite, P10Error,
+  P10ReadContext, P10Token, P10WriteConfig,
+};
+
+use crate::{DicomDirError, DirectoryRecord, DirectoryRecordType};
+
+/// The UID of the 'Media Storage Directory Storage' SOP Class, which is the
+/// SOP Class of all DICOMDIR files.
+///
+pub const MEDIA_STORAGE_DIRECTORY_STORAGE_UID: &str = "1.2.840.10008.1.3.10";
+
+/// A DICOMDIR, which indexes the DICOM P10 files in a File-set using a
+/// hierarchy of directory records. The root directory entity usually holds
+/// patient records, which reference study records, which reference series
+/// records, which reference records for the individual files in the File-set.
+///
+/// Ref: PS3.3 F.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DicomDir {
+  /// The *'(0004,1130) File-set ID'*.
+  pub file_set_id: Option<String>,
+
+  /// The *'(0002,0003) Media Storage SOP Instance UID'*. When this isn't set,
+  /// a UID derived from the DICOMDIR's content is used when it's written.
+  pub sop_instance_uid: Option<String>,
+
+  /// The directory records in the root directory entity.
+  pub records: Vec<DirectoryRecord>,
+}
+
+impl DicomDir {
+  /// Creates a new empty DICOMDIR.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Reads a DICOMDIR from a DICOM P10 file.
+  ///
+  #[cfg(feature = "std")]
+  pub fn read_p10_file<P: AsRef<Path>>(
+    filename: P,
+  ) -> Result<Self, DicomDirError> {
+    match std::fs::File::open(filename) {
+      Ok(mut file) => Self::read_p10_stream(&mut file),
+
+      Err(e) => Err(DicomDirError::P10Error(P10Error::FileError {
+        when: "Opening file".into(),
+        details: e.to_string().into(),
+      })),
+    }
+  }
+
+  /// Reads a DICOMDIR from a stream of DICOM P10 data.
+  ///
+  /// The directory hierarchy is reconstructed by following the record offsets
+  /// stored in the DICOMDIR, starting at the first record of the root
+  /// directory entity. Records that are flagged as not in use are skipped.
+  ///
+  pub fn read_p10_stream<S: IoRead>(
+    stream: &mut S,
+  ) -> Result<Self, DicomDirError> {
+    let (data_set, item_offsets) =
+      read_data_set_and_item_offsets(&mut |context| {
+        dcmfx_p10::read_tokens_from_stream(stream, context, None)
+      })?;
+
+    Self::from_data_set(&data_set, &item_offsets)
+  }
+
+  /// Returns the total number of directory records in this DICOMDIR.
+  ///
+  pub fn record_count(&self) -> usize {
+    self
+      .records
+      .iter()
+      .map(|record| record.record_count())
+      .sum()
+  }
+
+  /// Adds directory records for a DICOM P10 file in the File-set, creating the
+  /// patient, study, and series records that it belongs to if they aren't
+  /// already present.
+  ///
+  /// Patients are matched on *'(0010,0020) Patient ID'*, studies on
+  /// *'(0020,000D) Study Instance UID'*, and series on *'(0020,000E) Series
+  /// Instance UID'*. If the series already has a record for the same SOP
+  /// Instance UID then that record is replaced.
+  ///
+  /// The referenced file ID is the path of the file relative to the directory
+  /// containing the DICOMDIR, and each of its components must be a valid
+  /// *'(0004,1500) Referenced File ID'* value.
+  ///
+  pub fn add_file(
+    &mut self,
+    referenced_file_id: &[&str],
+    data_set: &DataSet,
+  ) -> Result<(), DicomDirError> {
+    let instance_record = instance_record(referenced_file_id, data_set)?;
+
+    let patient_id = data_set
+      .get_string(dictionary::PATIENT_ID.tag)
+      .unwrap_or_default();
+    let study_instance_uid =
+      data_set.get_string(dictionary::STUDY_INSTANCE_UID.tag)?;
+    let series_instance_uid =
+      data_set.get_string(dictionary::SERIES_INSTANCE_UID.tag)?;
+
+    let patient = find_or_insert_record(
+      &mut self.records,
+      dictionary::PATIENT_ID.tag,
+      patient_id,
+      || {
+        new_record(
+          DirectoryRecordType::Patient,
+          data_set,
+          &[&dictionary::PATIENT_NAME, &dictionary::PATIENT_ID],
+        )
+      },
+    );
+
+    let study = find_or_insert_record(
+      &mut patient.children,
+      dictionary::STUDY_INSTANCE_UID.tag,
+      study_instance_uid,
+      || {
+        new_record(
+          DirectoryRecordType::Study,
+          data_set,
+          &[
+            &dictionary::STUDY_DATE,
+            &dictionary::STUDY_TIME,
+            &dictionary::ACCESSION_NUMBER,
+            &dictionary::STUDY_DESCRIPTION,
+            &dictionary::STUDY_INSTANCE_UID,
+            &dictionary::STUDY_ID,
+          ],
+        )
+      },
+    );
+
+    let series = find_or_insert_record(
+      &mut study.children,
+      dictionary::SERIES_INSTANCE_UID.tag,
+      series_instance_uid,
+      || {
+        new_record(
+          DirectoryRecordType::Series,
+          data_set,
+          &[
+            &dictionary::MODALITY,
+            &dictionary::SERIES_DESCRIPTION,
+            &dictionary::SERIES_INSTANCE_UID,
+            &dictionary::SERIES_NUMBER,
+          ],
+        )
+      },
+    );
+
+    match series.children.iter_mut().find(|record| {
+      record.referenced_sop_instance_uid()
+        == instance_record.referenced_sop_instance_uid()
+    }) {
+      Some(record) => *record = instance_record,
+      None => series.children.push(instance_record),
+    }
+
+    Ok(())
+  }
+
+  /// Converts this DICOMDIR to a data set that includes its File Meta
+  /// Information. The directory record offsets in the returned data set are
+  /// only correct when it is written using the same write config.
+  ///
+  pub fn to_data_set(
+    &self,
+    config: Option<P10WriteConfig>,
+  ) -> Result<DataSet, DicomDirError> {
+    // Flatten the directory hierarchy into the items of the Directory Record
+    // Sequence, recording the next and lower-level record for each item
+    let mut items = vec![];
+    let (first_record, last_record) =
+      flatten_records(&self.records, &mut items);
+
+    // Write the DICOMDIR once with all offsets set to zero in order to find
+    // the offset of each item. The offsets are fixed-size values so filling
+    // them in doesn't alter the offsets of the items.
+    let data_set = self.build_data_set(
+      &items,
+      first_record,
+      last_record,
+      &vec![0; items.len()],
+    )?;
+
+    let mut bytes = vec![];
+    data_set.to_p10_bytes(
+      &mut |data: RcByteSlice| {
+        bytes.extend_from_slice(&data);
+        Ok(())
+      },
+      config.clone(),
+    )?;
+
+    let mut bytes = Some(RcByteSlice::from(bytes));
+    let (_, item_offsets) = read_data_set_and_item_offsets(&mut |context| {
+      if let Some(bytes) = bytes.take() {
+        context.write_bytes(bytes, true)?;
+      }
+
+      context.read_tokens()
+    })?;
+
+    let item_offsets = item_offsets
+      .into_iter()
+      .map(|offset| {
+        u32::try_from(offset).map_err(|_| {
+          DataError::new_value_invalid(format!(
+            "Directory record offset {offset} exceeds the maximum of 2^32 - 1"
+          ))
+        })
+      })
+      .collect::<Result<Vec<u32>, DataError>>()?;
+
+    Ok(self.build_data_set(&items, first_record, last_record, &item_offsets)?)
+  }
+
+  /// Writes this DICOMDIR to a DICOM P10 file. This will overwrite any
+  /// existing file with the given name.
+  ///
+  #[cfg(feature = "std")]
+  pub fn write_p10_file<P: AsRef<Path>>(
+    &self,
+    filename: P,
+    config: Option<P10WriteConfig>,
+  ) -> Result<(), DicomDirError> {
+    let data_set = self.to_data_set(config.clone())?;
+
+    data_set
+      .write_p10_file(filename, config)
+      .map_err(DicomDirError::P10Error)
+  }
+
+  /// Writes this DICOMDIR as DICOM P10 data to a write stream.
+  ///
+  pub fn write_p10_stream<S: IoWrite>(
+    &self,
+    stream: &mut S,
+    config: Option<P10WriteConfig>,
+  ) -> Result<(), DicomDirError> {
+    let data_set = self.to_data_set(config.clone())?;
+
+    data_set
+      .write_p10_stream(stream, config)
+      .map_err(DicomDirError::P10Error)
+  }
+
+  fn from_data_set(
+    data_set: &DataSet,
+    item_offsets: &[u64],
+  ) -> Result<Self, DicomDirError> {
+    let file_set_id = data_set
+      .get_string(dictionary::FILE_SET_ID.tag)
+      .ok()
+      .filter(|s| !s.is_empty())
+      .map(|s| s.to_string());
+
+    let sop_instance_uid = data_set
+      .get_string(dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID.tag)
+      .ok()
+      .map(|s| s.to_string());
+
+    let items =
+      data_set.get_sequence_items(dictionary::DIRECTORY_RECORD_SEQUENCE.tag)?;
+
+    let first_record_offset = data_set.get_int_with_default::<u32>(
+      dictionary::OFFSET_OF_THE_FIRST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY
+        .tag,
+      0,
+    )?;
+
+    let mut item_indexes = BTreeMap::new();
+    for (index, offset) in item_offsets.iter().enumerate() {
+      if let Ok(offset) = u32::try_from(*offset) {
+        item_indexes.insert(offset, index);
+      }
+    }
+
+    let mut visited = vec![false; items.len()];
+    let records = read_directory_entity(
+      first_record_offset,
+      items,
+      &item_indexes,
+      &mut visited,
+    )?;
+
+    Ok(Self {
+      file_set_id,
+      sop_instance_uid,
+      records,
+    })
+  }
+
+  fn build_data_set(
+    &self,
+    items: &[FlattenedRecord],
+    first_record: Option<usize>,
+    last_record: Option<usize>,
+    item_offsets: &[u32],
+  ) -> Result<DataSet, DataError> {
+    let offset_of =
+      |index: Option<usize>| index.map(|i| item_offsets[i]).unwrap_or(0);
+
+    let mut data_set = DataSet::new();
+
+    data_set.insert_string_value(
+      &dictionary::MEDIA_STORAGE_SOP_CLASS_UID,
+      &[MEDIA_STORAGE_DIRECTORY_STORAGE_UID],
+    )?;
+    data_set.insert_string_value(
+      &dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID,
+      &[&self.sop_instance_uid()],
+    )?;
+    data_set.insert_string_value(
+      &dictionary::TRANSFER_SYNTAX_UID,
+      &[transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN.uid],
+    )?;
+
+    data_set.insert_string_value(
+      &dictionary::FILE_SET_ID,
+      &[self.file_set_id.as_deref().unwrap_or("")],
+    )?;
+    data_set.insert_int_value(
+      &dictionary::OFFSET_OF_THE_FIRST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+      &[offset_of(first_record).into()],
+    )?;
+    data_set.insert_int_value(
+      &dictionary::OFFSET_OF_THE_LAST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+      &[offset_of(last_record).into()],
+    )?;
+    data_set
+      .insert_int_value(&dictionary::FILE_SET_CONSISTENCY_FLAG, &[0x0000])?;
+
+    let mut sequence_items = Vec::with_capacity(items.len());
+    for item in items {
+      let mut item_data_set = item.record.data_set.clone();
+
+      item_data_set.insert_int_value(
+        &dictionary::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD,
+        &[offset_of(item.next).into()],
+      )?;
+      item_data_set
+        .insert_int_value(&dictionary::RECORD_IN_USE_FLAG, &[0xFFFF])?;
+      item_data_set.insert_int_value(
+        &dictionary::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+        &[offset_of(item.lower).into()],
+      )?;
+      item_data_set.insert_string_value(
+        &dictionary::DIRECTORY_RECORD_TYPE,
+        &[item.record.record_type.defined_term()],
+      )?;
+
+      sequence_items.push(item_data_set);
+    }
+
+    data_set.insert_sequence_value(
+      &dictionary::DIRECTORY_RECORD_SEQUENCE,
+      sequence_items,
+    )?;
+
+    Ok(data_set)
+  }
+
+  /// Returns the SOP Instance UID to use for this DICOMDIR. If one isn't set
+  /// then a UID is derived from the referenced SOP Instance UIDs.
+  ///
+  fn sop_instance_uid(&self) -> String {
+    if let Some(uid) = &self.sop_instance_uid {
+      return uid.clone();
+    }
+
+    fn hash_records(records: &[DirectoryRecord], hasher: &mut Sha256) {
+      for record in records {
+        hasher.update(record.record_type.defined_term().as_bytes());
+        if let Some(uid) = record.referenced_sop_instance_uid() {
+          hasher.update(uid.as_bytes());
+        }
+        hash_records(&record.children, hasher);
+      }
+    }
+
+    let mut hasher = Sha256::new();
+    hash_records(&self.records, &mut hasher);
+    let digest = hasher.finalize();
+
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[0..16]);
+
+    format!("2.25.{}", u128::from_be_bytes(bytes))
+  }
+}
+
+/// A directory record in the flattened form stored in the Directory Record
+/// Sequence, with the item indexes of its next and lower-level records.
+///
+struct FlattenedRecord<'a> {
+  record: &'a DirectoryRecord,
+  next: Option<usize>,
+  lower: Option<usize>,
+}
+
+/// Appends the records of a directory entity and all their descendants to the
+/// flattened list of records. Returns the indexes of the first and last records
+/// in the directory entity.
+///
+fn flatten_records<'a>(
+  records: &'a [DirectoryRecord],
+  items: &mut Vec<FlattenedRecord<'a>>,
+) -> (Option<usize>, Option<usize>) {
+  let mut first = None;
+  let mut previous: Option<usize> = None;
+
+  for record in records {
+    let index = items.len();
+    items.push(FlattenedRecord {
+      record,
+      next: None,
+      lower: None,
+    });
+
+    match previous {
+      Some(previous) => items[previous].next = Some(index),
+      None => first = Some(index),
+    }
+
+    items[index].lower = flatten_records(&record.children, items).0;
+
+    previous = Some(index);
+  }
+
+  (first, previous)
+}
+
+/// Reads a DICOMDIR data set using the passed function to read tokens, and
+/// returns it along with the byte offset of each item in its *'(0004,1220)
+/// Directory Record Sequence'*.
+///
+fn read_data_set_and_item_offsets(
+  read_tokens: &mut dyn FnMut(
+    &mut P10ReadContext,
+  ) -> Result<Vec<P10Token>, P10Error>,
+) -> Result<(DataSet, Vec<u64>), DicomDirError> {
+  let mut context = P10ReadContext::new(None);
+  let mut builder = DataSetBuilder::new();
+
+  let mut item_offsets = vec![];
+  let mut sequence_depth = 0usize;
+  let mut in_directory_record_sequence = false;
+
+  loop {
+    let tokens = read_tokens(&mut context)?;
+
+    for token in tokens.iter() {
+      match token {
+        P10Token::SequenceStart { tag, .. } => {
+          if sequence_depth == 0
+            && *tag == dictionary::DIRECTORY_RECORD_SEQUENCE.tag
+          {
+            in_directory_record_sequence = true;
+          }
+
+          sequence_depth += 1;
+        }
+
+        P10Token::SequenceDelimiter { .. } => {
+          sequence_depth = sequence_depth.saturating_sub(1);
+          if sequence_depth == 0 {
+            in_directory_record_sequence = false;
+          }
+        }
+
+        // Item headers are returned on their own by the read context, so the
+        // last data element header read is the one for this item
+        P10Token::SequenceItemStart { .. }
+          if in_directory_record_sequence && sequence_depth == 1 =>
+        {
+          item_offsets.push(context.last_data_element_header().1);
+        }
+
+        _ => (),
+      }
+
+      builder.add_token(token)?;
+    }
+
+    if let Ok(data_set) = builder.final_data_set() {
+      return Ok((data_set, item_offsets));
+    }
+  }
+}
+
+/// Reads the directory records in the directory entity whose first record is
+/// at the given offset, along with all their lower-level directory entities.
+///
+fn read_directory_entity(
+  mut offset: u32,
+  items: &[DataSet],
+  item_indexes: &BTreeMap<u32, usize>,
+  visited: &mut [bool],
+) -> Result<Vec<DirectoryRecord>, DicomDirError> {
+  let mut records = vec![];
+
+  while offset != 0 {
+    let index = match item_indexes.get(&offset) {
+      Some(index) if !visited[*index] => *index,
+      _ => return Err(DicomDirError::RecordOffsetInvalid { offset }),
+    };
+
+    visited[index] = true;
+
+    let item = &items[index];
+
+    let next_offset = item.get_int_with_default::<u32>(
+      dictionary::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD.tag,
+      0,
+    )?;
+    let lower_offset = item.get_int_with_default::<u32>(
+      dictionary::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY.tag,
+      0,
+    )?;
+    let in_use = item.get_int_with_default::<u16>(
+      dictionary::RECORD_IN_USE_FLAG.tag,
+      0xFFFF,
+    )?;
+
+    if in_use != 0 {
+      let record_type = DirectoryRecordType::from_defined_term(
+        item.get_string(dictionary::DIRECTORY_RECORD_TYPE.tag)?,
+      );
+
+      let mut data_set = item.clone();
+      for tag in [
+        dictionary::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD.tag,
+        dictionary::RECORD_IN_USE_FLAG.tag,
+        dictionary::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY.tag,
+        dictionary::DIRECTORY_RECORD_TYPE.tag,
+      ] {
+        data_set.delete(tag);
+      }
+
+      let children =
+        read_directory_entity(lower_offset, items, item_indexes, visited)?;
+
+      records.push(DirectoryRecord {
+        record_type,
+        data_set,
+        children,
+      });
+    }
+
+    offset = next_offset;
+  }
+
+  Ok(records)
+}
+
+/// Returns the record in the list whose value for the given tag matches the
+/// given value, appending a new record if there is no match.
+///
+fn find_or_insert_record<'a>(
+  records: &'a mut Vec<DirectoryRecord>,
+  tag: DataElementTag,
+  value: &str,
+  new_record: impl FnOnce() -> DirectoryRecord,
+) -> &'a mut DirectoryRecord {
+  let index = match records.iter().position(|record| {
+    record.data_set.get_string(tag).unwrap_or_default() == value
+  }) {
+    Some(index) => index,
+    None => {
+      records.push(new_record());
+      records.len() - 1
+    }
+  };
+
+  &mut records[index]
+}
+
+/// Creates a new directory record with the given key data elements copied from
+/// a data set. Keys missing from the data set are given an empty value.
+///
+fn new_record(
+  record_type: DirectoryRecordType,
+  data_set: &DataSet,
+  keys: &[&dictionary::Item],
+) -> DirectoryRecord {
+  let mut record = DirectoryRecord::new(record_type);
+
+  for key in keys {
+    let value = match data_set.get_value(key.tag) {
+      Ok(value) => value.clone(),
+      Err(_) => {
+        DataElementValue::new_binary_unchecked(key.vrs[0], RcByteSlice::empty())
+      }
+    };
+
+    record.data_set.insert(key.tag, value);
+  }
+
+  // String values are held as UTF-8, so records with non-ASCII values need to
+  // specify their character set
+  let has_non_ascii_values = record.data_set.iter().any(|(_, value)| {
+    value
+      .bytes()
+      .map(|bytes| !bytes.is_ascii())
+      .unwrap_or(false)
+  });
+
+  if has_non_ascii_values {
+    record
+      .data_set
+      .insert_string_value(&dictionary::SPECIFIC_CHARACTER_SET, &["ISO_IR 192"])
+      .unwrap();
+  }
+
+  record
+}
+
+/// Creates the lowest-level directory record for a file in the File-set, which
+/// references the file and the SOP Instance it holds.
+///
+fn instance_record(
+  referenced_file_id: &[&str],
+  data_set: &DataSet,
+) -> Result<DirectoryRecord, DicomDirError> {
+  let sop_class_uid = data_set
+    .get_string(dictionary::SOP_CLASS_UID.tag)
+    .or_else(|_| {
+      data_set.get_string(dictionary::MEDIA_STORAGE_SOP_CLASS_UID.tag)
+    })?;
+  let sop_instance_uid = data_set
+    .get_string(dictionary::SOP_INSTANCE_UID.tag)
+    .or_else(|_| {
+      data_set.get_string(dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID.tag)
+    })?;
+  let transfer_syntax_uid = data_set
+    .get_transfer_syntax()
+    .map(|transfer_syntax| transfer_syntax.uid)
+    .unwrap_or(transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN.uid);
+
+  let mut record = new_record(
+    record_type_for_sop_class(sop_class_uid),
+    data_set,
+    &[&dictionary::INSTANCE_NUMBER],
+  );
+
+  validate_referenced_file_id(referenced_file_id).map_err(|e| {
+    e.with_path(&DataSetPath::new_with_data_element(
+      dictionary::REFERENCED_FILE_ID.tag,
+    ))
+  })?;
+
+  record
+    .data_set
+    .insert_string_value(&dictionary::REFERENCED_FILE_ID, referenced_file_id)?;
+  record.data_set.insert_string_value(
+    &dictionary::REFERENCED_SOP_CLASS_UID_IN_FILE,
+    &[sop_class_uid],
+  )?;
+  record.data_set.insert_string_value(
+    &dictionary::REFERENCED_SOP_INSTANCE_UID_IN_FILE,
+    &[sop_instance_uid],
+  )?;
+  record.data_set.insert_string_value(
+    &dictionary::REFERENCED_TRANSFER_SYNTAX_UID_IN_FILE,
+    &[transfer_syntax_uid],
+  )?;
+
+  Ok(record)
+}
+
+/// Checks that a referenced file ID conforms to the restrictions on File IDs,
+/// which allow at most eight components that each have between one and eight
+/// uppercase letters, digits, or underscores.
+///
+/// Ref: PS3.10 8.2.
+///
+fn validate_referenced_file_id(
+  referenced_file_id: &[&str],
+) -> Result<(), DataError> {
+  if referenced_file_id.is_empty() || referenced_file_id.len() > 8 {
+    return Err(DataError::new_value_invalid(format!(
+      "File ID has {} components but must have between 1 and 8",
+      referenced_file_id.len()
+    )));
+  }
+
+  for component in referenced_file_id {
+    let is_valid = !component.is_empty()
+      && component.len() <= 8
+      && component
+        .bytes()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == b'_');
+
+    if !is_valid {
+      return Err(DataError::new_value_invalid(format!(
+        "File ID component '{component}' is invalid"
+      )));
+    }
+  }
+
+  Ok(())
+}
+
+/// Returns the directory record type to use for a file based on the category
+/// of its SOP Class. Files with unrecognized SOP Classes use image records.
+///
+fn record_type_for_sop_class(sop_class_uid: &str) -> DirectoryRecordType {
+  let Ok(sop_class) = SopClass::from_uid(sop_class_uid) else {
+    return DirectoryRecordType::Image;
+  };
+
+  if sop_class == &KEY_OBJECT_SELECTION_DOCUMENT_STORAGE {
+    return DirectoryRecordType::KeyObjectDoc;
+  }
+
+  match sop_class.category {
+    SopClassCategory::StructuredReport => DirectoryRecordType::SrDocument,
+    SopClassCategory::PresentationState => DirectoryRecordType::Presentation,
+    SopClassCategory::Waveform => DirectoryRecordType::Waveform,
+    SopClassCategory::EncapsulatedDocument => DirectoryRecordType::EncapDoc,
+    _ => DirectoryRecordType::Image,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn instance_data_set(
+    patient_id: &str,
+    study_instance_uid: &str,
+    series_instance_uid: &str,
+    sop_instance_uid: &str,
+  ) -> DataSet {
+    let mut data_set = DataSet::new();
+
+    data_set
+      .insert_string_value(
+        &dictionary::TRANSFER_SYNTAX_UID,
+        &[transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN.uid],
+      )
+      .unwrap();
+    data_set
+      .insert_string_value(
+        &dictionary::SOP_CLASS_UID,
+        &["1.2.840.10008.5.1.4.1.1.4"],
+      )
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &[sop_instance_uid])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &[patient_id])
+      .unwrap();
+    data_set
+      .insert_string_value(
+        &dictionary::STUDY_INSTANCE_UID,
+        &[study_instance_uid],
+      )
+      .unwrap();
+    data_set
+      .insert_string_value(
+        &dictionary::SERIES_INSTANCE_UID,
+        &[series_instance_uid],
+      )
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::MODALITY, &["MR"])
+      .unwrap();
+
+    data_set
+  }
+
+  #[test]
+  fn add_file_test() {
+    let mut dicom_dir = DicomDir::new();
+
+    dicom_dir
+      .add_file(
+        &["A", "1"],
+        &instance_data_set("P1", "1.1", "1.1.1", "1.1.1.1"),
+      )
+      .unwrap();
+    dicom_dir
+      .add_file(
+        &["A", "2"],
+        &instance_data_set("P1", "1.1", "1.1.1", "1.1.1.2"),
+      )
+      .unwrap();
+    dicom_dir
+      .add_file(
+        &["A", "3"],
+        &instance_data_set("P1", "1.1", "1.1.2", "1.1.2.1"),
+      )
+      .unwrap();
+    dicom_dir
+      .add_file(
+        &["B", "1"],
+        &instance_data_set("P2", "2.1", "2.1.1", "2.1.1.1"),
+      )
+      .unwrap();
+
+    assert_eq!(dicom_dir.records.len(), 2);
+    assert_eq!(dicom_dir.records[0].children.len(), 1);
+    assert_eq!(dicom_dir.records[0].children[0].children.len(), 2);
+    assert_eq!(dicom_dir.record_count(), 11);
+
+    let image = &dicom_dir.records[0].children[0].children[0].children[1];
+    assert_eq!(image.record_type, DirectoryRecordType::Image);
+    assert_eq!(image.referenced_file_id(), Some(vec!["A", "2"]));
+    assert_eq!(image.referenced_sop_instance_uid(), Some("1.1.1.2"));
+
+    assert!(
+      dicom_dir
+        .add_file(&["lowercase"], &instance_data_set("P1", "1", "1", "1"))
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn round_trip_test() {
+    let mut dicom_dir = DicomDir::new();
+    dicom_dir.file_set_id = Some("TEST".to_string());
+
+    dicom_dir
+      .add_file(
+        &["A", "1"],
+        &instance_data_set("P1", "1.1", "1.1.1", "1.1.1.1"),
+      )
+      .unwrap();
+    dicom_dir
+      .add_file(
+        &["A", "2"],
+        &instance_data_set("P1", "1.1", "1.1.2", "1.1.2.1"),
+      )
+      .unwrap();
+    dicom_dir
+      .add_file(
+        &["B", "1"],
+        &instance_data_set("P2", "2.1", "2.1.1", "2.1.1.1"),
+      )
+      .unwrap();
+
+    let mut bytes = vec![];
+    dicom_dir.write_p10_stream(&mut bytes, None).unwrap();
+
+    let mut read_dicom_dir =
+      DicomDir::read_p10_stream(&mut bytes.as_slice()).unwrap();
+
+    assert!(
+      read_dicom_dir
+        .sop_instance_uid
+        .take()
+        .unwrap()
+        .starts_with("2.25.")
+    );
+    assert_eq!(read_dicom_dir, dicom_dir);
+  }
+
+  #[test]
+  fn record_in_use_flag_test() {
+    let mut dicom_dir = DicomDir::new();
+    dicom_dir
+      .add_file(&["A"], &instance_data_set("P1", "1.1", "1.1.1", "1.1.1.1"))
+      .unwrap();
+
+    let mut data_set = dicom_dir.to_data_set(None).unwrap();
+    let mut items = data_set
+      .get_sequence_items(dictionary::DIRECTORY_RECORD_SEQUENCE.tag)
+      .unwrap()
+      .to_vec();
+    items[3]
+      .insert_int_value(&dictionary::RECORD_IN_USE_FLAG, &[0])
+      .unwrap();
+    data_set
+      .insert_sequence_value(&dictionary::DIRECTORY_RECORD_SEQUENCE, items)
+      .unwrap();
+
+    let mut bytes = vec![];
+    data_set.write_p10_stream(&mut bytes, None).unwrap();
+
+    let read_dicom_dir =
+      DicomDir::read_p10_stream(&mut bytes.as_slice()).unwrap();
+
+    assert_eq!(read_dicom_dir.record_count(), 3);
+  }
+}
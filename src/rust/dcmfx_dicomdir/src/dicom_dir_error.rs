@@ -0,0 +1,70 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{DataError, DcmfxError};
+use dcmfx_p10::P10Error;
+
+/// An error that occurred when reading or writing a DICOMDIR.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum DicomDirError {
+  /// An error that occurred when reading or writing DICOM P10 data.
+  P10Error(P10Error),
+
+  /// An error that occurred when reading or creating the DICOMDIR's data
+  /// elements.
+  DataError(DataError),
+
+  /// A directory record offset doesn't point to the start of an item in the
+  /// *'(0004,1220) Directory Record Sequence'*, or it points to a record that
+  /// has already been visited.
+  RecordOffsetInvalid { offset: u32 },
+}
+
+impl core::fmt::Display for DicomDirError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::P10Error(e) => e.fmt(f),
+      Self::DataError(e) => e.fmt(f),
+
+      Self::RecordOffsetInvalid { offset } => write!(
+        f,
+        "Directory record offset {offset} does not reference a valid \
+         directory record"
+      ),
+    }
+  }
+}
+
+impl DcmfxError for DicomDirError {
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::P10Error(e) => e.to_lines(task_description),
+      Self::DataError(e) => e.to_lines(task_description),
+
+      Self::RecordOffsetInvalid { offset } => vec![
+        format!("DICOMDIR error {task_description}"),
+        "".to_string(),
+        "  Error: Directory record offset invalid".to_string(),
+        format!("  Offset: {offset}"),
+      ],
+    }
+  }
+}
+
+impl From<P10Error> for DicomDirError {
+  fn from(e: P10Error) -> Self {
+    Self::P10Error(e)
+  }
+}
+
+impl From<DataError> for DicomDirError {
+  fn from(e: DataError) -> Self {
+    Self::DataError(e)
+  }
+}
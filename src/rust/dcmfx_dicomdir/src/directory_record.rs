@@ -0,0 +1,134 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{DataSet, dictionary};
+
+/// The type of a directory record, as specified by its *'(0004,1430) Directory
+/// Record Type'* data element.
+///
+/// Ref: PS3.3 F.5.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DirectoryRecordType {
+  Patient,
+  Study,
+  Series,
+  Image,
+  SrDocument,
+  KeyObjectDoc,
+  Presentation,
+  Waveform,
+  EncapDoc,
+
+  /// Any other directory record type, holding its defined term.
+  Other(String),
+}
+
+impl DirectoryRecordType {
+  /// Returns the directory record type for a *'(0004,1430) Directory Record
+  /// Type'* defined term.
+  ///
+  pub fn from_defined_term(value: &str) -> Self {
+    match value {
+      "PATIENT" => Self::Patient,
+      "STUDY" => Self::Study,
+      "SERIES" => Self::Series,
+      "IMAGE" => Self::Image,
+      "SR DOCUMENT" => Self::SrDocument,
+      "KEY OBJECT DOC" => Self::KeyObjectDoc,
+      "PRESENTATION" => Self::Presentation,
+      "WAVEFORM" => Self::Waveform,
+      "ENCAP DOC" => Self::EncapDoc,
+      _ => Self::Other(value.to_string()),
+    }
+  }
+
+  /// Returns the *'(0004,1430) Directory Record Type'* defined term for this
+  /// directory record type.
+  ///
+  pub fn defined_term(&self) -> &str {
+    match self {
+      Self::Patient => "PATIENT",
+      Self::Study => "STUDY",
+      Self::Series => "SERIES",
+      Self::Image => "IMAGE",
+      Self::SrDocument => "SR DOCUMENT",
+      Self::KeyObjectDoc => "KEY OBJECT DOC",
+      Self::Presentation => "PRESENTATION",
+      Self::Waveform => "WAVEFORM",
+      Self::EncapDoc => "ENCAP DOC",
+      Self::Other(value) => value,
+    }
+  }
+}
+
+impl core::fmt::Display for DirectoryRecordType {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(f, "{}", self.defined_term())
+  }
+}
+
+/// A single record in a DICOMDIR's directory hierarchy, along with the records
+/// in the lower-level directory entity that it references, e.g. the study
+/// records for a patient record.
+///
+/// The record's data set holds its key data elements, including those that
+/// reference a file in the File-set. The data elements that link records
+/// together are not included as they're derived from the hierarchy when the
+/// DICOMDIR is written.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirectoryRecord {
+  pub record_type: DirectoryRecordType,
+  pub data_set: DataSet,
+  pub children: Vec<DirectoryRecord>,
+}
+
+impl DirectoryRecord {
+  /// Creates a new directory record of the given type with no key data
+  /// elements or children.
+  ///
+  pub fn new(record_type: DirectoryRecordType) -> Self {
+    Self {
+      record_type,
+      data_set: DataSet::new(),
+      children: vec![],
+    }
+  }
+
+  /// Returns the components of the *'(0004,1500) Referenced File ID'* data
+  /// element, which is the path of the referenced file relative to the
+  /// DICOMDIR's directory.
+  ///
+  pub fn referenced_file_id(&self) -> Option<Vec<&str>> {
+    self
+      .data_set
+      .get_strings(dictionary::REFERENCED_FILE_ID.tag)
+      .ok()
+  }
+
+  /// Returns the *'(0004,1511) Referenced SOP Instance UID in File'* data
+  /// element.
+  ///
+  pub fn referenced_sop_instance_uid(&self) -> Option<&str> {
+    self
+      .data_set
+      .get_string(dictionary::REFERENCED_SOP_INSTANCE_UID_IN_FILE.tag)
+      .ok()
+  }
+
+  /// Returns the number of directory records in the tree rooted at this
+  /// record, including this record.
+  ///
+  pub fn record_count(&self) -> usize {
+    1 + self
+      .children
+      .iter()
+      .map(|child| child.record_count())
+      .sum::<usize>()
+  }
+}
@@ -0,0 +1,690 @@
+//! An in-memory index of DICOM instances held in many files, organized into a
+//! patient, study, series, and instance hierarchy that can be queried using
+//! attribute matching. This is the core of a lightweight local archive.
+//!
+//! Only the data elements listed in [`InstanceIndex::TAGS`] are held in the
+//! index, so files can be added to it using a partial read.
+//!
+//! Ref: PS3.4 C.2.2.2.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  collections::BTreeMap,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use dcmfx_core::{
+  DataElementTag, DataElementValue, DataSet, ValueRepresentation, dictionary,
+};
+
+use crate::DicomDirError;
+
+/// A level of the patient, study, series, and instance hierarchy.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryLevel {
+  Patient,
+  Study,
+  Series,
+  Instance,
+}
+
+impl QueryLevel {
+  /// Returns the data elements held in the index for entities at this level.
+  ///
+  pub fn tags(&self) -> &'static [DataElementTag] {
+    match self {
+      Self::Patient => &InstanceIndex::<()>::TAGS[0..4],
+      Self::Study => &InstanceIndex::<()>::TAGS[4..11],
+      Self::Series => &InstanceIndex::<()>::TAGS[11..16],
+      Self::Instance => &InstanceIndex::<()>::TAGS[16..19],
+    }
+  }
+
+  /// Returns the level below this one, if there is one.
+  ///
+  fn child(&self) -> Option<Self> {
+    match self {
+      Self::Patient => Some(Self::Study),
+      Self::Study => Some(Self::Series),
+      Self::Series => Some(Self::Instance),
+      Self::Instance => None,
+    }
+  }
+}
+
+/// An in-memory index of DICOM instances. Each instance is associated with a
+/// file of type `F`, e.g. a path or an open file handle, that is returned by
+/// queries.
+///
+/// Patients are keyed by *'(0010,0020) Patient ID'*, studies by *'(0020,000D)
+/// Study Instance UID'*, series by *'(0020,000E) Series Instance UID'*, and
+/// instances by *'(0008,0018) SOP Instance UID'*. The attributes of a patient,
+/// study, or series are taken from the first instance added to it.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceIndex<F> {
+  patients: BTreeMap<String, IndexEntity<F>>,
+}
+
+/// A patient, study, series, or instance in an [`InstanceIndex`]. Only
+/// instances have a file.
+///
+#[derive(Clone, Debug, PartialEq)]
+struct IndexEntity<F> {
+  attributes: DataSet,
+  children: BTreeMap<String, IndexEntity<F>>,
+  file: Option<F>,
+}
+
+/// An entity that matched a query on an [`InstanceIndex`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryMatch<'a, F> {
+  /// The indexed attributes of the matched entity and all the entities above
+  /// it in the hierarchy.
+  ///
+  pub attributes: DataSet,
+
+  /// The files of all the instances in or below the matched entity.
+  ///
+  pub files: Vec<&'a F>,
+}
+
+impl<F> Default for InstanceIndex<F> {
+  fn default() -> Self {
+    Self {
+      patients: BTreeMap::new(),
+    }
+  }
+}
+
+impl<F> InstanceIndex<F> {
+  /// The data elements held in the index, which are the only data elements
+  /// that need to be read from files being added to it.
+  ///
+  pub const TAGS: [DataElementTag; 19] = [
+    // Patient level
+    dictionary::PATIENT_NAME.tag,
+    dictionary::PATIENT_ID.tag,
+    dictionary::PATIENT_BIRTH_DATE.tag,
+    dictionary::PATIENT_SEX.tag,
+    // Study level
+    dictionary::STUDY_DATE.tag,
+    dictionary::STUDY_TIME.tag,
+    dictionary::ACCESSION_NUMBER.tag,
+    dictionary::REFERRING_PHYSICIAN_NAME.tag,
+    dictionary::STUDY_DESCRIPTION.tag,
+    dictionary::STUDY_INSTANCE_UID.tag,
+    dictionary::STUDY_ID.tag,
+    // Series level
+    dictionary::MODALITY.tag,
+    dictionary::SERIES_DESCRIPTION.tag,
+    dictionary::BODY_PART_EXAMINED.tag,
+    dictionary::SERIES_INSTANCE_UID.tag,
+    dictionary::SERIES_NUMBER.tag,
+    // Instance level
+    dictionary::SOP_CLASS_UID.tag,
+    dictionary::SOP_INSTANCE_UID.tag,
+    dictionary::INSTANCE_NUMBER.tag,
+  ];
+
+  /// Creates a new empty index.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds an instance to the index, creating the patient, study, and series
+  /// it belongs to if they aren't already present. If an instance with the
+  /// same SOP Instance UID is already in the series then it is replaced.
+  ///
+  /// The data set must contain the Study Instance UID, Series Instance UID,
+  /// and SOP Instance UID. A missing Patient ID is treated as empty.
+  ///
+  pub fn add_data_set(
+    &mut self,
+    file: F,
+    data_set: &DataSet,
+  ) -> Result<(), DicomDirError> {
+    let patient_id = data_set
+      .get_string(dictionary::PATIENT_ID.tag)
+      .unwrap_or_default();
+    let study_instance_uid =
+      data_set.get_string(dictionary::STUDY_INSTANCE_UID.tag)?;
+    let series_instance_uid =
+      data_set.get_string(dictionary::SERIES_INSTANCE_UID.tag)?;
+    let sop_instance_uid =
+      data_set.get_string(dictionary::SOP_INSTANCE_UID.tag)?;
+
+    let patient = self
+      .patients
+      .entry(patient_id.to_string())
+      .or_insert_with(|| IndexEntity::new(QueryLevel::Patient, data_set));
+
+    let study = patient
+      .children
+      .entry(study_instance_uid.to_string())
+      .or_insert_with(|| IndexEntity::new(QueryLevel::Study, data_set));
+
+    let series = study
+      .children
+      .entry(series_instance_uid.to_string())
+      .or_insert_with(|| IndexEntity::new(QueryLevel::Series, data_set));
+
+    let mut instance = IndexEntity::new(QueryLevel::Instance, data_set);
+    instance.file = Some(file);
+
+    series
+      .children
+      .insert(sop_instance_uid.to_string(), instance);
+
+    Ok(())
+  }
+
+  /// Removes the instance with the given SOP Instance UID from the index, and
+  /// returns its file. Patients, studies, and series left with no instances
+  /// are also removed.
+  ///
+  pub fn remove_instance(&mut self, sop_instance_uid: &str) -> Option<F> {
+    let file = self
+      .patients
+      .values_mut()
+      .flat_map(|patient| patient.children.values_mut())
+      .flat_map(|study| study.children.values_mut())
+      .find_map(|series| series.children.remove(sop_instance_uid))?
+      .file;
+
+    self.patients.retain(|_, patient| {
+      patient.children.retain(|_, study| {
+        study
+          .children
+          .retain(|_, series| !series.children.is_empty());
+        !study.children.is_empty()
+      });
+
+      !patient.children.is_empty()
+    });
+
+    file
+  }
+
+  /// Returns the number of entities in the index at the given level.
+  ///
+  pub fn count(&self, level: QueryLevel) -> usize {
+    fn count_entities<F>(
+      entities: &BTreeMap<String, IndexEntity<F>>,
+      entity_level: QueryLevel,
+      level: QueryLevel,
+    ) -> usize {
+      match entity_level.child() {
+        Some(child_level) if entity_level != level => entities
+          .values()
+          .map(|entity| count_entities(&entity.children, child_level, level))
+          .sum(),
+        _ => entities.len(),
+      }
+    }
+
+    count_entities(&self.patients, QueryLevel::Patient, level)
+  }
+
+  /// Returns the entities at the given level that match the query keys.
+  ///
+  /// Each query key is matched against the indexed data element with the same
+  /// tag, following the rules for C-FIND attribute matching:
+  ///
+  /// 1. An empty key matches any value, including a missing value.
+  /// 2. A *'UI'* key with multiple values matches any of its UIDs.
+  /// 3. A *'DA'*, *'TM'*, or *'DT'* key containing '-' is a range, and
+  ///    matches values between its start and end, either of which can be
+  ///    omitted.
+  /// 4. A key containing '*' or '?' is a wildcard, where '*' matches any
+  ///    sequence of characters and '?' matches a single character.
+  /// 5. Otherwise the key must equal the value exactly.
+  ///
+  /// Leading and trailing spaces are ignored. Keys for data elements that
+  /// aren't in the index, or that are below the query level, are ignored.
+  ///
+  /// Matches are returned in order of their keys at each level of the
+  /// hierarchy, i.e. by Patient ID, then Study Instance UID, and so on.
+  ///
+  pub fn query(
+    &self,
+    level: QueryLevel,
+    keys: &DataSet,
+  ) -> Vec<QueryMatch<'_, F>> {
+    let mut matches = vec![];
+
+    query_entities(
+      &self.patients,
+      QueryLevel::Patient,
+      level,
+      keys,
+      &DataSet::new(),
+      &mut matches,
+    );
+
+    matches
+  }
+}
+
+#[cfg(feature = "std")]
+impl InstanceIndex<PathBuf> {
+  /// Reads the data elements in [`Self::TAGS`] from a DICOM P10 file and adds
+  /// it to the index. See [`Self::add_data_set()`] for details.
+  ///
+  pub fn add_file<P: AsRef<Path>>(
+    &mut self,
+    path: P,
+  ) -> Result<(), DicomDirError> {
+    let data_set = dcmfx_p10::read_file_partial(
+      path.as_ref(),
+      &Self::TAGS,
+      Some(dcmfx_p10::P10ReadConfig::default().require_dicm_prefix(true)),
+    )?;
+
+    self.add_data_set(path.as_ref().to_path_buf(), &data_set)
+  }
+}
+
+impl<F> IndexEntity<F> {
+  /// Creates a new entity at the given level with its attributes copied from
+  /// a data set.
+  ///
+  fn new(level: QueryLevel, data_set: &DataSet) -> Self {
+    let mut attributes = DataSet::new();
+
+    for tag in level.tags() {
+      if let Ok(value) = data_set.get_value(*tag) {
+        attributes.insert(*tag, value.clone());
+      }
+    }
+
+    Self {
+      attributes,
+      children: BTreeMap::new(),
+      file: None,
+    }
+  }
+
+  /// Returns whether this entity's attributes match the query keys for its
+  /// level.
+  ///
+  fn matches(&self, level: QueryLevel, keys: &DataSet) -> bool {
+    keys
+      .iter()
+      .filter(|(tag, _)| level.tags().contains(*tag))
+      .all(|(tag, key)| {
+        attribute_matches(key, self.attributes.get_value(*tag).ok())
+      })
+  }
+
+  /// Returns the files of all the instances in or below this entity.
+  ///
+  fn files(&self) -> Vec<&F> {
+    match &self.file {
+      Some(file) => vec![file],
+      None => self
+        .children
+        .values()
+        .flat_map(|child| child.files())
+        .collect(),
+    }
+  }
+}
+
+/// Adds the entities that match the query keys to the list of matches,
+/// descending through the hierarchy until the query level is reached.
+///
+fn query_entities<'a, F>(
+  entities: &'a BTreeMap<String, IndexEntity<F>>,
+  entity_level: QueryLevel,
+  query_level: QueryLevel,
+  keys: &DataSet,
+  parent_attributes: &DataSet,
+  matches: &mut Vec<QueryMatch<'a, F>>,
+) {
+  for entity in entities.values() {
+    if !entity.matches(entity_level, keys) {
+      continue;
+    }
+
+    let mut attributes = parent_attributes.clone();
+    for (tag, value) in entity.attributes.iter() {
+      attributes.insert(*tag, value.clone());
+    }
+
+    match entity_level.child() {
+      Some(child_level) if entity_level != query_level => query_entities(
+        &entity.children,
+        child_level,
+        query_level,
+        keys,
+        &attributes,
+        matches,
+      ),
+
+      _ => matches.push(QueryMatch {
+        attributes,
+        files: entity.files(),
+      }),
+    }
+  }
+}
+
+/// Returns whether an indexed value matches a query key. See
+/// [`InstanceIndex::query()`] for details.
+///
+fn attribute_matches(
+  key: &DataElementValue,
+  value: Option<&DataElementValue>,
+) -> bool {
+  let Some(key_string) = trimmed_string(key) else {
+    return true;
+  };
+
+  if key_string.is_empty() {
+    return true;
+  }
+
+  let Some(value_string) = value.and_then(trimmed_string) else {
+    return false;
+  };
+
+  let vr = key.value_representation();
+
+  match vr {
+    ValueRepresentation::UniqueIdentifier => {
+      key_string.split('\\').any(|uid| uid.trim() == value_string)
+    }
+
+    ValueRepresentation::Date
+    | ValueRepresentation::Time
+    | ValueRepresentation::DateTime
+      if key_string.contains('-') =>
+    {
+      let (start, end) = key_string.split_once('-').unwrap();
+
+      // The end of the range is compared only to the same precision as it's
+      // specified, e.g. an end time of "1030" includes "103059"
+      (start.is_empty() || value_string >= start)
+        && (end.is_empty()
+          || value_string.get(..end.len()).unwrap_or(value_string) <= end)
+    }
+
+    _ if key_string.contains(['*', '?']) => {
+      let pattern: Vec<char> = key_string.chars().collect();
+
+      value_string.split('\\').any(|value| {
+        wildcard_matches(&pattern, &value.trim().chars().collect::<Vec<_>>())
+      })
+    }
+
+    _ => value_string
+      .split('\\')
+      .any(|value| value.trim() == key_string),
+  }
+}
+
+/// Returns a data element value as a string with leading and trailing spaces
+/// and nulls removed. Returns `None` if the value isn't a valid string.
+///
+fn trimmed_string(value: &DataElementValue) -> Option<&str> {
+  let bytes = value.bytes().ok()?;
+
+  core::str::from_utf8(bytes)
+    .ok()
+    .map(|s| s.trim_matches([' ', '\0']))
+}
+
+/// Returns whether a value matches a pattern in which '*' matches any sequence
+/// of characters, and '?' matches any single character.
+///
+fn wildcard_matches(pattern: &[char], value: &[char]) -> bool {
+  let (mut p, mut v) = (0, 0);
+
+  // The position in the pattern after the most recent '*', and the position in
+  // the value that it's currently matched up to
+  let mut backtrack: Option<(usize, usize)> = None;
+
+  while v < value.len() {
+    if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+      p += 1;
+      v += 1;
+    } else if p < pattern.len() && pattern[p] == '*' {
+      p += 1;
+      backtrack = Some((p, v));
+    } else if let Some((star_p, star_v)) = backtrack {
+      p = star_p;
+      v = star_v + 1;
+      backtrack = Some((star_p, v));
+    } else {
+      return false;
+    }
+  }
+
+  pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::{string::String, vec};
+
+  use super::*;
+
+  fn insert_value(
+    data_set: &mut DataSet,
+    item: &dictionary::Item,
+    value: &str,
+  ) {
+    data_set.insert(
+      item.tag,
+      DataElementValue::new_binary_unchecked(
+        item.vrs[0],
+        value.as_bytes().to_vec().into(),
+      ),
+    );
+  }
+
+  fn instance_data_set(
+    patient: (&str, &str),
+    study: (&str, &str),
+    series: (&str, &str),
+    sop_instance_uid: &str,
+  ) -> DataSet {
+    let mut data_set = DataSet::new();
+
+    for (item, value) in [
+      (&dictionary::PATIENT_NAME, patient.0),
+      (&dictionary::PATIENT_ID, patient.1),
+      (&dictionary::STUDY_INSTANCE_UID, study.0),
+      (&dictionary::STUDY_DATE, study.1),
+      (&dictionary::SERIES_INSTANCE_UID, series.0),
+      (&dictionary::MODALITY, series.1),
+      (&dictionary::SOP_INSTANCE_UID, sop_instance_uid),
+    ] {
+      insert_value(&mut data_set, item, value);
+    }
+
+    data_set
+  }
+
+  fn index() -> InstanceIndex<String> {
+    let mut index = InstanceIndex::new();
+
+    for (file, data_set) in [
+      (
+        "a1",
+        instance_data_set(
+          ("DOE^JOHN", "1"),
+          ("1.1", "20240105"),
+          ("1.1.1", "CT"),
+          "1.1.1.1",
+        ),
+      ),
+      (
+        "a2",
+        instance_data_set(
+          ("DOE^JOHN", "1"),
+          ("1.1", "20240105"),
+          ("1.1.1", "CT"),
+          "1.1.1.2",
+        ),
+      ),
+      (
+        "a3",
+        instance_data_set(
+          ("DOE^JOHN", "1"),
+          ("1.2", "20240320"),
+          ("1.2.1", "MR"),
+          "1.2.1.1",
+        ),
+      ),
+      (
+        "b1",
+        instance_data_set(
+          ("ROE^JANE", "2"),
+          ("2.1", "20231211"),
+          ("2.1.1", "CT"),
+          "2.1.1.1",
+        ),
+      ),
+    ] {
+      index.add_data_set(file.to_string(), &data_set).unwrap();
+    }
+
+    index
+  }
+
+  fn query_files(
+    index: &InstanceIndex<String>,
+    level: QueryLevel,
+    keys: &[(&dictionary::Item, &str)],
+  ) -> Vec<Vec<String>> {
+    let mut key_data_set = DataSet::new();
+    for (item, value) in keys {
+      insert_value(&mut key_data_set, item, value);
+    }
+
+    index
+      .query(level, &key_data_set)
+      .into_iter()
+      .map(|m| m.files.into_iter().cloned().collect())
+      .collect()
+  }
+
+  #[test]
+  fn count_test() {
+    let index = index();
+
+    assert_eq!(index.count(QueryLevel::Patient), 2);
+    assert_eq!(index.count(QueryLevel::Study), 3);
+    assert_eq!(index.count(QueryLevel::Series), 3);
+    assert_eq!(index.count(QueryLevel::Instance), 4);
+  }
+
+  #[test]
+  fn query_test() {
+    let index = index();
+
+    assert_eq!(
+      query_files(&index, QueryLevel::Patient, &[]),
+      vec![vec!["a1", "a2", "a3"], vec!["b1"]]
+    );
+
+    assert_eq!(
+      query_files(
+        &index,
+        QueryLevel::Patient,
+        &[(&dictionary::PATIENT_NAME, "DOE*")]
+      ),
+      vec![vec!["a1", "a2", "a3"]]
+    );
+
+    assert_eq!(
+      query_files(
+        &index,
+        QueryLevel::Study,
+        &[(&dictionary::STUDY_DATE, "20240101-")]
+      ),
+      vec![vec!["a1", "a2"], vec!["a3"]]
+    );
+
+    assert_eq!(
+      query_files(
+        &index,
+        QueryLevel::Series,
+        &[
+          (&dictionary::PATIENT_NAME, "?OE^J*"),
+          (&dictionary::STUDY_DATE, "-20240131"),
+          (&dictionary::MODALITY, "CT"),
+        ]
+      ),
+      vec![vec!["a1", "a2"], vec!["b1"]]
+    );
+
+    // Keys below the query level are ignored
+    assert_eq!(
+      query_files(
+        &index,
+        QueryLevel::Patient,
+        &[(&dictionary::MODALITY, "MR")]
+      ),
+      vec![vec!["a1", "a2", "a3"], vec!["b1"]]
+    );
+
+    let mut keys = DataSet::new();
+    insert_value(&mut keys, &dictionary::SOP_INSTANCE_UID, "1.1.1.2\\2.1.1.1");
+
+    let matches = index.query(QueryLevel::Instance, &keys);
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0].files, vec!["a2"]);
+    assert_eq!(
+      matches[0]
+        .attributes
+        .get_string(dictionary::PATIENT_NAME.tag),
+      Ok("DOE^JOHN")
+    );
+    assert_eq!(matches[1].files, vec!["b1"]);
+  }
+
+  #[test]
+  fn remove_instance_test() {
+    let mut index = index();
+
+    assert_eq!(index.remove_instance("1.2.1.1"), Some("a3".to_string()));
+    assert_eq!(index.remove_instance("1.2.1.1"), None);
+    assert_eq!(index.count(QueryLevel::Study), 2);
+
+    assert_eq!(index.remove_instance("2.1.1.1"), Some("b1".to_string()));
+    assert_eq!(index.count(QueryLevel::Patient), 1);
+    assert_eq!(index.count(QueryLevel::Instance), 2);
+  }
+
+  #[test]
+  fn wildcard_matches_test() {
+    let matches = |pattern: &str, value: &str| {
+      wildcard_matches(
+        &pattern.chars().collect::<Vec<_>>(),
+        &value.chars().collect::<Vec<_>>(),
+      )
+    };
+
+    assert!(matches("*", ""));
+    assert!(matches("A*C", "ABBC"));
+    assert!(matches("A?C*", "ABC"));
+    assert!(matches("*B*B", "ABAB"));
+    assert!(!matches("A?C", "AC"));
+    assert!(!matches("A*D", "ABC"));
+  }
+}
@@ -0,0 +1,24 @@
+//! Reads and writes DICOMDIR files, which index the DICOM P10 files in a
+//! File-set using a hierarchy of patient, study, series, and instance
+//! directory records.
+//!
+//! Ref: PS3.3 F, PS3.10 8.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+mod dicom_dir;
+mod dicom_dir_error;
+mod directory_record;
+mod instance_index;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+mod study_exporter;
+
+pub use dicom_dir::{DicomDir, MEDIA_STORAGE_DIRECTORY_STORAGE_UID};
+pub use dicom_dir_error::DicomDirError;
+pub use directory_record::{DirectoryRecord, DirectoryRecordType};
+pub use instance_index::{InstanceIndex, QueryLevel, QueryMatch};
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub use study_exporter::{StudyExportError, StudyExportTarget, StudyExporter};
@@ -0,0 +1,694 @@
+//! Exports a set of data sets as a File-set of DICOM P10 files indexed by a
+//! DICOMDIR.
+
+use std::{
+  fs::File,
+  io::{BufWriter, Write},
+  path::{Path, PathBuf},
+};
+
+use dcmfx_anonymize::{
+  AnonymizationProfile, DataSetAnonymizeExtensions, UidRemapper,
+};
+use dcmfx_core::{DataError, DataSet, DcmfxError, dictionary};
+use dcmfx_p10::{DataSetP10Extensions, P10Error, P10WriteConfig};
+
+use crate::{DicomDir, DicomDirError};
+
+/// The name of the directory in a File-set that holds its DICOM P10 files.
+///
+const FILES_DIRECTORY: &str = "DICOM";
+
+/// The name of the file in the staging directory that holds the UID map of the
+/// export's UID remapper, which is used to resume an interrupted export.
+///
+const UID_MAP_FILENAME: &str = "uid_map.csv";
+
+/// Where a [`StudyExporter`] writes its File-set.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum StudyExportTarget {
+  /// A directory that is created to hold the File-set. It must not already
+  /// exist.
+  Directory(PathBuf),
+
+  /// A ZIP archive that is created to hold the File-set. Its entries are
+  /// stored uncompressed, and ZIP64 is used when the archive is too large for
+  /// the original ZIP format. Any existing file is overwritten.
+  Zip(PathBuf),
+}
+
+impl StudyExportTarget {
+  /// Returns the path of this export target.
+  ///
+  pub fn path(&self) -> &Path {
+    match self {
+      Self::Directory(path) | Self::Zip(path) => path,
+    }
+  }
+
+  /// Returns the staging directory that the File-set is written into before
+  /// being moved to this export target. It is a sibling of the target with a
+  /// `.partial` suffix.
+  ///
+  pub fn staging_directory(&self) -> PathBuf {
+    let mut path = self.path().as_os_str().to_os_string();
+    path.push(".partial");
+
+    PathBuf::from(path)
+  }
+}
+
+/// Exports a set of data sets as a File-set made up of DICOM P10 files and a
+/// DICOMDIR that indexes them.
+///
+/// Anonymization and UID remapping are optional, and are applied consistently
+/// across all data sets so that the references between them remain intact.
+///
+/// The export is transactional: the File-set is written into a staging
+/// directory, verified by reading back the DICOMDIR and every DICOM P10 file,
+/// and only then moved to the export target. If an export is interrupted then
+/// the staging directory is left in place, and exporting the same data sets
+/// again resumes from it, reusing the DICOM P10 files already written. The
+/// UID remapper's map is kept in the staging directory so that resumed
+/// exports use the same UIDs, and it isn't included in the exported File-set.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StudyExporter {
+  anonymization_profile: Option<AnonymizationProfile>,
+  uid_remapper: Option<UidRemapper>,
+  file_set_id: Option<String>,
+  write_config: Option<P10WriteConfig>,
+}
+
+/// An error that occurred when exporting a File-set with a [`StudyExporter`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum StudyExportError {
+  /// An error that occurred when reading or writing DICOM P10 data, or when
+  /// accessing the file system.
+  P10Error(P10Error),
+
+  /// An error that occurred when reading data from a data set.
+  DataError(DataError),
+
+  /// An error that occurred when creating the DICOMDIR.
+  DicomDirError(DicomDirError),
+
+  /// The export target already exists.
+  TargetExists { path: PathBuf },
+
+  /// The exported File-set didn't match what was expected when it was read
+  /// back.
+  VerificationFailed { path: PathBuf, details: String },
+}
+
+impl core::fmt::Display for StudyExportError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::P10Error(e) => e.fmt(f),
+      Self::DataError(e) => e.fmt(f),
+      Self::DicomDirError(e) => e.fmt(f),
+
+      Self::TargetExists { path } => {
+        write!(f, "Export target \"{}\" already exists", path.display())
+      }
+
+      Self::VerificationFailed { path, details } => write!(
+        f,
+        "Verification of \"{}\" failed, details: {details}",
+        path.display()
+      ),
+    }
+  }
+}
+
+impl DcmfxError for StudyExportError {
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::P10Error(e) => e.to_lines(task_description),
+      Self::DataError(e) => e.to_lines(task_description),
+      Self::DicomDirError(e) => e.to_lines(task_description),
+
+      Self::TargetExists { path } => vec![
+        format!("Study export error {task_description}"),
+        "".to_string(),
+        "  Error: Export target already exists".to_string(),
+        format!("  Path: {}", path.display()),
+      ],
+
+      Self::VerificationFailed { path, details } => vec![
+        format!("Study export error {task_description}"),
+        "".to_string(),
+        "  Error: Verification failed".to_string(),
+        format!("  Path: {}", path.display()),
+        format!("  Details: {details}"),
+      ],
+    }
+  }
+}
+
+impl From<P10Error> for StudyExportError {
+  fn from(e: P10Error) -> Self {
+    Self::P10Error(e)
+  }
+}
+
+impl From<DataError> for StudyExportError {
+  fn from(e: DataError) -> Self {
+    Self::DataError(e)
+  }
+}
+
+impl From<DicomDirError> for StudyExportError {
+  fn from(e: DicomDirError) -> Self {
+    Self::DicomDirError(e)
+  }
+}
+
+impl StudyExporter {
+  /// Creates a new study exporter that exports data sets unaltered.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// The anonymization profile to apply to each data set.
+  ///
+  /// By default no anonymization is applied.
+  ///
+  pub fn anonymization_profile(mut self, value: AnonymizationProfile) -> Self {
+    self.anonymization_profile = Some(value);
+    self
+  }
+
+  /// The UID remapper used to replace the UIDs in each data set. The same
+  /// remapper is used for all data sets, and it can be retrieved after the
+  /// export with [`Self::get_uid_remapper()`] in order to save its map.
+  ///
+  /// If there is also an anonymization profile then the UIDs that it replaces
+  /// are remapped using this remapper, rather than being generated using the
+  /// profile's salt.
+  ///
+  /// By default UIDs aren't remapped.
+  ///
+  pub fn uid_remapper(mut self, value: UidRemapper) -> Self {
+    self.uid_remapper = Some(value);
+    self
+  }
+
+  /// The File-set ID to store in the DICOMDIR.
+  ///
+  /// By default there is no File-set ID.
+  ///
+  pub fn file_set_id(mut self, value: String) -> Self {
+    self.file_set_id = Some(value);
+    self
+  }
+
+  /// The config used when writing the DICOM P10 files and the DICOMDIR.
+  ///
+  /// By default [`P10WriteConfig::default()`] is used.
+  ///
+  pub fn write_config(mut self, value: P10WriteConfig) -> Self {
+    self.write_config = Some(value);
+    self
+  }
+
+  /// Returns the UID remapper used by this exporter, if there is one.
+  ///
+  pub fn get_uid_remapper(&self) -> Option<&UidRemapper> {
+    self.uid_remapper.as_ref()
+  }
+
+  /// Exports the given data sets to a File-set at the export target, and
+  /// returns the number of DICOM P10 files in it.
+  ///
+  /// The DICOM P10 files are named `DICOM/00000001`, `DICOM/00000002`, etc.
+  /// in the order of the passed data sets, and the DICOMDIR is at the root of
+  /// the File-set.
+  ///
+  pub fn export(
+    &mut self,
+    data_sets: &[DataSet],
+    target: &StudyExportTarget,
+  ) -> Result<usize, StudyExportError> {
+    let target_path = target.path();
+    if target_path.exists() && matches!(target, StudyExportTarget::Directory(_))
+    {
+      return Err(StudyExportError::TargetExists {
+        path: target_path.to_path_buf(),
+      });
+    }
+
+    let staging_directory = target.staging_directory();
+    let files_directory = staging_directory.join(FILES_DIRECTORY);
+    create_dir_all(&files_directory)?;
+
+    // If resuming an interrupted export then continue with its UID map
+    let uid_map_path = staging_directory.join(UID_MAP_FILENAME);
+    if let Some(uid_remapper) = self.uid_remapper.as_mut()
+      && uid_map_path.exists()
+    {
+      uid_remapper.import_map(&read_to_string(&uid_map_path)?)?;
+    }
+
+    // Alter all the data sets prior to writing any of them, so that the UID
+    // map is complete and saved before any file that uses it is written
+    let data_sets = data_sets
+      .iter()
+      .map(|data_set| self.prepare_data_set(data_set))
+      .collect::<Vec<_>>();
+
+    if let Some(uid_remapper) = self.uid_remapper.as_ref() {
+      write_atomically(&uid_map_path, uid_remapper.export_map().as_bytes())?;
+    }
+
+    let mut dicom_dir = DicomDir::new();
+    dicom_dir.file_set_id = self.file_set_id.clone();
+
+    for (i, data_set) in data_sets.iter().enumerate() {
+      let filename = format!("{:08}", i + 1);
+      let path = files_directory.join(&filename);
+
+      // Files left by an interrupted export are reused if they are valid
+      if verify_p10_file(&path, data_set).is_err() {
+        let temp_path = path.with_extension("tmp");
+
+        data_set.write_p10_file(&temp_path, self.write_config.clone())?;
+        rename(&temp_path, &path)?;
+      }
+
+      dicom_dir.add_file(&[FILES_DIRECTORY, &filename], data_set)?;
+    }
+
+    let dicom_dir_path = staging_directory.join("DICOMDIR");
+    dicom_dir.write_p10_file(&dicom_dir_path, self.write_config.clone())?;
+
+    // Verify the File-set by reading it back in full
+    let read_dicom_dir = DicomDir::read_p10_file(&dicom_dir_path)?;
+    if read_dicom_dir.record_count() != dicom_dir.record_count() {
+      return Err(StudyExportError::VerificationFailed {
+        path: dicom_dir_path,
+        details: format!(
+          "Expected {} directory records but found {}",
+          dicom_dir.record_count(),
+          read_dicom_dir.record_count()
+        ),
+      });
+    }
+
+    for (i, data_set) in data_sets.iter().enumerate() {
+      verify_p10_file(
+        &files_directory.join(format!("{:08}", i + 1)),
+        data_set,
+      )?;
+    }
+
+    // Move the verified File-set to the export target. The UID map isn't
+    // part of the File-set, so it's removed from the staging directory first.
+    // A ZIP archive only holds the files in the File-set.
+    match target {
+      StudyExportTarget::Directory(path) => {
+        if uid_map_path.exists() {
+          std::fs::remove_file(&uid_map_path)
+            .map_err(|e| file_error("Removing UID map", e))?;
+        }
+
+        rename(&staging_directory, path)?;
+      }
+
+      StudyExportTarget::Zip(path) => {
+        let temp_path = path.with_extension("zip.tmp");
+
+        let mut entries =
+          vec![("DICOMDIR".to_string(), dicom_dir_path.clone())];
+        for i in 0..data_sets.len() {
+          let name = format!("{FILES_DIRECTORY}/{:08}", i + 1);
+          entries.push((name.clone(), staging_directory.join(name)));
+        }
+
+        write_zip(&temp_path, &entries)?;
+        rename(&temp_path, path)?;
+
+        std::fs::remove_dir_all(&staging_directory)
+          .map_err(|e| file_error("Removing staging directory", e))?;
+      }
+    }
+
+    Ok(data_sets.len())
+  }
+
+  /// Applies UID remapping and anonymization to a data set. When both are
+  /// used, the UIDs that the anonymization profile replaces are remapped by
+  /// the UID remapper, so that each UID is only replaced once.
+  ///
+  fn prepare_data_set(&mut self, data_set: &DataSet) -> DataSet {
+    let mut data_set = data_set.clone();
+
+    match (
+      self.anonymization_profile.as_ref(),
+      self.uid_remapper.as_mut(),
+    ) {
+      (Some(profile), Some(uid_remapper)) => {
+        data_set.anonymize_with_profile_and_uid_remapper(profile, uid_remapper)
+      }
+
+      (Some(profile), None) => data_set.anonymize_with_profile(profile),
+
+      (None, Some(uid_remapper)) => data_set.remap_uids(uid_remapper),
+
+      (None, None) => (),
+    }
+
+    data_set
+  }
+}
+
+/// Reads a DICOM P10 file and checks that it holds the given data set's SOP
+/// Instance UID.
+///
+fn verify_p10_file(
+  path: &Path,
+  data_set: &DataSet,
+) -> Result<(), StudyExportError> {
+  let expected_uid = data_set.get_string(dictionary::SOP_INSTANCE_UID.tag)?;
+
+  let read_data_set = DataSet::read_p10_file(path, None)?;
+  let uid = read_data_set.get_string(dictionary::SOP_INSTANCE_UID.tag)?;
+
+  if uid != expected_uid {
+    return Err(StudyExportError::VerificationFailed {
+      path: path.to_path_buf(),
+      details: format!(
+        "Expected SOP Instance UID '{expected_uid}' but found '{uid}'"
+      ),
+    });
+  }
+
+  Ok(())
+}
+
+/// Writes a ZIP archive holding the given entries, each of which is a name in
+/// the archive and the path of the file that holds its data. Entries are
+/// stored uncompressed because pixel data rarely compresses well, and are
+/// streamed into the archive one at a time. ZIP64 records are written for
+/// sizes, offsets, and entry counts that don't fit in the original ZIP format.
+///
+/// Ref: PKWARE APPNOTE.TXT.
+///
+fn write_zip(
+  path: &Path,
+  entries: &[(String, PathBuf)],
+) -> Result<(), StudyExportError> {
+  let write_error = |e| file_error("Writing ZIP archive", e);
+
+  let file = File::create(path).map_err(write_error)?;
+  let mut writer = BufWriter::new(file);
+
+  let mut offset = 0u64;
+  let mut central_directory = vec![];
+
+  for (name, entry_path) in entries {
+    let read_error = |e| file_error("Reading file for ZIP archive", e);
+
+    // The CRC-32 and size are stored ahead of the entry's data, so the file
+    // is read once to calculate them and then again to copy its data
+    let mut crc_reader =
+      flate2::CrcReader::new(File::open(entry_path).map_err(read_error)?);
+    let size = std::io::copy(&mut crc_reader, &mut std::io::sink())
+      .map_err(read_error)?;
+    let crc = crc_reader.crc().sum();
+
+    let local_file_header = zip_local_file_header(name, crc, size);
+    writer.write_all(&local_file_header).map_err(write_error)?;
+
+    let mut entry_file = File::open(entry_path).map_err(read_error)?;
+    if std::io::copy(&mut entry_file, &mut writer).map_err(write_error)? != size
+    {
+      return Err(StudyExportError::VerificationFailed {
+        path: entry_path.clone(),
+        details: "File changed while being added to the ZIP archive"
+          .to_string(),
+      });
+    }
+
+    central_directory
+      .extend(zip_central_directory_header(name, crc, size, offset));
+
+    offset += local_file_header.len() as u64 + size;
+  }
+
+  writer.write_all(&central_directory).map_err(write_error)?;
+  writer
+    .write_all(&zip_end_of_central_directory(
+      entries.len() as u64,
+      central_directory.len() as u64,
+      offset,
+    ))
+    .map_err(write_error)?;
+
+  writer.flush().map_err(write_error)
+}
+
+/// The largest value of a 32-bit ZIP field. Values that don't fit are stored in
+/// a ZIP64 extra field and the 32-bit field is set to this value.
+///
+const ZIP_MAX_U32: u64 = 0xFFFF_FFFF;
+
+/// The largest value of a 16-bit ZIP field.
+///
+const ZIP_MAX_U16: u64 = 0xFFFF;
+
+/// Returns the local file header for a ZIP entry. When the entry's size
+/// doesn't fit in 32 bits, a ZIP64 extra field holds its uncompressed and
+/// compressed sizes.
+///
+fn zip_local_file_header(name: &str, crc: u32, size: u64) -> Vec<u8> {
+  let mut zip64_fields = vec![];
+  if size >= ZIP_MAX_U32 {
+    zip64_fields.extend_from_slice(&size.to_le_bytes());
+    zip64_fields.extend_from_slice(&size.to_le_bytes());
+  }
+
+  let extra_field = zip64_extra_field(&zip64_fields);
+
+  let mut header = vec![];
+  header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+  header.extend(zip_entry_fields(name, crc, size, extra_field.len()));
+  header.extend_from_slice(name.as_bytes());
+  header.extend(extra_field);
+
+  header
+}
+
+/// Returns the central directory header for a ZIP entry. The entry's sizes and
+/// the offset of its local file header are stored in a ZIP64 extra field when
+/// they don't fit in 32 bits.
+///
+fn zip_central_directory_header(
+  name: &str,
+  crc: u32,
+  size: u64,
+  offset: u64,
+) -> Vec<u8> {
+  let mut zip64_fields = vec![];
+  if size >= ZIP_MAX_U32 {
+    zip64_fields.extend_from_slice(&size.to_le_bytes());
+    zip64_fields.extend_from_slice(&size.to_le_bytes());
+  }
+  if offset >= ZIP_MAX_U32 {
+    zip64_fields.extend_from_slice(&offset.to_le_bytes());
+  }
+
+  let extra_field = zip64_extra_field(&zip64_fields);
+
+  let mut header = vec![];
+  header.extend_from_slice(&0x02014b50u32.to_le_bytes());
+  header.extend_from_slice(&zip_version(!extra_field.is_empty()).to_le_bytes());
+  header.extend(zip_entry_fields(name, crc, size, extra_field.len()));
+  header.extend_from_slice(&[0; 10]);
+  header.extend_from_slice(&(offset.min(ZIP_MAX_U32) as u32).to_le_bytes());
+  header.extend_from_slice(name.as_bytes());
+  header.extend(extra_field);
+
+  header
+}
+
+/// Returns the fields shared by the local file header and the central directory
+/// header: version needed, flags, method, time, date, CRC-32, sizes, and the
+/// lengths of the name and extra field.
+///
+fn zip_entry_fields(
+  name: &str,
+  crc: u32,
+  size: u64,
+  extra_field_length: usize,
+) -> Vec<u8> {
+  let size = size.min(ZIP_MAX_U32) as u32;
+
+  let mut fields = vec![];
+  fields.extend_from_slice(&zip_version(extra_field_length > 0).to_le_bytes());
+  fields.extend_from_slice(&0u16.to_le_bytes());
+  fields.extend_from_slice(&0u16.to_le_bytes());
+  fields.extend_from_slice(&0u16.to_le_bytes());
+  fields.extend_from_slice(&0x21u16.to_le_bytes());
+  fields.extend_from_slice(&crc.to_le_bytes());
+  fields.extend_from_slice(&size.to_le_bytes());
+  fields.extend_from_slice(&size.to_le_bytes());
+  fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+  fields.extend_from_slice(&(extra_field_length as u16).to_le_bytes());
+
+  fields
+}
+
+/// Returns the ZIP64 extended information extra field holding the given
+/// fields, or nothing if there are no fields.
+///
+fn zip64_extra_field(fields: &[u8]) -> Vec<u8> {
+  if fields.is_empty() {
+    return vec![];
+  }
+
+  let mut extra_field = vec![];
+  extra_field.extend_from_slice(&1u16.to_le_bytes());
+  extra_field.extend_from_slice(&(fields.len() as u16).to_le_bytes());
+  extra_field.extend_from_slice(fields);
+
+  extra_field
+}
+
+/// Returns the ZIP version needed to extract an entry, which is 4.5 when ZIP64
+/// is used and 2.0 otherwise.
+///
+fn zip_version(is_zip64: bool) -> u16 {
+  if is_zip64 { 45 } else { 20 }
+}
+
+/// Returns the records that end a ZIP archive. A ZIP64 end of central
+/// directory record and locator precede the end of central directory record
+/// when the entry count, or the size or offset of the central directory, don't
+/// fit in it.
+///
+fn zip_end_of_central_directory(
+  entry_count: u64,
+  central_directory_size: u64,
+  central_directory_offset: u64,
+) -> Vec<u8> {
+  let mut records = vec![];
+
+  if entry_count >= ZIP_MAX_U16
+    || central_directory_size >= ZIP_MAX_U32
+    || central_directory_offset >= ZIP_MAX_U32
+  {
+    let zip64_record_offset = central_directory_offset + central_directory_size;
+
+    records.extend_from_slice(&0x06064b50u32.to_le_bytes());
+    records.extend_from_slice(&44u64.to_le_bytes());
+    records.extend_from_slice(&zip_version(true).to_le_bytes());
+    records.extend_from_slice(&zip_version(true).to_le_bytes());
+    records.extend_from_slice(&[0; 8]);
+    records.extend_from_slice(&entry_count.to_le_bytes());
+    records.extend_from_slice(&entry_count.to_le_bytes());
+    records.extend_from_slice(&central_directory_size.to_le_bytes());
+    records.extend_from_slice(&central_directory_offset.to_le_bytes());
+
+    records.extend_from_slice(&0x07064b50u32.to_le_bytes());
+    records.extend_from_slice(&0u32.to_le_bytes());
+    records.extend_from_slice(&zip64_record_offset.to_le_bytes());
+    records.extend_from_slice(&1u32.to_le_bytes());
+  }
+
+  let entry_count = entry_count.min(ZIP_MAX_U16) as u16;
+
+  records.extend_from_slice(&0x06054b50u32.to_le_bytes());
+  records.extend_from_slice(&[0; 4]);
+  records.extend_from_slice(&entry_count.to_le_bytes());
+  records.extend_from_slice(&entry_count.to_le_bytes());
+  records.extend_from_slice(
+    &(central_directory_size.min(ZIP_MAX_U32) as u32).to_le_bytes(),
+  );
+  records.extend_from_slice(
+    &(central_directory_offset.min(ZIP_MAX_U32) as u32).to_le_bytes(),
+  );
+  records.extend_from_slice(&0u16.to_le_bytes());
+
+  records
+}
+
+/// Writes a file by writing to a temporary file and then renaming it, so that
+/// the file is never left partially written.
+///
+fn write_atomically(path: &Path, data: &[u8]) -> Result<(), StudyExportError> {
+  let temp_path = path.with_extension("tmp");
+
+  std::fs::write(&temp_path, data)
+    .map_err(|e| file_error("Writing file", e))?;
+
+  rename(&temp_path, path)
+}
+
+fn create_dir_all(path: &Path) -> Result<(), StudyExportError> {
+  std::fs::create_dir_all(path).map_err(|e| file_error("Creating directory", e))
+}
+
+fn read_to_string(path: &Path) -> Result<String, StudyExportError> {
+  std::fs::read_to_string(path).map_err(|e| file_error("Reading file", e))
+}
+
+fn rename(from: &Path, to: &Path) -> Result<(), StudyExportError> {
+  std::fs::rename(from, to).map_err(|e| file_error("Renaming file", e))
+}
+
+fn file_error(when: &'static str, e: std::io::Error) -> StudyExportError {
+  StudyExportError::P10Error(P10Error::FileError {
+    when: when.into(),
+    details: e.to_string().into(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn zip_headers_test() {
+    let header = zip_local_file_header("A", 0x12345678, 10);
+    assert_eq!(header.len(), 31);
+    assert_eq!(&header[4..6], &20u16.to_le_bytes());
+    assert_eq!(&header[18..26], &[10, 0, 0, 0, 10, 0, 0, 0]);
+
+    let size = 0x1_0000_0000u64;
+
+    let header = zip_local_file_header("A", 0x12345678, size);
+    assert_eq!(header.len(), 51);
+    assert_eq!(&header[4..6], &45u16.to_le_bytes());
+    assert_eq!(&header[18..26], &[0xFF; 8]);
+    assert_eq!(&header[28..30], &20u16.to_le_bytes());
+    assert_eq!(&header[31..35], &[1, 0, 16, 0]);
+    assert_eq!(&header[35..43], &size.to_le_bytes());
+    assert_eq!(&header[43..51], &size.to_le_bytes());
+
+    let header = zip_central_directory_header("A", 0x12345678, 10, size);
+    assert_eq!(header.len(), 59);
+    assert_eq!(&header[20..28], &[10, 0, 0, 0, 10, 0, 0, 0]);
+    assert_eq!(&header[42..46], &[0xFF; 4]);
+    assert_eq!(&header[47..51], &[1, 0, 8, 0]);
+    assert_eq!(&header[51..59], &size.to_le_bytes());
+  }
+
+  #[test]
+  fn zip_end_of_central_directory_test() {
+    let records = zip_end_of_central_directory(3, 100, 200);
+    assert_eq!(records.len(), 22);
+    assert_eq!(&records[8..12], &[3, 0, 3, 0]);
+
+    let records = zip_end_of_central_directory(0x10000, 100, 200);
+    assert_eq!(records.len(), 98);
+    assert_eq!(&records[0..4], &0x06064b50u32.to_le_bytes());
+    assert_eq!(&records[24..32], &0x10000u64.to_le_bytes());
+    assert_eq!(&records[56..60], &0x07064b50u32.to_le_bytes());
+    assert_eq!(&records[64..72], &300u64.to_le_bytes());
+    assert_eq!(&records[84..88], &[0xFF; 4]);
+  }
+}
@@ -1,19 +1,28 @@
 # Runs fuzz testing on DCMfx using AFL.
+#
+# The fuzz target to run is specified as the first argument, and defaults to
+# `dcmfx_fuzz`. The available fuzz targets are:
+#
+#   - dcmfx_fuzz: Reads, writes, and renders arbitrary DICOM P10 data.
+#   - decode_frame: Decodes arbitrary bytes as a frame of pixel data.
+#   - encode_frame: Encodes arbitrary images with arbitrary Image Pixel Modules.
 
 set -e
 
+TARGET="${1:-dcmfx_fuzz}"
+
 # Install afl.rs
 cargo install cargo-afl@0.17.1
 
 # Remove all data from previous runs
-rm -rf inputs outputs
+rm -rf inputs "outputs/$TARGET"
 
 # Copy test DICOM files to the inputs/ directory to be used for fuzzing
 mkdir inputs
 find ../../../test -type f -name '*.dcm' -exec cp {} inputs \;
 
-# Build instrumented binary
+# Build instrumented binaries
 cargo afl build --release
 
 # Run the fuzzing. This will run indefinitely until it is terminated.
-cargo afl fuzz -c 0 -i inputs -o outputs ./target/release/dcmfx_fuzz
+cargo afl fuzz -c 0 -i inputs -o "outputs/$TARGET" "./target/release/$TARGET"
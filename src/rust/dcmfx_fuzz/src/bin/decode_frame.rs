@@ -0,0 +1,63 @@
+//! Fuzzing of pixel data decoding using AFL. Run using
+//! `./fuzz.sh decode_frame`.
+
+#[macro_use]
+extern crate afl;
+
+use dcmfx::pixel_data::{
+  PixelDataDecodeConfig, PixelDataFrame, decode,
+  decode::{HighThroughputJpeg2000Decoder, JpegLsDecoder, JpegXlDecoder},
+};
+
+fn main() {
+  let decode_configs = [
+    PixelDataDecodeConfig::default(),
+    PixelDataDecodeConfig {
+      high_throughput_jpeg_2000_decoders: vec![
+        HighThroughputJpeg2000Decoder::OpenJpeg,
+      ],
+      jpeg_ls_decoders: vec![JpegLsDecoder::Dcmfx],
+      jpeg_xl_decoders: vec![JpegXlDecoder::JxlOxide],
+      thread_count: Some(1),
+      min_decoded_size: None,
+    },
+  ];
+
+  fuzz!(|data: &[u8]| {
+    let Some((image_pixel_module, data)) =
+      dcmfx_fuzz::image_pixel_module_from_bytes(data)
+    else {
+      return;
+    };
+
+    let Some((transfer_syntax_index, data)) = data.split_first() else {
+      return;
+    };
+
+    let transfer_syntax =
+      dcmfx_fuzz::TRANSFER_SYNTAXES[usize::from(*transfer_syntax_index)
+        % dcmfx_fuzz::TRANSFER_SYNTAXES.len()];
+
+    // Decoding arbitrary bytes should never panic, but a well-formed error is
+    // fine because the input is being fuzzed and so is almost always invalid
+    for decode_config in decode_configs.iter() {
+      let mut frame = PixelDataFrame::new_from_bytes(data.to_vec());
+
+      if image_pixel_module.is_monochrome() {
+        let _ = decode::decode_monochrome(
+          &mut frame,
+          transfer_syntax,
+          &image_pixel_module,
+          decode_config,
+        );
+      } else {
+        let _ = decode::decode_color(
+          &mut frame,
+          transfer_syntax,
+          &image_pixel_module,
+          decode_config,
+        );
+      }
+    }
+  });
+}
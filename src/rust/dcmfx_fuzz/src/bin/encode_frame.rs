@@ -0,0 +1,108 @@
+//! Fuzzing of pixel data encoding using AFL. Run using
+//! `./fuzz.sh encode_frame`.
+
+#[macro_use]
+extern crate afl;
+
+use dcmfx::{
+  core::transfer_syntax,
+  pixel_data::{
+    PixelDataDecodeConfig, PixelDataEncodeConfig, PixelDataFrame, decode,
+    encode,
+  },
+};
+
+fn main() {
+  fuzz!(|data: &[u8]| {
+    // Create the image to encode from the fuzz input, using one Image Pixel
+    // Module to create the image and a second one to encode it. This means the
+    // image and the Image Pixel Module passed to the encoder may not match.
+    let Some((image_pixel_module, data)) =
+      dcmfx_fuzz::image_pixel_module_from_bytes(data)
+    else {
+      return;
+    };
+
+    let Some((encode_image_pixel_module, data)) =
+      dcmfx_fuzz::image_pixel_module_from_bytes(data)
+    else {
+      return;
+    };
+
+    let Some((&quality, data)) = data.split_first() else {
+      return;
+    };
+
+    let mut frame_data = data.to_vec();
+    frame_data.resize(image_pixel_module.frame_size_in_bytes(), 0);
+    let mut frame = PixelDataFrame::new_from_bytes(frame_data);
+
+    let mut encode_config = PixelDataEncodeConfig::default();
+    encode_config.set_quality(quality % 100 + 1);
+    encode_config.set_effort(quality % 10 + 1);
+
+    // Encoding should never panic, but a well-formed error is fine because the
+    // image and Image Pixel Module may not be supported by the encoder
+    if image_pixel_module.is_monochrome() {
+      let Ok(image) = decode::decode_monochrome(
+        &mut frame,
+        &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+        &image_pixel_module,
+        &PixelDataDecodeConfig::default(),
+      ) else {
+        return;
+      };
+
+      for transfer_syntax in dcmfx_fuzz::TRANSFER_SYNTAXES {
+        let Ok(output_image_pixel_module) = encode::encode_image_pixel_module(
+          image_pixel_module.clone(),
+          transfer_syntax,
+          &encode_config,
+        ) else {
+          continue;
+        };
+
+        for image_pixel_module in
+          [&output_image_pixel_module, &encode_image_pixel_module]
+        {
+          let _ = encode::encode_monochrome(
+            &image,
+            image_pixel_module,
+            transfer_syntax,
+            &encode_config,
+          );
+        }
+      }
+    } else {
+      let Ok(image) = decode::decode_color(
+        &mut frame,
+        &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+        &image_pixel_module,
+        &PixelDataDecodeConfig::default(),
+      ) else {
+        return;
+      };
+
+      for transfer_syntax in dcmfx_fuzz::TRANSFER_SYNTAXES {
+        let Ok(output_image_pixel_module) = encode::encode_image_pixel_module(
+          image_pixel_module.clone(),
+          transfer_syntax,
+          &encode_config,
+        ) else {
+          continue;
+        };
+
+        for image_pixel_module in
+          [&output_image_pixel_module, &encode_image_pixel_module]
+        {
+          let _ = encode::encode_color(
+            &image,
+            image_pixel_module,
+            transfer_syntax,
+            &encode_config,
+          );
+        }
+      }
+    }
+  });
+}
@@ -0,0 +1,138 @@
+//! Utilities shared by the DCMfx fuzz targets.
+
+use dcmfx::{
+  core::{TransferSyntax, transfer_syntax},
+  pixel_data::iods::{
+    ImagePixelModule,
+    image_pixel_module::{
+      BitsAllocated, PhotometricInterpretation, PixelRepresentation,
+      PlanarConfiguration, SamplesPerPixel,
+    },
+  },
+};
+
+/// The transfer syntaxes that pixel data is decoded from and encoded into by
+/// the fuzz targets.
+///
+pub const TRANSFER_SYNTAXES: [&TransferSyntax; 18] = [
+  &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+  &transfer_syntax::EXPLICIT_VR_BIG_ENDIAN,
+  &transfer_syntax::DEFLATED_IMAGE_FRAME_COMPRESSION,
+  &transfer_syntax::RLE_LOSSLESS,
+  &transfer_syntax::JPEG_BASELINE_8BIT,
+  &transfer_syntax::JPEG_EXTENDED_12BIT,
+  &transfer_syntax::JPEG_LOSSLESS_NON_HIERARCHICAL,
+  &transfer_syntax::JPEG_LOSSLESS_NON_HIERARCHICAL_SV1,
+  &transfer_syntax::JPEG_LS_LOSSLESS,
+  &transfer_syntax::JPEG_LS_LOSSY_NEAR_LOSSLESS,
+  &transfer_syntax::JPEG_2000_LOSSLESS_ONLY,
+  &transfer_syntax::JPEG_2000,
+  &transfer_syntax::HIGH_THROUGHPUT_JPEG_2000_LOSSLESS_ONLY,
+  &transfer_syntax::HIGH_THROUGHPUT_JPEG_2000_WITH_RPCL_OPTIONS_LOSSLESS_ONLY,
+  &transfer_syntax::HIGH_THROUGHPUT_JPEG_2000,
+  &transfer_syntax::JPEG_XL_LOSSLESS,
+  &transfer_syntax::JPEG_XL_JPEG_RECOMPRESSION,
+  &transfer_syntax::JPEG_XL,
+];
+
+/// Creates an Image Pixel Module from the first five bytes of fuzz input, and
+/// returns it along with the remaining bytes. Rows and columns are limited to
+/// 64 so that each fuzz iteration is fast.
+///
+/// Returns `None` if there is insufficient input or the resulting module is
+/// invalid.
+///
+pub fn image_pixel_module_from_bytes(
+  data: &[u8],
+) -> Option<(ImagePixelModule, &[u8])> {
+  let [a, b, c, d, e, rest @ ..] = data else {
+    return None;
+  };
+
+  let pixel_representation = if a & 0x80 == 0 {
+    PixelRepresentation::Unsigned
+  } else {
+    PixelRepresentation::Signed
+  };
+
+  let planar_configuration = if a & 0x40 == 0 {
+    PlanarConfiguration::Interleaved
+  } else {
+    PlanarConfiguration::Separate
+  };
+
+  let (samples_per_pixel, photometric_interpretation) = match a % 8 {
+    0 => (
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation,
+      },
+    ),
+    1 => (
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation,
+      },
+    ),
+    2 => (
+      SamplesPerPixel::Three {
+        planar_configuration,
+      },
+      PhotometricInterpretation::Rgb,
+    ),
+    3 => (
+      SamplesPerPixel::Three {
+        planar_configuration,
+      },
+      PhotometricInterpretation::YbrFull,
+    ),
+    4 => (
+      SamplesPerPixel::Three {
+        planar_configuration,
+      },
+      PhotometricInterpretation::YbrFull422,
+    ),
+    5 => (
+      SamplesPerPixel::Three {
+        planar_configuration,
+      },
+      PhotometricInterpretation::YbrIct,
+    ),
+    6 => (
+      SamplesPerPixel::Three {
+        planar_configuration,
+      },
+      PhotometricInterpretation::YbrRct,
+    ),
+    _ => (
+      SamplesPerPixel::Three {
+        planar_configuration,
+      },
+      PhotometricInterpretation::Xyb,
+    ),
+  };
+
+  let bits_allocated = match b % 4 {
+    0 => BitsAllocated::One,
+    1 => BitsAllocated::Eight,
+    2 => BitsAllocated::Sixteen,
+    _ => BitsAllocated::ThirtyTwo,
+  };
+
+  let bits_stored = u16::from(*c % u8::from(bits_allocated)) + 1;
+
+  let rows = u16::from(d % 64) + 1;
+  let columns = u16::from(e % 64) + 1;
+
+  let image_pixel_module = ImagePixelModule::new_basic(
+    samples_per_pixel,
+    photometric_interpretation,
+    rows,
+    columns,
+    bits_allocated,
+    bits_stored,
+  )
+  .ok()?;
+
+  Some((image_pixel_module, rest))
+}
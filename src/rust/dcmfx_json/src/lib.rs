@@ -19,6 +19,8 @@ mod json_error;
 mod transforms;
 
 use dcmfx_core::{DataSet, DataSetPath};
+#[cfg(feature = "async")]
+use dcmfx_p10::DataSetP10AsyncExtensions;
 use dcmfx_p10::{DataSetP10Extensions, P10Token};
 
 pub use json_config::DicomJsonConfig;
@@ -51,6 +53,32 @@ where
   fn from_json(json: &str) -> Result<Self, JsonDeserializeError>;
 }
 
+/// The number of bytes of JSON data that are buffered before being written to
+/// the stream by [`DataSetJsonAsyncExtensions::to_json_stream_async()`].
+///
+#[cfg(feature = "async")]
+const ASYNC_WRITE_THRESHOLD: usize = 64 * 1024;
+
+/// Adds async functions to [`DataSet`] for converting to DICOM JSON.
+///
+#[cfg(feature = "async")]
+#[async_trait::async_trait(?Send)]
+pub trait DataSetJsonAsyncExtensions {
+  /// Converts a data set to DICOM JSON, writing the JSON data to an async
+  /// stream as it is generated. This allows large data sets to be streamed as
+  /// DICOM JSON, e.g. in an HTTP response, without first converting the whole
+  /// data set to a string.
+  ///
+  /// The JSON data is buffered and written to the stream in chunks of at least
+  /// 64 KiB, other than the final chunk.
+  ///
+  async fn to_json_stream_async<S: dcmfx_p10::IoAsyncWrite>(
+    &self,
+    config: DicomJsonConfig,
+    stream: &mut S,
+  ) -> Result<(), JsonSerializeError>;
+}
+
 #[cfg(not(feature = "std"))]
 struct Cursor {
   data: Vec<u8>,
@@ -122,6 +150,47 @@ impl DataSetJsonExtensions for DataSet {
   }
 }
 
+#[cfg(feature = "async")]
+#[async_trait::async_trait(?Send)]
+impl DataSetJsonAsyncExtensions for DataSet {
+  async fn to_json_stream_async<S: dcmfx_p10::IoAsyncWrite>(
+    &self,
+    config: DicomJsonConfig,
+    stream: &mut S,
+  ) -> Result<(), JsonSerializeError> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut json_transform = P10JsonTransform::new(config);
+    let mut buffer = vec![];
+
+    // The JSON is gathered into a buffer that is written to the stream each
+    // time it reaches the threshold size, rather than writing each token's JSON
+    // to the stream separately
+    let mut token_to_stream = async |token: P10Token| {
+      json_transform.add_token(&token, &mut buffer)?;
+
+      if buffer.len() >= ASYNC_WRITE_THRESHOLD {
+        stream
+          .write_all(&buffer)
+          .await
+          .map_err(JsonSerializeError::IOError)?;
+        buffer.clear();
+      }
+
+      Ok::<(), JsonSerializeError>(())
+    };
+
+    self.to_p10_token_stream_async(&mut token_to_stream).await?;
+
+    stream
+      .write_all(&buffer)
+      .await
+      .map_err(JsonSerializeError::IOError)?;
+
+    stream.flush().await.map_err(JsonSerializeError::IOError)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use dcmfx_core::{
@@ -154,6 +223,38 @@ mod tests {
     }
   }
 
+  #[cfg(feature = "async")]
+  #[test]
+  fn data_set_to_json_stream_async_test() {
+    let ds = dcmfx_p10::read_file(
+      "../../../test/assets/pydicom/test_files/examples_rgb_color.dcm",
+      None,
+    )
+    .unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .build()
+      .unwrap();
+
+    for pretty_print in [false, true] {
+      let config = DicomJsonConfig {
+        pretty_print,
+        ..JSON_CONFIG
+      };
+
+      let mut sync_json = vec![];
+      ds.to_json_stream(config.clone(), &mut sync_json).unwrap();
+
+      let mut async_json = vec![];
+      runtime
+        .block_on(ds.to_json_stream_async(config, &mut async_json))
+        .unwrap();
+
+      assert!(sync_json.len() > ASYNC_WRITE_THRESHOLD);
+      assert_eq!(async_json, sync_json);
+    }
+  }
+
   #[test]
   fn json_to_data_set_test() {
     for (data_elements, expected_json) in test_data_sets() {
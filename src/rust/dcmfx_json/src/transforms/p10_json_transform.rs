@@ -91,8 +91,8 @@ impl P10JsonTransform {
   ) -> Result<(), JsonSerializeError> {
     let token_stream_invalid_error = || {
       JsonSerializeError::P10Error(P10Error::TokenStreamInvalid {
-        when: "Adding token to JSON transform".to_string(),
-        details: "The transform was not able to write this token".to_string(),
+        when: "Adding token to JSON transform".into(),
+        details: "The transform was not able to write this token".into(),
         token: token.clone(),
       })
     };
@@ -212,6 +212,38 @@ impl P10JsonTransform {
     }
   }
 
+  /// Adds the next DICOM P10 token to this JSON transform, writing the
+  /// resulting bytes of JSON data to the provided async `stream`. See
+  /// [`Self::add_token()`] for details.
+  ///
+  /// The JSON for each token is buffered in memory and written to the stream
+  /// with a single write, so tokens for large values such as pixel data are
+  /// held in memory in full. Writing many small tokens results in many small
+  /// writes, so a buffered stream should be used where this is a concern.
+  ///
+  #[cfg(feature = "async")]
+  pub async fn add_token_async<S: dcmfx_p10::IoAsyncWrite>(
+    &mut self,
+    token: &P10Token,
+    stream: &mut S,
+  ) -> Result<(), JsonSerializeError> {
+    use tokio::io::AsyncWriteExt;
+
+    // The JSON for the token is gathered into a buffer that is then written to
+    // the async stream in a single call
+    let mut buffer = vec![];
+    self.add_token(token, &mut buffer)?;
+
+    if buffer.is_empty() {
+      return Ok(());
+    }
+
+    stream
+      .write_all(&buffer)
+      .await
+      .map_err(JsonSerializeError::IOError)
+  }
+
   fn indent(&self, offset: isize) -> String {
     let mut indent = 1isize;
     indent += self.data_set_path.sequence_item_count() as isize * 3;
@@ -800,9 +832,7 @@ impl P10JsonTransform {
       // PersonName value representation
       ValueRepresentation::PersonName => {
         let string = core::str::from_utf8(&bytes).map_err(|_| {
-          DataError::new_value_invalid(
-            "PersonName is invalid UTF-8".to_string(),
-          )
+          DataError::new_value_invalid("PersonName is invalid UTF-8")
         })?;
 
         string
@@ -938,9 +968,7 @@ impl P10JsonTransform {
       | ValueRepresentation::Time => {
         let string = core::str::from_utf8(&bytes)
           .map_err(|_| {
-            DataError::new_value_invalid(
-              "String bytes are not valid UTF-8".to_string(),
-            )
+            DataError::new_value_invalid("String bytes are not valid UTF-8")
           })?
           .trim_end_matches(' ');
 
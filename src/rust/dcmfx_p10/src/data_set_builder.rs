@@ -14,11 +14,11 @@ use alloc::{
 };
 
 use dcmfx_core::{
-  DataElementTag, DataElementValue, DataSet, RcByteSlice, ValueRepresentation,
-  dictionary,
+  DataElementTag, DataElementValue, DataSet, DataSetPath, RcByteSlice,
+  ValueRepresentation, dictionary, error_details,
 };
 
-use crate::{P10Error, P10Token};
+use crate::{DuplicateDataElementPolicy, P10Error, P10ReadConfig, P10Token};
 
 /// A data set builder that can be fed a stream of DICOM P10 tokens and
 /// materialize them into an in-memory data set.
@@ -30,6 +30,8 @@ pub struct DataSetBuilder {
   location: Vec<BuilderLocation>,
   pending_data_element: Option<PendingDataElement>,
   is_complete: bool,
+  duplicate_data_element_policy: DuplicateDataElementPolicy,
+  duplicate_data_elements: Vec<(DataSetPath, DataElementValue)>,
 }
 
 /// Tracks where in the data set the builder is currently at, specifically the
@@ -83,9 +85,45 @@ impl DataSetBuilder {
       }],
       pending_data_element: None,
       is_complete: false,
+      duplicate_data_element_policy: DuplicateDataElementPolicy::default(),
+      duplicate_data_elements: vec![],
     }
   }
 
+  /// Creates a new data set builder that uses the duplicate data element
+  /// policy from the given read config.
+  ///
+  pub(crate) fn new_for_read_config(config: Option<P10ReadConfig>) -> Self {
+    let mut builder = Self::new();
+    builder.set_duplicate_data_element_policy(
+      config.unwrap_or_default().duplicate_data_element_policy,
+    );
+
+    builder
+  }
+
+  /// Sets how a data set builder handles data elements that occur more than
+  /// once in the same data set or sequence item. See
+  /// [`DuplicateDataElementPolicy`] for details.
+  ///
+  /// By default each later occurrence of a data element replaces the earlier
+  /// one, i.e. [`DuplicateDataElementPolicy::LastWins`].
+  ///
+  pub fn set_duplicate_data_element_policy(
+    &mut self,
+    policy: DuplicateDataElementPolicy,
+  ) {
+    self.duplicate_data_element_policy = policy;
+  }
+
+  /// Returns the duplicate data elements that were not included in the final
+  /// data set, along with their paths. These are only recorded when the
+  /// duplicate data element policy is [`DuplicateDataElementPolicy::KeepBoth`].
+  ///
+  pub fn duplicate_data_elements(&self) -> &[(DataSetPath, DataElementValue)] {
+    &self.duplicate_data_elements
+  }
+
   /// Returns whether the data set builder is complete, i.e. whether it has
   /// received the final [`P10Token::End`] token signalling the end of the
   /// incoming DICOM P10 tokens.
@@ -144,6 +182,13 @@ impl DataSetBuilder {
 
     self.pending_data_element = None;
 
+    // A partially built data set is returned as-is, so duplicate data elements
+    // in it aren't treated as an error
+    if self.duplicate_data_element_policy == DuplicateDataElementPolicy::Error {
+      self.duplicate_data_element_policy =
+        DuplicateDataElementPolicy::FirstWins;
+    }
+
     while let Some(location) = self.location.last() {
       match location {
         BuilderLocation::Sequence { tag, .. } => self
@@ -178,8 +223,8 @@ impl DataSetBuilder {
   pub fn add_token(&mut self, token: &P10Token) -> Result<(), P10Error> {
     if self.is_complete {
       return Err(P10Error::TokenStreamInvalid {
-        when: "Building data set".to_string(),
-        details: "Token received after the token stream has ended".to_string(),
+        when: "Building data set".into(),
+        details: "Token received after the token stream has ended".into(),
         token: token.clone(),
       });
     }
@@ -261,7 +306,7 @@ impl DataSetBuilder {
           self.location.pop()
         {
           let sequence = DataElementValue::new_sequence(items);
-          self.insert_data_element_at_current_location(tag, sequence);
+          self.insert_data_element_at_current_location(tag, sequence, token)?;
         }
 
         Ok(())
@@ -301,7 +346,8 @@ impl DataSetBuilder {
           self.insert_data_element_at_current_location(
             dictionary::PIXEL_DATA.tag,
             DataElementValue::new_encapsulated_pixel_data_unchecked(vr, items),
-          );
+            token,
+          )?;
         }
 
         Ok(())
@@ -375,9 +421,9 @@ impl DataSetBuilder {
         }
 
         _ => Err(P10Error::TokenStreamInvalid {
-          when: "Building data set".to_string(),
+          when: "Building data set".into(),
           details: "Received sequence item delimiter token outside of an item"
-            .to_string(),
+            .into(),
           token: token.clone(),
         }),
       },
@@ -393,9 +439,8 @@ impl DataSetBuilder {
         }
 
         _ => Err(P10Error::TokenStreamInvalid {
-          when: "Building data set".to_string(),
-          details: "Received end token outside of the root data set"
-            .to_string(),
+          when: "Building data set".into(),
+          details: "Received end token outside of the root data set".into(),
           token: token.clone(),
         }),
       },
@@ -430,9 +475,9 @@ impl DataSetBuilder {
             &pending_data_element.data,
           );
 
-          self.insert_data_element_at_current_location(tag, value);
-
           self.pending_data_element = None;
+
+          self.insert_data_element_at_current_location(tag, value, token)?;
         }
 
         Ok(())
@@ -443,18 +488,45 @@ impl DataSetBuilder {
   }
 
   /// Inserts a new data element into the head of the given data set builder
-  /// location and returns an updated location.
+  /// location. If the data element is already present then the duplicate data
+  /// element policy determines the outcome.
   ///
   fn insert_data_element_at_current_location(
     &mut self,
     tag: DataElementTag,
     value: DataElementValue,
-  ) {
+    token: &P10Token,
+  ) -> Result<(), P10Error> {
     match (self.location.as_mut_slice(), value.bytes()) {
       // Insert new data element into the root data set or current sequence item
       ([BuilderLocation::RootDataSet { data_set }], _)
       | ([.., BuilderLocation::SequenceItem { data_set }], _) => {
-        data_set.insert(tag, value);
+        if !data_set.has(tag) {
+          data_set.insert(tag, value);
+          return Ok(());
+        }
+
+        match self.duplicate_data_element_policy {
+          DuplicateDataElementPolicy::FirstWins => (),
+
+          DuplicateDataElementPolicy::LastWins => data_set.insert(tag, value),
+
+          DuplicateDataElementPolicy::Error => {
+            return Err(P10Error::TokenStreamInvalid {
+              when: "Building data set".into(),
+              details: error_details!(
+                "Duplicate data element '{}'",
+                self.data_element_path(tag).to_detailed_string()
+              ),
+              token: token.clone(),
+            });
+          }
+
+          DuplicateDataElementPolicy::KeepBoth => {
+            let path = self.data_element_path(tag);
+            self.duplicate_data_elements.push((path, value));
+          }
+        }
       }
 
       // Insert new data element into the current encapsulated pixel data
@@ -471,14 +543,36 @@ impl DataSetBuilder {
       // is not expected to be logically possible.
       _ => unreachable!(),
     };
+
+    Ok(())
+  }
+
+  /// Returns the path to the data element with the given tag in the current
+  /// data set or sequence item. Paths are only needed for duplicate data
+  /// elements, so they're constructed from the builder's location on demand
+  /// rather than being tracked for every data element.
+  ///
+  fn data_element_path(&self, tag: DataElementTag) -> DataSetPath {
+    let mut path = DataSetPath::new();
+
+    for location in self.location.iter() {
+      if let BuilderLocation::Sequence { tag, items } = location {
+        let _ = path.add_data_element(*tag);
+        let _ = path.add_sequence_item(items.len());
+      }
+    }
+
+    let _ = path.add_data_element(tag);
+
+    path
   }
 
   /// The error returned when an unexpected DICOM P10 token is received.
   ///
   fn unexpected_token_error(&self, token: &P10Token) -> Result<(), P10Error> {
     Err(P10Error::TokenStreamInvalid {
-      when: "Building data set".to_string(),
-      details: format!(
+      when: "Building data set".into(),
+      details: error_details!(
         "Received unexpected P10 token at location: {}",
         location_to_string(&self.location),
       ),
@@ -490,7 +584,7 @@ impl DataSetBuilder {
 /// Takes the tag, VR, and final bytes for a new data element and returns the
 /// `DataElementValue` for it to insert into the active data set.
 ///
-fn build_final_data_element_value(
+pub(crate) fn build_final_data_element_value(
   tag: DataElementTag,
   vr: ValueRepresentation,
   value_bytes: &[RcByteSlice],
@@ -539,3 +633,209 @@ fn location_to_string(location: &[BuilderLocation]) -> String {
 
   result.join(".")
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{P10ReadConfig, P10ReadContext};
+  use dcmfx_core::{ErrorContext, ErrorContextChain};
+
+  /// Returns 'Implicit VR Little Endian' data that has a duplicate data element
+  /// in the root data set, and another in a sequence item.
+  ///
+  fn duplicate_data_elements_bytes() -> RcByteSlice {
+    let mut bytes = vec![];
+
+    let mut add = |group: u16, element: u16, length: u32, value: &[u8]| {
+      bytes.extend_from_slice(&group.to_le_bytes());
+      bytes.extend_from_slice(&element.to_le_bytes());
+      bytes.extend_from_slice(&length.to_le_bytes());
+      bytes.extend_from_slice(value);
+    };
+
+    add(0x0008, 0x1140, 0xFFFFFFFF, b"");
+    add(0xFFFE, 0xE000, 0xFFFFFFFF, b"");
+    add(0x0008, 0x1150, 2, b"1\0");
+    add(0x0008, 0x1150, 2, b"2\0");
+    add(0xFFFE, 0xE00D, 0, b"");
+    add(0xFFFE, 0xE0DD, 0, b"");
+    add(0x0010, 0x0010, 2, b"A ");
+    add(0x0010, 0x0020, 2, b"12");
+    add(0x0010, 0x0010, 2, b"B ");
+
+    bytes.into()
+  }
+
+  fn read_with_policy(
+    policy: DuplicateDataElementPolicy,
+  ) -> Result<DataSetBuilder, P10Error> {
+    let config = P10ReadConfig::default()
+      .require_ordered_data_elements(false)
+      .duplicate_data_element_policy(policy);
+
+    let mut context = P10ReadContext::new(Some(config));
+    context.write_bytes(duplicate_data_elements_bytes(), true)?;
+
+    let mut builder = DataSetBuilder::new_for_read_config(Some(config));
+    while !builder.is_complete() {
+      builder.add_tokens(&context.read_tokens()?)?;
+    }
+
+    Ok(builder)
+  }
+
+  fn patient_name_and_referenced_sop_instance_uid(
+    data_set: &DataSet,
+  ) -> (String, String) {
+    let path = DataSetPath::from_string("00081140/[0]/00081150").unwrap();
+
+    (
+      data_set
+        .get_string(dictionary::PATIENT_NAME.tag)
+        .unwrap()
+        .to_string(),
+      data_set
+        .get_value_at_path(&path)
+        .unwrap()
+        .get_string()
+        .unwrap()
+        .to_string(),
+    )
+  }
+
+  #[test]
+  fn duplicate_data_element_policy_first_wins_test() {
+    let mut builder =
+      read_with_policy(DuplicateDataElementPolicy::FirstWins).unwrap();
+
+    assert_eq!(
+      patient_name_and_referenced_sop_instance_uid(
+        &builder.final_data_set().unwrap()
+      ),
+      ("A".to_string(), "1".to_string())
+    );
+    assert!(builder.duplicate_data_elements().is_empty());
+  }
+
+  #[test]
+  fn duplicate_data_element_policy_last_wins_test() {
+    let mut builder =
+      read_with_policy(DuplicateDataElementPolicy::LastWins).unwrap();
+
+    assert_eq!(
+      patient_name_and_referenced_sop_instance_uid(
+        &builder.final_data_set().unwrap()
+      ),
+      ("B".to_string(), "2".to_string())
+    );
+    assert!(builder.duplicate_data_elements().is_empty());
+  }
+
+  #[test]
+  fn duplicate_data_element_policy_default_test() {
+    let config = P10ReadConfig::default().require_ordered_data_elements(false);
+
+    let mut context = P10ReadContext::new(Some(config));
+    context
+      .write_bytes(duplicate_data_elements_bytes(), true)
+      .unwrap();
+
+    let mut builder = DataSetBuilder::new();
+    while !builder.is_complete() {
+      builder.add_tokens(&context.read_tokens().unwrap()).unwrap();
+    }
+
+    assert_eq!(
+      patient_name_and_referenced_sop_instance_uid(
+        &builder.final_data_set().unwrap()
+      ),
+      ("B".to_string(), "2".to_string())
+    );
+  }
+
+  #[test]
+  fn duplicate_data_element_policy_error_test() {
+    let error = read_with_policy(DuplicateDataElementPolicy::Error)
+      .err()
+      .unwrap();
+    let path = DataSetPath::from_string("00081140/[0]").unwrap();
+
+    assert_eq!(
+      error.without_context(),
+      &P10Error::DataInvalid {
+        when: "Reading data element header".into(),
+        details: "Data element '(0008,1150) UN Referenced SOP Class UID' is a \
+          duplicate"
+          .into(),
+        path: path.clone(),
+        offset: 34,
+      }
+    );
+    assert_eq!(
+      error.context(),
+      Some(&ErrorContext::new().with_path(&path).with_transfer_syntax(
+        &dcmfx_core::transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN
+      ))
+    );
+  }
+
+  #[test]
+  fn duplicate_data_element_policy_error_in_builder_test() {
+    let mut context = P10ReadContext::new(None);
+    context
+      .write_bytes(duplicate_data_elements_bytes(), true)
+      .unwrap();
+
+    let mut builder = DataSetBuilder::new();
+    builder
+      .set_duplicate_data_element_policy(DuplicateDataElementPolicy::Error);
+    let error = loop {
+      if let Err(e) = builder.add_tokens(&context.read_tokens().unwrap()) {
+        break e;
+      }
+    };
+
+    let P10Error::TokenStreamInvalid { details, .. } = error else {
+      panic!("Unexpected error: {error:?}");
+    };
+
+    assert_eq!(
+      details,
+      "Duplicate data element '(0008,1140) Referenced Image Sequence / Item 0 \
+        / (0008,1150) Referenced SOP Class UID'"
+    );
+  }
+
+  #[test]
+  fn duplicate_data_element_policy_keep_both_test() {
+    let mut builder =
+      read_with_policy(DuplicateDataElementPolicy::KeepBoth).unwrap();
+
+    assert_eq!(
+      builder.duplicate_data_elements(),
+      &[
+        (
+          DataSetPath::from_string("00081140/[0]/00081150").unwrap(),
+          DataElementValue::new_binary_unchecked(
+            ValueRepresentation::UniqueIdentifier,
+            b"2\0".to_vec().into()
+          )
+        ),
+        (
+          DataSetPath::from_string("00100010").unwrap(),
+          DataElementValue::new_binary_unchecked(
+            ValueRepresentation::PersonName,
+            b"B ".to_vec().into()
+          )
+        ),
+      ]
+    );
+
+    assert_eq!(
+      patient_name_and_referenced_sop_instance_uid(
+        &builder.final_data_set().unwrap()
+      ),
+      ("A".to_string(), "1".to_string())
+    );
+  }
+}
@@ -0,0 +1,491 @@
+//! Encryption at rest of DICOM P10 data using an envelope format that wraps the
+//! P10 bytes with authenticated encryption.
+//!
+//! The envelope starts with a small header that holds the `DCME` magic bytes,
+//! a format version, the encryption algorithm, the chunk size, and a random
+//! nonce prefix. The P10 bytes follow as a sequence of chunks that are each
+//! encrypted with AES-256-GCM. Each chunk's nonce is formed from the nonce
+//! prefix, the chunk's index, and a flag that marks the final chunk, and the
+//! header is authenticated along with every chunk. This means that modified,
+//! reordered, or truncated data is always detected when it is decrypted.
+//!
+//! [`EncryptingWriter`] and [`DecryptingReader`] implement [`IoWrite`] and
+//! [`IoRead`] respectively, and so can be passed to any function that writes or
+//! reads a stream of DICOM P10 data, e.g. [`crate::write_stream()`] and
+//! [`crate::read_stream()`].
+//!
+//! Encrypted files conventionally use the `.dcme` file extension.
+
+use std::io::{Read, Write};
+
+use aes_gcm::{
+  Aes256Gcm, KeyInit, Nonce,
+  aead::{Aead, Payload},
+};
+
+use crate::{IoError, IoRead, IoWrite};
+
+/// The file extension used for DICOM P10 data stored in the encrypted envelope
+/// format.
+///
+pub const FILE_EXTENSION: &str = "dcme";
+
+/// The magic bytes at the start of the encrypted envelope format.
+///
+const MAGIC: &[u8; 4] = b"DCME";
+
+/// The version of the encrypted envelope format.
+///
+const FORMAT_VERSION: u8 = 1;
+
+/// The identifier for the AES-256-GCM encryption algorithm.
+///
+const ALGORITHM_AES_256_GCM: u8 = 1;
+
+/// The size of the header: magic bytes, format version, algorithm, chunk size,
+/// and nonce prefix.
+///
+const HEADER_SIZE: usize = 4 + 1 + 1 + 4 + NONCE_PREFIX_SIZE;
+
+/// The size of the random prefix of each chunk's nonce. The remaining five
+/// bytes of the nonce hold the chunk index and the final chunk flag.
+///
+const NONCE_PREFIX_SIZE: usize = 7;
+
+/// The size of the authentication tag appended to each encrypted chunk.
+///
+const TAG_SIZE: usize = 16;
+
+/// The number of bytes of plaintext in each chunk when encrypting.
+///
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The largest chunk size accepted when decrypting, which bounds the memory
+/// allocated for untrusted input.
+///
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// A 256-bit key used to encrypt and decrypt DICOM P10 data.
+///
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+  /// Creates an encryption key from the given bytes.
+  ///
+  pub fn new(bytes: [u8; 32]) -> Self {
+    Self(bytes)
+  }
+
+  /// Generates a new random encryption key using the operating system's
+  /// random number generator.
+  ///
+  pub fn generate() -> Result<Self, IoError> {
+    let mut bytes = [0u8; 32];
+    random_fill(&mut bytes)?;
+
+    Ok(Self(bytes))
+  }
+
+  /// Returns the bytes of this encryption key.
+  ///
+  pub fn as_bytes(&self) -> &[u8; 32] {
+    &self.0
+  }
+}
+
+impl core::fmt::Debug for EncryptionKey {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("EncryptionKey(..)")
+  }
+}
+
+/// Returns whether the given bytes start with the header of the encrypted
+/// envelope format.
+///
+pub fn is_encrypted_bytes(bytes: &[u8]) -> bool {
+  bytes.len() >= HEADER_SIZE && bytes[0..4] == *MAGIC
+}
+
+/// Encrypts the data written to it and writes the resulting encrypted envelope
+/// to an underlying write stream.
+///
+/// Data is encrypted in chunks, and the final chunk is only written when
+/// [`Self::finish()`] is called. If it isn't called then the encrypted output
+/// is incomplete, and will fail to decrypt.
+///
+pub struct EncryptingWriter<W: IoWrite> {
+  inner: W,
+  cipher: Aes256Gcm,
+  header: [u8; HEADER_SIZE],
+  chunk_size: usize,
+  chunk_index: u32,
+  buffer: Vec<u8>,
+}
+
+impl<W: IoWrite> EncryptingWriter<W> {
+  /// Creates a new encrypting writer that writes to the given stream. The
+  /// header of the encrypted envelope is written immediately.
+  ///
+  pub fn new(inner: W, key: &EncryptionKey) -> Result<Self, IoError> {
+    Self::new_with_chunk_size(inner, key, DEFAULT_CHUNK_SIZE)
+  }
+
+  fn new_with_chunk_size(
+    mut inner: W,
+    key: &EncryptionKey,
+    chunk_size: usize,
+  ) -> Result<Self, IoError> {
+    let mut header = [0u8; HEADER_SIZE];
+    header[0..4].copy_from_slice(MAGIC);
+    header[4] = FORMAT_VERSION;
+    header[5] = ALGORITHM_AES_256_GCM;
+    header[6..10].copy_from_slice(&(chunk_size as u32).to_le_bytes());
+    random_fill(&mut header[10..])?;
+
+    inner.write_all(&header)?;
+
+    Ok(Self {
+      inner,
+      cipher: Aes256Gcm::new(key.as_bytes().into()),
+      header,
+      chunk_size,
+      chunk_index: 0,
+      buffer: Vec::with_capacity(chunk_size),
+    })
+  }
+
+  /// Encrypts and writes the final chunk, flushes the underlying stream, and
+  /// returns it.
+  ///
+  pub fn finish(mut self) -> Result<W, IoError> {
+    let buffer = core::mem::take(&mut self.buffer);
+    self.write_chunk(&buffer, true)?;
+    self.inner.flush()?;
+
+    Ok(self.inner)
+  }
+
+  fn write_chunk(
+    &mut self,
+    plaintext: &[u8],
+    is_final: bool,
+  ) -> Result<(), IoError> {
+    let nonce = chunk_nonce(&self.header, self.chunk_index, is_final)?;
+
+    let ciphertext = self
+      .cipher
+      .encrypt(
+        Nonce::from_slice(&nonce),
+        Payload {
+          msg: plaintext,
+          aad: &self.header,
+        },
+      )
+      .map_err(|_| IoError::other("Encryption failed"))?;
+
+    self.inner.write_all(&ciphertext)?;
+    self.chunk_index = self.chunk_index.wrapping_add(1);
+
+    Ok(())
+  }
+}
+
+impl<W: IoWrite> Write for EncryptingWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> Result<usize, IoError> {
+    self.buffer.extend_from_slice(buf);
+
+    // Write chunks while there is more data buffered than fits in one chunk.
+    // The last chunk is held back because only the final chunk can be empty or
+    // partially filled, and it isn't known which chunk is final until
+    // `finish()` is called.
+    if self.buffer.len() > self.chunk_size {
+      let buffer = core::mem::take(&mut self.buffer);
+
+      let mut offset = 0;
+      while buffer.len() - offset > self.chunk_size {
+        self.write_chunk(&buffer[offset..offset + self.chunk_size], false)?;
+        offset += self.chunk_size;
+      }
+
+      self.buffer = buffer;
+      self.buffer.drain(0..offset);
+    }
+
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> Result<(), IoError> {
+    self.inner.flush()
+  }
+}
+
+/// Reads an encrypted envelope from an underlying read stream and decrypts it.
+/// An error is returned if the data has been modified or truncated, or if the
+/// key is incorrect.
+///
+pub struct DecryptingReader<R: IoRead> {
+  inner: R,
+  cipher: Aes256Gcm,
+  header: [u8; HEADER_SIZE],
+  chunk_size: usize,
+  chunk_index: u32,
+  plaintext: Vec<u8>,
+  plaintext_offset: usize,
+  is_complete: bool,
+}
+
+impl<R: IoRead> DecryptingReader<R> {
+  /// Creates a new decrypting reader that reads from the given stream. The
+  /// header of the encrypted envelope is read and validated immediately.
+  ///
+  pub fn new(mut inner: R, key: &EncryptionKey) -> Result<Self, IoError> {
+    let mut header = [0u8; HEADER_SIZE];
+    inner.read_exact(&mut header)?;
+
+    if !is_encrypted_bytes(&header) {
+      return Err(invalid_data_error("Encrypted envelope header is invalid"));
+    }
+
+    if header[4] != FORMAT_VERSION {
+      return Err(invalid_data_error(
+        "Encrypted envelope format version is not supported",
+      ));
+    }
+
+    if header[5] != ALGORITHM_AES_256_GCM {
+      return Err(invalid_data_error(
+        "Encrypted envelope algorithm is not supported",
+      ));
+    }
+
+    let chunk_size =
+      u32::from_le_bytes(header[6..10].try_into().unwrap()) as usize;
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+      return Err(invalid_data_error(
+        "Encrypted envelope chunk size is invalid",
+      ));
+    }
+
+    Ok(Self {
+      inner,
+      cipher: Aes256Gcm::new(key.as_bytes().into()),
+      header,
+      chunk_size,
+      chunk_index: 0,
+      plaintext: vec![],
+      plaintext_offset: 0,
+      is_complete: false,
+    })
+  }
+
+  /// Reads and decrypts the next chunk into the plaintext buffer.
+  ///
+  fn read_chunk(&mut self) -> Result<(), IoError> {
+    let mut ciphertext = vec![0u8; self.chunk_size + TAG_SIZE];
+
+    let mut length = 0;
+    while length < ciphertext.len() {
+      match self.inner.read(&mut ciphertext[length..])? {
+        0 => break,
+        n => length += n,
+      }
+    }
+    ciphertext.truncate(length);
+
+    // A full chunk may or may not be the final chunk, so try decrypting it as
+    // a non-final chunk first. A partial chunk must be the final chunk.
+    if length == self.chunk_size + TAG_SIZE
+      && let Ok(plaintext) = self.decrypt_chunk(&ciphertext, false)
+    {
+      self.plaintext = plaintext;
+    } else {
+      self.plaintext = self.decrypt_chunk(&ciphertext, true)?;
+      self.is_complete = true;
+
+      // There must be no data following the final chunk
+      if self.inner.read(&mut [0u8])? != 0 {
+        return Err(invalid_data_error(
+          "Encrypted envelope has data after its final chunk",
+        ));
+      }
+    }
+
+    self.plaintext_offset = 0;
+    self.chunk_index = self.chunk_index.wrapping_add(1);
+
+    Ok(())
+  }
+
+  fn decrypt_chunk(
+    &self,
+    ciphertext: &[u8],
+    is_final: bool,
+  ) -> Result<Vec<u8>, IoError> {
+    let nonce = chunk_nonce(&self.header, self.chunk_index, is_final)?;
+
+    self
+      .cipher
+      .decrypt(
+        Nonce::from_slice(&nonce),
+        Payload {
+          msg: ciphertext,
+          aad: &self.header,
+        },
+      )
+      .map_err(|_| {
+        invalid_data_error(
+          "Decryption failed because the data is modified or truncated, or \
+           the key is incorrect",
+        )
+      })
+  }
+}
+
+impl<R: IoRead> Read for DecryptingReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+    while self.plaintext_offset == self.plaintext.len() {
+      if self.is_complete {
+        return Ok(0);
+      }
+
+      self.read_chunk()?;
+    }
+
+    let length = buf.len().min(self.plaintext.len() - self.plaintext_offset);
+    buf[..length].copy_from_slice(
+      &self.plaintext[self.plaintext_offset..self.plaintext_offset + length],
+    );
+    self.plaintext_offset += length;
+
+    Ok(length)
+  }
+}
+
+/// Returns the nonce for the chunk at the given index. Chunk indexes can't be
+/// reused, so the number of chunks is limited to 2^32.
+///
+fn chunk_nonce(
+  header: &[u8; HEADER_SIZE],
+  chunk_index: u32,
+  is_final: bool,
+) -> Result<[u8; 12], IoError> {
+  if chunk_index == u32::MAX {
+    return Err(IoError::other("Encrypted envelope chunk limit exceeded"));
+  }
+
+  let mut nonce = [0u8; 12];
+  nonce[0..NONCE_PREFIX_SIZE]
+    .copy_from_slice(&header[HEADER_SIZE - NONCE_PREFIX_SIZE..]);
+  nonce[7..11].copy_from_slice(&chunk_index.to_be_bytes());
+  nonce[11] = u8::from(is_final);
+
+  Ok(nonce)
+}
+
+fn random_fill(bytes: &mut [u8]) -> Result<(), IoError> {
+  getrandom::fill(bytes).map_err(|e| IoError::other(e.to_string()))
+}
+
+fn invalid_data_error(details: &str) -> IoError {
+  IoError::new(std::io::ErrorKind::InvalidData, details)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx_core::{DataSet, dictionary};
+
+  fn encrypt(
+    plaintext: &[u8],
+    key: &EncryptionKey,
+    chunk_size: usize,
+  ) -> Vec<u8> {
+    let mut writer =
+      EncryptingWriter::new_with_chunk_size(vec![], key, chunk_size).unwrap();
+    writer.write_all(plaintext).unwrap();
+    writer.finish().unwrap()
+  }
+
+  fn decrypt(
+    ciphertext: &[u8],
+    key: &EncryptionKey,
+  ) -> Result<Vec<u8>, IoError> {
+    let mut reader = DecryptingReader::new(ciphertext, key)?;
+
+    let mut plaintext = vec![];
+    reader.read_to_end(&mut plaintext)?;
+
+    Ok(plaintext)
+  }
+
+  #[test]
+  fn round_trip_test() {
+    let key = EncryptionKey::generate().unwrap();
+
+    for length in [0, 1, 7, 8, 9, 16, 100] {
+      let plaintext: Vec<u8> = (0..length).map(|i| i as u8).collect();
+      let ciphertext = encrypt(&plaintext, &key, 8);
+
+      assert!(is_encrypted_bytes(&ciphertext));
+      assert_eq!(
+        ciphertext.len(),
+        HEADER_SIZE + length + (length.max(1).div_ceil(8)) * TAG_SIZE
+      );
+      assert_eq!(decrypt(&ciphertext, &key).unwrap(), plaintext);
+    }
+  }
+
+  #[test]
+  fn detects_tampering_test() {
+    let key = EncryptionKey::new([1; 32]);
+    let plaintext = [5u8; 20];
+    let ciphertext = encrypt(&plaintext, &key, 8);
+
+    // Wrong key
+    assert!(decrypt(&ciphertext, &EncryptionKey::new([2; 32])).is_err());
+
+    // Modified header
+    let mut modified = ciphertext.clone();
+    modified[HEADER_SIZE - 1] ^= 1;
+    assert!(decrypt(&modified, &key).is_err());
+
+    // Modified chunk
+    let mut modified = ciphertext.clone();
+    modified[HEADER_SIZE + 3] ^= 1;
+    assert!(decrypt(&modified, &key).is_err());
+
+    // Truncated at a chunk boundary
+    let truncated = &ciphertext[0..HEADER_SIZE + 2 * (8 + TAG_SIZE)];
+    assert!(decrypt(truncated, &key).is_err());
+
+    // Trailing data
+    let mut extended = ciphertext.clone();
+    extended.push(0);
+    assert!(decrypt(&extended, &key).is_err());
+  }
+
+  #[test]
+  fn data_set_round_trip_test() {
+    let key = EncryptionKey::generate().unwrap();
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["12345"])
+      .unwrap();
+
+    let mut writer = EncryptingWriter::new(vec![], &key).unwrap();
+    crate::write_stream(&mut writer, &data_set, None).unwrap();
+    let ciphertext = writer.finish().unwrap();
+
+    let mut reader =
+      DecryptingReader::new(ciphertext.as_slice(), &key).unwrap();
+    let read_data_set = crate::read_stream(&mut reader, None).unwrap();
+
+    assert_eq!(
+      read_data_set.get_string(dictionary::PATIENT_ID.tag),
+      Ok("12345")
+    );
+  }
+}
@@ -0,0 +1,173 @@
+//! Detection and generation of the 128-byte File Preamble that precedes the
+//! `DICM` prefix in DICOM P10 data.
+//!
+//! The File Preamble is usually all zero bytes, however applications are
+//! permitted to store their own data in it. The most common use of this is
+//! dual-personality DICOM/TIFF files, as used in digital pathology, which store
+//! a TIFF or BigTIFF header in the File Preamble so that the same file can be
+//! read as both a DICOM P10 file and a TIFF file.
+
+/// The kind of content stored in a File Preamble.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilePreambleKind {
+  /// The File Preamble contains only zero bytes.
+  Zero,
+
+  /// The File Preamble starts with a TIFF header.
+  Tiff {
+    big_endian: bool,
+    first_ifd_offset: u32,
+  },
+
+  /// The File Preamble starts with a BigTIFF header.
+  BigTiff {
+    big_endian: bool,
+    first_ifd_offset: u64,
+  },
+
+  /// The File Preamble contains application-specific data that isn't
+  /// recognized.
+  Other,
+}
+
+impl FilePreambleKind {
+  /// Returns whether this File Preamble holds a TIFF or BigTIFF header, which
+  /// means the DICOM P10 data is also readable as a TIFF file.
+  ///
+  pub fn is_tiff(&self) -> bool {
+    matches!(self, Self::Tiff { .. } | Self::BigTiff { .. })
+  }
+}
+
+/// Detects the kind of content stored in a File Preamble.
+///
+pub fn detect(preamble: &[u8; 128]) -> FilePreambleKind {
+  if preamble.iter().all(|b| *b == 0) {
+    return FilePreambleKind::Zero;
+  }
+
+  let big_endian = match &preamble[0..2] {
+    b"II" => false,
+    b"MM" => true,
+    _ => return FilePreambleKind::Other,
+  };
+
+  let read_u16 = |bytes: &[u8]| {
+    let bytes = [bytes[0], bytes[1]];
+    if big_endian {
+      u16::from_be_bytes(bytes)
+    } else {
+      u16::from_le_bytes(bytes)
+    }
+  };
+
+  match read_u16(&preamble[2..4]) {
+    42 => {
+      let bytes = preamble[4..8].try_into().unwrap();
+      let first_ifd_offset = if big_endian {
+        u32::from_be_bytes(bytes)
+      } else {
+        u32::from_le_bytes(bytes)
+      };
+
+      FilePreambleKind::Tiff {
+        big_endian,
+        first_ifd_offset,
+      }
+    }
+
+    // BigTIFF headers specify a byte size of 8 for offsets followed by a
+    // reserved zero value
+    43 if read_u16(&preamble[4..6]) == 8 && read_u16(&preamble[6..8]) == 0 => {
+      let bytes = preamble[8..16].try_into().unwrap();
+      let first_ifd_offset = if big_endian {
+        u64::from_be_bytes(bytes)
+      } else {
+        u64::from_le_bytes(bytes)
+      };
+
+      FilePreambleKind::BigTiff {
+        big_endian,
+        first_ifd_offset,
+      }
+    }
+
+    _ => FilePreambleKind::Other,
+  }
+}
+
+/// Creates a File Preamble holding a little endian TIFF header whose first
+/// Image File Directory (IFD) is at the given offset from the start of the
+/// DICOM P10 data. The rest of the File Preamble is zero bytes.
+///
+/// The IFD itself, and the image data it references, must be stored by the
+/// caller at the specified offset, e.g. in the value of a Data Set Trailing
+/// Padding data element, or the File Preamble itself if it is small enough.
+///
+pub fn new_tiff(first_ifd_offset: u32) -> [u8; 128] {
+  let mut preamble = [0u8; 128];
+
+  preamble[0..4].copy_from_slice(b"II*\0");
+  preamble[4..8].copy_from_slice(&first_ifd_offset.to_le_bytes());
+
+  preamble
+}
+
+/// Creates a File Preamble holding a little endian BigTIFF header whose first
+/// Image File Directory (IFD) is at the given offset from the start of the
+/// DICOM P10 data. BigTIFF is needed when the DICOM P10 data exceeds 4 GiB.
+///
+/// See [`new_tiff()`] for details.
+///
+pub fn new_big_tiff(first_ifd_offset: u64) -> [u8; 128] {
+  let mut preamble = [0u8; 128];
+
+  preamble[0..8].copy_from_slice(b"II+\0\x08\0\0\0");
+  preamble[8..16].copy_from_slice(&first_ifd_offset.to_le_bytes());
+
+  preamble
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn detect_test() {
+    assert_eq!(detect(&[0; 128]), FilePreambleKind::Zero);
+
+    assert_eq!(
+      detect(&new_tiff(0x1234)),
+      FilePreambleKind::Tiff {
+        big_endian: false,
+        first_ifd_offset: 0x1234
+      }
+    );
+
+    assert_eq!(
+      detect(&new_big_tiff(0x1_0000_0000)),
+      FilePreambleKind::BigTiff {
+        big_endian: false,
+        first_ifd_offset: 0x1_0000_0000
+      }
+    );
+
+    let mut preamble = [0u8; 128];
+    preamble[0..8].copy_from_slice(b"MM\0*\0\0\0\x08");
+    assert_eq!(
+      detect(&preamble),
+      FilePreambleKind::Tiff {
+        big_endian: true,
+        first_ifd_offset: 8
+      }
+    );
+
+    preamble[0..8].copy_from_slice(b"II+\0\x04\0\0\0");
+    assert_eq!(detect(&preamble), FilePreambleKind::Other);
+
+    preamble[0..4].copy_from_slice(b"ABCD");
+    assert_eq!(detect(&preamble), FilePreambleKind::Other);
+    assert!(!detect(&preamble).is_tiff());
+  }
+}
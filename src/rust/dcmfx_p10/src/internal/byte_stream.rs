@@ -175,6 +175,26 @@ impl ByteStream {
     }
   }
 
+  /// Reads up to the given number of bytes out of a byte stream, returning as
+  /// many as are currently available. An empty slice is returned once the
+  /// final bytes have been written and there are none left to read.
+  ///
+  pub fn read_available(
+    &mut self,
+    max_byte_count: usize,
+  ) -> Result<RcByteSlice, ByteStreamError> {
+    self.inflate_up_to_read_size(max_byte_count)?;
+
+    let byte_count =
+      core::cmp::min(max_byte_count as u64, self.bytes_queue_size) as usize;
+
+    if byte_count == 0 && !self.is_writing_finished {
+      return Err(ByteStreamError::DataRequired);
+    }
+
+    self.read(byte_count)
+  }
+
   /// Peeks at the next bytes that will be read out of a byte stream without
   /// actually consuming them.
   ///
@@ -23,12 +23,11 @@
 //!    requires the *'(0028,0103) Pixel Representation'* data element's value.
 
 #[cfg(feature = "std")]
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[cfg(not(feature = "std"))]
 use alloc::{
-  collections::BTreeMap,
-  format,
+  collections::{BTreeMap, BTreeSet},
   string::{String, ToString},
   vec,
   vec::Vec,
@@ -36,7 +35,8 @@ use alloc::{
 
 use dcmfx_character_set::{self, SpecificCharacterSet, StringType};
 use dcmfx_core::{
-  DataElementTag, RcByteSlice, ValueRepresentation, dictionary, utils,
+  DataElementTag, ErrorDetails, RcByteSlice, ValueRepresentation, dictionary,
+  error_details, utils,
 };
 
 use crate::{P10Error, P10Token, internal::value_length::ValueLength};
@@ -58,6 +58,7 @@ enum LocationEntry {
   RootDataSet {
     clarifying_data_elements: ClarifyingDataElements,
     last_data_element_tag: DataElementTag,
+    data_element_tags: BTreeSet<DataElementTag>,
   },
   Sequence {
     tag: DataElementTag,
@@ -68,10 +69,21 @@ enum LocationEntry {
   Item {
     clarifying_data_elements: ClarifyingDataElements,
     last_data_element_tag: DataElementTag,
+    data_element_tags: BTreeSet<DataElementTag>,
     ends_at: Option<u64>,
   },
 }
 
+/// The result of checking the order of a data element tag against the data
+/// elements that preceded it at the current P10 location.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataElementOrder {
+  Ascending,
+  Duplicate,
+  NotAscending,
+}
+
 /// The data elements needed to determine VRs of some data elements when the
 /// transfer syntax is 'Implicit VR Little Endian', and to decode non-UTF-8
 /// string data.
@@ -131,6 +143,7 @@ impl P10Location {
       entries: vec![LocationEntry::RootDataSet {
         clarifying_data_elements: ClarifyingDataElements::default(),
         last_data_element_tag: DataElementTag::ZERO,
+        data_element_tags: BTreeSet::new(),
       }],
     }
   }
@@ -143,30 +156,46 @@ impl P10Location {
   /// fashion because lower numbered data elements are sometimes used in the
   /// interpretation of higher numbered data elements.
   ///
+  /// A tag equal to the previous one is reported as a duplicate. If
+  /// `track_all_tags` is set then every tag at the current location is
+  /// remembered, which allows duplicates to also be detected when data
+  /// elements are not in ascending order.
+  ///
   pub fn check_data_element_ordering(
     &mut self,
     tag: DataElementTag,
-  ) -> Result<(), ()> {
+    track_all_tags: bool,
+  ) -> DataElementOrder {
     match self.entries.last_mut() {
       Some(LocationEntry::RootDataSet {
         last_data_element_tag,
+        data_element_tags,
         ..
       })
       | Some(LocationEntry::Item {
         last_data_element_tag,
+        data_element_tags,
         ..
       }) => {
-        if tag > *last_data_element_tag {
+        let is_duplicate = if track_all_tags {
+          !data_element_tags.insert(tag)
+        } else {
+          tag == *last_data_element_tag
+        };
+
+        if is_duplicate {
+          DataElementOrder::Duplicate
+        } else if tag > *last_data_element_tag {
           *last_data_element_tag = tag;
-          Ok(())
+          DataElementOrder::Ascending
         } else {
-          Err(())
+          DataElementOrder::NotAscending
         }
       }
 
-      Some(LocationEntry::Sequence { .. }) => Ok(()),
-
-      None => Err(()),
+      Some(LocationEntry::Sequence { .. }) | None => {
+        DataElementOrder::Ascending
+      }
     }
   }
 
@@ -300,7 +329,7 @@ impl P10Location {
     tag: DataElementTag,
     is_implicit_vr: bool,
     ends_at: Option<u64>,
-  ) -> Result<(), String> {
+  ) -> Result<(), ErrorDetails> {
     match self.entries.last() {
       Some(LocationEntry::RootDataSet { .. })
       | Some(LocationEntry::Item { .. }) => {
@@ -319,7 +348,7 @@ impl P10Location {
           .active_clarifying_data_elements()
           .private_creator_for_tag(tag);
 
-        Err(format!(
+        Err(error_details!(
           "Sequence data element '{}' encountered outside of the root data set \
             or an item",
           dictionary::tag_with_name(tag, private_creator.map(|x| x.as_str()))
@@ -330,7 +359,7 @@ impl P10Location {
 
   /// Ends the current sequence for a P10 location.
   ///
-  pub fn end_sequence(&mut self) -> Result<DataElementTag, String> {
+  pub fn end_sequence(&mut self) -> Result<DataElementTag, ErrorDetails> {
     match self.entries.last() {
       Some(LocationEntry::Sequence { tag, .. }) => {
         let tag = *tag;
@@ -338,9 +367,7 @@ impl P10Location {
         Ok(tag)
       }
 
-      _ => {
-        Err("Sequence delimiter encountered outside of a sequence".to_string())
-      }
+      _ => Err("Sequence delimiter encountered outside of a sequence".into()),
     }
   }
 
@@ -359,7 +386,7 @@ impl P10Location {
     &mut self,
     ends_at: Option<u64>,
     length: ValueLength,
-  ) -> Result<usize, String> {
+  ) -> Result<usize, ErrorDetails> {
     match self.entries.last_mut() {
       // Carry across the current clarifying data elements as the initial state
       // for the new item
@@ -373,13 +400,14 @@ impl P10Location {
             .active_clarifying_data_elements()
             .clone(),
           last_data_element_tag: DataElementTag::ZERO,
+          data_element_tags: BTreeSet::new(),
           ends_at,
         });
 
         Ok(index)
       }
 
-      _ => Err(format!(
+      _ => Err(error_details!(
         "Item encountered outside of a sequence, length: {length}",
       )),
     }
@@ -387,14 +415,14 @@ impl P10Location {
 
   /// Ends the current item for a P10 location.
   ///
-  pub fn end_item(&mut self) -> Result<(), String> {
+  pub fn end_item(&mut self) -> Result<(), ErrorDetails> {
     match self.entries.last() {
       Some(LocationEntry::Item { .. }) => {
         self.entries.pop();
         Ok(())
       }
 
-      _ => Err("Item delimiter encountered outside of an item".to_string()),
+      _ => Err("Item delimiter encountered outside of an item".into()),
     }
   }
 
@@ -482,7 +510,7 @@ impl P10Location {
       core::str::from_utf8(value_bytes).map_err(|_| {
         P10Error::SpecificCharacterSetInvalid {
           specific_character_set: utils::inspect_u8_slice(value_bytes, 64),
-          details: "Invalid UTF-8".to_string(),
+          details: "Invalid UTF-8".into(),
         }
       })?;
 
@@ -494,7 +522,7 @@ impl P10Location {
     )
     .map_err(|details| P10Error::SpecificCharacterSetInvalid {
       specific_character_set: specific_character_set.chars().take(64).collect(),
-      details,
+      details: details.into(),
     })?;
 
     *value_bytes = b"ISO_IR 192".to_vec().into();
@@ -0,0 +1,962 @@
+//! A read-only data set that is backed by raw DICOM P10 bytes, and only
+//! materializes data element values when they are accessed.
+//!
+//! Creating a lazy data set only reads the headers of the data elements in the
+//! root data set, and skips over their values. The exception is sequences and
+//! encapsulated pixel data with an undefined length, for which the headers of
+//! their items and nested data elements are read in order to find where they
+//! end. Values are then read from their bytes when they are accessed.
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::ToString, vec, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "std"))]
+use core::cell::OnceCell;
+
+#[cfg(feature = "std")]
+use std::sync::OnceLock as OnceCell;
+
+use core::ops::Range;
+
+use byteorder::ByteOrder;
+
+use dcmfx_core::{
+  DataElementTag, DataElementValue, DataError, DataSet, DataSetPath,
+  RcByteSlice, TransferSyntax, ValueRepresentation, dictionary, error_details,
+  transfer_syntax,
+};
+
+use crate::internal::byte_stream::ByteStream;
+use crate::internal::data_element_header::{
+  DataElementHeader, ValueLengthSize,
+};
+use crate::internal::p10_location;
+use crate::{
+  DataSetBuilder, DuplicateDataElementPolicy, P10Error, P10ReadConfig,
+  P10ReadContext, P10Token, TrailingDataPolicy,
+};
+
+/// A read-only data set that indexes the data elements in the root data set of
+/// DICOM P10 bytes by reading only their headers, and defers reading their
+/// values until they are accessed.
+///
+/// The index holds the byte range of each data element, and its bytes are read
+/// as slices of the original [`RcByteSlice`], so no copies of them are made.
+/// When the transfer syntax is deflated, the data set is inflated into memory
+/// when the lazy data set is created. Once a data element's value has been
+/// materialized it is cached and subsequent accesses return the cached value.
+///
+#[derive(Debug)]
+pub struct LazyDataSet {
+  config: Option<P10ReadConfig>,
+  file_meta_information: DataSet,
+  header_bytes: RcByteSlice,
+  data_set_bytes: RcByteSlice,
+  clarifying_data_elements: Vec<Range<usize>>,
+  data_elements: BTreeMap<DataElementTag, LazyDataElement>,
+}
+
+/// A data element in a lazy data set. Holds the range of the data set bytes
+/// that its value is read from when it is materialized.
+///
+/// A data element inserted by the read context has no header of its own, and
+/// its range is that of the bytes that cause it to be inserted.
+///
+#[derive(Debug)]
+struct LazyDataElement {
+  offset: Option<u64>,
+  range: Range<usize>,
+  value: OnceCell<DataElementValue>,
+}
+
+/// The value length that indicates a data element has an undefined length.
+///
+const UNDEFINED_LENGTH: u32 = 0xFFFFFFFF;
+
+impl LazyDataSet {
+  /// Creates a new lazy data set from the passed DICOM P10 bytes. The data
+  /// elements in the root data set are indexed, but their values aren't
+  /// materialized until they're accessed.
+  ///
+  /// The File Meta Information is always fully materialized.
+  ///
+  /// When duplicate data elements are allowed by the read config, only the
+  /// occurrence specified by its [`DuplicateDataElementPolicy`] is kept.
+  ///
+  pub fn from_bytes(
+    bytes: RcByteSlice,
+    config: Option<P10ReadConfig>,
+  ) -> Result<Self, P10Error> {
+    let mut context = P10ReadContext::new(config);
+    context.write_bytes(bytes.clone(), true)?;
+
+    // Read the File Meta Information, after which the read context is at the
+    // start of the data set
+    let file_meta_information = loop {
+      let file_meta_information =
+        context
+          .read_tokens()?
+          .into_iter()
+          .find_map(|token| match token {
+            P10Token::FileMetaInformation { data_set } => Some(data_set),
+            _ => None,
+          });
+
+      if let Some(file_meta_information) = file_meta_information {
+        break file_meta_information;
+      }
+    };
+
+    let data_set_offset = context.bytes_read();
+    let mut transfer_syntax = context.transfer_syntax();
+
+    let mut data_set_bytes = bytes.drop(data_set_offset as usize);
+
+    // A deflated data set is inflated in full, and its values are then read
+    // as if they used the equivalent transfer syntax that isn't deflated
+    if transfer_syntax.is_deflated {
+      data_set_bytes = inflate(data_set_bytes, data_set_offset)?;
+      transfer_syntax = &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN;
+    }
+
+    let HeaderIndex {
+      clarifying_data_elements,
+      data_elements,
+      ..
+    } = HeaderIndex::new(
+      &data_set_bytes,
+      data_set_offset,
+      transfer_syntax,
+      config.unwrap_or_default(),
+    )?;
+
+    Ok(Self {
+      config,
+      file_meta_information,
+      header_bytes: p10_header_bytes(transfer_syntax),
+      data_set_bytes,
+      clarifying_data_elements,
+      data_elements,
+    })
+  }
+
+  /// Returns the File Meta Information of a lazy data set.
+  ///
+  pub fn file_meta_information(&self) -> &DataSet {
+    &self.file_meta_information
+  }
+
+  /// Returns the number of data elements in the root of a lazy data set.
+  ///
+  pub fn size(&self) -> usize {
+    self.data_elements.len()
+  }
+
+  /// Returns whether a lazy data set contains no data elements.
+  ///
+  pub fn is_empty(&self) -> bool {
+    self.data_elements.is_empty()
+  }
+
+  /// Returns whether a data element with the specified tag exists in the root
+  /// of a lazy data set.
+  ///
+  pub fn has(&self, tag: DataElementTag) -> bool {
+    self.data_elements.contains_key(&tag)
+  }
+
+  /// Returns the tags of the data elements in the root of a lazy data set, in
+  /// ascending order.
+  ///
+  pub fn tags(&self) -> Vec<DataElementTag> {
+    self.data_elements.keys().copied().collect()
+  }
+
+  /// Returns the byte offset of the header of the data element with the
+  /// specified tag in the DICOM P10 bytes. As with
+  /// [`P10ReadContext::bytes_read()`], offsets are counted after inflation when
+  /// the transfer syntax is deflated.
+  ///
+  /// Returns `None` if the data element isn't present, or if it was inserted
+  /// by the read context, e.g. a *'(0008,0005) Specific Character Set'* data
+  /// element added when string values are converted to UTF-8.
+  ///
+  pub fn offset(&self, tag: DataElementTag) -> Option<u64> {
+    self
+      .data_elements
+      .get(&tag)
+      .and_then(|data_element| data_element.offset)
+  }
+
+  /// Returns whether the value of the data element with the specified tag has
+  /// been materialized.
+  ///
+  pub fn is_materialized(&self, tag: DataElementTag) -> bool {
+    self
+      .data_elements
+      .get(&tag)
+      .is_some_and(|data_element| data_element.value.get().is_some())
+  }
+
+  /// Returns the data element value for the specified tag in a lazy data set,
+  /// materializing it if this hasn't already been done.
+  ///
+  /// If materializing the value fails then the error is returned as an invalid
+  /// value error.
+  ///
+  pub fn get_value(
+    &self,
+    tag: DataElementTag,
+  ) -> Result<&DataElementValue, DataError> {
+    match self.data_elements.get(&tag) {
+      Some(data_element) => self.value(tag, data_element).map_err(|e| {
+        DataError::new_value_invalid(e.details().to_string())
+          .with_path(&DataSetPath::new_with_data_element(tag))
+      }),
+      None => Err(
+        DataError::new_tag_not_present()
+          .with_path(&DataSetPath::new_with_data_element(tag)),
+      ),
+    }
+  }
+
+  /// Returns the singular string value for a data element in a lazy data set.
+  ///
+  /// See [`DataSet::get_string()`].
+  ///
+  pub fn get_string(&self, tag: DataElementTag) -> Result<&str, DataError> {
+    self
+      .get_value(tag)?
+      .get_string()
+      .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(tag)))
+  }
+
+  /// Materializes all data elements in a lazy data set into a new in-memory
+  /// data set that also includes the File Meta Information. This gives the
+  /// same result as reading the DICOM P10 bytes with [`crate::read_bytes()`].
+  ///
+  pub fn to_data_set(&self) -> Result<DataSet, P10Error> {
+    let mut data_set = self.file_meta_information.clone();
+
+    for (tag, data_element) in self.data_elements.iter() {
+      data_set.insert(*tag, self.value(*tag, data_element)?.clone());
+    }
+
+    Ok(data_set)
+  }
+
+  /// Returns the value of a lazy data element, materializing it if this hasn't
+  /// already been done.
+  ///
+  /// The value is materialized by reading a minimal DICOM P10 header followed
+  /// by the bytes of the clarifying data elements that precede the data
+  /// element, such as *'(0008,0005) Specific Character Set'*, and then the
+  /// bytes of the data element itself. This means that its value is read in
+  /// the same way as when reading the whole data set.
+  ///
+  fn value<'a>(
+    &self,
+    tag: DataElementTag,
+    data_element: &'a LazyDataElement,
+  ) -> Result<&'a DataElementValue, P10Error> {
+    if let Some(value) = data_element.value.get() {
+      return Ok(value);
+    }
+
+    let range = &data_element.range;
+
+    let error = |details: &str| P10Error::DataInvalid {
+      when: "Materializing lazy data element value".into(),
+      details: details.to_string().into(),
+      path: DataSetPath::new_with_data_element(tag),
+      offset: data_element.offset.unwrap_or(0),
+    };
+
+    let mut context = P10ReadContext::new(self.config);
+    context.write_bytes(self.header_bytes.clone(), false)?;
+
+    for clarifying_data_element in self
+      .clarifying_data_elements
+      .iter()
+      .filter(|r| r.end <= range.start)
+    {
+      context.write_bytes(
+        self
+          .data_set_bytes
+          .slice(clarifying_data_element.start, clarifying_data_element.end),
+        false,
+      )?;
+    }
+
+    context
+      .write_bytes(self.data_set_bytes.slice(range.start, range.end), true)?;
+
+    let mut builder = DataSetBuilder::new_for_read_config(self.config);
+
+    let value = loop {
+      let tokens = context.read_tokens().map_err(|e| error(e.details()))?;
+      builder
+        .add_tokens(&tokens)
+        .map_err(|e| error(e.details()))?;
+
+      if let Ok(mut data_set) = builder.final_data_set() {
+        break data_set.delete(tag).ok_or_else(|| {
+          error("Data element bytes did not produce a value")
+        })?;
+      }
+    };
+
+    // If another thread materialized the value first then its value is kept
+    let _ = data_element.value.set(value);
+
+    Ok(data_element.value.get().unwrap())
+  }
+}
+
+/// The index of the data elements in the root data set that is built by
+/// reading only their headers.
+///
+struct HeaderIndex<'a> {
+  bytes: &'a [u8],
+  offset: u64,
+  config: P10ReadConfig,
+  clarifying_data_elements: Vec<Range<usize>>,
+  data_elements: BTreeMap<DataElementTag, LazyDataElement>,
+}
+
+/// A data element header read when building a [`HeaderIndex`], along with its
+/// size in bytes.
+///
+struct IndexedHeader {
+  tag: DataElementTag,
+  vr: Option<ValueRepresentation>,
+  length: u32,
+  size: usize,
+}
+
+impl<'a> HeaderIndex<'a> {
+  /// Builds the index of the data elements in the root of the passed data set
+  /// bytes. The offset of the data set bytes in the DICOM P10 data is used for
+  /// the offsets of the data elements.
+  ///
+  /// The handling of the Specific Character Set, trailing padding, trailing
+  /// bytes, and duplicate and out of order data elements matches that of
+  /// [`P10ReadContext`].
+  ///
+  fn new(
+    bytes: &'a [u8],
+    offset: u64,
+    transfer_syntax: &TransferSyntax,
+    config: P10ReadConfig,
+  ) -> Result<Self, P10Error> {
+    let mut index = Self {
+      bytes,
+      offset,
+      config,
+      clarifying_data_elements: vec![],
+      data_elements: BTreeMap::new(),
+    };
+
+    let mut position = 0;
+    let mut previous_tag = None;
+    let mut has_specific_character_set = false;
+    let mut has_trailing_padding = false;
+
+    while position < bytes.len() {
+      // Trailing bytes that can't be read as a data element end the data set.
+      // When they're preserved the read context emits them as a '(FFFC,FFFC)
+      // Data Set Trailing Padding' data element.
+      if index.is_at_trailing_bytes(position, transfer_syntax) {
+        match config.trailing_data_policy {
+          TrailingDataPolicy::Error => {
+            return Err(P10Error::DataInvalid {
+              when: "Reading data element header".into(),
+              details: "Trailing bytes following the final data element \
+                can't be read as a data element"
+                .into(),
+              path: DataSetPath::new(),
+              offset: index.offset + position as u64,
+            });
+          }
+
+          TrailingDataPolicy::Preserve if !has_trailing_padding => {
+            let range = position..bytes.len();
+
+            if !has_specific_character_set {
+              index.insert_data_element(
+                dictionary::SPECIFIC_CHARACTER_SET.tag,
+                None,
+                range.clone(),
+              );
+            }
+
+            index.insert_data_element(
+              dictionary::DATA_SET_TRAILING_PADDING.tag,
+              None,
+              range,
+            );
+          }
+
+          _ => (),
+        }
+
+        break;
+      }
+
+      let header = index.read_header(position, transfer_syntax)?;
+      let tag = header.tag;
+
+      let end =
+        index.value_end(position + header.size, &header, transfer_syntax, 0)?;
+      let range = position..end;
+      let header_offset = index.offset + position as u64;
+
+      position = end;
+
+      // The read context inserts a Specific Character Set data element that
+      // specifies UTF-8 when the data set doesn't have one
+      if !has_specific_character_set
+        && tag >= dictionary::SPECIFIC_CHARACTER_SET.tag
+      {
+        if tag > dictionary::SPECIFIC_CHARACTER_SET.tag {
+          index.insert_data_element(
+            dictionary::SPECIFIC_CHARACTER_SET.tag,
+            None,
+            range.clone(),
+          );
+        }
+
+        has_specific_character_set = true;
+      }
+
+      // Sequence delimiters outside of a sequence are ignored
+      if tag == dictionary::SEQUENCE_DELIMITATION_ITEM.tag {
+        continue;
+      }
+
+      if tag == dictionary::ITEM.tag
+        || tag == dictionary::ITEM_DELIMITATION_ITEM.tag
+      {
+        return Err(P10Error::DataInvalid {
+          when: "Reading data element header".into(),
+          details: error_details!(
+            "Data element '{}' is not in a sequence",
+            dictionary::tag_with_name(tag, None)
+          ),
+          path: DataSetPath::new(),
+          offset: header_offset,
+        });
+      }
+
+      index.check_data_element_ordering(tag, previous_tag, header_offset)?;
+      previous_tag = Some(tag);
+
+      if p10_location::is_clarifying_data_element(tag) {
+        index.clarifying_data_elements.push(range.clone());
+      }
+
+      // Group length data elements are discarded, as is trailing padding
+      // unless it's being preserved
+      if tag == dictionary::DATA_SET_TRAILING_PADDING.tag {
+        has_trailing_padding = true;
+
+        if config.trailing_data_policy != TrailingDataPolicy::Preserve {
+          continue;
+        }
+      }
+
+      if tag.element == 0x0000 && header.length != UNDEFINED_LENGTH {
+        continue;
+      }
+
+      // When duplicates are allowed, the last occurrence is only kept if the
+      // policy says so
+      if index.data_elements.contains_key(&tag)
+        && config.duplicate_data_element_policy
+          != DuplicateDataElementPolicy::LastWins
+      {
+        continue;
+      }
+
+      index.insert_data_element(tag, Some(header_offset), range);
+    }
+
+    Ok(index)
+  }
+
+  fn insert_data_element(
+    &mut self,
+    tag: DataElementTag,
+    offset: Option<u64>,
+    range: Range<usize>,
+  ) {
+    self.data_elements.insert(
+      tag,
+      LazyDataElement {
+        offset,
+        range,
+        value: OnceCell::new(),
+      },
+    );
+  }
+
+  /// Checks that a data element in the root data set isn't a duplicate, and is
+  /// in ascending order, when the read config requires these.
+  ///
+  fn check_data_element_ordering(
+    &self,
+    tag: DataElementTag,
+    previous_tag: Option<DataElementTag>,
+    offset: u64,
+  ) -> Result<(), P10Error> {
+    let details = if self.data_elements.contains_key(&tag)
+      && self.config.duplicate_data_element_policy
+        == DuplicateDataElementPolicy::Error
+    {
+      "is a duplicate"
+    } else if self.config.require_ordered_data_elements
+      && previous_tag.is_some_and(|previous_tag| tag <= previous_tag)
+    {
+      "is not in ascending order"
+    } else {
+      return Ok(());
+    };
+
+    Err(P10Error::DataInvalid {
+      when: "Reading data element header".into(),
+      details: error_details!(
+        "Data element '{}' {}",
+        dictionary::tag_with_name(tag, None),
+        details
+      ),
+      path: DataSetPath::new(),
+      offset,
+    })
+  }
+
+  /// Returns whether the bytes at the given position in the root data set are
+  /// trailing bytes that can't be read as a data element header.
+  ///
+  fn is_at_trailing_bytes(
+    &self,
+    position: usize,
+    transfer_syntax: &TransferSyntax,
+  ) -> bool {
+    let Some(data) = self.bytes.get(position..position + 8) else {
+      return true;
+    };
+
+    let tag = DataElementTag::new(
+      read_u16(&data[0..2], transfer_syntax),
+      read_u16(&data[2..4], transfer_syntax),
+    );
+
+    if tag == DataElementTag::ZERO {
+      return true;
+    }
+
+    if transfer_syntax.vr_serialization
+      == transfer_syntax::VrSerialization::VrImplicit
+      || tag.group == 0xFFFE
+    {
+      return false;
+    }
+
+    let vr = match ValueRepresentation::from_bytes(&data[4..6]) {
+      Ok(vr) => vr,
+      Err(_) => match data[4..6] {
+        [0x00, 0x00] | [0x20, 0x20] => ValueRepresentation::Unknown,
+        _ => return true,
+      },
+    };
+
+    matches!(
+      DataElementHeader::value_length_size(vr),
+      ValueLengthSize::U32
+    ) && position + 12 > self.bytes.len()
+  }
+
+  /// Reads the data element header at the given position.
+  ///
+  fn read_header(
+    &self,
+    position: usize,
+    transfer_syntax: &TransferSyntax,
+  ) -> Result<IndexedHeader, P10Error> {
+    let data = self.read_bytes(position, 8, "Reading data element header")?;
+
+    let tag = DataElementTag::new(
+      read_u16(&data[0..2], transfer_syntax),
+      read_u16(&data[2..4], transfer_syntax),
+    );
+
+    // The item and delimitation tags always use implicit VRs
+    if transfer_syntax.vr_serialization
+      == transfer_syntax::VrSerialization::VrImplicit
+      || tag.group == 0xFFFE
+    {
+      return Ok(IndexedHeader {
+        tag,
+        vr: None,
+        length: read_u32(&data[4..8], transfer_syntax),
+        size: 8,
+      });
+    }
+
+    let vr = match ValueRepresentation::from_bytes(&data[4..6]) {
+      Ok(vr) => vr,
+      Err(_) => match data[4..6] {
+        [0x00, 0x00] | [0x20, 0x20] => ValueRepresentation::Unknown,
+        _ => {
+          return Err(P10Error::DataInvalid {
+            when: "Reading data element VR".into(),
+            details: error_details!(
+              "Unrecognized VR {:?} for tag '{}'",
+              &data[4..6],
+              dictionary::tag_with_name(tag, None)
+            ),
+            path: DataSetPath::new(),
+            offset: self.offset + position as u64,
+          });
+        }
+      },
+    };
+
+    match DataElementHeader::value_length_size(vr) {
+      ValueLengthSize::U16 => Ok(IndexedHeader {
+        tag,
+        vr: Some(vr),
+        length: read_u16(&data[6..8], transfer_syntax).into(),
+        size: 8,
+      }),
+
+      ValueLengthSize::U32 => {
+        let data =
+          self.read_bytes(position, 12, "Reading data element header")?;
+
+        Ok(IndexedHeader {
+          tag,
+          vr: Some(vr),
+          length: read_u32(&data[8..12], transfer_syntax),
+          size: 12,
+        })
+      }
+    }
+  }
+
+  /// Returns the position of the end of the value of a data element that
+  /// starts at the given position. Values with a defined length are skipped
+  /// over, and values with an undefined length have their items read until
+  /// the sequence delimiter is reached.
+  ///
+  fn value_end(
+    &self,
+    position: usize,
+    header: &IndexedHeader,
+    transfer_syntax: &TransferSyntax,
+    sequence_depth: usize,
+  ) -> Result<usize, P10Error> {
+    if header.length != UNDEFINED_LENGTH {
+      return self
+        .read_bytes(
+          position,
+          header.length as usize,
+          "Reading data element value bytes",
+        )
+        .map(|_| position + header.length as usize);
+    }
+
+    if sequence_depth >= self.config.max_sequence_depth {
+      return Err(P10Error::MaximumExceeded {
+        details: "Maximum allowed sequence depth reached".into(),
+        path: DataSetPath::new(),
+        offset: self.offset + position as u64,
+      });
+    }
+
+    // When the VR is unknown and the length is undefined, as per DICOM
+    // Correction Proposal CP-246 the 'Implicit VR Little Endian' transfer
+    // syntax is used to read the sequence's data.
+    // Ref: https://dicom.nema.org/dicom/cp/cp246_01.pdf.
+    let transfer_syntax = if header.vr == Some(ValueRepresentation::Unknown) {
+      &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN
+    } else {
+      transfer_syntax
+    };
+
+    let mut position = position;
+
+    // As with the read context, data that ends on a data element boundary
+    // inside a sequence or item isn't an error
+    while position < self.bytes.len() {
+      let item_header = self.read_header(position, transfer_syntax)?;
+      position += item_header.size;
+
+      if item_header.tag == dictionary::SEQUENCE_DELIMITATION_ITEM.tag {
+        break;
+      }
+
+      if item_header.tag != dictionary::ITEM.tag {
+        return Err(P10Error::DataInvalid {
+          when: "Reading data element header".into(),
+          details: error_details!(
+            "Data element '{}' is not an item",
+            dictionary::tag_with_name(item_header.tag, None)
+          ),
+          path: DataSetPath::new(),
+          offset: self.offset + position as u64,
+        });
+      }
+
+      if item_header.length != UNDEFINED_LENGTH {
+        position = self.value_end(
+          position,
+          &item_header,
+          transfer_syntax,
+          sequence_depth,
+        )?;
+        continue;
+      }
+
+      while position < self.bytes.len() {
+        let header = self.read_header(position, transfer_syntax)?;
+        position += header.size;
+
+        if header.tag == dictionary::ITEM_DELIMITATION_ITEM.tag {
+          break;
+        }
+
+        position = self.value_end(
+          position,
+          &header,
+          transfer_syntax,
+          sequence_depth + 1,
+        )?;
+      }
+    }
+
+    Ok(position)
+  }
+
+  /// Returns the given number of bytes at the given position, or an error if
+  /// the data ends before them.
+  ///
+  fn read_bytes(
+    &self,
+    position: usize,
+    length: usize,
+    when: &str,
+  ) -> Result<&'a [u8], P10Error> {
+    position
+      .checked_add(length)
+      .and_then(|end| self.bytes.get(position..end))
+      .ok_or_else(|| P10Error::DataEndedUnexpectedly {
+        when: when.to_string().into(),
+        path: DataSetPath::new(),
+        offset: self.offset + position as u64,
+      })
+  }
+}
+
+fn read_u16(bytes: &[u8], transfer_syntax: &TransferSyntax) -> u16 {
+  match transfer_syntax.endianness {
+    transfer_syntax::Endianness::LittleEndian => {
+      byteorder::LittleEndian::read_u16(bytes)
+    }
+    transfer_syntax::Endianness::BigEndian => {
+      byteorder::BigEndian::read_u16(bytes)
+    }
+  }
+}
+
+fn read_u32(bytes: &[u8], transfer_syntax: &TransferSyntax) -> u32 {
+  match transfer_syntax.endianness {
+    transfer_syntax::Endianness::LittleEndian => {
+      byteorder::LittleEndian::read_u32(bytes)
+    }
+    transfer_syntax::Endianness::BigEndian => {
+      byteorder::BigEndian::read_u32(bytes)
+    }
+  }
+}
+
+/// Inflates the bytes of a deflated data set. The offset of the data set in
+/// the DICOM P10 data is used in the returned error.
+///
+fn inflate(bytes: RcByteSlice, offset: u64) -> Result<RcByteSlice, P10Error> {
+  let error = || P10Error::DataInvalid {
+    when: "Inflating deflated data set".into(),
+    details: "Zlib data is invalid".into(),
+    path: DataSetPath::new(),
+    offset,
+  };
+
+  let mut stream = ByteStream::new();
+  stream.write(bytes, true).map_err(|_| error())?;
+  stream.start_zlib_inflate().map_err(|_| error())?;
+
+  let mut inflated_bytes = vec![];
+
+  loop {
+    let data = stream.read_available(256 * 1024).map_err(|_| error())?;
+    if data.is_empty() {
+      break;
+    }
+
+    inflated_bytes.extend_from_slice(&data);
+  }
+
+  Ok(inflated_bytes.into())
+}
+
+/// Returns the bytes of a minimal DICOM P10 header that specifies the given
+/// transfer syntax. Its File Meta Information has a group length so that data
+/// elements in group 0x0002 that follow it are read as part of the data set.
+///
+fn p10_header_bytes(transfer_syntax: &TransferSyntax) -> RcByteSlice {
+  let mut uid = transfer_syntax.uid.as_bytes().to_vec();
+  if uid.len() % 2 == 1 {
+    uid.push(0);
+  }
+
+  let mut bytes = vec![0; 128];
+  bytes.extend_from_slice(b"DICM");
+
+  bytes.extend_from_slice(&[0x02, 0x00, 0x00, 0x00, b'U', b'L', 4, 0]);
+  bytes.extend_from_slice(&(8 + uid.len() as u32).to_le_bytes());
+
+  bytes.extend_from_slice(&[0x02, 0x00, 0x10, 0x00, b'U', b'I']);
+  bytes.extend_from_slice(&(uid.len() as u16).to_le_bytes());
+  bytes.extend_from_slice(&uid);
+
+  bytes.into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx_core::dictionary;
+
+  #[test]
+  fn lazy_data_set_test() {
+    let bytes: RcByteSlice =
+      std::fs::read("../../../test/assets/pydicom/test_files/693_J2KI.dcm")
+        .unwrap()
+        .into();
+
+    let data_set = crate::read_bytes(bytes.clone(), None).unwrap();
+    let lazy_data_set = LazyDataSet::from_bytes(bytes, None).unwrap();
+
+    assert_eq!(
+      lazy_data_set.file_meta_information(),
+      &data_set.file_meta_information()
+    );
+    assert!(!lazy_data_set.has(dictionary::TRANSFER_SYNTAX_UID.tag));
+    assert!(!lazy_data_set.is_materialized(dictionary::ROWS.tag));
+
+    assert_eq!(
+      lazy_data_set.get_value(dictionary::ROWS.tag),
+      data_set.get_value(dictionary::ROWS.tag)
+    );
+    assert!(lazy_data_set.is_materialized(dictionary::ROWS.tag));
+    assert!(!lazy_data_set.is_materialized(dictionary::PIXEL_DATA.tag));
+
+    assert_eq!(
+      lazy_data_set.get_value(dictionary::PATIENT_ID.tag).is_ok(),
+      data_set.has(dictionary::PATIENT_ID.tag)
+    );
+    assert_eq!(
+      lazy_data_set.get_value(dictionary::SPECTRAL_WIDTH.tag),
+      Err(DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(dictionary::SPECTRAL_WIDTH.tag)
+      ))
+    );
+
+    assert_eq!(lazy_data_set.to_data_set(), Ok(data_set));
+  }
+
+  #[test]
+  fn read_bytes_equivalence_test() {
+    for entry in
+      std::fs::read_dir("../../../test/assets/pydicom/test_files").unwrap()
+    {
+      let path = entry.unwrap().path();
+      if path.extension().is_none_or(|extension| extension != "dcm") {
+        continue;
+      }
+
+      let bytes: RcByteSlice = std::fs::read(&path).unwrap().into();
+
+      let Ok(data_set) = crate::read_bytes(bytes.clone(), None) else {
+        continue;
+      };
+
+      let lazy_data_set = LazyDataSet::from_bytes(bytes, None).unwrap();
+
+      assert_eq!(lazy_data_set.to_data_set(), Ok(data_set), "{path:?}");
+    }
+  }
+
+  #[test]
+  fn duplicate_data_element_policy_test() {
+    let mut bytes = vec![];
+
+    let mut add = |group: u16, element: u16, length: u32, value: &[u8]| {
+      bytes.extend_from_slice(&group.to_le_bytes());
+      bytes.extend_from_slice(&element.to_le_bytes());
+      bytes.extend_from_slice(&length.to_le_bytes());
+      bytes.extend_from_slice(value);
+    };
+
+    add(0x0008, 0x1140, 0xFFFFFFFF, b"");
+    add(0xFFFE, 0xE000, 0xFFFFFFFF, b"");
+    add(0x0008, 0x1150, 2, b"1\0");
+    add(0x0008, 0x1150, 2, b"2\0");
+    add(0xFFFE, 0xE00D, 0, b"");
+    add(0xFFFE, 0xE0DD, 0, b"");
+
+    let config = P10ReadConfig::default()
+      .duplicate_data_element_policy(DuplicateDataElementPolicy::LastWins);
+
+    let lazy_data_set =
+      LazyDataSet::from_bytes(bytes.into(), Some(config)).unwrap();
+    let data_set = lazy_data_set.to_data_set().unwrap();
+
+    assert_eq!(
+      data_set
+        .get_value_at_path(
+          &DataSetPath::from_string("00081140/[0]/00081150").unwrap()
+        )
+        .unwrap()
+        .get_string(),
+      Ok("2")
+    );
+  }
+
+  #[test]
+  fn offset_test() {
+    let bytes: RcByteSlice =
+      std::fs::read("../../../test/assets/pydicom/test_files/693_J2KI.dcm")
+        .unwrap()
+        .into();
+
+    let lazy_data_set = LazyDataSet::from_bytes(bytes.clone(), None).unwrap();
+
+    // Check that each offset points to the data element's tag
+    for tag in lazy_data_set.tags() {
+      let offset = lazy_data_set.offset(tag).unwrap() as usize;
+
+      assert_eq!(
+        DataElementTag::new(
+          u16::from_le_bytes([bytes[offset], bytes[offset + 1]]),
+          u16::from_le_bytes([bytes[offset + 2], bytes[offset + 3]])
+        ),
+        tag
+      );
+    }
+  }
+}
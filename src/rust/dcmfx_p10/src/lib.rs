@@ -7,15 +7,22 @@
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use alloc::{boxed::Box, string::ToString, sync::Arc, vec, vec::Vec};
 
 pub mod data_set_builder;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+pub mod file_preamble;
+pub mod lazy_data_set;
+pub mod multipart;
 pub mod p10_error;
 pub mod p10_read;
 pub mod p10_read_config;
 pub mod p10_token;
+pub mod p10_token_trace;
 pub mod p10_write;
 pub mod p10_write_config;
+pub mod parallel_deflate;
 pub mod transforms;
 pub mod uids;
 
@@ -23,28 +30,42 @@ mod internal;
 mod io;
 
 #[cfg(feature = "std")]
-use std::path::Path;
+use std::{path::Path, sync::Arc};
 
 pub use io::{IoError, IoRead, IoWrite};
 
 #[cfg(feature = "async")]
 pub use io::{IoAsyncRead, IoAsyncWrite};
 
-use dcmfx_core::{DataElementTag, DataSet, DataSetPath, RcByteSlice};
+use dcmfx_core::{
+  DataElementTag, DataError, DataSet, DataSetPath, RcByteSlice, TransferSyntax,
+  ValueRepresentation,
+};
 
 pub use data_set_builder::DataSetBuilder;
+pub use file_preamble::FilePreambleKind;
+pub use lazy_data_set::LazyDataSet;
+pub use multipart::{MultipartReadContext, MultipartToken};
 pub use p10_error::P10Error;
-pub use p10_read::P10ReadContext;
-pub use p10_read_config::P10ReadConfig;
+pub use p10_read::{P10ReadContext, P10ReadStatistics};
+pub use p10_read_config::{
+  DuplicateDataElementPolicy, P10ReadConfig, TrailingDataPolicy,
+};
 pub use p10_token::P10Token;
+pub use p10_token_trace::P10TokenTrace;
 pub use p10_write::P10WriteContext;
-pub use p10_write_config::P10WriteConfig;
+pub use p10_write_config::{P10WriteConfig, UidWritePolicy};
 pub use transforms::p10_custom_type_transform::{
   P10CustomTypeTransform, P10CustomTypeTransformError,
 };
 pub use transforms::p10_filter_transform::P10FilterTransform;
 pub use transforms::p10_insert_transform::P10InsertTransform;
 pub use transforms::p10_print_transform::P10PrintTransform;
+pub use transforms::p10_size_limit_transform::{
+  P10SizeLimitGroup, P10SizeLimitTransform,
+};
+pub use transforms::p10_sr_content_item_transform::P10SrContentItemTransform;
+pub use transforms::p10_value_transform::P10ValueTransform;
 
 /// Returns whether a file contains DICOM P10 data by checking for the presence
 /// of the 'DICM' prefix at offset 128.
@@ -86,6 +107,46 @@ pub async fn is_valid_file_async<P: AsRef<Path>>(filename: P) -> bool {
   }
 }
 
+/// Reads the 128-byte File Preamble from a file containing DICOM P10 data. If
+/// the file doesn't have the 'DICM' prefix at offset 128 then it has no File
+/// Preamble, and all zero bytes are returned.
+///
+/// Use [`file_preamble::detect()`] to check the content of the returned File
+/// Preamble, and [`P10WriteConfig::file_preamble()`] to write it back out.
+///
+#[cfg(feature = "std")]
+pub fn read_file_preamble<P: AsRef<Path>>(
+  filename: P,
+) -> Result<[u8; 128], P10Error> {
+  use std::io::Read;
+
+  let mut file =
+    std::fs::File::open(filename).map_err(|e| P10Error::FileError {
+      when: "Opening file".into(),
+      details: e.to_string().into(),
+    })?;
+
+  let mut buffer = [0u8; 132];
+  let mut preamble = [0u8; 128];
+
+  match file.read_exact(&mut buffer) {
+    Ok(()) => {
+      if is_valid_bytes(&buffer) {
+        preamble.copy_from_slice(&buffer[0..128]);
+      }
+
+      Ok(preamble)
+    }
+
+    Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(preamble),
+
+    Err(e) => Err(P10Error::FileError {
+      when: "Reading file".into(),
+      details: e.to_string().into(),
+    }),
+  }
+}
+
 /// Returns whether the given bytes contain DICOM P10 data by checking for the
 /// presence of the 'DICM' prefix at offset 128.
 ///
@@ -136,8 +197,8 @@ pub fn read_file_returning_builder_on_error<P: AsRef<Path>>(
 
     Err(e) => Err((
       P10Error::FileError {
-        when: "Opening file".to_string(),
-        details: e.to_string(),
+        when: "Opening file".into(),
+        details: e.to_string().into(),
       },
       Box::new(DataSetBuilder::new()),
     )),
@@ -161,8 +222,8 @@ pub async fn read_file_returning_builder_on_error_async<P: AsRef<Path>>(
 
     Err(e) => Err((
       P10Error::FileError {
-        when: "Opening file".to_string(),
-        details: e.to_string(),
+        when: "Opening file".into(),
+        details: e.to_string().into(),
       },
       Box::new(DataSetBuilder::new()),
     )),
@@ -177,7 +238,7 @@ pub fn read_stream<S: IoRead>(
   config: Option<P10ReadConfig>,
 ) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
   let mut context = P10ReadContext::new(config);
-  let mut builder = Box::new(DataSetBuilder::new());
+  let mut builder = Box::new(DataSetBuilder::new_for_read_config(config));
 
   loop {
     // Read the next tokens from the stream
@@ -208,7 +269,7 @@ pub async fn read_stream_async<I: IoAsyncRead>(
   config: Option<P10ReadConfig>,
 ) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
   let mut context = P10ReadContext::new(config);
-  let mut builder = Box::new(DataSetBuilder::new());
+  let mut builder = Box::new(DataSetBuilder::new_for_read_config(config));
 
   loop {
     // Read the next tokens from the stream
@@ -261,8 +322,8 @@ pub fn read_tokens_from_stream<S: IoRead>(
 
         let read_bytes_count =
           stream.read(&mut buffer).map_err(|e| P10Error::FileError {
-            when: "Reading from stream".to_string(),
-            details: e.to_string(),
+            when: "Reading from stream".into(),
+            details: e.to_string().into(),
           })?;
 
         if read_bytes_count == 0 {
@@ -314,8 +375,8 @@ pub async fn read_tokens_from_stream_async<I: IoAsyncRead>(
             .read(&mut buffer)
             .await
             .map_err(|e| P10Error::FileError {
-              when: "Reading from stream".to_string(),
-              details: e.to_string(),
+              when: "Reading from stream".into(),
+              details: e.to_string().into(),
             })?;
 
         if read_bytes_count == 0 {
@@ -338,7 +399,7 @@ pub fn read_bytes(
   config: Option<P10ReadConfig>,
 ) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
   let mut context = P10ReadContext::new(config);
-  let mut builder = Box::new(DataSetBuilder::new());
+  let mut builder = Box::new(DataSetBuilder::new_for_read_config(config));
 
   // Add the bytes to the P10 read context
   match context.write_bytes(bytes, true) {
@@ -385,8 +446,8 @@ pub fn read_file_partial<P: AsRef<Path>>(
     Ok(mut file) => read_stream_partial(&mut file, tags, config),
 
     Err(e) => Err(P10Error::FileError {
-      when: "Opening file".to_string(),
-      details: e.to_string(),
+      when: "Opening file".into(),
+      details: e.to_string().into(),
     }),
   }
 }
@@ -406,8 +467,8 @@ pub async fn read_file_partial_async<P: AsRef<Path>>(
     Ok(mut file) => read_stream_partial_async(&mut file, tags, config).await,
 
     Err(e) => Err(P10Error::FileError {
-      when: "Opening file".to_string(),
-      details: e.to_string(),
+      when: "Opening file".into(),
+      details: e.to_string().into(),
     }),
   }
 }
@@ -426,7 +487,7 @@ pub fn read_stream_partial<S: IoRead>(
     read_stream_partial_prepare(tags);
 
   let mut context = P10ReadContext::new(config);
-  let mut data_set_builder = DataSetBuilder::new();
+  let mut data_set_builder = DataSetBuilder::new_for_read_config(config);
   let mut is_done = false;
 
   while !is_done {
@@ -434,7 +495,7 @@ pub fn read_stream_partial<S: IoRead>(
 
     read_stream_partial_process_tokens(
       &tokens,
-      largest_tag,
+      &|tag| tag > largest_tag,
       &mut filter,
       &mut data_set_builder,
       &mut is_done,
@@ -442,7 +503,10 @@ pub fn read_stream_partial<S: IoRead>(
     )?;
   }
 
-  Ok(read_stream_partial_complete(data_set_builder, tags))
+  Ok(read_stream_partial_complete(
+    data_set_builder,
+    &|tag, _vr| tags.contains(&tag),
+  ))
 }
 
 /// Reads DICOM P10 data from a stream into an in-memory data set. Only the
@@ -460,7 +524,7 @@ pub async fn read_stream_partial_async<I: IoAsyncRead>(
     read_stream_partial_prepare(tags);
 
   let mut context = P10ReadContext::new(config);
-  let mut data_set_builder = DataSetBuilder::new();
+  let mut data_set_builder = DataSetBuilder::new_for_read_config(config);
   let mut is_done = false;
 
   while !is_done {
@@ -469,7 +533,7 @@ pub async fn read_stream_partial_async<I: IoAsyncRead>(
 
     read_stream_partial_process_tokens(
       &tokens,
-      largest_tag,
+      &|tag| tag > largest_tag,
       &mut filter,
       &mut data_set_builder,
       &mut is_done,
@@ -477,7 +541,174 @@ pub async fn read_stream_partial_async<I: IoAsyncRead>(
     )?;
   }
 
-  Ok(read_stream_partial_complete(data_set_builder, tags))
+  Ok(read_stream_partial_complete(
+    data_set_builder,
+    &|tag, _vr| tags.contains(&tag),
+  ))
+}
+
+/// Specifies when a partial read made by [`read_stream_partial_matching()`]
+/// stops reading.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PartialReadStop {
+  /// Reading stops at the first data element in the root data set with a tag
+  /// greater than or equal to the given tag. That data element isn't read.
+  BeforeTag(DataElementTag),
+
+  /// Reading stops once the given number of bytes of DICOM P10 data have been
+  /// parsed, as reported by [`P10ReadContext::bytes_read()`]. Data elements
+  /// that aren't complete at that point aren't returned. More bytes than this
+  /// may be read from the stream because it is read in chunks.
+  ByteBudget(u64),
+
+  /// Reading continues to the end of the DICOM P10 data.
+  End,
+}
+
+/// Defines a function called by [`read_stream_partial_matching()`] that
+/// determines whether a data element in the root data set should be read.
+///
+pub type PartialReadPredicateFn =
+  dyn Fn(DataElementTag, ValueRepresentation) -> bool + Send + Sync;
+
+/// Reads DICOM P10 data from a file into an in-memory data set. Only the data
+/// elements at the root of the main data set that match the given predicate
+/// are read. See [`read_stream_partial_matching()`] for details.
+///
+#[cfg(feature = "std")]
+pub fn read_file_partial_matching<P: AsRef<Path>>(
+  filename: P,
+  predicate: Arc<PartialReadPredicateFn>,
+  stop: PartialReadStop,
+  config: Option<P10ReadConfig>,
+) -> Result<DataSet, P10Error> {
+  match std::fs::File::open(filename) {
+    Ok(mut file) => {
+      read_stream_partial_matching(&mut file, predicate, stop, config)
+    }
+
+    Err(e) => Err(P10Error::FileError {
+      when: "Opening file".into(),
+      details: e.to_string().into(),
+    }),
+  }
+}
+
+/// Reads DICOM P10 data from a file into an in-memory data set. Only the data
+/// elements at the root of the main data set that match the given predicate
+/// are read. See [`read_stream_partial_matching()`] for details.
+///
+#[cfg(feature = "async")]
+pub async fn read_file_partial_matching_async<P: AsRef<Path>>(
+  filename: P,
+  predicate: Arc<PartialReadPredicateFn>,
+  stop: PartialReadStop,
+  config: Option<P10ReadConfig>,
+) -> Result<DataSet, P10Error> {
+  match tokio::fs::File::open(filename).await {
+    Ok(mut file) => {
+      read_stream_partial_matching_async(&mut file, predicate, stop, config)
+        .await
+    }
+
+    Err(e) => Err(P10Error::FileError {
+      when: "Opening file".into(),
+      details: e.to_string().into(),
+    }),
+  }
+}
+
+/// Reads DICOM P10 data from a stream into an in-memory data set. Only the data
+/// elements at the root of the main data set that match the given predicate
+/// are read, along with everything nested inside the sequences among them.
+/// File Meta Information data elements are also passed to the predicate.
+///
+/// Unlike [`read_stream_partial()`], the data elements to read don't need to
+/// be listed explicitly, e.g. a predicate can select all data elements in
+/// particular groups along with any private creator data elements. The stream
+/// is read until the stop condition is met, or the end of the data is reached.
+///
+pub fn read_stream_partial_matching<S: IoRead>(
+  stream: &mut S,
+  predicate: Arc<PartialReadPredicateFn>,
+  stop: PartialReadStop,
+  config: Option<P10ReadConfig>,
+) -> Result<DataSet, P10Error> {
+  let (mut filter, mut chunk_size) =
+    read_stream_partial_matching_prepare(predicate.clone());
+
+  let mut context = P10ReadContext::new(config);
+  let mut data_set_builder = DataSetBuilder::new_for_read_config(config);
+  let mut is_done = false;
+
+  while !is_done {
+    let tokens = read_tokens_from_stream(stream, &mut context, chunk_size)?;
+
+    read_stream_partial_process_tokens(
+      &tokens,
+      &|tag| stop.is_stop_tag(tag),
+      &mut filter,
+      &mut data_set_builder,
+      &mut is_done,
+      &mut chunk_size,
+    )?;
+
+    if stop.is_byte_budget_used(context.bytes_read()) {
+      is_done = true;
+    }
+  }
+
+  Ok(read_stream_partial_complete(data_set_builder, &*predicate))
+}
+
+/// Reads DICOM P10 data from a stream into an in-memory data set. Only the data
+/// elements at the root of the main data set that match the given predicate
+/// are read. See [`read_stream_partial_matching()`] for details.
+///
+#[cfg(feature = "async")]
+pub async fn read_stream_partial_matching_async<I: IoAsyncRead>(
+  stream: &mut I,
+  predicate: Arc<PartialReadPredicateFn>,
+  stop: PartialReadStop,
+  config: Option<P10ReadConfig>,
+) -> Result<DataSet, P10Error> {
+  let (mut filter, mut chunk_size) =
+    read_stream_partial_matching_prepare(predicate.clone());
+
+  let mut context = P10ReadContext::new(config);
+  let mut data_set_builder = DataSetBuilder::new_for_read_config(config);
+  let mut is_done = false;
+
+  while !is_done {
+    let tokens =
+      read_tokens_from_stream_async(stream, &mut context, chunk_size).await?;
+
+    read_stream_partial_process_tokens(
+      &tokens,
+      &|tag| stop.is_stop_tag(tag),
+      &mut filter,
+      &mut data_set_builder,
+      &mut is_done,
+      &mut chunk_size,
+    )?;
+
+    if stop.is_byte_budget_used(context.bytes_read()) {
+      is_done = true;
+    }
+  }
+
+  Ok(read_stream_partial_complete(data_set_builder, &*predicate))
+}
+
+impl PartialReadStop {
+  fn is_stop_tag(&self, tag: DataElementTag) -> bool {
+    matches!(self, Self::BeforeTag(stop_tag) if tag >= *stop_tag)
+  }
+
+  fn is_byte_budget_used(&self, bytes_read: u64) -> bool {
+    matches!(self, Self::ByteBudget(budget) if bytes_read >= *budget)
+  }
 }
 
 fn read_stream_partial_prepare(
@@ -504,34 +735,47 @@ fn read_stream_partial_prepare(
   (largest_tag, filter, chunk_size)
 }
 
+fn read_stream_partial_matching_prepare(
+  predicate: Arc<PartialReadPredicateFn>,
+) -> (P10FilterTransform, Option<usize>) {
+  let filter =
+    P10FilterTransform::new(Box::new(move |tag, vr, _length, path| -> bool {
+      !path.is_root() || predicate(tag, vr)
+    }));
+
+  // Use the same small initial chunk size as other partial reads
+  let chunk_size = Some(8 * 1024);
+
+  (filter, chunk_size)
+}
+
 fn read_stream_partial_process_tokens(
   tokens: &[P10Token],
-  largest_tag: DataElementTag,
+  is_stop_tag: &dyn Fn(DataElementTag) -> bool,
   filter: &mut P10FilterTransform,
   data_set_builder: &mut DataSetBuilder,
   is_done: &mut bool,
   chunk_size: &mut Option<usize>,
 ) -> Result<(), P10Error> {
   for token in tokens {
+    // Stop before the data element that meets the stop condition is added to
+    // the data set builder
+    if let P10Token::DataElementHeader { tag, path, .. }
+    | P10Token::SequenceStart { tag, path, .. } = token
+      && path.is_root()
+      && is_stop_tag(*tag)
+    {
+      *is_done = true;
+      break;
+    }
+
     if filter.add_token(token)? {
       data_set_builder.add_token(token)?;
     }
 
-    match token {
-      P10Token::DataElementHeader { tag, path, .. }
-      | P10Token::SequenceStart { tag, path, .. } => {
-        if *tag > largest_tag && path.is_root() {
-          *is_done = true;
-          break;
-        }
-      }
-
-      P10Token::End => {
-        *is_done = true;
-        break;
-      }
-
-      _ => (),
+    if *token == P10Token::End {
+      *is_done = true;
+      break;
     }
   }
 
@@ -542,14 +786,15 @@ fn read_stream_partial_process_tokens(
 
 fn read_stream_partial_complete(
   mut data_set_builder: DataSetBuilder,
-  tags: &[DataElementTag],
+  is_requested: &dyn Fn(DataElementTag, ValueRepresentation) -> bool,
 ) -> DataSet {
   data_set_builder.force_end();
   let mut data_set = data_set_builder.final_data_set().unwrap();
 
   // Exclude File Meta Information tags unless they were explicitly requested
-  data_set.retain(|tag, _value| {
-    !tag.is_file_meta_information() || tags.contains(&tag)
+  data_set.retain(|tag, value| {
+    !tag.is_file_meta_information()
+      || is_requested(tag, value.value_representation())
   });
 
   data_set
@@ -570,8 +815,8 @@ pub fn write_file<P: AsRef<Path>>(
     Ok(mut file) => write_stream(&mut file, data_set, config),
 
     Err(e) => Err(P10Error::FileError {
-      when: "Opening file".to_string(),
-      details: e.to_string(),
+      when: "Opening file".into(),
+      details: e.to_string().into(),
     }),
   }
 }
@@ -591,8 +836,8 @@ pub async fn write_file_async<P: AsRef<Path>>(
     Ok(mut file) => write_stream_async(&mut file, data_set, config).await,
 
     Err(e) => Err(P10Error::FileError {
-      when: "Opening file".to_string(),
-      details: e.to_string(),
+      when: "Opening file".into(),
+      details: e.to_string().into(),
     }),
   }
 }
@@ -609,8 +854,8 @@ pub fn write_stream<S: IoWrite>(
       Ok(()) => Ok(()),
 
       Err(e) => Err(P10Error::FileError {
-        when: "Writing DICOM P10 data to stream".to_string(),
-        details: e.to_string(),
+        when: "Writing DICOM P10 data to stream".into(),
+        details: e.to_string().into(),
       }),
     }
   };
@@ -618,8 +863,8 @@ pub fn write_stream<S: IoWrite>(
   data_set.to_p10_bytes(&mut bytes_callback, config)?;
 
   stream.flush().map_err(|e| P10Error::FileError {
-    when: "Writing DICOM P10 data to stream".to_string(),
-    details: e.to_string(),
+    when: "Writing DICOM P10 data to stream".into(),
+    details: e.to_string().into(),
   })
 }
 
@@ -639,8 +884,8 @@ pub async fn write_stream_async<S: IoAsyncWrite>(
         Ok(()) => Ok(()),
 
         Err(e) => Err(P10Error::FileError {
-          when: "Writing DICOM P10 data to stream".to_string(),
-          details: e.to_string(),
+          when: "Writing DICOM P10 data to stream".into(),
+          details: e.to_string().into(),
         }),
       }
     };
@@ -650,8 +895,8 @@ pub async fn write_stream_async<S: IoAsyncWrite>(
     .await?;
 
   stream.flush().await.map_err(|e| P10Error::FileError {
-    when: "Writing DICOM P10 data to stream".to_string(),
-    details: e.to_string(),
+    when: "Writing DICOM P10 data to stream".into(),
+    details: e.to_string().into(),
   })
 }
 
@@ -671,15 +916,15 @@ pub fn write_tokens_to_stream<S: IoWrite>(
   let p10_bytes = context.read_bytes();
   for bytes in p10_bytes.iter() {
     stream.write_all(bytes).map_err(|e| P10Error::FileError {
-      when: "Writing to output stream".to_string(),
-      details: e.to_string(),
+      when: "Writing to output stream".into(),
+      details: e.to_string().into(),
     })?;
   }
 
   if tokens.last() == Some(&P10Token::End) {
     stream.flush().map_err(|e| P10Error::FileError {
-      when: "Writing to output stream".to_string(),
-      details: e.to_string(),
+      when: "Writing to output stream".into(),
+      details: e.to_string().into(),
     })?;
 
     Ok(true)
@@ -710,15 +955,15 @@ pub async fn write_tokens_to_stream_async<S: IoAsyncWrite>(
       .write_all(bytes)
       .await
       .map_err(|e| P10Error::FileError {
-        when: "Writing to output stream".to_string(),
-        details: e.to_string(),
+        when: "Writing to output stream".into(),
+        details: e.to_string().into(),
       })?;
   }
 
   if tokens.last() == Some(&P10Token::End) {
     stream.flush().await.map_err(|e| P10Error::FileError {
-      when: "Writing to output stream".to_string(),
-      details: e.to_string(),
+      when: "Writing to output stream".into(),
+      details: e.to_string().into(),
     })?;
 
     Ok(true)
@@ -739,8 +984,8 @@ pub fn rewrite_file<P: AsRef<Path>>(
     Ok(file) => file,
     Err(e) => {
       return Err(P10Error::FileError {
-        when: "Opening input file".to_string(),
-        details: e.to_string(),
+        when: "Opening input file".into(),
+        details: e.to_string().into(),
       });
     }
   };
@@ -749,8 +994,8 @@ pub fn rewrite_file<P: AsRef<Path>>(
     Ok(file) => file,
     Err(e) => {
       return Err(P10Error::FileError {
-        when: "Opening output file".to_string(),
-        details: e.to_string(),
+        when: "Opening output file".into(),
+        details: e.to_string().into(),
       });
     }
   };
@@ -770,8 +1015,8 @@ pub async fn rewrite_file_async<P: AsRef<Path>>(
     Ok(file) => file,
     Err(e) => {
       return Err(P10Error::FileError {
-        when: "Opening input file".to_string(),
-        details: e.to_string(),
+        when: "Opening input file".into(),
+        details: e.to_string().into(),
       });
     }
   };
@@ -780,8 +1025,8 @@ pub async fn rewrite_file_async<P: AsRef<Path>>(
     Ok(file) => file,
     Err(e) => {
       return Err(P10Error::FileError {
-        when: "Opening output file".to_string(),
-        details: e.to_string(),
+        when: "Opening output file".into(),
+        details: e.to_string().into(),
       });
     }
   };
@@ -819,8 +1064,8 @@ pub fn rewrite_stream<I: IoRead, O: IoWrite>(
       output_stream
         .write_all(&bytes)
         .map_err(|e| P10Error::FileError {
-          when: "Writing rewritten DICOM P10 data to output stream".to_string(),
-          details: e.to_string(),
+          when: "Writing rewritten DICOM P10 data to output stream".into(),
+          details: e.to_string().into(),
         })?;
     }
   }
@@ -861,8 +1106,8 @@ pub async fn rewrite_stream_async<I: IoAsyncRead, O: IoAsyncWrite>(
     for bytes in write_context.read_bytes() {
       output_stream.write_all(&bytes).await.map_err(|e| {
         P10Error::FileError {
-          when: "Writing rewritten DICOM P10 data to output stream".to_string(),
-          details: e.to_string(),
+          when: "Writing rewritten DICOM P10 data to output stream".into(),
+          details: e.to_string().into(),
         }
       })?;
     }
@@ -964,6 +1209,17 @@ where
     bytes_callback: &mut impl FnMut(RcByteSlice) -> Result<(), P10Error>,
     config: Option<P10WriteConfig>,
   ) -> Result<(), P10Error>;
+
+  /// Adds File Meta Information data elements that are missing from a data
+  /// set, using the passed transfer syntax if it doesn't specify one.
+  ///
+  /// See [`p10_write::synthesize_file_meta_information()`].
+  ///
+  fn synthesize_file_meta_information(
+    &mut self,
+    transfer_syntax: &TransferSyntax,
+    config: Option<P10WriteConfig>,
+  ) -> Result<(), DataError>;
 }
 
 /// Adds functions to [`DataSet`] for converting to and from the DICOM P10
@@ -1091,6 +1347,14 @@ impl DataSetP10Extensions for DataSet {
       config,
     )
   }
+
+  fn synthesize_file_meta_information(
+    &mut self,
+    transfer_syntax: &TransferSyntax,
+    config: Option<P10WriteConfig>,
+  ) -> Result<(), DataError> {
+    p10_write::synthesize_file_meta_information(self, transfer_syntax, config)
+  }
 }
 
 #[cfg(feature = "async")]
@@ -1157,7 +1421,7 @@ impl DataSetP10AsyncExtensions for DataSet {
 mod tests {
   use super::*;
 
-  use dcmfx_core::dictionary;
+  use dcmfx_core::{ErrorContextChain, ValueRepresentation, dictionary};
 
   #[test]
   fn read_file_partial_test() {
@@ -1175,4 +1439,276 @@ mod tests {
       vec![dictionary::ROWS.tag, dictionary::COLUMNS.tag]
     );
   }
+
+  #[test]
+  fn read_file_partial_matching_test() {
+    let path = "../../../test/assets/pydicom/test_files/693_J2KI.dcm";
+
+    let ds = read_file_partial_matching(
+      path,
+      Arc::new(|tag, _vr| tag.group == 0x0028),
+      PartialReadStop::BeforeTag(dictionary::PIXEL_DATA.tag),
+      None,
+    )
+    .unwrap();
+
+    assert!(ds.has(dictionary::ROWS.tag));
+    assert!(ds.has(dictionary::COLUMNS.tag));
+    assert!(ds.tags().iter().all(|tag| tag.group == 0x0028));
+
+    let ds = read_file_partial_matching(
+      path,
+      Arc::new(|_tag, _vr| true),
+      PartialReadStop::ByteBudget(1900),
+      None,
+    )
+    .unwrap();
+
+    assert!(ds.has(dictionary::ROWS.tag));
+    assert!(!ds.has(dictionary::PIXEL_DATA.tag));
+  }
+
+  /// Returns 'Implicit VR Little Endian' data with one data element followed
+  /// by the given trailing bytes.
+  ///
+  fn bytes_with_trailing_bytes(trailing_bytes: &[u8]) -> RcByteSlice {
+    let mut bytes = vec![0x10, 0x00, 0x10, 0x00, 0x02, 0x00, 0x00, 0x00];
+    bytes.extend_from_slice(b"A ");
+    bytes.extend_from_slice(trailing_bytes);
+
+    bytes.into()
+  }
+
+  /// Reads all tokens from the given bytes, returning the read context along
+  /// with the resulting data set.
+  ///
+  fn read_with_trailing_data_policy(
+    bytes: RcByteSlice,
+    policy: TrailingDataPolicy,
+  ) -> Result<(P10ReadContext, DataSet), P10Error> {
+    let config = P10ReadConfig::default().trailing_data_policy(policy);
+
+    let mut context = P10ReadContext::new(Some(config));
+    context.write_bytes(bytes, true)?;
+
+    let mut builder = DataSetBuilder::new();
+    while !builder.is_complete() {
+      for token in context.read_tokens()? {
+        builder.add_token(&token)?;
+      }
+    }
+
+    let data_set = builder.final_data_set().unwrap();
+
+    Ok((context, data_set))
+  }
+
+  #[test]
+  fn trailing_bytes_test() {
+    let trailing_bytes = [0, 0, 0, 0, 1, 2, 3];
+
+    assert!(matches!(
+      read_with_trailing_data_policy(
+        bytes_with_trailing_bytes(&trailing_bytes),
+        TrailingDataPolicy::Error,
+      )
+      .map_err(|e| e.without_context().clone()),
+      Err(P10Error::DataInvalid { offset: 10, .. })
+    ));
+
+    let (context, data_set) = read_with_trailing_data_policy(
+      bytes_with_trailing_bytes(&trailing_bytes),
+      TrailingDataPolicy::Ignore,
+    )
+    .unwrap();
+    assert_eq!(
+      data_set.tags(),
+      vec![
+        dictionary::SPECIFIC_CHARACTER_SET.tag,
+        dictionary::PATIENT_NAME.tag
+      ]
+    );
+    assert_eq!(context.trailing_bytes(), Some((10, 7)));
+    assert_eq!(context.data_set_trailing_padding(), None);
+
+    let (context, data_set) = read_with_trailing_data_policy(
+      bytes_with_trailing_bytes(&trailing_bytes),
+      TrailingDataPolicy::Preserve,
+    )
+    .unwrap();
+    assert_eq!(
+      data_set
+        .get_value_bytes(dictionary::DATA_SET_TRAILING_PADDING.tag)
+        .unwrap()
+        .as_ref(),
+      trailing_bytes
+    );
+    assert_eq!(context.trailing_bytes(), Some((10, 7)));
+  }
+
+  #[test]
+  fn data_set_trailing_padding_test() {
+    let trailing_bytes = [0xFC, 0xFF, 0xFC, 0xFF, 4, 0, 0, 0, 0, 0, 0, 0, 1];
+
+    for policy in [TrailingDataPolicy::Ignore, TrailingDataPolicy::Preserve] {
+      let (context, data_set) = read_with_trailing_data_policy(
+        bytes_with_trailing_bytes(&trailing_bytes),
+        policy,
+      )
+      .unwrap();
+
+      assert_eq!(
+        data_set.has(dictionary::DATA_SET_TRAILING_PADDING.tag),
+        policy == TrailingDataPolicy::Preserve
+      );
+      assert_eq!(context.data_set_trailing_padding(), Some((18, 4)));
+      assert_eq!(context.trailing_bytes(), Some((22, 1)));
+    }
+
+    let (context, data_set) = read_with_trailing_data_policy(
+      bytes_with_trailing_bytes(&trailing_bytes[0..12]),
+      TrailingDataPolicy::Error,
+    )
+    .unwrap();
+    assert!(!data_set.has(dictionary::DATA_SET_TRAILING_PADDING.tag));
+    assert_eq!(context.data_set_trailing_padding(), Some((18, 4)));
+    assert_eq!(context.trailing_bytes(), None);
+  }
+
+  #[test]
+  fn file_preamble_round_trip_test() {
+    let preamble = file_preamble::new_tiff(0x100);
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+
+    let mut bytes = vec![];
+    data_set
+      .to_p10_bytes(
+        &mut |b: RcByteSlice| {
+          bytes.extend_from_slice(&b);
+          Ok(())
+        },
+        Some(P10WriteConfig::default().file_preamble(Some(preamble))),
+      )
+      .unwrap();
+
+    assert_eq!(bytes[0..128], preamble);
+    assert!(is_valid_bytes(&bytes));
+
+    let mut context = P10ReadContext::new(None);
+    context.write_bytes(bytes.into(), true).unwrap();
+
+    let mut builder = DataSetBuilder::new();
+    while !builder.is_complete() {
+      for token in context.read_tokens().unwrap() {
+        builder.add_token(&token).unwrap();
+      }
+    }
+
+    assert_eq!(builder.file_preamble(), Ok(&preamble));
+    assert!(file_preamble::detect(&preamble).is_tiff());
+  }
+
+  #[test]
+  fn infer_implicit_vr_sequences_test() {
+    let mut bytes = vec![];
+
+    // (0009,0010) Private Creator
+    bytes.extend_from_slice(&[0x09, 0x00, 0x10, 0x00, 0x04, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(b"ACME");
+
+    // (0009,1001) Private sequence with a defined length and one item
+    bytes.extend_from_slice(&[0x09, 0x00, 0x01, 0x10, 0x16, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(&[0xFE, 0xFF, 0x00, 0xE0, 0x0E, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0x06, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(b"Smith ");
+
+    // (0009,1002) Private binary data that doesn't start with an item
+    bytes.extend_from_slice(&[0x09, 0x00, 0x02, 0x10, 0x08, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(&[0xFE, 0xFF, 0x00, 0xE0, 0x10, 0x00, 0x00, 0x00]);
+
+    let read = |infer_implicit_vr_sequences: bool| {
+      let config = P10ReadConfig::default()
+        .infer_implicit_vr_sequences(infer_implicit_vr_sequences);
+
+      let mut context = P10ReadContext::new(Some(config));
+      context.write_bytes(bytes.clone().into(), true).unwrap();
+
+      let mut builder = DataSetBuilder::new();
+      while !builder.is_complete() {
+        for token in context.read_tokens().unwrap() {
+          builder.add_token(&token).unwrap();
+        }
+      }
+
+      builder.final_data_set().unwrap()
+    };
+
+    let sequence_tag = DataElementTag::new(0x0009, 0x1001);
+    let binary_tag = DataElementTag::new(0x0009, 0x1002);
+
+    let data_set = read(false);
+    assert_eq!(
+      data_set
+        .get_value(sequence_tag)
+        .unwrap()
+        .value_representation(),
+      ValueRepresentation::Unknown
+    );
+
+    let data_set = read(true);
+    let items = data_set.get_sequence_items(sequence_tag).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(
+      items[0].get_string(dictionary::PATIENT_NAME.tag),
+      Ok("Smith")
+    );
+    assert_eq!(
+      data_set
+        .get_value(binary_tag)
+        .unwrap()
+        .value_representation(),
+      ValueRepresentation::Unknown
+    );
+  }
+
+  #[test]
+  fn read_statistics_test() {
+    let mut bytes = vec![];
+
+    // (0008,0005) Specific Character Set
+    bytes.extend_from_slice(&[0x08, 0x00, 0x05, 0x00, 0x0A, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(b"ISO_IR 100");
+
+    // (0008,1140) Referenced Image Sequence, with one item
+    bytes.extend_from_slice(&[0x08, 0x00, 0x40, 0x11, 0xFF, 0xFF, 0xFF, 0xFF]);
+    bytes.extend_from_slice(&[0xFE, 0xFF, 0x00, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF]);
+    bytes.extend_from_slice(&[0x08, 0x00, 0x55, 0x11, 0x02, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(b"1\0");
+    bytes.extend_from_slice(&[0xFE, 0xFF, 0x0D, 0xE0, 0x00, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(&[0xFE, 0xFF, 0xDD, 0xE0, 0x00, 0x00, 0x00, 0x00]);
+
+    // (0010,0010) Patient's Name, containing a Latin-1 character
+    bytes.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0x04, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(&[b'R', b'e', b'n', 0xE9]);
+
+    let (context, _) =
+      read_with_trailing_data_policy(bytes.into(), TrailingDataPolicy::Error)
+        .unwrap();
+
+    assert_eq!(
+      context.statistics(),
+      &P10ReadStatistics {
+        data_element_count: 4,
+        sequence_count: 1,
+        max_sequence_depth: 1,
+        character_set_conversion_count: 1,
+        vr_inference_count: 4,
+        recovered_anomaly_count: 1,
+      }
+    );
+  }
 }
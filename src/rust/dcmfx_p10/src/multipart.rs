@@ -0,0 +1,580 @@
+//! Reads and writes multipart MIME bodies containing DICOM P10 data, such as
+//! the `multipart/related; type="application/dicom"` bodies used by DICOMweb
+//! WADO-RS responses and STOW-RS requests. Ref: RFC 2046 section 5.1.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{DataSet, DataSetPath, RcByteSlice};
+
+use crate::{
+  DataSetBuilder, IoRead, IoWrite, P10Error, P10ReadConfig, P10ReadContext,
+  P10WriteConfig, p10_write,
+};
+
+/// A token read from a multipart MIME body by a [`MultipartReadContext`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultipartToken {
+  /// The start of a new part, along with the headers of that part. Header
+  /// names are as they appear in the body, and header values have surrounding
+  /// whitespace removed.
+  PartStart { headers: Vec<(String, String)> },
+
+  /// A chunk of the content of the current part. The content of a single part
+  /// may be split across any number of these tokens.
+  PartData(RcByteSlice),
+
+  /// The end of the current part.
+  PartEnd,
+
+  /// The end of the multipart body. No further tokens will be returned after
+  /// this token.
+  End,
+}
+
+/// A read context for splitting a multipart MIME body into its parts. Bytes
+/// are written to the context as they become available, and the content of
+/// each part is returned in chunks so that large bodies don't need to be held
+/// in memory in their entirety.
+///
+pub struct MultipartReadContext {
+  delimiter: Vec<u8>,
+  buffer: Vec<u8>,
+  is_buffer_complete: bool,
+  bytes_read: u64,
+  next_action: NextAction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NextAction {
+  ReadPreamble,
+  ReadDelimiterSuffix,
+  ReadHeaders,
+  ReadContent,
+  Ended,
+}
+
+impl MultipartReadContext {
+  /// Creates a new multipart read context for a body that uses the given
+  /// boundary to separate its parts. The boundary is usually taken from the
+  /// `boundary` parameter of the body's `Content-Type` header, see
+  /// [`boundary_from_content_type()`].
+  ///
+  pub fn new(boundary: &str) -> Self {
+    // The leading CRLF is part of the delimiter, so one is added to the start
+    // of the buffer in order to match a delimiter at the very start of the body
+    let mut delimiter = b"\r\n--".to_vec();
+    delimiter.extend_from_slice(boundary.as_bytes());
+
+    Self {
+      delimiter,
+      buffer: b"\r\n".to_vec(),
+      is_buffer_complete: false,
+      bytes_read: 0,
+      next_action: NextAction::ReadPreamble,
+    }
+  }
+
+  /// Writes raw bytes of a multipart body to the read context that will be
+  /// parsed by subsequent calls to [`Self::read_tokens()`]. If `done` is true
+  /// this indicates the end of the incoming data, after which any further
+  /// calls to this function will error.
+  ///
+  pub fn write_bytes(
+    &mut self,
+    bytes: RcByteSlice,
+    done: bool,
+  ) -> Result<(), P10Error> {
+    if self.is_buffer_complete {
+      return Err(P10Error::WriteAfterCompletion);
+    }
+
+    self.buffer.extend_from_slice(&bytes);
+    self.is_buffer_complete = done;
+
+    Ok(())
+  }
+
+  /// Reads the next tokens from the read context. On success, zero or more
+  /// tokens are returned and the function can be called again to read further
+  /// tokens.
+  ///
+  /// On error, a value of [`P10Error::DataRequired`] means the read context
+  /// does not have enough data to return the next token, i.e. further calls to
+  /// [`Self::write_bytes`] are required before the next token is able to be
+  /// read.
+  ///
+  pub fn read_tokens(&mut self) -> Result<Vec<MultipartToken>, P10Error> {
+    match self.next_action {
+      NextAction::ReadPreamble => self.read_preamble(),
+      NextAction::ReadDelimiterSuffix => self.read_delimiter_suffix(),
+      NextAction::ReadHeaders => self.read_headers(),
+      NextAction::ReadContent => self.read_content(),
+      NextAction::Ended => Ok(vec![]),
+    }
+  }
+
+  fn read_preamble(&mut self) -> Result<Vec<MultipartToken>, P10Error> {
+    match find(&self.buffer, &self.delimiter) {
+      Some(index) => {
+        self.consume(index + self.delimiter.len());
+        self.next_action = NextAction::ReadDelimiterSuffix;
+
+        Ok(vec![])
+      }
+
+      // Discard the preamble, retaining enough bytes to match a delimiter
+      // that's split across multiple writes
+      None => {
+        let retain_count = self.delimiter.len() - 1;
+        if self.buffer.len() > retain_count {
+          self.consume(self.buffer.len() - retain_count);
+        }
+
+        Err(self.data_required_error("Reading multipart preamble"))
+      }
+    }
+  }
+
+  fn read_delimiter_suffix(&mut self) -> Result<Vec<MultipartToken>, P10Error> {
+    // A delimiter followed by "--" is the close delimiter that ends the body
+    if self.buffer.starts_with(b"--") {
+      self.buffer.clear();
+      self.next_action = NextAction::Ended;
+
+      return Ok(vec![MultipartToken::End]);
+    }
+
+    // Otherwise the delimiter is followed by optional whitespace and a CRLF
+    match find(&self.buffer, b"\r\n") {
+      Some(index) => {
+        if self.buffer[0..index]
+          .iter()
+          .any(|b| *b != b' ' && *b != b'\t')
+        {
+          return Err(self.data_invalid_error(
+            "Reading multipart delimiter",
+            "Delimiter is not followed by a line break".to_string(),
+          ));
+        }
+
+        self.consume(index + 2);
+        self.next_action = NextAction::ReadHeaders;
+
+        Ok(vec![])
+      }
+
+      None => Err(self.data_required_error("Reading multipart delimiter")),
+    }
+  }
+
+  fn read_headers(&mut self) -> Result<Vec<MultipartToken>, P10Error> {
+    // A part with no headers starts immediately with a blank line
+    let headers_length = if self.buffer.starts_with(b"\r\n") {
+      0
+    } else {
+      match find(&self.buffer, b"\r\n\r\n") {
+        Some(index) => index + 2,
+        None => {
+          return Err(self.data_required_error("Reading multipart headers"));
+        }
+      }
+    };
+
+    let headers = String::from_utf8_lossy(&self.buffer[0..headers_length])
+      .split("\r\n")
+      .filter_map(|line| line.split_once(':'))
+      .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+      .collect();
+
+    self.consume(headers_length + 2);
+    self.next_action = NextAction::ReadContent;
+
+    Ok(vec![MultipartToken::PartStart { headers }])
+  }
+
+  fn read_content(&mut self) -> Result<Vec<MultipartToken>, P10Error> {
+    match find(&self.buffer, &self.delimiter) {
+      Some(index) => {
+        let mut tokens = vec![];
+
+        if index > 0 {
+          let data = self.buffer[0..index].to_vec();
+          tokens.push(MultipartToken::PartData(data.into()));
+        }
+        tokens.push(MultipartToken::PartEnd);
+
+        self.consume(index + self.delimiter.len());
+        self.next_action = NextAction::ReadDelimiterSuffix;
+
+        Ok(tokens)
+      }
+
+      // Return all content that can't be the start of a delimiter
+      None => {
+        let retain_count = self.delimiter.len() - 1;
+        if self.buffer.len() <= retain_count {
+          return Err(self.data_required_error("Reading multipart content"));
+        }
+
+        let data_length = self.buffer.len() - retain_count;
+        let data = self.buffer[0..data_length].to_vec();
+        self.consume(data_length);
+
+        Ok(vec![MultipartToken::PartData(data.into())])
+      }
+    }
+  }
+
+  /// Removes bytes from the start of the buffer once they've been read.
+  ///
+  fn consume(&mut self, count: usize) {
+    self.buffer.drain(0..count);
+    self.bytes_read += count as u64;
+  }
+
+  fn data_required_error(&self, when: &str) -> P10Error {
+    if self.is_buffer_complete {
+      P10Error::DataEndedUnexpectedly {
+        when: when.to_string().into(),
+        path: DataSetPath::new(),
+        offset: self.offset(),
+      }
+    } else {
+      P10Error::DataRequired {
+        when: when.to_string().into(),
+      }
+    }
+  }
+
+  fn data_invalid_error(&self, when: &str, details: String) -> P10Error {
+    P10Error::DataInvalid {
+      when: when.to_string().into(),
+      details: details.into(),
+      path: DataSetPath::new(),
+      offset: self.offset(),
+    }
+  }
+
+  /// Returns the offset into the multipart body of the start of the buffer,
+  /// excluding the CRLF that was added to the buffer initially.
+  ///
+  fn offset(&self) -> u64 {
+    self.bytes_read.saturating_sub(2)
+  }
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+///
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  haystack
+    .windows(needle.len())
+    .position(|window| window == needle)
+}
+
+/// Returns the value of the `boundary` parameter in a multipart
+/// `Content-Type` header value, e.g. `multipart/related;
+/// type="application/dicom"; boundary=abc`.
+///
+pub fn boundary_from_content_type(content_type: &str) -> Option<String> {
+  content_type.split(';').skip(1).find_map(|parameter| {
+    let (name, value) = parameter.split_once('=')?;
+
+    if name.trim().eq_ignore_ascii_case("boundary") {
+      Some(value.trim().trim_matches('"').to_string())
+    } else {
+      None
+    }
+  })
+}
+
+/// Returns the `Content-Type` header value for a multipart body written by
+/// [`write_stream()`] with the given boundary.
+///
+pub fn content_type(boundary: &str) -> String {
+  format!("multipart/related; type=\"application/dicom\"; boundary={boundary}")
+}
+
+/// Reads a multipart body from a read stream, and reads each of its parts as
+/// DICOM P10 data into an in-memory data set. The content of each part is
+/// passed to a DICOM P10 read context as it is read, so the body is never
+/// held in memory in its entirety.
+///
+/// The chunk size defaults to 256 KiB if not specified.
+///
+pub fn read_stream<S: IoRead>(
+  stream: &mut S,
+  boundary: &str,
+  config: Option<P10ReadConfig>,
+  chunk_size: Option<usize>,
+) -> Result<Vec<DataSet>, P10Error> {
+  let chunk_size = chunk_size.unwrap_or(256 * 1024);
+
+  let mut context = MultipartReadContext::new(boundary);
+  let mut part: Option<(P10ReadContext, DataSetBuilder)> = None;
+  let mut data_sets = vec![];
+
+  loop {
+    let tokens = match context.read_tokens() {
+      Ok(tokens) => tokens,
+
+      // If the read context needs more data then read bytes from the stream,
+      // write them to the read context, and try again
+      Err(P10Error::DataRequired { .. }) => {
+        let mut buffer = vec![0u8; chunk_size];
+
+        let read_bytes_count =
+          stream.read(&mut buffer).map_err(|e| P10Error::FileError {
+            when: "Reading from stream".into(),
+            details: e.to_string().into(),
+          })?;
+
+        if read_bytes_count == 0 {
+          context.write_bytes(RcByteSlice::empty(), true)?;
+        } else {
+          buffer.resize(read_bytes_count, 0);
+          context.write_bytes(buffer.into(), false)?;
+        }
+
+        continue;
+      }
+
+      Err(e) => return Err(e),
+    };
+
+    for token in tokens {
+      match (token, part.as_mut()) {
+        (MultipartToken::PartStart { .. }, _) => {
+          part = Some((
+            P10ReadContext::new(config),
+            DataSetBuilder::new_for_read_config(config),
+          ));
+        }
+
+        (MultipartToken::PartData(bytes), Some((p10_context, builder))) => {
+          p10_context.write_bytes(bytes, false)?;
+          read_available_p10_tokens(p10_context, builder)?;
+        }
+
+        (MultipartToken::PartEnd, Some((p10_context, builder))) => {
+          p10_context.write_bytes(RcByteSlice::empty(), true)?;
+          read_available_p10_tokens(p10_context, builder)?;
+
+          let data_set =
+            builder
+              .final_data_set()
+              .map_err(|_| P10Error::DataInvalid {
+                when: "Reading multipart part".into(),
+                details: "Part does not contain a complete data set".into(),
+                path: DataSetPath::new(),
+                offset: 0,
+              })?;
+
+          data_sets.push(data_set);
+          part = None;
+        }
+
+        (MultipartToken::End, _) => return Ok(data_sets),
+
+        (_, None) => unreachable!(),
+      }
+    }
+  }
+}
+
+/// Reads all DICOM P10 tokens currently available in a read context into a
+/// data set builder.
+///
+fn read_available_p10_tokens(
+  context: &mut P10ReadContext,
+  builder: &mut DataSetBuilder,
+) -> Result<(), P10Error> {
+  while !builder.is_complete() {
+    match context.read_tokens() {
+      Ok(tokens) => builder.add_tokens(&tokens)?,
+      Err(P10Error::DataRequired { .. }) => break,
+      Err(e) => return Err(e),
+    }
+  }
+
+  Ok(())
+}
+
+/// Writes data sets to a write stream as a multipart body, with each data set
+/// written as DICOM P10 data in its own `application/dicom` part. The
+/// `Content-Type` header value for the body is returned by
+/// [`content_type()`].
+///
+pub fn write_stream<S: IoWrite>(
+  stream: &mut S,
+  data_sets: &[DataSet],
+  boundary: &str,
+  config: Option<P10WriteConfig>,
+) -> Result<(), P10Error> {
+  let mut write_bytes = |bytes: &[u8]| -> Result<(), P10Error> {
+    stream.write_all(bytes).map_err(|e| P10Error::FileError {
+      when: "Writing multipart data to stream".into(),
+      details: e.to_string().into(),
+    })
+  };
+
+  for data_set in data_sets {
+    write_bytes(
+      format!("--{boundary}\r\nContent-Type: application/dicom\r\n\r\n")
+        .as_bytes(),
+    )?;
+
+    p10_write::data_set_to_bytes(
+      data_set,
+      &DataSetPath::new(),
+      &mut |p10_bytes: RcByteSlice| write_bytes(&p10_bytes),
+      config.clone(),
+    )?;
+
+    write_bytes(b"\r\n")?;
+  }
+
+  write_bytes(format!("--{boundary}--\r\n").as_bytes())?;
+
+  stream.flush().map_err(|e| P10Error::FileError {
+    when: "Writing multipart data to stream".into(),
+    details: e.to_string().into(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use dcmfx_core::dictionary;
+
+  fn read_all_tokens(
+    body: &[u8],
+    boundary: &str,
+    chunk_size: usize,
+  ) -> Result<Vec<MultipartToken>, P10Error> {
+    let mut context = MultipartReadContext::new(boundary);
+    let mut chunks = body.chunks(chunk_size);
+    let mut tokens = vec![];
+
+    loop {
+      match context.read_tokens() {
+        Ok(new_tokens) => {
+          let is_end = new_tokens.last() == Some(&MultipartToken::End);
+          tokens.extend(new_tokens);
+
+          if is_end {
+            return Ok(tokens);
+          }
+        }
+
+        Err(P10Error::DataRequired { .. }) => match chunks.next() {
+          Some(chunk) => context.write_bytes(chunk.to_vec().into(), false)?,
+          None => context.write_bytes(RcByteSlice::empty(), true)?,
+        },
+
+        Err(e) => return Err(e),
+      }
+    }
+  }
+
+  /// Merges adjacent part data tokens so that results don't depend on how the
+  /// input was chunked.
+  ///
+  fn merge_part_data(tokens: Vec<MultipartToken>) -> Vec<MultipartToken> {
+    let mut merged: Vec<MultipartToken> = vec![];
+
+    for token in tokens {
+      match (merged.last_mut(), token) {
+        (Some(MultipartToken::PartData(a)), MultipartToken::PartData(b)) => {
+          let mut bytes = a.to_vec();
+          bytes.extend_from_slice(&b);
+          *a = bytes.into();
+        }
+
+        (_, token) => merged.push(token),
+      }
+    }
+
+    merged
+  }
+
+  #[test]
+  fn read_tokens_test() {
+    let body = b"preamble\r\n--abc\r\nContent-Type: text/plain\r\n\r\n\
+      Hello\r\n--abc  \r\n\r\nWorld\r\n--\r\n--abc--\r\nepilogue";
+
+    for chunk_size in [1, 2, 7, body.len()] {
+      let tokens = read_all_tokens(body, "abc", chunk_size).unwrap();
+
+      assert_eq!(
+        merge_part_data(tokens),
+        vec![
+          MultipartToken::PartStart {
+            headers: vec![(
+              "Content-Type".to_string(),
+              "text/plain".to_string()
+            )]
+          },
+          MultipartToken::PartData(b"Hello".to_vec().into()),
+          MultipartToken::PartEnd,
+          MultipartToken::PartStart { headers: vec![] },
+          MultipartToken::PartData(b"World\r\n--".to_vec().into()),
+          MultipartToken::PartEnd,
+          MultipartToken::End,
+        ]
+      );
+    }
+  }
+
+  #[test]
+  fn read_tokens_truncated_test() {
+    assert!(matches!(
+      read_all_tokens(b"--abc\r\n\r\nHello", "abc", 4),
+      Err(P10Error::DataEndedUnexpectedly { .. })
+    ));
+  }
+
+  #[test]
+  fn boundary_from_content_type_test() {
+    assert_eq!(
+      boundary_from_content_type(
+        "multipart/related; type=\"application/dicom\"; boundary=\"a b\""
+      ),
+      Some("a b".to_string())
+    );
+
+    assert_eq!(boundary_from_content_type("application/dicom"), None);
+  }
+
+  #[test]
+  fn write_and_read_stream_test() {
+    let mut data_sets = vec![];
+    for patient_id in ["1", "2"] {
+      let mut data_set = DataSet::new();
+      data_set
+        .insert_string_value(&dictionary::PATIENT_ID, &[patient_id])
+        .unwrap();
+      data_sets.push(data_set);
+    }
+
+    let mut body = vec![];
+    write_stream(&mut body, &data_sets, "boundary", None).unwrap();
+
+    let read_data_sets =
+      read_stream(&mut body.as_slice(), "boundary", None, Some(5)).unwrap();
+
+    assert_eq!(read_data_sets.len(), 2);
+    for (data_set, read_data_set) in data_sets.iter().zip(read_data_sets) {
+      assert_eq!(
+        read_data_set.get_string(dictionary::PATIENT_ID.tag),
+        data_set.get_string(dictionary::PATIENT_ID.tag)
+      );
+    }
+  }
+}
@@ -3,15 +3,18 @@
 
 #[cfg(not(feature = "std"))]
 use alloc::{
+  boxed::Box,
   format,
   string::{String, ToString},
   vec,
   vec::Vec,
 };
 
-use dcmfx_core::{DataSetPath, DcmfxError};
+use dcmfx_core::{
+  DataSetPath, DcmfxError, ErrorContext, ErrorContextChain, ErrorDetails,
+};
 
-use crate::P10Token;
+use crate::{P10SizeLimitGroup, P10Token};
 
 /// An error that occurred when reading or writing DICOM P10 data.
 ///
@@ -29,18 +32,18 @@ pub enum P10Error {
   /// sets defined by the DICOM standard are supported.
   SpecificCharacterSetInvalid {
     specific_character_set: String,
-    details: String,
+    details: ErrorDetails,
   },
 
   /// This error occurs when a DICOM P10 read context requires more data to be
   /// added to it before the next token can be read.
-  DataRequired { when: String },
+  DataRequired { when: ErrorDetails },
 
   /// This error occurs when a DICOM P10 read context reaches the end of its
   /// data while reading the next token, and no more data is able to be added.
   /// This means the provided data is malformed or truncated.
   DataEndedUnexpectedly {
-    when: String,
+    when: ErrorDetails,
     path: DataSetPath,
     offset: u64,
   },
@@ -53,8 +56,8 @@ pub enum P10Error {
   /// DICOM P10 token because the supplied data is invalid, and also when a
   /// DICOM P10 write context is unable to serialize a token written to it.
   DataInvalid {
-    when: String,
-    details: String,
+    when: ErrorDetails,
+    details: ErrorDetails,
     path: DataSetPath,
     offset: u64,
   },
@@ -63,18 +66,27 @@ pub enum P10Error {
   /// context is exceeded during reading of the supplied data. These maximums
   /// are used to control memory usage when reading.
   MaximumExceeded {
-    details: String,
+    details: ErrorDetails,
     path: DataSetPath,
     offset: u64,
   },
 
+  /// This error occurs when a [`crate::P10SizeLimitTransform`] sees data element
+  /// values whose cumulative size exceeds one of its configured limits.
+  SizeLimitExceeded {
+    details: ErrorDetails,
+    group: P10SizeLimitGroup,
+    limit: u64,
+    size: u64,
+  },
+
   /// This error occurs when a stream of [`P10Token`]s is being ingested and a
   /// token is received that is invalid at the current location in the token
   /// stream. E.g. a [`P10Token::DataElementValueBytes`] token that does not
   /// follow a [`P10Token::DataElementHeader`].
   TokenStreamInvalid {
-    when: String,
-    details: String,
+    when: ErrorDetails,
+    details: ErrorDetails,
     token: P10Token,
   },
 
@@ -84,16 +96,32 @@ pub enum P10Error {
 
   /// This error occurs when there is an error with an underlying file or file
   /// stream.
-  FileError { when: String, details: String },
+  FileError {
+    when: ErrorDetails,
+    details: ErrorDetails,
+  },
 
   /// A fallback/general-purpose error for cases not covered by the other error
   /// variants.
-  OtherError { error_type: String, details: String },
+  OtherError {
+    error_type: String,
+    details: ErrorDetails,
+  },
+
+  /// Wraps another error with an [`ErrorContext`] that describes where it
+  /// occurred. See [`ErrorContextChain`].
+  WithContext {
+    error: Box<P10Error>,
+    context: ErrorContext,
+  },
 }
 
 impl core::fmt::Display for P10Error {
   fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-    write!(f, "DICOM P10 error: {}", self.name())
+    match self {
+      Self::WithContext { error, context } => write!(f, "{error} ({context})"),
+      _ => write!(f, "DICOM P10 error: {}", self.name()),
+    }
   }
 }
 
@@ -113,10 +141,12 @@ impl P10Error {
       Self::DicmPrefixNotPresent => "'DICM' prefix is not present",
       Self::DataInvalid { .. } => "Invalid data",
       Self::MaximumExceeded { .. } => "Maximum exceeded",
+      Self::SizeLimitExceeded { .. } => "Size limit exceeded",
       Self::TokenStreamInvalid { .. } => "P10 token stream invalid",
       Self::WriteAfterCompletion => "Write after completion",
       Self::FileError { .. } => "File I/O failure",
       Self::OtherError { error_type, .. } => error_type,
+      Self::WithContext { error, .. } => error.name(),
     }
   }
 
@@ -131,10 +161,23 @@ impl P10Error {
       Self::DicmPrefixNotPresent => "",
       Self::DataInvalid { details, .. } => details,
       Self::MaximumExceeded { details, .. } => details,
+      Self::SizeLimitExceeded { details, .. } => details,
       Self::TokenStreamInvalid { details, .. } => details,
       Self::WriteAfterCompletion => "",
       Self::FileError { details, .. } => details,
       Self::OtherError { details, .. } => details,
+      Self::WithContext { error, .. } => error.details(),
+    }
+  }
+  /// Returns the `path` field of the error, if one exists.
+  ///
+  pub fn path(&self) -> Option<&DataSetPath> {
+    match self {
+      Self::DataEndedUnexpectedly { path, .. }
+      | Self::DataInvalid { path, .. }
+      | Self::MaximumExceeded { path, .. } => Some(path),
+      Self::WithContext { error, .. } => error.path(),
+      _ => None,
     }
   }
 }
@@ -144,6 +187,12 @@ impl DcmfxError for P10Error {
   /// format.
   ///
   fn to_lines(&self, task_description: &str) -> Vec<String> {
+    if let Self::WithContext { error, context } = self {
+      let mut lines = error.to_lines(task_description);
+      context.append_to_lines(&mut lines);
+      return lines;
+    }
+
     let mut lines = vec![];
 
     lines.push(format!("DICOM P10 error {task_description}"));
@@ -193,6 +242,7 @@ impl DcmfxError for P10Error {
 
       P10Error::DataInvalid { details, .. }
       | P10Error::MaximumExceeded { details, .. }
+      | P10Error::SizeLimitExceeded { details, .. }
       | P10Error::FileError { details, .. }
       | P10Error::OtherError { details, .. } => {
         lines.push(format!("  Details: {details}"));
@@ -216,3 +266,38 @@ impl DcmfxError for P10Error {
     lines
   }
 }
+
+impl ErrorContextChain for P10Error {
+  fn with_context(self, context: ErrorContext) -> Self {
+    match self {
+      Self::WithContext {
+        error,
+        context: existing_context,
+      } => Self::WithContext {
+        error,
+        context: existing_context.merge(context),
+      },
+
+      error if context.is_empty() => error,
+
+      error => Self::WithContext {
+        error: Box::new(error),
+        context,
+      },
+    }
+  }
+
+  fn context(&self) -> Option<&ErrorContext> {
+    match self {
+      Self::WithContext { context, .. } => Some(context),
+      _ => None,
+    }
+  }
+
+  fn without_context(&self) -> &Self {
+    match self {
+      Self::WithContext { error, .. } => error,
+      _ => self,
+    }
+  }
+}
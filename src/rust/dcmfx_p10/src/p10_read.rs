@@ -21,17 +21,20 @@ use byteorder::ByteOrder;
 
 use dcmfx_core::{
   DataElementTag, DataElementValue, DataError, DataSet, DataSetPath,
-  RcByteSlice, TransferSyntax, ValueRepresentation, dictionary,
-  transfer_syntax,
+  ErrorContext, ErrorContextChain, RcByteSlice, TransferSyntax,
+  ValueRepresentation,
+  data_element_value::unique_identifier::{self, UidConformanceError},
+  dictionary, error_details, transfer_syntax,
 };
 
 use crate::internal::byte_stream::{ByteStream, ByteStreamError};
 use crate::internal::data_element_header::{
   DataElementHeader, ValueLengthSize,
 };
-use crate::internal::p10_location::{self, P10Location};
+use crate::internal::p10_location::{self, DataElementOrder, P10Location};
 use crate::{
-  P10Error, P10ReadConfig, P10Token, internal::value_length::ValueLength,
+  DuplicateDataElementPolicy, P10Error, P10ReadConfig, P10Token,
+  TrailingDataPolicy, internal::value_length::ValueLength,
 };
 
 /// A read context holds the current state of an in-progress DICOM P10 read. Raw
@@ -50,6 +53,45 @@ pub struct P10ReadContext {
   path: DataSetPath,
   location: P10Location,
   has_emitted_specific_character_set_data_element: bool,
+  last_data_element_header: (DataElementTag, u64),
+  uid_conformance_warnings: Vec<(DataSetPath, UidConformanceError)>,
+  data_set_trailing_padding: Option<(u64, u32)>,
+  trailing_bytes: Option<(u64, u64)>,
+  statistics: P10ReadStatistics,
+}
+
+/// Statistics on the DICOM P10 data read by a [`P10ReadContext`]. These are
+/// updated as tokens are read, and are complete once the final
+/// [`P10Token::End`] token has been read.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct P10ReadStatistics {
+  /// The number of data elements read, including sequences and data elements
+  /// nested inside sequences. File Meta Information data elements are not
+  /// included.
+  pub data_element_count: u64,
+
+  /// The number of sequences read, not including encapsulated pixel data.
+  pub sequence_count: u64,
+
+  /// The deepest level of sequence nesting read. This is zero if there are no
+  /// sequence items.
+  pub max_sequence_depth: usize,
+
+  /// The number of string values with non-ASCII content that were converted
+  /// to UTF-8 from the character set specified by *'(0008,0005) Specific
+  /// Character Set'*.
+  pub character_set_conversion_count: u64,
+
+  /// The number of data elements that had their VR inferred because it was
+  /// implicit or unknown.
+  pub vr_inference_count: u64,
+
+  /// The number of anomalies in the DICOM P10 data that were recovered from
+  /// rather than causing an error, e.g. a missing `DICM` prefix, duplicate or
+  /// out of order data elements, a blank VR, or a sequence delimiter outside
+  /// of a sequence.
+  pub recovered_anomaly_count: u64,
 }
 
 /// The next action specifies what will be attempted to be read next from a read
@@ -75,6 +117,9 @@ enum NextAction {
   ReadPixelDataItem {
     vr: ValueRepresentation,
   },
+  ReadTrailingBytes {
+    preserved_bytes: Option<Vec<u8>>,
+  },
 }
 
 impl P10ReadContext {
@@ -91,6 +136,11 @@ impl P10ReadContext {
       path: DataSetPath::new(),
       location: P10Location::new(),
       has_emitted_specific_character_set_data_element: false,
+      last_data_element_header: (DataElementTag::ZERO, 0),
+      uid_conformance_warnings: vec![],
+      data_set_trailing_padding: None,
+      trailing_bytes: None,
+      statistics: P10ReadStatistics::default(),
     }
   }
 
@@ -102,6 +152,62 @@ impl P10ReadContext {
     self.transfer_syntax
   }
 
+  /// Returns the number of bytes that have been consumed by the read context
+  /// while reading out DICOM P10 tokens. If the transfer syntax is deflated
+  /// then bytes following the File Meta Information are counted after they
+  /// have been inflated.
+  ///
+  pub fn bytes_read(&self) -> u64 {
+    self.stream.bytes_read()
+  }
+
+  /// Returns the tag and byte offset of the most recently read data element
+  /// header, i.e. the data element currently being read. As with
+  /// [`Self::bytes_read()`], offsets are counted after inflation when the
+  /// transfer syntax is deflated.
+  ///
+  /// Note that the tokens emitted for a data element may not have been read
+  /// out yet, and that a data element inserted by the read context, such as
+  /// '(0008,0005) Specific Character Set', doesn't have its own header.
+  ///
+  pub fn last_data_element_header(&self) -> (DataElementTag, u64) {
+    self.last_data_element_header
+  }
+
+  /// Returns the non-conformant UIDs that have been read, along with the path
+  /// of the data element they were read from. These are only recorded when
+  /// [`P10ReadConfig::validate_uids()`] is enabled.
+  ///
+  pub fn uid_conformance_warnings(
+    &self,
+  ) -> &[(DataSetPath, UidConformanceError)] {
+    &self.uid_conformance_warnings
+  }
+
+  /// Returns the byte offset and length of the value of the *'(FFFC,FFFC) Data
+  /// Set Trailing Padding'* data element in the root data set, if one has been
+  /// read. See [`P10ReadConfig::trailing_data_policy()`].
+  ///
+  pub fn data_set_trailing_padding(&self) -> Option<(u64, u32)> {
+    self.data_set_trailing_padding
+  }
+
+  /// Returns the byte offset and length of the trailing bytes that follow the
+  /// final data element and can't be read as a data element, if any have been
+  /// read. The length increases as trailing bytes are read. See
+  /// [`P10ReadConfig::trailing_data_policy()`].
+  ///
+  pub fn trailing_bytes(&self) -> Option<(u64, u64)> {
+    self.trailing_bytes
+  }
+
+  /// Returns statistics on the DICOM P10 data read so far, which can be used
+  /// to log data quality metrics without a second pass over the data.
+  ///
+  pub fn statistics(&self) -> &P10ReadStatistics {
+    &self.statistics
+  }
+
   /// Writes raw DICOM P10 bytes to a read context that will be parsed into
   /// DICOM P10 tokens by subsequent calls to [`Self::read_tokens()`]. If `done`
   /// is true this indicates the end of the incoming DICOM P10 data to be
@@ -130,7 +236,43 @@ impl P10ReadContext {
   /// [`Self::write_bytes`] are required before the next token is able to be
   /// read.
   ///
+  /// Other errors have an [`ErrorContext`] attached that gives the path and
+  /// transfer syntax in use when the error occurred.
+  ///
   pub fn read_tokens(&mut self) -> Result<Vec<P10Token>, P10Error> {
+    self
+      .read_next_tokens()
+      .map_err(|e| self.add_error_context(e))
+  }
+
+  /// Attaches the current path and transfer syntax to an error that occurred
+  /// while reading. Errors that signal the state of the incoming data rather
+  /// than a problem with it are returned unchanged.
+  ///
+  fn add_error_context(&self, error: P10Error) -> P10Error {
+    match error {
+      P10Error::DataRequired { .. }
+      | P10Error::DicmPrefixNotPresent
+      | P10Error::WriteAfterCompletion => error,
+
+      _ => {
+        let path = error.path().unwrap_or(&self.path);
+        let mut context = ErrorContext::new().with_path(path);
+
+        if !matches!(
+          self.next_action,
+          NextAction::ReadFilePreambleAndDICMPrefix
+            | NextAction::ReadFileMetaInformation { .. }
+        ) {
+          context = context.with_transfer_syntax(self.active_transfer_syntax());
+        }
+
+        error.with_context(context)
+      }
+    }
+  }
+
+  fn read_next_tokens(&mut self) -> Result<Vec<P10Token>, P10Error> {
     match self.next_action {
       NextAction::ReadFilePreambleAndDICMPrefix => {
         self.read_file_preamble_and_dicm_prefix_token()
@@ -149,7 +291,7 @@ impl P10ReadContext {
         }
 
         // Detect the end of the DICOM data
-        if self.stream.is_fully_consumed() {
+        if self.stream.is_fully_consumed() || self.trailing_bytes.is_some() {
           // Return the tokens required to end any active sequences and items.
           //
           // This means there is no check that all items and sequences have been
@@ -165,6 +307,12 @@ impl P10ReadContext {
         } else {
           let is_at_root = self.path.entries().is_empty();
 
+          // Check for trailing bytes in the root data set that can't be read
+          // as a data element
+          if is_at_root && self.is_at_trailing_bytes()? {
+            return self.start_reading_trailing_bytes();
+          }
+
           // There is more data so start reading the next data element
           let (mut tokens, tag) = self.read_data_element_header_token()?;
 
@@ -203,6 +351,8 @@ impl P10ReadContext {
       NextAction::ReadPixelDataItem { vr } => {
         self.read_pixel_data_item_token(vr)
       }
+
+      NextAction::ReadTrailingBytes { .. } => self.read_trailing_bytes_tokens(),
     }
   }
 
@@ -252,6 +402,8 @@ impl P10ReadContext {
         } else {
           // The 'DICM' prefix is absent but is not configured as required, so
           // return empty preamble bytes
+          self.statistics.recovered_anomaly_count += 1;
+
           Ok(Box::new([0u8; 128]))
         }
       }
@@ -263,6 +415,8 @@ impl P10ReadContext {
         if self.config.require_dicm_prefix {
           Err(P10Error::DicmPrefixNotPresent)
         } else {
+          self.statistics.recovered_anomaly_count += 1;
+
           Ok(Box::new([0; 128]))
         }
       }
@@ -335,8 +489,8 @@ impl P10ReadContext {
       // Get the VR for the data element
       let vr = ValueRepresentation::from_bytes(&data[4..6]).map_err(|_| {
         P10Error::DataInvalid {
-          when: "Reading File Meta Information".to_string(),
-          details: "Data element has invalid VR".to_string(),
+          when: "Reading File Meta Information".into(),
+          details: "Data element has invalid VR".into(),
           path: DataSetPath::new_with_data_element(tag),
           offset: self.stream.bytes_read(),
         }
@@ -346,9 +500,8 @@ impl P10ReadContext {
       // Meta Information
       if vr == ValueRepresentation::Sequence {
         return Err(P10Error::DataInvalid {
-          when: "Reading File Meta Information".to_string(),
-          details: "Data element in File Meta Information is a sequence"
-            .to_string(),
+          when: "Reading File Meta Information".into(),
+          details: "Data element in File Meta Information is a sequence".into(),
           path: DataSetPath::new_with_data_element(tag),
           offset: self.stream.bytes_read(),
         });
@@ -385,7 +538,7 @@ impl P10ReadContext {
         > u64::from(self.config.max_token_size)
       {
         return Err(P10Error::MaximumExceeded {
-          details: format!(
+          details: error_details!(
             "File Meta Information exceeds the max token size of {} bytes",
             self.config.max_token_size
           ),
@@ -416,8 +569,8 @@ impl P10ReadContext {
             Ok(i) => *ends_at = Some(*starts_at + 12 + u64::from(i)),
             Err(_) => {
               return Err(P10Error::DataInvalid {
-                when: "Reading File Meta Information".to_string(),
-                details: format!(
+                when: "Reading File Meta Information".into(),
+                details: error_details!(
                   "Group length is invalid: {:?}",
                   value.to_string(DataElementTag::ZERO, 80)
                 ),
@@ -446,8 +599,8 @@ impl P10ReadContext {
               Ok(self.transfer_syntax)
             } else {
               Err(P10Error::DataInvalid {
-                when: "Reading File Meta Information".to_string(),
-                details: e.to_string(),
+                when: "Reading File Meta Information".into(),
+                details: e.to_string().into(),
                 path: DataSetPath::new_with_data_element(
                   dictionary::TRANSFER_SYNTAX_UID.tag,
                 ),
@@ -469,8 +622,8 @@ impl P10ReadContext {
         Err(_) => {
           return Err(P10Error::DataInvalid {
             when: "Starting zlib decompression for deflated transfer syntax"
-              .to_string(),
-            details: "Zlib data is invalid".to_string(),
+              .into(),
+            details: "Zlib data is invalid".into(),
             path: DataSetPath::new(),
             offset: self.stream.bytes_read(),
           });
@@ -500,19 +653,29 @@ impl P10ReadContext {
   fn read_data_element_header_token(
     &mut self,
   ) -> Result<(Vec<P10Token>, DataElementTag), P10Error> {
+    let header_offset = self.stream.bytes_read();
+
+    // This check peeks the start of the value, so it happens before the header
+    // is read and consumed
+    let value_starts_with_item = self.implicit_vr_value_starts_with_item()?;
+
     // Read a data element header if bytes for one are available
     let header = self.read_data_element_header()?;
 
+    self.last_data_element_header = (header.tag, header_offset);
+
     // If the VR is UN (Unknown) then attempt to infer it
     let vr = match header.vr {
       Some(ValueRepresentation::Unknown) => {
+        self.statistics.vr_inference_count += 1;
+
         Some(self.location.infer_vr_for_tag(header.tag).map_err(
           |missing_tag| P10Error::DataInvalid {
-            when: format!(
+            when: error_details!(
               "Inferring VR for data element '{}'",
               dictionary::tag_with_name(header.tag, None)
             ),
-            details: format!(
+            details: error_details!(
               "The value for the '{}' data element is missing or invalid",
               dictionary::tag_with_name(missing_tag, None)
             ),
@@ -524,6 +687,15 @@ impl P10ReadContext {
       vr => vr,
     };
 
+    // If the VR is still unknown but the value starts with an item then read
+    // the value as an implicit VR sequence
+    let vr =
+      if value_starts_with_item && vr == Some(ValueRepresentation::Unknown) {
+        Some(ValueRepresentation::Sequence)
+      } else {
+        vr
+      };
+
     match (header.tag, vr, header.length) {
       // If this is the start of a new sequence then add it to the location
       (tag, Some(ValueRepresentation::Sequence), _)
@@ -547,7 +719,7 @@ impl P10ReadContext {
           .location
           .add_sequence(tag, is_implicit_vr, ends_at)
           .map_err(|details| P10Error::DataInvalid {
-            when: "Reading data element header".to_string(),
+            when: "Reading data element header".into(),
             details,
             path: self.path.clone(),
             offset: self.stream.bytes_read(),
@@ -556,7 +728,7 @@ impl P10ReadContext {
         // Check that the maximum sequence depth hasn't been reached
         if self.path.len() / 2 >= self.config.max_sequence_depth {
           return Err(P10Error::MaximumExceeded {
-            details: "Maximum allowed sequence depth reached".to_string(),
+            details: "Maximum allowed sequence depth reached".into(),
             path: self.path.clone(),
             offset: self.stream.bytes_read(),
           });
@@ -565,6 +737,9 @@ impl P10ReadContext {
         // Add sequence to the path
         self.path.add_data_element(tag).unwrap();
 
+        self.statistics.data_element_count += 1;
+        self.statistics.sequence_count += 1;
+
         Ok((
           vec![P10Token::SequenceStart {
             tag,
@@ -586,7 +761,7 @@ impl P10ReadContext {
 
         let index = self.location.add_item(ends_at, header.length).map_err(
           |details| P10Error::DataInvalid {
-            when: "Reading data element header".to_string(),
+            when: "Reading data element header".into(),
             details,
             path: self.path.clone(),
             offset: self.stream.bytes_read(),
@@ -596,6 +771,11 @@ impl P10ReadContext {
         // Add item to the path
         self.path.add_sequence_item(index).unwrap();
 
+        self.statistics.max_sequence_depth = self
+          .statistics
+          .max_sequence_depth
+          .max(self.path.sequence_item_count());
+
         Ok((vec![P10Token::SequenceItemStart { index }], header.tag))
       }
 
@@ -612,7 +792,7 @@ impl P10ReadContext {
           .location
           .add_sequence(tag, false, None)
           .map_err(|details| P10Error::DataInvalid {
-            when: "Reading data element header".to_string(),
+            when: "Reading data element header".into(),
             details,
             path: self.path.clone(),
             offset: self.stream.bytes_read(),
@@ -620,6 +800,8 @@ impl P10ReadContext {
 
         self.path.add_data_element(tag).unwrap();
 
+        self.statistics.data_element_count += 1;
+
         self.next_action = NextAction::ReadPixelDataItem { vr };
 
         Ok((
@@ -647,6 +829,7 @@ impl P10ReadContext {
           // sequence delimiters have been observed in some DICOM P10 data, and
           // not propagating an error right here doesn't do any harm and allows
           // such data to be read.
+          self.statistics.recovered_anomaly_count += 1;
 
           vec![]
         };
@@ -663,7 +846,7 @@ impl P10ReadContext {
           .location
           .end_item()
           .map_err(|details| P10Error::DataInvalid {
-            when: "Reading data element header".to_string(),
+            when: "Reading data element header".into(),
             details,
             path: self.path.clone(),
             offset: self.stream.bytes_read(),
@@ -686,7 +869,7 @@ impl P10ReadContext {
         // doesn't exceed the max string size
         if materialized_value_required && length > self.config.max_string_size {
           return Err(P10Error::MaximumExceeded {
-            details: format!(
+            details: error_details!(
               "Value for '{}' with VR {} and length {} bytes exceeds the \
               maximum allowed string size of {} bytes",
               dictionary::tag_with_name(header.tag, None),
@@ -699,26 +882,41 @@ impl P10ReadContext {
           });
         }
 
+        let is_at_root = self.path.is_root();
+
+        self.statistics.data_element_count += 1;
+
         // Add data element to the path
         self
           .path
           .add_data_element(tag)
           .map_err(|_| P10Error::DataInvalid {
-            when: "Reading data element header".to_string(),
-            details: format!(
+            when: "Reading data element header".into(),
+            details: error_details!(
               "Data element '{header}' is not valid for the current path"
             ),
             path: self.path.clone(),
             offset: self.stream.bytes_read(),
           })?;
 
-        // Swallow the '(FFFC,FFFC) Data Set Trailing Padding' data element. No
-        // tokens for it are emitted. Ref: PS3.10 7.2.
+        // Swallow the '(FFFC,FFFC) Data Set Trailing Padding' data element
+        // unless it's in the root data set and is being preserved. Ref: PS3.10
+        // 7.2.
         //
         // Also swallow group length tags that have an element of 0x0000.
         // Ref: PS3.5 7.2.
-        let emit_tokens = header.tag
-          != dictionary::DATA_SET_TRAILING_PADDING.tag
+        let is_trailing_padding =
+          header.tag == dictionary::DATA_SET_TRAILING_PADDING.tag;
+
+        if is_trailing_padding && is_at_root {
+          self.data_set_trailing_padding =
+            Some((self.stream.bytes_read(), length));
+        }
+
+        let emit_tokens = (!is_trailing_padding
+          || (is_at_root
+            && self.config.trailing_data_policy
+              == TrailingDataPolicy::Preserve))
           && header.tag.element != 0x0000;
 
         // If the whole value is being materialized then the DataElementHeader
@@ -749,14 +947,201 @@ impl P10ReadContext {
       }
 
       (_, _, _) => Err(P10Error::DataInvalid {
-        when: "Reading data element header".to_string(),
-        details: format!("Invalid data element '{header}'"),
+        when: "Reading data element header".into(),
+        details: error_details!("Invalid data element '{header}'"),
         path: self.path.clone(),
         offset: self.stream.bytes_read(),
       }),
     }
   }
 
+  /// Returns whether the next bytes in the root data set are trailing bytes
+  /// that can't be read as a data element header. This is the case when too
+  /// few bytes remain for a header, when the tag is (0000,0000), or when an
+  /// explicit VR isn't recognized.
+  ///
+  fn is_at_trailing_bytes(&mut self) -> Result<bool, P10Error> {
+    let data = match self.stream.peek(8) {
+      Ok(data) => data,
+      Err(ByteStreamError::DataEnd) => return Ok(true),
+      Err(e) => {
+        return Err(
+          self.map_byte_stream_error(e, "Reading data element header"),
+        );
+      }
+    };
+
+    let transfer_syntax = self.active_transfer_syntax();
+
+    let tag = match transfer_syntax.endianness {
+      transfer_syntax::Endianness::LittleEndian => DataElementTag::new(
+        byteorder::LittleEndian::read_u16(&data[0..2]),
+        byteorder::LittleEndian::read_u16(&data[2..4]),
+      ),
+      transfer_syntax::Endianness::BigEndian => DataElementTag::new(
+        byteorder::BigEndian::read_u16(&data[0..2]),
+        byteorder::BigEndian::read_u16(&data[2..4]),
+      ),
+    };
+
+    if tag == DataElementTag::ZERO {
+      return Ok(true);
+    }
+
+    // The item and delimitation tags always use implicit VRs, so there's no
+    // explicit VR to check
+    if transfer_syntax.vr_serialization
+      == transfer_syntax::VrSerialization::VrImplicit
+      || tag.group == 0xFFFE
+    {
+      return Ok(false);
+    }
+
+    let vr = match ValueRepresentation::from_bytes(&data[4..6]) {
+      Ok(vr) => vr,
+      Err(_) => match data[4..6] {
+        [0x00, 0x00] | [0x20, 0x20] => ValueRepresentation::Unknown,
+        _ => return Ok(true),
+      },
+    };
+
+    // Check there are enough bytes for the full header
+    if matches!(
+      DataElementHeader::value_length_size(vr),
+      ValueLengthSize::U32
+    ) {
+      match self.stream.peek(12) {
+        Ok(_) => (),
+        Err(ByteStreamError::DataEnd) => return Ok(true),
+        Err(e) => {
+          return Err(
+            self.map_byte_stream_error(e, "Reading data element header"),
+          );
+        }
+      }
+    }
+
+    Ok(false)
+  }
+
+  /// Starts reading the trailing bytes in the root data set, or returns an
+  /// error if trailing bytes aren't allowed.
+  ///
+  fn start_reading_trailing_bytes(
+    &mut self,
+  ) -> Result<Vec<P10Token>, P10Error> {
+    let offset = self.stream.bytes_read();
+
+    let preserved_bytes = match self.config.trailing_data_policy {
+      TrailingDataPolicy::Error => {
+        return Err(P10Error::DataInvalid {
+          when: "Reading data element header".into(),
+          details: "Trailing bytes following the final data element can't be \
+            read as a data element"
+            .into(),
+          path: self.path.clone(),
+          offset,
+        });
+      }
+
+      // Trailing bytes that follow a preserved trailing padding data element
+      // are discarded because only one trailing padding data element is
+      // allowed
+      TrailingDataPolicy::Preserve
+        if self.data_set_trailing_padding.is_none() =>
+      {
+        Some(vec![])
+      }
+
+      _ => None,
+    };
+
+    self.trailing_bytes = Some((offset, 0));
+    self.next_action = NextAction::ReadTrailingBytes { preserved_bytes };
+
+    self.read_trailing_bytes_tokens()
+  }
+
+  /// Reads all remaining trailing bytes. If they're being preserved then they
+  /// are emitted as a '(FFFC,FFFC) Data Set Trailing Padding' data element once
+  /// the end of the data is reached.
+  ///
+  fn read_trailing_bytes_tokens(&mut self) -> Result<Vec<P10Token>, P10Error> {
+    loop {
+      let data = match self.stream.read_available(256 * 1024) {
+        Ok(data) => data,
+        Err(e) => {
+          return Err(self.map_byte_stream_error(e, "Reading trailing bytes"));
+        }
+      };
+
+      if data.is_empty() {
+        break;
+      }
+
+      if let Some((_, length)) = self.trailing_bytes.as_mut() {
+        *length += data.len() as u64;
+      }
+
+      if let NextAction::ReadTrailingBytes {
+        preserved_bytes: Some(preserved_bytes),
+      } = &mut self.next_action
+      {
+        if preserved_bytes.len() + data.len()
+          > self.config.max_token_size as usize
+        {
+          return Err(P10Error::MaximumExceeded {
+            details: error_details!(
+              "Trailing bytes being preserved exceed the maximum token size \
+              of {} bytes",
+              self.config.max_token_size
+            ),
+            path: self.path.clone(),
+            offset: self.stream.bytes_read(),
+          });
+        }
+
+        preserved_bytes.extend_from_slice(&data);
+      }
+    }
+
+    let next_action = core::mem::replace(
+      &mut self.next_action,
+      NextAction::ReadDataElementHeader,
+    );
+
+    let mut tokens = vec![];
+
+    if let NextAction::ReadTrailingBytes {
+      preserved_bytes: Some(preserved_bytes),
+    } = next_action
+      && !preserved_bytes.is_empty()
+    {
+      if !self.has_emitted_specific_character_set_data_element {
+        tokens.extend(Self::specific_character_set_utf8_tokens());
+        self.has_emitted_specific_character_set_data_element = true;
+      }
+
+      let tag = dictionary::DATA_SET_TRAILING_PADDING.tag;
+      let vr = ValueRepresentation::OtherByteString;
+
+      tokens.push(P10Token::DataElementHeader {
+        tag,
+        vr,
+        length: preserved_bytes.len() as u32,
+        path: DataSetPath::new_with_data_element(tag),
+      });
+      tokens.push(P10Token::DataElementValueBytes {
+        tag,
+        vr,
+        data: preserved_bytes.into(),
+        bytes_remaining: 0,
+      });
+    }
+
+    Ok(tokens)
+  }
+
   /// Returns the two tokens for the '(0008,0005) Specific Character Set' data
   /// element that specifies UTF-8 (ISO_IR 192).
   ///
@@ -834,25 +1219,56 @@ impl P10ReadContext {
   }
 
   /// Checks that the specified data element tag is greater than the previous
-  /// one at the current P10 location.
+  /// one at the current P10 location, and that it isn't a duplicate if
+  /// duplicates aren't allowed.
   ///
   fn check_data_element_ordering(
     &mut self,
     header: &DataElementHeader,
   ) -> Result<(), P10Error> {
-    if !self.config.require_ordered_data_elements {
-      return Ok(());
-    }
+    // Every tag only needs to be tracked when duplicates are an error and data
+    // elements aren't required to be in ascending order. Otherwise duplicates
+    // are either adjacent or out of order, or are handled by the data set
+    // builder.
+    let is_duplicate_error = self.config.duplicate_data_element_policy
+      == DuplicateDataElementPolicy::Error;
+
+    let order = self.location.check_data_element_ordering(
+      header.tag,
+      is_duplicate_error && !self.config.require_ordered_data_elements,
+    );
+
+    match order {
+      DataElementOrder::Duplicate if is_duplicate_error => {
+        Err(P10Error::DataInvalid {
+          when: "Reading data element header".into(),
+          details: error_details!("Data element '{header}' is a duplicate"),
+          path: self.path.clone(),
+          offset: self.stream.bytes_read(),
+        })
+      }
 
-    self
-      .location
-      .check_data_element_ordering(header.tag)
-      .map_err(|_| P10Error::DataInvalid {
-        when: "Reading data element header".to_string(),
-        details: format!("Data element '{header}' is not in ascending order"),
-        path: self.path.clone(),
-        offset: self.stream.bytes_read(),
-      })
+      DataElementOrder::NotAscending
+        if self.config.require_ordered_data_elements =>
+      {
+        Err(P10Error::DataInvalid {
+          when: "Reading data element header".into(),
+          details: error_details!(
+            "Data element '{header}' is not in ascending order"
+          ),
+          path: self.path.clone(),
+          offset: self.stream.bytes_read(),
+        })
+      }
+
+      DataElementOrder::Duplicate | DataElementOrder::NotAscending => {
+        self.statistics.recovered_anomaly_count += 1;
+
+        Ok(())
+      }
+
+      DataElementOrder::Ascending => Ok(()),
+    }
   }
 
   /// Returns the transfer syntax that should be used to decode the current
@@ -915,6 +1331,58 @@ impl P10ReadContext {
     }
   }
 
+  /// Returns whether the next data element has an implicit VR, a defined
+  /// length, and a value that starts with an item. Such values are read as
+  /// sequences when [`P10ReadConfig::infer_implicit_vr_sequences()`] is
+  /// enabled, which allows private sequences that have no dictionary entry to
+  /// be navigated.
+  ///
+  /// The data element header and the following item header are peeked, and
+  /// nothing is consumed.
+  ///
+  fn implicit_vr_value_starts_with_item(&mut self) -> Result<bool, P10Error> {
+    if !self.config.infer_implicit_vr_sequences
+      || self.active_transfer_syntax().vr_serialization
+        != transfer_syntax::VrSerialization::VrImplicit
+    {
+      return Ok(false);
+    }
+
+    let data = match self.stream.peek(16) {
+      Ok(data) => data,
+
+      // If the data ends before the item header then this data element can't
+      // be a sequence
+      Err(ByteStreamError::DataEnd) => return Ok(false),
+
+      Err(e) => {
+        return Err(
+          self.map_byte_stream_error(e, "Reading data element header"),
+        );
+      }
+    };
+
+    // Item and delimitation tags are never sequences
+    let group = byteorder::LittleEndian::read_u16(&data[0..2]);
+    if group == 0xFFFE {
+      return Ok(false);
+    }
+
+    let length = byteorder::LittleEndian::read_u32(&data[4..8]);
+    if length == 0xFFFFFFFF || length < 8 {
+      return Ok(false);
+    }
+
+    if data[8..12] != [0xFE, 0xFF, 0x00, 0xE0] {
+      return Ok(false);
+    }
+
+    // The item must have an undefined length, or fit within the value
+    let item_length = byteorder::LittleEndian::read_u32(&data[12..16]);
+
+    Ok(item_length == 0xFFFFFFFF || item_length <= length - 8)
+  }
+
   /// Reads the explicit VR and value length following a data element tag when
   /// the transfer syntax is not 'Implicit VR Little Endian'.
   ///
@@ -935,11 +1403,15 @@ impl P10ReadContext {
           // part of the DICOM P10 spec, but such data has been observed in the
           // wild.
           _ => match vr_bytes {
-            [0x00, 0x00] | [0x20, 0x20] => Ok(ValueRepresentation::Unknown),
+            [0x00, 0x00] | [0x20, 0x20] => {
+              self.statistics.recovered_anomaly_count += 1;
+
+              Ok(ValueRepresentation::Unknown)
+            }
 
             _ => Err(P10Error::DataInvalid {
-              when: "Reading data element VR".to_string(),
-              details: format!(
+              when: "Reading data element VR".into(),
+              details: error_details!(
                 "Unrecognized VR {:?} for tag '{}'",
                 vr_bytes,
                 dictionary::tag_with_name(tag, None)
@@ -1060,8 +1532,8 @@ impl P10ReadContext {
               });
             } else {
               return Err(P10Error::DataInvalid {
-                when: "Reading data element value bytes".to_string(),
-                details: format!(
+                when: "Reading data element value bytes".into(),
+                details: error_details!(
                   "Length of {} bytes exceeds the maximum of {} bytes after \
                     conversion to UTF-8",
                   data.len(),
@@ -1155,12 +1627,28 @@ impl P10ReadContext {
     vr: ValueRepresentation,
     mut value_bytes: RcByteSlice,
   ) -> Result<RcByteSlice, P10Error> {
+    // Check UIDs are conformant prior to any sanitization of their bytes
+    if self.config.validate_uids
+      && vr == ValueRepresentation::UniqueIdentifier
+      && let Err(e) = unique_identifier::validate_bytes(&value_bytes)
+    {
+      self.uid_conformance_warnings.push((self.path.clone(), e));
+    }
+
     // Decode string values using the relevant character set
     if vr.is_string() {
       // Private Creator values must only contain characters from the Default
       // Character Repertoire and so are sanitized against that character set.
       // Ref: PS3.5 7.8.1.
       if vr.is_encoded_string() && !tag.is_private_creator() {
+        // Only values with non-ASCII bytes or escape sequences are counted, as
+        // ASCII bytes are unchanged by conversion
+        if !self.location.is_specific_character_set_utf8()
+          && value_bytes.iter().any(|b| *b >= 0x80 || *b == 0x1B)
+        {
+          self.statistics.character_set_conversion_count += 1;
+        }
+
         value_bytes =
           self.location.decode_string_bytes(vr, &value_bytes).into();
       } else {
@@ -1220,7 +1708,7 @@ impl P10ReadContext {
 
           self.location.end_sequence().map_err(|details| {
             P10Error::DataInvalid {
-              when: "Reading encapsulated pixel data item".to_string(),
+              when: "Reading encapsulated pixel data item".into(),
               details,
               path: self.path.clone(),
               offset: self.stream.bytes_read(),
@@ -1235,8 +1723,8 @@ impl P10ReadContext {
         }
 
         _ => Err(P10Error::DataInvalid {
-          when: "Reading encapsulated pixel data item".to_string(),
-          details: format!("Invalid data element '{header}'"),
+          when: "Reading encapsulated pixel data item".into(),
+          details: error_details!("Invalid data element '{header}'"),
           path: self.path.clone(),
           offset: self.stream.bytes_read(),
         }),
@@ -1269,18 +1757,18 @@ fn map_byte_stream_error(
 
   match error {
     ByteStreamError::DataRequired => P10Error::DataRequired {
-      when: when.to_string(),
+      when: when.to_string().into(),
     },
 
     ByteStreamError::DataEnd => P10Error::DataEndedUnexpectedly {
-      when: when.to_string(),
+      when: when.to_string().into(),
       path: path.clone(),
       offset,
     },
 
     ByteStreamError::ZlibDataError => P10Error::DataInvalid {
-      when: when.to_string(),
-      details: "Zlib data is invalid".to_string(),
+      when: when.to_string().into(),
+      details: "Zlib data is invalid".into(),
       path: path.clone(),
       offset,
     },
@@ -9,7 +9,65 @@ pub struct P10ReadConfig {
   pub(crate) max_sequence_depth: usize,
   pub(crate) require_dicm_prefix: bool,
   pub(crate) require_ordered_data_elements: bool,
+  pub(crate) duplicate_data_element_policy: DuplicateDataElementPolicy,
   pub(crate) default_transfer_syntax: &'static TransferSyntax,
+  pub(crate) validate_uids: bool,
+  pub(crate) trailing_data_policy: TrailingDataPolicy,
+  pub(crate) infer_implicit_vr_sequences: bool,
+}
+
+/// Specifies how data elements that occur more than once in the same data set
+/// or sequence item are handled. Such data is malformed, but is sometimes
+/// encountered in practice.
+///
+/// A read context only acts on [`DuplicateDataElementPolicy::Error`], as it
+/// emits tokens for every data element it reads. The other policies are applied
+/// by [`crate::DataSetBuilder`] when it materializes the tokens into a data
+/// set.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DuplicateDataElementPolicy {
+  /// The first occurrence of the data element is kept and later occurrences
+  /// are discarded.
+  FirstWins,
+
+  /// Each later occurrence of the data element replaces the earlier one.
+  #[default]
+  LastWins,
+
+  /// Duplicate data elements are an error.
+  Error,
+
+  /// The first occurrence of the data element is kept, and later occurrences
+  /// are recorded along with their path so they aren't lost. See
+  /// [`crate::DataSetBuilder::duplicate_data_elements()`].
+  KeepBoth,
+}
+
+/// Specifies how data at the end of DICOM P10 data that follows its final data
+/// element is handled. This covers the *'(FFFC,FFFC) Data Set Trailing
+/// Padding'* data element, as well as trailing bytes that can't be read as a
+/// data element, e.g. zero padding or other garbage appended to the data.
+///
+/// A read context records the location of any trailing data it finds. See
+/// [`crate::P10ReadContext::data_set_trailing_padding()`] and
+/// [`crate::P10ReadContext::trailing_bytes()`].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TrailingDataPolicy {
+  /// Trailing padding and trailing bytes are read and discarded. No tokens are
+  /// emitted for them.
+  Ignore,
+
+  /// Trailing padding is emitted as a *'(FFFC,FFFC) Data Set Trailing Padding'*
+  /// data element so that it is retained when the tokens are written out.
+  /// Trailing bytes are emitted the same way, unless they follow a trailing
+  /// padding data element, in which case they are discarded.
+  Preserve,
+
+  /// Trailing padding is read and discarded, and trailing bytes are an error.
+  #[default]
+  Error,
 }
 
 impl Default for P10ReadConfig {
@@ -20,7 +78,11 @@ impl Default for P10ReadConfig {
       max_sequence_depth: 10_000,
       require_dicm_prefix: false,
       require_ordered_data_elements: true,
+      duplicate_data_element_policy: DuplicateDataElementPolicy::LastWins,
       default_transfer_syntax: &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+      validate_uids: false,
+      trailing_data_policy: TrailingDataPolicy::Error,
+      infer_implicit_vr_sequences: false,
     }
   }
 }
@@ -116,6 +178,9 @@ impl P10ReadConfig {
   ///    all be read with a VR of UN (when the transfer syntax is 'Implicit VR
   ///    Little Endian').
   ///
+  /// Data elements that occur more than once are handled separately, see
+  /// [`P10ReadConfig::duplicate_data_element_policy()`].
+  ///
   /// By default this requirement is enforced.
   ///
   pub fn require_ordered_data_elements(mut self, value: bool) -> Self {
@@ -123,6 +188,20 @@ impl P10ReadConfig {
     self
   }
 
+  /// How to handle data elements that occur more than once in the same data
+  /// set or sequence item. See [`DuplicateDataElementPolicy`] for details.
+  ///
+  /// By default each later occurrence of a data element replaces the earlier
+  /// one, i.e. [`DuplicateDataElementPolicy::LastWins`].
+  ///
+  pub fn duplicate_data_element_policy(
+    mut self,
+    value: DuplicateDataElementPolicy,
+  ) -> Self {
+    self.duplicate_data_element_policy = value;
+    self
+  }
+
   /// The transfer syntax to use when reading DICOM P10 data that doesn't
   /// specify a transfer syntax in its File Meta Information, or doesn't have
   /// any File Meta Information.
@@ -136,4 +215,45 @@ impl P10ReadConfig {
     self.default_transfer_syntax = value;
     self
   }
+
+  /// Whether to check that the values of data elements with a VR of
+  /// `UniqueIdentifier` conform to the rules for UIDs, i.e. their length,
+  /// digit components, leading zeros, and padding. Non-conformant UIDs don't
+  /// stop the data being read, and are instead recorded by the read context.
+  /// See [`crate::P10ReadContext::uid_conformance_warnings()`].
+  ///
+  /// By default UIDs are not validated.
+  ///
+  pub fn validate_uids(mut self, value: bool) -> Self {
+    self.validate_uids = value;
+    self
+  }
+
+  /// How to handle data that follows the final data element, i.e. Data Set
+  /// Trailing Padding and trailing bytes that can't be read as a data element.
+  /// See [`TrailingDataPolicy`] for details.
+  ///
+  /// By default trailing bytes are an error.
+  ///
+  pub fn trailing_data_policy(mut self, value: TrailingDataPolicy) -> Self {
+    self.trailing_data_policy = value;
+    self
+  }
+
+  /// Whether to read data elements that have a defined length and a VR of UN
+  /// (Unknown) as sequences when their value starts with an item, rather than
+  /// as binary data. This only applies when the VR is implicit, and is most
+  /// relevant to private sequences, which otherwise can't be navigated because
+  /// their VR can't be inferred from the data element dictionary.
+  ///
+  /// The value is detected as a sequence when it starts with an item tag whose
+  /// length is either undefined or fits within the data element's value. If
+  /// this isn't the case then the value is read as binary data as usual.
+  ///
+  /// By default such values are read as binary data.
+  ///
+  pub fn infer_implicit_vr_sequences(mut self, value: bool) -> Self {
+    self.infer_implicit_vr_sequences = value;
+    self
+  }
 }
@@ -0,0 +1,543 @@
+//! Records streams of DICOM P10 tokens into a compact binary format, and
+//! replays them.
+//!
+//! A token trace holds the exact sequence of [`P10Token`]s emitted when reading
+//! DICOM P10 data. Traces can be attached to bug reports in place of the
+//! original files, optionally with their text values redacted using
+//! [`P10TokenTrace::redact_text_values()`], and replayed in regression tests
+//! to pass the same tokens through transforms deterministically.
+//!
+//! The binary format starts with the `DCMT` magic bytes and a format version,
+//! followed by each token in turn. Each token is a one byte token type followed
+//! by its fields, with all integers stored in little endian.
+//!
+//! Token traces conventionally use the `.dcmt` file extension.
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format, vec, vec::Vec};
+
+use dcmfx_core::{
+  DataElementTag, DataElementValue, DataSet, DataSetPath, ErrorDetails,
+  RcByteSlice, ValueRepresentation, data_set_path::DataSetPathEntry,
+};
+
+use crate::{P10Error, P10Token};
+
+/// The file extension used for token traces.
+///
+pub const FILE_EXTENSION: &str = "dcmt";
+
+/// The magic bytes at the start of a token trace.
+///
+const MAGIC: &[u8; 4] = b"DCMT";
+
+/// The version of the token trace format.
+///
+const FORMAT_VERSION: u8 = 1;
+
+const TOKEN_FILE_PREAMBLE_AND_DICM_PREFIX: u8 = 0;
+const TOKEN_FILE_META_INFORMATION: u8 = 1;
+const TOKEN_DATA_ELEMENT_HEADER: u8 = 2;
+const TOKEN_DATA_ELEMENT_VALUE_BYTES: u8 = 3;
+const TOKEN_SEQUENCE_START: u8 = 4;
+const TOKEN_SEQUENCE_DELIMITER: u8 = 5;
+const TOKEN_SEQUENCE_ITEM_START: u8 = 6;
+const TOKEN_SEQUENCE_ITEM_DELIMITER: u8 = 7;
+const TOKEN_PIXEL_DATA_ITEM: u8 = 8;
+const TOKEN_END: u8 = 9;
+
+/// A recorded sequence of DICOM P10 tokens.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct P10TokenTrace {
+  tokens: Vec<P10Token>,
+}
+
+impl P10TokenTrace {
+  /// Creates a new empty token trace.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Records a token at the end of this token trace.
+  ///
+  pub fn record(&mut self, token: &P10Token) {
+    self.tokens.push(token.clone());
+  }
+
+  /// Returns the tokens in this token trace.
+  ///
+  pub fn tokens(&self) -> &[P10Token] {
+    &self.tokens
+  }
+
+  /// Passes each token in this token trace to the given callback, in the order
+  /// they were recorded. Replay stops at the first error returned by the
+  /// callback.
+  ///
+  pub fn replay<E>(
+    &self,
+    mut callback: impl FnMut(&P10Token) -> Result<(), E>,
+  ) -> Result<(), E> {
+    for token in self.tokens.iter() {
+      callback(token)?;
+    }
+
+    Ok(())
+  }
+
+  /// Replaces the text in values that are able to contain identifying
+  /// information, such as names, dates, and free text, with `X` characters.
+  /// Value lengths, multiplicities, and padding are unchanged, and the File
+  /// Preamble is zeroed.
+  ///
+  /// Values with a VR of `CS`, `DS`, `IS`, or `UI`, and all binary values, are
+  /// left unchanged because they are commonly needed in order to reproduce
+  /// issues. The tokens in a token trace should still be reviewed before it is
+  /// shared.
+  ///
+  pub fn redact_text_values(&mut self) {
+    for token in self.tokens.iter_mut() {
+      match token {
+        P10Token::FilePreambleAndDICMPrefix { preamble } => {
+          **preamble = [0; 128];
+        }
+
+        P10Token::DataElementValueBytes { vr, data, .. }
+          if is_redacted_vr(*vr) =>
+        {
+          let redacted: Vec<u8> = data
+            .iter()
+            .map(|byte| match byte {
+              b'\\' | b' ' | 0 => *byte,
+              _ => b'X',
+            })
+            .collect();
+
+          *data = redacted.into();
+        }
+
+        _ => (),
+      }
+    }
+  }
+
+  /// Serializes this token trace to bytes.
+  ///
+  /// Returns an error if a File Meta Information token contains a sequence,
+  /// which isn't supported.
+  ///
+  pub fn to_bytes(&self) -> Result<Vec<u8>, P10Error> {
+    let mut bytes = Vec::with_capacity(64);
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(FORMAT_VERSION);
+
+    for token in self.tokens.iter() {
+      write_token(&mut bytes, token)?;
+    }
+
+    Ok(bytes)
+  }
+
+  /// Deserializes a token trace from bytes created by [`Self::to_bytes()`].
+  ///
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, P10Error> {
+    let mut reader = TraceReader { bytes, offset: 0 };
+
+    if reader.read_bytes(4)? != MAGIC {
+      return Err(reader.error("Token trace does not start with 'DCMT'"));
+    }
+
+    let version = reader.read_u8()?;
+    if version != FORMAT_VERSION {
+      return Err(
+        reader.error(format!("Token trace version {version} is not supported")),
+      );
+    }
+
+    let mut tokens = vec![];
+    while reader.offset < bytes.len() {
+      tokens.push(reader.read_token()?);
+    }
+
+    Ok(Self { tokens })
+  }
+}
+
+/// Returns whether the values of the given VR are redacted by
+/// [`P10TokenTrace::redact_text_values()`].
+///
+fn is_redacted_vr(vr: ValueRepresentation) -> bool {
+  matches!(
+    vr,
+    ValueRepresentation::ApplicationEntity
+      | ValueRepresentation::AgeString
+      | ValueRepresentation::Date
+      | ValueRepresentation::DateTime
+      | ValueRepresentation::LongString
+      | ValueRepresentation::LongText
+      | ValueRepresentation::PersonName
+      | ValueRepresentation::ShortString
+      | ValueRepresentation::ShortText
+      | ValueRepresentation::Time
+      | ValueRepresentation::UnlimitedCharacters
+      | ValueRepresentation::UniversalResourceIdentifier
+      | ValueRepresentation::UnlimitedText
+  )
+}
+
+fn write_token(bytes: &mut Vec<u8>, token: &P10Token) -> Result<(), P10Error> {
+  match token {
+    P10Token::FilePreambleAndDICMPrefix { preamble } => {
+      bytes.push(TOKEN_FILE_PREAMBLE_AND_DICM_PREFIX);
+      bytes.extend_from_slice(&preamble[..]);
+    }
+
+    P10Token::FileMetaInformation { data_set } => {
+      bytes.push(TOKEN_FILE_META_INFORMATION);
+      bytes.extend_from_slice(&(data_set.size() as u32).to_le_bytes());
+
+      for (tag, value) in data_set.iter() {
+        let data = value.bytes().map_err(|_| P10Error::TokenStreamInvalid {
+          when: "Writing token trace".into(),
+          details: format!(
+            "File Meta Information data element {tag} is not binary"
+          )
+          .into(),
+          token: token.clone(),
+        })?;
+
+        write_tag(bytes, *tag);
+        bytes.extend_from_slice(&value.value_representation().to_bytes());
+        write_data(bytes, data);
+      }
+    }
+
+    P10Token::DataElementHeader {
+      tag,
+      vr,
+      length,
+      path,
+    } => {
+      bytes.push(TOKEN_DATA_ELEMENT_HEADER);
+      write_tag(bytes, *tag);
+      bytes.extend_from_slice(&vr.to_bytes());
+      bytes.extend_from_slice(&length.to_le_bytes());
+      write_path(bytes, path);
+    }
+
+    P10Token::DataElementValueBytes {
+      tag,
+      vr,
+      data,
+      bytes_remaining,
+    } => {
+      bytes.push(TOKEN_DATA_ELEMENT_VALUE_BYTES);
+      write_tag(bytes, *tag);
+      bytes.extend_from_slice(&vr.to_bytes());
+      write_data(bytes, data);
+      bytes.extend_from_slice(&bytes_remaining.to_le_bytes());
+    }
+
+    P10Token::SequenceStart { tag, vr, path } => {
+      bytes.push(TOKEN_SEQUENCE_START);
+      write_tag(bytes, *tag);
+      bytes.extend_from_slice(&vr.to_bytes());
+      write_path(bytes, path);
+    }
+
+    P10Token::SequenceDelimiter { tag } => {
+      bytes.push(TOKEN_SEQUENCE_DELIMITER);
+      write_tag(bytes, *tag);
+    }
+
+    P10Token::SequenceItemStart { index } => {
+      bytes.push(TOKEN_SEQUENCE_ITEM_START);
+      bytes.extend_from_slice(&(*index as u32).to_le_bytes());
+    }
+
+    P10Token::SequenceItemDelimiter => {
+      bytes.push(TOKEN_SEQUENCE_ITEM_DELIMITER);
+    }
+
+    P10Token::PixelDataItem { index, length } => {
+      bytes.push(TOKEN_PIXEL_DATA_ITEM);
+      bytes.extend_from_slice(&(*index as u32).to_le_bytes());
+      bytes.extend_from_slice(&length.to_le_bytes());
+    }
+
+    P10Token::End => bytes.push(TOKEN_END),
+  }
+
+  Ok(())
+}
+
+fn write_tag(bytes: &mut Vec<u8>, tag: DataElementTag) {
+  bytes.extend_from_slice(&tag.group.to_le_bytes());
+  bytes.extend_from_slice(&tag.element.to_le_bytes());
+}
+
+fn write_data(bytes: &mut Vec<u8>, data: &[u8]) {
+  bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+  bytes.extend_from_slice(data);
+}
+
+fn write_path(bytes: &mut Vec<u8>, path: &DataSetPath) {
+  bytes.extend_from_slice(&(path.len() as u16).to_le_bytes());
+
+  for entry in path.entries() {
+    match entry {
+      DataSetPathEntry::DataElement { tag } => {
+        bytes.push(0);
+        write_tag(bytes, *tag);
+      }
+
+      DataSetPathEntry::SequenceItem { index } => {
+        bytes.push(1);
+        bytes.extend_from_slice(&(*index as u32).to_le_bytes());
+      }
+    }
+  }
+}
+
+/// Reads the fields of a token trace from its bytes.
+///
+struct TraceReader<'a> {
+  bytes: &'a [u8],
+  offset: usize,
+}
+
+impl TraceReader<'_> {
+  fn error(&self, details: impl Into<ErrorDetails>) -> P10Error {
+    P10Error::DataInvalid {
+      when: "Reading token trace".into(),
+      details: details.into(),
+      path: DataSetPath::new(),
+      offset: self.offset as u64,
+    }
+  }
+
+  fn read_bytes(&mut self, count: usize) -> Result<&[u8], P10Error> {
+    if self.bytes.len() - self.offset < count {
+      return Err(P10Error::DataEndedUnexpectedly {
+        when: "Reading token trace".into(),
+        path: DataSetPath::new(),
+        offset: self.offset as u64,
+      });
+    }
+
+    let bytes = &self.bytes[self.offset..self.offset + count];
+    self.offset += count;
+
+    Ok(bytes)
+  }
+
+  fn read_u8(&mut self) -> Result<u8, P10Error> {
+    Ok(self.read_bytes(1)?[0])
+  }
+
+  fn read_u16(&mut self) -> Result<u16, P10Error> {
+    Ok(u16::from_le_bytes(self.read_bytes(2)?.try_into().unwrap()))
+  }
+
+  fn read_u32(&mut self) -> Result<u32, P10Error> {
+    Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+  }
+
+  fn read_tag(&mut self) -> Result<DataElementTag, P10Error> {
+    let group = self.read_u16()?;
+    let element = self.read_u16()?;
+
+    Ok(DataElementTag::new(group, element))
+  }
+
+  fn read_vr(&mut self) -> Result<ValueRepresentation, P10Error> {
+    let bytes: [u8; 2] = self.read_bytes(2)?.try_into().unwrap();
+
+    ValueRepresentation::from_bytes(&bytes)
+      .map_err(|_| self.error("Token trace VR is invalid"))
+  }
+
+  fn read_data(&mut self) -> Result<RcByteSlice, P10Error> {
+    let length = self.read_u32()? as usize;
+
+    Ok(self.read_bytes(length)?.to_vec().into())
+  }
+
+  fn read_path(&mut self) -> Result<DataSetPath, P10Error> {
+    let mut path = DataSetPath::new();
+
+    for _ in 0..self.read_u16()? {
+      let result = match self.read_u8()? {
+        0 => {
+          let tag = self.read_tag()?;
+          path.add_data_element(tag)
+        }
+
+        1 => {
+          let index = self.read_u32()? as usize;
+          path.add_sequence_item(index)
+        }
+
+        _ => return Err(self.error("Token trace path entry is invalid")),
+      };
+
+      result.map_err(|details| self.error(details))?;
+    }
+
+    Ok(path)
+  }
+
+  fn read_token(&mut self) -> Result<P10Token, P10Error> {
+    let token = match self.read_u8()? {
+      TOKEN_FILE_PREAMBLE_AND_DICM_PREFIX => {
+        let mut preamble = Box::new([0u8; 128]);
+        preamble.copy_from_slice(self.read_bytes(128)?);
+
+        P10Token::FilePreambleAndDICMPrefix { preamble }
+      }
+
+      TOKEN_FILE_META_INFORMATION => {
+        let mut data_set = DataSet::new();
+
+        for _ in 0..self.read_u32()? {
+          let tag = self.read_tag()?;
+          let vr = self.read_vr()?;
+          let data = self.read_data()?;
+
+          data_set
+            .insert(tag, DataElementValue::new_binary_unchecked(vr, data));
+        }
+
+        P10Token::FileMetaInformation { data_set }
+      }
+
+      TOKEN_DATA_ELEMENT_HEADER => P10Token::DataElementHeader {
+        tag: self.read_tag()?,
+        vr: self.read_vr()?,
+        length: self.read_u32()?,
+        path: self.read_path()?,
+      },
+
+      TOKEN_DATA_ELEMENT_VALUE_BYTES => P10Token::DataElementValueBytes {
+        tag: self.read_tag()?,
+        vr: self.read_vr()?,
+        data: self.read_data()?,
+        bytes_remaining: self.read_u32()?,
+      },
+
+      TOKEN_SEQUENCE_START => P10Token::SequenceStart {
+        tag: self.read_tag()?,
+        vr: self.read_vr()?,
+        path: self.read_path()?,
+      },
+
+      TOKEN_SEQUENCE_DELIMITER => P10Token::SequenceDelimiter {
+        tag: self.read_tag()?,
+      },
+
+      TOKEN_SEQUENCE_ITEM_START => P10Token::SequenceItemStart {
+        index: self.read_u32()? as usize,
+      },
+
+      TOKEN_SEQUENCE_ITEM_DELIMITER => P10Token::SequenceItemDelimiter,
+
+      TOKEN_PIXEL_DATA_ITEM => P10Token::PixelDataItem {
+        index: self.read_u32()? as usize,
+        length: self.read_u32()?,
+      },
+
+      TOKEN_END => P10Token::End,
+
+      token_type => {
+        return Err(
+          self.error(format!("Token trace token type {token_type} is invalid")),
+        );
+      }
+    };
+
+    Ok(token)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::DataSetP10Extensions;
+  use dcmfx_core::dictionary;
+
+  #[test]
+  fn round_trip_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["12345"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(
+        &dictionary::REFERENCED_SERIES_SEQUENCE,
+        vec![data_set.clone()],
+      )
+      .unwrap();
+
+    let mut trace = P10TokenTrace::new();
+    for token in data_set.to_p10_tokens() {
+      trace.record(&token);
+    }
+
+    let bytes = trace.to_bytes().unwrap();
+    assert_eq!(P10TokenTrace::from_bytes(&bytes), Ok(trace.clone()));
+
+    let mut replayed = vec![];
+    trace
+      .replay(|token| {
+        replayed.push(token.clone());
+        Ok::<(), ()>(())
+      })
+      .unwrap();
+    assert_eq!(replayed, trace.tokens());
+
+    // Removing only the final end token leaves a valid trace, so truncate
+    // partway through the sequence delimiter token that precedes it
+    assert!(P10TokenTrace::from_bytes(&bytes[0..bytes.len() - 2]).is_err());
+    assert!(P10TokenTrace::from_bytes(b"DCMT\x02").is_err());
+  }
+
+  #[test]
+  fn redact_text_values_test() {
+    let mut trace = P10TokenTrace::new();
+    trace.record(&P10Token::DataElementValueBytes {
+      tag: dictionary::PATIENT_NAME.tag,
+      vr: ValueRepresentation::PersonName,
+      data: b"Doe^John\\Jo ".to_vec().into(),
+      bytes_remaining: 0,
+    });
+    trace.record(&P10Token::DataElementValueBytes {
+      tag: dictionary::MODALITY.tag,
+      vr: ValueRepresentation::CodeString,
+      data: b"CT".to_vec().into(),
+      bytes_remaining: 0,
+    });
+
+    trace.redact_text_values();
+
+    assert_eq!(
+      trace.tokens(),
+      [
+        P10Token::DataElementValueBytes {
+          tag: dictionary::PATIENT_NAME.tag,
+          vr: ValueRepresentation::PersonName,
+          data: b"XXXXXXXX\\XX ".to_vec().into(),
+          bytes_remaining: 0,
+        },
+        P10Token::DataElementValueBytes {
+          tag: dictionary::MODALITY.tag,
+          vr: ValueRepresentation::CodeString,
+          data: b"CT".to_vec().into(),
+          bytes_remaining: 0,
+        },
+      ]
+    );
+  }
+}
@@ -4,7 +4,6 @@
 #[cfg(not(feature = "std"))]
 use alloc::{
   boxed::Box,
-  format,
   string::{String, ToString},
   vec,
   vec::Vec,
@@ -14,18 +13,22 @@ use byteorder::ByteOrder;
 
 use dcmfx_core::DataSetPath;
 use dcmfx_core::{
-  DataElementValue, DataError, DataSet, RcByteSlice, TransferSyntax,
-  dictionary, transfer_syntax, transfer_syntax::Endianness,
+  DataElementTag, DataElementValue, DataError, DataSet, ErrorContext,
+  ErrorContextChain, ErrorDetails, RcByteSlice, TransferSyntax,
+  ValueRepresentation, data_element_value::unique_identifier, dictionary,
+  error_details, transfer_syntax, transfer_syntax::Endianness,
 };
 
 use crate::internal::p10_location::P10Location;
 use crate::{
   P10Error, P10FilterTransform, P10InsertTransform, P10Token, P10WriteConfig,
+  UidWritePolicy,
   internal::{
     data_element_header::{DataElementHeader, ValueLengthSize},
     value_length::ValueLength,
   },
   p10_token,
+  parallel_deflate::ParallelDeflate,
 };
 
 /// Data is compressed into chunks of this size when writing deflated transfer
@@ -45,8 +48,12 @@ pub struct P10WriteContext {
   is_ended: bool,
   transfer_syntax: &'static TransferSyntax,
   zlib_stream: Option<flate2::Compress>,
+  parallel_deflate: Option<ParallelDeflate>,
   location: P10Location,
   path: DataSetPath,
+  pending_data_element: Option<(P10Token, Vec<u8>)>,
+  pending_pad_byte: Option<u8>,
+  padded_values: Vec<DataSetPath>,
 }
 
 impl P10WriteContext {
@@ -60,8 +67,12 @@ impl P10WriteContext {
       is_ended: false,
       transfer_syntax: &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
       zlib_stream: None,
+      parallel_deflate: None,
       location: P10Location::new(),
       path: DataSetPath::new(),
+      pending_data_element: None,
+      pending_pad_byte: None,
+      padded_values: vec![],
     }
   }
 
@@ -72,17 +83,259 @@ impl P10WriteContext {
     core::mem::take(&mut self.p10_bytes)
   }
 
+  /// Returns the paths of the data elements and pixel data items whose values
+  /// had an odd length and were padded to an even length when they were
+  /// written. Values are only padded when
+  /// [`P10WriteConfig::auto_pad_values()`] is enabled.
+  ///
+  pub fn padded_values(&self) -> &[DataSetPath] {
+    &self.padded_values
+  }
+
   /// Writes a DICOM P10 token to a write context. On success an updated write
   /// context is returned. Use [`Self::read_bytes()`] to get the new DICOM P10
   /// bytes generated as a result of writing this token.
   ///
+  /// Errors have an [`ErrorContext`] attached that gives the path and transfer
+  /// syntax in use when the error occurred.
+  ///
   pub fn write_token(&mut self, token: &P10Token) -> Result<(), P10Error> {
+    self.write_token_checked(token).map_err(|e| {
+      let context = ErrorContext::new()
+        .with_path(e.path().unwrap_or(&self.path))
+        .with_transfer_syntax(self.transfer_syntax);
+
+      e.with_context(context)
+    })
+  }
+
+  /// Writes a DICOM P10 token to a write context, buffering and checking its
+  /// value first when required by the write config.
+  ///
+  fn write_token_checked(&mut self, token: &P10Token) -> Result<(), P10Error> {
+    // When values are being checked, the value of each data element that needs
+    // checking as a whole is gathered up so that it can be checked, and
+    // possibly have its length altered, before being written. Values that
+    // aren't gathered up and have an odd length are padded as they stream
+    // through if auto padding is enabled.
+    if !self.is_ended {
+      match token {
+        P10Token::DataElementHeader {
+          tag,
+          vr,
+          length,
+          path,
+        } => {
+          if self.is_value_buffered(*vr) {
+            self.pending_data_element = Some((token.clone(), vec![]));
+            return Ok(());
+          }
+
+          // Odd length values have a pad byte appended to their final value
+          // bytes token
+          let length =
+            if self.config.auto_pad_values && !length.is_multiple_of(2) {
+              self.pending_pad_byte = Some(self.pad_byte(*vr));
+              *length + 1
+            } else {
+              *length
+            };
+
+          if self.config.strict_conformance {
+            self.check_conformance(*tag, *vr, length as usize, None, path)?;
+          }
+
+          if self.pending_pad_byte.is_some() {
+            return self.write_token_internal(&P10Token::DataElementHeader {
+              tag: *tag,
+              vr: *vr,
+              length,
+              path: path.clone(),
+            });
+          }
+        }
+
+        P10Token::PixelDataItem { index, length }
+          if self.config.auto_pad_values && !length.is_multiple_of(2) =>
+        {
+          self.pending_pad_byte = Some(self.config.binary_pad_byte);
+
+          return self.write_token_internal(&P10Token::PixelDataItem {
+            index: *index,
+            length: *length + 1,
+          });
+        }
+
+        P10Token::DataElementValueBytes {
+          tag,
+          vr,
+          data,
+          bytes_remaining,
+        } => {
+          if let Some((_, value_bytes)) = self.pending_data_element.as_mut() {
+            value_bytes.extend_from_slice(data);
+
+            if *bytes_remaining == 0 {
+              let (header_token, value_bytes) =
+                self.pending_data_element.take().unwrap();
+
+              return self
+                .write_buffered_data_element(header_token, value_bytes);
+            }
+
+            return Ok(());
+          }
+
+          if *bytes_remaining == 0
+            && let Some(pad_byte) = self.pending_pad_byte.take()
+          {
+            let mut data = data.to_vec();
+            data.push(pad_byte);
+
+            self.padded_values.push(self.path.clone());
+
+            return self.write_token_internal(
+              &P10Token::DataElementValueBytes {
+                tag: *tag,
+                vr: *vr,
+                data: data.into(),
+                bytes_remaining: 0,
+              },
+            );
+          }
+        }
+
+        _ => (),
+      }
+    }
+
+    self.write_token_internal(token)
+  }
+
+  /// Returns whether the values of data elements with the given VR are
+  /// gathered up in full so they can be checked before being written.
+  ///
+  fn is_value_buffered(&self, vr: ValueRepresentation) -> bool {
+    (vr == ValueRepresentation::UniqueIdentifier
+      && self.config.uid_write_policy != UidWritePolicy::Unchecked)
+      || (self.config.strict_conformance && vr.is_string())
+  }
+
+  /// Checks the value of a buffered data element according to the active
+  /// [`UidWritePolicy`] and strict conformance setting, then writes its header
+  /// and value tokens.
+  ///
+  fn write_buffered_data_element(
+    &mut self,
+    header_token: P10Token,
+    value_bytes: Vec<u8>,
+  ) -> Result<(), P10Error> {
+    let P10Token::DataElementHeader { tag, vr, path, .. } = header_token else {
+      unreachable!();
+    };
+
+    let value_bytes = if vr == ValueRepresentation::UniqueIdentifier
+      && self.config.uid_write_policy == UidWritePolicy::FixPadding
+    {
+      unique_identifier::fix_padding(&value_bytes)
+    } else {
+      value_bytes
+    };
+
+    let value_bytes =
+      if self.config.auto_pad_values && !value_bytes.len().is_multiple_of(2) {
+        let mut value_bytes = value_bytes;
+        value_bytes.push(self.pad_byte(vr));
+        self.padded_values.push(path.clone());
+        value_bytes
+      } else {
+        value_bytes
+      };
+
+    if vr == ValueRepresentation::UniqueIdentifier
+      && let Err(e) = unique_identifier::validate_bytes(&value_bytes)
+    {
+      return Err(P10Error::DataInvalid {
+        when: "Writing UniqueIdentifier value".into(),
+        details: error_details!(
+          "{e}, value: {:?}",
+          String::from_utf8_lossy(&value_bytes)
+        ),
+        path,
+        offset: self.p10_total_byte_count,
+      });
+    }
+
+    if self.config.strict_conformance {
+      self.check_conformance(
+        tag,
+        vr,
+        value_bytes.len(),
+        Some(&value_bytes),
+        &path,
+      )?;
+    }
+
+    self.write_token_internal(&P10Token::DataElementHeader {
+      tag,
+      vr,
+      length: value_bytes.len() as u32,
+      path,
+    })?;
+
+    self.write_token_internal(&P10Token::DataElementValueBytes {
+      tag,
+      vr,
+      data: value_bytes.into(),
+      bytes_remaining: 0,
+    })
+  }
+
+  /// Returns the byte used to pad an odd length value with the given VR when
+  /// [`P10WriteConfig::auto_pad_values()`] is enabled.
+  ///
+  fn pad_byte(&self, vr: ValueRepresentation) -> u8 {
+    if vr == ValueRepresentation::UniqueIdentifier {
+      0x00
+    } else if vr.is_string() {
+      0x20
+    } else {
+      self.config.binary_pad_byte
+    }
+  }
+
+  /// Checks that a data element's value conforms to the rules for its VR, VM,
+  /// and length. The value's bytes are required for string VRs, and for other
+  /// VRs only the length of the value is checked.
+  ///
+  fn check_conformance(
+    &self,
+    tag: DataElementTag,
+    vr: ValueRepresentation,
+    length: usize,
+    value_bytes: Option<&[u8]>,
+    path: &DataSetPath,
+  ) -> Result<(), P10Error> {
+    validate_value_conformance(tag, vr, length, value_bytes).map_err(
+      |details| P10Error::DataInvalid {
+        when: "Writing data element value in strict conformance mode".into(),
+        details,
+        path: path.clone(),
+        offset: self.p10_total_byte_count,
+      },
+    )
+  }
+
+  /// Writes a DICOM P10 token to a write context without any checking of
+  /// values.
+  ///
+  fn write_token_internal(&mut self, token: &P10Token) -> Result<(), P10Error> {
     if self.is_ended {
       return Err(P10Error::TokenStreamInvalid {
-        when: "Writing DICOM P10 token".to_string(),
+        when: "Writing DICOM P10 token".into(),
         details:
           "Received a further DICOM P10 token after the write was completed"
-            .to_string(),
+            .into(),
         token: token.clone(),
       });
     }
@@ -108,10 +361,17 @@ impl P10WriteContext {
         // If this is a deflated transfer syntax then start a zlib compressor
         // and exclude the zlib header
         if new_transfer_syntax.is_deflated {
-          self.zlib_stream = Some(flate2::Compress::new(
-            flate2::Compression::new(self.config.zlib_compression_level),
-            false,
-          ));
+          if self.config.deflate_thread_count > 1 {
+            self.parallel_deflate = Some(ParallelDeflate::new(
+              self.config.zlib_compression_level,
+              self.config.deflate_thread_count,
+            ));
+          } else {
+            self.zlib_stream = Some(flate2::Compress::new(
+              flate2::Compression::new(self.config.zlib_compression_level),
+              false,
+            ));
+          }
         }
 
         self.transfer_syntax = new_transfer_syntax;
@@ -139,8 +399,12 @@ impl P10WriteContext {
                 flate2::FlushCompress::Finish,
               )
               .map_err(|error| P10Error::DataInvalid {
-                when: "Performing zlib compression".to_string(),
-                details: error.message().unwrap_or("<unknown>").to_string(),
+                when: "Performing zlib compression".into(),
+                details: error
+                  .message()
+                  .unwrap_or("<unknown>")
+                  .to_string()
+                  .into(),
                 path: self.path.clone(),
                 offset: self.p10_total_byte_count,
               })?;
@@ -160,6 +424,14 @@ impl P10WriteContext {
           self.zlib_stream = None;
         }
 
+        if let Some(mut parallel_deflate) = self.parallel_deflate.take() {
+          let output = parallel_deflate
+            .finish()
+            .map_err(|error| self.zlib_error(error))?;
+
+          self.push_deflated_bytes(output);
+        }
+
         self.is_ended = true;
 
         Ok(())
@@ -167,8 +439,8 @@ impl P10WriteContext {
 
       _ => {
         let map_to_p10_token_stream_error =
-          |details: String| P10Error::TokenStreamInvalid {
-            when: "Writing token to context".to_string(),
+          |details: ErrorDetails| P10Error::TokenStreamInvalid {
+            when: "Writing token to context".into(),
             details,
             token: token.clone(),
           };
@@ -267,6 +539,12 @@ impl P10WriteContext {
             token_bytes_remaining =
               &token_bytes_remaining[input_bytes_consumed..];
           }
+        } else if let Some(parallel_deflate) = self.parallel_deflate.as_mut() {
+          let output = parallel_deflate
+            .write(&token_bytes)
+            .map_err(|error| self.zlib_error(error))?;
+
+          self.push_deflated_bytes(output);
         } else {
           self.p10_total_byte_count += token_bytes.len() as u64;
           self.p10_bytes.push(token_bytes);
@@ -277,11 +555,35 @@ impl P10WriteContext {
     }
   }
 
+  /// Adds the output of parallel deflate compression to the pending P10 bytes.
+  ///
+  fn push_deflated_bytes(&mut self, output: Vec<Vec<u8>>) {
+    for bytes in output {
+      if !bytes.is_empty() {
+        self.p10_total_byte_count += bytes.len() as u64;
+        self.p10_bytes.push(bytes.into());
+      }
+    }
+  }
+
+  /// Converts a zlib compression error into a [`P10Error`].
+  ///
+  fn zlib_error(&self, error: flate2::CompressError) -> P10Error {
+    P10Error::DataInvalid {
+      when: "Performing zlib compression".into(),
+      details: error.message().unwrap_or("<unknown>").to_string().into(),
+      path: self.path.clone(),
+      offset: self.p10_total_byte_count,
+    }
+  }
+
   /// Converts a single DICOM P10 token to raw DICOM P10 bytes.
   ///
   fn token_to_bytes(&self, token: &P10Token) -> Result<RcByteSlice, P10Error> {
     match token {
       P10Token::FilePreambleAndDICMPrefix { preamble } => {
+        let preamble = self.config.file_preamble.as_ref().unwrap_or(preamble);
+
         let mut data = Vec::with_capacity(132);
 
         data.extend_from_slice(preamble.as_ref());
@@ -298,8 +600,8 @@ impl P10WriteContext {
           &self.config.implementation_version_name,
         )
         .map_err(|e| P10Error::DataInvalid {
-          when: "Serializing File Meta Information".to_string(),
-          details: e.details().to_string(),
+          when: "Serializing File Meta Information".into(),
+          details: e.details().to_string().into(),
           path: e.path().cloned().unwrap_or_default(),
           offset: self.p10_total_byte_count,
         })?;
@@ -318,8 +620,8 @@ impl P10WriteContext {
 
           let value_bytes =
             value.bytes().map_err(|_| P10Error::DataInvalid {
-              when: "Serializing File Meta Information".to_string(),
-              details: format!(
+              when: "Serializing File Meta Information".into(),
+              details: error_details!(
                 "Tag '{tag}' with value representation '{vr}' is not allowed \
                  in File Meta Information"
               ),
@@ -482,8 +784,8 @@ impl P10WriteContext {
           ValueLengthSize::U16 => {
             if length > u16::MAX.into() {
               return Err(P10Error::DataInvalid {
-                when: "Serializing data element header".to_string(),
-                details: format!(
+                when: "Serializing data element header".into(),
+                details: error_details!(
                   "Length {} exceeds the maximum of 2^16 - 1 bytes",
                   header.length.to_u32(),
                 ),
@@ -527,6 +829,124 @@ impl Default for P10WriteContext {
   }
 }
 
+/// Checks that a data element's value conforms to the rules for its VR, VM,
+/// and length. See [`P10WriteConfig::strict_conformance()`] for details.
+///
+fn validate_value_conformance(
+  tag: DataElementTag,
+  vr: ValueRepresentation,
+  length: usize,
+  value_bytes: Option<&[u8]>,
+) -> Result<(), ErrorDetails> {
+  let requirements = vr.length_requirements();
+
+  // String VRs with a small maximum length, such as dates, have that maximum
+  // applied to each of their values rather than to the value as a whole
+  let is_bytes_max_per_value =
+    vr.is_string() && requirements.bytes_max < 0xFFFE;
+
+  if !is_bytes_max_per_value && length > requirements.bytes_max {
+    return Err(error_details!(
+      "Value length of {length} bytes exceeds the maximum of {} bytes for VR \
+       {vr}",
+      requirements.bytes_max
+    ));
+  }
+
+  let bytes_multiple_of = requirements.bytes_multiple_of.unwrap_or(2);
+  if !length.is_multiple_of(bytes_multiple_of) {
+    return Err(error_details!(
+      "Value length of {length} bytes is not a multiple of \
+       {bytes_multiple_of} bytes for VR {vr}"
+    ));
+  }
+
+  let value_count = match (value_bytes, requirements.bytes_multiple_of) {
+    (Some(value_bytes), _) if vr.is_string() => {
+      validate_string_value(vr, value_bytes)?
+    }
+    (_, Some(bytes_multiple_of)) if !vr.is_binary() => {
+      length / bytes_multiple_of
+    }
+    _ => 1,
+  };
+
+  // Check the number of values against the data element's VM. Empty values
+  // are always allowed.
+  if length > 0
+    && vr != ValueRepresentation::Unknown
+    && !tag.is_private()
+    && let Ok(item) = dictionary::find(tag, None)
+    && !item.multiplicity.contains(value_count)
+  {
+    return Err(error_details!(
+      "Value multiplicity of {value_count} is not allowed for '{}', which has \
+       a VM of {}",
+      item.name,
+      item.multiplicity
+    ));
+  }
+
+  Ok(())
+}
+
+/// Checks the characters and the length of each value in a string value, and
+/// returns the number of values it contains.
+///
+fn validate_string_value(
+  vr: ValueRepresentation,
+  value_bytes: &[u8],
+) -> Result<usize, ErrorDetails> {
+  let Ok(value) = core::str::from_utf8(value_bytes) else {
+    return Err(error_details!("Value for VR {vr} is not valid UTF-8"));
+  };
+
+  if !vr.is_encoded_string() {
+    let invalid_byte = value.bytes().find(|b| {
+      !matches!(b, 0x00 | 0x09 | 0x0A | 0x0C | 0x0D | 0x1B | 0x20..=0x7E)
+    });
+
+    if let Some(invalid_byte) = invalid_byte {
+      return Err(error_details!(
+        "Value for VR {vr} has disallowed byte: 0x{invalid_byte:02X}"
+      ));
+    }
+  }
+
+  // Text VRs always hold a single value that may contain backslashes
+  let values: Vec<&str> = match vr {
+    ValueRepresentation::LongText
+    | ValueRepresentation::ShortText
+    | ValueRepresentation::UnlimitedText
+    | ValueRepresentation::UniversalResourceIdentifier => vec![value],
+    _ => value.split('\\').collect(),
+  };
+
+  let requirements = vr.length_requirements();
+
+  for value in values.iter() {
+    let value = value.trim_end_matches([' ', '\0']);
+
+    if let Some(characters_max) = requirements.string_characters_max
+      && value.chars().count() > characters_max
+    {
+      return Err(error_details!(
+        "Value {value:?} exceeds the maximum of {characters_max} characters \
+         for VR {vr}"
+      ));
+    }
+
+    if requirements.bytes_max < 0xFFFE && value.len() > requirements.bytes_max {
+      return Err(error_details!(
+        "Value {value:?} exceeds the maximum of {} bytes for VR {vr}",
+        requirements.bytes_max
+      ));
+    }
+  }
+
+  Ok(values.len())
+}
+
 /// Converts a data set to DICOM P10 tokens. The generated P10 tokens are
 /// returned via a callback.
 ///
@@ -706,6 +1126,62 @@ pub async fn data_set_to_bytes_async(
   data_set_to_tokens_async(data_set, path, &mut process_token).await
 }
 
+/// Adds File Meta Information data elements that are missing from a data set.
+/// This is useful when a data set didn't come from DICOM P10 data, e.g. when it
+/// was read from DICOM JSON, which commonly omits group 0x0002.
+///
+/// The following data elements are added if they aren't already present:
+///
+/// - *'(0002,0002) Media Storage SOP Class UID'*, copied from *'(0008,0016) SOP
+///   Class UID'* if it is present.
+/// - *'(0002,0003) Media Storage SOP Instance UID'*, copied from *'(0008,0018)
+///   SOP Instance UID'* if it is present.
+/// - *'(0002,0010) Transfer Syntax UID'*, set to the passed transfer syntax.
+///
+/// *'(0002,0001) File Meta Information Version'*, *'(0002,0012) Implementation
+/// Class UID'* and *'(0002,0013) Implementation Version Name'* are always set
+/// using the passed write config, which matches what happens when the data set
+/// is serialized to DICOM P10.
+///
+pub fn synthesize_file_meta_information(
+  data_set: &mut DataSet,
+  transfer_syntax: &TransferSyntax,
+  config: Option<P10WriteConfig>,
+) -> Result<(), DataError> {
+  let config = config.unwrap_or_default();
+
+  for (media_storage_item, sop_item) in [
+    (
+      &dictionary::MEDIA_STORAGE_SOP_CLASS_UID,
+      &dictionary::SOP_CLASS_UID,
+    ),
+    (
+      &dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID,
+      &dictionary::SOP_INSTANCE_UID,
+    ),
+  ] {
+    if data_set.has(media_storage_item.tag) || !data_set.has(sop_item.tag) {
+      continue;
+    }
+
+    let uid = data_set.get_string(sop_item.tag)?.to_string();
+    data_set.insert_string_value(media_storage_item, &[uid.as_str()])?;
+  }
+
+  if !data_set.has(dictionary::TRANSFER_SYNTAX_UID.tag) {
+    data_set.insert_string_value(
+      &dictionary::TRANSFER_SYNTAX_UID,
+      &[transfer_syntax.uid],
+    )?;
+  }
+
+  prepare_file_meta_information_token_data_set(
+    data_set,
+    &config.implementation_class_uid,
+    &config.implementation_version_name,
+  )
+}
+
 /// Sets the *'(0002,0001) File Meta Information Version'*, *'(0002,0012)
 /// Implementation Class UID'* and *'(0002,0013) Implementation Version Name'*
 /// values in the File Meta Information. This is done prior to serializing it
@@ -811,9 +1287,8 @@ mod tests {
         Endianness::LittleEndian,
       ),
       Err(P10Error::DataInvalid {
-        when: "Serializing data element header".to_string(),
-        details: "Length 74565 exceeds the maximum of 2^16 - 1 bytes"
-          .to_string(),
+        when: "Serializing data element header".into(),
+        details: "Length 74565 exceeds the maximum of 2^16 - 1 bytes".into(),
         path: DataSetPath::new(),
         offset: 0
       })
@@ -843,4 +1318,372 @@ mod tests {
       Ok(vec![0, 40, 1, 6, 83, 83, 18, 52].into())
     );
   }
+
+  #[test]
+  fn synthesize_file_meta_information_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::SOP_CLASS_UID, &["1.2.3"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.3.4"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID, &["5"])
+      .unwrap();
+
+    synthesize_file_meta_information(
+      &mut data_set,
+      &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      Some(
+        P10WriteConfig::default().implementation_version_name("TEST".into()),
+      ),
+    )
+    .unwrap();
+
+    assert_eq!(
+      data_set.get_string(dictionary::MEDIA_STORAGE_SOP_CLASS_UID.tag),
+      Ok("1.2.3")
+    );
+    assert_eq!(
+      data_set.get_string(dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID.tag),
+      Ok("5")
+    );
+    assert_eq!(
+      data_set.get_transfer_syntax(),
+      Ok(&transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN)
+    );
+    assert_eq!(
+      data_set.get_string(dictionary::IMPLEMENTATION_VERSION_NAME.tag),
+      Ok("TEST")
+    );
+    assert!(data_set.has(dictionary::FILE_META_INFORMATION_VERSION.tag));
+
+    let mut data_set = DataSet::new();
+    synthesize_file_meta_information(
+      &mut data_set,
+      &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+      None,
+    )
+    .unwrap();
+
+    assert!(!data_set.has(dictionary::MEDIA_STORAGE_SOP_CLASS_UID.tag));
+    assert!(!data_set.has(dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID.tag));
+    assert_eq!(
+      data_set.get_transfer_syntax(),
+      Ok(&transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN)
+    );
+  }
+
+  #[test]
+  fn uid_write_policy_test() {
+    use crate::{P10ReadConfig, P10ReadContext};
+    use dcmfx_core::data_element_value::unique_identifier::UidConformanceError;
+
+    let mut data_set = DataSet::new();
+    data_set.insert(
+      dictionary::SOP_INSTANCE_UID.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::UniqueIdentifier,
+        b"1.2.3 ".to_vec().into(),
+      ),
+    );
+
+    let write = |uid_write_policy| {
+      let mut bytes = vec![];
+      data_set_to_bytes(
+        &data_set,
+        &DataSetPath::new(),
+        &mut |chunk| {
+          bytes.extend_from_slice(&chunk);
+          Ok(())
+        },
+        Some(P10WriteConfig::default().uid_write_policy(uid_write_policy)),
+      )
+      .map(|_| bytes)
+    };
+
+    let uid_conformance_warnings = |bytes: Vec<u8>| {
+      let mut context =
+        P10ReadContext::new(Some(P10ReadConfig::default().validate_uids(true)));
+      context.write_bytes(bytes.into(), true).unwrap();
+
+      while !context.read_tokens().unwrap().contains(&P10Token::End) {}
+
+      context.uid_conformance_warnings().to_vec()
+    };
+
+    let mut path = DataSetPath::new();
+    path
+      .add_data_element(dictionary::SOP_INSTANCE_UID.tag)
+      .unwrap();
+
+    assert_eq!(
+      uid_conformance_warnings(write(UidWritePolicy::Unchecked).unwrap()),
+      vec![(path.clone(), UidConformanceError::InvalidPadding)]
+    );
+
+    let error = write(UidWritePolicy::Error).unwrap_err();
+    assert_eq!(
+      error.without_context(),
+      &P10Error::DataInvalid {
+        when: "Writing UniqueIdentifier value".into(),
+        details: "UID has invalid padding, value: \"1.2.3 \"".into(),
+        path: path.clone(),
+        offset: 250,
+      }
+    );
+    assert_eq!(
+      error.context(),
+      Some(
+        &ErrorContext::new()
+          .with_path(&path)
+          .with_transfer_syntax(&transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN)
+      )
+    );
+
+    assert_eq!(
+      uid_conformance_warnings(write(UidWritePolicy::FixPadding).unwrap()),
+      vec![]
+    );
+  }
+
+  #[test]
+  fn strict_conformance_test() {
+    let write = |data_set: &DataSet| {
+      data_set_to_bytes(
+        data_set,
+        &DataSetPath::new(),
+        &mut |_| Ok(()),
+        Some(P10WriteConfig::default().strict_conformance(true)),
+      )
+    };
+
+    let strict_conformance_error = |details: &str, path: &DataSetPath| {
+      Err::<(), _>((details.to_string(), path.clone()))
+    };
+
+    let error_details_and_path = |result: Result<(), P10Error>| match result
+      .map_err(|e| e.without_context().clone())
+    {
+      Err(P10Error::DataInvalid { details, path, .. }) => {
+        Err((details.to_string(), path))
+      }
+      _ => Ok(()),
+    };
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::MODALITY, &["CT"])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::ROWS, &[512])
+      .unwrap();
+    data_set.insert(
+      dictionary::RETRIEVE_AE_TITLE.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::ApplicationEntity,
+        b"STORE1\\STORE2 ".to_vec().into(),
+      ),
+    );
+
+    assert_eq!(write(&data_set), Ok(()));
+
+    // Check a value multiplicity that isn't allowed by the dictionary
+    let mut invalid_data_set = data_set.clone();
+    invalid_data_set.insert(
+      dictionary::ROWS.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::UnsignedShort,
+        vec![0, 2, 0, 2].into(),
+      ),
+    );
+
+    assert_eq!(
+      error_details_and_path(write(&invalid_data_set)),
+      strict_conformance_error(
+        "Value multiplicity of 2 is not allowed for 'Rows', which has a VM of \
+         1",
+        &DataSetPath::new_with_data_element(dictionary::ROWS.tag)
+      )
+    );
+
+    // Check a value length that isn't a multiple of the VR's data type size
+    invalid_data_set.insert(
+      dictionary::ROWS.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::UnsignedShort,
+        vec![0, 2, 0].into(),
+      ),
+    );
+
+    assert_eq!(
+      error_details_and_path(write(&invalid_data_set)),
+      strict_conformance_error(
+        "Value length of 3 bytes is not a multiple of 2 bytes for VR US",
+        &DataSetPath::new_with_data_element(dictionary::ROWS.tag)
+      )
+    );
+
+    // Check a string value that's too long in a sequence item
+    let mut item = DataSet::new();
+    item.insert(
+      dictionary::MODALITY.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::CodeString,
+        b"A_VERY_LONG_MODALITY".to_vec().into(),
+      ),
+    );
+
+    let mut invalid_data_set = data_set.clone();
+    invalid_data_set.insert(
+      dictionary::REFERENCED_SERIES_SEQUENCE.tag,
+      DataElementValue::new_sequence(vec![item]),
+    );
+
+    let mut path = DataSetPath::new_with_data_element(
+      dictionary::REFERENCED_SERIES_SEQUENCE.tag,
+    );
+    path.add_sequence_item(0).unwrap();
+    path.add_data_element(dictionary::MODALITY.tag).unwrap();
+
+    assert_eq!(
+      error_details_and_path(write(&invalid_data_set)),
+      strict_conformance_error(
+        "Value \"A_VERY_LONG_MODALITY\" exceeds the maximum of 16 characters \
+         for VR CS",
+        &path
+      )
+    );
+
+    // Check a string value with a disallowed byte
+    let mut invalid_data_set = data_set.clone();
+    invalid_data_set.insert(
+      dictionary::MODALITY.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::CodeString,
+        b"C\x01".to_vec().into(),
+      ),
+    );
+
+    assert_eq!(
+      error_details_and_path(write(&invalid_data_set)),
+      strict_conformance_error(
+        "Value for VR CS has disallowed byte: 0x01",
+        &DataSetPath::new_with_data_element(dictionary::MODALITY.tag)
+      )
+    );
+  }
+
+  #[test]
+  fn auto_pad_values_test() {
+    let mut data_set = DataSet::new();
+    data_set.insert(
+      dictionary::MODALITY.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::CodeString,
+        b"CTX".to_vec().into(),
+      ),
+    );
+    data_set.insert(
+      dictionary::SOP_INSTANCE_UID.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::UniqueIdentifier,
+        b"1.2.3".to_vec().into(),
+      ),
+    );
+    data_set.insert(
+      dictionary::ENCAPSULATED_DOCUMENT.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::OtherByteString,
+        vec![1, 2, 3].into(),
+      ),
+    );
+
+    let mut context = P10WriteContext::new(Some(
+      P10WriteConfig::default()
+        .auto_pad_values(true)
+        .binary_pad_byte(0xFF),
+    ));
+
+    let mut bytes = vec![];
+    data_set_to_tokens(&data_set, &DataSetPath::new(), &mut |token| {
+      context.write_token(&token)?;
+
+      for chunk in context.read_bytes() {
+        bytes.extend_from_slice(&chunk);
+      }
+
+      Ok::<(), P10Error>(())
+    })
+    .unwrap();
+
+    assert_eq!(
+      context.padded_values(),
+      [
+        DataSetPath::new_with_data_element(dictionary::SOP_INSTANCE_UID.tag),
+        DataSetPath::new_with_data_element(dictionary::MODALITY.tag),
+        DataSetPath::new_with_data_element(
+          dictionary::ENCAPSULATED_DOCUMENT.tag
+        ),
+      ]
+    );
+
+    let read_data_set = crate::read_bytes(bytes.into(), None).unwrap();
+    assert_eq!(
+      read_data_set.get_value_bytes(dictionary::MODALITY.tag),
+      Ok(&b"CTX ".to_vec().into())
+    );
+    assert_eq!(
+      read_data_set.get_value_bytes(dictionary::SOP_INSTANCE_UID.tag),
+      Ok(&b"1.2.3\0".to_vec().into())
+    );
+    assert_eq!(
+      read_data_set.get_value_bytes(dictionary::ENCAPSULATED_DOCUMENT.tag),
+      Ok(&vec![1, 2, 3, 0xFF].into())
+    );
+  }
+
+  #[test]
+  fn deflate_thread_count_test() {
+    let mut data_set = DataSet::new();
+    data_set.insert(
+      dictionary::ENCAPSULATED_DOCUMENT.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::OtherByteString,
+        (0..3_000_000u32)
+          .map(|i| (i % 199) as u8)
+          .collect::<Vec<u8>>()
+          .into(),
+      ),
+    );
+
+    synthesize_file_meta_information(
+      &mut data_set,
+      &transfer_syntax::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN,
+      None,
+    )
+    .unwrap();
+
+    let mut bytes = vec![];
+    data_set_to_bytes(
+      &data_set,
+      &DataSetPath::new(),
+      &mut |chunk| {
+        bytes.extend_from_slice(&chunk);
+        Ok(())
+      },
+      Some(P10WriteConfig::default().deflate_thread_count(4)),
+    )
+    .unwrap();
+
+    assert!(bytes.len() < 1_000_000);
+
+    let tag = dictionary::ENCAPSULATED_DOCUMENT.tag;
+    let read_data_set = crate::read_bytes(bytes.into(), None).unwrap();
+    assert_eq!(
+      read_data_set.get_value_bytes(tag),
+      data_set.get_value_bytes(tag)
+    );
+  }
 }
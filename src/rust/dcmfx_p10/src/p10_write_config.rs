@@ -1,5 +1,8 @@
 #[cfg(not(feature = "std"))]
-use alloc::string::{String, ToString};
+use alloc::{
+  boxed::Box,
+  string::{String, ToString},
+};
 
 pub use crate::uids;
 
@@ -10,6 +13,31 @@ pub struct P10WriteConfig {
   pub(crate) implementation_class_uid: String,
   pub(crate) implementation_version_name: String,
   pub(crate) zlib_compression_level: u32,
+  pub(crate) deflate_thread_count: usize,
+  pub(crate) uid_write_policy: UidWritePolicy,
+  pub(crate) strict_conformance: bool,
+  pub(crate) auto_pad_values: bool,
+  pub(crate) binary_pad_byte: u8,
+  pub(crate) file_preamble: Option<Box<[u8; 128]>>,
+}
+
+/// Specifies how the values of data elements with a VR of `UniqueIdentifier`
+/// are checked when writing DICOM P10 data. Non-conformant UIDs are a common
+/// cause of data being rejected by other systems.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum UidWritePolicy {
+  /// UIDs are written as-is without being checked.
+  #[default]
+  Unchecked,
+
+  /// Non-conformant UIDs are an error.
+  Error,
+
+  /// Leading and trailing spaces and nulls are removed from UIDs, and values
+  /// are padded to an even length with a single trailing null. UIDs that are
+  /// non-conformant for any other reason are an error.
+  FixPadding,
 }
 
 impl Default for P10WriteConfig {
@@ -20,6 +48,12 @@ impl Default for P10WriteConfig {
       implementation_version_name: uids::DCMFX_IMPLEMENTATION_VERSION_NAME
         .to_string(),
       zlib_compression_level: 6,
+      deflate_thread_count: 1,
+      uid_write_policy: UidWritePolicy::Unchecked,
+      strict_conformance: false,
+      auto_pad_values: false,
+      binary_pad_byte: 0x00,
+      file_preamble: None,
     }
   }
 }
@@ -59,4 +93,94 @@ impl P10WriteConfig {
     self.zlib_compression_level = value.clamp(0, 9);
     self
   }
+
+  /// The number of threads to use for zlib compression when the transfer
+  /// syntax being used is deflated. When this is greater than one, data is
+  /// compressed in chunks using [`crate::parallel_deflate`], which gives
+  /// slightly larger output and uses more memory, but is much faster for large
+  /// data sets.
+  ///
+  /// Default: 1.
+  ///
+  pub fn deflate_thread_count(mut self, value: usize) -> Self {
+    self.deflate_thread_count = value.max(1);
+    self
+  }
+
+  /// How to check the values of data elements with a VR of `UniqueIdentifier`
+  /// as they are written. See [`UidWritePolicy`] for details.
+  ///
+  /// Default: [`UidWritePolicy::Unchecked`].
+  ///
+  pub fn uid_write_policy(mut self, value: UidWritePolicy) -> Self {
+    self.uid_write_policy = value;
+    self
+  }
+
+  /// Whether to check that every data element written conforms to the rules
+  /// for its VR, VM, and length. When enabled, writing fails on the first
+  /// non-conformant value with an error that gives the path to its data
+  /// element, rather than passing the invalid value through to the output.
+  ///
+  /// The checks made are:
+  ///
+  /// 1. The value's length doesn't exceed the maximum for its VR, and is a
+  ///    multiple of the size of the VR's data type.
+  /// 2. String values contain only the characters allowed by their VR, and
+  ///    each value doesn't exceed the VR's maximum number of characters.
+  /// 3. UIDs are conformant, as for [`UidWritePolicy::Error`].
+  /// 4. The number of values is allowed by the data element's VM in the data
+  ///    dictionary. Private and unknown data elements aren't checked.
+  ///
+  /// String values are buffered in full before being written so that they can
+  /// be checked. Other values are checked using only their length.
+  ///
+  /// Default: `false`.
+  ///
+  pub fn strict_conformance(mut self, value: bool) -> Self {
+    self.strict_conformance = value;
+    self
+  }
+
+  /// Whether to pad values that have an odd length to an even length as they
+  /// are written, rather than writing them unchanged. The DICOM standard
+  /// requires all values to have an even length.
+  ///
+  /// String values are padded with a space, `UniqueIdentifier` values with a
+  /// null, and all other values with [`Self::binary_pad_byte()`]. Use
+  /// [`crate::P10WriteContext::padded_values()`] to get the data elements that
+  /// were padded.
+  ///
+  /// Default: `false`.
+  ///
+  pub fn auto_pad_values(mut self, value: bool) -> Self {
+    self.auto_pad_values = value;
+    self
+  }
+
+  /// The byte used to pad binary values that have an odd length when
+  /// [`Self::auto_pad_values()`] is enabled. This also applies to encapsulated
+  /// pixel data items.
+  ///
+  /// Default: `0x00`.
+  ///
+  pub fn binary_pad_byte(mut self, value: u8) -> Self {
+    self.binary_pad_byte = value;
+    self
+  }
+
+  /// The 128-byte File Preamble to write at the start of serialized DICOM P10
+  /// data. When this is `None`, the File Preamble of the DICOM P10 tokens being
+  /// written is used, which is all zero bytes when writing a data set.
+  ///
+  /// This can be used to preserve a File Preamble read from existing DICOM P10
+  /// data, e.g. the TIFF header of a dual-personality DICOM/TIFF file. See
+  /// [`crate::file_preamble`] for details.
+  ///
+  /// Default: `None`.
+  ///
+  pub fn file_preamble(mut self, value: Option<[u8; 128]>) -> Self {
+    self.file_preamble = value.map(Box::new);
+    self
+  }
 }
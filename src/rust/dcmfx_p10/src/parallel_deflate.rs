@@ -0,0 +1,259 @@
+//! Deflate compression that uses multiple threads.
+//!
+//! Input data is split into fixed-size chunks that are compressed
+//! independently and concatenated into a single raw deflate stream. Every
+//! chunk except the last ends with a sync flush, which byte-aligns its output
+//! without marking it as the final block, so the combined output decompresses
+//! the same as data deflated by a single compressor.
+//!
+//! Because chunks don't share a dictionary the compression ratio is marginally
+//! lower than single-threaded deflate, and the exact bytes output depend on the
+//! chunk size, but not on the number of threads used.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+/// The size of the chunks that input data is split into for compression.
+///
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// A streaming deflate compressor that compresses chunks of its input in
+/// parallel. Data is added with [`Self::write()`] and the stream is completed
+/// with [`Self::finish()`]. The output is a raw deflate stream with no zlib
+/// header.
+///
+/// Threads are only used when the `std` feature is enabled. Without it, chunks
+/// are compressed in turn on the calling thread.
+///
+#[derive(Clone, Debug)]
+pub struct ParallelDeflate {
+  compression_level: u32,
+  thread_count: usize,
+  pending: Vec<u8>,
+}
+
+impl ParallelDeflate {
+  /// Creates a new parallel deflate compressor that uses the specified zlib
+  /// compression level and number of threads.
+  ///
+  pub fn new(compression_level: u32, thread_count: usize) -> Self {
+    Self {
+      compression_level: compression_level.clamp(0, 9),
+      thread_count: thread_count.max(1),
+      pending: vec![],
+    }
+  }
+
+  /// Adds data to the compressor. Once enough input has accumulated to give
+  /// every thread a chunk to compress, all complete chunks are compressed and
+  /// their output is returned.
+  ///
+  pub fn write(
+    &mut self,
+    data: &[u8],
+  ) -> Result<Vec<Vec<u8>>, flate2::CompressError> {
+    self.pending.extend_from_slice(data);
+
+    if self.pending.len() < CHUNK_SIZE * self.thread_count {
+      return Ok(vec![]);
+    }
+
+    let complete_length = self.pending.len() / CHUNK_SIZE * CHUNK_SIZE;
+
+    let output = compress_chunks(
+      &self.pending[..complete_length],
+      self.compression_level,
+      self.thread_count,
+      false,
+    )?;
+
+    self.pending.drain(..complete_length);
+
+    Ok(output)
+  }
+
+  /// Compresses all remaining input and ends the deflate stream, returning the
+  /// final output. The compressor must not be used after this call.
+  ///
+  pub fn finish(&mut self) -> Result<Vec<Vec<u8>>, flate2::CompressError> {
+    let pending = core::mem::take(&mut self.pending);
+
+    compress_chunks(&pending, self.compression_level, self.thread_count, true)
+  }
+}
+
+/// Deflates the passed data in parallel, returning a complete raw deflate
+/// stream as a list of byte buffers that should be concatenated in order.
+///
+pub fn deflate(
+  data: &[u8],
+  compression_level: u32,
+  thread_count: usize,
+) -> Result<Vec<Vec<u8>>, flate2::CompressError> {
+  compress_chunks(
+    data,
+    compression_level.clamp(0, 9),
+    thread_count.max(1),
+    true,
+  )
+}
+
+/// Compresses data in chunks of [`CHUNK_SIZE`], spreading the chunks across
+/// the specified number of threads. If `is_final` is true then the last chunk
+/// ends the deflate stream.
+///
+fn compress_chunks(
+  data: &[u8],
+  compression_level: u32,
+  thread_count: usize,
+  is_final: bool,
+) -> Result<Vec<Vec<u8>>, flate2::CompressError> {
+  if data.is_empty() {
+    return if is_final {
+      Ok(vec![compress_chunk(&[], compression_level, true)?])
+    } else {
+      Ok(vec![])
+    };
+  }
+
+  let chunks: Vec<&[u8]> = data.chunks(CHUNK_SIZE).collect();
+  let chunk_count = chunks.len();
+
+  #[cfg(feature = "std")]
+  if thread_count > 1 && chunk_count > 1 {
+    let chunks_per_thread = chunk_count.div_ceil(thread_count);
+
+    return std::thread::scope(|scope| {
+      let handles: Vec<_> = chunks
+        .chunks(chunks_per_thread)
+        .enumerate()
+        .map(|(group_index, group)| {
+          scope.spawn(move || {
+            group
+              .iter()
+              .enumerate()
+              .map(|(i, chunk)| {
+                let chunk_index = group_index * chunks_per_thread + i;
+                let is_last = is_final && chunk_index == chunk_count - 1;
+
+                compress_chunk(chunk, compression_level, is_last)
+              })
+              .collect::<Result<Vec<_>, _>>()
+          })
+        })
+        .collect();
+
+      let mut output = Vec::with_capacity(chunk_count);
+      for handle in handles {
+        match handle.join() {
+          Ok(group_output) => output.extend(group_output?),
+          Err(e) => std::panic::resume_unwind(e),
+        }
+      }
+
+      Ok(output)
+    });
+  }
+
+  #[cfg(not(feature = "std"))]
+  let _ = thread_count;
+
+  chunks
+    .iter()
+    .enumerate()
+    .map(|(i, chunk)| {
+      compress_chunk(chunk, compression_level, is_final && i == chunk_count - 1)
+    })
+    .collect()
+}
+
+/// Compresses a single chunk with its own compressor. Chunks that aren't the
+/// last end with a sync flush so their output can be directly followed by the
+/// next chunk's output.
+///
+fn compress_chunk(
+  chunk: &[u8],
+  compression_level: u32,
+  is_last: bool,
+) -> Result<Vec<u8>, flate2::CompressError> {
+  let mut compressor =
+    flate2::Compress::new(flate2::Compression::new(compression_level), false);
+
+  let flush = if is_last {
+    flate2::FlushCompress::Finish
+  } else {
+    flate2::FlushCompress::Sync
+  };
+
+  let mut output = Vec::with_capacity(chunk.len() + chunk.len() / 16 + 64);
+
+  loop {
+    if output.len() == output.capacity() {
+      output.reserve(64 * 1024);
+    }
+
+    let total_in = compressor.total_in() as usize;
+    let status =
+      compressor.compress_vec(&chunk[total_in..], &mut output, flush)?;
+
+    if is_last {
+      if status == flate2::Status::StreamEnd {
+        break;
+      }
+    } else if compressor.total_in() as usize == chunk.len()
+      && output.len() < output.capacity()
+    {
+      break;
+    }
+  }
+
+  output.shrink_to_fit();
+
+  Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use super::*;
+
+  fn inflate(buffers: &[Vec<u8>]) -> Vec<u8> {
+    let deflated = buffers.concat();
+    miniz_oxide::inflate::decompress_to_vec(&deflated).unwrap()
+  }
+
+  fn test_data(length: usize) -> Vec<u8> {
+    (0..length)
+      .map(|i| (((i * 7) % 251) ^ (i >> 12)) as u8)
+      .collect()
+  }
+
+  #[test]
+  fn deflate_test() {
+    for length in [0, 1, CHUNK_SIZE, CHUNK_SIZE * 3 + 12345] {
+      let data = test_data(length);
+
+      for thread_count in [1, 2, 4] {
+        let output = deflate(&data, 6, thread_count).unwrap();
+        assert_eq!(inflate(&output), data);
+      }
+    }
+  }
+
+  #[test]
+  fn streaming_test() {
+    let data = test_data(CHUNK_SIZE * 5 + 999);
+
+    let mut compressor = ParallelDeflate::new(6, 2);
+    let mut output = vec![];
+    for piece in data.chunks(300_000) {
+      output.extend(compressor.write(piece).unwrap());
+    }
+    output.extend(compressor.finish().unwrap());
+
+    assert_eq!(inflate(&output), data);
+    assert_eq!(output.concat(), deflate(&data, 6, 3).unwrap().concat());
+  }
+}
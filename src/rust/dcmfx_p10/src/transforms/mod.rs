@@ -6,3 +6,6 @@ pub mod p10_custom_type_transform;
 pub mod p10_filter_transform;
 pub mod p10_insert_transform;
 pub mod p10_print_transform;
+pub mod p10_size_limit_transform;
+pub mod p10_sr_content_item_transform;
+pub mod p10_value_transform;
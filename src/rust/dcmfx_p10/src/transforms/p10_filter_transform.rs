@@ -1,5 +1,5 @@
 #[cfg(not(feature = "std"))]
-use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use alloc::{boxed::Box, vec, vec::Vec};
 
 use dcmfx_core::{DataElementTag, DataSetPath, ValueRepresentation};
 
@@ -76,9 +76,9 @@ impl P10FilterTransform {
           .path_filter_results
           .pop()
           .ok_or(P10Error::TokenStreamInvalid {
-            when: "Adding token to filter transform".to_string(),
+            when: "Adding token to filter transform".into(),
             details: "Sequence delimiter received when current path is empty"
-              .to_string(),
+              .into(),
             token: token.clone(),
           })?;
 
@@ -96,10 +96,10 @@ impl P10FilterTransform {
           .path_filter_results
           .pop()
           .ok_or(P10Error::TokenStreamInvalid {
-            when: "Adding token to filter transform".to_string(),
+            when: "Adding token to filter transform".into(),
             details:
               "Sequence item delimiter received when current path is empty"
-                .to_string(),
+                .into(),
             token: token.clone(),
           })?;
 
@@ -119,9 +119,9 @@ impl P10FilterTransform {
         if *bytes_remaining == 0 {
           self.path_filter_results.pop().ok_or(
             P10Error::TokenStreamInvalid {
-              when: "Adding token to filter transform".to_string(),
+              when: "Adding token to filter transform".into(),
               details: "Data element bytes ended when current path is empty"
-                .to_string(),
+                .into(),
               token: token.clone(),
             },
           )?;
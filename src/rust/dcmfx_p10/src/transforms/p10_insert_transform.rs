@@ -1,5 +1,5 @@
 #[cfg(not(feature = "std"))]
-use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use alloc::{boxed::Box, vec, vec::Vec};
 
 use dcmfx_core::{
   DataElementTag, DataElementValue, DataSet, DataSetPath, IodModule,
@@ -108,9 +108,8 @@ impl P10InsertTransform {
             .pop()
             .and_then(|path| path.add_data_element(data_element.0))
             .map_err(|_| P10Error::TokenStreamInvalid {
-              when: "Adding token to insert transform".to_string(),
-              details: "Failed altering path for data element to insert"
-                .to_string(),
+              when: "Adding token to insert transform".into(),
+              details: "Failed altering path for data element to insert".into(),
               token: token.clone(),
             })?;
 
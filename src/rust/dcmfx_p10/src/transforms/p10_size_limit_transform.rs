@@ -0,0 +1,268 @@
+use dcmfx_core::{DataElementTag, dictionary, error_details};
+
+use crate::{P10Error, P10Token};
+
+/// Transform that tracks the cumulative size of the data element values in a
+/// stream of DICOM P10 tokens, and returns an error as soon as a configured
+/// size limit is exceeded. This allows quotas to be enforced part way through
+/// reading a stream, e.g. when accepting an upload, rather than after the
+/// whole stream has been received.
+///
+/// Sizes are tracked separately for pixel data and for all other data element
+/// values, which are referred to as metadata. The size of the File Meta
+/// Information is not included.
+///
+/// This transform doesn't perform any I/O and so is usable in both sync and
+/// async pipelines. Tokens are passed to [`P10SizeLimitTransform::add_token()`]
+/// as they're read, and are unaltered by it.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct P10SizeLimitTransform {
+  max_pixel_data_size: Option<u64>,
+  max_metadata_size: Option<u64>,
+  max_total_size: Option<u64>,
+
+  pixel_data_size: u64,
+  metadata_size: u64,
+
+  encapsulated_pixel_data_depth: Option<usize>,
+  sequence_depth: usize,
+}
+
+/// The groups of data that a [`P10SizeLimitTransform`] tracks sizes for.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum P10SizeLimitGroup {
+  /// The values of the *'(7FE0,0008) Float Pixel Data'*, *'(7FE0,0009) Double
+  /// Float Pixel Data'*, and *'(7FE0,0010) Pixel Data'* data elements,
+  /// including encapsulated pixel data items.
+  PixelData,
+
+  /// The values of all data elements that aren't pixel data.
+  Metadata,
+
+  /// The combined size of pixel data and metadata.
+  Total,
+}
+
+impl core::fmt::Display for P10SizeLimitGroup {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::PixelData => write!(f, "pixel data"),
+      Self::Metadata => write!(f, "metadata"),
+      Self::Total => write!(f, "total"),
+    }
+  }
+}
+
+impl P10SizeLimitTransform {
+  /// Creates a new size limit transform that has no limits set.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the maximum size in bytes of the pixel data in the stream.
+  ///
+  pub fn max_pixel_data_size(mut self, value: u64) -> Self {
+    self.max_pixel_data_size = Some(value);
+    self
+  }
+
+  /// Sets the maximum size in bytes of the data element values in the stream
+  /// that aren't pixel data.
+  ///
+  pub fn max_metadata_size(mut self, value: u64) -> Self {
+    self.max_metadata_size = Some(value);
+    self
+  }
+
+  /// Sets the maximum size in bytes of all data element values in the stream.
+  ///
+  pub fn max_total_size(mut self, value: u64) -> Self {
+    self.max_total_size = Some(value);
+    self
+  }
+
+  /// Returns the number of bytes of pixel data seen so far.
+  ///
+  pub fn pixel_data_size(&self) -> u64 {
+    self.pixel_data_size
+  }
+
+  /// Returns the number of bytes of data element values that aren't pixel data
+  /// seen so far.
+  ///
+  pub fn metadata_size(&self) -> u64 {
+    self.metadata_size
+  }
+
+  /// Returns the number of bytes of all data element values seen so far.
+  ///
+  pub fn total_size(&self) -> u64 {
+    self.pixel_data_size + self.metadata_size
+  }
+
+  /// Adds the next token to the size limit transform. Returns an error if this
+  /// token causes any of the configured size limits to be exceeded.
+  ///
+  pub fn add_token(&mut self, token: &P10Token) -> Result<(), P10Error> {
+    match token {
+      P10Token::SequenceStart { tag, .. } => {
+        if self.encapsulated_pixel_data_depth.is_none()
+          && is_pixel_data_tag(*tag)
+        {
+          self.encapsulated_pixel_data_depth = Some(self.sequence_depth);
+        }
+
+        self.sequence_depth += 1;
+      }
+
+      P10Token::SequenceDelimiter { .. } => {
+        self.sequence_depth = self.sequence_depth.saturating_sub(1);
+
+        if self.encapsulated_pixel_data_depth == Some(self.sequence_depth) {
+          self.encapsulated_pixel_data_depth = None;
+        }
+      }
+
+      P10Token::DataElementValueBytes { tag, data, .. } => {
+        let size = data.len() as u64;
+
+        if is_pixel_data_tag(*tag)
+          || self.encapsulated_pixel_data_depth.is_some()
+        {
+          self.pixel_data_size += size;
+          check_limit(
+            P10SizeLimitGroup::PixelData,
+            self.pixel_data_size,
+            self.max_pixel_data_size,
+          )?;
+        } else {
+          self.metadata_size += size;
+          check_limit(
+            P10SizeLimitGroup::Metadata,
+            self.metadata_size,
+            self.max_metadata_size,
+          )?;
+        }
+
+        check_limit(
+          P10SizeLimitGroup::Total,
+          self.total_size(),
+          self.max_total_size,
+        )?;
+      }
+
+      _ => (),
+    }
+
+    Ok(())
+  }
+}
+
+fn is_pixel_data_tag(tag: DataElementTag) -> bool {
+  tag == dictionary::PIXEL_DATA.tag
+    || tag == dictionary::FLOAT_PIXEL_DATA.tag
+    || tag == dictionary::DOUBLE_FLOAT_PIXEL_DATA.tag
+}
+
+fn check_limit(
+  group: P10SizeLimitGroup,
+  size: u64,
+  limit: Option<u64>,
+) -> Result<(), P10Error> {
+  match limit {
+    Some(limit) if size > limit => Err(P10Error::SizeLimitExceeded {
+      details: error_details!(
+        "Size of {group} exceeded the limit of {limit} bytes, reached {size} \
+         bytes"
+      ),
+      group,
+      limit,
+      size,
+    }),
+
+    _ => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(not(feature = "std"))]
+  use alloc::{vec, vec::Vec};
+
+  use dcmfx_core::{DataSetPath, ValueRepresentation};
+
+  #[test]
+  fn add_tokens_test() {
+    let tokens: Vec<P10Token> = vec![
+      value_bytes_token(dictionary::PATIENT_NAME.tag, 10),
+      P10Token::SequenceStart {
+        tag: dictionary::PIXEL_DATA.tag,
+        vr: ValueRepresentation::OtherByteString,
+        path: DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+      },
+      P10Token::PixelDataItem {
+        index: 0,
+        length: 20,
+      },
+      value_bytes_token(dictionary::ITEM.tag, 20),
+      P10Token::SequenceDelimiter {
+        tag: dictionary::PIXEL_DATA.tag,
+      },
+      value_bytes_token(dictionary::DATA_SET_TRAILING_PADDING.tag, 4),
+      P10Token::End,
+    ];
+
+    let mut transform = P10SizeLimitTransform::new()
+      .max_pixel_data_size(20)
+      .max_metadata_size(14)
+      .max_total_size(34);
+
+    for token in tokens.iter() {
+      transform.add_token(token).unwrap();
+    }
+
+    assert_eq!(transform.pixel_data_size(), 20);
+    assert_eq!(transform.metadata_size(), 14);
+    assert_eq!(transform.total_size(), 34);
+
+    let mut transform = P10SizeLimitTransform::new().max_pixel_data_size(19);
+    assert_eq!(
+      tokens
+        .iter()
+        .try_for_each(|token| transform.add_token(token)),
+      Err(P10Error::SizeLimitExceeded {
+        details: "Size of pixel data exceeded the limit of 19 bytes, reached \
+                  20 bytes"
+          .into(),
+        group: P10SizeLimitGroup::PixelData,
+        limit: 19,
+        size: 20,
+      })
+    );
+
+    let mut transform = P10SizeLimitTransform::new().max_total_size(33);
+    assert!(matches!(
+      tokens
+        .iter()
+        .try_for_each(|token| transform.add_token(token)),
+      Err(P10Error::SizeLimitExceeded {
+        group: P10SizeLimitGroup::Total,
+        ..
+      })
+    ));
+  }
+
+  fn value_bytes_token(tag: DataElementTag, length: usize) -> P10Token {
+    P10Token::DataElementValueBytes {
+      tag,
+      vr: ValueRepresentation::OtherByteString,
+      data: vec![0; length].into(),
+      bytes_remaining: 0,
+    }
+  }
+}
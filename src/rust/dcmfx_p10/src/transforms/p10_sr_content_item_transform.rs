@@ -0,0 +1,398 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{
+  DataSet, dictionary,
+  iods::sr::{CodedEntry, ContentItem},
+};
+
+use crate::{DataSetBuilder, P10CustomTypeTransformError, P10Token};
+
+/// Transforms a stream of DICOM P10 tokens into the content items of an SR
+/// document that have one of a set of requested concept names, without
+/// building the document's full content tree in memory.
+///
+/// Content items are read one at a time as they stream through. Each one's own
+/// data elements are gathered and its concept name is checked. Content items
+/// that don't match are discarded, and the search continues into their Content
+/// Sequence. Content items that do match are kept along with all the content
+/// items nested beneath them.
+///
+/// Only content items in a *'(0040,A730) Content Sequence'* are considered,
+/// i.e. the root of the content tree is never output. Use
+/// [`dcmfx_core::iods::sr::SrDocumentContentModule`] to read a whole content
+/// tree.
+///
+/// Matched content items are accessed using
+/// [`P10SrContentItemTransform::get_output()`], and can be removed as they
+/// become available using [`P10SrContentItemTransform::take_output()`] so that
+/// they don't accumulate in memory.
+///
+pub struct P10SrContentItemTransform {
+  concept_names: Vec<(String, String)>,
+  location: Vec<Location>,
+  output: Vec<ContentItem>,
+}
+
+/// The current location in the stream of DICOM P10 tokens.
+///
+enum Location {
+  /// The root data set.
+  Root,
+
+  /// A Content Sequence whose items are being searched for matching content
+  /// items.
+  ContentSequence,
+
+  /// A content item in a Content Sequence. Its data elements are gathered into
+  /// a data set, except for the content items nested beneath it which are only
+  /// gathered if this content item matches.
+  ContentItem {
+    builder: DataSetBuilder,
+    data_set: DataSet,
+    is_match: Option<bool>,
+  },
+
+  /// Any other sequence or sequence item. Its tokens go to the data set
+  /// builder of the enclosing content item, if there is one.
+  Other,
+}
+
+impl P10SrContentItemTransform {
+  /// Creates a new transform that extracts the content items that have any of
+  /// the given concept names, each of which is specified as a code value and
+  /// coding scheme designator.
+  ///
+  pub fn new(concept_names: &[(&str, &str)]) -> Self {
+    Self {
+      concept_names: concept_names
+        .iter()
+        .map(|(code_value, coding_scheme_designator)| {
+          (code_value.to_string(), coding_scheme_designator.to_string())
+        })
+        .collect(),
+      location: vec![Location::Root],
+      output: vec![],
+    }
+  }
+
+  /// Adds the next token in the DICOM P10 token stream.
+  ///
+  pub fn add_token(
+    &mut self,
+    token: &P10Token,
+  ) -> Result<(), P10CustomTypeTransformError> {
+    match token {
+      P10Token::SequenceStart { tag, .. }
+        if *tag == dictionary::CONTENT_SEQUENCE.tag =>
+      {
+        match self.location.last_mut() {
+          Some(Location::Root) => {
+            self.location.push(Location::ContentSequence);
+            return Ok(());
+          }
+
+          Some(Location::ContentItem {
+            builder,
+            data_set,
+            is_match,
+          }) => {
+            // All data elements that precede the Content Sequence, including
+            // the concept name, have now been gathered, so determine whether
+            // this content item matches
+            data_set.merge(finish_data_set_builder(builder));
+            let matches = concept_names_match(&self.concept_names, data_set);
+            *is_match = Some(matches);
+
+            // Gather nested content items only into a matching content item,
+            // otherwise search them for further matches
+            if !matches {
+              self.location.push(Location::ContentSequence);
+              return Ok(());
+            }
+          }
+
+          _ => (),
+        }
+
+        self.add_token_to_content_item(token)?;
+        self.location.push(Location::Other);
+      }
+
+      P10Token::SequenceStart { .. } => {
+        self.add_token_to_content_item(token)?;
+        self.location.push(Location::Other);
+      }
+
+      P10Token::SequenceItemStart { .. } => {
+        if let Some(Location::ContentSequence) = self.location.last() {
+          self.location.push(Location::ContentItem {
+            builder: DataSetBuilder::new(),
+            data_set: DataSet::new(),
+            is_match: None,
+          });
+        } else {
+          self.add_token_to_content_item(token)?;
+          self.location.push(Location::Other);
+        }
+      }
+
+      P10Token::SequenceItemDelimiter => {
+        if let Some(Location::ContentItem { .. }) = self.location.last() {
+          let Some(Location::ContentItem {
+            mut builder,
+            mut data_set,
+            is_match,
+          }) = self.location.pop()
+          else {
+            unreachable!();
+          };
+
+          data_set.merge(finish_data_set_builder(&mut builder));
+
+          let is_match = is_match.unwrap_or_else(|| {
+            concept_names_match(&self.concept_names, &data_set)
+          });
+
+          if is_match {
+            self.output.push(
+              ContentItem::from_data_set(&data_set)
+                .map_err(P10CustomTypeTransformError::DataError)?,
+            );
+          }
+        } else {
+          self.add_token_to_content_item(token)?;
+          self.location.pop();
+        }
+      }
+
+      P10Token::SequenceDelimiter { .. } => {
+        if let Some(Location::ContentSequence) = self.location.last() {
+          self.location.pop();
+        } else {
+          self.add_token_to_content_item(token)?;
+          self.location.pop();
+        }
+      }
+
+      P10Token::DataElementHeader { .. }
+      | P10Token::DataElementValueBytes { .. }
+      | P10Token::PixelDataItem { .. } => {
+        self.add_token_to_content_item(token)?;
+      }
+
+      P10Token::FilePreambleAndDICMPrefix { .. }
+      | P10Token::FileMetaInformation { .. }
+      | P10Token::End => (),
+    }
+
+    Ok(())
+  }
+
+  /// Returns the matching content items extracted by this transform so far.
+  ///
+  pub fn get_output(&self) -> &[ContentItem] {
+    &self.output
+  }
+
+  /// Removes and returns the matching content items extracted by this
+  /// transform so far.
+  ///
+  pub fn take_output(&mut self) -> Vec<ContentItem> {
+    core::mem::take(&mut self.output)
+  }
+
+  /// Adds a token to the data set builder of the enclosing content item. If
+  /// there is no enclosing content item, or the token is in a Content Sequence
+  /// that is being searched, then the token is discarded.
+  ///
+  fn add_token_to_content_item(
+    &mut self,
+    token: &P10Token,
+  ) -> Result<(), P10CustomTypeTransformError> {
+    for location in self.location.iter_mut().rev() {
+      match location {
+        Location::Other => continue,
+
+        Location::ContentItem { builder, .. } => {
+          return builder
+            .add_token(token)
+            .map_err(P10CustomTypeTransformError::P10Error);
+        }
+
+        Location::Root | Location::ContentSequence => return Ok(()),
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Returns whether the concept name of the content item in the given data set
+/// is one of the passed concept names.
+///
+fn concept_names_match(
+  concept_names: &[(String, String)],
+  data_set: &DataSet,
+) -> bool {
+  let Ok(Some(concept_name)) = CodedEntry::from_optional_sequence(
+    data_set,
+    dictionary::CONCEPT_NAME_CODE_SEQUENCE.tag,
+  ) else {
+    return false;
+  };
+
+  concept_names
+    .iter()
+    .any(|(code_value, coding_scheme_designator)| {
+      concept_name.is(code_value, coding_scheme_designator)
+    })
+}
+
+/// Completes a data set builder and returns its data set, leaving a new data
+/// set builder in its place.
+///
+fn finish_data_set_builder(builder: &mut DataSetBuilder) -> DataSet {
+  let mut builder = core::mem::take(builder);
+  builder.force_end();
+
+  builder.final_data_set().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use crate::DataSetP10Extensions;
+
+  fn code_item(code_value: &str, code_meaning: &str) -> DataSet {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::CODE_VALUE, &[code_value])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::CODING_SCHEME_DESIGNATOR, &["DCM"])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::CODE_MEANING, &[code_meaning])
+      .unwrap();
+    item
+  }
+
+  fn content_item(
+    value_type: &str,
+    concept_name: DataSet,
+    children: Vec<DataSet>,
+  ) -> DataSet {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::RELATIONSHIP_TYPE, &["CONTAINS"])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::VALUE_TYPE, &[value_type])
+      .unwrap();
+    item
+      .insert_sequence_value(
+        &dictionary::CONCEPT_NAME_CODE_SEQUENCE,
+        vec![concept_name],
+      )
+      .unwrap();
+
+    if value_type == "TEXT" {
+      item
+        .insert_string_value(&dictionary::TEXT_VALUE, &["Normal"])
+        .unwrap();
+    }
+
+    if !children.is_empty() {
+      item
+        .insert_sequence_value(&dictionary::CONTENT_SEQUENCE, children)
+        .unwrap();
+    }
+
+    item
+  }
+
+  fn sr_data_set() -> DataSet {
+    let finding = content_item("TEXT", code_item("121071", "Finding"), vec![]);
+
+    let group = content_item(
+      "CONTAINER",
+      code_item("125007", "Measurement Group"),
+      vec![
+        content_item("TEXT", code_item("121071", "Finding"), vec![]),
+        content_item("TEXT", code_item("121106", "Comment"), vec![]),
+      ],
+    );
+
+    let findings = content_item(
+      "CONTAINER",
+      code_item("121070", "Findings"),
+      vec![finding, group],
+    );
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::VALUE_TYPE, &["CONTAINER"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(
+        &dictionary::CONCEPT_NAME_CODE_SEQUENCE,
+        vec![code_item("126000", "Imaging Measurement Report")],
+      )
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::CONTINUITY_OF_CONTENT, &["SEPARATE"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(&dictionary::CONTENT_SEQUENCE, vec![findings])
+      .unwrap();
+
+    data_set
+  }
+
+  fn extract(concept_names: &[(&str, &str)]) -> Vec<ContentItem> {
+    let mut transform = P10SrContentItemTransform::new(concept_names);
+
+    for token in sr_data_set().to_p10_tokens() {
+      transform.add_token(&token).unwrap();
+    }
+
+    transform.take_output()
+  }
+
+  #[test]
+  fn add_token_test() {
+    let items = extract(&[("121071", "DCM")]);
+    assert_eq!(
+      items.iter().map(|item| item.to_lines()).collect::<Vec<_>>(),
+      vec![
+        vec!["CONTAINS TEXT (121071, DCM, \"Finding\") = \"Normal\""],
+        vec!["CONTAINS TEXT (121071, DCM, \"Finding\") = \"Normal\""],
+      ]
+    );
+
+    let items = extract(&[("125007", "DCM"), ("121106", "DCM")]);
+    assert_eq!(
+      items.iter().map(|item| item.to_lines()).collect::<Vec<_>>(),
+      vec![vec![
+        "CONTAINS CONTAINER (125007, DCM, \"Measurement Group\")",
+        "  CONTAINS TEXT (121071, DCM, \"Finding\") = \"Normal\"",
+        "  CONTAINS TEXT (121106, DCM, \"Comment\") = \"Normal\"",
+      ]]
+    );
+
+    assert_eq!(extract(&[("126000", "DCM")]), vec![]);
+    assert_eq!(extract(&[("121071", "SRT")]), vec![]);
+  }
+}
@@ -0,0 +1,272 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+
+use dcmfx_core::{
+  DataElementTag, DataElementValue, DataError, DataSetPath, RcByteSlice,
+  ValueRepresentation,
+};
+
+use crate::{
+  P10Error, P10Token, data_set_builder, p10_token,
+  transforms::p10_filter_transform::PredicateFn,
+};
+
+/// Transform that rewrites the values of selected data elements in a stream of
+/// DICOM P10 tokens. This allows values to be normalized as they pass through,
+/// e.g. on their way to a [`crate::P10WriteContext`], without first having to
+/// read the whole data set into memory.
+///
+/// Incoming data elements are passed to a predicate function that determines
+/// whether their value should be transformed. The value of each selected data
+/// element is gathered in full and passed to a transform function, and the
+/// value it returns is emitted in its place. All other tokens pass through
+/// unaltered.
+///
+/// Only data elements that are not sequences or encapsulated pixel data can be
+/// transformed, and because selected values are held in memory the predicate
+/// function should avoid selecting large values such as pixel data.
+///
+pub struct P10ValueTransform {
+  predicate: Box<PredicateFn>,
+  transform: Box<ValueTransformFn>,
+  pending_data_element: Option<PendingDataElement>,
+}
+
+/// Defines a function called by a [`P10ValueTransform`] that returns the new
+/// value for a selected data element. Returning an error stops the transform.
+///
+pub type ValueTransformFn = dyn Fn(
+    DataElementTag,
+    DataElementValue,
+    &DataSetPath,
+  ) -> Result<DataElementValue, DataError>
+  + Send;
+
+struct PendingDataElement {
+  tag: DataElementTag,
+  vr: ValueRepresentation,
+  path: DataSetPath,
+  value_bytes: Vec<RcByteSlice>,
+}
+
+impl P10ValueTransform {
+  /// Creates a new transform for rewriting the values of data elements in a
+  /// stream of DICOM P10 tokens.
+  ///
+  /// The predicate function is called with the header of each data element,
+  /// and the transform function is then called with the complete value of
+  /// those data elements for which the predicate function returned `true`.
+  ///
+  pub fn new(
+    predicate: Box<PredicateFn>,
+    transform: Box<ValueTransformFn>,
+  ) -> Self {
+    Self {
+      predicate,
+      transform,
+      pending_data_element: None,
+    }
+  }
+
+  /// Adds the next token to the value transform and returns the resulting
+  /// tokens.
+  ///
+  pub fn add_token(
+    &mut self,
+    token: &P10Token,
+  ) -> Result<Vec<P10Token>, P10Error> {
+    match token {
+      P10Token::DataElementHeader {
+        tag,
+        vr,
+        length,
+        path,
+      } if self.pending_data_element.is_none()
+        && (self.predicate)(*tag, *vr, Some(*length), path) =>
+      {
+        self.pending_data_element = Some(PendingDataElement {
+          tag: *tag,
+          vr: *vr,
+          path: path.clone(),
+          value_bytes: vec![],
+        });
+
+        Ok(vec![])
+      }
+
+      P10Token::DataElementValueBytes {
+        data,
+        bytes_remaining,
+        ..
+      } if self.pending_data_element.is_some() => {
+        let pending_data_element = self.pending_data_element.as_mut().unwrap();
+        pending_data_element.value_bytes.push(data.clone());
+
+        if *bytes_remaining > 0 {
+          return Ok(vec![]);
+        }
+
+        let PendingDataElement {
+          tag,
+          vr,
+          path,
+          value_bytes,
+        } = self.pending_data_element.take().unwrap();
+
+        let value = data_set_builder::build_final_data_element_value(
+          tag,
+          vr,
+          &value_bytes,
+        );
+
+        let value = (self.transform)(tag, value, &path).map_err(|e| {
+          P10Error::OtherError {
+            error_type: "Value transform failed".to_string(),
+            details: e.with_path(&path).to_string().into(),
+          }
+        })?;
+
+        let mut output_tokens = vec![];
+        p10_token::data_element_to_tokens::<()>(
+          tag,
+          &value,
+          &path,
+          &mut |token: P10Token| {
+            output_tokens.push(token);
+            Ok(())
+          },
+        )
+        .unwrap();
+
+        Ok(output_tokens)
+      }
+
+      _ => Ok(vec![token.clone()]),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(not(feature = "std"))]
+  use alloc::string::String;
+
+  use dcmfx_core::dictionary;
+
+  #[test]
+  fn add_tokens_test() {
+    let mut value_transform = P10ValueTransform::new(
+      Box::new(|_tag, vr, _length, _path| {
+        vr == ValueRepresentation::CodeString
+      }),
+      Box::new(|_tag, value, _path| {
+        let values = value
+          .get_strings()?
+          .into_iter()
+          .map(|s| s.to_uppercase())
+          .collect::<Vec<String>>();
+
+        DataElementValue::new_code_string(
+          &values.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+        )
+      }),
+    );
+
+    let modality = dictionary::MODALITY.tag;
+    let study_description = dictionary::STUDY_DESCRIPTION.tag;
+
+    let input_tokens = [
+      P10Token::DataElementHeader {
+        tag: modality,
+        vr: ValueRepresentation::CodeString,
+        length: 2,
+        path: DataSetPath::new_with_data_element(modality),
+      },
+      P10Token::DataElementValueBytes {
+        tag: modality,
+        vr: ValueRepresentation::CodeString,
+        data: b"m".to_vec().into(),
+        bytes_remaining: 1,
+      },
+      P10Token::DataElementValueBytes {
+        tag: modality,
+        vr: ValueRepresentation::CodeString,
+        data: b"r".to_vec().into(),
+        bytes_remaining: 0,
+      },
+      P10Token::DataElementHeader {
+        tag: study_description,
+        vr: ValueRepresentation::LongString,
+        length: 2,
+        path: DataSetPath::new_with_data_element(study_description),
+      },
+      P10Token::DataElementValueBytes {
+        tag: study_description,
+        vr: ValueRepresentation::LongString,
+        data: b"ab".to_vec().into(),
+        bytes_remaining: 0,
+      },
+      P10Token::End,
+    ];
+
+    let mut output_tokens = vec![];
+    for token in input_tokens.iter() {
+      output_tokens.extend(value_transform.add_token(token).unwrap());
+    }
+
+    assert_eq!(
+      output_tokens,
+      vec![
+        P10Token::DataElementHeader {
+          tag: modality,
+          vr: ValueRepresentation::CodeString,
+          length: 2,
+          path: DataSetPath::new_with_data_element(modality),
+        },
+        P10Token::DataElementValueBytes {
+          tag: modality,
+          vr: ValueRepresentation::CodeString,
+          data: b"MR".to_vec().into(),
+          bytes_remaining: 0,
+        },
+        input_tokens[3].clone(),
+        input_tokens[4].clone(),
+        P10Token::End,
+      ]
+    );
+  }
+
+  #[test]
+  fn transform_error_test() {
+    let mut value_transform = P10ValueTransform::new(
+      Box::new(|_tag, _vr, _length, _path| true),
+      Box::new(|_tag, _value, _path| {
+        Err(DataError::new_value_invalid("Invalid"))
+      }),
+    );
+
+    let tag = dictionary::MODALITY.tag;
+
+    value_transform
+      .add_token(&P10Token::DataElementHeader {
+        tag,
+        vr: ValueRepresentation::CodeString,
+        length: 2,
+        path: DataSetPath::new_with_data_element(tag),
+      })
+      .unwrap();
+
+    assert!(
+      value_transform
+        .add_token(&P10Token::DataElementValueBytes {
+          tag,
+          vr: ValueRepresentation::CodeString,
+          data: b"MR".to_vec().into(),
+          bytes_remaining: 0,
+        })
+        .is_err()
+    );
+  }
+}
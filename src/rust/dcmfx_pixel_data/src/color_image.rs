@@ -4,6 +4,7 @@ use alloc::vec::Vec;
 use dcmfx_core::Rc;
 
 use crate::{
+  IndexedColorImage, image_hash, image_quality,
   iods::{PaletteColorLookupTableModule, image_pixel_module::BitsAllocated},
   transforms::CropRect,
   utils::udiv_round,
@@ -268,6 +269,36 @@ impl ColorImage {
     )
   }
 
+  /// Converts this color image to an [`IndexedColorImage`] if it stores palette
+  /// color data that uses no more than 256 distinct palette entries. When the
+  /// stored values are all less than 256 they are used directly as indices,
+  /// which preserves the original palette. Returns `None` for non-palette color
+  /// data or when there are too many distinct palette entries in use.
+  ///
+  pub fn to_indexed_color_image(&self) -> Option<IndexedColorImage> {
+    match &self.data {
+      ColorImageData::PaletteU8 { data, palette } => {
+        IndexedColorImage::from_stored_values(
+          self.width,
+          self.height,
+          data,
+          |v| palette.lookup_normalized_u8(v.into()),
+        )
+      }
+
+      ColorImageData::PaletteU16 { data, palette } => {
+        IndexedColorImage::from_stored_values(
+          self.width,
+          self.height,
+          data,
+          |v| palette.lookup_normalized_u8(v.into()),
+        )
+      }
+
+      _ => None,
+    }
+  }
+
   /// Returns the number of bits allocated for each sample.
   ///
   pub fn bits_allocated(&self) -> BitsAllocated {
@@ -614,6 +645,55 @@ impl ColorImage {
     self.height = height;
   }
 
+  /// Blacks out the specified rectangle of this color image. RGB pixels are
+  /// set to zero, YBR pixels are set to zero luminance with no chrominance,
+  /// and palette color pixels are set to index zero. This is used to redact
+  /// burned-in annotations.
+  ///
+  pub fn black_out(&mut self, rect: &CropRect) {
+    let left = rect.left;
+    let top = rect.top;
+    let (height, width) = rect.apply(self.height(), self.width());
+
+    let image_width = usize::from(self.width);
+    let indices =
+      (usize::from(top)..usize::from(top + height)).flat_map(move |row| {
+        let start = row * image_width + usize::from(left);
+        start..start + usize::from(width)
+      });
+
+    let black = |color_space: &ColorSpace| -> [u32; 3] {
+      match color_space {
+        ColorSpace::Rgb => [0, 0, 0],
+        ColorSpace::Ybr { .. } => {
+          let half = 1 << (self.bits_stored - 1);
+          [0, half, half]
+        }
+      }
+    };
+
+    match &mut self.data {
+      ColorImageData::U8 { data, color_space } => {
+        let black = black(color_space).map(|v| v as u8);
+        indices.for_each(|i| data[i * 3..i * 3 + 3].copy_from_slice(&black));
+      }
+      ColorImageData::U16 { data, color_space } => {
+        let black = black(color_space).map(|v| v as u16);
+        indices.for_each(|i| data[i * 3..i * 3 + 3].copy_from_slice(&black));
+      }
+      ColorImageData::U32 { data, color_space } => {
+        let black = black(color_space);
+        indices.for_each(|i| data[i * 3..i * 3 + 3].copy_from_slice(&black));
+      }
+      ColorImageData::PaletteU8 { data, .. } => {
+        indices.for_each(|i| data[i] = 0)
+      }
+      ColorImageData::PaletteU16 { data, .. } => {
+        indices.for_each(|i| data[i] = 0)
+      }
+    }
+  }
+
   /// Converts this color image to an 8-bit RGB image.
   ///
   pub fn into_rgb_u8_image(
@@ -946,6 +1026,155 @@ impl ColorImage {
     )
     .unwrap()
   }
+
+  /// Returns a SHA-256 hash of this color image's dimensions, color space, and
+  /// stored values. Images with the same hash have identical stored values,
+  /// even if they were encoded using different transfer syntaxes.
+  ///
+  /// For palette color images the stored values are the palette indexes, and
+  /// the palette itself isn't included in the hash.
+  ///
+  pub fn stored_value_hash(&self) -> [u8; 32] {
+    fn hash<T: Copy + Into<i64>>(
+      image: &ColorImage,
+      data: &[T],
+      kind: u8,
+    ) -> [u8; 32] {
+      let mut hasher =
+        image_hash::StoredValueHasher::new(image.width, image.height, kind);
+
+      for value in data {
+        hasher.update((*value).into());
+      }
+
+      hasher.finalize()
+    }
+
+    let kind = |color_space: &ColorSpace| match color_space {
+      ColorSpace::Rgb => 2,
+      ColorSpace::Ybr { .. } => 3,
+    };
+
+    match &self.data {
+      ColorImageData::U8 { data, color_space } => {
+        hash(self, data, kind(color_space))
+      }
+      ColorImageData::U16 { data, color_space } => {
+        hash(self, data, kind(color_space))
+      }
+      ColorImageData::U32 { data, color_space } => {
+        hash(self, data, kind(color_space))
+      }
+      ColorImageData::PaletteU8 { data, .. } => hash(self, data, 4),
+      ColorImageData::PaletteU16 { data, .. } => hash(self, data, 4),
+    }
+  }
+
+  /// Returns the dHash perceptual hash of this color image, computed on its
+  /// luminance.
+  ///
+  /// See [`image_hash::perceptual_hash_distance()`].
+  ///
+  pub fn dhash(&self) -> u64 {
+    image_hash::dhash(&self.luminance(), self.width.into(), self.height.into())
+  }
+
+  /// Returns the pHash perceptual hash of this color image, computed on its
+  /// luminance.
+  ///
+  /// See [`image_hash::perceptual_hash_distance()`].
+  ///
+  pub fn phash(&self) -> u64 {
+    image_hash::phash(&self.luminance(), self.width.into(), self.height.into())
+  }
+
+  /// Returns the MSE, PSNR, and SSIM of this color image compared to a
+  /// reference image, e.g. the original image prior to lossy compression.
+  /// Returns `None` if the images' dimensions differ.
+  ///
+  /// Both images are converted to RGB normalized into the range 0-1 using each
+  /// image's bits stored, so images with different bit depths and color spaces
+  /// can be compared. SSIM is computed on each channel and then averaged.
+  ///
+  /// See [`image_quality::ImageQualityMetrics`].
+  ///
+  pub fn quality_metrics(
+    &self,
+    reference: &Self,
+  ) -> Option<image_quality::ImageQualityMetrics> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::ImageQualityMetrics::compute(
+      reference.to_rgb_f64_image().as_raw(),
+      self.to_rgb_f64_image().as_raw(),
+      self.width.into(),
+      self.height.into(),
+      3,
+    ))
+  }
+
+  /// Returns the MSE of this color image compared to a reference image.
+  /// Returns `None` if the images' dimensions differ.
+  ///
+  /// See [`Self::quality_metrics()`] and [`image_quality::mse()`].
+  ///
+  pub fn mse(&self, reference: &Self) -> Option<f64> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::mse(
+      reference.to_rgb_f64_image().as_raw(),
+      self.to_rgb_f64_image().as_raw(),
+    ))
+  }
+
+  /// Returns the PSNR in decibels of this color image compared to a reference
+  /// image. Returns `None` if the images' dimensions differ.
+  ///
+  /// See [`Self::quality_metrics()`] and [`image_quality::psnr()`].
+  ///
+  pub fn psnr(&self, reference: &Self) -> Option<f64> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::psnr(
+      reference.to_rgb_f64_image().as_raw(),
+      self.to_rgb_f64_image().as_raw(),
+    ))
+  }
+
+  /// Returns the SSIM of this color image compared to a reference image.
+  /// Returns `None` if the images' dimensions differ.
+  ///
+  /// See [`Self::quality_metrics()`] and [`image_quality::ssim()`].
+  ///
+  pub fn ssim(&self, reference: &Self) -> Option<f64> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::ssim(
+      reference.to_rgb_f64_image().as_raw(),
+      self.to_rgb_f64_image().as_raw(),
+      self.width.into(),
+      self.height.into(),
+      3,
+    ))
+  }
+
+  /// Returns the luminance of each pixel in this color image.
+  ///
+  fn luminance(&self) -> Vec<f64> {
+    self
+      .to_rgb_f64_image()
+      .pixels()
+      .map(|rgb| 0.299 * rgb[0] + 0.587 * rgb[1] + 0.114 * rgb[2])
+      .collect()
+  }
 }
 
 /// Converts a YBR color into RGB.
@@ -9,29 +9,6 @@ use crate::{
   },
 };
 
-/// Returns the photometric interpretation used by data decoded using CharLS.
-///
-pub fn decode_photometric_interpretation(
-  photometric_interpretation: &PhotometricInterpretation,
-) -> Result<&PhotometricInterpretation, PixelDataDecodeError> {
-  match photometric_interpretation {
-    PhotometricInterpretation::Monochrome1 { .. }
-    | PhotometricInterpretation::Monochrome2 { .. }
-    | PhotometricInterpretation::Rgb
-    | PhotometricInterpretation::YbrFull
-    | PhotometricInterpretation::PaletteColor { .. } => {
-      Ok(photometric_interpretation)
-    }
-
-    _ => Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
-      details: format!(
-        "Photometric interpretation '{photometric_interpretation}' is not \
-         supported"
-      ),
-    }),
-  }
-}
-
 /// Decodes monochrome pixel data using CharLS.
 ///
 pub fn decode_monochrome(
@@ -0,0 +1,633 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec, vec::Vec};
+
+use dcmfx_core::{TransferSyntax, transfer_syntax};
+
+use crate::iods::{
+  ImagePixelModule, image_pixel_module::PhotometricInterpretation,
+};
+
+/// Details of the image held in a frame of encapsulated pixel data, as read
+/// from the headers of its codestream. These describe what the codestream
+/// actually contains, which may differ from what is claimed by the Image Pixel
+/// Module, and so are useful for diagnostics and for correcting an Image Pixel
+/// Module that doesn't match its pixel data.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DecodedFrameInfo {
+  /// The width of the image in the codestream.
+  pub width: u32,
+
+  /// The height of the image in the codestream.
+  pub height: u32,
+
+  /// The precision of the samples in the codestream. If components have
+  /// differing precisions then this is the highest precision.
+  pub bits_per_sample: u8,
+
+  /// The number of components in the codestream.
+  pub samples_per_pixel: u8,
+
+  /// The horizontal and vertical subsampling factors of the second component
+  /// relative to the first, e.g. `(2, 1)` for 4:2:2 chroma subsampling and
+  /// `(2, 2)` for 4:2:0. This is `(1, 1)` when there's no subsampling.
+  pub subsampling: (u8, u8),
+
+  /// Whether the codestream uses lossy compression, if this is detectable.
+  pub lossy: Option<bool>,
+
+  /// The progression used by the codestream, if it has one.
+  pub progression: Option<CodestreamProgression>,
+
+  /// The color transform applied to the first three components by the
+  /// codestream, if any. This is only detected for JPEG 2000 codestreams.
+  pub color_transform: Option<ColorTransform>,
+}
+
+/// A color transform that a JPEG 2000 codestream applies to its first three
+/// components. JPEG 2000 decoders reverse this transform, so decoded pixel data
+/// is RGB whenever a color transform is present.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorTransform {
+  /// The irreversible color transform, which is used with the irreversible 9-7
+  /// wavelet transform and corresponds to the `YBR_ICT` photometric
+  /// interpretation.
+  Ict,
+
+  /// The reversible color transform, which is used with the reversible 5-3
+  /// wavelet transform and corresponds to the `YBR_RCT` photometric
+  /// interpretation.
+  Rct,
+}
+
+impl ColorTransform {
+  /// Returns the photometric interpretation that describes pixel data encoded
+  /// with this color transform.
+  ///
+  pub fn photometric_interpretation(&self) -> PhotometricInterpretation {
+    match self {
+      Self::Ict => PhotometricInterpretation::YbrIct,
+      Self::Rct => PhotometricInterpretation::YbrRct,
+    }
+  }
+}
+
+impl core::fmt::Display for ColorTransform {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let s = match self {
+      Self::Ict => "ICT",
+      Self::Rct => "RCT",
+    };
+
+    f.write_str(s)
+  }
+}
+
+/// The order in which image data is stored in a codestream.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CodestreamProgression {
+  /// JPEG sequential mode, where each component is stored in a single scan.
+  Sequential,
+
+  /// JPEG progressive mode, where the image is refined over multiple scans.
+  Progressive,
+
+  /// JPEG 2000 layer-resolution-component-position progression.
+  Lrcp,
+
+  /// JPEG 2000 resolution-layer-component-position progression.
+  Rlcp,
+
+  /// JPEG 2000 resolution-position-component-layer progression.
+  Rpcl,
+
+  /// JPEG 2000 position-component-resolution-layer progression.
+  Pcrl,
+
+  /// JPEG 2000 component-position-resolution-layer progression.
+  Cprl,
+}
+
+impl core::fmt::Display for CodestreamProgression {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let s = match self {
+      Self::Sequential => "sequential",
+      Self::Progressive => "progressive",
+      Self::Lrcp => "LRCP",
+      Self::Rlcp => "RLCP",
+      Self::Rpcl => "RPCL",
+      Self::Pcrl => "PCRL",
+      Self::Cprl => "CPRL",
+    };
+
+    f.write_str(s)
+  }
+}
+
+impl DecodedFrameInfo {
+  /// Reads the details of the image held in a frame of pixel data by parsing
+  /// the headers of its codestream. Returns `None` if the transfer syntax
+  /// doesn't store frames in a codestream with readable headers, or if the
+  /// headers are invalid.
+  ///
+  /// This is currently supported for the JPEG, JPEG-LS, JPEG 2000, and
+  /// High-Throughput JPEG 2000 transfer syntaxes.
+  ///
+  pub fn from_codestream(
+    data: &[u8],
+    transfer_syntax: &'static TransferSyntax,
+  ) -> Option<Self> {
+    use transfer_syntax::*;
+
+    match transfer_syntax {
+      &JPEG_BASELINE_8BIT
+      | &JPEG_EXTENDED_12BIT
+      | &JPEG_LOSSLESS_NON_HIERARCHICAL
+      | &JPEG_LOSSLESS_NON_HIERARCHICAL_SV1
+      | &JPEG_LS_LOSSLESS
+      | &JPEG_LS_LOSSY_NEAR_LOSSLESS => read_jpeg(data),
+
+      &JPEG_2000 | &HIGH_THROUGHPUT_JPEG_2000 => read_jpeg_2000(data, false),
+
+      &JPEG_2000_LOSSLESS_ONLY
+      | &HIGH_THROUGHPUT_JPEG_2000_LOSSLESS_ONLY
+      | &HIGH_THROUGHPUT_JPEG_2000_WITH_RPCL_OPTIONS_LOSSLESS_ONLY => {
+        read_jpeg_2000(data, true)
+      }
+
+      _ => None,
+    }
+  }
+
+  /// Returns descriptions of the ways in which the given Image Pixel Module
+  /// differs from this codestream. An empty list is returned if they match.
+  ///
+  pub fn image_pixel_module_mismatches(
+    &self,
+    image_pixel_module: &ImagePixelModule,
+  ) -> Vec<String> {
+    let mut mismatches = vec![];
+
+    if self.width != u32::from(image_pixel_module.columns())
+      || self.height != u32::from(image_pixel_module.rows())
+    {
+      mismatches.push(format!(
+        "Codestream size is {}x{} but the Image Pixel Module specifies {}x{}",
+        self.width,
+        self.height,
+        image_pixel_module.columns(),
+        image_pixel_module.rows()
+      ));
+    }
+
+    let samples_per_pixel = u8::from(image_pixel_module.samples_per_pixel());
+    if self.samples_per_pixel != samples_per_pixel {
+      mismatches.push(format!(
+        "Codestream has {} components but the Image Pixel Module specifies {} \
+         samples per pixel",
+        self.samples_per_pixel, samples_per_pixel
+      ));
+    }
+
+    let bits_stored = image_pixel_module.bits_stored();
+    if u16::from(self.bits_per_sample) != bits_stored {
+      mismatches.push(format!(
+        "Codestream precision is {} bits but the Image Pixel Module specifies \
+         {} bits stored",
+        self.bits_per_sample, bits_stored
+      ));
+    }
+
+    // JPEG 2000 pixel data with a color transform is only correctly described
+    // by YBR_ICT or YBR_RCT, and vice versa. Such data still decodes to RGB
+    // when the photometric interpretation is wrong.
+    let photometric_interpretation =
+      image_pixel_module.photometric_interpretation();
+    match self.color_transform {
+      Some(color_transform)
+        if *photometric_interpretation
+          != color_transform.photometric_interpretation() =>
+      {
+        mismatches.push(format!(
+          "Codestream uses the {color_transform} color transform but the \
+           Image Pixel Module specifies a photometric interpretation of \
+           '{photometric_interpretation}'"
+        ));
+      }
+
+      None
+        if photometric_interpretation.is_ybr_ict()
+          || photometric_interpretation.is_ybr_rct() =>
+      {
+        mismatches.push(format!(
+          "Codestream doesn't use a color transform but the Image Pixel \
+           Module specifies a photometric interpretation of \
+           '{photometric_interpretation}'"
+        ));
+      }
+
+      _ => (),
+    }
+
+    mismatches
+  }
+}
+
+/// Reads the frame header of a JPEG or JPEG-LS codestream. For JPEG-LS, the
+/// first scan header is also read to determine whether it's lossy.
+///
+fn read_jpeg(data: &[u8]) -> Option<DecodedFrameInfo> {
+  if data.get(0..2)? != [0xFF, 0xD8] {
+    return None;
+  }
+
+  let mut position = 2;
+  let mut info: Option<DecodedFrameInfo> = None;
+
+  loop {
+    // Skip fill bytes that precede the marker
+    if *data.get(position)? != 0xFF {
+      return None;
+    }
+    while *data.get(position)? == 0xFF {
+      position += 1;
+    }
+
+    let marker = data[position];
+    position += 1;
+
+    // Markers without a segment
+    if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+      continue;
+    }
+
+    if marker == 0xD9 {
+      return info;
+    }
+
+    let length = usize::from(u16::from_be_bytes(
+      data.get(position..position + 2)?.try_into().ok()?,
+    ));
+    let segment = data.get(position + 2..position + length.max(2))?;
+    position += length;
+
+    match marker {
+      // Start of frame markers, excluding DHT, JPG, and DAC
+      0xC0..=0xCF if marker != 0xC4 && marker != 0xC8 && marker != 0xCC => {
+        let mut frame_info = read_frame_header(segment)?;
+
+        frame_info.lossy = Some(marker & 0x03 != 0x03);
+        frame_info.progression = Some(if marker & 0x03 == 0x02 {
+          CodestreamProgression::Progressive
+        } else {
+          CodestreamProgression::Sequential
+        });
+
+        info = Some(frame_info);
+      }
+
+      // JPEG-LS start of frame
+      0xF7 => info = Some(read_frame_header(segment)?),
+
+      // Start of scan, which for JPEG-LS holds the NEAR parameter that is
+      // non-zero for lossy compression
+      0xDA => {
+        let mut info = info?;
+
+        if info.lossy.is_none() {
+          let component_count = usize::from(*segment.first()?);
+          let near = *segment.get(1 + component_count * 2)?;
+
+          info.lossy = Some(near != 0);
+        }
+
+        return Some(info);
+      }
+
+      _ => (),
+    }
+  }
+}
+
+/// Reads a JPEG or JPEG-LS frame header segment.
+///
+fn read_frame_header(segment: &[u8]) -> Option<DecodedFrameInfo> {
+  let bits_per_sample = *segment.first()?;
+  let height = u16::from_be_bytes([*segment.get(1)?, *segment.get(2)?]);
+  let width = u16::from_be_bytes([*segment.get(3)?, *segment.get(4)?]);
+  let samples_per_pixel = *segment.get(5)?;
+
+  let sampling_factors = (0..usize::from(samples_per_pixel))
+    .map(|i| segment.get(6 + i * 3 + 1).map(|f| (f >> 4, f & 0x0F)))
+    .collect::<Option<Vec<_>>>()?;
+
+  let subsampling = match sampling_factors.as_slice() {
+    [first, second, ..] if second.0 > 0 && second.1 > 0 => {
+      (first.0 / second.0, first.1 / second.1)
+    }
+    _ => (1, 1),
+  };
+
+  Some(DecodedFrameInfo {
+    width: u32::from(width),
+    height: u32::from(height),
+    bits_per_sample,
+    samples_per_pixel,
+    subsampling,
+    lossy: None,
+    progression: None,
+    color_transform: None,
+  })
+}
+
+/// Reads the SIZ and COD marker segments in the main header of a JPEG 2000
+/// codestream.
+///
+fn read_jpeg_2000(
+  data: &[u8],
+  is_lossless_only: bool,
+) -> Option<DecodedFrameInfo> {
+  if data.get(0..4)? != [0xFF, 0x4F, 0xFF, 0x51] {
+    return None;
+  }
+
+  let read_u16 = |offset: usize| -> Option<u16> {
+    Some(u16::from_be_bytes(
+      data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+  };
+  let read_u32 = |offset: usize| -> Option<u32> {
+    Some(u32::from_be_bytes(
+      data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+  };
+
+  // Read the SIZ segment, which follows the marker and its length
+  let siz = 6;
+  let width = read_u32(siz + 2)?.checked_sub(read_u32(siz + 10)?)?;
+  let height = read_u32(siz + 6)?.checked_sub(read_u32(siz + 14)?)?;
+  let samples_per_pixel = u8::try_from(read_u16(siz + 34)?).ok()?;
+
+  let components = (0..usize::from(samples_per_pixel))
+    .map(|i| data.get(siz + 36 + i * 3..siz + 39 + i * 3))
+    .collect::<Option<Vec<_>>>()?;
+
+  let bits_per_sample = components.iter().map(|c| (c[0] & 0x7F) + 1).max()?;
+
+  let subsampling = match components.as_slice() {
+    [first, second, ..] if first[1] > 0 && first[2] > 0 => {
+      (second[1] / first[1], second[2] / first[2])
+    }
+    _ => (1, 1),
+  };
+
+  let mut info = DecodedFrameInfo {
+    width,
+    height,
+    bits_per_sample,
+    samples_per_pixel,
+    subsampling,
+    lossy: None,
+    progression: None,
+    color_transform: None,
+  };
+
+  // Look for the COD segment in the rest of the main header, which ends at the
+  // first tile-part
+  let mut position = 4 + usize::from(read_u16(4)?);
+  loop {
+    let marker = read_u16(position)?;
+    if marker == 0xFF90 {
+      return Some(info);
+    }
+
+    let length = usize::from(read_u16(position + 2)?);
+
+    if marker == 0xFF52 {
+      info.progression = match data.get(position + 5)? {
+        0 => Some(CodestreamProgression::Lrcp),
+        1 => Some(CodestreamProgression::Rlcp),
+        2 => Some(CodestreamProgression::Rpcl),
+        3 => Some(CodestreamProgression::Pcrl),
+        4 => Some(CodestreamProgression::Cprl),
+        _ => None,
+      };
+
+      // The irreversible 9-7 wavelet transform is always lossy, but the
+      // reversible 5-3 transform is only lossless if no data was discarded,
+      // which can only be relied on for lossless-only transfer syntaxes
+      let is_reversible = *data.get(position + 13)? == 1;
+      info.lossy = if !is_reversible {
+        Some(true)
+      } else if is_lossless_only {
+        Some(false)
+      } else {
+        None
+      };
+
+      // The color transform is specified by the multiple component
+      // transformation flag, and is reversible when the wavelet transform is
+      info.color_transform = match data.get(position + 8)? {
+        1 if is_reversible => Some(ColorTransform::Rct),
+        1 => Some(ColorTransform::Ict),
+        _ => None,
+      };
+
+      return Some(info);
+    }
+
+    position += 2 + length;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::iods::image_pixel_module::{
+    BitsAllocated, PixelRepresentation, PlanarConfiguration, SamplesPerPixel,
+  };
+
+  #[test]
+  fn jpeg_test() {
+    let data = [
+      0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC2, 0x00, 0x11,
+      0x08, 0x00, 0x20, 0x00, 0x40, 0x03, 0x01, 0x22, 0x00, 0x02, 0x11, 0x01,
+      0x03, 0x11, 0x01, 0xFF, 0xD9,
+    ];
+
+    assert_eq!(
+      DecodedFrameInfo::from_codestream(
+        &data,
+        &transfer_syntax::JPEG_BASELINE_8BIT
+      ),
+      Some(DecodedFrameInfo {
+        width: 64,
+        height: 32,
+        bits_per_sample: 8,
+        samples_per_pixel: 3,
+        subsampling: (2, 2),
+        lossy: Some(true),
+        progression: Some(CodestreamProgression::Progressive),
+        color_transform: None,
+      })
+    );
+  }
+
+  #[test]
+  fn jpeg_ls_test() {
+    let data = [
+      0xFF, 0xD8, 0xFF, 0xF7, 0x00, 0x0B, 0x0C, 0x00, 0x10, 0x00, 0x10, 0x01,
+      0x01, 0x11, 0x00, 0xFF, 0xDA, 0x00, 0x08, 0x01, 0x01, 0x00, 0x02, 0x00,
+      0x00,
+    ];
+
+    assert_eq!(
+      DecodedFrameInfo::from_codestream(
+        &data,
+        &transfer_syntax::JPEG_LS_LOSSY_NEAR_LOSSLESS
+      ),
+      Some(DecodedFrameInfo {
+        width: 16,
+        height: 16,
+        bits_per_sample: 12,
+        samples_per_pixel: 1,
+        subsampling: (1, 1),
+        lossy: Some(true),
+        progression: None,
+        color_transform: None,
+      })
+    );
+  }
+
+  #[test]
+  fn jpeg_2000_test() {
+    let mut data = vec![0xFF, 0x4F, 0xFF, 0x51, 0x00, 0x29, 0x00, 0x00];
+    for value in [256u32, 128, 0, 0, 256, 128, 0, 0] {
+      data.extend_from_slice(&value.to_be_bytes());
+    }
+    data.extend_from_slice(&[0x00, 0x01, 0x0F, 0x01, 0x01]);
+    data.extend_from_slice(&[
+      0xFF, 0x52, 0x00, 0x0C, 0x00, 0x02, 0x00, 0x01, 0x00, 0x05, 0x04, 0x04,
+      0x00, 0x01,
+    ]);
+    data.extend_from_slice(&[0xFF, 0x90]);
+
+    let info = DecodedFrameInfo::from_codestream(
+      &data,
+      &transfer_syntax::JPEG_2000_LOSSLESS_ONLY,
+    );
+
+    assert_eq!(
+      info,
+      Some(DecodedFrameInfo {
+        width: 256,
+        height: 128,
+        bits_per_sample: 16,
+        samples_per_pixel: 1,
+        subsampling: (1, 1),
+        lossy: Some(false),
+        progression: Some(CodestreamProgression::Rpcl),
+        color_transform: None,
+      })
+    );
+
+    assert_eq!(
+      DecodedFrameInfo::from_codestream(&data, &transfer_syntax::JPEG_2000)
+        .unwrap()
+        .lossy,
+      None
+    );
+
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      128,
+      256,
+      BitsAllocated::Sixteen,
+      16,
+    )
+    .unwrap();
+
+    assert!(
+      info
+        .unwrap()
+        .image_pixel_module_mismatches(&image_pixel_module)
+        .is_empty()
+    );
+  }
+
+  #[test]
+  fn jpeg_2000_color_transform_test() {
+    let mut data = vec![0xFF, 0x4F, 0xFF, 0x51, 0x00, 0x2F, 0x00, 0x00];
+    for value in [64u32, 32, 0, 0, 64, 32, 0, 0] {
+      data.extend_from_slice(&value.to_be_bytes());
+    }
+    data.extend_from_slice(&[0x00, 0x03]);
+    for _ in 0..3 {
+      data.extend_from_slice(&[0x07, 0x01, 0x01]);
+    }
+    data.extend_from_slice(&[
+      0xFF, 0x52, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x01, 0x01, 0x05, 0x04, 0x04,
+      0x00, 0x01,
+    ]);
+    data.extend_from_slice(&[0xFF, 0x90]);
+
+    let info = DecodedFrameInfo::from_codestream(
+      &data,
+      &transfer_syntax::JPEG_2000_LOSSLESS_ONLY,
+    )
+    .unwrap();
+
+    assert_eq!(info.samples_per_pixel, 3);
+    assert_eq!(info.color_transform, Some(ColorTransform::Rct));
+
+    let image_pixel_module = |photometric_interpretation| {
+      ImagePixelModule::new_basic(
+        SamplesPerPixel::Three {
+          planar_configuration: PlanarConfiguration::Interleaved,
+        },
+        photometric_interpretation,
+        32,
+        64,
+        BitsAllocated::Eight,
+        8,
+      )
+      .unwrap()
+    };
+
+    assert!(
+      info
+        .image_pixel_module_mismatches(&image_pixel_module(
+          PhotometricInterpretation::YbrRct
+        ))
+        .is_empty()
+    );
+
+    assert_eq!(
+      info.image_pixel_module_mismatches(&image_pixel_module(
+        PhotometricInterpretation::Rgb
+      )),
+      vec![
+        "Codestream uses the RCT color transform but the Image Pixel Module \
+         specifies a photometric interpretation of 'Rgb'"
+          .to_string()
+      ]
+    );
+
+    // Irreversible wavelet transform
+    let position = data.len() - 3;
+    data[position] = 0x00;
+
+    assert_eq!(
+      DecodedFrameInfo::from_codestream(&data, &transfer_syntax::JPEG_2000)
+        .unwrap()
+        .color_transform,
+      Some(ColorTransform::Ict)
+    );
+  }
+}
@@ -0,0 +1,443 @@
+//! Decodes MPEG-2, H.264, and HEVC pixel data by running FFmpeg in a child
+//! process. The `ffmpeg` binary must be on the path.
+//!
+//! Video pixel data is held in a single frame that contains the whole video
+//! stream. FFmpeg is run once on the video stream, and outputs each of its
+//! pictures in turn.
+
+use std::{
+  io::{Read, Write},
+  process::{Command, Stdio},
+};
+
+use crate::{
+  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeConfig,
+  PixelDataDecodeError,
+  iods::image_pixel_module::{
+    BitsAllocated, ImagePixelModule, PhotometricInterpretation,
+    PixelRepresentation,
+  },
+};
+
+/// Returns the photometric interpretation resulting from decoding video with
+/// FFmpeg. Color video is always converted to RGB.
+///
+pub fn decode_photometric_interpretation(
+  photometric_interpretation: &PhotometricInterpretation,
+) -> Result<&PhotometricInterpretation, PixelDataDecodeError> {
+  match photometric_interpretation {
+    PhotometricInterpretation::Monochrome1 { .. }
+    | PhotometricInterpretation::Monochrome2 { .. }
+    | PhotometricInterpretation::Rgb => Ok(photometric_interpretation),
+
+    PhotometricInterpretation::YbrFull
+    | PhotometricInterpretation::YbrFull422
+    | PhotometricInterpretation::YbrPartial420 => {
+      Ok(&PhotometricInterpretation::Rgb)
+    }
+
+    _ => Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+      details: format!(
+        "Photometric interpretation '{photometric_interpretation}' is not \
+         supported"
+      ),
+    }),
+  }
+}
+
+/// Decodes the pictures in monochrome video pixel data using FFmpeg. If a
+/// maximum picture count is given then decoding stops once that many pictures
+/// have been decoded.
+///
+pub fn decode_monochrome(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+  max_picture_count: Option<usize>,
+  decode_config: &PixelDataDecodeConfig,
+) -> Result<Vec<MonochromeImage>, PixelDataDecodeError> {
+  let width = image_pixel_module.columns();
+  let height = image_pixel_module.rows();
+  let bits_stored = image_pixel_module.bits_stored();
+  let is_monochrome1 = image_pixel_module
+    .photometric_interpretation()
+    .is_monochrome1();
+
+  match (
+    image_pixel_module.photometric_interpretation(),
+    image_pixel_module.bits_allocated(),
+  ) {
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      BitsAllocated::Eight,
+    ) => {
+      let pictures = decode(
+        image_pixel_module,
+        data,
+        max_picture_count,
+        "gray",
+        1,
+        decode_config,
+      )?;
+
+      pictures
+        .into_iter()
+        .map(|pixels| {
+          MonochromeImage::new_u8(
+            width,
+            height,
+            pixels,
+            bits_stored,
+            is_monochrome1,
+          )
+          .map_err(PixelDataDecodeError::ImageCreationFailed)
+        })
+        .collect()
+    }
+
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      BitsAllocated::Sixteen,
+    ) => {
+      let pictures = decode(
+        image_pixel_module,
+        data,
+        max_picture_count,
+        "gray16le",
+        2,
+        decode_config,
+      )?;
+
+      pictures
+        .into_iter()
+        .map(|picture| {
+          MonochromeImage::new_u16(
+            width,
+            height,
+            to_u16_samples(&picture, bits_stored),
+            bits_stored,
+            is_monochrome1,
+          )
+          .map_err(PixelDataDecodeError::ImageCreationFailed)
+        })
+        .collect()
+    }
+
+    (photometric_interpretation, bits_allocated) => {
+      Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+        details: format!(
+          "FFmpeg monochrome decode not supported for photometric \
+           interpretation '{}', bits allocated '{}'",
+          photometric_interpretation,
+          u8::from(bits_allocated)
+        ),
+      })
+    }
+  }
+}
+
+/// Decodes the pictures in color video pixel data using FFmpeg. If a maximum
+/// picture count is given then decoding stops once that many pictures have
+/// been decoded.
+///
+pub fn decode_color(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+  max_picture_count: Option<usize>,
+  decode_config: &PixelDataDecodeConfig,
+) -> Result<Vec<ColorImage>, PixelDataDecodeError> {
+  let width = image_pixel_module.columns();
+  let height = image_pixel_module.rows();
+  let bits_stored = image_pixel_module.bits_stored();
+
+  match (
+    image_pixel_module.photometric_interpretation(),
+    image_pixel_module.bits_allocated(),
+  ) {
+    (
+      PhotometricInterpretation::Rgb
+      | PhotometricInterpretation::YbrFull
+      | PhotometricInterpretation::YbrFull422
+      | PhotometricInterpretation::YbrPartial420,
+      BitsAllocated::Eight,
+    ) => {
+      let pictures = decode(
+        image_pixel_module,
+        data,
+        max_picture_count,
+        "rgb24",
+        3,
+        decode_config,
+      )?;
+
+      pictures
+        .into_iter()
+        .map(|pixels| {
+          ColorImage::new_u8(
+            width,
+            height,
+            pixels,
+            ColorSpace::Rgb,
+            bits_stored,
+          )
+          .map_err(PixelDataDecodeError::ImageCreationFailed)
+        })
+        .collect()
+    }
+
+    (
+      PhotometricInterpretation::Rgb
+      | PhotometricInterpretation::YbrFull
+      | PhotometricInterpretation::YbrFull422
+      | PhotometricInterpretation::YbrPartial420,
+      BitsAllocated::Sixteen,
+    ) => {
+      let pictures = decode(
+        image_pixel_module,
+        data,
+        max_picture_count,
+        "rgb48le",
+        6,
+        decode_config,
+      )?;
+
+      pictures
+        .into_iter()
+        .map(|picture| {
+          ColorImage::new_u16(
+            width,
+            height,
+            to_u16_samples(&picture, bits_stored),
+            ColorSpace::Rgb,
+            bits_stored,
+          )
+          .map_err(PixelDataDecodeError::ImageCreationFailed)
+        })
+        .collect()
+    }
+
+    (photometric_interpretation, bits_allocated) => {
+      Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+        details: format!(
+          "FFmpeg color decode not supported for photometric \
+           interpretation '{}', bits allocated '{}'",
+          photometric_interpretation,
+          u8::from(bits_allocated)
+        ),
+      })
+    }
+  }
+}
+
+/// Decodes the pictures in the given video data using a single run of FFmpeg,
+/// and returns the raw pixels of each picture in the specified FFmpeg pixel
+/// format.
+///
+fn decode(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+  max_picture_count: Option<usize>,
+  pixel_format: &str,
+  bytes_per_pixel: usize,
+  decode_config: &PixelDataDecodeConfig,
+) -> Result<Vec<Vec<u8>>, PixelDataDecodeError> {
+  let thread_count = decode_config.thread_count.map(|n| n.to_string());
+
+  let mut args = vec!["-v", "error"];
+  if let Some(thread_count) = thread_count.as_deref() {
+    args.extend(["-threads", thread_count]);
+  }
+  args.extend(["-i", "pipe:0"]);
+
+  let max_picture_count = max_picture_count.map(|n| n.to_string());
+  if let Some(max_picture_count) = max_picture_count.as_deref() {
+    args.extend(["-frames:v", max_picture_count]);
+  }
+
+  // Passing timestamps through stops FFmpeg duplicating or dropping pictures
+  // to match a frame rate
+  args.extend(["-fps_mode", "passthrough", "-f", "rawvideo"]);
+  args.extend(["-pix_fmt", pixel_format, "pipe:1"]);
+
+  let mut child = Command::new(ffmpeg_binary())
+    .args(args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|_| PixelDataDecodeError::DecoderNotAvailable {
+      name: ffmpeg_binary().to_string(),
+    })?;
+
+  let (Some(mut stdin), Some(mut stdout), Some(mut stderr)) =
+    (child.stdin.take(), child.stdout.take(), child.stderr.take())
+  else {
+    return Err(PixelDataDecodeError::DecoderNotAvailable {
+      name: ffmpeg_binary().to_string(),
+    });
+  };
+
+  // The input is written and the errors are read on separate threads so that
+  // FFmpeg is never blocked on a full pipe. FFmpeg exits without reading all
+  // of its input once the maximum picture count is reached, so write errors
+  // are ignored.
+  let (output, errors) = std::thread::scope(|scope| {
+    scope.spawn(move || {
+      let _ = stdin.write_all(data);
+    });
+
+    let errors = scope.spawn(move || {
+      let mut errors = String::new();
+      let _ = stderr.read_to_string(&mut errors);
+      errors
+    });
+
+    let mut output = vec![];
+    let _ = stdout.read_to_end(&mut output);
+
+    (output, errors.join().unwrap_or_default())
+  });
+
+  let status =
+    child
+      .wait()
+      .map_err(|e| PixelDataDecodeError::DecoderNotAvailable {
+        name: format!("{} ({e})", ffmpeg_binary()),
+      })?;
+
+  if !status.success() {
+    return Err(PixelDataDecodeError::DataInvalid {
+      details: format!(
+        "FFmpeg decode failed with '{}'",
+        errors.lines().last().unwrap_or_default()
+      ),
+    });
+  }
+
+  if output.is_empty() {
+    return Err(PixelDataDecodeError::DataInvalid {
+      details: "FFmpeg did not decode any pictures".to_string(),
+    });
+  }
+
+  let picture_size = image_pixel_module.pixel_count() * bytes_per_pixel;
+  if !output.len().is_multiple_of(picture_size) {
+    return Err(PixelDataDecodeError::DataInvalid {
+      details: "FFmpeg decoded image has incorrect dimensions".to_string(),
+    });
+  }
+
+  Ok(
+    output
+      .chunks_exact(picture_size)
+      .map(|picture| picture.to_vec())
+      .collect(),
+  )
+}
+
+/// Converts little endian 16-bit samples output by FFmpeg to the range of
+/// the stored bits. FFmpeg scales samples with a lower bit depth up to use
+/// the full 16-bit range.
+///
+fn to_u16_samples(data: &[u8], bits_stored: u16) -> Vec<u16> {
+  let shift = 16u16.saturating_sub(bits_stored);
+
+  data
+    .chunks_exact(2)
+    .map(|sample| u16::from_le_bytes([sample[0], sample[1]]) >> shift)
+    .collect()
+}
+
+fn ffmpeg_binary() -> &'static str {
+  #[cfg(not(windows))]
+  return "ffmpeg";
+
+  #[cfg(windows)]
+  return "ffmpeg.exe";
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn to_u16_samples_test() {
+    assert_eq!(
+      to_u16_samples(&[0xC0, 0xFF, 0x40, 0x80], 10),
+      vec![0x3FF, 0x201]
+    );
+
+    assert_eq!(to_u16_samples(&[0x34, 0x12], 16), vec![0x1234]);
+  }
+
+  // This test is only built with the 'ffmpeg' feature, which requires FFmpeg
+  // to be on the path
+  #[test]
+  fn decode_pictures_test() {
+    use crate::iods::image_pixel_module::SamplesPerPixel;
+
+    // Encode a video of three pictures that get brighter with each picture
+    let output = Command::new(ffmpeg_binary())
+      .args(["-v", "error", "-f", "lavfi"])
+      .args(["-i", "color=black:s=16x16:r=25"])
+      .args(["-vf", "geq=lum=16+N*80:cb=128:cr=128", "-frames:v", "3"])
+      .args(["-c:v", "mpeg2video", "-f", "mpeg2video", "pipe:1"])
+      .output()
+      .expect("FFmpeg is required to test the 'ffmpeg' feature");
+    assert!(output.status.success());
+
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      16,
+      16,
+      BitsAllocated::Eight,
+      8,
+    )
+    .unwrap();
+
+    let decode_config = PixelDataDecodeConfig::default();
+
+    let mean_values = decode_monochrome(
+      &image_pixel_module,
+      &output.stdout,
+      None,
+      &decode_config,
+    )
+    .unwrap()
+    .iter()
+    .map(|image| {
+      let values = image.to_stored_values();
+      values.iter().sum::<i64>() / values.len() as i64
+    })
+    .collect::<Vec<_>>();
+
+    assert_eq!(mean_values.len(), 3);
+    assert!(mean_values[0] < 10);
+    assert!(mean_values[1] > mean_values[0] + 60);
+    assert!(mean_values[2] > mean_values[1] + 60);
+
+    assert_eq!(
+      decode_monochrome(
+        &image_pixel_module,
+        &output.stdout,
+        Some(1),
+        &decode_config
+      )
+      .unwrap()
+      .len(),
+      1
+    );
+  }
+}
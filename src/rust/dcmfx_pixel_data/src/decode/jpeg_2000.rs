@@ -1,8 +1,11 @@
 #[cfg(not(feature = "std"))]
 use alloc::format;
 
+use dcmfx_core::transfer_syntax;
+
 use crate::{
-  PixelDataDecodeError, iods::image_pixel_module::PhotometricInterpretation,
+  ColorSpace, DecodedFrameInfo, PixelDataDecodeError,
+  iods::{ImagePixelModule, image_pixel_module::PhotometricInterpretation},
 };
 
 /// Returns the photometric interpretation used by decoded JPEG 2000 pixel data.
@@ -29,3 +32,29 @@ pub fn decode_photometric_interpretation(
     }),
   }
 }
+
+/// Returns the color space of color pixel data decoded from a JPEG 2000
+/// codestream. JPEG 2000 decoders reverse any color transform used by the
+/// codestream, so decoded data is only YBR when the photometric interpretation
+/// is `YBR_FULL` and the codestream doesn't use a color transform. This means
+/// that codestreams with a color transform decode correctly even when their
+/// photometric interpretation is `RGB` or `YBR_FULL`.
+///
+pub fn decode_color_space(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+) -> ColorSpace {
+  let has_color_transform =
+    DecodedFrameInfo::from_codestream(data, &transfer_syntax::JPEG_2000)
+      .is_some_and(|info| info.color_transform.is_some());
+
+  if image_pixel_module
+    .photometric_interpretation()
+    .is_ybr_full()
+    && !has_color_transform
+  {
+    ColorSpace::Ybr { is_422: false }
+  } else {
+    ColorSpace::Rgb
+  }
+}
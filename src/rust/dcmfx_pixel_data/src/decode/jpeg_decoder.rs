@@ -8,39 +8,23 @@ use crate::{
   },
 };
 
-/// Returns the photometric interpretation used by data decoded using
-/// jpeg-decoder.
+/// The maximum number of times jpeg-decoder is able to halve the resolution of
+/// an image during decoding. Its smallest scale factor is 1/8.
 ///
-pub fn decode_photometric_interpretation(
-  photometric_interpretation: &PhotometricInterpretation,
-) -> Result<&PhotometricInterpretation, PixelDataDecodeError> {
-  match photometric_interpretation {
-    PhotometricInterpretation::Monochrome1 { .. }
-    | PhotometricInterpretation::Monochrome2 { .. }
-    | PhotometricInterpretation::PaletteColor { .. }
-    | PhotometricInterpretation::Rgb => Ok(photometric_interpretation),
+pub const MAX_RESOLUTION_REDUCTION: u32 = 3;
 
-    PhotometricInterpretation::YbrFull => Ok(&PhotometricInterpretation::Rgb),
-
-    _ => Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
-      details: format!(
-        "Photometric interpretation '{photometric_interpretation}' is not \
-         supported"
-      ),
-    }),
-  }
-}
-
-/// Decodes monochrome pixel data using jpeg-decoder.
+/// Decodes monochrome pixel data using jpeg-decoder. If a minimum decoded size
+/// is specified then the image is decoded at the lowest resolution that is at
+/// least that size.
 ///
 pub fn decode_monochrome(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  min_decoded_size: Option<(u16, u16)>,
 ) -> Result<MonochromeImage, PixelDataDecodeError> {
-  let (pixels, pixel_format) = decode(image_pixel_module, data)?;
+  let (pixels, pixel_format, width, height) =
+    decode(image_pixel_module, data, min_decoded_size)?;
 
-  let width = image_pixel_module.columns();
-  let height = image_pixel_module.rows();
   let bits_stored = image_pixel_module.bits_stored();
   let is_monochrome1 = image_pixel_module
     .photometric_interpretation()
@@ -111,7 +95,7 @@ pub fn decode_monochrome(
 
     _ => Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
       details: format!(
-        "JPEG Lossless monochrome decode not supported for photometric \
+        "JPEG monochrome decode not supported for photometric \
          interpretation '{}', decoded pixel format '{:?}'",
         image_pixel_module.photometric_interpretation(),
         pixel_format
@@ -120,16 +104,18 @@ pub fn decode_monochrome(
   }
 }
 
-/// Decodes color pixel data using jpeg-decoder.
+/// Decodes color pixel data using jpeg-decoder. If a minimum decoded size is
+/// specified then the image is decoded at the lowest resolution that is at
+/// least that size.
 ///
 pub fn decode_color(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  min_decoded_size: Option<(u16, u16)>,
 ) -> Result<ColorImage, PixelDataDecodeError> {
-  let (pixels, pixel_format) = decode(image_pixel_module, data)?;
+  let (pixels, pixel_format, width, height) =
+    decode(image_pixel_module, data, min_decoded_size)?;
 
-  let width = image_pixel_module.columns();
-  let height = image_pixel_module.rows();
   let bits_stored = image_pixel_module.bits_stored();
 
   match (
@@ -164,7 +150,9 @@ pub fn decode_color(
     }
 
     (
-      PhotometricInterpretation::Rgb | PhotometricInterpretation::YbrFull,
+      PhotometricInterpretation::Rgb
+      | PhotometricInterpretation::YbrFull
+      | PhotometricInterpretation::YbrFull422,
       jpeg_decoder::PixelFormat::RGB24,
     ) => {
       ColorImage::new_u8(width, height, pixels, ColorSpace::Rgb, bits_stored)
@@ -173,7 +161,7 @@ pub fn decode_color(
 
     _ => Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
       details: format!(
-        "JPEG Lossless color decode not supported for photometric \
+        "JPEG color decode not supported for photometric \
          interpretation '{}', decoded pixel format '{:?}'",
         image_pixel_module.photometric_interpretation(),
         pixel_format
@@ -182,10 +170,13 @@ pub fn decode_color(
   }
 }
 
+#[allow(clippy::type_complexity)]
 fn decode(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
-) -> Result<(Vec<u8>, jpeg_decoder::PixelFormat), PixelDataDecodeError> {
+  min_decoded_size: Option<(u16, u16)>,
+) -> Result<(Vec<u8>, jpeg_decoder::PixelFormat, u16, u16), PixelDataDecodeError>
+{
   let mut decoder = jpeg_decoder::Decoder::new(data);
 
   if image_pixel_module.is_color() {
@@ -195,7 +186,7 @@ fn decode(
   decoder
     .read_info()
     .map_err(|e| PixelDataDecodeError::DataInvalid {
-      details: format!("JPEG Lossless pixel data decode failed with '{e}'"),
+      details: format!("JPEG pixel data decode failed with '{e}'"),
     })?;
 
   let image_info = decoder.info().unwrap();
@@ -204,16 +195,36 @@ fn decode(
     || image_info.height != image_pixel_module.rows()
   {
     return Err(PixelDataDecodeError::DataInvalid {
-      details: "JPEG Lossless pixel data has incorrect dimensions".to_string(),
+      details: "JPEG pixel data has incorrect dimensions".to_string(),
     });
   }
 
+  let mut width = image_info.width;
+  let mut height = image_info.height;
+
+  let reduction = super::resolution_reduction(
+    image_pixel_module,
+    min_decoded_size,
+    MAX_RESOLUTION_REDUCTION,
+  );
+
+  if reduction > 0 {
+    (width, height) = decoder
+      .scale(
+        width.div_ceil(1 << reduction),
+        height.div_ceil(1 << reduction),
+      )
+      .map_err(|e| PixelDataDecodeError::DataInvalid {
+        details: format!("JPEG pixel data decode failed with '{e}'"),
+      })?;
+  }
+
   let pixels =
     decoder
       .decode()
       .map_err(|e| PixelDataDecodeError::DataInvalid {
-        details: format!("JPEG Lossless pixel data decode failed with '{e}'"),
+        details: format!("JPEG pixel data decode failed with '{e}'"),
       })?;
 
-  Ok((pixels, image_info.pixel_format))
+  Ok((pixels, image_info.pixel_format, width, height))
 }
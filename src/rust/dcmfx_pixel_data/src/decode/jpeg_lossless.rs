@@ -0,0 +1,997 @@
+//! A JPEG Lossless decoder written in pure Rust that implements the lossless
+//! process of ITU-T T.81 Annex H.
+//!
+//! Interleaved and non-interleaved scans, restart intervals, all seven
+//! predictors, and point transforms are supported. Hierarchical and arithmetic
+//! coded streams, and subsampled components, are not supported.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec, vec::Vec};
+
+use crate::{
+  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeError,
+  iods::image_pixel_module::{
+    BitsAllocated, ImagePixelModule, PhotometricInterpretation,
+    PixelRepresentation,
+  },
+};
+
+/// Returns the photometric interpretation used by data decoded from JPEG
+/// Lossless.
+///
+pub fn decode_photometric_interpretation(
+  photometric_interpretation: &PhotometricInterpretation,
+) -> Result<&PhotometricInterpretation, PixelDataDecodeError> {
+  match photometric_interpretation {
+    PhotometricInterpretation::Monochrome1 { .. }
+    | PhotometricInterpretation::Monochrome2 { .. }
+    | PhotometricInterpretation::Rgb
+    | PhotometricInterpretation::YbrFull
+    | PhotometricInterpretation::PaletteColor { .. } => {
+      Ok(photometric_interpretation)
+    }
+
+    _ => Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+      details: format!(
+        "Photometric interpretation '{photometric_interpretation}' is not \
+         supported"
+      ),
+    }),
+  }
+}
+
+/// Decodes monochrome JPEG Lossless pixel data.
+///
+pub fn decode_monochrome(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+) -> Result<MonochromeImage, PixelDataDecodeError> {
+  let width = image_pixel_module.columns();
+  let height = image_pixel_module.rows();
+  let bits_stored = image_pixel_module.bits_stored();
+  let is_monochrome1 = image_pixel_module
+    .photometric_interpretation()
+    .is_monochrome1();
+
+  match (
+    image_pixel_module.photometric_interpretation(),
+    image_pixel_module.bits_allocated(),
+  ) {
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      BitsAllocated::Eight,
+    ) => {
+      let pixels = decode_u8(data, image_pixel_module)?;
+      MonochromeImage::new_u8(
+        width,
+        height,
+        pixels,
+        bits_stored,
+        is_monochrome1,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Signed,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Signed,
+      },
+      BitsAllocated::Eight,
+    ) => {
+      let shift = 8 - bits_stored.min(8);
+      let pixels = decode_u8(data, image_pixel_module)?
+        .into_iter()
+        .map(|pixel| ((pixel << shift) as i8) >> shift)
+        .collect();
+
+      MonochromeImage::new_i8(
+        width,
+        height,
+        pixels,
+        bits_stored,
+        is_monochrome1,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      BitsAllocated::Sixteen,
+    ) => {
+      let pixels = decode(data, image_pixel_module)?;
+      MonochromeImage::new_u16(
+        width,
+        height,
+        pixels,
+        bits_stored,
+        is_monochrome1,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Signed,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Signed,
+      },
+      BitsAllocated::Sixteen,
+    ) => {
+      let shift = 16 - bits_stored.min(16);
+      let pixels = decode(data, image_pixel_module)?
+        .into_iter()
+        .map(|pixel| ((pixel << shift) as i16) >> shift)
+        .collect();
+
+      MonochromeImage::new_i16(
+        width,
+        height,
+        pixels,
+        bits_stored,
+        is_monochrome1,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (photometric_interpretation, bits_allocated) => {
+      Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+        details: format!(
+          "JPEG Lossless monochrome decode not supported for photometric \
+           interpretation '{}', bits allocated '{}'",
+          photometric_interpretation,
+          u8::from(bits_allocated)
+        ),
+      })
+    }
+  }
+}
+
+/// Decodes color JPEG Lossless pixel data.
+///
+pub fn decode_color(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+) -> Result<ColorImage, PixelDataDecodeError> {
+  let width = image_pixel_module.columns();
+  let height = image_pixel_module.rows();
+  let bits_stored = image_pixel_module.bits_stored();
+
+  let color_space = if image_pixel_module.photometric_interpretation().is_rgb()
+  {
+    ColorSpace::Rgb
+  } else {
+    ColorSpace::Ybr { is_422: false }
+  };
+
+  match (
+    image_pixel_module.photometric_interpretation(),
+    image_pixel_module.bits_allocated(),
+  ) {
+    (
+      PhotometricInterpretation::Rgb | PhotometricInterpretation::YbrFull,
+      BitsAllocated::Eight,
+    ) => {
+      let pixels = decode_u8(data, image_pixel_module)?;
+      ColorImage::new_u8(width, height, pixels, color_space, bits_stored)
+        .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::PaletteColor { palette },
+      BitsAllocated::Eight,
+    ) => {
+      let pixels = decode_u8(data, image_pixel_module)?;
+      ColorImage::new_palette8(
+        width,
+        height,
+        pixels,
+        palette.clone(),
+        bits_stored,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::Rgb | PhotometricInterpretation::YbrFull,
+      BitsAllocated::Sixteen,
+    ) => {
+      let pixels = decode(data, image_pixel_module)?;
+      ColorImage::new_u16(width, height, pixels, color_space, bits_stored)
+        .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::PaletteColor { palette },
+      BitsAllocated::Sixteen,
+    ) => {
+      let pixels = decode(data, image_pixel_module)?;
+      ColorImage::new_palette16(
+        width,
+        height,
+        pixels,
+        palette.clone(),
+        bits_stored,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (photometric_interpretation, bits_allocated) => {
+      Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+        details: format!(
+          "JPEG Lossless color decode not supported for photometric \
+           interpretation '{}', bits allocated '{}'",
+          photometric_interpretation,
+          u8::from(bits_allocated)
+        ),
+      })
+    }
+  }
+}
+
+fn decode_u8(
+  data: &[u8],
+  image_pixel_module: &ImagePixelModule,
+) -> Result<Vec<u8>, PixelDataDecodeError> {
+  let samples = decode(data, image_pixel_module)?;
+
+  Ok(samples.into_iter().map(|sample| sample as u8).collect())
+}
+
+/// Decodes a JPEG Lossless codestream, returning its samples with the
+/// components of each pixel interleaved.
+///
+fn decode(
+  data: &[u8],
+  image_pixel_module: &ImagePixelModule,
+) -> Result<Vec<u16>, PixelDataDecodeError> {
+  let mut position = 0;
+
+  if read_marker(data, &mut position)? != MARKER_SOI {
+    return Err(data_invalid("Start of image marker not found"));
+  }
+
+  let mut frame: Option<FrameHeader> = None;
+  let mut huffman_tables: [Option<HuffmanTable>; 4] = Default::default();
+  let mut restart_interval = 0;
+  let mut samples = vec![];
+  let mut decoded_components = vec![];
+
+  loop {
+    let marker = read_marker(data, &mut position)?;
+
+    match marker {
+      MARKER_SOF3 => {
+        if frame.is_some() {
+          return Err(data_invalid("Multiple frame headers"));
+        }
+
+        let header = FrameHeader::read(read_segment(data, &mut position)?)?;
+        header.validate(image_pixel_module)?;
+
+        samples =
+          vec![0u16; header.width * header.height * header.component_ids.len()];
+        decoded_components = vec![false; header.component_ids.len()];
+
+        frame = Some(header);
+      }
+
+      MARKER_DHT => {
+        let mut segment = read_segment(data, &mut position)?;
+
+        while !segment.is_empty() {
+          let (index, table) = HuffmanTable::read(&mut segment)?;
+          huffman_tables[index] = Some(table);
+        }
+      }
+
+      0xC0..=0xCF => {
+        return Err(data_invalid(&format!(
+          "Frame type 0xFF{marker:02X} is not supported, only \
+           non-hierarchical lossless Huffman coding is supported"
+        )));
+      }
+
+      MARKER_DRI => match *read_segment(data, &mut position)? {
+        [a, b] => restart_interval = usize::from(u16::from_be_bytes([a, b])),
+        _ => return Err(data_invalid("Restart interval segment is invalid")),
+      },
+
+      MARKER_SOS => {
+        let Some(frame) = &frame else {
+          return Err(data_invalid("Scan found before frame header"));
+        };
+
+        let scan = ScanHeader::read(
+          read_segment(data, &mut position)?,
+          frame,
+          &huffman_tables,
+        )?;
+
+        for index in scan.component_indices.iter() {
+          if decoded_components[*index] {
+            return Err(data_invalid("Component is in multiple scans"));
+          }
+
+          decoded_components[*index] = true;
+        }
+
+        let mut decoder =
+          ScanDecoder::new(data, position, frame, &scan, restart_interval);
+        decoder.decode(&mut samples)?;
+        position = decoder.reader.skip_to_marker();
+      }
+
+      MARKER_EOI => break,
+
+      0xE0..=0xEF | MARKER_COM => {
+        read_segment(data, &mut position)?;
+      }
+
+      _ => {
+        return Err(data_invalid(&format!(
+          "Unexpected marker 0xFF{marker:02X}"
+        )));
+      }
+    }
+  }
+
+  if frame.is_none() || decoded_components.contains(&false) {
+    return Err(data_invalid("Not all components were decoded"));
+  }
+
+  Ok(samples)
+}
+
+const MARKER_SOF3: u8 = 0xC3;
+const MARKER_DHT: u8 = 0xC4;
+const MARKER_RST0: u8 = 0xD0;
+const MARKER_SOI: u8 = 0xD8;
+const MARKER_EOI: u8 = 0xD9;
+const MARKER_SOS: u8 = 0xDA;
+const MARKER_DRI: u8 = 0xDD;
+const MARKER_COM: u8 = 0xFE;
+
+fn data_invalid(details: &str) -> PixelDataDecodeError {
+  PixelDataDecodeError::DataInvalid {
+    details: format!("JPEG Lossless pixel data decode failed with '{details}'"),
+  }
+}
+
+/// Reads the next marker, skipping any fill bytes that precede it.
+///
+fn read_marker(
+  data: &[u8],
+  position: &mut usize,
+) -> Result<u8, PixelDataDecodeError> {
+  if data.get(*position) != Some(&0xFF) {
+    return Err(data_invalid("Expected marker not found"));
+  }
+
+  while data.get(*position) == Some(&0xFF) {
+    *position += 1;
+  }
+
+  let marker = *data
+    .get(*position)
+    .ok_or_else(|| data_invalid("Data ended unexpectedly"))?;
+  *position += 1;
+
+  Ok(marker)
+}
+
+/// Reads a marker segment, returning its content following the length.
+///
+fn read_segment<'a>(
+  data: &'a [u8],
+  position: &mut usize,
+) -> Result<&'a [u8], PixelDataDecodeError> {
+  let length = match data.get(*position..*position + 2) {
+    Some(&[a, b]) => usize::from(u16::from_be_bytes([a, b])),
+    _ => return Err(data_invalid("Data ended unexpectedly")),
+  };
+
+  if length < 2 {
+    return Err(data_invalid("Marker segment length is invalid"));
+  }
+
+  let segment = data
+    .get(*position + 2..*position + length)
+    .ok_or_else(|| data_invalid("Data ended unexpectedly"))?;
+  *position += length;
+
+  Ok(segment)
+}
+
+/// The content of a lossless frame header. Ref: ITU-T T.81 B.2.2.
+///
+struct FrameHeader {
+  precision: u32,
+  sampling_factors: u8,
+  width: usize,
+  height: usize,
+  component_ids: Vec<u8>,
+}
+
+impl FrameHeader {
+  fn read(segment: &[u8]) -> Result<Self, PixelDataDecodeError> {
+    let [precision, h0, h1, w0, w1, component_count, components @ ..] = segment
+    else {
+      return Err(data_invalid("Frame header is invalid"));
+    };
+
+    let component_count = usize::from(*component_count);
+    if component_count == 0 || components.len() != component_count * 3 {
+      return Err(data_invalid("Frame header is invalid"));
+    }
+
+    // Components must not be subsampled, i.e. they must all have the same
+    // sampling factors
+    let sampling_factors = components[1];
+    if components.chunks_exact(3).any(|c| c[1] != sampling_factors) {
+      return Err(data_invalid("Subsampled components are not supported"));
+    }
+
+    Ok(Self {
+      precision: u32::from(*precision),
+      sampling_factors,
+      width: usize::from(u16::from_be_bytes([*w0, *w1])),
+      height: usize::from(u16::from_be_bytes([*h0, *h1])),
+      component_ids: components.chunks_exact(3).map(|c| c[0]).collect(),
+    })
+  }
+
+  /// Checks that the frame matches the Image Pixel Module that describes it.
+  ///
+  fn validate(
+    &self,
+    image_pixel_module: &ImagePixelModule,
+  ) -> Result<(), PixelDataDecodeError> {
+    if !(2..=16).contains(&self.precision) {
+      return Err(data_invalid("Sample precision is invalid"));
+    }
+
+    if self.width != usize::from(image_pixel_module.columns())
+      || self.height != usize::from(image_pixel_module.rows())
+      || self.component_ids.len()
+        != usize::from(u8::from(image_pixel_module.samples_per_pixel()))
+      || self.precision
+        > u32::from(u8::from(image_pixel_module.bits_allocated()))
+    {
+      return Err(data_invalid(
+        "Image does not have the expected width, height, samples per pixel, \
+         or bits allocated",
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+/// The content of a lossless scan header. Ref: ITU-T T.81 B.2.3.
+///
+struct ScanHeader {
+  component_indices: Vec<usize>,
+  huffman_tables: Vec<HuffmanTable>,
+  predictor: u8,
+  point_transform: u32,
+}
+
+impl ScanHeader {
+  fn read(
+    segment: &[u8],
+    frame: &FrameHeader,
+    huffman_tables: &[Option<HuffmanTable>; 4],
+  ) -> Result<Self, PixelDataDecodeError> {
+    let [component_count, rest @ ..] = segment else {
+      return Err(data_invalid("Scan header is invalid"));
+    };
+
+    let component_count = usize::from(*component_count);
+    if component_count == 0 || rest.len() != component_count * 2 + 3 {
+      return Err(data_invalid("Scan header is invalid"));
+    }
+
+    // Each MCU of an interleaved scan is expected to hold a single sample of
+    // each component
+    if component_count > 1 && frame.sampling_factors != 0x11 {
+      return Err(data_invalid(
+        "Interleaved scans with sampling factors other than one are not \
+         supported",
+      ));
+    }
+
+    let mut component_indices = vec![];
+    let mut scan_huffman_tables = vec![];
+
+    for component in rest[0..component_count * 2].chunks_exact(2) {
+      let index = frame
+        .component_ids
+        .iter()
+        .position(|id| *id == component[0])
+        .ok_or_else(|| data_invalid("Scan component is not in the frame"))?;
+
+      let table = huffman_tables
+        .get(usize::from(component[1] >> 4))
+        .cloned()
+        .flatten()
+        .ok_or_else(|| data_invalid("Huffman table is not defined"))?;
+
+      component_indices.push(index);
+      scan_huffman_tables.push(table);
+    }
+
+    let predictor = rest[component_count * 2];
+    let point_transform = u32::from(rest[component_count * 2 + 2] & 0x0F);
+
+    if !(1..=7).contains(&predictor) {
+      return Err(data_invalid("Predictor is invalid"));
+    }
+
+    if point_transform >= frame.precision {
+      return Err(data_invalid("Point transform is invalid"));
+    }
+
+    Ok(Self {
+      component_indices,
+      huffman_tables: scan_huffman_tables,
+      predictor,
+      point_transform,
+    })
+  }
+}
+
+/// A Huffman table used to decode the magnitude categories of differences.
+/// Ref: ITU-T T.81 C and F.2.2.3.
+///
+#[derive(Clone)]
+struct HuffmanTable {
+  max_code: [i32; 17],
+  value_offset: [i32; 17],
+  values: Vec<u8>,
+}
+
+impl HuffmanTable {
+  /// Reads the next table from a DHT segment, returning its destination
+  /// index. Ref: ITU-T T.81 B.2.4.2.
+  ///
+  fn read(segment: &mut &[u8]) -> Result<(usize, Self), PixelDataDecodeError> {
+    let Some((&class_and_index, counts)) = segment.split_first() else {
+      return Err(data_invalid("Huffman table is invalid"));
+    };
+
+    let index = usize::from(class_and_index & 0x0F);
+    if class_and_index >> 4 != 0 || index > 3 {
+      return Err(data_invalid("Huffman table is invalid"));
+    }
+
+    let counts = counts
+      .get(0..16)
+      .ok_or_else(|| data_invalid("Huffman table is invalid"))?;
+
+    let value_count = counts.iter().map(|c| usize::from(*c)).sum::<usize>();
+    let values = segment
+      .get(17..17 + value_count)
+      .ok_or_else(|| data_invalid("Huffman table is invalid"))?;
+
+    // Assign canonical codes to each code length
+    let mut max_code = [-1; 17];
+    let mut value_offset = [0; 17];
+    let mut code = 0i32;
+    let mut k = 0i32;
+
+    for length in 1..=16 {
+      let count = i32::from(counts[length - 1]);
+
+      if count > 0 {
+        value_offset[length] = k - code;
+        code += count;
+        k += count;
+        max_code[length] = code - 1;
+
+        if code > 1 << length {
+          return Err(data_invalid("Huffman table is invalid"));
+        }
+      }
+
+      code <<= 1;
+    }
+
+    let table = Self {
+      max_code,
+      value_offset,
+      values: values.to_vec(),
+    };
+
+    *segment = &segment[17 + value_count..];
+
+    Ok((index, table))
+  }
+}
+
+/// Decodes the samples for the components in a single lossless scan.
+///
+struct ScanDecoder<'a> {
+  reader: BitReader<'a>,
+  width: usize,
+  height: usize,
+  component_count: usize,
+  precision: u32,
+  scan: &'a ScanHeader,
+  restart_interval: usize,
+}
+
+impl<'a> ScanDecoder<'a> {
+  fn new(
+    data: &'a [u8],
+    position: usize,
+    frame: &FrameHeader,
+    scan: &'a ScanHeader,
+    restart_interval: usize,
+  ) -> Self {
+    Self {
+      reader: BitReader::new(data, position),
+      width: frame.width,
+      height: frame.height,
+      component_count: frame.component_ids.len(),
+      precision: frame.precision,
+      scan,
+      restart_interval,
+    }
+  }
+
+  /// Decodes the scan's samples into their positions in the interleaved
+  /// output. Each MCU holds one sample of each of the scan's components.
+  ///
+  fn decode(
+    &mut self,
+    samples: &mut [u16],
+  ) -> Result<(), PixelDataDecodeError> {
+    let width = self.width;
+    let stride = self.component_count;
+    let point_transform = self.scan.point_transform;
+    let initial_prediction = 1i32 << (self.precision - point_transform - 1);
+
+    let mut restart_index = 0;
+    let mut is_first_mcu = true;
+    let mut reset_row = 0;
+
+    for y in 0..self.height {
+      for x in 0..width {
+        let mcu_index = y * width + x;
+
+        if self.restart_interval > 0
+          && mcu_index > 0
+          && mcu_index.is_multiple_of(self.restart_interval)
+        {
+          self.reader.read_restart_marker(restart_index)?;
+          restart_index = (restart_index + 1) % 8;
+
+          // Prediction restarts the same way as at the start of the scan
+          is_first_mcu = true;
+          reset_row = y;
+        }
+
+        for (component, table) in self
+          .scan
+          .component_indices
+          .iter()
+          .zip(self.scan.huffman_tables.iter())
+        {
+          let i = mcu_index * stride + component;
+
+          // Ref: ITU-T T.81 H.1.2.1
+          let prediction = if is_first_mcu {
+            initial_prediction
+          } else if y == reset_row {
+            i32::from(samples[i - stride])
+          } else if x == 0 {
+            i32::from(samples[i - width * stride])
+          } else {
+            let ra = i32::from(samples[i - stride]);
+            let rb = i32::from(samples[i - width * stride]);
+            let rc = i32::from(samples[i - width * stride - stride]);
+
+            match self.scan.predictor {
+              1 => ra,
+              2 => rb,
+              3 => rc,
+              4 => ra + rb - rc,
+              5 => ra + ((rb - rc) >> 1),
+              6 => rb + ((ra - rc) >> 1),
+              _ => (ra + rb) >> 1,
+            }
+          };
+
+          let difference = self.reader.read_difference(table)?;
+
+          samples[i] = (prediction + difference) as u16;
+        }
+
+        is_first_mcu = false;
+      }
+    }
+
+    // Undo the point transform now that no further predictions need the
+    // transformed values
+    if point_transform > 0 {
+      for component in self.scan.component_indices.iter() {
+        for sample in samples.iter_mut().skip(*component).step_by(stride) {
+          *sample <<= point_transform;
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Reads entropy-coded data, removing the zero bytes stuffed after 0xFF bytes.
+/// Ref: ITU-T T.81 F.1.2.3.
+///
+struct BitReader<'a> {
+  data: &'a [u8],
+  position: usize,
+  bits: u64,
+  bit_count: u32,
+}
+
+impl<'a> BitReader<'a> {
+  fn new(data: &'a [u8], position: usize) -> Self {
+    Self {
+      data,
+      position,
+      bits: 0,
+      bit_count: 0,
+    }
+  }
+
+  fn fill(&mut self) {
+    while self.bit_count <= 56 {
+      let Some(&byte) = self.data.get(self.position) else {
+        return;
+      };
+
+      if byte == 0xFF {
+        // A 0xFF byte not followed by a stuffed zero byte is a marker
+        if self.data.get(self.position + 1) != Some(&0x00) {
+          return;
+        }
+
+        self.position += 1;
+      }
+
+      self.bits |= u64::from(byte) << (56 - self.bit_count);
+      self.bit_count += 8;
+      self.position += 1;
+    }
+  }
+
+  fn read_bits(&mut self, count: u32) -> Result<u32, PixelDataDecodeError> {
+    if count == 0 {
+      return Ok(0);
+    }
+
+    if self.bit_count < count {
+      self.fill();
+
+      if self.bit_count < count {
+        return Err(data_invalid("Scan data ended unexpectedly"));
+      }
+    }
+
+    let value = (self.bits >> (64 - count)) as u32;
+    self.bits <<= count;
+    self.bit_count -= count;
+
+    Ok(value)
+  }
+
+  /// Reads a Huffman coded magnitude category followed by the additional bits
+  /// that give the difference's value. Ref: ITU-T T.81 F.2.2.1 and H.1.2.2.
+  ///
+  fn read_difference(
+    &mut self,
+    table: &HuffmanTable,
+  ) -> Result<i32, PixelDataDecodeError> {
+    let mut code = 0;
+    let mut length = 0;
+
+    let category = loop {
+      code = (code << 1) | self.read_bits(1)? as i32;
+      length += 1;
+
+      if code <= table.max_code[length] {
+        let index = table.value_offset[length] + code;
+        break table.values[index as usize];
+      }
+
+      if length == 16 {
+        return Err(data_invalid("Huffman code is invalid"));
+      }
+    };
+
+    match category {
+      0 => Ok(0),
+
+      1..=15 => {
+        let category = u32::from(category);
+        let value = self.read_bits(category)? as i32;
+
+        if value < 1 << (category - 1) {
+          Ok(value - (1 << category) + 1)
+        } else {
+          Ok(value)
+        }
+      }
+
+      16 => Ok(32768),
+
+      _ => Err(data_invalid("Difference magnitude category is invalid")),
+    }
+  }
+
+  /// Discards the remaining bits of the current restart interval, then reads
+  /// the restart marker that is expected to follow.
+  ///
+  fn read_restart_marker(
+    &mut self,
+    restart_index: u8,
+  ) -> Result<(), PixelDataDecodeError> {
+    self.bits = 0;
+    self.bit_count = 0;
+
+    let marker = read_marker(self.data, &mut self.position)?;
+    if marker != MARKER_RST0 + restart_index {
+      return Err(data_invalid("Restart marker is invalid"));
+    }
+
+    Ok(())
+  }
+
+  /// Discards the remaining bits of the scan and returns the position of the
+  /// next marker.
+  ///
+  fn skip_to_marker(&mut self) -> usize {
+    self.bits = 0;
+    self.bit_count = 0;
+
+    while let Some(&byte) = self.data.get(self.position) {
+      if byte == 0xFF && self.data.get(self.position + 1) != Some(&0x00) {
+        break;
+      }
+
+      self.position += 1;
+    }
+
+    self.position
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::iods::image_pixel_module::SamplesPerPixel;
+
+  #[test]
+  fn decode_invalid_data() {
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      2,
+      2,
+      BitsAllocated::Eight,
+      8,
+    )
+    .unwrap();
+
+    assert!(decode_monochrome(&image_pixel_module, &[]).is_err());
+    assert!(decode_monochrome(&image_pixel_module, &[0xFF, 0xD8]).is_err());
+    assert!(
+      decode_monochrome(&image_pixel_module, &[0xFF, 0xD8, 0xFF, 0xD9])
+        .is_err()
+    );
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn decode_non_interleaved_scans_with_restart_intervals() {
+    // Three non-interleaved scans that each use a different predictor and
+    // have a restart interval of one row. The blue component's scan also uses
+    // a point transform, and the coded data contains stuffed zero bytes.
+    let data = std::fs::read(
+      "../../../test/assets/other/\
+       jpeg_lossless_non_interleaved_with_restarts.jpg",
+    )
+    .unwrap();
+
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::Three {
+        planar_configuration:
+          crate::iods::image_pixel_module::PlanarConfiguration::Interleaved,
+      },
+      PhotometricInterpretation::Rgb,
+      5,
+      7,
+      BitsAllocated::Eight,
+      8,
+    )
+    .unwrap();
+
+    let mut pixels = vec![];
+    for y in 0..5u32 {
+      for x in 0..7u32 {
+        pixels.push(((x * 37 + y * 11) % 256) as u8);
+        pixels.push(((x * x * 5 + y * 23) % 256) as u8);
+        pixels.push(((x + y) * 18 % 256) as u8 & 0xFE);
+      }
+    }
+
+    assert_eq!(
+      decode_color(&image_pixel_module, &data),
+      Ok(ColorImage::new_u8(7, 5, pixels, ColorSpace::Rgb, 8).unwrap())
+    );
+
+    // Decoding fails if a restart marker is missing
+    let position = data
+      .windows(2)
+      .position(|window| window == [0xFF, MARKER_RST0 + 1])
+      .unwrap();
+    let mut data = data;
+    data.drain(position..position + 2);
+
+    assert!(decode_color(&image_pixel_module, &data).is_err());
+  }
+
+  #[test]
+  fn decode_signed_monochrome() {
+    // A 2x1 image with 12 bits stored, coded with the first predictor and a
+    // Huffman table whose only code is for magnitude category 11
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Signed,
+      },
+      1,
+      2,
+      BitsAllocated::Sixteen,
+      12,
+    )
+    .unwrap();
+
+    // The first difference is 0xFFF - 0x800 = 0x7FF, the second 0x800 - 0xFFF
+    // = -0x7FF, each coded as a 1 bit Huffman code and 11 additional bits
+    #[rustfmt::skip]
+    let data = [
+      0xFF, 0xD8,
+      0xFF, 0xC3, 0x00, 0x0B, 0x0C, 0x00, 0x01, 0x00, 0x02, 0x01, 0x01, 0x11,
+      0x00,
+      0xFF, 0xC4, 0x00, 0x14, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B,
+      0xFF, 0xDA, 0x00, 0x08, 0x01, 0x01, 0x00, 0x01, 0x00, 0x00,
+      0b0111_1111, 0b1111_0000, 0b0000_0000,
+      0xFF, 0xD9,
+    ];
+
+    assert_eq!(
+      decode_monochrome(&image_pixel_module, &data),
+      Ok(MonochromeImage::new_i16(2, 1, vec![-1, -2048], 12, false).unwrap())
+    );
+  }
+}
@@ -0,0 +1,1514 @@
+//! A JPEG-LS decoder written in pure Rust that implements ITU-T T.87. This is
+//! used on platforms where CharLS isn't available, such as WASM.
+//!
+//! Lossless and near-lossless coding, all interleave modes, restart intervals,
+//! and preset coding parameters are supported. Mapping tables, point
+//! transforms, and the color transforms defined by CharLS are not supported.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec, vec::Vec};
+
+use crate::{
+  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeError,
+  iods::image_pixel_module::{
+    BitsAllocated, ImagePixelModule, PhotometricInterpretation,
+    PixelRepresentation,
+  },
+};
+
+/// Returns the photometric interpretation used by data decoded from JPEG-LS.
+///
+pub fn decode_photometric_interpretation(
+  photometric_interpretation: &PhotometricInterpretation,
+) -> Result<&PhotometricInterpretation, PixelDataDecodeError> {
+  match photometric_interpretation {
+    PhotometricInterpretation::Monochrome1 { .. }
+    | PhotometricInterpretation::Monochrome2 { .. }
+    | PhotometricInterpretation::Rgb
+    | PhotometricInterpretation::YbrFull
+    | PhotometricInterpretation::PaletteColor { .. } => {
+      Ok(photometric_interpretation)
+    }
+
+    _ => Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+      details: format!(
+        "Photometric interpretation '{photometric_interpretation}' is not \
+         supported"
+      ),
+    }),
+  }
+}
+
+/// Decodes monochrome JPEG-LS pixel data.
+///
+pub fn decode_monochrome(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+) -> Result<MonochromeImage, PixelDataDecodeError> {
+  let width = image_pixel_module.columns();
+  let height = image_pixel_module.rows();
+  let bits_stored = image_pixel_module.bits_stored();
+  let is_monochrome1 = image_pixel_module
+    .photometric_interpretation()
+    .is_monochrome1();
+
+  match (
+    image_pixel_module.photometric_interpretation(),
+    image_pixel_module.bits_allocated(),
+  ) {
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      BitsAllocated::Eight,
+    ) => {
+      let pixels = decode_u8(data, image_pixel_module)?;
+      MonochromeImage::new_u8(
+        width,
+        height,
+        pixels,
+        bits_stored,
+        is_monochrome1,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::Monochrome1 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      }
+      | PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      BitsAllocated::Sixteen,
+    ) => {
+      let pixels = decode(data, image_pixel_module)?;
+      MonochromeImage::new_u16(
+        width,
+        height,
+        pixels,
+        bits_stored,
+        is_monochrome1,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (photometric_interpretation, bits_allocated) => {
+      Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+        details: format!(
+          "JPEG-LS monochrome decode not supported for photometric \
+           interpretation '{}', bits allocated '{}'",
+          photometric_interpretation,
+          u8::from(bits_allocated)
+        ),
+      })
+    }
+  }
+}
+
+/// Decodes color JPEG-LS pixel data.
+///
+pub fn decode_color(
+  image_pixel_module: &ImagePixelModule,
+  data: &[u8],
+) -> Result<ColorImage, PixelDataDecodeError> {
+  let width = image_pixel_module.columns();
+  let height = image_pixel_module.rows();
+  let bits_stored = image_pixel_module.bits_stored();
+
+  let color_space = if image_pixel_module.photometric_interpretation().is_rgb()
+  {
+    ColorSpace::Rgb
+  } else {
+    ColorSpace::Ybr { is_422: false }
+  };
+
+  match (
+    image_pixel_module.photometric_interpretation(),
+    image_pixel_module.bits_allocated(),
+  ) {
+    (
+      PhotometricInterpretation::Rgb | PhotometricInterpretation::YbrFull,
+      BitsAllocated::Eight,
+    ) => {
+      let pixels = decode_u8(data, image_pixel_module)?;
+      ColorImage::new_u8(width, height, pixels, color_space, bits_stored)
+        .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::PaletteColor { palette },
+      BitsAllocated::Eight,
+    ) => {
+      let pixels = decode_u8(data, image_pixel_module)?;
+      ColorImage::new_palette8(
+        width,
+        height,
+        pixels,
+        palette.clone(),
+        bits_stored,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::Rgb | PhotometricInterpretation::YbrFull,
+      BitsAllocated::Sixteen,
+    ) => {
+      let pixels = decode(data, image_pixel_module)?;
+      ColorImage::new_u16(width, height, pixels, color_space, bits_stored)
+        .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (
+      PhotometricInterpretation::PaletteColor { palette },
+      BitsAllocated::Sixteen,
+    ) => {
+      let pixels = decode(data, image_pixel_module)?;
+      ColorImage::new_palette16(
+        width,
+        height,
+        pixels,
+        palette.clone(),
+        bits_stored,
+      )
+      .map_err(PixelDataDecodeError::ImageCreationFailed)
+    }
+
+    (photometric_interpretation, bits_allocated) => {
+      Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
+        details: format!(
+          "JPEG-LS color decode not supported for photometric interpretation \
+           '{}', bits allocated '{}'",
+          photometric_interpretation,
+          u8::from(bits_allocated)
+        ),
+      })
+    }
+  }
+}
+
+fn decode_u8(
+  data: &[u8],
+  image_pixel_module: &ImagePixelModule,
+) -> Result<Vec<u8>, PixelDataDecodeError> {
+  let samples = decode(data, image_pixel_module)?;
+
+  Ok(samples.into_iter().map(|sample| sample as u8).collect())
+}
+
+/// Decodes a JPEG-LS codestream, returning its samples with the components of
+/// each pixel interleaved.
+///
+fn decode(
+  data: &[u8],
+  image_pixel_module: &ImagePixelModule,
+) -> Result<Vec<u16>, PixelDataDecodeError> {
+  let mut position = 0;
+
+  if read_marker(data, &mut position)? != MARKER_SOI {
+    return Err(data_invalid("Start of image marker not found"));
+  }
+
+  let mut frame: Option<FrameHeader> = None;
+  let mut preset_parameters = PresetParameters::default();
+  let mut restart_interval = 0;
+  let mut samples = vec![];
+  let mut decoded_component_count = 0;
+
+  loop {
+    let marker = read_marker(data, &mut position)?;
+
+    match marker {
+      MARKER_SOF55 => {
+        if frame.is_some() {
+          return Err(data_invalid("Multiple frame headers"));
+        }
+
+        let header = FrameHeader::read(read_segment(data, &mut position)?)?;
+        header.validate(image_pixel_module)?;
+
+        samples =
+          vec![0u16; header.width * header.height * header.component_ids.len()];
+
+        frame = Some(header);
+      }
+
+      MARKER_LSE => {
+        preset_parameters =
+          PresetParameters::read(read_segment(data, &mut position)?)?;
+      }
+
+      MARKER_DRI => {
+        let segment = read_segment(data, &mut position)?;
+        restart_interval = match *segment {
+          [a, b] => usize::from(u16::from_be_bytes([a, b])),
+          [a, b, c] => {
+            (usize::from(a) << 16) | usize::from(u16::from_be_bytes([b, c]))
+          }
+          [a, b, c, d] => u32::from_be_bytes([a, b, c, d]) as usize,
+          _ => return Err(data_invalid("Restart interval segment is invalid")),
+        };
+      }
+
+      MARKER_SOS => {
+        let Some(frame) = &frame else {
+          return Err(data_invalid("Scan found before frame header"));
+        };
+
+        let scan = ScanHeader::read(read_segment(data, &mut position)?, frame)?;
+        let parameters = CodingParameters::new(
+          frame.bits_per_sample,
+          scan.near,
+          &preset_parameters,
+        )?;
+
+        let mut decoder = ScanDecoder::new(
+          data,
+          position,
+          frame,
+          &scan,
+          parameters,
+          restart_interval,
+        );
+        decoder.decode(&mut samples)?;
+        position = decoder.reader.position;
+
+        decoded_component_count += scan.component_indices.len();
+      }
+
+      MARKER_EOI => break,
+
+      // CharLS stores its color transform in an APP8 segment. This isn't
+      // supported, so fail if a color transform is specified.
+      MARKER_APP8 => {
+        let segment = read_segment(data, &mut position)?;
+        if segment.len() >= 5 && &segment[0..4] == b"mrfx" && segment[4] != 0 {
+          return Err(data_invalid(
+            "JPEG-LS color transforms are not supported",
+          ));
+        }
+      }
+
+      0xE0..=0xEF | MARKER_COM => {
+        read_segment(data, &mut position)?;
+      }
+
+      _ => {
+        return Err(data_invalid(&format!(
+          "Unexpected marker 0xFF{marker:02X}"
+        )));
+      }
+    }
+  }
+
+  match frame {
+    Some(frame) if decoded_component_count == frame.component_ids.len() => {
+      Ok(samples)
+    }
+
+    _ => Err(data_invalid("Not all components were decoded")),
+  }
+}
+
+const MARKER_SOI: u8 = 0xD8;
+const MARKER_EOI: u8 = 0xD9;
+const MARKER_SOS: u8 = 0xDA;
+const MARKER_DRI: u8 = 0xDD;
+const MARKER_APP8: u8 = 0xE8;
+const MARKER_SOF55: u8 = 0xF7;
+const MARKER_LSE: u8 = 0xF8;
+const MARKER_COM: u8 = 0xFE;
+
+/// The order of the Golomb codes used for run lengths in run mode, indexed by
+/// `RUNindex`. Ref: ITU-T T.87 A.7.1.
+///
+const J: [u32; 32] = [
+  0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 9,
+  10, 11, 12, 13, 14, 15,
+];
+
+fn data_invalid(details: &str) -> PixelDataDecodeError {
+  PixelDataDecodeError::DataInvalid {
+    details: format!("JPEG-LS pixel data decode failed with '{details}'"),
+  }
+}
+
+/// Reads the next marker, skipping any fill bytes that precede it.
+///
+fn read_marker(
+  data: &[u8],
+  position: &mut usize,
+) -> Result<u8, PixelDataDecodeError> {
+  if data.get(*position) != Some(&0xFF) {
+    return Err(data_invalid("Expected marker not found"));
+  }
+
+  while data.get(*position) == Some(&0xFF) {
+    *position += 1;
+  }
+
+  let marker = *data
+    .get(*position)
+    .ok_or_else(|| data_invalid("Data ended unexpectedly"))?;
+  *position += 1;
+
+  Ok(marker)
+}
+
+/// Reads a marker segment, returning its content following the length.
+///
+fn read_segment<'a>(
+  data: &'a [u8],
+  position: &mut usize,
+) -> Result<&'a [u8], PixelDataDecodeError> {
+  let length = match data.get(*position..*position + 2) {
+    Some(&[a, b]) => usize::from(u16::from_be_bytes([a, b])),
+    _ => return Err(data_invalid("Data ended unexpectedly")),
+  };
+
+  if length < 2 {
+    return Err(data_invalid("Marker segment length is invalid"));
+  }
+
+  let segment = data
+    .get(*position + 2..*position + length)
+    .ok_or_else(|| data_invalid("Data ended unexpectedly"))?;
+  *position += length;
+
+  Ok(segment)
+}
+
+/// The content of a JPEG-LS frame header. Ref: ITU-T T.87 C.2.2.
+///
+struct FrameHeader {
+  bits_per_sample: u32,
+  width: usize,
+  height: usize,
+  component_ids: Vec<u8>,
+}
+
+impl FrameHeader {
+  fn read(segment: &[u8]) -> Result<Self, PixelDataDecodeError> {
+    let [
+      bits_per_sample,
+      h0,
+      h1,
+      w0,
+      w1,
+      component_count,
+      components @ ..,
+    ] = segment
+    else {
+      return Err(data_invalid("Frame header is invalid"));
+    };
+
+    let component_count = usize::from(*component_count);
+    if component_count == 0 || components.len() != component_count * 3 {
+      return Err(data_invalid("Frame header is invalid"));
+    }
+
+    Ok(Self {
+      bits_per_sample: u32::from(*bits_per_sample),
+      width: usize::from(u16::from_be_bytes([*w0, *w1])),
+      height: usize::from(u16::from_be_bytes([*h0, *h1])),
+      component_ids: components.chunks_exact(3).map(|c| c[0]).collect(),
+    })
+  }
+
+  /// Checks that the frame matches the Image Pixel Module that describes it.
+  ///
+  fn validate(
+    &self,
+    image_pixel_module: &ImagePixelModule,
+  ) -> Result<(), PixelDataDecodeError> {
+    if !(2..=16).contains(&self.bits_per_sample) {
+      return Err(data_invalid("Bits per sample is invalid"));
+    }
+
+    if self.width != usize::from(image_pixel_module.columns())
+      || self.height != usize::from(image_pixel_module.rows())
+      || self.component_ids.len()
+        != usize::from(u8::from(image_pixel_module.samples_per_pixel()))
+      || self.bits_per_sample.div_ceil(8) * 8
+        != u32::from(u8::from(image_pixel_module.bits_allocated()))
+    {
+      return Err(data_invalid(
+        "Image does not have the expected width, height, samples per pixel, \
+         or bits allocated",
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+/// The content of a JPEG-LS scan header. Ref: ITU-T T.87 C.2.3.
+///
+struct ScanHeader {
+  component_indices: Vec<usize>,
+  near: i32,
+  interleave_mode: InterleaveMode,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum InterleaveMode {
+  None,
+  Line,
+  Sample,
+}
+
+impl ScanHeader {
+  fn read(
+    segment: &[u8],
+    frame: &FrameHeader,
+  ) -> Result<Self, PixelDataDecodeError> {
+    let [component_count, rest @ ..] = segment else {
+      return Err(data_invalid("Scan header is invalid"));
+    };
+
+    let component_count = usize::from(*component_count);
+    if component_count == 0 || rest.len() != component_count * 2 + 3 {
+      return Err(data_invalid("Scan header is invalid"));
+    }
+
+    let mut component_indices = vec![];
+    for component in rest[0..component_count * 2].chunks_exact(2) {
+      let index = frame
+        .component_ids
+        .iter()
+        .position(|id| *id == component[0])
+        .ok_or_else(|| data_invalid("Scan component is not in the frame"))?;
+
+      if component[1] != 0 {
+        return Err(data_invalid("Mapping tables are not supported"));
+      }
+
+      component_indices.push(index);
+    }
+
+    let near = i32::from(rest[component_count * 2]);
+    let interleave_mode = rest[component_count * 2 + 1];
+    let point_transform = rest[component_count * 2 + 2] & 0x0F;
+
+    if point_transform != 0 {
+      return Err(data_invalid("Point transforms are not supported"));
+    }
+
+    let interleave_mode = match (interleave_mode, component_count) {
+      (_, 1) => InterleaveMode::None,
+      (1, _) => InterleaveMode::Line,
+      (2, _) => InterleaveMode::Sample,
+      _ => return Err(data_invalid("Interleave mode is invalid")),
+    };
+
+    Ok(Self {
+      component_indices,
+      near,
+      interleave_mode,
+    })
+  }
+}
+
+/// Preset coding parameters specified in a JPEG-LS preset parameters marker
+/// segment. Values of zero indicate that the default value is used. Ref: ITU-T
+/// T.87 C.2.4.1.1.
+///
+#[derive(Default)]
+struct PresetParameters {
+  maximum_sample_value: i32,
+  threshold1: i32,
+  threshold2: i32,
+  threshold3: i32,
+  reset: i32,
+}
+
+impl PresetParameters {
+  fn read(segment: &[u8]) -> Result<Self, PixelDataDecodeError> {
+    match segment {
+      [1, values @ ..] if values.len() == 10 => {
+        let value = |i: usize| {
+          i32::from(u16::from_be_bytes([values[i * 2], values[i * 2 + 1]]))
+        };
+
+        Ok(Self {
+          maximum_sample_value: value(0),
+          threshold1: value(1),
+          threshold2: value(2),
+          threshold3: value(3),
+          reset: value(4),
+        })
+      }
+
+      _ => Err(data_invalid(
+        "Only preset coding parameters are supported in JPEG-LS preset \
+         parameters segments",
+      )),
+    }
+  }
+}
+
+/// The coding parameters used when decoding a scan. Ref: ITU-T T.87 A.2.1.
+///
+struct CodingParameters {
+  maximum_sample_value: i32,
+  near: i32,
+  range: i32,
+  qbpp: u32,
+  limit: u32,
+  threshold1: i32,
+  threshold2: i32,
+  threshold3: i32,
+  reset: i32,
+}
+
+impl CodingParameters {
+  fn new(
+    bits_per_sample: u32,
+    near: i32,
+    preset_parameters: &PresetParameters,
+  ) -> Result<Self, PixelDataDecodeError> {
+    let default_maximum_sample_value = (1 << bits_per_sample) - 1;
+
+    let maximum_sample_value = match preset_parameters.maximum_sample_value {
+      0 => default_maximum_sample_value,
+      value if value <= default_maximum_sample_value => value,
+      _ => return Err(data_invalid("Maximum sample value is invalid")),
+    };
+
+    if near > (maximum_sample_value / 2).min(255) {
+      return Err(data_invalid("NEAR parameter is invalid"));
+    }
+
+    let range = (maximum_sample_value + 2 * near) / (2 * near + 1) + 1;
+    let qbpp = ceil_log2(range);
+    let bpp = ceil_log2(maximum_sample_value + 1).max(2);
+    let limit = 2 * (bpp + bpp.max(8));
+
+    // Calculate the default thresholds. Ref: ITU-T T.87 C.2.4.1.1.1.
+    let clamp = |value: i32, min: i32| {
+      if value > maximum_sample_value || value < min {
+        min
+      } else {
+        value
+      }
+    };
+
+    let (threshold1, threshold2, threshold3) = if maximum_sample_value >= 128 {
+      let factor = (maximum_sample_value.min(4095) + 128) / 256;
+      let t1 = clamp(factor + 2 + 3 * near, near + 1);
+      let t2 = clamp(factor * 4 + 3 + 5 * near, t1);
+      let t3 = clamp(factor * 17 + 4 + 7 * near, t2);
+      (t1, t2, t3)
+    } else {
+      let factor = 256 / (maximum_sample_value + 1);
+      let t1 = clamp((3 / factor + 3 * near).max(2), near + 1);
+      let t2 = clamp((7 / factor + 5 * near).max(3), t1);
+      let t3 = clamp((21 / factor + 7 * near).max(4), t2);
+      (t1, t2, t3)
+    };
+
+    let or_default = |value: i32, default: i32| {
+      if value == 0 { default } else { value }
+    };
+
+    let reset = or_default(preset_parameters.reset, 64);
+    if reset < 3 {
+      return Err(data_invalid("Reset value is invalid"));
+    }
+
+    Ok(Self {
+      maximum_sample_value,
+      near,
+      range,
+      qbpp,
+      limit,
+      threshold1: or_default(preset_parameters.threshold1, threshold1),
+      threshold2: or_default(preset_parameters.threshold2, threshold2),
+      threshold3: or_default(preset_parameters.threshold3, threshold3),
+      reset,
+    })
+  }
+
+  /// Quantizes a local gradient into the range -4 to 4. Ref: ITU-T T.87 A.3.3.
+  ///
+  fn quantize_gradient(&self, d: i32) -> i32 {
+    if d <= -self.threshold3 {
+      -4
+    } else if d <= -self.threshold2 {
+      -3
+    } else if d <= -self.threshold1 {
+      -2
+    } else if d < -self.near {
+      -1
+    } else if d <= self.near {
+      0
+    } else if d < self.threshold1 {
+      1
+    } else if d < self.threshold2 {
+      2
+    } else if d < self.threshold3 {
+      3
+    } else {
+      4
+    }
+  }
+
+  /// Returns the context for the given neighboring samples, which is zero
+  /// when run mode should be used. Ref: ITU-T T.87 A.3.
+  ///
+  fn context(&self, ra: i32, rb: i32, rc: i32, rd: i32) -> i32 {
+    (self.quantize_gradient(rd - rb) * 9 + self.quantize_gradient(rb - rc)) * 9
+      + self.quantize_gradient(rc - ra)
+  }
+
+  /// Reconstructs a sample from its predicted value and decoded prediction
+  /// error. Ref: ITU-T T.87 A.4.4.
+  ///
+  fn reconstruct(&self, predicted: i32, error: i32) -> i32 {
+    let mut value = predicted + error * (2 * self.near + 1);
+
+    if value < -self.near {
+      value += self.range * (2 * self.near + 1);
+    } else if value > self.maximum_sample_value + self.near {
+      value -= self.range * (2 * self.near + 1);
+    }
+
+    value.clamp(0, self.maximum_sample_value)
+  }
+}
+
+fn ceil_log2(value: i32) -> u32 {
+  let mut bits = 0;
+  while (1 << bits) < value {
+    bits += 1;
+  }
+
+  bits
+}
+
+/// The variables for a regular mode context. Ref: ITU-T T.87 A.2.1.
+///
+#[derive(Clone, Copy)]
+struct RegularContext {
+  a: i32,
+  b: i32,
+  c: i32,
+  n: i32,
+}
+
+impl RegularContext {
+  fn golomb_parameter(&self) -> u32 {
+    let mut k = 0;
+    while (self.n << k) < self.a && k < 24 {
+      k += 1;
+    }
+
+    k
+  }
+
+  /// Updates the context variables following the decode of a sample. Ref:
+  /// ITU-T T.87 A.6.
+  ///
+  fn update(&mut self, error: i32, near: i32, reset: i32) {
+    self.a = self.a.saturating_add(error.abs());
+    self.b += error * (2 * near + 1);
+
+    if self.n == reset {
+      self.a >>= 1;
+      self.b >>= 1;
+      self.n >>= 1;
+    }
+
+    self.n += 1;
+
+    if self.b + self.n <= 0 {
+      self.b += self.n;
+      if self.b <= -self.n {
+        self.b = -self.n + 1;
+      }
+      if self.c > -128 {
+        self.c -= 1;
+      }
+    } else if self.b > 0 {
+      self.b -= self.n;
+      if self.b > 0 {
+        self.b = 0;
+      }
+      if self.c < 127 {
+        self.c += 1;
+      }
+    }
+  }
+}
+
+/// The variables for a run interruption context. Ref: ITU-T T.87 A.7.2.
+///
+#[derive(Clone, Copy)]
+struct RunInterruptionContext {
+  run_interruption_type: i32,
+  a: i32,
+  n: i32,
+  nn: i32,
+}
+
+impl RunInterruptionContext {
+  fn golomb_parameter(&self) -> u32 {
+    let temp = self.a + (self.n >> 1) * self.run_interruption_type;
+
+    let mut k = 0;
+    while (self.n << k) < temp && k < 24 {
+      k += 1;
+    }
+
+    k
+  }
+
+  fn error_value(&self, temp: i32, k: u32) -> i32 {
+    let map = temp & 1 == 1;
+    let error_value = (temp + i32::from(map)) / 2;
+
+    if (k != 0 || 2 * self.nn >= self.n) == map {
+      -error_value
+    } else {
+      error_value
+    }
+  }
+
+  fn update(&mut self, error: i32, mapped_error: i32, reset: i32) {
+    if error < 0 {
+      self.nn += 1;
+    }
+
+    self.a = self
+      .a
+      .saturating_add((mapped_error + 1 - self.run_interruption_type) >> 1);
+
+    if self.n == reset {
+      self.a >>= 1;
+      self.n >>= 1;
+      self.nn >>= 1;
+    }
+
+    self.n += 1;
+  }
+}
+
+/// Decodes the samples in a single scan.
+///
+struct ScanDecoder<'a> {
+  reader: BitReader<'a>,
+  parameters: CodingParameters,
+  width: usize,
+  height: usize,
+  component_count: usize,
+  component_indices: Vec<usize>,
+  interleave_mode: InterleaveMode,
+  restart_interval: usize,
+  regular_contexts: [RegularContext; 365],
+  run_interruption_contexts: [RunInterruptionContext; 2],
+  run_indices: Vec<usize>,
+}
+
+impl<'a> ScanDecoder<'a> {
+  fn new(
+    data: &'a [u8],
+    position: usize,
+    frame: &FrameHeader,
+    scan: &ScanHeader,
+    parameters: CodingParameters,
+    restart_interval: usize,
+  ) -> Self {
+    let mut decoder = Self {
+      reader: BitReader::new(data, position),
+      parameters,
+      width: frame.width,
+      height: frame.height,
+      component_count: frame.component_ids.len(),
+      component_indices: scan.component_indices.clone(),
+      interleave_mode: scan.interleave_mode,
+      restart_interval,
+      regular_contexts: [RegularContext {
+        a: 0,
+        b: 0,
+        c: 0,
+        n: 1,
+      }; 365],
+      run_interruption_contexts: [RunInterruptionContext {
+        run_interruption_type: 0,
+        a: 0,
+        n: 1,
+        nn: 0,
+      }; 2],
+      run_indices: vec![0; scan.component_indices.len()],
+    };
+
+    decoder.reset();
+
+    decoder
+  }
+
+  /// Initializes the context variables and run indices, which happens at the
+  /// start of the scan and following each restart marker. Ref: ITU-T T.87
+  /// A.2.1.
+  ///
+  fn reset(&mut self) {
+    let a = ((self.parameters.range + 32) / 64).max(2);
+
+    for context in self.regular_contexts.iter_mut() {
+      *context = RegularContext {
+        a,
+        b: 0,
+        c: 0,
+        n: 1,
+      };
+    }
+
+    for (i, context) in self.run_interruption_contexts.iter_mut().enumerate() {
+      *context = RunInterruptionContext {
+        run_interruption_type: i as i32,
+        a,
+        n: 1,
+        nn: 0,
+      };
+    }
+
+    self.run_indices.fill(0);
+  }
+
+  /// Decodes the scan's samples into the output. Samples are stored with the
+  /// components of each pixel interleaved.
+  ///
+  fn decode(
+    &mut self,
+    samples: &mut [u16],
+  ) -> Result<(), PixelDataDecodeError> {
+    match self.interleave_mode {
+      InterleaveMode::None | InterleaveMode::Line => self.decode_lines(samples),
+      InterleaveMode::Sample => self.decode_sample_interleaved(samples),
+    }?;
+
+    self.reader.align_to_byte();
+
+    Ok(())
+  }
+
+  /// Decodes a scan where each line of each component is coded separately.
+  ///
+  fn decode_lines(
+    &mut self,
+    samples: &mut [u16],
+  ) -> Result<(), PixelDataDecodeError> {
+    let scan_component_count = self.component_indices.len();
+
+    // Each line buffer has an extra sample at each end for the neighbors of
+    // the first and last samples on a line
+    let mut previous_lines =
+      vec![vec![0i32; self.width + 2]; scan_component_count];
+    let mut current_lines = previous_lines.clone();
+
+    for line in 0..self.height {
+      if line > 0
+        && self.restart_interval > 0
+        && line % self.restart_interval == 0
+      {
+        self.restart(line / self.restart_interval - 1)?;
+        previous_lines.iter_mut().for_each(|line| line.fill(0));
+      }
+
+      for c in 0..scan_component_count {
+        let previous_line = &mut previous_lines[c];
+        let current_line = &mut current_lines[c];
+
+        previous_line[self.width + 1] = previous_line[self.width];
+        current_line[0] = previous_line[1];
+
+        self.decode_line(c, previous_line, current_line)?;
+
+        let component_index = self.component_indices[c];
+        for x in 0..self.width {
+          samples[(line * self.width + x) * self.component_count
+            + component_index] = current_line[x + 1] as u16;
+        }
+      }
+
+      core::mem::swap(&mut previous_lines, &mut current_lines);
+    }
+
+    Ok(())
+  }
+
+  fn decode_line(
+    &mut self,
+    c: usize,
+    previous_line: &[i32],
+    current_line: &mut [i32],
+  ) -> Result<(), PixelDataDecodeError> {
+    let mut x = 0;
+
+    while x < self.width {
+      let ra = current_line[x];
+      let rb = previous_line[x + 1];
+      let rc = previous_line[x];
+      let rd = previous_line[x + 2];
+
+      let context = self.parameters.context(ra, rb, rc, rd);
+      if context != 0 {
+        current_line[x + 1] = self.decode_regular(context, ra, rb, rc)?;
+        x += 1;
+        continue;
+      }
+
+      // Run mode
+      let run_length = self.decode_run_length(c, self.width - x)?;
+      current_line[x + 1..x + 1 + run_length].fill(ra);
+      x += run_length;
+
+      if x == self.width {
+        break;
+      }
+
+      // Run interruption sample
+      let rb = previous_line[x + 1];
+      current_line[x + 1] = if (ra - rb).abs() <= self.parameters.near {
+        let error = self.decode_run_interruption_error(1, c)?;
+        self.parameters.reconstruct(ra, error)
+      } else {
+        let error = self.decode_run_interruption_error(0, c)?;
+        self.parameters.reconstruct(rb, error * sign(rb - ra))
+      };
+
+      self.run_indices[c] = self.run_indices[c].saturating_sub(1);
+      x += 1;
+    }
+
+    Ok(())
+  }
+
+  /// Decodes a scan where the components of each pixel are coded together.
+  ///
+  fn decode_sample_interleaved(
+    &mut self,
+    samples: &mut [u16],
+  ) -> Result<(), PixelDataDecodeError> {
+    let scan_component_count = self.component_indices.len();
+
+    let mut previous_lines =
+      vec![vec![0i32; self.width + 2]; scan_component_count];
+    let mut current_lines = previous_lines.clone();
+
+    let mut contexts = vec![0; scan_component_count];
+    let mut ra = vec![0; scan_component_count];
+
+    for line in 0..self.height {
+      if line > 0
+        && self.restart_interval > 0
+        && line % self.restart_interval == 0
+      {
+        self.restart(line / self.restart_interval - 1)?;
+        previous_lines.iter_mut().for_each(|line| line.fill(0));
+      }
+
+      for c in 0..scan_component_count {
+        let width = self.width;
+        previous_lines[c][width + 1] = previous_lines[c][width];
+        current_lines[c][0] = previous_lines[c][1];
+      }
+
+      let mut x = 0;
+      while x < self.width {
+        for c in 0..scan_component_count {
+          ra[c] = current_lines[c][x];
+          contexts[c] = self.parameters.context(
+            ra[c],
+            previous_lines[c][x + 1],
+            previous_lines[c][x],
+            previous_lines[c][x + 2],
+          );
+        }
+
+        if contexts.iter().any(|context| *context != 0) {
+          for c in 0..scan_component_count {
+            current_lines[c][x + 1] = self.decode_regular(
+              contexts[c],
+              ra[c],
+              previous_lines[c][x + 1],
+              previous_lines[c][x],
+            )?;
+          }
+
+          x += 1;
+          continue;
+        }
+
+        // Run mode
+        let run_length = self.decode_run_length(0, self.width - x)?;
+        for c in 0..scan_component_count {
+          current_lines[c][x + 1..x + 1 + run_length].fill(ra[c]);
+        }
+        x += run_length;
+
+        if x == self.width {
+          break;
+        }
+
+        // Run interruption pixel
+        for c in 0..scan_component_count {
+          let rb = previous_lines[c][x + 1];
+          let error = self.decode_run_interruption_error(0, 0)?;
+          current_lines[c][x + 1] =
+            self.parameters.reconstruct(rb, error * sign(rb - ra[c]));
+        }
+
+        self.run_indices[0] = self.run_indices[0].saturating_sub(1);
+        x += 1;
+      }
+
+      for (c, current_line) in current_lines.iter().enumerate() {
+        let component_index = self.component_indices[c];
+        for x in 0..self.width {
+          samples[(line * self.width + x) * self.component_count
+            + component_index] = current_line[x + 1] as u16;
+        }
+      }
+
+      core::mem::swap(&mut previous_lines, &mut current_lines);
+    }
+
+    Ok(())
+  }
+
+  /// Reads the restart marker at the end of a restart interval and resets the
+  /// decoder's state. Ref: ITU-T T.87 D.2.
+  ///
+  fn restart(&mut self, interval: usize) -> Result<(), PixelDataDecodeError> {
+    self.reader.align_to_byte();
+
+    let marker = read_marker(self.reader.data, &mut self.reader.position)?;
+    if usize::from(marker) != 0xD0 + interval % 8 {
+      return Err(data_invalid("Restart marker not found"));
+    }
+
+    self.reset();
+
+    Ok(())
+  }
+
+  /// Decodes a sample in regular mode. Ref: ITU-T T.87 A.4 - A.6.
+  ///
+  fn decode_regular(
+    &mut self,
+    context: i32,
+    ra: i32,
+    rb: i32,
+    rc: i32,
+  ) -> Result<i32, PixelDataDecodeError> {
+    let sign = sign(context);
+    let near = self.parameters.near;
+    let reset = self.parameters.reset;
+
+    let context = &mut self.regular_contexts[context.unsigned_abs() as usize];
+    let k = context.golomb_parameter();
+
+    // Edge-detecting predictor with bias correction
+    let predicted = if rc >= ra.max(rb) {
+      ra.min(rb)
+    } else if rc <= ra.min(rb) {
+      ra.max(rb)
+    } else {
+      ra + rb - rc
+    };
+    let predicted = (predicted + sign * context.c)
+      .clamp(0, self.parameters.maximum_sample_value);
+
+    let mapped_error = self.reader.read_golomb(
+      k,
+      self.parameters.limit,
+      self.parameters.qbpp,
+    )?;
+
+    // Undo the error mapping. Ref: ITU-T T.87 A.5.2.
+    let mut error = if mapped_error & 1 == 1 {
+      -((mapped_error + 1) >> 1)
+    } else {
+      mapped_error >> 1
+    };
+
+    if k == 0 && near == 0 && 2 * context.b + context.n - 1 < 0 {
+      error = -error - 1;
+    }
+
+    check_prediction_error(error, &self.parameters)?;
+
+    context.update(error, near, reset);
+
+    Ok(self.parameters.reconstruct(predicted, sign * error))
+  }
+
+  /// Decodes the length of a run in run mode, which is limited to the given
+  /// number of samples remaining on the line. Ref: ITU-T T.87 A.7.1.
+  ///
+  fn decode_run_length(
+    &mut self,
+    c: usize,
+    remaining: usize,
+  ) -> Result<usize, PixelDataDecodeError> {
+    let run_index = &mut self.run_indices[c];
+    let mut run_length = 0;
+
+    while self.reader.read_bits(1)? == 1 {
+      let count = (1 << J[*run_index]).min(remaining - run_length);
+      run_length += count;
+
+      if count == 1 << J[*run_index] {
+        *run_index = (*run_index + 1).min(31);
+      }
+
+      if run_length == remaining {
+        return Ok(run_length);
+      }
+    }
+
+    run_length += self.reader.read_bits(J[*run_index])? as usize;
+
+    if run_length > remaining {
+      return Err(data_invalid("Run length is invalid"));
+    }
+
+    Ok(run_length)
+  }
+
+  /// Decodes the prediction error for a run interruption sample. Ref: ITU-T
+  /// T.87 A.7.2.
+  ///
+  fn decode_run_interruption_error(
+    &mut self,
+    run_interruption_type: usize,
+    c: usize,
+  ) -> Result<i32, PixelDataDecodeError> {
+    let run_index = self.run_indices[c];
+    let reset = self.parameters.reset;
+
+    let context = &mut self.run_interruption_contexts[run_interruption_type];
+    let k = context.golomb_parameter();
+
+    let mapped_error = self.reader.read_golomb(
+      k,
+      self.parameters.limit - J[run_index] - 1,
+      self.parameters.qbpp,
+    )?;
+
+    let error =
+      context.error_value(mapped_error + context.run_interruption_type, k);
+
+    check_prediction_error(error, &self.parameters)?;
+
+    context.update(error, mapped_error, reset);
+
+    Ok(error)
+  }
+}
+
+/// Checks that a decoded prediction error is within the range that results
+/// from its modulo reduction. Larger errors only occur in malformed data, and
+/// would otherwise overflow when the sample is reconstructed. Ref: ITU-T T.87
+/// A.4.5.
+///
+fn check_prediction_error(
+  error: i32,
+  parameters: &CodingParameters,
+) -> Result<(), PixelDataDecodeError> {
+  if error.unsigned_abs() > parameters.range as u32 {
+    return Err(data_invalid("Prediction error is invalid"));
+  }
+
+  Ok(())
+}
+
+/// Returns -1 for negative values, otherwise 1.
+///
+fn sign(value: i32) -> i32 {
+  if value < 0 { -1 } else { 1 }
+}
+
+/// Reads bits from JPEG-LS scan data, removing the zero bit that's stuffed
+/// after every 0xFF byte. Reading stops at the marker that ends the scan data.
+///
+struct BitReader<'a> {
+  data: &'a [u8],
+  position: usize,
+  bits: u64,
+  bit_count: u32,
+  previous_byte_was_ff: bool,
+}
+
+impl<'a> BitReader<'a> {
+  fn new(data: &'a [u8], position: usize) -> Self {
+    Self {
+      data,
+      position,
+      bits: 0,
+      bit_count: 0,
+      previous_byte_was_ff: false,
+    }
+  }
+
+  fn fill(&mut self) {
+    while self.bit_count <= 56 {
+      let Some(&byte) = self.data.get(self.position) else {
+        return;
+      };
+
+      // A 0xFF byte followed by a byte with its high bit set is a marker
+      if byte == 0xFF
+        && self
+          .data
+          .get(self.position + 1)
+          .is_none_or(|next| *next >= 0x80)
+      {
+        return;
+      }
+
+      if self.previous_byte_was_ff {
+        self.bits |= u64::from(byte & 0x7F) << (57 - self.bit_count);
+        self.bit_count += 7;
+      } else {
+        self.bits |= u64::from(byte) << (56 - self.bit_count);
+        self.bit_count += 8;
+      }
+
+      self.previous_byte_was_ff = byte == 0xFF;
+      self.position += 1;
+    }
+  }
+
+  fn read_bits(&mut self, count: u32) -> Result<u32, PixelDataDecodeError> {
+    if count == 0 {
+      return Ok(0);
+    }
+
+    if self.bit_count < count {
+      self.fill();
+
+      if self.bit_count < count {
+        return Err(data_invalid("Scan data ended unexpectedly"));
+      }
+    }
+
+    let value = (self.bits >> (64 - count)) as u32;
+    self.bits <<= count;
+    self.bit_count -= count;
+
+    Ok(value)
+  }
+
+  /// Reads a limited length Golomb code. Ref: ITU-T T.87 A.5.3.
+  ///
+  fn read_golomb(
+    &mut self,
+    k: u32,
+    limit: u32,
+    qbpp: u32,
+  ) -> Result<i32, PixelDataDecodeError> {
+    let high_bits = self.read_unary(limit)?;
+
+    if high_bits >= limit - (qbpp + 1) {
+      return Ok(self.read_bits(qbpp)? as i32 + 1);
+    }
+
+    Ok(((high_bits << k) | self.read_bits(k)?) as i32)
+  }
+
+  /// Reads zero bits up to and including the next one bit, returning the
+  /// number of zero bits.
+  ///
+  fn read_unary(&mut self, limit: u32) -> Result<u32, PixelDataDecodeError> {
+    let mut count = 0;
+
+    loop {
+      if self.bit_count == 0 {
+        self.fill();
+
+        if self.bit_count == 0 {
+          return Err(data_invalid("Scan data ended unexpectedly"));
+        }
+      }
+
+      let leading_zeros = self.bits.leading_zeros();
+      if leading_zeros < self.bit_count {
+        self.bits = self.bits.checked_shl(leading_zeros + 1).unwrap_or(0);
+        self.bit_count -= leading_zeros + 1;
+
+        return Ok(count + leading_zeros);
+      }
+
+      count += self.bit_count;
+      self.bits = 0;
+      self.bit_count = 0;
+
+      if count > limit {
+        return Err(data_invalid("Golomb code is invalid"));
+      }
+    }
+  }
+
+  /// Discards any bits remaining in the current byte.
+  ///
+  fn align_to_byte(&mut self) {
+    self.bits = 0;
+    self.bit_count = 0;
+    self.previous_byte_was_ff = false;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decode_invalid_data() {
+    let image_pixel_module = ImagePixelModule::new_basic(
+      crate::iods::image_pixel_module::SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      2,
+      2,
+      BitsAllocated::Eight,
+      8,
+    )
+    .unwrap();
+
+    assert!(decode_monochrome(&image_pixel_module, &[]).is_err());
+    assert!(decode_monochrome(&image_pixel_module, &[0xFF, 0xD8]).is_err());
+    assert!(
+      decode_monochrome(&image_pixel_module, &[0xFF, 0xD8, 0xFF, 0xD9])
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn decode_invalid_prediction_error() {
+    let frame = FrameHeader {
+      bits_per_sample: 16,
+      width: 16,
+      height: 16,
+      component_ids: vec![1],
+    };
+    let scan = ScanHeader {
+      component_indices: vec![0],
+      near: 255,
+      interleave_mode: InterleaveMode::None,
+    };
+    let parameters =
+      CodingParameters::new(16, 255, &PresetParameters::default()).unwrap();
+
+    // A Golomb code with a long unary prefix followed by 24 bits, which
+    // decodes to a prediction error far larger than the range allows
+    let data = [0, 0, 0, 0, 0, 0, 0b0010_0111, 0xF7, 0xF7, 0xF0];
+
+    let mut decoder = ScanDecoder::new(&data, 0, &frame, &scan, parameters, 0);
+
+    // Put the context into the state reached after a run of large errors, in
+    // which the largest Golomb parameter is used
+    decoder.regular_contexts[1].a = 1 << 30;
+
+    assert_eq!(
+      decoder.decode_regular(1, 0, 0, 0),
+      Err(data_invalid("Prediction error is invalid"))
+    );
+
+    // Context variables saturate rather than overflow
+    let mut context = RegularContext {
+      a: i32::MAX - 1,
+      b: 0,
+      c: 0,
+      n: 2,
+    };
+    context.update(100, 0, 64);
+    assert_eq!(context.a, i32::MAX);
+  }
+
+  #[cfg(all(feature = "native", feature = "std"))]
+  #[test]
+  fn decode_matches_charls() {
+    use dcmfx_core::transfer_syntax;
+
+    use crate::{PixelDataEncodeConfig, encode};
+
+    for (bits_allocated, bits_stored) in
+      [(BitsAllocated::Eight, 8), (BitsAllocated::Sixteen, 12)]
+    {
+      for near in [0, 3] {
+        let image_pixel_module = ImagePixelModule::new_basic(
+          crate::iods::image_pixel_module::SamplesPerPixel::One,
+          PhotometricInterpretation::Monochrome2 {
+            pixel_representation: PixelRepresentation::Unsigned,
+          },
+          37,
+          53,
+          bits_allocated,
+          bits_stored,
+        )
+        .unwrap();
+
+        // Create an image that has flat regions, gradients, and noise in order
+        // to exercise both regular mode and run mode
+        let mut seed = 1u32;
+        let pixels: Vec<u16> = (0..37 * 53)
+          .map(|i| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let (x, y) = (i % 53, i / 53);
+
+            let value = if y < 10 {
+              100
+            } else if x < 20 {
+              x * 7 + y * 3
+            } else {
+              (seed >> 16) as usize
+            };
+
+            (value % (1 << bits_stored)) as u16
+          })
+          .collect();
+
+        let image = if bits_allocated == BitsAllocated::Eight {
+          MonochromeImage::new_u8(
+            53,
+            37,
+            pixels.iter().map(|pixel| *pixel as u8).collect(),
+            bits_stored,
+            false,
+          )
+        } else {
+          MonochromeImage::new_u16(53, 37, pixels, bits_stored, false)
+        }
+        .unwrap();
+
+        let mut encode_config = PixelDataEncodeConfig::default();
+        encode_config.set_jpeg_ls_near(Some(near));
+
+        let data = encode::encode_monochrome(
+          &image,
+          &image_pixel_module,
+          &transfer_syntax::JPEG_LS_LOSSY_NEAR_LOSSLESS,
+          &encode_config,
+        )
+        .unwrap()
+        .to_bytes();
+
+        assert_eq!(
+          decode_monochrome(&image_pixel_module, &data),
+          super::super::charls::decode_monochrome(&image_pixel_module, &data)
+        );
+
+        if near == 0 {
+          assert_eq!(decode_monochrome(&image_pixel_module, &data), Ok(image));
+        }
+      }
+    }
+  }
+}
@@ -1,10 +1,16 @@
 #[cfg(not(feature = "std"))]
 use alloc::{format, string::ToString, vec};
 
-use jxl_oxide::{FrameBufferSample, JxlImage, Render, image::BitDepth};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use jxl_oxide::{
+  FrameBufferSample, JxlImage, JxlThreadPool, Render, image::BitDepth,
+};
 
 use crate::{
-  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeError,
+  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeConfig,
+  PixelDataDecodeError,
   iods::image_pixel_module::{
     BitsAllocated, ImagePixelModule, PhotometricInterpretation,
     PixelRepresentation,
@@ -16,8 +22,10 @@ use crate::{
 pub fn decode_monochrome(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  decode_config: &PixelDataDecodeConfig,
 ) -> Result<MonochromeImage, PixelDataDecodeError> {
-  let (jxl_image, jxl_render) = decode(image_pixel_module, data)?;
+  let (jxl_image, jxl_render) =
+    decode(image_pixel_module, data, decode_config)?;
 
   let width = image_pixel_module.columns();
   let height = image_pixel_module.rows();
@@ -95,8 +103,10 @@ pub fn decode_monochrome(
 pub fn decode_color(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  decode_config: &PixelDataDecodeConfig,
 ) -> Result<ColorImage, PixelDataDecodeError> {
-  let (jxl_image, jxl_render) = decode(image_pixel_module, data)?;
+  let (jxl_image, jxl_render) =
+    decode(image_pixel_module, data, decode_config)?;
   let width = image_pixel_module.columns();
   let height = image_pixel_module.rows();
   let bits_stored = image_pixel_module.bits_stored();
@@ -155,6 +165,7 @@ pub fn decode_color(
 fn decode(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  decode_config: &PixelDataDecodeConfig,
 ) -> Result<(JxlImage, Render), PixelDataDecodeError> {
   if image_pixel_module.pixel_representation().is_signed() {
     return Err(PixelDataDecodeError::ImagePixelModuleNotSupported {
@@ -162,7 +173,19 @@ fn decode(
     });
   }
 
-  let mut image = JxlImage::read_with_defaults(data).map_err(|e| {
+  let mut image_builder = JxlImage::builder();
+
+  // Use a dedicated thread pool if a thread count is specified, otherwise the
+  // default thread pool is used
+  match decode_config.thread_count {
+    Some(1) => image_builder = image_builder.pool(JxlThreadPool::none()),
+    Some(thread_count) => {
+      image_builder = image_builder.pool(thread_pool(thread_count))
+    }
+    None => (),
+  }
+
+  let mut image = image_builder.read(data).map_err(|e| {
     PixelDataDecodeError::DataInvalid {
       details: format!("JPEG XL decode failed with '{e}'"),
     }
@@ -193,6 +216,29 @@ fn decode(
   Ok((image, render))
 }
 
+/// Returns a thread pool with the given number of threads. Thread pools are
+/// cached by thread count so that their threads are reused across frames.
+///
+#[cfg(feature = "std")]
+fn thread_pool(thread_count: usize) -> JxlThreadPool {
+  static THREAD_POOLS: std::sync::Mutex<BTreeMap<usize, JxlThreadPool>> =
+    std::sync::Mutex::new(BTreeMap::new());
+
+  let mut thread_pools = THREAD_POOLS.lock().unwrap_or_else(|e| e.into_inner());
+
+  thread_pools
+    .entry(thread_count)
+    .or_insert_with(|| JxlThreadPool::rayon(Some(thread_count)))
+    .clone()
+}
+
+/// Returns a thread pool with the given number of threads.
+///
+#[cfg(not(feature = "std"))]
+fn thread_pool(thread_count: usize) -> JxlThreadPool {
+  JxlThreadPool::rayon(Some(thread_count))
+}
+
 fn render_samples<Sample: FrameBufferSample>(
   jxl_render: &Render,
   buffer: &mut [Sample],
@@ -1,5 +1,6 @@
 use crate::{
-  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeError,
+  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeConfig,
+  PixelDataDecodeError,
   iods::image_pixel_module::{
     BitsAllocated, ImagePixelModule, PhotometricInterpretation,
     PixelRepresentation,
@@ -11,6 +12,7 @@ use crate::{
 pub fn decode_monochrome(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  decode_config: &PixelDataDecodeConfig,
 ) -> Result<MonochromeImage, PixelDataDecodeError> {
   let width = image_pixel_module.columns();
   let height = image_pixel_module.rows();
@@ -32,7 +34,7 @@ pub fn decode_monochrome(
       },
       BitsAllocated::Eight,
     ) => {
-      let buffer = decode::<u8>(image_pixel_module, data)?;
+      let buffer = decode::<u8>(image_pixel_module, data, decode_config)?;
 
       MonochromeImage::new_u8(
         width,
@@ -53,7 +55,7 @@ pub fn decode_monochrome(
       },
       BitsAllocated::Sixteen,
     ) => {
-      let buffer = decode::<u16>(image_pixel_module, data)?;
+      let buffer = decode::<u16>(image_pixel_module, data, decode_config)?;
 
       MonochromeImage::new_u16(
         width,
@@ -83,6 +85,7 @@ pub fn decode_monochrome(
 pub fn decode_color(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  decode_config: &PixelDataDecodeConfig,
 ) -> Result<ColorImage, PixelDataDecodeError> {
   let width = image_pixel_module.columns();
   let height = image_pixel_module.rows();
@@ -99,7 +102,7 @@ pub fn decode_color(
       | PhotometricInterpretation::Xyb,
       BitsAllocated::Eight,
     ) => {
-      let buffer = decode::<u8>(image_pixel_module, data)?;
+      let buffer = decode::<u8>(image_pixel_module, data, decode_config)?;
 
       ColorImage::new_u8(width, height, buffer, ColorSpace::Rgb, bits_stored)
         .map_err(PixelDataDecodeError::ImageCreationFailed)
@@ -112,7 +115,7 @@ pub fn decode_color(
       | PhotometricInterpretation::Xyb,
       BitsAllocated::Sixteen,
     ) => {
-      let buffer = decode::<u16>(image_pixel_module, data)?;
+      let buffer = decode::<u16>(image_pixel_module, data, decode_config)?;
 
       ColorImage::new_u16(width, height, buffer, ColorSpace::Rgb, bits_stored)
         .map_err(PixelDataDecodeError::ImageCreationFailed)
@@ -134,6 +137,7 @@ pub fn decode_color(
 fn decode<T: Clone + Default>(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  decode_config: &PixelDataDecodeConfig,
 ) -> Result<Vec<T>, PixelDataDecodeError> {
   let mut error_message = [0 as core::ffi::c_char; 200];
 
@@ -154,6 +158,7 @@ fn decode<T: Clone + Default>(
       image_pixel_module.rows().into(),
       u8::from(image_pixel_module.samples_per_pixel()).into(),
       u8::from(image_pixel_module.bits_allocated()).into(),
+      decode_config.thread_count.unwrap_or(0),
       output_buffer.as_mut_ptr() as *mut core::ffi::c_void,
       output_buffer.len()
         * usize::from(u8::from(image_pixel_module.bits_allocated()) / 8),
@@ -185,6 +190,7 @@ mod ffi {
       height: usize,
       samples_per_pixel: usize,
       bits_allocated: usize,
+      thread_count: usize,
       output_buffer: *mut core::ffi::c_void,
       output_buffer_size: usize,
       error_buffer: *mut core::ffi::c_char,
@@ -13,11 +13,19 @@ use crate::{
   iods::{ImagePixelModule, image_pixel_module::PhotometricInterpretation},
 };
 
+#[cfg(feature = "ffmpeg")]
+use crate::VideoCodec;
+
 #[cfg(all(feature = "native", feature = "std"))]
 mod charls;
+mod decoded_frame_info;
+#[cfg(feature = "ffmpeg")]
+mod ffmpeg;
 #[cfg(feature = "native")]
 mod jpeg_2000;
 mod jpeg_decoder;
+mod jpeg_lossless;
+mod jpeg_ls;
 mod jpeg_xl;
 mod jxl_oxide;
 #[cfg(feature = "native")]
@@ -32,38 +40,84 @@ mod openjph;
 mod rle_lossless;
 mod zune_jpeg;
 
+pub use decoded_frame_info::{
+  CodestreamProgression, ColorTransform, DecodedFrameInfo,
+};
+
 /// Configuration used when decoding pixel data.
 ///
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// Each codec family that has more than one available decoder is configured
+/// with an ordered list of decoders. The first decoder in the list is used,
+/// and if it reports that the data is invalid then the next decoder in the
+/// list is tried, and so on. If every decoder fails then the error from the
+/// first decoder is returned. The name of the decoder that succeeded is
+/// recorded on the decoded frame, see [`PixelDataFrame::decoder()`].
+///
+#[derive(Clone, Debug, PartialEq)]
 pub struct PixelDataDecodeConfig {
-  /// The library to use for decoding High-Throughput JPEG 2000 pixel data.
-  /// Defaults to [`HighThroughputJpeg2000Decoder::OpenJph`] except on WASM
-  /// where it defaults to [`HighThroughputJpeg2000Decoder::OpenJpeg`].
+  /// The libraries to use for decoding High-Throughput JPEG 2000 pixel data,
+  /// in the order they are tried. Defaults to
+  /// [`HighThroughputJpeg2000Decoder::OpenJph`] except on WASM where it
+  /// defaults to [`HighThroughputJpeg2000Decoder::OpenJpeg`].
   ///
-  pub high_throughput_jpeg_2000_decoder: HighThroughputJpeg2000Decoder,
+  pub high_throughput_jpeg_2000_decoders: Vec<HighThroughputJpeg2000Decoder>,
 
-  /// The library to use for decoding JPEG XL pixel data. Defaults to
-  /// [`JpegXlDecoder::LibJxl`] except on WASM where it defaults to
-  /// [`JpegXlDecoder::JxlOxide`].
+  /// The libraries to use for decoding JPEG-LS pixel data, in the order they
+  /// are tried. Defaults to [`JpegLsDecoder::CharLs`] except on WASM where it
+  /// defaults to [`JpegLsDecoder::Dcmfx`].
+  ///
+  pub jpeg_ls_decoders: Vec<JpegLsDecoder>,
+
+  /// The libraries to use for decoding JPEG XL pixel data, in the order they
+  /// are tried. Defaults to [`JpegXlDecoder::LibJxl`] except on WASM where it
+  /// defaults to [`JpegXlDecoder::JxlOxide`].
+  ///
+  pub jpeg_xl_decoders: Vec<JpegXlDecoder>,
+
+  /// The maximum number of threads that a decoder may use when decoding a
+  /// single frame of pixel data. If this is `None` then the decoder's default
+  /// is used, which is typically the number of CPU cores. This is currently
+  /// only used when decoding JPEG XL pixel data.
+  ///
+  pub thread_count: Option<usize>,
+
+  /// The minimum width and height that decoded images need to have. When this
+  /// is set, decoders that are able to decode at a reduced resolution will do
+  /// so when the reduced image is still at least this size, which makes
+  /// decoding faster when only a downscaled image is needed. This means that
+  /// decoded images may be smaller than the rows and columns specified by the
+  /// Image Pixel Module.
   ///
-  pub jpeg_xl_decoder: JpegXlDecoder,
+  /// This is currently used when decoding JPEG Baseline 8-bit pixel data, and
+  /// JPEG 2000 pixel data decoded by OpenJPEG. Defaults to `None`.
+  ///
+  pub min_decoded_size: Option<(u16, u16)>,
 }
 
 impl Default for PixelDataDecodeConfig {
   #[cfg(all(feature = "native", feature = "std"))]
   fn default() -> Self {
     Self {
-      high_throughput_jpeg_2000_decoder: HighThroughputJpeg2000Decoder::OpenJph,
-      jpeg_xl_decoder: JpegXlDecoder::LibJxl,
+      high_throughput_jpeg_2000_decoders: vec![
+        HighThroughputJpeg2000Decoder::OpenJph,
+      ],
+      jpeg_ls_decoders: vec![JpegLsDecoder::CharLs],
+      jpeg_xl_decoders: vec![JpegXlDecoder::LibJxl],
+      thread_count: None,
+      min_decoded_size: None,
     }
   }
 
   #[cfg(not(all(feature = "native", feature = "std")))]
   fn default() -> Self {
     Self {
-      high_throughput_jpeg_2000_decoder:
+      high_throughput_jpeg_2000_decoders: vec![
         HighThroughputJpeg2000Decoder::OpenJpeg,
-      jpeg_xl_decoder: JpegXlDecoder::JxlOxide,
+      ],
+      jpeg_ls_decoders: vec![JpegLsDecoder::Dcmfx],
+      jpeg_xl_decoders: vec![JpegXlDecoder::JxlOxide],
+      thread_count: None,
+      min_decoded_size: None,
     }
   }
 }
@@ -74,30 +128,73 @@ pub enum HighThroughputJpeg2000Decoder {
   OpenJph,
 }
 
+impl HighThroughputJpeg2000Decoder {
+  /// Returns the name of this decoder.
+  ///
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::OpenJpeg => "openjpeg",
+      Self::OpenJph => "openjph",
+    }
+  }
+}
+
 impl core::fmt::Display for HighThroughputJpeg2000Decoder {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+/// The decoders available for JPEG-LS pixel data. [`JpegLsDecoder::Dcmfx`] is
+/// a decoder written in pure Rust that is included in DCMfx, and is available
+/// on all platforms.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JpegLsDecoder {
+  CharLs,
+  Dcmfx,
+}
+
+impl JpegLsDecoder {
+  /// Returns the name of this decoder.
+  ///
+  pub fn name(&self) -> &'static str {
     match self {
-      Self::OpenJpeg => f.write_str("openjpeg"),
-      Self::OpenJph => f.write_str("openjph"),
+      Self::CharLs => "charls",
+      Self::Dcmfx => "dcmfx",
     }
   }
 }
 
+impl core::fmt::Display for JpegLsDecoder {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum JpegXlDecoder {
   LibJxl,
   JxlOxide,
 }
 
-impl core::fmt::Display for JpegXlDecoder {
-  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+impl JpegXlDecoder {
+  /// Returns the name of this decoder.
+  ///
+  pub fn name(&self) -> &'static str {
     match self {
-      Self::LibJxl => f.write_str("libjxl"),
-      Self::JxlOxide => f.write_str("jxl-oxide"),
+      Self::LibJxl => "libjxl",
+      Self::JxlOxide => "jxl-oxide",
     }
   }
 }
 
+impl core::fmt::Display for JpegXlDecoder {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
 /// Errors that can occur when decoding frames of image data in a specific
 /// transfer syntax.
 ///
@@ -236,14 +333,13 @@ pub fn decode_photometric_interpretation<'a>(
     ),
 
     &JPEG_LOSSLESS_NON_HIERARCHICAL | &JPEG_LOSSLESS_NON_HIERARCHICAL_SV1 => {
-      jpeg_decoder::decode_photometric_interpretation(
+      jpeg_lossless::decode_photometric_interpretation(
         photometric_interpretation,
       )
     }
 
-    #[cfg(all(feature = "native", feature = "std"))]
     &JPEG_LS_LOSSLESS | &JPEG_LS_LOSSY_NEAR_LOSSLESS => {
-      charls::decode_photometric_interpretation(photometric_interpretation)
+      jpeg_ls::decode_photometric_interpretation(photometric_interpretation)
     }
 
     #[cfg(feature = "native")]
@@ -263,6 +359,11 @@ pub fn decode_photometric_interpretation<'a>(
       native::decode_photometric_interpretation(photometric_interpretation)
     }
 
+    #[cfg(feature = "ffmpeg")]
+    _ if VideoCodec::from_transfer_syntax(transfer_syntax).is_some() => {
+      ffmpeg::decode_photometric_interpretation(photometric_interpretation)
+    }
+
     _ => {
       Err(PixelDataDecodeError::TransferSyntaxNotSupported { transfer_syntax })
     }
@@ -273,6 +374,13 @@ pub fn decode_photometric_interpretation<'a>(
 /// returned image needs to have a grayscale pipeline applied in order to reach
 /// final grayscale display values.
 ///
+/// When the frame holds a video stream only its first picture is decoded. Use
+/// [`decode_video_monochrome()`] to decode all of its pictures.
+///
+/// The name of the decoder that was used, and the details of the image read
+/// from the frame's codestream, are recorded on the frame. See
+/// [`PixelDataFrame::decoder()`] and [`PixelDataFrame::decoded_frame_info()`].
+///
 pub fn decode_monochrome(
   frame: &mut PixelDataFrame,
   transfer_syntax: &'static TransferSyntax,
@@ -280,8 +388,32 @@ pub fn decode_monochrome(
   decode_config: &PixelDataDecodeConfig,
 ) -> Result<MonochromeImage, PixelDataDecodeError> {
   let frame_bit_offset = frame.bit_offset();
+
   let data = frame.combine_chunks();
+  let decoded_frame_info =
+    DecodedFrameInfo::from_codestream(data, transfer_syntax);
 
+  let (image, decoder) = decode_monochrome_data(
+    data,
+    frame_bit_offset,
+    transfer_syntax,
+    image_pixel_module,
+    decode_config,
+  )?;
+
+  frame.set_decoder(decoder);
+  frame.set_decoded_frame_info(decoded_frame_info);
+
+  Ok(image)
+}
+
+fn decode_monochrome_data(
+  data: &[u8],
+  frame_bit_offset: usize,
+  transfer_syntax: &'static TransferSyntax,
+  image_pixel_module: &ImagePixelModule,
+  decode_config: &PixelDataDecodeConfig,
+) -> Result<(MonochromeImage, &'static str), PixelDataDecodeError> {
   use transfer_syntax::*;
 
   match transfer_syntax {
@@ -289,77 +421,154 @@ pub fn decode_monochrome(
     | &EXPLICIT_VR_LITTLE_ENDIAN
     | &ENCAPSULATED_UNCOMPRESSED_EXPLICIT_VR_LITTLE_ENDIAN
     | &DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN
-    | &EXPLICIT_VR_BIG_ENDIAN => {
-      native::decode_monochrome(image_pixel_module, data, frame_bit_offset)
+    | &EXPLICIT_VR_BIG_ENDIAN => Ok((
+      native::decode_monochrome(image_pixel_module, data, frame_bit_offset)?,
+      NATIVE_DECODER,
+    )),
+
+    &RLE_LOSSLESS => Ok((
+      rle_lossless::decode_monochrome(image_pixel_module, data)?,
+      RLE_LOSSLESS_DECODER,
+    )),
+
+    // zune-jpeg can't decode at a reduced resolution, so jpeg-decoder is used
+    // when a reduced resolution decode is possible
+    &JPEG_BASELINE_8BIT
+      if resolution_reduction(
+        image_pixel_module,
+        decode_config.min_decoded_size,
+        jpeg_decoder::MAX_RESOLUTION_REDUCTION,
+      ) > 0 =>
+    {
+      Ok((
+        jpeg_decoder::decode_monochrome(
+          image_pixel_module,
+          data,
+          decode_config.min_decoded_size,
+        )?,
+        JPEG_DECODER_DECODER,
+      ))
     }
 
-    &RLE_LOSSLESS => rle_lossless::decode_monochrome(image_pixel_module, data),
-
-    &JPEG_BASELINE_8BIT => {
-      zune_jpeg::decode_monochrome(image_pixel_module, data)
-    }
+    &JPEG_BASELINE_8BIT => Ok((
+      zune_jpeg::decode_monochrome(image_pixel_module, data)?,
+      ZUNE_JPEG_DECODER,
+    )),
 
     #[cfg(feature = "native")]
-    &JPEG_EXTENDED_12BIT => {
-      libjpeg_12bit::decode_monochrome(image_pixel_module, data)
-    }
+    &JPEG_EXTENDED_12BIT => Ok((
+      libjpeg_12bit::decode_monochrome(image_pixel_module, data)?,
+      LIBJPEG_12BIT_DECODER,
+    )),
 
     &JPEG_LOSSLESS_NON_HIERARCHICAL | &JPEG_LOSSLESS_NON_HIERARCHICAL_SV1 => {
-      jpeg_decoder::decode_monochrome(image_pixel_module, data)
+      Ok((
+        jpeg_lossless::decode_monochrome(image_pixel_module, data)?,
+        JPEG_LOSSLESS_DECODER,
+      ))
     }
 
-    #[cfg(all(feature = "native", feature = "std"))]
-    &JPEG_LS_LOSSLESS | &JPEG_LS_LOSSY_NEAR_LOSSLESS => {
-      charls::decode_monochrome(image_pixel_module, data)
-    }
+    &JPEG_LS_LOSSLESS | &JPEG_LS_LOSSY_NEAR_LOSSLESS => decode_with_decoders(
+      transfer_syntax,
+      &decode_config.jpeg_ls_decoders,
+      JpegLsDecoder::name,
+      |decoder| match decoder {
+        #[cfg(all(feature = "native", feature = "std"))]
+        JpegLsDecoder::CharLs => {
+          charls::decode_monochrome(image_pixel_module, data)
+        }
+
+        JpegLsDecoder::Dcmfx => {
+          jpeg_ls::decode_monochrome(image_pixel_module, data)
+        }
+
+        #[allow(unreachable_patterns)]
+        _ => Err(PixelDataDecodeError::DecoderNotAvailable {
+          name: decoder.to_string(),
+        }),
+      },
+    ),
 
     #[cfg(feature = "native")]
-    &JPEG_2000 | &JPEG_2000_LOSSLESS_ONLY => {
-      openjpeg::decode_monochrome(image_pixel_module, data)
-    }
+    &JPEG_2000 | &JPEG_2000_LOSSLESS_ONLY => Ok((
+      openjpeg::decode_monochrome(
+        image_pixel_module,
+        data,
+        decode_config.min_decoded_size,
+      )?,
+      HighThroughputJpeg2000Decoder::OpenJpeg.name(),
+    )),
 
     #[cfg(feature = "native")]
     &HIGH_THROUGHPUT_JPEG_2000_LOSSLESS_ONLY
     | &HIGH_THROUGHPUT_JPEG_2000_WITH_RPCL_OPTIONS_LOSSLESS_ONLY
-    | &HIGH_THROUGHPUT_JPEG_2000 => {
-      #[cfg(feature = "std")]
-      if decode_config.high_throughput_jpeg_2000_decoder
-        == HighThroughputJpeg2000Decoder::OpenJph
-      {
-        return openjph::decode_monochrome(image_pixel_module, data);
-      }
-
-      if decode_config.high_throughput_jpeg_2000_decoder
-        == HighThroughputJpeg2000Decoder::OpenJpeg
-      {
-        return openjpeg::decode_monochrome(image_pixel_module, data);
-      }
-
-      Err(PixelDataDecodeError::DecoderNotAvailable {
-        name: decode_config.high_throughput_jpeg_2000_decoder.to_string(),
-      })
-    }
+    | &HIGH_THROUGHPUT_JPEG_2000 => decode_with_decoders(
+      transfer_syntax,
+      &decode_config.high_throughput_jpeg_2000_decoders,
+      HighThroughputJpeg2000Decoder::name,
+      |decoder| match decoder {
+        #[cfg(feature = "std")]
+        HighThroughputJpeg2000Decoder::OpenJph => {
+          openjph::decode_monochrome(image_pixel_module, data)
+        }
+
+        HighThroughputJpeg2000Decoder::OpenJpeg => openjpeg::decode_monochrome(
+          image_pixel_module,
+          data,
+          decode_config.min_decoded_size,
+        ),
+
+        #[allow(unreachable_patterns)]
+        _ => Err(PixelDataDecodeError::DecoderNotAvailable {
+          name: decoder.to_string(),
+        }),
+      },
+    ),
 
     &JPEG_XL_LOSSLESS | &JPEG_XL_JPEG_RECOMPRESSION | &JPEG_XL => {
-      #[cfg(all(feature = "native", feature = "std"))]
-      if decode_config.jpeg_xl_decoder == JpegXlDecoder::LibJxl {
-        return libjxl::decode_monochrome(image_pixel_module, data);
-      }
-
-      if decode_config.jpeg_xl_decoder == JpegXlDecoder::JxlOxide {
-        return jxl_oxide::decode_monochrome(image_pixel_module, data);
-      }
+      decode_with_decoders(
+        transfer_syntax,
+        &decode_config.jpeg_xl_decoders,
+        JpegXlDecoder::name,
+        |decoder| match decoder {
+          #[cfg(all(feature = "native", feature = "std"))]
+          JpegXlDecoder::LibJxl => {
+            libjxl::decode_monochrome(image_pixel_module, data, decode_config)
+          }
+
+          JpegXlDecoder::JxlOxide => jxl_oxide::decode_monochrome(
+            image_pixel_module,
+            data,
+            decode_config,
+          ),
 
-      Err(PixelDataDecodeError::DecoderNotAvailable {
-        name: decode_config.jpeg_xl_decoder.to_string(),
-      })
+          #[allow(unreachable_patterns)]
+          _ => Err(PixelDataDecodeError::DecoderNotAvailable {
+            name: decoder.to_string(),
+          }),
+        },
+      )
     }
 
-    &DEFLATED_IMAGE_FRAME_COMPRESSION => native::decode_monochrome(
-      image_pixel_module,
-      &inflate_frame_data(data, image_pixel_module)?,
-      0,
-    ),
+    &DEFLATED_IMAGE_FRAME_COMPRESSION => Ok((
+      native::decode_monochrome(
+        image_pixel_module,
+        &inflate_frame_data(data, image_pixel_module)?,
+        0,
+      )?,
+      NATIVE_DECODER,
+    )),
+
+    #[cfg(feature = "ffmpeg")]
+    _ if VideoCodec::from_transfer_syntax(transfer_syntax).is_some() => Ok((
+      first_video_picture(ffmpeg::decode_monochrome(
+        image_pixel_module,
+        data,
+        Some(1),
+        decode_config,
+      )?)?,
+      FFMPEG_DECODER,
+    )),
 
     _ => {
       Err(PixelDataDecodeError::TransferSyntaxNotSupported { transfer_syntax })
@@ -369,6 +578,13 @@ pub fn decode_monochrome(
 
 /// Decodes a frame of color pixel data into a [`ColorImage`].
 ///
+/// When the frame holds a video stream only its first picture is decoded. Use
+/// [`decode_video_color()`] to decode all of its pictures.
+///
+/// The name of the decoder that was used, and the details of the image read
+/// from the frame's codestream, are recorded on the frame. See
+/// [`PixelDataFrame::decoder()`] and [`PixelDataFrame::decoded_frame_info()`].
+///
 pub fn decode_color(
   frame: &mut PixelDataFrame,
   transfer_syntax: &'static TransferSyntax,
@@ -376,7 +592,28 @@ pub fn decode_color(
   decode_config: &PixelDataDecodeConfig,
 ) -> Result<ColorImage, PixelDataDecodeError> {
   let data = frame.combine_chunks();
+  let decoded_frame_info =
+    DecodedFrameInfo::from_codestream(data, transfer_syntax);
+
+  let (image, decoder) = decode_color_data(
+    data,
+    transfer_syntax,
+    image_pixel_module,
+    decode_config,
+  )?;
+
+  frame.set_decoder(decoder);
+  frame.set_decoded_frame_info(decoded_frame_info);
+
+  Ok(image)
+}
 
+fn decode_color_data(
+  data: &[u8],
+  transfer_syntax: &'static TransferSyntax,
+  image_pixel_module: &ImagePixelModule,
+  decode_config: &PixelDataDecodeConfig,
+) -> Result<(ColorImage, &'static str), PixelDataDecodeError> {
   use transfer_syntax::*;
 
   match transfer_syntax {
@@ -384,77 +621,309 @@ pub fn decode_color(
     | &EXPLICIT_VR_LITTLE_ENDIAN
     | &ENCAPSULATED_UNCOMPRESSED_EXPLICIT_VR_LITTLE_ENDIAN
     | &DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN
-    | &EXPLICIT_VR_BIG_ENDIAN => native::decode_color(image_pixel_module, data),
-
-    &RLE_LOSSLESS => rle_lossless::decode_color(image_pixel_module, data),
+    | &EXPLICIT_VR_BIG_ENDIAN => Ok((
+      native::decode_color(image_pixel_module, data)?,
+      NATIVE_DECODER,
+    )),
+
+    &RLE_LOSSLESS => Ok((
+      rle_lossless::decode_color(image_pixel_module, data)?,
+      RLE_LOSSLESS_DECODER,
+    )),
+
+    // zune-jpeg can't decode at a reduced resolution, so jpeg-decoder is used
+    // when a reduced resolution decode is possible
+    &JPEG_BASELINE_8BIT
+      if resolution_reduction(
+        image_pixel_module,
+        decode_config.min_decoded_size,
+        jpeg_decoder::MAX_RESOLUTION_REDUCTION,
+      ) > 0 =>
+    {
+      Ok((
+        jpeg_decoder::decode_color(
+          image_pixel_module,
+          data,
+          decode_config.min_decoded_size,
+        )?,
+        JPEG_DECODER_DECODER,
+      ))
+    }
 
-    &JPEG_BASELINE_8BIT => zune_jpeg::decode_color(image_pixel_module, data),
+    &JPEG_BASELINE_8BIT => Ok((
+      zune_jpeg::decode_color(image_pixel_module, data)?,
+      ZUNE_JPEG_DECODER,
+    )),
 
     #[cfg(feature = "native")]
-    &JPEG_EXTENDED_12BIT => {
-      libjpeg_12bit::decode_color(image_pixel_module, data)
-    }
+    &JPEG_EXTENDED_12BIT => Ok((
+      libjpeg_12bit::decode_color(image_pixel_module, data)?,
+      LIBJPEG_12BIT_DECODER,
+    )),
 
     &JPEG_LOSSLESS_NON_HIERARCHICAL | &JPEG_LOSSLESS_NON_HIERARCHICAL_SV1 => {
-      jpeg_decoder::decode_color(image_pixel_module, data)
+      Ok((
+        jpeg_lossless::decode_color(image_pixel_module, data)?,
+        JPEG_LOSSLESS_DECODER,
+      ))
     }
 
-    #[cfg(all(feature = "native", feature = "std"))]
-    &JPEG_LS_LOSSLESS | &JPEG_LS_LOSSY_NEAR_LOSSLESS => {
-      charls::decode_color(image_pixel_module, data)
-    }
+    &JPEG_LS_LOSSLESS | &JPEG_LS_LOSSY_NEAR_LOSSLESS => decode_with_decoders(
+      transfer_syntax,
+      &decode_config.jpeg_ls_decoders,
+      JpegLsDecoder::name,
+      |decoder| match decoder {
+        #[cfg(all(feature = "native", feature = "std"))]
+        JpegLsDecoder::CharLs => charls::decode_color(image_pixel_module, data),
+
+        JpegLsDecoder::Dcmfx => jpeg_ls::decode_color(image_pixel_module, data),
+
+        #[allow(unreachable_patterns)]
+        _ => Err(PixelDataDecodeError::DecoderNotAvailable {
+          name: decoder.to_string(),
+        }),
+      },
+    ),
 
     #[cfg(feature = "native")]
-    &JPEG_2000 | &JPEG_2000_LOSSLESS_ONLY => {
-      openjpeg::decode_color(image_pixel_module, data)
-    }
+    &JPEG_2000 | &JPEG_2000_LOSSLESS_ONLY => Ok((
+      openjpeg::decode_color(
+        image_pixel_module,
+        data,
+        decode_config.min_decoded_size,
+      )?,
+      HighThroughputJpeg2000Decoder::OpenJpeg.name(),
+    )),
 
     #[cfg(feature = "native")]
     &HIGH_THROUGHPUT_JPEG_2000_LOSSLESS_ONLY
     | &HIGH_THROUGHPUT_JPEG_2000_WITH_RPCL_OPTIONS_LOSSLESS_ONLY
-    | &HIGH_THROUGHPUT_JPEG_2000 => {
-      #[cfg(feature = "std")]
-      if decode_config.high_throughput_jpeg_2000_decoder
-        == HighThroughputJpeg2000Decoder::OpenJph
-      {
-        return openjph::decode_color(image_pixel_module, data);
-      }
+    | &HIGH_THROUGHPUT_JPEG_2000 => decode_with_decoders(
+      transfer_syntax,
+      &decode_config.high_throughput_jpeg_2000_decoders,
+      HighThroughputJpeg2000Decoder::name,
+      |decoder| match decoder {
+        #[cfg(feature = "std")]
+        HighThroughputJpeg2000Decoder::OpenJph => {
+          openjph::decode_color(image_pixel_module, data)
+        }
+
+        HighThroughputJpeg2000Decoder::OpenJpeg => openjpeg::decode_color(
+          image_pixel_module,
+          data,
+          decode_config.min_decoded_size,
+        ),
+
+        #[allow(unreachable_patterns)]
+        _ => Err(PixelDataDecodeError::DecoderNotAvailable {
+          name: decoder.to_string(),
+        }),
+      },
+    ),
 
-      if decode_config.high_throughput_jpeg_2000_decoder
-        == HighThroughputJpeg2000Decoder::OpenJpeg
-      {
-        return openjpeg::decode_color(image_pixel_module, data);
-      }
+    &JPEG_XL_LOSSLESS | &JPEG_XL_JPEG_RECOMPRESSION | &JPEG_XL => {
+      decode_with_decoders(
+        transfer_syntax,
+        &decode_config.jpeg_xl_decoders,
+        JpegXlDecoder::name,
+        |decoder| match decoder {
+          #[cfg(all(feature = "native", feature = "std"))]
+          JpegXlDecoder::LibJxl => {
+            libjxl::decode_color(image_pixel_module, data, decode_config)
+          }
+
+          JpegXlDecoder::JxlOxide => {
+            jxl_oxide::decode_color(image_pixel_module, data, decode_config)
+          }
+
+          #[allow(unreachable_patterns)]
+          _ => Err(PixelDataDecodeError::DecoderNotAvailable {
+            name: decoder.to_string(),
+          }),
+        },
+      )
+    }
 
-      Err(PixelDataDecodeError::DecoderNotAvailable {
-        name: decode_config.high_throughput_jpeg_2000_decoder.to_string(),
-      })
+    &DEFLATED_IMAGE_FRAME_COMPRESSION => Ok((
+      native::decode_color(
+        image_pixel_module,
+        &inflate_frame_data(data, image_pixel_module)?,
+      )?,
+      NATIVE_DECODER,
+    )),
+
+    #[cfg(feature = "ffmpeg")]
+    _ if VideoCodec::from_transfer_syntax(transfer_syntax).is_some() => Ok((
+      first_video_picture(ffmpeg::decode_color(
+        image_pixel_module,
+        data,
+        Some(1),
+        decode_config,
+      )?)?,
+      FFMPEG_DECODER,
+    )),
+
+    _ => {
+      Err(PixelDataDecodeError::TransferSyntaxNotSupported { transfer_syntax })
     }
+  }
+}
 
-    &JPEG_XL_LOSSLESS | &JPEG_XL_JPEG_RECOMPRESSION | &JPEG_XL => {
-      #[cfg(all(feature = "native", feature = "std"))]
-      if decode_config.jpeg_xl_decoder == JpegXlDecoder::LibJxl {
-        return libjxl::decode_color(image_pixel_module, data);
-      }
+/// Decodes every picture in a frame of monochrome pixel data that holds a video
+/// stream. The video stream is decoded once, and its pictures are returned in
+/// order.
+///
+/// The name of the decoder that was used is recorded on the frame. See
+/// [`PixelDataFrame::decoder()`].
+///
+pub fn decode_video_monochrome(
+  frame: &mut PixelDataFrame,
+  transfer_syntax: &'static TransferSyntax,
+  image_pixel_module: &ImagePixelModule,
+  decode_config: &PixelDataDecodeConfig,
+) -> Result<Vec<MonochromeImage>, PixelDataDecodeError> {
+  #[cfg(feature = "ffmpeg")]
+  if VideoCodec::from_transfer_syntax(transfer_syntax).is_some() {
+    let images = ffmpeg::decode_monochrome(
+      image_pixel_module,
+      frame.combine_chunks(),
+      None,
+      decode_config,
+    )?;
 
-      if decode_config.jpeg_xl_decoder == JpegXlDecoder::JxlOxide {
-        return jxl_oxide::decode_color(image_pixel_module, data);
+    frame.set_decoder(FFMPEG_DECODER);
+
+    return Ok(images);
+  }
+
+  #[cfg(not(feature = "ffmpeg"))]
+  let _ = (frame, image_pixel_module, decode_config);
+
+  Err(PixelDataDecodeError::TransferSyntaxNotSupported { transfer_syntax })
+}
+
+/// Decodes every picture in a frame of color pixel data that holds a video
+/// stream. The video stream is decoded once, and its pictures are returned in
+/// order.
+///
+/// The name of the decoder that was used is recorded on the frame. See
+/// [`PixelDataFrame::decoder()`].
+///
+pub fn decode_video_color(
+  frame: &mut PixelDataFrame,
+  transfer_syntax: &'static TransferSyntax,
+  image_pixel_module: &ImagePixelModule,
+  decode_config: &PixelDataDecodeConfig,
+) -> Result<Vec<ColorImage>, PixelDataDecodeError> {
+  #[cfg(feature = "ffmpeg")]
+  if VideoCodec::from_transfer_syntax(transfer_syntax).is_some() {
+    let images = ffmpeg::decode_color(
+      image_pixel_module,
+      frame.combine_chunks(),
+      None,
+      decode_config,
+    )?;
+
+    frame.set_decoder(FFMPEG_DECODER);
+
+    return Ok(images);
+  }
+
+  #[cfg(not(feature = "ffmpeg"))]
+  let _ = (frame, image_pixel_module, decode_config);
+
+  Err(PixelDataDecodeError::TransferSyntaxNotSupported { transfer_syntax })
+}
+
+/// Returns the first of the pictures decoded from a video stream.
+///
+#[cfg(feature = "ffmpeg")]
+fn first_video_picture<T>(pictures: Vec<T>) -> Result<T, PixelDataDecodeError> {
+  pictures
+    .into_iter()
+    .next()
+    .ok_or_else(|| PixelDataDecodeError::DataInvalid {
+      details: "Video stream has no pictures".to_string(),
+    })
+}
+
+// Names of the decoders that aren't configurable in `PixelDataDecodeConfig`,
+// as recorded by `PixelDataFrame::decoder()`
+const NATIVE_DECODER: &str = "native";
+const RLE_LOSSLESS_DECODER: &str = "rle-lossless";
+const ZUNE_JPEG_DECODER: &str = "zune-jpeg";
+const JPEG_DECODER_DECODER: &str = "jpeg-decoder";
+const JPEG_LOSSLESS_DECODER: &str = "jpeg-lossless";
+#[cfg(feature = "native")]
+const LIBJPEG_12BIT_DECODER: &str = "libjpeg-12bit";
+#[cfg(feature = "ffmpeg")]
+const FFMPEG_DECODER: &str = "ffmpeg";
+
+/// Decodes pixel data using each of the given decoders in turn until one
+/// succeeds. The next decoder is only tried when the previous one reports that
+/// the data is invalid. If every decoder fails then the error from the first
+/// decoder is returned.
+///
+/// The name of the decoder that succeeded is returned along with its result.
+///
+fn decode_with_decoders<D: Copy, T>(
+  transfer_syntax: &'static TransferSyntax,
+  decoders: &[D],
+  decoder_name: impl Fn(&D) -> &'static str,
+  decode: impl Fn(D) -> Result<T, PixelDataDecodeError>,
+) -> Result<(T, &'static str), PixelDataDecodeError> {
+  let mut first_error = None;
+
+  for decoder in decoders {
+    match decode(*decoder) {
+      Ok(result) => return Ok((result, decoder_name(decoder))),
+
+      Err(e @ PixelDataDecodeError::DataInvalid { .. }) => {
+        first_error.get_or_insert(e);
       }
 
-      Err(PixelDataDecodeError::DecoderNotAvailable {
-        name: decode_config.jpeg_xl_decoder.to_string(),
-      })
+      Err(e) => return Err(first_error.unwrap_or(e)),
     }
+  }
 
-    &DEFLATED_IMAGE_FRAME_COMPRESSION => native::decode_color(
-      image_pixel_module,
-      &inflate_frame_data(data, image_pixel_module)?,
-    ),
+  Err(
+    first_error.unwrap_or(PixelDataDecodeError::TransferSyntaxNotSupported {
+      transfer_syntax,
+    }),
+  )
+}
 
-    _ => {
-      Err(PixelDataDecodeError::TransferSyntaxNotSupported { transfer_syntax })
+/// Returns the number of times the width and height of the image described by
+/// the Image Pixel Module can be halved, rounding up, while remaining at least
+/// the minimum decoded size. The result is at most `max_reduction`, and is zero
+/// when there is no minimum decoded size.
+///
+fn resolution_reduction(
+  image_pixel_module: &ImagePixelModule,
+  min_decoded_size: Option<(u16, u16)>,
+  max_reduction: u32,
+) -> u32 {
+  let Some((min_width, min_height)) = min_decoded_size else {
+    return 0;
+  };
+
+  let width = u32::from(image_pixel_module.columns());
+  let height = u32::from(image_pixel_module.rows());
+
+  let mut reduction = 0;
+  while reduction < max_reduction {
+    let divisor = 1 << (reduction + 1);
+
+    if width.div_ceil(divisor) < u32::from(min_width.max(1))
+      || height.div_ceil(divisor) < u32::from(min_height.max(1))
+    {
+      break;
     }
+
+    reduction += 1;
   }
+
+  reduction
 }
 
 /// Inflates deflated data for a single frame. This is used by the 'Deflated
@@ -498,3 +967,137 @@ fn inflate_frame_data(
     }),
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn data_invalid() -> Result<u8, PixelDataDecodeError> {
+    Err(PixelDataDecodeError::DataInvalid {
+      details: "primary".to_string(),
+    })
+  }
+
+  #[test]
+  fn decode_with_decoders_test() {
+    let decode = |decoder| match decoder {
+      JpegLsDecoder::CharLs => data_invalid(),
+      JpegLsDecoder::Dcmfx => Ok(2),
+    };
+
+    let transfer_syntax = &transfer_syntax::JPEG_LS_LOSSLESS;
+
+    assert_eq!(
+      decode_with_decoders(
+        transfer_syntax,
+        &[JpegLsDecoder::Dcmfx],
+        JpegLsDecoder::name,
+        decode
+      ),
+      Ok((2, "dcmfx"))
+    );
+
+    assert_eq!(
+      decode_with_decoders(
+        transfer_syntax,
+        &[JpegLsDecoder::CharLs, JpegLsDecoder::Dcmfx],
+        JpegLsDecoder::name,
+        decode
+      ),
+      Ok((2, "dcmfx"))
+    );
+
+    assert_eq!(
+      decode_with_decoders(
+        transfer_syntax,
+        &[JpegLsDecoder::CharLs],
+        JpegLsDecoder::name,
+        decode
+      ),
+      Err(data_invalid().unwrap_err())
+    );
+
+    assert_eq!(
+      decode_with_decoders(
+        transfer_syntax,
+        &[JpegLsDecoder::CharLs, JpegLsDecoder::CharLs],
+        JpegLsDecoder::name,
+        decode
+      ),
+      Err(data_invalid().unwrap_err())
+    );
+
+    // Errors other than invalid data stop the chain
+    assert_eq!(
+      decode_with_decoders(
+        transfer_syntax,
+        &[JpegLsDecoder::Dcmfx, JpegLsDecoder::CharLs],
+        JpegLsDecoder::name,
+        |_| -> Result<u8, _> {
+          Err(PixelDataDecodeError::DecoderNotAvailable {
+            name: "dcmfx".to_string(),
+          })
+        }
+      ),
+      Err(PixelDataDecodeError::DecoderNotAvailable {
+        name: "dcmfx".to_string(),
+      })
+    );
+
+    assert_eq!(
+      decode_with_decoders(transfer_syntax, &[], JpegLsDecoder::name, decode),
+      Err(PixelDataDecodeError::TransferSyntaxNotSupported { transfer_syntax })
+    );
+  }
+
+  #[test]
+  fn resolution_reduction_test() {
+    use crate::iods::image_pixel_module::{
+      BitsAllocated, PixelRepresentation, SamplesPerPixel,
+    };
+
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      1000,
+      1001,
+      BitsAllocated::Eight,
+      8,
+    )
+    .unwrap();
+
+    assert_eq!(resolution_reduction(&image_pixel_module, None, 3), 0);
+
+    assert_eq!(
+      resolution_reduction(&image_pixel_module, Some((1001, 1000)), 3),
+      0
+    );
+
+    assert_eq!(
+      resolution_reduction(&image_pixel_module, Some((501, 500)), 3),
+      1
+    );
+
+    assert_eq!(
+      resolution_reduction(&image_pixel_module, Some((252, 100)), 3),
+      1
+    );
+
+    assert_eq!(
+      resolution_reduction(&image_pixel_module, Some((126, 125)), 3),
+      3
+    );
+
+    assert_eq!(
+      resolution_reduction(&image_pixel_module, Some((0, 0)), 3),
+      3
+    );
+
+    assert_eq!(
+      resolution_reduction(&image_pixel_module, Some((1, 1)), 5),
+      5
+    );
+  }
+}
@@ -820,6 +820,64 @@ mod tests {
     );
   }
 
+  #[test]
+  fn decode_monochrome_32_bit_signed_with_24_bits_stored() {
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Signed,
+      },
+      2,
+      2,
+      BitsAllocated::ThirtyTwo,
+      24,
+    )
+    .unwrap();
+
+    let data: Vec<_> = [0u32, 0x7FFFFF, 0x800000, 0xFFFFFF]
+      .into_iter()
+      .flat_map(|i| i.to_le_bytes())
+      .collect();
+
+    assert_eq!(
+      decode_monochrome(&image_pixel_module, &data, 0).unwrap(),
+      MonochromeImage::new_i32(
+        2,
+        2,
+        vec![0, 0x7FFFFF, -0x800000, -1],
+        24,
+        false
+      )
+      .unwrap()
+    );
+  }
+
+  #[test]
+  fn decode_monochrome_32_bit_unsigned() {
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      2,
+      2,
+      BitsAllocated::ThirtyTwo,
+      32,
+    )
+    .unwrap();
+
+    let data: Vec<_> = [0u32, 1, 0x10000, u32::MAX]
+      .into_iter()
+      .flat_map(|i| i.to_le_bytes())
+      .collect();
+
+    assert_eq!(
+      decode_monochrome(&image_pixel_module, &data, 0).unwrap(),
+      MonochromeImage::new_u32(2, 2, vec![0, 1, 0x10000, u32::MAX], 32, false)
+        .unwrap()
+    );
+  }
+
   #[test]
   fn decode_rgb_8_bit_interleaved() {
     let image_pixel_module = ImagePixelModule::new_basic(
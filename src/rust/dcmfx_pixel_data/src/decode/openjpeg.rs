@@ -2,21 +2,28 @@
 use alloc::{format, string::ToString, vec, vec::Vec};
 
 use crate::{
-  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeError,
+  ColorImage, MonochromeImage, PixelDataDecodeError,
   iods::image_pixel_module::{
     BitsAllocated, ImagePixelModule, PhotometricInterpretation,
     PixelRepresentation,
   },
 };
 
-/// Decodes monochrome pixel data using OpenJPEG.
+/// The maximum number of times OpenJPEG is asked to halve the resolution of an
+/// image during decoding. The actual limit is the number of resolution levels
+/// in the JPEG 2000 codestream, which is commonly six.
+///
+const MAX_RESOLUTION_REDUCTION: u32 = 5;
+
+/// Decodes monochrome pixel data using OpenJPEG. If a minimum decoded size is
+/// specified then the image is decoded at the lowest resolution level that is
+/// at least that size.
 ///
 pub fn decode_monochrome(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  min_decoded_size: Option<(u16, u16)>,
 ) -> Result<MonochromeImage, PixelDataDecodeError> {
-  let width = image_pixel_module.columns();
-  let height = image_pixel_module.rows();
   let bits_stored = image_pixel_module.bits_stored();
   let is_monochrome1 = image_pixel_module
     .photometric_interpretation()
@@ -35,7 +42,8 @@ pub fn decode_monochrome(
       },
       BitsAllocated::Eight,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       MonochromeImage::new_u8(
         width,
         height,
@@ -55,7 +63,8 @@ pub fn decode_monochrome(
       },
       BitsAllocated::Eight,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       MonochromeImage::new_i8(
         width,
         height,
@@ -75,7 +84,8 @@ pub fn decode_monochrome(
       },
       BitsAllocated::Sixteen,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       MonochromeImage::new_u16(
         width,
         height,
@@ -95,7 +105,8 @@ pub fn decode_monochrome(
       },
       BitsAllocated::Sixteen,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       MonochromeImage::new_i16(
         width,
         height,
@@ -115,7 +126,8 @@ pub fn decode_monochrome(
       },
       BitsAllocated::ThirtyTwo,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       MonochromeImage::new_u32(
         width,
         height,
@@ -135,7 +147,8 @@ pub fn decode_monochrome(
       },
       BitsAllocated::ThirtyTwo,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       MonochromeImage::new_i32(
         width,
         height,
@@ -159,23 +172,19 @@ pub fn decode_monochrome(
   }
 }
 
-/// Decodes color pixel data using OpenJPEG.
+/// Decodes color pixel data using OpenJPEG. If a minimum decoded size is
+/// specified then the image is decoded at the lowest resolution level that is
+/// at least that size.
 ///
 pub fn decode_color(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
+  min_decoded_size: Option<(u16, u16)>,
 ) -> Result<ColorImage, PixelDataDecodeError> {
-  let width = image_pixel_module.columns();
-  let height = image_pixel_module.rows();
   let bits_stored = image_pixel_module.bits_stored();
 
-  let color_space = if image_pixel_module.photometric_interpretation()
-    == &PhotometricInterpretation::YbrFull
-  {
-    ColorSpace::Ybr { is_422: false }
-  } else {
-    ColorSpace::Rgb
-  };
+  let color_space =
+    super::jpeg_2000::decode_color_space(image_pixel_module, data);
 
   match (
     &image_pixel_module.photometric_interpretation(),
@@ -185,7 +194,8 @@ pub fn decode_color(
       PhotometricInterpretation::PaletteColor { palette },
       BitsAllocated::Eight,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       ColorImage::new_palette8(
         width,
         height,
@@ -200,7 +210,8 @@ pub fn decode_color(
       PhotometricInterpretation::PaletteColor { palette },
       BitsAllocated::Sixteen,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       ColorImage::new_palette16(
         width,
         height,
@@ -218,7 +229,8 @@ pub fn decode_color(
       | PhotometricInterpretation::YbrRct,
       BitsAllocated::Eight,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       ColorImage::new_u8(width, height, pixels, color_space, bits_stored)
         .map_err(PixelDataDecodeError::ImageCreationFailed)
     }
@@ -230,7 +242,8 @@ pub fn decode_color(
       | PhotometricInterpretation::YbrRct,
       BitsAllocated::Sixteen,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       ColorImage::new_u16(width, height, pixels, color_space, bits_stored)
         .map_err(PixelDataDecodeError::ImageCreationFailed)
     }
@@ -242,7 +255,8 @@ pub fn decode_color(
       | PhotometricInterpretation::YbrRct,
       BitsAllocated::ThirtyTwo,
     ) => {
-      let pixels = decode(image_pixel_module, data)?;
+      let (pixels, width, height) =
+        decode(image_pixel_module, data, min_decoded_size)?;
       ColorImage::new_u32(width, height, pixels, color_space, bits_stored)
         .map_err(PixelDataDecodeError::ImageCreationFailed)
     }
@@ -263,13 +277,22 @@ pub fn decode_color(
 fn decode<T: Clone + Default + bytemuck::Pod>(
   image_pixel_module: &ImagePixelModule,
   data: &[u8],
-) -> Result<Vec<T>, PixelDataDecodeError> {
+  min_decoded_size: Option<(u16, u16)>,
+) -> Result<(Vec<T>, u16, u16), PixelDataDecodeError> {
   let samples_per_pixel = u8::from(image_pixel_module.samples_per_pixel());
   let bits_allocated = u8::from(image_pixel_module.bits_allocated()).max(8);
   let mut pixel_representation =
     u8::from(image_pixel_module.pixel_representation()) as usize;
   let mut error_buffer = [0 as core::ffi::c_char; 256];
 
+  // OpenJPEG limits this to the number of resolution levels in the codestream,
+  // and returns the reduction that was actually used
+  let mut reduction = super::resolution_reduction(
+    image_pixel_module,
+    min_decoded_size,
+    MAX_RESOLUTION_REDUCTION,
+  ) as usize;
+
   // Allocate output buffer
   let mut output_buffer: Vec<T> = vec![
     T::default();
@@ -287,6 +310,7 @@ fn decode<T: Clone + Default + bytemuck::Pod>(
       samples_per_pixel.into(),
       bits_allocated.into(),
       &mut pixel_representation,
+      &mut reduction,
       output_buffer.as_mut_ptr() as *mut core::ffi::c_void,
       output_buffer.len() * core::mem::size_of::<T>(),
       error_buffer.as_mut_ptr(),
@@ -305,6 +329,14 @@ fn decode<T: Clone + Default + bytemuck::Pod>(
     });
   }
 
+  // When decoding at a reduced resolution only the start of the output buffer
+  // is used
+  let width = image_pixel_module.columns().div_ceil(1 << reduction);
+  let height = image_pixel_module.rows().div_ceil(1 << reduction);
+  output_buffer.truncate(
+    usize::from(width) * usize::from(height) * usize::from(samples_per_pixel),
+  );
+
   if pixel_representation
     != u8::from(image_pixel_module.pixel_representation()) as usize
   {
@@ -327,7 +359,7 @@ fn decode<T: Clone + Default + bytemuck::Pod>(
     }
   }
 
-  Ok(output_buffer)
+  Ok((output_buffer, width, height))
 }
 
 /// Converts unsigned values to signed two's complement values based on the
@@ -389,6 +421,7 @@ mod ffi {
       samples_per_pixel: usize,
       bits_allocated: usize,
       pixel_representation: *mut usize,
+      reduction: *mut usize,
       output_data: *mut core::ffi::c_void,
       output_data_size: usize,
       error_buffer: *mut core::ffi::c_char,
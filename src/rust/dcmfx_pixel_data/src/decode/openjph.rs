@@ -2,7 +2,7 @@
 use alloc::{format, string::ToString, vec, vec::Vec};
 
 use crate::{
-  ColorImage, ColorSpace, MonochromeImage, PixelDataDecodeError,
+  ColorImage, MonochromeImage, PixelDataDecodeError,
   iods::image_pixel_module::{
     BitsAllocated, ImagePixelModule, PhotometricInterpretation,
     PixelRepresentation,
@@ -169,13 +169,8 @@ pub fn decode_color(
   let height = image_pixel_module.rows();
   let bits_stored = image_pixel_module.bits_stored();
 
-  let color_space = if image_pixel_module.photometric_interpretation()
-    == &PhotometricInterpretation::YbrFull
-  {
-    ColorSpace::Ybr { is_422: false }
-  } else {
-    ColorSpace::Rgb
-  };
+  let color_space =
+    super::jpeg_2000::decode_color_space(image_pixel_module, data);
 
   match (
     &image_pixel_module.photometric_interpretation(),
@@ -0,0 +1,147 @@
+//! Detection of duplicate frames of pixel data across a set of instances, e.g.
+//! the same frame stored in multiple instances or series. Useful for archive
+//! cleanup.
+//!
+//! Frames are compared by hash, which is either:
+//!
+//! 1. The hash of the frame's data exactly as it is stored, as returned by
+//!    [`stored_data_hash()`]. This doesn't require decoding, but only matches
+//!    frames that have been encoded identically.
+//!
+//! 2. The hash of the frame's decoded stored values, as returned by
+//!    [`MonochromeImage::stored_value_hash()`] and
+//!    [`ColorImage::stored_value_hash()`]. This matches frames with identical
+//!    stored values regardless of their transfer syntax.
+//!
+//! [`MonochromeImage::stored_value_hash()`]: crate::MonochromeImage::stored_value_hash
+//! [`ColorImage::stored_value_hash()`]: crate::ColorImage::stored_value_hash
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::PixelDataFrame;
+
+/// Returns a SHA-256 hash of a frame's data exactly as it is stored, which for
+/// encapsulated transfer syntaxes is its compressed data.
+///
+pub fn stored_data_hash(frame: &PixelDataFrame) -> [u8; 32] {
+  let mut hasher = Sha256::new();
+
+  if frame.bit_offset() == 0 {
+    for chunk in frame.chunks() {
+      hasher.update(chunk);
+    }
+  } else {
+    hasher.update(frame.to_bytes());
+  }
+
+  hasher.finalize().into()
+}
+
+/// Collects the hashes of frames from a set of instances and reports the
+/// frames that have the same hash.
+///
+/// The type `T` identifies the instance a frame is in, e.g. its path or SOP
+/// Instance UID.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateFrameFinder<T> {
+  // The frames that have each hash, in the order they were added, and the
+  // index of each hash's group of frames
+  groups: Vec<Vec<(T, usize)>>,
+  group_indices: BTreeMap<[u8; 32], usize>,
+}
+
+impl<T> Default for DuplicateFrameFinder<T> {
+  fn default() -> Self {
+    Self {
+      groups: Vec::new(),
+      group_indices: BTreeMap::new(),
+    }
+  }
+}
+
+impl<T> DuplicateFrameFinder<T> {
+  /// Creates a new, empty duplicate frame finder.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds the hash of a frame in an instance.
+  ///
+  pub fn add_frame(&mut self, hash: [u8; 32], instance: T, frame_index: usize) {
+    let group_index = *self.group_indices.entry(hash).or_insert_with(|| {
+      self.groups.push(Vec::new());
+      self.groups.len() - 1
+    });
+
+    self.groups[group_index].push((instance, frame_index));
+  }
+
+  /// Returns the number of frames that have been added.
+  ///
+  pub fn frame_count(&self) -> usize {
+    self.groups.iter().map(|group| group.len()).sum()
+  }
+
+  /// Returns each group of frames that have the same hash, as the instance and
+  /// index of each frame. Only groups with more than one frame are returned.
+  /// Groups are ordered by when their first frame was added, and frames in a
+  /// group are in the order they were added.
+  ///
+  pub fn duplicates(&self) -> Vec<&[(T, usize)]> {
+    self
+      .groups
+      .iter()
+      .filter(|group| group.len() > 1)
+      .map(|group| group.as_slice())
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use super::*;
+
+  #[test]
+  fn stored_data_hash_test() {
+    let mut a = PixelDataFrame::new();
+    a.push_bytes(vec![1, 2].into());
+    a.push_bytes(vec![3].into());
+
+    let b = PixelDataFrame::new_from_bytes(vec![1, 2, 3]);
+    let c = PixelDataFrame::new_from_bytes(vec![1, 2, 4]);
+
+    assert_eq!(stored_data_hash(&a), stored_data_hash(&b));
+    assert_ne!(stored_data_hash(&a), stored_data_hash(&c));
+  }
+
+  #[test]
+  fn duplicates_test() {
+    let mut finder = DuplicateFrameFinder::new();
+
+    finder.add_frame([1; 32], "a", 0);
+    finder.add_frame([2; 32], "a", 1);
+    finder.add_frame([3; 32], "b", 0);
+    finder.add_frame([2; 32], "b", 1);
+    finder.add_frame([1; 32], "c", 0);
+    finder.add_frame([2; 32], "c", 1);
+
+    assert_eq!(finder.frame_count(), 6);
+    assert_eq!(
+      finder.duplicates(),
+      vec![
+        &[("a", 0), ("c", 0)][..],
+        &[("a", 1), ("b", 1), ("c", 1)][..],
+      ]
+    );
+  }
+}
@@ -2,7 +2,8 @@
 use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
 
 use crate::{
-  ColorImage, ColorSpace, MonochromeImage, PixelDataEncodeError,
+  ColorImage, ColorSpace, MonochromeImage, PixelDataEncodeConfig,
+  PixelDataEncodeError,
   color_image::ColorImageData,
   iods::image_pixel_module::{
     BitsAllocated, ImagePixelModule, PhotometricInterpretation,
@@ -43,7 +44,7 @@ pub fn encode_image_pixel_module(
 pub fn encode_monochrome(
   image: &MonochromeImage,
   image_pixel_module: &ImagePixelModule,
-  quality: Option<u8>,
+  encode_config: Option<&PixelDataEncodeConfig>,
 ) -> Result<Vec<u8>, PixelDataEncodeError> {
   let width = image.width();
   let height = image.height();
@@ -63,7 +64,7 @@ pub fn encode_monochrome(
       },
       BitsAllocated::Eight,
     ) if image_pixel_module.bits_stored() >= 2 => {
-      encode(data, width, height, image_pixel_module, quality)
+      encode(data, width, height, image_pixel_module, encode_config)
     }
 
     (
@@ -80,7 +81,7 @@ pub fn encode_monochrome(
       width,
       height,
       image_pixel_module,
-      quality,
+      encode_config,
     ),
 
     _ => Err(PixelDataEncodeError::NotSupported {
@@ -96,7 +97,7 @@ pub fn encode_monochrome(
 pub fn encode_color(
   image: &ColorImage,
   image_pixel_module: &ImagePixelModule,
-  quality: Option<u8>,
+  encode_config: Option<&PixelDataEncodeConfig>,
 ) -> Result<Vec<u8>, PixelDataEncodeError> {
   let width = image.width();
   let height = image.height();
@@ -104,7 +105,7 @@ pub fn encode_color(
   match (
     image.data(),
     image_pixel_module.photometric_interpretation(),
-    quality,
+    encode_config,
   ) {
     (
       ColorImageData::U8 {
@@ -127,7 +128,7 @@ pub fn encode_color(
       PhotometricInterpretation::PaletteColor { .. },
       None,
     ) if image_pixel_module.bits_stored() >= 2 => {
-      encode(data, width, height, image_pixel_module, quality)
+      encode(data, width, height, image_pixel_module, encode_config)
     }
 
     (
@@ -155,7 +156,7 @@ pub fn encode_color(
       width,
       height,
       image_pixel_module,
-      quality,
+      encode_config,
     ),
 
     _ => Err(PixelDataEncodeError::NotSupported {
@@ -171,24 +172,15 @@ fn encode(
   width: u16,
   height: u16,
   image_pixel_module: &ImagePixelModule,
-  quality: Option<u8>,
+  encode_config: Option<&PixelDataEncodeConfig>,
 ) -> Result<Vec<u8>, PixelDataEncodeError> {
   let mut output_buffer = vec![];
 
   let mut error_buffer = [0 as core::ffi::c_char; 256];
 
-  let near_lossless: u8 = if let Some(quality) = quality {
-    // Determine the maximum near_lossless value
-    let max_near_lossless =
-      2u16.pow(image_pixel_module.bits_stored() as u32 - 1) - 1;
-
-    // Convert input u8 quality in range 1-100 to normalized value
-    let quality = 1.0 - (quality - 1) as f32 / 99.0;
-
-    // Map into the lossy range for CharLS compressor
-    (1.0 + (max_near_lossless - 1) as f32 * quality) as u8
-  } else {
-    0
+  let near_lossless = match encode_config {
+    Some(encode_config) => near_lossless(encode_config, image_pixel_module),
+    None => 0,
   };
 
   let bytes_written = unsafe {
@@ -222,6 +214,29 @@ fn encode(
   Ok(output_buffer)
 }
 
+/// Returns the NEAR parameter to use for a lossy JPEG-LS encode. An explicit
+/// NEAR parameter in the encode config takes precedence, otherwise it's
+/// derived from the quality.
+///
+fn near_lossless(
+  encode_config: &PixelDataEncodeConfig,
+  image_pixel_module: &ImagePixelModule,
+) -> u8 {
+  // Determine the maximum near_lossless value
+  let max_near_lossless =
+    2u16.pow(image_pixel_module.bits_stored() as u32 - 1) - 1;
+
+  if let Some(near) = encode_config.jpeg_ls_near() {
+    return near.min(max_near_lossless.min(255) as u8);
+  }
+
+  // Convert input u8 quality in range 1-100 to normalized value
+  let quality = 1.0 - (encode_config.quality() - 1) as f32 / 99.0;
+
+  // Map into the lossy range for CharLS compressor
+  (1.0 + (max_near_lossless - 1) as f32 * quality) as u8
+}
+
 /// This function is passed as a callback to [`ffi::charls_encode()`] and
 /// is then called to allocate output data.
 ///
@@ -2,7 +2,10 @@ use crate::iods::image_pixel_module::{
   ImagePixelModule, PhotometricInterpretation, PlanarConfiguration,
 };
 
-/// Returns the Image Pixel Module resulting from encoding into JPEG 2000.
+/// Returns the Image Pixel Module resulting from encoding into JPEG 2000. A
+/// `YBR_ICT` or `YBR_RCT` photometric interpretation is changed to whichever of
+/// the two matches the encode, i.e. `YBR_ICT` when lossy and `YBR_RCT` when
+/// lossless.
 ///
 pub fn encode_image_pixel_module(
   mut image_pixel_module: ImagePixelModule,
@@ -14,16 +17,18 @@ pub fn encode_image_pixel_module(
     | PhotometricInterpretation::Rgb
     | PhotometricInterpretation::YbrFull => (),
 
-    // YBR_ICT is only permitted for lossy encodes
-    PhotometricInterpretation::YbrIct => {
-      if quality.is_none() {
-        return Err(());
-      }
+    // The color transform has to match the wavelet transform, so YBR_ICT is
+    // selected for lossy encodes and YBR_RCT for lossless encodes
+    PhotometricInterpretation::YbrIct | PhotometricInterpretation::YbrRct => {
+      image_pixel_module.set_photometric_interpretation(if quality.is_some() {
+        PhotometricInterpretation::YbrIct
+      } else {
+        PhotometricInterpretation::YbrRct
+      });
     }
 
-    // YBR_RCT and PALETTE_COLOR are only permitted for lossless encodes
-    PhotometricInterpretation::YbrRct
-    | PhotometricInterpretation::PaletteColor { .. } => {
+    // PALETTE_COLOR is only permitted for lossless encodes
+    PhotometricInterpretation::PaletteColor { .. } => {
       if quality.is_some() {
         return Err(());
       }
@@ -218,6 +218,7 @@ fn encode(
       lossless.into(),
       encode_config.quality.into(),
       encode_config.effort.into(),
+      encode_config.thread_count.unwrap_or(0),
       output_data_callback,
       &mut output_data as *mut Vec<u8> as *mut core::ffi::c_void,
       error_buffer.as_mut_ptr(),
@@ -268,6 +269,7 @@ mod ffi {
       lossless: usize,
       quality: usize,
       effort: usize,
+      thread_count: usize,
       output_data_callback: extern "C" fn(
         usize,
         *mut core::ffi::c_void,
@@ -28,6 +28,11 @@ mod openjpeg;
 #[cfg(all(feature = "native", feature = "std"))]
 mod openjph;
 mod rle_lossless;
+mod size_estimate;
+
+pub use size_estimate::{
+  PixelDataSizeEstimate, PixelDataSizeEstimateAccuracy, estimate_encoded_size,
+};
 
 /// Configuration used when encoding pixel data.
 ///
@@ -36,6 +41,12 @@ pub struct PixelDataEncodeConfig {
   quality: u8,
   effort: u8,
   zlib_compression_level: u32,
+  deflate_thread_count: usize,
+  thread_count: Option<usize>,
+  jpeg_ls_near: Option<u8>,
+  high_throughput_jpeg_2000_params: HighThroughputJpeg2000EncodeParams,
+  max_fragment_size: Option<u32>,
+  frame_buffer_memory_limit: Option<usize>,
 }
 
 impl Default for PixelDataEncodeConfig {
@@ -44,6 +55,13 @@ impl Default for PixelDataEncodeConfig {
       quality: 90,
       effort: 7,
       zlib_compression_level: 6,
+      deflate_thread_count: 1,
+      thread_count: None,
+      jpeg_ls_near: None,
+      high_throughput_jpeg_2000_params:
+        HighThroughputJpeg2000EncodeParams::default(),
+      max_fragment_size: None,
+      frame_buffer_memory_limit: None,
     }
   }
 }
@@ -115,6 +133,259 @@ impl PixelDataEncodeConfig {
   pub fn set_zlib_compression_level(&mut self, compression_level: u32) {
     self.zlib_compression_level = compression_level.clamp(0, 9);
   }
+
+  /// Returns the number of threads used to deflate each frame when encoding
+  /// pixel data into the 'Deflated Image Frame Compression' transfer syntax.
+  ///
+  /// When this is greater than one, frames are split into chunks that are
+  /// deflated in parallel using [`dcmfx_p10::parallel_deflate`]. This is much
+  /// faster for large frames, at the cost of slightly larger output.
+  ///
+  /// Default: 1.
+  ///
+  pub fn deflate_thread_count(&self) -> usize {
+    self.deflate_thread_count
+  }
+
+  /// Sets the number of threads used to deflate each frame when encoding pixel
+  /// data into the 'Deflated Image Frame Compression' transfer syntax.
+  ///
+  pub fn set_deflate_thread_count(&mut self, thread_count: usize) {
+    self.deflate_thread_count = thread_count.max(1);
+  }
+
+  /// Returns the maximum number of threads that an encoder may use when
+  /// encoding a single frame of pixel data. If this is `None` then the
+  /// encoder's default is used, which is typically the number of CPU cores.
+  ///
+  /// The thread count is used by the following transfer syntaxes:
+  ///
+  /// - JPEG XL Lossless
+  /// - JPEG XL
+  ///
+  /// Default: `None`.
+  ///
+  pub fn thread_count(&self) -> Option<usize> {
+    self.thread_count
+  }
+
+  /// Sets the maximum number of threads that an encoder may use when encoding
+  /// a single frame of pixel data.
+  ///
+  pub fn set_thread_count(&mut self, thread_count: Option<usize>) {
+    self.thread_count = thread_count.map(|thread_count| thread_count.max(1));
+  }
+
+  /// Returns the NEAR parameter to use when encoding into the 'JPEG-LS Lossy
+  /// (Near-Lossless)' transfer syntax, which is the maximum error allowed
+  /// between an original and a reconstructed sample value. If this is `None`
+  /// then the NEAR parameter is derived from the quality.
+  ///
+  /// The NEAR parameter is limited to the maximum allowed by JPEG-LS for the
+  /// bits stored of the pixel data being encoded. A NEAR parameter of zero
+  /// results in lossless compression, in which case the output isn't marked as
+  /// having undergone lossy compression.
+  ///
+  /// Default: `None`.
+  ///
+  pub fn jpeg_ls_near(&self) -> Option<u8> {
+    self.jpeg_ls_near
+  }
+
+  /// Sets the NEAR parameter to use when encoding into the 'JPEG-LS Lossy
+  /// (Near-Lossless)' transfer syntax.
+  ///
+  pub fn set_jpeg_ls_near(&mut self, jpeg_ls_near: Option<u8>) {
+    self.jpeg_ls_near = jpeg_ls_near;
+  }
+
+  /// Returns the parameters that control the structure of the codestream when
+  /// encoding into the 'High-Throughput JPEG 2000' transfer syntaxes.
+  ///
+  /// Default: [`HighThroughputJpeg2000EncodeParams::default()`].
+  ///
+  pub fn high_throughput_jpeg_2000_params(
+    &self,
+  ) -> &HighThroughputJpeg2000EncodeParams {
+    &self.high_throughput_jpeg_2000_params
+  }
+
+  /// Sets the parameters that control the structure of the codestream when
+  /// encoding into the 'High-Throughput JPEG 2000' transfer syntaxes. The
+  /// parameters are checked when encoding, and invalid parameters cause
+  /// encoding to fail.
+  ///
+  pub fn set_high_throughput_jpeg_2000_params(
+    &mut self,
+    params: HighThroughputJpeg2000EncodeParams,
+  ) {
+    self.high_throughput_jpeg_2000_params = params;
+  }
+
+  /// Returns the maximum size in bytes of the fragments that encoded frames are
+  /// split into when writing encapsulated pixel data. If this is `None` then
+  /// each frame is written as a single fragment.
+  ///
+  /// When frames are split into multiple fragments a Basic Offset Table is
+  /// generated so that readers can determine which fragments belong to each
+  /// frame. Because the Basic Offset Table precedes the fragments, this
+  /// requires all encoded frames to be buffered until the last frame has been
+  /// encoded. See [`Self::frame_buffer_memory_limit()`] to limit how much of
+  /// this buffering is done in memory.
+  ///
+  /// Default: `None`.
+  ///
+  pub fn max_fragment_size(&self) -> Option<u32> {
+    self.max_fragment_size
+  }
+
+  /// Sets the maximum size in bytes of the fragments that encoded frames are
+  /// split into when writing encapsulated pixel data. Fragments must have an
+  /// even length, so the size is rounded down to a multiple of two, with a
+  /// minimum of two.
+  ///
+  pub fn set_max_fragment_size(&mut self, max_fragment_size: Option<u32>) {
+    self.max_fragment_size = max_fragment_size
+      .map(|max_fragment_size| (max_fragment_size & !1).max(2));
+  }
+
+  /// Returns the maximum number of bytes of encoded frames that are buffered in
+  /// memory during a transcode. Frames buffered beyond this limit are spilled
+  /// to a temporary file and re-read when the output pixel data is assembled,
+  /// which allows huge instances to be transcoded on machines with limited
+  /// memory. If this is `None` then all buffering is done in memory.
+  ///
+  /// Spilling to a temporary file requires the `std` feature, and isn't
+  /// available on WASM.
+  ///
+  /// Default: `None`.
+  ///
+  pub fn frame_buffer_memory_limit(&self) -> Option<usize> {
+    self.frame_buffer_memory_limit
+  }
+
+  /// Sets the maximum number of bytes of encoded frames that are buffered in
+  /// memory during a transcode.
+  ///
+  pub fn set_frame_buffer_memory_limit(&mut self, limit: Option<usize>) {
+    self.frame_buffer_memory_limit = limit;
+  }
+}
+
+/// Parameters that control the structure of the codestream when encoding into
+/// the 'High-Throughput JPEG 2000' transfer syntaxes. Large frames, such as
+/// those used in mammography and whole slide imaging, can be tiled so that
+/// regions of interest can be decoded without decoding the whole frame.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HighThroughputJpeg2000EncodeParams {
+  /// The width and height of tiles. If this is `None` then the whole frame is
+  /// encoded as a single tile.
+  ///
+  /// Default: `None`.
+  pub tile_size: Option<(u32, u32)>,
+
+  /// The width and height of codeblocks. Both must be a power of two in the
+  /// range 4-1024, and their product must not exceed 4096.
+  ///
+  /// Default: 64x64.
+  pub codeblock_size: (u32, u32),
+
+  /// The progression order of the codestream.
+  ///
+  /// Default: [`Jpeg2000ProgressionOrder::Rpcl`].
+  pub progression_order: Jpeg2000ProgressionOrder,
+
+  /// The number of wavelet decompositions, in the range 0-32. Each
+  /// decomposition adds a lower resolution level that can be decoded on its
+  /// own.
+  ///
+  /// Default: 5.
+  pub decompositions: u8,
+}
+
+impl Default for HighThroughputJpeg2000EncodeParams {
+  fn default() -> Self {
+    Self {
+      tile_size: None,
+      codeblock_size: (64, 64),
+      progression_order: Jpeg2000ProgressionOrder::Rpcl,
+      decompositions: 5,
+    }
+  }
+}
+
+impl HighThroughputJpeg2000EncodeParams {
+  /// Checks that these parameters are valid, returning a description of the
+  /// problem if they aren't.
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    if let Some((width, height)) = self.tile_size
+      && (width == 0 || height == 0)
+    {
+      return Err(format!("Tile size {width}x{height} is invalid"));
+    }
+
+    let (width, height) = self.codeblock_size;
+    if !width.is_power_of_two()
+      || !height.is_power_of_two()
+      || !(4..=1024).contains(&width)
+      || !(4..=1024).contains(&height)
+      || width * height > 4096
+    {
+      return Err(format!(
+        "Codeblock size {width}x{height} is invalid, the width and height \
+         must be powers of two in the range 4-1024 with a product no greater \
+         than 4096"
+      ));
+    }
+
+    if self.decompositions > 32 {
+      return Err(format!(
+        "Decomposition count {} is invalid, the maximum is 32",
+        self.decompositions
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+/// The progression order of a JPEG 2000 codestream, which determines the order
+/// in which quality layers, resolutions, components, and positions are stored.
+///
+/// Ref: ISO/IEC 15444-1 Annex B.12.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Jpeg2000ProgressionOrder {
+  /// Layer-resolution-component-position.
+  Lrcp,
+
+  /// Resolution-layer-component-position.
+  Rlcp,
+
+  /// Resolution-position-component-layer.
+  Rpcl,
+
+  /// Position-component-resolution-layer.
+  Pcrl,
+
+  /// Component-position-resolution-layer.
+  Cprl,
+}
+
+impl Jpeg2000ProgressionOrder {
+  /// Returns the name of this progression order, e.g. "RPCL".
+  ///
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Lrcp => "LRCP",
+      Self::Rlcp => "RLCP",
+      Self::Rpcl => "RPCL",
+      Self::Pcrl => "PCRL",
+      Self::Cprl => "CPRL",
+    }
+  }
 }
 
 /// Errors that can occur when encoding frames of image data into a specific
@@ -370,12 +641,10 @@ pub fn encode_monochrome(
     }
 
     #[cfg(all(feature = "native", feature = "std"))]
-    &JPEG_LS_LOSSY_NEAR_LOSSLESS => charls::encode_monochrome(
-      image,
-      image_pixel_module,
-      Some(encode_config.quality()),
-    )
-    .map(PixelDataFrame::new_from_bytes),
+    &JPEG_LS_LOSSY_NEAR_LOSSLESS => {
+      charls::encode_monochrome(image, image_pixel_module, Some(encode_config))
+        .map(PixelDataFrame::new_from_bytes)
+    }
 
     #[cfg(feature = "native")]
     &JPEG_2000_LOSSLESS_ONLY => {
@@ -393,7 +662,7 @@ pub fn encode_monochrome(
 
     #[cfg(all(feature = "native", feature = "std"))]
     &HIGH_THROUGHPUT_JPEG_2000_LOSSLESS_ONLY => {
-      openjph::encode_monochrome(image, image_pixel_module, None)
+      openjph::encode_monochrome(image, image_pixel_module, encode_config, None)
         .map(PixelDataFrame::new_from_bytes)
     }
 
@@ -401,6 +670,7 @@ pub fn encode_monochrome(
     &HIGH_THROUGHPUT_JPEG_2000 => openjph::encode_monochrome(
       image,
       image_pixel_module,
+      encode_config,
       Some(encode_config.quality),
     )
     .map(PixelDataFrame::new_from_bytes),
@@ -432,6 +702,7 @@ pub fn encode_monochrome(
     &DEFLATED_IMAGE_FRAME_COMPRESSION => deflate_frame_data(
       native::encode_monochrome(image, image_pixel_module)?,
       encode_config.zlib_compression_level,
+      encode_config.deflate_thread_count,
     ),
 
     _ => {
@@ -479,12 +750,10 @@ pub fn encode_color(
       .map(PixelDataFrame::new_from_bytes),
 
     #[cfg(all(feature = "native", feature = "std"))]
-    &JPEG_LS_LOSSY_NEAR_LOSSLESS => charls::encode_color(
-      image,
-      image_pixel_module,
-      Some(encode_config.quality()),
-    )
-    .map(PixelDataFrame::new_from_bytes),
+    &JPEG_LS_LOSSY_NEAR_LOSSLESS => {
+      charls::encode_color(image, image_pixel_module, Some(encode_config))
+        .map(PixelDataFrame::new_from_bytes)
+    }
 
     #[cfg(feature = "native")]
     &JPEG_2000_LOSSLESS_ONLY => {
@@ -502,7 +771,7 @@ pub fn encode_color(
 
     #[cfg(all(feature = "native", feature = "std"))]
     &HIGH_THROUGHPUT_JPEG_2000_LOSSLESS_ONLY => {
-      openjph::encode_color(image, image_pixel_module, None)
+      openjph::encode_color(image, image_pixel_module, encode_config, None)
         .map(PixelDataFrame::new_from_bytes)
     }
 
@@ -510,6 +779,7 @@ pub fn encode_color(
     &HIGH_THROUGHPUT_JPEG_2000 => openjph::encode_color(
       image,
       image_pixel_module,
+      encode_config,
       Some(encode_config.quality),
     )
     .map(PixelDataFrame::new_from_bytes),
@@ -539,7 +809,11 @@ pub fn encode_color(
       let frame = native::encode_color(image, image_pixel_module)
         .map(PixelDataFrame::new_from_bytes)?;
 
-      deflate_frame_data(frame, encode_config.zlib_compression_level)
+      deflate_frame_data(
+        frame,
+        encode_config.zlib_compression_level,
+        encode_config.deflate_thread_count,
+      )
     }
 
     _ => {
@@ -554,11 +828,33 @@ pub fn encode_color(
 fn deflate_frame_data(
   mut frame: PixelDataFrame,
   compression_level: u32,
+  thread_count: usize,
 ) -> Result<PixelDataFrame, PixelDataEncodeError> {
   let mut input = frame.combine_chunks();
 
   let mut deflated_frame = PixelDataFrame::new();
 
+  // Use parallel deflate if more than one thread is configured
+  if thread_count > 1 {
+    let output = dcmfx_p10::parallel_deflate::deflate(
+      input,
+      compression_level,
+      thread_count,
+    )
+    .map_err(|e| PixelDataEncodeError::OtherError {
+      name: "Deflate failed".to_string(),
+      details: e.to_string(),
+    })?;
+
+    for bytes in output {
+      if !bytes.is_empty() {
+        deflated_frame.push_bytes(bytes.into());
+      }
+    }
+
+    return Ok(deflated_frame);
+  }
+
   let compression_level = flate2::Compression::new(compression_level);
   let mut compressor = flate2::Compress::new(compression_level, false);
 
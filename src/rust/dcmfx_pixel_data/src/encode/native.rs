@@ -220,7 +220,7 @@ pub fn encode_monochrome(
     (
       MonochromeImageData::I32(data),
       true,
-      16,
+      32,
       PhotometricInterpretation::Monochrome1 {
         pixel_representation: PixelRepresentation::Signed,
       },
@@ -229,7 +229,7 @@ pub fn encode_monochrome(
     | (
       MonochromeImageData::I32(data),
       false,
-      16,
+      32,
       PhotometricInterpretation::Monochrome2 {
         pixel_representation: PixelRepresentation::Signed,
       },
@@ -316,10 +316,24 @@ pub fn encode_color(
   image: &ColorImage,
   image_pixel_module: &ImagePixelModule,
 ) -> Result<Vec<u8>, PixelDataEncodeError> {
-  let mut result = vec![0; image_pixel_module.frame_size_in_bytes()];
-
   let photometric_interpretation =
     image_pixel_module.photometric_interpretation();
+
+  // RGB and YBR 444 color images are subsampled when encoding into
+  // YBR_FULL_422, which allows native YBR_FULL_422 pixel data to be written
+  // directly from them
+  if photometric_interpretation.is_ybr_full_422()
+    && !image.is_palette_color()
+    && image.color_space() != (ColorSpace::Ybr { is_422: true })
+  {
+    let mut image = image.clone();
+    image.convert_to_ybr_422_color_space();
+
+    return encode_color(&image, image_pixel_module);
+  }
+
+  let mut result = vec![0; image_pixel_module.frame_size_in_bytes()];
+
   let planar_configuration = image_pixel_module.planar_configuration();
 
   match (
@@ -677,3 +691,73 @@ pub fn encode_color(
 
   Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx_core::transfer_syntax;
+
+  use crate::{
+    PixelDataDecodeConfig, iods::image_pixel_module::SamplesPerPixel,
+  };
+
+  /// Encodes a 2x2 signed 32-bit monochrome image, checks that decoding it
+  /// gives back the original image, and returns the encoded bytes.
+  ///
+  fn round_trip_monochrome_i32(bits_stored: u16, data: Vec<i32>) -> Vec<u8> {
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Signed,
+      },
+      2,
+      2,
+      BitsAllocated::ThirtyTwo,
+      bits_stored,
+    )
+    .unwrap();
+
+    let image =
+      MonochromeImage::new_i32(2, 2, data, bits_stored, false).unwrap();
+
+    let mut frame = encode_monochrome(&image, &image_pixel_module).unwrap();
+    let bytes = frame.combine_chunks().to_vec();
+
+    assert_eq!(
+      crate::decode::decode_monochrome(
+        &mut frame,
+        &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+        &image_pixel_module,
+        &PixelDataDecodeConfig::default(),
+      )
+      .unwrap(),
+      image
+    );
+
+    bytes
+  }
+
+  #[test]
+  fn encode_monochrome_32_bit_signed_round_trip() {
+    assert_eq!(
+      round_trip_monochrome_i32(32, vec![0, i32::MAX, i32::MIN, -1]),
+      [
+        0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x7F, 0x00, 0x00, 0x00, 0x80,
+        0xFF, 0xFF, 0xFF, 0xFF
+      ]
+    );
+  }
+
+  #[test]
+  fn encode_monochrome_32_bit_signed_with_16_bits_stored_round_trip() {
+    // Bits above the high bit are zero rather than sign-extended
+    assert_eq!(
+      round_trip_monochrome_i32(16, vec![0, 0x7FFF, -0x8000, -1]),
+      [
+        0x00, 0x00, 0x00, 0x00, 0xFF, 0x7F, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+        0xFF, 0xFF, 0x00, 0x00
+      ]
+    );
+  }
+}
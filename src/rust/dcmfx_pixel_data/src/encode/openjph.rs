@@ -1,5 +1,6 @@
 use crate::{
-  ColorImage, ColorSpace, MonochromeImage, PixelDataEncodeError,
+  ColorImage, ColorSpace, MonochromeImage, PixelDataEncodeConfig,
+  PixelDataEncodeError,
   color_image::ColorImageData,
   iods::image_pixel_module::{
     BitsAllocated, ImagePixelModule, PhotometricInterpretation,
@@ -14,6 +15,7 @@ use crate::{
 pub fn encode_monochrome(
   image: &MonochromeImage,
   image_pixel_module: &ImagePixelModule,
+  encode_config: &PixelDataEncodeConfig,
   quality: Option<u8>,
 ) -> Result<Vec<u8>, PixelDataEncodeError> {
   let width = image.width();
@@ -45,6 +47,7 @@ pub fn encode_monochrome(
       width,
       height,
       image_pixel_module,
+      encode_config,
       quality,
     ),
 
@@ -63,7 +66,14 @@ pub fn encode_monochrome(
         pixel_representation: PixelRepresentation::Unsigned,
       },
       BitsAllocated::Eight,
-    ) => encode(data, width, height, image_pixel_module, quality),
+    ) => encode(
+      data,
+      width,
+      height,
+      image_pixel_module,
+      encode_config,
+      quality,
+    ),
 
     (
       MonochromeImageData::I16(data),
@@ -85,6 +95,7 @@ pub fn encode_monochrome(
       width,
       height,
       image_pixel_module,
+      encode_config,
       quality,
     ),
 
@@ -108,6 +119,7 @@ pub fn encode_monochrome(
       width,
       height,
       image_pixel_module,
+      encode_config,
       quality,
     ),
 
@@ -131,6 +143,7 @@ pub fn encode_monochrome(
       width,
       height,
       image_pixel_module,
+      encode_config,
       quality,
     ),
 
@@ -154,6 +167,7 @@ pub fn encode_monochrome(
       width,
       height,
       image_pixel_module,
+      encode_config,
       quality,
     ),
 
@@ -171,6 +185,7 @@ pub fn encode_monochrome(
 pub fn encode_color(
   image: &ColorImage,
   image_pixel_module: &ImagePixelModule,
+  encode_config: &PixelDataEncodeConfig,
   quality: Option<u8>,
 ) -> Result<Vec<u8>, PixelDataEncodeError> {
   let width = image.width();
@@ -223,7 +238,14 @@ pub fn encode_color(
       PhotometricInterpretation::PaletteColor { .. },
       BitsAllocated::Eight,
       None,
-    ) => encode(data, width, height, image_pixel_module, quality),
+    ) => encode(
+      data,
+      width,
+      height,
+      image_pixel_module,
+      encode_config,
+      quality,
+    ),
 
     (
       ColorImageData::U16 {
@@ -271,6 +293,7 @@ pub fn encode_color(
       width,
       height,
       image_pixel_module,
+      encode_config,
       quality,
     ),
 
@@ -314,6 +337,7 @@ pub fn encode_color(
       width,
       height,
       image_pixel_module,
+      encode_config,
       quality,
     ),
 
@@ -333,8 +357,17 @@ fn encode(
   width: u16,
   height: u16,
   image_pixel_module: &ImagePixelModule,
+  encode_config: &PixelDataEncodeConfig,
   quality: Option<u8>,
 ) -> Result<Vec<u8>, PixelDataEncodeError> {
+  let params = encode_config.high_throughput_jpeg_2000_params();
+  params
+    .validate()
+    .map_err(|details| PixelDataEncodeError::OtherError {
+      name: "OpenJPH encode parameters are invalid".to_string(),
+      details,
+    })?;
+
   ENCODE_INITIALIZE_ONCE_LOCK
     .get_or_init(|| unsafe { ffi::openjph_encode_initialize() });
 
@@ -357,6 +390,12 @@ fn encode(
     0.0
   };
 
+  let (tile_width, tile_height) = params.tile_size.unwrap_or((0, 0));
+  let (codeblock_width, codeblock_height) = params.codeblock_size;
+
+  let progression_order =
+    std::ffi::CString::new(params.progression_order.as_str()).unwrap();
+
   let result = unsafe {
     ffi::openjph_encode(
       data.as_ptr() as *const core::ffi::c_void,
@@ -368,6 +407,12 @@ fn encode(
       u8::from(image_pixel_module.pixel_representation()).into(),
       color_photometric_interpretation,
       quantization_step_size,
+      tile_width as usize,
+      tile_height as usize,
+      codeblock_width as usize,
+      codeblock_height as usize,
+      progression_order.as_ptr(),
+      params.decompositions.into(),
       append_output_data,
       &mut output_data as *mut Vec<u8> as *mut core::ffi::c_void,
       error_buffer.as_mut_ptr(),
@@ -435,6 +480,12 @@ mod ffi {
       pixel_representation: usize,
       color_photometric_interpretation: usize,
       quantization_step_size: f32,
+      tile_width: usize,
+      tile_height: usize,
+      codeblock_width: usize,
+      codeblock_height: usize,
+      progression_order: *const core::ffi::c_char,
+      decompositions: usize,
       output_data_callback: extern "C" fn(
         *const core::ffi::c_void,
         usize,
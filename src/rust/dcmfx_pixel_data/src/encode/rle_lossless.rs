@@ -32,6 +32,70 @@ pub fn encode_image_pixel_module(
   Ok(image_pixel_module)
 }
 
+/// Returns the minimum and maximum size in bytes of a single frame of RLE
+/// Lossless pixel data with the given Image Pixel Module, as produced by this
+/// encoder. The size of the encoded data depends on its content, but is always
+/// within these bounds.
+///
+pub fn encoded_frame_size_bounds(
+  image_pixel_module: &ImagePixelModule,
+) -> (u64, u64) {
+  let columns = u64::from(image_pixel_module.columns());
+
+  let (segment_count, segment_size, row_size) =
+    match image_pixel_module.bits_allocated() {
+      BitsAllocated::One => {
+        let segment_size = image_pixel_module.frame_size_in_bytes() as u64;
+
+        let row_size = if columns.is_multiple_of(8) {
+          columns / 8
+        } else {
+          segment_size
+        };
+
+        (1, segment_size, row_size)
+      }
+
+      bits_allocated => (
+        u64::from(u8::from(image_pixel_module.samples_per_pixel()))
+          * u64::from(u8::from(bits_allocated) / 8),
+        image_pixel_module.pixel_count() as u64,
+        columns,
+      ),
+    };
+
+  let (segment_min, segment_max) =
+    encoded_segment_size_bounds(segment_size, row_size);
+
+  let round_to_even = |size: u64| size + size % 2;
+
+  (
+    round_to_even(64 + segment_count * segment_min),
+    round_to_even(64 + segment_count * segment_max),
+  )
+}
+
+/// Returns the minimum and maximum size in bytes of an RLE encoded segment with
+/// the given size and row size.
+///
+/// The smallest output occurs when each row is made up of replicate runs of
+/// 128 bytes, which encode to two bytes each. The largest output occurs when
+/// each row is made up of literal runs, which add one byte per 128 bytes.
+///
+fn encoded_segment_size_bounds(segment_size: u64, row_size: u64) -> (u64, u64) {
+  if row_size == 0 {
+    return (0, 0);
+  }
+
+  let row_count = segment_size / row_size;
+  let run_count = row_size.div_ceil(128);
+
+  (
+    row_count * run_count * 2,
+    row_count * (row_size + run_count),
+  )
+}
+
 /// Encodes a [`MonochromeImage`] into RLE Lossless raw bytes.
 ///
 pub fn encode_monochrome(
@@ -741,6 +805,32 @@ mod tests {
     );
   }
 
+  #[test]
+  fn encoded_segment_size_bounds_test() {
+    let rows: [&[u8]; 6] = [
+      &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+      &[5; 10],
+      &[5, 5, 1, 2, 2, 2, 3, 3, 4, 4],
+      &[8, 8, 1, 8, 8, 1, 8, 8, 1, 8],
+      &[1, 2, 3, 3, 4, 5, 6, 7, 7, 7],
+      &[0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+    ];
+
+    let (min, max) = encoded_segment_size_bounds(10, 10);
+
+    for row in rows {
+      let size = encode_segment(row, 10).unwrap().len() as u64;
+      assert!(size >= min && size <= max);
+    }
+
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * i % 7) as u8).collect();
+    let (min, max) = encoded_segment_size_bounds(1000, 200);
+    let size = encode_segment(&data, 200).unwrap().len() as u64;
+    assert!(size >= min && size <= max);
+
+    assert_eq!(encoded_segment_size_bounds(129, 129), (4, 131));
+  }
+
   #[test]
   fn odd_length_segment_adds_padding_byte() {
     assert_eq!(
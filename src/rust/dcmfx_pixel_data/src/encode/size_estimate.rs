@@ -0,0 +1,380 @@
+use dcmfx_core::{TransferSyntax, transfer_syntax};
+
+use crate::{
+  PixelDataEncodeConfig, PixelDataEncodeError,
+  iods::image_pixel_module::ImagePixelModule,
+};
+
+use super::rle_lossless;
+
+/// An estimate of the size of the *'(7FE0,0010) Pixel Data'* value that will
+/// result from encoding pixel data into a specific transfer syntax.
+///
+/// For encapsulated transfer syntaxes the size includes the items that make up
+/// the encapsulated pixel data sequence, i.e. the empty Basic Offset Table, one
+/// item per frame, and the sequence delimiter.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelDataSizeEstimate {
+  /// The smallest expected size in bytes.
+  pub min: u64,
+
+  /// The largest expected size in bytes.
+  pub max: u64,
+
+  /// How accurate the range of expected sizes is.
+  pub accuracy: PixelDataSizeEstimateAccuracy,
+}
+
+/// The accuracy of a [`PixelDataSizeEstimate`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PixelDataSizeEstimateAccuracy {
+  /// The size is known exactly, i.e. the minimum and maximum are the same.
+  Exact,
+
+  /// The size depends on the content of the pixel data, but is guaranteed to
+  /// lie within the minimum and maximum.
+  Bounded,
+
+  /// The size depends on the content of the pixel data and on the behavior of
+  /// the encoder, and the minimum and maximum are based on typical compression
+  /// ratios. The actual size may fall outside this range.
+  Heuristic,
+}
+
+impl core::ops::Add for PixelDataSizeEstimate {
+  type Output = Self;
+
+  /// Combines two size estimates, e.g. to estimate the total storage required
+  /// by a number of files. The accuracy of the result is the lower of the two
+  /// accuracies.
+  ///
+  fn add(self, rhs: Self) -> Self {
+    Self {
+      min: self.min + rhs.min,
+      max: self.max + rhs.max,
+      accuracy: self.accuracy.max(rhs.accuracy),
+    }
+  }
+}
+
+impl core::fmt::Display for PixelDataSizeEstimate {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self.accuracy {
+      PixelDataSizeEstimateAccuracy::Exact => write!(f, "{} bytes", self.max),
+
+      PixelDataSizeEstimateAccuracy::Bounded => {
+        write!(f, "{}-{} bytes", self.min, self.max)
+      }
+
+      PixelDataSizeEstimateAccuracy::Heuristic => {
+        write!(f, "{}-{} bytes (approximate)", self.min, self.max)
+      }
+    }
+  }
+}
+
+/// The number of bytes allowed for the headers, markers, and tables that
+/// compressed frames contain in addition to their image data. This matters for
+/// small images where these make up most of the encoded data.
+///
+const COMPRESSED_FRAME_HEADER_ALLOWANCE: u64 = 1024;
+
+/// Estimates the size of the pixel data that will result from encoding frames
+/// described by the given Image Pixel Module into the specified transfer
+/// syntax.
+///
+/// The Image Pixel Module is that of the images being encoded, i.e. after any
+/// changes to the photometric interpretation made prior to encoding. It is
+/// passed through [`super::encode_image_pixel_module()`], so an error is
+/// returned if encoding into the transfer syntax isn't supported.
+///
+/// The estimate is exact for native and encapsulated uncompressed transfer
+/// syntaxes, bounded for 'RLE Lossless' and 'Deflated Image Frame Compression',
+/// and heuristic for all other transfer syntaxes. Heuristic estimates for lossy
+/// transfer syntaxes take into account the quality in the encode config.
+///
+/// For the 'Deflated Explicit VR Little Endian' transfer syntax the size is
+/// that of the pixel data prior to the whole data set being deflated.
+///
+pub fn estimate_encoded_size(
+  image_pixel_module: &ImagePixelModule,
+  number_of_frames: usize,
+  transfer_syntax: &'static TransferSyntax,
+  encode_config: &PixelDataEncodeConfig,
+) -> Result<PixelDataSizeEstimate, PixelDataEncodeError> {
+  use transfer_syntax::*;
+
+  let image_pixel_module = super::encode_image_pixel_module(
+    image_pixel_module.clone(),
+    transfer_syntax,
+    encode_config,
+  )?;
+
+  let frame_count = number_of_frames as u64;
+
+  // Native pixel data stores all frames contiguously, including when frames
+  // with a bits allocated of one don't end on a byte boundary
+  if !transfer_syntax.is_encapsulated {
+    let size = (image_pixel_module.frame_size_in_bits() * frame_count)
+      .div_ceil(8)
+      .next_multiple_of(2);
+
+    return Ok(PixelDataSizeEstimate {
+      min: size,
+      max: size,
+      accuracy: PixelDataSizeEstimateAccuracy::Exact,
+    });
+  }
+
+  let frame_size = image_pixel_module.frame_size_in_bytes() as u64;
+
+  let (frame_min, frame_max, accuracy) = match transfer_syntax {
+    &ENCAPSULATED_UNCOMPRESSED_EXPLICIT_VR_LITTLE_ENDIAN => {
+      (frame_size, frame_size, PixelDataSizeEstimateAccuracy::Exact)
+    }
+
+    &RLE_LOSSLESS => {
+      let (min, max) =
+        rle_lossless::encoded_frame_size_bounds(&image_pixel_module);
+
+      (min, max, PixelDataSizeEstimateAccuracy::Bounded)
+    }
+
+    // Deflate's maximum compression ratio is 1032:1, and its worst case
+    // expansion is given by zlib's compressBound()
+    &DEFLATED_IMAGE_FRAME_COMPRESSION => (
+      frame_size.div_ceil(1032),
+      frame_size
+        + (frame_size >> 12)
+        + (frame_size >> 14)
+        + (frame_size >> 25)
+        + 13,
+      PixelDataSizeEstimateAccuracy::Bounded,
+    ),
+
+    // Lossy compression ratios depend on the quality, so use a typical ratio
+    // for the quality and allow for it to be off by a factor of two either way
+    &JPEG_BASELINE_8BIT
+    | &JPEG_EXTENDED_12BIT
+    | &JPEG_LS_LOSSY_NEAR_LOSSLESS
+    | &JPEG_2000
+    | &HIGH_THROUGHPUT_JPEG_2000
+    | &JPEG_XL
+    | &JPEG_XL_JPEG_RECOMPRESSION => {
+      let mut ratio = 2.0 + f64::from(100 - encode_config.quality()) * 0.5;
+
+      // JPEG XL JPEG Recompression stores JPEG data around 20% smaller
+      if transfer_syntax == &JPEG_XL_JPEG_RECOMPRESSION {
+        ratio *= 1.25;
+      }
+
+      let typical_size = frame_size as f64 / ratio;
+
+      (
+        (typical_size / 2.0) as u64,
+        ((typical_size * 2.0) as u64).min(frame_size)
+          + COMPRESSED_FRAME_HEADER_ALLOWANCE,
+        PixelDataSizeEstimateAccuracy::Heuristic,
+      )
+    }
+
+    // Lossless compression of medical images typically achieves ratios
+    // between 2:1 and 3:1, but images with little noise can compress further
+    _ => (
+      frame_size / 5,
+      frame_size + COMPRESSED_FRAME_HEADER_ALLOWANCE,
+      PixelDataSizeEstimateAccuracy::Heuristic,
+    ),
+  };
+
+  // Each frame is stored in an item with an 8 byte header and even length, and
+  // there is also an empty Basic Offset Table item and a sequence delimiter
+  let overhead = 8 + frame_count * 8 + 8;
+
+  Ok(PixelDataSizeEstimate {
+    min: overhead + frame_count * frame_min.next_multiple_of(2),
+    max: overhead + frame_count * frame_max.next_multiple_of(2),
+    accuracy,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::iods::image_pixel_module::{
+    BitsAllocated, PhotometricInterpretation, PixelRepresentation,
+    PlanarConfiguration, SamplesPerPixel,
+  };
+
+  fn monochrome_image_pixel_module(
+    bits_allocated: BitsAllocated,
+  ) -> ImagePixelModule {
+    ImagePixelModule::new_basic(
+      SamplesPerPixel::One,
+      PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Unsigned,
+      },
+      101,
+      99,
+      bits_allocated,
+      u8::from(bits_allocated).into(),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn native_test() {
+    let config = PixelDataEncodeConfig::default();
+
+    assert_eq!(
+      estimate_encoded_size(
+        &monochrome_image_pixel_module(BitsAllocated::Sixteen),
+        3,
+        &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+        &config,
+      ),
+      Ok(PixelDataSizeEstimate {
+        min: 59_994,
+        max: 59_994,
+        accuracy: PixelDataSizeEstimateAccuracy::Exact,
+      })
+    );
+
+    // 1-bit frames are packed together with no padding between them
+    assert_eq!(
+      estimate_encoded_size(
+        &monochrome_image_pixel_module(BitsAllocated::One),
+        3,
+        &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+        &config,
+      ),
+      Ok(PixelDataSizeEstimate {
+        min: 3_750,
+        max: 3_750,
+        accuracy: PixelDataSizeEstimateAccuracy::Exact,
+      })
+    );
+  }
+
+  #[test]
+  fn encapsulated_uncompressed_test() {
+    assert_eq!(
+      estimate_encoded_size(
+        &monochrome_image_pixel_module(BitsAllocated::Eight),
+        2,
+        &transfer_syntax::ENCAPSULATED_UNCOMPRESSED_EXPLICIT_VR_LITTLE_ENDIAN,
+        &PixelDataEncodeConfig::default(),
+      ),
+      Ok(PixelDataSizeEstimate {
+        min: 20_032,
+        max: 20_032,
+        accuracy: PixelDataSizeEstimateAccuracy::Exact,
+      })
+    );
+  }
+
+  #[test]
+  fn rle_lossless_test() {
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::Three {
+        planar_configuration: PlanarConfiguration::Interleaved,
+      },
+      PhotometricInterpretation::Rgb,
+      2,
+      200,
+      BitsAllocated::Eight,
+      8,
+    )
+    .unwrap();
+
+    // Three segments of two rows of 200 bytes each, with each row encoded as
+    // two runs
+    assert_eq!(
+      estimate_encoded_size(
+        &image_pixel_module,
+        1,
+        &transfer_syntax::RLE_LOSSLESS,
+        &PixelDataEncodeConfig::default(),
+      ),
+      Ok(PixelDataSizeEstimate {
+        min: 24 + 64 + 3 * 2 * 4,
+        max: 24 + 64 + 3 * 2 * 202,
+        accuracy: PixelDataSizeEstimateAccuracy::Bounded,
+      })
+    );
+  }
+
+  #[test]
+  fn lossy_test() {
+    let image_pixel_module =
+      monochrome_image_pixel_module(BitsAllocated::Eight);
+
+    let mut config = PixelDataEncodeConfig::default();
+
+    config.set_quality(90);
+    let high_quality = estimate_encoded_size(
+      &image_pixel_module,
+      1,
+      &transfer_syntax::JPEG_BASELINE_8BIT,
+      &config,
+    )
+    .unwrap();
+
+    config.set_quality(50);
+    let low_quality = estimate_encoded_size(
+      &image_pixel_module,
+      1,
+      &transfer_syntax::JPEG_BASELINE_8BIT,
+      &config,
+    )
+    .unwrap();
+
+    assert_eq!(
+      high_quality.accuracy,
+      PixelDataSizeEstimateAccuracy::Heuristic
+    );
+    assert!(high_quality.min < high_quality.max);
+    assert!(low_quality.max < high_quality.max);
+  }
+
+  #[test]
+  fn transfer_syntax_not_supported_test() {
+    assert_eq!(
+      estimate_encoded_size(
+        &monochrome_image_pixel_module(BitsAllocated::Eight),
+        1,
+        &transfer_syntax::MPEG2_MAIN_PROFILE_MAIN_LEVEL,
+        &PixelDataEncodeConfig::default(),
+      ),
+      Err(PixelDataEncodeError::TransferSyntaxNotSupported {
+        transfer_syntax: &transfer_syntax::MPEG2_MAIN_PROFILE_MAIN_LEVEL,
+      })
+    );
+  }
+
+  #[test]
+  fn add_test() {
+    let a = PixelDataSizeEstimate {
+      min: 10,
+      max: 10,
+      accuracy: PixelDataSizeEstimateAccuracy::Exact,
+    };
+    let b = PixelDataSizeEstimate {
+      min: 5,
+      max: 20,
+      accuracy: PixelDataSizeEstimateAccuracy::Heuristic,
+    };
+
+    assert_eq!(
+      a + b,
+      PixelDataSizeEstimate {
+        min: 15,
+        max: 30,
+        accuracy: PixelDataSizeEstimateAccuracy::Heuristic,
+      }
+    );
+  }
+}
@@ -0,0 +1,296 @@
+//! Selection of a subset of the frames of pixel data in a data set.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec::Vec,
+};
+
+use core::{
+  ops::{Range, RangeInclusive},
+  time::Duration,
+};
+
+/// Represents a selection of frames in a DICOM file. Negative indexes are
+/// treated as offsets from the end.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameSelection {
+  /// A selection of individual frames.
+  Individual { indexes: Vec<isize> },
+
+  /// A selection of frames in an inclusive range.
+  Range { range: RangeInclusive<isize> },
+
+  /// A selection of the frames that are displayed during a range of time,
+  /// measured from the start of the first frame. A frame is selected if any
+  /// part of its display interval lies in the range. If the range is empty
+  /// then the frame displayed at its start time is selected.
+  ///
+  /// An open-ended range has an end of [`Duration::MAX`].
+  TimeRange { range: Range<Duration> },
+}
+
+impl core::fmt::Display for FrameSelection {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      FrameSelection::Individual { indexes } => {
+        let indexes = indexes
+          .iter()
+          .map(|i| i.to_string())
+          .collect::<Vec<_>>()
+          .join(",");
+
+        write!(f, "{indexes}")
+      }
+
+      FrameSelection::Range { range } => {
+        if *range.end() == isize::MAX {
+          write!(f, "{}..", range.start())
+        } else {
+          write!(f, "{}..{}", range.start(), range.end())
+        }
+      }
+
+      FrameSelection::TimeRange { range } => {
+        if range.end == Duration::MAX {
+          write!(f, "{}s-", range.start.as_secs_f64())
+        } else {
+          write!(
+            f,
+            "{}s-{}s",
+            range.start.as_secs_f64(),
+            range.end.as_secs_f64()
+          )
+        }
+      }
+    }
+  }
+}
+
+impl core::str::FromStr for FrameSelection {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Ok(indexes) = s
+      .split(",")
+      .map(|s| s.parse::<isize>())
+      .collect::<Result<Vec<_>, _>>()
+    {
+      return Ok(FrameSelection::Individual { indexes });
+    }
+
+    if let Some((a, b)) = s.split_once("..") {
+      let start = a.parse::<isize>().map_err(|e| e.to_string())?;
+
+      let end = if b.is_empty() {
+        isize::MAX
+      } else {
+        b.parse::<isize>().map_err(|e| e.to_string())?
+      };
+
+      return Ok(FrameSelection::Range { range: start..=end });
+    }
+
+    if s.contains('s') {
+      return Self::parse_time_range(s);
+    }
+
+    Err(format!("Invalid frame range: {s}"))
+  }
+}
+
+impl FrameSelection {
+  /// Parses a time range selection of the form `2.5s-7.0s`, or `2.5s-` for an
+  /// open-ended range. Times are in seconds.
+  ///
+  pub fn parse_time_range(s: &str) -> Result<Self, String> {
+    let parse_time = |time: &str| -> Result<Duration, String> {
+      time
+        .strip_suffix('s')
+        .and_then(|seconds| seconds.parse::<f64>().ok())
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("Invalid time: {time}"))
+    };
+
+    let Some((a, b)) = s.split_once('-') else {
+      return Err(format!("Invalid time range: {s}"));
+    };
+
+    let start = parse_time(a)?;
+
+    let end = if b.is_empty() {
+      Duration::MAX
+    } else {
+      parse_time(b)?
+    };
+
+    if end < start {
+      return Err(format!("Invalid time range: {s}"));
+    }
+
+    Ok(FrameSelection::TimeRange { range: start..end })
+  }
+
+  /// Returns whether this frame selection requires the display interval of
+  /// each frame, i.e. whether it selects frames by time.
+  ///
+  pub fn is_time_based(&self) -> bool {
+    matches!(self, FrameSelection::TimeRange { .. })
+  }
+
+  /// Checks if the given frame index is contained within this frame selection.
+  ///
+  /// Time-based selections require the display interval of the frame, which
+  /// can be obtained from [`crate::iods::CineModule::frame_interval()`]. If it
+  /// isn't available then the frame isn't selected.
+  ///
+  pub fn contains(
+    &self,
+    frame_index: usize,
+    number_of_frames: usize,
+    frame_interval: Option<&Range<Duration>>,
+  ) -> bool {
+    match self {
+      FrameSelection::Individual { indexes } => {
+        for index in indexes.iter() {
+          if frame_index as isize
+            == FrameSelection::standardize_index(*index, number_of_frames)
+          {
+            return true;
+          }
+        }
+
+        false
+      }
+
+      FrameSelection::Range { range } => {
+        (FrameSelection::standardize_index(*range.start(), number_of_frames)
+          ..=FrameSelection::standardize_index(*range.end(), number_of_frames))
+          .contains(&(frame_index as isize))
+      }
+
+      FrameSelection::TimeRange { range } => {
+        let Some(frame_interval) = frame_interval else {
+          return false;
+        };
+
+        frame_interval.end > range.start
+          && (frame_interval.start < range.end
+            || frame_interval.start <= range.start)
+      }
+    }
+  }
+
+  /// Checks if all frames for this frame selection are done, given the
+  /// specified frame has been processed.
+  ///
+  pub fn is_complete(
+    &self,
+    frame_index: usize,
+    number_of_frames: usize,
+    frame_interval: Option<&Range<Duration>>,
+  ) -> bool {
+    match self {
+      FrameSelection::Individual { indexes } => {
+        let max = indexes
+          .iter()
+          .map(|i| FrameSelection::standardize_index(*i, number_of_frames))
+          .max()
+          .unwrap();
+
+        max <= frame_index as isize
+      }
+
+      FrameSelection::Range { range } => {
+        let end =
+          FrameSelection::standardize_index(*range.end(), number_of_frames);
+
+        frame_index as isize >= end
+      }
+
+      FrameSelection::TimeRange { range } => frame_interval
+        .is_some_and(|interval| interval.end >= range.end.max(range.start)),
+    }
+  }
+
+  /// Converts negative frame indexes to positive ones by treating them as
+  /// offsets from the end.
+  ///
+  fn standardize_index(index: isize, number_of_frames: usize) -> isize {
+    if index >= 0 {
+      index
+    } else {
+      number_of_frames as isize + index
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use core::str::FromStr;
+
+  fn ms(milliseconds: u64) -> Duration {
+    Duration::from_millis(milliseconds)
+  }
+
+  #[test]
+  fn parse_test() {
+    assert_eq!(
+      FrameSelection::from_str("2.5s-7.0s"),
+      Ok(FrameSelection::TimeRange {
+        range: ms(2500)..ms(7000)
+      })
+    );
+
+    assert_eq!(
+      FrameSelection::from_str("1s-"),
+      Ok(FrameSelection::TimeRange {
+        range: ms(1000)..Duration::MAX
+      })
+    );
+
+    assert_eq!(
+      FrameSelection::from_str("-2,-1"),
+      Ok(FrameSelection::Individual {
+        indexes: vec![-2, -1]
+      })
+    );
+
+    assert!(FrameSelection::from_str("7s-2s").is_err());
+    assert!(FrameSelection::from_str("-1s-2s").is_err());
+    assert!(FrameSelection::parse_time_range("2.5-7.0").is_err());
+
+    assert_eq!(
+      FrameSelection::parse_time_range("0.5s-2s")
+        .unwrap()
+        .to_string(),
+      "0.5s-2s"
+    );
+  }
+
+  #[test]
+  fn time_range_contains_test() {
+    let selection = FrameSelection::parse_time_range("0.1s-0.2s").unwrap();
+
+    let frame_interval = |i: u64| ms(i * 40)..ms(i * 40 + 40);
+
+    let selected_frames: Vec<u64> = (0..10)
+      .filter(|i| selection.contains(0, 0, Some(&frame_interval(*i))))
+      .collect();
+    assert_eq!(selected_frames, vec![2, 3, 4]);
+
+    assert!(!selection.is_complete(0, 0, Some(&frame_interval(3))));
+    assert!(selection.is_complete(0, 0, Some(&frame_interval(4))));
+
+    assert!(!selection.contains(3, 10, None));
+
+    let selection = FrameSelection::parse_time_range("0.1s-0.1s").unwrap();
+    assert!(selection.contains(0, 0, Some(&frame_interval(2))));
+    assert!(!selection.contains(0, 0, Some(&frame_interval(3))));
+    assert!(selection.is_complete(0, 0, Some(&frame_interval(2))));
+  }
+}
@@ -0,0 +1,275 @@
+//! Determines the acquisition timing of the frames in multi-frame pixel data.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::{ops::Range, time::Duration};
+
+use dcmfx_core::{
+  DataElementTag, DataError, DataSet, DataSetPath, IodModule,
+  StructuredDateTime, ValueRepresentation, data_set_path::DataSetPathEntry,
+  dictionary,
+};
+
+use crate::iods::{CineModule, FunctionalGroupsModule, MultiFrameModule};
+
+/// The timing of the frames in multi-frame pixel data, which specifies the
+/// interval of time that each frame covers measured from the start of the
+/// first frame.
+///
+/// Frame timing is taken from the *'(0018,9074) Frame Acquisition DateTime'*
+/// and *'(0018,9220) Frame Acquisition Duration'* values in the Frame Content
+/// functional group when they are present for all frames, which is the case
+/// for Enhanced multi-frame IODs. Otherwise it is taken from the Cine Module's
+/// *'(0018,1063) Frame Time'* and *'(0018,1065) Frame Time Vector'* values.
+/// See [`CineModule::frame_interval()`] for details.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameTiming {
+  pub cine_module: CineModule,
+  pub multiframe_module: MultiFrameModule,
+
+  // The acquisition time of each frame in seconds relative to the earliest
+  // frame, and the acquisition duration of each frame in seconds, taken from
+  // the Frame Content functional group
+  acquisition_times: Vec<f64>,
+  acquisition_durations: Vec<Option<f64>>,
+}
+
+impl IodModule for FrameTiming {
+  fn is_iod_module_data_element(
+    tag: DataElementTag,
+    vr: ValueRepresentation,
+    length: Option<u32>,
+    path: &DataSetPath,
+  ) -> bool {
+    if CineModule::is_iod_module_data_element(tag, vr, length, path)
+      || MultiFrameModule::is_iod_module_data_element(tag, vr, length, path)
+    {
+      return true;
+    }
+
+    // Only the Frame Content functional group is needed from the functional
+    // groups sequences
+    match path.entries().as_slice() {
+      [DataSetPathEntry::DataElement { .. }] => {
+        FunctionalGroupsModule::TAGS.contains(&tag)
+      }
+
+      [
+        DataSetPathEntry::DataElement { tag: sequence_tag },
+        DataSetPathEntry::SequenceItem { .. },
+        DataSetPathEntry::DataElement { tag: group_tag },
+        ..,
+      ] => {
+        FunctionalGroupsModule::TAGS.contains(sequence_tag)
+          && *group_tag == dictionary::FRAME_CONTENT_SEQUENCE.tag
+      }
+
+      _ => false,
+    }
+  }
+
+  fn iod_module_highest_tag() -> DataElementTag {
+    MultiFrameModule::iod_module_highest_tag()
+  }
+
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let cine_module = CineModule::from_data_set(data_set)?;
+    let multiframe_module = MultiFrameModule::from_data_set(data_set)?;
+    let functional_groups_module =
+      FunctionalGroupsModule::from_data_set(data_set)?;
+
+    let number_of_frames = functional_groups_module.per_frame.len();
+
+    let mut acquisition_times = Vec::with_capacity(number_of_frames);
+    let mut acquisition_durations = Vec::with_capacity(number_of_frames);
+
+    for frame_index in 0..number_of_frames {
+      let groups = functional_groups_module.frame(frame_index);
+
+      // Per-frame acquisition times are only used if all frames have one
+      let Some(date_time) = groups.frame_acquisition_date_time.as_ref() else {
+        acquisition_times.clear();
+        acquisition_durations.clear();
+        break;
+      };
+
+      acquisition_times.push(date_time_to_seconds(date_time));
+      acquisition_durations
+        .push(groups.frame_acquisition_duration.map(|ms| ms / 1000.0));
+    }
+
+    let earliest_time = acquisition_times
+      .iter()
+      .copied()
+      .fold(f64::INFINITY, f64::min);
+    for time in acquisition_times.iter_mut() {
+      *time -= earliest_time;
+    }
+
+    Ok(Self {
+      cine_module,
+      multiframe_module,
+      acquisition_times,
+      acquisition_durations,
+    })
+  }
+}
+
+impl FrameTiming {
+  /// Returns the interval of time covered by the specified frame, measured
+  /// from the start of the first frame. Returns `None` if frame timing isn't
+  /// available.
+  ///
+  pub fn frame_interval(&self, frame_index: usize) -> Option<Range<Duration>> {
+    if self.acquisition_times.is_empty() {
+      return self
+        .cine_module
+        .frame_interval(frame_index, &self.multiframe_module);
+    }
+
+    let start = *self.acquisition_times.get(frame_index)?;
+
+    // When there is no Frame Acquisition Duration the frame lasts until the
+    // next frame starts, and the last frame has the same duration as the
+    // frame before it
+    let duration = match self.acquisition_durations[frame_index] {
+      Some(duration) => duration,
+      None => match self.acquisition_times.get(frame_index + 1) {
+        Some(next_start) => next_start - start,
+        None => frame_index
+          .checked_sub(1)
+          .map(|i| start - self.acquisition_times[i])
+          .unwrap_or(0.0),
+      },
+    };
+
+    let start = Duration::try_from_secs_f64(start).ok()?;
+    let duration = Duration::try_from_secs_f64(duration).ok()?;
+
+    Some(start..(start + duration))
+  }
+}
+
+/// Converts a date/time into a number of seconds since the Unix epoch. Any
+/// date or time components that aren't specified take their lowest value.
+///
+fn date_time_to_seconds(date_time: &StructuredDateTime) -> f64 {
+  let year = i64::from(date_time.year);
+  let month = i64::from(date_time.month.unwrap_or(1));
+  let day = i64::from(date_time.day.unwrap_or(1));
+
+  // Count days since the epoch using the proleptic Gregorian calendar, with
+  // years starting in March so that the leap day comes last
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let day_of_era =
+    year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  let days = era * 146097 + day_of_era - 719468;
+
+  // Time zone offsets have the format +/-HHMM
+  let time_zone_offset = date_time
+    .time_zone_offset
+    .map(|offset| i64::from(offset / 100) * 60 + i64::from(offset % 100))
+    .unwrap_or(0);
+
+  let minutes = days * 1440
+    + i64::from(date_time.hour.unwrap_or(0)) * 60
+    + i64::from(date_time.minute.unwrap_or(0))
+    - time_zone_offset;
+
+  minutes as f64 * 60.0 + date_time.second.unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use super::*;
+
+  use dcmfx_core::DataElementValue;
+
+  fn frame_content_item(date_time: &str, duration: Option<f64>) -> DataSet {
+    let mut frame_content = DataSet::new();
+    frame_content.insert(
+      dictionary::FRAME_ACQUISITION_DATE_TIME.tag,
+      DataElementValue::new_date_time(
+        &StructuredDateTime::from_bytes(date_time.as_bytes()).unwrap(),
+      )
+      .unwrap(),
+    );
+    if let Some(duration) = duration {
+      frame_content
+        .insert_float_value(
+          &dictionary::FRAME_ACQUISITION_DURATION,
+          &[duration],
+        )
+        .unwrap();
+    }
+
+    let mut item = DataSet::new();
+    item
+      .insert_sequence_value(
+        &dictionary::FRAME_CONTENT_SEQUENCE,
+        vec![frame_content],
+      )
+      .unwrap();
+    item
+  }
+
+  #[test]
+  fn frame_interval_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_float_value(&dictionary::FRAME_TIME_VECTOR, &[0.0, 40.0, 60.0])
+      .unwrap();
+
+    let frame_timing = FrameTiming::from_data_set(&data_set).unwrap();
+    assert_eq!(
+      frame_timing.frame_interval(1),
+      Some(Duration::from_millis(40)..Duration::from_millis(100))
+    );
+
+    data_set
+      .insert_sequence_value(
+        &dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+        vec![
+          frame_content_item("20240131235959.5", None),
+          frame_content_item("20240201000000.25", Some(100.0)),
+          frame_content_item("20240201000001", None),
+        ],
+      )
+      .unwrap();
+
+    let frame_timing = FrameTiming::from_data_set(&data_set).unwrap();
+    assert_eq!(
+      frame_timing.frame_interval(0),
+      Some(Duration::ZERO..Duration::from_millis(750))
+    );
+    assert_eq!(
+      frame_timing.frame_interval(1),
+      Some(Duration::from_millis(750)..Duration::from_millis(850))
+    );
+    assert_eq!(
+      frame_timing.frame_interval(2),
+      Some(Duration::from_millis(1500)..Duration::from_millis(2250))
+    );
+    assert_eq!(frame_timing.frame_interval(3), None);
+  }
+
+  #[test]
+  fn date_time_to_seconds_test() {
+    let date_time =
+      StructuredDateTime::from_bytes(b"19700102000001.5+0100").unwrap();
+
+    assert_eq!(date_time_to_seconds(&date_time), 86401.5 - 3600.0);
+
+    let date_time = StructuredDateTime::from_bytes(b"2000").unwrap();
+
+    assert_eq!(date_time_to_seconds(&date_time), 946684800.0);
+  }
+}
@@ -3,8 +3,11 @@ use core::cell::{Ref, RefCell};
 #[cfg(not(feature = "std"))]
 use alloc::vec;
 
+#[cfg(feature = "std")]
+use std::{collections::VecDeque, sync::Mutex};
+
 use dcmfx_core::{
-  DataElementTag, DataError, DataSet, DataSetPath, IodModule,
+  DataElementTag, DataError, DataSet, DataSetPath, IodModule, Rc, SopClass,
   ValueRepresentation,
 };
 
@@ -13,7 +16,7 @@ use crate::{
   dictionary::SOP_CLASS_UID,
   iods::{
     ModalityLutModule, SoftcopyPresentationLutModule, VoiLutModule,
-    voi_lut_module::VoiWindow,
+    functional_groups_module::FunctionalGroups, voi_lut_module::VoiWindow,
   },
 };
 
@@ -43,9 +46,101 @@ pub struct GrayscalePipeline {
   softcopy_presentation_lut_module: SoftcopyPresentationLutModule,
   apply_modality_lut: bool,
 
-  // Internal caches used when the stored value range has <= 2^16 items
-  output_cache_u8: RefCell<Option<StoredValueOutputCache<u8>>>,
-  output_cache_u16: RefCell<Option<StoredValueOutputCache<u16>>>,
+  // Internal caches used when the stored value range has <= 2^16 items. These
+  // are shared with other grayscale pipelines that have the same
+  // configuration, see [`SharedOutputCache`].
+  output_cache_u8: RefCell<Option<Rc<StoredValueOutputCache<u8>>>>,
+  output_cache_u16: RefCell<Option<Rc<StoredValueOutputCache<u16>>>>,
+}
+
+/// The maximum number of output caches of each type that are held in the
+/// shared output caches. When full, the least recently used output cache is
+/// evicted.
+///
+#[cfg(feature = "std")]
+const SHARED_OUTPUT_CACHE_CAPACITY: usize = 16;
+
+/// Output caches that are shared between all grayscale pipelines with the same
+/// configuration. This avoids recomputing identical output caches for every
+/// instance in a series, which commonly all have the same Modality LUT and VOI
+/// LUT.
+///
+#[cfg(feature = "std")]
+static SHARED_OUTPUT_CACHE_U8: SharedOutputCache<u8> = SharedOutputCache::new();
+
+#[cfg(feature = "std")]
+static SHARED_OUTPUT_CACHE_U16: SharedOutputCache<u16> =
+  SharedOutputCache::new();
+
+/// A small cache of output caches keyed by grayscale pipeline configuration.
+/// Entries are found using a hash of the configuration, and are then checked
+/// for an exact match. The most recently used entries are at the front.
+///
+#[cfg(feature = "std")]
+struct SharedOutputCache<T: Copy> {
+  entries: Mutex<VecDeque<SharedOutputCacheEntry<T>>>,
+}
+
+#[cfg(feature = "std")]
+struct SharedOutputCacheEntry<T: Copy> {
+  hash: u64,
+  grayscale_pipeline: GrayscalePipeline,
+  output_cache: Rc<StoredValueOutputCache<T>>,
+}
+
+#[cfg(feature = "std")]
+impl<T: Copy> SharedOutputCache<T> {
+  const fn new() -> Self {
+    Self {
+      entries: Mutex::new(VecDeque::new()),
+    }
+  }
+
+  /// Returns the output cache for the given grayscale pipeline, creating it if
+  /// there isn't a matching one in the shared cache.
+  ///
+  fn get_or_create(
+    &self,
+    grayscale_pipeline: &GrayscalePipeline,
+    create: impl FnOnce() -> StoredValueOutputCache<T>,
+  ) -> Rc<StoredValueOutputCache<T>> {
+    let hash = grayscale_pipeline.configuration_hash();
+
+    {
+      let mut entries = self.entries.lock().unwrap();
+
+      if let Some(index) = entries.iter().position(|entry| {
+        entry.hash == hash
+          && entry
+            .grayscale_pipeline
+            .has_same_configuration(grayscale_pipeline)
+      }) {
+        let entry = entries.remove(index).unwrap();
+        let output_cache = entry.output_cache.clone();
+        entries.push_front(entry);
+
+        return output_cache;
+      }
+    }
+
+    // Create the output cache without holding the lock, as this is the
+    // expensive part
+    let output_cache = Rc::new(create());
+
+    let mut entries = self.entries.lock().unwrap();
+    entries.truncate(SHARED_OUTPUT_CACHE_CAPACITY - 1);
+    entries.push_front(SharedOutputCacheEntry {
+      hash,
+      grayscale_pipeline: grayscale_pipeline.without_output_caches(),
+      output_cache: output_cache.clone(),
+    });
+
+    output_cache
+  }
+
+  fn clear(&self) {
+    self.entries.lock().unwrap().clear();
+  }
 }
 
 impl GrayscalePipeline {
@@ -98,13 +193,8 @@ impl GrayscalePipeline {
     //
     // See https://groups.google.com/g/comp.protocols.dicom/c/UBxhOZ2anJ0/m/b37Hccl7lDkJ
 
-    const XA_XRF_SOP_CLASS_UIDS: &[&str] = &[
-      "1.2.840.10008.5.1.4.1.1.12.1",
-      "1.2.840.10008.5.1.4.1.1.12.2",
-      "1.2.840.10008.5.1.4.1.1.12.3",
-    ];
-
-    let apply_modality_lut = !XA_XRF_SOP_CLASS_UIDS.contains(&sop_class_uid);
+    let apply_modality_lut = !SopClass::from_uid(sop_class_uid)
+      .is_ok_and(|sop_class| sop_class.is_xa_xrf_image_storage());
 
     Ok(GrayscalePipeline {
       stored_value_range,
@@ -151,6 +241,64 @@ impl GrayscalePipeline {
     *self.output_cache_u16.get_mut() = None;
   }
 
+  /// Sets the VOI LUT, overriding the currently active VOI LUT configuration.
+  ///
+  pub fn set_voi_lut(&mut self, voi_lut_module: VoiLutModule) {
+    self.voi_lut_module = voi_lut_module;
+
+    // Clear caches
+    *self.output_cache_u8.get_mut() = None;
+    *self.output_cache_u16.get_mut() = None;
+  }
+
+  /// Returns a copy of this grayscale pipeline with the Modality LUT and VOI
+  /// LUT replaced by those specified in the given functional groups, if
+  /// present. This is used to apply the Pixel Value Transformation and Frame
+  /// VOI LUT functional groups of Enhanced multi-frame IODs.
+  ///
+  pub fn with_functional_groups(&self, groups: &FunctionalGroups) -> Self {
+    let modality_lut_module = groups
+      .modality_lut_module
+      .clone()
+      .unwrap_or_else(|| self.modality_lut_module.clone());
+
+    let modality_lut_output_range =
+      modality_lut_module.output_range(&self.stored_value_range);
+
+    let voi_lut_module = groups
+      .voi_lut_module
+      .clone()
+      .unwrap_or_else(|| self.voi_lut_module.clone());
+
+    Self {
+      stored_value_range: self.stored_value_range.clone(),
+      modality_lut_module,
+      modality_lut_output_range,
+      voi_lut_module,
+      softcopy_presentation_lut_module: self
+        .softcopy_presentation_lut_module
+        .clone(),
+      apply_modality_lut: self.apply_modality_lut,
+
+      output_cache_u8: RefCell::new(None),
+      output_cache_u16: RefCell::new(None),
+    }
+  }
+
+  /// Sets the Softcopy Presentation LUT, overriding the one specified in the
+  /// data set.
+  ///
+  pub fn set_softcopy_presentation_lut(
+    &mut self,
+    softcopy_presentation_lut_module: SoftcopyPresentationLutModule,
+  ) {
+    self.softcopy_presentation_lut_module = softcopy_presentation_lut_module;
+
+    // Clear caches
+    *self.output_cache_u8.get_mut() = None;
+    *self.output_cache_u16.get_mut() = None;
+  }
+
   /// Takes a stored value from pixel data and passes in through the Modality
   /// LUT, VOI LUT, and Softcopy Presentation LUT modules to get a normalized
   /// final Presentation Value (P-Value).
@@ -191,20 +339,77 @@ impl GrayscalePipeline {
       .clamp(0.0, 65535.0) as u16
   }
 
+  /// Takes a stored value from pixel data and passes it through only the
+  /// Modality LUT, returning the resulting modality value, e.g. Hounsfield
+  /// Units for CT. The result isn't normalized.
+  ///
+  /// Unlike [`Self::apply()`], the Modality LUT is also applied for XA/XRF
+  /// images, as its output is meaningful for analysis even though it isn't
+  /// used for display.
+  ///
+  pub fn apply_modality_lut(&self, stored_value: i64) -> f32 {
+    self.modality_lut_module.apply_to_stored_value(stored_value)
+  }
+
+  /// Takes a stored value from pixel data and passes it through only the
+  /// Modality LUT, then normalizes the result into the 0-1 range based on the
+  /// range of possible Modality LUT output values. The VOI LUT and Softcopy
+  /// Presentation LUT are not applied, so the result is a linear mapping of the
+  /// full range of input values.
+  ///
+  pub fn apply_linear(&self, stored_value: i64) -> f32 {
+    let x = if self.apply_modality_lut {
+      self.modality_lut_module.apply_to_stored_value(stored_value)
+    } else {
+      stored_value as f32
+    };
+
+    let start = self.modality_lut_output_range.start();
+    let end = self.modality_lut_output_range.end();
+
+    if end > start {
+      ((x - start) / (end - start)).clamp(0.0, 1.0)
+    } else {
+      0.0
+    }
+  }
+
+  /// The same as [`Self::apply_linear()`] but the normalized value is converted
+  /// to a `u16`.
+  ///
+  pub fn apply_linear_u16(&self, stored_value: i64) -> u16 {
+    (self.apply_linear(stored_value) * 65535.0)
+      .round()
+      .clamp(0.0, 65535.0) as u16
+  }
+
   /// Returns the cache for converting a pixel data stored value into a final
   /// `u8` Presentation Value (P-Value) using this grayscale pipeline.
   ///
   /// Caches are only available when the stored value range has <= 2^16 items.
+  /// Caches are shared between grayscale pipelines that have the same
+  /// configuration, e.g. those for the instances in a series.
   ///
-  pub fn output_cache_u8(&self) -> Ref<'_, Option<StoredValueOutputCache<u8>>> {
+  pub fn output_cache_u8(
+    &self,
+  ) -> Ref<'_, Option<Rc<StoredValueOutputCache<u8>>>> {
     if let Ok(mut output_cache_u8) = self.output_cache_u8.try_borrow_mut()
       && output_cache_u8.is_none()
       && self.is_stored_value_range_cacheable()
     {
-      *output_cache_u8 = Some(StoredValueOutputCache::new(
-        &self.stored_value_range,
-        |pixel| self.apply_u8(pixel),
-      ));
+      let create = || {
+        StoredValueOutputCache::new(&self.stored_value_range, |pixel| {
+          self.apply_u8(pixel)
+        })
+      };
+
+      #[cfg(feature = "std")]
+      let output_cache = SHARED_OUTPUT_CACHE_U8.get_or_create(self, create);
+
+      #[cfg(not(feature = "std"))]
+      let output_cache = Rc::new(create());
+
+      *output_cache_u8 = Some(output_cache);
     }
 
     self.output_cache_u8.borrow()
@@ -214,23 +419,101 @@ impl GrayscalePipeline {
   /// `u16` Presentation Value (P-Value) using this grayscale pipeline.
   ///
   /// Caches are only available when the stored value range has <= 2^16 items.
+  /// Caches are shared between grayscale pipelines that have the same
+  /// configuration, e.g. those for the instances in a series.
   ///
   pub fn output_cache_u16(
     &self,
-  ) -> Ref<'_, Option<StoredValueOutputCache<u16>>> {
+  ) -> Ref<'_, Option<Rc<StoredValueOutputCache<u16>>>> {
     if let Ok(mut output_cache_u16) = self.output_cache_u16.try_borrow_mut()
       && output_cache_u16.is_none()
       && self.is_stored_value_range_cacheable()
     {
-      *output_cache_u16 = Some(StoredValueOutputCache::new(
-        &self.stored_value_range,
-        |pixel| self.apply_u16(pixel),
-      ));
+      let create = || {
+        StoredValueOutputCache::new(&self.stored_value_range, |pixel| {
+          self.apply_u16(pixel)
+        })
+      };
+
+      #[cfg(feature = "std")]
+      let output_cache = SHARED_OUTPUT_CACHE_U16.get_or_create(self, create);
+
+      #[cfg(not(feature = "std"))]
+      let output_cache = Rc::new(create());
+
+      *output_cache_u16 = Some(output_cache);
     }
 
     self.output_cache_u16.borrow()
   }
 
+  /// Clears the output caches that are shared between grayscale pipelines.
+  /// Output caches already in use by grayscale pipelines aren't affected.
+  ///
+  #[cfg(feature = "std")]
+  pub fn clear_shared_output_caches() {
+    SHARED_OUTPUT_CACHE_U8.clear();
+    SHARED_OUTPUT_CACHE_U16.clear();
+  }
+
+  /// Returns whether this grayscale pipeline has the same configuration as
+  /// another, i.e. it converts stored values to the same outputs. Output caches
+  /// aren't compared.
+  ///
+  #[cfg(feature = "std")]
+  fn has_same_configuration(&self, other: &Self) -> bool {
+    self.stored_value_range == other.stored_value_range
+      && self.modality_lut_module == other.modality_lut_module
+      && self.modality_lut_output_range == other.modality_lut_output_range
+      && self.voi_lut_module == other.voi_lut_module
+      && self.softcopy_presentation_lut_module
+        == other.softcopy_presentation_lut_module
+      && self.apply_modality_lut == other.apply_modality_lut
+  }
+
+  /// Returns a copy of this grayscale pipeline without any output caches. The
+  /// output caches aren't accessed, so this can be called while they are
+  /// borrowed.
+  ///
+  #[cfg(feature = "std")]
+  fn without_output_caches(&self) -> Self {
+    Self {
+      stored_value_range: self.stored_value_range.clone(),
+      modality_lut_module: self.modality_lut_module.clone(),
+      modality_lut_output_range: self.modality_lut_output_range.clone(),
+      voi_lut_module: self.voi_lut_module.clone(),
+      softcopy_presentation_lut_module: self
+        .softcopy_presentation_lut_module
+        .clone(),
+      apply_modality_lut: self.apply_modality_lut,
+
+      output_cache_u8: RefCell::new(None),
+      output_cache_u16: RefCell::new(None),
+    }
+  }
+
+  /// Returns a hash of this grayscale pipeline's configuration, including the
+  /// content of any LUTs.
+  ///
+  #[cfg(feature = "std")]
+  fn configuration_hash(&self) -> u64 {
+    use core::hash::{Hash, Hasher};
+
+    use crate::utils::hash_f32;
+
+    let mut hasher = std::hash::DefaultHasher::new();
+
+    self.stored_value_range.hash(&mut hasher);
+    self.modality_lut_module.hash(&mut hasher);
+    hash_f32(*self.modality_lut_output_range.start(), &mut hasher);
+    hash_f32(*self.modality_lut_output_range.end(), &mut hasher);
+    self.voi_lut_module.hash(&mut hasher);
+    self.softcopy_presentation_lut_module.hash(&mut hasher);
+    self.apply_modality_lut.hash(&mut hasher);
+
+    hasher.finish()
+  }
+
   /// Controls whether the stored value range will be cached. Caching only
   /// occurs when the range of stored values has <= 2^16 items.
   ///
@@ -242,7 +525,7 @@ impl GrayscalePipeline {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use dcmfx_core::dictionary::*;
+  use dcmfx_core::{DataElementValue, dictionary::*};
 
   #[test]
   fn test_empty_pipeline() {
@@ -267,6 +550,9 @@ mod tests {
 
     assert_eq!(pipeline.apply(-128), 0.0);
     assert_eq!(pipeline.apply(127), 1.0);
+
+    assert_eq!(pipeline.apply_modality_lut(-128), -231.0);
+    assert_eq!(pipeline.apply_modality_lut(127), 279.0);
   }
 
   #[test]
@@ -293,6 +579,143 @@ mod tests {
     assert_eq!(pipeline.apply(64), 0.5 + (64.0 * 2.0 + 25.0) / 500.0);
   }
 
+  #[test]
+  fn test_linear_ignores_voi_lut() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_float_value(&RESCALE_INTERCEPT, &[25.0])
+      .unwrap();
+    data_set.insert_float_value(&RESCALE_SLOPE, &[2.0]).unwrap();
+    data_set.insert_float_value(&WINDOW_CENTER, &[0.0]).unwrap();
+    data_set.insert_float_value(&WINDOW_WIDTH, &[10.0]).unwrap();
+
+    let pipeline =
+      GrayscalePipeline::from_data_set(&data_set, -128..=127).unwrap();
+
+    assert_eq!(pipeline.apply_linear(-128), 0.0);
+    assert_eq!(pipeline.apply_linear_u16(127), 65535);
+    assert_eq!(pipeline.apply_linear_u16(0), 32896);
+  }
+
+  #[test]
+  fn test_with_functional_groups() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_float_value(&WINDOW_CENTER, &[50.0])
+      .unwrap();
+    data_set
+      .insert_float_value(&WINDOW_WIDTH, &[100.0])
+      .unwrap();
+    data_set
+      .insert_string_value(&VOILUT_FUNCTION, &["LINEAR_EXACT"])
+      .unwrap();
+
+    let pipeline =
+      GrayscalePipeline::from_data_set(&data_set, 0..=100).unwrap();
+
+    let mut pixel_value_transformation = DataSet::new();
+    pixel_value_transformation
+      .insert_float_value(&RESCALE_INTERCEPT, &[-50.0])
+      .unwrap();
+    pixel_value_transformation
+      .insert_float_value(&RESCALE_SLOPE, &[1.0])
+      .unwrap();
+
+    let groups = FunctionalGroups {
+      modality_lut_module: Some(
+        ModalityLutModule::from_data_set(&pixel_value_transformation).unwrap(),
+      ),
+      ..FunctionalGroups::default()
+    };
+
+    let frame_pipeline = pipeline.with_functional_groups(&groups);
+
+    assert_eq!(pipeline.apply(50), 0.5);
+    assert_eq!(frame_pipeline.apply(100), 0.5);
+    assert_eq!(frame_pipeline.voi_lut(), pipeline.voi_lut());
+  }
+
+  #[test]
+  fn test_sigmoid_voi_lut() {
+    let mut data_set = DataSet::new();
+    data_set.insert_float_value(&WINDOW_CENTER, &[0.0]).unwrap();
+    data_set
+      .insert_float_value(&WINDOW_WIDTH, &[200.0])
+      .unwrap();
+    data_set
+      .insert_string_value(&VOILUT_FUNCTION, &["SIGMOID"])
+      .unwrap();
+
+    let pipeline =
+      GrayscalePipeline::from_data_set(&data_set, -1000..=1000).unwrap();
+
+    // Reference values from PS3.3 C.11.2.1.3.1, i.e. 1 / (1 + e^(-4x / w))
+    assert_eq!(pipeline.apply(0), 0.5);
+    assert_eq!(pipeline.apply_u8(-100), 30);
+    assert_eq!(pipeline.apply_u8(50), 186);
+    assert_eq!(pipeline.apply_u16(100), 57723);
+    assert_eq!(pipeline.apply_u8(1000), 255);
+  }
+
+  #[test]
+  fn test_voi_lut_function_applies_to_all_windows() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_float_value(&WINDOW_CENTER, &[40.0, 600.0])
+      .unwrap();
+    data_set
+      .insert_float_value(&WINDOW_WIDTH, &[400.0, 2800.0])
+      .unwrap();
+    data_set
+      .insert_string_value(&VOILUT_FUNCTION, &["LINEAR_EXACT"])
+      .unwrap();
+
+    let pipeline =
+      GrayscalePipeline::from_data_set(&data_set, -1024..=3071).unwrap();
+
+    for window in pipeline.voi_lut().windows() {
+      assert_eq!(window.function().as_str(), "LINEAR_EXACT");
+    }
+
+    assert_eq!(pipeline.apply_u8(-160), 0);
+    assert_eq!(pipeline.apply_u8(140), 191);
+  }
+
+  #[test]
+  fn test_voi_lut_sequence() {
+    let mut lut = DataSet::new();
+    lut.insert(
+      LUT_DESCRIPTOR.tag,
+      DataElementValue::new_lookup_table_descriptor_unchecked(
+        ValueRepresentation::UnsignedShort,
+        vec![4, 0, 10, 0, 16, 0].into(),
+      ),
+    );
+    lut.insert(
+      LUT_DATA.tag,
+      DataElementValue::new_unsigned_short(&[0, 16384, 49152, 65535]).unwrap(),
+    );
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_sequence_value(&VOILUT_SEQUENCE, vec![lut])
+      .unwrap();
+
+    // The window is ignored because a VOI LUT Sequence takes precedence
+    data_set.insert_float_value(&WINDOW_CENTER, &[0.0]).unwrap();
+    data_set.insert_float_value(&WINDOW_WIDTH, &[1.0]).unwrap();
+
+    let pipeline =
+      GrayscalePipeline::from_data_set(&data_set, 0..=100).unwrap();
+
+    assert_eq!(pipeline.apply_u16(0), 0);
+    assert_eq!(pipeline.apply_u16(10), 0);
+    assert_eq!(pipeline.apply_u16(11), 16384);
+    assert_eq!(pipeline.apply_u16(12), 49152);
+    assert_eq!(pipeline.apply_u16(13), 65535);
+    assert_eq!(pipeline.apply_u16(100), 65535);
+  }
+
   #[test]
   fn test_presentation_lut_shape_inverse() {
     let mut data_set = DataSet::new();
@@ -307,4 +730,72 @@ mod tests {
     assert_eq!(pipeline.apply(50), 0.5);
     assert_eq!(pipeline.apply(0), 1.0);
   }
+
+  #[test]
+  fn test_shared_output_caches() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_float_value(&WINDOW_CENTER, &[1234.0])
+      .unwrap();
+    data_set
+      .insert_float_value(&WINDOW_WIDTH, &[567.0])
+      .unwrap();
+
+    let pipeline_a =
+      GrayscalePipeline::from_data_set(&data_set, 0..=4095).unwrap();
+    let pipeline_b =
+      GrayscalePipeline::from_data_set(&data_set, 0..=4095).unwrap();
+
+    data_set
+      .insert_float_value(&WINDOW_WIDTH, &[568.0])
+      .unwrap();
+    let pipeline_c =
+      GrayscalePipeline::from_data_set(&data_set, 0..=4095).unwrap();
+
+    let cache_a = pipeline_a.output_cache_u16().clone().unwrap();
+    let cache_b = pipeline_b.output_cache_u16().clone().unwrap();
+    let cache_c = pipeline_c.output_cache_u16().clone().unwrap();
+
+    assert!(Rc::ptr_eq(&cache_a, &cache_b));
+    assert!(!Rc::ptr_eq(&cache_a, &cache_c));
+    assert_eq!(cache_a.get(1234), pipeline_a.apply_u16(1234));
+  }
+
+  #[test]
+  fn test_configuration_hash_includes_lut_data() {
+    let data_set_with_lut_data = |lut_data: &[u16]| {
+      let mut lut = DataSet::new();
+      lut.insert(
+        LUT_DESCRIPTOR.tag,
+        DataElementValue::new_lookup_table_descriptor_unchecked(
+          ValueRepresentation::UnsignedShort,
+          vec![4, 0, 10, 0, 16, 0].into(),
+        ),
+      );
+      lut.insert(
+        LUT_DATA.tag,
+        DataElementValue::new_unsigned_short(lut_data).unwrap(),
+      );
+
+      let mut data_set = DataSet::new();
+      data_set
+        .insert_sequence_value(&VOILUT_SEQUENCE, vec![lut])
+        .unwrap();
+
+      GrayscalePipeline::from_data_set(&data_set, 0..=100).unwrap()
+    };
+
+    let pipeline_a = data_set_with_lut_data(&[0, 16384, 49152, 65535]);
+    let pipeline_b = data_set_with_lut_data(&[0, 16384, 49152, 65535]);
+    let pipeline_c = data_set_with_lut_data(&[0, 16384, 49153, 65535]);
+
+    assert_eq!(
+      pipeline_a.configuration_hash(),
+      pipeline_b.configuration_hash()
+    );
+    assert_ne!(
+      pipeline_a.configuration_hash(),
+      pipeline_c.configuration_hash()
+    );
+  }
 }
@@ -0,0 +1,268 @@
+//! Hashing of decoded images. Used to detect duplicate frames of pixel data,
+//! including when they have been encoded using different transfer syntaxes.
+//!
+//! Two kinds of hash are provided:
+//!
+//! 1. An exact hash of the stored values. This only matches when the stored
+//!    values are identical, e.g. after a lossless transcode.
+//!
+//! 2. Perceptual hashes, i.e. dHash and pHash, that match images that look the
+//!    same even if their stored values differ slightly, e.g. after a lossy
+//!    transcode. Perceptual hashes are compared using
+//!    [`perceptual_hash_distance()`].
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use sha2::{Digest, Sha256};
+
+/// The width and height of the image that the pHash DCT is computed on.
+///
+const PHASH_SIZE: usize = 32;
+
+/// The width and height of the low frequency block of DCT coefficients used by
+/// pHash.
+///
+const PHASH_LOW_FREQUENCY_SIZE: usize = 8;
+
+/// Returns the number of bits that differ between two perceptual hashes. Lower
+/// values indicate more similar images. A distance of up to around 10 out of
+/// the 64 bits generally indicates the images are visually the same.
+///
+pub fn perceptual_hash_distance(a: u64, b: u64) -> u32 {
+  (a ^ b).count_ones()
+}
+
+/// Incrementally computes the exact hash of an image's stored values. The
+/// image's dimensions and a kind byte that distinguishes how the stored values
+/// are interpreted are included in the hash.
+///
+pub(crate) struct StoredValueHasher {
+  hasher: Sha256,
+  buffer: Vec<u8>,
+}
+
+impl StoredValueHasher {
+  pub fn new(width: u16, height: u16, kind: u8) -> Self {
+    let mut hasher = Sha256::new();
+
+    hasher.update(width.to_le_bytes());
+    hasher.update(height.to_le_bytes());
+    hasher.update([kind]);
+
+    Self {
+      hasher,
+      buffer: Vec::with_capacity(64 * 1024),
+    }
+  }
+
+  /// Adds a stored value to the hash. Stored values are always hashed as 64-bit
+  /// integers so that the hash doesn't depend on the bits allocated.
+  ///
+  pub fn update(&mut self, value: i64) {
+    self.buffer.extend_from_slice(&value.to_le_bytes());
+
+    if self.buffer.len() == self.buffer.capacity() {
+      self.hasher.update(&self.buffer);
+      self.buffer.clear();
+    }
+  }
+
+  pub fn finalize(mut self) -> [u8; 32] {
+    self.hasher.update(&self.buffer);
+    self.hasher.finalize().into()
+  }
+}
+
+/// Computes the dHash of an image from its luminance values. The image is
+/// reduced to 9x8 and each bit of the hash records whether a pixel is brighter
+/// than the pixel to its right.
+///
+pub(crate) fn dhash(luminance: &[f64], width: usize, height: usize) -> u64 {
+  let small = resize(luminance, width, height, 9, 8);
+
+  let mut hash = 0u64;
+
+  for y in 0..8 {
+    for x in 0..8 {
+      hash <<= 1;
+
+      if small[y * 9 + x] > small[y * 9 + x + 1] {
+        hash |= 1;
+      }
+    }
+  }
+
+  hash
+}
+
+/// Computes the pHash of an image from its luminance values. The image is
+/// reduced to 32x32 and a DCT is taken, then each bit of the hash records
+/// whether one of the 8x8 lowest frequency coefficients is above their median.
+/// The DC coefficient is excluded when calculating the median.
+///
+pub(crate) fn phash(luminance: &[f64], width: usize, height: usize) -> u64 {
+  let small = resize(luminance, width, height, PHASH_SIZE, PHASH_SIZE);
+
+  let mut cosines = [[0.0; PHASH_SIZE]; PHASH_LOW_FREQUENCY_SIZE];
+  for (u, row) in cosines.iter_mut().enumerate() {
+    for (x, cosine) in row.iter_mut().enumerate() {
+      *cosine = (core::f64::consts::PI * (2 * x + 1) as f64 * u as f64
+        / (2 * PHASH_SIZE) as f64)
+        .cos();
+    }
+  }
+
+  // Transform the rows, keeping only the low frequency coefficients
+  let mut rows = [[0.0; PHASH_LOW_FREQUENCY_SIZE]; PHASH_SIZE];
+  for (y, row) in rows.iter_mut().enumerate() {
+    for (u, coefficient) in row.iter_mut().enumerate() {
+      *coefficient = (0..PHASH_SIZE)
+        .map(|x| small[y * PHASH_SIZE + x] * cosines[u][x])
+        .sum();
+    }
+  }
+
+  // Transform the columns
+  let mut coefficients =
+    [0.0; PHASH_LOW_FREQUENCY_SIZE * PHASH_LOW_FREQUENCY_SIZE];
+  for v in 0..PHASH_LOW_FREQUENCY_SIZE {
+    for u in 0..PHASH_LOW_FREQUENCY_SIZE {
+      coefficients[v * PHASH_LOW_FREQUENCY_SIZE + u] =
+        (0..PHASH_SIZE).map(|y| rows[y][u] * cosines[v][y]).sum();
+    }
+  }
+
+  let mut ac_coefficients = coefficients[1..].to_vec();
+  ac_coefficients.sort_by(f64::total_cmp);
+  let median = (ac_coefficients[ac_coefficients.len() / 2 - 1]
+    + ac_coefficients[ac_coefficients.len() / 2])
+    / 2.0;
+
+  coefficients.iter().fold(0u64, |hash, coefficient| {
+    (hash << 1) | u64::from(*coefficient > median)
+  })
+}
+
+/// Resizes luminance values to the given dimensions by averaging the area of
+/// the source image that each output pixel covers.
+///
+fn resize(
+  luminance: &[f64],
+  width: usize,
+  height: usize,
+  output_width: usize,
+  output_height: usize,
+) -> Vec<f64> {
+  let mut output = vec![0.0; output_width * output_height];
+
+  if width == 0 || height == 0 {
+    return output;
+  }
+
+  let source_range = |i: usize, size: usize, output_size: usize| {
+    let start = (i * size / output_size).min(size - 1);
+    let end = ((i + 1) * size / output_size).clamp(start + 1, size);
+
+    start..end
+  };
+
+  for oy in 0..output_height {
+    let y_range = source_range(oy, height, output_height);
+
+    for ox in 0..output_width {
+      let x_range = source_range(ox, width, output_width);
+
+      let mut sum = 0.0;
+      for y in y_range.clone() {
+        for x in x_range.clone() {
+          sum += luminance[y * width + x];
+        }
+      }
+
+      output[oy * output_width + ox] =
+        sum / (y_range.len() * x_range.len()) as f64;
+    }
+  }
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn gradient(width: usize, height: usize) -> Vec<f64> {
+    (0..width * height)
+      .map(|i| ((i % width) * (i / width)) as f64)
+      .collect()
+  }
+
+  #[test]
+  fn resize_test() {
+    assert_eq!(resize(&[1.0, 2.0, 3.0, 4.0], 2, 2, 1, 1), vec![2.5]);
+
+    assert_eq!(resize(&[1.0, 2.0], 2, 1, 4, 1), vec![1.0, 1.0, 2.0, 2.0]);
+  }
+
+  #[test]
+  fn dhash_test() {
+    let luminance = gradient(90, 80);
+
+    // Every pixel is darker than the one to its right
+    assert_eq!(dhash(&luminance, 90, 80), 0);
+
+    let inverted: Vec<f64> = luminance.iter().map(|v| -v).collect();
+    assert_eq!(dhash(&inverted, 90, 80), u64::MAX);
+  }
+
+  #[test]
+  fn phash_test() {
+    let luminance: Vec<f64> = (0..64 * 64)
+      .map(|i| {
+        let (x, y) = (i % 64, i / 64);
+        ((x * x + 3 * y * y + 7 * x * y) % 251) as f64
+      })
+      .collect();
+
+    // Scaling and offsetting luminance doesn't change the hash
+    let scaled: Vec<f64> = luminance.iter().map(|v| v * 3.0 + 100.0).collect();
+    assert_eq!(phash(&luminance, 64, 64), phash(&scaled, 64, 64));
+
+    // Small amounts of noise only change the hash slightly
+    let noisy: Vec<f64> = luminance
+      .iter()
+      .enumerate()
+      .map(|(i, v)| v + (i % 7) as f64)
+      .collect();
+    assert!(
+      perceptual_hash_distance(
+        phash(&luminance, 64, 64),
+        phash(&noisy, 64, 64)
+      ) <= 10
+    );
+
+    let inverted: Vec<f64> = luminance.iter().map(|v| -v).collect();
+    assert!(
+      perceptual_hash_distance(
+        phash(&luminance, 64, 64),
+        phash(&inverted, 64, 64)
+      ) > 32
+    );
+  }
+
+  #[test]
+  fn stored_value_hasher_test() {
+    let hash = |width, height, values: &[i64]| {
+      let mut hasher = StoredValueHasher::new(width, height, 0);
+      for value in values {
+        hasher.update(*value);
+      }
+      hasher.finalize()
+    };
+
+    assert_eq!(hash(2, 1, &[1, 2]), hash(2, 1, &[1, 2]));
+    assert_ne!(hash(2, 1, &[1, 2]), hash(2, 1, &[2, 1]));
+    assert_ne!(hash(2, 1, &[1, 2]), hash(1, 2, &[1, 2]));
+  }
+}
@@ -0,0 +1,552 @@
+//! Creates new DICOM instances from images in standard formats such as PNG,
+//! JPEG, and TIFF that have been loaded using the `image` crate.
+//!
+//! The created data sets use either the Secondary Capture Image Storage or VL
+//! Photographic Image Storage SOP Class, and their Image Pixel Module is
+//! derived from the image using [`ImagePixelModule::from_image()`].
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{
+  DataError, DataSet, DcmfxError, SopClass, TransferSyntax,
+  ValueRepresentation, dictionary, sop_class, transfer_syntax,
+};
+use dcmfx_p10::DataSetP10Extensions;
+use sha2::{Digest, Sha256};
+
+use crate::{
+  ColorImage, ColorSpace, DataSetPixelDataExtensions, MonochromeImage,
+  PixelDataDecodeConfig, PixelDataEncodeConfig, PixelDataEncodeError, encode,
+  iods::{
+    ImagePixelModule, ImagePixelModuleOptions,
+    image_pixel_module::BitsAllocated,
+  },
+  transforms::P10PixelDataTranscodeTransformError,
+};
+
+/// Configuration for the creation of a new DICOM instance from an image by
+/// [`import_image()`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageImportConfig {
+  pub(crate) sop_class: &'static SopClass,
+  pub(crate) transfer_syntax: &'static TransferSyntax,
+  pub(crate) encode_config: PixelDataEncodeConfig,
+}
+
+impl Default for ImageImportConfig {
+  fn default() -> Self {
+    Self {
+      sop_class: &sop_class::SECONDARY_CAPTURE_IMAGE_STORAGE,
+      transfer_syntax: &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      encode_config: PixelDataEncodeConfig::default(),
+    }
+  }
+}
+
+impl ImageImportConfig {
+  /// The SOP Class of the created data set. This must be either Secondary
+  /// Capture Image Storage or VL Photographic Image Storage. VL Photographic
+  /// Image Storage requires an image with 8-bit samples.
+  ///
+  /// By default this is Secondary Capture Image Storage.
+  ///
+  pub fn sop_class(mut self, value: &'static SopClass) -> Self {
+    self.sop_class = value;
+    self
+  }
+
+  /// The transfer syntax of the created data set. Pixel data is encoded into
+  /// this transfer syntax, which requires a suitable pixel data encoder to be
+  /// available.
+  ///
+  /// By default this is Explicit VR Little Endian.
+  ///
+  pub fn transfer_syntax(mut self, value: &'static TransferSyntax) -> Self {
+    self.transfer_syntax = value;
+    self
+  }
+
+  /// The configuration used when encoding pixel data into an encapsulated
+  /// transfer syntax.
+  ///
+  pub fn encode_config(mut self, value: PixelDataEncodeConfig) -> Self {
+    self.encode_config = value;
+    self
+  }
+}
+
+/// An error that occurred when creating a new DICOM instance from an image.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageImportError {
+  /// The image can't be stored in the configured SOP Class, e.g. because it
+  /// is too large or its bit depth isn't permitted.
+  ImageNotSupported {
+    details: String,
+  },
+
+  DataError(DataError),
+  PixelDataEncodeError(PixelDataEncodeError),
+  P10PixelDataTranscodeTransformError(P10PixelDataTranscodeTransformError),
+}
+
+impl ImageImportError {
+  /// Returns the name of the error as a human-readable string.
+  ///
+  pub fn name(&self) -> String {
+    match self {
+      Self::ImageNotSupported { .. } => "Image not supported".to_string(),
+      Self::DataError(e) => e.name().to_string(),
+      Self::PixelDataEncodeError(e) => e.name(),
+      Self::P10PixelDataTranscodeTransformError(_) => {
+        "Transcode failed".to_string()
+      }
+    }
+  }
+}
+
+impl core::fmt::Display for ImageImportError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::ImageNotSupported { details } => {
+        write!(f, "Image not supported, details: {details}")
+      }
+      Self::DataError(e) => e.fmt(f),
+      Self::PixelDataEncodeError(e) => e.fmt(f),
+      Self::P10PixelDataTranscodeTransformError(e) => e.fmt(f),
+    }
+  }
+}
+
+impl DcmfxError for ImageImportError {
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::ImageNotSupported { details } => vec![
+        format!("Image import error {task_description}"),
+        "".to_string(),
+        format!("  Error: {}", self.name()),
+        format!("  Details: {details}"),
+      ],
+      Self::DataError(e) => e.to_lines(task_description),
+      Self::PixelDataEncodeError(e) => e.to_lines(task_description),
+      Self::P10PixelDataTranscodeTransformError(e) => {
+        e.to_lines(task_description)
+      }
+    }
+  }
+}
+
+impl From<DataError> for ImageImportError {
+  fn from(e: DataError) -> Self {
+    Self::DataError(e)
+  }
+}
+
+impl From<PixelDataEncodeError> for ImageImportError {
+  fn from(e: PixelDataEncodeError) -> Self {
+    Self::PixelDataEncodeError(e)
+  }
+}
+
+impl From<P10PixelDataTranscodeTransformError> for ImageImportError {
+  fn from(e: P10PixelDataTranscodeTransformError) -> Self {
+    Self::P10PixelDataTranscodeTransformError(e)
+  }
+}
+
+/// An image converted from an [`image::DynamicImage`] ready to be stored as
+/// pixel data.
+///
+enum ImportedImage {
+  Monochrome(MonochromeImage),
+  Color(ColorImage),
+}
+
+/// Creates a new DICOM instance from an image. The returned data set includes
+/// File Meta Information for the configured transfer syntax, and so can be
+/// written straight to a DICOM P10 file.
+///
+/// Alpha channels are discarded, and images with floating point samples are
+/// converted to 16-bit.
+///
+/// Data elements in the template data set, such as patient and study details,
+/// are copied into the created data set and take precedence over the defaults
+/// used for mandatory data elements. The template's File Meta Information,
+/// Image Pixel Module, Pixel Data, SOP Class UID, and SOP Instance UID are
+/// ignored.
+///
+/// Study Instance UID and Series Instance UID are taken from the template if
+/// present. Otherwise they, along with the SOP Instance UID, are generated
+/// from a hash of the template and the image, which means that importing the
+/// same image with the same template always results in the same UIDs.
+///
+pub fn import_image(
+  image: &image::DynamicImage,
+  template: Option<&DataSet>,
+  config: &ImageImportConfig,
+) -> Result<DataSet, ImageImportError> {
+  let not_supported =
+    |details: String| ImageImportError::ImageNotSupported { details };
+
+  let is_vl_photographic =
+    config.sop_class == &sop_class::VL_PHOTOGRAPHIC_IMAGE_STORAGE;
+
+  if config.sop_class != &sop_class::SECONDARY_CAPTURE_IMAGE_STORAGE
+    && !is_vl_photographic
+  {
+    return Err(not_supported(format!(
+      "SOP Class '{}' is not supported for image import",
+      config.sop_class.name
+    )));
+  }
+
+  let imported_image = convert_image(image)?;
+
+  let image_pixel_module = match &imported_image {
+    ImportedImage::Monochrome(image) => {
+      ImagePixelModule::from_image(image, &ImagePixelModuleOptions::default())
+    }
+    ImportedImage::Color(image) => {
+      ImagePixelModule::from_image(image, &ImagePixelModuleOptions::default())
+    }
+  }?;
+
+  if is_vl_photographic && u8::from(image_pixel_module.bits_allocated()) != 8 {
+    return Err(not_supported(
+      "VL Photographic Image Storage requires an image with 8-bit samples"
+        .to_string(),
+    ));
+  }
+
+  // Encode the image into native pixel data
+  let native_transfer_syntax = &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN;
+  let frame = match &imported_image {
+    ImportedImage::Monochrome(image) => encode::encode_monochrome(
+      image,
+      &image_pixel_module,
+      native_transfer_syntax,
+      &config.encode_config,
+    ),
+    ImportedImage::Color(image) => encode::encode_color(
+      image,
+      &image_pixel_module,
+      native_transfer_syntax,
+      &config.encode_config,
+    ),
+  }?;
+
+  let mut pixel_data = frame.to_bytes().to_vec();
+
+  let vr = if image_pixel_module.bits_allocated() == BitsAllocated::Eight {
+    ValueRepresentation::OtherByteString
+  } else {
+    ValueRepresentation::OtherWordString
+  };
+  vr.pad_bytes_to_even_length(&mut pixel_data);
+
+  // Hash the template and pixel data in order to generate UIDs
+  let mut hasher = Sha256::new();
+  if let Some(template) = template {
+    for (tag, value) in template.iter() {
+      hasher.update(tag.to_int().to_be_bytes());
+      if let Ok(bytes) = value.bytes() {
+        hasher.update(&**bytes);
+      }
+    }
+  }
+  hasher.update(&pixel_data);
+  let digest = hasher.finalize();
+
+  let generate_uid = |index: u8| {
+    let mut hasher = Sha256::new();
+    hasher.update(digest);
+    hasher.update([index]);
+
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[0..16]);
+
+    format!("2.25.{}", u128::from_be_bytes(bytes))
+  };
+
+  let mut data_set = DataSet::new();
+  add_default_data_elements(&mut data_set, is_vl_photographic)?;
+
+  data_set.insert_string_value(
+    &dictionary::STUDY_INSTANCE_UID,
+    &[&generate_uid(0)],
+  )?;
+  data_set.insert_string_value(
+    &dictionary::SERIES_INSTANCE_UID,
+    &[&generate_uid(1)],
+  )?;
+
+  // Copy in the template's data elements
+  if let Some(template) = template {
+    for (tag, value) in template.iter() {
+      if tag.group == 0x0002
+        || ImagePixelModule::TAGS.contains(tag)
+        || *tag == dictionary::PIXEL_DATA.tag
+        || *tag == dictionary::NUMBER_OF_FRAMES.tag
+        || *tag == dictionary::SOP_CLASS_UID.tag
+        || *tag == dictionary::SOP_INSTANCE_UID.tag
+      {
+        continue;
+      }
+
+      data_set.insert(*tag, value.clone());
+    }
+  }
+
+  data_set
+    .insert_string_value(&dictionary::SOP_CLASS_UID, &[config.sop_class.uid])?;
+  data_set
+    .insert_string_value(&dictionary::SOP_INSTANCE_UID, &[&generate_uid(2)])?;
+
+  data_set.merge(image_pixel_module.to_data_set()?);
+  data_set.insert_binary_value(
+    dictionary::PIXEL_DATA.tag,
+    vr,
+    pixel_data.into(),
+  )?;
+
+  // Transcode into the target transfer syntax if it's encapsulated
+  let mut data_set = if config.transfer_syntax.is_encapsulated {
+    data_set.synthesize_file_meta_information(native_transfer_syntax, None)?;
+
+    data_set
+      .transcode_pixel_data(
+        config.transfer_syntax,
+        PixelDataDecodeConfig::default(),
+        config.encode_config,
+        None,
+      )?
+      .ok_or_else(|| {
+        not_supported("Image has no valid Image Pixel Module".to_string())
+      })?
+  } else {
+    data_set
+  };
+
+  data_set.synthesize_file_meta_information(config.transfer_syntax, None)?;
+
+  Ok(data_set)
+}
+
+/// Converts an [`image::DynamicImage`] into a [`MonochromeImage`] or
+/// [`ColorImage`].
+///
+fn convert_image(
+  image: &image::DynamicImage,
+) -> Result<ImportedImage, ImageImportError> {
+  let (Ok(width), Ok(height)) =
+    (u16::try_from(image.width()), u16::try_from(image.height()))
+  else {
+    return Err(ImageImportError::ImageNotSupported {
+      details: format!(
+        "Image dimensions {}x{} exceed the maximum of 65535x65535",
+        image.width(),
+        image.height()
+      ),
+    });
+  };
+
+  let imported_image = match image {
+    image::DynamicImage::ImageLuma8(_)
+    | image::DynamicImage::ImageLumaA8(_) => MonochromeImage::new_u8(
+      width,
+      height,
+      image.to_luma8().into_raw(),
+      8,
+      false,
+    )
+    .map(ImportedImage::Monochrome),
+
+    image::DynamicImage::ImageLuma16(_)
+    | image::DynamicImage::ImageLumaA16(_) => MonochromeImage::new_u16(
+      width,
+      height,
+      image.to_luma16().into_raw(),
+      16,
+      false,
+    )
+    .map(ImportedImage::Monochrome),
+
+    image::DynamicImage::ImageRgb8(_) | image::DynamicImage::ImageRgba8(_) => {
+      ColorImage::new_u8(
+        width,
+        height,
+        image.to_rgb8().into_raw(),
+        ColorSpace::Rgb,
+        8,
+      )
+      .map(ImportedImage::Color)
+    }
+
+    _ => ColorImage::new_u16(
+      width,
+      height,
+      image.to_rgb16().into_raw(),
+      ColorSpace::Rgb,
+      16,
+    )
+    .map(ImportedImage::Color),
+  };
+
+  imported_image.map_err(|details| ImageImportError::ImageNotSupported {
+    details: details.to_string(),
+  })
+}
+
+/// Adds the data elements that are mandatory for the Secondary Capture and VL
+/// Photographic IODs and that aren't derived from the image. Where possible,
+/// these are type 2 data elements that are left empty.
+///
+fn add_default_data_elements(
+  data_set: &mut DataSet,
+  is_vl_photographic: bool,
+) -> Result<(), DataError> {
+  for (tag, vr) in [
+    (
+      dictionary::PATIENT_NAME.tag,
+      ValueRepresentation::PersonName,
+    ),
+    (dictionary::PATIENT_ID.tag, ValueRepresentation::LongString),
+    (
+      dictionary::PATIENT_BIRTH_DATE.tag,
+      ValueRepresentation::Date,
+    ),
+    (dictionary::PATIENT_SEX.tag, ValueRepresentation::CodeString),
+    (dictionary::STUDY_DATE.tag, ValueRepresentation::Date),
+    (dictionary::STUDY_TIME.tag, ValueRepresentation::Time),
+    (
+      dictionary::REFERRING_PHYSICIAN_NAME.tag,
+      ValueRepresentation::PersonName,
+    ),
+    (dictionary::STUDY_ID.tag, ValueRepresentation::ShortString),
+    (
+      dictionary::ACCESSION_NUMBER.tag,
+      ValueRepresentation::ShortString,
+    ),
+    (
+      dictionary::SERIES_NUMBER.tag,
+      ValueRepresentation::IntegerString,
+    ),
+    (
+      dictionary::INSTANCE_NUMBER.tag,
+      ValueRepresentation::IntegerString,
+    ),
+    (
+      dictionary::PATIENT_ORIENTATION.tag,
+      ValueRepresentation::CodeString,
+    ),
+  ] {
+    data_set.insert_binary_value(tag, vr, vec![].into())?;
+  }
+
+  data_set.insert_string_value(&dictionary::MANUFACTURER, &["DCMfx"])?;
+
+  if is_vl_photographic {
+    data_set.insert_string_value(&dictionary::MODALITY, &["XC"])?;
+    data_set.insert_string_value(
+      &dictionary::IMAGE_TYPE,
+      &["DERIVED", "SECONDARY"],
+    )?;
+    data_set.insert_binary_value(
+      dictionary::CONTENT_DATE.tag,
+      ValueRepresentation::Date,
+      vec![].into(),
+    )?;
+    data_set.insert_binary_value(
+      dictionary::CONTENT_TIME.tag,
+      ValueRepresentation::Time,
+      vec![].into(),
+    )?;
+    data_set.insert_sequence_value(
+      &dictionary::ACQUISITION_CONTEXT_SEQUENCE,
+      vec![],
+    )?;
+  } else {
+    data_set.insert_string_value(&dictionary::MODALITY, &["OT"])?;
+    data_set.insert_string_value(&dictionary::CONVERSION_TYPE, &["WSD"])?;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn import_image_test() {
+    let image = image::DynamicImage::ImageRgba8(
+      image::RgbaImage::from_raw(2, 1, vec![1, 2, 3, 255, 4, 5, 6, 255])
+        .unwrap(),
+    );
+
+    let mut template = DataSet::new();
+    template
+      .insert_string_value(&dictionary::PATIENT_ID, &["1234"])
+      .unwrap();
+    template
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+
+    let data_set =
+      import_image(&image, Some(&template), &ImageImportConfig::default())
+        .unwrap();
+
+    assert_eq!(data_set.get_string(dictionary::PATIENT_ID.tag), Ok("1234"));
+    assert_eq!(data_set.get_string(dictionary::MODALITY.tag), Ok("OT"));
+    assert_eq!(
+      data_set.get_string(dictionary::SOP_CLASS_UID.tag),
+      Ok(sop_class::SECONDARY_CAPTURE_IMAGE_STORAGE.uid)
+    );
+    assert!(
+      data_set
+        .get_string(dictionary::SOP_INSTANCE_UID.tag)
+        .unwrap()
+        .starts_with("2.25.")
+    );
+    assert_eq!(
+      data_set.get_string(dictionary::PHOTOMETRIC_INTERPRETATION.tag),
+      Ok("RGB")
+    );
+    assert_eq!(
+      data_set.get_pixel_data_color_images().unwrap()[0]
+        .clone()
+        .into_rgb_u8_image()
+        .into_raw(),
+      vec![1, 2, 3, 4, 5, 6]
+    );
+
+    // Importing the same image and template gives the same UIDs
+    assert_eq!(
+      import_image(&image, Some(&template), &ImageImportConfig::default())
+        .unwrap(),
+      data_set
+    );
+  }
+
+  #[test]
+  fn import_image_vl_photographic_16_bit_test() {
+    let image = image::DynamicImage::ImageLuma16(
+      image::ImageBuffer::from_raw(1, 1, vec![1000u16]).unwrap(),
+    );
+
+    let config = ImageImportConfig::default()
+      .sop_class(&sop_class::VL_PHOTOGRAPHIC_IMAGE_STORAGE);
+
+    assert!(matches!(
+      import_image(&image, None, &config),
+      Err(ImageImportError::ImageNotSupported { .. })
+    ));
+  }
+}
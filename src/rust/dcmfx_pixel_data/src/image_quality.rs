@@ -0,0 +1,357 @@
+//! Image quality metrics that compare a distorted image, e.g. the result of
+//! lossy compression, against a reference image.
+//!
+//! Three metrics are provided:
+//!
+//! 1. MSE (Mean Squared Error), in the range 0-1. Lower values indicate less
+//!    distortion, and identical images have an MSE of zero.
+//!
+//! 2. PSNR (Peak Signal-to-Noise Ratio), in decibels. Higher values indicate
+//!    less distortion, and identical images have a PSNR of infinity.
+//!
+//! 3. SSIM (Structural Similarity Index Measure), in the range -1 to 1. Higher
+//!    values indicate more similar images, and identical images have an SSIM
+//!    of 1.
+//!
+//! Samples are normalized into the range 0-1 before the metrics are computed,
+//! so the results are comparable across bit depths and signedness. See
+//! [`MonochromeImage::quality_metrics()`] and
+//! [`ColorImage::quality_metrics()`].
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+
+#[cfg(doc)]
+use crate::{ColorImage, MonochromeImage};
+
+/// The width and height of the window that SSIM is computed over. The final
+/// SSIM is the mean over all windows in the image.
+///
+const SSIM_WINDOW_SIZE: usize = 8;
+
+/// The constants used to stabilize the SSIM division when the means or
+/// variances are close to zero, for samples in the range 0-1.
+///
+const SSIM_C1: f64 = 0.01 * 0.01;
+const SSIM_C2: f64 = 0.03 * 0.03;
+
+/// The MSE, PSNR, and SSIM of a distorted image compared to a reference image.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageQualityMetrics {
+  /// The mean squared error of the normalized samples, in the range 0-1.
+  pub mse: f64,
+
+  /// The peak signal-to-noise ratio in decibels. This is infinity when the
+  /// images are identical.
+  pub psnr: f64,
+
+  /// The mean structural similarity index, in the range -1 to 1.
+  pub ssim: f64,
+}
+
+impl ImageQualityMetrics {
+  /// Computes all image quality metrics for two images whose samples have been
+  /// normalized into the range 0-1. See [`ssim()`] for details on the layout of
+  /// the samples.
+  ///
+  pub fn compute(
+    reference: &[f64],
+    distorted: &[f64],
+    width: usize,
+    height: usize,
+    channels: usize,
+  ) -> Self {
+    let mse = mse(reference, distorted);
+
+    Self {
+      mse,
+      psnr: psnr_from_mse(mse),
+      ssim: ssim(reference, distorted, width, height, channels),
+    }
+  }
+}
+
+/// Returns the mean squared error between two sets of samples that have been
+/// normalized into the range 0-1.
+///
+/// The two sets of samples must be the same length.
+///
+pub fn mse(reference: &[f64], distorted: &[f64]) -> f64 {
+  if reference.is_empty() {
+    return 0.0;
+  }
+
+  let sum: f64 = reference
+    .iter()
+    .zip(distorted)
+    .map(|(a, b)| (a - b) * (a - b))
+    .sum();
+
+  sum / reference.len() as f64
+}
+
+/// Returns the PSNR in decibels between two sets of samples that have been
+/// normalized into the range 0-1. Returns infinity if the samples are
+/// identical.
+///
+/// The two sets of samples must be the same length.
+///
+pub fn psnr(reference: &[f64], distorted: &[f64]) -> f64 {
+  psnr_from_mse(mse(reference, distorted))
+}
+
+/// Converts an MSE for samples in the range 0-1 into a PSNR in decibels.
+///
+fn psnr_from_mse(mse: f64) -> f64 {
+  if mse == 0.0 {
+    f64::INFINITY
+  } else {
+    -10.0 * mse.log10()
+  }
+}
+
+/// Returns the mean SSIM between two images whose samples have been normalized
+/// into the range 0-1. Samples are interleaved, with `channels` samples per
+/// pixel, and SSIM is computed on each channel separately and then averaged.
+///
+/// SSIM is computed over every 8x8 window in the image using a uniform
+/// weighting. Images smaller than 8x8 use a single window that covers the
+/// whole image.
+///
+/// The two sets of samples must be the same length, and be
+/// `width * height * channels` in length.
+///
+pub fn ssim(
+  reference: &[f64],
+  distorted: &[f64],
+  width: usize,
+  height: usize,
+  channels: usize,
+) -> f64 {
+  if width == 0 || height == 0 || channels == 0 {
+    return 1.0;
+  }
+
+  let total: f64 = (0..channels)
+    .map(|channel| {
+      channel_ssim(reference, distorted, width, height, channels, channel)
+    })
+    .sum();
+
+  total / channels as f64
+}
+
+/// Computes the mean SSIM for a single channel of interleaved samples. Sums
+/// over each window are computed in constant time using summed-area tables.
+///
+fn channel_ssim(
+  reference: &[f64],
+  distorted: &[f64],
+  width: usize,
+  height: usize,
+  channels: usize,
+  channel: usize,
+) -> f64 {
+  let window_width = SSIM_WINDOW_SIZE.min(width);
+  let window_height = SSIM_WINDOW_SIZE.min(height);
+
+  let table_width = width + 1;
+  let table_size = table_width * (height + 1);
+
+  // Summed-area tables of x, y, x², y², and xy
+  let mut tables = vec![[0.0f64; 5]; table_size];
+
+  for y in 0..height {
+    let mut row_sums = [0.0f64; 5];
+
+    for x in 0..width {
+      let index = (y * width + x) * channels + channel;
+      let a = reference[index];
+      let b = distorted[index];
+
+      row_sums[0] += a;
+      row_sums[1] += b;
+      row_sums[2] += a * a;
+      row_sums[3] += b * b;
+      row_sums[4] += a * b;
+
+      let above = tables[y * table_width + x + 1];
+      let entry = &mut tables[(y + 1) * table_width + x + 1];
+      for i in 0..5 {
+        entry[i] = above[i] + row_sums[i];
+      }
+    }
+  }
+
+  let window_sum = |x: usize, y: usize, i: usize| {
+    let x1 = x + window_width;
+    let y1 = y + window_height;
+
+    tables[y1 * table_width + x1][i]
+      - tables[y * table_width + x1][i]
+      - tables[y1 * table_width + x][i]
+      + tables[y * table_width + x][i]
+  };
+
+  let n = (window_width * window_height) as f64;
+
+  let mut total = 0.0;
+  let mut window_count = 0usize;
+
+  for y in 0..=(height - window_height) {
+    for x in 0..=(width - window_width) {
+      let mean_a = window_sum(x, y, 0) / n;
+      let mean_b = window_sum(x, y, 1) / n;
+      let variance_a = (window_sum(x, y, 2) / n - mean_a * mean_a).max(0.0);
+      let variance_b = (window_sum(x, y, 3) / n - mean_b * mean_b).max(0.0);
+      let covariance = window_sum(x, y, 4) / n - mean_a * mean_b;
+
+      total += ((2.0 * mean_a * mean_b + SSIM_C1)
+        * (2.0 * covariance + SSIM_C2))
+        / ((mean_a * mean_a + mean_b * mean_b + SSIM_C1)
+          * (variance_a + variance_b + SSIM_C2));
+
+      window_count += 1;
+    }
+  }
+
+  total / window_count as f64
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use super::*;
+  use crate::{ColorImage, ColorSpace, MonochromeImage};
+
+  fn gradient(width: usize, height: usize) -> Vec<f64> {
+    (0..width * height)
+      .map(|i| ((i % width) + (i / width)) as f64 / (width + height) as f64)
+      .collect()
+  }
+
+  #[test]
+  fn mse_test() {
+    assert_eq!(mse(&[], &[]), 0.0);
+    assert_eq!(mse(&[0.25, 0.5], &[0.25, 0.5]), 0.0);
+    assert_eq!(mse(&[0.0, 0.0], &[0.5, 0.0]), 0.125);
+  }
+
+  #[test]
+  fn psnr_test() {
+    assert_eq!(psnr(&[0.0, 0.5, 1.0], &[0.0, 0.5, 1.0]), f64::INFINITY);
+    assert!((psnr(&[0.0, 0.0], &[0.1, 0.1]) - 20.0).abs() < 1e-9);
+    assert!((psnr(&[0.0, 0.0], &[0.5, 0.5]) - 6.0206).abs() < 1e-4);
+  }
+
+  #[test]
+  fn ssim_test() {
+    let reference = gradient(16, 12);
+
+    assert!((ssim(&reference, &reference, 16, 12, 1) - 1.0).abs() < 1e-9);
+
+    let noisy: Vec<f64> = reference
+      .iter()
+      .enumerate()
+      .map(|(i, v)| v + if i % 3 == 0 { 0.05 } else { -0.025 })
+      .collect();
+    let noisy_ssim = ssim(&reference, &noisy, 16, 12, 1);
+    assert!(noisy_ssim > 0.5 && noisy_ssim < 0.99);
+
+    let inverted: Vec<f64> = reference.iter().map(|v| 1.0 - v).collect();
+    assert!(ssim(&reference, &inverted, 16, 12, 1) < 0.0);
+
+    // Images smaller than the window use a single window
+    let small = [0.2, 0.4, 0.6];
+    assert!((ssim(&small, &small, 3, 1, 1) - 1.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn monochrome_bit_depth_test() {
+    let data: Vec<u16> = (0..64).map(|i| i * 4).collect();
+
+    let image_u8 = MonochromeImage::new_u8(
+      8,
+      8,
+      data.iter().map(|v| *v as u8).collect(),
+      8,
+      false,
+    )
+    .unwrap();
+    let image_u16 =
+      MonochromeImage::new_u16(8, 8, data.clone(), 8, false).unwrap();
+
+    let metrics = image_u16.quality_metrics(&image_u8).unwrap();
+    assert_eq!(metrics.mse, 0.0);
+    assert_eq!(metrics.psnr, f64::INFINITY);
+
+    // The same stored values at a higher bits stored are darker
+    let image_u16_12bit =
+      MonochromeImage::new_u16(8, 8, data, 12, false).unwrap();
+    let metrics = image_u16_12bit.quality_metrics(&image_u8).unwrap();
+    assert!(metrics.mse > 0.0);
+    assert!(metrics.psnr.is_finite());
+  }
+
+  #[test]
+  fn monochrome_signedness_test() {
+    let unsigned_data: Vec<u16> = (0..64).map(|i| i * 64).collect();
+    let signed_data: Vec<i16> =
+      unsigned_data.iter().map(|v| *v as i16 - 2048).collect();
+
+    let unsigned_image =
+      MonochromeImage::new_u16(8, 8, unsigned_data, 12, false).unwrap();
+    let signed_image =
+      MonochromeImage::new_i16(8, 8, signed_data, 12, false).unwrap();
+
+    assert_eq!(signed_image.mse(&unsigned_image), Some(0.0));
+    assert_eq!(signed_image.psnr(&unsigned_image), Some(f64::INFINITY));
+  }
+
+  #[test]
+  fn monochrome1_test() {
+    let data: Vec<u8> = (0..64).map(|i| i * 4).collect();
+    let inverted_data: Vec<u8> = data.iter().map(|v| 255 - v).collect();
+
+    let monochrome2 = MonochromeImage::new_u8(8, 8, data, 8, false).unwrap();
+    let monochrome1 =
+      MonochromeImage::new_u8(8, 8, inverted_data, 8, true).unwrap();
+
+    assert_eq!(monochrome1.mse(&monochrome2), Some(0.0));
+  }
+
+  #[test]
+  fn dimensions_mismatch_test() {
+    let a = MonochromeImage::new_u8(2, 2, vec![0; 4], 8, false).unwrap();
+    let b = MonochromeImage::new_u8(4, 1, vec![0; 4], 8, false).unwrap();
+
+    assert_eq!(a.quality_metrics(&b), None);
+    assert_eq!(a.psnr(&b), None);
+  }
+
+  #[test]
+  fn color_test() {
+    let data: Vec<u8> = (0..192).map(|i| i as u8).collect();
+    let brighter_data: Vec<u8> = data.iter().map(|v| v + 10).collect();
+
+    let reference =
+      ColorImage::new_u8(8, 8, data.clone(), ColorSpace::Rgb, 8).unwrap();
+    let identical = ColorImage::new_u8(8, 8, data, ColorSpace::Rgb, 8).unwrap();
+    let brighter =
+      ColorImage::new_u8(8, 8, brighter_data, ColorSpace::Rgb, 8).unwrap();
+
+    let metrics = identical.quality_metrics(&reference).unwrap();
+    assert_eq!(metrics.mse, 0.0);
+    assert_eq!(metrics.psnr, f64::INFINITY);
+    assert!((metrics.ssim - 1.0).abs() < 1e-9);
+
+    let metrics = brighter.quality_metrics(&reference).unwrap();
+    let expected_mse = (10.0f64 / 255.0).powi(2);
+    assert!((metrics.mse - expected_mse).abs() < 1e-12);
+    assert!((metrics.psnr - -10.0 * expected_mse.log10()).abs() < 1e-9);
+    assert!(metrics.ssim < 1.0);
+  }
+}
@@ -0,0 +1,211 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+/// An indexed color image that stores an 8-bit index into a palette of up to
+/// 256 RGB colors for each pixel. This is a compact way of representing palette
+/// color data, or grayscale data that has had a color palette applied, without
+/// expanding it to RGB.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedColorImage {
+  width: u16,
+  height: u16,
+  indices: Vec<u8>,
+  palette: Vec<[u8; 3]>,
+}
+
+impl IndexedColorImage {
+  /// Creates a new indexed color image. There must be one index per pixel, the
+  /// palette must have between 1 and 256 entries, and every index must refer to
+  /// an entry in the palette.
+  ///
+  pub fn new(
+    width: u16,
+    height: u16,
+    indices: Vec<u8>,
+    palette: Vec<[u8; 3]>,
+  ) -> Result<Self, &'static str> {
+    if indices.len() != usize::from(width) * usize::from(height) {
+      return Err("Indexed color image data size is incorrect");
+    }
+
+    if palette.is_empty() || palette.len() > 256 {
+      return Err("Indexed color image palette must have 1-256 entries");
+    }
+
+    if indices.iter().any(|i| usize::from(*i) >= palette.len()) {
+      return Err("Indexed color image index is outside the palette");
+    }
+
+    Ok(Self {
+      width,
+      height,
+      indices,
+      palette,
+    })
+  }
+
+  /// Creates an indexed color image from stored values that are looked up in a
+  /// palette by the passed function. If all stored values are less than 256
+  /// then they are used directly as indices so that the ordering of the source
+  /// palette is preserved. Otherwise, the distinct stored values are assigned
+  /// new indices, which fails if there are more than 256 of them.
+  ///
+  pub(crate) fn from_stored_values<T>(
+    width: u16,
+    height: u16,
+    data: &[T],
+    lookup: impl Fn(T) -> [u8; 3],
+  ) -> Option<Self>
+  where
+    T: Copy + Into<u32> + TryFrom<u32>,
+  {
+    let max_value: u32 = data.iter().map(|v| (*v).into()).max().unwrap_or(0);
+
+    let (indices, palette) = if max_value < 256 {
+      let indices = data.iter().map(|v| Into::<u32>::into(*v) as u8).collect();
+
+      let palette = (0..=max_value)
+        .map(|v| lookup(T::try_from(v).ok().unwrap()))
+        .collect();
+
+      (indices, palette)
+    } else {
+      let mut index_map = vec![None; max_value as usize + 1];
+      let mut palette = vec![];
+
+      let mut indices = Vec::with_capacity(data.len());
+
+      for value in data {
+        let entry = &mut index_map[Into::<u32>::into(*value) as usize];
+
+        let index = match entry {
+          Some(index) => *index,
+          None => {
+            if palette.len() == 256 {
+              return None;
+            }
+
+            let index = palette.len() as u8;
+            palette.push(lookup(*value));
+            *entry = Some(index);
+
+            index
+          }
+        };
+
+        indices.push(index);
+      }
+
+      (indices, palette)
+    };
+
+    Self::new(width, height, indices, palette).ok()
+  }
+
+  /// Returns the width in pixels of this indexed color image.
+  ///
+  pub fn width(&self) -> u16 {
+    self.width
+  }
+
+  /// Returns the height in pixels of this indexed color image.
+  ///
+  pub fn height(&self) -> u16 {
+    self.height
+  }
+
+  /// Returns the palette index of each pixel in this indexed color image.
+  ///
+  pub fn indices(&self) -> &[u8] {
+    &self.indices
+  }
+
+  /// Returns the RGB palette of this indexed color image.
+  ///
+  pub fn palette(&self) -> &[[u8; 3]] {
+    &self.palette
+  }
+
+  /// Converts this indexed color image into its palette indices stored in an
+  /// 8-bit grayscale image, and its palette. This allows geometric operations
+  /// that don't alter pixel values, such as cropping and rotation, to be
+  /// performed on the indices.
+  ///
+  pub fn into_indices_image(self) -> (image::GrayImage, Vec<[u8; 3]>) {
+    let indices = image::GrayImage::from_raw(
+      self.width.into(),
+      self.height.into(),
+      self.indices,
+    )
+    .unwrap();
+
+    (indices, self.palette)
+  }
+
+  /// Converts this indexed color image to an 8-bit RGB image by looking up each
+  /// pixel's color in the palette.
+  ///
+  pub fn to_rgb_u8_image(&self) -> image::RgbImage {
+    let mut rgb_pixels = Vec::with_capacity(self.indices.len() * 3);
+
+    for index in self.indices.iter() {
+      rgb_pixels.extend_from_slice(&self.palette[usize::from(*index)]);
+    }
+
+    image::RgbImage::from_raw(self.width.into(), self.height.into(), rgb_pixels)
+      .unwrap()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn new_test() {
+    assert!(IndexedColorImage::new(2, 1, vec![0, 1], vec![[0; 3]; 2]).is_ok());
+
+    assert_eq!(
+      IndexedColorImage::new(2, 2, vec![0, 1], vec![[0; 3]; 2]),
+      Err("Indexed color image data size is incorrect")
+    );
+
+    assert_eq!(
+      IndexedColorImage::new(0, 0, vec![], vec![]),
+      Err("Indexed color image palette must have 1-256 entries")
+    );
+
+    assert_eq!(
+      IndexedColorImage::new(2, 1, vec![0, 2], vec![[0; 3]; 2]),
+      Err("Indexed color image index is outside the palette")
+    );
+  }
+
+  #[test]
+  fn from_stored_values_test() {
+    let lookup = |v: u16| [(v >> 8) as u8, v as u8, 0];
+
+    let image =
+      IndexedColorImage::from_stored_values(3, 1, &[2u16, 0, 2], lookup)
+        .unwrap();
+    assert_eq!(image.indices(), &[2, 0, 2]);
+    assert_eq!(image.palette(), &[[0, 0, 0], [0, 1, 0], [0, 2, 0]]);
+
+    let image =
+      IndexedColorImage::from_stored_values(3, 1, &[1000u16, 5, 1000], lookup)
+        .unwrap();
+    assert_eq!(image.indices(), &[0, 1, 0]);
+    assert_eq!(image.palette(), &[[3, 232, 0], [0, 5, 0]]);
+    assert_eq!(
+      image.to_rgb_u8_image().into_raw(),
+      vec![3, 232, 0, 0, 5, 0, 3, 232, 0]
+    );
+
+    let data: Vec<u16> = (0..257).map(|v| v * 2).collect();
+    assert_eq!(
+      IndexedColorImage::from_stored_values(257, 1, &data, lookup),
+      None
+    );
+  }
+}
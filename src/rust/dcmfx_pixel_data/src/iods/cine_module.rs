@@ -1,7 +1,7 @@
 #[cfg(not(feature = "std"))]
 use alloc::{format, vec::Vec};
 
-use core::time::Duration;
+use core::{ops::Range, time::Duration};
 
 use dcmfx_core::{
   DataElementTag, DataError, DataSet, DataSetPath, IodModule,
@@ -258,6 +258,38 @@ impl CineModule {
     None
   }
 
+  /// Returns the interval of time during which the specified frame is
+  /// displayed, measured from the start of the first frame. This is calculated
+  /// by summing the durations of the preceding frames, and so handles variable
+  /// frame times specified by a Frame Time Vector.
+  ///
+  /// Returns `None` if frame durations aren't available.
+  ///
+  pub fn frame_interval(
+    &self,
+    frame_index: usize,
+    multiframe_module: &MultiFrameModule,
+  ) -> Option<Range<Duration>> {
+    let mut start = Duration::ZERO;
+    for i in 0..frame_index {
+      start += self.frame_duration(i, multiframe_module)?;
+    }
+
+    // A Frame Time Vector doesn't specify the duration of the last frame, so
+    // fall back to the duration of the preceding frame
+    let duration = self
+      .frame_duration(frame_index, multiframe_module)
+      .or_else(|| {
+        frame_index
+          .checked_sub(1)
+          .and_then(|i| self.frame_duration(i, multiframe_module))
+      })?;
+
+    let end = start + duration;
+
+    Some(start..end)
+  }
+
   fn lookup_frame_time_vector(&self, frame_index: usize) -> Option<Duration> {
     if let Some(frame_time_vector) = self.frame_time_vector.as_ref()
       && let Some(time) = frame_time_vector.get(frame_index + 1)
@@ -305,3 +337,39 @@ impl PreferredPlaybackSequencing {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn frame_interval_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_float_value(&dictionary::FRAME_TIME_VECTOR, &[0.0, 100.0, 50.0])
+      .unwrap();
+
+    let cine_module = CineModule::from_data_set(&data_set).unwrap();
+    let multiframe_module = MultiFrameModule {
+      number_of_frames: Some(3),
+      frame_increment_pointer: Some(dictionary::FRAME_TIME_VECTOR.tag),
+      stereo_pairs_present: None,
+      encapsulated_pixel_data_value_total_length: None,
+    };
+
+    assert_eq!(
+      cine_module.frame_interval(0, &multiframe_module),
+      Some(Duration::ZERO..Duration::from_millis(100))
+    );
+    assert_eq!(
+      cine_module.frame_interval(1, &multiframe_module),
+      Some(Duration::from_millis(100)..Duration::from_millis(150))
+    );
+
+    assert_eq!(
+      cine_module.frame_interval(2, &multiframe_module),
+      Some(Duration::from_millis(150)..Duration::from_millis(200))
+    );
+    assert_eq!(cine_module.frame_interval(3, &multiframe_module), None);
+  }
+}
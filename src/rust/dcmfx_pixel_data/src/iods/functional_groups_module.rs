@@ -0,0 +1,380 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use dcmfx_core::{
+  DataElementTag, DataError, DataSet, DataSetPath, IodModule,
+  StructuredDateTime, ValueRepresentation, data_set_path::DataSetPathEntry,
+  dictionary,
+};
+
+use crate::iods::{ModalityLutModule, VoiLutModule};
+
+/// The attributes of the Multi-frame Functional Groups Module, which is used by
+/// Enhanced multi-frame IODs such as Enhanced CT and Enhanced MR to specify
+/// attributes that are either shared by all frames, or that vary per frame.
+///
+/// Only the functional groups relevant to interpreting and displaying pixel
+/// data are read.
+///
+/// Ref: PS3.3 C.7.6.16.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FunctionalGroupsModule {
+  /// The functional groups from the *'(5200,9229) Shared Functional Groups
+  /// Sequence'*, which apply to all frames.
+  pub shared: FunctionalGroups,
+
+  /// The functional groups from the *'(5200,9230) Per-Frame Functional Groups
+  /// Sequence'*, indexed by frame.
+  pub per_frame: Vec<FunctionalGroups>,
+}
+
+impl FunctionalGroupsModule {
+  /// The data element tags used when reading [`FunctionalGroupsModule`]. All
+  /// data elements nested in these sequences are also used.
+  ///
+  pub const TAGS: [DataElementTag; 2] = [
+    dictionary::SHARED_FUNCTIONAL_GROUPS_SEQUENCE.tag,
+    dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag,
+  ];
+
+  /// Returns whether the given data set has any functional groups.
+  ///
+  pub fn is_present(data_set: &DataSet) -> bool {
+    Self::TAGS.iter().any(|tag| data_set.has(*tag))
+  }
+
+  /// Returns the functional groups that apply to the specified frame, with
+  /// per-frame functional groups taking precedence over shared ones.
+  ///
+  pub fn frame(&self, frame_index: usize) -> FunctionalGroups {
+    match self.per_frame.get(frame_index) {
+      Some(per_frame) => per_frame.or(&self.shared),
+      None => self.shared.clone(),
+    }
+  }
+
+  /// Returns whether any per-frame functional groups specify a Modality LUT
+  /// or VOI LUT, which means frames may need different grayscale pipelines.
+  ///
+  pub fn has_per_frame_grayscale_pipeline(&self) -> bool {
+    self.per_frame.iter().any(|groups| {
+      groups.modality_lut_module.is_some() || groups.voi_lut_module.is_some()
+    })
+  }
+}
+
+impl IodModule for FunctionalGroupsModule {
+  fn is_iod_module_data_element(
+    tag: DataElementTag,
+    _vr: ValueRepresentation,
+    _length: Option<u32>,
+    path: &DataSetPath,
+  ) -> bool {
+    if path.is_root() {
+      return Self::TAGS.contains(&tag);
+    }
+
+    match path.entries().first() {
+      Some(DataSetPathEntry::DataElement { tag }) => Self::TAGS.contains(tag),
+      _ => false,
+    }
+  }
+
+  fn iod_module_highest_tag() -> DataElementTag {
+    dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag
+  }
+
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let tag = dictionary::SHARED_FUNCTIONAL_GROUPS_SEQUENCE.tag;
+    let shared = if data_set.has(tag) {
+      match data_set.get_sequence_items(tag)? {
+        [item] => FunctionalGroups::from_data_set(item)?,
+        _ => {
+          return Err(
+            DataError::new_multiplicity_mismatch()
+              .with_path(&DataSetPath::new_with_data_element(tag)),
+          );
+        }
+      }
+    } else {
+      FunctionalGroups::default()
+    };
+
+    let tag = dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag;
+    let per_frame = if data_set.has(tag) {
+      data_set
+        .get_sequence_items(tag)?
+        .iter()
+        .map(FunctionalGroups::from_data_set)
+        .collect::<Result<Vec<_>, _>>()?
+    } else {
+      Vec::new()
+    };
+
+    Ok(Self { shared, per_frame })
+  }
+}
+
+/// The values read from the functional group macros in a single item of the
+/// shared or per-frame functional groups sequences. Values are `None` when the
+/// relevant functional group isn't present.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FunctionalGroups {
+  /// From the Pixel Measures functional group.
+  pub pixel_spacing: Option<[f32; 2]>,
+
+  /// From the Pixel Measures functional group.
+  pub slice_thickness: Option<f32>,
+
+  /// From the Pixel Measures functional group.
+  pub spacing_between_slices: Option<f32>,
+
+  /// From the Plane Position (Patient) functional group.
+  pub image_position_patient: Option<[f32; 3]>,
+
+  /// From the Plane Orientation (Patient) functional group.
+  pub image_orientation_patient: Option<[f32; 6]>,
+
+  /// From the Pixel Value Transformation functional group.
+  pub modality_lut_module: Option<ModalityLutModule>,
+
+  /// From the Frame VOI LUT functional group.
+  pub voi_lut_module: Option<VoiLutModule>,
+
+  /// From the Frame Content functional group.
+  pub frame_acquisition_date_time: Option<StructuredDateTime>,
+
+  /// From the Frame Content functional group. This is in milliseconds.
+  pub frame_acquisition_duration: Option<f64>,
+}
+
+impl FunctionalGroups {
+  /// Reads the functional groups from an item of the shared or per-frame
+  /// functional groups sequences.
+  ///
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let mut groups = Self::default();
+
+    if let Some(item) =
+      functional_group_item(data_set, dictionary::PIXEL_MEASURES_SEQUENCE.tag)?
+    {
+      groups.pixel_spacing =
+        optional_floats::<2>(item, dictionary::PIXEL_SPACING.tag)?;
+      groups.slice_thickness =
+        optional_floats::<1>(item, dictionary::SLICE_THICKNESS.tag)?
+          .map(|[f]| f);
+      groups.spacing_between_slices =
+        optional_floats::<1>(item, dictionary::SPACING_BETWEEN_SLICES.tag)?
+          .map(|[f]| f);
+    }
+
+    if let Some(item) =
+      functional_group_item(data_set, dictionary::PLANE_POSITION_SEQUENCE.tag)?
+    {
+      groups.image_position_patient =
+        optional_floats::<3>(item, dictionary::IMAGE_POSITION_PATIENT.tag)?;
+    }
+
+    if let Some(item) = functional_group_item(
+      data_set,
+      dictionary::PLANE_ORIENTATION_SEQUENCE.tag,
+    )? {
+      groups.image_orientation_patient =
+        optional_floats::<6>(item, dictionary::IMAGE_ORIENTATION_PATIENT.tag)?;
+    }
+
+    if let Some(item) = functional_group_item(
+      data_set,
+      dictionary::PIXEL_VALUE_TRANSFORMATION_SEQUENCE.tag,
+    )? {
+      groups.modality_lut_module =
+        Some(ModalityLutModule::from_data_set(item)?);
+    }
+
+    if let Some(item) =
+      functional_group_item(data_set, dictionary::FRAME_VOILUT_SEQUENCE.tag)?
+    {
+      groups.voi_lut_module = Some(VoiLutModule::from_data_set(item)?);
+    }
+
+    if let Some(item) =
+      functional_group_item(data_set, dictionary::FRAME_CONTENT_SEQUENCE.tag)?
+    {
+      let tag = dictionary::FRAME_ACQUISITION_DATE_TIME.tag;
+      if item.has(tag) {
+        groups.frame_acquisition_date_time = Some(item.get_date_time(tag)?);
+      }
+
+      let tag = dictionary::FRAME_ACQUISITION_DURATION.tag;
+      if item.has(tag) {
+        groups.frame_acquisition_duration = Some(item.get_float(tag)?);
+      }
+    }
+
+    Ok(groups)
+  }
+
+  /// Returns these functional groups with any values that aren't present
+  /// taken from the passed functional groups.
+  ///
+  pub fn or(&self, other: &Self) -> Self {
+    Self {
+      pixel_spacing: self.pixel_spacing.or(other.pixel_spacing),
+      slice_thickness: self.slice_thickness.or(other.slice_thickness),
+      spacing_between_slices: self
+        .spacing_between_slices
+        .or(other.spacing_between_slices),
+      image_position_patient: self
+        .image_position_patient
+        .or(other.image_position_patient),
+      image_orientation_patient: self
+        .image_orientation_patient
+        .or(other.image_orientation_patient),
+      modality_lut_module: self
+        .modality_lut_module
+        .clone()
+        .or_else(|| other.modality_lut_module.clone()),
+      voi_lut_module: self
+        .voi_lut_module
+        .clone()
+        .or_else(|| other.voi_lut_module.clone()),
+      frame_acquisition_date_time: self
+        .frame_acquisition_date_time
+        .clone()
+        .or_else(|| other.frame_acquisition_date_time.clone()),
+      frame_acquisition_duration: self
+        .frame_acquisition_duration
+        .or(other.frame_acquisition_duration),
+    }
+  }
+}
+
+/// Returns the single item of a functional group sequence, or `None` if the
+/// functional group isn't present.
+///
+fn functional_group_item(
+  data_set: &DataSet,
+  tag: DataElementTag,
+) -> Result<Option<&DataSet>, DataError> {
+  if !data_set.has(tag) {
+    return Ok(None);
+  }
+
+  match data_set.get_sequence_items(tag)? {
+    [item] => Ok(Some(item)),
+    _ => Err(
+      DataError::new_multiplicity_mismatch()
+        .with_path(&DataSetPath::new_with_data_element(tag)),
+    ),
+  }
+}
+
+/// Reads a data element that must have exactly `N` decimal values, returning
+/// `None` if it isn't present.
+///
+fn optional_floats<const N: usize>(
+  data_set: &DataSet,
+  tag: DataElementTag,
+) -> Result<Option<[f32; N]>, DataError> {
+  if !data_set.has(tag) {
+    return Ok(None);
+  }
+
+  let floats = data_set.get_floats(tag)?;
+  if floats.len() != N {
+    return Err(
+      DataError::new_multiplicity_mismatch()
+        .with_path(&DataSetPath::new_with_data_element(tag)),
+    );
+  }
+
+  Ok(Some(core::array::from_fn(|i| floats[i] as f32)))
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use super::*;
+
+  #[test]
+  fn from_data_set_test() {
+    let mut pixel_measures = DataSet::new();
+    pixel_measures
+      .insert_float_value(&dictionary::PIXEL_SPACING, &[0.5, 0.5])
+      .unwrap();
+
+    let mut shared = DataSet::new();
+    shared
+      .insert_sequence_value(
+        &dictionary::PIXEL_MEASURES_SEQUENCE,
+        vec![pixel_measures],
+      )
+      .unwrap();
+
+    let per_frame = [(1.0, 100.0), (2.0, 200.0)]
+      .into_iter()
+      .map(|(z, window_center)| {
+        let mut plane_position = DataSet::new();
+        plane_position
+          .insert_float_value(
+            &dictionary::IMAGE_POSITION_PATIENT,
+            &[0.0, 0.0, z],
+          )
+          .unwrap();
+
+        let mut frame_voi_lut = DataSet::new();
+        frame_voi_lut
+          .insert_float_value(&dictionary::WINDOW_CENTER, &[window_center])
+          .unwrap();
+        frame_voi_lut
+          .insert_float_value(&dictionary::WINDOW_WIDTH, &[50.0])
+          .unwrap();
+
+        let mut item = DataSet::new();
+        item
+          .insert_sequence_value(
+            &dictionary::PLANE_POSITION_SEQUENCE,
+            vec![plane_position],
+          )
+          .unwrap();
+        item
+          .insert_sequence_value(
+            &dictionary::FRAME_VOILUT_SEQUENCE,
+            vec![frame_voi_lut],
+          )
+          .unwrap();
+        item
+      })
+      .collect();
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_sequence_value(
+        &dictionary::SHARED_FUNCTIONAL_GROUPS_SEQUENCE,
+        vec![shared],
+      )
+      .unwrap();
+    data_set
+      .insert_sequence_value(
+        &dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+        per_frame,
+      )
+      .unwrap();
+
+    let module = FunctionalGroupsModule::from_data_set(&data_set).unwrap();
+
+    assert!(module.has_per_frame_grayscale_pipeline());
+
+    let frame = module.frame(1);
+    assert_eq!(frame.pixel_spacing, Some([0.5, 0.5]));
+    assert_eq!(frame.image_position_patient, Some([0.0, 0.0, 2.0]));
+    assert_eq!(frame.voi_lut_module.unwrap().windows[0].center(), 200.0);
+    assert_eq!(frame.modality_lut_module, None);
+
+    assert_eq!(module.frame(2), module.shared);
+  }
+}
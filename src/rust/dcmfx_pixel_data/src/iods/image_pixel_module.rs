@@ -11,7 +11,10 @@ use dcmfx_core::{
   Rc, RcByteSlice, ValueRepresentation, dictionary,
 };
 
-use crate::iods::PaletteColorLookupTableModule;
+use crate::{
+  ColorImage, ColorSpace, MonochromeImage, color_image::ColorImageData,
+  iods::PaletteColorLookupTableModule,
+};
 
 /// Holds values of all of the data elements relevant to decoding and
 /// decompressing pixel data.
@@ -224,9 +227,7 @@ impl ImagePixelModule {
     if image_pixel_module.frame_size_in_bits().div_ceil(8)
       > u64::from(u32::MAX - 1)
     {
-      return Err(DataError::new_value_invalid(
-        "Frame size exceeds 2^32 - 2".to_string(),
-      ));
+      return Err(DataError::new_value_invalid("Frame size exceeds 2^32 - 2"));
     }
 
     Ok(image_pixel_module)
@@ -260,6 +261,118 @@ impl ImagePixelModule {
     )
   }
 
+  /// Creates a new [`ImagePixelModule`] that describes the pixel data of the
+  /// given monochrome or color image, e.g. in order to create a new DICOM
+  /// instance from an image that didn't come from DICOM pixel data.
+  ///
+  /// YBR color images use the `YBR_FULL` photometric interpretation.
+  ///
+  pub fn from_image<'a>(
+    image: impl Into<ImagePixelModuleSource<'a>>,
+    options: &ImagePixelModuleOptions,
+  ) -> Result<Self, DataError> {
+    let image = image.into();
+
+    let (width, height) = match image {
+      ImagePixelModuleSource::Monochrome(image) => {
+        (image.width(), image.height())
+      }
+      ImagePixelModuleSource::Color(image) => (image.width(), image.height()),
+    };
+
+    if width == 0 || height == 0 {
+      return Err(DataError::new_value_invalid(
+        "Image Pixel Module cannot be created from an empty image",
+      ));
+    }
+
+    match image {
+      ImagePixelModuleSource::Monochrome(image) => {
+        let pixel_representation = if image.is_signed() {
+          PixelRepresentation::Signed
+        } else {
+          PixelRepresentation::Unsigned
+        };
+
+        let photometric_interpretation = if image.is_monochrome1() {
+          PhotometricInterpretation::Monochrome1 {
+            pixel_representation,
+          }
+        } else {
+          PhotometricInterpretation::Monochrome2 {
+            pixel_representation,
+          }
+        };
+
+        // Smallest and Largest Image Pixel Value use the US or SS VRs, so are
+        // only included when the bits allocated is no more than 16
+        let (smallest_image_pixel_value, largest_image_pixel_value) =
+          match image.min_max_values() {
+            Some((min, max))
+              if options.include_pixel_value_range
+                && u8::from(image.bits_allocated()) <= 16 =>
+            {
+              (Some(min), Some(max))
+            }
+            _ => (None, None),
+          };
+
+        Self::new(
+          SamplesPerPixel::One,
+          photometric_interpretation,
+          height,
+          width,
+          image.bits_allocated(),
+          image.bits_stored(),
+          image.bits_stored() - 1,
+          None,
+          smallest_image_pixel_value,
+          largest_image_pixel_value,
+          None,
+          None,
+        )
+      }
+
+      ImagePixelModuleSource::Color(image) => {
+        let (samples_per_pixel, photometric_interpretation) = match image.data()
+        {
+          ColorImageData::PaletteU8 { palette, .. }
+          | ColorImageData::PaletteU16 { palette, .. } => (
+            SamplesPerPixel::One,
+            PhotometricInterpretation::PaletteColor {
+              palette: palette.clone(),
+            },
+          ),
+
+          _ => (
+            SamplesPerPixel::Three {
+              planar_configuration: options.planar_configuration,
+            },
+            match image.color_space() {
+              ColorSpace::Rgb => PhotometricInterpretation::Rgb,
+              ColorSpace::Ybr { .. } => PhotometricInterpretation::YbrFull,
+            },
+          ),
+        };
+
+        Self::new(
+          samples_per_pixel,
+          photometric_interpretation,
+          height,
+          width,
+          image.bits_allocated(),
+          image.bits_stored(),
+          image.bits_stored() - 1,
+          None,
+          None,
+          None,
+          None,
+          None,
+        )
+      }
+    }
+  }
+
   /// Returns this image pixel module's number of samples per pixel.
   ///
   pub fn samples_per_pixel(&self) -> SamplesPerPixel {
@@ -456,6 +569,10 @@ impl ImagePixelModule {
       PhotometricInterpretation::YbrFull422 => {
         u8::from(self.bits_allocated) * 2
       }
+
+      PhotometricInterpretation::YbrPartial420 => {
+        u8::from(self.bits_allocated) * 3 / 2
+      }
     }
   }
 
@@ -629,6 +746,51 @@ impl ImagePixelModule {
   }
 }
 
+/// An image that an [`ImagePixelModule`] can be created from using
+/// [`ImagePixelModule::from_image()`].
+///
+#[derive(Clone, Copy, Debug)]
+pub enum ImagePixelModuleSource<'a> {
+  Monochrome(&'a MonochromeImage),
+  Color(&'a ColorImage),
+}
+
+impl<'a> From<&'a MonochromeImage> for ImagePixelModuleSource<'a> {
+  fn from(image: &'a MonochromeImage) -> Self {
+    Self::Monochrome(image)
+  }
+}
+
+impl<'a> From<&'a ColorImage> for ImagePixelModuleSource<'a> {
+  fn from(image: &'a ColorImage) -> Self {
+    Self::Color(image)
+  }
+}
+
+/// Options for [`ImagePixelModule::from_image()`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImagePixelModuleOptions {
+  /// The planar configuration to use for color images that have three samples
+  /// per pixel. Defaults to [`PlanarConfiguration::Interleaved`].
+  pub planar_configuration: PlanarConfiguration,
+
+  /// Whether to include the *'(0028,0106) Smallest Image Pixel Value'* and
+  /// *'(0028,0107) Largest Image Pixel Value'* data elements for monochrome
+  /// images. These are only included when the bits allocated is no more than
+  /// 16. Defaults to `false`.
+  pub include_pixel_value_range: bool,
+}
+
+impl Default for ImagePixelModuleOptions {
+  fn default() -> Self {
+    Self {
+      planar_configuration: PlanarConfiguration::Interleaved,
+      include_pixel_value_range: false,
+    }
+  }
+}
+
 /// Specifies the number of separate planes in the pixel data image. For
 /// monochrome (grayscale) and palette color images, the number of planes is 1.
 /// For RGB and other three vector color models, the number of planes is 3.
@@ -739,6 +901,13 @@ pub enum PhotometricInterpretation {
   /// there are half as many CB and CR values as Y values.
   YbrFull422,
 
+  /// The same as [`PhotometricInterpretation::YbrFull`] except that the Y
+  /// values use a partial range, and the CB and CR values are sampled at half
+  /// the Y rate both horizontally and vertically. This photometric
+  /// interpretation is only used with the MPEG-2, H.264, and HEVC transfer
+  /// syntaxes.
+  YbrPartial420,
+
   /// Irreversible Color Transformation.
   ///
   /// Pixel data represent a color image described by one luminance (Y) and two
@@ -792,13 +961,7 @@ impl PhotometricInterpretation {
       "YBR_FULL" => Ok(Self::YbrFull),
       "YBR_FULL_422" => Ok(Self::YbrFull422),
 
-      "YBR_PARTIAL_420" => Err(
-        DataError::new_value_invalid(
-          "Photometric interpretation 'YBR_PARTIAL_420' is not supported"
-            .to_string(),
-        )
-        .with_path(&DataSetPath::new_with_data_element(tag)),
-      ),
+      "YBR_PARTIAL_420" => Ok(Self::YbrPartial420),
 
       "YBR_ICT" => Ok(Self::YbrIct),
       "YBR_RCT" => Ok(Self::YbrRct),
@@ -855,6 +1018,13 @@ impl PhotometricInterpretation {
     self == &Self::YbrFull422
   }
 
+  /// Returns whether this photometric interpretation specifies YBR 420 color
+  /// data that uses a partial range.
+  ///
+  pub fn is_ybr_partial_420(&self) -> bool {
+    self == &Self::YbrPartial420
+  }
+
   /// Returns whether this photometric interpretation is
   /// [`PhotometricInterpretation::Monochrome1`].
   ///
@@ -908,6 +1078,7 @@ impl PhotometricInterpretation {
       Self::Rgb => "RGB",
       Self::YbrFull => "YBR_FULL",
       Self::YbrFull422 => "YBR_FULL_422",
+      Self::YbrPartial420 => "YBR_PARTIAL_420",
       Self::YbrIct => "YBR_ICT",
       Self::YbrRct => "YBR_RCT",
       Self::Xyb => "XYB",
@@ -926,6 +1097,7 @@ impl core::fmt::Display for PhotometricInterpretation {
       Self::Rgb => "Rgb",
       Self::YbrFull => "YbrFull",
       Self::YbrFull422 => "YbrFull422",
+      Self::YbrPartial420 => "YbrPartial420",
       Self::YbrIct => "YbrIct",
       Self::YbrRct => "YbrRct",
       Self::Xyb => "Xyb",
@@ -1110,6 +1282,9 @@ impl From<PixelRepresentation> for u8 {
 
 #[cfg(test)]
 mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
   use super::*;
 
   #[test]
@@ -1150,4 +1325,65 @@ mod tests {
       0..=i64::from(u32::MAX)
     );
   }
+
+  #[test]
+  fn from_image_test() {
+    let image =
+      MonochromeImage::new_i16(2, 3, vec![-100, 0, 5, 7, 100, 1], 12, false)
+        .unwrap();
+
+    let image_pixel_module = ImagePixelModule::from_image(
+      &image,
+      &ImagePixelModuleOptions {
+        include_pixel_value_range: true,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+
+    assert_eq!(image_pixel_module.rows(), 3);
+    assert_eq!(image_pixel_module.columns(), 2);
+    assert_eq!(image_pixel_module.bits_allocated(), BitsAllocated::Sixteen);
+    assert_eq!(image_pixel_module.bits_stored(), 12);
+    assert_eq!(image_pixel_module.high_bit(), 11);
+    assert_eq!(
+      image_pixel_module.photometric_interpretation(),
+      &PhotometricInterpretation::Monochrome2 {
+        pixel_representation: PixelRepresentation::Signed,
+      }
+    );
+    assert_eq!(image_pixel_module.smallest_image_pixel_value, Some(-100));
+    assert_eq!(image_pixel_module.largest_image_pixel_value, Some(100));
+
+    let image = ColorImage::new_u8(
+      1,
+      1,
+      vec![1, 2, 3],
+      ColorSpace::Ybr { is_422: false },
+      8,
+    )
+    .unwrap();
+
+    let image_pixel_module = ImagePixelModule::from_image(
+      &image,
+      &ImagePixelModuleOptions {
+        planar_configuration: PlanarConfiguration::Separate,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+
+    assert_eq!(
+      image_pixel_module.photometric_interpretation(),
+      &PhotometricInterpretation::YbrFull
+    );
+    assert_eq!(
+      image_pixel_module.samples_per_pixel(),
+      SamplesPerPixel::Three {
+        planar_configuration: PlanarConfiguration::Separate
+      }
+    );
+    assert_eq!(image_pixel_module.bits_allocated(), BitsAllocated::Eight);
+    assert_eq!(image_pixel_module.bits_stored(), 8);
+  }
 }
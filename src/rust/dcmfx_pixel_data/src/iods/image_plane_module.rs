@@ -43,7 +43,7 @@ impl IodModule for ImagePlaneModule {
       _ => {
         return Err(
           DataError::new_value_invalid(
-            "Pixel spacing must have exactly two values".into(),
+            "Pixel spacing must have exactly two values",
           )
           .with_path(&DataSetPath::new_with_data_element(tag)),
         );
@@ -58,7 +58,7 @@ impl IodModule for ImagePlaneModule {
       _ => {
         return Err(
           DataError::new_value_invalid(
-            "Image orientation must have exactly six values".into(),
+            "Image orientation must have exactly six values",
           )
           .with_path(&DataSetPath::new_with_data_element(tag)),
         );
@@ -71,7 +71,7 @@ impl IodModule for ImagePlaneModule {
       _ => {
         return Err(
           DataError::new_value_invalid(
-            "Image position must have exactly three values".into(),
+            "Image position must have exactly three values",
           )
           .with_path(&DataSetPath::new_with_data_element(tag)),
         );
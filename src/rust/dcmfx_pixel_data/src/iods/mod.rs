@@ -1,19 +1,27 @@
 pub mod cine_module;
+pub mod functional_groups_module;
 pub mod image_pixel_module;
 pub mod image_plane_module;
 pub mod modality_lut_module;
 pub mod multi_frame_module;
 pub mod overlay_plane_module;
 pub mod palette_color_lookup_table_module;
+pub mod rt_dose_module;
+pub mod segmentation_module;
 pub mod softcopy_presentation_lut_module;
 pub mod voi_lut_module;
 
 pub use cine_module::CineModule;
-pub use image_pixel_module::ImagePixelModule;
+pub use functional_groups_module::FunctionalGroupsModule;
+pub use image_pixel_module::{
+  ImagePixelModule, ImagePixelModuleOptions, ImagePixelModuleSource,
+};
 pub use image_plane_module::ImagePlaneModule;
 pub use modality_lut_module::ModalityLutModule;
 pub use multi_frame_module::MultiFrameModule;
 pub use overlay_plane_module::OverlayPlaneModule;
 pub use palette_color_lookup_table_module::PaletteColorLookupTableModule;
+pub use rt_dose_module::RtDoseModule;
+pub use segmentation_module::SegmentationModule;
 pub use softcopy_presentation_lut_module::SoftcopyPresentationLutModule;
-pub use voi_lut_module::VoiLutModule;
+pub use voi_lut_module::{VoiLutModule, VoiSelection};
@@ -6,7 +6,7 @@ use dcmfx_core::{
   ValueRepresentation, dictionary,
 };
 
-use crate::LookupTable;
+use crate::{LookupTable, utils::hash_f32};
 
 /// The attributes of the Modality LUT Module which processes raw pixel data
 /// stored values. A modality LUT is defined either by a lookup table, or by
@@ -30,6 +30,31 @@ pub enum ModalityLutModule {
   Identity,
 }
 
+impl core::hash::Hash for ModalityLutModule {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    core::mem::discriminant(self).hash(state);
+
+    match self {
+      Self::LookupTable { lut, lut_type } => {
+        lut.hash(state);
+        lut_type.hash(state);
+      }
+
+      Self::Rescale {
+        rescale_intercept,
+        rescale_slope,
+        rescale_type,
+      } => {
+        hash_f32(*rescale_intercept, state);
+        hash_f32(*rescale_slope, state);
+        rescale_type.hash(state);
+      }
+
+      Self::Identity => (),
+    }
+  }
+}
+
 impl IodModule for ModalityLutModule {
   fn is_iod_module_data_element(
     tag: DataElementTag,
@@ -90,7 +115,7 @@ impl ModalityLutModule {
 
       _ => Err(
         DataError::new_value_invalid(
-          "Modality LUT sequence does not have exactly one item".to_string(),
+          "Modality LUT sequence does not have exactly one item",
         )
         .with_path(&DataSetPath::new_with_data_element(
           dictionary::MODALITY_LUT_SEQUENCE.tag,
@@ -186,7 +211,7 @@ impl ModalityLutModule {
 ///
 /// Ref: PS3.3 C.11.1.1.2.
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 pub enum ModalityLutOutputType {
   OpticalDensity,
   HounsfieldUnits,
@@ -217,4 +242,23 @@ impl ModalityLutOutputType {
       _ => Self::Unrecognized(s.to_string()),
     }
   }
+
+  /// Returns the string value for this output type, as stored in the
+  /// *'(0028,1054) Rescale Type'* and *'(0028,3004) Modality LUT Type'* data
+  /// elements.
+  ///
+  pub fn as_str(&self) -> &str {
+    match self {
+      Self::OpticalDensity => "OD",
+      Self::HounsfieldUnits => "HU",
+      Self::Unspecified => "US",
+      Self::MilligramsPerMilliliter => "MGML",
+      Self::EffectiveAtomicNumber => "Z_EFF",
+      Self::ElectronDensity => "ED",
+      Self::ElectronDensityNormalized => "EDW",
+      Self::HounsfieldUnitsModified => "HU_MOD",
+      Self::Percentage => "PCT",
+      Self::Unrecognized(s) => s,
+    }
+  }
 }
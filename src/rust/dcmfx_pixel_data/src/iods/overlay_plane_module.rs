@@ -220,7 +220,7 @@ impl OverlayPlane {
         DataError::new_value_length_invalid(
           ValueRepresentation::SignedShort,
           2,
-          "Overlay Origin does not have exactly two values".to_string(),
+          "Overlay Origin does not have exactly two values",
         )
         .with_path(&DataSetPath::new_with_data_element(origin_tag)),
       );
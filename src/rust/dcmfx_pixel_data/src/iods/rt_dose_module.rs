@@ -0,0 +1,416 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+use dcmfx_core::{
+  DataElementTag, DataError, DataSet, DataSetPath, IodModule,
+  ValueRepresentation, dictionary,
+};
+
+use crate::{MonochromeImage, iods::ImagePlaneModule};
+
+/// The attributes of the RT Dose Module that describe how to interpret the
+/// dose grid stored in the pixel data of an RT Dose instance.
+///
+/// Ref: PS3.3 C.8.8.3.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct RtDoseModule {
+  pub dose_units: DoseUnits,
+  pub dose_type: String,
+  pub dose_summation_type: String,
+  pub grid_frame_offset_vector: Option<Vec<f64>>,
+  pub dose_grid_scaling: Option<f64>,
+}
+
+impl IodModule for RtDoseModule {
+  fn is_iod_module_data_element(
+    tag: DataElementTag,
+    _vr: ValueRepresentation,
+    _length: Option<u32>,
+    path: &DataSetPath,
+  ) -> bool {
+    if !path.is_root() {
+      return false;
+    }
+
+    tag == dictionary::DOSE_UNITS.tag
+      || tag == dictionary::DOSE_TYPE.tag
+      || tag == dictionary::DOSE_SUMMATION_TYPE.tag
+      || tag == dictionary::GRID_FRAME_OFFSET_VECTOR.tag
+      || tag == dictionary::DOSE_GRID_SCALING.tag
+  }
+
+  fn iod_module_highest_tag() -> DataElementTag {
+    dictionary::DOSE_GRID_SCALING.tag
+  }
+
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let dose_units = DoseUnits::from_data_set(data_set)?;
+
+    let dose_type = data_set
+      .get_string(dictionary::DOSE_TYPE.tag)?
+      .trim()
+      .into();
+
+    let dose_summation_type = data_set
+      .get_string(dictionary::DOSE_SUMMATION_TYPE.tag)?
+      .trim()
+      .into();
+
+    let grid_frame_offset_vector =
+      if data_set.has(dictionary::GRID_FRAME_OFFSET_VECTOR.tag) {
+        Some(data_set.get_floats(dictionary::GRID_FRAME_OFFSET_VECTOR.tag)?)
+      } else {
+        None
+      };
+
+    let dose_grid_scaling = if data_set.has(dictionary::DOSE_GRID_SCALING.tag) {
+      Some(data_set.get_float(dictionary::DOSE_GRID_SCALING.tag)?)
+    } else {
+      None
+    };
+
+    Ok(Self {
+      dose_units,
+      dose_type,
+      dose_summation_type,
+      grid_frame_offset_vector,
+      dose_grid_scaling,
+    })
+  }
+}
+
+impl RtDoseModule {
+  /// Converts the stored values of a frame of RT Dose pixel data into dose
+  /// values by applying the Dose Grid Scaling. The returned dose values are in
+  /// the units specified by [`Self::dose_units`].
+  ///
+  pub fn frame_doses(&self, image: &MonochromeImage) -> Vec<f32> {
+    let scaling = self.dose_grid_scaling.unwrap_or(1.0);
+
+    image
+      .stored_values()
+      .map(|stored_value| (stored_value as f64 * scaling) as f32)
+      .collect()
+  }
+
+  /// Returns the offset of the specified frame along the normal of the image
+  /// plane, relative to the first frame. This is taken from the *'(3004,000C)
+  /// Grid Frame Offset Vector'* data element, which may specify either
+  /// relative offsets where the first value is zero, or absolute offsets where
+  /// the first value is the position of the first frame. Both are handled.
+  ///
+  pub fn frame_offset(&self, frame_index: usize) -> Option<f64> {
+    match self.grid_frame_offset_vector.as_deref() {
+      Some(offsets) => {
+        let first_offset = offsets.first()?;
+        offsets.get(frame_index).map(|offset| offset - first_offset)
+      }
+
+      None if frame_index == 0 => Some(0.0),
+      None => None,
+    }
+  }
+}
+
+/// The units of the dose values in an RT Dose instance.
+///
+/// Ref: PS3.3 C.8.8.3.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DoseUnits {
+  /// Dose values are in Gray.
+  Gray,
+
+  /// Dose values are relative to an implicit reference value.
+  Relative,
+}
+
+impl DoseUnits {
+  /// Creates a new [`DoseUnits`] from the *'(3004,0002) Dose Units'* data
+  /// element in the given data set.
+  ///
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let tag = dictionary::DOSE_UNITS.tag;
+
+    match data_set.get_string(tag)?.trim() {
+      "GY" => Ok(Self::Gray),
+      "RELATIVE" => Ok(Self::Relative),
+      value => Err(
+        DataError::new_value_invalid(format!(
+          "Dose units value of '{value}' is invalid"
+        ))
+        .with_path(&DataSetPath::new_with_data_element(tag)),
+      ),
+    }
+  }
+}
+
+impl core::fmt::Display for DoseUnits {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Gray => f.write_str("GY"),
+      Self::Relative => f.write_str("RELATIVE"),
+    }
+  }
+}
+
+/// A three dimensional RT Dose grid positioned in the patient coordinate
+/// system, which allows the dose at any patient coordinate inside the grid to
+/// be sampled.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct RtDoseGrid {
+  columns: usize,
+  rows: usize,
+  frame_offsets: Vec<f64>,
+  doses: Vec<f32>,
+
+  origin: [f64; 3],
+  row_direction: [f64; 3],
+  column_direction: [f64; 3],
+  normal: [f64; 3],
+  pixel_spacing: [f64; 2],
+}
+
+impl RtDoseGrid {
+  /// Creates a new dose grid from the decoded frames of an RT Dose instance.
+  /// Dose Grid Scaling is applied to the stored values of each frame.
+  ///
+  pub fn new(
+    rt_dose_module: &RtDoseModule,
+    image_plane_module: &ImagePlaneModule,
+    frames: &[MonochromeImage],
+  ) -> Result<Self, String> {
+    let Some(first_frame) = frames.first() else {
+      return Err("RT Dose grid has no frames".into());
+    };
+
+    let columns = usize::from(first_frame.width());
+    let rows = usize::from(first_frame.height());
+
+    let mut frame_offsets = Vec::with_capacity(frames.len());
+    let mut doses = Vec::with_capacity(columns * rows * frames.len());
+
+    for (frame_index, frame) in frames.iter().enumerate() {
+      if usize::from(frame.width()) != columns
+        || usize::from(frame.height()) != rows
+      {
+        return Err("RT Dose grid frames have inconsistent dimensions".into());
+      }
+
+      let Some(offset) = rt_dose_module.frame_offset(frame_index) else {
+        return Err(format!(
+          "RT Dose grid frame offset for frame {frame_index} is missing"
+        ));
+      };
+
+      frame_offsets.push(offset);
+      doses.extend(rt_dose_module.frame_doses(frame));
+    }
+
+    // Frame offsets must be strictly monotonic so that sampling can locate the
+    // frames either side of a point
+    let is_increasing = frame_offsets.windows(2).all(|w| w[0] < w[1]);
+    let is_decreasing = frame_offsets.windows(2).all(|w| w[0] > w[1]);
+    if !is_increasing && !is_decreasing {
+      return Err("RT Dose grid frame offsets are not monotonic".into());
+    }
+
+    let orientation =
+      image_plane_module.image_orientation_patient.map(f64::from);
+    let row_direction = [orientation[0], orientation[1], orientation[2]];
+    let column_direction = [orientation[3], orientation[4], orientation[5]];
+
+    Ok(Self {
+      columns,
+      rows,
+      frame_offsets,
+      doses,
+      origin: image_plane_module.image_position_patient.map(f64::from),
+      row_direction,
+      column_direction,
+      normal: cross(row_direction, column_direction),
+      pixel_spacing: image_plane_module.pixel_spacing.map(f64::from),
+    })
+  }
+
+  /// Returns the number of columns in the dose grid.
+  ///
+  pub fn columns(&self) -> usize {
+    self.columns
+  }
+
+  /// Returns the number of rows in the dose grid.
+  ///
+  pub fn rows(&self) -> usize {
+    self.rows
+  }
+
+  /// Returns the number of frames in the dose grid.
+  ///
+  pub fn frame_count(&self) -> usize {
+    self.frame_offsets.len()
+  }
+
+  /// Returns the dose value at the given column, row, and frame of the dose
+  /// grid.
+  ///
+  pub fn dose(&self, column: usize, row: usize, frame: usize) -> Option<f32> {
+    if column >= self.columns || row >= self.rows {
+      return None;
+    }
+
+    self
+      .doses
+      .get((frame * self.rows + row) * self.columns + column)
+      .copied()
+  }
+
+  /// Samples the dose at a coordinate in the patient coordinate system using
+  /// trilinear interpolation. Returns `None` if the coordinate lies outside the
+  /// dose grid.
+  ///
+  pub fn sample(&self, point: [f64; 3]) -> Option<f32> {
+    let d = [
+      point[0] - self.origin[0],
+      point[1] - self.origin[1],
+      point[2] - self.origin[2],
+    ];
+
+    // Pixel Spacing is the spacing between rows followed by the spacing between
+    // columns
+    let x = dot(d, self.row_direction) / self.pixel_spacing[1];
+    let y = dot(d, self.column_direction) / self.pixel_spacing[0];
+    let (z0, z1, tz) = self.locate_frames(dot(d, self.normal))?;
+
+    let (x0, x1, tx) = locate_index(x, self.columns)?;
+    let (y0, y1, ty) = locate_index(y, self.rows)?;
+
+    let sample_frame = |z: usize| -> Option<f64> {
+      let d00 = f64::from(self.dose(x0, y0, z)?);
+      let d10 = f64::from(self.dose(x1, y0, z)?);
+      let d01 = f64::from(self.dose(x0, y1, z)?);
+      let d11 = f64::from(self.dose(x1, y1, z)?);
+
+      let top = d00 + (d10 - d00) * tx;
+      let bottom = d01 + (d11 - d01) * tx;
+
+      Some(top + (bottom - top) * ty)
+    };
+
+    let a = sample_frame(z0)?;
+    let b = sample_frame(z1)?;
+
+    Some((a + (b - a) * tz) as f32)
+  }
+
+  /// Finds the two frames either side of the given offset along the normal, as
+  /// well as the interpolation weight between them.
+  ///
+  fn locate_frames(&self, offset: f64) -> Option<(usize, usize, f64)> {
+    let offsets = &self.frame_offsets;
+
+    if offsets.len() == 1 {
+      return if (offset - offsets[0]).abs() < 1e-3 {
+        Some((0, 0, 0.0))
+      } else {
+        None
+      };
+    }
+
+    for i in 0..offsets.len() - 1 {
+      let (a, b) = (offsets[i], offsets[i + 1]);
+
+      if offset >= a.min(b) && offset <= a.max(b) {
+        return Some((i, i + 1, (offset - a) / (b - a)));
+      }
+    }
+
+    None
+  }
+}
+
+/// Finds the two indices either side of a fractional grid position, as well as
+/// the interpolation weight between them. Returns `None` if the position lies
+/// outside the grid.
+///
+fn locate_index(position: f64, size: usize) -> Option<(usize, usize, f64)> {
+  if size == 0 || position < 0.0 || position > (size - 1) as f64 {
+    return None;
+  }
+
+  let i0 = position.floor() as usize;
+  let i1 = (i0 + 1).min(size - 1);
+
+  Some((i0, i1, position - i0 as f64))
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+  [
+    a[1] * b[2] - a[2] * b[1],
+    a[2] * b[0] - a[0] * b[2],
+    a[0] * b[1] - a[1] * b[0],
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  fn rt_dose_module() -> RtDoseModule {
+    RtDoseModule {
+      dose_units: DoseUnits::Gray,
+      dose_type: "PHYSICAL".into(),
+      dose_summation_type: "PLAN".into(),
+      grid_frame_offset_vector: Some(vec![-10.0, -8.0]),
+      dose_grid_scaling: Some(0.5),
+    }
+  }
+
+  fn image_plane_module() -> ImagePlaneModule {
+    ImagePlaneModule {
+      pixel_spacing: [2.0, 1.0],
+      image_orientation_patient: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+      image_position_patient: [100.0, 200.0, -10.0],
+      slice_thickness: None,
+      spacing_between_slices: None,
+      slice_location: None,
+    }
+  }
+
+  #[test]
+  fn frame_offset_test() {
+    let module = rt_dose_module();
+
+    assert_eq!(module.frame_offset(0), Some(0.0));
+    assert_eq!(module.frame_offset(1), Some(2.0));
+    assert_eq!(module.frame_offset(2), None);
+  }
+
+  #[test]
+  fn sample_test() {
+    let frames = [
+      MonochromeImage::new_u32(2, 2, vec![0, 2, 4, 6], 32, false).unwrap(),
+      MonochromeImage::new_u32(2, 2, vec![8, 10, 12, 14], 32, false).unwrap(),
+    ];
+
+    let grid =
+      RtDoseGrid::new(&rt_dose_module(), &image_plane_module(), &frames)
+        .unwrap();
+
+    assert_eq!(grid.dose(1, 1, 1), Some(7.0));
+    assert_eq!(grid.sample([100.0, 200.0, -10.0]), Some(0.0));
+    assert_eq!(grid.sample([101.0, 202.0, -8.0]), Some(7.0));
+    assert_eq!(grid.sample([100.5, 201.0, -9.0]), Some(3.5));
+    assert_eq!(grid.sample([99.0, 200.0, -10.0]), None);
+    assert_eq!(grid.sample([100.0, 200.0, -7.0]), None);
+  }
+}
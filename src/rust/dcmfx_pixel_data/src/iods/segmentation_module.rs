@@ -0,0 +1,551 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec, vec::Vec};
+
+use dcmfx_core::{
+  DataElementTag, DataError, DataSet, DataSetPath, IodModule,
+  ValueRepresentation, data_set_path::DataSetPathEntry, dictionary,
+};
+
+use crate::MonochromeImage;
+
+/// The attributes of the Segmentation Image Module, along with the mapping of
+/// frames to segments specified by the Segment Identification functional
+/// group. Together these describe how to interpret the frames of pixel data in
+/// a Segmentation (SEG) instance.
+///
+/// Ref: PS3.3 C.8.20.2, PS3.3 C.8.20.3.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentationModule {
+  pub segmentation_type: SegmentationType,
+  pub segments: Vec<Segment>,
+
+  /// The segment number of each frame of pixel data, indexed by frame.
+  pub frame_segment_numbers: Vec<u16>,
+}
+
+impl SegmentationModule {
+  /// The tags of the data elements in the root data set that are used when
+  /// reading the Segmentation Module. All data elements nested in the segment
+  /// and functional group sequences are also used.
+  ///
+  pub const TAGS: [DataElementTag; 6] = [
+    dictionary::NUMBER_OF_FRAMES.tag,
+    dictionary::SEGMENTATION_TYPE.tag,
+    dictionary::SEGMENT_SEQUENCE.tag,
+    dictionary::MAXIMUM_FRACTIONAL_VALUE.tag,
+    dictionary::SEGMENTATION_FRACTIONAL_TYPE.tag,
+    dictionary::SHARED_FUNCTIONAL_GROUPS_SEQUENCE.tag,
+  ];
+}
+
+impl IodModule for SegmentationModule {
+  fn is_iod_module_data_element(
+    tag: DataElementTag,
+    _vr: ValueRepresentation,
+    _length: Option<u32>,
+    path: &DataSetPath,
+  ) -> bool {
+    if path.is_root() {
+      return Self::TAGS.contains(&tag)
+        || tag == dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag;
+    }
+
+    match path.entries().first() {
+      Some(DataSetPathEntry::DataElement { tag: sequence_tag }) => {
+        *sequence_tag == dictionary::SEGMENT_SEQUENCE.tag
+          || *sequence_tag == dictionary::SHARED_FUNCTIONAL_GROUPS_SEQUENCE.tag
+          || *sequence_tag
+            == dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag
+      }
+
+      _ => false,
+    }
+  }
+
+  fn iod_module_highest_tag() -> DataElementTag {
+    dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag
+  }
+
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let segmentation_type = SegmentationType::from_data_set(data_set)?;
+
+    let segments = data_set
+      .get_sequence_items(dictionary::SEGMENT_SEQUENCE.tag)?
+      .iter()
+      .map(Segment::from_data_set)
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let frame_segment_numbers = frame_segment_numbers(data_set)?;
+
+    // Check that every frame refers to a segment that's defined
+    for segment_number in frame_segment_numbers.iter() {
+      if !segments.iter().any(|s| s.number == *segment_number) {
+        return Err(
+          DataError::new_value_invalid(format!(
+            "Referenced segment number {segment_number} is not in the segment \
+             sequence"
+          ))
+          .with_path(&DataSetPath::new_with_data_element(
+            dictionary::SEGMENT_SEQUENCE.tag,
+          )),
+        );
+      }
+    }
+
+    Ok(Self {
+      segmentation_type,
+      segments,
+      frame_segment_numbers,
+    })
+  }
+}
+
+impl SegmentationModule {
+  /// Returns the segment with the given segment number.
+  ///
+  pub fn segment(&self, segment_number: u16) -> Option<&Segment> {
+    self.segments.iter().find(|s| s.number == segment_number)
+  }
+
+  /// Returns the indices of the frames that hold the given segment.
+  ///
+  pub fn segment_frame_indices(&self, segment_number: u16) -> Vec<usize> {
+    self
+      .frame_segment_numbers
+      .iter()
+      .enumerate()
+      .filter(|(_, number)| **number == segment_number)
+      .map(|(frame_index, _)| frame_index)
+      .collect()
+  }
+
+  /// Converts a decoded frame of segmentation pixel data into a mask for the
+  /// segment the frame holds.
+  ///
+  /// For binary segmentations each mask value is zero or one. For fractional
+  /// segmentations each mask value is the stored value, which is in the range
+  /// zero to the Maximum Fractional Value.
+  ///
+  pub fn segment_mask(
+    &self,
+    frame_index: usize,
+    image: &MonochromeImage,
+  ) -> Result<SegmentMask, DataError> {
+    let Some(segment_number) = self.frame_segment_numbers.get(frame_index)
+    else {
+      return Err(DataError::new_value_invalid(format!(
+        "Frame {frame_index} has no segment number"
+      )));
+    };
+
+    let max_value = match self.segmentation_type {
+      SegmentationType::Binary => 1,
+      SegmentationType::Fractional {
+        maximum_fractional_value,
+        ..
+      } => i64::from(maximum_fractional_value),
+    };
+
+    let data = image
+      .stored_values()
+      .map(|value| value.clamp(0, max_value) as u8)
+      .collect();
+
+    Ok(SegmentMask {
+      segment_number: *segment_number,
+      frame_index,
+      width: image.width(),
+      height: image.height(),
+      data,
+    })
+  }
+}
+
+/// Returns the segment number of each frame, taken from the Segment
+/// Identification functional group in either the per-frame or shared
+/// functional groups.
+///
+fn frame_segment_numbers(data_set: &DataSet) -> Result<Vec<u16>, DataError> {
+  let shared_segment_number = match data_set
+    .get_sequence_items(dictionary::SHARED_FUNCTIONAL_GROUPS_SEQUENCE.tag)
+  {
+    Ok([item]) => referenced_segment_number(item)?,
+    _ => None,
+  };
+
+  if data_set.has(dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag) {
+    let items = data_set.get_sequence_items(
+      dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag,
+    )?;
+
+    return items
+      .iter()
+      .map(|item| {
+        referenced_segment_number(item)?
+          .or(shared_segment_number)
+          .ok_or_else(|| {
+            DataError::new_tag_not_present().with_path(
+              &DataSetPath::new_with_data_element(
+                dictionary::SEGMENT_IDENTIFICATION_SEQUENCE.tag,
+              ),
+            )
+          })
+      })
+      .collect();
+  }
+
+  match shared_segment_number {
+    Some(segment_number) => {
+      let number_of_frames = if data_set.has(dictionary::NUMBER_OF_FRAMES.tag) {
+        data_set.get_int::<usize>(dictionary::NUMBER_OF_FRAMES.tag)?
+      } else {
+        1
+      };
+
+      Ok(vec![segment_number; number_of_frames])
+    }
+
+    None => Err(DataError::new_tag_not_present().with_path(
+      &DataSetPath::new_with_data_element(
+        dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag,
+      ),
+    )),
+  }
+}
+
+/// Reads the *'(0062,000B) Referenced Segment Number'* from the Segment
+/// Identification Sequence in a functional groups item, if present.
+///
+fn referenced_segment_number(
+  functional_groups: &DataSet,
+) -> Result<Option<u16>, DataError> {
+  let tag = dictionary::SEGMENT_IDENTIFICATION_SEQUENCE.tag;
+  if !functional_groups.has(tag) {
+    return Ok(None);
+  }
+
+  match functional_groups.get_sequence_items(tag)? {
+    [item] => Ok(Some(
+      item.get_int::<u16>(dictionary::REFERENCED_SEGMENT_NUMBER.tag)?,
+    )),
+    _ => Err(
+      DataError::new_multiplicity_mismatch()
+        .with_path(&DataSetPath::new_with_data_element(tag)),
+    ),
+  }
+}
+
+/// The type of segmentation stored in a Segmentation instance.
+///
+/// Ref: PS3.3 C.8.20.2.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum SegmentationType {
+  /// Each pixel is either in or out of the segment, and pixel data is stored
+  /// using one bit per pixel.
+  Binary,
+
+  /// Each pixel holds the probability or occupancy of the segment, scaled to
+  /// the range zero to `maximum_fractional_value`.
+  Fractional {
+    fractional_type: SegmentationFractionalType,
+    maximum_fractional_value: u8,
+  },
+}
+
+/// The meaning of the values in a fractional segmentation.
+///
+/// Ref: PS3.3 C.8.20.2.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SegmentationFractionalType {
+  Probability,
+  Occupancy,
+}
+
+impl SegmentationType {
+  /// Creates a new [`SegmentationType`] from the *'(0062,0001) Segmentation
+  /// Type'* and related data elements in the given data set.
+  ///
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let tag = dictionary::SEGMENTATION_TYPE.tag;
+
+    match data_set.get_string(tag)?.trim() {
+      "BINARY" => Ok(Self::Binary),
+
+      "FRACTIONAL" => {
+        let tag = dictionary::SEGMENTATION_FRACTIONAL_TYPE.tag;
+        let fractional_type = match data_set.get_string(tag)?.trim() {
+          "PROBABILITY" => SegmentationFractionalType::Probability,
+          "OCCUPANCY" => SegmentationFractionalType::Occupancy,
+          value => {
+            return Err(
+              DataError::new_value_invalid(format!(
+                "Segmentation fractional type value of '{value}' is invalid"
+              ))
+              .with_path(&DataSetPath::new_with_data_element(tag)),
+            );
+          }
+        };
+
+        let maximum_fractional_value =
+          data_set.get_int::<u8>(dictionary::MAXIMUM_FRACTIONAL_VALUE.tag)?;
+
+        Ok(Self::Fractional {
+          fractional_type,
+          maximum_fractional_value,
+        })
+      }
+
+      value => Err(
+        DataError::new_value_invalid(format!(
+          "Segmentation type value of '{value}' is invalid"
+        ))
+        .with_path(&DataSetPath::new_with_data_element(tag)),
+      ),
+    }
+  }
+}
+
+/// A segment defined by an item in the *'(0062,0002) Segment Sequence'*.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+  pub number: u16,
+  pub label: String,
+  pub description: Option<String>,
+  pub algorithm_type: String,
+}
+
+impl Segment {
+  fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let number = data_set.get_int::<u16>(dictionary::SEGMENT_NUMBER.tag)?;
+
+    let label = data_set
+      .get_string(dictionary::SEGMENT_LABEL.tag)?
+      .trim()
+      .into();
+
+    let description = if data_set.has(dictionary::SEGMENT_DESCRIPTION.tag) {
+      Some(
+        data_set
+          .get_string(dictionary::SEGMENT_DESCRIPTION.tag)?
+          .trim()
+          .into(),
+      )
+    } else {
+      None
+    };
+
+    let algorithm_type = data_set
+      .get_string(dictionary::SEGMENT_ALGORITHM_TYPE.tag)?
+      .trim()
+      .into();
+
+    Ok(Self {
+      number,
+      label,
+      description,
+      algorithm_type,
+    })
+  }
+}
+
+/// The mask of a single segment in one frame of a Segmentation instance.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentMask {
+  pub segment_number: u16,
+  pub frame_index: usize,
+  pub width: u16,
+  pub height: u16,
+
+  /// The mask values in row-major order. See
+  /// [`SegmentationModule::segment_mask()`] for their meaning.
+  pub data: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_data_set_test() {
+    let data_set = segmentation_data_set();
+
+    let module = SegmentationModule::from_data_set(&data_set).unwrap();
+
+    assert_eq!(module.segmentation_type, SegmentationType::Binary);
+    assert_eq!(module.segments.len(), 2);
+    assert_eq!(module.segment(2).unwrap().label, "Tumor");
+    assert_eq!(module.frame_segment_numbers, vec![1, 2, 1]);
+    assert_eq!(module.segment_frame_indices(1), vec![0, 2]);
+  }
+
+  #[test]
+  fn get_segment_masks_test() {
+    use crate::DataSetPixelDataExtensions;
+
+    let data_set = segmentation_data_set_with_pixel_data();
+
+    let masks = data_set.get_segment_masks(1).unwrap();
+    assert_eq!(masks.len(), 2);
+    assert_eq!(masks[0].frame_index, 0);
+    assert_eq!(masks[0].data, vec![1, 0, 1]);
+    assert_eq!(masks[1].frame_index, 2);
+    assert_eq!(masks[1].data, vec![1, 1, 0]);
+
+    let masks = data_set.get_segment_masks(2).unwrap();
+    assert_eq!(masks.len(), 1);
+    assert_eq!(masks[0].segment_number, 2);
+    assert_eq!((masks[0].width, masks[0].height), (3, 1));
+    assert_eq!(masks[0].data, vec![0, 1, 1]);
+  }
+
+  #[test]
+  fn p10_segment_mask_transform_test() {
+    use crate::transforms::P10SegmentMaskTransform;
+    use dcmfx_p10::DataSetP10Extensions;
+
+    let mut transform = P10SegmentMaskTransform::new(&[2]);
+
+    let mut masks = vec![];
+    for token in segmentation_data_set_with_pixel_data().to_p10_tokens() {
+      masks.extend(transform.add_token(&token).unwrap());
+    }
+
+    assert_eq!(
+      masks,
+      vec![SegmentMask {
+        segment_number: 2,
+        frame_index: 1,
+        width: 3,
+        height: 1,
+        data: vec![0, 1, 1],
+      }]
+    );
+  }
+
+  #[test]
+  fn undefined_segment_test() {
+    let mut data_set = segmentation_data_set();
+
+    let mut segment = DataSet::new();
+    segment
+      .insert_int_value(&dictionary::SEGMENT_NUMBER, &[1])
+      .unwrap();
+    segment
+      .insert_string_value(&dictionary::SEGMENT_LABEL, &["Liver"])
+      .unwrap();
+    segment
+      .insert_string_value(&dictionary::SEGMENT_ALGORITHM_TYPE, &["MANUAL"])
+      .unwrap();
+    data_set
+      .insert_sequence_value(&dictionary::SEGMENT_SEQUENCE, vec![segment])
+      .unwrap();
+
+    assert!(SegmentationModule::from_data_set(&data_set).is_err());
+  }
+
+  fn segmentation_data_set_with_pixel_data() -> DataSet {
+    use dcmfx_core::DataElementValue;
+
+    let mut data_set = segmentation_data_set();
+    data_set
+      .insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    data_set
+      .insert_string_value(
+        &dictionary::PHOTOMETRIC_INTERPRETATION,
+        &["MONOCHROME2"],
+      )
+      .unwrap();
+    data_set.insert_int_value(&dictionary::ROWS, &[1]).unwrap();
+    data_set
+      .insert_int_value(&dictionary::COLUMNS, &[3])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::BITS_ALLOCATED, &[1])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::BITS_STORED, &[1])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::HIGH_BIT, &[0])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::PIXEL_REPRESENTATION, &[0])
+      .unwrap();
+    data_set.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_binary(
+        ValueRepresentation::OtherByteString,
+        vec![0b11110101, 0].into(),
+      )
+      .unwrap(),
+    );
+
+    data_set
+  }
+
+  fn segmentation_data_set() -> DataSet {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::SEGMENTATION_TYPE, &["BINARY"])
+      .unwrap();
+
+    let segments = [(1, "Liver"), (2, "Tumor")]
+      .into_iter()
+      .map(|(number, label)| {
+        let mut segment = DataSet::new();
+        segment
+          .insert_int_value(&dictionary::SEGMENT_NUMBER, &[number])
+          .unwrap();
+        segment
+          .insert_string_value(&dictionary::SEGMENT_LABEL, &[label])
+          .unwrap();
+        segment
+          .insert_string_value(&dictionary::SEGMENT_ALGORITHM_TYPE, &["MANUAL"])
+          .unwrap();
+        segment
+      })
+      .collect();
+    data_set
+      .insert_sequence_value(&dictionary::SEGMENT_SEQUENCE, segments)
+      .unwrap();
+
+    let per_frame_items = [1, 2, 1]
+      .into_iter()
+      .map(|segment_number| {
+        let mut segment_identification = DataSet::new();
+        segment_identification
+          .insert_int_value(
+            &dictionary::REFERENCED_SEGMENT_NUMBER,
+            &[segment_number],
+          )
+          .unwrap();
+
+        let mut item = DataSet::new();
+        item
+          .insert_sequence_value(
+            &dictionary::SEGMENT_IDENTIFICATION_SEQUENCE,
+            vec![segment_identification],
+          )
+          .unwrap();
+        item
+      })
+      .collect();
+    data_set
+      .insert_sequence_value(
+        &dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+        per_frame_items,
+      )
+      .unwrap();
+
+    data_set
+  }
+}
@@ -18,7 +18,7 @@ use crate::LookupTable;
 ///
 /// Ref: PS3.3 C.11.4.
 ///
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Hash)]
 pub enum SoftcopyPresentationLutModule {
   LookupTable { lut: LookupTable },
   Shape { shape: PresentationLutShape },
@@ -98,7 +98,7 @@ impl SoftcopyPresentationLutModule {
 ///
 /// Ref: PS3.3 C.11.6.
 ///
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Hash)]
 pub enum PresentationLutShape {
   /// No further translation necessary, input values are P-Values.
   Identity,
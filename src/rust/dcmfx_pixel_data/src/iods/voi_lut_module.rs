@@ -11,7 +11,7 @@ use dcmfx_core::{
   ValueRepresentation, dictionary,
 };
 
-use crate::LookupTable;
+use crate::{LookupTable, utils::hash_f32};
 
 /// The attributes of the VOI LUT Module, which describe a Value Of Interest
 /// (VOI) LUT that is used to define how grayscale pixel data samples are
@@ -22,7 +22,7 @@ use crate::LookupTable;
 ///
 /// Ref: PS3.3 C.11.2.
 ///
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Hash)]
 pub struct VoiLutModule {
   /// The grayscale LUTs for this VOI LUT.
   pub luts: Vec<LookupTable>,
@@ -101,6 +101,42 @@ impl VoiLutModule {
     &self.windows
   }
 
+  /// Returns a copy of this VOI LUT that contains only the grayscale LUT or
+  /// window chosen by the given selection, which will then be used by
+  /// [`Self::apply()`]. Returns `None` if no grayscale LUT or window matches.
+  ///
+  pub fn select(&self, selection: &VoiSelection) -> Option<Self> {
+    let index = match selection {
+      VoiSelection::Index(index) => *index,
+
+      VoiSelection::Explanation(explanation) => {
+        let explanation = explanation.trim();
+
+        self
+          .luts
+          .iter()
+          .map(|lut| lut.explanation().unwrap_or(""))
+          .chain(self.windows.iter().map(|window| window.explanation()))
+          .position(|s| s.trim().eq_ignore_ascii_case(explanation))?
+      }
+    };
+
+    if let Some(lut) = self.luts.get(index) {
+      Some(Self {
+        luts: vec![lut.clone()],
+        windows: vec![],
+      })
+    } else {
+      self
+        .windows
+        .get(index - self.luts.len())
+        .map(|window| Self {
+          luts: vec![],
+          windows: vec![window.clone()],
+        })
+    }
+  }
+
   /// Applies this VOI LUT to an input value. If there are any grayscale LUTs
   /// specified then the first one is used, otherwise if there are any windows
   /// specified then the first one is used. If there are no grayscale LUTs and
@@ -117,6 +153,22 @@ impl VoiLutModule {
   }
 }
 
+/// Selects one of the grayscale LUTs or windows in a [`VoiLutModule`]. See
+/// [`VoiLutModule::select()`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum VoiSelection {
+  /// Selects by index, where the grayscale LUTs come first followed by the
+  /// windows. When there are no grayscale LUTs, which is the common case, this
+  /// is the index of the window.
+  Index(usize),
+
+  /// Selects the first grayscale LUT or window whose *'(0028,3003) LUT
+  /// Explanation'* or *'(0028,1055) Window Center & Width Explanation'*
+  /// matches, ignoring case and surrounding whitespace.
+  Explanation(String),
+}
+
 /// Describes a single VOI LUT windowing function that can be applied in order
 /// to visualize pixel data.
 ///
@@ -137,6 +189,15 @@ pub struct VoiWindow {
   one_over_width: f32,
 }
 
+impl core::hash::Hash for VoiWindow {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    hash_f32(self.center, state);
+    hash_f32(self.width, state);
+    self.explanation.hash(state);
+    self.function.hash(state);
+  }
+}
+
 impl VoiWindow {
   /// Creates [`VoiWindow`]s from the relevant data elements in a data set.
   ///
@@ -160,7 +221,7 @@ impl VoiWindow {
     // Read VOI LUT Function without regard for the specified VR. This makes it
     // readable evevn if the VR is 'OB', which is out of spec but has been
     // observed in the wild from Siemens AXIOM-Artis modalities.
-    let functions = if let Ok(bytes) =
+    let mut functions = if let Ok(bytes) =
       data_set.get_value_bytes(dictionary::VOILUT_FUNCTION.tag)
     {
       let value = DataElementValue::new_binary_unchecked(
@@ -177,6 +238,12 @@ impl VoiWindow {
       vec!["LINEAR".to_string(); centers.len()]
     };
 
+    // The VOI LUT Function has a VM of 1, in which case it applies to every
+    // window
+    if functions.len() == 1 && centers.len() > 1 {
+      functions = vec![functions[0].clone(); centers.len()];
+    }
+
     if centers.len() != widths.len()
       || centers.len() != explanations.len()
       || centers.len() != functions.len()
@@ -244,6 +311,35 @@ impl VoiWindow {
     }
   }
 
+  /// Returns the window center. For the [`VoiLutFunction::Linear`] function
+  /// this is the value as specified, prior to the adjustment made by
+  /// [`Self::new()`].
+  ///
+  pub fn center(&self) -> f32 {
+    match self.function {
+      VoiLutFunction::Linear => self.center + 0.5,
+      _ => self.center,
+    }
+  }
+
+  /// Returns the window width.
+  ///
+  pub fn width(&self) -> f32 {
+    self.width
+  }
+
+  /// Returns the explanation of this window, which may be empty.
+  ///
+  pub fn explanation(&self) -> &str {
+    &self.explanation
+  }
+
+  /// Returns the VOI LUT function used by this window.
+  ///
+  pub fn function(&self) -> VoiLutFunction {
+    self.function
+  }
+
   /// Applies this VOI window to an input value, into an output range of 0-1.
   ///
   pub fn compute(&self, x: f32) -> f32 {
@@ -268,7 +364,7 @@ impl VoiWindow {
 ///
 /// Ref: PS3.3 C.11.2.1.3.
 ///
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 pub enum VoiLutFunction {
   /// A linear conversion. This is the default function if none is specified.
   ///
@@ -308,6 +404,17 @@ impl VoiLutFunction {
       ),
     }
   }
+
+  /// Returns the defined term for this VOI LUT function, as stored in the
+  /// *'(0028,1056) VOI LUT Function'* data element.
+  ///
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Linear => "LINEAR",
+      Self::LinearExact => "LINEAR_EXACT",
+      Self::Sigmoid => "SIGMOID",
+    }
+  }
 }
 
 #[cfg(test)]
@@ -350,6 +457,55 @@ mod tests {
     assert_eq!(window.compute(1000000000.0), 1.0);
   }
 
+  #[test]
+  fn window_accessors() {
+    let window =
+      VoiWindow::new(40.0, 400.0, "SOFT".to_string(), VoiLutFunction::Linear);
+
+    assert_eq!(window.center(), 40.0);
+    assert_eq!(window.width(), 400.0);
+    assert_eq!(window.explanation(), "SOFT");
+    assert_eq!(window.function().as_str(), "LINEAR");
+  }
+
+  #[test]
+  fn select_test() {
+    let voi_lut_module = VoiLutModule {
+      luts: vec![],
+      windows: vec![
+        VoiWindow::new(40.0, 400.0, "SOFT".to_string(), VoiLutFunction::Linear),
+        VoiWindow::new(
+          600.0,
+          2800.0,
+          "BONE".to_string(),
+          VoiLutFunction::Linear,
+        ),
+      ],
+    };
+
+    assert_eq!(
+      voi_lut_module.select(&VoiSelection::Index(1)),
+      Some(VoiLutModule {
+        luts: vec![],
+        windows: vec![voi_lut_module.windows[1].clone()],
+      })
+    );
+
+    assert_eq!(
+      voi_lut_module.select(&VoiSelection::Explanation(" soft".to_string())),
+      Some(VoiLutModule {
+        luts: vec![],
+        windows: vec![voi_lut_module.windows[0].clone()],
+      })
+    );
+
+    assert_eq!(voi_lut_module.select(&VoiSelection::Index(2)), None);
+    assert_eq!(
+      voi_lut_module.select(&VoiSelection::Explanation("LUNG".to_string())),
+      None
+    );
+  }
+
   #[test]
   fn voi_lut_function_from_string() {
     assert_eq!(
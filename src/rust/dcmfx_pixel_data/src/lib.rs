@@ -1,4 +1,8 @@
 //! Access pixel data in a DICOM data set.
+//!
+//! Decoding and encoding of pixel data never panics on invalid or untrusted
+//! input, and instead returns an error. This is checked by the `decode_frame`
+//! and `encode_frame` fuzz targets in `dcmfx_fuzz`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -13,31 +17,62 @@ mod no_std_allocator;
 
 mod color_image;
 pub mod decode;
+pub mod duplicate_frames;
 pub mod encode;
+pub mod frame_selection;
+pub mod frame_timing;
 mod grayscale_pipeline;
+pub mod image_hash;
+pub mod image_import;
+pub mod image_quality;
+mod indexed_color_image;
 pub mod iods;
 #[cfg(all(feature = "native", feature = "std"))]
 mod jpeg_xl_jpeg_recompression;
 mod lookup_table;
 mod monochrome_image;
+mod pixel_data_fragment_map;
 mod pixel_data_frame;
+mod pixel_data_frame_iter;
 mod pixel_data_renderer;
+mod render_profile;
+pub mod series_geometry;
+pub mod series_summary;
 pub mod standard_color_palettes;
 mod stored_value_output_cache;
 pub mod transforms;
 mod utils;
+pub mod video_remux;
+pub mod volume_consistency;
 
 pub use color_image::{ColorImage, ColorSpace};
-pub use decode::{PixelDataDecodeConfig, PixelDataDecodeError};
-pub use encode::{PixelDataEncodeConfig, PixelDataEncodeError};
+pub use decode::{
+  CodestreamProgression, ColorTransform, DecodedFrameInfo,
+  PixelDataDecodeConfig, PixelDataDecodeError,
+};
+pub use encode::{
+  PixelDataEncodeConfig, PixelDataEncodeError, PixelDataSizeEstimate,
+  PixelDataSizeEstimateAccuracy,
+};
+pub use frame_selection::FrameSelection;
+pub use frame_timing::FrameTiming;
 pub use grayscale_pipeline::GrayscalePipeline;
+pub use indexed_color_image::IndexedColorImage;
 pub use lookup_table::LookupTable;
 pub use monochrome_image::{MonochromeImage, MonochromeImageData};
+pub use pixel_data_fragment_map::{
+  PixelDataFragment, PixelDataFragmentMap, PixelDataFrameFragments,
+};
 pub use pixel_data_frame::PixelDataFrame;
-pub use pixel_data_renderer::PixelDataRenderer;
+pub use pixel_data_frame_iter::{PixelDataFrameIter, iter_pixel_data_frames};
+pub use pixel_data_renderer::{GrayscaleOutputDepth, PixelDataRenderer};
+pub use render_profile::{RenderProfile, RenderProfiles};
+pub use series_geometry::SeriesGeometry;
 pub use standard_color_palettes::StandardColorPalette;
 pub use stored_value_output_cache::StoredValueOutputCache;
+pub use video_remux::{Mp4RemuxError, VideoCodec, remux_to_mp4};
 
+use iods::{SegmentationModule, segmentation_module::SegmentMask};
 use transforms::{
   P10PixelDataFrameTransform, P10PixelDataFrameTransformError,
   P10PixelDataTranscodeTransform, P10PixelDataTranscodeTransformError,
@@ -66,6 +101,15 @@ where
     &self,
   ) -> Result<Vec<PixelDataFrame>, P10PixelDataFrameTransformError>;
 
+  /// Returns the map of fragments that make up each frame of the encapsulated
+  /// pixel data in this data set. No pixel data is copied.
+  ///
+  /// See [`PixelDataFragmentMap::from_data_set()`] for details.
+  ///
+  fn get_pixel_data_fragment_map(
+    &self,
+  ) -> Result<PixelDataFragmentMap, DataError>;
+
   /// Returns the frames of pixel data in this data set as fully resolved RGB8
   /// images. Output image data may have been subjected to a lossy conversion to
   /// 8-bit depth.
@@ -83,6 +127,33 @@ where
     color_palette: Option<&StandardColorPalette>,
   ) -> Result<Vec<image::RgbImage>, GetPixelDataError>;
 
+  /// Returns the frames of monochrome pixel data in this data set as 16-bit
+  /// grayscale images. Any Modality LUT and VOI LUT present in the data set
+  /// are applied, and the output retains more precision than
+  /// [`Self::get_pixel_data_images()`].
+  ///
+  /// This will only succeed when the pixel data uses a monochrome photometric
+  /// interpretation.
+  ///
+  #[allow(clippy::type_complexity)]
+  fn get_pixel_data_gray_u16_images(
+    &self,
+  ) -> Result<
+    Vec<image::ImageBuffer<image::Luma<u16>, Vec<u16>>>,
+    GetPixelDataError,
+  >;
+
+  /// Returns the frames of monochrome pixel data in this data set as modality
+  /// values, e.g. Hounsfield Units for CT, with the Modality LUT applied but
+  /// no VOI LUT windowing. Each frame's values are in row-major order.
+  ///
+  /// This will only succeed when the pixel data uses a monochrome photometric
+  /// interpretation.
+  ///
+  fn get_pixel_data_f32_frames(
+    &self,
+  ) -> Result<Vec<Vec<f32>>, GetPixelDataError>;
+
   /// Returns the frames of pixel data in this data set as [`MonochromeImage`]s.
   ///
   /// This will only succeed when the pixel data uses a monochrome photometric
@@ -101,6 +172,18 @@ where
     &self,
   ) -> Result<Vec<ColorImage>, GetPixelDataError>;
 
+  /// Returns the masks for the specified segment in this Segmentation (SEG)
+  /// data set, one for each frame that holds the segment. Binary
+  /// segmentations stored using one bit per pixel are unpacked.
+  ///
+  /// See [`SegmentationModule::segment_mask()`] for the meaning of the mask
+  /// values.
+  ///
+  fn get_segment_masks(
+    &self,
+    segment_number: u16,
+  ) -> Result<Vec<SegmentMask>, GetPixelDataError>;
+
   /// Transcode's the pixel data in this data set into a new data set that uses
   /// the specified [`TransferSyntax`]. If this data set does not contain a
   /// valid Image Pixel Module then no transcoding will occur and `Ok(None)` is
@@ -124,6 +207,7 @@ impl DataSetPixelDataExtensions for DataSet {
     // on the whole data set.
     let mut ds = DataSet::new();
     for tag in [
+      dictionary::TRANSFER_SYNTAX_UID.tag,
       dictionary::NUMBER_OF_FRAMES.tag,
       dictionary::SAMPLES_PER_PIXEL.tag,
       dictionary::PHOTOMETRIC_INTERPRETATION.tag,
@@ -151,6 +235,12 @@ impl DataSetPixelDataExtensions for DataSet {
     Ok(frames)
   }
 
+  fn get_pixel_data_fragment_map(
+    &self,
+  ) -> Result<PixelDataFragmentMap, DataError> {
+    PixelDataFragmentMap::from_data_set(self)
+  }
+
   fn get_pixel_data_images(
     &self,
     color_palette: Option<&StandardColorPalette>,
@@ -160,6 +250,25 @@ impl DataSetPixelDataExtensions for DataSet {
     })
   }
 
+  fn get_pixel_data_gray_u16_images(
+    &self,
+  ) -> Result<
+    Vec<image::ImageBuffer<image::Luma<u16>, Vec<u16>>>,
+    GetPixelDataError,
+  > {
+    get_pixel_data(self, |renderer, frame| {
+      renderer.render_gray_u16_frame(frame)
+    })
+  }
+
+  fn get_pixel_data_f32_frames(
+    &self,
+  ) -> Result<Vec<Vec<f32>>, GetPixelDataError> {
+    get_pixel_data(self, |renderer, frame| {
+      renderer.decode_monochrome_frame_f32(frame)
+    })
+  }
+
   fn get_pixel_data_monochrome_images(
     &self,
   ) -> Result<Vec<MonochromeImage>, GetPixelDataError> {
@@ -174,6 +283,43 @@ impl DataSetPixelDataExtensions for DataSet {
     get_pixel_data(self, |renderer, frame| renderer.decode_color_frame(frame))
   }
 
+  fn get_segment_masks(
+    &self,
+    segment_number: u16,
+  ) -> Result<Vec<SegmentMask>, GetPixelDataError> {
+    let segmentation_module = SegmentationModule::from_data_set(self)
+      .map_err(GetPixelDataError::DataError)?;
+
+    let renderer = PixelDataRenderer::from_data_set(self)
+      .map_err(GetPixelDataError::DataError)?;
+
+    let frames = self
+      .get_pixel_data_frames()
+      .map_err(GetPixelDataError::P10PixelDataFrameTransformError)?;
+
+    frames
+      .into_iter()
+      .enumerate()
+      .filter(|(frame_index, _)| {
+        segmentation_module.frame_segment_numbers.get(*frame_index)
+          == Some(&segment_number)
+      })
+      .map(|(frame_index, mut frame)| {
+        let image =
+          renderer
+            .decode_monochrome_frame(&mut frame)
+            .map_err(|error| GetPixelDataError::PixelDataDecodeError {
+              frame_index,
+              error,
+            })?;
+
+        segmentation_module
+          .segment_mask(frame_index, &image)
+          .map_err(GetPixelDataError::DataError)
+      })
+      .collect()
+  }
+
   fn transcode_pixel_data(
     &self,
     output_transfer_syntax: &'static TransferSyntax,
@@ -191,12 +337,16 @@ impl DataSetPixelDataExtensions for DataSet {
     let mut data_set_builder = DataSetBuilder::new();
 
     self.to_p10_token_stream(&mut |token| {
-      let tokens = transcode_transform.add_token(&token)?;
+      let mut tokens = transcode_transform.add_token(&token)?;
+
+      while !tokens.is_empty() {
+        for token in tokens.iter() {
+          data_set_builder
+            .add_token(token)
+            .map_err(P10PixelDataTranscodeTransformError::P10Error)?;
+        }
 
-      for token in tokens.iter() {
-        data_set_builder
-          .add_token(token)
-          .map_err(P10PixelDataTranscodeTransformError::P10Error)?;
+        tokens = transcode_transform.read_pending_tokens()?;
       }
 
       Ok(())
@@ -410,6 +560,86 @@ mod tests {
     );
   }
 
+  #[test]
+  fn get_pixel_data_gray_u16_images_test() {
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::ROWS, &[1]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[4]).unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[8])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_STORED, &[8]).unwrap();
+    ds.insert_int_value(&dictionary::HIGH_BIT, &[7]).unwrap();
+    ds.insert_int_value(&dictionary::PIXEL_REPRESENTATION, &[0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::WINDOW_CENTER, &[100.0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::WINDOW_WIDTH, &[100.0])
+      .unwrap();
+    ds.insert_string_value(&dictionary::VOILUT_FUNCTION, &["LINEAR_EXACT"])
+      .unwrap();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_binary(
+        ValueRepresentation::OtherByteString,
+        vec![50, 100, 150, 200].into(),
+      )
+      .unwrap(),
+    );
+
+    let images = ds.get_pixel_data_gray_u16_images().unwrap();
+
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0].as_raw(), &vec![0, 32768, 65535, 65535]);
+  }
+
+  #[test]
+  fn get_pixel_data_f32_frames_test() {
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::ROWS, &[1]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[3]).unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[16])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_STORED, &[12])
+      .unwrap();
+    ds.insert_int_value(&dictionary::HIGH_BIT, &[11]).unwrap();
+    ds.insert_int_value(&dictionary::PIXEL_REPRESENTATION, &[0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::RESCALE_INTERCEPT, &[-1024.0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::RESCALE_SLOPE, &[1.0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::WINDOW_CENTER, &[40.0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::WINDOW_WIDTH, &[400.0])
+      .unwrap();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_binary(
+        ValueRepresentation::OtherWordString,
+        vec![0, 0, 0, 4, 0xFF, 0x0F].into(),
+      )
+      .unwrap(),
+    );
+
+    let frames = ds.get_pixel_data_f32_frames().unwrap();
+
+    assert_eq!(frames, vec![vec![-1024.0, 0.0, 3071.0]]);
+  }
+
   #[test]
   fn read_native_multi_frame() {
     let mut ds = DataSet::new();
@@ -658,6 +888,95 @@ mod tests {
     );
   }
 
+  #[test]
+  fn transcode_with_max_fragment_size() {
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::ROWS, &[4]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[8]).unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[8])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_STORED, &[8]).unwrap();
+    ds.insert_int_value(&dictionary::HIGH_BIT, &[7]).unwrap();
+    ds.insert_int_value(&dictionary::PIXEL_REPRESENTATION, &[0])
+      .unwrap();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_binary(
+        ValueRepresentation::OtherByteString,
+        (0..96).map(|i| (i * 7) as u8).collect::<Vec<u8>>().into(),
+      )
+      .unwrap(),
+    );
+
+    let transcode = |max_fragment_size, frame_buffer_memory_limit| {
+      let mut encode_config = PixelDataEncodeConfig::default();
+      encode_config.set_max_fragment_size(max_fragment_size);
+      encode_config.set_frame_buffer_memory_limit(frame_buffer_memory_limit);
+
+      ds.transcode_pixel_data(
+        &transfer_syntax::RLE_LOSSLESS,
+        PixelDataDecodeConfig::default(),
+        encode_config,
+        None,
+      )
+      .unwrap()
+      .unwrap()
+    };
+
+    let unfragmented = transcode(None, None);
+    let fragmented = transcode(Some(25), None);
+
+    // Check that spilling buffered frames to disk gives the same result
+    assert_eq!(transcode(Some(25), Some(1)), fragmented);
+
+    let unfragmented_items = unfragmented
+      .get_value(dictionary::PIXEL_DATA.tag)
+      .unwrap()
+      .encapsulated_pixel_data()
+      .unwrap();
+    let fragmented_items = fragmented
+      .get_value(dictionary::PIXEL_DATA.tag)
+      .unwrap()
+      .encapsulated_pixel_data()
+      .unwrap();
+
+    // Check fragments are no larger than the maximum fragment size rounded down
+    // to an even number
+    assert!(fragmented_items.len() > unfragmented_items.len());
+    assert!(fragmented_items[1..].iter().all(|item| item.len() <= 24));
+
+    // Check the Basic Offset Table points to the first fragment of each frame
+    let basic_offset_table: Vec<u32> = fragmented_items[0]
+      .chunks_exact(4)
+      .map(|offset| u32::from_le_bytes(offset.try_into().unwrap()))
+      .collect();
+    let mut expected_basic_offset_table = vec![];
+    let mut offset = 0;
+    for frame in &unfragmented_items[1..] {
+      expected_basic_offset_table.push(offset);
+      offset += (frame.len() + frame.len().div_ceil(24) * 8) as u32;
+    }
+    assert_eq!(basic_offset_table, expected_basic_offset_table);
+
+    // Check the frames read from the fragmented pixel data are unchanged
+    let frames: Vec<RcByteSlice> = fragmented
+      .get_pixel_data_frames()
+      .unwrap()
+      .iter()
+      .map(|frame| frame.to_bytes())
+      .collect();
+    assert_eq!(frames, unfragmented_items[1..]);
+  }
+
   fn frame_with_fragments(fragments: &[&[u8]]) -> PixelDataFrame {
     let mut frame = PixelDataFrame::new();
 
@@ -5,7 +5,7 @@ use byteorder::ByteOrder;
 
 use dcmfx_core::{DataElementTag, DataError, DataSet, DataSetPath};
 
-use crate::utils::udiv_round;
+use crate::utils::{hash_f32, udiv_round};
 
 /// A lookup table containing unsigned 16-bit values that is created from LUT
 /// Descriptor, LUT Data (or Segmented LUT Data), and optionally LUT Explanation
@@ -44,6 +44,17 @@ pub struct LookupTable {
   normalization_scale: f32,
 }
 
+impl core::hash::Hash for LookupTable {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.first_input_value.hash(state);
+    self.explanation.hash(state);
+    self.data.hash(state);
+    self.bits_per_entry.hash(state);
+    self.int_max.hash(state);
+    hash_f32(self.normalization_scale, state);
+  }
+}
+
 impl LookupTable {
   /// Creates a [`LookupTable`] from the relevant data elements in a data set.
   ///
@@ -92,10 +103,8 @@ impl LookupTable {
           data.iter().map(|i| u16::from(*i)).collect()
         } else {
           return Err(
-            DataError::new_value_invalid(
-              "LUT data length is invalid".to_string(),
-            )
-            .with_path(&DataSetPath::new_with_data_element(lut_data_tag)),
+            DataError::new_value_invalid("LUT data length is invalid")
+              .with_path(&DataSetPath::new_with_data_element(lut_data_tag)),
           );
         }
       }
@@ -171,7 +180,7 @@ impl LookupTable {
       );
       buffer
     } else {
-      return Err(DataError::new_value_invalid("".to_string()));
+      return Err(DataError::new_value_invalid(""));
     };
 
     let mut lut = vec![];
@@ -215,7 +224,7 @@ impl LookupTable {
             Some(y1) => *y1,
             None => {
               return Err(DataError::new_value_invalid(
-                "Linear segment Y1 value is missing".to_string(),
+                "Linear segment Y1 value is missing",
               ));
             }
           };
@@ -261,6 +270,13 @@ impl LookupTable {
     &self.input_data_set
   }
 
+  /// Returns the free form text explanation of the meaning of this lookup
+  /// table, if one was specified.
+  ///
+  pub fn explanation(&self) -> Option<&str> {
+    self.explanation.as_deref()
+  }
+
   /// Returns the number of entries in the lookup table. This will never exceed
   /// 65536.
   ///
@@ -2,7 +2,7 @@
 use alloc::{vec, vec::Vec};
 
 use crate::{
-  GrayscalePipeline,
+  GrayscalePipeline, StoredValueOutputCache, image_hash, image_quality,
   iods::{
     image_pixel_module::BitsAllocated,
     voi_lut_module::{VoiLutFunction, VoiWindow},
@@ -490,6 +490,71 @@ impl MonochromeImage {
     self.height = height;
   }
 
+  /// Blacks out the specified rectangle of this monochrome image by setting
+  /// its pixels to the darkest storable value, which is the maximum value for
+  /// MONOCHROME1 images and the minimum value for MONOCHROME2 images. This is
+  /// used to redact burned-in annotations.
+  ///
+  pub fn black_out(&mut self, rect: &CropRect) {
+    let left = rect.left;
+    let top = rect.top;
+    let (height, width) = rect.apply(self.height(), self.width());
+
+    let image_width = usize::from(self.width);
+    let indices =
+      (usize::from(top)..usize::from(top + height)).flat_map(move |row| {
+        let start = row * image_width + usize::from(left);
+        start..start + usize::from(width)
+      });
+
+    let is_signed = self.is_signed();
+    let max_value = if is_signed {
+      (1i64 << (self.bits_stored - 1)) - 1
+    } else {
+      (1i64 << self.bits_stored) - 1
+    };
+    let min_value = if is_signed {
+      -(1i64 << (self.bits_stored - 1))
+    } else {
+      0
+    };
+    let value = if self.is_monochrome1 {
+      max_value
+    } else {
+      min_value
+    };
+
+    match &mut self.data {
+      MonochromeImageData::Bitmap { data, .. } => {
+        for i in indices {
+          if value == 0 {
+            data[i / 8] &= !(1 << (i % 8));
+          } else {
+            data[i / 8] |= 1 << (i % 8);
+          }
+        }
+      }
+      MonochromeImageData::I8(data) => {
+        indices.for_each(|i| data[i] = value as i8)
+      }
+      MonochromeImageData::U8(data) => {
+        indices.for_each(|i| data[i] = value as u8)
+      }
+      MonochromeImageData::I16(data) => {
+        indices.for_each(|i| data[i] = value as i16)
+      }
+      MonochromeImageData::U16(data) => {
+        indices.for_each(|i| data[i] = value as u16)
+      }
+      MonochromeImageData::I32(data) => {
+        indices.for_each(|i| data[i] = value as i32)
+      }
+      MonochromeImageData::U32(data) => {
+        indices.for_each(|i| data[i] = value as u32)
+      }
+    }
+  }
+
   /// Converts this monochrome image to an 8-bit grayscale image by passing
   /// its values through the given grayscale LUT pipeline.
   ///
@@ -502,9 +567,17 @@ impl MonochromeImage {
         self.to_gray_image(|stored_value: i64| cache.get(stored_value))
       }
 
-      None => self.to_gray_image(|stored_value: i64| {
+      None => match self.stored_value_output_cache(|stored_value| {
         grayscale_pipeline.apply_u8(stored_value)
-      }),
+      }) {
+        Some(cache) => {
+          self.to_gray_image(|stored_value: i64| cache.get(stored_value))
+        }
+
+        None => self.to_gray_image(|stored_value: i64| {
+          grayscale_pipeline.apply_u8(stored_value)
+        }),
+      },
     }
   }
 
@@ -520,12 +593,84 @@ impl MonochromeImage {
         self.to_gray_image(|stored_value: i64| cache.get(stored_value))
       }
 
-      None => self.to_gray_image(|stored_value: i64| {
+      None => match self.stored_value_output_cache(|stored_value| {
         grayscale_pipeline.apply_u16(stored_value)
+      }) {
+        Some(cache) => {
+          self.to_gray_image(|stored_value: i64| cache.get(stored_value))
+        }
+
+        None => self.to_gray_image(|stored_value: i64| {
+          grayscale_pipeline.apply_u16(stored_value)
+        }),
+      },
+    }
+  }
+
+  /// Converts this monochrome image to a 16-bit grayscale image by passing its
+  /// values through only the Modality LUT of the given grayscale pipeline, and
+  /// then linearly mapping the full range of output values into 16 bits. No
+  /// VOI LUT windowing is performed.
+  ///
+  pub fn to_gray_u16_linear_image(
+    &self,
+    grayscale_pipeline: &GrayscalePipeline,
+  ) -> image::ImageBuffer<image::Luma<u16>, Vec<u16>> {
+    match self.stored_value_output_cache(|stored_value| {
+      grayscale_pipeline.apply_linear_u16(stored_value)
+    }) {
+      Some(cache) => {
+        self.to_gray_image(|stored_value: i64| cache.get(stored_value))
+      }
+
+      None => self.to_gray_image(|stored_value: i64| {
+        grayscale_pipeline.apply_linear_u16(stored_value)
       }),
     }
   }
 
+  /// Converts this monochrome image to modality values, e.g. Hounsfield Units
+  /// for CT, by passing its stored values through only the Modality LUT of the
+  /// given grayscale pipeline. No VOI LUT windowing is performed, and the
+  /// values aren't normalized.
+  ///
+  /// MONOCHROME1 data is not inverted.
+  ///
+  pub fn to_modality_values(
+    &self,
+    grayscale_pipeline: &GrayscalePipeline,
+  ) -> Vec<f32> {
+    self
+      .stored_values()
+      .map(|stored_value| grayscale_pipeline.apply_modality_lut(stored_value))
+      .collect()
+  }
+
+  /// Converts this monochrome image to a 16-bit grayscale image that contains
+  /// its stored values without any grayscale pipeline being applied. Signed
+  /// stored values are offset so that the smallest storable value maps to
+  /// zero, and stored values that don't fit in 16 bits are clamped.
+  ///
+  /// MONOCHROME1 data is not inverted.
+  ///
+  pub fn to_stored_value_u16_image(
+    &self,
+  ) -> image::ImageBuffer<image::Luma<u16>, Vec<u16>> {
+    let offset = if self.is_signed() {
+      1i64 << (self.bits_stored - 1)
+    } else {
+      0
+    };
+
+    let pixels = self
+      .stored_values()
+      .map(|stored_value| (stored_value + offset).clamp(0, 65535) as u16)
+      .collect();
+
+    image::ImageBuffer::from_raw(self.width.into(), self.height.into(), pixels)
+      .unwrap()
+  }
+
   fn to_gray_image<T: image::Primitive>(
     &self,
     stored_value_to_gray: impl Fn(i64) -> T,
@@ -612,6 +757,37 @@ impl MonochromeImage {
     gray_pixels
   }
 
+  /// Creates a cache of the result of passing the stored values present in
+  /// this monochrome image through the given function. This is used when the
+  /// grayscale pipeline doesn't cache its output because the range of possible
+  /// stored values is too large, which is the case for 32-bit data.
+  ///
+  /// No cache is created if the range of stored values present in this image
+  /// has more than 2^16 items.
+  ///
+  fn stored_value_output_cache<T: Copy>(
+    &self,
+    stored_value_to_gray: impl Fn(i64) -> T,
+  ) -> Option<StoredValueOutputCache<T>> {
+    let (mut min, mut max) = self.min_max_values()?;
+
+    // The stored values passed to the grayscale pipeline for MONOCHROME1 data
+    // are negated and offset, so the range to cache is adjusted to match
+    if self.is_monochrome1 {
+      let offset = self.monochrome1_offset();
+      (min, max) = (-max + offset, -min + offset);
+    }
+
+    if max - min >= 65536 {
+      return None;
+    }
+
+    Some(StoredValueOutputCache::new(
+      &(min..=max),
+      stored_value_to_gray,
+    ))
+  }
+
   /// Calculates the offset to add after negating the stored pixel value in
   /// order to convert to Monochrome2.
   ///
@@ -637,6 +813,162 @@ impl MonochromeImage {
       index: 0,
     }
   }
+
+  /// Returns a SHA-256 hash of this monochrome image's dimensions and stored
+  /// values. Images with the same hash have identical stored values, even if
+  /// they were encoded using different transfer syntaxes or bits allocated.
+  ///
+  pub fn stored_value_hash(&self) -> [u8; 32] {
+    let mut hasher = image_hash::StoredValueHasher::new(
+      self.width,
+      self.height,
+      u8::from(self.is_monochrome1),
+    );
+
+    for value in self.stored_values() {
+      hasher.update(value);
+    }
+
+    hasher.finalize()
+  }
+
+  /// Returns the dHash perceptual hash of this monochrome image. This is
+  /// computed on the stored values, so is independent of any grayscale
+  /// pipeline that is applied for display.
+  ///
+  /// See [`image_hash::perceptual_hash_distance()`].
+  ///
+  pub fn dhash(&self) -> u64 {
+    image_hash::dhash(&self.luminance(), self.width.into(), self.height.into())
+  }
+
+  /// Returns the pHash perceptual hash of this monochrome image. This is
+  /// computed on the stored values, so is independent of any grayscale
+  /// pipeline that is applied for display.
+  ///
+  /// See [`image_hash::perceptual_hash_distance()`].
+  ///
+  pub fn phash(&self) -> u64 {
+    image_hash::phash(&self.luminance(), self.width.into(), self.height.into())
+  }
+
+  /// Returns the MSE, PSNR, and SSIM of this monochrome image compared to a
+  /// reference image, e.g. the original image prior to lossy compression.
+  /// Returns `None` if the images' dimensions differ.
+  ///
+  /// Stored values are normalized into the range 0-1 using each image's bits
+  /// stored and signedness, and MONOCHROME1 images are inverted, so images
+  /// with different bit depths and photometric interpretations can be
+  /// compared.
+  ///
+  /// See [`image_quality::ImageQualityMetrics`].
+  ///
+  pub fn quality_metrics(
+    &self,
+    reference: &Self,
+  ) -> Option<image_quality::ImageQualityMetrics> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::ImageQualityMetrics::compute(
+      &reference.normalized_luminance(),
+      &self.normalized_luminance(),
+      self.width.into(),
+      self.height.into(),
+      1,
+    ))
+  }
+
+  /// Returns the MSE of this monochrome image compared to a reference image.
+  /// Returns `None` if the images' dimensions differ.
+  ///
+  /// See [`Self::quality_metrics()`] and [`image_quality::mse()`].
+  ///
+  pub fn mse(&self, reference: &Self) -> Option<f64> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::mse(
+      &reference.normalized_luminance(),
+      &self.normalized_luminance(),
+    ))
+  }
+
+  /// Returns the PSNR in decibels of this monochrome image compared to a
+  /// reference image. Returns `None` if the images' dimensions differ.
+  ///
+  /// See [`Self::quality_metrics()`] and [`image_quality::psnr()`].
+  ///
+  pub fn psnr(&self, reference: &Self) -> Option<f64> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::psnr(
+      &reference.normalized_luminance(),
+      &self.normalized_luminance(),
+    ))
+  }
+
+  /// Returns the SSIM of this monochrome image compared to a reference image.
+  /// Returns `None` if the images' dimensions differ.
+  ///
+  /// See [`Self::quality_metrics()`] and [`image_quality::ssim()`].
+  ///
+  pub fn ssim(&self, reference: &Self) -> Option<f64> {
+    if self.width != reference.width || self.height != reference.height {
+      return None;
+    }
+
+    Some(image_quality::ssim(
+      &reference.normalized_luminance(),
+      &self.normalized_luminance(),
+      self.width.into(),
+      self.height.into(),
+      1,
+    ))
+  }
+
+  /// Returns this monochrome image's stored values as luminance values
+  /// normalized into the range 0-1, based on the range of values representable
+  /// by its bits stored. Larger values are brighter.
+  ///
+  fn normalized_luminance(&self) -> Vec<f64> {
+    let range = (1i64 << self.bits_stored) - 1;
+    let min = if self.is_signed() {
+      -(1i64 << (self.bits_stored - 1))
+    } else {
+      0
+    };
+
+    self
+      .stored_values()
+      .map(|value| {
+        let value = value - min;
+
+        if self.is_monochrome1 {
+          (range - value) as f64 / range as f64
+        } else {
+          value as f64 / range as f64
+        }
+      })
+      .collect()
+  }
+
+  /// Returns this monochrome image's stored values as luminance values where
+  /// larger values are brighter. The range of the returned values isn't
+  /// normalized.
+  ///
+  fn luminance(&self) -> Vec<f64> {
+    let sign = if self.is_monochrome1 { -1.0 } else { 1.0 };
+
+    self
+      .stored_values()
+      .map(|value| value as f64 * sign)
+      .collect()
+  }
 }
 
 /// Iterator to the stored values of a monochrome image.
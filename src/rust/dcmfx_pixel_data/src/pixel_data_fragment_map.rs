@@ -0,0 +1,319 @@
+//! Describes how the fragments of encapsulated pixel data make up its frames.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec, vec::Vec};
+
+use core::ops::Range;
+
+use dcmfx_core::{DataError, DataSet, DataSetPath, dictionary};
+
+use crate::transforms::{
+  parse_basic_offset_table, parse_extended_offset_table,
+};
+
+/// A map of the fragments in encapsulated pixel data, and of which fragments
+/// make up each frame. The map holds only offsets and lengths, and no pixel
+/// data is copied when creating it.
+///
+/// This is useful when pixel data needs to be accessed by byte range, e.g. for
+/// DICOMweb frame retrieval, or when checking the integrity of offset tables.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelDataFragmentMap {
+  fragments: Vec<PixelDataFragment>,
+  frames: Vec<PixelDataFrameFragments>,
+}
+
+/// A single fragment of encapsulated pixel data, i.e. a pixel data item that
+/// follows the Basic Offset Table item.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelDataFragment {
+  /// The offset in bytes of this fragment's item header, relative to the first
+  /// byte of the first fragment's item header. This is the same reference
+  /// point used by the Basic Offset Table and the Extended Offset Table.
+  ///
+  /// The fragment's data begins 8 bytes after this offset.
+  pub offset: u64,
+
+  /// The length in bytes of this fragment's data, excluding its item header.
+  pub length: u64,
+}
+
+/// The fragments of encapsulated pixel data that make up a single frame.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelDataFrameFragments {
+  /// The indices of the fragments that make up this frame. Fragment indices
+  /// start at zero for the first fragment after the Basic Offset Table item.
+  pub fragment_indices: Range<usize>,
+
+  /// The length in bytes of this frame's data, if specified by the
+  /// *'(7FE0,0002) Extended Offset Table Lengths'* data element. When present,
+  /// this may be less than the total length of the frame's fragments.
+  pub length: Option<u64>,
+}
+
+impl PixelDataFragmentMap {
+  /// Creates the fragment map for the encapsulated pixel data in a data set.
+  ///
+  /// The *'(7FE0,0010) Pixel Data'* data element must be present in the data
+  /// set and hold encapsulated pixel data. The *'(0028,0008) Number of
+  /// Frames'*, *'(7FE0,0001) Extended Offset Table'*, and *'(7FE0,0002)
+  /// Extended Offset Table Lengths'* data elements are used when present and
+  /// relevant.
+  ///
+  pub fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
+    let items = data_set
+      .get_value(dictionary::PIXEL_DATA.tag)?
+      .encapsulated_pixel_data()
+      .map_err(|e| {
+        e.with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag,
+        ))
+      })?;
+
+    let number_of_frames = data_set
+      .get_int_with_default::<usize>(dictionary::NUMBER_OF_FRAMES.tag, 1)?;
+
+    // Determine the offset and length of each fragment
+    let mut fragments = vec![];
+    let mut offset = 0u64;
+    for item in items.iter().skip(1) {
+      let length = item.len() as u64;
+
+      fragments.push(PixelDataFragment { offset, length });
+
+      offset += 8 + length;
+    }
+
+    // Read the Basic Offset Table, falling back to the Extended Offset Table
+    // if it's empty
+    let basic_offset_table = match items.first() {
+      Some(item) => parse_basic_offset_table(item)?,
+      None => Default::default(),
+    };
+
+    let extended_offset_table = match (
+      data_set.get_value(dictionary::EXTENDED_OFFSET_TABLE.tag),
+      data_set.get_value(dictionary::EXTENDED_OFFSET_TABLE_LENGTHS.tag),
+    ) {
+      (Ok(offsets), Ok(lengths)) => {
+        Some(parse_extended_offset_table(offsets, lengths)?)
+      }
+      _ => None,
+    };
+
+    let offset_table = if basic_offset_table.is_empty() {
+      extended_offset_table.unwrap_or_default()
+    } else {
+      // Validate that the Extended Offset Table is absent. Ref: PS3.5 A.4.
+      if extended_offset_table.is_some() {
+        return Err(DataError::new_value_invalid(
+          "Extended Offset Table must be absent when there is a Basic Offset \
+           Table"
+            .to_string(),
+        ));
+      }
+
+      basic_offset_table
+    };
+
+    let mut frames = vec![];
+
+    if offset_table.is_empty() {
+      // If the offset table is empty and there is more than one frame then each
+      // fragment is treated as a single frame, otherwise all fragments are part
+      // of a single frame
+      if number_of_frames > 1 {
+        for i in 0..fragments.len() {
+          frames.push(PixelDataFrameFragments {
+            fragment_indices: i..(i + 1),
+            length: None,
+          });
+        }
+      } else if !fragments.is_empty() {
+        frames.push(PixelDataFrameFragments {
+          fragment_indices: 0..fragments.len(),
+          length: None,
+        });
+      }
+    } else {
+      // Use the offset table to determine the first fragment of each frame.
+      // Every offset must be the offset of a fragment.
+      let mut first_fragment_indices = vec![];
+      for (offset, _) in offset_table.iter() {
+        match fragments.binary_search_by_key(offset, |f| f.offset) {
+          Ok(index) => first_fragment_indices.push(index),
+          Err(_) => {
+            return Err(DataError::new_value_invalid(
+              "Pixel data offset table is malformed",
+            ));
+          }
+        }
+      }
+
+      for (i, (_, length)) in offset_table.iter().enumerate() {
+        let end = first_fragment_indices
+          .get(i + 1)
+          .copied()
+          .unwrap_or(fragments.len());
+
+        frames.push(PixelDataFrameFragments {
+          fragment_indices: first_fragment_indices[i]..end,
+          length: *length,
+        });
+      }
+    }
+
+    Ok(Self { fragments, frames })
+  }
+
+  /// Returns all fragments in the encapsulated pixel data.
+  ///
+  pub fn fragments(&self) -> &[PixelDataFragment] {
+    &self.fragments
+  }
+
+  /// Returns the fragments that make up each frame, indexed by frame index.
+  ///
+  pub fn frames(&self) -> &[PixelDataFrameFragments] {
+    &self.frames
+  }
+
+  /// Returns the number of frames in the encapsulated pixel data.
+  ///
+  pub fn number_of_frames(&self) -> usize {
+    self.frames.len()
+  }
+
+  /// Returns the fragments that make up the specified frame. Returns `None` if
+  /// the frame index is out of range.
+  ///
+  pub fn frame_fragments(
+    &self,
+    frame_index: usize,
+  ) -> Option<&[PixelDataFragment]> {
+    let frame = self.frames.get(frame_index)?;
+
+    self.fragments.get(frame.fragment_indices.clone())
+  }
+
+  /// Returns the range of bytes occupied by the specified frame's fragments,
+  /// including their item headers, relative to the first byte of the first
+  /// fragment's item header. Returns `None` if the frame index is out of range
+  /// or the frame has no fragments.
+  ///
+  pub fn frame_byte_range(&self, frame_index: usize) -> Option<Range<u64>> {
+    let fragments = self.frame_fragments(frame_index)?;
+
+    let first = fragments.first()?;
+    let last = fragments.last()?;
+
+    Some(first.offset..(last.offset + 8 + last.length))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx_core::{DataElementValue, ValueRepresentation};
+
+  #[test]
+  fn from_data_set_with_basic_offset_table() {
+    let ds = data_set(
+      2,
+      vec![
+        vec![0, 0, 0, 0, 0x10, 0x04, 0, 0],
+        vec![1; 512],
+        vec![2; 512],
+        vec![3; 1024],
+      ],
+    );
+
+    let map = PixelDataFragmentMap::from_data_set(&ds).unwrap();
+
+    assert_eq!(
+      map.fragments(),
+      &[
+        PixelDataFragment {
+          offset: 0,
+          length: 512
+        },
+        PixelDataFragment {
+          offset: 520,
+          length: 512
+        },
+        PixelDataFragment {
+          offset: 1040,
+          length: 1024
+        },
+      ]
+    );
+
+    assert_eq!(
+      map.frames(),
+      &[
+        PixelDataFrameFragments {
+          fragment_indices: 0..2,
+          length: None
+        },
+        PixelDataFrameFragments {
+          fragment_indices: 2..3,
+          length: None
+        },
+      ]
+    );
+
+    assert_eq!(map.frame_byte_range(0), Some(0..1040));
+    assert_eq!(map.frame_byte_range(1), Some(1040..2072));
+    assert_eq!(map.frame_byte_range(2), None);
+  }
+
+  #[test]
+  fn from_data_set_without_offset_table() {
+    let ds = data_set(2, vec![vec![], vec![1; 16], vec![2; 32]]);
+
+    let map = PixelDataFragmentMap::from_data_set(&ds).unwrap();
+    assert_eq!(map.number_of_frames(), 2);
+    assert_eq!(map.frame_fragments(1), Some(&map.fragments()[1..2]));
+
+    let ds = data_set(1, vec![vec![], vec![1; 16], vec![2; 32]]);
+
+    let map = PixelDataFragmentMap::from_data_set(&ds).unwrap();
+    assert_eq!(map.number_of_frames(), 1);
+    assert_eq!(map.frame_byte_range(0), Some(0..64));
+  }
+
+  #[test]
+  fn from_data_set_with_malformed_offset_table() {
+    let ds = data_set(
+      2,
+      vec![vec![0, 0, 0, 0, 0x20, 0, 0, 0], vec![1; 16], vec![2; 16]],
+    );
+
+    assert_eq!(
+      PixelDataFragmentMap::from_data_set(&ds),
+      Err(DataError::new_value_invalid(
+        "Pixel data offset table is malformed".to_string()
+      ))
+    );
+  }
+
+  fn data_set(number_of_frames: i64, items: Vec<Vec<u8>>) -> DataSet {
+    let mut ds = DataSet::new();
+
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[number_of_frames])
+      .unwrap();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_encapsulated_pixel_data_unchecked(
+        ValueRepresentation::OtherByteString,
+        items.into_iter().map(|item| item.into()).collect(),
+      ),
+    );
+
+    ds
+  }
+}
@@ -4,8 +4,12 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
+use core::{ops::Range, time::Duration};
+
 use dcmfx_core::RcByteSlice;
 
+use crate::DecodedFrameInfo;
+
 /// A single frame of pixel data in its raw form. It is made up of a one or more
 /// slices into reference-counted `Vec<u8>` data, which avoids copying of data.
 ///
@@ -15,9 +19,12 @@ use dcmfx_core::RcByteSlice;
 #[derive(Clone, Debug, Default)]
 pub struct PixelDataFrame {
   frame_index: Option<usize>,
+  time_interval: Option<Range<Duration>>,
   chunks: Vec<RcByteSlice>,
   length_in_bits: u64,
   bit_offset: usize,
+  decoder: Option<&'static str>,
+  decoded_frame_info: Option<DecodedFrameInfo>,
 }
 
 impl PixelDataFrame {
@@ -49,6 +56,22 @@ impl PixelDataFrame {
     self.frame_index = Some(index);
   }
 
+  /// Returns the interval of time covered by this frame, measured from the
+  /// start of the first frame in its DICOM data set. Returns `None` if the
+  /// frame's timing isn't known. See [`crate::FrameTiming`] for details on how
+  /// frame timing is determined.
+  ///
+  pub fn time_interval(&self) -> Option<&Range<Duration>> {
+    self.time_interval.as_ref()
+  }
+
+  /// Sets the interval of time covered by this frame. See
+  /// [`Self::time_interval()`] for details.
+  ///
+  pub fn set_time_interval(&mut self, time_interval: Option<Range<Duration>>) {
+    self.time_interval = time_interval;
+  }
+
   /// Adds the next chunk of pixel data to this frame.
   ///
   pub fn push_bytes(&mut self, fragment: RcByteSlice) {
@@ -93,6 +116,42 @@ impl PixelDataFrame {
     self.bit_offset = bit_offset.clamp(0, 7);
   }
 
+  /// Returns the name of the decoder that was used to decode this frame, e.g.
+  /// `"openjph"`. This is set when the frame is decoded using the functions in
+  /// [`crate::decode`], and is useful for diagnostics when multiple decoders
+  /// are configured in [`crate::PixelDataDecodeConfig`].
+  ///
+  pub fn decoder(&self) -> Option<&'static str> {
+    self.decoder
+  }
+
+  /// Sets the name of the decoder that was used to decode this frame. See
+  /// [`Self::decoder()`] for details.
+  ///
+  pub fn set_decoder(&mut self, decoder: &'static str) {
+    self.decoder = Some(decoder);
+  }
+
+  /// Returns the details of the image held in this frame's codestream. This is
+  /// set when the frame is decoded using the functions in [`crate::decode`],
+  /// and is `None` if the frame hasn't been decoded or its transfer syntax
+  /// doesn't have codestream headers that can be read. See
+  /// [`DecodedFrameInfo::from_codestream()`] for details.
+  ///
+  pub fn decoded_frame_info(&self) -> Option<&DecodedFrameInfo> {
+    self.decoded_frame_info.as_ref()
+  }
+
+  /// Sets the details of the image held in this frame's codestream. See
+  /// [`Self::decoded_frame_info()`] for details.
+  ///
+  pub fn set_decoded_frame_info(
+    &mut self,
+    decoded_frame_info: Option<DecodedFrameInfo>,
+  ) {
+    self.decoded_frame_info = decoded_frame_info;
+  }
+
   /// Returns whether this frame of pixel data is empty.
   ///
   pub fn is_empty(&self) -> bool {
@@ -166,6 +225,72 @@ impl PixelDataFrame {
     RcByteSlice::from_vec(buffer)
   }
 
+  /// Writes this frame's data to a writer as a standalone image codestream,
+  /// without decoding it. This allows frames of encapsulated pixel data, e.g.
+  /// JPEG Baseline, to be written directly to image files.
+  ///
+  /// The frame must hold a complete JPEG or JPEG 2000 codestream, i.e. it must
+  /// start with an SOI or SOC marker and end with an EOI or EOC marker. Zero
+  /// bytes after the end marker, which pad fragments to an even length, aren't
+  /// written. An error of kind [`std::io::ErrorKind::InvalidData`] is returned
+  /// if the frame isn't a complete codestream.
+  ///
+  #[cfg(feature = "std")]
+  pub fn write_raw_to<W: std::io::Write>(
+    &self,
+    writer: &mut W,
+  ) -> std::io::Result<()> {
+    let Some(length) = self.codestream_length() else {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "Frame is not a complete JPEG or JPEG 2000 codestream",
+      ));
+    };
+
+    let mut remaining = length as usize;
+    for chunk in self.chunks.iter() {
+      let chunk_length = chunk.len().min(remaining);
+      writer.write_all(&chunk[..chunk_length])?;
+
+      remaining -= chunk_length;
+    }
+
+    Ok(())
+  }
+
+  /// Returns the length of the JPEG or JPEG 2000 codestream held in this
+  /// frame, excluding any trailing zero padding. Returns `None` if this frame
+  /// doesn't start with an SOI or SOC marker, or doesn't end with an EOI or
+  /// EOC marker.
+  ///
+  #[cfg(feature = "std")]
+  fn codestream_length(&self) -> Option<u64> {
+    if self.bit_offset != 0 {
+      return None;
+    }
+
+    let mut bytes = self.chunks.iter().flat_map(|chunk| chunk.iter().copied());
+    if !matches!([bytes.next()?, bytes.next()?], [0xFF, 0xD8] | [0xFF, 0x4F]) {
+      return None;
+    }
+
+    // JPEG's EOI marker and JPEG 2000's EOC marker are both 0xFFD9
+    let end_bytes = self
+      .chunks
+      .iter()
+      .rev()
+      .flat_map(|chunk| chunk.iter().rev().copied());
+    let padding_length = end_bytes.clone().position(|byte| byte != 0)?;
+    let end_marker: Vec<u8> = end_bytes.skip(padding_length).take(2).collect();
+
+    let length = self.len() - padding_length as u64;
+    if end_marker != [0xD9, 0xFF] || length < 4 {
+      return None;
+    }
+
+    Some(length)
+  }
+
   /// If this frame of pixel data contains more than one chunk, combines them
   /// into one new chunk. Returns the slice of the first (and only) chunk that
   /// contains all the pixel data for this frame.
@@ -243,4 +368,42 @@ mod tests {
     frame.drop_end_bytes(2);
     assert_eq!(frame.to_bytes(), vec![0, 1, 2, 3].into());
   }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn write_raw_to_test() {
+    let write_raw = |chunks: &[&[u8]]| {
+      let mut frame = PixelDataFrame::new();
+      for chunk in chunks {
+        frame.push_bytes(chunk.to_vec().into());
+      }
+
+      let mut output = vec![];
+      frame.write_raw_to(&mut output).map(|_| output)
+    };
+
+    assert_eq!(
+      write_raw(&[&[0xFF, 0xD8, 0x01], &[0x02, 0xFF, 0xD9, 0x00]]).unwrap(),
+      vec![0xFF, 0xD8, 0x01, 0x02, 0xFF, 0xD9]
+    );
+
+    assert_eq!(
+      write_raw(&[&[0xFF, 0x4F, 0xFF, 0x51, 0xFF], &[0xD9], &[0x00, 0x00]])
+        .unwrap(),
+      vec![0xFF, 0x4F, 0xFF, 0x51, 0xFF, 0xD9]
+    );
+
+    assert_eq!(
+      write_raw(&[&[0xFF, 0xD8, 0x01, 0x02]]).unwrap_err().kind(),
+      std::io::ErrorKind::InvalidData
+    );
+    assert_eq!(
+      write_raw(&[&[0x00, 0xD8, 0xFF, 0xD9]]).unwrap_err().kind(),
+      std::io::ErrorKind::InvalidData
+    );
+    assert_eq!(
+      write_raw(&[&[0xFF, 0xD9]]).unwrap_err().kind(),
+      std::io::ErrorKind::InvalidData
+    );
+  }
 }
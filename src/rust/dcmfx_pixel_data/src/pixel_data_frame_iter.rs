@@ -0,0 +1,122 @@
+//! Iterates over the frames of pixel data in a stream of DICOM P10 data.
+
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::VecDeque;
+
+use dcmfx_p10::{IoRead, P10ReadConfig, P10ReadContext, P10Token};
+
+use crate::{
+  PixelDataFrame,
+  transforms::{P10PixelDataFrameTransform, P10PixelDataFrameTransformError},
+};
+
+/// Returns an iterator over the frames of pixel data in a stream of DICOM P10
+/// data. Frames are yielded as soon as all of their data has been read from
+/// the stream, and no [`dcmfx_core::DataSet`] is built.
+///
+/// This wires a [`P10ReadContext`] and a [`P10PixelDataFrameTransform`]
+/// together over the stream. Iteration ends after the first error.
+///
+pub fn iter_pixel_data_frames<S: IoRead>(
+  stream: S,
+  config: Option<P10ReadConfig>,
+) -> PixelDataFrameIter<S> {
+  PixelDataFrameIter {
+    stream,
+    context: P10ReadContext::new(config),
+    frame_transform: P10PixelDataFrameTransform::new(),
+    pending_frames: VecDeque::new(),
+    is_done: false,
+  }
+}
+
+/// Iterator over the frames of pixel data in a stream of DICOM P10 data. See
+/// [`iter_pixel_data_frames()`] for details.
+///
+pub struct PixelDataFrameIter<S: IoRead> {
+  stream: S,
+  context: P10ReadContext,
+  frame_transform: P10PixelDataFrameTransform,
+  pending_frames: VecDeque<PixelDataFrame>,
+  is_done: bool,
+}
+
+impl<S: IoRead> PixelDataFrameIter<S> {
+  /// Returns the read context used to read the stream of DICOM P10 data, e.g.
+  /// to check how many bytes have been read.
+  ///
+  pub fn context(&self) -> &P10ReadContext {
+    &self.context
+  }
+
+  /// Reads the next batch of DICOM P10 tokens from the stream and passes them
+  /// through the frame transform.
+  ///
+  fn read_next_tokens(
+    &mut self,
+  ) -> Result<(), P10PixelDataFrameTransformError> {
+    let tokens = dcmfx_p10::read_tokens_from_stream(
+      &mut self.stream,
+      &mut self.context,
+      None,
+    )
+    .map_err(P10PixelDataFrameTransformError::P10Error)?;
+
+    for token in tokens.iter() {
+      self
+        .pending_frames
+        .extend(self.frame_transform.add_token(token)?);
+
+      if *token == P10Token::End {
+        self.is_done = true;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl<S: IoRead> Iterator for PixelDataFrameIter<S> {
+  type Item = Result<PixelDataFrame, P10PixelDataFrameTransformError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(frame) = self.pending_frames.pop_front() {
+        return Some(Ok(frame));
+      }
+
+      if self.is_done {
+        return None;
+      }
+
+      if let Err(e) = self.read_next_tokens() {
+        self.is_done = true;
+        return Some(Err(e));
+      }
+    }
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+  use super::*;
+  use crate::DataSetPixelDataExtensions;
+
+  #[test]
+  fn iter_pixel_data_frames_test() {
+    let path = "../../../test/assets/pydicom/test_files/SC_rgb_rle_2frame.dcm";
+
+    let frames =
+      iter_pixel_data_frames(std::fs::File::open(path).unwrap(), None)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let data_set = dcmfx_p10::read_file(path, None).unwrap();
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames, data_set.get_pixel_data_frames().unwrap());
+  }
+}
@@ -1,5 +1,7 @@
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{borrow::Cow, format, vec::Vec};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
 
 use dcmfx_core::{
   DataElementTag, DataError, DataSet, DataSetPath, IodModule, TransferSyntax,
@@ -7,9 +9,13 @@ use dcmfx_core::{
 };
 
 use crate::{
-  ColorImage, GrayscalePipeline, MonochromeImage, PixelDataDecodeConfig,
-  PixelDataDecodeError, PixelDataFrame, StandardColorPalette, decode,
-  iods::ImagePixelModule,
+  ColorImage, GrayscalePipeline, IndexedColorImage, MonochromeImage,
+  PixelDataDecodeConfig, PixelDataDecodeError, PixelDataFrame, RenderProfile,
+  StandardColorPalette, decode,
+  iods::{
+    FunctionalGroupsModule, ImagePixelModule, SoftcopyPresentationLutModule,
+    VoiSelection, softcopy_presentation_lut_module::PresentationLutShape,
+  },
 };
 
 /// Defines a pixel data renderer that can take a [`PixelDataFrame`] and render
@@ -21,6 +27,53 @@ pub struct PixelDataRenderer {
   pub image_pixel_module: ImagePixelModule,
   pub grayscale_pipeline: GrayscalePipeline,
   pub decode_config: PixelDataDecodeConfig,
+  pub grayscale_output_depth: GrayscaleOutputDepth,
+
+  /// The functional groups of an Enhanced multi-frame data set. When present,
+  /// the shared functional groups are applied to [`Self::grayscale_pipeline`],
+  /// and per-frame Modality LUTs and VOI LUTs are applied when rendering each
+  /// frame. See [`Self::frame_grayscale_pipeline()`].
+  pub functional_groups: Option<FunctionalGroupsModule>,
+
+  /// The color palette used to visualize monochrome pixel data when one isn't
+  /// passed to a render function.
+  pub color_palette: Option<&'static StandardColorPalette>,
+
+  /// The pictures of a video stream decoded by [`Self::decode_video_frame()`].
+  video_pictures: Option<VideoPictures>,
+}
+
+/// The decoded pictures of a video stream.
+///
+#[derive(Clone, Debug, PartialEq)]
+enum VideoPictures {
+  Monochrome(Vec<MonochromeImage>),
+  Color(Vec<ColorImage>),
+}
+
+/// Specifies how monochrome pixel data is converted to grayscale output values
+/// by [`PixelDataRenderer::render_grayscale_frame()`] and
+/// [`PixelDataRenderer::render_grayscale_image()`].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GrayscaleOutputDepth {
+  /// The full grayscale pipeline, including VOI LUT windowing, is applied and
+  /// the result is output as 8-bit grayscale.
+  #[default]
+  Windowed8,
+
+  /// The full grayscale pipeline, including VOI LUT windowing, is applied and
+  /// the result is output as 16-bit grayscale.
+  Windowed16,
+
+  /// Only the Modality LUT is applied, and the full range of its output is
+  /// linearly mapped to 16-bit grayscale. No VOI LUT windowing is performed.
+  Linear16,
+
+  /// Stored values are output as 16-bit grayscale without any part of the
+  /// grayscale pipeline being applied. Signed stored values are offset so that
+  /// the smallest storable value maps to zero.
+  StoredValues,
 }
 
 impl IodModule for PixelDataRenderer {
@@ -32,11 +85,15 @@ impl IodModule for PixelDataRenderer {
   ) -> bool {
     ImagePixelModule::is_iod_module_data_element(tag, vr, length, path)
       || GrayscalePipeline::is_iod_module_data_element(tag, vr, length, path)
+      || FunctionalGroupsModule::is_iod_module_data_element(
+        tag, vr, length, path,
+      )
   }
 
   fn iod_module_highest_tag() -> DataElementTag {
     ImagePixelModule::iod_module_highest_tag()
       .max(GrayscalePipeline::iod_module_highest_tag())
+      .max(FunctionalGroupsModule::iod_module_highest_tag())
   }
 
   fn from_data_set(data_set: &DataSet) -> Result<Self, DataError> {
@@ -47,28 +104,153 @@ impl IodModule for PixelDataRenderer {
     };
 
     let image_pixel_module = ImagePixelModule::from_data_set(data_set)?;
-    let grayscale_pipeline = GrayscalePipeline::from_data_set(
+    let mut grayscale_pipeline = GrayscalePipeline::from_data_set(
       data_set,
       image_pixel_module.stored_value_range(),
     )?;
 
+    let functional_groups = if FunctionalGroupsModule::is_present(data_set) {
+      let functional_groups = FunctionalGroupsModule::from_data_set(data_set)?;
+
+      grayscale_pipeline =
+        grayscale_pipeline.with_functional_groups(&functional_groups.shared);
+
+      Some(functional_groups)
+    } else {
+      None
+    };
+
     Ok(PixelDataRenderer {
       transfer_syntax,
       image_pixel_module,
       grayscale_pipeline,
       decode_config: PixelDataDecodeConfig::default(),
+      grayscale_output_depth: GrayscaleOutputDepth::default(),
+      functional_groups,
+      color_palette: None,
+      video_pictures: None,
     })
   }
 }
 
 impl PixelDataRenderer {
+  /// Applies the settings in a [`RenderProfile`] to this pixel data renderer.
+  /// Settings that aren't specified in the profile are left unchanged.
+  ///
+  /// Inverting grayscale output is done by setting an `INVERSE` Presentation
+  /// LUT Shape on the grayscale pipeline, and so doesn't affect the
+  /// [`GrayscaleOutputDepth::Linear16`] and
+  /// [`GrayscaleOutputDepth::StoredValues`] grayscale output depths.
+  ///
+  pub fn apply_render_profile(&mut self, profile: &RenderProfile) {
+    if let Some(color_palette) = profile.color_palette {
+      self.color_palette = Some(color_palette);
+    }
+
+    if let Some(voi_window) = &profile.voi_window {
+      self.grayscale_pipeline.set_voi_window(voi_window.clone());
+
+      // The profile's VOI window takes precedence over per-frame VOI LUTs
+      if let Some(functional_groups) = &mut self.functional_groups {
+        for groups in functional_groups.per_frame.iter_mut() {
+          groups.voi_lut_module = None;
+        }
+      }
+    }
+
+    if let Some(invert) = profile.invert {
+      let shape = if invert {
+        PresentationLutShape::Inverse
+      } else {
+        PresentationLutShape::Identity
+      };
+
+      self.grayscale_pipeline.set_softcopy_presentation_lut(
+        SoftcopyPresentationLutModule::Shape { shape },
+      );
+    }
+
+    if let Some(grayscale_output_depth) = profile.grayscale_output_depth {
+      self.grayscale_output_depth = grayscale_output_depth;
+    }
+  }
+
+  /// Returns the grayscale pipeline to use for the frame with the given index.
+  /// This is [`Self::grayscale_pipeline`] with any Modality LUT and VOI LUT
+  /// specified by the frame's per-frame functional groups applied.
+  ///
+  pub fn frame_grayscale_pipeline(
+    &self,
+    frame_index: Option<usize>,
+  ) -> Cow<'_, GrayscalePipeline> {
+    let per_frame = self.functional_groups.as_ref().and_then(|groups| {
+      groups.per_frame.get(frame_index?).filter(|groups| {
+        groups.modality_lut_module.is_some() || groups.voi_lut_module.is_some()
+      })
+    });
+
+    match per_frame {
+      Some(groups) => {
+        Cow::Owned(self.grayscale_pipeline.with_functional_groups(groups))
+      }
+      None => Cow::Borrowed(&self.grayscale_pipeline),
+    }
+  }
+
+  /// Makes all frames use the VOI LUT of the frame with the given index, which
+  /// gives a consistent appearance to the frames of Enhanced multi-frame data
+  /// sets that specify a different VOI LUT for each frame. Per-frame Modality
+  /// LUTs are still applied.
+  ///
+  pub fn set_uniform_voi_lut(&mut self, frame_index: usize) {
+    let voi_lut_module = self
+      .frame_grayscale_pipeline(Some(frame_index))
+      .voi_lut()
+      .clone();
+
+    self.grayscale_pipeline.set_voi_lut(voi_lut_module);
+
+    if let Some(functional_groups) = &mut self.functional_groups {
+      for groups in functional_groups.per_frame.iter_mut() {
+        groups.voi_lut_module = None;
+      }
+    }
+  }
+
+  /// Returns the grayscale pipeline to use for the frame with the given index,
+  /// as returned by [`Self::frame_grayscale_pipeline()`], with its VOI LUT
+  /// restricted to the grayscale LUT or window chosen by the given selection.
+  ///
+  /// For Enhanced multi-frame IODs the selection is made from the frame's
+  /// *'(0028,9132) Frame VOI LUT Sequence'* when it is present. If the
+  /// selection doesn't match any grayscale LUT or window then the frame's
+  /// default VOI LUT is used.
+  ///
+  pub fn frame_grayscale_pipeline_with_voi(
+    &self,
+    frame_index: Option<usize>,
+    voi_selection: &VoiSelection,
+  ) -> Cow<'_, GrayscalePipeline> {
+    let mut grayscale_pipeline = self.frame_grayscale_pipeline(frame_index);
+
+    if let Some(voi_lut_module) =
+      grayscale_pipeline.voi_lut().select(voi_selection)
+      && voi_lut_module != *grayscale_pipeline.voi_lut()
+    {
+      grayscale_pipeline.to_mut().set_voi_lut(voi_lut_module);
+    }
+
+    grayscale_pipeline
+  }
+
   /// Renders a frame of pixel data to an RGB 8-bit image. The grayscale
-  /// pipeline is applied to monochrome images, and resulting grayscale values
-  /// are then expanded to RGB.
+  /// pipeline for the frame is applied to monochrome images, and resulting
+  /// grayscale values are then expanded to RGB.
   ///
   /// Monochrome frames can optionally be visualized using a color palette. The
   /// well-known color palettes defined in PS3.6 B.1 are provided in
-  /// [`crate::standard_color_palettes`].
+  /// [`crate::standard_color_palettes`]. If no color palette is specified then
+  /// [`Self::color_palette`] is used.
   ///
   pub fn render_frame(
     &self,
@@ -83,7 +265,13 @@ impl PixelDataRenderer {
         &self.decode_config,
       )?;
 
-      Ok(self.render_monochrome_image(&image, color_palette))
+      let grayscale_pipeline = self.frame_grayscale_pipeline(frame.index());
+
+      Ok(self.render_monochrome_image_with_grayscale_pipeline(
+        &image,
+        &grayscale_pipeline,
+        color_palette,
+      ))
     } else {
       let image = decode::decode_color(
         frame,
@@ -96,24 +284,69 @@ impl PixelDataRenderer {
     }
   }
 
+  /// Renders a frame of pixel data to an RGB 8-bit image in the same way as
+  /// [`Self::render_frame()`], but with the VOI LUT for monochrome frames
+  /// chosen by the given selection. See
+  /// [`Self::frame_grayscale_pipeline_with_voi()`].
+  ///
+  pub fn render_frame_with_voi(
+    &self,
+    frame: &mut PixelDataFrame,
+    voi_selection: &VoiSelection,
+    color_palette: Option<&StandardColorPalette>,
+  ) -> Result<image::RgbImage, PixelDataDecodeError> {
+    if !self.image_pixel_module.is_monochrome() {
+      return self.render_frame(frame, color_palette);
+    }
+
+    let image = self.decode_monochrome_frame(frame)?;
+
+    let grayscale_pipeline =
+      self.frame_grayscale_pipeline_with_voi(frame.index(), voi_selection);
+
+    Ok(self.render_monochrome_image_with_grayscale_pipeline(
+      &image,
+      &grayscale_pipeline,
+      color_palette,
+    ))
+  }
+
   /// Renders a [`MonochromeImage`] to an RGB 8-bit image. The grayscale
   /// pipeline is applied, and resulting grayscale values are then expanded
   /// to RGB.
   ///
   /// The result can optionally be visualized using a color palette. The
   /// well-known color palettes defined in PS3.6 B.1 are provided in
-  /// [`crate::standard_color_palettes`].
+  /// [`crate::standard_color_palettes`]. If no color palette is specified then
+  /// [`Self::color_palette`] is used.
   ///
   pub fn render_monochrome_image(
     &self,
     image: &MonochromeImage,
     color_palette: Option<&StandardColorPalette>,
+  ) -> image::RgbImage {
+    self.render_monochrome_image_with_grayscale_pipeline(
+      image,
+      &self.grayscale_pipeline,
+      color_palette,
+    )
+  }
+
+  /// Renders a [`MonochromeImage`] to an RGB 8-bit image in the same way as
+  /// [`Self::render_monochrome_image()`], but using the given grayscale
+  /// pipeline, e.g. one returned by [`Self::frame_grayscale_pipeline()`].
+  ///
+  pub fn render_monochrome_image_with_grayscale_pipeline(
+    &self,
+    image: &MonochromeImage,
+    grayscale_pipeline: &GrayscalePipeline,
+    color_palette: Option<&StandardColorPalette>,
   ) -> image::RgbImage {
     let mut pixels = Vec::with_capacity(image.pixel_count() * 3);
 
-    let gray_image = image.to_gray_u8_image(&self.grayscale_pipeline);
+    let gray_image = image.to_gray_u8_image(grayscale_pipeline);
 
-    if let Some(color_palette) = color_palette {
+    if let Some(color_palette) = color_palette.or(self.color_palette) {
       for pixel in gray_image.pixels() {
         pixels.extend_from_slice(&color_palette.lookup(pixel.0[0]));
       }
@@ -129,6 +362,218 @@ impl PixelDataRenderer {
       .unwrap()
   }
 
+  /// Renders a frame of monochrome pixel data to a grayscale image. The 8-bit
+  /// or 16-bit output, and which parts of the grayscale pipeline are applied,
+  /// is controlled by [`Self::grayscale_output_depth`].
+  ///
+  pub fn render_grayscale_frame(
+    &self,
+    frame: &mut PixelDataFrame,
+  ) -> Result<image::DynamicImage, PixelDataDecodeError> {
+    let image = self.decode_monochrome_frame(frame)?;
+    let grayscale_pipeline = self.frame_grayscale_pipeline(frame.index());
+
+    Ok(self.render_grayscale_image_with_grayscale_pipeline(
+      &image,
+      &grayscale_pipeline,
+    ))
+  }
+
+  /// Renders a frame of monochrome pixel data to a 16-bit grayscale image with
+  /// the frame's Modality LUT and VOI LUT applied, regardless of
+  /// [`Self::grayscale_output_depth`]. The extra precision over 8-bit output is
+  /// useful for viewers that perform further window/level adjustments, e.g.
+  /// on the GPU. Use [`image::ImageBuffer::into_raw()`] to get the raw
+  /// `Vec<u16>` of grayscale values.
+  ///
+  pub fn render_gray_u16_frame(
+    &self,
+    frame: &mut PixelDataFrame,
+  ) -> Result<
+    image::ImageBuffer<image::Luma<u16>, Vec<u16>>,
+    PixelDataDecodeError,
+  > {
+    let image = self.decode_monochrome_frame(frame)?;
+    let grayscale_pipeline = self.frame_grayscale_pipeline(frame.index());
+
+    Ok(image.to_gray_u16_image(&grayscale_pipeline))
+  }
+
+  /// Decodes a frame of monochrome pixel data into its modality values, e.g.
+  /// Hounsfield Units for CT, by applying the frame's Modality LUT, i.e.
+  /// Rescale Slope and Rescale Intercept. No VOI LUT windowing is performed.
+  /// Values are returned in row-major order.
+  ///
+  pub fn decode_monochrome_frame_f32(
+    &self,
+    frame: &mut PixelDataFrame,
+  ) -> Result<Vec<f32>, PixelDataDecodeError> {
+    let image = self.decode_monochrome_frame(frame)?;
+    let grayscale_pipeline = self.frame_grayscale_pipeline(frame.index());
+
+    Ok(image.to_modality_values(&grayscale_pipeline))
+  }
+
+  /// Renders a frame of monochrome pixel data to a grayscale image in the same
+  /// way as [`Self::render_grayscale_frame()`], but with the VOI LUT chosen by
+  /// the given selection. See [`Self::frame_grayscale_pipeline_with_voi()`].
+  ///
+  pub fn render_grayscale_frame_with_voi(
+    &self,
+    frame: &mut PixelDataFrame,
+    voi_selection: &VoiSelection,
+  ) -> Result<image::DynamicImage, PixelDataDecodeError> {
+    let image = self.decode_monochrome_frame(frame)?;
+    let grayscale_pipeline =
+      self.frame_grayscale_pipeline_with_voi(frame.index(), voi_selection);
+
+    Ok(self.render_grayscale_image_with_grayscale_pipeline(
+      &image,
+      &grayscale_pipeline,
+    ))
+  }
+
+  /// Renders a [`MonochromeImage`] to a grayscale image. The 8-bit or 16-bit
+  /// output, and which parts of the grayscale pipeline are applied, is
+  /// controlled by [`Self::grayscale_output_depth`].
+  ///
+  pub fn render_grayscale_image(
+    &self,
+    image: &MonochromeImage,
+  ) -> image::DynamicImage {
+    self.render_grayscale_image_with_grayscale_pipeline(
+      image,
+      &self.grayscale_pipeline,
+    )
+  }
+
+  /// Renders a [`MonochromeImage`] to a grayscale image in the same way as
+  /// [`Self::render_grayscale_image()`], but using the given grayscale
+  /// pipeline, e.g. one returned by [`Self::frame_grayscale_pipeline()`].
+  ///
+  pub fn render_grayscale_image_with_grayscale_pipeline(
+    &self,
+    image: &MonochromeImage,
+    grayscale_pipeline: &GrayscalePipeline,
+  ) -> image::DynamicImage {
+    match self.grayscale_output_depth {
+      GrayscaleOutputDepth::Windowed8 => {
+        image.to_gray_u8_image(grayscale_pipeline).into()
+      }
+
+      GrayscaleOutputDepth::Windowed16 => {
+        image.to_gray_u16_image(grayscale_pipeline).into()
+      }
+
+      GrayscaleOutputDepth::Linear16 => {
+        image.to_gray_u16_linear_image(grayscale_pipeline).into()
+      }
+
+      GrayscaleOutputDepth::StoredValues => {
+        image.to_stored_value_u16_image().into()
+      }
+    }
+  }
+
+  /// Renders a frame of pixel data to an [`IndexedColorImage`] that stores an
+  /// 8-bit palette index for each pixel rather than expanding the pixels to
+  /// RGB. This is possible in two cases:
+  ///
+  /// 1. A monochrome frame is being visualized using a color palette, either
+  ///    the one specified or [`Self::color_palette`]. The grayscale pipeline
+  ///    is applied and the 8-bit grayscale values are used as indices into the
+  ///    color palette.
+  ///
+  /// 2. A `PALETTE COLOR` frame uses no more than 256 distinct entries in its
+  ///    palette color lookup table.
+  ///
+  /// Returns `None` if neither case applies, in which case
+  /// [`Self::render_frame()`] should be used instead.
+  ///
+  pub fn render_frame_indexed(
+    &self,
+    frame: &mut PixelDataFrame,
+    color_palette: Option<&StandardColorPalette>,
+  ) -> Result<Option<IndexedColorImage>, PixelDataDecodeError> {
+    if self.image_pixel_module.is_monochrome() {
+      let Some(color_palette) = color_palette.or(self.color_palette) else {
+        return Ok(None);
+      };
+
+      let image = decode::decode_monochrome(
+        frame,
+        self.transfer_syntax,
+        &self.image_pixel_module,
+        &self.decode_config,
+      )?;
+
+      let grayscale_pipeline = self.frame_grayscale_pipeline(frame.index());
+
+      Ok(Some(
+        self.render_monochrome_image_indexed_with_grayscale_pipeline(
+          &image,
+          &grayscale_pipeline,
+          color_palette,
+        ),
+      ))
+    } else if self
+      .image_pixel_module
+      .photometric_interpretation()
+      .is_palette_color()
+    {
+      let image = decode::decode_color(
+        frame,
+        self.transfer_syntax,
+        &self.image_pixel_module,
+        &self.decode_config,
+      )?;
+
+      Ok(image.to_indexed_color_image())
+    } else {
+      Ok(None)
+    }
+  }
+
+  /// Renders a [`MonochromeImage`] to an [`IndexedColorImage`] using the
+  /// specified color palette. The grayscale pipeline is applied, and resulting
+  /// 8-bit grayscale values are used as indices into the color palette.
+  ///
+  pub fn render_monochrome_image_indexed(
+    &self,
+    image: &MonochromeImage,
+    color_palette: &StandardColorPalette,
+  ) -> IndexedColorImage {
+    self.render_monochrome_image_indexed_with_grayscale_pipeline(
+      image,
+      &self.grayscale_pipeline,
+      color_palette,
+    )
+  }
+
+  /// Renders a [`MonochromeImage`] to an [`IndexedColorImage`] in the same way
+  /// as [`Self::render_monochrome_image_indexed()`], but using the given
+  /// grayscale pipeline, e.g. one returned by
+  /// [`Self::frame_grayscale_pipeline()`].
+  ///
+  pub fn render_monochrome_image_indexed_with_grayscale_pipeline(
+    &self,
+    image: &MonochromeImage,
+    grayscale_pipeline: &GrayscalePipeline,
+    color_palette: &StandardColorPalette,
+  ) -> IndexedColorImage {
+    let gray_image = image.to_gray_u8_image(grayscale_pipeline);
+
+    let palette = (0..=255).map(|i| color_palette.lookup(i)).collect();
+
+    IndexedColorImage::new(
+      image.width(),
+      image.height(),
+      gray_image.into_raw(),
+      palette,
+    )
+    .unwrap()
+  }
+
   /// Decodes a frame of monochrome pixel data into a [`MonochromeImage`]. The
   /// returned image needs to have a grayscale pipeline applied in order to
   /// reach final grayscale display values.
@@ -137,6 +582,10 @@ impl PixelDataRenderer {
     &self,
     frame: &mut PixelDataFrame,
   ) -> Result<MonochromeImage, PixelDataDecodeError> {
+    if let Some(VideoPictures::Monochrome(pictures)) = &self.video_pictures {
+      return video_picture(pictures, frame);
+    }
+
     decode::decode_monochrome(
       frame,
       self.transfer_syntax,
@@ -151,6 +600,10 @@ impl PixelDataRenderer {
     &self,
     frame: &mut PixelDataFrame,
   ) -> Result<ColorImage, PixelDataDecodeError> {
+    if let Some(VideoPictures::Color(pictures)) = &self.video_pictures {
+      return video_picture(pictures, frame);
+    }
+
     decode::decode_color(
       frame,
       self.transfer_syntax,
@@ -158,4 +611,58 @@ impl PixelDataRenderer {
       &self.decode_config,
     )
   }
+
+  /// Decodes every picture in a frame that holds a video stream, and returns
+  /// the number of pictures.
+  ///
+  /// The decoded pictures are kept by this renderer. Subsequent calls to
+  /// [`Self::decode_monochrome_frame()`] and [`Self::decode_color_frame()`]
+  /// return the picture at the index of the frame passed to them, which means
+  /// each picture can be rendered in turn by setting the index of the video
+  /// frame, without the video stream being decoded again.
+  ///
+  pub fn decode_video_frame(
+    &mut self,
+    frame: &mut PixelDataFrame,
+  ) -> Result<usize, PixelDataDecodeError> {
+    let video_pictures = if self.image_pixel_module.is_monochrome() {
+      VideoPictures::Monochrome(decode::decode_video_monochrome(
+        frame,
+        self.transfer_syntax,
+        &self.image_pixel_module,
+        &self.decode_config,
+      )?)
+    } else {
+      VideoPictures::Color(decode::decode_video_color(
+        frame,
+        self.transfer_syntax,
+        &self.image_pixel_module,
+        &self.decode_config,
+      )?)
+    };
+
+    let picture_count = match &video_pictures {
+      VideoPictures::Monochrome(pictures) => pictures.len(),
+      VideoPictures::Color(pictures) => pictures.len(),
+    };
+
+    self.video_pictures = Some(video_pictures);
+
+    Ok(picture_count)
+  }
+}
+
+/// Returns the decoded video picture at the index of the given frame.
+///
+fn video_picture<T: Clone>(
+  pictures: &[T],
+  frame: &PixelDataFrame,
+) -> Result<T, PixelDataDecodeError> {
+  let index = frame.index().unwrap_or(0);
+
+  pictures.get(index).cloned().ok_or_else(|| {
+    PixelDataDecodeError::DataInvalid {
+      details: format!("Video picture {index} is not present"),
+    }
+  })
 }
@@ -0,0 +1,178 @@
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+  GrayscaleOutputDepth, StandardColorPalette, iods::voi_lut_module::VoiWindow,
+};
+
+/// Default settings for rendering monochrome pixel data. Render profiles are
+/// typically defined per modality, see [`RenderProfiles`], so that images
+/// exported across an organization are rendered consistently.
+///
+/// Settings that are `None` are left unchanged when the profile is applied to
+/// a [`crate::PixelDataRenderer`].
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RenderProfile {
+  /// The well-known color palette used to visualize monochrome pixel data in
+  /// color.
+  pub color_palette: Option<&'static StandardColorPalette>,
+
+  /// The VOI window to use instead of the VOI LUT specified in the DICOM data,
+  /// e.g. a standard window preset for the modality.
+  pub voi_window: Option<VoiWindow>,
+
+  /// Whether to invert grayscale output values.
+  pub invert: Option<bool>,
+
+  /// The bit depth of grayscale output values, and which parts of the
+  /// grayscale pipeline are applied.
+  pub grayscale_output_depth: Option<GrayscaleOutputDepth>,
+}
+
+impl RenderProfile {
+  /// Returns a new render profile that uses the settings specified in
+  /// `overrides`, and falls back to the settings in this profile for those
+  /// that aren't specified.
+  ///
+  pub fn merge(&self, overrides: &RenderProfile) -> RenderProfile {
+    RenderProfile {
+      color_palette: overrides.color_palette.or(self.color_palette),
+      voi_window: overrides
+        .voi_window
+        .clone()
+        .or_else(|| self.voi_window.clone()),
+      invert: overrides.invert.or(self.invert),
+      grayscale_output_depth: overrides
+        .grayscale_output_depth
+        .or(self.grayscale_output_depth),
+    }
+  }
+}
+
+/// A set of [`RenderProfile`]s keyed by modality, along with an optional
+/// default profile used for modalities that don't have their own profile.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RenderProfiles {
+  default_profile: Option<RenderProfile>,
+  modality_profiles: Vec<(String, RenderProfile)>,
+}
+
+impl RenderProfiles {
+  /// Creates a new empty set of render profiles.
+  ///
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns whether there are no render profiles in this set.
+  ///
+  pub fn is_empty(&self) -> bool {
+    self.default_profile.is_none() && self.modality_profiles.is_empty()
+  }
+
+  /// Sets the render profile used for modalities that don't have their own
+  /// profile.
+  ///
+  pub fn set_default_profile(&mut self, profile: RenderProfile) {
+    self.default_profile = Some(profile);
+  }
+
+  /// Sets the render profile for a modality, as specified by the
+  /// *'(0008,0060) Modality'* data element, e.g. `"CT"`. Any existing profile
+  /// for the modality is replaced.
+  ///
+  pub fn insert(&mut self, modality: &str, profile: RenderProfile) {
+    let modality = modality.trim().to_ascii_uppercase();
+
+    self.modality_profiles.retain(|(m, _)| *m != modality);
+    self.modality_profiles.push((modality, profile));
+  }
+
+  /// Returns the render profile to use for the given modality. If there isn't
+  /// a profile for the modality then the default profile is returned, if one
+  /// is set.
+  ///
+  pub fn profile_for_modality(
+    &self,
+    modality: Option<&str>,
+  ) -> Option<&RenderProfile> {
+    modality
+      .map(|modality| modality.trim().to_ascii_uppercase())
+      .and_then(|modality| {
+        self
+          .modality_profiles
+          .iter()
+          .find(|(m, _)| *m == modality)
+          .map(|(_, profile)| profile)
+      })
+      .or(self.default_profile.as_ref())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::{iods::voi_lut_module::VoiLutFunction, standard_color_palettes};
+
+  #[test]
+  fn profile_for_modality_test() {
+    let ct_profile = RenderProfile {
+      voi_window: Some(VoiWindow::new(
+        40.0,
+        400.0,
+        "".to_string(),
+        VoiLutFunction::LinearExact,
+      )),
+      ..RenderProfile::default()
+    };
+
+    let default_profile = RenderProfile {
+      grayscale_output_depth: Some(GrayscaleOutputDepth::Windowed8),
+      ..RenderProfile::default()
+    };
+
+    let mut profiles = RenderProfiles::new();
+    assert!(profiles.is_empty());
+    assert_eq!(profiles.profile_for_modality(Some("CT")), None);
+
+    profiles.insert("ct", ct_profile.clone());
+    assert_eq!(profiles.profile_for_modality(Some("CT")), Some(&ct_profile));
+    assert_eq!(profiles.profile_for_modality(Some("MR")), None);
+
+    profiles.set_default_profile(default_profile.clone());
+    assert_eq!(
+      profiles.profile_for_modality(Some("MR")),
+      Some(&default_profile)
+    );
+    assert_eq!(profiles.profile_for_modality(None), Some(&default_profile));
+  }
+
+  #[test]
+  fn merge_test() {
+    let profile = RenderProfile {
+      color_palette: Some(&standard_color_palettes::HOT_IRON),
+      voi_window: None,
+      invert: Some(true),
+      grayscale_output_depth: Some(GrayscaleOutputDepth::Windowed16),
+    };
+
+    let overrides = RenderProfile {
+      color_palette: Some(&standard_color_palettes::PET),
+      invert: Some(false),
+      ..RenderProfile::default()
+    };
+
+    assert_eq!(
+      profile.merge(&overrides),
+      RenderProfile {
+        color_palette: Some(&standard_color_palettes::PET),
+        voi_window: None,
+        invert: Some(false),
+        grayscale_output_depth: Some(GrayscaleOutputDepth::Windowed16),
+      }
+    );
+  }
+}
@@ -0,0 +1,359 @@
+//! Validates that the instances in a series form a consistent spatial stack of
+//! slices, and determines the order of the slices along the slice normal.
+//!
+//! This extends the checks in [`crate::volume_consistency`], which work on
+//! individual slices, to whole data sets by also checking that the instances
+//! belong to one series, have the same dimensions, and that no slices are
+//! missing from the stack.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{DataElementTag, DataError, DataSet, IodModule, dictionary};
+
+use crate::{
+  iods::ImagePlaneModule,
+  volume_consistency::{
+    VolumeConsistencyConfig, VolumeConsistencyIssue, VolumeSlice,
+    check_volume_consistency,
+  },
+};
+
+/// A problem found when validating the geometry of a series. Slice indices
+/// refer to the data sets in the order they were passed in.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum SeriesGeometryIssue {
+  /// The instances don't all have the same Series Instance UID.
+  MixedSeriesInstanceUids { series_instance_uids: Vec<String> },
+
+  /// The Rows or Columns of some instances differ from those of the first
+  /// instance.
+  MixedDimensions { slice_indices: Vec<usize> },
+
+  /// The gaps between some adjacent slices are whole multiples of the nominal
+  /// slice spacing, which indicates that slices are missing from the stack.
+  MissingSlices { count: usize },
+
+  /// A problem found with the position or orientation of the slices.
+  VolumeConsistency(VolumeConsistencyIssue),
+}
+
+impl core::fmt::Display for SeriesGeometryIssue {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::MixedSeriesInstanceUids { .. } => {
+        write!(f, "Instances belong to more than one series")
+      }
+
+      Self::MixedDimensions { slice_indices } => write!(
+        f,
+        "{} slice(s) have different dimensions",
+        slice_indices.len()
+      ),
+
+      Self::MissingSlices { count } => {
+        write!(f, "{count} slice(s) appear to be missing")
+      }
+
+      Self::VolumeConsistency(issue) => issue.fmt(f),
+    }
+  }
+}
+
+/// The spatial layout of the instances in a series, along with any problems
+/// that prevent them from forming a consistent stack of slices.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeriesGeometry {
+  /// The Image Plane Module of each instance, in the order they were passed
+  /// in.
+  ///
+  pub image_plane_modules: Vec<ImagePlaneModule>,
+
+  /// The indices of the instances sorted by their position along the slice
+  /// normal.
+  ///
+  pub slice_order: Vec<usize>,
+
+  /// The position of each slice along the slice normal in mm, in the order
+  /// given by `slice_order`.
+  ///
+  pub slice_positions: Vec<f32>,
+
+  /// The nominal spacing between slices in mm. This is `None` if there are
+  /// fewer than two slices.
+  ///
+  pub slice_spacing: Option<f32>,
+
+  /// The angle in degrees between the slice normal and the direction the
+  /// slices are stacked in, which is non-zero when a CT gantry is tilted.
+  ///
+  pub gantry_tilt: f32,
+
+  /// The problems found. If this is empty then the instances form a
+  /// consistent stack of slices.
+  ///
+  pub issues: Vec<SeriesGeometryIssue>,
+}
+
+impl SeriesGeometry {
+  /// The tags of the data elements read from each data set. Data sets only
+  /// need to contain these data elements, so they can be read with a partial
+  /// read.
+  ///
+  pub const TAGS: [DataElementTag; 10] = [
+    dictionary::SERIES_INSTANCE_UID.tag,
+    dictionary::FRAME_OF_REFERENCE_UID.tag,
+    dictionary::ROWS.tag,
+    dictionary::COLUMNS.tag,
+    dictionary::PIXEL_SPACING.tag,
+    dictionary::IMAGE_ORIENTATION_PATIENT.tag,
+    dictionary::IMAGE_POSITION_PATIENT.tag,
+    dictionary::SLICE_THICKNESS.tag,
+    dictionary::SPACING_BETWEEN_SLICES.tag,
+    dictionary::SLICE_LOCATION.tag,
+  ];
+
+  /// Validates the geometry of the given data sets, which can be passed in
+  /// any order. Returns an error if a data set doesn't have a valid Image
+  /// Plane Module.
+  ///
+  pub fn from_data_sets(
+    data_sets: &[&DataSet],
+    config: &VolumeConsistencyConfig,
+  ) -> Result<Self, DataError> {
+    let image_plane_modules = data_sets
+      .iter()
+      .map(|data_set| ImagePlaneModule::from_data_set(data_set))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let mut issues = vec![];
+
+    // Check the instances all belong to the same series
+    let mut series_instance_uids: Vec<String> = vec![];
+    for data_set in data_sets {
+      if let Ok(uid) = data_set.get_string(dictionary::SERIES_INSTANCE_UID.tag)
+        && !series_instance_uids.iter().any(|u| u == uid)
+      {
+        series_instance_uids.push(uid.to_string());
+      }
+    }
+
+    if series_instance_uids.len() > 1 {
+      issues.push(SeriesGeometryIssue::MixedSeriesInstanceUids {
+        series_instance_uids,
+      });
+    }
+
+    // Check the instances all have the same dimensions as the first
+    let dimensions = |data_set: &DataSet| {
+      (
+        data_set.get_int::<u16>(dictionary::ROWS.tag).ok(),
+        data_set.get_int::<u16>(dictionary::COLUMNS.tag).ok(),
+      )
+    };
+
+    let mismatched_slices: Vec<usize> = data_sets
+      .iter()
+      .enumerate()
+      .skip(1)
+      .filter(|(_, data_set)| dimensions(data_set) != dimensions(data_sets[0]))
+      .map(|(i, _)| i)
+      .collect();
+
+    if !mismatched_slices.is_empty() {
+      issues.push(SeriesGeometryIssue::MixedDimensions {
+        slice_indices: mismatched_slices,
+      });
+    }
+
+    // Check the positions and orientations of the slices
+    let slices: Vec<VolumeSlice> = data_sets
+      .iter()
+      .zip(image_plane_modules.iter())
+      .map(|(data_set, image_plane_module)| VolumeSlice {
+        frame_of_reference_uid: data_set
+          .get_string(dictionary::FRAME_OF_REFERENCE_UID.tag)
+          .ok(),
+        image_plane_module,
+      })
+      .collect();
+
+    let report = check_volume_consistency(&slices, config);
+
+    // Count the slices missing from gaps that are close to a whole multiple of
+    // the nominal slice spacing
+    let mut missing_slice_count = 0;
+    if let Some(slice_spacing) = report.slice_spacing
+      && slice_spacing > 0.0
+    {
+      for w in report.slice_positions.windows(2) {
+        let steps = (w[1] - w[0]) / slice_spacing;
+        let whole_steps = steps.round();
+
+        if whole_steps >= 2.0
+          && (steps - whole_steps).abs() <= config.slice_spacing_tolerance
+        {
+          missing_slice_count += whole_steps as usize - 1;
+        }
+      }
+    }
+
+    issues.extend(
+      report
+        .issues
+        .into_iter()
+        .map(SeriesGeometryIssue::VolumeConsistency),
+    );
+
+    if missing_slice_count > 0 {
+      issues.push(SeriesGeometryIssue::MissingSlices {
+        count: missing_slice_count,
+      });
+    }
+
+    Ok(Self {
+      image_plane_modules,
+      slice_order: report.slice_order,
+      slice_positions: report.slice_positions,
+      slice_spacing: report.slice_spacing,
+      gantry_tilt: report.gantry_tilt,
+      issues,
+    })
+  }
+
+  /// Returns whether the instances form a consistent stack of slices.
+  ///
+  pub fn is_consistent(&self) -> bool {
+    self.issues.is_empty()
+  }
+
+  /// Returns lines of text that describe the geometry and any issues found.
+  ///
+  pub fn to_lines(&self) -> Vec<String> {
+    let mut lines = vec![format!("Slices: {}", self.slice_order.len())];
+
+    if let Some(slice_spacing) = self.slice_spacing {
+      lines.push(format!("Slice spacing: {slice_spacing:.3} mm"));
+    }
+
+    if self.issues.is_empty() {
+      lines.push("Geometry is consistent".to_string());
+    } else {
+      for issue in self.issues.iter() {
+        lines.push(format!("Issue: {issue}"));
+      }
+    }
+
+    lines
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn slice(z: f64, rows: u16) -> DataSet {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::SERIES_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::FRAME_OF_REFERENCE_UID, &["1.2.4"])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::ROWS, &[rows.into()])
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::COLUMNS, &[2])
+      .unwrap();
+    data_set
+      .insert_float_value(&dictionary::IMAGE_POSITION_PATIENT, &[0.0, 0.0, z])
+      .unwrap();
+    data_set
+      .insert_float_value(
+        &dictionary::IMAGE_ORIENTATION_PATIENT,
+        &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+      )
+      .unwrap();
+    data_set
+      .insert_float_value(&dictionary::PIXEL_SPACING, &[1.0, 1.0])
+      .unwrap();
+
+    data_set
+  }
+
+  fn geometry(data_sets: &[DataSet]) -> SeriesGeometry {
+    SeriesGeometry::from_data_sets(
+      &data_sets.iter().collect::<Vec<_>>(),
+      &VolumeConsistencyConfig::default(),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn consistent_geometry_test() {
+    let geometry =
+      geometry(&[slice(4.0, 2), slice(0.0, 2), slice(2.0, 2), slice(6.0, 2)]);
+
+    assert!(geometry.is_consistent());
+    assert_eq!(geometry.slice_order, vec![1, 2, 0, 3]);
+    assert_eq!(geometry.slice_positions, vec![0.0, 2.0, 4.0, 6.0]);
+    assert_eq!(geometry.slice_spacing, Some(2.0));
+  }
+
+  #[test]
+  fn inconsistent_geometry_test() {
+    let mut other_series = slice(2.0, 2);
+    other_series
+      .insert_string_value(&dictionary::SERIES_INSTANCE_UID, &["1.2.5"])
+      .unwrap();
+
+    let geometry = geometry(&[
+      slice(0.0, 2),
+      other_series,
+      slice(4.0, 3),
+      slice(6.0, 2),
+      slice(12.0, 2),
+    ]);
+
+    assert_eq!(
+      geometry.issues,
+      vec![
+        SeriesGeometryIssue::MixedSeriesInstanceUids {
+          series_instance_uids: vec!["1.2.3".to_string(), "1.2.5".to_string()]
+        },
+        SeriesGeometryIssue::MixedDimensions {
+          slice_indices: vec![2]
+        },
+        SeriesGeometryIssue::VolumeConsistency(
+          VolumeConsistencyIssue::NonUniformSliceSpacing {
+            min_spacing: 2.0,
+            max_spacing: 6.0
+          }
+        ),
+        SeriesGeometryIssue::MissingSlices { count: 2 },
+      ]
+    );
+  }
+
+  #[test]
+  fn missing_image_plane_module_test() {
+    let mut data_set = slice(0.0, 2);
+    data_set.delete(dictionary::IMAGE_POSITION_PATIENT.tag);
+
+    assert!(
+      SeriesGeometry::from_data_sets(
+        &[&data_set],
+        &VolumeConsistencyConfig::default()
+      )
+      .is_err()
+    );
+  }
+}
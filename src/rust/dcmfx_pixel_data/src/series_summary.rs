@@ -0,0 +1,434 @@
+//! Summarizes the attributes of the instances in a series that are used when
+//! deciding how to lay out the series for display, e.g. by hanging protocols.
+//!
+//! A [`SeriesSummary`] is built in a single pass over the instances in a
+//! series, and describes the orientation of the images and the acquisition
+//! plane they lie in, along with groupings of instances by MR echo and cardiac
+//! phase, and by CT energy level.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{DataSet, dictionary};
+
+/// The anatomical plane that the images in a series lie in.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AcquisitionPlane {
+  /// The image plane is perpendicular to the head-foot axis.
+  Axial,
+
+  /// The image plane is perpendicular to the anterior-posterior axis.
+  Coronal,
+
+  /// The image plane is perpendicular to the left-right axis.
+  Sagittal,
+
+  /// The image plane isn't close enough to any of the axial, coronal, or
+  /// sagittal planes to be classified as one of them.
+  Oblique,
+}
+
+impl core::fmt::Display for AcquisitionPlane {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let s = match self {
+      Self::Axial => "Axial",
+      Self::Coronal => "Coronal",
+      Self::Sagittal => "Sagittal",
+      Self::Oblique => "Oblique",
+    };
+
+    f.write_str(s)
+  }
+}
+
+/// The orientation of the images in a series, described using the patient
+/// orientation labels defined in PS3.3 C.7.6.1.1.1.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeriesOrientation {
+  /// The direction of the rows of the images, i.e. the direction from the
+  /// first column to the last, e.g. "L" or "LP". The first letter is the most
+  /// significant direction.
+  ///
+  pub row_label: String,
+
+  /// The direction of the columns of the images, i.e. the direction from the
+  /// first row to the last, e.g. "P" or "FA". The first letter is the most
+  /// significant direction.
+  ///
+  pub column_label: String,
+
+  /// The anatomical plane the images lie in.
+  ///
+  pub acquisition_plane: AcquisitionPlane,
+}
+
+/// A group of instances in a series that share the same value for an
+/// attribute. Instance indices refer to the instances in the order they were
+/// passed in.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceGroup<T> {
+  /// The value shared by the instances in the group.
+  ///
+  pub value: T,
+
+  /// The indices of the instances in the group, in ascending order.
+  ///
+  pub instance_indices: Vec<usize>,
+}
+
+/// A summary of the instances in a series for use when deciding how to lay out
+/// the series for display.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeriesSummary {
+  /// The number of instances in the series.
+  ///
+  pub instance_count: usize,
+
+  /// The value of the *'(0008,0060) Modality'* data element of the first
+  /// instance that has one.
+  ///
+  pub modality: Option<String>,
+
+  /// The orientation of the images in the series. This is `None` if no
+  /// instances have an *'(0020,0037) Image Orientation (Patient)'* data
+  /// element, or if the instances don't all have the same orientation.
+  ///
+  pub orientation: Option<SeriesOrientation>,
+
+  /// Whether instances in the series have differing orientations.
+  ///
+  pub has_mixed_orientations: bool,
+
+  /// The groups of MR instances that share the same *'(0018,0086) Echo
+  /// Numbers'* value, sorted by echo number. This is empty for non-MR series.
+  ///
+  pub echo_groups: Vec<InstanceGroup<i64>>,
+
+  /// The groups of MR instances that share the same *'(0018,1060) Trigger
+  /// Time'* value, which identifies the cardiac phase, sorted by trigger time
+  /// in milliseconds. This is empty for non-MR series.
+  ///
+  pub phase_groups: Vec<InstanceGroup<f64>>,
+
+  /// The groups of CT instances that share the same *'(0018,0060) KVP'* value,
+  /// which identifies the energy level in multi-energy acquisitions, sorted by
+  /// kVp. This is empty for non-CT series.
+  ///
+  pub energy_groups: Vec<InstanceGroup<f64>>,
+}
+
+impl SeriesSummary {
+  /// Summarizes the given instances, which are expected to all be in the same
+  /// series. Attributes that are missing or invalid in an instance are ignored,
+  /// so an instance that has no echo number isn't in any echo group.
+  ///
+  pub fn from_instances(instances: &[&DataSet]) -> Self {
+    let mut summary = Self {
+      instance_count: instances.len(),
+      modality: None,
+      orientation: None,
+      has_mixed_orientations: false,
+      echo_groups: vec![],
+      phase_groups: vec![],
+      energy_groups: vec![],
+    };
+
+    for (i, instance) in instances.iter().enumerate() {
+      if summary.modality.is_none()
+        && let Ok(modality) = instance.get_string(dictionary::MODALITY.tag)
+      {
+        summary.modality = Some(modality.to_string());
+      }
+
+      let orientation = instance
+        .get_floats(dictionary::IMAGE_ORIENTATION_PATIENT.tag)
+        .ok()
+        .and_then(|values| series_orientation(&values));
+
+      if let Some(orientation) = orientation {
+        match &summary.orientation {
+          None if !summary.has_mixed_orientations => {
+            summary.orientation = Some(orientation)
+          }
+          Some(existing) if *existing != orientation => {
+            summary.orientation = None;
+            summary.has_mixed_orientations = true;
+          }
+          _ => (),
+        }
+      }
+
+      match summary.modality.as_deref() {
+        Some("MR") => {
+          if let Ok(echo_number) =
+            instance.get_int::<i64>(dictionary::ECHO_NUMBERS.tag)
+          {
+            add_to_group(&mut summary.echo_groups, echo_number, i);
+          }
+
+          if let Ok(trigger_time) =
+            instance.get_float(dictionary::TRIGGER_TIME.tag)
+          {
+            add_to_group(&mut summary.phase_groups, trigger_time, i);
+          }
+        }
+
+        Some("CT") => {
+          if let Ok(kvp) = instance.get_float(dictionary::KVP.tag) {
+            add_to_group(&mut summary.energy_groups, kvp, i);
+          }
+        }
+
+        _ => (),
+      }
+    }
+
+    summary.echo_groups.sort_by_key(|group| group.value);
+    summary
+      .phase_groups
+      .sort_by(|a, b| a.value.total_cmp(&b.value));
+    summary
+      .energy_groups
+      .sort_by(|a, b| a.value.total_cmp(&b.value));
+
+    summary
+  }
+}
+
+/// Adds an instance to the group for the given value, creating the group if it
+/// doesn't exist.
+///
+fn add_to_group<T: PartialEq>(
+  groups: &mut Vec<InstanceGroup<T>>,
+  value: T,
+  instance_index: usize,
+) {
+  match groups.iter_mut().find(|group| group.value == value) {
+    Some(group) => group.instance_indices.push(instance_index),
+    None => groups.push(InstanceGroup {
+      value,
+      instance_indices: vec![instance_index],
+    }),
+  }
+}
+
+/// The minimum size of a direction cosine's component for its direction to be
+/// included in an orientation label. Smaller components are considered to be
+/// rounding error.
+///
+const LABEL_COMPONENT_THRESHOLD: f64 = 0.0001;
+
+/// The minimum size of the largest component of the image plane's normal for
+/// the plane to be classified as axial, coronal, or sagittal rather than
+/// oblique. This corresponds to an angle of approximately 25 degrees.
+///
+const STANDARD_PLANE_THRESHOLD: f64 = 0.9;
+
+/// Returns the orientation described by an *'(0020,0037) Image Orientation
+/// (Patient)'* value.
+///
+fn series_orientation(
+  image_orientation_patient: &[f64],
+) -> Option<SeriesOrientation> {
+  let [a, b, c, d, e, f] = image_orientation_patient else {
+    return None;
+  };
+
+  let row = [*a, *b, *c];
+  let column = [*d, *e, *f];
+  let normal = [
+    row[1] * column[2] - row[2] * column[1],
+    row[2] * column[0] - row[0] * column[2],
+    row[0] * column[1] - row[1] * column[0],
+  ];
+
+  let [x, y, z] = normal.map(f64::abs);
+  let acquisition_plane = if z >= x && z >= y && z >= STANDARD_PLANE_THRESHOLD {
+    AcquisitionPlane::Axial
+  } else if y >= x && y >= STANDARD_PLANE_THRESHOLD {
+    AcquisitionPlane::Coronal
+  } else if x >= STANDARD_PLANE_THRESHOLD {
+    AcquisitionPlane::Sagittal
+  } else {
+    AcquisitionPlane::Oblique
+  };
+
+  Some(SeriesOrientation {
+    row_label: orientation_label(row),
+    column_label: orientation_label(column),
+    acquisition_plane,
+  })
+}
+
+/// Returns the patient orientation label for a direction cosine in the patient
+/// coordinate system, which has the x-axis increasing to the patient's left,
+/// the y-axis increasing to the posterior, and the z-axis increasing towards
+/// the head.
+///
+fn orientation_label(direction: [f64; 3]) -> String {
+  let mut components: Vec<(f64, char)> = direction
+    .iter()
+    .zip([('L', 'R'), ('P', 'A'), ('H', 'F')])
+    .map(|(value, (positive, negative))| {
+      (value.abs(), if *value > 0.0 { positive } else { negative })
+    })
+    .filter(|(magnitude, _)| *magnitude > LABEL_COMPONENT_THRESHOLD)
+    .collect();
+
+  components.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+  components.into_iter().map(|(_, label)| label).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn instance(
+    modality: &str,
+    image_orientation_patient: &[f64],
+    echo_number: Option<i64>,
+  ) -> DataSet {
+    let mut data_set = DataSet::new();
+
+    data_set
+      .insert_string_value(&dictionary::MODALITY, &[modality])
+      .unwrap();
+    data_set
+      .insert_float_value(
+        &dictionary::IMAGE_ORIENTATION_PATIENT,
+        image_orientation_patient,
+      )
+      .unwrap();
+
+    if let Some(echo_number) = echo_number {
+      data_set
+        .insert_int_value(&dictionary::ECHO_NUMBERS, &[echo_number])
+        .unwrap();
+    }
+
+    data_set
+  }
+
+  const AXIAL: [f64; 6] = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+  const SAGITTAL: [f64; 6] = [0.0, 1.0, 0.0, 0.0, 0.0, -1.0];
+
+  #[test]
+  fn orientation_test() {
+    assert_eq!(
+      series_orientation(&AXIAL),
+      Some(SeriesOrientation {
+        row_label: "L".to_string(),
+        column_label: "P".to_string(),
+        acquisition_plane: AcquisitionPlane::Axial,
+      })
+    );
+
+    assert_eq!(
+      series_orientation(&SAGITTAL),
+      Some(SeriesOrientation {
+        row_label: "P".to_string(),
+        column_label: "F".to_string(),
+        acquisition_plane: AcquisitionPlane::Sagittal,
+      })
+    );
+
+    assert_eq!(
+      series_orientation(&[1.0, 0.0, 0.0, 0.0, 0.0, -1.0])
+        .map(|o| o.acquisition_plane),
+      Some(AcquisitionPlane::Coronal)
+    );
+
+    let oblique = series_orientation(&[
+      core::f64::consts::FRAC_1_SQRT_2,
+      core::f64::consts::FRAC_1_SQRT_2,
+      0.0,
+      0.0,
+      0.0,
+      -1.0,
+    ])
+    .unwrap();
+    assert_eq!(oblique.row_label, "LP");
+    assert_eq!(oblique.acquisition_plane, AcquisitionPlane::Oblique);
+
+    assert_eq!(series_orientation(&[1.0, 0.0, 0.0]), None);
+  }
+
+  #[test]
+  fn from_instances_test() {
+    let instances = [
+      instance("MR", &AXIAL, Some(2)),
+      instance("MR", &AXIAL, Some(1)),
+      instance("MR", &AXIAL, Some(2)),
+      instance("MR", &AXIAL, None),
+    ];
+    let instances: Vec<&DataSet> = instances.iter().collect();
+
+    let summary = SeriesSummary::from_instances(&instances);
+
+    assert_eq!(summary.instance_count, 4);
+    assert_eq!(summary.modality.as_deref(), Some("MR"));
+    assert_eq!(
+      summary.orientation.map(|o| o.acquisition_plane),
+      Some(AcquisitionPlane::Axial)
+    );
+    assert!(!summary.has_mixed_orientations);
+    assert_eq!(
+      summary.echo_groups,
+      vec![
+        InstanceGroup {
+          value: 1,
+          instance_indices: vec![1]
+        },
+        InstanceGroup {
+          value: 2,
+          instance_indices: vec![0, 2]
+        }
+      ]
+    );
+    assert!(summary.phase_groups.is_empty());
+    assert!(summary.energy_groups.is_empty());
+  }
+
+  #[test]
+  fn from_instances_with_mixed_orientations_test() {
+    let mut instances = [
+      instance("CT", &AXIAL, None),
+      instance("CT", &SAGITTAL, None),
+      instance("CT", &AXIAL, None),
+    ];
+    for (instance, kvp) in instances.iter_mut().zip([140.0, 80.0, 140.0]) {
+      instance
+        .insert_float_value(&dictionary::KVP, &[kvp])
+        .unwrap();
+    }
+    let instances: Vec<&DataSet> = instances.iter().collect();
+
+    let summary = SeriesSummary::from_instances(&instances);
+
+    assert_eq!(summary.orientation, None);
+    assert!(summary.has_mixed_orientations);
+    assert_eq!(
+      summary.energy_groups,
+      vec![
+        InstanceGroup {
+          value: 80.0,
+          instance_indices: vec![1]
+        },
+        InstanceGroup {
+          value: 140.0,
+          instance_indices: vec![0, 2]
+        }
+      ]
+    );
+    assert!(summary.echo_groups.is_empty());
+  }
+}
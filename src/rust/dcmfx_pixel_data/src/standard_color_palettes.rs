@@ -1,5 +1,15 @@
-/// Defines a color palette defined by the DICOM standard that converts 8-bit
-/// grayscale values to colors using a lookup table.
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+/// Defines a color palette that converts 8-bit grayscale values to colors
+/// using a lookup table. The color palettes defined by the DICOM standard are
+/// provided as constants, and custom color palettes can be created from colors,
+/// segments, or CSV.
 ///
 /// Ref: PS3.6 B.
 ///
@@ -9,6 +19,139 @@ pub struct StandardColorPalette {
 }
 
 impl StandardColorPalette {
+  /// Creates a color palette from the 256 colors for each grayscale value.
+  ///
+  pub fn from_colors(colors: &[[u8; 3]]) -> Result<Self, String> {
+    if colors.len() != 256 {
+      return Err(format!(
+        "Color palette must have 256 colors, but has {}",
+        colors.len()
+      ));
+    }
+
+    let mut data = [0; 768];
+    for (chunk, color) in data.chunks_exact_mut(3).zip(colors) {
+      chunk.copy_from_slice(color);
+    }
+
+    Ok(Self { data })
+  }
+
+  /// Creates a color palette from segments, which specify the colors at a set
+  /// of grayscale values. Colors for the grayscale values between segments are
+  /// linearly interpolated, and the first and last colors are extended to the
+  /// start and end of the palette. Segments must be in ascending order of
+  /// grayscale value.
+  ///
+  pub fn from_segments(segments: &[(u8, [u8; 3])]) -> Result<Self, String> {
+    if segments.is_empty() {
+      return Err("Color palette has no segments".to_string());
+    }
+
+    if segments.windows(2).any(|w| w[0].0 >= w[1].0) {
+      return Err(
+        "Color palette segments must be in ascending order".to_string(),
+      );
+    }
+
+    let mut data = [0; 768];
+
+    for (i, chunk) in data.chunks_exact_mut(3).enumerate() {
+      let next = segments.partition_point(|(index, _)| usize::from(*index) < i);
+
+      let color = match (segments.get(next.wrapping_sub(1)), segments.get(next))
+      {
+        (_, Some((index, color))) if usize::from(*index) == i => *color,
+        (Some((start, a)), Some((end, b))) => {
+          let t = (i - usize::from(*start)) as f32 / f32::from(end - start);
+
+          core::array::from_fn(|c| {
+            (f32::from(a[c]) + (f32::from(b[c]) - f32::from(a[c])) * t).round()
+              as u8
+          })
+        }
+        (Some((_, color)), None) | (None, Some((_, color))) => *color,
+        (None, None) => unreachable!(),
+      };
+
+      chunk.copy_from_slice(&color);
+    }
+
+    Ok(Self { data })
+  }
+
+  /// Parses a color palette from CSV. Each line holds either the red, green,
+  /// and blue values of one of the 256 colors, or the grayscale value, red,
+  /// green, and blue values of a segment. Empty lines, lines starting with
+  /// `#`, and a leading header line are ignored.
+  ///
+  /// See [`Self::from_colors()`] and [`Self::from_segments()`] for details.
+  ///
+  pub fn from_csv(csv: &str) -> Result<Self, String> {
+    let mut rows: Vec<Vec<u8>> = vec![];
+    let mut is_first_line = true;
+
+    for line in csv.lines() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let row = line
+        .split(',')
+        .map(|value| value.trim().parse::<u8>())
+        .collect::<Result<Vec<_>, _>>();
+
+      match row {
+        Ok(row) if row.len() == 3 || row.len() == 4 => rows.push(row),
+        Err(_) if is_first_line => (),
+        _ => return Err(format!("Invalid color palette line: {line}")),
+      }
+
+      is_first_line = false;
+    }
+
+    if rows.iter().all(|row| row.len() == 3) {
+      let colors: Vec<[u8; 3]> =
+        rows.iter().map(|row| [row[0], row[1], row[2]]).collect();
+
+      Self::from_colors(&colors)
+    } else if rows.iter().all(|row| row.len() == 4) {
+      let segments: Vec<(u8, [u8; 3])> = rows
+        .iter()
+        .map(|row| (row[0], [row[1], row[2], row[3]]))
+        .collect();
+
+      Self::from_segments(&segments)
+    } else {
+      Err("Color palette lines must all have the same number of values".into())
+    }
+  }
+
+  /// Returns the 256 colors in this color palette.
+  ///
+  pub fn colors(&self) -> Vec<[u8; 3]> {
+    self
+      .data
+      .chunks_exact(3)
+      .map(|c| [c[0], c[1], c[2]])
+      .collect()
+  }
+
+  /// Converts this color palette to CSV with a header line followed by the
+  /// red, green, and blue values of each of its 256 colors. This can be read
+  /// back with [`Self::from_csv()`].
+  ///
+  pub fn to_csv(&self) -> String {
+    let mut csv = "red,green,blue\n".to_string();
+
+    for [r, g, b] in self.colors() {
+      csv.push_str(&format!("{r},{g},{b}\n"));
+    }
+
+    csv
+  }
+
   /// Looks up a grayscale value in this color palette.
   ///
   pub fn lookup(&self, grayscale_value: u8) -> [u8; 3] {
@@ -32,6 +175,31 @@ impl StandardColorPalette {
   }
 }
 
+/// The names and color palettes of all the standard color palettes. Names are
+/// in kebab case, e.g. "hot-iron".
+///
+pub const ALL: [(&str, &StandardColorPalette); 8] = [
+  ("hot-iron", &HOT_IRON),
+  ("pet", &PET),
+  ("hot-metal-blue", &HOT_METAL_BLUE),
+  ("pet-20-step", &PET_20_STEP),
+  ("spring", &SPRING),
+  ("summer", &SUMMER),
+  ("fall", &FALL),
+  ("winter", &WINTER),
+];
+
+/// Returns the standard color palette with the given name. Names are matched
+/// case-insensitively, e.g. "hot-iron" or "Hot-Iron". Returns `None` if there
+/// is no standard color palette with the name.
+///
+pub fn from_name(name: &str) -> Option<&'static StandardColorPalette> {
+  ALL
+    .iter()
+    .find(|(palette_name, _)| palette_name.eq_ignore_ascii_case(name))
+    .map(|(_, palette)| *palette)
+}
+
 /// The Hot Iron color palette is often used in nuclear medicine applications to
 /// make differences in signal intensity (counts) more apparent to the human
 /// observer.
@@ -491,3 +659,49 @@ pub const WINTER: StandardColorPalette = StandardColorPalette {
     253, 129, 126, 254, 128, 127, 255, 128,
   ],
 };
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_segments_test() {
+    let palette = StandardColorPalette::from_segments(&[
+      (10, [0, 0, 0]),
+      (20, [100, 50, 0]),
+    ])
+    .unwrap();
+
+    assert_eq!(palette.lookup(0), [0, 0, 0]);
+    assert_eq!(palette.lookup(15), [50, 25, 0]);
+    assert_eq!(palette.lookup(20), [100, 50, 0]);
+    assert_eq!(palette.lookup(255), [100, 50, 0]);
+
+    assert!(
+      StandardColorPalette::from_segments(&[(20, [0; 3]), (10, [0; 3])])
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn csv_test() {
+    assert_eq!(
+      StandardColorPalette::from_csv(&HOT_IRON.to_csv()),
+      Ok(HOT_IRON)
+    );
+
+    assert_eq!(
+      StandardColorPalette::from_csv("# Segments\n0,0,0,0\n255,255,0,0\n")
+        .map(|palette| palette.lookup(51)),
+      Ok([51, 0, 0])
+    );
+
+    assert!(StandardColorPalette::from_csv("1,2,3\n").is_err());
+  }
+
+  #[test]
+  fn from_name_test() {
+    assert_eq!(from_name("Hot-Iron"), Some(&HOT_IRON));
+    assert_eq!(from_name("unknown"), None);
+  }
+}
@@ -0,0 +1,197 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use dcmfx_core::RcByteSlice;
+use dcmfx_p10::P10Error;
+
+/// Buffers encoded frames of pixel data until they can be emitted. Frames are
+/// held in memory up to an optional memory limit, beyond which further frames
+/// are spilled to an anonymous temporary file and re-read when they are taken
+/// from the buffer.
+///
+/// Spilling to disk is only available when the `std` feature is enabled and
+/// the target isn't WASM. Otherwise, all frames are held in memory regardless
+/// of the memory limit.
+///
+pub(crate) struct FrameBuffer {
+  frames: Vec<BufferedFrame>,
+  memory_limit: Option<usize>,
+  memory_size: usize,
+
+  #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+  spill_file: Option<std::fs::File>,
+  spill_file_size: u64,
+}
+
+enum BufferedFrame {
+  Memory(RcByteSlice),
+
+  #[cfg_attr(
+    not(all(feature = "std", not(target_arch = "wasm32"))),
+    allow(dead_code)
+  )]
+  Spilled {
+    offset: u64,
+    length: usize,
+  },
+}
+
+impl FrameBuffer {
+  /// Creates a new frame buffer with the given memory limit in bytes.
+  ///
+  pub fn new(memory_limit: Option<usize>) -> Self {
+    Self {
+      frames: Vec::new(),
+      memory_limit,
+      memory_size: 0,
+
+      #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+      spill_file: None,
+      spill_file_size: 0,
+    }
+  }
+
+  /// Returns the number of frames in the buffer.
+  ///
+  pub fn len(&self) -> usize {
+    self.frames.len()
+  }
+
+  /// Returns the length in bytes of each frame in the buffer.
+  ///
+  pub fn frame_lengths(&self) -> impl Iterator<Item = usize> + '_ {
+    self.frames.iter().map(|frame| match frame {
+      BufferedFrame::Memory(data) => data.len(),
+      BufferedFrame::Spilled { length, .. } => *length,
+    })
+  }
+
+  /// Returns the total number of bytes that have been spilled to disk.
+  ///
+  #[cfg(test)]
+  pub fn spilled_size(&self) -> u64 {
+    self.spill_file_size
+  }
+
+  /// Adds a frame to the buffer, spilling it to disk if holding it in memory
+  /// would exceed the memory limit.
+  ///
+  pub fn push(&mut self, frame: RcByteSlice) -> Result<(), P10Error> {
+    let exceeds_memory_limit = self
+      .memory_limit
+      .is_some_and(|limit| self.memory_size + frame.len() > limit);
+
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    if exceeds_memory_limit {
+      use std::io::{Seek, SeekFrom, Write};
+
+      let spill_file = match self.spill_file.as_mut() {
+        Some(file) => file,
+        None => self.spill_file.insert(
+          tempfile::tempfile().map_err(|e| spill_error(e, "Creating"))?,
+        ),
+      };
+
+      spill_file
+        .seek(SeekFrom::Start(self.spill_file_size))
+        .and_then(|_| spill_file.write_all(&frame))
+        .map_err(|e| spill_error(e, "Writing"))?;
+
+      self.frames.push(BufferedFrame::Spilled {
+        offset: self.spill_file_size,
+        length: frame.len(),
+      });
+      self.spill_file_size += frame.len() as u64;
+
+      return Ok(());
+    }
+
+    #[cfg(not(all(feature = "std", not(target_arch = "wasm32"))))]
+    let _ = exceeds_memory_limit;
+
+    self.memory_size += frame.len();
+    self.frames.push(BufferedFrame::Memory(frame));
+
+    Ok(())
+  }
+
+  /// Returns the frame at the given index, re-reading it from disk if it was
+  /// spilled. Frames held in memory are released when they are taken.
+  ///
+  pub fn take(&mut self, index: usize) -> Result<RcByteSlice, P10Error> {
+    match &mut self.frames[index] {
+      BufferedFrame::Memory(data) => {
+        let data = core::mem::replace(data, RcByteSlice::empty());
+        self.memory_size -= data.len();
+
+        Ok(data)
+      }
+
+      #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+      BufferedFrame::Spilled { offset, length } => {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let spill_file = self.spill_file.as_mut().unwrap();
+
+        let mut data = vec![0u8; *length];
+        spill_file
+          .seek(SeekFrom::Start(*offset))
+          .and_then(|_| spill_file.read_exact(&mut data))
+          .map_err(|e| spill_error(e, "Reading"))?;
+
+        Ok(data.into())
+      }
+
+      #[cfg(not(all(feature = "std", not(target_arch = "wasm32"))))]
+      BufferedFrame::Spilled { .. } => unreachable!(),
+    }
+  }
+
+  /// Removes all frames from the buffer and deletes any spilled data.
+  ///
+  pub fn clear(&mut self) {
+    self.frames.clear();
+    self.memory_size = 0;
+    self.spill_file_size = 0;
+
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    {
+      self.spill_file = None;
+    }
+  }
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+fn spill_error(error: std::io::Error, action: &str) -> P10Error {
+  P10Error::FileError {
+    when: format!("{action} temporary file for spilled frames").into(),
+    details: error.to_string().into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn spill_test() {
+    let mut buffer = FrameBuffer::new(Some(10));
+
+    for i in 0..4u8 {
+      buffer.push(vec![i; 6].into()).unwrap();
+    }
+
+    assert_eq!(buffer.len(), 4);
+    assert_eq!(buffer.frame_lengths().collect::<Vec<_>>(), vec![6; 4]);
+
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    assert_eq!(buffer.spilled_size(), 18);
+
+    for i in 0..4u8 {
+      assert_eq!(&*buffer.take(i as usize).unwrap(), &[i; 6]);
+    }
+
+    buffer.clear();
+    assert_eq!(buffer.len(), 0);
+  }
+}
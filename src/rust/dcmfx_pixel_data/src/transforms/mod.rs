@@ -1,12 +1,20 @@
 mod crop_rect;
+mod frame_buffer;
 mod p10_pixel_data_frame_transform;
 mod p10_pixel_data_transcode_transform;
+mod p10_segment_mask_transform;
 
 pub use crop_rect::CropRect;
 pub use p10_pixel_data_frame_transform::{
   P10PixelDataFrameTransform, P10PixelDataFrameTransformError,
 };
+pub(crate) use p10_pixel_data_frame_transform::{
+  parse_basic_offset_table, parse_extended_offset_table,
+};
 pub use p10_pixel_data_transcode_transform::{
   P10PixelDataTranscodeTransform, P10PixelDataTranscodeTransformError,
   TranscodeImageDataFunctions,
 };
+pub use p10_segment_mask_transform::{
+  P10SegmentMaskTransform, P10SegmentMaskTransformError,
+};
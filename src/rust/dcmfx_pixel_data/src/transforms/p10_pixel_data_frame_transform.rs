@@ -24,7 +24,7 @@ use dcmfx_p10::{
   P10FilterTransform, P10Token,
 };
 
-use crate::PixelDataFrame;
+use crate::{FrameTiming, PixelDataFrame, VideoCodec};
 
 /// This transform takes a stream of DICOM P10 tokens and emits the frames of
 /// pixel data it contains. Each frame is returned with no copying of pixel
@@ -32,13 +32,25 @@ use crate::PixelDataFrame;
 ///
 /// All native and encapsulated pixel data is supported.
 ///
+/// Pixel data that holds a video stream is emitted as a single frame once the
+/// whole stream has been read. Its pictures are decoded using
+/// [`crate::decode::decode_video_monochrome()`] and
+/// [`crate::decode::decode_video_color()`].
+///
 pub struct P10PixelDataFrameTransform {
   is_encapsulated: bool,
 
+  // Whether the transfer syntax is one of the video transfer syntaxes
+  is_video: bool,
+
   // Extracts the value of relevant data elements from the stream of DICOM P10
   // tokens
   details: P10CustomTypeTransform<PixelDataFrameTransformDetails>,
 
+  // Extracts the timing of frames so it can be set on each emitted frame. This
+  // is set to `None` if the frame timing can't be read, as it is optional.
+  frame_timing: Option<P10CustomTypeTransform<FrameTiming>>,
+
   // Filter used to extract only the '(7FE0,0010) Pixel Data' data element
   pixel_data_filter: P10FilterTransform,
 
@@ -67,7 +79,10 @@ pub struct P10PixelDataFrameTransform {
   next_frame_index: usize,
 }
 
-type OffsetTable = VecDeque<(u64, Option<u64>)>;
+/// An offset table for encapsulated pixel data. Each entry holds the offset of
+/// a frame's first pixel data item, and the length of the frame if known.
+///
+pub(crate) type OffsetTable = VecDeque<(u64, Option<u64>)>;
 
 #[derive(Clone, Debug, PartialEq)]
 struct PixelDataFrameTransformDetails {
@@ -168,7 +183,9 @@ impl P10PixelDataFrameTransform {
 
     Self {
       is_encapsulated: false,
+      is_video: false,
       details: details_transform,
+      frame_timing: Some(P10CustomTypeTransform::new_for_iod_module()),
       pixel_data_filter,
       native_pixel_data_frame_size: 0,
       pixel_data: VecDeque::new(),
@@ -197,15 +214,45 @@ impl P10PixelDataFrameTransform {
       }
     };
 
+    if let P10Token::FileMetaInformation { data_set } = token {
+      self.is_video = data_set
+        .get_transfer_syntax()
+        .ok()
+        .and_then(VideoCodec::from_transfer_syntax)
+        .is_some();
+    }
+
+    // Add the token into the frame timing transform. Frames are still emitted
+    // if their timing can't be read, they just won't have a time interval.
+    if let Some(frame_timing) = self.frame_timing.as_mut()
+      && frame_timing.add_token(token).is_err()
+    {
+      self.frame_timing = None;
+    }
+
     if !token.is_header_token()
       && self
         .pixel_data_filter
         .add_token(token)
         .map_err(P10PixelDataFrameTransformError::P10Error)?
     {
-      self
+      let mut frames = self
         .process_next_pixel_data_token(token)
-        .map_err(P10PixelDataFrameTransformError::DataError)
+        .map_err(P10PixelDataFrameTransformError::DataError)?;
+
+      if let Some(frame_timing) = self
+        .frame_timing
+        .as_ref()
+        .and_then(|transform| transform.get_output())
+      {
+        for frame in frames.iter_mut() {
+          if let Some(frame_index) = frame.index() {
+            frame.set_time_interval(frame_timing.frame_interval(frame_index));
+          }
+        }
+      }
+
+      Ok(frames)
     } else {
       Ok(vec![])
     }
@@ -247,6 +294,11 @@ impl P10PixelDataFrameTransform {
         Ok(vec![])
       }
 
+      // The end of the encapsulated pixel data
+      P10Token::SequenceDelimiter { .. } if self.is_video => {
+        Ok(self.get_video_frames())
+      }
+
       // The end of the encapsulated pixel data
       P10Token::SequenceDelimiter { .. } => {
         let mut frames = vec![];
@@ -297,7 +349,12 @@ impl P10PixelDataFrameTransform {
         self.pixel_data_write_offset += data.len() as u64 * 8;
 
         if self.is_encapsulated {
-          if *bytes_remaining == 0 {
+          // Video streams are only split into frames once they've been read
+          // in full, however the Basic Offset Table still needs to be read
+          let is_video_stream_data =
+            self.is_video && self.offset_table.is_some();
+
+          if *bytes_remaining == 0 && !is_video_stream_data {
             self.get_pending_encapsulated_frames()
           } else {
             Ok(vec![])
@@ -461,7 +518,7 @@ impl P10PixelDataFrameTransform {
             // Check that the frame ended exactly on the expected offset
             if self.pixel_data_read_offset != offset * 8 {
               return Err(DataError::new_value_invalid(
-                "Pixel data offset table is malformed".to_string(),
+                "Pixel data offset table is malformed",
               ));
             }
 
@@ -479,6 +536,27 @@ impl P10PixelDataFrameTransform {
     }
   }
 
+  /// Returns the encapsulated video stream as a single frame.
+  ///
+  fn get_video_frames(&mut self) -> Vec<PixelDataFrame> {
+    if self.pixel_data.is_empty() {
+      return vec![];
+    }
+
+    let mut frame = PixelDataFrame::new();
+    frame.set_index(self.next_frame_index);
+
+    for (chunk, _, is_pixel_data_item_header) in self.pixel_data.drain(..) {
+      if !is_pixel_data_item_header {
+        frame.push_bytes(chunk);
+      }
+    }
+
+    self.next_frame_index += 1;
+
+    vec![frame]
+  }
+
   fn read_offset_table(&self) -> Result<OffsetTable, DataError> {
     let basic_offset_table = self.read_basic_offset_table()?;
     let extended_offset_table = self.read_extended_offset_table()?;
@@ -512,41 +590,7 @@ impl P10PixelDataFrameTransform {
       offset_table_data.extend_from_slice(data);
     }
 
-    if offset_table_data.is_empty() {
-      return Ok(VecDeque::new());
-    }
-
-    // Validate the data's length is a multiple of 4
-    if offset_table_data.len() % 4 != 0 {
-      return Err(DataError::new_value_invalid(
-        "Basic Offset Table length is not a multiple of 4".to_string(),
-      ));
-    }
-
-    // Read data into u32 values
-    let mut offsets = vec![0u32; offset_table_data.len() / 4];
-    byteorder::LittleEndian::read_u32_into(&offset_table_data, &mut offsets);
-
-    // Check that the first offset is zero. Ref: PS3.5 A.4.
-    if offsets.first() != Some(&0) {
-      return Err(DataError::new_value_invalid(
-        "Basic Offset Table first value must be zero".to_string(),
-      ));
-    }
-
-    // Check that the offsets are sorted
-    if !offsets.is_sorted() {
-      return Err(DataError::new_value_invalid(
-        "Basic Offset Table values are not sorted".to_string(),
-      ));
-    }
-
-    let mut offset_table = VecDeque::new();
-    for offset in offsets {
-      offset_table.push_back((u64::from(offset), None));
-    }
-
-    Ok(offset_table)
+    parse_basic_offset_table(&offset_table_data)
   }
 
   fn read_extended_offset_table(
@@ -557,77 +601,10 @@ impl P10PixelDataFrameTransform {
         extended_offset_table: Some(extended_offset_table),
         extended_offset_table_lengths: Some(extended_offset_table_lengths),
         ..
-      }) => {
-        // Get the value of the '(0x7FE0,0001) Extended Offset Table' data
-        // element
-        let extended_offset_table_bytes = extended_offset_table
-          .vr_bytes(&[ValueRepresentation::OtherVeryLongString])?;
-
-        if extended_offset_table_bytes.len() % 8 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Extended Offset Table has invalid size".to_string(),
-          ));
-        }
-
-        let mut extended_offset_table =
-          vec![0u64; extended_offset_table_bytes.len() / 8];
-        byteorder::LittleEndian::read_u64_into(
-          extended_offset_table_bytes,
-          extended_offset_table.as_mut_slice(),
-        );
-
-        // Check that the first offset is zero
-        if *extended_offset_table.first().unwrap_or(&0) != 0 {
-          return Err(DataError::new_value_invalid(
-            "Extended Offset Table first value must be zero".to_string(),
-          ));
-        }
-
-        // Check that the offsets are sorted
-        if !extended_offset_table.is_sorted() {
-          return Err(DataError::new_value_invalid(
-            "Extended Offset Table values are not sorted".to_string(),
-          ));
-        }
-
-        // Get the value of the '(0x7FE0,0002) Extended Offset Table Lengths'
-        // data element
-        let extended_offset_table_lengths_bytes = extended_offset_table_lengths
-          .vr_bytes(&[ValueRepresentation::OtherVeryLongString])?;
-
-        if extended_offset_table_lengths_bytes.len() % 8 != 0 {
-          return Err(DataError::new_value_invalid(
-            "Extended Offset Table Lengths has invalid size".to_string(),
-          ));
-        }
-
-        let mut extended_offset_table_lengths =
-          vec![0u64; extended_offset_table_lengths_bytes.len() / 8];
-        byteorder::LittleEndian::read_u64_into(
-          extended_offset_table_lengths_bytes,
-          extended_offset_table_lengths.as_mut_slice(),
-        );
-
-        // Check the two are of the same length
-        if extended_offset_table.len() != extended_offset_table_lengths.len() {
-          return Err(DataError::new_value_invalid(
-            "Extended Offset Table and Lengths don't have the same number of \
-          items"
-              .to_string(),
-          ));
-        }
-
-        // Return the offset table
-        let mut entries = VecDeque::with_capacity(extended_offset_table.len());
-        for i in 0..extended_offset_table.len() {
-          entries.push_back((
-            extended_offset_table[i],
-            Some(extended_offset_table_lengths[i]),
-          ));
-        }
-
-        Ok(Some(entries))
-      }
+      }) => Ok(Some(parse_extended_offset_table(
+        extended_offset_table,
+        extended_offset_table_lengths,
+      )?)),
 
       _ => Ok(None),
     }
@@ -664,6 +641,127 @@ impl Default for P10PixelDataFrameTransform {
   }
 }
 
+/// Parses the content of a Basic Offset Table, i.e. the value of the first
+/// pixel data item in encapsulated pixel data.
+///
+pub(crate) fn parse_basic_offset_table(
+  offset_table_data: &[u8],
+) -> Result<OffsetTable, DataError> {
+  if offset_table_data.is_empty() {
+    return Ok(VecDeque::new());
+  }
+
+  // Validate the data's length is a multiple of 4
+  if !offset_table_data.len().is_multiple_of(4) {
+    return Err(DataError::new_value_invalid(
+      "Basic Offset Table length is not a multiple of 4",
+    ));
+  }
+
+  // Read data into u32 values
+  let mut offsets = vec![0u32; offset_table_data.len() / 4];
+  byteorder::LittleEndian::read_u32_into(offset_table_data, &mut offsets);
+
+  // Check that the first offset is zero. Ref: PS3.5 A.4.
+  if offsets.first() != Some(&0) {
+    return Err(DataError::new_value_invalid(
+      "Basic Offset Table first value must be zero",
+    ));
+  }
+
+  // Check that the offsets are sorted
+  if !offsets.is_sorted() {
+    return Err(DataError::new_value_invalid(
+      "Basic Offset Table values are not sorted",
+    ));
+  }
+
+  let mut offset_table = VecDeque::new();
+  for offset in offsets {
+    offset_table.push_back((u64::from(offset), None));
+  }
+
+  Ok(offset_table)
+}
+
+/// Parses the values of the *'(7FE0,0001) Extended Offset Table'* and
+/// *'(7FE0,0002) Extended Offset Table Lengths'* data elements.
+///
+pub(crate) fn parse_extended_offset_table(
+  extended_offset_table: &DataElementValue,
+  extended_offset_table_lengths: &DataElementValue,
+) -> Result<OffsetTable, DataError> {
+  // Get the value of the '(0x7FE0,0001) Extended Offset Table' data
+  // element
+  let extended_offset_table_bytes = extended_offset_table
+    .vr_bytes(&[ValueRepresentation::OtherVeryLongString])?;
+
+  if extended_offset_table_bytes.len() % 8 != 0 {
+    return Err(DataError::new_value_invalid(
+      "Extended Offset Table has invalid size",
+    ));
+  }
+
+  let mut extended_offset_table =
+    vec![0u64; extended_offset_table_bytes.len() / 8];
+  byteorder::LittleEndian::read_u64_into(
+    extended_offset_table_bytes,
+    extended_offset_table.as_mut_slice(),
+  );
+
+  // Check that the first offset is zero
+  if *extended_offset_table.first().unwrap_or(&0) != 0 {
+    return Err(DataError::new_value_invalid(
+      "Extended Offset Table first value must be zero",
+    ));
+  }
+
+  // Check that the offsets are sorted
+  if !extended_offset_table.is_sorted() {
+    return Err(DataError::new_value_invalid(
+      "Extended Offset Table values are not sorted",
+    ));
+  }
+
+  // Get the value of the '(0x7FE0,0002) Extended Offset Table Lengths'
+  // data element
+  let extended_offset_table_lengths_bytes = extended_offset_table_lengths
+    .vr_bytes(&[ValueRepresentation::OtherVeryLongString])?;
+
+  if extended_offset_table_lengths_bytes.len() % 8 != 0 {
+    return Err(DataError::new_value_invalid(
+      "Extended Offset Table Lengths has invalid size",
+    ));
+  }
+
+  let mut extended_offset_table_lengths =
+    vec![0u64; extended_offset_table_lengths_bytes.len() / 8];
+  byteorder::LittleEndian::read_u64_into(
+    extended_offset_table_lengths_bytes,
+    extended_offset_table_lengths.as_mut_slice(),
+  );
+
+  // Check the two are of the same length
+  if extended_offset_table.len() != extended_offset_table_lengths.len() {
+    return Err(DataError::new_value_invalid(
+      "Extended Offset Table and Lengths don't have the same number of \
+    items"
+        .to_string(),
+    ));
+  }
+
+  // Return the offset table
+  let mut entries = VecDeque::with_capacity(extended_offset_table.len());
+  for i in 0..extended_offset_table.len() {
+    entries.push_back((
+      extended_offset_table[i],
+      Some(extended_offset_table_lengths[i]),
+    ));
+  }
+
+  Ok(entries)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -736,6 +834,97 @@ mod tests {
     );
   }
 
+  #[test]
+  fn sets_frame_time_intervals() {
+    use core::time::Duration;
+
+    use dcmfx_p10::DataSetP10Extensions;
+
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[2])
+      .unwrap();
+    ds.insert_float_value(&dictionary::FRAME_TIME, &[40.0])
+      .unwrap();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::ROWS, &[2]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[2]).unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[8])
+      .unwrap();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      vec![0; 8].into(),
+    )
+    .unwrap();
+
+    let mut transform = P10PixelDataFrameTransform::new();
+
+    let mut frames = vec![];
+    for token in ds.to_p10_tokens() {
+      frames.extend(transform.add_token(&token).unwrap());
+    }
+
+    assert_eq!(
+      frames
+        .iter()
+        .map(|frame| frame.time_interval().cloned())
+        .collect::<Vec<_>>(),
+      vec![
+        Some(Duration::ZERO..Duration::from_millis(40)),
+        Some(Duration::from_millis(40)..Duration::from_millis(80)),
+      ]
+    );
+  }
+
+  #[test]
+  fn reads_video_stream_as_single_frame() {
+    use dcmfx_core::transfer_syntax;
+    use dcmfx_p10::DataSetP10Extensions;
+
+    let mut ds = DataSet::new();
+    ds.insert_string_value(
+      &dictionary::TRANSFER_SYNTAX_UID,
+      &[transfer_syntax::MPEG2_MAIN_PROFILE_MAIN_LEVEL.uid],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::ROWS, &[32]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[32]).unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[8])
+      .unwrap();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_encapsulated_pixel_data_unchecked(
+        ValueRepresentation::OtherByteString,
+        vec![vec![].into(), vec![1; 4].into(), vec![2; 4].into()],
+      ),
+    );
+
+    let mut transform = P10PixelDataFrameTransform::new();
+    let mut frames = vec![];
+    for token in ds.to_p10_tokens() {
+      frames.extend(transform.add_token(&token).unwrap());
+    }
+
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].index(), Some(0));
+    assert_eq!(frames[0].combine_chunks(), [1, 1, 1, 1, 2, 2, 2, 2]);
+  }
+
   fn p10_tokens_to_frames(tokens: &[P10Token]) -> Vec<Vec<u8>> {
     let mut transform = P10PixelDataFrameTransform::new();
 
@@ -8,9 +8,9 @@ use alloc::{
 };
 
 use dcmfx_core::{
-  DataElementValue, DataError, DataSet, DataSetPath, DcmfxError, IodModule, Rc,
-  RcByteSlice, TransferSyntax, ValueRepresentation, dictionary,
-  transfer_syntax,
+  DataElementValue, DataError, DataSet, DataSetPath, DcmfxError, ErrorContext,
+  ErrorContextChain, IodModule, Rc, RcByteSlice, TransferSyntax,
+  ValueRepresentation, dictionary, error_details, transfer_syntax,
 };
 use dcmfx_p10::{
   P10CustomTypeTransform, P10CustomTypeTransformError, P10Error,
@@ -20,11 +20,12 @@ use dcmfx_p10::{
 use crate::{
   ColorImage, MonochromeImage, P10PixelDataFrameTransform,
   P10PixelDataFrameTransformError, PixelDataDecodeConfig, PixelDataDecodeError,
-  PixelDataEncodeConfig, PixelDataEncodeError, PixelDataFrame, decode, encode,
+  PixelDataEncodeConfig, PixelDataEncodeError, PixelDataFrame, VideoCodec,
+  decode, encode,
   iods::image_pixel_module::{
     ImagePixelModule, PhotometricInterpretation, PlanarConfiguration,
   },
-  transforms::CropRect,
+  transforms::{CropRect, frame_buffer::FrameBuffer},
 };
 
 /// This transform takes a stream of DICOM P10 tokens and transcodes its pixel
@@ -91,6 +92,19 @@ pub struct P10PixelDataTranscodeTransform {
   /// every frame of pixel data that's emitted.
   native_pixel_data_bytes_remaining: u32,
 
+  /// When encoded frames are being split into multiple fragments, the encoded
+  /// frames are buffered here until the last frame has been encoded. This is
+  /// because the Basic Offset Table that precedes the fragments can't be
+  /// generated until the size of every encoded frame is known. Frames beyond
+  /// the encode config's frame buffer memory limit are spilled to disk.
+  fragmented_frames_buffer: FrameBuffer,
+
+  /// Once the last frame has been encoded, the index of the next buffered frame
+  /// to emit fragments for, and the index of its first pixel data item.
+  /// Buffered frames are emitted in batches no larger than the frame buffer
+  /// memory limit, see [`Self::read_pending_tokens()`].
+  next_fragmented_frame: Option<(usize, usize)>,
+
   /// If the output transfer syntax is lossy, this is an insert transform that
   /// inserts the '(0028,2110) Lossy Image Compression' data element.
   lossy_image_compression_insert_transform: Option<P10InsertTransform>,
@@ -143,6 +157,12 @@ impl P10PixelDataTranscodeTransform {
     encode_config: PixelDataEncodeConfig,
     image_data_functions: Option<TranscodeImageDataFunctions>,
   ) -> Self {
+    let lossy_image_compression_insert_transform =
+      Self::lossy_image_compression_insert_transform(
+        output_transfer_syntax,
+        &encode_config,
+      );
+
     Self {
       input_transfer_syntax: &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
       output_transfer_syntax,
@@ -161,8 +181,11 @@ impl P10PixelDataTranscodeTransform {
       )),
       p10_pixel_data_frame_transform: P10PixelDataFrameTransform::new(),
       native_pixel_data_bytes_remaining: 0,
-      lossy_image_compression_insert_transform:
-        Self::lossy_image_compression_insert_transform(output_transfer_syntax),
+      fragmented_frames_buffer: FrameBuffer::new(
+        encode_config.frame_buffer_memory_limit(),
+      ),
+      next_fragmented_frame: None,
+      lossy_image_compression_insert_transform,
     }
   }
 
@@ -198,6 +221,13 @@ impl P10PixelDataTranscodeTransform {
   /// an altered token stream containing the transcoded data set. Some internal
   /// buffering of tokens is expected.
   ///
+  /// When a frame buffer memory limit is set in the encode config, tokens for
+  /// buffered frames may be held back in order to keep memory usage bounded.
+  /// These are returned by [`Self::read_pending_tokens()`], which should be
+  /// called repeatedly after each call to this function until it returns no
+  /// tokens. Any pending tokens that haven't been read are output at the start
+  /// of the next call to this function.
+  ///
   pub fn add_token(
     &mut self,
     token: &P10Token,
@@ -206,6 +236,12 @@ impl P10PixelDataTranscodeTransform {
       return Ok(vec![token.clone()]);
     }
 
+    // Output any pending tokens first so that token order is preserved
+    let mut pending_tokens = vec![];
+    while self.next_fragmented_frame.is_some() {
+      pending_tokens.extend(self.read_pending_tokens()?);
+    }
+
     // Store the input transfer syntax if one is specified in the File Meta
     // Information
     if let P10Token::FileMetaInformation { data_set } = token
@@ -273,17 +309,44 @@ impl P10PixelDataTranscodeTransform {
     // Iterate over the available pixel data frames and convert them into the
     // target transfer syntax, appending the resulting tokens to the vector
     for mut input_frame in input_frames {
-      let encoded_frame = self.transcode_frame(&mut input_frame)?;
-
-      let frame_index = input_frame.index().unwrap();
+      // Errors that occur on this frame have the path to the pixel data and
+      // the frame index added to their context
+      let frame_error_context = |frame_index: usize| {
+        move |e: P10PixelDataTranscodeTransformError| {
+          e.with_context(
+            ErrorContext::new()
+              .with_path(&DataSetPath::new_with_data_element(
+                dictionary::PIXEL_DATA.tag,
+              ))
+              .with_frame_index(frame_index),
+          )
+        }
+      };
 
-      if self.output_transfer_syntax.is_encapsulated {
-        output_tokens.extend(
-          self.encapsulated_pixel_data_tokens(frame_index, encoded_frame)?,
-        );
-      } else {
-        output_tokens
-          .extend(self.native_pixel_data_tokens(frame_index, encoded_frame)?);
+      let first_frame_index = input_frame.index().unwrap();
+
+      // A frame that holds a video stream is transcoded into one frame for
+      // each of its pictures
+      let encoded_frames = self
+        .transcode_frame(&mut input_frame)
+        .map_err(frame_error_context(first_frame_index))?;
+
+      for (i, encoded_frame) in encoded_frames.into_iter().enumerate() {
+        let frame_index = first_frame_index + i;
+
+        if self.output_transfer_syntax.is_encapsulated {
+          output_tokens.extend(
+            self
+              .encapsulated_pixel_data_tokens(frame_index, encoded_frame)
+              .map_err(frame_error_context(frame_index))?,
+          );
+        } else {
+          output_tokens.extend(
+            self
+              .native_pixel_data_tokens(frame_index, encoded_frame)
+              .map_err(frame_error_context(frame_index))?,
+          );
+        }
       }
     }
 
@@ -304,9 +367,46 @@ impl P10PixelDataTranscodeTransform {
       output_tokens = new_tokens;
     }
 
+    if !pending_tokens.is_empty() {
+      pending_tokens.extend(output_tokens);
+      output_tokens = pending_tokens;
+    }
+
     Ok(output_tokens)
   }
 
+  /// Returns the next batch of tokens that were held back in order to keep
+  /// memory usage within the frame buffer memory limit. Returns no tokens once
+  /// all pending tokens have been read. See [`Self::add_token()`].
+  ///
+  pub fn read_pending_tokens(
+    &mut self,
+  ) -> Result<Vec<P10Token>, P10PixelDataTranscodeTransformError> {
+    let Some(max_fragment_size) = self.encode_config.max_fragment_size() else {
+      return Ok(vec![]);
+    };
+
+    let tokens = self.fragmented_frame_tokens(max_fragment_size)?;
+
+    match &mut self.lossy_image_compression_insert_transform {
+      Some(lossy_image_compression_insert_transform) => {
+        let mut new_tokens = vec![];
+
+        for token in tokens {
+          new_tokens.extend(
+            lossy_image_compression_insert_transform
+              .add_token(&token)
+              .map_err(P10PixelDataTranscodeTransformError::P10Error)?,
+          );
+        }
+
+        Ok(new_tokens)
+      }
+
+      None => Ok(tokens),
+    }
+  }
+
   /// Adds the next token to the buffer of initial tokens that buffers all
   /// tokens until the Image Pixel Module has been fully received. The initial
   /// tokens are then transformed as required, modifying the Image Pixel Module
@@ -459,13 +559,32 @@ impl P10PixelDataTranscodeTransform {
     ))
   }
 
-  /// Transcodes a single [`PixelDataFrame`] into a frame for the target
-  /// transfer syntax.
+  /// Transcodes a single [`PixelDataFrame`] into frames for the target
+  /// transfer syntax. A frame that holds a video stream is transcoded into one
+  /// frame for each of its pictures, and any other frame is transcoded into a
+  /// single frame.
   ///
   fn transcode_frame(
     &mut self,
     input_frame: &mut PixelDataFrame,
-  ) -> Result<RcByteSlice, P10PixelDataTranscodeTransformError> {
+  ) -> Result<Vec<RcByteSlice>, P10PixelDataTranscodeTransformError> {
+    // Decode errors have the input transfer syntax added to their context, and
+    // encode errors have the output transfer syntax added
+    let input_transfer_syntax = self.input_transfer_syntax;
+    let output_transfer_syntax = self.output_transfer_syntax;
+
+    let decode_error = |e| {
+      P10PixelDataTranscodeTransformError::PixelDataDecodeError(e).with_context(
+        ErrorContext::new().with_transfer_syntax(input_transfer_syntax),
+      )
+    };
+
+    let encode_error = |e| {
+      P10PixelDataTranscodeTransformError::PixelDataEncodeError(e).with_context(
+        ErrorContext::new().with_transfer_syntax(output_transfer_syntax),
+      )
+    };
+
     // Special case for direct recompression/reconstruction of JPEG Baseline
     // 8-bit to/from JPEG XL. This is a fast path that can be taken when a full
     // encode/decode cycle isn't needed.
@@ -486,9 +605,9 @@ impl P10PixelDataTranscodeTransform {
           crate::jpeg_xl_jpeg_recompression::recompress_jpeg_to_jpeg_xl(
             jpeg_data,
           )
-          .map_err(P10PixelDataTranscodeTransformError::PixelDataEncodeError)?;
+          .map_err(encode_error)?;
 
-        return Ok(jpeg_xl_data.into());
+        return Ok(vec![jpeg_xl_data.into()]);
       }
 
       if self.input_transfer_syntax == &JPEG_XL_JPEG_RECOMPRESSION
@@ -499,9 +618,9 @@ impl P10PixelDataTranscodeTransform {
           crate::jpeg_xl_jpeg_recompression::reconstruct_jpeg_from_jpeg_xl(
             jpeg_xl_data,
           )
-          .map_err(P10PixelDataTranscodeTransformError::PixelDataEncodeError)?;
+          .map_err(encode_error)?;
 
-        return Ok(jpeg_data.into());
+        return Ok(vec![jpeg_data.into()]);
       }
     }
 
@@ -510,74 +629,117 @@ impl P10PixelDataTranscodeTransform {
       .get_output()
       .unwrap();
 
-    let output_frame = if image_pixel_module.is_color() {
+    // All pictures are decoded from a frame that holds a video stream
+    let is_video =
+      VideoCodec::from_transfer_syntax(self.input_transfer_syntax).is_some();
+
+    let output_frames = if image_pixel_module.is_color() {
       // Decode using the input Image Pixel Module
-      let mut image = crate::decode::decode_color(
-        input_frame,
-        self.input_transfer_syntax,
-        image_pixel_module,
-        &self.decode_config,
-      )
-      .map_err(P10PixelDataTranscodeTransformError::PixelDataDecodeError)?;
+      let images = if is_video {
+        crate::decode::decode_video_color(
+          input_frame,
+          self.input_transfer_syntax,
+          image_pixel_module,
+          &self.decode_config,
+        )
+        .map_err(decode_error)?
+      } else {
+        vec![
+          crate::decode::decode_color(
+            input_frame,
+            self.input_transfer_syntax,
+            image_pixel_module,
+            &self.decode_config,
+          )
+          .map_err(decode_error)?,
+        ]
+      };
 
-      // Pass through the relevant image data function
-      (self.image_data_functions.process_color_image)(
-        &mut image,
-        self.output_image_pixel_module.as_ref().unwrap(),
-      )?;
+      let mut output_frames = vec![];
+
+      for mut image in images {
+        // Pass through the relevant image data function
+        (self.image_data_functions.process_color_image)(
+          &mut image,
+          self.output_image_pixel_module.as_ref().unwrap(),
+        )?;
+
+        // Encode using the output Image Pixel Module
+        output_frames.push(
+          crate::encode::encode_color(
+            &image,
+            self.output_image_pixel_module.as_ref().unwrap(),
+            self.output_transfer_syntax,
+            &self.encode_config,
+          )
+          .map_err(encode_error)?,
+        );
+      }
 
-      // Encode using the output Image Pixel Module
-      crate::encode::encode_color(
-        &image,
-        self.output_image_pixel_module.as_ref().unwrap(),
-        self.output_transfer_syntax,
-        &self.encode_config,
-      )
-      .map_err(P10PixelDataTranscodeTransformError::PixelDataEncodeError)?
+      output_frames
     } else {
       // Decode using the input Image Pixel Module
-      let mut image = crate::decode::decode_monochrome(
-        input_frame,
-        self.input_transfer_syntax,
-        image_pixel_module,
-        &self.decode_config,
-      )
-      .map_err(P10PixelDataTranscodeTransformError::PixelDataDecodeError)?;
+      let images = if is_video {
+        crate::decode::decode_video_monochrome(
+          input_frame,
+          self.input_transfer_syntax,
+          image_pixel_module,
+          &self.decode_config,
+        )
+        .map_err(decode_error)?
+      } else {
+        vec![
+          crate::decode::decode_monochrome(
+            input_frame,
+            self.input_transfer_syntax,
+            image_pixel_module,
+            &self.decode_config,
+          )
+          .map_err(decode_error)?,
+        ]
+      };
 
-      // Pass through the relevant image data function
-      (self.image_data_functions.process_monochrome_image)(
-        &mut image,
-        self.output_image_pixel_module.as_ref().unwrap(),
-      )?;
+      let mut output_frames = vec![];
+
+      for mut image in images {
+        // Pass through the relevant image data function
+        (self.image_data_functions.process_monochrome_image)(
+          &mut image,
+          self.output_image_pixel_module.as_ref().unwrap(),
+        )?;
+
+        // Encode using the output Image Pixel Module
+        let frame = crate::encode::encode_monochrome(
+          &image,
+          self.output_image_pixel_module.as_ref().unwrap(),
+          self.output_transfer_syntax,
+          &self.encode_config,
+        )
+        .map_err(encode_error)?;
+
+        // Transcoding of multi-frame data where the frames aren't a whole
+        // number of bytes isn't supported. This is an extremely rare
+        // occurrence as it only occurs on non-encapsulated multi-frame data
+        // where bits allocated is one and the pixel count isn't a multiple of
+        // eight.
+        if !self.output_transfer_syntax.is_encapsulated
+          && input_frame.index().unwrap() != 0
+          && frame.len_bits() % 8 != 0
+        {
+          return Err(P10PixelDataTranscodeTransformError::NotSupported {
+            details: "Transcoding multi-frame bitmap pixel data that isn't \
+              byte-aligned is not supported"
+              .to_string(),
+          });
+        }
 
-      // Encode using the output Image Pixel Module
-      let frame = crate::encode::encode_monochrome(
-        &image,
-        self.output_image_pixel_module.as_ref().unwrap(),
-        self.output_transfer_syntax,
-        &self.encode_config,
-      )
-      .map_err(P10PixelDataTranscodeTransformError::PixelDataEncodeError)?;
-
-      // Transcoding of multi-frame data where the frames aren't a whole number
-      // of bytes isn't supported. This is an extremely rare occurrence as it
-      // only occurs on non-encapsulated multi-frame data where bits allocated
-      // is one and the pixel count isn't a multiple of eight.
-      if !self.output_transfer_syntax.is_encapsulated
-        && input_frame.index().unwrap() != 0
-        && frame.len_bits() % 8 != 0
-      {
-        return Err(P10PixelDataTranscodeTransformError::NotSupported {
-          details: "Transcoding multi-frame bitmap pixel data that isn't \
-            byte-aligned is not supported"
-            .to_string(),
-        });
+        output_frames.push(frame);
       }
 
-      frame
+      output_frames
     };
 
-    Ok(output_frame.to_bytes())
+    Ok(output_frames.iter().map(PixelDataFrame::to_bytes).collect())
   }
 
   /// Returns the DICOM P10 tokens for the next transcoded frame of native pixel
@@ -612,7 +774,7 @@ impl P10PixelDataTranscodeTransform {
           DataError::new_value_length_invalid(
             vr,
             pixel_data_value_length,
-            "Native pixel data length exceeds 2^32 - 1".to_string(),
+            "Native pixel data length exceeds 2^32 - 1",
           )
           .with_path(&DataSetPath::new_with_data_element(
             dictionary::PIXEL_DATA.tag,
@@ -637,7 +799,7 @@ impl P10PixelDataTranscodeTransform {
       return Err(P10PixelDataTranscodeTransformError::P10Error(
         P10Error::OtherError {
           error_type: "Transcoded pixel data too large".to_string(),
-          details: format!(
+          details: error_details!(
             "Frame {} of length {} exceeds the remaining size of the native \
              pixel data {}",
             frame_index,
@@ -665,39 +827,17 @@ impl P10PixelDataTranscodeTransform {
   /// pixel data.
   ///
   fn encapsulated_pixel_data_tokens(
-    &self,
+    &mut self,
     frame_index: usize,
     encoded_frame: RcByteSlice,
   ) -> Result<Vec<P10Token>, P10PixelDataTranscodeTransformError> {
-    let mut tokens = vec![];
-
-    // On the first frame, emit tokens for the start of the pixel data sequence
-    // as well as an empty basic offset table
-    if frame_index == 0 {
-      tokens.push(P10Token::SequenceStart {
-        tag: dictionary::PIXEL_DATA.tag,
-        vr: ValueRepresentation::OtherByteString,
-        path: DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
-      });
-      tokens.push(P10Token::PixelDataItem {
-        index: 0,
-        length: 0,
-      });
-      tokens.push(P10Token::DataElementValueBytes {
-        tag: dictionary::ITEM.tag,
-        vr: ValueRepresentation::OtherByteString,
-        data: RcByteSlice::empty(),
-        bytes_remaining: 0,
-      });
-    }
-
     // Check the length of the encoded frame is a valid u32 length
     if encoded_frame.len() > (u32::MAX - 1) as usize {
       return Err(P10PixelDataTranscodeTransformError::DataError(
         DataError::new_value_length_invalid(
           ValueRepresentation::OtherByteString,
           encoded_frame.len() as u64,
-          "Encoded frame length exceeds 2^32 - 1".to_string(),
+          "Encoded frame length exceeds 2^32 - 1",
         )
         .with_path(&DataSetPath::new_with_data_element(
           dictionary::PIXEL_DATA.tag,
@@ -710,6 +850,41 @@ impl P10PixelDataTranscodeTransform {
       encoded_frame.push(0);
     }
 
+    let is_last_frame = frame_index + 1
+      == self.p10_pixel_data_frame_transform.get_number_of_frames();
+
+    if let Some(max_fragment_size) = self.encode_config.max_fragment_size() {
+      self
+        .fragmented_frames_buffer
+        .push(encoded_frame.into())
+        .map_err(P10PixelDataTranscodeTransformError::P10Error)?;
+
+      if !is_last_frame {
+        return Ok(vec![]);
+      }
+
+      let basic_offset_table = Self::basic_offset_table(
+        self.fragmented_frames_buffer.frame_lengths(),
+        max_fragment_size,
+      )?;
+
+      let mut tokens =
+        Self::pixel_data_sequence_start_tokens(&basic_offset_table);
+
+      self.next_fragmented_frame = Some((0, 1));
+      tokens.extend(self.fragmented_frame_tokens(max_fragment_size)?);
+
+      return Ok(tokens);
+    }
+
+    let mut tokens = vec![];
+
+    // On the first frame, emit tokens for the start of the pixel data sequence
+    // as well as an empty basic offset table
+    if frame_index == 0 {
+      tokens.extend(Self::pixel_data_sequence_start_tokens(&[]));
+    }
+
     tokens.push(P10Token::PixelDataItem {
       index: frame_index,
       length: encoded_frame.len() as u32,
@@ -723,9 +898,7 @@ impl P10PixelDataTranscodeTransform {
     });
 
     // On the last frame, emit a sequence delimiter
-    if frame_index + 1
-      == self.p10_pixel_data_frame_transform.get_number_of_frames()
-    {
+    if is_last_frame {
       tokens.push(P10Token::SequenceDelimiter {
         tag: dictionary::PIXEL_DATA.tag,
       })
@@ -734,12 +907,160 @@ impl P10PixelDataTranscodeTransform {
     Ok(tokens)
   }
 
+  /// Returns the Basic Offset Table for encapsulated pixel data where each of
+  /// the frames with the given lengths is split into fragments no larger than
+  /// the specified maximum fragment size. The Basic Offset Table holds the
+  /// offset of the first fragment of each frame. Ref: PS3.5 A.4.
+  ///
+  fn basic_offset_table(
+    frame_lengths: impl Iterator<Item = usize>,
+    max_fragment_size: u32,
+  ) -> Result<Vec<u32>, P10PixelDataTranscodeTransformError> {
+    let max_fragment_size = max_fragment_size as usize;
+
+    // Calculate the offset of each frame's first fragment relative to the first
+    // byte of the item following the Basic Offset Table. Each fragment has an
+    // eight byte item header.
+    let mut basic_offset_table = vec![];
+    let mut offset = 0u64;
+    for frame_length in frame_lengths {
+      if offset > u64::from(u32::MAX) {
+        return Err(P10PixelDataTranscodeTransformError::DataError(
+          DataError::new_value_invalid(
+            "Basic Offset Table offset exceeds 2^32 - 1",
+          )
+          .with_path(&DataSetPath::new_with_data_element(
+            dictionary::PIXEL_DATA.tag,
+          )),
+        ));
+      }
+
+      basic_offset_table.push(offset as u32);
+
+      let fragment_count = frame_length.div_ceil(max_fragment_size).max(1);
+      offset += (frame_length + fragment_count * 8) as u64;
+    }
+
+    Ok(basic_offset_table)
+  }
+
+  /// Returns the DICOM P10 tokens for the next batch of buffered frames, with
+  /// each frame split into fragments no larger than the specified maximum
+  /// fragment size. Frames are added to the batch until its size reaches the
+  /// frame buffer memory limit. The sequence delimiter is emitted after the
+  /// last frame.
+  ///
+  fn fragmented_frame_tokens(
+    &mut self,
+    max_fragment_size: u32,
+  ) -> Result<Vec<P10Token>, P10PixelDataTranscodeTransformError> {
+    let Some((mut frame_index, mut item_index)) = self.next_fragmented_frame
+    else {
+      return Ok(vec![]);
+    };
+
+    let max_fragment_size = max_fragment_size as usize;
+    let memory_limit = self.encode_config.frame_buffer_memory_limit();
+
+    let mut tokens = vec![];
+    let mut batch_size = 0;
+
+    while frame_index < self.fragmented_frames_buffer.len()
+      && memory_limit.is_none_or(|limit| batch_size == 0 || batch_size < limit)
+    {
+      let frame = self
+        .fragmented_frames_buffer
+        .take(frame_index)
+        .map_err(P10PixelDataTranscodeTransformError::P10Error)?;
+
+      let mut start = 0;
+
+      loop {
+        let end = (start + max_fragment_size).min(frame.len());
+
+        tokens.push(P10Token::PixelDataItem {
+          index: item_index,
+          length: (end - start) as u32,
+        });
+
+        tokens.push(P10Token::DataElementValueBytes {
+          tag: dictionary::ITEM.tag,
+          vr: ValueRepresentation::OtherByteString,
+          data: frame.slice(start, end),
+          bytes_remaining: 0,
+        });
+
+        item_index += 1;
+        start = end;
+
+        if start >= frame.len() {
+          break;
+        }
+      }
+
+      batch_size += frame.len();
+      frame_index += 1;
+    }
+
+    if frame_index < self.fragmented_frames_buffer.len() {
+      self.next_fragmented_frame = Some((frame_index, item_index));
+    } else {
+      self.next_fragmented_frame = None;
+      self.fragmented_frames_buffer.clear();
+
+      tokens.push(P10Token::SequenceDelimiter {
+        tag: dictionary::PIXEL_DATA.tag,
+      });
+    }
+
+    Ok(tokens)
+  }
+
+  /// Returns the DICOM P10 tokens for the start of an encapsulated pixel data
+  /// sequence, including the Basic Offset Table item with the given offsets.
+  ///
+  fn pixel_data_sequence_start_tokens(
+    basic_offset_table: &[u32],
+  ) -> Vec<P10Token> {
+    let basic_offset_table: Vec<u8> = basic_offset_table
+      .iter()
+      .flat_map(|offset| offset.to_le_bytes())
+      .collect();
+
+    vec![
+      P10Token::SequenceStart {
+        tag: dictionary::PIXEL_DATA.tag,
+        vr: ValueRepresentation::OtherByteString,
+        path: DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+      },
+      P10Token::PixelDataItem {
+        index: 0,
+        length: basic_offset_table.len() as u32,
+      },
+      P10Token::DataElementValueBytes {
+        tag: dictionary::ITEM.tag,
+        vr: ValueRepresentation::OtherByteString,
+        data: basic_offset_table.into(),
+        bytes_remaining: 0,
+      },
+    ]
+  }
+
   /// If the output transfer is lossy, returns an insert transform that sets
-  /// '(0028,2110) Lossy Image Compression'.
+  /// '(0028,2110) Lossy Image Compression'. JPEG-LS Lossy (Near-Lossless) with
+  /// an explicit NEAR parameter of zero is lossless, and for this case `None`
+  /// is returned.
   ///
   fn lossy_image_compression_insert_transform(
     output_transfer_syntax: &'static TransferSyntax,
+    encode_config: &PixelDataEncodeConfig,
   ) -> Option<P10InsertTransform> {
+    if output_transfer_syntax == &transfer_syntax::JPEG_LS_LOSSY_NEAR_LOSSLESS
+      && encode_config.jpeg_ls_near() == Some(0)
+    {
+      return None;
+    }
+
     let lossy_output_transfer_syntaxes = [
       &transfer_syntax::JPEG_BASELINE_8BIT,
       &transfer_syntax::JPEG_EXTENDED_12BIT,
@@ -773,7 +1094,16 @@ pub enum P10PixelDataTranscodeTransformError {
   P10Error(P10Error),
   PixelDataDecodeError(PixelDataDecodeError),
   PixelDataEncodeError(PixelDataEncodeError),
-  NotSupported { details: String },
+  NotSupported {
+    details: String,
+  },
+
+  /// Wraps another error with an [`ErrorContext`] that describes where it
+  /// occurred. See [`ErrorContextChain`].
+  WithContext {
+    error: Box<P10PixelDataTranscodeTransformError>,
+    context: ErrorContext,
+  },
 }
 
 impl core::fmt::Display for P10PixelDataTranscodeTransformError {
@@ -786,6 +1116,7 @@ impl core::fmt::Display for P10PixelDataTranscodeTransformError {
       Self::NotSupported { details } => {
         write!(f, "Transcode not supported, details: {details}")
       }
+      Self::WithContext { error, context } => write!(f, "{error} ({context})"),
     }
   }
 }
@@ -804,6 +1135,46 @@ impl DcmfxError for P10PixelDataTranscodeTransformError {
           format!("  Details: {}", details),
         ]
       }
+      Self::WithContext { error, context } => {
+        let mut lines = error.to_lines(task_description);
+        context.append_to_lines(&mut lines);
+        lines
+      }
+    }
+  }
+}
+
+impl ErrorContextChain for P10PixelDataTranscodeTransformError {
+  fn with_context(self, context: ErrorContext) -> Self {
+    match self {
+      Self::WithContext {
+        error,
+        context: existing_context,
+      } => Self::WithContext {
+        error,
+        context: existing_context.merge(context),
+      },
+
+      error if context.is_empty() => error,
+
+      error => Self::WithContext {
+        error: Box::new(error),
+        context,
+      },
+    }
+  }
+
+  fn context(&self) -> Option<&ErrorContext> {
+    match self {
+      Self::WithContext { context, .. } => Some(context),
+      _ => None,
+    }
+  }
+
+  fn without_context(&self) -> &Self {
+    match self {
+      Self::WithContext { error, .. } => error,
+      _ => self,
     }
   }
 }
@@ -0,0 +1,153 @@
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+
+use dcmfx_core::{DataError, DcmfxError};
+use dcmfx_p10::{
+  P10CustomTypeTransform, P10CustomTypeTransformError, P10Error, P10Token,
+};
+
+use crate::{
+  P10PixelDataFrameTransform, P10PixelDataFrameTransformError,
+  PixelDataDecodeError, PixelDataRenderer,
+  iods::segmentation_module::{SegmentMask, SegmentationModule},
+};
+
+/// Transforms a stream of DICOM P10 tokens for a Segmentation instance into
+/// the masks for a set of requested segments.
+///
+/// Frames of pixel data are read one at a time as they stream through, and
+/// only frames that hold one of the requested segments are decoded into a
+/// mask. All other frames are discarded without being decoded, so the masks of
+/// segments that aren't needed are never built.
+///
+pub struct P10SegmentMaskTransform {
+  segment_numbers: Vec<u16>,
+  segmentation_module: P10CustomTypeTransform<SegmentationModule>,
+  renderer: P10CustomTypeTransform<PixelDataRenderer>,
+  pixel_data_frame_transform: P10PixelDataFrameTransform,
+}
+
+/// An error that occurred in the process of extracting segment masks from a
+/// stream of DICOM P10 tokens.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum P10SegmentMaskTransformError {
+  /// An error that occurred when adding a P10 token. This can happen when the
+  /// stream of DICOM P10 tokens is invalid.
+  P10Error(P10Error),
+
+  /// An error that occurred when reading the Segmentation Module or the pixel
+  /// data renderer from the stream of DICOM P10 tokens.
+  DataError(DataError),
+
+  /// An error that occurred when decoding a frame of segmentation pixel data.
+  PixelDataDecodeError(PixelDataDecodeError),
+}
+
+impl core::fmt::Display for P10SegmentMaskTransformError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::P10Error(e) => e.fmt(f),
+      Self::DataError(e) => e.fmt(f),
+      Self::PixelDataDecodeError(e) => e.fmt(f),
+    }
+  }
+}
+
+impl DcmfxError for P10SegmentMaskTransformError {
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::P10Error(e) => e.to_lines(task_description),
+      Self::DataError(e) => e.to_lines(task_description),
+      Self::PixelDataDecodeError(e) => e.to_lines(task_description),
+    }
+  }
+}
+
+impl From<P10CustomTypeTransformError> for P10SegmentMaskTransformError {
+  fn from(error: P10CustomTypeTransformError) -> Self {
+    match error {
+      P10CustomTypeTransformError::P10Error(e) => Self::P10Error(e),
+      P10CustomTypeTransformError::DataError(e) => Self::DataError(e),
+    }
+  }
+}
+
+impl From<P10PixelDataFrameTransformError> for P10SegmentMaskTransformError {
+  fn from(error: P10PixelDataFrameTransformError) -> Self {
+    match error {
+      P10PixelDataFrameTransformError::P10Error(e) => Self::P10Error(e),
+      P10PixelDataFrameTransformError::DataError(e) => Self::DataError(e),
+    }
+  }
+}
+
+impl P10SegmentMaskTransform {
+  /// Creates a new transform that extracts the masks for the segments with the
+  /// given segment numbers.
+  ///
+  pub fn new(segment_numbers: &[u16]) -> Self {
+    Self {
+      segment_numbers: segment_numbers.to_vec(),
+      segmentation_module: P10CustomTypeTransform::new_for_iod_module(),
+      renderer: P10CustomTypeTransform::new_for_iod_module(),
+      pixel_data_frame_transform: P10PixelDataFrameTransform::new(),
+    }
+  }
+
+  /// Adds the next token in the DICOM P10 token stream, returning the masks
+  /// for any frames of the requested segments that are now available.
+  ///
+  pub fn add_token(
+    &mut self,
+    token: &P10Token,
+  ) -> Result<Vec<SegmentMask>, P10SegmentMaskTransformError> {
+    self.segmentation_module.add_token(token)?;
+    self.renderer.add_token(token)?;
+
+    let frames = self.pixel_data_frame_transform.add_token(token)?;
+    if frames.is_empty() {
+      return Ok(vec![]);
+    }
+
+    // The Segmentation Module and Image Pixel Module precede the pixel data,
+    // so both are available once frames are being emitted
+    let (Some(segmentation_module), Some(renderer)) = (
+      self.segmentation_module.get_output(),
+      self.renderer.get_output(),
+    ) else {
+      return Err(P10SegmentMaskTransformError::DataError(
+        DataError::new_value_invalid(
+          "Segmentation Module and Image Pixel Module must precede the pixel \
+           data",
+        ),
+      ));
+    };
+
+    let mut masks = vec![];
+
+    for mut frame in frames {
+      let frame_index = frame.index().unwrap();
+
+      let is_requested = segmentation_module
+        .frame_segment_numbers
+        .get(frame_index)
+        .is_some_and(|number| self.segment_numbers.contains(number));
+      if !is_requested {
+        continue;
+      }
+
+      let image = renderer
+        .decode_monochrome_frame(&mut frame)
+        .map_err(P10SegmentMaskTransformError::PixelDataDecodeError)?;
+
+      masks.push(
+        segmentation_module
+          .segment_mask(frame_index, &image)
+          .map_err(P10SegmentMaskTransformError::DataError)?,
+      );
+    }
+
+    Ok(masks)
+  }
+}
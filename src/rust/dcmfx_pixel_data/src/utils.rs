@@ -1,3 +1,4 @@
+use core::hash::Hasher;
 use core::ops::{Add, Div};
 
 /// Unsigned integer division that rounds to the nearest integer instead of
@@ -10,3 +11,13 @@ where
 {
   (numer + (denom / T::from(2))) / denom
 }
+
+/// Hashes an `f32` by its bit pattern. Negative zero is hashed as positive zero
+/// so that values that compare equal also hash equally.
+///
+#[inline]
+pub fn hash_f32<H: Hasher>(value: f32, state: &mut H) {
+  let value = if value == 0.0 { 0.0 } else { value };
+
+  state.write_u32(value.to_bits());
+}
@@ -0,0 +1,397 @@
+//! Remuxes the video streams stored in pixel data that uses the MPEG-2,
+//! MPEG-4 AVC/H.264, and HEVC/H.265 transfer syntaxes into playable MP4 files.
+//!
+//! The video stream is copied into the MP4 file by running FFmpeg in a child
+//! process with `-c copy`, so it isn't decoded or re-encoded. This requires the
+//! `ffmpeg` feature, and the `ffmpeg` binary must be on the path.
+//!
+//! The frame rate of the MP4 file is taken from the frame timing of the data
+//! set, i.e. the Cine Module or the Frame Content functional group. See
+//! [`FrameTiming`] for details.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+#[cfg(feature = "ffmpeg")]
+use std::{
+  io::{Read, Write},
+  process::{Command, Stdio},
+};
+
+use dcmfx_core::{DcmfxError, TransferSyntax, transfer_syntax};
+
+use crate::FrameTiming;
+
+/// The frame rate used when frame timing isn't available.
+///
+#[cfg(feature = "ffmpeg")]
+const DEFAULT_FRAME_RATE: f64 = 30.0;
+
+/// The video codecs that can be remuxed into an MP4 file.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VideoCodec {
+  /// MPEG-2 video.
+  Mpeg2,
+
+  /// MPEG-4 AVC/H.264 video.
+  H264,
+
+  /// HEVC/H.265 video.
+  Hevc,
+}
+
+impl VideoCodec {
+  /// Returns the video codec used by the given transfer syntax, or `None` if
+  /// it isn't one of the video transfer syntaxes.
+  ///
+  pub fn from_transfer_syntax(ts: &TransferSyntax) -> Option<Self> {
+    use transfer_syntax::*;
+
+    match ts {
+      ts if ts == &MPEG2_MAIN_PROFILE_MAIN_LEVEL
+        || ts == &FRAGMENTABLE_MPEG2_MAIN_PROFILE_MAIN_LEVEL
+        || ts == &MPEG2_MAIN_PROFILE_HIGH_LEVEL
+        || ts == &FRAGMENTABLE_MPEG2_MAIN_PROFILE_HIGH_LEVEL =>
+      {
+        Some(Self::Mpeg2)
+      }
+
+      ts if ts == &MPEG4_AVC_H264_HIGH_PROFILE
+        || ts == &FRAGMENTABLE_MPEG4_AVC_H264_HIGH_PROFILE
+        || ts == &MPEG4_AVC_H264_BD_COMPATIBLE_HIGH_PROFILE
+        || ts == &FRAGMENTABLE_MPEG4_AVC_H264_BD_COMPATIBLE_HIGH_PROFILE
+        || ts == &MPEG4_AVC_H264_HIGH_PROFILE_FOR_2D_VIDEO
+        || ts == &FRAGMENTABLE_MPEG4_AVC_H264_HIGH_PROFILE_FOR_2D_VIDEO
+        || ts == &MPEG4_AVC_H264_HIGH_PROFILE_FOR_3D_VIDEO
+        || ts == &FRAGMENTABLE_MPEG4_AVC_H264_HIGH_PROFILE_FOR_3D_VIDEO
+        || ts == &MPEG4_AVC_H264_STEREO_HIGH_PROFILE
+        || ts == &FRAGMENTABLE_MPEG4_AVC_H264_STEREO_HIGH_PROFILE =>
+      {
+        Some(Self::H264)
+      }
+
+      ts if ts == &HEVC_H265_MAIN_PROFILE
+        || ts == &HEVC_H265_MAIN_10_PROFILE =>
+      {
+        Some(Self::Hevc)
+      }
+
+      _ => None,
+    }
+  }
+
+  /// Returns the name of the video codec as a human-readable string.
+  ///
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Mpeg2 => "MPEG-2",
+      Self::H264 => "H.264",
+      Self::Hevc => "HEVC",
+    }
+  }
+}
+
+/// An error that occurred when remuxing a video stream into an MP4 file.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mp4RemuxError {
+  /// The video stream is invalid, e.g. it is truncated or contains no
+  /// pictures.
+  DataInvalid { details: String },
+
+  /// The video stream uses a feature that isn't supported for remuxing.
+  NotSupported { details: String },
+}
+
+impl Mp4RemuxError {
+  /// Returns the name of the MP4 remux error as a human-readable string.
+  ///
+  pub fn name(&self) -> String {
+    match self {
+      Self::DataInvalid { .. } => "Data invalid".to_string(),
+      Self::NotSupported { .. } => "Not supported".to_string(),
+    }
+  }
+
+  #[cfg(feature = "ffmpeg")]
+  fn data_invalid(details: &str) -> Self {
+    Self::DataInvalid {
+      details: details.to_string(),
+    }
+  }
+
+  fn not_supported(details: &str) -> Self {
+    Self::NotSupported {
+      details: details.to_string(),
+    }
+  }
+}
+
+impl core::fmt::Display for Mp4RemuxError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::DataInvalid { details } => {
+        write!(f, "Data invalid, details: '{details}'")
+      }
+      Self::NotSupported { details } => {
+        write!(f, "Not supported, details: '{details}'")
+      }
+    }
+  }
+}
+
+impl DcmfxError for Mp4RemuxError {
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    let mut lines = vec![
+      format!("MP4 remux error {task_description}"),
+      "".to_string(),
+      format!("  Error: {}", self.name()),
+    ];
+
+    match self {
+      Self::DataInvalid { details } | Self::NotSupported { details } => {
+        lines.push(format!("  Details: {details}"));
+      }
+    }
+
+    lines
+  }
+}
+
+/// Remuxes a video stream into an MP4 file that is returned as bytes. The
+/// video stream is the data of the single frame that video pixel data is read
+/// into.
+///
+/// The MP4 file has a constant frame rate that is the average frame rate of
+/// the given frame timing. If frame timing isn't available then the frame rate
+/// is 30 frames per second.
+///
+#[cfg(feature = "ffmpeg")]
+pub fn remux_to_mp4(
+  data: &[u8],
+  codec: VideoCodec,
+  frame_timing: Option<&FrameTiming>,
+) -> Result<Vec<u8>, Mp4RemuxError> {
+  let output_file = tempfile::NamedTempFile::new().map_err(|e| {
+    Mp4RemuxError::data_invalid(&format!("Creating output file failed: {e}"))
+  })?;
+
+  let frame_rate = frame_rate(frame_timing).to_string();
+
+  // The input frame rate overrides any timestamps in the video stream, which
+  // elementary streams don't have
+  let mut child = Command::new(ffmpeg_binary())
+    .args(["-v", "error", "-r", &frame_rate, "-i", "pipe:0"])
+    .args(["-map", "0:v:0", "-c", "copy", "-f", "mp4"])
+    .args(["-movflags", "+faststart", "-y"])
+    .arg(output_file.path())
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|_| {
+      Mp4RemuxError::not_supported(&format!(
+        "Remuxing {} video requires '{}' to be available",
+        codec.name(),
+        ffmpeg_binary()
+      ))
+    })?;
+
+  let (Some(mut stdin), Some(mut stderr)) =
+    (child.stdin.take(), child.stderr.take())
+  else {
+    return Err(Mp4RemuxError::not_supported("FFmpeg could not be started"));
+  };
+
+  // The input is written on a separate thread so that FFmpeg is never blocked
+  // on a full pipe
+  let errors = std::thread::scope(|scope| {
+    scope.spawn(move || {
+      let _ = stdin.write_all(data);
+    });
+
+    let mut errors = String::new();
+    let _ = stderr.read_to_string(&mut errors);
+    errors
+  });
+
+  let status = child.wait().map_err(|e| {
+    Mp4RemuxError::not_supported(&format!("FFmpeg did not run: {e}"))
+  })?;
+
+  if !status.success() {
+    return Err(Mp4RemuxError::data_invalid(&format!(
+      "FFmpeg remux of {} video failed with '{}'",
+      codec.name(),
+      errors.lines().last().unwrap_or_default()
+    )));
+  }
+
+  let mp4 = std::fs::read(output_file.path()).map_err(|e| {
+    Mp4RemuxError::data_invalid(&format!("Reading output file failed: {e}"))
+  })?;
+
+  if mp4.is_empty() {
+    return Err(Mp4RemuxError::data_invalid(&format!(
+      "No {} pictures found in the video stream",
+      codec.name()
+    )));
+  }
+
+  Ok(mp4)
+}
+
+/// Remuxes a video stream into an MP4 file. This always returns an error
+/// because remuxing requires the `ffmpeg` feature.
+///
+#[cfg(not(feature = "ffmpeg"))]
+pub fn remux_to_mp4(
+  _data: &[u8],
+  codec: VideoCodec,
+  _frame_timing: Option<&FrameTiming>,
+) -> Result<Vec<u8>, Mp4RemuxError> {
+  Err(Mp4RemuxError::not_supported(&format!(
+    "Remuxing {} video requires the 'ffmpeg' feature",
+    codec.name()
+  )))
+}
+
+/// Returns the average frame rate of the given frame timing, which is the
+/// number of frames divided by the time at which the last frame ends.
+///
+#[cfg(feature = "ffmpeg")]
+fn frame_rate(frame_timing: Option<&FrameTiming>) -> f64 {
+  frame_timing
+    .and_then(|frame_timing| {
+      let number_of_frames = frame_timing.multiframe_module.number_of_frames?;
+      let last_frame = frame_timing.frame_interval(number_of_frames - 1)?;
+
+      let end = last_frame.end.as_secs_f64();
+      (end > 0.0).then(|| number_of_frames as f64 / end)
+    })
+    .unwrap_or(DEFAULT_FRAME_RATE)
+}
+
+#[cfg(feature = "ffmpeg")]
+fn ffmpeg_binary() -> &'static str {
+  #[cfg(not(windows))]
+  return "ffmpeg";
+
+  #[cfg(windows)]
+  return "ffmpeg.exe";
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_transfer_syntax_test() {
+    assert_eq!(
+      VideoCodec::from_transfer_syntax(
+        &transfer_syntax::MPEG2_MAIN_PROFILE_HIGH_LEVEL
+      ),
+      Some(VideoCodec::Mpeg2)
+    );
+
+    assert_eq!(
+      VideoCodec::from_transfer_syntax(
+        &transfer_syntax::MPEG4_AVC_H264_HIGH_PROFILE
+      ),
+      Some(VideoCodec::H264)
+    );
+
+    assert_eq!(
+      VideoCodec::from_transfer_syntax(
+        &transfer_syntax::HEVC_H265_MAIN_10_PROFILE
+      ),
+      Some(VideoCodec::Hevc)
+    );
+
+    assert_eq!(
+      VideoCodec::from_transfer_syntax(&transfer_syntax::JPEG_BASELINE_8BIT),
+      None
+    );
+  }
+
+  #[cfg(feature = "ffmpeg")]
+  #[test]
+  fn frame_rate_test() {
+    use dcmfx_core::{DataSet, IodModule, dictionary};
+
+    assert_eq!(frame_rate(None), DEFAULT_FRAME_RATE);
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[4])
+      .unwrap();
+    data_set
+      .insert_float_value(&dictionary::FRAME_TIME, &[40.0])
+      .unwrap();
+
+    let frame_timing = FrameTiming::from_data_set(&data_set).unwrap();
+    assert_eq!(frame_rate(Some(&frame_timing)), 25.0);
+
+    data_set.delete(dictionary::FRAME_TIME.tag);
+    data_set
+      .insert_float_value(
+        &dictionary::FRAME_TIME_VECTOR,
+        &[0.0, 10.0, 40.0, 25.0],
+      )
+      .unwrap();
+    data_set
+      .insert_attribute_tag_value(
+        &dictionary::FRAME_INCREMENT_POINTER,
+        &[dictionary::FRAME_TIME_VECTOR.tag],
+      )
+      .unwrap();
+
+    let frame_timing = FrameTiming::from_data_set(&data_set).unwrap();
+    assert_eq!(frame_rate(Some(&frame_timing)), 40.0);
+  }
+
+  #[cfg(feature = "ffmpeg")]
+  #[test]
+  fn remux_to_mp4_test() {
+    // Encode an MPEG-2 video of five pictures. This test only runs when FFmpeg
+    // is available.
+    let Ok(output) = Command::new(ffmpeg_binary())
+      .args(["-v", "error", "-f", "lavfi", "-i", "testsrc=s=32x32:r=25"])
+      .args(["-frames:v", "5", "-c:v", "mpeg2video", "-f", "mpeg2video"])
+      .arg("pipe:1")
+      .output()
+    else {
+      return;
+    };
+    assert!(output.status.success());
+
+    let mp4 = remux_to_mp4(&output.stdout, VideoCodec::Mpeg2, None).unwrap();
+    assert_eq!(&mp4[4..8], b"ftyp");
+
+    // Probe the MP4 file to check its frame count and duration
+    let mut mp4_file = tempfile::NamedTempFile::new().unwrap();
+    mp4_file.write_all(&mp4).unwrap();
+    let probe = Command::new(ffmpeg_binary())
+      .args(["-v", "error", "-i"])
+      .arg(mp4_file.path())
+      .args(["-c", "copy", "-f", "framemd5", "pipe:1"])
+      .output()
+      .unwrap();
+    assert!(probe.status.success());
+
+    let packets = String::from_utf8(probe.stdout).unwrap();
+    let packets: Vec<_> = packets
+      .lines()
+      .filter(|line| !line.starts_with('#'))
+      .collect();
+    assert_eq!(packets.len(), 5);
+
+    assert!(remux_to_mp4(&[0, 1, 2, 3], VideoCodec::Mpeg2, None).is_err());
+  }
+}
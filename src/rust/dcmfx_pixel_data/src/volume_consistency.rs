@@ -0,0 +1,691 @@
+//! Checks that a set of image slices, e.g. the instances in a CT or MR series,
+//! are spatially consistent with each other so they can be assembled into a
+//! volume.
+//!
+//! Rather than failing on the first problem found, all problems are collected
+//! into a [`VolumeConsistencyReport`] that describes mixed Frame of Reference
+//! UIDs, mixed orientations, gantry tilt, duplicate slices, and non-uniform
+//! slice spacing. When requested, the report also describes how to resample the
+//! slices to a uniform spacing.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use crate::iods::ImagePlaneModule;
+
+/// A single slice that is to be assembled into a volume.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VolumeSlice<'a> {
+  /// The value of the *'(0020,0052) Frame of Reference UID'* data element for
+  /// the slice, if present.
+  ///
+  pub frame_of_reference_uid: Option<&'a str>,
+
+  /// The Image Plane Module for the slice, which specifies its position and
+  /// orientation.
+  ///
+  pub image_plane_module: &'a ImagePlaneModule,
+}
+
+/// Configuration used when checking the consistency of a volume.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VolumeConsistencyConfig {
+  /// The maximum angle in degrees that a slice's orientation can differ from
+  /// that of the first slice. Defaults to 0.5 degrees.
+  ///
+  pub orientation_tolerance: f32,
+
+  /// The maximum angle in degrees between the slice normal and the direction
+  /// the slices are stacked in before the volume is reported as having gantry
+  /// tilt. Defaults to 0.5 degrees.
+  ///
+  pub gantry_tilt_tolerance: f32,
+
+  /// The maximum amount that the spacing between adjacent slices can differ
+  /// from the nominal slice spacing, as a fraction of the nominal slice
+  /// spacing. Slices closer together than this fraction of the nominal slice
+  /// spacing are reported as duplicates. Defaults to 0.01, i.e. 1%.
+  ///
+  pub slice_spacing_tolerance: f32,
+
+  /// Whether to describe how to resample the slices. Defaults to
+  /// [`VolumeResampling::None`].
+  ///
+  pub resampling: VolumeResampling,
+}
+
+impl Default for VolumeConsistencyConfig {
+  fn default() -> Self {
+    Self {
+      orientation_tolerance: 0.5,
+      gantry_tilt_tolerance: 0.5,
+      slice_spacing_tolerance: 0.01,
+      resampling: VolumeResampling::None,
+    }
+  }
+}
+
+/// Specifies whether slices should be resampled when assembling a volume.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VolumeResampling {
+  /// Slices are not resampled.
+  None,
+
+  /// Slices are resampled so they have a uniform spacing. If no spacing is
+  /// specified then the nominal slice spacing is used.
+  UniformSpacing { spacing: Option<f32> },
+}
+
+/// A problem found when checking the consistency of a volume. Slice indices
+/// refer to the slices in the order they were passed in.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum VolumeConsistencyIssue {
+  /// There are fewer than two slices, so no volume can be assembled.
+  TooFewSlices,
+
+  /// Some slices don't have a Frame of Reference UID.
+  MissingFrameOfReferenceUid { slice_indices: Vec<usize> },
+
+  /// The slices don't all have the same Frame of Reference UID, which means
+  /// their positions can't be compared.
+  MixedFrameOfReferenceUids {
+    frame_of_reference_uids: Vec<String>,
+  },
+
+  /// The orientation of some slices differs from that of the first slice by
+  /// more than the orientation tolerance. The largest difference found is
+  /// given in degrees.
+  MixedOrientations {
+    slice_indices: Vec<usize>,
+    max_angle: f32,
+  },
+
+  /// The slices are stacked in a direction that isn't perpendicular to their
+  /// plane, as happens when a CT gantry is tilted. The angle is in degrees.
+  GantryTilt { angle: f32 },
+
+  /// Pairs of slices are at the same position.
+  DuplicateSlicePositions { slice_indices: Vec<(usize, usize)> },
+
+  /// The spacing between adjacent slices varies by more than the slice spacing
+  /// tolerance, which commonly indicates missing slices.
+  NonUniformSliceSpacing { min_spacing: f32, max_spacing: f32 },
+}
+
+impl core::fmt::Display for VolumeConsistencyIssue {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::TooFewSlices => write!(f, "Fewer than two slices"),
+
+      Self::MissingFrameOfReferenceUid { slice_indices } => write!(
+        f,
+        "{} slice(s) have no Frame of Reference UID",
+        slice_indices.len()
+      ),
+
+      Self::MixedFrameOfReferenceUids {
+        frame_of_reference_uids,
+      } => write!(
+        f,
+        "Mixed Frame of Reference UIDs: {}",
+        frame_of_reference_uids.join(", ")
+      ),
+
+      Self::MixedOrientations {
+        slice_indices,
+        max_angle,
+      } => write!(
+        f,
+        "{} slice(s) have a different orientation, by up to {max_angle:.2} \
+         degrees",
+        slice_indices.len()
+      ),
+
+      Self::GantryTilt { angle } => {
+        write!(f, "Gantry tilt of {angle:.2} degrees")
+      }
+
+      Self::DuplicateSlicePositions { slice_indices } => write!(
+        f,
+        "{} pair(s) of slices are at the same position",
+        slice_indices.len()
+      ),
+
+      Self::NonUniformSliceSpacing {
+        min_spacing,
+        max_spacing,
+      } => write!(
+        f,
+        "Non-uniform slice spacing between {min_spacing:.3} and \
+         {max_spacing:.3} mm"
+      ),
+    }
+  }
+}
+
+/// A slice of a resampled volume, which is made by linearly interpolating
+/// between two of the input slices.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResampledSlice {
+  /// The position of the resampled slice along the slice normal, in mm.
+  ///
+  pub position: f32,
+
+  /// The index of the input slice at or before this position.
+  ///
+  pub lower_slice: usize,
+
+  /// The index of the input slice at or after this position.
+  ///
+  pub upper_slice: usize,
+
+  /// The weight to give the upper slice when interpolating, in the range 0-1.
+  /// The lower slice has a weight of `1 - weight`.
+  ///
+  pub weight: f32,
+}
+
+/// The result of checking the consistency of a set of slices.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct VolumeConsistencyReport {
+  /// The indices of the slices sorted by their position along the slice
+  /// normal.
+  ///
+  pub slice_order: Vec<usize>,
+
+  /// The position of each slice along the slice normal in mm, in the order
+  /// given by `slice_order`.
+  ///
+  pub slice_positions: Vec<f32>,
+
+  /// The nominal spacing between slices in mm, which is the median spacing
+  /// between adjacent slices. This is `None` if there are fewer than two
+  /// slices.
+  ///
+  pub slice_spacing: Option<f32>,
+
+  /// The angle in degrees between the slice normal and the direction the
+  /// slices are stacked in.
+  ///
+  pub gantry_tilt: f32,
+
+  /// The problems found with the slices. If this is empty then the slices form
+  /// a consistent volume.
+  ///
+  pub issues: Vec<VolumeConsistencyIssue>,
+
+  /// The slices of the volume after resampling, if resampling was requested.
+  /// This is `None` if the slices have issues other than non-uniform slice
+  /// spacing, as such slices can't be reliably resampled.
+  ///
+  pub resampled_slices: Option<Vec<ResampledSlice>>,
+}
+
+impl VolumeConsistencyReport {
+  /// Returns whether the slices form a consistent volume.
+  ///
+  pub fn is_consistent(&self) -> bool {
+    self.issues.is_empty()
+  }
+
+  /// Returns a line of text describing each issue found.
+  ///
+  pub fn to_lines(&self) -> Vec<String> {
+    self.issues.iter().map(|issue| issue.to_string()).collect()
+  }
+}
+
+/// Checks whether the given slices can be assembled into a volume, and returns
+/// a report describing their spatial layout along with any problems found.
+///
+pub fn check_volume_consistency(
+  slices: &[VolumeSlice],
+  config: &VolumeConsistencyConfig,
+) -> VolumeConsistencyReport {
+  let mut report = VolumeConsistencyReport {
+    slice_order: (0..slices.len()).collect(),
+    slice_positions: vec![],
+    slice_spacing: None,
+    gantry_tilt: 0.0,
+    issues: vec![],
+    resampled_slices: None,
+  };
+
+  let Some(first_slice) = slices.first() else {
+    report.issues.push(VolumeConsistencyIssue::TooFewSlices);
+    return report;
+  };
+
+  check_frame_of_reference_uids(slices, &mut report);
+
+  // Check every slice has the same orientation as the first slice
+  let (row, column) = orientation_vectors(
+    first_slice.image_plane_module.image_orientation_patient,
+  );
+  let normal = normalize(cross(row, column));
+
+  let mut mismatched_slices = vec![];
+  let mut max_angle = 0.0f32;
+  for (i, slice) in slices.iter().enumerate().skip(1) {
+    let (slice_row, slice_column) =
+      orientation_vectors(slice.image_plane_module.image_orientation_patient);
+
+    let angle =
+      angle_between(row, slice_row).max(angle_between(column, slice_column));
+
+    if angle > config.orientation_tolerance {
+      mismatched_slices.push(i);
+      max_angle = max_angle.max(angle);
+    }
+  }
+
+  if !mismatched_slices.is_empty() {
+    report
+      .issues
+      .push(VolumeConsistencyIssue::MixedOrientations {
+        slice_indices: mismatched_slices,
+        max_angle,
+      });
+  }
+
+  // Sort the slices by their position along the slice normal
+  let positions: Vec<f32> = slices
+    .iter()
+    .map(|slice| dot(slice.image_plane_module.image_position_patient, normal))
+    .collect();
+
+  report
+    .slice_order
+    .sort_by(|a, b| positions[*a].total_cmp(&positions[*b]));
+  report.slice_positions =
+    report.slice_order.iter().map(|i| positions[*i]).collect();
+
+  if slices.len() < 2 {
+    report.issues.push(VolumeConsistencyIssue::TooFewSlices);
+    return report;
+  }
+
+  // Check the direction the slices are stacked in is along the slice normal
+  let first_position = slices[report.slice_order[0]]
+    .image_plane_module
+    .image_position_patient;
+  let last_position = slices[*report.slice_order.last().unwrap()]
+    .image_plane_module
+    .image_position_patient;
+  let stack_direction = subtract(last_position, first_position);
+
+  if length(stack_direction) > 0.0 {
+    report.gantry_tilt =
+      angle_between(normalize(stack_direction), normal).min(angle_between(
+        normalize(stack_direction),
+        [-normal[0], -normal[1], -normal[2]],
+      ));
+
+    if report.gantry_tilt > config.gantry_tilt_tolerance {
+      report.issues.push(VolumeConsistencyIssue::GantryTilt {
+        angle: report.gantry_tilt,
+      });
+    }
+  }
+
+  check_slice_spacing(config, &mut report);
+
+  if let VolumeResampling::UniformSpacing { spacing } = config.resampling {
+    let can_resample = report.issues.iter().all(|issue| {
+      matches!(issue, VolumeConsistencyIssue::NonUniformSliceSpacing { .. })
+    });
+
+    if can_resample
+      && let Some(spacing) = spacing.or(report.slice_spacing)
+      && spacing > 0.0
+    {
+      report.resampled_slices = Some(resample(&report, spacing));
+    }
+  }
+
+  report
+}
+
+/// Checks that all slices have the same Frame of Reference UID.
+///
+fn check_frame_of_reference_uids(
+  slices: &[VolumeSlice],
+  report: &mut VolumeConsistencyReport,
+) {
+  let missing_slices: Vec<usize> = slices
+    .iter()
+    .enumerate()
+    .filter(|(_, slice)| slice.frame_of_reference_uid.is_none())
+    .map(|(i, _)| i)
+    .collect();
+
+  if !missing_slices.is_empty() {
+    report
+      .issues
+      .push(VolumeConsistencyIssue::MissingFrameOfReferenceUid {
+        slice_indices: missing_slices,
+      });
+  }
+
+  let mut frame_of_reference_uids: Vec<String> = vec![];
+  for uid in slices.iter().filter_map(|s| s.frame_of_reference_uid) {
+    if !frame_of_reference_uids.iter().any(|u| u == uid) {
+      frame_of_reference_uids.push(uid.to_string());
+    }
+  }
+
+  if frame_of_reference_uids.len() > 1 {
+    report
+      .issues
+      .push(VolumeConsistencyIssue::MixedFrameOfReferenceUids {
+        frame_of_reference_uids,
+      });
+  }
+}
+
+/// Calculates the nominal slice spacing, and checks for duplicate slices and
+/// non-uniform slice spacing.
+///
+fn check_slice_spacing(
+  config: &VolumeConsistencyConfig,
+  report: &mut VolumeConsistencyReport,
+) {
+  let spacings: Vec<f32> = report
+    .slice_positions
+    .windows(2)
+    .map(|w| w[1] - w[0])
+    .collect();
+
+  let mut sorted_spacings = spacings.clone();
+  sorted_spacings.sort_by(f32::total_cmp);
+  let slice_spacing = sorted_spacings[sorted_spacings.len() / 2];
+  report.slice_spacing = Some(slice_spacing);
+
+  let tolerance = slice_spacing * config.slice_spacing_tolerance;
+
+  let duplicate_slices: Vec<(usize, usize)> = spacings
+    .iter()
+    .enumerate()
+    .filter(|(_, spacing)| **spacing <= tolerance)
+    .map(|(i, _)| (report.slice_order[i], report.slice_order[i + 1]))
+    .collect();
+
+  if !duplicate_slices.is_empty() {
+    report
+      .issues
+      .push(VolumeConsistencyIssue::DuplicateSlicePositions {
+        slice_indices: duplicate_slices,
+      });
+  }
+
+  let min_spacing = sorted_spacings[0];
+  let max_spacing = *sorted_spacings.last().unwrap();
+
+  if slice_spacing - min_spacing > tolerance
+    || max_spacing - slice_spacing > tolerance
+  {
+    report
+      .issues
+      .push(VolumeConsistencyIssue::NonUniformSliceSpacing {
+        min_spacing,
+        max_spacing,
+      });
+  }
+}
+
+/// Returns the slices of a volume resampled to the given uniform spacing. The
+/// resampled volume covers the same extent as the input slices.
+///
+fn resample(
+  report: &VolumeConsistencyReport,
+  spacing: f32,
+) -> Vec<ResampledSlice> {
+  let positions = &report.slice_positions;
+  let first_position = positions[0];
+  let extent = positions[positions.len() - 1] - first_position;
+
+  // Allow a small amount of rounding error so the last slice isn't dropped
+  let slice_count = (extent / spacing + 0.001) as usize + 1;
+
+  let mut resampled_slices = Vec::with_capacity(slice_count);
+  let mut segment = 0;
+
+  for i in 0..slice_count {
+    let position = first_position + i as f32 * spacing;
+
+    while segment + 2 < positions.len() && position > positions[segment + 1] {
+      segment += 1;
+    }
+
+    let lower_position = positions[segment];
+    let upper_position = positions[segment + 1];
+
+    let weight = ((position - lower_position)
+      / (upper_position - lower_position))
+      .clamp(0.0, 1.0);
+
+    resampled_slices.push(ResampledSlice {
+      position,
+      lower_slice: report.slice_order[segment],
+      upper_slice: report.slice_order[segment + 1],
+      weight,
+    });
+  }
+
+  resampled_slices
+}
+
+type Vector = [f32; 3];
+
+fn orientation_vectors(
+  image_orientation_patient: [f32; 6],
+) -> (Vector, Vector) {
+  let [a, b, c, d, e, f] = image_orientation_patient;
+
+  (normalize([a, b, c]), normalize([d, e, f]))
+}
+
+fn dot(a: Vector, b: Vector) -> f32 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+  [
+    a[1] * b[2] - a[2] * b[1],
+    a[2] * b[0] - a[0] * b[2],
+    a[0] * b[1] - a[1] * b[0],
+  ]
+}
+
+fn subtract(a: Vector, b: Vector) -> Vector {
+  [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn length(a: Vector) -> f32 {
+  dot(a, a).sqrt()
+}
+
+fn normalize(a: Vector) -> Vector {
+  let length = length(a);
+
+  if length == 0.0 {
+    a
+  } else {
+    [a[0] / length, a[1] / length, a[2] / length]
+  }
+}
+
+/// Returns the angle in degrees between two unit vectors.
+///
+fn angle_between(a: Vector, b: Vector) -> f32 {
+  dot(a, b).clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn image_plane_module(
+    image_orientation_patient: [f32; 6],
+    image_position_patient: [f32; 3],
+  ) -> ImagePlaneModule {
+    ImagePlaneModule {
+      pixel_spacing: [1.0, 1.0],
+      image_orientation_patient,
+      image_position_patient,
+      slice_thickness: None,
+      spacing_between_slices: None,
+      slice_location: None,
+    }
+  }
+
+  const AXIAL: [f32; 6] = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+
+  fn axial_slices(z_positions: &[f32]) -> Vec<ImagePlaneModule> {
+    z_positions
+      .iter()
+      .map(|z| image_plane_module(AXIAL, [0.0, 0.0, *z]))
+      .collect()
+  }
+
+  fn check(
+    image_plane_modules: &[ImagePlaneModule],
+    frame_of_reference_uids: &[Option<&str>],
+    config: &VolumeConsistencyConfig,
+  ) -> VolumeConsistencyReport {
+    let slices: Vec<VolumeSlice> = image_plane_modules
+      .iter()
+      .zip(frame_of_reference_uids)
+      .map(|(image_plane_module, frame_of_reference_uid)| VolumeSlice {
+        frame_of_reference_uid: *frame_of_reference_uid,
+        image_plane_module,
+      })
+      .collect();
+
+    check_volume_consistency(&slices, config)
+  }
+
+  #[test]
+  fn consistent_volume_test() {
+    let report = check(
+      &axial_slices(&[2.0, 0.0, 1.0]),
+      &[Some("1.2"); 3],
+      &VolumeConsistencyConfig::default(),
+    );
+
+    assert!(report.is_consistent());
+    assert_eq!(report.slice_order, vec![1, 2, 0]);
+    assert_eq!(report.slice_positions, vec![0.0, 1.0, 2.0]);
+    assert_eq!(report.slice_spacing, Some(1.0));
+    assert_eq!(report.resampled_slices, None);
+  }
+
+  #[test]
+  fn inconsistent_volume_test() {
+    let mut image_plane_modules = axial_slices(&[0.0, 1.0, 2.0, 2.0, 4.0]);
+    image_plane_modules[1].image_orientation_patient =
+      [1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+
+    let report = check(
+      &image_plane_modules,
+      &[Some("1.2"), Some("1.3"), None, Some("1.2"), Some("1.2")],
+      &VolumeConsistencyConfig::default(),
+    );
+
+    assert_eq!(
+      report.issues,
+      vec![
+        VolumeConsistencyIssue::MissingFrameOfReferenceUid {
+          slice_indices: vec![2]
+        },
+        VolumeConsistencyIssue::MixedFrameOfReferenceUids {
+          frame_of_reference_uids: vec!["1.2".to_string(), "1.3".to_string()]
+        },
+        VolumeConsistencyIssue::MixedOrientations {
+          slice_indices: vec![1],
+          max_angle: 90.0
+        },
+        VolumeConsistencyIssue::DuplicateSlicePositions {
+          slice_indices: vec![(2, 3)]
+        },
+        VolumeConsistencyIssue::NonUniformSliceSpacing {
+          min_spacing: 0.0,
+          max_spacing: 2.0
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn gantry_tilt_test() {
+    let image_plane_modules: Vec<ImagePlaneModule> = (0..3)
+      .map(|i| image_plane_module(AXIAL, [0.0, i as f32, i as f32]))
+      .collect();
+
+    let report = check(
+      &image_plane_modules,
+      &[Some("1.2"); 3],
+      &VolumeConsistencyConfig::default(),
+    );
+
+    assert_eq!(report.issues.len(), 1);
+    assert!((report.gantry_tilt - 45.0).abs() < 0.01);
+  }
+
+  #[test]
+  fn resampling_test() {
+    let config = VolumeConsistencyConfig {
+      resampling: VolumeResampling::UniformSpacing { spacing: None },
+      ..VolumeConsistencyConfig::default()
+    };
+
+    let report =
+      check(&axial_slices(&[0.0, 1.0, 3.0]), &[Some("1.2"); 3], &config);
+
+    assert_eq!(
+      report.issues,
+      vec![VolumeConsistencyIssue::NonUniformSliceSpacing {
+        min_spacing: 1.0,
+        max_spacing: 2.0
+      }]
+    );
+
+    assert_eq!(report.slice_spacing, Some(2.0));
+
+    assert_eq!(
+      report.resampled_slices,
+      Some(vec![
+        ResampledSlice {
+          position: 0.0,
+          lower_slice: 0,
+          upper_slice: 1,
+          weight: 0.0
+        },
+        ResampledSlice {
+          position: 2.0,
+          lower_slice: 1,
+          upper_slice: 2,
+          weight: 0.5
+        },
+      ])
+    );
+
+    // Slices with a missing Frame of Reference UID aren't resampled
+    let report = check(
+      &axial_slices(&[0.0, 1.0, 3.0]),
+      &[Some("1.2"), None, Some("1.2")],
+      &config,
+    );
+    assert_eq!(report.resampled_slices, None);
+  }
+}
@@ -11,6 +11,7 @@ use dcmfx_core::{
 use dcmfx_pixel_data::{
   ColorImage, ColorSpace, LookupTable, MonochromeImage, PixelDataDecodeConfig,
   PixelDataEncodeConfig, decode, encode,
+  encode::{HighThroughputJpeg2000EncodeParams, Jpeg2000ProgressionOrder},
   iods::{
     PaletteColorLookupTableModule,
     image_pixel_module::{
@@ -132,6 +133,31 @@ fn test_jpeg_ls_near_lossless_encode_decode_cycle() {
   );
 }
 
+#[test]
+fn test_jpeg_ls_near_lossless_with_zero_near_encode_decode_cycle() {
+  let mut encode_config = encode_config();
+  encode_config.set_jpeg_ls_near(Some(0));
+
+  test_encode_decode_cycle(
+    all_image_pixel_modules()
+      .into_iter()
+      .filter(|m| {
+        !m.photometric_interpretation().is_palette_color()
+          && !m.photometric_interpretation().is_ybr_full_422()
+          && (m.bits_allocated() == BitsAllocated::Eight
+            || m.bits_allocated() == BitsAllocated::Sixteen)
+          && m.bits_stored() >= 2
+          && m.pixel_representation().is_unsigned()
+      })
+      .collect(),
+    &transfer_syntax::JPEG_LS_LOSSY_NEAR_LOSSLESS,
+    encode_config,
+    PixelDataDecodeConfig::default(),
+    0.0,
+    0.0,
+  );
+}
+
 #[test]
 fn test_jpeg_2000_lossless_only_encode_decode_cycle() {
   test_encode_decode_cycle(
@@ -175,8 +201,10 @@ fn test_high_throughput_jpeg_2000_lossless_only_encode_decode_cycle() {
     HighThroughputJpeg2000Decoder::OpenJpeg,
     HighThroughputJpeg2000Decoder::OpenJph,
   ] {
-    let mut decode_config = PixelDataDecodeConfig::default();
-    decode_config.high_throughput_jpeg_2000_decoder = decoder;
+    let decode_config = PixelDataDecodeConfig {
+      high_throughput_jpeg_2000_decoders: vec![decoder],
+      ..Default::default()
+    };
 
     test_encode_decode_cycle(
       all_image_pixel_modules()
@@ -195,14 +223,54 @@ fn test_high_throughput_jpeg_2000_lossless_only_encode_decode_cycle() {
   }
 }
 
+#[test]
+fn test_high_throughput_jpeg_2000_tiled_encode_decode_cycle() {
+  let mut encode_config = encode_config();
+  encode_config.set_high_throughput_jpeg_2000_params(
+    HighThroughputJpeg2000EncodeParams {
+      tile_size: Some((16, 8)),
+      codeblock_size: (32, 16),
+      progression_order: Jpeg2000ProgressionOrder::Lrcp,
+      decompositions: 2,
+    },
+  );
+
+  for decoder in [
+    HighThroughputJpeg2000Decoder::OpenJpeg,
+    HighThroughputJpeg2000Decoder::OpenJph,
+  ] {
+    let decode_config = PixelDataDecodeConfig {
+      high_throughput_jpeg_2000_decoders: vec![decoder],
+      ..Default::default()
+    };
+
+    test_encode_decode_cycle(
+      all_image_pixel_modules()
+        .into_iter()
+        .filter(|m| {
+          !m.photometric_interpretation().is_ybr_full_422()
+            && (2..=30).contains(&m.bits_stored())
+        })
+        .collect(),
+      &transfer_syntax::HIGH_THROUGHPUT_JPEG_2000_LOSSLESS_ONLY,
+      encode_config,
+      decode_config,
+      0.0,
+      0.0,
+    );
+  }
+}
+
 #[test]
 fn test_high_throughput_jpeg_2000_encode_decode_cycle() {
   for decoder in [
     HighThroughputJpeg2000Decoder::OpenJpeg,
     HighThroughputJpeg2000Decoder::OpenJph,
   ] {
-    let mut decode_config = PixelDataDecodeConfig::default();
-    decode_config.high_throughput_jpeg_2000_decoder = decoder;
+    let decode_config = PixelDataDecodeConfig {
+      high_throughput_jpeg_2000_decoders: vec![decoder],
+      ..Default::default()
+    };
 
     test_encode_decode_cycle(
       all_image_pixel_modules()
@@ -225,8 +293,10 @@ fn test_high_throughput_jpeg_2000_encode_decode_cycle() {
 #[test]
 fn test_jpeg_xl_lossless_encode_decode_cycle() {
   for jpeg_xl_decoder in [JpegXlDecoder::LibJxl, JpegXlDecoder::JxlOxide] {
-    let mut decode_config = PixelDataDecodeConfig::default();
-    decode_config.jpeg_xl_decoder = jpeg_xl_decoder;
+    let decode_config = PixelDataDecodeConfig {
+      jpeg_xl_decoders: vec![jpeg_xl_decoder],
+      ..Default::default()
+    };
 
     test_encode_decode_cycle(
       all_image_pixel_modules()
@@ -251,8 +321,10 @@ fn test_jpeg_xl_lossless_encode_decode_cycle() {
 #[test]
 fn test_jpeg_xl_encode_decode_cycle() {
   for jpeg_xl_decoder in [JpegXlDecoder::LibJxl, JpegXlDecoder::JxlOxide] {
-    let mut decode_config = PixelDataDecodeConfig::default();
-    decode_config.jpeg_xl_decoder = jpeg_xl_decoder;
+    let decode_config = PixelDataDecodeConfig {
+      jpeg_xl_decoders: vec![jpeg_xl_decoder],
+      ..Default::default()
+    };
 
     test_encode_decode_cycle(
       all_image_pixel_modules()
@@ -286,6 +358,73 @@ fn test_deflated_image_frame_encode_decode_cycle() {
   );
 }
 
+#[test]
+fn test_deflated_image_frame_parallel_encode_decode_cycle() {
+  let mut encode_config = encode_config();
+  encode_config.set_deflate_thread_count(4);
+
+  test_encode_decode_cycle(
+    all_image_pixel_modules(),
+    &transfer_syntax::DEFLATED_IMAGE_FRAME_COMPRESSION,
+    encode_config,
+    PixelDataDecodeConfig::default(),
+    0.0,
+    0.0,
+  );
+}
+
+#[test]
+fn test_native_ybr_full_422_encode_from_rgb() {
+  for planar_configuration in [
+    PlanarConfiguration::Interleaved,
+    PlanarConfiguration::Separate,
+  ] {
+    let image_pixel_module = ImagePixelModule::new_basic(
+      SamplesPerPixel::Three {
+        planar_configuration,
+      },
+      PhotometricInterpretation::YbrFull422,
+      2,
+      4,
+      BitsAllocated::Eight,
+      8,
+    )
+    .unwrap();
+
+    let rgb_image = ColorImage::new_u8(
+      4,
+      2,
+      (0..24).map(|i| (i * 10) as u8).collect(),
+      ColorSpace::Rgb,
+      8,
+    )
+    .unwrap();
+
+    let mut encoded_frame = encode::encode_color(
+      &rgb_image,
+      &image_pixel_module,
+      &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      &encode_config(),
+    )
+    .unwrap();
+
+    assert_eq!(encoded_frame.len(), 16);
+
+    let decoded_image = decode::decode_color(
+      &mut encoded_frame,
+      &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      &image_pixel_module,
+      &PixelDataDecodeConfig::default(),
+    )
+    .unwrap();
+
+    let mut expected_image = rgb_image.clone();
+    expected_image.convert_to_ybr_422_color_space();
+
+    assert_eq!(decoded_image, expected_image);
+  }
+}
+
 fn test_encode_decode_cycle(
   image_pixel_modules: Vec<ImagePixelModule>,
   transfer_syntax: &'static TransferSyntax,
@@ -302,7 +441,7 @@ fn test_encode_decode_cycle(
           &image_pixel_module,
           transfer_syntax,
           encode_config,
-          decode_config,
+          &decode_config,
           monochrome_image_max_reencode_delta,
         );
       } else {
@@ -310,7 +449,7 @@ fn test_encode_decode_cycle(
           &image_pixel_module,
           transfer_syntax,
           encode_config,
-          decode_config,
+          &decode_config,
           color_image_max_reencode_delta,
         );
       }
@@ -321,7 +460,7 @@ fn test_monochrome_image_encode_decode_cycle(
   image_pixel_module: &ImagePixelModule,
   transfer_syntax: &'static TransferSyntax,
   encode_config: PixelDataEncodeConfig,
-  decode_config: PixelDataDecodeConfig,
+  decode_config: &PixelDataDecodeConfig,
   max_reencode_delta: f64,
 ) {
   let original_image = create_monochrome_image(&image_pixel_module);
@@ -340,7 +479,7 @@ fn test_monochrome_image_encode_decode_cycle(
     &mut encoded_frame,
     transfer_syntax,
     &image_pixel_module,
-    &decode_config,
+    decode_config,
   )
   .unwrap();
 
@@ -348,6 +487,16 @@ fn test_monochrome_image_encode_decode_cycle(
   assert_eq!(original_image.width(), decoded_image.width());
   assert_eq!(original_image.height(), decoded_image.height());
 
+  // Check the codestream's details, if available, match the image
+  if let Some(info) = encoded_frame.decoded_frame_info() {
+    assert_eq!(info.width, u32::from(original_image.width()));
+    assert_eq!(info.height, u32::from(original_image.height()));
+    assert_eq!(
+      info.samples_per_pixel,
+      u8::from(image_pixel_module.samples_per_pixel())
+    );
+  }
+
   // Convert images to stored values so that they can be compared
   let original_image = original_image.to_stored_values();
   let decoded_image = decoded_image.to_stored_values();
@@ -379,7 +528,7 @@ fn test_color_image_encode_decode_cycle(
   image_pixel_module: &ImagePixelModule,
   transfer_syntax: &'static TransferSyntax,
   encode_config: PixelDataEncodeConfig,
-  decode_config: PixelDataDecodeConfig,
+  decode_config: &PixelDataDecodeConfig,
   mut max_reencode_delta: f64,
 ) {
   // If the Image Pixel Module isn't supported for encoding then there's
@@ -408,7 +557,7 @@ fn test_color_image_encode_decode_cycle(
     &mut encoded_frame,
     transfer_syntax,
     &encoded_image_pixel_module,
-    &decode_config,
+    decode_config,
   )
   .unwrap();
 
@@ -416,6 +565,16 @@ fn test_color_image_encode_decode_cycle(
   assert_eq!(original_image.width(), decoded_image.width());
   assert_eq!(original_image.height(), decoded_image.height());
 
+  // Check the codestream's details, if available, match the image
+  if let Some(info) = encoded_frame.decoded_frame_info() {
+    assert_eq!(info.width, u32::from(original_image.width()));
+    assert_eq!(info.height, u32::from(original_image.height()));
+    assert_eq!(
+      info.samples_per_pixel,
+      u8::from(image_pixel_module.samples_per_pixel())
+    );
+  }
+
   // Convert images to RGB f64 so their pixels can each be compared
   let original_image = original_image.to_rgb_f64_image();
   let decoded_image = decoded_image.to_rgb_f64_image();
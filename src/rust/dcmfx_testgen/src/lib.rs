@@ -0,0 +1,615 @@
+//! Generates synthetic DICOM data sets for use as test data.
+//!
+//! Generated data sets contain the modules required by their SOP Class along
+//! with pixel data made up of a gradient overlaid with noise. All UIDs,
+//! identifiers, and pixel values are derived from a seed so that the same
+//! [`TestDataSetConfig`] always generates the same data set.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+mod random;
+mod test_data_set_config;
+
+pub use test_data_set_config::TestDataSetConfig;
+
+use dcmfx_core::{
+  DataElementValue, DataError, DataSet, DcmfxError, PersonNameComponents,
+  SopClass, StructuredDate, StructuredPersonName, StructuredTime,
+  ValueRepresentation, dictionary, sop_class, transfer_syntax,
+};
+use dcmfx_p10::DataSetP10Extensions;
+use dcmfx_pixel_data::{
+  DataSetPixelDataExtensions, PixelDataDecodeConfig, PixelDataEncodeConfig,
+  transforms::P10PixelDataTranscodeTransformError,
+};
+
+use random::SplitMix64;
+
+/// The SOP Classes that test data sets can be generated for.
+///
+pub const SUPPORTED_SOP_CLASSES: [&SopClass; 11] = [
+  &sop_class::COMPUTED_RADIOGRAPHY_IMAGE_STORAGE,
+  &sop_class::CT_IMAGE_STORAGE,
+  &sop_class::DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION,
+  &sop_class::MR_IMAGE_STORAGE,
+  &sop_class::MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE,
+  &sop_class::MULTI_FRAME_GRAYSCALE_WORD_SECONDARY_CAPTURE_IMAGE_STORAGE,
+  &sop_class::MULTI_FRAME_TRUE_COLOR_SECONDARY_CAPTURE_IMAGE_STORAGE,
+  &sop_class::SECONDARY_CAPTURE_IMAGE_STORAGE,
+  &sop_class::ULTRASOUND_IMAGE_STORAGE,
+  &sop_class::ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE,
+  &sop_class::X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE,
+];
+
+/// An error that occurred when generating a test data set.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestDataSetError {
+  /// The [`TestDataSetConfig`] describes a data set that can't be generated,
+  /// e.g. because its bit depth isn't permitted by its SOP Class.
+  ConfigInvalid {
+    details: String,
+  },
+
+  DataError(DataError),
+  P10PixelDataTranscodeTransformError(P10PixelDataTranscodeTransformError),
+}
+
+impl TestDataSetError {
+  /// Returns the name of the error as a human-readable string.
+  ///
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::ConfigInvalid { .. } => "Config invalid",
+      Self::DataError(e) => e.name(),
+      Self::P10PixelDataTranscodeTransformError(_) => "Transcode failed",
+    }
+  }
+}
+
+impl core::fmt::Display for TestDataSetError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::ConfigInvalid { details } => {
+        write!(f, "Test data set config invalid, details: {details}")
+      }
+      Self::DataError(e) => e.fmt(f),
+      Self::P10PixelDataTranscodeTransformError(e) => e.fmt(f),
+    }
+  }
+}
+
+impl DcmfxError for TestDataSetError {
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::ConfigInvalid { details } => vec![
+        format!("Test data generation error {task_description}"),
+        "".to_string(),
+        format!("  Error: {}", self.name()),
+        format!("  Details: {details}"),
+      ],
+      Self::DataError(e) => e.to_lines(task_description),
+      Self::P10PixelDataTranscodeTransformError(e) => {
+        e.to_lines(task_description)
+      }
+    }
+  }
+}
+
+impl From<DataError> for TestDataSetError {
+  fn from(e: DataError) -> Self {
+    Self::DataError(e)
+  }
+}
+
+impl From<P10PixelDataTranscodeTransformError> for TestDataSetError {
+  fn from(e: P10PixelDataTranscodeTransformError) -> Self {
+    Self::P10PixelDataTranscodeTransformError(e)
+  }
+}
+
+/// Describes the constraints a SOP Class places on generated data sets. Where
+/// `color` or `bits_allocated` is `None` any value is permitted.
+///
+struct SopClassProfile {
+  modality: &'static str,
+  multi_frame: bool,
+  color: Option<bool>,
+  bits_allocated: Option<u16>,
+}
+
+const fn profile(
+  modality: &'static str,
+  multi_frame: bool,
+  color: Option<bool>,
+  bits_allocated: Option<u16>,
+) -> SopClassProfile {
+  SopClassProfile {
+    modality,
+    multi_frame,
+    color,
+    bits_allocated,
+  }
+}
+
+/// The constraints for each of the [`SUPPORTED_SOP_CLASSES`].
+///
+const SOP_CLASS_PROFILES: [SopClassProfile; 11] = [
+  profile("CR", false, Some(false), None),
+  profile("CT", false, Some(false), Some(16)),
+  profile("DX", false, Some(false), None),
+  profile("MR", false, Some(false), Some(16)),
+  profile("OT", true, Some(false), Some(8)),
+  profile("OT", true, Some(false), Some(16)),
+  profile("OT", true, Some(true), Some(8)),
+  profile("OT", false, None, None),
+  profile("US", false, None, Some(8)),
+  profile("US", true, None, Some(8)),
+  profile("XA", true, Some(false), None),
+];
+
+fn sop_class_profile(sop_class: &SopClass) -> Option<&'static SopClassProfile> {
+  SUPPORTED_SOP_CLASSES
+    .iter()
+    .position(|supported| *supported == sop_class)
+    .map(|i| &SOP_CLASS_PROFILES[i])
+}
+
+/// Generates a synthetic data set as specified by the passed config. The
+/// returned data set includes File Meta Information for the configured
+/// transfer syntax, and so can be written straight to a DICOM P10 file.
+///
+pub fn generate_data_set(
+  config: &TestDataSetConfig,
+) -> Result<DataSet, TestDataSetError> {
+  let profile = validate_config(config)?;
+
+  let mut rng = SplitMix64::new(config.seed);
+  let mut data_set = DataSet::new();
+
+  add_patient_and_study_modules(&mut data_set, config, &mut rng)?;
+  add_series_and_equipment_modules(&mut data_set, config, profile, &mut rng)?;
+  add_image_modules(&mut data_set, config, profile)?;
+  add_image_pixel_module(&mut data_set, config, &mut rng)?;
+
+  let mut data_set = if config.transfer_syntax.is_encapsulated {
+    data_set.synthesize_file_meta_information(
+      &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      None,
+    )?;
+
+    data_set
+      .transcode_pixel_data(
+        config.transfer_syntax,
+        PixelDataDecodeConfig::default(),
+        PixelDataEncodeConfig::default(),
+        None,
+      )?
+      .ok_or_else(|| TestDataSetError::ConfigInvalid {
+        details: "Generated data set has no valid Image Pixel Module"
+          .to_string(),
+      })?
+  } else {
+    data_set
+  };
+
+  data_set.synthesize_file_meta_information(config.transfer_syntax, None)?;
+
+  Ok(data_set)
+}
+
+/// Checks that a config describes a data set that can be generated, and
+/// returns the constraints for its SOP Class.
+///
+fn validate_config(
+  config: &TestDataSetConfig,
+) -> Result<&'static SopClassProfile, TestDataSetError> {
+  let invalid = |details: String| TestDataSetError::ConfigInvalid { details };
+
+  let profile = sop_class_profile(config.sop_class).ok_or_else(|| {
+    invalid(format!(
+      "SOP Class '{}' is not supported",
+      config.sop_class.name
+    ))
+  })?;
+
+  if config.rows == 0 || config.columns == 0 {
+    return Err(invalid("Rows and columns must not be zero".to_string()));
+  }
+
+  if config.bits_allocated != 8 && config.bits_allocated != 16 {
+    return Err(invalid(format!(
+      "Bits allocated must be 8 or 16 but is {}",
+      config.bits_allocated
+    )));
+  }
+
+  if config.bits_stored == 0 || config.bits_stored > config.bits_allocated {
+    return Err(invalid(format!(
+      "Bits stored must be in the range 1-{} but is {}",
+      config.bits_allocated, config.bits_stored
+    )));
+  }
+
+  if config.color && config.bits_stored != 8 {
+    return Err(invalid(
+      "Color pixel data requires 8 bits allocated and stored".to_string(),
+    ));
+  }
+
+  if config.number_of_frames == 0 {
+    return Err(invalid("Number of frames must not be zero".to_string()));
+  }
+
+  if config.number_of_frames > 1 && !profile.multi_frame {
+    return Err(invalid(format!(
+      "SOP Class '{}' does not support multiple frames",
+      config.sop_class.name
+    )));
+  }
+
+  if let Some(color) = profile.color
+    && color != config.color
+  {
+    return Err(invalid(format!(
+      "SOP Class '{}' requires {} pixel data",
+      config.sop_class.name,
+      if color { "color" } else { "grayscale" }
+    )));
+  }
+
+  if let Some(bits_allocated) = profile.bits_allocated
+    && bits_allocated != config.bits_allocated
+  {
+    return Err(invalid(format!(
+      "SOP Class '{}' requires {} bits allocated",
+      config.sop_class.name, bits_allocated
+    )));
+  }
+
+  Ok(profile)
+}
+
+/// The date and time used for all date and time values in generated data sets.
+///
+const STUDY_DATE: StructuredDate = StructuredDate {
+  year: 2000,
+  month: 1,
+  day: 1,
+};
+const STUDY_TIME: StructuredTime = StructuredTime {
+  hour: 12,
+  minute: Some(0),
+  second: Some(0.0),
+};
+
+fn add_patient_and_study_modules(
+  data_set: &mut DataSet,
+  config: &TestDataSetConfig,
+  rng: &mut SplitMix64,
+) -> Result<(), DataError> {
+  data_set.insert_person_name_value(
+    &dictionary::PATIENT_NAME,
+    &[StructuredPersonName {
+      alphabetic: Some(PersonNameComponents {
+        last_name: "TESTGEN".to_string(),
+        first_name: format!("{}", config.seed),
+        middle_name: "".to_string(),
+        prefix: "".to_string(),
+        suffix: "".to_string(),
+      }),
+      ideographic: None,
+      phonetic: None,
+    }],
+  )?;
+  data_set.insert_string_value(
+    &dictionary::PATIENT_ID,
+    &[&format!("TESTGEN-{:016X}", config.seed)],
+  )?;
+  data_set.insert_date_value(
+    &dictionary::PATIENT_BIRTH_DATE,
+    &StructuredDate {
+      year: 1950 + rng.next_below(50) as u16,
+      month: 1 + rng.next_below(12) as u8,
+      day: 1 + rng.next_below(28) as u8,
+    },
+  )?;
+  data_set.insert_string_value(&dictionary::PATIENT_SEX, &["O"])?;
+
+  data_set
+    .insert_string_value(&dictionary::STUDY_INSTANCE_UID, &[&rng.next_uid()])?;
+  data_set.insert_date_value(&dictionary::STUDY_DATE, &STUDY_DATE)?;
+  data_set.insert_time_value(&dictionary::STUDY_TIME, &STUDY_TIME)?;
+  data_set.insert_binary_value(
+    dictionary::REFERRING_PHYSICIAN_NAME.tag,
+    ValueRepresentation::PersonName,
+    vec![].into(),
+  )?;
+  data_set.insert_string_value(&dictionary::STUDY_ID, &["1"])?;
+  data_set.insert_binary_value(
+    dictionary::ACCESSION_NUMBER.tag,
+    ValueRepresentation::ShortString,
+    vec![].into(),
+  )?;
+
+  Ok(())
+}
+
+fn add_series_and_equipment_modules(
+  data_set: &mut DataSet,
+  config: &TestDataSetConfig,
+  profile: &SopClassProfile,
+  rng: &mut SplitMix64,
+) -> Result<(), DataError> {
+  data_set.insert_string_value(&dictionary::MODALITY, &[profile.modality])?;
+  data_set.insert_string_value(
+    &dictionary::SERIES_INSTANCE_UID,
+    &[&rng.next_uid()],
+  )?;
+  data_set.insert_int_value(&dictionary::SERIES_NUMBER, &[1])?;
+
+  if config.sop_class == &sop_class::CT_IMAGE_STORAGE
+    || config.sop_class == &sop_class::MR_IMAGE_STORAGE
+  {
+    data_set.insert_string_value(
+      &dictionary::FRAME_OF_REFERENCE_UID,
+      &[&rng.next_uid()],
+    )?;
+    data_set.insert_binary_value(
+      dictionary::POSITION_REFERENCE_INDICATOR.tag,
+      ValueRepresentation::LongString,
+      vec![].into(),
+    )?;
+  }
+
+  data_set.insert_string_value(&dictionary::MANUFACTURER, &["DCMfx"])?;
+
+  data_set
+    .insert_string_value(&dictionary::SOP_CLASS_UID, &[config.sop_class.uid])?;
+  data_set
+    .insert_string_value(&dictionary::SOP_INSTANCE_UID, &[&rng.next_uid()])?;
+
+  Ok(())
+}
+
+fn add_image_modules(
+  data_set: &mut DataSet,
+  config: &TestDataSetConfig,
+  profile: &SopClassProfile,
+) -> Result<(), DataError> {
+  data_set.insert_int_value(&dictionary::INSTANCE_NUMBER, &[1])?;
+  data_set.insert_date_value(&dictionary::CONTENT_DATE, &STUDY_DATE)?;
+  data_set.insert_time_value(&dictionary::CONTENT_TIME, &STUDY_TIME)?;
+  data_set
+    .insert_string_value(&dictionary::IMAGE_TYPE, &["ORIGINAL", "PRIMARY"])?;
+  data_set.insert_binary_value(
+    dictionary::PATIENT_ORIENTATION.tag,
+    ValueRepresentation::CodeString,
+    vec![].into(),
+  )?;
+
+  if config.sop_class == &sop_class::CT_IMAGE_STORAGE
+    || config.sop_class == &sop_class::MR_IMAGE_STORAGE
+  {
+    data_set.insert_float_value(&dictionary::PIXEL_SPACING, &[1.0, 1.0])?;
+    data_set.insert_float_value(
+      &dictionary::IMAGE_ORIENTATION_PATIENT,
+      &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+    )?;
+    data_set
+      .insert_float_value(&dictionary::IMAGE_POSITION_PATIENT, &[0.0; 3])?;
+    data_set.insert_float_value(&dictionary::SLICE_THICKNESS, &[1.0])?;
+  }
+
+  if config.sop_class == &sop_class::CT_IMAGE_STORAGE {
+    data_set.insert_float_value(&dictionary::RESCALE_INTERCEPT, &[-1024.0])?;
+    data_set.insert_float_value(&dictionary::RESCALE_SLOPE, &[1.0])?;
+  }
+
+  if config.sop_class == &sop_class::MR_IMAGE_STORAGE {
+    data_set.insert_string_value(&dictionary::SCANNING_SEQUENCE, &["SE"])?;
+    data_set.insert_string_value(&dictionary::SEQUENCE_VARIANT, &["NONE"])?;
+  }
+
+  if config.sop_class
+    == &sop_class::DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION
+  {
+    data_set.insert_string_value(
+      &dictionary::PRESENTATION_INTENT_TYPE,
+      &["FOR PRESENTATION"],
+    )?;
+  }
+
+  if profile.modality == "OT" {
+    data_set.insert_string_value(&dictionary::CONVERSION_TYPE, &["WSD"])?;
+  }
+
+  if profile.multi_frame {
+    data_set.insert_int_value(
+      &dictionary::NUMBER_OF_FRAMES,
+      &[i64::from(config.number_of_frames)],
+    )?;
+    data_set.insert_float_value(&dictionary::FRAME_TIME, &[40.0])?;
+    data_set.insert_attribute_tag_value(
+      &dictionary::FRAME_INCREMENT_POINTER,
+      &[dictionary::FRAME_TIME.tag],
+    )?;
+  }
+
+  Ok(())
+}
+
+fn add_image_pixel_module(
+  data_set: &mut DataSet,
+  config: &TestDataSetConfig,
+  rng: &mut SplitMix64,
+) -> Result<(), DataError> {
+  let samples_per_pixel = if config.color { 3 } else { 1 };
+
+  data_set
+    .insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[samples_per_pixel])?;
+  data_set.insert_string_value(
+    &dictionary::PHOTOMETRIC_INTERPRETATION,
+    &[if config.color { "RGB" } else { "MONOCHROME2" }],
+  )?;
+  if config.color {
+    data_set.insert_int_value(&dictionary::PLANAR_CONFIGURATION, &[0])?;
+  }
+  data_set.insert_int_value(&dictionary::ROWS, &[i64::from(config.rows)])?;
+  data_set
+    .insert_int_value(&dictionary::COLUMNS, &[i64::from(config.columns)])?;
+  data_set.insert_int_value(
+    &dictionary::BITS_ALLOCATED,
+    &[i64::from(config.bits_allocated)],
+  )?;
+  data_set.insert_int_value(
+    &dictionary::BITS_STORED,
+    &[i64::from(config.bits_stored)],
+  )?;
+  data_set.insert_int_value(
+    &dictionary::HIGH_BIT,
+    &[i64::from(config.bits_stored) - 1],
+  )?;
+  data_set.insert_int_value(&dictionary::PIXEL_REPRESENTATION, &[0])?;
+
+  let vr = if config.bits_allocated == 8 {
+    ValueRepresentation::OtherByteString
+  } else {
+    ValueRepresentation::OtherWordString
+  };
+
+  let mut pixel_data = generate_pixel_data(config, rng);
+  vr.pad_bytes_to_even_length(&mut pixel_data);
+
+  data_set.insert(
+    dictionary::PIXEL_DATA.tag,
+    DataElementValue::new_binary_unchecked(vr, pixel_data.into()),
+  );
+
+  Ok(())
+}
+
+/// Generates native little endian pixel data consisting of a diagonal gradient
+/// that moves across successive frames, with noise added to each sample.
+///
+fn generate_pixel_data(
+  config: &TestDataSetConfig,
+  rng: &mut SplitMix64,
+) -> Vec<u8> {
+  let samples_per_pixel: u64 = if config.color { 3 } else { 1 };
+  let rows = u64::from(config.rows);
+  let columns = u64::from(config.columns);
+  let max_value = (1u64 << config.bits_stored) - 1;
+  let noise_range = max_value / 32 + 1;
+  let period = rows + columns;
+
+  let sample_count =
+    rows * columns * samples_per_pixel * u64::from(config.number_of_frames);
+  let mut pixel_data = Vec::with_capacity(
+    (sample_count * u64::from(config.bits_allocated / 8)) as usize,
+  );
+
+  for frame in 0..u64::from(config.number_of_frames) {
+    for y in 0..rows {
+      for x in 0..columns {
+        for sample in 0..samples_per_pixel {
+          let phase =
+            (x + y + frame * 8 + sample * period / samples_per_pixel) % period;
+          let value = (phase * max_value / period
+            + rng.next_below(noise_range))
+          .min(max_value);
+
+          if config.bits_allocated == 8 {
+            pixel_data.push(value as u8);
+          } else {
+            pixel_data.extend_from_slice(&(value as u16).to_le_bytes());
+          }
+        }
+      }
+    }
+  }
+
+  pixel_data
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn generate_data_set_test() {
+    let config = TestDataSetConfig::default()
+      .sop_class(&sop_class::ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE)
+      .matrix_size(3, 5)
+      .bit_depth(8, 8)
+      .color(true)
+      .number_of_frames(2)
+      .seed(7);
+
+    let data_set = generate_data_set(&config).unwrap();
+
+    assert_eq!(data_set.get_string(dictionary::MODALITY.tag), Ok("US"));
+    assert_eq!(
+      data_set.get_int::<i64>(dictionary::NUMBER_OF_FRAMES.tag),
+      Ok(2)
+    );
+    assert_eq!(
+      data_set.get_string(dictionary::PHOTOMETRIC_INTERPRETATION.tag),
+      Ok("RGB")
+    );
+    assert_eq!(
+      data_set
+        .get_value_bytes(dictionary::PIXEL_DATA.tag)
+        .map(|bytes| bytes.len()),
+      Ok(3 * 5 * 3 * 2)
+    );
+    assert_eq!(
+      data_set.get_string(dictionary::TRANSFER_SYNTAX_UID.tag),
+      Ok(transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN.uid)
+    );
+  }
+
+  #[test]
+  fn generate_data_set_is_reproducible_test() {
+    let config = TestDataSetConfig::default().matrix_size(16, 16).seed(1);
+
+    assert_eq!(generate_data_set(&config), generate_data_set(&config));
+    assert_ne!(
+      generate_data_set(&config),
+      generate_data_set(&config.clone().seed(2))
+    );
+  }
+
+  #[test]
+  fn validate_config_test() {
+    assert!(validate_config(&TestDataSetConfig::default()).is_ok());
+
+    for config in [
+      TestDataSetConfig::default().sop_class(&sop_class::RT_PLAN_STORAGE),
+      TestDataSetConfig::default().matrix_size(0, 1),
+      TestDataSetConfig::default().bit_depth(12, 12),
+      TestDataSetConfig::default().bit_depth(8, 9),
+      TestDataSetConfig::default().color(true),
+      TestDataSetConfig::default().number_of_frames(2),
+      TestDataSetConfig::default()
+        .sop_class(&sop_class::CT_IMAGE_STORAGE)
+        .bit_depth(8, 8),
+    ] {
+      assert!(matches!(
+        validate_config(&config),
+        Err(TestDataSetError::ConfigInvalid { .. })
+      ));
+    }
+  }
+}
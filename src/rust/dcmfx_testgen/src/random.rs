@@ -0,0 +1,62 @@
+//! A small seedable pseudo-random number generator.
+//!
+//! An external RNG crate isn't used because its output sequence for a given
+//! seed may change between versions, and generated test data must be
+//! reproducible indefinitely.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+
+/// A SplitMix64 pseudo-random number generator. This is fast, has good
+/// statistical properties for test data, and its output for a given seed never
+/// changes.
+///
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+  /// Creates a new generator from a seed.
+  ///
+  pub fn new(seed: u64) -> Self {
+    Self(seed)
+  }
+
+  /// Returns the next pseudo-random 64-bit value.
+  ///
+  pub fn next_u64(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+  }
+
+  /// Returns a pseudo-random value in the range `0..bound`. `bound` must not
+  /// be zero.
+  ///
+  pub fn next_below(&mut self, bound: u64) -> u64 {
+    self.next_u64() % bound
+  }
+
+  /// Returns a pseudo-random UID under the `2.25` root defined in PS3.5 B.2.
+  ///
+  pub fn next_uid(&mut self) -> String {
+    let value =
+      (u128::from(self.next_u64()) << 64) | u128::from(self.next_u64());
+
+    format!("2.25.{value}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn next_u64_test() {
+    let mut rng = SplitMix64::new(0);
+
+    assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+    assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+  }
+}
@@ -0,0 +1,113 @@
+//! Configuration for the generation of synthetic test data sets.
+
+use dcmfx_core::{SopClass, TransferSyntax, sop_class, transfer_syntax};
+
+/// Configuration that specifies the content of a synthetic test data set
+/// created by [`crate::generate_data_set()`].
+///
+/// The same configuration, including the seed, always generates exactly the
+/// same data set.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestDataSetConfig {
+  pub(crate) sop_class: &'static SopClass,
+  pub(crate) rows: u16,
+  pub(crate) columns: u16,
+  pub(crate) bits_allocated: u16,
+  pub(crate) bits_stored: u16,
+  pub(crate) color: bool,
+  pub(crate) number_of_frames: u32,
+  pub(crate) transfer_syntax: &'static TransferSyntax,
+  pub(crate) seed: u64,
+}
+
+impl Default for TestDataSetConfig {
+  fn default() -> Self {
+    Self {
+      sop_class: &sop_class::SECONDARY_CAPTURE_IMAGE_STORAGE,
+      rows: 256,
+      columns: 256,
+      bits_allocated: 16,
+      bits_stored: 12,
+      color: false,
+      number_of_frames: 1,
+      transfer_syntax: &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      seed: 0,
+    }
+  }
+}
+
+impl TestDataSetConfig {
+  /// The SOP Class of the generated data set. This must be one of the SOP
+  /// Classes in [`crate::SUPPORTED_SOP_CLASSES`].
+  ///
+  /// By default this is Secondary Capture Image Storage.
+  ///
+  pub fn sop_class(mut self, value: &'static SopClass) -> Self {
+    self.sop_class = value;
+    self
+  }
+
+  /// The number of rows and columns in each frame of pixel data.
+  ///
+  /// By default this is 256 x 256.
+  ///
+  pub fn matrix_size(mut self, rows: u16, columns: u16) -> Self {
+    self.rows = rows;
+    self.columns = columns;
+    self
+  }
+
+  /// The number of bits allocated and stored for each pixel sample. Bits
+  /// allocated must be 8 or 16, and bits stored must be in the range 1 to bits
+  /// allocated.
+  ///
+  /// By default 16 bits are allocated and 12 bits are stored.
+  ///
+  pub fn bit_depth(mut self, bits_allocated: u16, bits_stored: u16) -> Self {
+    self.bits_allocated = bits_allocated;
+    self.bits_stored = bits_stored;
+    self
+  }
+
+  /// Whether to generate RGB color pixel data rather than MONOCHROME2
+  /// grayscale pixel data. Color pixel data requires 8 bits allocated.
+  ///
+  /// By default this is `false`.
+  ///
+  pub fn color(mut self, value: bool) -> Self {
+    self.color = value;
+    self
+  }
+
+  /// The number of frames of pixel data. Values greater than one require a
+  /// multi-frame SOP Class.
+  ///
+  /// By default this is one.
+  ///
+  pub fn number_of_frames(mut self, value: u32) -> Self {
+    self.number_of_frames = value;
+    self
+  }
+
+  /// The transfer syntax of the generated data set. Pixel data is encoded into
+  /// this transfer syntax, which requires a suitable pixel data encoder to be
+  /// available.
+  ///
+  /// By default this is Explicit VR Little Endian.
+  ///
+  pub fn transfer_syntax(mut self, value: &'static TransferSyntax) -> Self {
+    self.transfer_syntax = value;
+    self
+  }
+
+  /// The seed for the pseudo-random number generator used to create UIDs,
+  /// identifiers, and pixel data.
+  ///
+  /// By default this is zero.
+  ///
+  pub fn seed(mut self, value: u64) -> Self {
+    self.seed = value;
+    self
+  }
+}
@@ -0,0 +1,18 @@
+//! Assembles the single-frame instances of a series, such as a CT or MR series,
+//! into a 3D voxel volume, reslices and projects volumes along the axial,
+//! coronal, and sagittal planes, and exports volumes to other formats such as
+//! NIfTI-1.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+mod mpr;
+mod nifti;
+mod projection;
+mod volume;
+
+pub use mpr::{Interpolation, Plane};
+pub use projection::{ProjectionMode, ProjectionSlab};
+pub use volume::{Volume, VolumeError};
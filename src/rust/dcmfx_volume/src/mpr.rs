@@ -0,0 +1,375 @@
+//! Multi-planar reconstruction (MPR), which reslices a volume along the
+//! axial, coronal, or sagittal planes of the patient coordinate system.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use dcmfx_pixel_data::MonochromeImage;
+
+use crate::Volume;
+
+/// A plane of the patient coordinate system that a volume can be resliced
+/// along.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Plane {
+  /// Slices perpendicular to the patient's head-foot axis. Images have the
+  /// patient's right on the left and anterior at the top, and slices are
+  /// ordered from inferior to superior.
+  Axial,
+
+  /// Slices perpendicular to the patient's anterior-posterior axis. Images
+  /// have the patient's right on the left and superior at the top, and slices
+  /// are ordered from anterior to posterior.
+  Coronal,
+
+  /// Slices perpendicular to the patient's left-right axis. Images have
+  /// anterior on the left and superior at the top, and slices are ordered from
+  /// the patient's right to their left.
+  Sagittal,
+}
+
+impl Plane {
+  /// Returns the patient axis and direction, in the DICOM LPS coordinate
+  /// system, of the image's horizontal axis, the image's vertical axis, and
+  /// the slice normal for this plane.
+  ///
+  fn patient_axes(&self) -> [(usize, bool); 3] {
+    match self {
+      Plane::Axial => [(0, true), (1, true), (2, true)],
+      Plane::Coronal => [(0, true), (2, false), (1, true)],
+      Plane::Sagittal => [(1, true), (2, false), (0, true)],
+    }
+  }
+}
+
+/// The interpolation used to sample voxel values when reslicing a volume.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+  /// Takes the value of the nearest voxel.
+  Nearest,
+
+  /// Bilinearly interpolates between the four nearest voxels in the slice.
+  #[default]
+  Linear,
+}
+
+/// How one axis of a resliced image maps onto an axis of a volume's voxel
+/// grid.
+///
+#[derive(Clone, Copy, Debug)]
+struct AxisMapping {
+  volume_axis: usize,
+  is_reversed: bool,
+}
+
+impl Volume {
+  /// Returns the number of slices in this volume along the given plane.
+  ///
+  pub fn slice_count(&self, plane: Plane) -> usize {
+    let [_, _, normal] = self.axis_mappings(plane);
+
+    self.dimensions[normal.volume_axis]
+  }
+
+  /// Reslices this volume along the given plane using linear interpolation.
+  /// See [`Self::slice_with_interpolation()`] for details.
+  ///
+  pub fn slice(&self, plane: Plane, index: usize) -> Option<MonochromeImage> {
+    self.slice_with_interpolation(plane, index, Interpolation::Linear)
+  }
+
+  /// Reslices this volume along the given plane, returning the slice at the
+  /// given index as a monochrome image of modality values rounded to the
+  /// nearest integer. Returns `None` if the index is out of range, or the
+  /// slice is too large to be held in a [`MonochromeImage`].
+  ///
+  /// Each axis of the volume's voxel grid is assigned to the patient axis it
+  /// is most closely aligned with, and the output image is resampled to have
+  /// square pixels whose size is the smaller of the two in-plane voxel
+  /// spacings. Obliquity in the volume's orientation, such as from a gantry
+  /// tilt, is not corrected for.
+  ///
+  pub fn slice_with_interpolation(
+    &self,
+    plane: Plane,
+    index: usize,
+    interpolation: Interpolation,
+  ) -> Option<MonochromeImage> {
+    let (width, height, values) =
+      self.resample_slice(plane, index, interpolation)?;
+
+    values_to_monochrome_image(width, height, &values)
+  }
+
+  /// Returns the spacing between adjacent slices along the given plane.
+  ///
+  pub(crate) fn slice_spacing(&self, plane: Plane) -> f64 {
+    let [_, _, normal] = self.axis_mappings(plane);
+
+    self.spacing[normal.volume_axis]
+  }
+
+  /// Resamples the slice at the given index along the given plane, returning
+  /// its width, height, and voxel values in row-major order.
+  ///
+  pub(crate) fn resample_slice(
+    &self,
+    plane: Plane,
+    index: usize,
+    interpolation: Interpolation,
+  ) -> Option<(u16, u16, Vec<f64>)> {
+    let [x_axis, y_axis, normal] = self.axis_mappings(plane);
+
+    if index >= self.dimensions[normal.volume_axis] {
+      return None;
+    }
+
+    let slice_coordinate = if normal.is_reversed {
+      self.dimensions[normal.volume_axis] - 1 - index
+    } else {
+      index
+    };
+
+    // Determine the output pixel size and dimensions
+    let x_spacing = self.spacing[x_axis.volume_axis];
+    let y_spacing = self.spacing[y_axis.volume_axis];
+    let pixel_size = x_spacing.min(y_spacing);
+
+    let output_size = |axis: AxisMapping, spacing: f64| -> Option<u16> {
+      let count = self.dimensions[axis.volume_axis];
+      if count == 0 {
+        return None;
+      }
+
+      let size = ((count - 1) as f64 * spacing / pixel_size).round() + 1.0;
+
+      if size.is_finite() && size <= f64::from(u16::MAX) {
+        Some(size as u16)
+      } else {
+        None
+      }
+    };
+
+    let width = output_size(x_axis, x_spacing)?;
+    let height = output_size(y_axis, y_spacing)?;
+
+    // Converts an output pixel position into a continuous voxel coordinate
+    // along the given axis
+    let voxel_coordinate = |axis: AxisMapping, spacing: f64, i: u16| {
+      let coordinate = f64::from(i) * pixel_size / spacing;
+      let max = (self.dimensions[axis.volume_axis] - 1) as f64;
+
+      if axis.is_reversed {
+        (max - coordinate).clamp(0.0, max)
+      } else {
+        coordinate.clamp(0.0, max)
+      }
+    };
+
+    let mut data = Vec::with_capacity(usize::from(width) * usize::from(height));
+
+    for row in 0..height {
+      let y = voxel_coordinate(y_axis, y_spacing, row);
+
+      for column in 0..width {
+        let x = voxel_coordinate(x_axis, x_spacing, column);
+
+        let value = match interpolation {
+          Interpolation::Nearest => self.voxel(
+            [x_axis, y_axis, normal],
+            [x.round() as usize, y.round() as usize, slice_coordinate],
+          ),
+
+          Interpolation::Linear => {
+            let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+            let x1 = (x0 + 1).min(self.dimensions[x_axis.volume_axis] - 1);
+            let y1 = (y0 + 1).min(self.dimensions[y_axis.volume_axis] - 1);
+            let (fx, fy) = (x - x0 as f64, y - y0 as f64);
+
+            let axes = [x_axis, y_axis, normal];
+            let voxel = |x, y| self.voxel(axes, [x, y, slice_coordinate]);
+
+            let top = voxel(x0, y0) * (1.0 - fx) + voxel(x1, y0) * fx;
+            let bottom = voxel(x0, y1) * (1.0 - fx) + voxel(x1, y1) * fx;
+
+            top * (1.0 - fy) + bottom * fy
+          }
+        };
+
+        data.push(value);
+      }
+    }
+
+    Some((width, height, data))
+  }
+
+  /// Returns how the horizontal image axis, vertical image axis, and slice
+  /// normal of the given plane map onto the axes of this volume's voxel grid.
+  ///
+  fn axis_mappings(&self, plane: Plane) -> [AxisMapping; 3] {
+    let mut mappings = [AxisMapping {
+      volume_axis: 0,
+      is_reversed: false,
+    }; 3];
+    let mut is_volume_axis_used = [false; 3];
+
+    for (mapping, (patient_axis, is_positive)) in
+      mappings.iter_mut().zip(plane.patient_axes())
+    {
+      // Find the unused volume axis whose direction is most closely aligned
+      // with this patient axis
+      let volume_axis = (0..3)
+        .filter(|axis| !is_volume_axis_used[*axis])
+        .max_by(|a, b| {
+          self
+            .axis_alignment(*a, patient_axis)
+            .abs()
+            .total_cmp(&self.axis_alignment(*b, patient_axis).abs())
+        })
+        .unwrap();
+
+      is_volume_axis_used[volume_axis] = true;
+
+      let is_aligned = self.axis_alignment(volume_axis, patient_axis) >= 0.0;
+
+      *mapping = AxisMapping {
+        volume_axis,
+        is_reversed: is_aligned != is_positive,
+      };
+    }
+
+    mappings
+  }
+
+  /// Returns the component along the given patient axis of the unit direction
+  /// of the given volume axis.
+  ///
+  fn axis_alignment(&self, volume_axis: usize, patient_axis: usize) -> f64 {
+    let direction = crate::volume::normalize([
+      self.affine[0][volume_axis],
+      self.affine[1][volume_axis],
+      self.affine[2][volume_axis],
+    ]);
+
+    direction[patient_axis]
+  }
+
+  /// Returns the value of the voxel at the given coordinates, which are
+  /// specified in the order of the given axis mappings.
+  ///
+  fn voxel(&self, axes: [AxisMapping; 3], coordinates: [usize; 3]) -> f64 {
+    let mut voxel = [0; 3];
+    for (axis, coordinate) in axes.iter().zip(coordinates) {
+      voxel[axis.volume_axis] = coordinate;
+    }
+
+    let index = voxel[0]
+      + voxel[1] * self.dimensions[0]
+      + voxel[2] * self.dimensions[0] * self.dimensions[1];
+
+    f64::from(self.data[index])
+  }
+}
+
+/// Creates a monochrome image from values in row-major order by rounding them
+/// to the nearest integer.
+///
+pub(crate) fn values_to_monochrome_image(
+  width: u16,
+  height: u16,
+  values: &[f64],
+) -> Option<MonochromeImage> {
+  let data = values.iter().map(|value| value.round() as i32).collect();
+
+  MonochromeImage::new_i32(width, height, data, 32, false).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use super::*;
+
+  use dcmfx_pixel_data::MonochromeImageData;
+
+  use crate::volume::tests::axial_slice;
+
+  fn test_volume() -> Volume {
+    let slices = [
+      axial_slice(0.0, [1024, 1025, 1026, 1027]),
+      axial_slice(0.5, [1028, 1029, 1030, 1031]),
+      axial_slice(1.0, [1032, 1033, 1034, 1035]),
+    ];
+
+    Volume::from_data_sets(&slices.iter().collect::<Vec<_>>()).unwrap()
+  }
+
+  fn slice_data(image: &MonochromeImage) -> (u16, u16, Vec<i32>) {
+    match image.data() {
+      MonochromeImageData::I32(data) => {
+        (image.width(), image.height(), data.clone())
+      }
+      _ => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn slice_count_test() {
+    let volume = test_volume();
+
+    assert_eq!(volume.slice_count(Plane::Axial), 3);
+    assert_eq!(volume.slice_count(Plane::Coronal), 2);
+    assert_eq!(volume.slice_count(Plane::Sagittal), 2);
+  }
+
+  #[test]
+  fn axial_slice_test() {
+    let volume = test_volume();
+
+    // The column spacing is 0.25 and the row spacing is 0.5, so rows are
+    // interpolated to give square pixels
+    assert_eq!(
+      volume
+        .slice_with_interpolation(Plane::Axial, 1, Interpolation::Linear)
+        .map(|image| slice_data(&image)),
+      Some((2, 3, vec![4, 5, 5, 6, 6, 7]))
+    );
+
+    assert_eq!(
+      volume
+        .slice_with_interpolation(Plane::Axial, 1, Interpolation::Nearest)
+        .map(|image| slice_data(&image)),
+      Some((2, 3, vec![4, 5, 6, 7, 6, 7]))
+    );
+
+    assert_eq!(volume.slice(Plane::Axial, 3), None);
+  }
+
+  #[test]
+  fn coronal_slice_test() {
+    let volume = test_volume();
+
+    // Superior is at the top, so the last axial slice comes first
+    assert_eq!(
+      volume
+        .slice_with_interpolation(Plane::Coronal, 1, Interpolation::Nearest)
+        .map(|image| slice_data(&image)),
+      Some((2, 5, vec![10, 11, 10, 11, 6, 7, 6, 7, 2, 3]))
+    );
+  }
+
+  #[test]
+  fn sagittal_slice_test() {
+    let volume = test_volume();
+
+    assert_eq!(
+      volume
+        .slice_with_interpolation(Plane::Sagittal, 0, Interpolation::Linear)
+        .map(|image| slice_data(&image)),
+      Some((2, 3, vec![8, 10, 4, 6, 0, 2]))
+    );
+  }
+}
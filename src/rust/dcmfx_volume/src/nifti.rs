@@ -0,0 +1,256 @@
+//! Exports volumes to the NIfTI-1 file format.
+//!
+//! Ref: https://nifti.nimh.nih.gov/nifti-1
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::volume::{Vector, Volume, cross, dot, normalize};
+
+/// The size of a NIfTI-1 header in bytes.
+///
+const HEADER_SIZE: usize = 348;
+
+/// The offset of the voxel data in a single-file NIfTI-1 image. This is the
+/// header followed by four bytes that indicate there are no header extensions.
+///
+const VOX_OFFSET: usize = HEADER_SIZE + 4;
+
+/// The NIfTI-1 datatype code for 32-bit floats.
+///
+const DT_FLOAT32: i16 = 16;
+
+/// The NIfTI-1 code used for `qform_code` and `sform_code` which indicates the
+/// coordinates are scanner-based anatomical coordinates.
+///
+const NIFTI_XFORM_SCANNER_ANAT: i16 = 1;
+
+/// The NIfTI-1 code used for `xyzt_units` which indicates spatial units are mm.
+///
+const NIFTI_UNITS_MM: u8 = 2;
+
+impl Volume {
+  /// Converts this volume into a single-file NIfTI-1 image, i.e. the content of
+  /// a `.nii` file.
+  ///
+  /// Voxels are stored as 32-bit floats. The volume's affine transform is
+  /// converted from DICOM's LPS patient coordinates into the RAS coordinates
+  /// used by NIfTI, and is stored as both the sform and the qform. The qform
+  /// can only store a rotation, so for volumes with gantry tilt it uses the
+  /// slice normal and the sform should be preferred.
+  ///
+  pub fn to_nifti1(&self) -> Vec<u8> {
+    let mut header = [0u8; HEADER_SIZE];
+
+    let mut write_i16 = |offset: usize, value: i16| {
+      header[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    };
+
+    write_i16(40, 3);
+    write_i16(42, self.dimensions[0] as i16);
+    write_i16(44, self.dimensions[1] as i16);
+    write_i16(46, self.dimensions[2] as i16);
+    write_i16(48, 1);
+    write_i16(50, 1);
+    write_i16(52, 1);
+    write_i16(54, 1);
+    write_i16(70, DT_FLOAT32);
+    write_i16(72, 32);
+    write_i16(252, NIFTI_XFORM_SCANNER_ANAT);
+    write_i16(254, NIFTI_XFORM_SCANNER_ANAT);
+
+    // Convert the affine from LPS to RAS by negating its first two rows
+    let mut affine = self.affine;
+    for row in affine.iter_mut().take(2) {
+      for value in row.iter_mut() {
+        *value = -*value;
+      }
+    }
+
+    let (quaternion, qfac) = affine_to_quaternion(&affine);
+
+    let mut write_f32 = |offset: usize, value: f64| {
+      header[offset..offset + 4].copy_from_slice(&(value as f32).to_le_bytes());
+    };
+
+    write_f32(76, qfac);
+    write_f32(80, self.spacing[0]);
+    write_f32(84, self.spacing[1]);
+    write_f32(88, self.spacing[2]);
+    write_f32(108, VOX_OFFSET as f64);
+    write_f32(112, 1.0);
+
+    write_f32(256, quaternion[0]);
+    write_f32(260, quaternion[1]);
+    write_f32(264, quaternion[2]);
+    write_f32(268, affine[0][3]);
+    write_f32(272, affine[1][3]);
+    write_f32(276, affine[2][3]);
+
+    for (row, values) in affine.iter().take(3).enumerate() {
+      for (column, value) in values.iter().enumerate() {
+        write_f32(280 + row * 16 + column * 4, *value);
+      }
+    }
+
+    header[0..4].copy_from_slice(&(HEADER_SIZE as i32).to_le_bytes());
+    header[123] = NIFTI_UNITS_MM;
+    header[344..348].copy_from_slice(b"n+1\0");
+
+    let mut nifti = Vec::with_capacity(VOX_OFFSET + self.data.len() * 4);
+    nifti.extend_from_slice(&header);
+    nifti.extend_from_slice(&[0, 0, 0, 0]);
+
+    for value in self.data.iter() {
+      nifti.extend_from_slice(&value.to_le_bytes());
+    }
+
+    nifti
+  }
+}
+
+/// Returns the quaternion parameters `b`, `c`, and `d` of the rotation in an
+/// affine transform, along with the `qfac` value that is -1 when the third
+/// column of the affine points opposite to the normal of the first two. The
+/// rotation is taken from the first two columns of the affine and their
+/// normal.
+///
+/// Ref: `nifti_mat44_to_quatern()` in nifti1_io.c.
+///
+fn affine_to_quaternion(affine: &[[f64; 4]; 4]) -> (Vector, f64) {
+  let column = |i: usize| [affine[0][i], affine[1][i], affine[2][i]];
+
+  let x = normalize(column(0));
+  let y = normalize(column(1));
+  let z = normalize(cross(x, y));
+
+  let qfac = if dot(z, column(2)) < 0.0 { -1.0 } else { 1.0 };
+
+  let (r11, r12, r13) = (x[0], y[0], z[0]);
+  let (r21, r22, r23) = (x[1], y[1], z[1]);
+  let (r31, r32, r33) = (x[2], y[2], z[2]);
+
+  let trace = r11 + r22 + r33 + 1.0;
+
+  let (a, b, c, d) = if trace > 0.5 {
+    let a = 0.5 * trace.sqrt();
+    (
+      a,
+      0.25 * (r32 - r23) / a,
+      0.25 * (r13 - r31) / a,
+      0.25 * (r21 - r12) / a,
+    )
+  } else {
+    let xd = 1.0 + r11 - (r22 + r33);
+    let yd = 1.0 + r22 - (r11 + r33);
+    let zd = 1.0 + r33 - (r11 + r22);
+
+    if xd > 1.0 {
+      let b = 0.5 * xd.sqrt();
+      (
+        0.25 * (r32 - r23) / b,
+        b,
+        0.25 * (r12 + r21) / b,
+        0.25 * (r13 + r31) / b,
+      )
+    } else if yd > 1.0 {
+      let c = 0.5 * yd.sqrt();
+      (
+        0.25 * (r13 - r31) / c,
+        0.25 * (r12 + r21) / c,
+        c,
+        0.25 * (r23 + r32) / c,
+      )
+    } else {
+      let d = 0.5 * zd.sqrt();
+      (
+        0.25 * (r21 - r12) / d,
+        0.25 * (r13 + r31) / d,
+        0.25 * (r23 + r32) / d,
+        d,
+      )
+    }
+  };
+
+  let quaternion = if a < 0.0 { [-b, -c, -d] } else { [b, c, d] };
+
+  (quaternion, qfac)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::volume::tests::axial_slice;
+
+  fn read_i16(bytes: &[u8], offset: usize) -> i16 {
+    i16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+  }
+
+  fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+  }
+
+  #[test]
+  fn to_nifti1_test() {
+    let slices = [
+      axial_slice(0.0, [1024, 1025, 1026, 1027]),
+      axial_slice(3.0, [1027, 1028, 1029, 1030]),
+    ];
+    let volume = Volume::from_data_sets(&[&slices[0], &slices[1]]).unwrap();
+
+    let nifti = volume.to_nifti1();
+
+    assert_eq!(nifti.len(), 352 + 8 * 4);
+    assert_eq!(&nifti[0..4], &348i32.to_le_bytes());
+    assert_eq!(&nifti[344..348], b"n+1\0");
+    assert_eq!(
+      (0..8)
+        .map(|i| read_i16(&nifti, 40 + i * 2))
+        .collect::<Vec<_>>(),
+      vec![3, 2, 2, 2, 1, 1, 1, 1]
+    );
+    assert_eq!(read_i16(&nifti, 70), 16);
+    assert_eq!(read_f32(&nifti, 108), 352.0);
+    assert_eq!(
+      (0..4)
+        .map(|i| read_f32(&nifti, 76 + i * 4))
+        .collect::<Vec<_>>(),
+      vec![1.0, 0.25, 0.5, 3.0]
+    );
+
+    // The axial LPS orientation is a rotation of 180 degrees about z in RAS
+    assert_eq!(
+      (0..6)
+        .map(|i| read_f32(&nifti, 256 + i * 4))
+        .collect::<Vec<_>>(),
+      vec![0.0, 0.0, 1.0, 10.0, 20.0, 0.0]
+    );
+    assert_eq!(
+      (0..12)
+        .map(|i| read_f32(&nifti, 280 + i * 4))
+        .collect::<Vec<_>>(),
+      vec![
+        -0.25, 0.0, 0.0, 10.0, 0.0, -0.5, 0.0, 20.0, 0.0, 0.0, 3.0, 0.0
+      ]
+    );
+
+    assert_eq!(read_f32(&nifti, 352), 0.0);
+    assert_eq!(read_f32(&nifti, 352 + 7 * 4), 6.0);
+  }
+
+  #[test]
+  fn affine_to_quaternion_test() {
+    let identity = [
+      [1.0, 0.0, 0.0, 0.0],
+      [0.0, 1.0, 0.0, 0.0],
+      [0.0, 0.0, 1.0, 0.0],
+      [0.0, 0.0, 0.0, 1.0],
+    ];
+    assert_eq!(affine_to_quaternion(&identity), ([0.0, 0.0, 0.0], 1.0));
+
+    let mut flipped = identity;
+    flipped[2][2] = -1.0;
+    assert_eq!(affine_to_quaternion(&flipped), ([0.0, 0.0, 0.0], -1.0));
+  }
+}
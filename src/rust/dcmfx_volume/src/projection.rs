@@ -0,0 +1,193 @@
+//! Intensity projections, such as Maximum Intensity Projection (MIP), which
+//! combine the slices of a volume along a plane into a single image.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use dcmfx_pixel_data::MonochromeImage;
+
+use crate::{
+  Volume,
+  mpr::{Interpolation, Plane, values_to_monochrome_image},
+};
+
+/// The function used to combine voxel values along the projection direction.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectionMode {
+  /// Maximum Intensity Projection (MIP), which takes the largest value.
+  Maximum,
+
+  /// Minimum Intensity Projection (MinIP), which takes the smallest value.
+  Minimum,
+
+  /// Average Intensity Projection (AvgIP), which takes the mean value.
+  Average,
+}
+
+/// A slab of slices to project, specified as the index of the slice at its
+/// center and its thickness in millimeters. The slab always contains at least
+/// the center slice.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProjectionSlab {
+  pub center: usize,
+  pub thickness: f64,
+}
+
+impl Volume {
+  /// Projects this volume along the normal of the given plane, returning a
+  /// monochrome image of modality values rounded to the nearest integer. The
+  /// projection covers the whole volume when no slab is specified. Returns
+  /// `None` if the slab's center is out of range, or the projection is too
+  /// large to be held in a [`MonochromeImage`].
+  ///
+  /// Slices are resampled in the same way as by [`Self::slice()`].
+  ///
+  pub fn project(
+    &self,
+    plane: Plane,
+    mode: ProjectionMode,
+    slab: Option<ProjectionSlab>,
+  ) -> Option<MonochromeImage> {
+    let slice_count = self.slice_count(plane);
+
+    let slices = match slab {
+      Some(slab) => {
+        if slab.center >= slice_count {
+          return None;
+        }
+
+        let slab_slice_count = (slab.thickness / self.slice_spacing(plane))
+          .round()
+          .max(1.0)
+          .min(slice_count as f64) as usize;
+
+        let start = slab
+          .center
+          .saturating_sub(slab_slice_count / 2)
+          .min(slice_count - slab_slice_count);
+
+        start..(start + slab_slice_count)
+      }
+
+      None => 0..slice_count,
+    };
+
+    let mut width = 0;
+    let mut height = 0;
+    let mut values: Vec<f64> = Vec::new();
+
+    for index in slices.clone() {
+      let (slice_width, slice_height, slice_values) =
+        self.resample_slice(plane, index, Interpolation::Linear)?;
+
+      if values.is_empty() {
+        width = slice_width;
+        height = slice_height;
+        values = slice_values;
+        continue;
+      }
+
+      for (value, slice_value) in values.iter_mut().zip(slice_values) {
+        *value = match mode {
+          ProjectionMode::Maximum => value.max(slice_value),
+          ProjectionMode::Minimum => value.min(slice_value),
+          ProjectionMode::Average => *value + slice_value,
+        };
+      }
+    }
+
+    if mode == ProjectionMode::Average {
+      for value in values.iter_mut() {
+        *value /= slices.len() as f64;
+      }
+    }
+
+    values_to_monochrome_image(width, height, &values)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(not(feature = "std"))]
+  use alloc::vec;
+
+  use super::*;
+
+  use dcmfx_pixel_data::MonochromeImageData;
+
+  use crate::volume::tests::axial_slice;
+
+  fn projection_data(image: Option<MonochromeImage>) -> Option<Vec<i32>> {
+    image.map(|image| match image.data() {
+      MonochromeImageData::I32(data) => data.clone(),
+      _ => unreachable!(),
+    })
+  }
+
+  #[test]
+  fn project_test() {
+    let slices = [
+      axial_slice(0.0, [1024, 1030, 1026, 1027]),
+      axial_slice(1.0, [1028, 1025, 1030, 1031]),
+      axial_slice(2.0, [1020, 1033, 1034, 1029]),
+    ];
+
+    let volume =
+      Volume::from_data_sets(&slices.iter().collect::<Vec<_>>()).unwrap();
+
+    // Rows are interpolated to give square pixels, so the middle row of each
+    // slice is the average of its first and last rows
+    assert_eq!(
+      projection_data(volume.project(
+        Plane::Axial,
+        ProjectionMode::Maximum,
+        None
+      )),
+      Some(vec![4, 9, 5, 7, 10, 7])
+    );
+
+    assert_eq!(
+      projection_data(volume.project(
+        Plane::Axial,
+        ProjectionMode::Minimum,
+        None
+      )),
+      Some(vec![-4, 1, 1, 4, 2, 3])
+    );
+
+    assert_eq!(
+      projection_data(volume.project(
+        Plane::Axial,
+        ProjectionMode::Average,
+        None
+      )),
+      Some(vec![0, 5, 3, 5, 6, 5])
+    );
+
+    assert_eq!(
+      projection_data(volume.project(
+        Plane::Axial,
+        ProjectionMode::Maximum,
+        Some(ProjectionSlab {
+          center: 0,
+          thickness: 2.0
+        })
+      )),
+      Some(vec![4, 6, 5, 5, 6, 7])
+    );
+
+    assert_eq!(
+      volume.project(
+        Plane::Axial,
+        ProjectionMode::Maximum,
+        Some(ProjectionSlab {
+          center: 3,
+          thickness: 1.0
+        })
+      ),
+      None
+    );
+  }
+}
@@ -0,0 +1,419 @@
+//! Assembles single-frame instances into a 3D voxel volume.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use dcmfx_core::{DataError, DataSet, DcmfxError, dictionary};
+use dcmfx_pixel_data::{
+  DataSetPixelDataExtensions, GetPixelDataError, PixelDataDecodeError,
+  SeriesGeometry,
+  series_geometry::SeriesGeometryIssue,
+  transforms::P10PixelDataFrameTransformError,
+  volume_consistency::{VolumeConsistencyConfig, VolumeConsistencyIssue},
+};
+
+/// A 3D volume of voxels assembled from the slices in a series.
+///
+/// Voxels are stored with the column index varying fastest, then the row
+/// index, then the slice index. Slices are ordered by their position along the
+/// slice normal, and each voxel holds its modality value, e.g. Hounsfield
+/// Units for CT.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Volume {
+  /// The number of columns, rows, and slices in the volume.
+  ///
+  pub dimensions: [usize; 3],
+
+  /// The spacing in mm between the centers of adjacent voxels along each of
+  /// the volume's three axes.
+  ///
+  pub spacing: [f64; 3],
+
+  /// The affine transform that maps a voxel's column, row, and slice index to
+  /// its position in the patient coordinate system, in mm. This is stored in
+  /// row-major order, and uses the DICOM patient coordinate system, i.e. LPS.
+  ///
+  pub affine: [[f64; 4]; 4],
+
+  /// The voxel values.
+  ///
+  pub data: Vec<f32>,
+}
+
+/// An error that occurred when assembling a volume.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum VolumeError {
+  /// An error that occurred reading data elements from an instance.
+  DataError(DataError),
+
+  /// An error that occurred reading the frames of pixel data from an
+  /// instance.
+  P10PixelDataFrameTransformError(P10PixelDataFrameTransformError),
+
+  /// An error that occurred decoding an instance's pixel data.
+  PixelDataDecodeError(PixelDataDecodeError),
+
+  /// The instances can't be assembled into a volume, e.g. because they belong
+  /// to different series or have inconsistent orientations.
+  InvalidSlices { details: Vec<String> },
+}
+
+impl core::fmt::Display for VolumeError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::DataError(e) => e.fmt(f),
+      Self::P10PixelDataFrameTransformError(e) => e.fmt(f),
+      Self::PixelDataDecodeError(e) => e.fmt(f),
+      Self::InvalidSlices { details } => {
+        write!(f, "Invalid volume slices, details: {}", details.join(", "))
+      }
+    }
+  }
+}
+
+impl DcmfxError for VolumeError {
+  fn to_lines(&self, task_description: &str) -> Vec<String> {
+    match self {
+      Self::DataError(e) => e.to_lines(task_description),
+      Self::P10PixelDataFrameTransformError(e) => e.to_lines(task_description),
+      Self::PixelDataDecodeError(e) => e.to_lines(task_description),
+      Self::InvalidSlices { details } => {
+        let mut lines =
+          vec![format!("Volume error {task_description}"), "".to_string()];
+
+        for detail in details {
+          lines.push(format!("  Details: {detail}"));
+        }
+
+        lines
+      }
+    }
+  }
+}
+
+impl From<GetPixelDataError> for VolumeError {
+  fn from(error: GetPixelDataError) -> Self {
+    match error {
+      GetPixelDataError::DataError(e) => Self::DataError(e),
+      GetPixelDataError::P10PixelDataFrameTransformError(e) => {
+        Self::P10PixelDataFrameTransformError(e)
+      }
+      GetPixelDataError::PixelDataDecodeError { error, .. } => {
+        Self::PixelDataDecodeError(error)
+      }
+    }
+  }
+}
+
+impl Volume {
+  /// Assembles a volume from the single-frame instances of one series. The
+  /// instances can be passed in any order as they are sorted by their Image
+  /// Position (Patient).
+  ///
+  /// The instances must share the same Series Instance UID, Frame of
+  /// Reference UID, orientation, and dimensions, and be evenly spaced. Slices
+  /// stacked at an angle to their plane, as happens with CT gantry tilt, are
+  /// permitted, and are represented by a sheared affine transform.
+  ///
+  pub fn from_data_sets(data_sets: &[&DataSet]) -> Result<Self, VolumeError> {
+    if data_sets.is_empty() {
+      return Err(VolumeError::InvalidSlices {
+        details: vec!["No instances were provided".to_string()],
+      });
+    }
+
+    // Check the slices form a consistent stack, and determine their order
+    let geometry = SeriesGeometry::from_data_sets(
+      data_sets,
+      &VolumeConsistencyConfig::default(),
+    )
+    .map_err(VolumeError::DataError)?;
+
+    let issues: Vec<String> = geometry
+      .issues
+      .iter()
+      .filter(|issue| match issue {
+        SeriesGeometryIssue::VolumeConsistency(
+          VolumeConsistencyIssue::TooFewSlices,
+        ) => data_sets.len() > 1,
+        SeriesGeometryIssue::VolumeConsistency(
+          VolumeConsistencyIssue::GantryTilt { .. },
+        ) => false,
+        _ => true,
+      })
+      .map(|issue| issue.to_string())
+      .collect();
+
+    if !issues.is_empty() {
+      return Err(VolumeError::InvalidSlices { details: issues });
+    }
+
+    // Decode the slices in order
+    let mut dimensions = [0, 0, data_sets.len()];
+    let mut data = vec![];
+
+    for (i, slice_index) in geometry.slice_order.iter().enumerate() {
+      let data_set = data_sets[*slice_index];
+
+      let slice_dimensions = [
+        data_set
+          .get_int::<usize>(dictionary::COLUMNS.tag)
+          .map_err(VolumeError::DataError)?,
+        data_set
+          .get_int::<usize>(dictionary::ROWS.tag)
+          .map_err(VolumeError::DataError)?,
+      ];
+
+      if i == 0 {
+        dimensions[0] = slice_dimensions[0];
+        dimensions[1] = slice_dimensions[1];
+      } else if slice_dimensions != [dimensions[0], dimensions[1]] {
+        return Err(VolumeError::InvalidSlices {
+          details: vec!["Slices have different dimensions".to_string()],
+        });
+      }
+
+      let mut frames = data_set.get_pixel_data_f32_frames()?;
+      if frames.len() != 1 {
+        return Err(VolumeError::InvalidSlices {
+          details: vec![format!(
+            "Slices must have one frame, but found {} frames",
+            frames.len()
+          )],
+        });
+      }
+
+      data.append(&mut frames[0]);
+    }
+
+    // Construct the affine transform from the first slice's orientation and
+    // position, and the step between adjacent slices
+    let first = &geometry.image_plane_modules[geometry.slice_order[0]];
+    let last =
+      &geometry.image_plane_modules[*geometry.slice_order.last().unwrap()];
+
+    let [a, b, c, d, e, f] = first.image_orientation_patient.map(f64::from);
+    let row_direction = normalize([a, b, c]);
+    let column_direction = normalize([d, e, f]);
+
+    let first_position = first.image_position_patient.map(f64::from);
+    let last_position = last.image_position_patient.map(f64::from);
+
+    let slice_step = if data_sets.len() > 1 {
+      let count = (data_sets.len() - 1) as f64;
+
+      [
+        (last_position[0] - first_position[0]) / count,
+        (last_position[1] - first_position[1]) / count,
+        (last_position[2] - first_position[2]) / count,
+      ]
+    } else {
+      let normal = cross(row_direction, column_direction);
+      let thickness = first
+        .spacing_between_slices
+        .or(first.slice_thickness)
+        .map(f64::from)
+        .unwrap_or(1.0);
+
+      [
+        normal[0] * thickness,
+        normal[1] * thickness,
+        normal[2] * thickness,
+      ]
+    };
+
+    // Pixel Spacing holds the spacing between rows followed by the spacing
+    // between columns
+    let column_spacing = f64::from(first.pixel_spacing[1]);
+    let row_spacing = f64::from(first.pixel_spacing[0]);
+
+    let mut affine = [[0.0; 4]; 4];
+    for (axis, row) in affine.iter_mut().take(3).enumerate() {
+      *row = [
+        row_direction[axis] * column_spacing,
+        column_direction[axis] * row_spacing,
+        slice_step[axis],
+        first_position[axis],
+      ];
+    }
+    affine[3] = [0.0, 0.0, 0.0, 1.0];
+
+    Ok(Self {
+      dimensions,
+      spacing: [column_spacing, row_spacing, length(slice_step)],
+      affine,
+      data,
+    })
+  }
+}
+
+pub(crate) type Vector = [f64; 3];
+
+pub(crate) fn dot(a: Vector, b: Vector) -> f64 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: Vector, b: Vector) -> Vector {
+  [
+    a[1] * b[2] - a[2] * b[1],
+    a[2] * b[0] - a[0] * b[2],
+    a[0] * b[1] - a[1] * b[0],
+  ]
+}
+
+pub(crate) fn length(a: Vector) -> f64 {
+  dot(a, a).sqrt()
+}
+
+pub(crate) fn normalize(a: Vector) -> Vector {
+  let length = length(a);
+
+  if length == 0.0 {
+    a
+  } else {
+    [a[0] / length, a[1] / length, a[2] / length]
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+
+  use dcmfx_core::{DataElementValue, ValueRepresentation};
+
+  /// Creates a 2x2 axial slice at the given z position whose pixels have the
+  /// given stored values.
+  ///
+  pub fn axial_slice(z: f64, pixels: [u16; 4]) -> DataSet {
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::SERIES_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+    ds.insert_string_value(&dictionary::FRAME_OF_REFERENCE_UID, &["1.2.4"])
+      .unwrap();
+    ds.insert_float_value(
+      &dictionary::IMAGE_POSITION_PATIENT,
+      &[-10.0, -20.0, z],
+    )
+    .unwrap();
+    ds.insert_float_value(
+      &dictionary::IMAGE_ORIENTATION_PATIENT,
+      &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+    )
+    .unwrap();
+    ds.insert_float_value(&dictionary::PIXEL_SPACING, &[0.5, 0.25])
+      .unwrap();
+    ds.insert_float_value(&dictionary::SLICE_THICKNESS, &[2.0])
+      .unwrap();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::ROWS, &[2]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[2]).unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[16])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_STORED, &[16])
+      .unwrap();
+    ds.insert_int_value(&dictionary::HIGH_BIT, &[15]).unwrap();
+    ds.insert_int_value(&dictionary::PIXEL_REPRESENTATION, &[0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::RESCALE_INTERCEPT, &[-1024.0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::RESCALE_SLOPE, &[1.0])
+      .unwrap();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_binary(
+        ValueRepresentation::OtherWordString,
+        pixels
+          .iter()
+          .flat_map(|pixel| pixel.to_le_bytes())
+          .collect::<Vec<u8>>()
+          .into(),
+      )
+      .unwrap(),
+    );
+
+    ds
+  }
+
+  #[test]
+  fn from_data_sets_test() {
+    let slices = [
+      axial_slice(6.0, [1030, 1031, 1032, 1033]),
+      axial_slice(0.0, [1024, 1025, 1026, 1027]),
+      axial_slice(3.0, [1027, 1028, 1029, 1030]),
+    ];
+
+    let volume =
+      Volume::from_data_sets(&slices.iter().collect::<Vec<_>>()).unwrap();
+
+    assert_eq!(volume.dimensions, [2, 2, 3]);
+    assert_eq!(volume.spacing, [0.25, 0.5, 3.0]);
+    assert_eq!(
+      volume.affine,
+      [
+        [0.25, 0.0, 0.0, -10.0],
+        [0.0, 0.5, 0.0, -20.0],
+        [0.0, 0.0, 3.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+      ]
+    );
+    assert_eq!(
+      volume.data,
+      vec![0.0, 1.0, 2.0, 3.0, 3.0, 4.0, 5.0, 6.0, 6.0, 7.0, 8.0, 9.0]
+    );
+  }
+
+  #[test]
+  fn single_slice_test() {
+    let slice = axial_slice(4.0, [1024, 1024, 1024, 1024]);
+
+    let volume = Volume::from_data_sets(&[&slice]).unwrap();
+
+    assert_eq!(volume.dimensions, [2, 2, 1]);
+    assert_eq!(volume.affine[2], [0.0, 0.0, 2.0, 4.0]);
+  }
+
+  #[test]
+  fn invalid_slices_test() {
+    assert!(matches!(
+      Volume::from_data_sets(&[]),
+      Err(VolumeError::InvalidSlices { .. })
+    ));
+
+    let mut other_series = axial_slice(3.0, [0; 4]);
+    other_series
+      .insert_string_value(&dictionary::SERIES_INSTANCE_UID, &["1.2.5"])
+      .unwrap();
+    assert_eq!(
+      Volume::from_data_sets(&[&axial_slice(0.0, [0; 4]), &other_series]),
+      Err(VolumeError::InvalidSlices {
+        details: vec!["Instances belong to more than one series".to_string()]
+      })
+    );
+
+    assert_eq!(
+      Volume::from_data_sets(&[
+        &axial_slice(0.0, [0; 4]),
+        &axial_slice(1.0, [0; 4]),
+        &axial_slice(5.0, [0; 4]),
+      ]),
+      Err(VolumeError::InvalidSlices {
+        details: vec![
+          "Non-uniform slice spacing between 1.000 and 4.000 mm".to_string()
+        ]
+      })
+    );
+  }
+}
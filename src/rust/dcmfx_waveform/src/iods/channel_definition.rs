@@ -367,6 +367,20 @@ impl ChannelDefinition {
 
     Ok(item)
   }
+
+  /// Converts a raw stored sample value for this channel into a physical value
+  /// in the units given by [`Self::sensitivity_units`]. This applies the
+  /// channel sensitivity, sensitivity correction factor, and baseline, each of
+  /// which is omitted from the calculation when not present.
+  ///
+  /// Ref: PS3.3 C.10.9.1.4.3.
+  ///
+  pub fn to_physical_value(&self, stored_value: i64) -> f64 {
+    stored_value as f64
+      * self.sensitivity.unwrap_or(1.0)
+      * self.sensitivity_correction_factor.unwrap_or(1.0)
+      + self.baseline.unwrap_or(0.0)
+  }
 }
 
 /// The status of a waveform channel, as stored in the *'(003A,0205) Channel
@@ -490,6 +504,23 @@ mod tests {
     );
   }
 
+  #[test]
+  fn to_physical_value() {
+    let mut channel_definition = test_channel_definition();
+    channel_definition.sensitivity_correction_factor = Some(2.0);
+    channel_definition.baseline = Some(-10.0);
+
+    assert_eq!(channel_definition.to_physical_value(0), -10.0);
+    assert_eq!(channel_definition.to_physical_value(100), 240.0);
+    assert_eq!(channel_definition.to_physical_value(-4), -20.0);
+
+    channel_definition.sensitivity = None;
+    channel_definition.sensitivity_correction_factor = None;
+    channel_definition.baseline = None;
+
+    assert_eq!(channel_definition.to_physical_value(-4), -4.0);
+  }
+
   #[test]
   fn channel_status_from_string() {
     assert_eq!(ChannelStatus::from_string("OK"), ChannelStatus::Ok);
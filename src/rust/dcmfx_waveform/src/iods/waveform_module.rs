@@ -1,11 +1,7 @@
 //! Specifies values of data elements in the Waveform module.
 
 #[cfg(not(feature = "std"))]
-use alloc::{
-  format,
-  string::{String, ToString},
-  vec::Vec,
-};
+use alloc::{format, string::String, vec::Vec};
 
 use dcmfx_core::{
   DataElementTag, DataElementValue, DataError, DataSet, DataSetPath, IodModule,
@@ -166,8 +162,7 @@ impl WaveformMultiplexGroup {
     // Check that there is at least one channel when there are samples
     if channels.is_empty() && number_of_samples > 0 {
       return Err(DataError::new_value_invalid(
-        "Waveform multiplex group with samples must have at least one channel"
-          .to_string(),
+        "Waveform multiplex group with samples must have at least one channel",
       ));
     }
 
@@ -1,13 +1,7 @@
 //! Extracts chunks of waveform data from a stream of DICOM P10 tokens.
 
 #[cfg(not(feature = "std"))]
-use alloc::{
-  boxed::Box,
-  format,
-  string::{String, ToString},
-  vec,
-  vec::Vec,
-};
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
 
 use dcmfx_core::{
   DataError, DataSetPath, DcmfxError, Rc, RcByteSlice, dictionary,
@@ -250,8 +244,7 @@ impl P10WaveformChunkTransform {
     else {
       return Err(P10WaveformChunkTransformError::DataError(
         DataError::new_value_invalid(
-          "Multiplex group has multiple waveform data data elements"
-            .to_string(),
+          "Multiplex group has multiple waveform data data elements",
         )
         .with_path(&DataSetPath::new_with_data_element(
           dictionary::WAVEFORM_DATA.tag,
@@ -370,8 +363,7 @@ impl P10WaveformChunkTransform {
       {
         return Err(P10WaveformChunkTransformError::DataError(
           DataError::new_value_invalid(
-            "Waveform data does not hold a whole number of sample sets"
-              .to_string(),
+            "Waveform data does not hold a whole number of sample sets",
           )
           .with_path(&DataSetPath::new_with_data_element(
             dictionary::WAVEFORM_DATA.tag,